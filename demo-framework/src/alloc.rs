@@ -0,0 +1,151 @@
+//! A global allocator that counts what it hands out.
+//!
+//! [`TrackingAllocator`] forwards every call to the system allocator and
+//! bumps two process-wide counters on the way. A scenario binary opts in
+//! with
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: demo_framework::alloc::TrackingAllocator =
+//!     demo_framework::alloc::TrackingAllocator;
+//! ```
+//!
+//! and [`counts`] then reports how many allocations the process has made so
+//! far, [`live_bytes`] how much of that memory is still held, and
+//! [`peak_live_bytes`] the most it has held at once since the last
+//! [`reset_peak`]. The counters are relaxed atomics: a few extra uncontended
+//! updates per allocation, cheap next to the allocation itself.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+static LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_LIVE_BYTES: AtomicU64 = AtomicU64::new(0);
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// The system allocator, counting allocations, requested bytes, bytes not
+/// yet freed and the high-water mark of those.
+pub struct TrackingAllocator;
+
+impl TrackingAllocator {
+    fn record(&self, size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(size as u64, Ordering::Relaxed);
+        INSTALLED.store(true, Ordering::Relaxed);
+    }
+
+    /// Adds a block that was handed out; failed calls hand out nothing.
+    fn hold(&self, ptr: *mut u8, size: usize) -> *mut u8 {
+        if !ptr.is_null() {
+            let live: u64 = LIVE_BYTES.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+            PEAK_LIVE_BYTES.fetch_max(live, Ordering::Relaxed);
+        }
+        ptr
+    }
+}
+
+// SAFETY: every method forwards to `System` with the caller's arguments
+// unchanged; the counters don't touch the memory.
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.record(layout.size());
+        self.hold(unsafe { System.alloc(layout) }, layout.size())
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.record(layout.size());
+        self.hold(unsafe { System.alloc_zeroed(layout) }, layout.size())
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    /// A grow or shrink counts as one allocation of the new size, since
+    /// that is what it costs when the block has to move.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        self.record(new_size);
+        let moved: *mut u8 = unsafe { System.realloc(ptr, layout, new_size) };
+        // On failure the old block stays allocated. The old size comes off
+        // first, so a block grown in place never counts twice in the peak
+        if !moved.is_null() {
+            LIVE_BYTES.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+        }
+        self.hold(moved, new_size)
+    }
+}
+
+/// Allocation counters at one point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AllocCounts {
+    /// Calls to `alloc`, `alloc_zeroed` and `realloc`.
+    pub allocations: u64,
+    /// Bytes requested by those calls.
+    pub bytes: u64,
+}
+
+impl AllocCounts {
+    /// What happened between `earlier` and `self`.
+    pub fn since(self, earlier: AllocCounts) -> AllocCounts {
+        AllocCounts {
+            allocations: self.allocations - earlier.allocations,
+            bytes: self.bytes - earlier.bytes,
+        }
+    }
+}
+
+/// The counters so far, or `None` if [`TrackingAllocator`] isn't the
+/// global allocator.
+pub fn counts() -> Option<AllocCounts> {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some(AllocCounts {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        bytes: BYTES.load(Ordering::Relaxed),
+    })
+}
+
+/// Bytes allocated and not yet freed, or `None` if [`TrackingAllocator`]
+/// isn't the global allocator.
+///
+/// Unlike [`counts`] this can go down. Memory a program holds at steady
+/// state shows up as a flat line; a leak shows up as a line that keeps
+/// climbing (see [`crate::soak`]).
+pub fn live_bytes() -> Option<u64> {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some(LIVE_BYTES.load(Ordering::Relaxed))
+}
+
+/// The most [`live_bytes`] has been since the last [`reset_peak`], or since
+/// the process started; `None` if [`TrackingAllocator`] isn't the global
+/// allocator.
+pub fn peak_live_bytes() -> Option<u64> {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some(PEAK_LIVE_BYTES.load(Ordering::Relaxed))
+}
+
+/// Starts a new peak at the current [`live_bytes`] and returns the peak it
+/// replaces, or `None` if [`TrackingAllocator`] isn't the global allocator.
+///
+/// There is one peak for the whole process. Code measuring a span inside a
+/// longer one hands the returned value to [`raise_peak`] when it is done,
+/// so the outer span still sees the highest point of both.
+pub fn reset_peak() -> Option<u64> {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some(PEAK_LIVE_BYTES.swap(LIVE_BYTES.load(Ordering::Relaxed), Ordering::Relaxed))
+}
+
+/// Raises the peak to `bytes` if it is lower.
+pub fn raise_peak(bytes: u64) {
+    PEAK_LIVE_BYTES.fetch_max(bytes, Ordering::Relaxed);
+}
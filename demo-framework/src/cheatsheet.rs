@@ -0,0 +1,113 @@
+//! Reference tables generated from a scenario's data.
+//!
+//! A scenario describes its types once (complexities, capabilities, trade-
+//! offs) and turns that description into [`Table`]s. The `cheatsheet`
+//! subcommand prints them as aligned text or Markdown, and the README embeds
+//! the Markdown between [`BEGIN_MARKER`] and [`END_MARKER`], so a test can
+//! check the teaching tables never drift from the code.
+
+use std::io::{self, Write};
+
+/// Marks the start of a generated block in a README.
+pub const BEGIN_MARKER: &str =
+    "<!-- cheatsheet:begin (generated by `cargo run -- cheatsheet --markdown`) -->";
+
+/// Marks the end of a generated block in a README.
+pub const END_MARKER: &str = "<!-- cheatsheet:end -->";
+
+/// One reference table: a header row, data rows and footnotes.
+pub struct Table {
+    pub title: &'static str,
+    pub columns: Vec<&'static str>,
+    /// One cell per column; the first cell names the row.
+    pub rows: Vec<Vec<String>>,
+    /// Footnotes for `*`-style markers in the cells.
+    pub notes: Vec<&'static str>,
+}
+
+impl Table {
+    /// Prints the table with padded columns, for a terminal.
+    pub fn write_text(&self, out: &mut dyn Write) -> io::Result<()> {
+        let widths: Vec<usize> = (0..self.columns.len())
+            .map(|i| {
+                self.rows
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([self.columns[i].chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |out: &mut dyn Write, cells: &[&str]| -> io::Result<()> {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{:<width$}", cell))
+                .collect();
+            writeln!(out, "{}", padded.join("  ").trim_end())
+        };
+
+        writeln!(out, "{}", self.title)?;
+        writeln!(out, "{}", "=".repeat(self.title.chars().count()))?;
+        line(out, &self.columns)?;
+        let rules: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+        line(
+            out,
+            &rules.iter().map(String::as_str).collect::<Vec<&str>>(),
+        )?;
+        for row in &self.rows {
+            line(out, &row.iter().map(String::as_str).collect::<Vec<&str>>())?;
+        }
+        for note in &self.notes {
+            writeln!(out, "{}", note)?;
+        }
+        Ok(())
+    }
+
+    /// Prints the table as GitHub-flavored Markdown, row names in bold.
+    pub fn write_markdown(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "**{}:**", self.title)?;
+        writeln!(out)?;
+        writeln!(out, "| {} |", self.columns.join(" | "))?;
+        let rules: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| "-".repeat(column.len() + 2))
+            .collect();
+        writeln!(out, "|{}|", rules.join("|"))?;
+        for row in &self.rows {
+            writeln!(out, "| **{}** | {} |", row[0], row[1..].join(" | "))?;
+        }
+        if !self.notes.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "*Notes:*")?;
+            for note in &self.notes {
+                // A leading `*` would start a Markdown list item or emphasis
+                writeln!(out, "- {}", note.replace('*', "\\*"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders `tables` as one Markdown block, tables separated by blank lines.
+pub fn markdown(tables: &[Table]) -> String {
+    let mut buffer: Vec<u8> = Vec::new();
+    for (i, table) in tables.iter().enumerate() {
+        if i > 0 {
+            buffer.push(b'\n');
+        }
+        table
+            .write_markdown(&mut buffer)
+            .expect("writing to a Vec<u8> cannot fail");
+    }
+    String::from_utf8(buffer).expect("tables are valid UTF-8")
+}
+
+/// The generated block embedded in `readme`, without the markers, or `None`
+/// if the markers are missing.
+pub fn embedded(readme: &str) -> Option<&str> {
+    let start: usize = readme.find(BEGIN_MARKER)? + BEGIN_MARKER.len();
+    let end: usize = start + readme[start..].find(END_MARKER)?;
+    Some(readme[start..end].trim_matches('\n'))
+}
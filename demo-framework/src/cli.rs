@@ -0,0 +1,304 @@
+//! Command-line front end shared by every scenario binary.
+//!
+//! ```text
+//! cargo run -p collections_demo                      # run every module
+//! cargo run -p collections_demo -- vec_examples      # run selected modules
+//! cargo run -p collections_demo -- vec               # `_examples` may be left off
+//! cargo run -p collections_demo -- --list            # list the registry
+//! cargo run -p collections_demo -- --step union_find_examples  # pause after each algorithm step
+//! cargo run -p collections_demo -- --export diagrams  # write graphs as .dot and .mmd files
+//! cargo run -p collections_demo -- cheatsheet        # print reference tables
+//! cargo run -p collections_demo -- soak --seconds 60  # check a workload doesn't leak
+//! cargo run -p collections_demo -- --log-json vec 2> run.jsonl  # trace the run as JSON
+//! ```
+
+use crate::Module;
+use crate::cheatsheet::{self, Table};
+use crate::diagram;
+use crate::embed;
+use crate::report::{self, Report};
+use crate::soak::{self, Round, SoakReport};
+use crate::step;
+use crate::trace::{self, Format};
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// Builder for a scenario's `main`.
+pub struct Cli {
+    title: &'static str,
+    modules: &'static [Module],
+    reports: Vec<Box<dyn Report>>,
+    cheatsheet: Option<fn() -> Vec<Table>>,
+    soak: Option<fn() -> Round>,
+}
+
+impl Cli {
+    pub fn new(title: &'static str, modules: &'static [Module]) -> Self {
+        Cli {
+            title,
+            modules,
+            reports: Vec::new(),
+            cheatsheet: None,
+            soak: None,
+        }
+    }
+
+    /// Enables the `cheatsheet` subcommand, printing the tables `tables`
+    /// builds.
+    pub fn cheatsheet(mut self, tables: fn() -> Vec<Table>) -> Self {
+        self.cheatsheet = Some(tables);
+        self
+    }
+
+    /// Enables the `soak` subcommand, repeating the round function
+    /// `workload` builds.
+    pub fn soak(mut self, workload: fn() -> Round) -> Self {
+        self.soak = Some(workload);
+        self
+    }
+
+    /// Registers a report hook for the run.
+    pub fn report(mut self, report: impl Report + 'static) -> Self {
+        self.reports.push(Box::new(report));
+        self
+    }
+
+    /// Parses `std::env::args` and runs the selected modules against stdout.
+    pub fn run(self) -> ExitCode {
+        let args: Vec<String> = env::args().skip(1).collect();
+        let mut stdout = io::stdout().lock();
+
+        match self.run_with_args(&args, &mut stdout) {
+            Ok(code) => code,
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::FAILURE
+            }
+        }
+    }
+
+    fn run_with_args(self, args: &[String], out: &mut dyn Write) -> io::Result<ExitCode> {
+        if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+            self.print_usage(out)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        if args.iter().any(|arg| arg == "--list") {
+            for module in self.modules {
+                writeln!(out, "{}", module.name)?;
+            }
+            return Ok(ExitCode::SUCCESS);
+        }
+        if let (Some("cheatsheet"), Some(tables)) =
+            (args.first().map(String::as_str), self.cheatsheet)
+        {
+            return run_cheatsheet(&tables(), &args[1..], out);
+        }
+        if let (Some("soak"), Some(workload)) = (args.first().map(String::as_str), self.soak) {
+            return run_soak(workload, &args[1..], out);
+        }
+
+        let mut log_format: Format = Format::Text;
+        let mut stepping: bool = false;
+        let mut export_dir: Option<PathBuf> = None;
+        let mut names: Vec<&String> = Vec::with_capacity(args.len());
+        let mut rest = args.iter();
+        while let Some(arg) = rest.next() {
+            match arg.as_str() {
+                "--step" => stepping = true,
+                "--log-json" => log_format = Format::Json,
+                "--export" => match rest.next() {
+                    Some(dir) => export_dir = Some(PathBuf::from(dir)),
+                    None => {
+                        eprintln!("error: --export needs a directory");
+                        return Ok(ExitCode::from(2));
+                    }
+                },
+                _ => names.push(arg),
+            }
+        }
+        let args: Vec<&String> = names;
+
+        let selected: Vec<&Module> = if args.is_empty() {
+            self.modules.iter().collect()
+        } else {
+            let mut selected: Vec<&Module> = Vec::with_capacity(args.len());
+            for arg in args {
+                // `hashmap` is short for `hashmap_examples`
+                match embed::find_module(self.modules, arg) {
+                    Some(module) => selected.push(module),
+                    None => {
+                        eprintln!("error: unknown module `{arg}` (see --list)");
+                        return Ok(ExitCode::from(2));
+                    }
+                }
+            }
+            selected
+        };
+
+        trace::install(log_format);
+        for report in self.reports {
+            report::install(report);
+        }
+        if stepping {
+            step::enable(Box::new(io::stdin().lock()));
+        }
+        if let Some(dir) = export_dir {
+            fs::create_dir_all(&dir)?;
+            diagram::enable(dir);
+        }
+
+        crate::banner(out, self.title)?;
+        for module in selected {
+            let _span = trace::module_span(module.name).entered();
+            (module.run)(out)?;
+        }
+        report::finish(out)?;
+
+        Ok(ExitCode::SUCCESS)
+    }
+
+    fn print_usage(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", self.title)?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "Usage: [MODULE]... [--step] [--export <DIR>] [--log-json] [--list] [--help]"
+        )?;
+        if self.cheatsheet.is_some() {
+            writeln!(out, "       cheatsheet [--markdown] [--output <FILE>]")?;
+        }
+        if self.soak.is_some() {
+            writeln!(out, "       soak [--seconds <N>]")?;
+        }
+        writeln!(out)?;
+        writeln!(
+            out,
+            "Runs every example module, or only the ones named (`vec` for `vec_examples`)."
+        )?;
+        writeln!(
+            out,
+            "--step pauses algorithm demos after each step and draws their data structures."
+        )?;
+        writeln!(
+            out,
+            "--export writes the graphs and trees demos build to DIR as Graphviz (.dot) and Mermaid (.mmd) files."
+        )?;
+        writeln!(
+            out,
+            "Runs are traced to stderr with `tracing`: set RUST_LOG=demo=info to see them, or pass --log-json for JSON lines."
+        )?;
+        if self.cheatsheet.is_some() {
+            writeln!(
+                out,
+                "`cheatsheet` prints the reference tables instead; --output writes them to FILE as Markdown."
+            )?;
+        }
+        if self.soak.is_some() {
+            writeln!(
+                out,
+                "`soak` repeats a mixed workload for N seconds (default {}) and fails if the live heap keeps growing.",
+                DEFAULT_SOAK_SECONDS
+            )?;
+        }
+        writeln!(out, "Modules:")?;
+        for module in self.modules {
+            writeln!(out, "  {}", module.name)?;
+        }
+        Ok(())
+    }
+}
+
+/// How long `soak` runs without `--seconds`.
+const DEFAULT_SOAK_SECONDS: u64 = 10;
+
+/// `cheatsheet [--markdown] [--output <FILE>]`
+fn run_cheatsheet(tables: &[Table], args: &[String], out: &mut dyn Write) -> io::Result<ExitCode> {
+    let mut markdown: bool = false;
+    let mut output: Option<&str> = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--markdown" => markdown = true,
+            "--output" => match args.next() {
+                Some(path) => output = Some(path),
+                None => {
+                    eprintln!("error: --output needs a file name");
+                    return Ok(ExitCode::from(2));
+                }
+            },
+            _ => {
+                eprintln!("error: unknown cheatsheet option `{arg}`");
+                return Ok(ExitCode::from(2));
+            }
+        }
+    }
+
+    if let Some(path) = output {
+        fs::write(path, cheatsheet::markdown(tables))?;
+        writeln!(out, "Wrote the cheat sheet to {}", path)?;
+    } else if markdown {
+        write!(out, "{}", cheatsheet::markdown(tables))?;
+    } else {
+        for (i, table) in tables.iter().enumerate() {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            table.write_text(out)?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `soak [--seconds <N>]`
+fn run_soak(workload: fn() -> Round, args: &[String], out: &mut dyn Write) -> io::Result<ExitCode> {
+    let mut seconds: u64 = DEFAULT_SOAK_SECONDS;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seconds" => match args.next().and_then(|value| value.parse::<u64>().ok()) {
+                Some(value) if value > 0 => seconds = value,
+                _ => {
+                    eprintln!("error: --seconds needs a positive whole number");
+                    return Ok(ExitCode::from(2));
+                }
+            },
+            _ => {
+                eprintln!("error: unknown soak option `{arg}`");
+                return Ok(ExitCode::from(2));
+            }
+        }
+    }
+
+    writeln!(
+        out,
+        "Soaking for {} s: {} windows, the first {} warm-up",
+        seconds,
+        soak::WINDOWS,
+        soak::WARM_UP
+    )?;
+    let mut round: Round = workload();
+    let report: SoakReport = soak::run(out, Duration::from_secs(seconds), &mut round)?;
+    writeln!(
+        out,
+        "\n{} rounds. Live heap: {} at the start, {} settled, {} at the end (tolerance {})",
+        report.rounds(),
+        report::bytes(report.start_bytes),
+        report::bytes(report.settled_peak()),
+        report::bytes(report.final_peak()),
+        report::bytes(report.tolerance())
+    )?;
+    if report.plateaued() {
+        writeln!(out, "The live heap levelled off: no leak found")?;
+        Ok(ExitCode::SUCCESS)
+    } else {
+        writeln!(
+            out,
+            "The live heap kept growing after warm-up: something is holding on to memory"
+        )?;
+        Ok(ExitCode::FAILURE)
+    }
+}
@@ -0,0 +1,267 @@
+//! Graphviz and Mermaid export for the structures demos build.
+//!
+//! A demo describes a graph or tree as a [`Diagram`] of numbered nodes and
+//! edges, some of them highlighted (a shortest path, a spanning tree), and
+//! hands it to [`export`]. Normally that does nothing, so the output is the
+//! same as a plain run. With `--export <DIR>` on the command line, [`export`]
+//! writes the diagram to `DIR/<name>.dot` and `DIR/<name>.mmd` and prints
+//! where:
+//!
+//! ```text
+//! Exported road_map: diagrams/road_map.dot, diagrams/road_map.mmd
+//! ```
+//!
+//! Render them with `dot -Tsvg road_map.dot -o road_map.svg`, or paste the
+//! Mermaid file into any Markdown viewer that supports it. Like step mode,
+//! export mode is per thread.
+
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// A graph to export: nodes numbered by the demo, edges between them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagram {
+    name: String,
+    directed: bool,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Node {
+    id: usize,
+    label: String,
+    highlighted: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Edge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+    highlighted: bool,
+}
+
+impl Diagram {
+    /// A diagram whose edges have a direction, like a dependency graph or a
+    /// tree's parent-to-child links. `name` becomes the file name.
+    pub fn directed(name: &str) -> Self {
+        Diagram::new(name, true)
+    }
+
+    /// A diagram whose edges go both ways, like roads.
+    pub fn undirected(name: &str) -> Self {
+        Diagram::new(name, false)
+    }
+
+    fn new(name: &str, directed: bool) -> Self {
+        Diagram {
+            name: name.to_string(),
+            directed,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Adds node `id` drawn as `label`.
+    pub fn node(&mut self, id: usize, label: impl Into<String>) {
+        self.nodes.push(Node {
+            id,
+            label: label.into(),
+            highlighted: false,
+        });
+    }
+
+    /// Adds an edge, with an optional label such as its weight.
+    pub fn edge(&mut self, from: usize, to: usize, label: Option<String>) {
+        self.edges.push(Edge {
+            from,
+            to,
+            label,
+            highlighted: false,
+        });
+    }
+
+    /// Draws node `id` in the highlight style.
+    pub fn highlight_node(&mut self, id: usize) {
+        for node in self.nodes.iter_mut().filter(|node| node.id == id) {
+            node.highlighted = true;
+        }
+    }
+
+    /// Draws the edge from `from` to `to` in the highlight style. In an
+    /// undirected diagram either order finds it.
+    pub fn highlight_edge(&mut self, from: usize, to: usize) {
+        let directed: bool = self.directed;
+        for edge in &mut self.edges {
+            if (edge.from, edge.to) == (from, to)
+                || (!directed && (edge.from, edge.to) == (to, from))
+            {
+                edge.highlighted = true;
+            }
+        }
+    }
+
+    /// Highlights every node of `path` and the edges between consecutive
+    /// ones.
+    pub fn highlight_path(&mut self, path: &[usize]) {
+        for &node in path {
+            self.highlight_node(node);
+        }
+        for pair in path.windows(2) {
+            self.highlight_edge(pair[0], pair[1]);
+        }
+    }
+
+    /// The diagram in Graphviz's DOT language.
+    pub fn to_dot(&self) -> String {
+        let (keyword, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        let mut dot: String = format!("{} {} {{\n", keyword, dot_id(&self.name));
+        dot += "    node [shape=box, style=rounded, fontname=\"Helvetica\"];\n";
+        dot += "    edge [fontname=\"Helvetica\"];\n";
+        for node in &self.nodes {
+            dot += &format!("    n{} [label={}", node.id, dot_id(&node.label));
+            if node.highlighted {
+                dot += ", color=crimson, penwidth=2";
+            }
+            dot += "];\n";
+        }
+        for edge in &self.edges {
+            let mut attributes: Vec<String> = Vec::new();
+            if let Some(label) = &edge.label {
+                attributes.push(format!("label={}", dot_id(label)));
+            }
+            if edge.highlighted {
+                attributes.push("color=crimson, penwidth=3".to_string());
+            }
+            dot += &format!("    n{} {} n{}", edge.from, arrow, edge.to);
+            if !attributes.is_empty() {
+                dot += &format!(" [{}]", attributes.join(", "));
+            }
+            dot += ";\n";
+        }
+        dot += "}\n";
+        dot
+    }
+
+    /// The diagram as a Mermaid flowchart, laid out left to right.
+    pub fn to_mermaid(&self) -> String {
+        let link: &str = if self.directed { "-->" } else { "---" };
+        let mut mermaid: String = format!("%% {}\nflowchart LR\n", self.name);
+        for node in &self.nodes {
+            mermaid += &format!("    n{}[\"{}\"]\n", node.id, mermaid_text(&node.label));
+        }
+        for edge in &self.edges {
+            let label: String = match &edge.label {
+                Some(label) => format!("|\"{}\"|", mermaid_text(label)),
+                None => String::new(),
+            };
+            mermaid += &format!("    n{} {}{} n{}\n", edge.from, link, label, edge.to);
+        }
+
+        let nodes: Vec<String> = self
+            .nodes
+            .iter()
+            .filter(|node| node.highlighted)
+            .map(|node| format!("n{}", node.id))
+            .collect();
+        if !nodes.is_empty() {
+            mermaid += "    classDef highlight stroke:crimson,stroke-width:2px\n";
+            mermaid += &format!("    class {} highlight\n", nodes.join(","));
+        }
+        // Mermaid styles links by their position in the file
+        let links: Vec<String> = self
+            .edges
+            .iter()
+            .enumerate()
+            .filter(|(_, edge)| edge.highlighted)
+            .map(|(i, _)| i.to_string())
+            .collect();
+        if !links.is_empty() {
+            mermaid += &format!(
+                "    linkStyle {} stroke:crimson,stroke-width:3px\n",
+                links.join(",")
+            );
+        }
+        mermaid
+    }
+
+    /// Writes `<name>.dot` and `<name>.mmd` into `dir` and returns their
+    /// paths.
+    pub fn write_files(&self, dir: &Path) -> io::Result<(PathBuf, PathBuf)> {
+        let dot: PathBuf = dir.join(format!("{}.dot", self.name));
+        let mermaid: PathBuf = dir.join(format!("{}.mmd", self.name));
+        fs::write(&dot, self.to_dot())?;
+        fs::write(&mermaid, self.to_mermaid())?;
+        Ok((dot, mermaid))
+    }
+}
+
+/// A quoted DOT identifier.
+fn dot_id(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Text safe inside a quoted Mermaid label.
+fn mermaid_text(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+thread_local! {
+    static EXPORT_DIR: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Turns export mode on for this thread, writing diagrams into `dir`.
+pub fn enable(dir: PathBuf) {
+    EXPORT_DIR.with(|export_dir| *export_dir.borrow_mut() = Some(dir));
+}
+
+/// Turns export mode off for this thread.
+pub fn disable() {
+    EXPORT_DIR.with(|export_dir| export_dir.borrow_mut().take());
+}
+
+/// Whether [`export`] currently writes files.
+pub fn is_enabled() -> bool {
+    EXPORT_DIR.with(|export_dir| export_dir.borrow().is_some())
+}
+
+/// In export mode, writes the diagram `build` returns and prints the file
+/// names. Otherwise returns without building it, so demos can offer a
+/// diagram at no cost to a plain run.
+pub fn export(out: &mut dyn Write, build: impl FnOnce() -> Diagram) -> io::Result<()> {
+    let Some(dir) = EXPORT_DIR.with(|export_dir| export_dir.borrow().clone()) else {
+        return Ok(());
+    };
+    let diagram: Diagram = build();
+    let (dot, mermaid) = diagram.write_files(&dir)?;
+    writeln!(
+        out,
+        "Exported {}: {}, {}",
+        diagram.name,
+        dot.display(),
+        mermaid.display()
+    )
+}
@@ -0,0 +1,202 @@
+//! Running demos from other programs.
+//!
+//! [`run_demo`] runs one module, or one demo inside a module, against a
+//! buffer and hands back a [`DemoOutput`]: the text it wrote, how long it
+//! took, and any values it [`record`]ed along the way. A dashboard, a web
+//! page or a test can call it directly instead of spawning the scenario
+//! binary and parsing stdout.
+//!
+//! Names are looked up the way the command line looks up modules, plus
+//! demo names:
+//!
+//! ```text
+//! vec_examples                   every demo in the module
+//! vec                            the same; `_examples` may be left off
+//! vec_examples::sorting_demo     one demo in that module
+//! sorting_demo                   one demo, in the first module that has it
+//! ```
+//!
+//! Running a single demo still calls its module's `run_all`, but
+//! [`crate::section`] skips every section except the one asked for, so only
+//! that demo does any work. Like the other modes, capture state is per
+//! thread.
+
+use crate::trace;
+use crate::{Module, capture};
+use std::cell::RefCell;
+use std::fmt::{self, Display};
+use std::time::{Duration, Instant};
+
+/// What one [`run_demo`] call produced.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DemoOutput {
+    /// Everything the demo wrote, section frames included.
+    pub text: String,
+    /// Values the demo passed to [`record`], in the order it recorded them.
+    pub data: Vec<(String, String)>,
+    /// Wall-clock time of the run.
+    pub duration: Duration,
+}
+
+impl DemoOutput {
+    /// The last value recorded under `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.data
+            .iter()
+            .rev()
+            .find(|(recorded, _)| recorded == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// No module or demo has the name passed to [`run_demo`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownDemo {
+    pub name: String,
+}
+
+impl Display for UnknownDemo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no module or demo named `{}`", self.name)
+    }
+}
+
+impl std::error::Error for UnknownDemo {}
+
+/// A run in progress on this thread.
+struct Capture {
+    /// The one demo to run, or `None` for a whole module.
+    only: Option<String>,
+    /// Sections entered since the selected one started, itself included.
+    depth: usize,
+    matched: bool,
+    data: Vec<(String, String)>,
+}
+
+thread_local! {
+    static CAPTURE: RefCell<Option<Capture>> = const { RefCell::new(None) };
+}
+
+/// Clears the capture state however the run ends, panics included.
+struct CaptureGuard;
+
+impl Drop for CaptureGuard {
+    fn drop(&mut self) {
+        CAPTURE.with(|capture| capture.borrow_mut().take());
+    }
+}
+
+/// Attaches `value` under `key` to the output of the current [`run_demo`],
+/// and emits it as a `recorded` event (see [`crate::trace`]). Outside a
+/// [`run_demo`] only the event is emitted, so demos can record
+/// unconditionally.
+pub fn record(key: &str, value: impl Display) {
+    let value: String = value.to_string();
+    trace::recorded(key, &value);
+    CAPTURE.with(|capture| {
+        if let Some(capture) = capture.borrow_mut().as_mut() {
+            capture.data.push((key.to_string(), value));
+        }
+    });
+}
+
+/// Runs the module or demo called `name` from `modules` and captures it.
+pub fn run_demo(modules: &[Module], name: &str) -> Result<DemoOutput, UnknownDemo> {
+    let unknown = || UnknownDemo {
+        name: name.to_string(),
+    };
+
+    let (candidates, demo): (Vec<&Module>, Option<&str>) = match name.split_once("::") {
+        Some((module, demo)) => (
+            vec![find_module(modules, module).ok_or_else(unknown)?],
+            Some(demo),
+        ),
+        None => match find_module(modules, name) {
+            Some(module) => (vec![module], None),
+            None => (modules.iter().collect(), Some(name)),
+        },
+    };
+
+    for module in candidates {
+        let (output, matched): (DemoOutput, bool) = run_captured(module, demo);
+        if demo.is_none() || matched {
+            return Ok(output);
+        }
+    }
+    Err(unknown())
+}
+
+/// Looks a module up by its name, or by its name without `_examples`.
+pub(crate) fn find_module<'a>(modules: &'a [Module], name: &str) -> Option<&'a Module> {
+    modules
+        .iter()
+        .find(|module| module.name == name || module.name.strip_suffix("_examples") == Some(name))
+}
+
+/// Runs `module`, or only its section `demo`, with capture state installed.
+/// Also returns whether `demo` was found.
+fn run_captured(module: &Module, demo: Option<&str>) -> (DemoOutput, bool) {
+    let _guard: CaptureGuard = CaptureGuard;
+    CAPTURE.with(|capture| {
+        *capture.borrow_mut() = Some(Capture {
+            only: demo.map(str::to_string),
+            depth: 0,
+            matched: false,
+            data: Vec::new(),
+        });
+    });
+
+    let start: Instant = Instant::now();
+    let text: String = {
+        let _span = trace::module_span(module.name).entered();
+        capture(module.run)
+    };
+    let duration: Duration = start.elapsed();
+
+    let (matched, data) = CAPTURE.with(|capture| {
+        let capture = capture.borrow_mut().take().expect("installed above");
+        (capture.matched, capture.data)
+    });
+    (
+        DemoOutput {
+            text,
+            data,
+            duration,
+        },
+        matched,
+    )
+}
+
+/// Called by [`crate::section`] before running `name`: whether to run it.
+pub(crate) fn enter_section(name: &str) -> bool {
+    CAPTURE.with(|capture| {
+        let mut capture = capture.borrow_mut();
+        let Some(capture) = capture.as_mut() else {
+            return true;
+        };
+        match &capture.only {
+            None => true,
+            // Sections nested in the selected one run too
+            Some(_) if capture.depth > 0 => {
+                capture.depth += 1;
+                true
+            }
+            Some(only) if only == name => {
+                capture.depth = 1;
+                capture.matched = true;
+                true
+            }
+            Some(_) => false,
+        }
+    })
+}
+
+/// Called by [`crate::section`] after a section that [`enter_section`]
+/// let run.
+pub(crate) fn exit_section() {
+    CAPTURE.with(|capture| {
+        if let Some(capture) = capture.borrow_mut().as_mut() {
+            capture.depth = capture.depth.saturating_sub(1);
+        }
+    });
+}
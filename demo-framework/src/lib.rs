@@ -0,0 +1,104 @@
+//! Shared demo framework for the playground scenarios.
+//!
+//! Every scenario binary prints its examples the same way: a banner with the
+//! scenario title and compiler version, followed by one framed section per
+//! demo function. Keeping these helpers in one crate means a new scenario
+//! only has to write its examples, not the scaffolding around them.
+//!
+//! Demos never print to stdout directly. They write to an injected
+//! `&mut dyn Write`, so the binary can hand them a locked stdout while tests
+//! capture the exact same output into a buffer.
+//!
+//! Each scenario registers its example modules as a `&[Module]` list; the
+//! [`cli::Cli`] runner turns that registry into the scenario's command line
+//! and notifies any installed [`report::Report`] hooks as sections run.
+//! Scenarios may also describe their types as [`cheatsheet::Table`]s, which
+//! the same command line prints as reference tables, algorithm demos can
+//! pause after each step under `--step` (see [`step`]), and demos that build
+//! graphs or trees can write them out as Graphviz and Mermaid files under
+//! `--export` (see [`diagram`]). A scenario can also register a workload for
+//! the `soak` subcommand, which repeats it for a while and checks the live
+//! heap levels off (see [`soak`]). Other programs can run a single module or
+//! demo and get its text, timing and recorded values back (see [`embed`]).
+//! Runs are also traced with `tracing`: a span per module and section, and
+//! events for section timings and recorded values (see [`trace`]).
+//! Performance smoke tests time the README's headline comparisons with
+//! [`micro_bench`] and fail if one stops holding. [`ops`] is the operation
+//! language the property tests, fuzzing decoders and soak workloads drive
+//! map-like structures with, and [`rng`] the seeded generator behind every
+//! demo input and randomized test.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub mod alloc;
+pub mod cheatsheet;
+pub mod cli;
+pub mod diagram;
+pub mod embed;
+pub mod micro_bench;
+pub mod ops;
+pub mod report;
+pub mod rng;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+pub mod soak;
+pub mod step;
+pub mod trace;
+
+/// Signature shared by every demo function and every module's `run_all`.
+pub type DemoFn = fn(&mut dyn Write) -> io::Result<()>;
+
+/// One entry in a scenario's demo registry, e.g. `vec_examples`.
+pub struct Module {
+    /// Module name, also used to select it on the command line.
+    pub name: &'static str,
+    /// Runs every demo in the module.
+    pub run: DemoFn,
+}
+
+/// Prints the scenario banner shown at the top of every `cargo run`.
+pub fn banner(out: &mut dyn Write, title: &str) -> io::Result<()> {
+    writeln!(out, "{title}")?;
+    writeln!(out, "Compiled with: {:?}", rustc_version_runtime::version())
+}
+
+/// Runs a single demo inside a framed section.
+///
+/// `name` is usually the demo function name, `what` a one-line summary of
+/// what the demo shows.
+pub fn section(
+    out: &mut dyn Write,
+    name: &str,
+    what: &str,
+    f: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> io::Result<()> {
+    // Skipped when embed::run_demo asked for a different demo
+    if !embed::enter_section(name) {
+        return Ok(());
+    }
+    writeln!(out, "\n{:=<80}", "")?;
+    writeln!(out, "DEMO: {name}")?;
+    writeln!(out, "  {what}")?;
+    writeln!(out, "{:=<80}", "")?;
+
+    let _span = trace::section_span(name).entered();
+    report::section_started(name);
+    let start: Instant = Instant::now();
+    let result: io::Result<()> = f(out);
+    let elapsed: Duration = start.elapsed();
+    report::section_finished(name);
+    trace::section_finished(elapsed, result.is_ok());
+    step::section_finished();
+    embed::exit_section();
+    result
+}
+
+/// Runs `f` against an in-memory buffer and returns everything it wrote.
+///
+/// This is what the snapshot tests use to observe a module's output.
+pub fn capture(f: impl FnOnce(&mut dyn Write) -> io::Result<()>) -> String {
+    let mut buffer: Vec<u8> = Vec::new();
+    f(&mut buffer).expect("writing to a Vec<u8> cannot fail");
+    String::from_utf8(buffer).expect("demo output is valid UTF-8")
+}
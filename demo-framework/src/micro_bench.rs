@@ -0,0 +1,42 @@
+//! A tiny timing harness for performance smoke tests.
+//!
+//! Criterion gives trustworthy numbers but takes minutes and never fails.
+//! The smoke tests only need to know that one side of a comparison is still
+//! clearly ahead, so [`fastest`] runs a closure a few times and keeps the
+//! quickest run: noise from the scheduler or other tests only ever adds
+//! time, so the minimum is the steadiest single number. [`assert_faster`]
+//! then checks the ratio against a margin far below what the README quotes,
+//! loose enough to hold in an unoptimized `cargo test` build.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Runs `f` once to warm up, then `rounds` more times, and returns the
+/// quickest timed run. The result goes through `black_box` so the work
+/// can't be optimized away.
+pub fn fastest<R>(rounds: u32, mut f: impl FnMut() -> R) -> Duration {
+    black_box(f());
+    (0..rounds.max(1))
+        .map(|_| {
+            let start: Instant = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .expect("at least one round")
+}
+
+/// How many times longer `slow` took than `fast`.
+pub fn speedup(slow: Duration, fast: Duration) -> f64 {
+    slow.as_secs_f64() / fast.as_secs_f64().max(f64::MIN_POSITIVE)
+}
+
+/// Panics unless `fast` beat `slow` by at least `at_least` times; the
+/// message names the claim and both timings.
+pub fn assert_faster(claim: &str, fast: Duration, slow: Duration, at_least: f64) {
+    let ratio: f64 = speedup(slow, fast);
+    assert!(
+        ratio >= at_least,
+        "{claim}: expected at least {at_least}x, measured {ratio:.2}x ({fast:?} vs {slow:?})"
+    );
+}
@@ -0,0 +1,342 @@
+//! A small language of map operations, for driving any map-like structure
+//! with generated input and comparing it against `BTreeMap`.
+//!
+//! A [`Program`] is a list of [`Op`]s. Three things produce programs:
+//!
+//! - `program`, a proptest strategy behind the `proptest` feature. It
+//!   shrinks well: fewer operations, keys and values towards 0, and
+//!   variants towards [`Op::Insert`]. A failing property ends up reported
+//!   as a few lines of calls.
+//! - [`Program::from_bytes`], which turns arbitrary bytes into a program.
+//!   Any input decodes, so a fuzzer can feed it whatever it likes.
+//! - [`random_op`], which draws one operation from any source of numbers.
+//!   A scenario's soak workload uses it with its own generator, and
+//!   [`Program::from_bytes`] uses it with the bytes.
+//!
+//! A structure joins in by implementing [`OpTarget`]. [`check`] runs a
+//! program on it and on a `BTreeMap` together, and stops at the first
+//! operation where they disagree. The [`Divergence`] it returns prints the
+//! steps up to that point:
+//!
+//! ```text
+//! diverged at step 2:
+//!     map.insert(0, 0);       // None
+//!     map.insert(1, 0);       // None
+//!     map.insert(2, 0);       // None
+//! len: expected 3, got 2
+//! ```
+
+#[cfg(feature = "proptest")]
+use proptest::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// One operation on a map from `u32` to `u32`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Insert(u32, u32),
+    Remove(u32),
+    Get(u32),
+    /// `*map.entry(key).or_insert(0) += 1`, returning the new count.
+    Bump(u32),
+    /// Every entry with `start <= key < end`, in key order. Empty when
+    /// `start >= end`, rather than panicking like `BTreeMap::range`.
+    RangeScan(u32, u32),
+    /// Keeps the entries whose value is not a multiple of the modulus.
+    Retain(u32),
+    /// Changes capacity only; what the map holds stays the same.
+    ShrinkToFit,
+    Clear,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::Insert(key, value) => write!(f, "map.insert({}, {});", key, value),
+            Op::Remove(key) => write!(f, "map.remove({});", key),
+            Op::Get(key) => write!(f, "map.get({});", key),
+            Op::Bump(key) => write!(f, "*map.entry({}).or_insert(0) += 1;", key),
+            Op::RangeScan(start, end) => write!(f, "map.range({}..{});", start, end),
+            Op::Retain(modulus) => write!(f, "map.retain(|_, v| *v % {} != 0);", modulus),
+            Op::ShrinkToFit => write!(f, "map.shrink_to_fit();"),
+            Op::Clear => write!(f, "map.clear();"),
+        }
+    }
+}
+
+/// What an operation returned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// The old value from `Insert` or `Remove`, the value from `Get`, or
+    /// the new count from `Bump`.
+    Value(Option<u32>),
+    Entries(Vec<(u32, u32)>),
+    /// The number of entries left after `Retain` or `ShrinkToFit`.
+    Len(usize),
+    Cleared,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Value(value) => write!(f, "{:?}", value),
+            Outcome::Entries(entries) => write!(f, "{:?}", entries),
+            Outcome::Len(len) => write!(f, "len {}", len),
+            Outcome::Cleared => write!(f, "()"),
+        }
+    }
+}
+
+/// A structure that can run [`Op`]s.
+pub trait OpTarget {
+    fn apply(&mut self, op: Op) -> Outcome;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The reference every other target is checked against.
+impl OpTarget for BTreeMap<u32, u32> {
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::Insert(key, value) => Outcome::Value(self.insert(key, value)),
+            Op::Remove(key) => Outcome::Value(self.remove(&key)),
+            Op::Get(key) => Outcome::Value(self.get(&key).copied()),
+            Op::Bump(key) => {
+                let count: &mut u32 = self.entry(key).or_insert(0);
+                *count += 1;
+                Outcome::Value(Some(*count))
+            }
+            Op::RangeScan(start, end) if start < end => Outcome::Entries(
+                self.range(start..end)
+                    .map(|(&key, &value)| (key, value))
+                    .collect(),
+            ),
+            Op::RangeScan(_, _) => Outcome::Entries(Vec::new()),
+            Op::Retain(modulus) => {
+                self.retain(|_, value| *value % modulus != 0);
+                Outcome::Len(BTreeMap::len(self))
+            }
+            Op::ShrinkToFit => Outcome::Len(BTreeMap::len(self)),
+            Op::Clear => {
+                self.clear();
+                Outcome::Cleared
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+}
+
+impl OpTarget for HashMap<u32, u32> {
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::Insert(key, value) => Outcome::Value(self.insert(key, value)),
+            Op::Remove(key) => Outcome::Value(self.remove(&key)),
+            Op::Get(key) => Outcome::Value(self.get(&key).copied()),
+            Op::Bump(key) => {
+                let count: &mut u32 = self.entry(key).or_insert(0);
+                *count += 1;
+                Outcome::Value(Some(*count))
+            }
+            Op::RangeScan(start, end) => Outcome::Entries(scan(self.iter(), start, end)),
+            Op::Retain(modulus) => {
+                self.retain(|_, value| *value % modulus != 0);
+                Outcome::Len(HashMap::len(self))
+            }
+            Op::ShrinkToFit => {
+                self.shrink_to_fit();
+                Outcome::Len(HashMap::len(self))
+            }
+            Op::Clear => {
+                self.clear();
+                Outcome::Cleared
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+/// [`Op::RangeScan`] for a structure without key order: every entry
+/// visited, the matches sorted.
+pub fn scan<'a>(
+    entries: impl Iterator<Item = (&'a u32, &'a u32)>,
+    start: u32,
+    end: u32,
+) -> Vec<(u32, u32)> {
+    let mut found: Vec<(u32, u32)> = entries
+        .filter(|&(&key, _)| start <= key && key < end)
+        .map(|(&key, &value)| (key, value))
+        .collect();
+    found.sort_unstable();
+    found
+}
+
+/// A list of operations. `Debug` prints one call per line, so proptest's
+/// report of a shrunk input reads as a program.
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct Program(pub Vec<Op>);
+
+impl fmt::Debug for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Program {{")?;
+        for op in &self.0 {
+            writeln!(f, "    {}", op)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+impl Program {
+    /// Decodes any bytes into a program over keys `0..key_space`. Each
+    /// number [`random_op`] asks for takes as many bytes as its bound
+    /// needs, and bytes past the end read as 0.
+    pub fn from_bytes(bytes: &[u8], key_space: u32) -> Self {
+        let mut rest: &[u8] = bytes;
+        let mut ops: Vec<Op> = Vec::new();
+        while !rest.is_empty() {
+            ops.push(random_op(key_space, &mut |bound| {
+                let mut value: u64 = 0;
+                let mut reach: u64 = 1;
+                while reach < bound {
+                    let (&byte, tail) = rest.split_first().unwrap_or((&0, &[]));
+                    rest = tail;
+                    value = value << 8 | byte as u64;
+                    reach <<= 8;
+                }
+                value % bound
+            }));
+        }
+        Program(ops)
+    }
+
+    /// Runs every operation, returning what each one returned.
+    pub fn run(&self, target: &mut dyn OpTarget) -> Vec<Outcome> {
+        self.0.iter().map(|&op| target.apply(op)).collect()
+    }
+}
+
+/// Draws one operation. `next(bound)` must return a value in `0..bound`.
+///
+/// Inserts come up most often, then lookups, removes and bumps, so a map
+/// over a small key space stays partly full. `Retain`, `ShrinkToFit` and
+/// `Clear` are rare.
+pub fn random_op(key_space: u32, next: &mut dyn FnMut(u64) -> u64) -> Op {
+    let key_space: u64 = key_space.max(1) as u64;
+    let kind: u64 = next(100);
+    match kind {
+        95..97 => return Op::Retain(next(3) as u32 + 2),
+        97 => return Op::ShrinkToFit,
+        98.. => return Op::Clear,
+        _ => {}
+    }
+    let key: u32 = next(key_space) as u32;
+    match kind {
+        0..35 => Op::Insert(key, next(1_000) as u32),
+        35..55 => Op::Get(key),
+        55..72 => Op::Remove(key),
+        72..85 => Op::Bump(key),
+        _ => Op::RangeScan(key, key.saturating_add(next(key_space / 4 + 1) as u32)),
+    }
+}
+
+/// A proptest strategy for one operation over keys `0..key_space`.
+#[cfg(feature = "proptest")]
+pub fn op(key_space: u32) -> impl Strategy<Value = Op> {
+    let key = 0..key_space.max(1);
+    prop_oneof![
+        8 => (key.clone(), 0..1_000u32).prop_map(|(key, value)| Op::Insert(key, value)),
+        5 => key.clone().prop_map(Op::Get),
+        4 => key.clone().prop_map(Op::Remove),
+        3 => key.clone().prop_map(Op::Bump),
+        2 => (key.clone(), key).prop_map(|(start, end)| Op::RangeScan(start, end)),
+        1 => (2..5u32).prop_map(Op::Retain),
+        1 => Just(Op::ShrinkToFit),
+        1 => Just(Op::Clear),
+    ]
+}
+
+/// A proptest strategy for programs of up to `max_len` operations.
+#[cfg(feature = "proptest")]
+pub fn program(key_space: u32, max_len: usize) -> impl Strategy<Value = Program> {
+    prop::collection::vec(op(key_space), 0..=max_len).prop_map(Program)
+}
+
+/// Where a target first disagreed with the reference.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    /// Index of the operation that disagreed.
+    pub step: usize,
+    /// Every operation up to and including `step`, with what the
+    /// reference returned.
+    pub trace: Vec<(Op, Outcome)>,
+    pub mismatch: Mismatch,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mismatch {
+    Outcome {
+        expected: Outcome,
+        actual: Outcome,
+    },
+    /// Same return value, but a different number of entries afterwards.
+    Len {
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "diverged at step {}:", self.step)?;
+        for (op, outcome) in &self.trace {
+            writeln!(f, "    {:<23} // {}", op.to_string(), outcome)?;
+        }
+        match &self.mismatch {
+            Mismatch::Outcome { expected, actual } => {
+                write!(f, "returned: expected {}, got {}", expected, actual)
+            }
+            Mismatch::Len { expected, actual } => {
+                write!(f, "len: expected {}, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+/// Runs `program` on `target` and on an empty `BTreeMap`, comparing the
+/// return value and the length after every operation.
+pub fn check(program: &Program, target: &mut dyn OpTarget) -> Result<(), Divergence> {
+    let mut model: BTreeMap<u32, u32> = BTreeMap::new();
+    let mut trace: Vec<(Op, Outcome)> = Vec::with_capacity(program.0.len());
+    for (step, &op) in program.0.iter().enumerate() {
+        let expected: Outcome = model.apply(op);
+        let actual: Outcome = target.apply(op);
+        trace.push((op, expected.clone()));
+        let mismatch: Option<Mismatch> = if expected != actual {
+            Some(Mismatch::Outcome { expected, actual })
+        } else if model.len() != target.len() {
+            Some(Mismatch::Len {
+                expected: model.len(),
+                actual: target.len(),
+            })
+        } else {
+            None
+        };
+        if let Some(mismatch) = mismatch {
+            return Err(Divergence {
+                step,
+                trace,
+                mismatch,
+            });
+        }
+    }
+    Ok(())
+}
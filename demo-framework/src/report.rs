@@ -0,0 +1,227 @@
+//! Report hooks.
+//!
+//! A [`Report`] observes a run without being part of the demo output: it is
+//! told when each section starts and finishes, and gets one chance to print
+//! a summary after the last module. Hooks are installed per thread, so tests
+//! capturing output in parallel never see each other's sections.
+//!
+//! [`SectionSummary`] is the stock hook: a table of how long each section
+//! took and, with [`crate::alloc::TrackingAllocator`] installed, how much it
+//! allocated and the most extra heap it held at once.
+
+use crate::alloc::{self, AllocCounts};
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// Observer notified as demo sections run. All methods default to no-ops.
+pub trait Report {
+    /// Called right before the demo named `name` runs.
+    fn section_started(&mut self, _name: &str) {}
+
+    /// Called right after the demo named `name` returns.
+    fn section_finished(&mut self, _name: &str) {}
+
+    /// Called once after the whole run, to print a summary.
+    fn finish(&mut self, _out: &mut dyn Write) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+thread_local! {
+    static REPORTS: RefCell<Vec<Box<dyn Report>>> = RefCell::new(Vec::new());
+}
+
+/// Installs `report` for every section run on this thread from now on.
+pub fn install(report: Box<dyn Report>) {
+    REPORTS.with(|reports| reports.borrow_mut().push(report));
+}
+
+/// Lets every installed report print its summary, then uninstalls them.
+pub fn finish(out: &mut dyn Write) -> io::Result<()> {
+    let reports: Vec<Box<dyn Report>> = REPORTS.with(|reports| reports.take());
+    for mut report in reports {
+        report.finish(out)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn section_started(name: &str) {
+    REPORTS.with(|reports| {
+        for report in reports.borrow_mut().iter_mut() {
+            report.section_started(name);
+        }
+    });
+}
+
+pub(crate) fn section_finished(name: &str) {
+    REPORTS.with(|reports| {
+        for report in reports.borrow_mut().iter_mut() {
+            report.section_finished(name);
+        }
+    });
+}
+
+/// Times every section and counts its allocations, then prints one row per
+/// section and the slowest few.
+#[derive(Default)]
+pub struct SectionSummary {
+    /// Sections still running, innermost last.
+    running: Vec<RunningSection>,
+    finished: Vec<SectionStats>,
+}
+
+struct RunningSection {
+    start: Instant,
+    counts: Option<AllocCounts>,
+    /// Live heap when the section started.
+    live: Option<u64>,
+    /// The enclosing span's peak, put back when the section finishes.
+    outer_peak: Option<u64>,
+}
+
+struct SectionStats {
+    name: String,
+    elapsed: Duration,
+    allocated: Option<AllocCounts>,
+    /// Most heap held at once beyond what was live at the start.
+    peak: Option<u64>,
+}
+
+impl SectionSummary {
+    pub fn new() -> Self {
+        SectionSummary::default()
+    }
+}
+
+impl Report for SectionSummary {
+    fn section_started(&mut self, _name: &str) {
+        // Counters first, so the Instant isn't part of the measured span
+        let counts: Option<AllocCounts> = alloc::counts();
+        let live: Option<u64> = alloc::live_bytes();
+        let outer_peak: Option<u64> = alloc::reset_peak();
+        self.running.push(RunningSection {
+            start: Instant::now(),
+            counts,
+            live,
+            outer_peak,
+        });
+    }
+
+    fn section_finished(&mut self, name: &str) {
+        let Some(section) = self.running.pop() else {
+            return;
+        };
+        let elapsed: Duration = section.start.elapsed();
+        let allocated: Option<AllocCounts> = section
+            .counts
+            .zip(alloc::counts())
+            .map(|(before, after)| after.since(before));
+        let peak: Option<u64> = section
+            .live
+            .zip(alloc::peak_live_bytes())
+            .map(|(live, peak)| peak.saturating_sub(live));
+        if let Some(outer_peak) = section.outer_peak {
+            alloc::raise_peak(outer_peak);
+        }
+        self.finished.push(SectionStats {
+            name: name.to_string(),
+            elapsed,
+            allocated,
+            peak,
+        });
+    }
+
+    fn finish(&mut self, out: &mut dyn Write) -> io::Result<()> {
+        if self.finished.is_empty() {
+            return Ok(());
+        }
+        let width: usize = self
+            .finished
+            .iter()
+            .map(|stats| stats.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("Section".len());
+
+        writeln!(out, "\n{:=<80}", "")?;
+        writeln!(out, "SUMMARY: {} sections", self.finished.len())?;
+        writeln!(out, "{:=<80}", "")?;
+        writeln!(
+            out,
+            "{:<width$} {:>10} {:>12} {:>11} {:>11}",
+            "Section", "Time", "Allocations", "Allocated", "Peak"
+        )?;
+        for stats in &self.finished {
+            writeln!(
+                out,
+                "{:<width$} {:>10.1?} {:>12} {:>11} {:>11}",
+                stats.name,
+                stats.elapsed,
+                stats
+                    .allocated
+                    .map_or("-".to_string(), |a| a.allocations.to_string()),
+                stats.allocated.map_or("-".to_string(), |a| bytes(a.bytes)),
+                stats.peak.map_or("-".to_string(), bytes),
+            )?;
+        }
+
+        let total_time: Duration = self.finished.iter().map(|stats| stats.elapsed).sum();
+        let total: Option<AllocCounts> = self
+            .finished
+            .iter()
+            .map(|stats| stats.allocated)
+            .collect::<Option<Vec<AllocCounts>>>()
+            .map(|all| AllocCounts {
+                allocations: all.iter().map(|a| a.allocations).sum(),
+                bytes: all.iter().map(|a| a.bytes).sum(),
+            });
+        // Sections free most of what they allocate, so the run's peak is
+        // the highest section's, not the sum
+        let highest: Option<u64> = self
+            .finished
+            .iter()
+            .map(|stats| stats.peak)
+            .collect::<Option<Vec<u64>>>()
+            .and_then(|peaks| peaks.into_iter().max());
+        writeln!(
+            out,
+            "{:<width$} {:>10.1?} {:>12} {:>11} {:>11}",
+            "Total",
+            total_time,
+            total.map_or("-".to_string(), |a| a.allocations.to_string()),
+            total.map_or("-".to_string(), |a| bytes(a.bytes)),
+            highest.map_or("-".to_string(), bytes),
+        )?;
+
+        let mut slowest: Vec<&SectionStats> = self.finished.iter().collect();
+        slowest.sort_by_key(|stats| std::cmp::Reverse(stats.elapsed));
+        writeln!(out, "\nSlowest sections:")?;
+        for stats in slowest.iter().take(5) {
+            writeln!(out, "  {:<width$} {:>10.1?}", stats.name, stats.elapsed)?;
+        }
+        if total.is_none() {
+            writeln!(
+                out,
+                "\nAllocations are counted only with demo_framework::alloc::TrackingAllocator as the global allocator."
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// `1536` -> `1.5 KiB`.
+pub(crate) fn bytes(count: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value: f64 = count as f64;
+    let mut unit: usize = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", count)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
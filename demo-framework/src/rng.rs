@@ -0,0 +1,43 @@
+//! The seeded generator behind every demo input and randomized test.
+//!
+//! Demos print the same numbers on every run and tests replay the same
+//! operations from a seed, so neither wants a real random source. [`Lcg`]
+//! is Knuth's MMIX linear congruential generator: one multiply and one add
+//! per step. Its low bits repeat with short periods, so callers take the
+//! high ones: [`below`](Lcg::below) and [`bits`](Lcg::bits) use the top 31,
+//! [`unit`](Lcg::unit) the top 53, and [`next_u64`](Lcg::next_u64) hands
+//! out the whole state for callers that split it themselves.
+
+/// A deterministic pseudo-random sequence, fixed by its seed.
+#[derive(Clone, Debug)]
+pub struct Lcg(u64);
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Lcg(seed)
+    }
+
+    /// Advances one step and returns the new state.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0
+    }
+
+    /// The top 31 bits of the next step, in `0..2^31`.
+    pub fn bits(&mut self) -> u64 {
+        self.next_u64() >> 33
+    }
+
+    /// A value in `0..bound`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.bits() % bound
+    }
+
+    /// A value in `[0, 1)`.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
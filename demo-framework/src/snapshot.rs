@@ -0,0 +1,143 @@
+//! Helpers that make captured demo output stable enough to snapshot.
+//!
+//! Demo output is mostly deterministic, but a few things change from run
+//! to run: timings, randomly keyed hash values, pointer addresses and the
+//! order inside Debug-printed `HashMap`s and `HashSet`s. These helpers
+//! rewrite exactly those parts so snapshots only fail when the educational
+//! text changes.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Volatile values and what they are replaced with.
+static REDACTIONS: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    [
+        // Duration's Debug output: 1.2ms, 350ns, 4.5µs, 1.05s
+        (r"\b\d+(?:\.\d+)?(?:ns|µs|ms|s)\b", "[duration]"),
+        // Speedup/slowdown ratios: 2.03x
+        (r"\b\d+\.\d+x\b", "[ratio]"),
+        // Throughput and per-operation cost: 163.4 MB/s, 12.5 ns/hash
+        (r"\b\d+(?:\.\d+)? (ns/hash|MB/s|GB/s)", "[rate] $1"),
+        // Pointer addresses
+        (r"\b0x[0-9a-f]{6,}\b", "[addr]"),
+        // 64-bit hash values, as 16 hex digits or 15 to 20 decimal ones. The
+        // leading group keeps the digits after a decimal point visible.
+        (r"(^|[^.\w])[0-9a-f]{16}\b", "${1}[hash]"),
+        (r"(^|[^.\w])\d{15,20}\b", "${1}[hash]"),
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| (Regex::new(pattern).expect("valid regex"), replacement))
+    .collect()
+});
+
+/// Replaces every match of each `(pattern, replacement)` rule.
+///
+/// For output that is random in a way the generic helpers can't recognise,
+/// e.g. an arbitrary sample taken from a `HashSet`. Apply it before
+/// [`stabilize`].
+pub fn redact(output: &str, rules: &[(&str, &str)]) -> String {
+    rules
+        .iter()
+        .fold(output.to_string(), |text, (pattern, replacement)| {
+            let regex: Regex = Regex::new(pattern).expect("valid redaction pattern");
+            regex.replace_all(&text, *replacement).into_owned()
+        })
+}
+
+/// Redacts timings, hash values and addresses, and puts the contents of
+/// every `{...}` (the Debug form of maps and sets) into sorted order.
+///
+/// Everything else a demo prints from a `HashMap` or `HashSet` must already
+/// be in a fixed order (sorted, or collected into a `BTreeMap`), so that a
+/// snapshot still catches output that moves.
+pub fn stabilize(output: &str) -> String {
+    output
+        .lines()
+        .map(|line| {
+            let redacted: String = REDACTIONS
+                .iter()
+                .fold(line.to_string(), |text, (regex, replacement)| {
+                    regex.replace_all(&text, *replacement).into_owned()
+                });
+            sort_delimited(&redacted, '{', '}')
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Sorts the comma-separated items inside every `open ... close` group,
+/// recursing into nested groups first. String literals are kept intact.
+fn sort_delimited(line: &str, open: char, close: char) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut result: String = String::with_capacity(line.len());
+    let mut i: usize = 0;
+
+    while i < chars.len() {
+        if chars[i] == open {
+            let (group, next) = sort_group(&chars, i, open, close);
+            result.push_str(&group);
+            i = next;
+        } else {
+            result.push(chars[i]);
+            i += 1;
+        }
+    }
+    result
+}
+
+/// Parses the group starting at `chars[start] == open` and returns it with
+/// its items sorted, plus the index just past the closing delimiter.
+fn sort_group(chars: &[char], start: usize, open: char, close: char) -> (String, usize) {
+    let mut items: Vec<String> = Vec::new();
+    let mut current: String = String::new();
+    let mut depth: usize = 0;
+    let mut i: usize = start + 1;
+
+    while i < chars.len() {
+        let c: char = chars[i];
+        if c == '"' {
+            // Copy the whole string literal, including escaped quotes
+            current.push(c);
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    current.push(chars[i]);
+                    i += 1;
+                }
+                current.push(chars[i]);
+                i += 1;
+            }
+            if i < chars.len() {
+                current.push('"');
+            }
+        } else if c == open {
+            let (group, next) = sort_group(chars, i, open, close);
+            current.push_str(&group);
+            i = next;
+            continue;
+        } else if c == close && depth == 0 {
+            items.push(current);
+            let mut items: Vec<String> = items
+                .into_iter()
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect();
+            items.sort();
+            return (format!("{open}{}{close}", items.join(", ")), i + 1);
+        } else if c == ',' && depth == 0 {
+            items.push(std::mem::take(&mut current));
+        } else {
+            if matches!(c, '(' | '[' | '{') {
+                depth += 1;
+            } else if matches!(c, ')' | ']' | '}') {
+                depth = depth.saturating_sub(1);
+            }
+            current.push(c);
+        }
+        i += 1;
+    }
+
+    // Unbalanced group: leave the rest of the line untouched
+    let rest: String = chars[start..].iter().collect();
+    (rest, chars.len())
+}
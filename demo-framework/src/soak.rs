@@ -0,0 +1,142 @@
+//! Soak runs: one workload repeated for minutes while the live heap is
+//! watched.
+//!
+//! A leak of a few bytes per operation never shows in a demo that runs for a
+//! millisecond. [`run`] calls a workload's round function over and over for
+//! a fixed time and reads [`alloc::live_bytes`] after every round. The run
+//! is split into [`WINDOWS`] equal slices of time, each reporting the highest
+//! live heap it saw:
+//!
+//! ```text
+//! window   rounds   peak live    end live
+//!      1     4210     1.2 MiB     1.2 MiB   (warm-up)
+//!      2     4388     1.3 MiB     1.3 MiB   (warm-up)
+//!      3     4401     1.3 MiB     1.3 MiB
+//!    ...
+//! ```
+//!
+//! The first [`WARM_UP`] windows let caches and pools fill up. After that a
+//! workload that frees what it drops holds a flat line, and
+//! [`SoakReport::plateaued`] checks that the last window's peak is no higher
+//! than the first settled window's, give or take [`SoakReport::tolerance`].
+
+use crate::alloc;
+use crate::report::bytes;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// A workload's round function. It gets the round number, does a batch of
+/// work and leaves its structures in a steady state.
+pub type Round = Box<dyn FnMut(u64)>;
+
+/// Slices of time a run is split into.
+pub const WINDOWS: usize = 10;
+
+/// Leading windows that don't count towards the plateau.
+pub const WARM_UP: usize = 2;
+
+/// Growth below this is allocator noise, whatever the heap size.
+const SLACK_BYTES: u64 = 64 * 1024;
+
+/// One slice of a soak run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Window {
+    pub rounds: u64,
+    /// The highest live heap after any round in the window.
+    pub peak_bytes: u64,
+    /// The live heap after the window's last round.
+    pub end_bytes: u64,
+}
+
+/// What a soak run saw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SoakReport {
+    /// The live heap before the first round.
+    pub start_bytes: u64,
+    pub windows: Vec<Window>,
+}
+
+impl SoakReport {
+    pub fn rounds(&self) -> u64 {
+        self.windows.iter().map(|window| window.rounds).sum()
+    }
+
+    /// The peak of the first window after warm-up.
+    pub fn settled_peak(&self) -> u64 {
+        self.windows[WARM_UP].peak_bytes
+    }
+
+    /// The peak of the last window.
+    pub fn final_peak(&self) -> u64 {
+        self.windows[WINDOWS - 1].peak_bytes
+    }
+
+    /// How far the last peak may sit above the settled one: 5% of it plus
+    /// 64 KiB for the allocator's own bookkeeping.
+    pub fn tolerance(&self) -> u64 {
+        self.settled_peak() / 20 + SLACK_BYTES
+    }
+
+    /// Whether the live heap stopped growing after warm-up.
+    pub fn plateaued(&self) -> bool {
+        self.final_peak() <= self.settled_peak() + self.tolerance()
+    }
+}
+
+/// Calls `round` until `duration` has passed, printing a row per window as
+/// it finishes.
+///
+/// Fails without running anything if [`alloc::TrackingAllocator`] isn't
+/// the global allocator, since there would be nothing to watch.
+pub fn run(
+    out: &mut dyn Write,
+    duration: Duration,
+    round: &mut dyn FnMut(u64),
+) -> io::Result<SoakReport> {
+    let Some(start_bytes) = alloc::live_bytes() else {
+        return Err(io::Error::other(
+            "soak runs need demo_framework::alloc::TrackingAllocator as the global allocator",
+        ));
+    };
+
+    writeln!(
+        out,
+        "{:>6} {:>8} {:>11} {:>11}",
+        "window", "rounds", "peak live", "end live"
+    )?;
+    let window_length: Duration = duration / WINDOWS as u32;
+    let start: Instant = Instant::now();
+    let mut next_round: u64 = 0;
+    let mut windows: Vec<Window> = Vec::with_capacity(WINDOWS);
+    for index in 0..WINDOWS {
+        let deadline: Instant = start + window_length * (index as u32 + 1);
+        let mut window: Window = Window::default();
+        // At least one round per window, however slow a round is
+        loop {
+            round(next_round);
+            next_round += 1;
+            window.rounds += 1;
+            let live: u64 = alloc::live_bytes().unwrap_or(0);
+            window.peak_bytes = window.peak_bytes.max(live);
+            window.end_bytes = live;
+            if Instant::now() >= deadline {
+                break;
+            }
+        }
+        writeln!(
+            out,
+            "{:>6} {:>8} {:>11} {:>11}{}",
+            index + 1,
+            window.rounds,
+            bytes(window.peak_bytes),
+            bytes(window.end_bytes),
+            if index < WARM_UP { "   (warm-up)" } else { "" }
+        )?;
+        windows.push(window);
+    }
+
+    Ok(SoakReport {
+        start_bytes,
+        windows,
+    })
+}
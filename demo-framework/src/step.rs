@@ -0,0 +1,123 @@
+//! Step-through mode for algorithm demos.
+//!
+//! Algorithm demos run their algorithm one step at a time and call
+//! [`pause`] after each step. Normally that does nothing, so the output is
+//! the same as a plain run. With `--step` on the command line, [`pause`]
+//! prints the data structures as they are after the step and waits for
+//! Enter:
+//!
+//! ```text
+//! Step 3: settle (3, 5) at cost 2
+//!   ...rendered state...
+//! [Enter] next step  [c] finish this demo  [q] stop stepping
+//! ```
+//!
+//! Like report hooks, the mode is per thread, so tests that enable it with a
+//! scripted input never affect each other.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+struct StepMode {
+    input: Box<dyn BufRead>,
+    /// Steps shown in the current section so far.
+    steps: usize,
+    /// Set by `c`: run the rest of the section without pausing.
+    skip_section: bool,
+}
+
+thread_local! {
+    static MODE: RefCell<Option<StepMode>> = const { RefCell::new(None) };
+}
+
+/// Turns step mode on for this thread, reading answers from `input`.
+pub fn enable(input: Box<dyn BufRead>) {
+    MODE.with(|mode| {
+        *mode.borrow_mut() = Some(StepMode {
+            input,
+            steps: 0,
+            skip_section: false,
+        })
+    });
+}
+
+/// Turns step mode off for this thread.
+pub fn disable() {
+    MODE.with(|mode| mode.borrow_mut().take());
+}
+
+/// Whether [`pause`] currently stops after each step.
+pub fn is_enabled() -> bool {
+    MODE.with(|mode| {
+        mode.borrow()
+            .as_ref()
+            .is_some_and(|mode| !mode.skip_section)
+    })
+}
+
+/// Ends a step: in step mode, prints `label` and the state `render` draws,
+/// then waits for a line of input. Otherwise returns without formatting
+/// either, so demos can call it in their inner loops.
+///
+/// An empty line moves on to the next step, `c` finishes the current demo
+/// section without pausing, and `q` or the end of the input turns step mode
+/// off for the rest of the run.
+pub fn pause(
+    out: &mut dyn Write,
+    label: fmt::Arguments<'_>,
+    render: impl FnOnce() -> String,
+) -> io::Result<()> {
+    if !is_enabled() {
+        return Ok(());
+    }
+    let step: usize = MODE.with(|mode| {
+        let mut mode = mode.borrow_mut();
+        let mode: &mut StepMode = mode.as_mut().expect("step mode is enabled");
+        mode.steps += 1;
+        mode.steps
+    });
+
+    writeln!(out, "\nStep {}: {}", step, label)?;
+    for line in render().lines() {
+        writeln!(out, "  {}", line)?;
+    }
+    write!(
+        out,
+        "[Enter] next step  [c] finish this demo  [q] stop stepping "
+    )?;
+    out.flush()?;
+
+    let mut answer: String = String::new();
+    let read: usize = MODE.with(|mode| {
+        let mut mode = mode.borrow_mut();
+        let mode: &mut StepMode = mode.as_mut().expect("step mode is enabled");
+        mode.input.read_line(&mut answer)
+    })?;
+    match answer.trim() {
+        _ if read == 0 => {
+            // Nothing was typed, so end the prompt line here
+            writeln!(out)?;
+            disable();
+        }
+        "q" => disable(),
+        "c" => MODE.with(|mode| {
+            if let Some(mode) = mode.borrow_mut().as_mut() {
+                mode.skip_section = true;
+            }
+        }),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Called by [`crate::section`] when a demo finishes: step numbering starts
+/// over and a `c` answer stops applying.
+pub(crate) fn section_finished() {
+    MODE.with(|mode| {
+        if let Some(mode) = mode.borrow_mut().as_mut() {
+            mode.steps = 0;
+            mode.skip_section = false;
+        }
+    });
+}
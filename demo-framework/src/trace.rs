@@ -0,0 +1,109 @@
+//! Structured logging of demo runs with `tracing`.
+//!
+//! Every module runs inside a `module` span and every demo inside a
+//! `section` span, with the name in a field: `module = "vec_examples"`,
+//! `demo = "sorting_demo"`. Each section ends with a `section finished`
+//! event carrying how long it took, and every value a demo passes to
+//! [`crate::embed::record`] becomes a `recorded` event in the span of the
+//! section that recorded it. All of them use the `demo` target, so
+//! `RUST_LOG=demo=info` selects exactly these.
+//!
+//! The demo text still goes to the injected writer; the trace goes to
+//! stderr, so the two never mix. The scenario command line installs a
+//! subscriber before running anything:
+//!
+//! ```text
+//! RUST_LOG=demo=info cargo run -- vec        # readable lines on stderr
+//! cargo run -- --log-json vec 2> run.jsonl    # one JSON object per line
+//! ```
+//!
+//! Without `RUST_LOG`, JSON keeps the `demo` events at `info` and the
+//! readable format keeps only `warn`, which the demos never reach, so a
+//! plain run prints only the demos.
+
+use std::io;
+use std::time::Duration;
+use tracing::Span;
+use tracing::Subscriber;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Target of every span and event emitted for a demo run.
+pub const TARGET: &str = "demo";
+
+/// How the installed subscriber writes the trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable lines.
+    Text,
+    /// One JSON object per event, with the enclosing spans attached.
+    Json,
+}
+
+impl Format {
+    /// The filter used when `RUST_LOG` is not set.
+    pub fn default_filter(self) -> &'static str {
+        match self {
+            Format::Text => "warn",
+            Format::Json => "demo=info",
+        }
+    }
+}
+
+/// A subscriber writing `format` to `writer`, keeping what `filter`
+/// (in `RUST_LOG` syntax) lets through.
+pub fn subscriber<W>(format: Format, filter: &str, writer: W) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(filter))
+        .with_ansi(false)
+        .with_writer(writer);
+    match format {
+        Format::Text => Box::new(builder.finish()),
+        Format::Json => Box::new(
+            builder
+                .json()
+                .flatten_event(true)
+                .with_current_span(false)
+                .with_span_list(true)
+                .finish(),
+        ),
+    }
+}
+
+/// Installs a stderr subscriber for the rest of the process, filtered by
+/// `RUST_LOG` or else by the format's default. Does nothing if one is
+/// already installed.
+pub fn install(format: Format) {
+    let filter: String =
+        std::env::var("RUST_LOG").unwrap_or_else(|_| format.default_filter().to_string());
+    // Fails only when a global subscriber is already set, which is fine
+    let _ = tracing::subscriber::set_global_default(subscriber(format, &filter, io::stderr));
+}
+
+/// The span a module's `run_all` runs in.
+pub(crate) fn module_span(name: &str) -> Span {
+    tracing::info_span!(target: TARGET, "module", module = name)
+}
+
+/// The span a single demo runs in.
+pub(crate) fn section_span(name: &str) -> Span {
+    tracing::info_span!(target: TARGET, "section", demo = name)
+}
+
+/// Emitted as a section returns, inside its span.
+pub(crate) fn section_finished(elapsed: Duration, ok: bool) {
+    tracing::info!(
+        target: TARGET,
+        elapsed_us = elapsed.as_micros() as u64,
+        ok,
+        "section finished"
+    );
+}
+
+/// Emitted by [`crate::embed::record`].
+pub(crate) fn recorded(key: &str, value: &str) {
+    tracing::info!(target: TARGET, key, value, "recorded");
+}
@@ -0,0 +1,126 @@
+//! One entry point for every scenario in the workspace.
+//!
+//! Usage:
+//!   cargo run -p playground -- list
+//!   cargo run -p playground -- run collections hashmap
+//!   cargo run -p playground -- bench hashing --quick
+//!   cargo run -p playground -- new-scenario <name>
+//!
+//! `run` and `bench` look the scenario up by short name, package, directory
+//! or number and hand the remaining arguments to `cargo run` or
+//! `cargo bench` for its crate, from the workspace root. `new-scenario` is
+//! kept for existing muscle memory and forwards to the same generator as
+//! `cargo xtask new-scenario`.
+
+use std::env;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+use xtask::scenarios::{self, ScenarioCrate};
+
+const USAGE: &str = "\
+Usage: playground <command>
+
+Commands:
+  list                                  List the scenarios
+  run [--release] <scenario> [args...]  Run a scenario's demos; args go to its CLI
+                                        (e.g. `run collections hashmap`, `run 1 --list`)
+  bench <scenario> [args...]            Run a scenario's benchmarks; args go to Criterion
+                                        (e.g. `bench hashing --quick`)
+  new-scenario <name>                   Generate a new scenario crate (e.g. `smart-pointers`)
+  help                                  Print this message
+
+A scenario is named by its short name (`collections`), package (`collections_demo`),
+directory or number (`1`).";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result: Result<ExitCode, Box<dyn Error>> = match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<&str>>()
+        .as_slice()
+    {
+        ["list"] => list(),
+        ["run", "--release", scenario, rest @ ..] => cargo("run", &["--release"], scenario, rest),
+        ["run", scenario, rest @ ..] => cargo("run", &[], scenario, rest),
+        // Every bench target, so libtest's harness for the lib never sees
+        // Criterion's arguments
+        ["bench", scenario, rest @ ..] => cargo("bench", &["--bench", "*"], scenario, rest),
+        ["new-scenario", name] => xtask::scaffold::new_scenario(name).map(|scenario| {
+            scenario.print_next_steps();
+            ExitCode::SUCCESS
+        }),
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{USAGE}");
+            Ok(ExitCode::SUCCESS)
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            Ok(ExitCode::from(2))
+        }
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn list() -> Result<ExitCode, Box<dyn Error>> {
+    let root: PathBuf = scenarios::workspace_root()?;
+    for scenario in scenarios::discover(&root)? {
+        println!(
+            "{:>2}  {:<12} {:<18} {}",
+            scenario.number, scenario.short_name, scenario.package, scenario.dir
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Runs `cargo <subcommand> <flags> -p <package> -- <rest>` from the
+/// workspace root and exits with its status.
+fn cargo(
+    subcommand: &str,
+    flags: &[&str],
+    query: &str,
+    rest: &[&str],
+) -> Result<ExitCode, Box<dyn Error>> {
+    let root: PathBuf = scenarios::workspace_root()?;
+    let scenarios: Vec<ScenarioCrate> = scenarios::discover(&root)?;
+    let Some(scenario) = scenarios.iter().find(|scenario| scenario.matches(query)) else {
+        let known: Vec<&str> = scenarios
+            .iter()
+            .map(|scenario| scenario.short_name.as_str())
+            .collect();
+        eprintln!(
+            "error: unknown scenario `{query}` (known: {})",
+            known.join(", ")
+        );
+        return Ok(ExitCode::from(2));
+    };
+
+    // Cargo sets $CARGO for `cargo run -p playground`
+    let cargo: String = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut command: Command = Command::new(cargo);
+    command
+        .current_dir(&root)
+        .arg(subcommand)
+        .args(flags)
+        .args(["-p", &scenario.package]);
+    if !rest.is_empty() {
+        command.arg("--").args(rest);
+    }
+
+    let status = command.status()?;
+    Ok(match status.code() {
+        Some(0) => ExitCode::SUCCESS,
+        Some(code) => ExitCode::from(u8::try_from(code).unwrap_or(1)),
+        // Killed by a signal
+        None => ExitCode::FAILURE,
+    })
+}
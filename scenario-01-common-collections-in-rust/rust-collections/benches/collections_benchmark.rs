@@ -13,6 +13,34 @@
 // To run a specific benchmark group:
 //   cargo bench -- Insertions
 //   cargo bench -- Lookups
+//   cargo bench -- Small_Sizes
+//   cargo bench -- String_Keys
+//   cargo bench -- Sorted_Vec_Set
+//   cargo bench -- IndexMap
+//   cargo bench -- Bitsets
+//   cargo bench -- Enum_Keyed_Maps
+//   cargo bench -- LRU_Cache
+//   cargo bench -- Streaming_Stats
+//   cargo bench -- Map_Diff
+//   cargo bench -- Composite_Keys
+//   cargo bench -- Iteration_Order
+//   cargo bench -- Union_Find
+//   cargo bench -- Graph_Representations
+//   cargo bench -- Persistent_Collections
+//   cargo bench -- MultiMap
+//   cargo bench -- Object_Pool
+//   cargo bench -- Arena
+//   cargo bench -- Sorting
+//   cargo bench -- Static_Lookups
+//   cargo bench -- Matrix
+//   cargo bench -- Spatial_Hash
+//   cargo bench -- KdTree
+//   cargo bench -- Conditional_Removal
+//   cargo bench -- Iterator_vs_Loop
+//   cargo bench -- Cow_Normalization
+//   cargo bench -- Timer_Queues
+//   cargo bench -- Map_Views
+//   cargo bench -- Columnar_Group_By
 //
 // Results are saved to target/criterion/ with HTML reports.
 //
@@ -38,13 +66,81 @@
 //
 // ============================================================================
 
+use arrayvec::ArrayVec;
+use bitvec::prelude::*;
+use bumpalo::Bump;
+use collections_demo::arena_examples::{ArenaTree, BoxTree, Node, tree_keys};
+use collections_demo::bitset_examples::WordBitSet;
+use collections_demo::columnar_examples::{
+    SaleRow, SalesColumns, group_codes, group_columns, group_rows, sales_rows,
+};
+use collections_demo::composite_keys_examples::{
+    LastLogin, Tenant, TenantDirectory, User, directory,
+};
+use collections_demo::cow_examples::{normalize, normalize_to_string, sample_log_lines};
+use collections_demo::enum_map_examples::{
+    Level, LevelTable, count_array, count_btreemap, count_enum_map, count_hashmap, log_levels,
+};
+use collections_demo::extract_if_examples::extract_matching_fallback;
+use collections_demo::graph_examples::{
+    self, AdjacencyList, Csr, HashGraph, adjacency_list, adjacency_matrix, bfs, edge_checksum,
+    hash_graph,
+};
+use collections_demo::grid_examples::{Grid, sum_column_major, sum_row_major};
+use collections_demo::heap_alternatives_examples::{
+    DaryHeap, Graph, PairingHeap, dijkstra_binary_heap, dijkstra_dary_heap, dijkstra_pairing_heap,
+    random_graph,
+};
+use collections_demo::iteration_order_examples::{compressed_len, sales, serialize, subtotals};
+use collections_demo::iterator_examples::{
+    dot_indexed, dot_zip, sample_values, sum_even_squares_indexed, sum_even_squares_iter,
+};
+use collections_demo::kdtree_examples::{KdTree, linear_nearest, linear_range};
+use collections_demo::lru_examples::{DequeLru, LinkedLru, skewed_keys};
+use collections_demo::map_diff_examples::{diff_maps, inventory_snapshots};
+use collections_demo::map_view_examples::MapView;
+use collections_demo::matrix_examples::{
+    grid_sum_by_columns, grid_sum_by_rows, multiply_ijk, multiply_ikj, multiply_transposed,
+    nested_from_grid, nested_sum_by_columns, nested_sum_by_rows, sample_matrix,
+};
+use collections_demo::multimap_examples::{MultiMap, SmallMultiMap};
+use collections_demo::nested_map_examples::{flatten, nest_sorted};
+use collections_demo::object_pool_examples::{Pool, Pooled, buffer_pool};
+use collections_demo::set_examples::SortedVecSet;
+use collections_demo::simulation_examples::{EventQueue, Time, TimerWheel, timer_deadlines};
+use collections_demo::spatial_examples::{
+    EntityId, Point, SpatialHash, brute_force_pairs, scatter,
+};
+use collections_demo::static_table_examples::{
+    HTTP_STATUSES, STATUS_MAP, WEEKDAY_NAMES, Weekday, parse_weekday, status_reason,
+    status_reason_hashed, status_reason_indexed,
+};
+use collections_demo::streaming_stats_examples::{
+    RunningMedian, latencies, naive_running_medians, naive_sliding_window_max, sliding_window_max,
+};
+use collections_demo::union_find_examples::{
+    Edge, QuickUnion, UnionFind, kruskal, random_edges, random_pairs,
+};
 use criterion::measurement::WallTime;
 use criterion::{
     BenchmarkGroup, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main,
 };
-use std::cmp::Reverse;
+use demo_framework::rng::Lcg;
+use enum_map::EnumMap;
+use indexmap::IndexMap;
+use lru::LruCache;
+use slab::Slab;
+use slotmap::{DefaultKey, DenseSlotMap, SlotMap};
+use smallvec::SmallVec;
+use std::cmp::{Ordering, Reverse};
+use std::collections::hash_map::Entry;
 use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
 use std::hint::black_box;
+use std::num::NonZeroUsize;
+use std::sync::LazyLock;
+use std::time::Duration;
+use tinyvec::TinyVec;
+use typed_arena::Arena;
 
 // ============================================================================
 // INSERTION BENCHMARKS
@@ -299,6 +395,290 @@ fn bench_lookups(c: &mut Criterion) {
     group.finish();
 }
 
+// ============================================================================
+// SMALL-SIZE BENCHMARKS
+// ============================================================================
+// The Lookups group starts at 100 elements. Below that, hashing a key can
+// cost more than comparing it against every element, so this group repeats
+// map-style get and insert at 4 to 32 entries of (u64, u64): a Vec and an
+// ArrayVec<_, 32> scanned linearly, a HashMap and a BTreeMap.
+//
+// "get" looks up every key plus as many absent ones, in scrambled order, so
+// the linear scans pay for misses too. "insert" builds the collection from
+// empty, checking for each key before adding it, as a map does.
+
+/// The value stored under `key`, by checking every entry.
+fn scan_get(entries: &[(u64, u64)], key: u64) -> Option<u64> {
+    entries.iter().find(|(k, _)| *k == key).map(|&(_, v)| v)
+}
+
+fn bench_small_sizes(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Small_Sizes");
+
+    for size in [4u64, 8, 16, 32] {
+        // Scattered keys, so neither the scan order nor the tree order
+        // happens to match the query order
+        let keys: Vec<u64> = (0..size)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 16)
+            .collect();
+        let mut queries: Vec<u64> = keys.iter().flat_map(|&k| [k, k + 1]).collect();
+        queries.reverse();
+        queries.rotate_left(size as usize / 2);
+
+        let vec: Vec<(u64, u64)> = keys.iter().map(|&k| (k, k * 2)).collect();
+        let array: ArrayVec<(u64, u64), 32> = vec.iter().copied().collect();
+        let hashmap: HashMap<u64, u64> = vec.iter().copied().collect();
+        let btreemap: BTreeMap<u64, u64> = vec.iter().copied().collect();
+
+        group.throughput(Throughput::Elements(queries.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("Vec_get", size), &queries, |b, queries| {
+            b.iter(|| {
+                queries
+                    .iter()
+                    .filter_map(|&q| scan_get(black_box(&vec), q))
+                    .sum::<u64>()
+            })
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("ArrayVec_get", size),
+            &queries,
+            |b, queries| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter_map(|&q| scan_get(black_box(&array), q))
+                        .sum::<u64>()
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("HashMap_get", size),
+            &queries,
+            |b, queries| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter_map(|q| black_box(&hashmap).get(q).copied())
+                        .sum::<u64>()
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("BTreeMap_get", size),
+            &queries,
+            |b, queries| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter_map(|q| black_box(&btreemap).get(q).copied())
+                        .sum::<u64>()
+                })
+            },
+        );
+
+        group.throughput(Throughput::Elements(size));
+
+        group.bench_with_input(BenchmarkId::new("Vec_insert", size), &keys, |b, keys| {
+            b.iter(|| {
+                let mut entries: Vec<(u64, u64)> = Vec::new();
+                for &key in black_box(keys) {
+                    match entries.iter_mut().find(|(k, _)| *k == key) {
+                        Some(entry) => entry.1 = key,
+                        None => entries.push((key, key)),
+                    }
+                }
+                entries
+            })
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("ArrayVec_insert", size),
+            &keys,
+            |b, keys| {
+                b.iter(|| {
+                    let mut entries: ArrayVec<(u64, u64), 32> = ArrayVec::new();
+                    for &key in black_box(keys) {
+                        match entries.iter_mut().find(|(k, _)| *k == key) {
+                            Some(entry) => entry.1 = key,
+                            None => entries.push((key, key)),
+                        }
+                    }
+                    entries
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("HashMap_insert", size),
+            &keys,
+            |b, keys| {
+                b.iter(|| {
+                    let mut entries: HashMap<u64, u64> = HashMap::new();
+                    for &key in black_box(keys) {
+                        entries.insert(key, key);
+                    }
+                    entries
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("BTreeMap_insert", size),
+            &keys,
+            |b, keys| {
+                b.iter(|| {
+                    let mut entries: BTreeMap<u64, u64> = BTreeMap::new();
+                    for &key in black_box(keys) {
+                        entries.insert(key, key);
+                    }
+                    entries
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// STRING KEY BENCHMARKS
+// ============================================================================
+// Every other group keys by integers. Real maps are often keyed by text, and
+// then hashing and comparing the key is the work. Two key shapes:
+//
+//   short: "user00482"                           9 bytes
+//   long:  "https://shop.example.com/api/v2/..."  79 bytes, and every key
+//          shares its first 52 with the others
+//
+// HashMap hashes every byte once per lookup. BTreeMap and a sorted Vec
+// compare keys instead, and a comparison stops at the first differing byte,
+// so a long shared prefix is read again at every step of the search.
+// "get" looks up 1,000 keys per iteration in scrambled order; "insert" times
+// filling the collection from keys that were cloned outside the timing.
+
+/// `count` keys of one shape, in scrambled order.
+fn string_keys(count: usize, long: bool) -> Vec<String> {
+    (0..count)
+        .map(|i| {
+            let n: usize = i.wrapping_mul(7_919) % count;
+            if long {
+                format!(
+                    "https://shop.example.com/api/v2/tenants/0042/orders/{:06}/items?expand=details",
+                    n
+                )
+            } else {
+                format!("user{:05}", n)
+            }
+        })
+        .collect()
+}
+
+fn bench_string_keys(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("String_Keys");
+
+    for (shape, long) in [("short", false), ("long", true)] {
+        for size in [1_000usize, 100_000] {
+            let keys: Vec<String> = string_keys(size, long);
+            let queries: Vec<&str> = keys
+                .iter()
+                .step_by(size / 1_000)
+                .map(String::as_str)
+                .collect();
+
+            let hashmap: HashMap<String, usize> = keys.iter().cloned().zip(0..).collect();
+            let btreemap: BTreeMap<String, usize> = keys.iter().cloned().zip(0..).collect();
+            let mut sorted: Vec<(String, usize)> = keys.iter().cloned().zip(0..).collect();
+            sorted.sort_unstable();
+
+            let id = |name: &str| BenchmarkId::new(format!("{}_{}", name, shape), size);
+
+            group.throughput(Throughput::Elements(queries.len() as u64));
+
+            group.bench_with_input(id("HashMap_get"), &queries, |b, queries| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter_map(|&q| hashmap.get(black_box(q)))
+                        .sum::<usize>()
+                })
+            });
+
+            group.bench_with_input(id("BTreeMap_get"), &queries, |b, queries| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter_map(|&q| btreemap.get(black_box(q)))
+                        .sum::<usize>()
+                })
+            });
+
+            group.bench_with_input(id("SortedVec_get"), &queries, |b, queries| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter_map(|&q| {
+                            let q: &str = black_box(q);
+                            sorted
+                                .binary_search_by(|(k, _)| k.as_str().cmp(q))
+                                .ok()
+                                .map(|i| sorted[i].1)
+                        })
+                        .sum::<usize>()
+                })
+            });
+
+            group.throughput(Throughput::Elements(size as u64));
+
+            group.bench_with_input(id("HashMap_insert"), &keys, |b, keys| {
+                b.iter_batched(
+                    || keys.clone(),
+                    |keys| {
+                        let mut map: HashMap<String, usize> = HashMap::with_capacity(keys.len());
+                        for (value, key) in keys.into_iter().enumerate() {
+                            map.insert(key, value);
+                        }
+                        map
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            });
+
+            group.bench_with_input(id("BTreeMap_insert"), &keys, |b, keys| {
+                b.iter_batched(
+                    || keys.clone(),
+                    |keys| {
+                        let mut map: BTreeMap<String, usize> = BTreeMap::new();
+                        for (value, key) in keys.into_iter().enumerate() {
+                            map.insert(key, value);
+                        }
+                        map
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            });
+
+            // Collect, then sort once: the way to build a sorted Vec
+            group.bench_with_input(id("SortedVec_build"), &keys, |b, keys| {
+                b.iter_batched(
+                    || keys.clone(),
+                    |keys| {
+                        let mut entries: Vec<(String, usize)> = keys.into_iter().zip(0..).collect();
+                        entries.sort_unstable();
+                        entries
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+
+    group.finish();
+}
+
 // ============================================================================
 // FRONT OPERATIONS BENCHMARKS
 // ============================================================================
@@ -472,6 +852,193 @@ fn bench_range_queries(c: &mut Criterion) {
     group.finish();
 }
 
+// ============================================================================
+// MAP VIEW BENCHMARKS
+// ============================================================================
+// The same query - a quarter of the keys, readings above a threshold - read
+// once through a MapView and through snapshots collected into a BTreeMap or
+// a Vec first. `sum` reads every match; `top_10` stops after ten, which the
+// lazy view does and a snapshot can't, since it collects every match first.
+
+fn bench_map_views(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Map_Views");
+
+    for size in [10_000u64, 1_000_000] {
+        let readings: BTreeMap<u64, f64> = (0..size)
+            .map(|second| (second, 20.0 + (second % 60) as f64 / 10.0))
+            .collect();
+        let window = size / 4..size / 2;
+        group.throughput(Throughput::Elements(window.end - window.start));
+
+        let view = MapView::new(&readings)
+            .range(window.clone())
+            .filter(|_, celsius| *celsius > 24.0);
+        group.bench_function(BenchmarkId::new("sum/MapView", size), |b| {
+            b.iter(|| black_box(&view).values().sum::<f64>())
+        });
+        group.bench_function(BenchmarkId::new("sum/BTreeMap_snapshot", size), |b| {
+            b.iter(|| {
+                let snapshot: BTreeMap<u64, f64> = black_box(&readings)
+                    .range(window.clone())
+                    .filter(|(_, celsius)| **celsius > 24.0)
+                    .map(|(&second, &celsius)| (second, celsius))
+                    .collect();
+                snapshot.values().sum::<f64>()
+            })
+        });
+        group.bench_function(BenchmarkId::new("sum/Vec_snapshot", size), |b| {
+            b.iter(|| {
+                let snapshot: Vec<(u64, f64)> = black_box(&readings)
+                    .range(window.clone())
+                    .filter(|(_, celsius)| **celsius > 24.0)
+                    .map(|(&second, &celsius)| (second, celsius))
+                    .collect();
+                snapshot.iter().map(|(_, celsius)| celsius).sum::<f64>()
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("top_10/MapView", size), |b| {
+            b.iter(|| black_box(&view).keys().take(10).sum::<u64>())
+        });
+        group.bench_function(BenchmarkId::new("top_10/Vec_snapshot", size), |b| {
+            b.iter(|| {
+                let snapshot: Vec<(u64, f64)> = black_box(&readings)
+                    .range(window.clone())
+                    .filter(|(_, celsius)| **celsius > 24.0)
+                    .map(|(&second, &celsius)| (second, celsius))
+                    .collect();
+                snapshot
+                    .iter()
+                    .take(10)
+                    .map(|(second, _)| second)
+                    .sum::<u64>()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// SORTED VEC AS A SET BENCHMARKS
+// ============================================================================
+// SortedVecSet (binary search over one slice) against HashSet and BTreeSet:
+// contains on hits and misses at three sizes, building the set from unsorted
+// values, and intersecting two sets of equal size and of very different
+// sizes (merge vs binary search per element of the small set).
+
+fn bench_sorted_vec_set(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Sorted_Vec_Set");
+
+    for size in [16u64, 1_000, 100_000] {
+        // Odd multiples of a large odd number: spread out, no duplicates
+        let values: Vec<u64> = (0..size)
+            .map(|i| (i * 2 + 1).wrapping_mul(0x9E37_79B9) % (size * 8))
+            .collect();
+        let sorted: SortedVecSet<u64> = SortedVecSet::from_vec(values.clone());
+        let hashed: HashSet<u64> = values.iter().copied().collect();
+        let tree: BTreeSet<u64> = values.iter().copied().collect();
+        // Half hits, half misses
+        let queries: Vec<u64> = (0..1_000u64)
+            .map(|i| {
+                if i % 2 == 0 {
+                    values[(i * 7 % size) as usize]
+                } else {
+                    i.wrapping_mul(0x9E37_79B9) % (size * 8)
+                }
+            })
+            .collect();
+        group.throughput(Throughput::Elements(queries.len() as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("contains/SortedVecSet", size),
+            &size,
+            |b, _| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter(|q| sorted.contains(black_box(q)))
+                        .count()
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("contains/HashSet", size), &size, |b, _| {
+            b.iter(|| {
+                queries
+                    .iter()
+                    .filter(|q| hashed.contains(black_box(q)))
+                    .count()
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("contains/BTreeSet", size),
+            &size,
+            |b, _| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .filter(|q| tree.contains(black_box(q)))
+                        .count()
+                })
+            },
+        );
+
+        group.throughput(Throughput::Elements(size));
+        group.bench_with_input(
+            BenchmarkId::new("build/SortedVecSet", size),
+            &size,
+            |b, _| b.iter(|| SortedVecSet::from_vec(black_box(values.clone())).len()),
+        );
+        group.bench_with_input(BenchmarkId::new("build/HashSet", size), &size, |b, _| {
+            b.iter(|| {
+                black_box(&values)
+                    .iter()
+                    .copied()
+                    .collect::<HashSet<u64>>()
+                    .len()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("build/BTreeSet", size), &size, |b, _| {
+            b.iter(|| {
+                black_box(&values)
+                    .iter()
+                    .copied()
+                    .collect::<BTreeSet<u64>>()
+                    .len()
+            })
+        });
+    }
+
+    // Multiples of 5 against multiples of 3 (similar sizes) or of 499 (500x
+    // fewer, spread over the same range, so the merge walks all of both)
+    let multiples = |every: u64| (0..500_000u64).filter(move |n| n % every == 0);
+    for (label, every) in [("equal", 3u64), ("lopsided", 499)] {
+        let a: SortedVecSet<u64> = multiples(every).collect();
+        let b: SortedVecSet<u64> = multiples(5).collect();
+        let a_hash: HashSet<u64> = a.iter().copied().collect();
+        let b_hash: HashSet<u64> = b.iter().copied().collect();
+        let a_tree: BTreeSet<u64> = a.iter().copied().collect();
+        let b_tree: BTreeSet<u64> = b.iter().copied().collect();
+
+        group.throughput(Throughput::Elements((a.len() + b.len()) as u64));
+        group.bench_function(format!("intersect_{}/SortedVecSet_merge", label), |bench| {
+            bench.iter(|| a.intersection(black_box(&b)).len())
+        });
+        group.bench_function(
+            format!("intersect_{}/SortedVecSet_search", label),
+            |bench| bench.iter(|| a.intersection_by_search(black_box(&b)).len()),
+        );
+        group.bench_function(format!("intersect_{}/HashSet", label), |bench| {
+            bench.iter(|| a_hash.intersection(black_box(&b_hash)).count())
+        });
+        group.bench_function(format!("intersect_{}/BTreeSet", label), |bench| {
+            bench.iter(|| a_tree.intersection(black_box(&b_tree)).count())
+        });
+    }
+
+    group.finish();
+}
+
 // ============================================================================
 // PRIORITY QUEUE BENCHMARKS
 // ============================================================================
@@ -535,114 +1102,334 @@ fn bench_priority_operations(c: &mut Criterion) {
 }
 
 // ============================================================================
-// ENTRY API BENCHMARKS
+// HEAP ALTERNATIVE BENCHMARKS
 // ============================================================================
-// Demonstrates why the Entry API is more efficient than contains_key + insert.
+// BinaryHeap against a D-ary heap and a pairing heap. Plain push/pop is
+// BinaryHeap's home ground; the alternatives should win when pushes dominate,
+// when Dijkstra has many decrease-keys (a dense graph), and when many heaps
+// are merged before taking only the top few elements.
 
-fn bench_entry_api(c: &mut Criterion) {
-    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Entry_API");
+fn bench_heap_alternatives(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Heap_Alternatives");
 
-    // Generate sample text for word counting
-    let words: Vec<&str> = vec![
-        "the", "quick", "brown", "fox", "jumps", "over", "the", "lazy", "dog", "the", "fox", "is",
-        "quick", "and", "the", "dog", "is", "lazy",
-    ];
-    let text: Vec<&str> = (0..10_000).map(|i| words[i % words.len()]).collect();
+    let size: u32 = 10_000;
+    let values: Vec<u32> = (0..size)
+        .map(|i| i.wrapping_mul(2_654_435_761) % size)
+        .collect();
 
-    // GOOD: Entry API - single lookup per word
-    group.bench_function("HashMap_entry_api", |b| {
+    group.bench_function("push_pop/BinaryHeap", |b| {
         b.iter(|| {
-            let mut counts: HashMap<&str, i32> = HashMap::new();
-            for word in &text {
-                *counts.entry(*word).or_insert(0) += 1;
+            let mut heap: BinaryHeap<Reverse<u32>> = BinaryHeap::new();
+            for &v in &values {
+                heap.push(Reverse(black_box(v)));
             }
-            counts
+            let mut sum: u64 = 0;
+            while let Some(Reverse(v)) = heap.pop() {
+                sum += v as u64;
+            }
+            sum
         })
     });
 
-    // BAD: contains_key + get_mut - two lookups per word
-    group.bench_function("HashMap_contains_key", |b| {
+    fn push_pop_dary<const D: usize>(values: &[u32]) -> u64 {
+        let mut heap: DaryHeap<u32, D> = DaryHeap::new();
+        for &v in values {
+            heap.push(black_box(v));
+        }
+        let mut sum: u64 = 0;
+        while let Some(v) = heap.pop() {
+            sum += v as u64;
+        }
+        sum
+    }
+
+    group.bench_function("push_pop/DaryHeap<2>", |b| {
+        b.iter(|| push_pop_dary::<2>(&values))
+    });
+    group.bench_function("push_pop/DaryHeap<4>", |b| {
+        b.iter(|| push_pop_dary::<4>(&values))
+    });
+    group.bench_function("push_pop/DaryHeap<8>", |b| {
+        b.iter(|| push_pop_dary::<8>(&values))
+    });
+
+    group.bench_function("push_pop/PairingHeap", |b| {
         b.iter(|| {
-            let mut counts: HashMap<&str, i32> = HashMap::new();
-            for word in &text {
-                if counts.contains_key(word) {
-                    *counts.get_mut(word).unwrap() += 1;
-                } else {
-                    counts.insert(*word, 1);
-                }
+            let mut heap: PairingHeap<u32> = PairingHeap::new();
+            for &v in &values {
+                heap.push(black_box(v));
             }
-            counts
+            let mut sum: u64 = 0;
+            while let Some(v) = heap.pop() {
+                sum += v as u64;
+            }
+            sum
         })
     });
 
-    // BTreeMap Entry API for comparison
-    group.bench_function("BTreeMap_entry_api", |b| {
+    // Push-heavy: fill the heap, then take only the first 1%
+    group.bench_function("push_heavy/BinaryHeap", |b| {
         b.iter(|| {
-            let mut counts: BTreeMap<&str, i32> = BTreeMap::new();
-            for word in &text {
-                *counts.entry(*word).or_insert(0) += 1;
+            let mut heap: BinaryHeap<Reverse<u32>> = BinaryHeap::new();
+            for &v in &values {
+                heap.push(Reverse(black_box(v)));
             }
-            counts
+            (0..100).filter_map(|_| heap.pop()).count()
         })
     });
 
-    group.finish();
-}
+    fn push_heavy_dary<const D: usize>(values: &[u32]) -> usize {
+        let mut heap: DaryHeap<u32, D> = DaryHeap::new();
+        for &v in values {
+            heap.push(black_box(v));
+        }
+        (0..100).filter_map(|_| heap.pop()).count()
+    }
 
-// ============================================================================
-// REMOVAL BENCHMARKS
-// ============================================================================
-// Measures the cost of removing elements from different collections.
+    group.bench_function("push_heavy/DaryHeap<2>", |b| {
+        b.iter(|| push_heavy_dary::<2>(&values))
+    });
+    group.bench_function("push_heavy/DaryHeap<4>", |b| {
+        b.iter(|| push_heavy_dary::<4>(&values))
+    });
+    group.bench_function("push_heavy/DaryHeap<8>", |b| {
+        b.iter(|| push_heavy_dary::<8>(&values))
+    });
 
-fn bench_removals(c: &mut Criterion) {
-    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Removals");
+    // A sparse graph has few improvements per node; a dense one has many,
+    // which is where lazy re-pushing piles up stale entries
+    for (nodes, extra_edges) in [(10_000, 8), (1_000, 200)] {
+        let graph: Graph = random_graph(nodes, extra_edges, 42);
+        let shape: String = format!("{}x{}", nodes, extra_edges);
 
-    let size: i32 = 1_000i32;
+        group.bench_function(BenchmarkId::new("dijkstra/BinaryHeap_lazy", &shape), |b| {
+            b.iter(|| dijkstra_binary_heap(black_box(&graph), 0))
+        });
+        group.bench_function(BenchmarkId::new("dijkstra/DaryHeap<4>_lazy", &shape), |b| {
+            b.iter(|| dijkstra_dary_heap::<4>(black_box(&graph), 0))
+        });
+        group.bench_function(
+            BenchmarkId::new("dijkstra/PairingHeap_decrease_key", &shape),
+            |b| b.iter(|| dijkstra_pairing_heap(black_box(&graph), 0)),
+        );
+    }
 
-    // Vec: Remove from end (O(1)) vs remove from front (O(n))
-    group.bench_function("Vec_pop_back", |b| {
+    // 100 heaps of 1,000 merged into one, then the 10 smallest taken
+    let shards: Vec<Vec<u32>> = (0..100u32)
+        .map(|shard| {
+            (0..1_000u32)
+                .map(|i| (shard * 7_919 + i * 104_729) % 1_000_000)
+                .collect()
+        })
+        .collect();
+
+    group.bench_function("merge_then_top_10/BinaryHeap", |b| {
         b.iter_batched(
-            || (0..size).collect::<Vec<i32>>(),
-            |mut v: Vec<i32>| {
-                while v.pop().is_some() {}
-                v
+            || {
+                shards
+                    .iter()
+                    .map(|s| s.iter().map(|&v| Reverse(v)).collect())
+                    .collect::<Vec<BinaryHeap<Reverse<u32>>>>()
+            },
+            |mut heaps| {
+                let mut merged: BinaryHeap<Reverse<u32>> = BinaryHeap::new();
+                for heap in &mut heaps {
+                    merged.append(heap);
+                }
+                (0..10).filter_map(|_| merged.pop()).count()
             },
             criterion::BatchSize::SmallInput,
         )
     });
 
-    group.bench_function("Vec_remove_front", |b| {
+    group.bench_function("merge_then_top_10/PairingHeap", |b| {
         b.iter_batched(
-            || (0..size).collect::<Vec<i32>>(),
-            |mut v: Vec<i32>| {
-                while !v.is_empty() {
-                    v.remove(0);
+            || {
+                shards
+                    .iter()
+                    .map(|s| {
+                        let mut heap: PairingHeap<u32> = PairingHeap::new();
+                        for &v in s {
+                            heap.push(v);
+                        }
+                        heap
+                    })
+                    .collect::<Vec<PairingHeap<u32>>>()
+            },
+            |heaps| {
+                let mut merged: PairingHeap<u32> = PairingHeap::new();
+                for heap in heaps {
+                    merged.merge(heap);
                 }
-                v
+                (0..10).filter_map(|_| merged.pop()).count()
             },
             criterion::BatchSize::SmallInput,
         )
     });
 
-    // VecDeque: O(1) from both ends
-    group.bench_function("VecDeque_pop_front", |b| {
-        b.iter_batched(
-            || (0..size).collect::<VecDeque<i32>>(),
-            |mut d: VecDeque<i32>| {
-                while d.pop_front().is_some() {}
-                d
+    group.finish();
+}
+
+// ============================================================================
+// STREAMING STATISTICS BENCHMARKS
+// ============================================================================
+// The running median after every value, from two heaps vs re-selecting the
+// middle of everything so far, and the maximum of every window, from a
+// monotonic VecDeque vs scanning each window. The incremental versions do
+// the same work per value at any size; the naive ones grow with the history
+// or the window.
+
+fn bench_streaming_stats(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Streaming_Stats");
+    group.sample_size(10);
+
+    for n in [1_000usize, 10_000] {
+        let values: Vec<u64> = latencies(n, 42);
+        group.throughput(Throughput::Elements(n as u64));
+
+        group.bench_with_input(BenchmarkId::new("median/two_heaps", n), &n, |b, _| {
+            b.iter(|| {
+                let mut median: RunningMedian<u64> = RunningMedian::new();
+                let mut sum: u64 = 0;
+                for &value in &values {
+                    median.push(black_box(value));
+                    sum += median.low_median().copied().unwrap_or(0);
+                }
+                sum
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("median/recompute", n), &n, |b, _| {
+            b.iter(|| naive_running_medians(black_box(&values)).len())
+        });
+    }
+
+    let values: Vec<u64> = latencies(100_000, 7);
+    group.throughput(Throughput::Elements(values.len() as u64));
+    for window in [16usize, 256, 4_096] {
+        group.bench_with_input(
+            BenchmarkId::new("window_max/monotonic_deque", window),
+            &window,
+            |b, &window| b.iter(|| sliding_window_max(black_box(&values), window).len()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("window_max/rescan", window),
+            &window,
+            |b, &window| b.iter(|| naive_sliding_window_max(black_box(&values), window).len()),
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// ENTRY API BENCHMARKS
+// ============================================================================
+// Demonstrates why the Entry API is more efficient than contains_key + insert.
+
+fn bench_entry_api(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Entry_API");
+
+    // Generate sample text for word counting
+    let words: Vec<&str> = vec![
+        "the", "quick", "brown", "fox", "jumps", "over", "the", "lazy", "dog", "the", "fox", "is",
+        "quick", "and", "the", "dog", "is", "lazy",
+    ];
+    let text: Vec<&str> = (0..10_000).map(|i| words[i % words.len()]).collect();
+
+    // GOOD: Entry API - single lookup per word
+    group.bench_function("HashMap_entry_api", |b| {
+        b.iter(|| {
+            let mut counts: HashMap<&str, i32> = HashMap::new();
+            for word in &text {
+                *counts.entry(*word).or_insert(0) += 1;
+            }
+            counts
+        })
+    });
+
+    // BAD: contains_key + get_mut - two lookups per word
+    group.bench_function("HashMap_contains_key", |b| {
+        b.iter(|| {
+            let mut counts: HashMap<&str, i32> = HashMap::new();
+            for word in &text {
+                if counts.contains_key(word) {
+                    *counts.get_mut(word).unwrap() += 1;
+                } else {
+                    counts.insert(*word, 1);
+                }
+            }
+            counts
+        })
+    });
+
+    // BTreeMap Entry API for comparison
+    group.bench_function("BTreeMap_entry_api", |b| {
+        b.iter(|| {
+            let mut counts: BTreeMap<&str, i32> = BTreeMap::new();
+            for word in &text {
+                *counts.entry(*word).or_insert(0) += 1;
+            }
+            counts
+        })
+    });
+
+    group.finish();
+}
+
+// Entry manipulation beyond or_insert: each pair does the same work, once
+// through a single entry lookup and once through the naive two-call form.
+
+fn bench_entry_manipulation(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Entry_Manipulation");
+
+    let size: u32 = 1_000u32;
+
+    // Decrement every counter, removing it when it reaches zero
+    group.bench_function("decrement_remove/entry_match", |b| {
+        b.iter_batched(
+            || {
+                (0..size)
+                    .map(|i| (i, i % 3 + 1))
+                    .collect::<HashMap<u32, u32>>()
+            },
+            |mut m: HashMap<u32, u32>| {
+                for _ in 0..3 {
+                    for i in 0..size {
+                        if let Entry::Occupied(mut e) = m.entry(black_box(i)) {
+                            *e.get_mut() -= 1;
+                            if *e.get() == 0 {
+                                e.remove();
+                            }
+                        }
+                    }
+                }
+                m
             },
             criterion::BatchSize::SmallInput,
         )
     });
 
-    // HashMap: O(1) average removal
-    group.bench_function("HashMap_remove", |b| {
+    group.bench_function("decrement_remove/get_mut_then_remove", |b| {
         b.iter_batched(
-            || (0..size).map(|i| (i, i)).collect::<HashMap<i32, i32>>(),
-            |mut m: HashMap<i32, i32>| {
-                for i in 0..size {
-                    m.remove(&i);
+            || {
+                (0..size)
+                    .map(|i| (i, i % 3 + 1))
+                    .collect::<HashMap<u32, u32>>()
+            },
+            |mut m: HashMap<u32, u32>| {
+                for _ in 0..3 {
+                    for i in 0..size {
+                        let emptied: bool = match m.get_mut(&black_box(i)) {
+                            Some(count) => {
+                                *count -= 1;
+                                *count == 0
+                            }
+                            None => false,
+                        };
+                        if emptied {
+                            m.remove(&i);
+                        }
+                    }
                 }
                 m
             },
@@ -650,32 +1437,2572 @@ fn bench_removals(c: &mut Criterion) {
         )
     });
 
-    // BTreeMap: O(log n) removal
-    group.bench_function("BTreeMap_remove", |b| {
+    // Overwrite a value and read it back
+    let map: HashMap<u32, u32> = (0..size).map(|i| (i, i)).collect();
+
+    group.bench_function("upsert_read/insert_entry", |b| {
         b.iter_batched(
-            || (0..size).map(|i| (i, i)).collect::<BTreeMap<i32, i32>>(),
-            |mut m: BTreeMap<i32, i32>| {
+            || map.clone(),
+            |mut m: HashMap<u32, u32>| {
+                let mut total: u64 = 0;
                 for i in 0..size {
-                    m.remove(&i);
+                    let e = m.entry(black_box(i)).insert_entry(i * 2);
+                    total += *e.get() as u64;
                 }
-                m
+                (m, total)
             },
             criterion::BatchSize::SmallInput,
         )
     });
 
-    // BinaryHeap: O(log n) pop
-    group.bench_function("BinaryHeap_pop_all", |b| {
+    group.bench_function("upsert_read/insert_then_get", |b| {
         b.iter_batched(
-            || (0..size).collect::<BinaryHeap<i32>>(),
-            |mut h: BinaryHeap<i32>| {
-                while h.pop().is_some() {}
-                h
+            || map.clone(),
+            |mut m: HashMap<u32, u32>| {
+                let mut total: u64 = 0;
+                for i in 0..size {
+                    m.insert(black_box(i), i * 2);
+                    total += *m.get(&i).unwrap() as u64;
+                }
+                (m, total)
             },
             criterion::BatchSize::SmallInput,
         )
     });
 
+    // Count words into String keys: allocation per word vs per new word
+    let words: Vec<String> = (0..10_000).map(|i| format!("w{}", i % 100)).collect();
+
+    group.bench_function("string_keys/entry_to_string", |b| {
+        b.iter(|| {
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            for word in &words {
+                *counts.entry(word.as_str().to_string()).or_insert(0) += 1;
+            }
+            counts
+        })
+    });
+
+    group.bench_function("string_keys/get_mut_then_entry", |b| {
+        b.iter(|| {
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            for word in &words {
+                if let Some(count) = counts.get_mut(word.as_str()) {
+                    *count += 1;
+                } else {
+                    counts.entry(word.clone()).or_insert(1);
+                }
+            }
+            counts
+        })
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// EXTRACT_IF BENCHMARKS
+// ============================================================================
+// Moving every other entry out of a map: extract_if against the fallbacks
+// used before it was stabilized. Values are Strings, so the clone-based
+// fallback pays for what extract_if simply moves.
+
+fn bench_extract_if(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Extract_If");
+
+    let size: u32 = 10_000u32;
+    let setup = || {
+        (0..size)
+            .map(|i| (i, format!("value-{}", i)))
+            .collect::<HashMap<u32, String>>()
+    };
+
+    group.bench_function("extract_if", |b| {
+        b.iter_batched(
+            setup,
+            |mut m: HashMap<u32, String>| {
+                let extracted: HashMap<u32, String> = m
+                    .extract_if(|k, _| black_box(*k).is_multiple_of(2))
+                    .collect();
+                (m, extracted)
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("retain_collect", |b| {
+        b.iter_batched(
+            setup,
+            |mut m: HashMap<u32, String>| {
+                let extracted: HashMap<u32, String> =
+                    extract_matching_fallback(&mut m, |k, _| black_box(*k).is_multiple_of(2));
+                (m, extracted)
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("keys_remove", |b| {
+        b.iter_batched(
+            setup,
+            |mut m: HashMap<u32, String>| {
+                let keys: Vec<u32> = m
+                    .keys()
+                    .filter(|k| black_box(**k).is_multiple_of(2))
+                    .copied()
+                    .collect();
+                let extracted: HashMap<u32, String> =
+                    keys.iter().filter_map(|k| m.remove_entry(k)).collect();
+                (m, extracted)
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("partition_rebuild", |b| {
+        b.iter_batched(
+            setup,
+            |m: HashMap<u32, String>| {
+                let (extracted, kept): (HashMap<u32, String>, HashMap<u32, String>) = m
+                    .into_iter()
+                    .partition(|(k, _)| black_box(*k).is_multiple_of(2));
+                (kept, extracted)
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// CONDITIONAL REMOVAL BENCHMARKS
+// ============================================================================
+// Removing every element that matches a predicate from 100,000 entries, at
+// three removal ratios (the benchmark parameter is the percent removed):
+//
+//   retain             in place, one pass; keeps order in Vec and BTreeMap
+//   drain_filter       moves the survivors into a new collection
+//   extract_if         in place, one pass, hands the removed ones back
+//   one_by_one         Vec: swap_remove in a loop (order not kept);
+//                      maps: collect the matching keys, then remove each
+//
+// Removed entries are dropped (or counted, for extract_if) so every
+// strategy does the same job. Values are u64s, so the numbers show the
+// strategy's own bookkeeping rather than the cost of dropping values.
+
+const REMOVAL_SIZE: u64 = 100_000;
+
+/// Whether `key` is among the `percent` removed: the first `percent` of
+/// every hundred keys, so removals are spread through the collection.
+fn removed(key: u64, percent: u64) -> bool {
+    key % 100 < percent
+}
+
+fn bench_conditional_removal(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Conditional_Removal");
+    group.throughput(Throughput::Elements(REMOVAL_SIZE));
+
+    let vec: Vec<u64> = (0..REMOVAL_SIZE).collect();
+    let hashmap: HashMap<u64, u64> = (0..REMOVAL_SIZE).map(|k| (k, k)).collect();
+    let btreemap: BTreeMap<u64, u64> = (0..REMOVAL_SIZE).map(|k| (k, k)).collect();
+
+    for percent in [1u64, 50, 99] {
+        // -----------------------------------------------------------------
+        // Vec
+        // -----------------------------------------------------------------
+
+        group.bench_with_input(
+            BenchmarkId::new("Vec_retain", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || vec.clone(),
+                    |mut v| {
+                        v.retain(|&x| !removed(black_box(x), p));
+                        v
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("Vec_drain_filter", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || vec.clone(),
+                    |mut v| {
+                        let kept: Vec<u64> =
+                            v.drain(..).filter(|&x| !removed(black_box(x), p)).collect();
+                        (v, kept)
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("Vec_extract_if", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || vec.clone(),
+                    |mut v| {
+                        let count: usize = v.extract_if(.., |x| removed(black_box(*x), p)).count();
+                        (v, count)
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("Vec_one_by_one", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || vec.clone(),
+                    |mut v| {
+                        let mut i: usize = 0;
+                        while i < v.len() {
+                            if removed(black_box(v[i]), p) {
+                                v.swap_remove(i);
+                            } else {
+                                i += 1;
+                            }
+                        }
+                        v
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        // -----------------------------------------------------------------
+        // HashMap
+        // -----------------------------------------------------------------
+
+        group.bench_with_input(
+            BenchmarkId::new("HashMap_retain", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || hashmap.clone(),
+                    |mut m| {
+                        m.retain(|&k, _| !removed(black_box(k), p));
+                        m
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("HashMap_drain_filter", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || hashmap.clone(),
+                    |mut m| {
+                        let kept: HashMap<u64, u64> = m
+                            .drain()
+                            .filter(|&(k, _)| !removed(black_box(k), p))
+                            .collect();
+                        (m, kept)
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("HashMap_extract_if", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || hashmap.clone(),
+                    |mut m| {
+                        let count: usize = m.extract_if(|&k, _| removed(black_box(k), p)).count();
+                        (m, count)
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("HashMap_one_by_one", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || hashmap.clone(),
+                    |mut m| {
+                        let keys: Vec<u64> = m
+                            .keys()
+                            .copied()
+                            .filter(|&k| removed(black_box(k), p))
+                            .collect();
+                        for key in &keys {
+                            m.remove(key);
+                        }
+                        m
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        // -----------------------------------------------------------------
+        // BTreeMap: no drain, so the survivors are collected from
+        // into_iter, which BTreeMap builds in bulk from sorted input
+        // -----------------------------------------------------------------
+
+        group.bench_with_input(
+            BenchmarkId::new("BTreeMap_retain", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || btreemap.clone(),
+                    |mut m| {
+                        m.retain(|&k, _| !removed(black_box(k), p));
+                        m
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("BTreeMap_drain_filter", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || btreemap.clone(),
+                    |m| {
+                        let kept: BTreeMap<u64, u64> = m
+                            .into_iter()
+                            .filter(|&(k, _)| !removed(black_box(k), p))
+                            .collect();
+                        kept
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("BTreeMap_extract_if", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || btreemap.clone(),
+                    |mut m| {
+                        let count: usize =
+                            m.extract_if(.., |&k, _| removed(black_box(k), p)).count();
+                        (m, count)
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("BTreeMap_one_by_one", percent),
+            &percent,
+            |b, &p| {
+                b.iter_batched(
+                    || btreemap.clone(),
+                    |mut m| {
+                        let keys: Vec<u64> = m
+                            .keys()
+                            .copied()
+                            .filter(|&k| removed(black_box(k), p))
+                            .collect();
+                        for key in &keys {
+                            m.remove(key);
+                        }
+                        m
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// LRU CACHE BENCHMARKS
+// ============================================================================
+// A get-or-insert loop over a skewed key stream, at three cache capacities.
+// LinkedLru and the `lru` crate are O(1) per request; DequeLru scans its
+// VecDeque on every hit, so it should fall further behind as capacity grows.
+
+fn bench_lru_caches(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("LRU_Cache");
+
+    for capacity in [100usize, 1_000, 10_000] {
+        let requests: Vec<u64> = skewed_keys(20_000, 10 * capacity as u64, 1);
+        group.throughput(Throughput::Elements(requests.len() as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("LinkedLru", capacity),
+            &requests,
+            |b, requests| {
+                b.iter(|| {
+                    let mut cache: LinkedLru<u64, u64> = LinkedLru::new(capacity);
+                    let mut hits: usize = 0;
+                    for &key in requests {
+                        if cache.get(&key).is_some() {
+                            hits += 1;
+                        } else {
+                            cache.put(key, black_box(key));
+                        }
+                    }
+                    hits
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("DequeLru", capacity),
+            &requests,
+            |b, requests| {
+                b.iter(|| {
+                    let mut cache: DequeLru<u64, u64> = DequeLru::new(capacity);
+                    let mut hits: usize = 0;
+                    for &key in requests {
+                        if cache.get(&key).is_some() {
+                            hits += 1;
+                        } else {
+                            cache.put(key, black_box(key));
+                        }
+                    }
+                    hits
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("lru::LruCache", capacity),
+            &requests,
+            |b, requests| {
+                b.iter(|| {
+                    let mut cache: LruCache<u64, u64> =
+                        LruCache::new(NonZeroUsize::new(capacity).unwrap());
+                    let mut hits: usize = 0;
+                    for &key in requests {
+                        if cache.get(&key).is_some() {
+                            hits += 1;
+                        } else {
+                            cache.put(key, black_box(key));
+                        }
+                    }
+                    hits
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// REMOVAL BENCHMARKS
+// ============================================================================
+// Measures the cost of removing elements from different collections.
+
+fn bench_removals(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Removals");
+
+    let size: i32 = 1_000i32;
+
+    // Vec: Remove from end (O(1)) vs remove from front (O(n))
+    group.bench_function("Vec_pop_back", |b| {
+        b.iter_batched(
+            || (0..size).collect::<Vec<i32>>(),
+            |mut v: Vec<i32>| {
+                while v.pop().is_some() {}
+                v
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("Vec_remove_front", |b| {
+        b.iter_batched(
+            || (0..size).collect::<Vec<i32>>(),
+            |mut v: Vec<i32>| {
+                while !v.is_empty() {
+                    v.remove(0);
+                }
+                v
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // VecDeque: O(1) from both ends
+    group.bench_function("VecDeque_pop_front", |b| {
+        b.iter_batched(
+            || (0..size).collect::<VecDeque<i32>>(),
+            |mut d: VecDeque<i32>| {
+                while d.pop_front().is_some() {}
+                d
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // HashMap: O(1) average removal
+    group.bench_function("HashMap_remove", |b| {
+        b.iter_batched(
+            || (0..size).map(|i| (i, i)).collect::<HashMap<i32, i32>>(),
+            |mut m: HashMap<i32, i32>| {
+                for i in 0..size {
+                    m.remove(&i);
+                }
+                m
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // BTreeMap: O(log n) removal
+    group.bench_function("BTreeMap_remove", |b| {
+        b.iter_batched(
+            || (0..size).map(|i| (i, i)).collect::<BTreeMap<i32, i32>>(),
+            |mut m: BTreeMap<i32, i32>| {
+                for i in 0..size {
+                    m.remove(&i);
+                }
+                m
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // BinaryHeap: O(log n) pop
+    group.bench_function("BinaryHeap_pop_all", |b| {
+        b.iter_batched(
+            || (0..size).collect::<BinaryHeap<i32>>(),
+            |mut h: BinaryHeap<i32>| {
+                while h.pop().is_some() {}
+                h
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// INLINE VEC BENCHMARKS
+// ============================================================================
+// Building and dropping a small vector, at sizes below and above the inline
+// capacity of 8. Below it, the inline types skip the allocator entirely;
+// above it, SmallVec and TinyVec spill and pay for both.
+
+fn bench_inline_vecs(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Inline_Vecs");
+
+    for size in [2u32, 4, 8, 16] {
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("Vec", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut v: Vec<u32> = Vec::new();
+                for i in 0..size {
+                    v.push(black_box(i));
+                }
+                v
+            })
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("Vec::with_capacity", size),
+            &size,
+            |b, &size| {
+                b.iter(|| {
+                    let mut v: Vec<u32> = Vec::with_capacity(size as usize);
+                    for i in 0..size {
+                        v.push(black_box(i));
+                    }
+                    v
+                })
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("SmallVec<8>", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut v: SmallVec<[u32; 8]> = SmallVec::new();
+                for i in 0..size {
+                    v.push(black_box(i));
+                }
+                v
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("TinyVec<8>", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut v: TinyVec<[u32; 8]> = TinyVec::new();
+                for i in 0..size {
+                    v.push(black_box(i));
+                }
+                v
+            })
+        });
+
+        // ArrayVec can't grow, so it gets a capacity that fits every size
+        group.bench_with_input(BenchmarkId::new("ArrayVec<16>", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut v: ArrayVec<u32, 16> = ArrayVec::new();
+                for i in 0..size {
+                    v.push(black_box(i));
+                }
+                v
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// INDEXMAP BENCHMARKS
+// ============================================================================
+// IndexMap (insertion-ordered hash map) against the two std maps. Lookups
+// should track HashMap; iteration should beat both, because entries live in
+// one dense Vec instead of a sparse table or a tree of nodes.
+
+fn bench_indexmap(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("IndexMap_vs_Maps");
+
+    for size in [1_00, 1_000, 10_000] {
+        group.throughput(Throughput::Elements(size as u64));
+
+        // Insertion: IndexMap pays for the hash table *and* the entries Vec
+        group.bench_with_input(
+            BenchmarkId::new("insert/HashMap", size),
+            &size,
+            |b, &size| {
+                b.iter(|| {
+                    let mut m: HashMap<i32, i32> = HashMap::new();
+                    for i in 0..size {
+                        m.insert(black_box(i), i);
+                    }
+                    m
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("insert/BTreeMap", size),
+            &size,
+            |b, &size| {
+                b.iter(|| {
+                    let mut m: BTreeMap<i32, i32> = BTreeMap::new();
+                    for i in 0..size {
+                        m.insert(black_box(i), i);
+                    }
+                    m
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("insert/IndexMap", size),
+            &size,
+            |b, &size| {
+                b.iter(|| {
+                    let mut m: IndexMap<i32, i32> = IndexMap::new();
+                    for i in 0..size {
+                        m.insert(black_box(i), i);
+                    }
+                    m
+                })
+            },
+        );
+
+        let hashmap: HashMap<i32, i32> = (0..size).map(|i| (i, i)).collect();
+        let btreemap: BTreeMap<i32, i32> = (0..size).map(|i| (i, i)).collect();
+        let indexmap: IndexMap<i32, i32> = (0..size).map(|i| (i, i)).collect();
+
+        // Lookup by key: one hash probe, then one index into the entries Vec
+        group.bench_with_input(BenchmarkId::new("get/HashMap", size), &size, |b, &size| {
+            b.iter(|| {
+                for i in 0..size {
+                    black_box(hashmap.get(&i));
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("get/BTreeMap", size), &size, |b, &size| {
+            b.iter(|| {
+                for i in 0..size {
+                    black_box(btreemap.get(&i));
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("get/IndexMap", size), &size, |b, &size| {
+            b.iter(|| {
+                for i in 0..size {
+                    black_box(indexmap.get(&i));
+                }
+            })
+        });
+
+        // Lookup by position: no equivalent on the std maps
+        group.bench_with_input(
+            BenchmarkId::new("get_index/IndexMap", size),
+            &size,
+            |b, &size| {
+                b.iter(|| {
+                    for i in 0..size as usize {
+                        black_box(indexmap.get_index(i));
+                    }
+                })
+            },
+        );
+
+        // Iteration: a dense Vec walk vs sparse buckets vs tree nodes
+        group.bench_with_input(BenchmarkId::new("iter/HashMap", size), &size, |b, _| {
+            b.iter(|| hashmap.values().sum::<i32>())
+        });
+
+        group.bench_with_input(BenchmarkId::new("iter/BTreeMap", size), &size, |b, _| {
+            b.iter(|| btreemap.values().sum::<i32>())
+        });
+
+        group.bench_with_input(BenchmarkId::new("iter/IndexMap", size), &size, |b, _| {
+            b.iter(|| indexmap.values().sum::<i32>())
+        });
+    }
+
+    group.finish();
+}
+
+// Removal is where IndexMap's two strategies diverge: swap_remove is O(1)
+// but reorders, shift_remove preserves order but moves every later entry.
+fn bench_indexmap_removals(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("IndexMap_Removals");
+
+    let size: i32 = 1_000i32;
+
+    group.bench_function("HashMap_remove", |b| {
+        b.iter_batched(
+            || (0..size).map(|i| (i, i)).collect::<HashMap<i32, i32>>(),
+            |mut m: HashMap<i32, i32>| {
+                for i in 0..size {
+                    m.remove(&i);
+                }
+                m
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("BTreeMap_remove", |b| {
+        b.iter_batched(
+            || (0..size).map(|i| (i, i)).collect::<BTreeMap<i32, i32>>(),
+            |mut m: BTreeMap<i32, i32>| {
+                for i in 0..size {
+                    m.remove(&i);
+                }
+                m
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    // Removing from the front in key order is the worst case for shift_remove
+    group.bench_function("IndexMap_swap_remove", |b| {
+        b.iter_batched(
+            || (0..size).map(|i| (i, i)).collect::<IndexMap<i32, i32>>(),
+            |mut m: IndexMap<i32, i32>| {
+                for i in 0..size {
+                    m.swap_remove(&i);
+                }
+                m
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("IndexMap_shift_remove", |b| {
+        b.iter_batched(
+            || (0..size).map(|i| (i, i)).collect::<IndexMap<i32, i32>>(),
+            |mut m: IndexMap<i32, i32>| {
+                for i in 0..size {
+                    m.shift_remove(&i);
+                }
+                m
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// SLAB / SLOTMAP BENCHMARKS
+// ============================================================================
+// Key-addressed storage without hashing, against the usual alternative of a
+// HashMap<u64, T> keyed by a counter. The workload inserts n values, removes
+// every other one, and iterates what is left.
+
+fn bench_slotmaps(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("SlotMap_vs_HashMap");
+
+    for size in [1_000u64, 10_000, 100_000] {
+        group.throughput(Throughput::Elements(size));
+
+        group.bench_with_input(
+            BenchmarkId::new("insert/HashMap<u64>", size),
+            &size,
+            |b, &size| {
+                b.iter(|| {
+                    let mut m: HashMap<u64, u64> = HashMap::new();
+                    for i in 0..size {
+                        m.insert(i, black_box(i));
+                    }
+                    m
+                })
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("insert/Slab", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut s: Slab<u64> = Slab::new();
+                for i in 0..size {
+                    s.insert(black_box(i));
+                }
+                s
+            })
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("insert/SlotMap", size),
+            &size,
+            |b, &size| {
+                b.iter(|| {
+                    let mut s: SlotMap<DefaultKey, u64> = SlotMap::new();
+                    for i in 0..size {
+                        s.insert(black_box(i));
+                    }
+                    s
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("insert/DenseSlotMap", size),
+            &size,
+            |b, &size| {
+                b.iter(|| {
+                    let mut s: DenseSlotMap<DefaultKey, u64> = DenseSlotMap::new();
+                    for i in 0..size {
+                        s.insert(black_box(i));
+                    }
+                    s
+                })
+            },
+        );
+
+        // Remove every other value, using the keys handed out on insert
+        group.bench_with_input(
+            BenchmarkId::new("remove/HashMap<u64>", size),
+            &size,
+            |b, &size| {
+                b.iter_batched(
+                    || (0..size).map(|i| (i, i)).collect::<HashMap<u64, u64>>(),
+                    |mut m: HashMap<u64, u64>| {
+                        for i in (0..size).step_by(2) {
+                            m.remove(&i);
+                        }
+                        m
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("remove/Slab", size), &size, |b, &size| {
+            b.iter_batched(
+                || (0..size).map(|i| (i as usize, i)).collect::<Slab<u64>>(),
+                |mut s: Slab<u64>| {
+                    for key in (0..size as usize).step_by(2) {
+                        s.remove(key);
+                    }
+                    s
+                },
+                criterion::BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("remove/SlotMap", size),
+            &size,
+            |b, &size| {
+                b.iter_batched(
+                    || {
+                        let mut s: SlotMap<DefaultKey, u64> = SlotMap::new();
+                        let keys: Vec<DefaultKey> = (0..size).map(|i| s.insert(i)).collect();
+                        (s, keys)
+                    },
+                    |(mut s, keys): (SlotMap<DefaultKey, u64>, Vec<DefaultKey>)| {
+                        for key in keys.iter().step_by(2) {
+                            s.remove(*key);
+                        }
+                        s
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("remove/DenseSlotMap", size),
+            &size,
+            |b, &size| {
+                b.iter_batched(
+                    || {
+                        let mut s: DenseSlotMap<DefaultKey, u64> = DenseSlotMap::new();
+                        let keys: Vec<DefaultKey> = (0..size).map(|i| s.insert(i)).collect();
+                        (s, keys)
+                    },
+                    |(mut s, keys): (DenseSlotMap<DefaultKey, u64>, Vec<DefaultKey>)| {
+                        for key in keys.iter().step_by(2) {
+                            s.remove(*key);
+                        }
+                        s
+                    },
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+
+        // Iterate a half-empty container: the vacant slots are where they differ
+        let mut hashmap: HashMap<u64, u64> = (0..size).map(|i| (i, i)).collect();
+        let mut slab: Slab<u64> = (0..size).map(|i| (i as usize, i)).collect();
+        let mut slotmap: SlotMap<DefaultKey, u64> = SlotMap::new();
+        let mut dense: DenseSlotMap<DefaultKey, u64> = DenseSlotMap::new();
+        let slot_keys: Vec<DefaultKey> = (0..size).map(|i| slotmap.insert(i)).collect();
+        let dense_keys: Vec<DefaultKey> = (0..size).map(|i| dense.insert(i)).collect();
+        for i in (0..size as usize).step_by(2) {
+            hashmap.remove(&(i as u64));
+            slab.remove(i);
+            slotmap.remove(slot_keys[i]);
+            dense.remove(dense_keys[i]);
+        }
+
+        group.bench_with_input(
+            BenchmarkId::new("iter/HashMap<u64>", size),
+            &size,
+            |b, _| b.iter(|| hashmap.values().sum::<u64>()),
+        );
+
+        group.bench_with_input(BenchmarkId::new("iter/Slab", size), &size, |b, _| {
+            b.iter(|| slab.iter().map(|(_, v)| v).sum::<u64>())
+        });
+
+        group.bench_with_input(BenchmarkId::new("iter/SlotMap", size), &size, |b, _| {
+            b.iter(|| slotmap.values().sum::<u64>())
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("iter/DenseSlotMap", size),
+            &size,
+            |b, _| b.iter(|| dense.values().sum::<u64>()),
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// NESTED MAP BENCHMARKS
+// ============================================================================
+// The same 10,000 entries as HashMap<A, HashMap<B, V>>, HashMap<(A, B), V>
+// and BTreeMap<A, BTreeMap<B, V>>, split into 10 wide rows and 1,000 narrow
+// ones. Point lookups should favor the flat map (one hash); reading a whole
+// row should favor the nested shapes (one lookup, then a dense scan).
+
+fn bench_nested_maps(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Nested_Maps");
+
+    for (rows, cols) in [(10u32, 1_000u32), (1_000, 10)] {
+        let shape: String = format!("{}x{}", rows, cols);
+        let nested: HashMap<u32, HashMap<u32, u64>> = (0..rows)
+            .map(|a| (a, (0..cols).map(|b| (b, (a * cols + b) as u64)).collect()))
+            .collect();
+        let flat: HashMap<(u32, u32), u64> = flatten(nested.clone());
+        let sorted: BTreeMap<u32, BTreeMap<u32, u64>> =
+            nest_sorted(flat.iter().map(|(&k, &v)| (k, v)));
+        let probes: Vec<(u32, u32)> = (0..1_000)
+            .map(|i| ((i * 7) % rows, (i * 13) % cols))
+            .collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("lookup/nested", &shape),
+            &probes,
+            |b, probes| {
+                b.iter(|| {
+                    probes
+                        .iter()
+                        .filter_map(|(a, k)| nested.get(a).and_then(|row| row.get(k)))
+                        .sum::<u64>()
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("lookup/flat", &shape),
+            &probes,
+            |b, probes| b.iter(|| probes.iter().filter_map(|key| flat.get(key)).sum::<u64>()),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("lookup/sorted", &shape),
+            &probes,
+            |b, probes| {
+                b.iter(|| {
+                    probes
+                        .iter()
+                        .filter_map(|(a, k)| sorted.get(a).and_then(|row| row.get(k)))
+                        .sum::<u64>()
+                })
+            },
+        );
+
+        group.bench_function(BenchmarkId::new("iterate_all/nested", &shape), |b| {
+            b.iter(|| nested.values().flat_map(|row| row.values()).sum::<u64>())
+        });
+
+        group.bench_function(BenchmarkId::new("iterate_all/flat", &shape), |b| {
+            b.iter(|| flat.values().sum::<u64>())
+        });
+
+        group.bench_function(BenchmarkId::new("iterate_all/sorted", &shape), |b| {
+            b.iter(|| sorted.values().flat_map(|row| row.values()).sum::<u64>())
+        });
+
+        // Everything for one outer key
+        let row: u32 = rows / 2;
+        group.bench_function(BenchmarkId::new("iterate_row/nested", &shape), |b| {
+            b.iter(|| nested[&black_box(row)].values().sum::<u64>())
+        });
+
+        group.bench_function(BenchmarkId::new("iterate_row/flat", &shape), |b| {
+            b.iter(|| {
+                let row: u32 = black_box(row);
+                flat.iter()
+                    .filter(|((a, _), _)| *a == row)
+                    .map(|(_, v)| v)
+                    .sum::<u64>()
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("iterate_row/sorted", &shape), |b| {
+            b.iter(|| sorted[&black_box(row)].values().sum::<u64>())
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// COMPOSITE KEY BENCHMARKS
+// ============================================================================
+// A (tenant, user) directory of 10,829 users over 100 skewed tenants, as
+// HashMap<(T, U), V>, HashMap<T, HashMap<U, V>> and BTreeMap<(T, U), V>.
+// Point lookups know both halves of the key; listing a tenant, reading one
+// page of 20 users and deleting a tenant know only the first half. Tenant 0
+// has 2,005 users and tenant 42 has 51.
+
+fn bench_composite_layout<D: TenantDirectory + Clone>(
+    group: &mut BenchmarkGroup<WallTime>,
+    name: &str,
+    layout: D,
+    probes: &[(Tenant, User)],
+) {
+    group.bench_function(BenchmarkId::new("get", name), |b| {
+        b.iter(|| {
+            probes
+                .iter()
+                .filter_map(|&(tenant, user)| layout.get(black_box(tenant), user))
+                .sum::<LastLogin>()
+        })
+    });
+
+    for tenant in [0, 42] {
+        group.bench_function(
+            BenchmarkId::new(format!("users_of_{}", tenant), name),
+            |b| b.iter(|| layout.users_of(black_box(tenant)).0.len()),
+        );
+    }
+
+    // The third page of tenant 0
+    let after: User = layout.page(0, None, 40)[39].0;
+    group.bench_function(BenchmarkId::new("page", name), |b| {
+        b.iter(|| layout.page(black_box(0), Some(after), 20).len())
+    });
+
+    group.bench_function(BenchmarkId::new("remove_tenant_42", name), |b| {
+        b.iter_batched(
+            || layout.clone(),
+            // Returned, so the map is dropped outside the timing
+            |mut layout| {
+                layout.remove_tenant(black_box(42));
+                layout
+            },
+            criterion::BatchSize::LargeInput,
+        )
+    });
+}
+
+fn bench_composite_keys(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Composite_Keys");
+
+    let entries: Vec<((Tenant, User), LastLogin)> = directory(100, 1);
+    let probes: Vec<(Tenant, User)> = entries.iter().step_by(11).map(|&(key, _)| key).collect();
+
+    let flat: HashMap<(Tenant, User), LastLogin> = entries.iter().copied().collect();
+    let mut nested: HashMap<Tenant, HashMap<User, LastLogin>> = HashMap::new();
+    for &((tenant, user), login) in &entries {
+        nested.entry(tenant).or_default().insert(user, login);
+    }
+    let sorted: BTreeMap<(Tenant, User), LastLogin> = entries.iter().copied().collect();
+
+    bench_composite_layout(&mut group, "flat_HashMap", flat, &probes);
+    bench_composite_layout(&mut group, "nested_HashMap", nested, &probes);
+    bench_composite_layout(&mut group, "BTreeMap_tuple", sorted, &probes);
+
+    group.finish();
+}
+
+// ============================================================================
+// MAP DIFF BENCHMARKS
+// ============================================================================
+// diff_maps on two 1,000,000-entry inventory snapshots where 0.5% of keys
+// were removed, as many added and 1% repriced. The HashMap diff does two
+// lookups per key; the BTreeMap diff walks both maps once in key order.
+// Each iteration takes tens of milliseconds, so the sample size is reduced.
+
+fn bench_map_diff(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Map_Diff");
+    group.sample_size(10);
+
+    let (old_rows, new_rows) = inventory_snapshots(1_000_000);
+    group.throughput(Throughput::Elements(
+        (old_rows.len() + new_rows.len()) as u64,
+    ));
+
+    let old_hashed: HashMap<u64, u64> = old_rows.iter().copied().collect();
+    let new_hashed: HashMap<u64, u64> = new_rows.iter().copied().collect();
+    group.bench_function("HashMap/1M", |b| {
+        b.iter(|| diff_maps(black_box(&old_hashed), black_box(&new_hashed)).len())
+    });
+
+    let old_sorted: BTreeMap<u64, u64> = old_rows.into_iter().collect();
+    let new_sorted: BTreeMap<u64, u64> = new_rows.into_iter().collect();
+    group.bench_function("BTreeMap/1M", |b| {
+        b.iter(|| diff_maps(black_box(&old_sorted), black_box(&new_sorted)).len())
+    });
+
+    // What a caller that needs a deterministic HashMap diff pays
+    group.bench_function("HashMap_sorted/1M", |b| {
+        b.iter(|| {
+            diff_maps(black_box(&old_hashed), black_box(&new_hashed))
+                .into_sorted()
+                .len()
+        })
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// ITERATION ORDER BENCHMARKS
+// ============================================================================
+// The same sales records walked in hash, key, arrival and sorted-IndexMap
+// order. `subtotals` starts a new group whenever the region changes: eight
+// times in key order, for most entries otherwise, with the branch that
+// decides it mispredicted about as often. `compress` serializes the map and
+// DEFLATEs it, to see whether order costs time as well as output size.
+
+fn bench_iteration_order(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Iteration_Order");
+
+    for size in [10_000, 200_000] {
+        group.throughput(Throughput::Elements(size as u64));
+        let arrivals: Vec<(u64, f64)> = sales(size, 5);
+        let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+        let sorted: BTreeMap<u64, f64> = arrivals.iter().copied().collect();
+        let inserted: IndexMap<u64, f64> = arrivals.iter().copied().collect();
+        let mut key_sorted: IndexMap<u64, f64> = inserted.clone();
+        key_sorted.sort_keys();
+
+        group.bench_with_input(
+            BenchmarkId::new("subtotals/HashMap", size),
+            &hash,
+            |b, map| b.iter(|| subtotals(black_box(map).iter()).len()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("subtotals/BTreeMap", size),
+            &sorted,
+            |b, map| b.iter(|| subtotals(black_box(map).iter()).len()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("subtotals/IndexMap_arrival", size),
+            &inserted,
+            |b, map| b.iter(|| subtotals(black_box(map).iter()).len()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("subtotals/IndexMap_sorted", size),
+            &key_sorted,
+            |b, map| b.iter(|| subtotals(black_box(map).iter()).len()),
+        );
+    }
+
+    // Compression is far slower per entry, so one smaller size
+    group.sample_size(20);
+    let size: usize = 20_000;
+    group.throughput(Throughput::Elements(size as u64));
+    let arrivals: Vec<(u64, f64)> = sales(size, 7);
+    let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+    let sorted: BTreeMap<u64, f64> = arrivals.iter().copied().collect();
+    group.bench_with_input(
+        BenchmarkId::new("compress/HashMap", size),
+        &hash,
+        |b, map| b.iter(|| compressed_len(&serialize(black_box(map).iter()))),
+    );
+    group.bench_with_input(
+        BenchmarkId::new("compress/BTreeMap", size),
+        &sorted,
+        |b, map| b.iter(|| compressed_len(&serialize(black_box(map).iter()))),
+    );
+
+    group.finish();
+}
+
+// ============================================================================
+// COLUMNAR GROUP-BY BENCHMARKS
+// ============================================================================
+// Total sales per store over 1M rows and 1,000 stores: the HashMap loop over
+// row structs with String keys, the same loop over dictionary-encoded
+// columns, and the columnar sort + run-length plan on shuffled rows and on
+// rows already sorted by store.
+
+fn bench_columnar_group_by(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Columnar_Group_By");
+    group.sample_size(10);
+
+    let rows: Vec<SaleRow> = sales_rows(1_000_000, 1_000, 42);
+    group.throughput(Throughput::Elements(rows.len() as u64));
+    let columns: SalesColumns = SalesColumns::from_rows(&rows);
+    let mut sorted_rows: Vec<SaleRow> = rows.clone();
+    sorted_rows.sort_by(|a, b| a.store.cmp(&b.store));
+    let sorted_columns: SalesColumns = SalesColumns::from_rows(&sorted_rows);
+
+    group.bench_function("rows_HashMap_String/1M", |b| {
+        b.iter(|| group_rows(black_box(&rows)).len())
+    });
+    group.bench_function("columns_HashMap_u32/1M", |b| {
+        b.iter(|| group_codes(black_box(&columns)).len())
+    });
+    group.bench_function("columns_sort_run_length/1M", |b| {
+        b.iter(|| group_columns(black_box(&columns)).store.len())
+    });
+    group.bench_function("columns_sort_run_length_presorted/1M", |b| {
+        b.iter(|| group_columns(black_box(&sorted_columns)).store.len())
+    });
+    // What a CSV source pays before it can use the columnar plans
+    group.bench_function("encode_rows_as_columns/1M", |b| {
+        b.iter(|| SalesColumns::from_rows(black_box(&rows)).len())
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// BITSET BENCHMARKS
+// ============================================================================
+// A set of small integers as Vec<bool>, bitvec's BitVec, the hand-rolled
+// WordBitSet and a HashSet<usize>. Counting and intersecting should favor the
+// packed forms by a wide margin: they touch 64 members per word.
+
+fn bench_bitsets(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Bitsets");
+
+    let n: usize = 100_000;
+    group.throughput(Throughput::Elements(n as u64));
+
+    // Every third number in one set, every fifth in the other
+    let a_bools: Vec<bool> = (0..n).map(|i| i.is_multiple_of(3)).collect();
+    let b_bools: Vec<bool> = (0..n).map(|i| i.is_multiple_of(5)).collect();
+    let a_bits: BitVec<u64, Lsb0> = a_bools.iter().copied().collect();
+    let b_bits: BitVec<u64, Lsb0> = b_bools.iter().copied().collect();
+    let mut a_words: WordBitSet = WordBitSet::new(n);
+    let mut b_words: WordBitSet = WordBitSet::new(n);
+    for i in (0..n).step_by(3) {
+        a_words.set(i);
+    }
+    for i in (0..n).step_by(5) {
+        b_words.set(i);
+    }
+    let a_hash: HashSet<usize> = (0..n).step_by(3).collect();
+    let b_hash: HashSet<usize> = (0..n).step_by(5).collect();
+
+    group.bench_function("set_all/Vec<bool>", |b| {
+        b.iter(|| {
+            let mut flags: Vec<bool> = vec![false; n];
+            for i in (0..n).step_by(3) {
+                flags[black_box(i)] = true;
+            }
+            flags
+        })
+    });
+
+    group.bench_function("set_all/BitVec", |b| {
+        b.iter(|| {
+            let mut bits: BitVec<u64, Lsb0> = bitvec![u64, Lsb0; 0; n];
+            for i in (0..n).step_by(3) {
+                bits.set(black_box(i), true);
+            }
+            bits
+        })
+    });
+
+    group.bench_function("set_all/WordBitSet", |b| {
+        b.iter(|| {
+            let mut words: WordBitSet = WordBitSet::new(n);
+            for i in (0..n).step_by(3) {
+                words.set(black_box(i));
+            }
+            words
+        })
+    });
+
+    group.bench_function("count/Vec<bool>", |b| {
+        b.iter(|| black_box(&a_bools).iter().filter(|&&x| x).count())
+    });
+
+    group.bench_function("count/BitVec", |b| {
+        b.iter(|| black_box(&a_bits).count_ones())
+    });
+
+    group.bench_function("count/WordBitSet", |b| {
+        b.iter(|| black_box(&a_words).count_ones())
+    });
+
+    group.bench_function("intersection/Vec<bool>", |b| {
+        b.iter(|| {
+            a_bools
+                .iter()
+                .zip(&b_bools)
+                .map(|(&x, &y)| x && y)
+                .collect::<Vec<bool>>()
+        })
+    });
+
+    group.bench_function("intersection/BitVec", |b| {
+        b.iter(|| a_bits.clone() & black_box(&b_bits))
+    });
+
+    group.bench_function("intersection/WordBitSet", |b| {
+        b.iter(|| {
+            let mut both: WordBitSet = a_words.clone();
+            both.intersect_with(black_box(&b_words));
+            both
+        })
+    });
+
+    group.bench_function("intersection/HashSet", |b| {
+        b.iter(|| a_hash.intersection(black_box(&b_hash)).count())
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// ENUM-KEYED MAP BENCHMARKS
+// ============================================================================
+// Per-level counters for 100,000 log lines, five possible keys, in
+// HashMap<Level, u64>, BTreeMap<Level, u64>, the hand-rolled LevelTable
+// array and enum-map's EnumMap. "count" builds the counters from empty;
+// "lookup" reads a filled map once per line. The arrays should win both by
+// skipping the hash (or the search) and the key comparison.
+
+fn bench_enum_keyed_maps(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Enum_Keyed_Maps");
+
+    let levels: Vec<Level> = log_levels(100_000, 7);
+    group.throughput(Throughput::Elements(levels.len() as u64));
+
+    group.bench_function("count/HashMap", |b| {
+        b.iter(|| count_hashmap(black_box(&levels)))
+    });
+    group.bench_function("count/BTreeMap", |b| {
+        b.iter(|| count_btreemap(black_box(&levels)))
+    });
+    group.bench_function("count/array", |b| {
+        b.iter(|| count_array(black_box(&levels)))
+    });
+    group.bench_function("count/EnumMap", |b| {
+        b.iter(|| count_enum_map(black_box(&levels)))
+    });
+
+    // A weight per level, summed over every line
+    let hashed: HashMap<Level, u64> = Level::ALL.map(|level| (level, level as u64 + 1)).into();
+    let sorted: BTreeMap<Level, u64> = Level::ALL.map(|level| (level, level as u64 + 1)).into();
+    let array: LevelTable<u64> = LevelTable::from_fn(|level| level as u64 + 1);
+    let enum_map: EnumMap<Level, u64> = EnumMap::from_fn(|level: Level| level as u64 + 1);
+
+    group.bench_function("lookup/HashMap", |b| {
+        b.iter(|| levels.iter().map(|level| hashed[level]).sum::<u64>())
+    });
+    group.bench_function("lookup/BTreeMap", |b| {
+        b.iter(|| levels.iter().map(|level| sorted[level]).sum::<u64>())
+    });
+    group.bench_function("lookup/array", |b| {
+        b.iter(|| levels.iter().map(|&level| array[level]).sum::<u64>())
+    });
+    group.bench_function("lookup/EnumMap", |b| {
+        b.iter(|| levels.iter().map(|&level| enum_map[level]).sum::<u64>())
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// UNION-FIND BENCHMARKS
+// ============================================================================
+// n random unions followed by n connected() queries, with union by rank and
+// path compression (UnionFind) and without either (QuickUnion). Even random
+// input grows QuickUnion trees thousands of levels deep: at 100,000 elements
+// one iteration takes about ten seconds, so it only runs at 10,000 while
+// UnionFind goes on to 1,000,000. Kruskal runs on 100,000 nodes and
+// 1,000,000 edges, most of the time going to the sort.
+
+fn bench_union_find(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Union_Find");
+    group.sample_size(10);
+
+    for n in [10_000usize, 100_000, 1_000_000] {
+        let pairs: Vec<(usize, usize)> = random_pairs(n, n, 42);
+        let queries: Vec<(usize, usize)> = random_pairs(n, n, 7);
+        group.throughput(Throughput::Elements((pairs.len() + queries.len()) as u64));
+
+        group.bench_with_input(BenchmarkId::new("UnionFind", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut sets: UnionFind = UnionFind::new(n);
+                for &(a, b) in &pairs {
+                    sets.union(a, b);
+                }
+                queries
+                    .iter()
+                    .filter(|&&(a, b)| sets.connected(a, b))
+                    .count()
+            })
+        });
+
+        if n > 10_000 {
+            continue;
+        }
+        group.bench_with_input(BenchmarkId::new("QuickUnion", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut sets: QuickUnion = QuickUnion::new(n);
+                for &(a, b) in &pairs {
+                    sets.union(a, b);
+                }
+                queries
+                    .iter()
+                    .filter(|&&(a, b)| sets.connected(a, b))
+                    .count()
+            })
+        });
+    }
+
+    let nodes: usize = 100_000;
+    let edges: Vec<Edge> = random_edges(nodes, 1_000_000, 1_000, 3);
+    group.throughput(Throughput::Elements(edges.len() as u64));
+    group.bench_function("Kruskal/100k_nodes_1M_edges", |b| {
+        b.iter(|| kruskal(nodes, black_box(&edges)).len())
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// GRAPH REPRESENTATION BENCHMARKS
+// ============================================================================
+// One random graph (100,000 nodes, 8 out-edges each) as a HashMap of Vecs, a
+// Vec<Vec<usize>> and CSR, scanned edge by edge and traversed with BFS.
+// "Vec<Vec>_scattered" is the same adjacency list built from the edges in
+// target order, so the per-node Vecs grow interleaved and end up spread
+// across the heap: same data, worse locality. The matrix only runs at 2,000
+// nodes, where its row scans already read 4 million cells.
+
+fn bench_graph_representations(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Graph_Representations");
+
+    let nodes: usize = 100_000;
+    let edges: Vec<graph_examples::Edge> = graph_examples::random_edges(nodes, 8, 42);
+    let hashed: HashGraph<usize> = hash_graph(&edges);
+    let list: AdjacencyList = adjacency_list(nodes, &edges);
+    let mut by_target: Vec<graph_examples::Edge> = edges.clone();
+    by_target.sort_by_key(|&(_, to)| to);
+    let scattered: AdjacencyList = adjacency_list(nodes, &by_target);
+    let csr: Csr = Csr::from_edges(nodes, &edges);
+    group.throughput(Throughput::Elements(edges.len() as u64));
+
+    group.bench_function("edge_scan/HashMap", |b| {
+        b.iter(|| edge_checksum(black_box(&hashed), nodes))
+    });
+    group.bench_function("edge_scan/Vec<Vec>", |b| {
+        b.iter(|| edge_checksum(black_box(&list), nodes))
+    });
+    group.bench_function("edge_scan/Vec<Vec>_scattered", |b| {
+        b.iter(|| edge_checksum(black_box(&scattered), nodes))
+    });
+    group.bench_function("edge_scan/CSR", |b| {
+        b.iter(|| edge_checksum(black_box(&csr), nodes))
+    });
+
+    group.bench_function("bfs/HashMap", |b| {
+        b.iter(|| bfs(black_box(&hashed), 0).len())
+    });
+    group.bench_function("bfs/Vec<Vec>", |b| {
+        b.iter(|| bfs(black_box(&list), 0).len())
+    });
+    group.bench_function("bfs/CSR", |b| b.iter(|| bfs(black_box(&csr), 0).len()));
+
+    let nodes: usize = 2_000;
+    let edges: Vec<graph_examples::Edge> = graph_examples::random_edges(nodes, 8, 42);
+    let matrix: Grid<bool> = adjacency_matrix(nodes, &edges);
+    let csr: Csr = Csr::from_edges(nodes, &edges);
+    group.throughput(Throughput::Elements(edges.len() as u64));
+    group.bench_function("edge_scan_2k/matrix", |b| {
+        b.iter(|| edge_checksum(black_box(&matrix), nodes))
+    });
+    group.bench_function("edge_scan_2k/CSR", |b| {
+        b.iter(|| edge_checksum(black_box(&csr), nodes))
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// PERSISTENT COLLECTION BENCHMARKS
+// ============================================================================
+// Clone-then-edit: copy a collection and change one entry, the pattern an
+// undo history or a snapshot repeats. std pays O(n) for the copy; im shares
+// everything but the edited path. "get" is the other side of the trade: a
+// lookup that never clones, where im's tree walk costs more than std's.
+
+fn bench_persistent_collections(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Persistent_Collections");
+
+    for size in [1_000u64, 100_000] {
+        let key: u64 = size / 2;
+        let std_vec: Vec<u64> = (0..size).collect();
+        let im_vec: im::Vector<u64> = (0..size).collect();
+        let std_map: HashMap<u64, u64> = (0..size).map(|i| (i, i)).collect();
+        let im_map: im::HashMap<u64, u64> = (0..size).map(|i| (i, i)).collect();
+        let std_tree: BTreeMap<u64, u64> = (0..size).map(|i| (i, i)).collect();
+        let im_tree: im::OrdMap<u64, u64> = (0..size).map(|i| (i, i)).collect();
+
+        group.bench_with_input(BenchmarkId::new("clone_edit/Vec", size), &size, |b, _| {
+            b.iter(|| {
+                let mut copy: Vec<u64> = black_box(&std_vec).clone();
+                copy[key as usize] = 0;
+                copy
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("clone_edit/im::Vector", size),
+            &size,
+            |b, _| {
+                b.iter(|| {
+                    let mut copy: im::Vector<u64> = black_box(&im_vec).clone();
+                    copy.set(key as usize, 0);
+                    copy
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("clone_edit/HashMap", size),
+            &size,
+            |b, _| {
+                b.iter(|| {
+                    let mut copy: HashMap<u64, u64> = black_box(&std_map).clone();
+                    copy.insert(key, 0);
+                    copy
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("clone_edit/im::HashMap", size),
+            &size,
+            |b, _| b.iter(|| black_box(&im_map).update(key, 0)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("clone_edit/BTreeMap", size),
+            &size,
+            |b, _| {
+                b.iter(|| {
+                    let mut copy: BTreeMap<u64, u64> = black_box(&std_tree).clone();
+                    copy.insert(key, 0);
+                    copy
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("clone_edit/im::OrdMap", size),
+            &size,
+            |b, _| b.iter(|| black_box(&im_tree).update(key, 0)),
+        );
+
+        group.bench_with_input(BenchmarkId::new("get/HashMap", size), &size, |b, _| {
+            b.iter(|| black_box(&std_map).get(&black_box(key)).copied())
+        });
+        group.bench_with_input(BenchmarkId::new("get/im::HashMap", size), &size, |b, _| {
+            b.iter(|| black_box(&im_map).get(&black_box(key)).copied())
+        });
+        group.bench_with_input(BenchmarkId::new("get/Vec", size), &size, |b, _| {
+            b.iter(|| black_box(&std_vec)[black_box(key) as usize])
+        });
+        group.bench_with_input(BenchmarkId::new("get/im::Vector", size), &size, |b, _| {
+            b.iter(|| black_box(&im_vec)[black_box(key) as usize])
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// MULTIMAP BENCHMARKS
+// ============================================================================
+// The same MultiMap over two value lists. With a few values per key the
+// SmallVec backing keeps them inline in the map's slot and skips one heap
+// allocation per key; past its inline capacity of 4 it spills and pays for
+// the larger slots instead. "get_all" sums every key's values: a SmallVec
+// checks whether it spilled before every read, so inline values don't make
+// lookups faster.
+
+fn bench_multimap(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("MultiMap");
+    let keys: u64 = 10_000;
+
+    for per_key in [2u64, 8] {
+        let pairs: Vec<(u64, u64)> = (0..keys * per_key).map(|i| (i % keys, i)).collect();
+        let vec_map: MultiMap<u64, u64> = pairs.iter().copied().collect();
+        let small_map: SmallMultiMap<u64, u64> = pairs.iter().copied().collect();
+        group.throughput(Throughput::Elements(pairs.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("build/Vec", per_key), &per_key, |b, _| {
+            b.iter(|| {
+                let map: MultiMap<u64, u64> = black_box(&pairs).iter().copied().collect();
+                map
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("build/SmallVec", per_key),
+            &per_key,
+            |b, _| {
+                b.iter(|| {
+                    let map: SmallMultiMap<u64, u64> = black_box(&pairs).iter().copied().collect();
+                    map
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("get_all/Vec", per_key),
+            &per_key,
+            |b, _| {
+                b.iter(|| {
+                    (0..keys)
+                        .map(|key| black_box(&vec_map).get_all(&key).iter().sum::<u64>())
+                        .sum::<u64>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("get_all/SmallVec", per_key),
+            &per_key,
+            |b, _| {
+                b.iter(|| {
+                    (0..keys)
+                        .map(|key| black_box(&small_map).get_all(&key).iter().sum::<u64>())
+                        .sum::<u64>()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// OBJECT POOL BENCHMARKS
+// ============================================================================
+// A hot loop that needs a large scratch buffer per iteration: allocate a
+// fresh Vec each time, or check one out of a Pool and let the guard return
+// it. Both fill the whole buffer, so the difference is the allocation.
+// Up to a few MiB the system allocator keeps the block it just freed and
+// hands it straight back, which is a pool already. Past its mmap threshold
+// (32 MiB at most with glibc) every fresh buffer is new pages from the OS,
+// and the first write to each page faults.
+
+fn bench_object_pool(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Object_Pool");
+
+    for size in [64 * 1024usize, 1024 * 1024, 64 * 1024 * 1024] {
+        group.throughput(Throughput::Bytes(size as u64));
+
+        group.bench_with_input(BenchmarkId::new("fresh", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut buffer: Vec<u8> = Vec::with_capacity(size);
+                buffer.resize(size, 1);
+                black_box(&buffer).len()
+            })
+        });
+
+        let pool: Pool<Vec<u8>> = buffer_pool(size);
+        pool.prefill(1);
+        group.bench_with_input(BenchmarkId::new("pooled", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut buffer: Pooled<Vec<u8>> = pool.checkout();
+                buffer.resize(size, 1);
+                black_box(&*buffer).len()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// ARENA BENCHMARKS
+// ============================================================================
+// The same binary search tree built and dropped with a Box per node, in a
+// typed_arena and in a bumpalo Bump. An arena tree borrows its arena, so it
+// can't be built in a setup closure and dropped in the measured one; Box
+// gets a separate drop-only case instead. The last case reuses one Bump and
+// resets it after each tree, which keeps its chunks and frees nothing.
+
+fn bench_arena(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Arena");
+
+    for count in [1_000usize, 100_000] {
+        let keys: Vec<u64> = tree_keys(count, 42);
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("box_build_and_drop", count),
+            &keys,
+            |b, keys| {
+                b.iter(|| {
+                    let mut tree: BoxTree = BoxTree::new();
+                    for &key in keys {
+                        tree.insert(key);
+                    }
+                    black_box(tree.len())
+                })
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("box_drop", count), &keys, |b, keys| {
+            b.iter_batched(
+                || {
+                    let mut tree: BoxTree = BoxTree::new();
+                    for &key in keys {
+                        tree.insert(key);
+                    }
+                    tree
+                },
+                drop,
+                criterion::BatchSize::LargeInput,
+            )
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("typed_arena_build_and_drop", count),
+            &keys,
+            |b, keys| {
+                b.iter(|| {
+                    let arena: Arena<Node> = Arena::new();
+                    let mut tree: ArenaTree = ArenaTree::new();
+                    for &key in keys {
+                        tree.insert(&arena, key);
+                    }
+                    black_box(tree.len())
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("bumpalo_build_and_drop", count),
+            &keys,
+            |b, keys| {
+                b.iter(|| {
+                    let bump: Bump = Bump::new();
+                    let mut tree: ArenaTree = ArenaTree::new();
+                    for &key in keys {
+                        tree.insert(&bump, key);
+                    }
+                    black_box(tree.len())
+                })
+            },
+        );
+
+        let mut bump: Bump = Bump::new();
+        group.bench_with_input(
+            BenchmarkId::new("bumpalo_reset", count),
+            &keys,
+            |b, keys| {
+                b.iter(|| {
+                    let len: usize = {
+                        let mut tree: ArenaTree = ArenaTree::new();
+                        for &key in keys {
+                            tree.insert(&bump, key);
+                        }
+                        tree.len()
+                    };
+                    bump.reset();
+                    black_box(len)
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// SORTING AND SEARCHING BENCHMARKS
+// ============================================================================
+// The sorts on three inputs of the same numbers: shuffled, already sorted,
+// and sorted with 1% of the elements swapped at random. Both std sorts find
+// existing runs, so sorted input is O(n); 1% of random swaps already cuts
+// the input into short runs. The key sorts use an allocating key (the
+// number as a string) to show what sort_by_cached_key saves. select_nth_unstable finds the median, which a
+// full sort would too, in O(n). The searches run on the sorted input.
+
+fn sorting_inputs(len: usize) -> Vec<(&'static str, Vec<u64>)> {
+    let mut rng: Lcg = Lcg::new(7);
+    let sorted: Vec<u64> = (0..len as u64).map(|i| i * 3).collect();
+    let mut random: Vec<u64> = sorted.clone();
+    for i in (1..len).rev() {
+        random.swap(i, rng.below(i as u64 + 1) as usize);
+    }
+    let mut nearly: Vec<u64> = sorted.clone();
+    for _ in 0..len / 100 {
+        nearly.swap(
+            rng.below(len as u64) as usize,
+            rng.below(len as u64) as usize,
+        );
+    }
+    vec![
+        ("random", random),
+        ("sorted", sorted),
+        ("nearly_sorted", nearly),
+    ]
+}
+
+fn bench_sorting(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Sorting");
+    let len: usize = 100_000;
+    group.throughput(Throughput::Elements(len as u64));
+
+    for (shape, input) in sorting_inputs(len) {
+        group.bench_with_input(BenchmarkId::new("sort", shape), &input, |b, input| {
+            b.iter_batched(
+                || input.clone(),
+                |mut v| v.sort(),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(
+            BenchmarkId::new("sort_unstable", shape),
+            &input,
+            |b, input| {
+                b.iter_batched(
+                    || input.clone(),
+                    |mut v| v.sort_unstable(),
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("sort_by_key_string", shape),
+            &input,
+            |b, input| {
+                b.iter_batched(
+                    || input.clone(),
+                    |mut v| v.sort_by_key(|x| x.to_string()),
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("sort_by_cached_key_string", shape),
+            &input,
+            |b, input| {
+                b.iter_batched(
+                    || input.clone(),
+                    |mut v| v.sort_by_cached_key(|x| x.to_string()),
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("select_nth_unstable_median", shape),
+            &input,
+            |b, input| {
+                b.iter_batched(
+                    || input.clone(),
+                    |mut v| *v.select_nth_unstable(len / 2).1,
+                    criterion::BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+
+    // 1,000 lookups of values spread over the range, half of them missing
+    let sorted: Vec<u64> = (0..len as u64).map(|i| i * 3).collect();
+    let targets: Vec<u64> = (0..1_000u64).map(|i| i * 299 + i % 2).collect();
+    group.throughput(Throughput::Elements(targets.len() as u64));
+    group.bench_function("binary_search_by/1k_lookups", |b| {
+        b.iter(|| {
+            targets
+                .iter()
+                .filter(|&&t| black_box(&sorted).binary_search_by(|x| x.cmp(&t)).is_ok())
+                .count()
+        })
+    });
+    group.bench_function("partition_point/1k_lookups", |b| {
+        b.iter(|| {
+            targets
+                .iter()
+                .map(|&t| black_box(&sorted).partition_point(|&x| x < t))
+                .sum::<usize>()
+        })
+    });
+    group.bench_function("linear_scan/1k_lookups", |b| {
+        b.iter(|| {
+            targets
+                .iter()
+                .map(|&t| black_box(&sorted).iter().take_while(|&&x| x < t).count())
+                .sum::<usize>()
+        })
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// STATIC LOOKUP BENCHMARKS
+// ============================================================================
+// Fixed tables known at compile time: 47 HTTP status codes and 14 weekday
+// names. The sorted static array (binary search) and the const-built index
+// are std-only; LazyLock<HashMap> is std's lazy_static, and phf builds a
+// perfect hash at compile time. "hits" looks up codes that are in the
+// table, "mixed" any code in 100..600, so most lookups miss.
+
+static PHF_STATUSES: phf::Map<u16, &'static str> = phf::phf_map! {
+    100u16 => "Continue", 101u16 => "Switching Protocols", 102u16 => "Processing",
+    103u16 => "Early Hints", 200u16 => "OK", 201u16 => "Created", 202u16 => "Accepted",
+    203u16 => "Non-Authoritative Information", 204u16 => "No Content",
+    205u16 => "Reset Content", 206u16 => "Partial Content", 300u16 => "Multiple Choices",
+    301u16 => "Moved Permanently", 302u16 => "Found", 303u16 => "See Other",
+    304u16 => "Not Modified", 307u16 => "Temporary Redirect", 308u16 => "Permanent Redirect",
+    400u16 => "Bad Request", 401u16 => "Unauthorized", 402u16 => "Payment Required",
+    403u16 => "Forbidden", 404u16 => "Not Found", 405u16 => "Method Not Allowed",
+    406u16 => "Not Acceptable", 408u16 => "Request Timeout", 409u16 => "Conflict",
+    410u16 => "Gone", 411u16 => "Length Required", 412u16 => "Precondition Failed",
+    413u16 => "Content Too Large", 414u16 => "URI Too Long",
+    415u16 => "Unsupported Media Type", 418u16 => "I'm a teapot",
+    422u16 => "Unprocessable Content", 425u16 => "Too Early", 426u16 => "Upgrade Required",
+    428u16 => "Precondition Required", 429u16 => "Too Many Requests",
+    431u16 => "Request Header Fields Too Large", 451u16 => "Unavailable For Legal Reasons",
+    500u16 => "Internal Server Error", 501u16 => "Not Implemented", 502u16 => "Bad Gateway",
+    503u16 => "Service Unavailable", 504u16 => "Gateway Timeout",
+    505u16 => "HTTP Version Not Supported",
+};
+
+static PHF_WEEKDAYS: phf::Map<&'static str, Weekday> = phf::phf_map! {
+    "monday" => Weekday::Monday, "mon" => Weekday::Monday,
+    "tuesday" => Weekday::Tuesday, "tue" => Weekday::Tuesday,
+    "wednesday" => Weekday::Wednesday, "wed" => Weekday::Wednesday,
+    "thursday" => Weekday::Thursday, "thu" => Weekday::Thursday,
+    "friday" => Weekday::Friday, "fri" => Weekday::Friday,
+    "saturday" => Weekday::Saturday, "sat" => Weekday::Saturday,
+    "sunday" => Weekday::Sunday, "sun" => Weekday::Sunday,
+};
+
+static WEEKDAY_MAP: LazyLock<HashMap<&'static str, Weekday>> =
+    LazyLock::new(|| WEEKDAY_NAMES.iter().copied().collect());
+
+fn bench_static_lookups(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Static_Lookups");
+
+    let mut rng: Lcg = Lcg::new(5);
+    let hits: Vec<u16> = (0..10_000)
+        .map(|_| HTTP_STATUSES[rng.below(HTTP_STATUSES.len() as u64) as usize].0)
+        .collect();
+    let mixed: Vec<u16> = (0..10_000).map(|_| 100 + rng.below(500) as u16).collect();
+    let names: Vec<&str> = (0..10_000)
+        .map(|_| WEEKDAY_NAMES[rng.below(WEEKDAY_NAMES.len() as u64) as usize].0)
+        .collect();
+    LazyLock::force(&STATUS_MAP);
+    LazyLock::force(&WEEKDAY_MAP);
+    group.throughput(Throughput::Elements(10_000));
+
+    type Lookup = fn(u16) -> Option<&'static str>;
+    let lookups: [(&str, Lookup); 4] = [
+        ("sorted_array", status_reason),
+        ("const_index", status_reason_indexed),
+        ("LazyLock<HashMap>", status_reason_hashed),
+        ("phf", |code| PHF_STATUSES.get(&code).copied()),
+    ];
+    for (input, codes) in [("hits", &hits), ("mixed", &mixed)] {
+        for (name, lookup) in lookups {
+            group.bench_function(format!("status_{}/{}", input, name), |b| {
+                b.iter(|| {
+                    codes
+                        .iter()
+                        .filter_map(|&code| lookup(black_box(code)))
+                        .map(str::len)
+                        .sum::<usize>()
+                })
+            });
+        }
+    }
+
+    group.bench_function("weekday/sorted_array", |b| {
+        b.iter(|| {
+            names
+                .iter()
+                .filter_map(|&name| parse_weekday(black_box(name)))
+                .count()
+        })
+    });
+    group.bench_function("weekday/LazyLock<HashMap>", |b| {
+        b.iter(|| {
+            names
+                .iter()
+                .filter_map(|&name| WEEKDAY_MAP.get(black_box(name)))
+                .count()
+        })
+    });
+    group.bench_function("weekday/phf", |b| {
+        b.iter(|| {
+            names
+                .iter()
+                .filter_map(|&name| PHF_WEEKDAYS.get(black_box(name)))
+                .count()
+        })
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// GRID BENCHMARKS
+// ============================================================================
+// Summing a square grid in row-major and column-major order, for a flat
+// Grid<u32> and a Vec<Vec<u32>>. Small grids fit in cache and the order
+// barely matters; large ones make column-major pay a cache miss per step.
+
+fn bench_grid_traversal(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Grid_Traversal");
+
+    for size in [64usize, 512, 2_048] {
+        let grid: Grid<u32> = Grid::from_fn(size, size, |row, col| (row ^ col) as u32);
+        let nested: Vec<Vec<u32>> = grid.iter_rows().map(|row| row.to_vec()).collect();
+        group.throughput(Throughput::Elements((size * size) as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("Grid_row_major", size),
+            &grid,
+            |b, grid| b.iter(|| sum_row_major(black_box(grid))),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("Grid_column_major", size),
+            &grid,
+            |b, grid| b.iter(|| sum_column_major(black_box(grid))),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("VecVec_row_major", size),
+            &nested,
+            |b, nested| {
+                b.iter(|| {
+                    let mut sum: u64 = 0;
+                    for row in black_box(nested) {
+                        for &v in row {
+                            sum += v as u64;
+                        }
+                    }
+                    sum
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("VecVec_column_major", size),
+            &nested,
+            |b, nested| {
+                b.iter(|| {
+                    let mut sum: u64 = 0;
+                    for col in 0..size {
+                        for row in black_box(nested) {
+                            sum += row[col] as u64;
+                        }
+                    }
+                    sum
+                })
+            },
+        );
+
+        // Tiled transpose against the naive one-strided-side version
+        group.bench_with_input(
+            BenchmarkId::new("transpose_tiled", size),
+            &grid,
+            |b, grid| b.iter(|| black_box(grid).transpose()),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("transpose_naive", size),
+            &grid,
+            |b, grid| b.iter(|| Grid::from_fn(size, size, |row, col| black_box(grid)[(col, row)])),
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// MATRIX BENCHMARKS
+// ============================================================================
+// The same f64 matrix as a Vec<Vec<f64>> and a flat Grid<f64>, summed by rows
+// and by columns, then the three multiply loop orders. i-j-k reads B a column
+// at a time; i-k-j and the transposed version only ever read rows.
+
+fn bench_matrix(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Matrix");
+
+    for size in [256usize, 2_048] {
+        let grid: Grid<f64> = sample_matrix(size, size, 1);
+        let nested: Vec<Vec<f64>> = nested_from_grid(&grid);
+        group.throughput(Throughput::Elements((size * size) as u64));
+
+        group.bench_with_input(BenchmarkId::new("Grid_sum_rows", size), &grid, |b, grid| {
+            b.iter(|| grid_sum_by_rows(black_box(grid)))
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("Grid_sum_columns", size),
+            &grid,
+            |b, grid| b.iter(|| grid_sum_by_columns(black_box(grid))),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("VecVec_sum_rows", size),
+            &nested,
+            |b, nested| b.iter(|| nested_sum_by_rows(black_box(nested))),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("VecVec_sum_columns", size),
+            &nested,
+            |b, nested| b.iter(|| nested_sum_by_columns(black_box(nested))),
+        );
+    }
+
+    // A 512x512 multiply is 134M multiply-adds per iteration
+    group.sample_size(10);
+    for size in [64usize, 512] {
+        let a: Grid<f64> = sample_matrix(size, size, 1);
+        let b: Grid<f64> = sample_matrix(size, size, 2);
+        group.throughput(Throughput::Elements((size * size * size) as u64));
+
+        group.bench_with_input(BenchmarkId::new("multiply_ijk", size), &size, |bench, _| {
+            bench.iter(|| multiply_ijk(black_box(&a), black_box(&b)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("multiply_ikj", size), &size, |bench, _| {
+            bench.iter(|| multiply_ikj(black_box(&a), black_box(&b)))
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("multiply_transposed", size),
+            &size,
+            |bench, _| bench.iter(|| multiply_transposed(black_box(&a), black_box(&b))),
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// SPATIAL HASH BENCHMARKS
+// ============================================================================
+// Every pair of circles closer than 2.0 apart, found by checking all pairs
+// and through a SpatialHash with cells of 2.0. The arena grows with n so the
+// density matches the collision demo (one circle per 20 square units):
+// all-pairs work grows with n^2, the grid's with n. "grid_build_and_pairs"
+// includes filling the grid, which a simulation pays every tick.
+fn bench_spatial_hash(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Spatial_Hash");
+    group.sample_size(10);
+    let distance: f64 = 2.0;
+
+    for n in [1_000u32, 10_000] {
+        let entities: Vec<(EntityId, Point)> = scatter(n, (n as f64 * 20.0).sqrt(), 3);
+        let mut grid: SpatialHash = SpatialHash::new(distance);
+        for &(id, point) in &entities {
+            grid.insert(id, point);
+        }
+        group.throughput(Throughput::Elements(n as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("all_pairs", n),
+            &entities,
+            |b, entities| b.iter(|| brute_force_pairs(black_box(entities), distance)),
+        );
+
+        group.bench_with_input(BenchmarkId::new("grid_pairs", n), &grid, |b, grid| {
+            b.iter(|| black_box(grid).colliding_pairs(distance))
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("grid_build_and_pairs", n),
+            &entities,
+            |b, entities| {
+                b.iter(|| {
+                    let mut grid: SpatialHash = SpatialHash::new(distance);
+                    for &(id, point) in black_box(entities) {
+                        grid.insert(id, point);
+                    }
+                    grid.colliding_pairs(distance)
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// K-D TREE BENCHMARKS
+// ============================================================================
+// Nearest-neighbour and rectangle queries over n random points, answered by
+// a KdTree and by a linear scan. Each iteration runs 1,000 nearest queries,
+// or 100 rectangles covering about 1% of the square each. Building the tree
+// is timed separately: it pays off once there are enough queries per build.
+fn bench_kdtree(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("KdTree");
+    let queries: Vec<Point> = scatter(1_000, 1_000.0, 2)
+        .into_iter()
+        .map(|(_, point)| point)
+        .collect();
+
+    for n in [1_000u32, 100_000] {
+        let entities: Vec<(EntityId, Point)> = scatter(n, 1_000.0, 1);
+        let tree: KdTree = KdTree::build(entities.clone());
+
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("build", n), &entities, |b, entities| {
+            b.iter(|| KdTree::build(black_box(entities.clone())))
+        });
+
+        group.throughput(Throughput::Elements(queries.len() as u64));
+        group.bench_with_input(BenchmarkId::new("tree_nearest", n), &tree, |b, tree| {
+            b.iter(|| {
+                queries
+                    .iter()
+                    .map(|&query| tree.nearest(black_box(query)).map_or(0, |f| f.id.0))
+                    .fold(0u32, u32::wrapping_add)
+            })
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("linear_nearest", n),
+            &entities,
+            |b, entities| {
+                b.iter(|| {
+                    queries
+                        .iter()
+                        .map(|&query| {
+                            linear_nearest(entities, black_box(query)).map_or(0, |f| f.id.0)
+                        })
+                        .fold(0u32, u32::wrapping_add)
+                })
+            },
+        );
+
+        group.throughput(Throughput::Elements(100));
+        group.bench_with_input(BenchmarkId::new("tree_range", n), &tree, |b, tree| {
+            b.iter(|| {
+                queries[..100]
+                    .iter()
+                    .map(|&min| {
+                        let max: Point = Point::new(min.x + 100.0, min.y + 100.0);
+                        tree.range(black_box(min), max).ids.len()
+                    })
+                    .sum::<usize>()
+            })
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("linear_range", n),
+            &entities,
+            |b, entities| {
+                b.iter(|| {
+                    queries[..100]
+                        .iter()
+                        .map(|&min| {
+                            let max: Point = Point::new(min.x + 100.0, min.y + 100.0);
+                            linear_range(entities, black_box(min), max).ids.len()
+                        })
+                        .sum::<usize>()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// ITERATOR VS LOOP BENCHMARKS
+// ============================================================================
+// The same work written as an index loop and as an iterator chain: the sum
+// of the even values' squares (a filter), and a dot product (zip vs two
+// indexed slices). If the chain is really zero-cost, each pair should time
+// the same; the index loop also keeps a bounds check on b[i] that zip
+// doesn't need.
+
+fn bench_iterator_vs_loop(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Iterator_vs_Loop");
+
+    for size in [10_000usize, 1_000_000] {
+        let values: Vec<u64> = sample_values(size, 1);
+        let a: Vec<u32> = values.iter().map(|&v| v as u32).collect();
+        let b: Vec<u32> = sample_values(size, 2).iter().map(|&v| v as u32).collect();
+        group.throughput(Throughput::Elements(size as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("even_squares_indexed", size),
+            &values,
+            |bench, values| bench.iter(|| sum_even_squares_indexed(black_box(values))),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("even_squares_iter", size),
+            &values,
+            |bench, values| bench.iter(|| sum_even_squares_iter(black_box(values))),
+        );
+
+        group.bench_with_input(BenchmarkId::new("dot_indexed", size), &size, |bench, _| {
+            bench.iter(|| dot_indexed(black_box(&a), black_box(&b)))
+        });
+
+        group.bench_with_input(BenchmarkId::new("dot_zip", size), &size, |bench, _| {
+            bench.iter(|| dot_zip(black_box(&a), black_box(&b)))
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// COW NORMALIZATION BENCHMARKS
+// ============================================================================
+// Normalizes 10k log lines (control characters, whitespace, ASCII case) with
+// a function returning String and with the Cow version, when none, one in
+// ten or all of the lines need a change. Each result is dropped right away,
+// so the String version pays an allocation and a free per line even when
+// the line comes back unchanged.
+
+fn bench_cow_normalization(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Cow_Normalization");
+
+    for (label, dirty_every) in [("0%", 0), ("10%", 10), ("100%", 1)] {
+        let lines: Vec<String> = sample_log_lines(10_000, dirty_every);
+        group.throughput(Throughput::Elements(lines.len() as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("to_string", label),
+            &lines,
+            |bench, lines| {
+                bench.iter(|| {
+                    lines
+                        .iter()
+                        .map(|line| normalize_to_string(black_box(line)).len())
+                        .sum::<usize>()
+                })
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("cow", label), &lines, |bench, lines| {
+            bench.iter(|| {
+                lines
+                    .iter()
+                    .map(|line| normalize(black_box(line)).len())
+                    .sum::<usize>()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// TIMER QUEUE BENCHMARKS
+// ============================================================================
+// Schedule 10,000 timers, then fire them all in deadline order. With 10
+// distinct deadlines a thousand timers share each one; with 10,000 nearly
+// none do. The heaps order ties by id, paying comparisons for every one.
+// The BTreeMap batches ties into one Vec per deadline, and the timer wheel
+// into one slot. The f64 heap uses seconds as keys, which need a total
+// order wrapper, and EventQueue (the simulation's queue) adds a HashMap of
+// payloads for cancelling.
+
+/// Seconds as f64, ordered with total_cmp so they can key a heap.
+#[derive(Clone, Copy, PartialEq)]
+struct Seconds(f64);
+
+impl Eq for Seconds {}
+
+impl PartialOrd for Seconds {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Seconds {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+fn bench_timer_queues(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Timer_Queues");
+
+    let count: usize = 10_000;
+    for distinct in [10, 1_000, 10_000] {
+        let deadlines: Vec<Time> = timer_deadlines(count, distinct, 60_000, 17);
+        group.throughput(Throughput::Elements(count as u64));
+
+        group.bench_with_input(
+            BenchmarkId::new("BinaryHeap_u64", distinct),
+            &deadlines,
+            |b, deadlines| {
+                b.iter(|| {
+                    let mut heap: BinaryHeap<Reverse<(Time, u64)>> = BinaryHeap::new();
+                    for (id, &at) in (0..).zip(deadlines) {
+                        heap.push(Reverse((black_box(at), id)));
+                    }
+                    let mut checksum: u64 = 0;
+                    while let Some(Reverse((at, id))) = heap.pop() {
+                        checksum = checksum.wrapping_mul(31).wrapping_add(at ^ id);
+                    }
+                    checksum
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("BinaryHeap_f64_seconds", distinct),
+            &deadlines,
+            |b, deadlines| {
+                b.iter(|| {
+                    let mut heap: BinaryHeap<Reverse<(Seconds, u64)>> = BinaryHeap::new();
+                    for (id, &at) in (0..).zip(deadlines) {
+                        heap.push(Reverse((Seconds(black_box(at) as f64 / 1_000.0), id)));
+                    }
+                    let mut checksum: u64 = 0;
+                    while let Some(Reverse((at, id))) = heap.pop() {
+                        checksum = checksum.wrapping_mul(31).wrapping_add(at.0.to_bits() ^ id);
+                    }
+                    checksum
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("BTreeMap_Duration_Vec", distinct),
+            &deadlines,
+            |b, deadlines| {
+                b.iter(|| {
+                    let mut timers: BTreeMap<Duration, Vec<u64>> = BTreeMap::new();
+                    for (id, &at) in (0..).zip(deadlines) {
+                        timers
+                            .entry(Duration::from_millis(black_box(at)))
+                            .or_default()
+                            .push(id);
+                    }
+                    let mut checksum: u64 = 0;
+                    while let Some((at, ids)) = timers.pop_first() {
+                        for id in ids {
+                            checksum = checksum
+                                .wrapping_mul(31)
+                                .wrapping_add(at.as_millis() as u64 ^ id);
+                        }
+                    }
+                    checksum
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("EventQueue", distinct),
+            &deadlines,
+            |b, deadlines| {
+                b.iter(|| {
+                    let mut queue: EventQueue<u64> = EventQueue::new();
+                    for (id, &at) in (0..).zip(deadlines) {
+                        queue.schedule(black_box(at), id);
+                    }
+                    let mut checksum: u64 = 0;
+                    while let Some((at, id)) = queue.pop() {
+                        checksum = checksum.wrapping_mul(31).wrapping_add(at ^ id);
+                    }
+                    checksum
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("TimerWheel", distinct),
+            &deadlines,
+            |b, deadlines| {
+                b.iter(|| {
+                    let mut wheel: TimerWheel<u64> = TimerWheel::new();
+                    for (id, &at) in (0..).zip(deadlines) {
+                        wheel.schedule(black_box(at), id);
+                    }
+                    let mut checksum: u64 = 0;
+                    while let Some((at, id)) = wheel.pop() {
+                        checksum = checksum.wrapping_mul(31).wrapping_add(at ^ id);
+                    }
+                    checksum
+                })
+            },
+        );
+    }
+
     group.finish();
 }
 
@@ -721,12 +4048,48 @@ criterion_group!(
     benches,
     bench_insertions,
     bench_lookups,
+    bench_small_sizes,
+    bench_string_keys,
     bench_front_operations,
     bench_iteration,
     bench_range_queries,
+    bench_map_views,
+    bench_sorted_vec_set,
     bench_priority_operations,
+    bench_heap_alternatives,
+    bench_streaming_stats,
     bench_entry_api,
+    bench_entry_manipulation,
+    bench_extract_if,
+    bench_conditional_removal,
+    bench_lru_caches,
     bench_removals,
+    bench_inline_vecs,
+    bench_grid_traversal,
+    bench_matrix,
+    bench_spatial_hash,
+    bench_kdtree,
+    bench_iterator_vs_loop,
+    bench_cow_normalization,
+    bench_timer_queues,
+    bench_indexmap,
+    bench_indexmap_removals,
+    bench_slotmaps,
+    bench_nested_maps,
+    bench_composite_keys,
+    bench_map_diff,
+    bench_iteration_order,
+    bench_columnar_group_by,
+    bench_bitsets,
+    bench_enum_keyed_maps,
+    bench_union_find,
+    bench_graph_representations,
+    bench_persistent_collections,
+    bench_multimap,
+    bench_object_pool,
+    bench_arena,
+    bench_sorting,
+    bench_static_lookups,
     bench_scaling,
 );
 
@@ -0,0 +1,550 @@
+// An arena hands out memory from a few big chunks instead of one heap
+// allocation per object, and frees every chunk at once when it goes away:
+//
+//   Box per node       malloc ──► [n1]   malloc ──► [n2]   malloc ──► [n3]
+//                      drop: free n1, free n2, free n3 (one call each)
+//
+//   arena              chunk: [n1][n2][n3][n4][ ......free...... ]
+//                      alloc: bump a pointer; drop: free the chunk
+//
+// Two crates, two trade-offs:
+//
+//   typed_arena::Arena<T>  one type per arena. Dropping the arena runs
+//                          every T's destructor, then frees the chunks.
+//   bumpalo::Bump          any mix of types, and it never runs destructors:
+//                          values with a Drop impl leak their resources
+//                          unless wrapped in bumpalo::boxed::Box.
+//
+// Every node lives exactly as long as the arena, so nodes can point at each
+// other with plain `&'a Node<'a>` references - back pointers and cycles
+// included - where owned nodes need Box, Rc/Weak or raw pointers.
+
+use bumpalo::Bump;
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::cell::Cell;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use typed_arena::Arena;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "typed_arena_tree",
+        "A binary search tree whose nodes come from a typed_arena::Arena",
+        typed_arena_tree,
+    )?;
+
+    section(
+        out,
+        "bump_linked_list",
+        "A doubly linked list in a bumpalo::Bump, with plain references both ways",
+        bump_linked_list,
+    )?;
+
+    section(
+        out,
+        "destructors_in_arenas",
+        "typed_arena runs destructors when it drops; bumpalo does not",
+        destructors_in_arenas,
+    )?;
+
+    section(
+        out,
+        "allocation_and_drop_cost",
+        "Building and dropping the same tree: Box per node vs typed_arena vs bumpalo",
+        allocation_and_drop_cost,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Box-per-node tree
+// ---------------------------------------------------------------------------
+
+struct BoxNode {
+    key: u64,
+    left: Option<Box<BoxNode>>,
+    right: Option<Box<BoxNode>>,
+}
+
+/// A binary search tree owning each node in its own `Box`.
+#[derive(Default)]
+pub struct BoxTree {
+    root: Option<Box<BoxNode>>,
+    len: usize,
+}
+
+impl BoxTree {
+    pub fn new() -> Self {
+        BoxTree::default()
+    }
+
+    /// Adds `key`; returns false if it was already there.
+    pub fn insert(&mut self, key: u64) -> bool {
+        let mut slot: &mut Option<Box<BoxNode>> = &mut self.root;
+        while let Some(node) = slot {
+            if key == node.key {
+                return false;
+            }
+            slot = if key < node.key {
+                &mut node.left
+            } else {
+                &mut node.right
+            };
+        }
+        *slot = Some(Box::new(BoxNode {
+            key,
+            left: None,
+            right: None,
+        }));
+        self.len += 1;
+        true
+    }
+
+    pub fn contains(&self, key: u64) -> bool {
+        let mut current: Option<&BoxNode> = self.root.as_deref();
+        while let Some(node) = current {
+            if key == node.key {
+                return true;
+            }
+            current = if key < node.key {
+                node.left.as_deref()
+            } else {
+                node.right.as_deref()
+            };
+        }
+        false
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Every key in ascending order.
+    pub fn keys(&self) -> Vec<u64> {
+        let mut keys: Vec<u64> = Vec::with_capacity(self.len);
+        let mut stack: Vec<&BoxNode> = Vec::new();
+        let mut current: Option<&BoxNode> = self.root.as_deref();
+        while current.is_some() || !stack.is_empty() {
+            while let Some(node) = current {
+                stack.push(node);
+                current = node.left.as_deref();
+            }
+            let node: &BoxNode = stack.pop().expect("loop condition");
+            keys.push(node.key);
+            current = node.right.as_deref();
+        }
+        keys
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Arena tree
+// ---------------------------------------------------------------------------
+
+/// A tree node that lives in an arena. Children are shared references set
+/// through `Cell`s, since nodes are only ever reached through `&`.
+pub struct Node<'a> {
+    pub key: u64,
+    left: Cell<Option<&'a Node<'a>>>,
+    right: Cell<Option<&'a Node<'a>>>,
+}
+
+/// Where [`ArenaTree`] gets its nodes from.
+pub trait NodeArena<'a> {
+    fn alloc_node(&'a self, node: Node<'a>) -> &'a Node<'a>;
+}
+
+impl<'a> NodeArena<'a> for Arena<Node<'a>> {
+    fn alloc_node(&'a self, node: Node<'a>) -> &'a Node<'a> {
+        self.alloc(node)
+    }
+}
+
+impl<'a> NodeArena<'a> for Bump {
+    fn alloc_node(&'a self, node: Node<'a>) -> &'a Node<'a> {
+        self.alloc(node)
+    }
+}
+
+/// The same binary search tree as [`BoxTree`], with nodes borrowed from an
+/// arena for `'a`. The tree owns nothing: dropping it is free, and the
+/// nodes go when the arena does.
+pub struct ArenaTree<'a> {
+    root: Option<&'a Node<'a>>,
+    len: usize,
+}
+
+impl<'a> ArenaTree<'a> {
+    pub fn new() -> Self {
+        ArenaTree { root: None, len: 0 }
+    }
+
+    /// Adds `key`, allocating its node from `arena`; returns false if it
+    /// was already there, without allocating.
+    pub fn insert(&mut self, arena: &'a impl NodeArena<'a>, key: u64) -> bool {
+        let new = || {
+            arena.alloc_node(Node {
+                key,
+                left: Cell::new(None),
+                right: Cell::new(None),
+            })
+        };
+        let Some(mut node) = self.root else {
+            self.root = Some(new());
+            self.len += 1;
+            return true;
+        };
+        loop {
+            if key == node.key {
+                return false;
+            }
+            let child: &Cell<Option<&'a Node<'a>>> = if key < node.key {
+                &node.left
+            } else {
+                &node.right
+            };
+            match child.get() {
+                Some(next) => node = next,
+                None => {
+                    child.set(Some(new()));
+                    self.len += 1;
+                    return true;
+                }
+            }
+        }
+    }
+
+    pub fn contains(&self, key: u64) -> bool {
+        let mut current: Option<&Node<'a>> = self.root;
+        while let Some(node) = current {
+            if key == node.key {
+                return true;
+            }
+            current = if key < node.key {
+                node.left.get()
+            } else {
+                node.right.get()
+            };
+        }
+        false
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Every key in ascending order.
+    pub fn keys(&self) -> Vec<u64> {
+        let mut keys: Vec<u64> = Vec::with_capacity(self.len);
+        let mut stack: Vec<&Node<'a>> = Vec::new();
+        let mut current: Option<&Node<'a>> = self.root;
+        while current.is_some() || !stack.is_empty() {
+            while let Some(node) = current {
+                stack.push(node);
+                current = node.left.get();
+            }
+            let node: &Node<'a> = stack.pop().expect("loop condition");
+            keys.push(node.key);
+            current = node.right.get();
+        }
+        keys
+    }
+}
+
+impl Default for ArenaTree<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `count` pseudo-random keys below `count * 4`, so some repeat.
+pub fn tree_keys(count: usize, seed: u64) -> Vec<u64> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..count).map(|_| rng.below(count as u64 * 4)).collect()
+}
+
+// ---------------------------------------------------------------------------
+// Linked list in a Bump
+// ---------------------------------------------------------------------------
+
+/// A doubly linked list node in a `Bump`. Both links are plain references:
+/// the arena outlives every node, so there is no owner to decide on.
+pub struct ListNode<'a> {
+    pub value: &'a str,
+    prev: Cell<Option<&'a ListNode<'a>>>,
+    next: Cell<Option<&'a ListNode<'a>>>,
+}
+
+impl<'a> ListNode<'a> {
+    pub fn prev(&self) -> Option<&'a ListNode<'a>> {
+        self.prev.get()
+    }
+
+    pub fn next(&self) -> Option<&'a ListNode<'a>> {
+        self.next.get()
+    }
+}
+
+/// Allocates a node for each of `values` in `bump` and links them in
+/// order; returns the first and last node.
+pub fn bump_list<'a>(
+    bump: &'a Bump,
+    values: &[&str],
+) -> Option<(&'a ListNode<'a>, &'a ListNode<'a>)> {
+    let mut ends: Option<(&'a ListNode<'a>, &'a ListNode<'a>)> = None;
+    for value in values {
+        let node: &'a ListNode<'a> = bump.alloc(ListNode {
+            // The strings are copied into the arena too
+            value: bump.alloc_str(value),
+            prev: Cell::new(None),
+            next: Cell::new(None),
+        });
+        ends = match ends {
+            None => Some((node, node)),
+            Some((head, tail)) => {
+                tail.next.set(Some(node));
+                node.prev.set(Some(tail));
+                Some((head, node))
+            }
+        };
+    }
+    ends
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// Demonstrates a tree built from a typed arena
+pub fn typed_arena_tree(out: &mut dyn Write) -> io::Result<()> {
+    let arena: Arena<Node> = Arena::new();
+    let mut tree: ArenaTree = ArenaTree::new();
+
+    let keys: [u64; 9] = [50, 30, 70, 20, 40, 60, 80, 30, 70];
+    let inserted: Vec<bool> = keys.iter().map(|&key| tree.insert(&arena, key)).collect();
+    writeln!(out, "Inserted {:?}", keys)?;
+    writeln!(out, "New key?    {:?}", inserted)?;
+    writeln!(
+        out,
+        "Tree holds {} keys, arena holds {} nodes (duplicates never allocate)",
+        tree.len(),
+        arena.len()
+    )?;
+    writeln!(out, "In order: {:?}", tree.keys())?;
+    writeln!(
+        out,
+        "contains(40): {}, contains(45): {}",
+        tree.contains(40),
+        tree.contains(45)
+    )?;
+
+    writeln!(
+        out,
+        "\nNode<'a> links are &'a Node<'a> in Cells: no Box, no Rc, no unsafe."
+    )?;
+    writeln!(
+        out,
+        "The borrow checker ties every node to the arena: the tree can't outlive it."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates a doubly linked list in a bump arena
+pub fn bump_linked_list(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Doubly Linked List in a Bump ---")?;
+
+    let bump: Bump = Bump::new();
+    let Some((head, tail)) = bump_list(&bump, &["parse", "check", "lower", "emit"]) else {
+        return Ok(());
+    };
+
+    let mut forward: Vec<&str> = Vec::new();
+    let mut current: Option<&ListNode> = Some(head);
+    while let Some(node) = current {
+        forward.push(node.value);
+        current = node.next();
+    }
+    let mut backward: Vec<&str> = Vec::new();
+    let mut current: Option<&ListNode> = Some(tail);
+    while let Some(node) = current {
+        backward.push(node.value);
+        current = node.prev();
+    }
+    writeln!(out, "Forward:  {:?}", forward)?;
+    writeln!(out, "Backward: {:?}", backward)?;
+    writeln!(
+        out,
+        "Compare RcList in custom_linked_list_examples: no Weak, no RefCell, no Drop impl."
+    )?;
+
+    // Any type can share the same Bump
+    let numbers: &mut [u32] = bump.alloc_slice_fill_copy(4, 7);
+    numbers[0] = 1;
+    writeln!(out, "\nA slice in the same arena: {:?}", numbers)?;
+    writeln!(
+        out,
+        "Chunk memory reserved by the Bump: {} bytes, one allocation for all of it",
+        bump.allocated_bytes()
+    )?;
+
+    Ok(())
+}
+
+/// Counts drops through a shared counter.
+struct Noisy<'c> {
+    drops: &'c Cell<usize>,
+}
+
+impl Drop for Noisy<'_> {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+/// Demonstrates what happens to destructors in each arena
+pub fn destructors_in_arenas(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Destructors ---")?;
+
+    let drops: Cell<usize> = Cell::new(0);
+    {
+        let arena: Arena<Noisy> = Arena::new();
+        for _ in 0..3 {
+            arena.alloc(Noisy { drops: &drops });
+        }
+        writeln!(
+            out,
+            "typed_arena: 3 values allocated, {} dropped while it lives",
+            drops.get()
+        )?;
+    }
+    writeln!(out, "typed_arena: {} dropped with the arena", drops.get())?;
+
+    drops.set(0);
+    {
+        let bump: Bump = Bump::new();
+        for _ in 0..3 {
+            bump.alloc(Noisy { drops: &drops });
+        }
+        // bumpalo's own Box runs the destructor when it goes out of scope
+        let boxed: bumpalo::boxed::Box<Noisy> =
+            bumpalo::boxed::Box::new_in(Noisy { drops: &drops }, &bump);
+        drop(boxed);
+        writeln!(
+            out,
+            "bumpalo: 3 values with bump.alloc, 1 in bumpalo::boxed::Box, dropped so far: {}",
+            drops.get()
+        )?;
+    }
+    writeln!(
+        out,
+        "bumpalo: {} dropped with the arena - the other 3 destructors never run",
+        drops.get()
+    )?;
+    writeln!(
+        out,
+        "Fine for plain data; a String or Vec inside a Bump leaks its own heap buffer."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates build and drop times of the three trees
+pub fn allocation_and_drop_cost(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Allocation and Drop Cost ---")?;
+
+    let keys: Vec<u64> = tree_keys(200_000, 42);
+
+    let start: Instant = Instant::now();
+    let mut boxed: BoxTree = BoxTree::new();
+    for &key in &keys {
+        boxed.insert(key);
+    }
+    let box_build: Duration = start.elapsed();
+    let box_len: usize = boxed.len();
+    let start: Instant = Instant::now();
+    drop(boxed);
+    let box_drop: Duration = start.elapsed();
+
+    // Arena-backed trees borrow their arena, so both are dropped by ending
+    // the block; the drop timer starts just before
+    let typed_drop_start: Instant;
+    let typed_build: Duration;
+    let typed_len: usize;
+    {
+        let start: Instant = Instant::now();
+        let arena: Arena<Node> = Arena::new();
+        let mut tree: ArenaTree = ArenaTree::new();
+        for &key in &keys {
+            tree.insert(&arena, key);
+        }
+        typed_build = start.elapsed();
+        typed_len = tree.len();
+        typed_drop_start = Instant::now();
+    }
+    let typed_drop: Duration = typed_drop_start.elapsed();
+
+    let bump_drop_start: Instant;
+    let bump_build: Duration;
+    let bump_len: usize;
+    {
+        let start: Instant = Instant::now();
+        let bump: Bump = Bump::new();
+        let mut tree: ArenaTree = ArenaTree::new();
+        for &key in &keys {
+            tree.insert(&bump, key);
+        }
+        bump_build = start.elapsed();
+        bump_len = tree.len();
+        bump_drop_start = Instant::now();
+    }
+    let bump_drop: Duration = bump_drop_start.elapsed();
+
+    writeln!(
+        out,
+        "{} random keys, {} distinct, same tree shape in all three",
+        keys.len(),
+        box_len
+    )?;
+    writeln!(
+        out,
+        "  Box per node  build {:?}, drop {:?}",
+        box_build, box_drop
+    )?;
+    writeln!(
+        out,
+        "  typed_arena   build {:?}, drop {:?}",
+        typed_build, typed_drop
+    )?;
+    writeln!(
+        out,
+        "  bumpalo       build {:?}, drop {:?}",
+        bump_build, bump_drop
+    )?;
+    writeln!(
+        out,
+        "Same length everywhere: {}",
+        box_len == typed_len && typed_len == bump_len
+    )?;
+    writeln!(
+        out,
+        "\nBuilding is mostly walking the tree, so the gap there is modest; dropping is"
+    )?;
+    writeln!(
+        out,
+        "one free per node for Box and a handful of chunk frees for an arena."
+    )?;
+
+    Ok(())
+}
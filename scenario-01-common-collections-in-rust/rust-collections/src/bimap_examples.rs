@@ -0,0 +1,468 @@
+// A bidirectional map (BiMap) is a one-to-one mapping you can look up from
+// either side: id -> name and name -> id, both in O(1). It is two HashMaps
+// that must always agree, so every insert and remove updates both.
+//
+//   left_to_right               right_to_left
+//   ┌──────┬──────────┐         ┌──────────┬──────┐
+//   │ 1001 │ "ada"    │         │ "ada"    │ 1001 │
+//   │ 1002 │ "grace"  │         │ "grace"  │ 1002 │
+//   └──────┴──────────┘         └──────────┴──────┘
+//
+// Storing each key twice would double the memory for large keys, so both
+// maps hold Rc pointers to the same allocation instead: a String name is
+// allocated once and shared by the two maps.
+//
+// One-to-one is the tricky part. Inserting (1001, "grace") when 1001 already
+// maps to "ada" AND "grace" already belongs to 1002 must remove both old
+// pairs, or the two maps would disagree. `insert` reports what it removed;
+// `insert_no_overwrite` refuses instead.
+
+use demo_framework::section;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "bimap_basics",
+        "Looking up ids by name and names by id",
+        bimap_basics,
+    )?;
+
+    section(
+        out,
+        "overwrite_semantics",
+        "What insert removes to keep the mapping one-to-one",
+        overwrite_semantics,
+    )?;
+
+    section(
+        out,
+        "shared_keys",
+        "Rc keys: each value is allocated once and shared by both maps",
+        shared_keys,
+    )?;
+
+    section(
+        out,
+        "practical_user_directory",
+        "Practical demo: User ids and unique usernames",
+        practical_user_directory,
+    )?;
+
+    Ok(())
+}
+
+/// The pairs `BiMap::insert` removed to keep the mapping one-to-one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Overwritten<L, R> {
+    /// Neither value was in the map.
+    Neither,
+    /// The left value was paired with something else; that pair is gone.
+    Left(L, R),
+    /// The right value was paired with something else; that pair is gone.
+    Right(L, R),
+    /// The exact pair was already there and has been replaced by itself.
+    Pair(L, R),
+    /// Both values were in different pairs: the left one's pair, then the
+    /// right one's.
+    Both((L, R), (L, R)),
+}
+
+/// A one-to-one map with O(1) lookup from either side.
+///
+/// Each value lives in one `Rc` shared by both internal maps. Lookups take
+/// `&L` and `&R`: an `Rc<String>` key borrows as a `String` but not as a
+/// `str`, so a `BiMap<_, String>` can't be searched with a bare `&str`.
+#[derive(Debug)]
+pub struct BiMap<L, R> {
+    left_to_right: HashMap<Rc<L>, Rc<R>>,
+    right_to_left: HashMap<Rc<R>, Rc<L>>,
+}
+
+impl<L: Hash + Eq, R: Hash + Eq> BiMap<L, R> {
+    pub fn new() -> Self {
+        BiMap {
+            left_to_right: HashMap::new(),
+            right_to_left: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.left_to_right.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left_to_right.is_empty()
+    }
+
+    pub fn get_by_left(&self, left: &L) -> Option<&R> {
+        self.left_to_right.get(left).map(|right| &**right)
+    }
+
+    pub fn get_by_right(&self, right: &R) -> Option<&L> {
+        self.right_to_left.get(right).map(|left| &**left)
+    }
+
+    pub fn contains_left(&self, left: &L) -> bool {
+        self.left_to_right.contains_key(left)
+    }
+
+    pub fn contains_right(&self, right: &R) -> bool {
+        self.right_to_left.contains_key(right)
+    }
+
+    /// Removes the pair whose left value is `left`, from both maps.
+    pub fn remove_by_left(&mut self, left: &L) -> Option<(L, R)> {
+        let (left, right) = self.left_to_right.remove_entry(left)?;
+        self.right_to_left.remove(&right);
+        Some((unshare(left), unshare(right)))
+    }
+
+    /// Removes the pair whose right value is `right`, from both maps.
+    pub fn remove_by_right(&mut self, right: &R) -> Option<(L, R)> {
+        let (right, left) = self.right_to_left.remove_entry(right)?;
+        self.left_to_right.remove(&left);
+        Some((unshare(left), unshare(right)))
+    }
+
+    /// Pairs `left` with `right`, first removing any pair that holds either
+    /// of them. Returns the removed pairs.
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        let by_left: Option<(L, R)> = self.remove_by_left(&left);
+        let by_right: Option<(L, R)> = self.remove_by_right(&right);
+        let overwritten: Overwritten<L, R> = match (by_left, by_right) {
+            (None, None) => Overwritten::Neither,
+            (Some((l, r)), None) if r == right => Overwritten::Pair(l, r),
+            (Some(pair), None) => Overwritten::Left(pair.0, pair.1),
+            (None, Some(pair)) => Overwritten::Right(pair.0, pair.1),
+            (Some(by_left), Some(by_right)) => Overwritten::Both(by_left, by_right),
+        };
+        self.insert_unchecked(left, right);
+        overwritten
+    }
+
+    /// Pairs `left` with `right` only if neither is in the map yet;
+    /// otherwise hands both back unchanged.
+    pub fn insert_no_overwrite(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.contains_left(&left) || self.contains_right(&right) {
+            return Err((left, right));
+        }
+        self.insert_unchecked(left, right);
+        Ok(())
+    }
+
+    fn insert_unchecked(&mut self, left: L, right: R) {
+        let (left, right): (Rc<L>, Rc<R>) = (Rc::new(left), Rc::new(right));
+        self.left_to_right
+            .insert(Rc::clone(&left), Rc::clone(&right));
+        self.right_to_left.insert(right, left);
+    }
+
+    /// Every pair, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&L, &R)> {
+        self.left_to_right
+            .iter()
+            .map(|(left, right)| (&**left, &**right))
+    }
+
+    /// Whether the two maps hold exactly the same pairs, sharing one `Rc`
+    /// per value. Always true unless a method above has a bug.
+    pub fn is_consistent(&self) -> bool {
+        self.left_to_right.len() == self.right_to_left.len()
+            && self.left_to_right.iter().all(|(left, right)| {
+                self.right_to_left
+                    .get(right)
+                    .is_some_and(|back| Rc::ptr_eq(back, left))
+                    && Rc::strong_count(left) == 2
+                    && Rc::strong_count(right) == 2
+            })
+    }
+}
+
+impl<L: Hash + Eq + Clone, R: Hash + Eq + Clone> Clone for BiMap<L, R> {
+    /// Copies every value. A derived `Clone` would share the `Rc`s with the
+    /// original, and a pair removed from one map would still be held by the
+    /// other.
+    fn clone(&self) -> Self {
+        let mut map: BiMap<L, R> = BiMap::new();
+        for (left, right) in self.iter() {
+            map.insert_unchecked(left.clone(), right.clone());
+        }
+        map
+    }
+}
+
+impl<L: Hash + Eq, R: Hash + Eq> Default for BiMap<L, R> {
+    fn default() -> Self {
+        BiMap::new()
+    }
+}
+
+impl<L: Hash + Eq, R: Hash + Eq> FromIterator<(L, R)> for BiMap<L, R> {
+    /// Later pairs overwrite earlier ones, as with repeated `insert`.
+    fn from_iter<I: IntoIterator<Item = (L, R)>>(iter: I) -> Self {
+        let mut map: BiMap<L, R> = BiMap::new();
+        for (left, right) in iter {
+            map.insert(left, right);
+        }
+        map
+    }
+}
+
+/// Takes a value back out of its `Rc` once both maps have let go of it.
+fn unshare<T>(value: Rc<T>) -> T {
+    Rc::try_unwrap(value)
+        .ok()
+        .expect("a removed value is no longer shared")
+}
+
+/// `1001 <-> ada, 1002 <-> grace`, ordered by id.
+fn show(map: &BiMap<u32, String>) -> String {
+    let mut pairs: Vec<(&u32, &String)> = map.iter().collect();
+    pairs.sort();
+    let pairs: Vec<String> = pairs
+        .into_iter()
+        .map(|(id, name)| format!("{} <-> {}", id, name))
+        .collect();
+    format!("[{}]", pairs.join(", "))
+}
+
+/// Demonstrates lookups in both directions.
+pub fn bimap_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "BiMap Basics")?;
+
+    let mut users: BiMap<u32, String> = BiMap::new();
+    users.insert(1001, "ada".to_string());
+    users.insert(1002, "grace".to_string());
+    users.insert(1003, "linus".to_string());
+    writeln!(out, "users: {}", show(&users))?;
+
+    writeln!(out, "\nget_by_left(&1002) = {:?}", users.get_by_left(&1002))?;
+    writeln!(
+        out,
+        "get_by_right(\"linus\") = {:?}",
+        users.get_by_right(&"linus".to_string())
+    )?;
+    writeln!(
+        out,
+        "get_by_right(\"bjarne\") = {:?}",
+        users.get_by_right(&"bjarne".to_string())
+    )?;
+    writeln!(
+        out,
+        "contains_left(&1001) = {}, contains_right(\"ada\") = {}",
+        users.contains_left(&1001),
+        users.contains_right(&"ada".to_string())
+    )?;
+
+    writeln!(
+        out,
+        "\nremove_by_right(\"grace\") = {:?}",
+        users.remove_by_right(&"grace".to_string())
+    )?;
+    writeln!(out, "get_by_left(&1002) = {:?}", users.get_by_left(&1002))?;
+    writeln!(out, "users: {} ({} pairs)", show(&users), users.len())?;
+
+    // Without a BiMap the reverse lookup is a linear scan
+    let forward: HashMap<u32, String> =
+        users.iter().map(|(&id, name)| (id, name.clone())).collect();
+    let by_scan: Option<&u32> = forward
+        .iter()
+        .find(|&(_, name)| name == "linus")
+        .map(|(id, _)| id);
+    writeln!(
+        out,
+        "\nA plain HashMap<u32, String> finds \"linus\" only by scanning every entry: {:?}",
+        by_scan
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates every case `insert` can report.
+pub fn overwrite_semantics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Overwrite Semantics")?;
+
+    let mut users: BiMap<u32, String> = [(1, "ada"), (2, "grace"), (3, "linus")]
+        .into_iter()
+        .map(|(id, name)| (id, name.to_string()))
+        .collect();
+    writeln!(out, "start: {}", show(&users))?;
+
+    let steps: [(u32, &str, &str); 5] = [
+        (4, "bjarne", "both new"),
+        (1, "ada", "the pair already exists"),
+        (1, "alan", "id 1 is taken: ada loses it"),
+        (5, "grace", "grace is taken: id 2 loses her"),
+        (3, "bjarne", "both taken, by different pairs"),
+    ];
+    for (id, name, note) in steps {
+        let overwritten: Overwritten<u32, String> = users.insert(id, name.to_string());
+        writeln!(out, "\ninsert({}, {:?}): {}", id, name, note)?;
+        writeln!(out, "  -> {:?}", overwritten)?;
+        writeln!(out, "  now {}", show(&users))?;
+    }
+    writeln!(
+        out,
+        "\n{} pairs left from 8 inserts: each overwrite removed whole pairs",
+        users.len()
+    )?;
+
+    writeln!(out, "\ninsert_no_overwrite refuses instead of removing:")?;
+    writeln!(
+        out,
+        "  insert_no_overwrite(9, \"alan\") = {:?}",
+        users.insert_no_overwrite(9, "alan".to_string())
+    )?;
+    writeln!(
+        out,
+        "  insert_no_overwrite(9, \"barbara\") = {:?}",
+        users.insert_no_overwrite(9, "barbara".to_string())
+    )?;
+    writeln!(out, "  now {}", show(&users))?;
+
+    // Two plain HashMaps updated naively drift apart
+    let mut forward: HashMap<u32, String> = HashMap::new();
+    let mut backward: HashMap<String, u32> = HashMap::new();
+    for (id, name) in [(1, "ada"), (1, "alan")] {
+        forward.insert(id, name.to_string());
+        backward.insert(name.to_string(), id);
+    }
+    writeln!(
+        out,
+        "\nTwo plain maps after insert(1, ada), insert(1, alan): forward has {} entry, backward has {}",
+        forward.len(),
+        backward.len()
+    )?;
+    writeln!(
+        out,
+        "  backward[\"ada\"] = {:?}, but forward[1] = {:?}",
+        backward.get("ada"),
+        forward.get(&1)
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates that both maps share one allocation per value.
+pub fn shared_keys(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Shared Rc Keys")?;
+
+    let names: BiMap<u32, String> = (0..1_000u32)
+        .map(|id| (id, format!("user-{:04}-{}", id, "x".repeat(40))))
+        .collect();
+    let name_bytes: usize = names.iter().map(|(_, name)| name.len()).sum();
+    writeln!(
+        out,
+        "1000 pairs of (u32, String), {} bytes of name text",
+        name_bytes
+    )?;
+    writeln!(out, "consistent: {}", names.is_consistent())?;
+
+    let (left, right) = names.left_to_right.iter().next().expect("non-empty");
+    writeln!(
+        out,
+        "Rc::strong_count of any id and name: {} and {} (one per map)",
+        Rc::strong_count(left),
+        Rc::strong_count(right)
+    )?;
+    writeln!(
+        out,
+        "Both maps hold the same String: {}",
+        Rc::ptr_eq(
+            right,
+            names.right_to_left.get_key_value(right).expect("present").0
+        )
+    )?;
+
+    writeln!(
+        out,
+        "\nWith Rc keys the name text is stored once: {} bytes",
+        name_bytes
+    )?;
+    writeln!(
+        out,
+        "Two HashMaps with cloned Strings store it twice: {} bytes",
+        2 * name_bytes
+    )?;
+    writeln!(
+        out,
+        "The cost: two reference counts per value ({} bytes), and Rc makes the map neither Send nor Sync",
+        2 * size_of::<usize>()
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: renaming accounts while usernames stay unique.
+pub fn practical_user_directory(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: User Directory")?;
+
+    let mut directory: BiMap<u32, String> = BiMap::new();
+    for (id, name) in [(1001, "ada"), (1002, "grace"), (1003, "linus")] {
+        directory
+            .insert_no_overwrite(id, name.to_string())
+            .expect("fresh directory");
+    }
+    writeln!(out, "directory: {}", show(&directory))?;
+
+    // Sign-ups: a new id with a username nobody has
+    for (id, name) in [(1004, "margaret"), (1005, "ada")] {
+        match directory.insert_no_overwrite(id, name.to_string()) {
+            Ok(()) => writeln!(out, "\nsign up {} as {:?}: ok", id, name)?,
+            Err((_, name)) => writeln!(
+                out,
+                "\nsign up {} as {:?}: taken by {}",
+                id,
+                name,
+                directory.get_by_right(&name).expect("taken")
+            )?,
+        }
+    }
+
+    // Renames: the id keeps its account, only the name moves
+    for (id, new_name) in [(1003, "torvalds"), (1002, "margaret")] {
+        if let Some(&owner) = directory.get_by_right(&new_name.to_string()) {
+            writeln!(
+                out,
+                "\nrename {} to {:?}: refused, {} has that name",
+                id, new_name, owner
+            )?;
+            continue;
+        }
+        let overwritten: Overwritten<u32, String> = directory.insert(id, new_name.to_string());
+        if let Overwritten::Left(_, old_name) = overwritten {
+            writeln!(
+                out,
+                "\nrename {} from {:?} to {:?}: ok, {:?} is free again",
+                id, old_name, new_name, old_name
+            )?;
+        }
+    }
+
+    // Login resolves name -> id; audit logs resolve id -> name
+    writeln!(
+        out,
+        "\nlogin as \"torvalds\" -> id {:?}",
+        directory.get_by_right(&"torvalds".to_string())
+    )?;
+    writeln!(
+        out,
+        "login as \"linus\" -> id {:?}",
+        directory.get_by_right(&"linus".to_string())
+    )?;
+    let log: [u32; 4] = [1001, 1004, 1003, 1001];
+    let names: Vec<&str> = log
+        .iter()
+        .map(|id| directory.get_by_left(id).map_or("?", String::as_str))
+        .collect();
+    writeln!(out, "audit log ids {:?} -> {:?}", log, names)?;
+
+    writeln!(out, "\ndirectory: {}", show(&directory))?;
+    writeln!(out, "consistent: {}", directory.is_consistent())?;
+
+    Ok(())
+}
@@ -23,85 +23,218 @@
 //   - push(): O(log n) - add element, "bubble up" to restore heap property
 //   - pop(): O(log n) - remove maximum, "bubble down" to restore heap property
 
+use crate::heap_visual::{changed, heap_diagram, heap_vec, render, render_tree};
+use demo_framework::{diagram, section, step};
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "creating_binaryheaps",
+        "All the different ways to create a BinaryHeap",
+        creating_binaryheaps,
+    )?;
+
+    section(
+        out,
+        "max_heap_behavior",
+        "The fundamental max-heap behavior",
+        max_heap_behavior,
+    )?;
+
+    section(
+        out,
+        "min_heap_with_reverse",
+        "Creating a min-heap using Reverse",
+        min_heap_with_reverse,
+    )?;
+
+    section(
+        out,
+        "push_pop_operations",
+        "Push, pop, and peek operations in detail",
+        push_pop_operations,
+    )?;
+
+    section(
+        out,
+        "bulk_operations",
+        "Bulk operations on BinaryHeap",
+        bulk_operations,
+    )?;
+
+    section(
+        out,
+        "conversion_operations",
+        "Converting a BinaryHeap to other collections",
+        conversion_operations,
+    )?;
+
+    section(
+        out,
+        "iteration_patterns",
+        "Iteration patterns for BinaryHeap",
+        iteration_patterns,
+    )?;
+
+    section(
+        out,
+        "practical_task_scheduler",
+        "Practical demo: Task scheduler with priorities",
+        practical_task_scheduler,
+    )?;
+
+    section(
+        out,
+        "practical_k_largest",
+        "Practical demo: Finding K largest elements efficiently",
+        practical_k_largest,
+    )?;
+
+    section(
+        out,
+        "practical_merge_sorted_lists",
+        "Practical demo: Merging K sorted lists",
+        practical_merge_sorted_lists,
+    )?;
+
+    section(
+        out,
+        "practical_dijkstra_concept",
+        "Practical demo: Dijkstra's shortest path algorithm structure",
+        practical_dijkstra_concept,
+    )?;
+
+    section(
+        out,
+        "practical_heapsort",
+        "Practical demo: Heapsort implementation",
+        practical_heapsort,
+    )?;
+
+    section(
+        out,
+        "custom_types_in_heap",
+        "Using custom types with BinaryHeap",
+        custom_types_in_heap,
+    )?;
+
+    Ok(())
+}
 
 /// Demonstrates all the different ways to create a BinaryHeap.
 ///
 /// Unlike HashMap/HashSet, BinaryHeap requires elements to implement Ord
 /// (not Hash). This is because elements are ordered by comparison, not hashing.
-pub fn creating_binaryheaps() {
+pub fn creating_binaryheaps(out: &mut dyn Write) -> io::Result<()> {
     // Method 1: BinaryHeap::new()
     // The most common way - start empty and add items
     let mut heap: BinaryHeap<i8> = BinaryHeap::new();
     heap.push(3);
     heap.push(1);
     heap.push(4);
-    println!("Created with new(): {:?}", heap);
-    println!("Note: Internal order is heap order, NOT sorted order!");
-    println!("But peek() always returns the maximum: {:?}", heap.peek());
+    writeln!(out, "Created with new(): {:?}", heap)?;
+    writeln!(out, "Note: Internal order is heap order, NOT sorted order!")?;
+    writeln!(
+        out,
+        "But peek() always returns the maximum: {:?}",
+        heap.peek()
+    )?;
 
     // Method 2: BinaryHeap::from() - from array
     // The heap property is established during construction
     let heap2: BinaryHeap<i8> = BinaryHeap::from([5, 2, 8, 1, 9]);
-    println!("\nFrom array [5, 2, 8, 1, 9]: {:?}", heap2);
-    println!("Maximum is: {:?}", heap2.peek());
+    writeln!(out, "\nFrom array [5, 2, 8, 1, 9]: {:?}", heap2)?;
+    writeln!(out, "Maximum is: {:?}", heap2.peek())?;
 
     // Method 3: collect() from iterator
     // Building from an iterator is O(n), more efficient than n pushes
     let heap3: BinaryHeap<i8> = (1..=5).collect();
-    println!("\nCollected from 1..=5: {:?}", heap3);
+    writeln!(out, "\nCollected from 1..=5: {:?}", heap3)?;
 
     // Method 4: with_capacity (performance optimization)
     // Pre-allocate when you know approximate size
     let heap4: BinaryHeap<i8> = BinaryHeap::with_capacity(1_00);
-    println!(
+    writeln!(
+        out,
         "\nWith capacity 100, current len: {}, capacity: {}",
         heap4.len(),
         heap4.capacity()
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates the fundamental max-heap behavior.
 ///
 /// The key insight: BinaryHeap always gives you the MAXIMUM element.
 /// Every push and pop operation maintains this invariant.
-pub fn max_heap_behavior() {
-    println!("Max-Heap behavior");
+pub fn max_heap_behavior(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Max-Heap behavior")?;
 
     let mut heap: BinaryHeap<i8> = BinaryHeap::new();
 
-    // Watch how the maximum changes as we push elements
-    println!("Pushing elements and observing the maximum:");
+    // Watch how the maximum changes as we push elements. The tree is the
+    // heap's internal Vec; [brackets] mark the slots the operation rewrote
+    writeln!(out, "Pushing elements and observing the maximum:")?;
     for item in [3, 1, 4, 1, 5, 9, 2, 6] {
+        let before: Vec<i8> = heap_vec(&heap);
         heap.push(item);
-        println!(
-            "  After push({}): peek = {:?}, heap = {:?}",
+        let after: Vec<i8> = heap_vec(&heap);
+        writeln!(
+            out,
+            "\n  After push({}): peek = {:?}, heap = {:?}",
             item,
             heap.peek(),
             heap
-        );
+        )?;
+        // The new element starts in the last slot and bubbles up while it
+        // beats its parent, so the marks run from a leaf toward the root
+        for line in render_tree(&after, &changed(&before, &after)).lines() {
+            writeln!(out, "    {}", line)?;
+        }
     }
 
+    diagram::export(out, || heap_diagram("max_heap", &heap_vec(&heap)))?;
+
     // peek() - see the maximum without removing (O(1))
-    println!("\npeek() returns {:?} - the maximum", heap.peek());
+    writeln!(out, "\npeek() returns {:?} - the maximum", heap.peek())?;
 
     // pop() - remove and return the maximum (O(log n))
     // Elements come out in descending order!
-    println!("\nPopping all elements (they come out in descending order):");
-    while let Some(max) = heap.pop() {
-        print!("{} ", max);
+    writeln!(
+        out,
+        "\nPopping all elements (they come out in descending order):"
+    )?;
+    let mut popped: Vec<i8> = Vec::new();
+    loop {
+        let before: Vec<i8> = heap_vec(&heap);
+        let Some(max) = heap.pop() else {
+            break;
+        };
+        popped.push(max);
+        let after: Vec<i8> = heap_vec(&heap);
+        writeln!(out, "\n  pop() -> {}, heap = {:?}", max, heap)?;
+        // The last element moves into the root's slot and sifts down
+        // through the larger child at each level
+        for line in render_tree(&after, &changed(&before, &after)).lines() {
+            writeln!(out, "    {}", line)?;
+        }
     }
-    println!();
-    println!("Heap is now empty: {}", heap.is_empty());
+    writeln!(out, "\nPopped in order: {:?}", popped)?;
+    writeln!(out, "Heap is now empty: {}", heap.is_empty())?;
+
+    Ok(())
 }
 
 /// Demonstrates how to create a min-heap using Reverse.
 ///
 /// BinaryHeap is a max-heap by default. To get min-heap behavior,
 /// wrap elements in std::cmp::Reverse, which inverts the ordering.
-pub fn min_heap_with_reverse() {
-    println!("Min-Heap with Reverse");
+pub fn min_heap_with_reverse(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Min-Heap with Reverse")?;
 
     // Reverse<T> inverts the Ord implementation
     // So Reverse(1) > Reverse(5), making the smallest value "largest"
@@ -111,125 +244,136 @@ pub fn min_heap_with_reverse() {
         min_heap.push(Reverse(val));
     }
 
-    println!("Min-heap created with values [3, 1, 4, 1, 5, 9, 2, 6]");
-    println!("peek() returns {:?}", min_heap.peek()); // Reverse(1)
+    writeln!(out, "Min-heap created with values [3, 1, 4, 1, 5, 9, 2, 6]")?;
+    writeln!(out, "peek() returns {:?}", min_heap.peek())?; // Reverse(1)
 
     // Pop gives smallest first (ascending order)
-    println!("\nPopping from min-heap (ascending order):");
+    writeln!(out, "\nPopping from min-heap (ascending order):")?;
     while let Some(Reverse(min)) = min_heap.pop() {
-        print!("{} ", min);
+        write!(out, "{} ", min)?;
     }
-    println!();
+    writeln!(out)?;
 
     // You can also use a type alias for clarity
     // type MinHeap<T> = BinaryHeap<Reverse<T>>;
+
+    Ok(())
 }
 
 /// Demonstrates push, pop, and peek operations in detail.
 ///
 /// These are the core operations that make BinaryHeap useful as a priority queue.
-pub fn push_pop_operations() {
-    println!("Push, Pop, and Peek Operations");
+pub fn push_pop_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Push, Pop, and Peek Operations")?;
 
     let mut heap: BinaryHeap<i8> = BinaryHeap::from([5, 3, 7]);
-    println!("Initial heap: {:?}", heap);
+    writeln!(out, "Initial heap: {:?}", heap)?;
 
     // push() - add an element, O(log n)
     // The heap reorganizes to maintain the max-heap property
     heap.push(10);
-    println!("\nAfter push(10): {:?}", heap);
-    println!("New maximum: {:?}", heap.peek());
+    writeln!(out, "\nAfter push(10): {:?}", heap)?;
+    writeln!(out, "New maximum: {:?}", heap.peek())?;
 
     heap.push(1);
-    println!("After push(1): {:?}", heap);
-    println!("Maximum unchanged: {:?}", heap.peek());
+    writeln!(out, "After push(1): {:?}", heap)?;
+    writeln!(out, "Maximum unchanged: {:?}", heap.peek())?;
 
     // pop() - remove and return the maximum, O(log n)
     let max: Option<i8> = heap.pop();
-    println!("\npop() returned: {:?}", max);
-    println!("Heap after pop: {:?}", heap);
-    println!("New maximum: {:?}", heap.peek());
+    writeln!(out, "\npop() returned: {:?}", max)?;
+    writeln!(out, "Heap after pop: {:?}", heap)?;
+    writeln!(out, "New maximum: {:?}", heap.peek())?;
 
     // peek() - view the maximum without removing, O(1)
     // Returns Option<&T> since heap might be empty
     match heap.peek() {
-        Some(&max) => println!("\nCurrent maximum is: {}", max),
-        None => println!("\nHeap is empty!"),
+        Some(&max) => writeln!(out, "\nCurrent maximum is: {}", max)?,
+        None => writeln!(out, "\nHeap is empty!")?,
     }
 
     // peek_mut() - modify the maximum in place
     // When the PeekMut guard is dropped, the heap reorganizes if needed
-    println!("\nUsing peek_mut() to modify the maximum:");
+    writeln!(out, "\nUsing peek_mut() to modify the maximum:")?;
     if let Some(mut max_ref) = heap.peek_mut() {
-        println!("  Current max: {}", *max_ref);
+        writeln!(out, "  Current max: {}", *max_ref)?;
         *max_ref = 1; // Change the maximum to a small value
-        println!("  Set to 1, heap will reorganize when guard drops");
+        writeln!(out, "  Set to 1, heap will reorganize when guard drops")?;
     }
-    println!("After peek_mut(): {:?}", heap);
-    println!("The heap automatically reorganized!");
+    writeln!(out, "After peek_mut(): {:?}", heap)?;
+    writeln!(out, "The heap automatically reorganized!")?;
+
+    Ok(())
 }
 
 /// Demonstrates bulk operations on BinaryHeap.
 ///
 /// These operations are useful for combining heaps or filtering elements.
-pub fn bulk_operations() {
-    println!("Bulk Operations");
+pub fn bulk_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Bulk Operations")?;
 
     // append() - move all elements from another heap, O(n + m)
     let mut heap1: BinaryHeap<i8> = BinaryHeap::from([1, 2, 3]);
     let mut heap2: BinaryHeap<i8> = BinaryHeap::from([4, 5, 6]);
 
-    println!("Before append:");
-    println!("  heap1: {:?}", heap1);
-    println!("  heap2: {:?}", heap2);
+    writeln!(out, "Before append:")?;
+    writeln!(out, "  heap1: {:?}", heap1)?;
+    writeln!(out, "  heap2: {:?}", heap2)?;
 
     heap1.append(&mut heap2);
-    println!("\nAfter heap1.append(&mut heap2):");
-    println!("  heap1: {:?}", heap1);
-    println!("  heap2: {:?} (now empty)", heap2);
+    writeln!(out, "\nAfter heap1.append(&mut heap2):")?;
+    writeln!(out, "  heap1: {:?}", heap1)?;
+    writeln!(out, "  heap2: {:?} (now empty)", heap2)?;
 
     // extend() - add elements from an iterator
     let mut heap: BinaryHeap<i8> = BinaryHeap::from([10]);
     heap.extend([1, 2, 3, 4, 5]);
-    println!("\nAfter extend([1,2,3,4,5]): {:?}", heap);
+    writeln!(out, "\nAfter extend([1,2,3,4,5]): {:?}", heap)?;
 
     // retain() - keep only elements matching a predicate
     let mut heap: BinaryHeap<i8> = (1..=10).collect();
-    println!("\nBefore retain: {:?}", heap);
+    writeln!(out, "\nBefore retain: {:?}", heap)?;
     heap.retain(|&x| x % 2 == 0); // Keep only even numbers
-    println!("After retain (even only): {:?}", heap);
+    writeln!(out, "After retain (even only): {:?}", heap)?;
 
     // clear() - remove all elements
     heap.clear();
-    println!("\nAfter clear(): {:?}, is_empty: {}", heap, heap.is_empty());
+    writeln!(
+        out,
+        "\nAfter clear(): {:?}, is_empty: {}",
+        heap,
+        heap.is_empty()
+    )?;
 
     // drain() - remove all elements as an iterator
     let mut heap: BinaryHeap<i8> = BinaryHeap::from([3, 1, 4, 1, 5]);
-    println!("\nDraining heap:");
+    writeln!(out, "\nDraining heap:")?;
     let drained: Vec<i8> = heap.drain().collect();
-    println!("  Drained elements: {:?}", drained);
-    println!("  Heap after drain: {:?}", heap);
+    writeln!(out, "  Drained elements: {:?}", drained)?;
+    writeln!(out, "  Heap after drain: {:?}", heap)?;
+
+    Ok(())
 }
 
 /// Demonstrates converting a BinaryHeap to other collections.
 ///
 /// Key insight: into_sorted_vec() gives you a sorted Vec efficiently.
-pub fn conversion_operations() {
-    println!("Conversion Operations");
+pub fn conversion_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Conversion Operations")?;
 
     let heap: BinaryHeap<i8> = BinaryHeap::from([3, 1, 4, 1, 5, 9, 2, 6]);
-    println!("Original heap: {:?}", heap);
+    writeln!(out, "Original heap: {:?}", heap)?;
 
     // into_vec() - consume heap into unsorted Vec, O(1)
     // This just unwraps the internal storage
     let heap_copy: BinaryHeap<i8> = BinaryHeap::from([3, 1, 4, 1, 5, 9, 2, 6]);
     let unsorted: Vec<i8> = heap_copy.into_vec();
-    println!("\ninto_vec() (unsorted): {:?}", unsorted);
+    writeln!(out, "\ninto_vec() (unsorted): {:?}", unsorted)?;
 
     // into_sorted_vec() - consume heap into sorted Vec, O(n log n)
     // This is essentially heapsort!
     let sorted: Vec<i8> = heap.into_sorted_vec();
-    println!("into_sorted_vec() (sorted ascending): {:?}", sorted);
+    writeln!(out, "into_sorted_vec() (sorted ascending): {:?}", sorted)?;
 
     // For descending order, pop repeatedly or use Reverse
     let heap: BinaryHeap<i8> = BinaryHeap::from([3, 1, 4, 1, 5, 9, 2, 6]);
@@ -238,46 +382,54 @@ pub fn conversion_operations() {
     while let Some(val) = heap_mut.pop() {
         descending.push(val);
     }
-    println!("Via repeated pop (sorted descending): {:?}", descending);
+    writeln!(
+        out,
+        "Via repeated pop (sorted descending): {:?}",
+        descending
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates iteration patterns for BinaryHeap.
 ///
 /// IMPORTANT: iter() does NOT give sorted order! Only pop() does.
-pub fn iteration_patterns() {
-    println!("Iteration patterns");
+pub fn iteration_patterns(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Iteration patterns")?;
 
     let heap: BinaryHeap<i8> = BinaryHeap::from([3, 1, 4, 1, 5, 9, 2, 6]);
 
     // iter() - iterate in ARBITRARY heap order (NOT sorted!)
-    println!("iter() gives heap order (NOT sorted):");
-    print!("  ");
+    writeln!(out, "iter() gives heap order (NOT sorted):")?;
+    write!(out, "  ")?;
     for val in heap.iter() {
-        print!("{} ", val);
+        write!(out, "{} ", val)?;
     }
-    println!();
+    writeln!(out)?;
 
     // For sorted iteration, you must pop()
-    println!("\nFor sorted iteration, use pop():");
+    writeln!(out, "\nFor sorted iteration, use pop():")?;
     let mut heap_clone: BinaryHeap<i8> = heap.clone();
-    print!("  ");
+    write!(out, "  ")?;
     while let Some(val) = heap_clone.pop() {
-        print!("{} ", val);
+        write!(out, "{} ", val)?;
     }
-    println!();
+    writeln!(out)?;
 
     // Or use into_sorted_vec()
-    println!("\nOr use into_sorted_vec():");
+    writeln!(out, "\nOr use into_sorted_vec():")?;
     let sorted: Vec<i8> = heap.into_sorted_vec();
-    println!("  {:?}", sorted);
+    writeln!(out, "  {:?}", sorted)?;
+
+    Ok(())
 }
 
 /// Practical example: Task scheduler with priorities.
 ///
 /// This is the classic use case for a priority queue - process tasks
 /// in order of priority, not in order of arrival.
-pub fn practical_task_scheduler() {
-    println!("Practical example: task scheduler");
+pub fn practical_task_scheduler(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical example: task scheduler")?;
 
     #[derive(Debug, Eq, PartialEq)]
     struct Task {
@@ -328,18 +480,20 @@ pub fn practical_task_scheduler() {
         name: "Performance optimization".to_string(),
     });
 
-    println!("Processing tasks by priority (highest first):");
+    writeln!(out, "Processing tasks by priority (highest first):")?;
     while let Some(task) = task_queue.pop() {
-        println!("  [Priority {:2}] {}", task.priority, task.name);
+        writeln!(out, "  [Priority {:2}] {}", task.priority, task.name)?;
     }
+
+    Ok(())
 }
 
 /// Practical example: Finding K largest elements efficiently.
 ///
 /// This is more memory-efficient than sorting the entire array
 /// when K is much smaller than N.
-pub fn practical_k_largest() {
-    println!("Practical Example: K Largest Elements");
+pub fn practical_k_largest(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: K Largest Elements")?;
 
     // Strategy: Use a MIN-heap of size k
     // - Keep only the k largest elements seen so far
@@ -375,61 +529,114 @@ pub fn practical_k_largest() {
     }
 
     let data: Vec<i8> = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7];
-    println!("Data: {:?}", data);
-    println!("5 largest: {:?}", k_largest(&data, 5));
-    println!("5 smallest: {:?}", k_smallest(&data, 5));
+    writeln!(out, "Data: {:?}", data)?;
+    writeln!(out, "5 largest: {:?}", k_largest(&data, 5))?;
+    writeln!(out, "5 smallest: {:?}", k_smallest(&data, 5))?;
+
+    Ok(())
 }
 
-/// Practical example: Merging K sorted lists.
+/// A k-way merge that emits one element per [`KWayMerge::advance`].
 ///
-/// This is a classic problem that demonstrates the power
-/// of priority queues for efficient merging.
-pub fn practical_merge_sorted_lists() {
-    println!("Practical Example: Merge K Sorted Lists");
-
-    fn merge_k_sorted(lists: Vec<Vec<i8>>) -> Vec<i8> {
-        // Entry: (value, list_index, element_index)
-        // Use Reverse for min-heap behavior (smallest value first)
-        let mut heap: BinaryHeap<Reverse<(i8, usize, usize)>> = BinaryHeap::new();
+/// The heap holds the smallest unmerged element of every list, so it never
+/// grows past k entries and each step costs O(log k).
+pub struct KWayMerge<T> {
+    lists: Vec<Vec<T>>,
+    // Entry: (value, list_index, element_index)
+    // Reverse turns the max-heap into a min-heap (smallest value first)
+    heap: BinaryHeap<Reverse<(T, usize, usize)>>,
+    merged: Vec<T>,
+}
 
+impl<T: Ord + Clone> KWayMerge<T> {
+    pub fn new(lists: Vec<Vec<T>>) -> Self {
         // Initialize with the first element of each list
-        for (list_idx, list) in lists.iter().enumerate() {
-            if !list.is_empty() {
-                heap.push(Reverse((list[0], list_idx, 0)));
-            }
+        let heap: BinaryHeap<Reverse<(T, usize, usize)>> = lists
+            .iter()
+            .enumerate()
+            .filter_map(|(list_idx, list)| Some(Reverse((list.first()?.clone(), list_idx, 0))))
+            .collect();
+        KWayMerge {
+            lists,
+            heap,
+            merged: Vec::new(),
         }
+    }
 
-        let mut result: Vec<i8> = Vec::new();
+    /// Moves the smallest remaining element to the output and queues the
+    /// next element of its list. Returns the element and its list index, or
+    /// `None` once every list is used up.
+    pub fn advance(&mut self) -> Option<(T, usize)> {
+        let Reverse((val, list_idx, elem_idx)) = self.heap.pop()?;
+        self.merged.push(val.clone());
+
+        // Add next element from the same list (if available)
+        let next_idx: usize = elem_idx + 1;
+        if let Some(next) = self.lists[list_idx].get(next_idx) {
+            self.heap.push(Reverse((next.clone(), list_idx, next_idx)));
+        }
+        Some((val, list_idx))
+    }
 
-        while let Some(Reverse((val, list_idx, elem_idx))) = heap.pop() {
-            result.push(val);
+    /// The output so far.
+    pub fn merged(&self) -> &[T] {
+        &self.merged
+    }
 
-            // Add next element from the same list (if available)
-            let next_idx: usize = elem_idx + 1;
-            if next_idx < lists[list_idx].len() {
-                heap.push(Reverse((lists[list_idx][next_idx], list_idx, next_idx)));
-            }
-        }
+    pub fn into_merged(self) -> Vec<T> {
+        self.merged
+    }
+}
 
-        result
+impl<T: Ord + Clone + std::fmt::Display> KWayMerge<T> {
+    /// The heap as a tree of `value/list` labels, then the output so far.
+    pub fn render(&self) -> String {
+        let labels: Vec<String> = heap_vec(&self.heap)
+            .into_iter()
+            .map(|Reverse((val, list_idx, _))| format!("{}/L{}", val, list_idx))
+            .collect();
+        let merged: Vec<String> = self.merged.iter().map(|val| val.to_string()).collect();
+        format!(
+            "{}\nmerged: [{}]",
+            render_tree(&labels, &[]),
+            merged.join(", ")
+        )
     }
+}
+
+/// Practical example: Merging K sorted lists.
+///
+/// This is a classic problem that demonstrates the power
+/// of priority queues for efficient merging.
+pub fn practical_merge_sorted_lists(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Merge K Sorted Lists")?;
 
     let lists: Vec<Vec<i8>> = vec![vec![1, 4, 7, 10], vec![2, 5, 8, 11], vec![3, 6, 9, 12]];
 
-    println!("Lists to merge:");
+    writeln!(out, "Lists to merge:")?;
     for (i, list) in lists.iter().enumerate() {
-        println!("  List {}: {:?}", i, list);
+        writeln!(out, "  List {}: {:?}", i, list)?;
+    }
+
+    let mut merge: KWayMerge<i8> = KWayMerge::new(lists);
+    while let Some((val, list_idx)) = merge.advance() {
+        step::pause(
+            out,
+            format_args!("pop {} from list {}", val, list_idx),
+            || merge.render(),
+        )?;
     }
+    let merged: Vec<i8> = merge.into_merged();
+    writeln!(out, "\nMerged result: {:?}", merged)?;
 
-    let merged: Vec<i8> = merge_k_sorted(lists);
-    println!("\nMerged result: {:?}", merged);
+    Ok(())
 }
 
 /// Practical example: Dijkstra's shortest path algorithm structure.
 ///
 /// BinaryHeap is essential for efficient graph algorithms.
-pub fn practical_dijkstra_concept() {
-    println!("Practical Example: Dijkstra's Algorithm (Concept)");
+pub fn practical_dijkstra_concept(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Dijkstra's Algorithm (Concept)")?;
 
     // In Dijkstra's algorithm, we process nodes by minimum distance
     // BinaryHeap (as min-heap) is perfect for this
@@ -463,23 +670,93 @@ pub fn practical_dijkstra_concept() {
     priority_queue.push(State { cost: 15, node: 3 });
     priority_queue.push(State { cost: 7, node: 4 });
 
-    println!("Processing nodes by minimum cost (Dijkstra's order):");
+    writeln!(out, "Processing nodes by minimum cost (Dijkstra's order):")?;
     while let Some(State { cost, node }) = priority_queue.pop() {
-        println!("  Visit node {} with distance {}", node, cost);
+        writeln!(out, "  Visit node {} with distance {}", node, cost)?;
+    }
+
+    writeln!(out, "\nIn real Dijkstra's:")?;
+    writeln!(out, "  1. Start with source node at distance 0")?;
+    writeln!(out, "  2. Pop minimum distance node from heap")?;
+    writeln!(
+        out,
+        "  3. For each neighbor, if new path is shorter, update and push"
+    )?;
+    writeln!(out, "  4. Repeat until destination reached or heap empty")?;
+    writeln!(
+        out,
+        "\nBinaryHeap has no decrease-key, so step 3 pushes a duplicate and"
+    )?;
+    writeln!(
+        out,
+        "stale entries are skipped on pop (see indexed_heap_examples)."
+    )?;
+    writeln!(
+        out,
+        "Complete Dijkstra and A* with paths: shortest_path_examples."
+    )?;
+
+    Ok(())
+}
+
+/// What one [`Heapsort::advance`] did.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeapsortStep<T> {
+    /// Building: the element went into the heap.
+    Pushed(T),
+    /// Sorting: the heap's maximum went to the output.
+    Popped(T),
+}
+
+/// Heapsort one heap operation at a time: first every element is pushed,
+/// then the maximum is popped until the heap is empty, giving the elements
+/// largest first.
+pub struct Heapsort<T> {
+    pending: std::vec::IntoIter<T>,
+    heap: BinaryHeap<T>,
+    sorted: Vec<T>,
+}
+
+impl<T: Ord + Clone> Heapsort<T> {
+    pub fn new(data: Vec<T>) -> Self {
+        Heapsort {
+            heap: BinaryHeap::with_capacity(data.len()),
+            sorted: Vec::with_capacity(data.len()),
+            pending: data.into_iter(),
+        }
+    }
+
+    /// Pushes the next element, or once all are in, pops the maximum.
+    /// `None` when the heap is empty again.
+    pub fn advance(&mut self) -> Option<HeapsortStep<T>> {
+        if let Some(val) = self.pending.next() {
+            self.heap.push(val.clone());
+            return Some(HeapsortStep::Pushed(val));
+        }
+        let val: T = self.heap.pop()?;
+        self.sorted.push(val.clone());
+        Some(HeapsortStep::Popped(val))
+    }
+
+    pub fn heap(&self) -> &BinaryHeap<T> {
+        &self.heap
     }
 
-    println!("\nIn real Dijkstra's:");
-    println!("  1. Start with source node at distance 0");
-    println!("  2. Pop minimum distance node from heap");
-    println!("  3. For each neighbor, if new path is shorter, update and push");
-    println!("  4. Repeat until destination reached or heap empty");
+    /// The output so far, largest first.
+    pub fn sorted(&self) -> &[T] {
+        &self.sorted
+    }
+
+    pub fn into_sorted(self) -> Vec<T> {
+        self.sorted
+    }
 }
 
 /// Practical example: Heapsort implementation.
 ///
 /// Demonstrates how BinaryHeap can be used for sorting.
-pub fn practical_heapsort() {
-    println!("Practical Example: Heapsort");
+pub fn practical_heapsort(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Heapsort")?;
 
     fn heapsort<T: Ord>(data: Vec<T>) -> Vec<T> {
         // Build heap - O(n)
@@ -488,26 +765,31 @@ pub fn practical_heapsort() {
         heap.into_sorted_vec()
     }
 
-    fn heapsort_descending<T: Ord>(data: Vec<T>) -> Vec<T> {
-        let mut heap: BinaryHeap<T> = data.into_iter().collect();
-        let mut result: Vec<T> = Vec::with_capacity(heap.len());
-        while let Some(val) = heap.pop() {
-            result.push(val);
-        }
-        result
+    let data: Vec<i8> = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+    writeln!(out, "Original: {:?}", data)?;
+    writeln!(out, "Heapsort ascending: {:?}", heapsort(data.clone()))?;
+
+    // Descending, one push or pop at a time (watch it with --step)
+    let mut sort: Heapsort<i8> = Heapsort::new(data);
+    while let Some(done) = sort.advance() {
+        let (action, val) = match done {
+            HeapsortStep::Pushed(val) => ("push", val),
+            HeapsortStep::Popped(val) => ("pop to the output:", val),
+        };
+        step::pause(out, format_args!("{} {}", action, val), || {
+            format!("{}\nsorted: {:?}", render(sort.heap()), sort.sorted())
+        })?;
     }
+    writeln!(out, "Heapsort descending: {:?}", sort.into_sorted())?;
 
-    let data: Vec<i8> = vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
-    println!("Original: {:?}", data);
-    println!("Heapsort ascending: {:?}", heapsort(data.clone()));
-    println!("Heapsort descending: {:?}", heapsort_descending(data));
+    Ok(())
 }
 
 /// Demonstrates using custom types with BinaryHeap.
 ///
 /// Your type must implement Ord (and therefore PartialOrd, Eq, PartialEq).
-pub fn custom_types_in_heap() {
-    println!("Custom Types in BinaryHeap");
+pub fn custom_types_in_heap(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Custom Types in BinaryHeap")?;
 
     // Example 1: Simple struct with derived ordering
     #[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
@@ -531,9 +813,9 @@ pub fn custom_types_in_heap() {
         player: "Charlie".to_string(),
     });
 
-    println!("Leaderboard (highest score first):");
+    writeln!(out, "Leaderboard (highest score first):")?;
     while let Some(score) = leaderboard.pop() {
-        println!("  {}: {} points", score.player, score.points);
+        writeln!(out, "  {}: {} points", score.player, score.points)?;
     }
 
     // Example 2: Custom ordering (multiple criteria)
@@ -578,11 +860,14 @@ pub fn custom_types_in_heap() {
         name: "Task C".to_string(),
     }); // Higher priority
 
-    println!("\nEvent queue with priority + timestamp ordering:");
+    writeln!(out, "\nEvent queue with priority + timestamp ordering:")?;
     while let Some(event) = event_queue.pop() {
-        println!(
+        writeln!(
+            out,
             "  [P{}, T{}] {}",
             event.priority, event.timestamp, event.name
-        );
+        )?;
     }
+
+    Ok(())
 }
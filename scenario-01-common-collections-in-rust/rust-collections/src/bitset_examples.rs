@@ -0,0 +1,457 @@
+// A bitset stores a set of small integers (or a row of flags) as one bit
+// each, packed into machine words. Three ways to get one:
+//
+//   Vec<bool>        1 byte per flag. Simple, but 8x the memory, and set
+//                    operations are a loop over every element.
+//   bitvec::BitVec   1 bit per flag behind a Vec-like API, with slicing,
+//                    bitwise operators and fast counting built in.
+//   u64 words        The hand-rolled version: bit i lives in word i / 64 at
+//                    position i % 64. Short enough to write yourself, and it
+//                    shows what the libraries do underneath.
+//
+// Packing 64 flags per word is what makes bitsets fast: count_ones() is a
+// single popcnt instruction per word, and union/intersection process 64
+// elements per OR/AND.
+
+use bitvec::prelude::*;
+use demo_framework::section;
+use std::io::{self, Write};
+use std::mem::{size_of, size_of_val};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "vec_bool_baseline",
+        "Vec<bool>: one byte per flag",
+        vec_bool_baseline,
+    )?;
+
+    section(
+        out,
+        "bitvec_examples",
+        "bitvec: a packed BitVec with a Vec-like API",
+        bitvec_examples,
+    )?;
+
+    section(
+        out,
+        "word_bitset_examples",
+        "A hand-rolled bitset on u64 words: set, test, clear",
+        word_bitset_examples,
+    )?;
+
+    section(
+        out,
+        "rank_and_select",
+        "Rank/select-style counting: ones before a position, position of the k-th one",
+        rank_and_select,
+    )?;
+
+    section(
+        out,
+        "set_operations",
+        "Union, intersection and difference as bitwise operations",
+        set_operations,
+    )?;
+
+    section(
+        out,
+        "memory_footprint",
+        "Bytes used for one million flags",
+        memory_footprint,
+    )?;
+
+    Ok(())
+}
+
+/// A fixed-size bitset backed by `u64` words.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WordBitSet {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl WordBitSet {
+    /// Creates a bitset that can hold bits `0..len`, all cleared.
+    pub fn new(len: usize) -> Self {
+        WordBitSet {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn set(&mut self, i: usize) {
+        assert!(
+            i < self.len,
+            "bit {} out of range for length {}",
+            i,
+            self.len
+        );
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    pub fn clear(&mut self, i: usize) {
+        assert!(
+            i < self.len,
+            "bit {} out of range for length {}",
+            i,
+            self.len
+        );
+        self.words[i / 64] &= !(1 << (i % 64));
+    }
+
+    pub fn test(&self, i: usize) -> bool {
+        i < self.len && self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    /// Number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Number of set bits in `0..i`.
+    pub fn rank(&self, i: usize) -> usize {
+        let i: usize = i.min(self.len);
+        let full: usize = self.words[..i / 64]
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum();
+        let partial: usize = if i.is_multiple_of(64) {
+            0
+        } else {
+            (self.words[i / 64] & ((1 << (i % 64)) - 1)).count_ones() as usize
+        };
+        full + partial
+    }
+
+    /// Position of the `k`-th set bit (0-based), if there are more than `k`.
+    pub fn select(&self, k: usize) -> Option<usize> {
+        let mut remaining: usize = k;
+        for (index, &word) in self.words.iter().enumerate() {
+            let ones: usize = word.count_ones() as usize;
+            if remaining < ones {
+                // Drop the lowest set bit `remaining` times, then take the next
+                let mut w: u64 = word;
+                for _ in 0..remaining {
+                    w &= w - 1;
+                }
+                return Some(index * 64 + w.trailing_zeros() as usize);
+            }
+            remaining -= ones;
+        }
+        None
+    }
+
+    /// Iterates over the positions of set bits, in ascending order.
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, &word)| {
+            let mut w: u64 = word;
+            std::iter::from_fn(move || {
+                if w == 0 {
+                    return None;
+                }
+                let bit: usize = w.trailing_zeros() as usize;
+                w &= w - 1;
+                Some(index * 64 + bit)
+            })
+        })
+    }
+
+    pub fn union_with(&mut self, other: &WordBitSet) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &WordBitSet) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= b;
+        }
+    }
+
+    pub fn difference_with(&mut self, other: &WordBitSet) {
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a &= !b;
+        }
+    }
+
+    /// Heap bytes used by the words.
+    pub fn heap_bytes(&self) -> usize {
+        self.words.capacity() * size_of::<u64>()
+    }
+}
+
+/// Demonstrates the baseline: a Vec<bool> as a set of flags.
+pub fn vec_bool_baseline(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Vec<bool> Baseline")?;
+
+    // Which of 0..20 are prime? A classic sieve over a Vec<bool>
+    let mut is_prime: Vec<bool> = vec![true; 20];
+    is_prime[0] = false;
+    is_prime[1] = false;
+    for i in 2..20 {
+        if is_prime[i] {
+            for multiple in (i * i..20).step_by(i) {
+                is_prime[multiple] = false;
+            }
+        }
+    }
+
+    let primes: Vec<usize> = (0..20).filter(|&i| is_prime[i]).collect();
+    writeln!(out, "Primes below 20: {:?}", primes)?;
+
+    // set / test / clear are plain indexing
+    is_prime[4] = true;
+    writeln!(out, "After is_prime[4] = true: test(4) = {}", is_prime[4])?;
+    is_prime[4] = false;
+
+    // Counting means visiting every element
+    let count: usize = is_prime.iter().filter(|&&b| b).count();
+    writeln!(out, "Count: {} (one comparison per flag)", count)?;
+    writeln!(
+        out,
+        "Memory: {} bytes for {} flags",
+        is_prime.len() * size_of::<bool>(),
+        is_prime.len()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates bitvec's BitVec.
+///
+/// `BitVec<u64, Lsb0>` stores bits in u64 words, least significant bit
+/// first - the same layout as the hand-rolled bitset below.
+pub fn bitvec_examples(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "bitvec")?;
+
+    // The same sieve, now 1 bit per flag
+    let mut is_prime: BitVec<u64, Lsb0> = bitvec![u64, Lsb0; 1; 20];
+    is_prime.set(0, false);
+    is_prime.set(1, false);
+    for i in 2..20 {
+        if is_prime[i] {
+            for multiple in (i * i..20).step_by(i) {
+                is_prime.set(multiple, false);
+            }
+        }
+    }
+    let primes: Vec<usize> = is_prime.iter_ones().collect();
+    writeln!(out, "Primes below 20: {:?}", primes)?;
+
+    // Counting and searching work a word at a time
+    writeln!(out, "count_ones(): {}", is_prime.count_ones())?;
+    writeln!(out, "first_one(): {:?}", is_prime.first_one())?;
+    writeln!(out, "last_one(): {:?}", is_prime.last_one())?;
+
+    // Bit slices: any sub-range is a &BitSlice
+    let window: &BitSlice<u64, Lsb0> = &is_prime[10..20];
+    writeln!(
+        out,
+        "\nPrimes in 10..20: {} (slice {})",
+        window.count_ones(),
+        window
+    )?;
+
+    // Pushing grows it like a Vec<bool>
+    is_prime.push(false);
+    writeln!(
+        out,
+        "After push: len {}, capacity {} bits",
+        is_prime.len(),
+        is_prime.capacity()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the hand-rolled WordBitSet.
+///
+/// Set, test and clear are one shift and one bitwise op each:
+///   set:   word |= 1 << bit
+///   clear: word &= !(1 << bit)
+///   test:  word & (1 << bit) != 0
+pub fn word_bitset_examples(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Hand-rolled u64 Bitset")?;
+
+    let mut online: WordBitSet = WordBitSet::new(200);
+    for user in [3, 64, 65, 130, 199] {
+        online.set(user);
+    }
+    writeln!(
+        out,
+        "Online users: {:?}",
+        online.iter_ones().collect::<Vec<_>>()
+    )?;
+    writeln!(
+        out,
+        "test(64): {}, test(66): {}",
+        online.test(64),
+        online.test(66)
+    )?;
+
+    online.clear(64);
+    writeln!(out, "After clear(64): test(64) = {}", online.test(64))?;
+    writeln!(out, "count_ones(): {}", online.count_ones())?;
+
+    // Bits 0..200 need 4 words; word 1 holds bits 64..128
+    writeln!(
+        out,
+        "\n{} bits stored in {} u64 words",
+        online.len(),
+        online.words.len()
+    )?;
+    writeln!(out, "Word 1 in binary (bit 65 set): {:#b}", online.words[1])?;
+
+    Ok(())
+}
+
+/// Demonstrates rank and select.
+///
+/// - rank(i): how many set bits come before position i
+/// - select(k): where the k-th set bit is
+///
+/// They turn a bitset into a compact index: with a bitset marking which of
+/// 1M ids are present, rank(id) is that id's position in a dense array of
+/// only the present ones. Here both are computed with popcount per word;
+/// succinct data structures add a small block index to make them O(1).
+pub fn rank_and_select(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Rank and Select")?;
+
+    let mut present: WordBitSet = WordBitSet::new(300);
+    for id in [2, 7, 63, 64, 100, 101, 250] {
+        present.set(id);
+    }
+    writeln!(
+        out,
+        "Present ids: {:?}",
+        present.iter_ones().collect::<Vec<_>>()
+    )?;
+
+    for i in [0, 8, 64, 65, 300] {
+        writeln!(out, "  rank({:>3}) = {}", i, present.rank(i))?;
+    }
+    for k in [0, 3, 6, 7] {
+        writeln!(out, "  select({}) = {:?}", k, present.select(k))?;
+    }
+
+    // rank maps sparse ids onto a dense array of just the present ones
+    let names: [&str; 7] = ["b", "g", "x", "y", "p", "q", "z"];
+    let id: usize = 100;
+    writeln!(
+        out,
+        "\nDense lookup: id {} -> names[rank({})] = {:?}",
+        id,
+        id,
+        names[present.rank(id)]
+    )?;
+
+    // The same questions with bitvec
+    let bits: BitVec<u64, Lsb0> = (0..300).map(|i| present.test(i)).collect();
+    writeln!(
+        out,
+        "bitvec: bits[..65].count_ones() = {}, iter_ones().nth(3) = {:?}",
+        bits[..65].count_ones(),
+        bits.iter_ones().nth(3)
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates set operations as bitwise operations.
+///
+/// Each u64 AND/OR handles 64 elements at once, so intersecting two sets of
+/// 1M possible members takes ~16K operations - regardless of how many
+/// members the sets actually have.
+pub fn set_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Set Operations")?;
+
+    let mut rust: WordBitSet = WordBitSet::new(10);
+    let mut go: WordBitSet = WordBitSet::new(10);
+    for dev in [0, 1, 2, 5, 8] {
+        rust.set(dev);
+    }
+    for dev in [1, 2, 3, 8, 9] {
+        go.set(dev);
+    }
+    writeln!(
+        out,
+        "Knows Rust: {:?}",
+        rust.iter_ones().collect::<Vec<_>>()
+    )?;
+    writeln!(out, "Knows Go:   {:?}", go.iter_ones().collect::<Vec<_>>())?;
+
+    let mut either: WordBitSet = rust.clone();
+    either.union_with(&go);
+    let mut both: WordBitSet = rust.clone();
+    both.intersect_with(&go);
+    let mut only_rust: WordBitSet = rust.clone();
+    only_rust.difference_with(&go);
+    writeln!(
+        out,
+        "\nUnion (a | b):        {:?}",
+        either.iter_ones().collect::<Vec<_>>()
+    )?;
+    writeln!(
+        out,
+        "Intersection (a & b): {:?}",
+        both.iter_ones().collect::<Vec<_>>()
+    )?;
+    writeln!(
+        out,
+        "Difference (a & !b):  {:?}",
+        only_rust.iter_ones().collect::<Vec<_>>()
+    )?;
+
+    // bitvec overloads the operators directly
+    let a: BitVec<u64, Lsb0> = (0..10).map(|i| rust.test(i)).collect();
+    let b: BitVec<u64, Lsb0> = (0..10).map(|i| go.test(i)).collect();
+    let xor: BitVec<u64, Lsb0> = a.clone() ^ b.clone();
+    writeln!(
+        out,
+        "\nbitvec a ^ b (exactly one): {:?}",
+        xor.iter_ones().collect::<Vec<_>>()
+    )?;
+    writeln!(out, "bitvec (a & b).count_ones(): {}", (a & b).count_ones())?;
+
+    Ok(())
+}
+
+/// Demonstrates the memory used by each representation.
+pub fn memory_footprint(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Memory Footprint (1,000,000 flags)")?;
+
+    let n: usize = 1_000_000;
+    let flags: Vec<bool> = vec![false; n];
+    let bits: BitVec<u64, Lsb0> = bitvec![u64, Lsb0; 0; n];
+    let words: WordBitSet = WordBitSet::new(n);
+
+    writeln!(
+        out,
+        "  Vec<bool>:        {:>9} bytes",
+        flags.capacity() * size_of::<bool>()
+    )?;
+    writeln!(
+        out,
+        "  BitVec<u64>:      {:>9} bytes",
+        size_of_val(bits.as_raw_slice())
+    )?;
+    writeln!(out, "  WordBitSet:       {:>9} bytes", words.heap_bytes())?;
+
+    // A HashSet<u32> needs ~4-5 bytes per *member* plus table overhead, so it
+    // only beats a bitset when fewer than ~1 in 40 possible members are present.
+    writeln!(
+        out,
+        "\nBits are 8x smaller than bools - and 8x more fit in each cache line."
+    )?;
+
+    Ok(())
+}
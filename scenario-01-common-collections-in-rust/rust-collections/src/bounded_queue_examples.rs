@@ -0,0 +1,362 @@
+// A bounded queue holds at most `capacity` items. A plain VecDeque grows
+// whenever a producer outpaces its consumer, so a slow consumer becomes
+// unbounded memory. Capping the queue forces a decision about what happens
+// to the item that doesn't fit, and that decision is the overflow policy:
+//
+//   full queue [a b c], push(d)
+//
+//   Reject           [a b c]    d comes back as an error and is dropped:
+//                               load shedding, the newest work is lost
+//   OverwriteOldest  [b c d]    a is evicted and returned: the queue keeps
+//                               the most recent items (telemetry, logs)
+//   Block            [a b c]    d comes back as WouldBlock: the producer
+//                               keeps it and must wait - backpressure
+//
+// A threaded queue would put the producer to sleep on Block. This one is
+// single-threaded, so Block is a sentinel: the push fails like Reject, but
+// the error says "try again later" and nothing counts as lost.
+//
+// BoundedQueue allocates its VecDeque at full capacity once and never grows
+// past it.
+
+use demo_framework::section;
+use std::collections::VecDeque;
+use std::collections::vec_deque::Iter;
+use std::fmt;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "bounded_queue_basics",
+        "A VecDeque with a hard capacity",
+        bounded_queue_basics,
+    )?;
+
+    section(
+        out,
+        "overflow_policies",
+        "Reject, OverwriteOldest and Block on the same input",
+        overflow_policies,
+    )?;
+
+    section(
+        out,
+        "practical_backpressure",
+        "Practical demo: a bursty producer and a rate-limited consumer",
+        practical_backpressure,
+    )?;
+
+    Ok(())
+}
+
+/// What a full [`BoundedQueue`] does with one more item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Refuse the new item; it is dropped.
+    Reject,
+    /// Evict the oldest item to make room.
+    OverwriteOldest,
+    /// Refuse the new item and tell the producer to retry later.
+    Block,
+}
+
+/// A successful push.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pushed<T> {
+    /// There was room.
+    Accepted,
+    /// The queue was full and this oldest item was evicted for the new one.
+    Overwrote(T),
+}
+
+/// A push that didn't fit. Both variants hand the item back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushError<T> {
+    /// Full under [`OverflowPolicy::Reject`].
+    Rejected(T),
+    /// Full under [`OverflowPolicy::Block`]: retry once items are popped.
+    WouldBlock(T),
+}
+
+impl<T> PushError<T> {
+    /// The item that didn't fit.
+    pub fn into_inner(self) -> T {
+        match self {
+            PushError::Rejected(item) | PushError::WouldBlock(item) => item,
+        }
+    }
+}
+
+impl<T> fmt::Display for PushError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PushError::Rejected(_) => write!(f, "queue is full, item rejected"),
+            PushError::WouldBlock(_) => write!(f, "queue is full, retry after a pop"),
+        }
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for PushError<T> {}
+
+/// What a [`BoundedQueue`] has done with the items pushed to it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Pushes that found room.
+    pub accepted: usize,
+    /// Oldest items evicted by [`OverflowPolicy::OverwriteOldest`].
+    pub overwritten: usize,
+    /// Pushes refused by [`OverflowPolicy::Reject`].
+    pub rejected: usize,
+    /// Pushes refused by [`OverflowPolicy::Block`].
+    pub blocked: usize,
+}
+
+impl fmt::Display for QueueStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "accepted {}, overwritten {}, rejected {}, blocked {}",
+            self.accepted, self.overwritten, self.rejected, self.blocked
+        )
+    }
+}
+
+/// A FIFO queue of at most `capacity` items, built on VecDeque.
+#[derive(Debug, Clone)]
+pub struct BoundedQueue<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    stats: QueueStats,
+}
+
+impl<T> BoundedQueue<T> {
+    /// An empty queue with room for `capacity` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        assert!(capacity > 0, "a bounded queue needs room for one item");
+        BoundedQueue {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+            policy,
+            stats: QueueStats::default(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() == self.capacity
+    }
+
+    /// Pushes that fit before the policy kicks in.
+    pub fn remaining(&self) -> usize {
+        self.capacity - self.items.len()
+    }
+
+    /// Adds `item` at the back, applying the overflow policy if full.
+    pub fn push(&mut self, item: T) -> Result<Pushed<T>, PushError<T>> {
+        if !self.is_full() {
+            self.items.push_back(item);
+            self.stats.accepted += 1;
+            return Ok(Pushed::Accepted);
+        }
+        match self.policy {
+            OverflowPolicy::Reject => {
+                self.stats.rejected += 1;
+                Err(PushError::Rejected(item))
+            }
+            OverflowPolicy::Block => {
+                self.stats.blocked += 1;
+                Err(PushError::WouldBlock(item))
+            }
+            OverflowPolicy::OverwriteOldest => {
+                let oldest: T = self.items.pop_front().expect("a full queue has a front");
+                self.items.push_back(item);
+                self.stats.accepted += 1;
+                self.stats.overwritten += 1;
+                Ok(Pushed::Overwrote(oldest))
+            }
+        }
+    }
+
+    /// Removes the oldest item.
+    pub fn pop(&mut self) -> Option<T> {
+        self.items.pop_front()
+    }
+
+    /// The oldest item, which `pop` would return.
+    pub fn peek(&self) -> Option<&T> {
+        self.items.front()
+    }
+
+    /// Items from oldest to newest.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        self.stats
+    }
+}
+
+/// Demonstrates the queue's capacity limit.
+pub fn bounded_queue_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Bounded Queue Basics")?;
+
+    let mut queue: BoundedQueue<&str> = BoundedQueue::new(3, OverflowPolicy::Reject);
+    for job in ["resize", "encode", "upload"] {
+        queue.push(job).expect("there is room");
+    }
+    writeln!(
+        out,
+        "Queued {:?}: len {}, full: {}",
+        queue.iter().collect::<Vec<_>>(),
+        queue.len(),
+        queue.is_full()
+    )?;
+
+    // The fourth push hands the item back instead of growing the deque
+    match queue.push("notify") {
+        Ok(_) => writeln!(out, "notify queued")?,
+        Err(error) => {
+            let message: String = error.to_string();
+            writeln!(
+                out,
+                "push(\"notify\"): {} -> got back {:?}",
+                message,
+                error.into_inner()
+            )?
+        }
+    }
+
+    writeln!(out, "peek(): {:?}", queue.peek())?;
+    writeln!(out, "pop():  {:?}", queue.pop())?;
+    writeln!(out, "Room for {} more", queue.remaining())?;
+    writeln!(out, "push(\"notify\") now: {:?}", queue.push("notify"))?;
+    writeln!(out, "Stats: {}", queue.stats())?;
+
+    Ok(())
+}
+
+/// Demonstrates the three policies on the same burst.
+pub fn overflow_policies(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Overflow Policies")?;
+    writeln!(out, "Pushing 1..=6 into a queue of capacity 3:")?;
+
+    for policy in [
+        OverflowPolicy::Reject,
+        OverflowPolicy::OverwriteOldest,
+        OverflowPolicy::Block,
+    ] {
+        let mut queue: BoundedQueue<u32> = BoundedQueue::new(3, policy);
+        let mut outcomes: Vec<String> = Vec::new();
+        for item in 1..=6 {
+            outcomes.push(match queue.push(item) {
+                Ok(Pushed::Accepted) => "ok".to_string(),
+                Ok(Pushed::Overwrote(old)) => format!("evicted {}", old),
+                Err(PushError::Rejected(item)) => format!("dropped {}", item),
+                Err(PushError::WouldBlock(item)) => format!("hold {}", item),
+            });
+        }
+        writeln!(out, "  {:?}:", policy)?;
+        writeln!(out, "    outcomes: {}", outcomes.join(", "))?;
+        writeln!(out, "    queue:    {:?}", queue.iter().collect::<Vec<_>>())?;
+        writeln!(out, "    stats:    {}", queue.stats())?;
+    }
+
+    // Reject keeps the oldest work, OverwriteOldest the newest. Block keeps
+    // the oldest too, but leaves 4, 5 and 6 with the producer instead of
+    // losing them
+
+    Ok(())
+}
+
+/// Practical example: bursts of requests against a consumer that handles
+/// two per tick.
+///
+/// The producer sends 6 requests every third tick, an average of 2 per tick,
+/// so the consumer keeps up overall but not during a burst. Under Reject
+/// the overflow of each burst is lost. Under Block the producer holds its
+/// overflow in a backlog and retries it first on the next tick; nothing is
+/// lost, and the waiting shows up on the producer's side instead of in the
+/// queue's memory.
+pub fn practical_backpressure(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Backpressure")?;
+
+    let ticks: u32 = 12;
+    let per_tick: usize = 2;
+    for policy in [OverflowPolicy::Reject, OverflowPolicy::Block] {
+        let mut queue: BoundedQueue<u32> = BoundedQueue::new(4, policy);
+        let mut backlog: VecDeque<u32> = VecDeque::new();
+        let mut next_id: u32 = 0;
+        let mut served: usize = 0;
+        let mut max_backlog: usize = 0;
+
+        writeln!(out, "  {:?}:", policy)?;
+        for tick in 0..ticks {
+            if tick % 3 == 0 {
+                for _ in 0..6 {
+                    backlog.push_back(next_id);
+                    next_id += 1;
+                }
+            }
+            // Offer the backlog in order; stop at the first WouldBlock so
+            // requests keep their order
+            while let Some(request) = backlog.pop_front() {
+                if let Err(PushError::WouldBlock(request)) = queue.push(request) {
+                    backlog.push_front(request);
+                    break;
+                }
+            }
+            max_backlog = max_backlog.max(backlog.len());
+
+            let mut handled: Vec<u32> = Vec::new();
+            for _ in 0..per_tick {
+                if let Some(request) = queue.pop() {
+                    handled.push(request);
+                }
+            }
+            served += handled.len();
+            if tick < 6 {
+                writeln!(
+                    out,
+                    "    tick {:>2}: served {:?}, queued {}, waiting at producer {}",
+                    tick,
+                    handled,
+                    queue.len(),
+                    backlog.len()
+                )?;
+            }
+        }
+        writeln!(
+            out,
+            "    after {} ticks: {} of {} served, {}, peak producer backlog {}",
+            ticks,
+            served,
+            next_id,
+            queue.stats(),
+            max_backlog
+        )?;
+    }
+
+    Ok(())
+}
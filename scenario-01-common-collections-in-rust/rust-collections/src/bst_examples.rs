@@ -0,0 +1,1125 @@
+// A binary search tree keeps one key per node: everything in the left
+// subtree is smaller, everything in the right subtree is larger. A lookup
+// compares against one node per level, so its cost is the tree's height.
+//
+//   50                       in-order (left, node, right): 20 30 40 50 60 70
+//   ├── 30
+//   │   ├── 20
+//   │   └── 40
+//   └── 70
+//       ├── 60
+//       └── -
+//
+// The unbalanced tree (Bst) takes whatever shape the insert order gives it.
+// Shuffled keys give about 2 ln n levels, but sorted keys give a chain n
+// levels deep: a linked list with extra steps. An AVL tree stores each
+// node's height and rotates after every insert and remove so the two
+// subtrees of any node differ in height by at most one. That caps the
+// height at about 1.44 log2 n whatever the order.
+//
+// Both trees link nodes with Option<Box<Node>>: one allocation per entry,
+// owned by its parent. The in-order iterator keeps an explicit stack of the
+// nodes it still has to visit instead of recursing, so it can hand out one
+// entry per next() call. std's BTreeMap puts up to 11 entries in each node
+// instead: a shorter tree, fewer allocations, and comparisons that stay
+// inside one node's arrays.
+
+use crate::btree_visual::shuffled;
+use demo_framework::ops::{Op, OpTarget, Outcome};
+use demo_framework::section;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::mem;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "bst_insert_and_remove",
+        "An unbalanced BST: inserts, and the three removal cases",
+        bst_insert_and_remove,
+    )?;
+
+    section(
+        out,
+        "sorted_input_degenerates",
+        "Sorted inserts turn a BST into a list; an AVL tree stays log n",
+        sorted_input_degenerates,
+    )?;
+
+    section(
+        out,
+        "avl_rotations",
+        "AVL rotations, one insert at a time",
+        avl_rotations,
+    )?;
+
+    section(
+        out,
+        "in_order_iterator",
+        "In-order iteration with an explicit stack",
+        in_order_iterator,
+    )?;
+
+    section(
+        out,
+        "size_vs_btreemap",
+        "One entry per Box vs BTreeMap's 11 per node",
+        size_vs_btreemap,
+    )?;
+
+    section(
+        out,
+        "practical_order_book",
+        "Practical demo: price levels of an order book in an AVL tree",
+        practical_order_book,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Nodes, shared by both trees
+// ---------------------------------------------------------------------------
+
+/// One entry and its two subtrees.
+#[derive(Debug)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    left: Link<K, V>,
+    right: Link<K, V>,
+    /// Levels in this subtree, 1 for a leaf. Only [`AvlTree`] keeps this
+    /// up to date; [`Bst`] measures its height by walking.
+    height: u8,
+}
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+impl<K, V> Node<K, V> {
+    fn leaf(key: K, value: V) -> Box<Self> {
+        Box::new(Node {
+            key,
+            value,
+            left: None,
+            right: None,
+            height: 1,
+        })
+    }
+}
+
+/// The value stored under `key` and the number of nodes compared on the
+/// way, hit or miss.
+fn find<'a, K: Ord, V>(root: &'a Link<K, V>, key: &K) -> (Option<&'a V>, usize) {
+    let mut link: &Link<K, V> = root;
+    let mut compared: usize = 0;
+    while let Some(node) = link {
+        compared += 1;
+        link = match key.cmp(&node.key) {
+            Ordering::Less => &node.left,
+            Ordering::Greater => &node.right,
+            Ordering::Equal => return (Some(&node.value), compared),
+        };
+    }
+    (None, compared)
+}
+
+/// Levels below `root`, found with a stack so a degenerate chain can't
+/// overflow the call stack.
+fn walk_height<K, V>(root: &Link<K, V>) -> usize {
+    let mut stack: Vec<(&Node<K, V>, usize)> = root.iter().map(|node| (&**node, 1)).collect();
+    let mut height: usize = 0;
+    while let Some((node, depth)) = stack.pop() {
+        height = height.max(depth);
+        for child in [&node.left, &node.right].into_iter().flatten() {
+            stack.push((child, depth + 1));
+        }
+    }
+    height
+}
+
+/// Checks that an in-order walk is strictly increasing and has `len`
+/// entries, which holds exactly when every node sits between its
+/// ancestors' keys. The walk keeps its own stack and count instead of
+/// using [`Iter`], whose count starts from `len`, so a tree holding more
+/// nodes than `len` says is reported rather than underflowing.
+fn check_order<K: Ord + std::fmt::Debug, V>(root: &Link<K, V>, len: usize) -> Result<(), String> {
+    let mut stack: Vec<&Node<K, V>> = Vec::new();
+    let mut link: &Link<K, V> = root;
+    let mut previous: Option<&K> = None;
+    let mut seen: usize = 0;
+    loop {
+        while let Some(node) = link {
+            stack.push(node);
+            link = &node.left;
+        }
+        let Some(node) = stack.pop() else {
+            break;
+        };
+        if let Some(previous) = previous
+            && previous >= &node.key
+        {
+            return Err(format!(
+                "{:?} comes after {:?} in order",
+                node.key, previous
+            ));
+        }
+        previous = Some(&node.key);
+        seen += 1;
+        link = &node.right;
+    }
+    if seen != len {
+        return Err(format!("len is {} but the tree holds {}", len, seen));
+    }
+    Ok(())
+}
+
+/// An outline of the tree, left child first. A node with one child shows
+/// the missing one as `-`. Recursive: meant for small trees.
+fn render<K: Display, V>(root: &Link<K, V>) -> String {
+    fn walk<K: Display, V>(node: &Node<K, V>, prefix: &str, lines: &mut Vec<String>) {
+        if node.left.is_none() && node.right.is_none() {
+            return;
+        }
+        for (child, last) in [(&node.left, false), (&node.right, true)] {
+            let (branch, indent) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            match child {
+                Some(child) => {
+                    lines.push(format!("{}{}{}", prefix, branch, child.key));
+                    walk(child, &format!("{}{}", prefix, indent), lines);
+                }
+                None => lines.push(format!("{}{}-", prefix, branch)),
+            }
+        }
+    }
+    let Some(root) = root else {
+        return "(empty)".to_string();
+    };
+    let mut lines: Vec<String> = vec![root.key.to_string()];
+    walk(root, "", &mut lines);
+    lines.join("\n")
+}
+
+// ---------------------------------------------------------------------------
+// In-order iterator
+// ---------------------------------------------------------------------------
+
+/// Entries in ascending key order. The stack holds the nodes whose left
+/// subtree is being visited, so it never grows past the tree's height.
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn new(root: &'a Link<K, V>, len: usize) -> Self {
+        let mut iter: Iter<'a, K, V> = Iter {
+            stack: Vec::new(),
+            remaining: len,
+        };
+        iter.push_left_spine(root);
+        iter
+    }
+
+    /// Pushes `link` and its chain of left children: the next entry is the
+    /// last one pushed.
+    fn push_left_spine(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+
+    /// Nodes waiting on the stack right now.
+    pub fn stack_depth(&self) -> usize {
+        self.stack.len()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node: &'a Node<K, V> = self.stack.pop()?;
+        self.push_left_spine(&node.right);
+        self.remaining -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Unbalanced BST
+// ---------------------------------------------------------------------------
+
+/// A binary search tree that never rebalances. Inserts, lookups and
+/// removes walk down iteratively, so even a chain of sorted keys works,
+/// just slowly.
+#[derive(Debug)]
+pub struct Bst<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+impl<K: Ord, V> Default for Bst<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> Bst<K, V> {
+    pub fn new() -> Self {
+        Bst { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds `key`, or replaces its value and returns the old one.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut link: &mut Link<K, V> = &mut self.root;
+        while let Some(node) = link {
+            link = match key.cmp(&node.key) {
+                Ordering::Less => &mut node.left,
+                Ordering::Greater => &mut node.right,
+                Ordering::Equal => return Some(mem::replace(&mut node.value, value)),
+            };
+        }
+        *link = Some(Node::leaf(key, value));
+        self.len += 1;
+        None
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        find(&self.root, key).0
+    }
+
+    /// Nodes a lookup of `key` compares against.
+    pub fn comparisons(&self, key: &K) -> usize {
+        find(&self.root, key).1
+    }
+
+    /// Removes `key`. A leaf just goes, a node with one child is replaced
+    /// by that child, and a node with two children is replaced by its
+    /// successor: the smallest key of its right subtree.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let mut link: &mut Link<K, V> = &mut self.root;
+        // Compare first and borrow the child after: holding the node across
+        // the break would keep `link` borrowed
+        loop {
+            let node: &mut Box<Node<K, V>> = match link.as_ref().map(|node| key.cmp(&node.key)) {
+                None => return None,
+                Some(Ordering::Equal) => break,
+                Some(_) => link.as_mut().unwrap(),
+            };
+            link = if *key < node.key {
+                &mut node.left
+            } else {
+                &mut node.right
+            };
+        }
+        let mut node: Box<Node<K, V>> = link.take().expect("the loop stopped on a node");
+        *link = match (node.left.take(), node.right.take()) {
+            (None, None) => None,
+            (Some(child), None) | (None, Some(child)) => Some(child),
+            (Some(left), Some(right)) => {
+                let mut right: Link<K, V> = Some(right);
+                let mut successor: Box<Node<K, V>> = take_min(&mut right);
+                successor.left = Some(left);
+                successor.right = right;
+                Some(successor)
+            }
+        };
+        self.len -= 1;
+        Some(node.value)
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter::new(&self.root, self.len)
+    }
+
+    /// Levels from the root to the deepest leaf; 0 when empty.
+    pub fn height(&self) -> usize {
+        walk_height(&self.root)
+    }
+
+    /// Checks the ordering invariant and the length.
+    pub fn check(&self) -> Result<(), String>
+    where
+        K: std::fmt::Debug,
+    {
+        check_order(&self.root, self.len)
+    }
+
+    pub fn render(&self) -> String
+    where
+        K: Display,
+    {
+        render(&self.root)
+    }
+}
+
+/// Unlinks the smallest node of a non-empty subtree, putting its right
+/// child in its place.
+fn take_min<K, V>(mut link: &mut Link<K, V>) -> Box<Node<K, V>> {
+    while link.as_ref().is_some_and(|node| node.left.is_some()) {
+        link = &mut link.as_mut().unwrap().left;
+    }
+    let mut min: Box<Node<K, V>> = link.take().expect("the subtree isn't empty");
+    *link = min.right.take();
+    min
+}
+
+/// The derived drop would recurse once per level, and a tree built from
+/// sorted keys has as many levels as entries. Unlinking nodes onto a heap
+/// stack frees them without recursing.
+impl<K, V> Drop for Bst<K, V> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<K, V>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// AVL tree
+// ---------------------------------------------------------------------------
+
+/// A BST that rebalances on the way back up from every insert and remove,
+/// keeping the heights of any node's two subtrees within one of each
+/// other. The height stays under 1.44 log2(n + 2), so the recursion is
+/// shallow.
+#[derive(Debug)]
+pub struct AvlTree<K, V> {
+    root: Link<K, V>,
+    len: usize,
+    rotations: usize,
+}
+
+impl<K: Ord, V> Default for AvlTree<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> AvlTree<K, V> {
+    pub fn new() -> Self {
+        AvlTree {
+            root: None,
+            len: 0,
+            rotations: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Single rotations performed so far; a double rotation counts as two.
+    pub fn rotations(&self) -> usize {
+        self.rotations
+    }
+
+    /// Adds `key`, or replaces its value and returns the old one.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let old: Option<V> = avl_insert(&mut self.root, key, value, &mut self.rotations);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        find(&self.root, key).0
+    }
+
+    /// Nodes a lookup of `key` compares against.
+    pub fn comparisons(&self, key: &K) -> usize {
+        find(&self.root, key).1
+    }
+
+    /// Removes `key` the same way [`Bst::remove`] does, then rebalances
+    /// every node on the path back to the root.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let removed: Option<V> = avl_remove(&mut self.root, key, &mut self.rotations);
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter::new(&self.root, self.len)
+    }
+
+    /// Levels from the root to the deepest leaf; 0 when empty.
+    pub fn height(&self) -> usize {
+        height(&self.root) as usize
+    }
+
+    /// Checks the ordering invariant, the length, every stored height and
+    /// every balance factor.
+    pub fn check(&self) -> Result<(), String>
+    where
+        K: std::fmt::Debug,
+    {
+        fn walk<K: std::fmt::Debug, V>(link: &Link<K, V>) -> Result<u8, String> {
+            let Some(node) = link else {
+                return Ok(0);
+            };
+            let (left, right) = (walk(&node.left)?, walk(&node.right)?);
+            if left.abs_diff(right) > 1 {
+                return Err(format!(
+                    "{:?} is out of balance: {} vs {}",
+                    node.key, left, right
+                ));
+            }
+            let expected: u8 = 1 + left.max(right);
+            if node.height != expected {
+                return Err(format!(
+                    "{:?} stores height {} but has {}",
+                    node.key, node.height, expected
+                ));
+            }
+            Ok(expected)
+        }
+        check_order(&self.root, self.len)?;
+        walk(&self.root).map(|_| ())
+    }
+
+    pub fn render(&self) -> String
+    where
+        K: Display,
+    {
+        render(&self.root)
+    }
+}
+
+fn height<K, V>(link: &Link<K, V>) -> u8 {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+/// Left height minus right height.
+fn balance<K, V>(link: &Link<K, V>) -> i16 {
+    link.as_ref().map_or(0, |node| {
+        i16::from(height(&node.left)) - i16::from(height(&node.right))
+    })
+}
+
+fn update_height<K, V>(node: &mut Node<K, V>) {
+    node.height = 1 + height(&node.left).max(height(&node.right));
+}
+
+/// Lifts the left child above its parent:
+///
+/// ```text
+///       y            x
+///      / \          / \
+///     x   C   ->   A   y
+///    / \              / \
+///   A   B            B   C
+/// ```
+fn rotate_right<K, V>(link: &mut Link<K, V>) {
+    let mut y: Box<Node<K, V>> = link.take().expect("rotating a node");
+    let mut x: Box<Node<K, V>> = y.left.take().expect("rotating right needs a left child");
+    y.left = x.right.take();
+    update_height(&mut y);
+    x.right = Some(y);
+    update_height(&mut x);
+    *link = Some(x);
+}
+
+/// The mirror image of [`rotate_right`].
+fn rotate_left<K, V>(link: &mut Link<K, V>) {
+    let mut x: Box<Node<K, V>> = link.take().expect("rotating a node");
+    let mut y: Box<Node<K, V>> = x.right.take().expect("rotating left needs a right child");
+    x.right = y.left.take();
+    update_height(&mut x);
+    y.left = Some(x);
+    update_height(&mut y);
+    *link = Some(y);
+}
+
+/// Refreshes the height of the node at `link` and, if its subtrees now
+/// differ by two, rotates it back into balance. A child leaning the other
+/// way gets rotated first (the double rotation).
+fn rebalance<K, V>(link: &mut Link<K, V>, rotations: &mut usize) {
+    let Some(node) = link.as_mut() else {
+        return;
+    };
+    update_height(node);
+    match balance(link) {
+        2.. => {
+            let node: &mut Node<K, V> = link.as_mut().unwrap();
+            if balance(&node.left) < 0 {
+                rotate_left(&mut node.left);
+                *rotations += 1;
+            }
+            rotate_right(link);
+            *rotations += 1;
+        }
+        ..=-2 => {
+            let node: &mut Node<K, V> = link.as_mut().unwrap();
+            if balance(&node.right) > 0 {
+                rotate_right(&mut node.right);
+                *rotations += 1;
+            }
+            rotate_left(link);
+            *rotations += 1;
+        }
+        _ => {}
+    }
+}
+
+fn avl_insert<K: Ord, V>(
+    link: &mut Link<K, V>,
+    key: K,
+    value: V,
+    rotations: &mut usize,
+) -> Option<V> {
+    let Some(node) = link else {
+        *link = Some(Node::leaf(key, value));
+        return None;
+    };
+    let old: Option<V> = match key.cmp(&node.key) {
+        Ordering::Less => avl_insert(&mut node.left, key, value, rotations),
+        Ordering::Greater => avl_insert(&mut node.right, key, value, rotations),
+        Ordering::Equal => return Some(mem::replace(&mut node.value, value)),
+    };
+    if old.is_none() {
+        rebalance(link, rotations);
+    }
+    old
+}
+
+fn avl_remove<K: Ord, V>(link: &mut Link<K, V>, key: &K, rotations: &mut usize) -> Option<V> {
+    let node: &mut Box<Node<K, V>> = link.as_mut()?;
+    let removed: Option<V> = match key.cmp(&node.key) {
+        Ordering::Less => avl_remove(&mut node.left, key, rotations),
+        Ordering::Greater => avl_remove(&mut node.right, key, rotations),
+        Ordering::Equal => {
+            let mut node: Box<Node<K, V>> = link.take().expect("matched a node");
+            *link = match (node.left.take(), node.right.take()) {
+                (None, None) => None,
+                (Some(child), None) | (None, Some(child)) => Some(child),
+                (Some(left), Some(right)) => {
+                    let mut right: Link<K, V> = Some(right);
+                    let mut successor: Box<Node<K, V>> = avl_take_min(&mut right, rotations);
+                    successor.left = Some(left);
+                    successor.right = right;
+                    Some(successor)
+                }
+            };
+            Some(node.value)
+        }
+    };
+    if removed.is_some() {
+        rebalance(link, rotations);
+    }
+    removed
+}
+
+/// [`take_min`], rebalancing the path it walked down.
+fn avl_take_min<K, V>(link: &mut Link<K, V>, rotations: &mut usize) -> Box<Node<K, V>> {
+    let node: &mut Box<Node<K, V>> = link.as_mut().expect("the subtree isn't empty");
+    if node.left.is_some() {
+        let min: Box<Node<K, V>> = avl_take_min(&mut node.left, rotations);
+        rebalance(link, rotations);
+        return min;
+    }
+    let mut min: Box<Node<K, V>> = link.take().unwrap();
+    *link = min.right.take();
+    min
+}
+
+// ---------------------------------------------------------------------------
+// Operation programs
+// ---------------------------------------------------------------------------
+
+/// Runs [`demo_framework::ops`] programs on a tree, so the property tests
+/// can check it against the `BTreeMap` reference.
+macro_rules! op_target {
+    ($tree:ident) => {
+        impl OpTarget for $tree<u32, u32> {
+            fn apply(&mut self, op: Op) -> Outcome {
+                match op {
+                    Op::Insert(key, value) => Outcome::Value(self.insert(key, value)),
+                    Op::Remove(key) => Outcome::Value(self.remove(&key)),
+                    Op::Get(key) => Outcome::Value(self.get(&key).copied()),
+                    Op::Bump(key) => {
+                        let count: u32 = self.get(&key).copied().unwrap_or(0) + 1;
+                        self.insert(key, count);
+                        Outcome::Value(Some(count))
+                    }
+                    // The in-order walk is already sorted
+                    Op::RangeScan(start, end) => Outcome::Entries(
+                        self.iter()
+                            .skip_while(|&(&key, _)| key < start)
+                            .take_while(|&(&key, _)| key < end)
+                            .map(|(&key, &value)| (key, value))
+                            .collect(),
+                    ),
+                    Op::Retain(modulus) => {
+                        let dropped: Vec<u32> = self
+                            .iter()
+                            .filter(|&(_, value)| value % modulus == 0)
+                            .map(|(&key, _)| key)
+                            .collect();
+                        for key in dropped {
+                            self.remove(&key);
+                        }
+                        Outcome::Len(self.len())
+                    }
+                    // One allocation per entry: there is no spare capacity
+                    Op::ShrinkToFit => Outcome::Len(self.len()),
+                    Op::Clear => {
+                        *self = $tree::new();
+                        Outcome::Cleared
+                    }
+                }
+            }
+
+            fn len(&self) -> usize {
+                $tree::len(self)
+            }
+        }
+    };
+}
+
+op_target!(Bst);
+op_target!(AvlTree);
+
+// ---------------------------------------------------------------------------
+// Sizes
+// ---------------------------------------------------------------------------
+
+/// Bytes of one tree node holding a `K -> V` entry.
+pub fn tree_node_bytes<K, V>() -> usize {
+    size_of::<Node<K, V>>()
+}
+
+/// Bytes of std's BTreeMap leaf and internal nodes for `K -> V`, from its
+/// layout: a parent pointer, a u16 index and length, then 11 keys and 11
+/// values; internal nodes add 12 child pointers. std keeps the nodes
+/// private, so this mirrors the source rather than measuring.
+pub fn btreemap_node_bytes<K, V>() -> (usize, usize) {
+    const CAPACITY: usize = 11;
+    let align: usize = align_of::<usize>()
+        .max(align_of::<K>())
+        .max(align_of::<V>());
+    let header: usize = size_of::<usize>() + 2 * size_of::<u16>();
+    let leaf: usize = (header.next_multiple_of(align_of::<K>()) + CAPACITY * size_of::<K>())
+        .next_multiple_of(align_of::<V>())
+        + CAPACITY * size_of::<V>();
+    let leaf: usize = leaf.next_multiple_of(align);
+    (leaf, leaf + (CAPACITY + 1) * size_of::<usize>())
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+fn write_tree(out: &mut dyn Write, rendered: &str) -> io::Result<()> {
+    for line in rendered.lines() {
+        writeln!(out, "    {}", line)?;
+    }
+    Ok(())
+}
+
+/// Demonstrates inserts, lookups and the leaf, one-child and two-children
+/// removals in an unbalanced BST.
+pub fn bst_insert_and_remove(out: &mut dyn Write) -> io::Result<()> {
+    let mut tree: Bst<u32, &str> = Bst::new();
+    let keys: [u32; 10] = [50, 30, 70, 20, 40, 60, 80, 35, 45, 65];
+    for key in keys {
+        tree.insert(key, "v");
+    }
+    writeln!(out, "\n  Inserting {:?}:", keys)?;
+    write_tree(out, &tree.render())?;
+    writeln!(
+        out,
+        "\n  get(45): {:?} after {} comparisons, get(55): {:?} after {}",
+        tree.get(&45),
+        tree.comparisons(&45),
+        tree.get(&55),
+        tree.comparisons(&55)
+    )?;
+    writeln!(
+        out,
+        "  insert(40, \"new\") -> {:?}: same key, value replaced",
+        tree.insert(40, "new")
+    )?;
+
+    for (key, case) in [
+        (20, "a leaf: unlink it"),
+        (60, "one child: 65 takes its place"),
+        (30, "two children: its successor 35 moves up"),
+    ] {
+        let removed: Option<&str> = tree.remove(&key);
+        writeln!(out, "\n  remove({}) -> {:?}, {}", key, removed, case)?;
+        write_tree(out, &tree.render())?;
+    }
+    writeln!(
+        out,
+        "\n  remove(99) -> {:?}; {} keys left, in order: {:?}",
+        tree.remove(&99),
+        tree.len(),
+        tree.iter().map(|(key, _)| *key).collect::<Vec<u32>>()
+    )?;
+    writeln!(out, "  check(): {:?}", tree.check())?;
+
+    Ok(())
+}
+
+/// Demonstrates how insert order decides an unbalanced tree's height, and
+/// how an AVL tree makes it irrelevant.
+pub fn sorted_input_degenerates(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "\n  Height, and average comparisons per successful lookup:"
+    )?;
+    writeln!(
+        out,
+        "    {:>5}  {:<8}  {:>10}  {:>10}  {:>10}  {:>10}",
+        "n", "order", "Bst height", "Bst avg", "AVL height", "AVL avg"
+    )?;
+    for n in [100, 1_000, 5_000] {
+        for (order, keys) in [
+            ("shuffled", shuffled(n, 7)),
+            ("sorted", (0..n).collect::<Vec<u32>>()),
+        ] {
+            let mut bst: Bst<u32, ()> = Bst::new();
+            let mut avl: AvlTree<u32, ()> = AvlTree::new();
+            for &key in &keys {
+                bst.insert(key, ());
+                avl.insert(key, ());
+            }
+            let average = |comparisons: &dyn Fn(&u32) -> usize| -> f64 {
+                keys.iter().map(comparisons).sum::<usize>() as f64 / n as f64
+            };
+            writeln!(
+                out,
+                "    {:>5}  {:<8}  {:>10}  {:>10.1}  {:>10}  {:>10.1}",
+                n,
+                order,
+                bst.height(),
+                average(&|key| bst.comparisons(key)),
+                avl.height(),
+                average(&|key| avl.comparisons(key))
+            )?;
+        }
+    }
+    writeln!(
+        out,
+        "\n  Sorted keys always go right, so the BST is a chain and a lookup reads"
+    )?;
+    writeln!(
+        out,
+        "  n/2 nodes on average. log2(5000) is 12.3: the AVL tree stays within"
+    )?;
+    writeln!(
+        out,
+        "  1.44 times that either way. Dropping the 5000-deep chain needs Bst's"
+    )?;
+    writeln!(out, "  own Drop: the derived one recurses once per level.")?;
+
+    Ok(())
+}
+
+/// Demonstrates single and double rotations keeping an AVL tree balanced.
+pub fn avl_rotations(out: &mut dyn Write) -> io::Result<()> {
+    let mut tree: AvlTree<u32, ()> = AvlTree::new();
+    writeln!(out, "\n  Inserting 1 to 7 in order:")?;
+    for key in 1..=7 {
+        let before: usize = tree.rotations();
+        tree.insert(key, ());
+        let note: String = match tree.rotations() - before {
+            0 => "no rotation".to_string(),
+            n => format!("{} rotation{}", n, if n == 1 { "" } else { "s" }),
+        };
+        writeln!(
+            out,
+            "\n  insert({}): {}, height {}",
+            key,
+            note,
+            tree.height()
+        )?;
+        write_tree(out, &tree.render())?;
+    }
+    writeln!(
+        out,
+        "\n  Each time a right subtree got two levels taller than the left, a"
+    )?;
+    writeln!(
+        out,
+        "  left rotation lifted the middle key. 7 sorted keys: a perfect tree."
+    )?;
+
+    // A child leaning the other way needs two rotations
+    let mut tree: AvlTree<u32, ()> = AvlTree::new();
+    for key in [30, 10, 20] {
+        tree.insert(key, ());
+    }
+    writeln!(
+        out,
+        "\n  Inserting 30, 10, 20: the left child leans right, so {} rotations",
+        tree.rotations()
+    )?;
+    writeln!(out, "  (left at 10, then right at 30) put 20 on top:")?;
+    write_tree(out, &tree.render())?;
+
+    // Removals rebalance too
+    let mut tree: AvlTree<u32, ()> = AvlTree::new();
+    for key in [40, 20, 60, 10, 30, 50, 70, 5, 15, 25, 35, 45] {
+        tree.insert(key, ());
+    }
+    let before: usize = tree.rotations();
+    for key in [50, 45, 70] {
+        tree.remove(&key);
+    }
+    writeln!(
+        out,
+        "\n  Removing 50, 45 and 70 from the right side: rotations {}, height {}",
+        tree.rotations() - before,
+        tree.height()
+    )?;
+    write_tree(out, &tree.render())?;
+    writeln!(out, "  check(): {:?}", tree.check())?;
+
+    Ok(())
+}
+
+/// Demonstrates the explicit-stack iterator: sorted output, a stack no
+/// deeper than the tree, and an exact size_hint.
+pub fn in_order_iterator(out: &mut dyn Write) -> io::Result<()> {
+    let words: [&str; 10] = [
+        "pear", "apple", "fig", "kiwi", "banana", "plum", "cherry", "lime", "date", "grape",
+    ];
+    let mut tree: AvlTree<&str, usize> = AvlTree::new();
+    let mut map: BTreeMap<&str, usize> = BTreeMap::new();
+    for (i, word) in words.into_iter().enumerate() {
+        tree.insert(word, i);
+        map.insert(word, i);
+    }
+    writeln!(out, "\n  10 words, each mapped to its insert position:")?;
+    write_tree(out, &tree.render())?;
+
+    let mut iter: Iter<&str, usize> = tree.iter();
+    writeln!(
+        out,
+        "\n  A new iterator has pushed the root's left spine: stack depth {}",
+        iter.stack_depth()
+    )?;
+    writeln!(
+        out,
+        "    {:<8}  {:>5}  {:>11}  {:>9}",
+        "key", "value", "stack after", "remaining"
+    )?;
+    while let Some((key, value)) = iter.next() {
+        writeln!(
+            out,
+            "    {:<8}  {:>5}  {:>11}  {:>9}",
+            key,
+            value,
+            iter.stack_depth(),
+            iter.size_hint().0
+        )?;
+    }
+    writeln!(
+        out,
+        "\n  Same order as BTreeMap: {}",
+        tree.iter().eq(map.iter())
+    )?;
+
+    // The stack holds one path, so it is bounded by the height
+    let mut big: AvlTree<u32, ()> = AvlTree::new();
+    for key in shuffled(10_000, 3) {
+        big.insert(key, ());
+    }
+    let mut iter: Iter<u32, ()> = big.iter();
+    let mut deepest: usize = iter.stack_depth();
+    let mut count: usize = 0;
+    while iter.next().is_some() {
+        deepest = deepest.max(iter.stack_depth());
+        count += 1;
+    }
+    writeln!(
+        out,
+        "  10000 keys: {} visited, deepest stack {} for a tree of height {}",
+        count,
+        deepest,
+        big.height()
+    )?;
+    writeln!(
+        out,
+        "\n  Each node is pushed once and popped once, so a full walk is O(n)"
+    )?;
+    writeln!(
+        out,
+        "  and one next() is O(1) on average. The recursive walk can't pause"
+    )?;
+    writeln!(out, "  between entries; the stack is what lets it.")?;
+
+    Ok(())
+}
+
+/// Demonstrates what one node per entry costs next to BTreeMap's wide
+/// nodes.
+pub fn size_vs_btreemap(out: &mut dyn Write) -> io::Result<()> {
+    let node: usize = tree_node_bytes::<u64, u64>();
+    let (leaf, internal) = btreemap_node_bytes::<u64, u64>();
+    writeln!(out, "\n  u64 -> u64, 16 bytes of data per entry:")?;
+    writeln!(
+        out,
+        "    {:<22}  {:>10}  {:>12}  {:>11}",
+        "", "node bytes", "entries/node", "bytes/entry"
+    )?;
+    writeln!(
+        out,
+        "    {:<22}  {:>10}  {:>12}  {:>11}",
+        "Bst / AvlTree node", node, 1, node
+    )?;
+    writeln!(
+        out,
+        "    {:<22}  {:>10}  {:>12}  {:>11}",
+        "BTreeMap leaf",
+        leaf,
+        "5 to 11",
+        format!("{} to {}", leaf.div_ceil(11), leaf.div_ceil(5))
+    )?;
+    writeln!(
+        out,
+        "    {:<22}  {:>10}  {:>12}  {:>11}",
+        "BTreeMap internal", internal, "5 to 11", "-"
+    )?;
+
+    let n: u32 = 100_000;
+    let mut avl: AvlTree<u64, u64> = AvlTree::new();
+    for key in shuffled(n, 11) {
+        avl.insert(u64::from(key), 0);
+    }
+    let lookups: usize = (0..n).map(|key| avl.comparisons(&u64::from(key))).sum();
+    let leaves: (usize, usize) = ((n as usize).div_ceil(11), (n as usize).div_ceil(5));
+    writeln!(out, "\n  {} shuffled entries:", n)?;
+    writeln!(
+        out,
+        "    AvlTree:  {} allocations, {} KiB, height {}, {:.1} comparisons per lookup",
+        avl.len(),
+        avl.len() * node / 1024,
+        avl.height(),
+        lookups as f64 / f64::from(n)
+    )?;
+    writeln!(
+        out,
+        "    BTreeMap: {} to {} leaves, {} to {} KiB, 5 or 6 levels",
+        leaves.0,
+        leaves.1,
+        leaves.0 * leaf / 1024,
+        leaves.1 * leaf / 1024
+    )?;
+    writeln!(
+        out,
+        "\n  Internal nodes are about 1 in 6 and add little. The AVL tree pays"
+    )?;
+    writeln!(
+        out,
+        "  two pointers and a height per entry plus one allocation each, and"
+    )?;
+    writeln!(
+        out,
+        "  every comparison is a pointer hop to a node that is likely not in"
+    )?;
+    writeln!(
+        out,
+        "  cache. BTreeMap compares within one node's arrays before hopping."
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: the ask side of an order book. Each price level is
+/// a key, the quantity resting there its value; a fill that empties a
+/// level removes it, and the best ask is the first entry in order.
+pub fn practical_order_book(out: &mut dyn Write) -> io::Result<()> {
+    let mut asks: AvlTree<u32, u32> = AvlTree::new();
+    let events: [(&str, u32, u32); 9] = [
+        ("add", 10_050, 300),
+        ("add", 10_020, 100),
+        ("add", 10_080, 500),
+        ("add", 10_020, 250),
+        ("fill", 10_020, 350),
+        ("add", 10_010, 40),
+        ("fill", 10_010, 40),
+        ("fill", 10_050, 120),
+        ("add", 10_030, 75),
+    ];
+    writeln!(out, "\n  {:<16}  {:>8}  levels", "event", "best ask")?;
+    for (kind, price, quantity) in events {
+        match kind {
+            "add" => {
+                let resting: u32 = asks.get(&price).copied().unwrap_or(0);
+                asks.insert(price, resting + quantity);
+            }
+            _ => {
+                let resting: u32 = asks.get(&price).copied().unwrap_or(0);
+                if quantity >= resting {
+                    asks.remove(&price);
+                } else {
+                    asks.insert(price, resting - quantity);
+                }
+            }
+        }
+        let best: String = asks
+            .iter()
+            .next()
+            .map_or("-".to_string(), |(price, _)| price.to_string());
+        let levels: Vec<String> = asks
+            .iter()
+            .map(|(price, quantity)| format!("{}x{}", price, quantity))
+            .collect();
+        writeln!(
+            out,
+            "  {:<16}  {:>8}  {}",
+            format!("{} {} {}", kind, price, quantity),
+            best,
+            levels.join(" ")
+        )?;
+    }
+    writeln!(
+        out,
+        "\n  {} levels, height {}, {} rotations, check(): {:?}",
+        asks.len(),
+        asks.height(),
+        asks.rotations(),
+        asks.check()
+    )?;
+    writeln!(
+        out,
+        "  A real book would use BTreeMap and first_key_value(); the shape of"
+    )?;
+    writeln!(
+        out,
+        "  the code is the same, since both are ordered maps with O(log n) edits."
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,511 @@
+// BTreeMap's speed comes from its shape, but std keeps the nodes private.
+// This module builds a small B-tree of its own, with the same rules, so the
+// nodes can be printed after every insert.
+//
+// A B-tree of order m stores up to m - 1 sorted keys per node, and an inner
+// node with k keys has k + 1 children. Keys in child i fall between keys
+// i - 1 and i of the parent:
+//
+//   [20 40]                  order 3: at most 2 keys, 3 children per node
+//   ├── [10]                 every leaf is at the same depth
+//   ├── [30]
+//   └── [50 60]
+//
+// Inserting always goes into a leaf. When a node overflows to m keys it
+// splits: the middle key moves up into the parent, the halves become two
+// nodes. If the root splits, a new root appears above it, and that is the
+// only way the tree gets taller. So the height grows by one only when the
+// whole tree has filled up, which keeps it at about log_{m/2}(n) levels.
+//
+// std's BTreeMap uses B = 6: up to 11 keys and 12 children per node. A
+// million keys take about 7 levels, and each node's keys sit together in one
+// cache-friendly array.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "btree_insert_step_by_step",
+        "A 2-3 tree (order 3) growing one insert at a time",
+        btree_insert_step_by_step,
+    )?;
+
+    section(
+        out,
+        "order_four_tree",
+        "Wider nodes: the same keys in an order-4 tree",
+        order_four_tree,
+    )?;
+
+    section(
+        out,
+        "why_log_n",
+        "Why every operation is O(log n): height and search paths",
+        why_log_n,
+    )?;
+
+    section(
+        out,
+        "sorted_order_for_free",
+        "In-order traversal gives the keys sorted, like BTreeSet",
+        sorted_order_for_free,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+struct Node<K> {
+    keys: Vec<K>,
+    /// Empty for a leaf, otherwise `keys.len() + 1` subtrees.
+    children: Vec<Node<K>>,
+}
+
+/// The keys a subtree must lie strictly between; `None` is unbounded.
+type Bounds<'a, K> = (Option<&'a K>, Option<&'a K>);
+
+/// What inserting into a subtree did.
+enum Inserted<K> {
+    Duplicate,
+    Done,
+    /// The subtree's root overflowed: the key to push up into the parent and
+    /// the new right sibling.
+    Split(K, Node<K>),
+}
+
+impl<K: Ord> Node<K> {
+    fn leaf() -> Self {
+        Node {
+            keys: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    fn insert(&mut self, key: K, order: usize, splits: &mut usize) -> Inserted<K> {
+        let index: usize = match self.keys.binary_search(&key) {
+            Ok(_) => return Inserted::Duplicate,
+            Err(index) => index,
+        };
+        if self.is_leaf() {
+            self.keys.insert(index, key);
+        } else {
+            match self.children[index].insert(key, order, splits) {
+                Inserted::Split(middle, right) => {
+                    self.keys.insert(index, middle);
+                    self.children.insert(index + 1, right);
+                }
+                other => return other,
+            }
+        }
+
+        if self.keys.len() < order {
+            return Inserted::Done;
+        }
+        // Overflow: m keys. Keep the left half, push the middle key up and
+        // move the right half (and its children) to a new node
+        let middle_index: usize = self.keys.len() / 2;
+        let right_keys: Vec<K> = self.keys.split_off(middle_index + 1);
+        let middle: K = self.keys.pop().expect("an overflowing node has keys");
+        let right_children: Vec<Node<K>> = if self.is_leaf() {
+            Vec::new()
+        } else {
+            self.children.split_off(middle_index + 1)
+        };
+        *splits += 1;
+        Inserted::Split(
+            middle,
+            Node {
+                keys: right_keys,
+                children: right_children,
+            },
+        )
+    }
+}
+
+/// An instructional B-tree set of a given order, with its nodes on display.
+#[derive(Clone, Debug)]
+pub struct BTree<K> {
+    root: Node<K>,
+    order: usize,
+    len: usize,
+    splits: usize,
+}
+
+impl<K: Ord> BTree<K> {
+    /// An empty tree whose nodes hold up to `order - 1` keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order < 3`: with two children per node, a split would
+    /// leave an empty half.
+    pub fn new(order: usize) -> Self {
+        assert!(order >= 3, "a B-tree needs order 3 or more");
+        BTree {
+            root: Node::leaf(),
+            order,
+            len: 0,
+            splits: 0,
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Node splits since the tree was created, root splits included.
+    pub fn splits(&self) -> usize {
+        self.splits
+    }
+
+    /// Adds `key`. Returns `false` if it was already present.
+    pub fn insert(&mut self, key: K) -> bool {
+        match self.root.insert(key, self.order, &mut self.splits) {
+            Inserted::Duplicate => return false,
+            Inserted::Done => {}
+            Inserted::Split(middle, right) => {
+                // The root split: grow a new root above both halves
+                let left: Node<K> = std::mem::replace(&mut self.root, Node::leaf());
+                self.root = Node {
+                    keys: vec![middle],
+                    children: vec![left, right],
+                };
+            }
+        }
+        self.len += 1;
+        true
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.search_path(key).1
+    }
+
+    /// The keys of every node a search for `key` visits, root first, and
+    /// whether it was found.
+    pub fn search_path(&self, key: &K) -> (Vec<&[K]>, bool) {
+        let mut path: Vec<&[K]> = Vec::new();
+        let mut node: &Node<K> = &self.root;
+        loop {
+            path.push(&node.keys);
+            match node.keys.binary_search(key) {
+                Ok(_) => return (path, true),
+                Err(_) if node.is_leaf() => return (path, false),
+                Err(index) => node = &node.children[index],
+            }
+        }
+    }
+
+    /// Levels from the root down to the leaves; 1 for a lone root.
+    pub fn height(&self) -> usize {
+        let mut height: usize = 1;
+        let mut node: &Node<K> = &self.root;
+        while let Some(child) = node.children.first() {
+            node = child;
+            height += 1;
+        }
+        height
+    }
+
+    pub fn node_count(&self) -> usize {
+        fn count<K>(node: &Node<K>) -> usize {
+            1 + node.children.iter().map(count).sum::<usize>()
+        }
+        count(&self.root)
+    }
+
+    /// Every key, by an in-order walk.
+    pub fn keys(&self) -> Vec<&K> {
+        fn walk<'a, K>(node: &'a Node<K>, keys: &mut Vec<&'a K>) {
+            for (i, key) in node.keys.iter().enumerate() {
+                if let Some(child) = node.children.get(i) {
+                    walk(child, keys);
+                }
+                keys.push(key);
+            }
+            if let Some(last) = node.children.get(node.keys.len()) {
+                walk(last, keys);
+            }
+        }
+        let mut keys: Vec<&K> = Vec::with_capacity(self.len);
+        walk(&self.root, &mut keys);
+        keys
+    }
+
+    /// Checks the B-tree rules: key counts per node, sorted keys, child
+    /// counts, separator bounds and equal leaf depth.
+    pub fn check(&self) -> Result<(), String> {
+        let min_keys: usize = self.order.div_ceil(2) - 1;
+        let mut leaf_depth: Option<usize> = None;
+        let mut stack: Vec<(&Node<K>, usize, Bounds<K>)> = vec![(&self.root, 1, (None, None))];
+        while let Some((node, depth, (low, high))) = stack.pop() {
+            let is_root: bool = depth == 1;
+            if node.keys.len() >= self.order {
+                return Err(format!("a node at depth {} overflows", depth));
+            }
+            if !is_root && node.keys.len() < min_keys {
+                return Err(format!("a node at depth {} underflows", depth));
+            }
+            if !node.keys.is_sorted_by(|a, b| a < b) {
+                return Err(format!("a node at depth {} is out of order", depth));
+            }
+            let first: Option<&K> = node.keys.first();
+            let last: Option<&K> = node.keys.last();
+            if low.is_some_and(|low| first.is_some_and(|first| first <= low))
+                || high.is_some_and(|high| last.is_some_and(|last| last >= high))
+            {
+                return Err(format!("a node at depth {} escapes its separators", depth));
+            }
+            if node.is_leaf() {
+                if *leaf_depth.get_or_insert(depth) != depth {
+                    return Err("leaves at different depths".to_string());
+                }
+                continue;
+            }
+            if node.children.len() != node.keys.len() + 1 {
+                return Err(format!(
+                    "a node at depth {} has the wrong child count",
+                    depth
+                ));
+            }
+            for (i, child) in node.children.iter().enumerate() {
+                let child_low: Option<&K> = if i == 0 { low } else { node.keys.get(i - 1) };
+                let child_high: Option<&K> = node.keys.get(i).or(high);
+                stack.push((child, depth + 1, (child_low, child_high)));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<K: Ord + Display> BTree<K> {
+    /// The tree as an outline, one node per line, children indented under
+    /// their parent:
+    ///
+    /// ```text
+    /// [20 40]
+    /// ├── [10]
+    /// ├── [30]
+    /// └── [50 60]
+    /// ```
+    pub fn render(&self) -> String {
+        fn label<K: Display>(node: &Node<K>) -> String {
+            let keys: Vec<String> = node.keys.iter().map(|key| key.to_string()).collect();
+            format!("[{}]", keys.join(" "))
+        }
+        fn walk<K: Display>(node: &Node<K>, prefix: &str, lines: &mut Vec<String>) {
+            for (i, child) in node.children.iter().enumerate() {
+                let last: bool = i + 1 == node.children.len();
+                let (branch, indent) = if last {
+                    ("└── ", "    ")
+                } else {
+                    ("├── ", "│   ")
+                };
+                lines.push(format!("{}{}{}", prefix, branch, label(child)));
+                walk(child, &format!("{}{}", prefix, indent), lines);
+            }
+        }
+        let mut lines: Vec<String> = vec![label(&self.root)];
+        walk(&self.root, "", &mut lines);
+        lines.join("\n")
+    }
+}
+
+/// Prints `tree`'s outline indented by two spaces.
+fn write_tree<K: Ord + Display>(out: &mut dyn Write, tree: &BTree<K>) -> io::Result<()> {
+    for line in tree.render().lines() {
+        writeln!(out, "  {}", line)?;
+    }
+    Ok(())
+}
+
+/// Deterministic shuffle of `0..len` (an LCG-driven Fisher-Yates).
+pub fn shuffled(len: u32, seed: u64) -> Vec<u32> {
+    let mut keys: Vec<u32> = (0..len).collect();
+    let mut rng: Lcg = Lcg::new(seed);
+    for i in (1..keys.len()).rev() {
+        keys.swap(i, rng.below(i as u64 + 1) as usize);
+    }
+    keys
+}
+
+/// Demonstrates leaf inserts, splits and root growth in a 2-3 tree.
+pub fn btree_insert_step_by_step(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "B-Tree Insert Step by Step")?;
+
+    let mut tree: BTree<u32> = BTree::new(3);
+    writeln!(
+        out,
+        "Order 3: at most 2 keys per node, so a third key splits it"
+    )?;
+    for key in [10, 20, 30, 40, 50, 60, 70, 25, 35] {
+        let (splits, height) = (tree.splits(), tree.height());
+        tree.insert(key);
+        let note: String = match tree.splits() - splits {
+            0 => "fits in a leaf".to_string(),
+            1 => "1 split".to_string(),
+            n => format!("{} splits", n),
+        };
+        let grew: &str = if tree.height() > height {
+            ", the root split: height +1"
+        } else {
+            ""
+        };
+        writeln!(out, "\ninsert({}): {}{}", key, note, grew)?;
+        write_tree(out, &tree)?;
+    }
+
+    writeln!(
+        out,
+        "\nDuplicate insert(30) -> {}: sets keep one copy",
+        tree.insert(30)
+    )?;
+    writeln!(
+        out,
+        "{} keys in {} nodes, height {}",
+        tree.len(),
+        tree.node_count(),
+        tree.height()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates how a wider node makes a shorter tree.
+pub fn order_four_tree(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Order-4 Tree")?;
+
+    let keys: Vec<u32> = shuffled(20, 3);
+    writeln!(out, "Inserting 20 keys in shuffled order: {:?}", keys)?;
+
+    for order in [3, 4] {
+        let mut tree: BTree<u32> = BTree::new(order);
+        for &key in &keys {
+            tree.insert(key);
+        }
+        writeln!(
+            out,
+            "\nOrder {} (up to {} keys per node): height {}, {} nodes, {} splits",
+            order,
+            order - 1,
+            tree.height(),
+            tree.node_count(),
+            tree.splits()
+        )?;
+        write_tree(out, &tree)?;
+    }
+
+    Ok(())
+}
+
+/// Demonstrates that height grows logarithmically and a search visits one
+/// node per level.
+pub fn why_log_n(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Why O(log n)")?;
+
+    // Order 12 matches std's BTreeMap node size
+    writeln!(out, "Height after inserting n shuffled keys:")?;
+    writeln!(
+        out,
+        "  {:>9}  {:>7}  {:>7}  {:>8}",
+        "n", "order 3", "order 4", "order 12"
+    )?;
+    for n in [10, 100, 1_000, 10_000, 100_000] {
+        let keys: Vec<u32> = shuffled(n, 42);
+        let heights: Vec<usize> = [3, 4, 12]
+            .into_iter()
+            .map(|order| {
+                let mut tree: BTree<u32> = BTree::new(order);
+                for &key in &keys {
+                    tree.insert(key);
+                }
+                tree.height()
+            })
+            .collect();
+        writeln!(
+            out,
+            "  {:>9}  {:>7}  {:>7}  {:>8}",
+            n, heights[0], heights[1], heights[2]
+        )?;
+    }
+    writeln!(
+        out,
+        "Each 10x adds a fixed number of levels (about 3 at order 3, 1 at order 12): log n"
+    )?;
+
+    // A search reads one node per level and binary-searches inside it
+    let mut tree: BTree<u32> = BTree::new(4);
+    for key in shuffled(1_000, 42) {
+        tree.insert(key);
+    }
+    for target in [617, 1_500] {
+        let (path, found) = tree.search_path(&target);
+        writeln!(
+            out,
+            "\nSearching 1000 keys (order 4, height {}) for {}: {}",
+            tree.height(),
+            target,
+            if found { "found" } else { "not found" }
+        )?;
+        for (level, keys) in path.iter().enumerate() {
+            writeln!(out, "  level {}: {:?}", level, keys)?;
+        }
+        writeln!(
+            out,
+            "  {} nodes read, {} keys in them",
+            path.len(),
+            path.iter().map(|keys| keys.len()).sum::<usize>()
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Demonstrates that an in-order walk yields sorted keys.
+pub fn sorted_order_for_free(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Sorted Order for Free")?;
+
+    let words: [&str; 10] = [
+        "pear", "apple", "fig", "kiwi", "banana", "plum", "cherry", "lime", "date", "grape",
+    ];
+    let mut tree: BTree<&str> = BTree::new(4);
+    let mut set: BTreeSet<&str> = BTreeSet::new();
+    for word in words {
+        tree.insert(word);
+        set.insert(word);
+    }
+    write_tree(out, &tree)?;
+
+    // Child i holds everything between keys i - 1 and i, so visiting child,
+    // key, child, key, ... reads the keys in order
+    writeln!(out, "\nIn-order walk: {:?}", tree.keys())?;
+    writeln!(out, "BTreeSet:      {:?}", set)?;
+    writeln!(
+        out,
+        "Same order: {}",
+        tree.keys().into_iter().eq(set.iter())
+    )?;
+    writeln!(
+        out,
+        "contains(\"kiwi\"): {}, contains(\"mango\"): {}",
+        tree.contains(&"kiwi"),
+        tree.contains(&"mango")
+    )?;
+
+    Ok(())
+}
@@ -8,44 +8,197 @@
 //   - Unordered             - Always sorted
 //   - Needs Hash + Eq       - Needs Ord (+ Eq implied)
 //   - No range queries      - Supports range queries!
-
+//
+// Bulk and structural edits:
+//   append() moves a whole map in, split_off() cuts one in two at a key,
+//   retain() and extract_if() remove by predicate - extract_if (stable
+//   since Rust 1.91) only over a key range, and hands the entries back.
+//   first_entry()/last_entry() edit or remove the ends without a search.
+//
+// Navigating around a key:
+//   Stable Rust answers "what comes before/after key K?" with range() plus
+//   next()/next_back(). Editing around K means collecting the affected keys
+//   first, because an iterator can't mutate the tree it borrows. Nightly's
+//   cursor API (feature "nightly") walks and edits the tree in place.
+
+use crate::map_view_examples::MapView;
+use demo_framework::section;
 use std::cmp::Reverse;
 use std::collections::BTreeMap;
+use std::collections::btree_map::Range;
+use std::io::{self, Write};
+use std::mem;
+use std::ops::Bound::{Excluded, Included, Unbounded};
+use std::ops::RangeInclusive;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "creating_btreemaps",
+        "All the different ways to create a BTreeMap",
+        creating_btreemaps,
+    )?;
+
+    section(
+        out,
+        "sorted_iteration",
+        "BTreeMap's feature: sorted iteration",
+        sorted_iteration,
+    )?;
+
+    section(
+        out,
+        "range_queries",
+        "Range queries - BTreeMap's other feature",
+        range_queries,
+    )?;
+
+    section(
+        out,
+        "mutable_range_queries",
+        "Mutable range queries with range_mut()",
+        mutable_range_queries,
+    )?;
+
+    section(
+        out,
+        "neighbor_queries",
+        "Finding the entries just before and after a key with range()",
+        neighbor_queries,
+    )?;
+
+    section(
+        out,
+        "partial_iteration",
+        "Partial iteration: resuming from a key, paging, walking backwards",
+        partial_iteration,
+    )?;
+
+    section(
+        out,
+        "prefix_search",
+        "Autocomplete: every key starting with a prefix, as one range query",
+        prefix_search,
+    )?;
+
+    section(
+        out,
+        "editing_around_a_key",
+        "Stable alternative to cursors: range + collect, then edit",
+        editing_around_a_key,
+    )?;
+
+    #[cfg(feature = "nightly")]
+    section(
+        out,
+        "cursor_navigation",
+        "Nightly cursor API: walking the tree from a bound",
+        cursor_navigation,
+    )?;
+
+    #[cfg(feature = "nightly")]
+    section(
+        out,
+        "cursor_mutation",
+        "Nightly CursorMut: inserting and removing in place around a key",
+        cursor_mutation,
+    )?;
+
+    section(
+        out,
+        "min_max_operations",
+        "First/last key access - finding min and max keys",
+        min_max_operations,
+    )?;
+
+    section(
+        out,
+        "entry_api_examples",
+        "The Entry API - same patterns as HashMap",
+        entry_api_examples,
+    )?;
+
+    section(
+        out,
+        "leaderboard_example",
+        "Using BTreeMap for a sorted leaderboard",
+        leaderboard_example,
+    )?;
+
+    section(
+        out,
+        "time_series_example",
+        "Practical demo: Time-series data storage and querying",
+        time_series_example,
+    )?;
+
+    section(
+        out,
+        "bulk_operations",
+        "append, split_off, retain and extract_if: whole-map edits",
+        bulk_operations,
+    )?;
+
+    section(
+        out,
+        "time_window_eviction",
+        "Practical demo: A sliding time window over the sensor readings",
+        time_window_eviction,
+    )?;
+
+    section(
+        out,
+        "calendar_example",
+        "Practical demo: Calendar/scheduling with time-based keys",
+        calendar_example,
+    )?;
+
+    section(
+        out,
+        "custom_key_types",
+        "Using custom types as BTreeMap keys",
+        custom_key_types,
+    )?;
+
+    Ok(())
+}
 
 /// Demonstrates all the different ways to create a BTreeMap.
 ///
 /// Unlike HashMap, BTreeMap doesn't need a hasher - it only requires
 /// that keys implement the `Ord` trait for comparison-based ordering.
-pub fn creating_btreemaps() {
+pub fn creating_btreemaps(out: &mut dyn Write) -> io::Result<()> {
     // Method 1: BTreeMap::new()
     // The most common way - start empty and add items
     let mut map: BTreeMap<&str, i8> = BTreeMap::new();
     map.insert("cherry", 3);
     map.insert("apple", 1);
     map.insert("banana", 2);
-    println!("Created with new(): {:?}", map);
-    println!("Notice: Keys are automatically sorted alphabetically!");
+    writeln!(out, "Created with new(): {:?}", map)?;
+    writeln!(out, "Notice: Keys are automatically sorted alphabetically!")?;
 
     // Method 2: BTreeMap::from() - from array of tuples
     // Concise syntax for small, known maps
     let map2: BTreeMap<i8, &str> = BTreeMap::from([(3, "three"), (1, "one"), (2, "two")]);
-    println!("\nFrom array: {:?}", map2);
-    println!("Notice: Keys 1, 2, 3 are sorted numerically!");
+    writeln!(out, "\nFrom array: {:?}", map2)?;
+    writeln!(out, "Notice: Keys 1, 2, 3 are sorted numerically!")?;
 
     // Method 3: collect() from iterator of tuples
     // Useful when transforming data
     let map3: BTreeMap<i8, i8> = (1..=5).map(|x| (x, x * x)).collect();
-    println!("\nCollected squares: {:?}", map3);
+    writeln!(out, "\nCollected squares: {:?}", map3)?;
 
     // Note: Unlike HashMap, BTreeMap has no with_capacity() method.
     // B-trees allocate nodes as needed, so pre-allocation isn't applicable.
+
+    Ok(())
 }
 
 /// Demonstrates BTreeMap's feature: sorted iteration.
 ///
 /// Every time you iterate over a BTreeMap, keys come out in sorted order.
 /// This is guaranteed and deterministic - unlike HashMap's arbitrary order.
-pub fn sorted_iteration() {
+pub fn sorted_iteration(out: &mut dyn Write) -> io::Result<()> {
     let mut scores: BTreeMap<String, i8> = BTreeMap::new();
 
     // Insert in deliberately random order
@@ -55,23 +208,27 @@ pub fn sorted_iteration() {
     scores.insert("Bob".to_string(), 88);
 
     // Iteration is ALWAYS in sorted order by key!
-    println!("Scores (automatically sorted by name):");
+    writeln!(out, "Scores (automatically sorted by name):")?;
     for (name, score) in &scores {
-        println!("  {}: {}", name, score);
+        writeln!(out, "  {}: {}", name, score)?;
     }
 
     // This deterministic ordering is impossible with HashMap!
     // HashMap iteration order can change between runs or even insertions.
 
     // Keys and values iterators are also sorted
-    println!(
+    writeln!(
+        out,
         "\nKeys only (sorted): {:?}",
         scores.keys().collect::<Vec<_>>()
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "Values in key order: {:?}",
         scores.values().collect::<Vec<_>>()
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates range queries - BTreeMap's other feature
@@ -79,8 +236,8 @@ pub fn sorted_iteration() {
 /// Because keys are sorted, BTreeMap can efficiently answer questions like
 /// "give me all entries where the key is between X and Y". HashMap cannot
 /// do this at all - you'd have to scan every entry.
-pub fn range_queries() {
-    println!("Range Queries");
+pub fn range_queries(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Range Queries")?;
 
     let mut temperature_log: BTreeMap<u16, f32> = BTreeMap::new();
 
@@ -92,62 +249,426 @@ pub fn range_queries() {
     temperature_log.insert(400, 21.8);
     temperature_log.insert(500, 20.9);
 
-    println!("Full temperature log: {:?}", temperature_log);
+    writeln!(out, "Full temperature log: {:?}", temperature_log)?;
 
     // range() with inclusive bounds: 100..=400 means 100 <= key <= 400
-    println!("\nTemperatures from t=100 to t=400 (inclusive):");
+    writeln!(out, "\nTemperatures from t=100 to t=400 (inclusive):")?;
     for (time, temp) in temperature_log.range(100..=400) {
-        println!("  t={}: {:.1}°C", time, temp);
+        writeln!(out, "  t={}: {:.1}°C", time, temp)?;
     }
 
     // range() with exclusive end: 100..400 means 100 <= key < 400
-    println!("\nTemperatures from t=100 to t=400 (exclusive end):");
+    writeln!(out, "\nTemperatures from t=100 to t=400 (exclusive end):")?;
     for (time, temp) in temperature_log.range(100..400) {
-        println!("  t={}: {:.1}°C", time, temp);
+        writeln!(out, "  t={}: {:.1}°C", time, temp)?;
     }
 
     // Unbounded start: ..250 means key < 250
-    println!("\nTemperatures before t=250:");
+    writeln!(out, "\nTemperatures before t=250:")?;
     for (time, temp) in temperature_log.range(..250) {
-        println!("  t={}: {:.1}°C", time, temp);
+        writeln!(out, "  t={}: {:.1}°C", time, temp)?;
     }
 
     // Unbounded end: 300.. means key >= 300
-    println!("\nTemperatures from t=300 onwards:");
+    writeln!(out, "\nTemperatures from t=300 onwards:")?;
     for (time, temp) in temperature_log.range(300..) {
-        println!("  t={}: {:.1}°C", time, temp);
+        writeln!(out, "  t={}: {:.1}°C", time, temp)?;
     }
 
     // This is O(log n + k) where k is the number of results.
     // HashMap would require O(n) to scan all entries!
+
+    Ok(())
 }
 
 /// Demonstrates mutable range queries with range_mut().
 ///
 /// You can modify values within a range without affecting the tree structure,
 /// as long as you don't change the keys.
-pub fn mutable_range_queries() {
-    println!("Mutable Range Queries");
+pub fn mutable_range_queries(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Mutable Range Queries")?;
 
     let mut readings: BTreeMap<i8, f32> =
         BTreeMap::from([(1, 10.0), (2, 20.0), (3, 30.0), (4, 40.0), (5, 50.0)]);
 
-    println!("Before modification: {:?}", readings);
+    writeln!(out, "Before modification: {:?}", readings)?;
 
     // Double all values where key is between 2 and 4
     for (_key, value) in readings.range_mut(2..=4) {
         *value *= 2.0;
     }
 
-    println!("After doubling values for keys 2-4: {:?}", readings);
+    writeln!(out, "After doubling values for keys 2-4: {:?}", readings)?;
+
+    Ok(())
+}
+
+/// Demonstrates finding the neighbors of a key with range().
+///
+/// "What is the closest entry at or below K?" (floor) and "the closest
+/// entry above K?" (ceiling/successor) are single O(log n) range queries,
+/// whether or not K itself is in the map.
+pub fn neighbor_queries(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Neighbor Queries")?;
+
+    // Price levels in an order book: price (in cents) -> quantity
+    let levels: BTreeMap<u32, u32> =
+        BTreeMap::from([(9_950, 40), (9_975, 15), (10_000, 60), (10_050, 25)]);
+    writeln!(out, "Price levels: {:?}", levels)?;
+
+    for target in [9_975, 10_010, 9_900] {
+        // Floor: the last entry with key <= target
+        let floor: Option<(&u32, &u32)> = levels.range(..=target).next_back();
+        // Predecessor: the last entry with key < target
+        let before: Option<(&u32, &u32)> = levels.range(..target).next_back();
+        // Successor: the first entry with key > target
+        let after: Option<(&u32, &u32)> = levels.range((Excluded(target), Unbounded)).next();
+
+        writeln!(out, "\nAround {}:", target)?;
+        writeln!(out, "  floor (<=):       {:?}", floor)?;
+        writeln!(out, "  predecessor (<):  {:?}", before)?;
+        writeln!(out, "  successor (>):    {:?}", after)?;
+    }
+
+    // Each query descends the tree once: O(log n), never a scan.
+    // A sorted Vec gets the same answers with binary_search, but pays O(n)
+    // for every insert; a HashMap can't answer them at all.
+
+    Ok(())
+}
+
+/// Demonstrates partial iteration: starting mid-map and stopping early.
+///
+/// range() iterators are lazy, so `take(n)` only visits n entries. That
+/// makes "resume after the last key I saw" pagination O(log n + page),
+/// no matter how large the map is.
+pub fn partial_iteration(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Partial Iteration")?;
+
+    let events: BTreeMap<u32, String> = (1..=10)
+        .map(|id| (id * 10, format!("event-{}", id)))
+        .collect();
+    writeln!(out, "{} events, ids 10..=100", events.len())?;
+
+    // Keyset pagination: remember the last key, resume strictly after it
+    let page_size: usize = 3;
+    let mut last_seen: Option<u32> = None;
+    let mut page: usize = 1;
+    loop {
+        let start: std::ops::Bound<u32> = match last_seen {
+            Some(key) => Excluded(key),
+            None => Unbounded,
+        };
+        let batch: Vec<(&u32, &String)> =
+            events.range((start, Unbounded)).take(page_size).collect();
+        if batch.is_empty() {
+            break;
+        }
+        writeln!(out, "  Page {}: {:?}", page, batch)?;
+        last_seen = batch.last().map(|(key, _)| **key);
+        page += 1;
+    }
+
+    // Walking backwards from a key: the 3 entries at or before 55
+    let previous: Vec<&u32> = events.range(..=55).rev().take(3).map(|(k, _)| k).collect();
+    writeln!(out, "\n3 ids at or before 55, newest first: {:?}", previous)?;
+
+    // Both ends at once: range() is double-ended
+    let mut window = events.range((Included(30), Included(80)));
+    writeln!(
+        out,
+        "Window 30..=80 - front: {:?}, back: {:?}",
+        window.next().map(|(k, _)| k),
+        window.next_back().map(|(k, _)| k)
+    )?;
+
+    Ok(())
+}
+
+/// The smallest string above every string that starts with `prefix`, or
+/// `None` when no string is (an empty prefix, or one made only of
+/// `char::MAX`).
+///
+/// `str` compares bytes, and UTF-8 byte order is code point order, so the
+/// bound is the prefix with its last char moved to the next code point.
+/// After U+D7FF that is U+E000, since surrogates aren't chars. A trailing
+/// `char::MAX` has no next code point: it is dropped and the char before
+/// it is moved instead.
+pub fn prefix_upper_bound(prefix: &str) -> Option<String> {
+    let mut bound: String = prefix.to_string();
+    while let Some(last) = bound.pop() {
+        let next: Option<char> = match last {
+            '\u{D7FF}' => Some('\u{E000}'),
+            char::MAX => None,
+            other => char::from_u32(other as u32 + 1),
+        };
+        if let Some(next) = next {
+            bound.push(next);
+            return Some(bound);
+        }
+    }
+    None
+}
+
+/// Every entry whose key starts with `prefix`, in key order.
+///
+/// One O(log n) descent to the first match, then only the matches are
+/// visited - unlike `iter().filter(starts_with)`, which reads every key.
+pub fn prefix_range<'a, V>(map: &'a BTreeMap<String, V>, prefix: &str) -> Range<'a, String, V> {
+    match prefix_upper_bound(prefix) {
+        Some(bound) => map.range::<str, _>((Included(prefix), Excluded(bound.as_str()))),
+        None => map.range::<str, _>((Included(prefix), Unbounded)),
+    }
+}
+
+/// Demonstrates prefix queries over String keys with range().
+///
+/// Keys sharing a prefix sit next to each other in a BTreeMap, so
+/// "autocomplete `car`" is the range from "car" up to the first string
+/// that no longer starts with it. Getting that upper bound right for any
+/// Unicode prefix is the whole trick.
+pub fn prefix_search(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Prefix Search")?;
+
+    // Word -> how often it was searched for
+    let searches: BTreeMap<String, u32> = [
+        ("car", 120),
+        ("card", 45),
+        ("care", 80),
+        ("careful", 12),
+        ("cargo", 30),
+        ("carême", 3),
+        ("cat", 200),
+        ("catalog", 25),
+        ("café", 60),
+        ("cafeteria", 8),
+        ("caffeine", 40),
+        ("dog", 150),
+    ]
+    .into_iter()
+    .map(|(word, count)| (word.to_string(), count))
+    .collect();
+
+    for prefix in ["car", "caf", "cat", "x"] {
+        let matches: Vec<&str> = prefix_range(&searches, prefix)
+            .map(|(word, _)| word.as_str())
+            .collect();
+        writeln!(
+            out,
+            "  {:?} (up to {:?}): {:?}",
+            prefix,
+            prefix_upper_bound(prefix),
+            matches
+        )?;
+    }
+
+    // Autocomplete usually wants the most popular matches, not the first
+    // ones alphabetically: rank just the matches
+    let mut ranked: Vec<(&String, &u32)> = prefix_range(&searches, "ca").collect();
+    ranked.sort_by_key(|&(word, count)| (Reverse(*count), word));
+    let top: Vec<&str> = ranked
+        .iter()
+        .take(3)
+        .map(|(word, _)| word.as_str())
+        .collect();
+    writeln!(out, "\nTop 3 for \"ca\" by searches: {:?}", top)?;
+
+    // The tempting shortcut prefix.."prefix + z" misses keys whose next
+    // char sorts after 'z' - every accented letter, for a start
+    let shortcut: usize = searches
+        .range::<str, _>((Included("car"), Excluded("carz")))
+        .count();
+    let correct: usize = prefix_range(&searches, "car").count();
+    writeln!(
+        out,
+        "\nrange(\"car\"..\"carz\") finds {} keys, the real bound finds {} (\"carême\")",
+        shortcut, correct
+    )?;
+
+    // Matching is by code point, not by what the reader sees: a precomposed
+    // é (U+00E9) doesn't start with "e", a decomposed e + U+0301 does
+    let accents: BTreeMap<String, u32> = BTreeMap::from([
+        ("\u{e9}t\u{e9}".to_string(), 1),
+        ("e\u{301}te\u{301}".to_string(), 2),
+        ("ete".to_string(), 3),
+    ]);
+    let under_e: Vec<&u32> = prefix_range(&accents, "e").map(|(_, id)| id).collect();
+    writeln!(
+        out,
+        "\nKeys starting with \"e\" among été (precomposed), été (decomposed), ete: ids {:?}",
+        under_e
+    )?;
+    writeln!(
+        out,
+        "Normalize keys and queries (e.g. to NFC) before storing them."
+    )?;
+
+    // Upper bounds at the edges of the char range
+    writeln!(out, "\nUpper bounds at the edges:")?;
+    for prefix in ["", "a\u{D7FF}", "z\u{10FFFF}", "\u{10FFFF}"] {
+        writeln!(
+            out,
+            "  {:<16} -> {:?}",
+            format!("{:?}", prefix),
+            prefix_upper_bound(prefix)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Demonstrates editing entries around a key on stable Rust.
+///
+/// A range iterator borrows the map, so the tree can't be modified while
+/// walking it. The stable pattern is: collect just the affected keys with
+/// range() (O(log n + k)), then apply the edits (O(k log n)).
+///
+/// The pattern to avoid is rebuilding: collecting the whole map into a Vec,
+/// editing that, and collecting it back costs O(n) for an edit that touches
+/// only k entries. Cursors (nightly) remove even the collect step.
+pub fn editing_around_a_key(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Editing Around a Key (stable)")?;
+
+    let mut timeline: BTreeMap<u32, &str> = BTreeMap::from([
+        (100, "boot"),
+        (200, "login"),
+        (250, "open file"),
+        (300, "edit"),
+        (350, "save"),
+        (400, "logout"),
+    ]);
+    writeln!(out, "Timeline: {:?}", timeline)?;
+
+    // Remove the two entries that follow t=200
+    let doomed: Vec<u32> = timeline
+        .range((Excluded(200), Unbounded))
+        .take(2)
+        .map(|(&t, _)| t)
+        .collect();
+    for t in &doomed {
+        timeline.remove(t);
+    }
+    writeln!(out, "\nRemoved the 2 entries after t=200 ({:?}):", doomed)?;
+    writeln!(out, "  {:?}", timeline)?;
+
+    // Insert right before the next entry after t=200, if there is room
+    if let Some((&next, _)) = timeline.range((Excluded(200), Unbounded)).next()
+        && next - 200 > 1
+    {
+        timeline.insert(200 + (next - 200) / 2, "idle");
+    }
+    writeln!(out, "Inserted between t=200 and its successor:")?;
+    writeln!(out, "  {:?}", timeline)?;
+
+    // The O(n) anti-pattern, for comparison: rebuild everything to drop one entry
+    let entries_touched: usize = timeline.len();
+    let rebuilt: BTreeMap<u32, &str> = timeline
+        .iter()
+        .filter(|(t, _)| **t != 100)
+        .map(|(&t, &e)| (t, e))
+        .collect();
+    writeln!(
+        out,
+        "\nRebuilding to drop t=100 touched all {} entries; range + remove touches 1.",
+        entries_touched
+    )?;
+    writeln!(out, "  {:?}", rebuilt)?;
+
+    Ok(())
+}
+
+/// Demonstrates read-only navigation with the nightly cursor API.
+///
+/// `lower_bound(bound)` returns a Cursor positioned in the *gap* before the
+/// first key matching the bound. `peek_prev`/`peek_next` look at the
+/// neighbors on either side; `next`/`prev` move across them.
+#[cfg(feature = "nightly")]
+pub fn cursor_navigation(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Cursor Navigation (nightly)")?;
+
+    let levels: BTreeMap<u32, u32> =
+        BTreeMap::from([(9_950, 40), (9_975, 15), (10_000, 60), (10_050, 25)]);
+    writeln!(out, "Price levels: {:?}", levels)?;
+
+    // Gap before the first key >= 10_010
+    let mut cursor = levels.lower_bound(Included(&10_010));
+    writeln!(out, "\nCursor at lower_bound(10_010):")?;
+    writeln!(out, "  peek_prev: {:?}", cursor.peek_prev())?;
+    writeln!(out, "  peek_next: {:?}", cursor.peek_next())?;
+
+    // Walk backwards from there - no new tree descent per step
+    writeln!(out, "Walking backwards:")?;
+    while let Some((price, qty)) = cursor.prev() {
+        writeln!(out, "  {} x {}", price, qty)?;
+    }
+
+    // upper_bound(Excluded(k)) is the gap just before k itself
+    let cursor = levels.upper_bound(Excluded(&10_000));
+    writeln!(
+        out,
+        "\nupper_bound(Excluded(10_000)): prev {:?}, next {:?}",
+        cursor.peek_prev(),
+        cursor.peek_next()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates in-place edits with the nightly CursorMut API.
+///
+/// A CursorMut can remove, insert and modify around its position without
+/// collecting keys or descending the tree again for each edit. Inserts are
+/// checked: a key that would break the sort order is rejected.
+#[cfg(feature = "nightly")]
+pub fn cursor_mutation(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Cursor Mutation (nightly)")?;
+
+    let mut timeline: BTreeMap<u32, &str> = BTreeMap::from([
+        (100, "boot"),
+        (200, "login"),
+        (250, "open file"),
+        (300, "edit"),
+        (350, "save"),
+        (400, "logout"),
+    ]);
+    writeln!(out, "Timeline: {:?}", timeline)?;
+
+    {
+        // Gap right after t=200
+        let mut cursor = timeline.lower_bound_mut(Excluded(&200));
+
+        // Remove the two entries that follow t=200
+        let first = cursor.remove_next();
+        let second = cursor.remove_next();
+        writeln!(out, "\nremove_next() twice: {:?}, {:?}", first, second)?;
+
+        // Insert into the gap; the key must fit between the neighbors
+        let rejected: bool = cursor.insert_before(50, "too early").is_err();
+        writeln!(
+            out,
+            "insert_before(50) rejected (out of order): {}",
+            rejected
+        )?;
+        cursor
+            .insert_before(225, "idle")
+            .expect("225 sits between 200 and 350");
+
+        // Modify the next value in place
+        if let Some((_, event)) = cursor.peek_next() {
+            *event = "save (autosave)";
+        }
+    }
+    writeln!(out, "After cursor edits: {:?}", timeline)?;
+
+    Ok(())
 }
 
 /// Demonstrates first/last key access - finding min and max keys.
 ///
 /// Because keys are sorted, finding the smallest (first) or largest (last)
 /// key is O(log n) - just traverse to the appropriate leaf node.
-pub fn min_max_operations() {
-    println!("Min/Max (First/Last) Operations");
+pub fn min_max_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Min/Max (First/Last) Operations")?;
 
     let mut prices: BTreeMap<&str, f32> = BTreeMap::new();
     prices.insert("apple", 1.50);
@@ -155,42 +676,48 @@ pub fn min_max_operations() {
     prices.insert("cherry", 3.00);
     prices.insert("date", 2.25);
 
-    println!("Price list: {:?}", prices);
+    writeln!(out, "Price list: {:?}", prices)?;
 
     // Peek at first (smallest key) and last (largest key) without removing
     if let Some((item, price)) = prices.first_key_value() {
-        println!("\nFirst item (alphabetically): {} at ${:.2}", item, price);
+        writeln!(
+            out,
+            "\nFirst item (alphabetically): {} at ${:.2}",
+            item, price
+        )?;
     }
     if let Some((item, price)) = prices.last_key_value() {
-        println!("Last item (alphabetically): {} at ${:.2}", item, price);
+        writeln!(out, "Last item (alphabetically): {} at ${:.2}", item, price)?;
     }
 
     // first_entry() and last_entry() give mutable access via OccupiedEntry
     if let Some(mut entry) = prices.first_entry() {
-        println!("\nFirst entry key: {}", entry.key());
+        writeln!(out, "\nFirst entry key: {}", entry.key())?;
         // We can modify the value
         *entry.get_mut() = 1.75;
-        println!("Updated first entry value to: ${:.2}", entry.get());
+        writeln!(out, "Updated first entry value to: ${:.2}", entry.get())?;
     }
 
-    println!("After modifying first entry: {:?}", prices);
+    writeln!(out, "After modifying first entry: {:?}", prices)?;
 
     // pop_first() and pop_last() remove and return
     let first: Option<(&str, f32)> = prices.pop_first();
-    println!("\npop_first() returned: {:?}", first);
+    writeln!(out, "\npop_first() returned: {:?}", first)?;
 
     let last: Option<(&str, f32)> = prices.pop_last();
-    println!("pop_last() returned: {:?}", last);
+    writeln!(out, "pop_last() returned: {:?}", last)?;
 
-    println!("Remaining after pops: {:?}", prices);
+    writeln!(out, "Remaining after pops: {:?}", prices)?;
+
+    Ok(())
 }
 
 /// Demonstrates the Entry API - same patterns as HashMap.
 ///
 /// BTreeMap supports the same entry API as HashMap for efficient
 /// conditional insertion and updates.
-pub fn entry_api_examples() {
-    println!("Entry API with BTreeMap");
+pub fn entry_api_examples(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Entry API with BTreeMap")?;
 
     // Pattern 1: Word frequency counter (same as HashMap example)
     let text: &str = "the quick brown fox jumps over the lazy dog the fox";
@@ -201,36 +728,40 @@ pub fn entry_api_examples() {
     }
 
     // Unlike HashMap, iteration is alphabetically sorted!
-    println!("Word counts (alphabetically sorted):");
+    writeln!(out, "Word counts (alphabetically sorted):")?;
     for (word, count) in &word_count {
-        println!("  {}: {}", word, count);
+        writeln!(out, "  {}: {}", word, count)?;
     }
 
     // Pattern 2: or_insert_with for lazy initialization
     let mut cache: BTreeMap<i8, String> = BTreeMap::new();
 
+    // or_insert_with() expects a plain String back, so these trace lines can't use `?`
     let value: &mut String = cache.entry(42).or_insert_with(|| {
-        println!("  Computing value for key 42...");
+        let _ = writeln!(out, "  Computing value for key 42...");
         "computed".to_string()
     });
-    println!("\nFirst access: {}", value);
+    writeln!(out, "\nFirst access: {}", value)?;
 
     // Second access - closure doesn't run
     let value2: &mut String = cache.entry(42).or_insert_with(|| {
-        println!("  This won't print!");
+        let _ = writeln!(out, "  This won't print!");
         "won't happen".to_string()
     });
-    println!("Second access (cached): {}", value2);
+    writeln!(out, "Second access (cached): {}", value2)?;
 
     // Pattern 3: and_modify + or_insert
     word_count
         .entry("fox")
         .and_modify(|c| *c += 100)
         .or_insert(1);
-    println!(
+    writeln!(
+        out,
         "\nAfter boosting 'fox': fox count = {:?}",
         word_count.get("fox")
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates using BTreeMap for a sorted leaderboard.
@@ -238,8 +769,8 @@ pub fn entry_api_examples() {
 /// A common challenge: BTreeMap sorts by key in ascending order,
 /// but leaderboards typically show highest scores first.
 /// Solution: Use std::cmp::Reverse to invert the ordering.
-pub fn leaderboard_example() {
-    println!("Leaderboard Example (Descending Order)");
+pub fn leaderboard_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Leaderboard Example (Descending Order)")?;
 
     // Using Reverse<i32> as the score component makes higher scores sort first
     // The tuple (Reverse(score), name) ensures:
@@ -254,43 +785,52 @@ pub fn leaderboard_example() {
     leaderboard.insert((Reverse(1350), "Diana".to_string()), ());
     leaderboard.insert((Reverse(1500), "Eve".to_string()), ()); // Same score as Alice
 
-    println!("Leaderboard (highest scores first):");
+    writeln!(out, "Leaderboard (highest scores first):")?;
     for (rank, ((Reverse(score), name), _)) in leaderboard.iter().enumerate() {
-        println!("  {}. {} - {} points", rank + 1, name, score);
+        writeln!(out, "  {}. {} - {} points", rank + 1, name, score)?;
     }
 
     // Get top 3 players
-    println!("\nTop 3 players:");
+    writeln!(out, "\nTop 3 players:")?;
     for ((Reverse(score), name), _) in leaderboard.iter().take(3) {
-        println!("  {} - {} points", name, score);
+        writeln!(out, "  {} - {} points", name, score)?;
+    }
+
+    // Everyone on 1350 points or more is a prefix of the map. Reverse(1349)
+    // sorts after Reverse(1350), and "" before every name, so the range
+    // ends just past the last player on 1350
+    let qualified = MapView::new(&leaderboard).range(..(Reverse(1349), String::new()));
+    writeln!(
+        out,
+        "\nQualified for the final (1350+ points): {}",
+        qualified.count()
+    )?;
+    for ((Reverse(score), name), _) in qualified.iter() {
+        writeln!(out, "  {} - {} points", name, score)?;
     }
 
     // Alternative approach: use negative scores (simpler but less clear)
-    println!("\n--- Alternative: Negative Score Trick ---");
+    writeln!(out, "\n--- Alternative: Negative Score Trick ---")?;
     let mut simple_leaderboard: BTreeMap<(i32, String), ()> = BTreeMap::new();
 
     simple_leaderboard.insert((-100, "Alice".to_string()), ());
     simple_leaderboard.insert((-85, "Bob".to_string()), ());
     simple_leaderboard.insert((-92, "Charlie".to_string()), ());
 
-    println!("Using negated scores:");
-    for ((neg_score, name), _) in &simple_leaderboard {
-        println!("  {}: {} points", name, -neg_score);
+    writeln!(out, "Using negated scores:")?;
+    for (neg_score, name) in simple_leaderboard.keys() {
+        writeln!(out, "  {}: {} points", name, -neg_score)?;
     }
+
+    Ok(())
 }
 
 /// Practical example: Time-series data storage and querying.
 ///
 /// BTreeMap excels at time-series data because timestamps are naturally
 /// ordered, and range queries let you efficiently retrieve time windows.
-pub fn time_series_example() {
-    println!("Practical Example: Time-Series Data");
-
-    #[derive(Debug)]
-    struct Measurement {
-        temperature: f64,
-        humidity: f64,
-    }
+pub fn time_series_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Time-Series Data")?;
 
     let mut readings: BTreeMap<u64, Measurement> = BTreeMap::new();
 
@@ -331,40 +871,269 @@ pub fn time_series_example() {
         },
     );
 
-    println!("All readings (chronologically sorted):");
+    writeln!(out, "All readings (chronologically sorted):")?;
     for (time, data) in &readings {
-        println!(
+        writeln!(
+            out,
             "  t={}: {:.1}°C, {:.1}% humidity",
             time, data.temperature, data.humidity
-        );
+        )?;
     }
 
     // Query a specific time window
-    println!("\nReadings between t=1100 and t=1300:");
-    for (time, data) in readings.range(1_100..=1_300) {
-        println!(
+    // A view of the window: nothing is collected until it is walked
+    let window = MapView::new(&readings).range(1_100..=1_300);
+    writeln!(out, "\nReadings between t=1100 and t=1300:")?;
+    for (time, data) in window.iter() {
+        writeln!(
+            out,
             "  t={}: {:.1}°C, {:.1}% humidity",
             time, data.temperature, data.humidity
-        );
+        )?;
     }
 
+    // Narrowing the view adds a filter; project() picks out one field
+    let warm = window.clone().filter(|_, data| data.temperature > 24.0);
+    writeln!(
+        out,
+        "Of those, above 24°C: {:?}",
+        warm.project(|data| data.temperature).collect::<Vec<_>>()
+    )?;
+
     // Get the latest reading efficiently
     if let Some((time, data)) = readings.last_key_value() {
-        println!("\nLatest reading (t={}): {:.1}°C", time, data.temperature);
+        writeln!(
+            out,
+            "\nLatest reading (t={}): {:.1}°C",
+            time, data.temperature
+        )?;
     }
 
     // Get the earliest reading
     if let Some((time, data)) = readings.first_key_value() {
-        println!("Earliest reading (t={}): {:.1}°C", time, data.temperature);
+        writeln!(
+            out,
+            "Earliest reading (t={}): {:.1}°C",
+            time, data.temperature
+        )?;
+    }
+
+    Ok(())
+}
+
+/// One sensor reading in the time-series demos.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub temperature: f64,
+    pub humidity: f64,
+}
+
+impl Measurement {
+    pub fn new(temperature: f64, humidity: f64) -> Self {
+        Measurement {
+            temperature,
+            humidity,
+        }
+    }
+}
+
+/// Demonstrates the whole-map edits: append, split_off, retain, extract_if.
+///
+/// Each replaces a loop of insert() or remove() calls. append and split_off
+/// move entries between trees without cloning them, and extract_if hands
+/// the removed entries back instead of dropping them like retain does.
+pub fn bulk_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Bulk Operations")?;
+
+    // append(): move every entry of `other` in; `other` is left empty and
+    // its values win on duplicate keys
+    let mut stock: BTreeMap<u32, &str> = BTreeMap::from([(1, "bolt"), (3, "nut"), (5, "gear")]);
+    let mut delivery: BTreeMap<u32, &str> =
+        BTreeMap::from([(2, "washer"), (3, "nut (new batch)"), (6, "spring")]);
+    stock.append(&mut delivery);
+    writeln!(out, "After stock.append(&mut delivery):")?;
+    writeln!(out, "  stock:    {:?}", stock)?;
+    writeln!(out, "  delivery: {:?}", delivery)?;
+
+    // split_off(&k): keys >= k move to the returned map, keys < k stay
+    let upper: BTreeMap<u32, &str> = stock.split_off(&3);
+    writeln!(out, "\nAfter let upper = stock.split_off(&3):")?;
+    writeln!(out, "  stock: {:?}", stock)?;
+    writeln!(out, "  upper: {:?}", upper)?;
+
+    // retain(): keep entries matching the predicate, drop the rest
+    let mut levels: BTreeMap<u32, u32> = (1..=10).map(|k| (k, k * 10)).collect();
+    levels.retain(|_, value| *value % 20 == 0);
+    writeln!(out, "\nretain(value % 20 == 0): {:?}", levels)?;
+
+    // extract_if(range, pred): only keys in `range` are visited, and the
+    // matching entries are returned by a lazy iterator
+    let mut levels: BTreeMap<u32, u32> = (1..=10).map(|k| (k, k * 10)).collect();
+    let extracted: BTreeMap<u32, u32> = levels
+        .extract_if(4..=8, |_, value| *value % 20 == 0)
+        .collect();
+    writeln!(out, "extract_if(4..=8, value % 20 == 0):")?;
+    writeln!(out, "  extracted: {:?}", extracted)?;
+    writeln!(out, "  left:      {:?}", levels)?;
+
+    // first_entry()/last_entry(): an OccupiedEntry for either end
+    if let Some(mut entry) = levels.last_entry() {
+        *entry.get_mut() += 1;
+    }
+    if let Some(entry) = levels.first_entry() {
+        let (key, value) = entry.remove_entry();
+        writeln!(out, "\nfirst_entry().remove_entry(): ({}, {})", key, value)?;
+    }
+    writeln!(out, "After bumping last_entry() too: {:?}", levels)?;
+
+    Ok(())
+}
+
+/// Sensor readings keyed by timestamp (ms) that keeps only the last `span`
+/// ms and at most `max_len` readings.
+///
+/// Readings arrive in batches that may overlap the window or each other.
+/// Every step is one structural BTreeMap call: `append` to merge a batch,
+/// `split_off` to cut off everything too old, `first_entry` to trim the
+/// oldest, `extract_if` to pull out bad readings, `last_entry` to correct
+/// the newest.
+#[derive(Debug, Clone)]
+pub struct SlidingWindow {
+    readings: BTreeMap<u64, Measurement>,
+    span: u64,
+    max_len: usize,
+}
+
+impl SlidingWindow {
+    pub fn new(span: u64, max_len: usize) -> Self {
+        SlidingWindow {
+            readings: BTreeMap::new(),
+            span,
+            max_len,
+        }
+    }
+
+    pub fn readings(&self) -> &BTreeMap<u64, Measurement> {
+        &self.readings
+    }
+
+    /// Merges `batch` into the window, then evicts what no longer fits.
+    /// A batch reading replaces a stored one with the same timestamp.
+    /// Returns the evicted readings, oldest first.
+    pub fn ingest(&mut self, mut batch: BTreeMap<u64, Measurement>) -> BTreeMap<u64, Measurement> {
+        self.readings.append(&mut batch);
+
+        let Some((&latest, _)) = self.readings.last_key_value() else {
+            return BTreeMap::new();
+        };
+        // Everything before the cutoff goes in one split, which moves whole
+        // nodes instead of removing readings one by one
+        let cutoff: u64 = latest.saturating_sub(self.span);
+        let kept: BTreeMap<u64, Measurement> = self.readings.split_off(&cutoff);
+        let mut evicted: BTreeMap<u64, Measurement> = mem::replace(&mut self.readings, kept);
+
+        while self.readings.len() > self.max_len {
+            if let Some(oldest) = self.readings.first_entry() {
+                let (time, reading) = oldest.remove_entry();
+                evicted.insert(time, reading);
+            }
+        }
+        evicted
+    }
+
+    /// Removes and returns the readings at or after `since` whose
+    /// temperature is outside `valid`. Earlier readings aren't visited.
+    pub fn drop_outliers(
+        &mut self,
+        since: u64,
+        valid: RangeInclusive<f64>,
+    ) -> BTreeMap<u64, Measurement> {
+        self.readings
+            .extract_if(since.., |_, reading| !valid.contains(&reading.temperature))
+            .collect()
+    }
+
+    /// Applies `fix` to the newest reading; returns its timestamp.
+    pub fn correct_latest(&mut self, fix: impl FnOnce(&mut Measurement)) -> Option<u64> {
+        let mut latest = self.readings.last_entry()?;
+        fix(latest.get_mut());
+        Some(*latest.key())
     }
 }
 
+fn format_times(readings: &BTreeMap<u64, Measurement>) -> String {
+    let times: Vec<String> = readings.keys().map(u64::to_string).collect();
+    format!("[{}]", times.join(", "))
+}
+
+/// Practical example: A sliding time window over the sensor readings.
+///
+/// The time-series demo above queries a fixed set of readings. A live
+/// sensor keeps sending them, so old ones have to go - by age and by count.
+pub fn time_window_eviction(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Sliding Time Window")?;
+
+    // Keep the last 300 ms, and never more than 5 readings
+    let mut window: SlidingWindow = SlidingWindow::new(300, 5);
+    writeln!(out, "Window: last 300 ms, at most 5 readings")?;
+
+    let batches: [BTreeMap<u64, Measurement>; 3] = [
+        BTreeMap::from([
+            (1_000, Measurement::new(22.5, 45.0)),
+            (1_100, Measurement::new(23.0, 43.0)),
+            (1_200, Measurement::new(24.5, 40.0)),
+        ]),
+        // Arrives late and overlaps: 1_200 is resent with a fixed value
+        BTreeMap::from([
+            (1_200, Measurement::new(24.0, 40.0)),
+            (1_250, Measurement::new(24.8, 39.0)),
+            (1_300, Measurement::new(26.0, 38.0)),
+            (1_400, Measurement::new(25.5, 42.0)),
+        ]),
+        BTreeMap::from([
+            (1_420, Measurement::new(25.9, 41.0)),
+            (1_450, Measurement::new(85.0, 41.0)),
+            (1_500, Measurement::new(26.2, 40.0)),
+        ]),
+    ];
+    for (i, batch) in batches.into_iter().enumerate() {
+        writeln!(out, "\nBatch {}: {}", i + 1, format_times(&batch))?;
+        let evicted: BTreeMap<u64, Measurement> = window.ingest(batch);
+        writeln!(out, "  evicted: {}", format_times(&evicted))?;
+        writeln!(out, "  window:  {}", format_times(window.readings()))?;
+        if let Some(reading) = window.readings().get(&1_200) {
+            writeln!(out, "  t=1200:  {:.1}°C", reading.temperature)?;
+        }
+    }
+
+    // A stuck sensor reported 85°C; only the newest readings are checked
+    let outliers: BTreeMap<u64, Measurement> = window.drop_outliers(1_400, -20.0..=60.0);
+    writeln!(out, "\ndrop_outliers(since 1400, -20..=60°C):")?;
+    for (time, reading) in &outliers {
+        writeln!(out, "  removed t={}: {:.1}°C", time, reading.temperature)?;
+    }
+
+    let corrected: Option<u64> = window.correct_latest(|reading| reading.temperature -= 0.2);
+    writeln!(out, "correct_latest(-0.2°C) changed t={:?}", corrected)?;
+
+    writeln!(out, "\nFinal window:")?;
+    for (time, data) in window.readings() {
+        writeln!(
+            out,
+            "  t={}: {:.1}°C, {:.1}% humidity",
+            time, data.temperature, data.humidity
+        )?;
+    }
+
+    Ok(())
+}
+
 /// Practical example: Calendar/scheduling with time-based keys.
 ///
 /// Using tuples as keys allows multi-level sorting - perfect for
 /// dates and times where you want to sort by hour, then minute.
-pub fn calendar_example() {
-    println!("Practical Example: Calendar Events");
+pub fn calendar_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Calendar Events")?;
 
     #[derive(Debug)]
     struct Event {
@@ -411,25 +1180,28 @@ pub fn calendar_example() {
         },
     );
 
-    println!("Today's schedule (automatically sorted by time):");
+    writeln!(out, "Today's schedule (automatically sorted by time):")?;
     for ((hour, min), event) in &calendar {
-        println!(
+        writeln!(
+            out,
             "  {:02}:{:02} - {} ({} min)",
             hour, min, event.title, event.duration_mins
-        );
+        )?;
     }
 
     // Find afternoon events (12:00 and later)
-    println!("\nAfternoon events:");
+    writeln!(out, "\nAfternoon events:")?;
     for ((hour, min), event) in calendar.range((12, 0)..) {
-        println!("  {:02}:{:02} - {}", hour, min, event.title);
+        writeln!(out, "  {:02}:{:02} - {}", hour, min, event.title)?;
     }
 
     // Find morning events (before 12:00)
-    println!("\nMorning events:");
+    writeln!(out, "\nMorning events:")?;
     for ((hour, min), event) in calendar.range(..(12, 0)) {
-        println!("  {:02}:{:02} - {}", hour, min, event.title);
+        writeln!(out, "  {:02}:{:02} - {}", hour, min, event.title)?;
     }
+
+    Ok(())
 }
 
 /// Demonstrates using custom types as BTreeMap keys.
@@ -437,8 +1209,8 @@ pub fn calendar_example() {
 /// Unlike HashMap (which requires Hash + Eq), BTreeMap requires Ord.
 /// The Ord trait defines a total ordering, which the B-tree uses to
 /// organize and search for keys.
-pub fn custom_key_types() {
-    println!("Custom Types as Keys");
+pub fn custom_key_types(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Custom Types as Keys")?;
 
     // Derive Ord (and its prerequisites) for automatic ordering
     // The derived ordering compares fields in declaration order:
@@ -469,22 +1241,26 @@ pub fn custom_key_types() {
     releases.insert(Version::new(1, 0, 1), "Bug fix");
     releases.insert(Version::new(1, 2, 0), "More features");
 
-    println!("Release history (sorted by version):");
+    writeln!(out, "Release history (sorted by version):")?;
     for (version, description) in &releases {
-        println!(
+        writeln!(
+            out,
             "  v{}.{}.{}: {}",
             version.major, version.minor, version.patch, description
-        );
+        )?;
     }
 
     // Range query: find all 1.x releases
     let start: Version = Version::new(1, 0, 0);
     let end: Version = Version::new(2, 0, 0);
-    println!("\nAll 1.x releases:");
+    writeln!(out, "\nAll 1.x releases:")?;
     for (version, description) in releases.range(start..end) {
-        println!(
+        writeln!(
+            out,
             "  v{}.{}.{}: {}",
             version.major, version.minor, version.patch, description
-        );
+        )?;
     }
+
+    Ok(())
 }
@@ -0,0 +1,570 @@
+// LRU is one answer to "which entry do we evict?". It only looks at
+// recency, which makes it fast and simple but easy to fool: one big scan of
+// cold keys flushes everything that was hot. Two alternatives:
+//
+//   LFU (least frequently used): evict the entry with the fewest hits.
+//   Entries live in frequency buckets; a hit moves a key from bucket f to
+//   bucket f + 1, and eviction takes the oldest key of the lowest bucket.
+//   Scan-proof, but slow to forget: yesterday's hot keys keep their counts.
+//
+//     freq 1: [ e, f ]      <- evict e (lowest frequency, oldest)
+//     freq 3: [ b ]
+//     freq 7: [ a, c ]
+//
+//   ARC (adaptive replacement cache): two LRU lists, one for keys seen once
+//   recently (T1) and one for keys seen at least twice (T2), plus "ghost"
+//   lists remembering the keys recently evicted from each (B1, B2). A miss
+//   that hits a ghost list says that list was too short, and shifts the
+//   target split between T1 and T2 towards it.
+//
+//     T1 (recent)   | T2 (frequent)       <- hold values, c in total
+//     B1 ghosts     | B2 ghosts           <- hold keys only
+//
+// The CachePolicy trait puts LRU, LFU and ARC behind one interface so the
+// same access traces can be replayed against each of them.
+
+use crate::lru_examples::LinkedLru;
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use indexmap::IndexSet;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "lfu_basics",
+        "LFU: frequency buckets and what gets evicted",
+        lfu_basics,
+    )?;
+
+    section(
+        out,
+        "arc_basics",
+        "ARC: recent and frequent lists, ghosts and the adaptive target",
+        arc_basics,
+    )?;
+
+    section(
+        out,
+        "scan_resistance",
+        "A one-off scan of cold keys: who keeps the hot set?",
+        scan_resistance,
+    )?;
+
+    section(
+        out,
+        "practical_policy_comparison",
+        "Practical demo: hit rates on Zipfian, scanning and shifting traces",
+        practical_policy_comparison,
+    )?;
+
+    Ok(())
+}
+
+/// An eviction policy with a fixed capacity.
+pub trait CachePolicy<K, V> {
+    fn name(&self) -> &'static str;
+
+    fn capacity(&self) -> usize;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Looks up `key`, counting it as an access.
+    fn get(&mut self, key: &K) -> Option<&V>;
+
+    /// Inserts or updates `key`. Returns the old value if `key` was cached,
+    /// otherwise the evicted entry if the cache was full.
+    fn put(&mut self, key: K, value: V) -> Option<(K, V)>;
+}
+
+impl<K: Hash + Eq + Clone, V> CachePolicy<K, V> for LinkedLru<K, V> {
+    fn name(&self) -> &'static str {
+        "LRU"
+    }
+
+    fn capacity(&self) -> usize {
+        LinkedLru::capacity(self)
+    }
+
+    fn len(&self) -> usize {
+        LinkedLru::len(self)
+    }
+
+    fn get(&mut self, key: &K) -> Option<&V> {
+        LinkedLru::get(self, key)
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        LinkedLru::put(self, key, value)
+    }
+}
+
+/// An LFU cache with frequency buckets. Ties within the lowest frequency
+/// are broken by age: the key that reached that frequency first goes.
+#[derive(Clone, Debug)]
+pub struct LfuCache<K, V> {
+    /// Value and hit count of every cached key.
+    entries: HashMap<K, (V, u64)>,
+    /// Keys by frequency, in the order they reached it.
+    buckets: HashMap<u64, IndexSet<K>>,
+    min_frequency: u64,
+    capacity: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> LfuCache<K, V> {
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "cache capacity must be non-zero");
+        LfuCache {
+            entries: HashMap::with_capacity(capacity),
+            buckets: HashMap::new(),
+            min_frequency: 0,
+            capacity,
+        }
+    }
+
+    /// How many times `key` has been accessed since it was inserted.
+    pub fn frequency(&self, key: &K) -> Option<u64> {
+        self.entries.get(key).map(|&(_, f)| f)
+    }
+
+    /// Removes and returns the entry that would be evicted next.
+    pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+        let bucket: &mut IndexSet<K> = self.buckets.get_mut(&self.min_frequency)?;
+        let key: K = bucket
+            .shift_remove_index(0)
+            .expect("buckets are never empty");
+        if bucket.is_empty() {
+            self.buckets.remove(&self.min_frequency);
+            self.min_frequency = self.buckets.keys().copied().min().unwrap_or(0);
+        }
+        let (value, _) = self.entries.remove(&key).expect("bucketed keys are cached");
+        Some((key, value))
+    }
+
+    /// Moves `key` up one frequency bucket.
+    fn touch(&mut self, key: &K) {
+        let Some((_, frequency)) = self.entries.get_mut(key) else {
+            return;
+        };
+        let old: u64 = *frequency;
+        *frequency += 1;
+
+        let bucket: &mut IndexSet<K> = self.buckets.get_mut(&old).expect("bucket exists");
+        // shift_remove keeps the bucket in arrival order, at O(bucket size).
+        // The textbook O(1) LFU links each bucket's keys like LinkedLru.
+        bucket.shift_remove(key);
+        if bucket.is_empty() {
+            self.buckets.remove(&old);
+            if self.min_frequency == old {
+                self.min_frequency = old + 1;
+            }
+        }
+        self.buckets.entry(old + 1).or_default().insert(key.clone());
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> CachePolicy<K, V> for LfuCache<K, V> {
+    fn name(&self) -> &'static str {
+        "LFU"
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn get(&mut self, key: &K) -> Option<&V> {
+        self.touch(key);
+        self.entries.get(key).map(|(value, _)| value)
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some((slot, _)) = self.entries.get_mut(&key) {
+            let old: V = std::mem::replace(slot, value);
+            self.touch(&key);
+            return Some((key, old));
+        }
+
+        let mut evicted: Option<(K, V)> = None;
+        if self.entries.len() == self.capacity {
+            evicted = self.pop_lfu();
+        }
+        self.entries.insert(key.clone(), (value, 1));
+        self.buckets.entry(1).or_default().insert(key);
+        self.min_frequency = 1;
+        evicted
+    }
+}
+
+/// The sizes of ARC's four lists and its current target for T1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArcLists {
+    pub recent: usize,
+    pub frequent: usize,
+    pub recent_ghosts: usize,
+    pub frequent_ghosts: usize,
+    pub target: usize,
+}
+
+/// A simplified ARC cache (Megiddo and Modha, 2003), built from four
+/// [`LinkedLru`] lists.
+///
+/// Simplified in that misses are handled in `put` rather than on a combined
+/// request, and the target moves in whole entries.
+#[derive(Clone, Debug)]
+pub struct ArcCache<K, V> {
+    /// T1: seen once recently.
+    recent: LinkedLru<K, V>,
+    /// T2: seen at least twice recently.
+    frequent: LinkedLru<K, V>,
+    /// B1: keys recently evicted from T1.
+    recent_ghosts: LinkedLru<K, ()>,
+    /// B2: keys recently evicted from T2.
+    frequent_ghosts: LinkedLru<K, ()>,
+    /// Target size of T1; T2 gets the rest.
+    target: usize,
+    capacity: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> ArcCache<K, V> {
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "cache capacity must be non-zero");
+        ArcCache {
+            recent: LinkedLru::new(capacity),
+            frequent: LinkedLru::new(capacity),
+            recent_ghosts: LinkedLru::new(capacity),
+            frequent_ghosts: LinkedLru::new(capacity),
+            target: 0,
+            capacity,
+        }
+    }
+
+    pub fn lists(&self) -> ArcLists {
+        ArcLists {
+            recent: self.recent.len(),
+            frequent: self.frequent.len(),
+            recent_ghosts: self.recent_ghosts.len(),
+            frequent_ghosts: self.frequent_ghosts.len(),
+            target: self.target,
+        }
+    }
+
+    /// Makes room in T1 + T2 by demoting one entry to its ghost list: from
+    /// T1 if T1 is over its target, otherwise from T2.
+    fn replace(&mut self, hit_frequent_ghost: bool) {
+        let recent: usize = self.recent.len();
+        let from_recent: bool =
+            recent > 0 && (recent > self.target || (hit_frequent_ghost && recent == self.target));
+        if from_recent {
+            if let Some((key, _)) = self.recent.pop_lru() {
+                self.recent_ghosts.put(key, ());
+            }
+        } else if let Some((key, _)) = self.frequent.pop_lru() {
+            self.frequent_ghosts.put(key, ());
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> CachePolicy<K, V> for ArcCache<K, V> {
+    fn name(&self) -> &'static str {
+        "ARC"
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn len(&self) -> usize {
+        self.recent.len() + self.frequent.len()
+    }
+
+    fn get(&mut self, key: &K) -> Option<&V> {
+        // A second hit promotes the entry from T1 to T2
+        if let Some(value) = self.recent.remove(key) {
+            self.frequent.put(key.clone(), value);
+        }
+        self.frequent.get(key)
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        // Already cached: update and promote
+        if let Some(old) = self.recent.remove(&key) {
+            self.frequent.put(key.clone(), value);
+            return Some((key, old));
+        }
+        if self.frequent.contains(&key) {
+            return self.frequent.put(key, value);
+        }
+
+        // A ghost hit: the list it was evicted from should have been longer
+        if self.recent_ghosts.contains(&key) {
+            let step: usize = (self.frequent_ghosts.len() / self.recent_ghosts.len()).max(1);
+            self.target = (self.target + step).min(self.capacity);
+            self.replace(false);
+            self.recent_ghosts.remove(&key);
+            self.frequent.put(key, value);
+            return None;
+        }
+        if self.frequent_ghosts.contains(&key) {
+            let step: usize = (self.recent_ghosts.len() / self.frequent_ghosts.len()).max(1);
+            self.target = self.target.saturating_sub(step);
+            self.replace(true);
+            self.frequent_ghosts.remove(&key);
+            self.frequent.put(key, value);
+            return None;
+        }
+
+        // A brand new key
+        let mut evicted: Option<(K, V)> = None;
+        let recent_side: usize = self.recent.len() + self.recent_ghosts.len();
+        let total: usize = recent_side + self.frequent.len() + self.frequent_ghosts.len();
+        if recent_side == self.capacity {
+            if self.recent.len() < self.capacity {
+                self.recent_ghosts.pop_lru();
+                self.replace(false);
+            } else {
+                evicted = self.recent.pop_lru();
+            }
+        } else if total >= self.capacity {
+            if total == 2 * self.capacity {
+                self.frequent_ghosts.pop_lru();
+            }
+            if self.len() == self.capacity {
+                self.replace(false);
+            }
+        }
+        self.recent.put(key, value);
+        evicted
+    }
+}
+
+/// Hits and misses from replaying a trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HitStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl HitStats {
+    pub fn hit_rate(&self) -> f64 {
+        self.hits as f64 / (self.hits + self.misses).max(1) as f64
+    }
+}
+
+/// Replays `trace` as get-or-insert requests against `cache`.
+pub fn simulate(cache: &mut dyn CachePolicy<u64, u64>, trace: &[u64]) -> HitStats {
+    let mut stats: HitStats = HitStats { hits: 0, misses: 0 };
+    for &key in trace {
+        if cache.get(&key).is_some() {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+            cache.put(key, key);
+        }
+    }
+    stats
+}
+
+/// A deterministic trace of `len` requests over `keys` keys, where the key
+/// of rank r is requested with probability proportional to `1 / r^exponent`.
+///
+/// Rank 1 is key 0, rank 2 is key 1, and so on.
+pub fn zipf_trace(len: usize, keys: usize, exponent: f64, seed: u64) -> Vec<u64> {
+    let mut cumulative: Vec<f64> = Vec::with_capacity(keys);
+    let mut total: f64 = 0.0;
+    for rank in 1..=keys {
+        total += 1.0 / (rank as f64).powf(exponent);
+        cumulative.push(total);
+    }
+
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..len)
+        .map(|_| {
+            let point: f64 = rng.unit() * total;
+            cumulative.partition_point(|&c| c <= point).min(keys - 1) as u64
+        })
+        .collect()
+}
+
+/// Builds one of each policy with the given capacity.
+pub fn all_policies(capacity: usize) -> Vec<Box<dyn CachePolicy<u64, u64>>> {
+    vec![
+        Box::new(LinkedLru::new(capacity)),
+        Box::new(LfuCache::new(capacity)),
+        Box::new(ArcCache::new(capacity)),
+    ]
+}
+
+/// Demonstrates frequency buckets and LFU eviction order.
+pub fn lfu_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "LFU Basics")?;
+
+    let mut cache: LfuCache<&str, u32> = LfuCache::new(3);
+    cache.put("a", 1);
+    cache.put("b", 2);
+    cache.put("c", 3);
+    for key in ["a", "a", "a", "b"] {
+        cache.get(&key);
+    }
+    for key in ["a", "b", "c"] {
+        writeln!(
+            out,
+            "  {}: frequency {}",
+            key,
+            cache.frequency(&key).unwrap_or(0)
+        )?;
+    }
+
+    // "c" has the fewest hits, however recently it was inserted
+    writeln!(out, "put(\"d\") evicts: {:?}", cache.put("d", 4))?;
+
+    // New keys start at frequency 1 and are the first candidates
+    writeln!(out, "put(\"e\") evicts: {:?}", cache.put("e", 5))?;
+    writeln!(out, "  (d and e were tied at 1; d reached it first)")?;
+
+    writeln!(
+        out,
+        "\nThe catch: frequency never decays. \"a\" stays cached"
+    )?;
+    writeln!(out, "long after anyone stops asking for it.")?;
+
+    Ok(())
+}
+
+/// Demonstrates ARC's lists moving as keys are seen once, twice, and
+/// again after eviction.
+pub fn arc_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "ARC Basics")?;
+
+    let mut cache: ArcCache<u32, u32> = ArcCache::new(4);
+    for key in [1, 2, 3, 4] {
+        cache.put(key, key);
+    }
+    writeln!(out, "put 1..=4 (all seen once):   {:?}", cache.lists())?;
+
+    cache.get(&1);
+    cache.get(&2);
+    writeln!(out, "get 1, 2 (promoted to T2):   {:?}", cache.lists())?;
+
+    for key in [5, 6] {
+        cache.put(key, key);
+    }
+    writeln!(out, "put 5, 6 (T1 evicts to B1):  {:?}", cache.lists())?;
+
+    // 3 was evicted from T1 and is requested again: T1 was too small
+    cache.put(3, 3);
+    writeln!(out, "put 3 again (B1 ghost hit):  {:?}", cache.lists())?;
+    writeln!(out, "  A ghost hit in B1 raises the target size of T1")?;
+
+    Ok(())
+}
+
+/// Demonstrates what a one-off sequential scan does to each policy.
+pub fn scan_resistance(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Scan Resistance")?;
+
+    // 50 hot keys requested repeatedly, then 1,000 cold keys once each
+    let hot: Vec<u64> = (0..50).cycle().take(500).collect();
+    let scan: Vec<u64> = (1_000..2_000).collect();
+
+    writeln!(
+        out,
+        "Capacity 100; warm up on 50 hot keys, scan 1,000 cold keys,"
+    )?;
+    writeln!(out, "then count how many hot keys are still cached:")?;
+    for mut cache in all_policies(100) {
+        simulate(cache.as_mut(), &hot);
+        simulate(cache.as_mut(), &scan);
+        let survivors: usize = (0..50).filter(|k| cache.get(k).is_some()).count();
+        writeln!(out, "  {:<4} {:>2} of 50", cache.name(), survivors)?;
+    }
+
+    Ok(())
+}
+
+/// Practical example: the same traces replayed against every policy.
+///
+/// - Zipfian: a stable popularity ranking. Frequency is the right signal
+/// - Zipf + scans: every 2,000 requests, 500 one-off keys
+/// - Shifting: the popular keys change halfway through
+pub fn practical_policy_comparison(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Policy Comparison")?;
+
+    let len: usize = 100_000;
+    let keys: usize = 10_000;
+
+    let zipf_08: Vec<u64> = zipf_trace(len, keys, 0.8, 1);
+    let zipf_12: Vec<u64> = zipf_trace(len, keys, 1.2, 2);
+
+    let mut scanning: Vec<u64> = Vec::with_capacity(len + len / 4);
+    let mut next_cold: u64 = 1_000_000;
+    for (i, &key) in zipf_trace(len, keys, 1.0, 3).iter().enumerate() {
+        scanning.push(key);
+        if i % 2_000 == 1_999 {
+            scanning.extend(next_cold..next_cold + 500);
+            next_cold += 500;
+        }
+    }
+
+    // Second half: same distribution over a disjoint set of keys
+    let mut shifting: Vec<u64> = zipf_trace(len / 2, keys, 1.0, 4);
+    shifting.extend(
+        zipf_trace(len / 2, keys, 1.0, 5)
+            .into_iter()
+            .map(|k| k + keys as u64),
+    );
+
+    let traces: [(&str, &[u64]); 4] = [
+        ("Zipf s=0.8", &zipf_08),
+        ("Zipf s=1.2", &zipf_12),
+        ("Zipf + scans", &scanning),
+        ("Shifting", &shifting),
+    ];
+
+    for capacity in [100, 1_000] {
+        writeln!(out, "\nCapacity {} (hit rate %):", capacity)?;
+        writeln!(
+            out,
+            "  {:<14} {:>6} {:>6} {:>6}",
+            "Trace", "LRU", "LFU", "ARC"
+        )?;
+        for (name, trace) in traces {
+            let rates: Vec<String> = all_policies(capacity)
+                .into_iter()
+                .map(|mut cache| {
+                    format!(
+                        "{:>6.1}",
+                        simulate(cache.as_mut(), trace).hit_rate() * 100.0
+                    )
+                })
+                .collect();
+            writeln!(out, "  {:<14} {}", name, rates.join(" "))?;
+        }
+    }
+
+    writeln!(
+        out,
+        "\nLFU wins on stable popularity and shrugs off scans, but"
+    )?;
+    writeln!(
+        out,
+        "keeps the old hot set after the shift. ARC tracks LFU on"
+    )?;
+    writeln!(
+        out,
+        "the stable traces and beats LRU once popularity moves."
+    )?;
+
+    Ok(())
+}
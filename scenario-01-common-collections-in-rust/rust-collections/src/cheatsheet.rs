@@ -0,0 +1,270 @@
+//! Complexity and capability facts for every collection the scenario covers.
+//!
+//! [`COLLECTIONS`] is the one place these facts are written down. The
+//! `cheatsheet` subcommand prints [`tables`] built from it, and the README's
+//! reference tables are that same output pasted between markers, checked by
+//! `tests/cheatsheet.rs`.
+
+use demo_framework::cheatsheet::Table;
+use std::fmt;
+
+/// The order iteration visits elements in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Wherever the caller put them.
+    Positional,
+    /// The order they were inserted, with removals closing gaps.
+    Insertion,
+    /// Sorted by key.
+    Sorted,
+    /// Heap layout: the maximum first, the rest in no useful order.
+    Heap,
+    /// Hash or slot order; changes as the collection changes.
+    Arbitrary,
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name: &str = match self {
+            Order::Positional => "positional",
+            Order::Insertion => "insertion",
+            Order::Sorted => "sorted",
+            Order::Heap => "heap (unsorted)",
+            Order::Arbitrary => "arbitrary",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// What the scenario teaches about one collection.
+pub struct CollectionFacts {
+    pub name: &'static str,
+    /// `std` or the crate it comes from.
+    pub source: &'static str,
+    pub insert: &'static str,
+    pub lookup: &'static str,
+    pub remove: &'static str,
+    pub iteration: &'static str,
+    /// The operation it is best at, with its cost.
+    pub special: &'static str,
+    pub order: Order,
+    /// `get(i)` by position in O(1).
+    pub positional_access: bool,
+    /// Lookup by key or value faster than a scan.
+    pub keyed_lookup: bool,
+    /// Everything between two keys, in order.
+    pub range_queries: bool,
+    /// Can hold two equal elements (sequences, heaps) or keys (maps, sets).
+    pub duplicates: bool,
+}
+
+pub const COLLECTIONS: &[CollectionFacts] = &[
+    CollectionFacts {
+        name: "Vec",
+        source: "std",
+        insert: "O(1)*",
+        lookup: "O(n) or O(log n)**",
+        remove: "O(1) back, O(n) front",
+        iteration: "O(n), cache-friendly",
+        special: "Random access O(1)",
+        order: Order::Positional,
+        positional_access: true,
+        keyed_lookup: false,
+        range_queries: false,
+        duplicates: true,
+    },
+    CollectionFacts {
+        name: "VecDeque",
+        source: "std",
+        insert: "O(1)* both ends",
+        lookup: "O(n) or O(log n)**",
+        remove: "O(1) both ends",
+        iteration: "O(n), cache-friendly",
+        special: "Double-ended queue",
+        order: Order::Positional,
+        positional_access: true,
+        keyed_lookup: false,
+        range_queries: false,
+        duplicates: true,
+    },
+    CollectionFacts {
+        name: "LinkedList",
+        source: "std",
+        insert: "O(1) both ends",
+        lookup: "O(n)",
+        remove: "O(1) at cursor",
+        iteration: "O(n), cache-unfriendly",
+        special: "O(1) split/append",
+        order: Order::Positional,
+        positional_access: false,
+        keyed_lookup: false,
+        range_queries: false,
+        duplicates: true,
+    },
+    CollectionFacts {
+        name: "HashMap",
+        source: "std",
+        insert: "O(1)*",
+        lookup: "O(1)*",
+        remove: "O(1)*",
+        iteration: "O(capacity)",
+        special: "Entry API",
+        order: Order::Arbitrary,
+        positional_access: false,
+        keyed_lookup: true,
+        range_queries: false,
+        duplicates: false,
+    },
+    CollectionFacts {
+        name: "BTreeMap",
+        source: "std",
+        insert: "O(log n)",
+        lookup: "O(log n)",
+        remove: "O(log n)",
+        iteration: "O(n), sorted",
+        special: "Range queries O(log n + k)",
+        order: Order::Sorted,
+        positional_access: false,
+        keyed_lookup: true,
+        range_queries: true,
+        duplicates: false,
+    },
+    CollectionFacts {
+        name: "HashSet",
+        source: "std",
+        insert: "O(1)*",
+        lookup: "O(1)*",
+        remove: "O(1)*",
+        iteration: "O(capacity)",
+        special: "Set operations",
+        order: Order::Arbitrary,
+        positional_access: false,
+        keyed_lookup: true,
+        range_queries: false,
+        duplicates: false,
+    },
+    CollectionFacts {
+        name: "BTreeSet",
+        source: "std",
+        insert: "O(log n)",
+        lookup: "O(log n)",
+        remove: "O(log n)",
+        iteration: "O(n), sorted",
+        special: "Range queries, min/max O(log n)",
+        order: Order::Sorted,
+        positional_access: false,
+        keyed_lookup: true,
+        range_queries: true,
+        duplicates: false,
+    },
+    CollectionFacts {
+        name: "BinaryHeap",
+        source: "std",
+        insert: "O(log n)*",
+        lookup: "O(n)",
+        remove: "O(log n) pop max",
+        iteration: "O(n), not sorted",
+        special: "Peek max O(1)",
+        order: Order::Heap,
+        positional_access: false,
+        keyed_lookup: false,
+        range_queries: false,
+        duplicates: true,
+    },
+    CollectionFacts {
+        name: "IndexMap",
+        source: "indexmap",
+        insert: "O(1)*",
+        lookup: "O(1)*",
+        remove: "O(1)* swap, O(n) shift",
+        iteration: "O(n), insertion order",
+        special: "Positional access O(1)",
+        order: Order::Insertion,
+        positional_access: true,
+        keyed_lookup: true,
+        range_queries: false,
+        duplicates: false,
+    },
+    CollectionFacts {
+        name: "SlotMap",
+        source: "slotmap",
+        insert: "O(1)*",
+        lookup: "O(1)",
+        remove: "O(1)",
+        iteration: "O(capacity)",
+        special: "Generational keys",
+        order: Order::Arbitrary,
+        positional_access: false,
+        keyed_lookup: true,
+        range_queries: false,
+        duplicates: false,
+    },
+];
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+/// The complexity and capability tables, in README order.
+pub fn tables() -> Vec<Table> {
+    let complexity: Table = Table {
+        title: "Complexity Reference",
+        columns: vec![
+            "Collection",
+            "Insert",
+            "Lookup",
+            "Remove",
+            "Iteration",
+            "Special",
+        ],
+        rows: COLLECTIONS
+            .iter()
+            .map(|c| {
+                vec![
+                    c.name.to_string(),
+                    c.insert.to_string(),
+                    c.lookup.to_string(),
+                    c.remove.to_string(),
+                    c.iteration.to_string(),
+                    c.special.to_string(),
+                ]
+            })
+            .collect(),
+        notes: vec![
+            "* Amortized - occasional O(n) for reallocation or rehashing",
+            "** O(log n) with binary_search on sorted data",
+        ],
+    };
+
+    let capabilities: Table = Table {
+        title: "Capability Reference",
+        columns: vec![
+            "Collection",
+            "From",
+            "Iteration order",
+            "Index by position",
+            "Fast lookup by key",
+            "Range queries",
+            "Duplicates",
+        ],
+        rows: COLLECTIONS
+            .iter()
+            .map(|c| {
+                vec![
+                    c.name.to_string(),
+                    c.source.to_string(),
+                    c.order.to_string(),
+                    yes_no(c.positional_access),
+                    yes_no(c.keyed_lookup),
+                    yes_no(c.range_queries),
+                    yes_no(c.duplicates),
+                ]
+            })
+            .collect(),
+        notes: vec![
+            "Duplicates: two equal elements for sequences and heaps, two equal keys for maps and sets",
+        ],
+    };
+
+    vec![complexity, capabilities]
+}
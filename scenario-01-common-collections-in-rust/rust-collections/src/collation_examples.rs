@@ -0,0 +1,586 @@
+// A BTreeMap<String, V> iterates in byte order, which is code point order:
+//
+//   "Zoë" < "adam" < "Émile"      uppercase before lowercase, and every
+//                                  accented letter after 'z'
+//
+// People expect a phone book: letters first, then accents, then case, and
+// which letters count as "different" depends on the language. Swedish puts
+// å, ä and ö after z; Spanish puts ñ after n; a German phone book files ü
+// as "ue".
+//
+// The Unicode Collation Algorithm gets there by comparing in levels, and only
+// looking at a level when every earlier one is equal:
+//
+//   level 1 (primary)    base letters           "Emile" = "Émile" = "émile"
+//   level 2 (secondary)  accents                "Emile" < "Émile"
+//   level 3 (tertiary)   case                   "émile" < "Émile"
+//   identical            canonical form (NFD)   "é" (one char) = "e" + U+0301
+//
+// BTreeMap has no comparator parameter: the order lives in the key type's Ord.
+// So the wrapper key computes a sort key once, on insert, holding the levels
+// as vectors that derive Ord; comparing two keys is then a plain
+// lexicographic comparison, with no Unicode work per comparison.
+//
+// The weights here are a small subset of the Unicode default (DUCET) table:
+// enough for Latin names with accents, digits and common punctuation, plus a
+// tailoring per locale. tests/collation.rs checks the orders against ones
+// recorded from ICU's collator. For anything beyond names, use ICU itself (icu_collator).
+
+use demo_framework::section;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "code_point_order",
+        "What BTreeMap<String, V> does with names",
+        code_point_order,
+    )?;
+
+    section(
+        out,
+        "case_insensitive_keys",
+        "A case-insensitive key type, and the keys it merges",
+        case_insensitive_keys,
+    )?;
+
+    section(
+        out,
+        "collation_levels",
+        "Sorting by letters, then accents, then case",
+        collation_levels,
+    )?;
+
+    section(
+        out,
+        "locale_tailoring",
+        "The same names in Swedish, Spanish and German phone-book order",
+        locale_tailoring,
+    )?;
+
+    section(
+        out,
+        "practical_contact_list",
+        "Practical demo: a contact list with a collated BTreeMap and letter ranges",
+        practical_contact_list,
+    )?;
+
+    Ok(())
+}
+
+/// A string key that compares and hashes ignoring case: "Bob" and "bob"
+/// are the same key.
+///
+/// Only case: accents still compare by code point, so "émile" sorts after
+/// "zoë".
+#[derive(Clone, Debug)]
+pub struct CaseInsensitive(pub String);
+
+impl CaseInsensitive {
+    fn folded(&self) -> impl Iterator<Item = char> + '_ {
+        self.0.chars().flat_map(char::to_lowercase)
+    }
+}
+
+impl PartialEq for CaseInsensitive {
+    fn eq(&self, other: &Self) -> bool {
+        self.folded().eq(other.folded())
+    }
+}
+
+impl Eq for CaseInsensitive {}
+
+impl PartialOrd for CaseInsensitive {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CaseInsensitive {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.folded().cmp(other.folded())
+    }
+}
+
+impl Hash for CaseInsensitive {
+    // Must agree with Eq: equal keys hash their folded chars the same way
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for c in self.folded() {
+            c.hash(state);
+        }
+    }
+}
+
+/// Which language's alphabet a [`Collated`] key follows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// The Unicode default order: accented letters are variants of their
+    /// base letter. English, French, Dutch and many others use it as is.
+    Root,
+    /// å, ä and ö are letters after z; æ and ø are variants of ä and ö,
+    /// and ü of y.
+    Swedish,
+    /// ñ is a letter after n.
+    Spanish,
+    /// ä, ö and ü are filed as ae, oe and ue (German phone books).
+    GermanPhonebook,
+}
+
+/// Character classes, in primary order: spaces, then punctuation, then
+/// digits, then letters.
+const SPACE: u8 = 0;
+const PUNCTUATION: u8 = 1;
+const DIGIT: u8 = 2;
+const LETTER: u8 = 3;
+
+/// Punctuation in the Unicode default order. Anything else sorts after
+/// these, by code point.
+const PUNCTUATION_ORDER: &str = "_-,;:!?.'\"()[]{}@*/\\&#%`^+<=>|~$";
+
+/// Accents in the Unicode default order, as combining marks. A base letter
+/// without an accent comes first (weight 0), then a locale's variants of
+/// it ([`VARIANT`]), then these from weight 2.
+const ACCENT_ORDER: [char; 11] = [
+    '\u{301}', // acute      é
+    '\u{300}', // grave      è
+    '\u{306}', // breve      ĕ
+    '\u{302}', // circumflex ê
+    '\u{30C}', // caron      ě
+    '\u{30A}', // ring       e̊
+    '\u{308}', // diaeresis  ë
+    '\u{303}', // tilde      ẽ
+    '\u{307}', // dot above  ė
+    '\u{328}', // ogonek     ę
+    '\u{304}', // macron     ē
+];
+
+/// Secondary weight of a letter written with a stroke (ø, ł, đ), which
+/// Unicode doesn't decompose into base letter plus mark.
+const STROKE: u16 = ACCENT_ORDER.len() as u16 + 2;
+
+/// Secondary weight that sets æ and œ apart from "ae" and "oe".
+const LIGATURE: u16 = STROKE + 1;
+
+/// Secondary weight of a letter a locale files right after another one,
+/// such as ä after "ae" in a German phone book.
+const VARIANT: u16 = 1;
+
+/// Tertiary weights: lowercase first.
+const LOWER: u8 = 0;
+const UPPER: u8 = 1;
+/// A letter that stands for two (ß for ss), after both cases of the pair.
+const EXPANSION: u8 = 2;
+
+/// A collation sort key: the levels, compared in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortKey {
+    /// (class, weight) per collation element.
+    primary: Vec<(u8, u32)>,
+    /// One weight per collation element (0 for none), plus one per accent
+    /// mark or stroke on it.
+    secondary: Vec<u16>,
+    tertiary: Vec<u8>,
+    /// The NFD form: only canonically equivalent strings tie here.
+    identical: String,
+}
+
+impl SortKey {
+    pub fn new(text: &str, locale: Locale) -> Self {
+        let mut key: SortKey = SortKey {
+            identical: text.nfd().collect(),
+            ..SortKey::default()
+        };
+        // NFC first, so a tailoring sees "å" whether it was typed as one
+        // char or as "a" + ring
+        for c in text.nfc() {
+            key.push_char(c, locale);
+        }
+        key
+    }
+
+    fn push_char(&mut self, c: char, locale: Locale) {
+        // A mark with no precomposed form, like the circumflex in "n̂",
+        // stays a char of its own: it accents the letter before it
+        if is_combining_mark(c) {
+            self.secondary.push(accent_weight(c));
+            return;
+        }
+        let case: u8 = if c.is_uppercase() { UPPER } else { LOWER };
+        let lower: char = c.to_lowercase().next().unwrap_or(c);
+        if let Some(elements) = tailoring(lower, locale) {
+            for &(letter, accent) in elements {
+                self.push_element((LETTER, letter), accent, case);
+            }
+            return;
+        }
+        // Letters that stand for two: ß is a variant of "ss" in case, the
+        // ligatures of "ae" and "oe" in accent
+        let pair: Option<(char, char, u16, u8)> = match lower {
+            'ß' => Some(('s', 's', 0, EXPANSION)),
+            'æ' => Some(('a', 'e', LIGATURE, case)),
+            'œ' => Some(('o', 'e', LIGATURE, case)),
+            _ => None,
+        };
+        if let Some((first, second, accent, case)) = pair {
+            self.push_element((LETTER, letter_weight(first)), 0, case);
+            self.push_element((LETTER, letter_weight(second)), accent, case);
+            return;
+        }
+
+        let mut decomposed = std::iter::once(lower).nfd();
+        let base: char = decomposed.next().unwrap_or(lower);
+        let (base, stroke) = match base {
+            'ø' => ('o', true),
+            'ł' => ('l', true),
+            'đ' => ('d', true),
+            'ħ' => ('h', true),
+            _ => (base, false),
+        };
+        let primary: (u8, u32) = if base.is_whitespace() {
+            (SPACE, base as u32)
+        } else if let Some(digit) = base.to_digit(10) {
+            (DIGIT, digit)
+        } else if base.is_alphabetic() {
+            (LETTER, letter_weight(base))
+        } else {
+            let position: Option<usize> = PUNCTUATION_ORDER.chars().position(|p| p == base);
+            (
+                PUNCTUATION,
+                position.map_or(base as u32 + 0x100, |p| p as u32),
+            )
+        };
+        self.push_element(primary, 0, case);
+        // A stroke counts as an accent on its letter
+        if stroke {
+            self.secondary.push(STROKE);
+        }
+        // Accents after the first add secondary weights of their own
+        for mark in decomposed.filter(|&m| is_combining_mark(m)) {
+            self.secondary.push(accent_weight(mark));
+        }
+    }
+
+    fn push_element(&mut self, primary: (u8, u32), accent: u16, case: u8) {
+        self.primary.push(primary);
+        self.secondary.push(accent);
+        self.tertiary.push(case);
+    }
+
+    /// Compares only base letters: "emile", "Émile" and "EMILE" are equal.
+    pub fn cmp_primary(&self, other: &SortKey) -> Ordering {
+        self.primary.cmp(&other.primary)
+    }
+}
+
+/// Room between letters for the ones a locale inserts after them.
+fn letter_weight(c: char) -> u32 {
+    (c as u32) << 2
+}
+
+fn accent_weight(mark: char) -> u16 {
+    ACCENT_ORDER
+        .iter()
+        .position(|&accent| accent == mark)
+        .map_or(LIGATURE + 1 + mark as u16 % 64, |p| p as u16 + 2)
+}
+
+/// Collation elements a locale assigns to a lowercase letter, as (letter
+/// weight, accent weight) pairs; `None` if it follows the default.
+fn tailoring(c: char, locale: Locale) -> Option<&'static [(u32, u16)]> {
+    const Z: u32 = ('z' as u32) << 2;
+    const N: u32 = ('n' as u32) << 2;
+    const Y: u32 = ('y' as u32) << 2;
+    const A: u32 = ('a' as u32) << 2;
+    const O: u32 = ('o' as u32) << 2;
+    const U: u32 = ('u' as u32) << 2;
+    const E: u32 = ('e' as u32) << 2;
+    match (locale, c) {
+        (Locale::Swedish, 'å') => Some(&[(Z + 1, 0)]),
+        (Locale::Swedish, 'ä') => Some(&[(Z + 2, 0)]),
+        (Locale::Swedish, 'ö') => Some(&[(Z + 3, 0)]),
+        // The Danish and Norwegian letters file with their Swedish twins
+        (Locale::Swedish, 'æ') => Some(&[(Z + 2, VARIANT)]),
+        (Locale::Swedish, 'ø') => Some(&[(Z + 3, VARIANT)]),
+        (Locale::Swedish, 'ü') => Some(&[(Y, VARIANT)]),
+        (Locale::Spanish, 'ñ') => Some(&[(N + 1, 0)]),
+        // Filed as the two letters, after the spelled-out form
+        (Locale::GermanPhonebook, 'ä') => Some(&[(A, 0), (E, VARIANT)]),
+        (Locale::GermanPhonebook, 'ö') => Some(&[(O, 0), (E, VARIANT)]),
+        (Locale::GermanPhonebook, 'ü') => Some(&[(U, 0), (E, VARIANT)]),
+        _ => None,
+    }
+}
+
+/// A string key that orders by collation in one locale.
+///
+/// Equal when the sort keys are: canonically equivalent spellings ("é" as
+/// one char or as "e" + U+0301) are the same key. Keys from different
+/// locales compare, but meaninglessly: keep one locale per map.
+#[derive(Clone, Debug)]
+pub struct Collated {
+    key: SortKey,
+    text: String,
+}
+
+impl PartialEq for Collated {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Collated {}
+
+impl PartialOrd for Collated {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collated {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl Hash for Collated {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl Collated {
+    pub fn new(text: &str, locale: Locale) -> Self {
+        Collated {
+            key: SortKey::new(text, locale),
+            text: text.to_string(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn sort_key(&self) -> &SortKey {
+        &self.key
+    }
+}
+
+impl fmt::Display for Collated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.text)
+    }
+}
+
+/// Sorts `names` in `locale`'s order.
+pub fn collate<'a>(names: &[&'a str], locale: Locale) -> Vec<&'a str> {
+    let mut keyed: Vec<(SortKey, &str)> = names
+        .iter()
+        .map(|&name| (SortKey::new(name, locale), name))
+        .collect();
+    keyed.sort();
+    keyed.into_iter().map(|(_, name)| name).collect()
+}
+
+const NAMES: [&str; 10] = [
+    "Zoë",
+    "adam",
+    "Émile",
+    "bob",
+    "Bob",
+    "Ødegaard",
+    "eve",
+    "Ana María",
+    "Anna",
+    "émile",
+];
+
+/// Demonstrates code point order on mixed-case, accented names.
+pub fn code_point_order(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Code Point Order")?;
+
+    let map: BTreeMap<&str, usize> = NAMES.iter().map(|&name| (name, name.len())).collect();
+    writeln!(
+        out,
+        "BTreeMap<&str, _> keys: {:?}",
+        map.keys().collect::<Vec<_>>()
+    )?;
+    for name in ["Zoë", "adam", "Émile"] {
+        let first: char = name.chars().next().unwrap_or_default();
+        writeln!(out, "  {:?} starts with U+{:04X}", name, first as u32)?;
+    }
+    writeln!(
+        out,
+        "Uppercase A-Z is 0x41-0x5A, lowercase a-z 0x61-0x7A, and É is 0xC9: after all of them"
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates a key type that ignores case, and what it merges.
+pub fn case_insensitive_keys(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Case-Insensitive Keys")?;
+
+    let mut map: BTreeMap<CaseInsensitive, usize> = BTreeMap::new();
+    for (id, name) in NAMES.iter().enumerate() {
+        map.insert(CaseInsensitive(name.to_string()), id);
+    }
+    let keys: Vec<&str> = map.keys().map(|key| key.0.as_str()).collect();
+    writeln!(out, "BTreeMap<CaseInsensitive, _> keys: {:?}", keys)?;
+    writeln!(
+        out,
+        "{} names went in, {} keys came out: \"bob\"/\"Bob\" and \"Émile\"/\"émile\" merged",
+        NAMES.len(),
+        map.len()
+    )?;
+    // The first spelling inserted stays as the key; the value is the last
+    writeln!(
+        out,
+        "get(\"BOB\"): key {:?} -> id {:?}",
+        map.get_key_value(&CaseInsensitive("BOB".to_string()))
+            .map(|(key, _)| key.0.as_str()),
+        map.get(&CaseInsensitive("BOB".to_string()))
+    )?;
+    writeln!(
+        out,
+        "Case is handled, accents aren't: \"émile\" still sorts after \"zoë\""
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the three comparison levels on names that differ in one.
+pub fn collation_levels(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Collation Levels")?;
+
+    let mut map: BTreeMap<Collated, usize> = BTreeMap::new();
+    for (id, name) in NAMES.iter().enumerate() {
+        map.insert(Collated::new(name, Locale::Root), id);
+    }
+    let keys: Vec<&str> = map.keys().map(Collated::as_str).collect();
+    writeln!(out, "BTreeMap<Collated, _> keys: {:?}", keys)?;
+
+    let pairs: [(&str, &str); 5] = [
+        ("emile", "Émile"),
+        ("émile", "Émile"),
+        ("Emile", "émile"),
+        ("Ødegaard", "Oden"),
+        ("é", "e\u{301}"),
+    ];
+    writeln!(out)?;
+    for (a, b) in pairs {
+        let (x, y) = (
+            Collated::new(a, Locale::Root),
+            Collated::new(b, Locale::Root),
+        );
+        let level: &str = if x.key.primary != y.key.primary {
+            "letters"
+        } else if x.key.secondary != y.key.secondary {
+            "accents"
+        } else if x.key.tertiary != y.key.tertiary {
+            "case"
+        } else {
+            "nothing: canonically equivalent"
+        };
+        writeln!(
+            out,
+            "  {:<12} {:?} {:<12} decided by {}",
+            format!("{:?}", a),
+            x.cmp(&y),
+            format!("{:?}", b),
+            level
+        )?;
+    }
+    writeln!(
+        out,
+        "\n\"Ødegaard\" < \"Oden\": Ø files under O, so g < n decides"
+    )?;
+    writeln!(
+        out,
+        "\"Emile\" < \"émile\": the accent (level 2) outranks the capital (level 3)"
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates locale tailorings on one list of names.
+pub fn locale_tailoring(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Locale Tailoring")?;
+
+    let names: [&str; 12] = [
+        "Zorn", "Åsa", "Ärla", "Örn", "Olof", "Aaron", "Ümit", "Yngve", "Nuñez", "Nuzzo", "Müller",
+        "Mufti",
+    ];
+    for (label, locale) in [
+        ("Root", Locale::Root),
+        ("Swedish", Locale::Swedish),
+        ("Spanish", Locale::Spanish),
+        ("German phone book", Locale::GermanPhonebook),
+    ] {
+        writeln!(out, "{:<18} {}", label, collate(&names, locale).join(", "))?;
+    }
+    writeln!(
+        out,
+        "\nSwedish: Åsa, Ärla and Örn move past Zorn; Ümit sorts as Ymit"
+    )?;
+    writeln!(
+        out,
+        "Spanish: Nuñez stays before Nuzzo, ñ being the letter after n"
+    )?;
+    writeln!(
+        out,
+        "German phone book: Müller is Mueller, before Mufti; Örn is Oern, before Olof"
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: a contact list sorted for people, with "everyone
+/// under E" as a range over the collated keys.
+pub fn practical_contact_list(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Contact List")?;
+
+    let contacts: [(&str, &str); 9] = [
+        ("Élodie Martin", "+33 1 23"),
+        ("edgar Poe", "+1 555 01"),
+        ("Ethan Hunt", "+1 555 02"),
+        ("Ève Dubois", "+33 4 56"),
+        ("Fatima Zahra", "+212 5 37"),
+        ("Dmitri Ivanov", "+7 495 1"),
+        ("Eszter Kovács", "+36 1 98"),
+        ("Łukasz Nowak", "+48 22 11"),
+        ("Lena Berg", "+46 8 77"),
+    ];
+    let book: BTreeMap<Collated, &str> = contacts
+        .iter()
+        .map(|&(name, phone)| (Collated::new(name, Locale::Root), phone))
+        .collect();
+
+    writeln!(out, "All contacts:")?;
+    for (name, phone) in &book {
+        writeln!(out, "  {:<16} {}", name, phone)?;
+    }
+
+    // "e" is below every name starting with any e, "f" above them all
+    let (e, f) = (
+        Collated::new("e", Locale::Root),
+        Collated::new("f", Locale::Root),
+    );
+    let under_e: Vec<&str> = book.range(e..f).map(|(name, _)| name.as_str()).collect();
+    writeln!(out, "\nrange(\"e\"..\"f\"): {:?}", under_e)?;
+
+    let mut by_code_point: Vec<&str> = contacts.iter().map(|&(name, _)| name).collect();
+    by_code_point.sort_unstable();
+    let code_point_e: Vec<&str> = by_code_point
+        .into_iter()
+        .filter(|name| ("e".."f").contains(name))
+        .collect();
+    writeln!(out, "The same range on plain strings: {:?}", code_point_e)?;
+
+    Ok(())
+}
@@ -0,0 +1,511 @@
+// Group-by over rows and over columns. The same sales data in two layouts:
+//
+//   rows (CSV-style)                    columns (Parquet-style)
+//   store       quantity  price         store       [0, 1, 0, 2, ...]  codes
+//   "store-07"  3         1250          quantity    [3, 1, 5, 2, ...]
+//   "store-12"  1         980           price       [1250, 980, ...]
+//   "store-07"  5         4410          dictionary  ["store-07", "store-12", ...]
+//   ...
+//
+// A row is one struct, so reading one column means stepping over the
+// others, and every row owns its key as a String. Columns keep each field
+// in its own Vec, and the key column is dictionary-encoded the way Parquet
+// stores repetitive strings: each distinct key once, and a u32 code per
+// row.
+//
+// Row-oriented group-by is the HashMap loop everyone writes: one lookup per
+// row, hashing the key each time, with the aggregate for that key updated
+// in place. The columnar plan does one step per column instead:
+//
+//   1. sort the key column, carrying each row's index along
+//   2. run-length encode the sorted keys: one (key, start, len) per group
+//   3. for each value column, gather it into sorted order once and
+//      aggregate each run as one contiguous slice
+//
+// Step 1 is O(n log n) against the HashMap's O(n), but it sorts plain u64s
+// instead of hashing strings, and steps 2 and 3 are straight scans over
+// slices. It allocates the same handful of buffers however many rows
+// there are, and its output is sorted by key and columnar too. On
+// shuffled rows the sort costs more than the hashing it replaces; on rows
+// already stored in key order it is one pass, and the plan catches up.
+
+use demo_framework::alloc;
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::mem;
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "rows_and_columns",
+        "The same sales as row structs and as dictionary-encoded columns",
+        rows_and_columns,
+    )?;
+
+    section(
+        out,
+        "run_length_grouping",
+        "Sort the keys, run-length group them, aggregate per column",
+        run_length_grouping,
+    )?;
+
+    section(
+        out,
+        "group_by_comparison",
+        "Practical demo: group-by over 1M rows, row-oriented vs columnar",
+        group_by_comparison,
+    )?;
+
+    Ok(())
+}
+
+/// One sale as a CSV reader would hand it over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaleRow {
+    pub store: String,
+    pub quantity: u32,
+    pub price_cents: u32,
+}
+
+/// `count` sales spread over `stores` stores at random.
+pub fn sales_rows(count: usize, stores: u32, seed: u64) -> Vec<SaleRow> {
+    let mut rng: Lcg = Lcg::new(seed);
+    let mut next = move |bound: u32| rng.below(u64::from(bound)) as u32;
+    (0..count)
+        .map(|_| SaleRow {
+            store: format!("store-{:04}", next(stores)),
+            quantity: 1 + next(10),
+            price_cents: 100 + next(19_901),
+        })
+        .collect()
+}
+
+/// The same sales, one Vec per field, with the store names
+/// dictionary-encoded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SalesColumns {
+    /// Each distinct store name once, in order of first appearance.
+    pub dictionary: Vec<String>,
+    /// Per row, the store's index in `dictionary`.
+    pub store: Vec<u32>,
+    pub quantity: Vec<u32>,
+    pub price_cents: Vec<u32>,
+}
+
+impl SalesColumns {
+    pub fn from_rows(rows: &[SaleRow]) -> Self {
+        let mut codes: HashMap<&str, u32> = HashMap::new();
+        let mut columns: SalesColumns = SalesColumns {
+            dictionary: Vec::new(),
+            store: Vec::with_capacity(rows.len()),
+            quantity: Vec::with_capacity(rows.len()),
+            price_cents: Vec::with_capacity(rows.len()),
+        };
+        for row in rows {
+            let code: u32 = *codes.entry(&row.store).or_insert_with(|| {
+                columns.dictionary.push(row.store.clone());
+                (columns.dictionary.len() - 1) as u32
+            });
+            columns.store.push(code);
+            columns.quantity.push(row.quantity);
+            columns.price_cents.push(row.price_cents);
+        }
+        columns
+    }
+
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+}
+
+/// What a group-by computes per store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Aggregate {
+    pub rows: u64,
+    pub quantity: u64,
+    pub revenue_cents: u64,
+    pub max_price_cents: u32,
+}
+
+impl Aggregate {
+    fn add(&mut self, quantity: u32, price_cents: u32) {
+        self.rows += 1;
+        self.quantity += u64::from(quantity);
+        self.revenue_cents += u64::from(quantity) * u64::from(price_cents);
+        self.max_price_cents = self.max_price_cents.max(price_cents);
+    }
+}
+
+/// Row-oriented group-by: one HashMap lookup per row. The key is cloned
+/// once per store, not once per row.
+pub fn group_rows(rows: &[SaleRow]) -> HashMap<String, Aggregate> {
+    let mut groups: HashMap<String, Aggregate> = HashMap::new();
+    for row in rows {
+        match groups.get_mut(&row.store) {
+            Some(aggregate) => aggregate.add(row.quantity, row.price_cents),
+            None => groups
+                .entry(row.store.clone())
+                .or_default()
+                .add(row.quantity, row.price_cents),
+        }
+    }
+    groups
+}
+
+/// The HashMap loop over the columns: the same algorithm, hashing u32
+/// codes instead of Strings.
+pub fn group_codes(columns: &SalesColumns) -> HashMap<u32, Aggregate> {
+    let mut groups: HashMap<u32, Aggregate> = HashMap::new();
+    for ((&code, &quantity), &price_cents) in columns
+        .store
+        .iter()
+        .zip(&columns.quantity)
+        .zip(&columns.price_cents)
+    {
+        groups.entry(code).or_default().add(quantity, price_cents);
+    }
+    groups
+}
+
+/// One group of equal keys in sorted order: rows `start..start + len`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Run {
+    pub key: u32,
+    pub start: usize,
+    pub len: usize,
+}
+
+/// Row indices ordered by key, and the runs of equal keys in that order.
+///
+/// Each row is packed into one u64, key in the high half and index in the
+/// low half, so one sort of plain integers orders rows by key and keeps
+/// equal keys in row order.
+pub fn sorted_runs(keys: &[u32]) -> (Vec<u32>, Vec<Run>) {
+    assert!(
+        u32::try_from(keys.len()).is_ok(),
+        "row indices must fit in u32"
+    );
+    let mut packed: Vec<u64> = keys
+        .iter()
+        .enumerate()
+        .map(|(index, &key)| (u64::from(key) << 32) | index as u64)
+        .collect();
+    packed.sort_unstable();
+
+    let mut runs: Vec<Run> = Vec::new();
+    for (position, &entry) in packed.iter().enumerate() {
+        let key: u32 = (entry >> 32) as u32;
+        match runs.last_mut() {
+            Some(run) if run.key == key => run.len += 1,
+            _ => runs.push(Run {
+                key,
+                start: position,
+                len: 1,
+            }),
+        }
+    }
+    let order: Vec<u32> = packed.into_iter().map(|entry| entry as u32).collect();
+    (order, runs)
+}
+
+/// Columnar group-by output: one entry per store, sorted by code, one Vec
+/// per aggregate.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GroupedColumns {
+    pub store: Vec<u32>,
+    pub rows: Vec<u64>,
+    pub quantity: Vec<u64>,
+    pub revenue_cents: Vec<u64>,
+    pub max_price_cents: Vec<u32>,
+}
+
+impl GroupedColumns {
+    /// The aggregates keyed by store name, to compare with the row version.
+    pub fn to_map(&self, dictionary: &[String]) -> BTreeMap<String, Aggregate> {
+        (0..self.store.len())
+            .map(|group| {
+                let aggregate: Aggregate = Aggregate {
+                    rows: self.rows[group],
+                    quantity: self.quantity[group],
+                    revenue_cents: self.revenue_cents[group],
+                    max_price_cents: self.max_price_cents[group],
+                };
+                (dictionary[self.store[group] as usize].clone(), aggregate)
+            })
+            .collect()
+    }
+}
+
+/// Columnar group-by: sort, run-length group, then one pass per column.
+pub fn group_columns(columns: &SalesColumns) -> GroupedColumns {
+    let (order, runs) = sorted_runs(&columns.store);
+    let group_count: usize = runs.len();
+
+    // Gathering puts each run's values next to each other, so every
+    // aggregate below is a loop over one slice
+    let gather = |column: &[u32], into: &mut Vec<u32>| {
+        into.clear();
+        into.extend(order.iter().map(|&row| column[row as usize]));
+    };
+    let mut quantities: Vec<u32> = Vec::with_capacity(order.len());
+    let mut prices: Vec<u32> = Vec::with_capacity(order.len());
+    gather(&columns.quantity, &mut quantities);
+    gather(&columns.price_cents, &mut prices);
+
+    let mut grouped: GroupedColumns = GroupedColumns {
+        store: runs.iter().map(|run| run.key).collect(),
+        rows: runs.iter().map(|run| run.len as u64).collect(),
+        quantity: Vec::with_capacity(group_count),
+        revenue_cents: Vec::with_capacity(group_count),
+        max_price_cents: Vec::with_capacity(group_count),
+    };
+    for run in &runs {
+        let span = run.start..run.start + run.len;
+        let quantity: &[u32] = &quantities[span.clone()];
+        let price: &[u32] = &prices[span];
+        grouped
+            .quantity
+            .push(quantity.iter().map(|&q| u64::from(q)).sum());
+        grouped.revenue_cents.push(
+            quantity
+                .iter()
+                .zip(price)
+                .map(|(&q, &p)| u64::from(q) * u64::from(p))
+                .sum(),
+        );
+        grouped
+            .max_price_cents
+            .push(price.iter().copied().max().unwrap_or(0));
+    }
+    grouped
+}
+
+// ----------------------------------------------------------------------------
+// Measuring
+// ----------------------------------------------------------------------------
+
+/// Runs `work` once, timing it and counting its allocations. Allocations
+/// are `None` unless the binary installs `TrackingAllocator`, as `cargo
+/// run` does.
+fn measure<T>(work: impl FnOnce() -> T) -> (T, Duration, Option<u64>) {
+    let before: Option<alloc::AllocCounts> = alloc::counts();
+    let start: Instant = Instant::now();
+    let result: T = work();
+    let elapsed: Duration = start.elapsed();
+    let allocations: Option<u64> = before
+        .zip(alloc::counts())
+        .map(|(before, after)| after.since(before).allocations);
+    (result, elapsed, allocations)
+}
+
+fn format_allocations(allocations: Option<u64>) -> String {
+    allocations.map_or_else(|| "untracked".to_string(), |count| count.to_string())
+}
+
+// ----------------------------------------------------------------------------
+// Demos
+// ----------------------------------------------------------------------------
+
+/// Demonstrates the two layouts and what each stores per row.
+pub fn rows_and_columns(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Rows and Columns")?;
+
+    let rows: Vec<SaleRow> = sales_rows(6, 3, 11);
+    writeln!(out, "Rows:")?;
+    for row in &rows {
+        writeln!(
+            out,
+            "  {:<12} quantity {:>2}  price {:>5}",
+            row.store, row.quantity, row.price_cents
+        )?;
+    }
+
+    let columns: SalesColumns = SalesColumns::from_rows(&rows);
+    writeln!(out, "Columns:")?;
+    writeln!(out, "  dictionary  {:?}", columns.dictionary)?;
+    writeln!(out, "  store       {:?}", columns.store)?;
+    writeln!(out, "  quantity    {:?}", columns.quantity)?;
+    writeln!(out, "  price_cents {:?}", columns.price_cents)?;
+
+    // Per row: the struct, plus the String's own heap buffer
+    let row_bytes: usize = mem::size_of::<SaleRow>() + rows[0].store.len();
+    let column_bytes: usize = 3 * mem::size_of::<u32>();
+    writeln!(
+        out,
+        "\nBytes per row: {} as a SaleRow ({} struct + {} key bytes on the heap), {} as columns",
+        row_bytes,
+        mem::size_of::<SaleRow>(),
+        rows[0].store.len(),
+        column_bytes
+    )?;
+    writeln!(
+        out,
+        "Allocations per row: 1 as rows (the String), 0 as columns (one Vec per field, one String per store)"
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the three steps of the columnar group-by on a few rows.
+pub fn run_length_grouping(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Run-Length Grouping")?;
+
+    let columns: SalesColumns = SalesColumns::from_rows(&sales_rows(8, 3, 4));
+    writeln!(out, "store:    {:?}", columns.store)?;
+    writeln!(out, "quantity: {:?}", columns.quantity)?;
+
+    // 1. Sort row indices by key
+    let (order, runs) = sorted_runs(&columns.store);
+    let sorted_keys: Vec<u32> = order
+        .iter()
+        .map(|&row| columns.store[row as usize])
+        .collect();
+    writeln!(out, "\n1. Row order sorted by store: {:?}", order)?;
+    writeln!(out, "   store in that order:        {:?}", sorted_keys)?;
+
+    // 2. Equal keys are now adjacent: one run per group
+    writeln!(out, "2. Runs (key, start, len):")?;
+    for run in &runs {
+        writeln!(out, "   ({}, {}, {})", run.key, run.start, run.len)?;
+    }
+
+    // 3. Gather a column in sorted order; each run is one slice of it
+    let quantities: Vec<u32> = order
+        .iter()
+        .map(|&row| columns.quantity[row as usize])
+        .collect();
+    writeln!(out, "3. quantity gathered:         {:?}", quantities)?;
+    for run in &runs {
+        let slice: &[u32] = &quantities[run.start..run.start + run.len];
+        writeln!(
+            out,
+            "   store {} ({}): {:?} -> sum {}",
+            run.key,
+            columns.dictionary[run.key as usize],
+            slice,
+            slice.iter().sum::<u32>()
+        )?;
+    }
+
+    let grouped: GroupedColumns = group_columns(&columns);
+    writeln!(
+        out,
+        "\ngroup_columns(): quantity per store {:?}",
+        grouped.quantity
+    )?;
+    writeln!(
+        out,
+        "Same result as the HashMap loop: {}",
+        grouped.to_map(&columns.dictionary)
+            == group_rows(&sales_rows(8, 3, 4)).into_iter().collect()
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: total sales per store over a million rows, three
+/// ways, and the columnar one again on rows sorted by store.
+///
+/// Building the columns from the rows is timed too, since a CSV source
+/// hands over rows; a Parquet source hands over columns and skips it. The
+/// allocation column needs the tracking allocator, so it only shows counts
+/// under `cargo run`.
+pub fn group_by_comparison(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Group-By Comparison")?;
+
+    let stores: u32 = 1_000;
+    let (rows, build_rows, rows_allocations) = measure(|| sales_rows(1_000_000, stores, 42));
+    let (columns, build_columns, columns_allocations) = measure(|| SalesColumns::from_rows(&rows));
+    writeln!(out, "{} sales over {} stores", rows.len(), stores)?;
+    writeln!(out, "  {:<36} {:>11}  time", "", "allocations")?;
+    writeln!(
+        out,
+        "  {:<36} {:>11}  {:.2?}",
+        "build rows (one String each)",
+        format_allocations(rows_allocations),
+        build_rows
+    )?;
+    writeln!(
+        out,
+        "  {:<36} {:>11}  {:.2?}",
+        "encode rows as columns",
+        format_allocations(columns_allocations),
+        build_columns
+    )?;
+
+    let (by_rows, rows_time, rows_group_allocations) = measure(|| group_rows(&rows));
+    let (by_codes, codes_time, codes_allocations) = measure(|| group_codes(&columns));
+    let (by_columns, columns_time, columns_group_allocations) = measure(|| group_columns(&columns));
+
+    // Columnar files are often written sorted by a key; the sort then finds
+    // one long ascending run and returns after a single pass
+    let mut sorted_rows: Vec<SaleRow> = rows.clone();
+    sorted_rows.sort_by(|a, b| a.store.cmp(&b.store));
+    let sorted_columns: SalesColumns = SalesColumns::from_rows(&sorted_rows);
+    let (by_sorted, sorted_time, sorted_allocations) = measure(|| group_columns(&sorted_columns));
+    for (name, allocations, elapsed) in [
+        (
+            "rows: HashMap<String, Aggregate>",
+            rows_group_allocations,
+            rows_time,
+        ),
+        (
+            "columns: HashMap<u32, Aggregate>",
+            codes_allocations,
+            codes_time,
+        ),
+        (
+            "columns: sort + run-length",
+            columns_group_allocations,
+            columns_time,
+        ),
+        (
+            "  same, rows already sorted by store",
+            sorted_allocations,
+            sorted_time,
+        ),
+    ] {
+        writeln!(
+            out,
+            "  {:<36} {:>11}  {:.2?}",
+            name,
+            format_allocations(allocations),
+            elapsed
+        )?;
+    }
+
+    // All four agree, once the codes are mapped back to names
+    let expected: BTreeMap<String, Aggregate> = by_rows.into_iter().collect();
+    let from_codes: BTreeMap<String, Aggregate> = by_codes
+        .into_iter()
+        .map(|(code, aggregate)| (columns.dictionary[code as usize].clone(), aggregate))
+        .collect();
+    writeln!(
+        out,
+        "\n{} groups; all four agree: {}",
+        by_columns.store.len(),
+        from_codes == expected
+            && by_columns.to_map(&columns.dictionary) == expected
+            && by_sorted.to_map(&sorted_columns.dictionary) == expected
+    )?;
+
+    let best: usize = (0..by_columns.store.len())
+        .max_by_key(|&group| by_columns.revenue_cents[group])
+        .expect("there are sales");
+    writeln!(
+        out,
+        "Top store by revenue: {} with ${:.2} over {} sales",
+        columns.dictionary[by_columns.store[best] as usize],
+        by_columns.revenue_cents[best] as f64 / 100.0,
+        by_columns.rows[best]
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,373 @@
+// A multi-tenant service keeps one record per (tenant, user). Three layouts
+// hold the same directory:
+//
+//   HashMap<(Tenant, User), V>           flat: one table, one hash per lookup
+//   HashMap<Tenant, HashMap<User, V>>    nested: a table per tenant
+//   BTreeMap<(Tenant, User), V>          flat and sorted: tuples compare
+//                                        tenant first, so a tenant's users
+//                                        are one contiguous run of keys
+//
+// Point lookups know both halves of the key. The other queries a directory
+// answers know only the tenant ("list this tenant's users", "the next page
+// after user 40", "delete this tenant"), and each layout answers them
+// differently:
+//
+//                        flat HashMap       nested HashMap     BTreeMap<(T, U)>
+//   get(t, u)            1 hash             2 hashes           O(log n) compares
+//   users of t           scan all n         1 hash + row       O(log n) + row
+//   users of t, sorted   scan + sort        row + sort         range, in order
+//   page after (t, u)    scan + sort        row + sort         range from (t, u+1)
+//   remove tenant t      retain over all n  1 remove           extract_if(range)
+//
+// Tenants in a real directory are skewed: a few large customers and a long
+// tail of small ones. `directory` builds one like that, and the examples
+// below use the same 100 tenants.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "tenant_user_layouts",
+        "One (tenant, user) directory in three layouts",
+        tenant_user_layouts,
+    )?;
+
+    section(
+        out,
+        "partial_key_queries",
+        "Queries that know only the tenant: list, page, user range",
+        partial_key_queries,
+    )?;
+
+    section(
+        out,
+        "removing_a_tenant",
+        "Deleting every key with a given first half",
+        removing_a_tenant,
+    )?;
+
+    Ok(())
+}
+
+pub type Tenant = u32;
+pub type User = u32;
+/// Last login, in seconds since the directory started.
+pub type LastLogin = u64;
+
+/// A (tenant, user) directory, whatever its layout.
+///
+/// Every method that returns users returns them sorted by user id, so the
+/// layouts can be compared entry for entry.
+pub trait TenantDirectory {
+    fn layout(&self) -> &'static str;
+
+    fn insert(&mut self, tenant: Tenant, user: User, login: LastLogin) -> Option<LastLogin>;
+
+    fn get(&self, tenant: Tenant, user: User) -> Option<LastLogin>;
+
+    /// Every user of `tenant`, and how many entries were visited to find them.
+    fn users_of(&self, tenant: Tenant) -> (Vec<(User, LastLogin)>, usize);
+
+    /// Up to `limit` users of `tenant` with ids above `after`: one page of a
+    /// listing that resumes where the previous page ended.
+    fn page(&self, tenant: Tenant, after: Option<User>, limit: usize) -> Vec<(User, LastLogin)>;
+
+    /// Removes every user of `tenant`, returning how many there were.
+    fn remove_tenant(&mut self, tenant: Tenant) -> usize;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Users of `tenant` with ids above `after`: the first key of the next page
+/// to `(tenant, MAX)`.
+fn after_range(tenant: Tenant, after: Option<User>) -> Option<RangeInclusive<(Tenant, User)>> {
+    let first: User = match after {
+        Some(User::MAX) => return None,
+        Some(user) => user + 1,
+        None => 0,
+    };
+    Some((tenant, first)..=(tenant, User::MAX))
+}
+
+/// The page `limit` users long that follows `after`, from unsorted users.
+fn page_of(
+    mut users: Vec<(User, LastLogin)>,
+    after: Option<User>,
+    limit: usize,
+) -> Vec<(User, LastLogin)> {
+    users.retain(|&(user, _)| after.is_none_or(|after| user > after));
+    users.sort_unstable();
+    users.truncate(limit);
+    users
+}
+
+impl TenantDirectory for HashMap<(Tenant, User), LastLogin> {
+    fn layout(&self) -> &'static str {
+        "HashMap<(T, U), V>"
+    }
+
+    fn insert(&mut self, tenant: Tenant, user: User, login: LastLogin) -> Option<LastLogin> {
+        HashMap::insert(self, (tenant, user), login)
+    }
+
+    fn get(&self, tenant: Tenant, user: User) -> Option<LastLogin> {
+        HashMap::get(self, &(tenant, user)).copied()
+    }
+
+    fn users_of(&self, tenant: Tenant) -> (Vec<(User, LastLogin)>, usize) {
+        // No way to find a tenant's keys except looking at all of them
+        let mut users: Vec<(User, LastLogin)> = self
+            .iter()
+            .filter(|&(&(t, _), _)| t == tenant)
+            .map(|(&(_, user), &login)| (user, login))
+            .collect();
+        users.sort_unstable();
+        (users, HashMap::len(self))
+    }
+
+    fn page(&self, tenant: Tenant, after: Option<User>, limit: usize) -> Vec<(User, LastLogin)> {
+        page_of(self.users_of(tenant).0, after, limit)
+    }
+
+    fn remove_tenant(&mut self, tenant: Tenant) -> usize {
+        let before: usize = HashMap::len(self);
+        self.retain(|&(t, _), _| t != tenant);
+        before - HashMap::len(self)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+impl TenantDirectory for HashMap<Tenant, HashMap<User, LastLogin>> {
+    fn layout(&self) -> &'static str {
+        "HashMap<T, HashMap<U, V>>"
+    }
+
+    fn insert(&mut self, tenant: Tenant, user: User, login: LastLogin) -> Option<LastLogin> {
+        self.entry(tenant).or_default().insert(user, login)
+    }
+
+    fn get(&self, tenant: Tenant, user: User) -> Option<LastLogin> {
+        HashMap::get(self, &tenant)?.get(&user).copied()
+    }
+
+    fn users_of(&self, tenant: Tenant) -> (Vec<(User, LastLogin)>, usize) {
+        let Some(row) = HashMap::get(self, &tenant) else {
+            return (Vec::new(), 0);
+        };
+        let mut users: Vec<(User, LastLogin)> = row.iter().map(|(&u, &l)| (u, l)).collect();
+        users.sort_unstable();
+        (users, row.len())
+    }
+
+    fn page(&self, tenant: Tenant, after: Option<User>, limit: usize) -> Vec<(User, LastLogin)> {
+        page_of(self.users_of(tenant).0, after, limit)
+    }
+
+    fn remove_tenant(&mut self, tenant: Tenant) -> usize {
+        self.remove(&tenant).map_or(0, |row| row.len())
+    }
+
+    fn len(&self) -> usize {
+        self.values().map(HashMap::len).sum()
+    }
+}
+
+impl TenantDirectory for BTreeMap<(Tenant, User), LastLogin> {
+    fn layout(&self) -> &'static str {
+        "BTreeMap<(T, U), V>"
+    }
+
+    fn insert(&mut self, tenant: Tenant, user: User, login: LastLogin) -> Option<LastLogin> {
+        BTreeMap::insert(self, (tenant, user), login)
+    }
+
+    fn get(&self, tenant: Tenant, user: User) -> Option<LastLogin> {
+        BTreeMap::get(self, &(tenant, user)).copied()
+    }
+
+    fn users_of(&self, tenant: Tenant) -> (Vec<(User, LastLogin)>, usize) {
+        // (tenant, 0) and (tenant, MAX) bracket the tenant's run of keys,
+        // which comes back already sorted by user
+        let users: Vec<(User, LastLogin)> = self
+            .range((tenant, 0)..=(tenant, User::MAX))
+            .map(|(&(_, user), &login)| (user, login))
+            .collect();
+        let visited: usize = users.len();
+        (users, visited)
+    }
+
+    fn page(&self, tenant: Tenant, after: Option<User>, limit: usize) -> Vec<(User, LastLogin)> {
+        let Some(range) = after_range(tenant, after) else {
+            return Vec::new();
+        };
+        self.range(range)
+            .take(limit)
+            .map(|(&(_, user), &login)| (user, login))
+            .collect()
+    }
+
+    fn remove_tenant(&mut self, tenant: Tenant) -> usize {
+        self.extract_if((tenant, 0)..=(tenant, User::MAX), |_, _| true)
+            .count()
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+}
+
+/// A directory of `tenants` tenants with skewed sizes: tenant `t` has
+/// `2000 / (t + 1) + 5` users, so tenant 0 has 2005 and most have a few
+/// dozen.
+/// User ids within a tenant are spread out, not consecutive.
+pub fn directory(tenants: u32, seed: u64) -> Vec<((Tenant, User), LastLogin)> {
+    let mut rng: Lcg = Lcg::new(seed);
+    let mut entries: Vec<((Tenant, User), LastLogin)> = Vec::new();
+    for tenant in 0..tenants {
+        let users: u32 = 2_000 / (tenant + 1) + 5;
+        for n in 0..users {
+            let user: User = n * 16 + rng.below(16) as u32;
+            entries.push(((tenant, user), rng.below(86_400)));
+        }
+    }
+    // Records arrive in no particular order
+    for i in (1..entries.len()).rev() {
+        entries.swap(i, rng.below(i as u64 + 1) as usize);
+    }
+    entries
+}
+
+/// The three layouts, each filled with `entries`.
+pub fn layouts(entries: &[((Tenant, User), LastLogin)]) -> Vec<Box<dyn TenantDirectory>> {
+    let mut directories: Vec<Box<dyn TenantDirectory>> = vec![
+        Box::new(HashMap::<(Tenant, User), LastLogin>::new()),
+        Box::new(HashMap::<Tenant, HashMap<User, LastLogin>>::new()),
+        Box::new(BTreeMap::<(Tenant, User), LastLogin>::new()),
+    ];
+    for directory in &mut directories {
+        for &((tenant, user), login) in entries {
+            directory.insert(tenant, user, login);
+        }
+    }
+    directories
+}
+
+/// Demonstrates the three layouts holding the same directory and answering
+/// a point lookup alike.
+pub fn tenant_user_layouts(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Tenant/User Layouts")?;
+
+    let entries: Vec<((Tenant, User), LastLogin)> = directory(100, 1);
+    let directories: Vec<Box<dyn TenantDirectory>> = layouts(&entries);
+    let (tenant, user) = entries[0].0;
+    writeln!(
+        out,
+        "{} users across 100 tenants; tenant 0 has {}, tenant 99 has {}",
+        entries.len(),
+        directories[2].users_of(0).0.len(),
+        directories[2].users_of(99).0.len()
+    )?;
+    writeln!(out, "get({}, {}):", tenant, user)?;
+    for directory in &directories {
+        writeln!(
+            out,
+            "  {:<26} {:?} ({} entries)",
+            directory.layout(),
+            directory.get(tenant, user),
+            directory.len()
+        )?;
+    }
+    Ok(())
+}
+
+/// Demonstrates queries by tenant alone.
+///
+/// The flat HashMap visits every entry to list one tenant, however small.
+/// The nested map goes straight to the tenant's row but has to sort it.
+/// The BTreeMap finds the start of the tenant's run and reads it in order,
+/// which also makes paging cheap: the next page starts at `(tenant,
+/// last_user + 1)`, so no page costs more than its own length.
+pub fn partial_key_queries(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Partial Key Queries")?;
+
+    let entries: Vec<((Tenant, User), LastLogin)> = directory(100, 1);
+    let directories: Vec<Box<dyn TenantDirectory>> = layouts(&entries);
+
+    for tenant in [0, 42] {
+        writeln!(out, "Users of tenant {}:", tenant)?;
+        for directory in &directories {
+            let (users, visited) = directory.users_of(tenant);
+            writeln!(
+                out,
+                "  {:<26} {:>4} users, {:>5} entries visited",
+                directory.layout(),
+                users.len(),
+                visited
+            )?;
+        }
+    }
+
+    // Listing tenant 42 three users at a time
+    let sorted: &dyn TenantDirectory = directories[2].as_ref();
+    let mut after: Option<User> = None;
+    writeln!(out, "\nTenant 42, three users per page:")?;
+    for number in 1..=3 {
+        let page: Vec<(User, LastLogin)> = sorted.page(42, after, 3);
+        let ids: Vec<User> = page.iter().map(|&(user, _)| user).collect();
+        writeln!(out, "  page {}: {:?}", number, ids)?;
+        after = ids.last().copied();
+    }
+    let agree: bool = directories
+        .iter()
+        .all(|directory| directory.page(42, after, 3) == sorted.page(42, after, 3));
+    writeln!(out, "  page 4 is the same from every layout: {}", agree)?;
+
+    // A range of users inside one tenant: only the sorted layout can do
+    // this without touching the rest of the tenant
+    let by_key: BTreeMap<(Tenant, User), LastLogin> = entries.iter().copied().collect();
+    let band: usize = by_key.range((0, 1_000)..(0, 1_100)).count();
+    writeln!(
+        out,
+        "\nTenant 0, user ids 1000..1100: {} users via range((0, 1000)..(0, 1100))",
+        band
+    )?;
+    Ok(())
+}
+
+/// Demonstrates deleting a whole tenant.
+///
+/// The nested map drops one inner table. The flat HashMap has to retain
+/// over every entry. The BTreeMap's `extract_if` over the tenant's range
+/// walks only that tenant's keys.
+pub fn removing_a_tenant(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Removing a Tenant")?;
+
+    let entries: Vec<((Tenant, User), LastLogin)> = directory(100, 1);
+    let mut directories: Vec<Box<dyn TenantDirectory>> = layouts(&entries);
+    for directory in &mut directories {
+        let before: usize = directory.len();
+        let removed: usize = directory.remove_tenant(7);
+        writeln!(
+            out,
+            "  {:<26} removed {} users, {} -> {} entries, tenant 7 now has {}",
+            directory.layout(),
+            removed,
+            before,
+            directory.len(),
+            directory.users_of(7).0.len()
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,473 @@
+// Copy-on-write: Cow and Arc::make_mut.
+//
+// Many functions only sometimes change their input. Returning String or
+// Vec<T> makes every call allocate and copy, even when the answer is the
+// input itself. Cow (clone on write) lets the function decide per call:
+//
+//   Cow::Borrowed(&input)   nothing to change: hand the input back, no copy
+//   Cow::Owned(new)         something changed: allocate the new value
+//
+// The caller reads either one through Deref, and calls into_owned() only if
+// it really needs to keep the result. to_mut() turns a Borrowed into an
+// Owned on the first write, so a function can start out borrowing and copy
+// lazily, at the first element it has to change.
+//
+// Arc<Vec<T>> is the shared-ownership version of the same idea. Readers
+// clone the Arc (a counter increment) and hold a snapshot; a writer calls
+// Arc::make_mut, which copies the Vec only if someone else still holds it,
+// and mutates in place when the writer is alone.
+//
+// The Cow_Normalization benchmarks compare a String-returning normalizer
+// with the Cow one on log lines where 0%, 10% and 100% need a change.
+
+use demo_framework::section;
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "cow_str_normalization",
+        "Cow<str>: returning the input itself when there is nothing to fix",
+        cow_str_normalization,
+    )?;
+
+    section(
+        out,
+        "chained_cow_steps",
+        "Chaining Cow steps so a line allocates at most once per change",
+        chained_cow_steps,
+    )?;
+
+    section(
+        out,
+        "cow_slice_to_mut",
+        "Cow<[T]>: copying a slice at the first write with to_mut",
+        cow_slice_to_mut,
+    )?;
+
+    section(
+        out,
+        "arc_make_mut_snapshots",
+        "Arc<Vec<T>>: cheap snapshots, copying only when a writer isn't alone",
+        arc_make_mut_snapshots,
+    )?;
+
+    section(
+        out,
+        "practical_log_normalization",
+        "Practical demo: normalizing log lines and counting the copies avoided",
+        practical_log_normalization,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Cow<str>
+// ---------------------------------------------------------------------------
+
+/// Trims the line and collapses every run of whitespace into one space.
+/// Borrows the input when it is already in that form.
+pub fn collapse_whitespace(line: &str) -> Cow<'_, str> {
+    let trimmed: &str = line.trim_ascii();
+    let clean: bool = !trimmed.contains("  ")
+        && !trimmed
+            .bytes()
+            .any(|b| b.is_ascii_whitespace() && b != b' ');
+    if clean {
+        return Cow::Borrowed(trimmed);
+    }
+    Cow::Owned(
+        trimmed
+            .split_ascii_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" "),
+    )
+}
+
+/// ASCII-lowercases the line, borrowing it when it has no uppercase.
+pub fn lowercase_ascii(line: &str) -> Cow<'_, str> {
+    if line.bytes().any(|b| b.is_ascii_uppercase()) {
+        Cow::Owned(line.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(line)
+    }
+}
+
+/// Replaces control characters (tabs excepted) with `?`, borrowing the
+/// line when it has none.
+pub fn strip_control(line: &str) -> Cow<'_, str> {
+    if line.chars().any(|c| c.is_control() && c != '\t') {
+        Cow::Owned(
+            line.chars()
+                .map(|c| if c.is_control() && c != '\t' { '?' } else { c })
+                .collect(),
+        )
+    } else {
+        Cow::Borrowed(line)
+    }
+}
+
+/// One normalization step.
+pub type Step = for<'a> fn(&'a str) -> Cow<'a, str>;
+
+/// The steps `normalize` runs, in order.
+pub const NORMALIZE_STEPS: [Step; 3] = [strip_control, collapse_whitespace, lowercase_ascii];
+
+/// Runs `steps` over `line`. A step that borrows keeps the previous result
+/// as it is; a step that changes a borrowed result allocates, and a step
+/// that changes an owned one replaces it. A line no step touches is
+/// returned borrowed, without a single allocation.
+pub fn apply_steps<'a>(line: &'a str, steps: &[Step]) -> Cow<'a, str> {
+    let mut result: Cow<'a, str> = Cow::Borrowed(line);
+    for step in steps {
+        result = match result {
+            Cow::Borrowed(text) => step(text),
+            Cow::Owned(text) => {
+                let changed: Option<String> = match step(&text) {
+                    Cow::Borrowed(same) if same.len() == text.len() => None,
+                    Cow::Borrowed(part) => Some(part.to_string()),
+                    Cow::Owned(new) => Some(new),
+                };
+                Cow::Owned(changed.unwrap_or(text))
+            }
+        };
+    }
+    result
+}
+
+/// Normalizes a log line: control characters, whitespace, case.
+pub fn normalize(line: &str) -> Cow<'_, str> {
+    apply_steps(line, &NORMALIZE_STEPS)
+}
+
+/// The same normalization, always returning a new `String`: the signature
+/// many APIs start with.
+pub fn normalize_to_string(line: &str) -> String {
+    let cleaned: String = line
+        .chars()
+        .map(|c| if c.is_control() && c != '\t' { '?' } else { c })
+        .collect();
+    cleaned
+        .split_ascii_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_ascii_lowercase()
+}
+
+/// `count` log lines, of which every `1 / dirty_every`th needs normalizing
+/// (mixed case, doubled spaces or a stray tab). `dirty_every` 0 means none.
+pub fn sample_log_lines(count: usize, dirty_every: usize) -> Vec<String> {
+    const LEVELS: [&str; 3] = ["info", "warn", "error"];
+    (0..count)
+        .map(|i| {
+            let clean: String = format!(
+                "{} request {} served in {} ms by worker {}",
+                LEVELS[i % LEVELS.len()],
+                i,
+                (i * 37) % 500,
+                i % 8
+            );
+            if dirty_every == 0 || i % dirty_every != 0 {
+                return clean;
+            }
+            match (i / dirty_every) % 3 {
+                0 => clean.to_uppercase(),
+                1 => clean.replace(" in ", "  in\t"),
+                _ => format!("  {clean} "),
+            }
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Cow<[T]>
+// ---------------------------------------------------------------------------
+
+/// Clamps every reading into `min..=max`. The slice is borrowed until the
+/// first out-of-range value; `to_mut` copies it there, once, and the rest
+/// of the writes go to the copy.
+pub fn clamp_readings(readings: &[i32], min: i32, max: i32) -> Cow<'_, [i32]> {
+    let mut result: Cow<[i32]> = Cow::Borrowed(readings);
+    for (i, &value) in readings.iter().enumerate() {
+        let clamped: i32 = value.clamp(min, max);
+        if clamped != value {
+            result.to_mut()[i] = clamped;
+        }
+    }
+    result
+}
+
+// ---------------------------------------------------------------------------
+// Arc<Vec<T>>
+// ---------------------------------------------------------------------------
+
+/// A list that readers snapshot and a single owner updates. Snapshots are
+/// `Arc` clones; an update copies the list only while a snapshot of the
+/// current version is still alive.
+#[derive(Debug)]
+pub struct SharedList<T> {
+    items: Arc<Vec<T>>,
+    /// Updates that had to copy the list.
+    pub copies: usize,
+}
+
+impl<T: Clone> SharedList<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        SharedList {
+            items: Arc::new(items),
+            copies: 0,
+        }
+    }
+
+    /// The current version, shared: no copy, just a counter increment.
+    pub fn snapshot(&self) -> Arc<Vec<T>> {
+        Arc::clone(&self.items)
+    }
+
+    /// Changes the list through `Arc::make_mut`. Returns true if that had to
+    /// copy it because a snapshot still shares the current version.
+    pub fn update(&mut self, change: impl FnOnce(&mut Vec<T>)) -> bool {
+        let before: *const Vec<T> = Arc::as_ptr(&self.items);
+        change(Arc::make_mut(&mut self.items));
+        let copied: bool = !std::ptr::eq(before, Arc::as_ptr(&self.items));
+        if copied {
+            self.copies += 1;
+        }
+        copied
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+#[allow(clippy::ptr_arg)] // Reports which variant it is, so it needs the Cow itself
+fn kind<T: ?Sized + ToOwned>(cow: &Cow<'_, T>) -> &'static str {
+    match cow {
+        Cow::Borrowed(_) => "Borrowed",
+        Cow::Owned(_) => "Owned",
+    }
+}
+
+/// Demonstrates a `Cow<str>` function: each input comes back borrowed when
+/// it is already clean, and only the ones that change cost an allocation.
+pub fn cow_str_normalization(out: &mut dyn Write) -> io::Result<()> {
+    let inputs: [&str; 4] = [
+        "disk full on /var",
+        "  disk full on /var  ",
+        "disk   full\ton /var",
+        "",
+    ];
+    writeln!(out, "\n  collapse_whitespace(line) -> Cow<str>:")?;
+    for input in inputs {
+        let result: Cow<str> = collapse_whitespace(input);
+        writeln!(
+            out,
+            "    {:<26} -> {:<8} {:?}",
+            format!("{input:?}"),
+            kind(&result),
+            result
+        )?;
+    }
+    writeln!(
+        out,
+        "\n  Trimming alone stays Borrowed: trim_ascii() returns a subslice of the input."
+    )?;
+    writeln!(
+        out,
+        "  The caller uses the result as a &str either way, and pays for into_owned()"
+    )?;
+    writeln!(out, "  only if it needs a String to keep.")?;
+    Ok(())
+}
+
+/// Demonstrates composing `Cow` steps: a step that changes nothing keeps
+/// the previous result, so a clean line never allocates and a dirty one
+/// allocates only in the steps that change it.
+pub fn chained_cow_steps(out: &mut dyn Write) -> io::Result<()> {
+    let lines: [&str; 4] = [
+        "warn cache miss for key 7",
+        "WARN cache miss for key 7",
+        "warn  cache\u{7}miss for key 7",
+        "WARN  Cache miss\u{1b} for key 7",
+    ];
+    writeln!(
+        out,
+        "\n  {:<38} {:>8} {:>9} {:>9}  result",
+        "line", "control", "spaces", "case"
+    )?;
+    for line in lines {
+        let mut changed: Vec<&str> = Vec::new();
+        let mut current: String = line.to_string();
+        for step in NORMALIZE_STEPS {
+            let next: Cow<str> = step(&current);
+            changed.push(kind(&next));
+            current = next.into_owned();
+        }
+        let result: Cow<str> = normalize(line);
+        writeln!(
+            out,
+            "  {:<38} {:>8} {:>9} {:>9}  {:<8} {:?}",
+            format!("{line:?}"),
+            changed[0],
+            changed[1],
+            changed[2],
+            kind(&result),
+            result
+        )?;
+    }
+    writeln!(
+        out,
+        "\n  A line needs one allocation per step that changes it, and none at all"
+    )?;
+    writeln!(
+        out,
+        "  if it is clean. normalize_to_string allocates four times for every line."
+    )?;
+    Ok(())
+}
+
+/// Demonstrates `Cow<[T]>` with `to_mut`: the copy happens at the first
+/// value that has to change, and later writes reuse it.
+pub fn cow_slice_to_mut(out: &mut dyn Write) -> io::Result<()> {
+    let batches: [&[i32]; 3] = [&[12, 40, 77, 3], &[12, -5, 77, 140], &[]];
+    writeln!(out, "\n  clamp_readings(batch, 0, 100):")?;
+    for batch in batches {
+        let clamped: Cow<[i32]> = clamp_readings(batch, 0, 100);
+        let same_memory: bool = std::ptr::eq(clamped.as_ptr(), batch.as_ptr());
+        writeln!(
+            out,
+            "    {:<22} -> {:<8} {:<22} same buffer: {same_memory}",
+            format!("{batch:?}"),
+            kind(&clamped),
+            format!("{:?}", &*clamped)
+        )?;
+    }
+    writeln!(
+        out,
+        "\n  The second batch copies once, at -5; the write at 140 goes to that copy."
+    )?;
+
+    let mut owned: Cow<[i32]> = Cow::Owned(vec![1, 2, 3]);
+    let before: *const i32 = owned.as_ptr();
+    owned.to_mut().push(4);
+    writeln!(
+        out,
+        "  to_mut() on an Owned Cow is free: {:?}, kept its buffer: {}",
+        &*owned,
+        std::ptr::eq(before, owned.as_ptr())
+    )?;
+    Ok(())
+}
+
+/// Demonstrates `Arc::make_mut` as copy-on-write for shared collections: an
+/// update copies only while a reader holds the current version.
+pub fn arc_make_mut_snapshots(out: &mut dyn Write) -> io::Result<()> {
+    let mut blocklist: SharedList<&str> = SharedList::new(vec!["10.0.0.8", "10.0.0.9"]);
+
+    let copied: bool = blocklist.update(|list| list.push("10.0.0.23"));
+    writeln!(
+        out,
+        "\n  No readers, push: copied = {copied}, {} entries",
+        blocklist.len()
+    )?;
+
+    let reader: Arc<Vec<&str>> = blocklist.snapshot();
+    writeln!(
+        out,
+        "  A reader takes a snapshot: strong_count = {}",
+        Arc::strong_count(&reader)
+    )?;
+    let copied: bool = blocklist.update(|list| list.retain(|ip| *ip != "10.0.0.8"));
+    writeln!(
+        out,
+        "  Remove while the reader holds it: copied = {copied}; reader still sees {:?}",
+        reader
+    )?;
+    let copied: bool = blocklist.update(|list| list.push("10.0.0.42"));
+    writeln!(
+        out,
+        "  Another update: copied = {copied}, the new version is unshared already"
+    )?;
+
+    drop(reader);
+    let latest: Arc<Vec<&str>> = blocklist.snapshot();
+    writeln!(
+        out,
+        "\n  Latest version: {:?} after {} copy in 3 updates",
+        latest, blocklist.copies
+    )?;
+    writeln!(
+        out,
+        "  Readers never block the writer and never see a half-made change. The"
+    )?;
+    writeln!(
+        out,
+        "  price is one full copy per version that a reader is still holding."
+    )?;
+    Ok(())
+}
+
+/// Practical example: normalize a batch of log lines with both functions and
+/// count the Strings each one creates.
+pub fn practical_log_normalization(out: &mut dyn Write) -> io::Result<()> {
+    let lines: Vec<String> = sample_log_lines(10_000, 10);
+    writeln!(out, "\n  {} log lines, for example:", lines.len())?;
+    for line in &lines[..3] {
+        writeln!(out, "    {line:?}")?;
+    }
+
+    let mut owned: usize = 0;
+    let mut bytes_copied: usize = 0;
+    for line in &lines {
+        let normalized: Cow<str> = normalize(line);
+        assert_eq!(normalized, normalize_to_string(line));
+        if let Cow::Owned(text) = &normalized {
+            owned += 1;
+            bytes_copied += text.len();
+        }
+    }
+    let total_bytes: usize = lines.iter().map(String::len).sum();
+
+    writeln!(
+        out,
+        "\n  {:<22} {:>14} {:>14}",
+        "", "Strings made", "bytes copied"
+    )?;
+    writeln!(
+        out,
+        "  {:<22} {:>14} {:>14}",
+        "normalize_to_string",
+        lines.len(),
+        total_bytes
+    )?;
+    writeln!(
+        out,
+        "  {:<22} {:>14} {:>14}",
+        "normalize (Cow)", owned, bytes_copied
+    )?;
+    writeln!(
+        out,
+        "\n  Only the lines that needed a change were copied, and the dirty lines that"
+    )?;
+    writeln!(
+        out,
+        "  only needed trimming came back Borrowed too. The Cow_Normalization"
+    )?;
+    writeln!(
+        out,
+        "  benchmark times both at 0%, 10% and 100% dirty lines."
+    )?;
+    Ok(())
+}
@@ -0,0 +1,719 @@
+// The same doubly linked list, written twice:
+//
+//   RcList<T>   safe Rust. Each node is an Rc<RefCell<..>>: `next` links are
+//               strong, `prev` links are Weak so the two directions don't
+//               form a reference cycle that never gets freed.
+//
+//   RawList<T>  unsafe Rust, the way std's LinkedList is built. Nodes are
+//               Boxes turned into raw pointers, and the list frees them
+//               itself in pop and Drop.
+//
+//             head                                   tail
+//              │                                      │
+//              ▼                                      ▼
+//           ┌──────┐ next ┌──────┐ next ┌──────┐
+//   None ◄──│  1   │─────►│  2   │─────►│  3   │──► None
+//           │      │◄─────│      │◄─────│      │
+//           └──────┘ prev └──────┘ prev └──────┘
+//
+// Both offer the same API: push and pop at either end, peek, O(1) append
+// and a walk from front to back. Writing them shows what std's LinkedList
+// already pays for: an allocation per element, two pointers per element,
+// and - for the safe version - reference counts and a borrow flag on top.
+// Run the tests under Miri (`cargo +nightly miri test --test
+// custom_linked_list`) to check the unsafe one for leaks, double frees and
+// dangling pointers.
+
+use demo_framework::section;
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::{LinkedList, VecDeque};
+use std::fmt;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::ptr::NonNull;
+use std::rc::{Rc, Weak};
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "rc_refcell_list",
+        "Safe version: Rc<RefCell> nodes with Weak back links",
+        rc_refcell_list,
+    )?;
+
+    section(
+        out,
+        "raw_pointer_list",
+        "Unsafe version: Box-allocated nodes behind raw pointers",
+        raw_pointer_list,
+    )?;
+
+    section(
+        out,
+        "what_it_costs",
+        "What a node costs, and how both compare with std",
+        what_it_costs,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// RcList: Rc<RefCell<..>> nodes
+// ---------------------------------------------------------------------------
+
+type Link<T> = Option<Rc<RefCell<RcNode<T>>>>;
+
+struct RcNode<T> {
+    value: T,
+    next: Link<T>,
+    prev: Option<Weak<RefCell<RcNode<T>>>>,
+}
+
+/// A doubly linked list in safe Rust.
+///
+/// The list holds strong references to both ends, and every node holds a
+/// strong reference to the next one, so the head has one owner and every
+/// other node two at most (the previous node, and the list if it is the
+/// tail). Back links are `Weak`, and popping a node checks that the list
+/// held the last strong reference before unwrapping it.
+pub struct RcList<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+}
+
+impl<T> RcList<T> {
+    pub fn new() -> Self {
+        RcList {
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        let node: Rc<RefCell<RcNode<T>>> = Rc::new(RefCell::new(RcNode {
+            value,
+            next: None,
+            prev: None,
+        }));
+        match self.head.take() {
+            Some(old) => {
+                old.borrow_mut().prev = Some(Rc::downgrade(&node));
+                node.borrow_mut().next = Some(old);
+            }
+            None => self.tail = Some(Rc::clone(&node)),
+        }
+        self.head = Some(node);
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let node: Rc<RefCell<RcNode<T>>> = Rc::new(RefCell::new(RcNode {
+            value,
+            next: None,
+            prev: None,
+        }));
+        match self.tail.take() {
+            Some(old) => {
+                node.borrow_mut().prev = Some(Rc::downgrade(&old));
+                old.borrow_mut().next = Some(Rc::clone(&node));
+            }
+            None => self.head = Some(Rc::clone(&node)),
+        }
+        self.tail = Some(node);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let old: Rc<RefCell<RcNode<T>>> = self.head.take()?;
+        let next: Link<T> = old.borrow_mut().next.take();
+        match next {
+            Some(next) => {
+                next.borrow_mut().prev = None;
+                self.head = Some(next);
+            }
+            None => self.tail = None,
+        }
+        self.len -= 1;
+        Some(Self::into_value(old))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let old: Rc<RefCell<RcNode<T>>> = self.tail.take()?;
+        let prev: Link<T> = old.borrow_mut().prev.take().and_then(|weak| weak.upgrade());
+        match prev {
+            Some(prev) => {
+                // Drops the previous node's strong reference to `old`
+                prev.borrow_mut().next = None;
+                self.tail = Some(prev);
+            }
+            None => self.head = None,
+        }
+        self.len -= 1;
+        Some(Self::into_value(old))
+    }
+
+    /// The front value. It comes back inside a `Ref` guard: the value
+    /// lives in a `RefCell`, so a plain `&T` can't escape it.
+    pub fn peek_front(&self) -> Option<Ref<'_, T>> {
+        self.head
+            .as_ref()
+            .map(|node| Ref::map(node.borrow(), |node| &node.value))
+    }
+
+    pub fn peek_back(&self) -> Option<Ref<'_, T>> {
+        self.tail
+            .as_ref()
+            .map(|node| Ref::map(node.borrow(), |node| &node.value))
+    }
+
+    pub fn peek_front_mut(&mut self) -> Option<RefMut<'_, T>> {
+        self.head
+            .as_ref()
+            .map(|node| RefMut::map(node.borrow_mut(), |node| &mut node.value))
+    }
+
+    /// Moves every element of `other` onto the back of this list in O(1).
+    pub fn append(&mut self, other: &mut Self) {
+        match (self.tail.take(), other.head.take()) {
+            (Some(tail), Some(head)) => {
+                head.borrow_mut().prev = Some(Rc::downgrade(&tail));
+                tail.borrow_mut().next = Some(head);
+                self.tail = other.tail.take();
+            }
+            (None, head) => {
+                self.head = head;
+                self.tail = other.tail.take();
+            }
+            (tail, None) => self.tail = tail,
+        }
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    /// Calls `f` on each value from front to back. There is no `iter()`
+    /// returning `&T`: each step clones an `Rc` and borrows a `RefCell`,
+    /// and a reference can't outlive that borrow.
+    pub fn for_each(&self, mut f: impl FnMut(&T)) {
+        let mut current: Link<T> = self.head.clone();
+        while let Some(node) = current {
+            let node = node.borrow();
+            f(&node.value);
+            current = node.next.clone();
+        }
+    }
+
+    /// The (strong, weak) reference counts of every node, front to back.
+    pub fn ref_counts(&self) -> Vec<(usize, usize)> {
+        let mut counts: Vec<(usize, usize)> = Vec::with_capacity(self.len);
+        let mut current: Link<T> = self.head.clone();
+        while let Some(node) = current {
+            // Minus the clone held by `current` itself
+            counts.push((Rc::strong_count(&node) - 1, Rc::weak_count(&node)));
+            current = node.borrow().next.clone();
+        }
+        counts
+    }
+
+    /// The value of an unlinked node. The list held its last strong
+    /// reference, so unwrapping can't fail unless a link was left behind.
+    fn into_value(node: Rc<RefCell<RcNode<T>>>) -> T {
+        match Rc::try_unwrap(node) {
+            Ok(cell) => cell.into_inner().value,
+            Err(_) => panic!("an unlinked node still has another strong reference"),
+        }
+    }
+}
+
+impl<T: Clone> RcList<T> {
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut values: Vec<T> = Vec::with_capacity(self.len);
+        self.for_each(|value| values.push(value.clone()));
+        values
+    }
+}
+
+impl<T> Default for RcList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for RcList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list: RcList<T> = RcList::new();
+        for value in iter {
+            list.push_back(value);
+        }
+        list
+    }
+}
+
+impl<T> Drop for RcList<T> {
+    /// Unlinks one node at a time. The derived drop would free `head`,
+    /// which frees its `next`, which frees its `next`... one stack frame
+    /// per node, enough to overflow the stack on a long list.
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RcList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        self.for_each(|value| {
+            list.entry(value);
+        });
+        list.finish()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// RawList: raw pointers
+// ---------------------------------------------------------------------------
+
+type RawLink<T> = Option<NonNull<RawNode<T>>>;
+
+struct RawNode<T> {
+    value: T,
+    next: RawLink<T>,
+    prev: RawLink<T>,
+}
+
+/// A doubly linked list built on raw pointers.
+///
+/// Every node is allocated with `Box` and leaked into a pointer on push,
+/// and turned back into a `Box` exactly once: when it is popped, or when
+/// the list is dropped. Between the two, the list is the only owner, and
+/// `&self` / `&mut self` on the list stand in for borrows of the nodes.
+pub struct RawList<T> {
+    head: RawLink<T>,
+    tail: RawLink<T>,
+    len: usize,
+    /// Tells the drop checker that the list owns `T`s.
+    _owns: PhantomData<Box<RawNode<T>>>,
+}
+
+impl<T> RawList<T> {
+    pub fn new() -> Self {
+        RawList {
+            head: None,
+            tail: None,
+            len: 0,
+            _owns: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        let node: NonNull<RawNode<T>> = NonNull::from(Box::leak(Box::new(RawNode {
+            value,
+            next: self.head,
+            prev: None,
+        })));
+        match self.head {
+            // SAFETY: `old` is a live node owned by this list, and `&mut
+            // self` means nothing else is looking at it
+            Some(old) => unsafe { (*old.as_ptr()).prev = Some(node) },
+            None => self.tail = Some(node),
+        }
+        self.head = Some(node);
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let node: NonNull<RawNode<T>> = NonNull::from(Box::leak(Box::new(RawNode {
+            value,
+            next: None,
+            prev: self.tail,
+        })));
+        match self.tail {
+            // SAFETY: as in push_front
+            Some(old) => unsafe { (*old.as_ptr()).next = Some(node) },
+            None => self.head = Some(node),
+        }
+        self.tail = Some(node);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let old: NonNull<RawNode<T>> = self.head?;
+        // SAFETY: `old` came from Box::leak in a push, and unlinking it
+        // below means no pointer to it is left for a second from_raw
+        let old: Box<RawNode<T>> = unsafe { Box::from_raw(old.as_ptr()) };
+        self.head = old.next;
+        match self.head {
+            // SAFETY: the new head is a live node owned by this list
+            Some(new) => unsafe { (*new.as_ptr()).prev = None },
+            None => self.tail = None,
+        }
+        self.len -= 1;
+        Some(old.value)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let old: NonNull<RawNode<T>> = self.tail?;
+        // SAFETY: as in pop_front
+        let old: Box<RawNode<T>> = unsafe { Box::from_raw(old.as_ptr()) };
+        self.tail = old.prev;
+        match self.tail {
+            // SAFETY: the new tail is a live node owned by this list
+            Some(new) => unsafe { (*new.as_ptr()).next = None },
+            None => self.head = None,
+        }
+        self.len -= 1;
+        Some(old.value)
+    }
+
+    pub fn peek_front(&self) -> Option<&T> {
+        // SAFETY: the node lives as long as it stays in the list, which
+        // `&self` guarantees for the returned lifetime
+        self.head.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn peek_back(&self) -> Option<&T> {
+        // SAFETY: as in peek_front
+        self.tail.map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn peek_front_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: as in peek_front, and `&mut self` makes the borrow unique
+        self.head.map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    /// Moves every element of `other` onto the back of this list in O(1).
+    pub fn append(&mut self, other: &mut Self) {
+        match (self.tail, other.head) {
+            // SAFETY: both are live nodes, one owned by each list
+            (Some(tail), Some(head)) => unsafe {
+                (*tail.as_ptr()).next = Some(head);
+                (*head.as_ptr()).prev = Some(tail);
+                self.tail = other.tail;
+            },
+            (None, _) => {
+                self.head = other.head;
+                self.tail = other.tail;
+            }
+            (Some(_), None) => {}
+        }
+        self.len += other.len;
+        // `other` must forget the nodes, or both lists would free them
+        other.head = None;
+        other.tail = None;
+        other.len = 0;
+    }
+
+    pub fn iter(&self) -> RawIter<'_, T> {
+        RawIter {
+            front: self.head,
+            back: self.tail,
+            remaining: self.len,
+            _list: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for RawList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for RawList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list: RawList<T> = RawList::new();
+        for value in iter {
+            list.push_back(value);
+        }
+        list
+    }
+}
+
+impl<T> Drop for RawList<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+// SAFETY: the list owns its values outright, like a Vec<T>; no node is
+// shared with anything outside it. Raw pointers opt out of both by default.
+unsafe impl<T: Send> Send for RawList<T> {}
+unsafe impl<T: Sync> Sync for RawList<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for RawList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Borrowing iterator over a [`RawList`], from either end.
+pub struct RawIter<'a, T> {
+    front: RawLink<T>,
+    back: RawLink<T>,
+    /// Stops the two ends from walking past each other.
+    remaining: usize,
+    _list: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for RawIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.front.map(|node| {
+            // SAFETY: the iterator borrows the list for 'a, so the node
+            // stays alive and unchanged for 'a
+            let node: &'a RawNode<T> = unsafe { &*node.as_ptr() };
+            self.front = node.next;
+            self.remaining -= 1;
+            &node.value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for RawIter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.back.map(|node| {
+            // SAFETY: as in next
+            let node: &'a RawNode<T> = unsafe { &*node.as_ptr() };
+            self.back = node.prev;
+            self.remaining -= 1;
+            &node.value
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for RawIter<'_, T> {}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// Demonstrates the safe list: ownership is shared and counted at run
+/// time, so peeks hand out `Ref` guards instead of references, and the
+/// (strong, weak) counts show who owns each node. The price is a count
+/// pair and a borrow flag per node, checked on every access.
+pub fn rc_refcell_list(out: &mut dyn Write) -> io::Result<()> {
+    let mut list: RcList<i32> = RcList::new();
+    list.push_back(2);
+    list.push_back(3);
+    list.push_front(1);
+    writeln!(out, "After pushes: {:?} (len {})", list, list.len())?;
+
+    // Peeking hands out a RefCell guard, not a plain reference
+    if let Some(front) = list.peek_front() {
+        writeln!(out, "peek_front() -> Ref guard holding {}", *front)?;
+    }
+    if let Some(mut front) = list.peek_front_mut() {
+        *front *= 10;
+    }
+    writeln!(out, "After *peek_front_mut() *= 10: {:?}", list)?;
+
+    // The head is owned by the list; inner nodes by the node before them;
+    // the tail by both. Each Weak is a back link from the next node.
+    writeln!(out, "(strong, weak) per node: {:?}", list.ref_counts())?;
+
+    let mut other: RcList<i32> = [4, 5].into_iter().collect();
+    list.append(&mut other);
+    writeln!(out, "After append: {:?}, other: {:?}", list, other)?;
+
+    writeln!(
+        out,
+        "pop_front: {:?}, pop_back: {:?}, left: {:?}",
+        list.pop_front(),
+        list.pop_back(),
+        list
+    )?;
+
+    let mut sum: i32 = 0;
+    list.for_each(|value| sum += value);
+    writeln!(out, "for_each sum: {}", sum)?;
+
+    Ok(())
+}
+
+/// Demonstrates the unsafe list: the list alone owns every node, so peeks
+/// and iterators return plain references tied to `&self`, with no counts
+/// or borrow flags. In exchange, the compiler no longer checks that each
+/// node is freed exactly once; `pop` and `Drop` have to get that right.
+pub fn raw_pointer_list(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Raw Pointer List ---")?;
+
+    let mut list: RawList<i32> = RawList::new();
+    list.push_back(2);
+    list.push_back(3);
+    list.push_front(1);
+    writeln!(out, "After pushes: {:?} (len {})", list, list.len())?;
+
+    // Plain references this time: &self stands in for borrowing the nodes
+    writeln!(
+        out,
+        "peek_front: {:?}, peek_back: {:?}",
+        list.peek_front(),
+        list.peek_back()
+    )?;
+    if let Some(front) = list.peek_front_mut() {
+        *front *= 10;
+    }
+    writeln!(out, "After *peek_front_mut() *= 10: {:?}", list)?;
+
+    let mut other: RawList<i32> = [4, 5].into_iter().collect();
+    list.append(&mut other);
+    writeln!(out, "After append: {:?}, other: {:?}", list, other)?;
+
+    let backwards: Vec<&i32> = list.iter().rev().collect();
+    writeln!(out, "iter().rev(): {:?}", backwards)?;
+
+    writeln!(
+        out,
+        "pop_front: {:?}, pop_back: {:?}, left: {:?}",
+        list.pop_front(),
+        list.pop_back(),
+        list
+    )?;
+    writeln!(out, "iter().sum(): {}", list.iter().sum::<i32>())?;
+
+    Ok(())
+}
+
+/// Pushes `count` values onto the back, then walks them and sums.
+fn time_push_and_sum<L>(
+    count: i64,
+    mut push: impl FnMut(&mut L, i64),
+    sum: impl Fn(&L) -> i64,
+    mut list: L,
+) -> (Duration, i64) {
+    let start: Instant = Instant::now();
+    for value in 0..count {
+        push(&mut list, value);
+    }
+    let total: i64 = sum(&list);
+    (start.elapsed(), total)
+}
+
+/// Demonstrates what each version costs per element and in time, next to
+/// std's LinkedList and VecDeque.
+pub fn what_it_costs(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- What It Costs ---")?;
+
+    // An Rc allocation is the node plus the RefCell's borrow flag plus the
+    // strong and weak counts
+    let rc_node: usize = size_of::<RcNode<i64>>();
+    let rc_allocation: usize = size_of::<RefCell<RcNode<i64>>>() + 2 * size_of::<usize>();
+    let raw_node: usize = size_of::<RawNode<i64>>();
+    writeln!(out, "Bytes per i64 element (8 bytes of data):")?;
+    writeln!(
+        out,
+        "  RcList:     {} per allocation ({} node + borrow flag + 2 counts)",
+        rc_allocation, rc_node
+    )?;
+    writeln!(
+        out,
+        "  RawList:    {} per allocation (value + 2 pointers), same as LinkedList",
+        raw_node
+    )?;
+    writeln!(
+        out,
+        "  VecDeque:   {} (no per-element allocation)",
+        size_of::<i64>()
+    )?;
+
+    let count: i64 = 100_000;
+    let results: [(&str, (Duration, i64)); 4] = [
+        (
+            "RcList",
+            time_push_and_sum(
+                count,
+                RcList::push_back,
+                |list: &RcList<i64>| {
+                    let mut total: i64 = 0;
+                    list.for_each(|value| total += value);
+                    total
+                },
+                RcList::new(),
+            ),
+        ),
+        (
+            "RawList",
+            time_push_and_sum(
+                count,
+                RawList::push_back,
+                |list: &RawList<i64>| list.iter().sum(),
+                RawList::new(),
+            ),
+        ),
+        (
+            "LinkedList",
+            time_push_and_sum(
+                count,
+                LinkedList::push_back,
+                |list: &LinkedList<i64>| list.iter().sum(),
+                LinkedList::new(),
+            ),
+        ),
+        (
+            "VecDeque",
+            time_push_and_sum(
+                count,
+                VecDeque::push_back,
+                |deque: &VecDeque<i64>| deque.iter().sum(),
+                VecDeque::new(),
+            ),
+        ),
+    ];
+
+    writeln!(out, "\nPush {} values and sum them:", count)?;
+    for (name, (time, _)) in &results {
+        writeln!(out, "  {:<11} {:?}", name, time)?;
+    }
+    let first_sum: i64 = results[0].1.1;
+    writeln!(
+        out,
+        "All four sums equal: {}",
+        results.iter().all(|(_, (_, total))| *total == first_sum)
+    )?;
+
+    writeln!(out, "\nWhat building one took:")?;
+    writeln!(
+        out,
+        "  RcList:  no unsafe, but a runtime borrow check on every step, no &T iterator, and a hand-written Drop to avoid recursing once per node"
+    )?;
+    writeln!(
+        out,
+        "  RawList: every pointer write is unsafe, and a missed link or double free is undefined behaviour only Miri catches"
+    )?;
+    writeln!(
+        out,
+        "std's LinkedList is RawList with cursors, split_off and years of review; VecDeque still wins on memory and on time."
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,258 @@
+//! A mirrored model of `VecDeque`'s ring buffer, drawn slot by slot.
+//!
+//! `VecDeque` keeps its elements in one buffer of `capacity` slots, starting
+//! at a `head` index and wrapping past the last slot back to slot 0. std
+//! doesn't expose `head`, so [`RingModel`] replays the same pushes and pops
+//! with the same index math and growth policy, and [`RingModel::render`]
+//! draws the result:
+//!
+//! ```text
+//! capacity 5, len 5, head 3, tail 3, wrapped: 2 in front, 3 at the start
+//! slot    0    1    2    3    4
+//!      │ 11 │ 12 │ 13 │  4 │  5 │
+//! head                   ^
+//! tail                   ^
+//! ```
+//!
+//! `tail` is the slot one past the back element. The model only promises
+//! what can be checked from outside: the contents, `capacity()`, the split
+//! `as_slices()` returns, and [`observed_head`] read off the slice pointers
+//! of a wrapped deque.
+
+use std::collections::VecDeque;
+use std::fmt::Display;
+
+/// Logical state of a `VecDeque<T>`'s buffer: which slot holds what, where
+/// the front is and how many elements follow it.
+#[derive(Clone, Debug)]
+pub struct RingModel<T> {
+    slots: Vec<Option<T>>,
+    head: usize,
+    len: usize,
+}
+
+impl<T: Clone> RingModel<T> {
+    /// Mirrors `VecDeque::new()`: no buffer until the first push.
+    pub fn new() -> Self {
+        RingModel::with_capacity(0)
+    }
+
+    /// Mirrors `VecDeque::with_capacity(capacity)`, which allocates exactly
+    /// `capacity` slots.
+    pub fn with_capacity(capacity: usize) -> Self {
+        RingModel {
+            slots: vec![None; capacity],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Slot of the front element.
+    pub fn head(&self) -> usize {
+        self.head
+    }
+
+    /// Slot one past the back element, where `push_back` writes unless the
+    /// buffer is full.
+    pub fn tail(&self) -> usize {
+        self.physical(self.len)
+    }
+
+    /// Whether the elements run past the last slot and continue at slot 0.
+    pub fn is_wrapped(&self) -> bool {
+        self.head + self.len > self.capacity()
+    }
+
+    /// Lengths of the two slices `VecDeque::as_slices` returns.
+    pub fn slice_lens(&self) -> (usize, usize) {
+        let front: usize = self.len.min(self.capacity() - self.head);
+        (front, self.len - front)
+    }
+
+    /// Elements front to back.
+    pub fn contents(&self) -> Vec<T> {
+        (0..self.len)
+            .map(|i| self.slots[self.physical(i)].clone().expect("occupied slot"))
+            .collect()
+    }
+
+    /// Buffer slot of logical index `i`.
+    fn physical(&self, i: usize) -> usize {
+        if self.capacity() == 0 {
+            0
+        } else {
+            (self.head + i) % self.capacity()
+        }
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.grow();
+        }
+        let slot: usize = self.physical(self.len);
+        self.slots[slot] = Some(value);
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.grow();
+        }
+        self.head = (self.head + self.capacity() - 1) % self.capacity();
+        self.slots[self.head] = Some(value);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let value: Option<T> = self.slots[self.head].take();
+        self.head = self.physical(1);
+        self.len -= 1;
+        value
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let slot: usize = self.physical(self.len);
+        self.slots[slot].take()
+    }
+
+    /// Grows a full buffer the way `VecDeque` does: the capacity at least
+    /// doubles (with a small minimum), then whichever wrapped part is
+    /// shorter moves so the elements are in order again.
+    fn grow(&mut self) {
+        let old: usize = self.capacity();
+        // RawVec's smallest non-zero capacity depends on the element size
+        let minimum: usize = match size_of::<T>() {
+            1 => 8,
+            size if size <= 1024 => 4,
+            _ => 1,
+        };
+        let new: usize = (old * 2).max(old + 1).max(minimum);
+        self.slots.resize(new, None);
+
+        if self.head + self.len <= old {
+            // Not wrapped: the new slots simply follow the back
+            return;
+        }
+        let head_len: usize = old - self.head;
+        let tail_len: usize = self.len - head_len;
+        if tail_len < head_len && tail_len <= new - old {
+            // Move the wrapped start to just past the old end
+            for i in 0..tail_len {
+                self.slots[old + i] = self.slots[i].take();
+            }
+        } else {
+            // Move the front part to the end of the new buffer
+            let new_head: usize = new - head_len;
+            for i in (0..head_len).rev() {
+                self.slots[new_head + i] = self.slots[self.head + i].take();
+            }
+            self.head = new_head;
+        }
+    }
+
+    /// Whether `deque` shows everything this model claims about it.
+    pub fn matches(&self, deque: &VecDeque<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        let (front, back) = deque.as_slices();
+        deque.iter().cloned().eq(self.contents())
+            && deque.capacity() == self.capacity()
+            && (front.len(), back.len()) == self.slice_lens()
+            && observed_head(deque).is_none_or(|head| head == self.head)
+    }
+}
+
+impl<T: Clone> Default for RingModel<T> {
+    fn default() -> Self {
+        RingModel::new()
+    }
+}
+
+impl<T: Clone + Display> RingModel<T> {
+    /// Draws the buffer slot by slot, with the head and tail marked.
+    /// Empty slots show as `·`.
+    pub fn render(&self) -> String {
+        let mut summary: String = format!(
+            "capacity {}, len {}, head {}, tail {}",
+            self.capacity(),
+            self.len,
+            self.head,
+            self.tail()
+        );
+        let (front, back) = self.slice_lens();
+        if self.is_wrapped() {
+            summary += &format!(", wrapped: {} in front, {} at the start", front, back);
+        }
+        if self.capacity() == 0 {
+            return summary + "\n(no buffer)";
+        }
+
+        let labels: Vec<String> = self
+            .slots
+            .iter()
+            .map(|slot| match slot {
+                Some(value) => value.to_string(),
+                None => "·".to_string(),
+            })
+            .collect();
+        let width: usize = labels
+            .iter()
+            .map(|label| label.chars().count())
+            .chain([(self.capacity() - 1).to_string().len()])
+            .max()
+            .unwrap_or(1)
+            .max(2);
+
+        let mut numbers: String = "slot  ".to_string();
+        let mut cells: String = "     │".to_string();
+        for (i, label) in labels.iter().enumerate() {
+            numbers += &format!(" {:>width$}  ", i);
+            cells += &format!(" {:>width$} │", label);
+        }
+        let marker = |name: &str, slot: usize| -> String {
+            // Under the last character of the slot's cell
+            format!("{:<6}{}^", name, " ".repeat(slot * (width + 3) + width))
+        };
+
+        [
+            summary,
+            numbers.trim_end().to_string(),
+            cells,
+            marker("head", self.head),
+            marker("tail", self.tail()),
+        ]
+        .join("\n")
+    }
+}
+
+/// The head slot of a wrapped `deque`, worked out from where its two slices
+/// start: the second slice begins at slot 0, so the distance between the
+/// slices is the head index. `None` unless the deque is wrapped.
+pub fn observed_head<T>(deque: &VecDeque<T>) -> Option<usize> {
+    let (front, back) = deque.as_slices();
+    if back.is_empty() || size_of::<T>() == 0 {
+        return None;
+    }
+    let distance: usize = front.as_ptr() as usize - back.as_ptr() as usize;
+    Some(distance / size_of::<T>())
+}
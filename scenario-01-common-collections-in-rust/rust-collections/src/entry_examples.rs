@@ -0,0 +1,277 @@
+// The Entry API beyond or_insert.
+//
+// map.entry(key) does the hash lookup once and hands back an Entry that
+// remembers where the key lives (or where it would go):
+//
+//   Entry::Occupied(OccupiedEntry)  - key found; read, replace, or remove it
+//   Entry::Vacant(VacantEntry)      - key missing; insert, or walk away
+//
+// or_insert() and friends cover the common "get or create" case. Matching on
+// the variants directly covers everything else - conditional removal,
+// recovering the owned key, keeping a handle after an insert - still with a
+// single lookup.
+
+use demo_framework::section;
+use std::collections::HashMap;
+use std::collections::hash_map::{Entry, OccupiedEntry};
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "matching_entry_variants",
+        "Matching on Occupied/Vacant to decide per case, with one lookup",
+        matching_entry_variants,
+    )?;
+
+    section(
+        out,
+        "occupied_entry_operations",
+        "OccupiedEntry: get, insert, remove and remove_entry",
+        occupied_entry_operations,
+    )?;
+
+    section(
+        out,
+        "vacant_entry_operations",
+        "VacantEntry: insert returning a reference, into_key",
+        vacant_entry_operations,
+    )?;
+
+    section(
+        out,
+        "insert_entry_examples",
+        "insert_entry: upsert and keep a handle to the entry",
+        insert_entry_examples,
+    )?;
+
+    section(
+        out,
+        "avoiding_double_hashing",
+        "Moving keys and values through entries instead of hashing twice",
+        avoiding_double_hashing,
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates matching on the Entry enum directly.
+///
+/// or_insert() always ends with the key present. Matching lets each case do
+/// something different - including removing the key or not inserting at all.
+pub fn matching_entry_variants(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Matching Entry Variants")?;
+
+    let mut stock: HashMap<&str, u32> = HashMap::from([("apple", 3), ("pear", 1)]);
+    writeln!(out, "Stock: {:?}", stock)?;
+
+    // Reserve one unit: decrement, drop the key at zero, refuse unknown items
+    for item in ["apple", "pear", "plum"] {
+        let outcome: String = match stock.entry(item) {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() -= 1;
+                if *entry.get() == 0 {
+                    // Same slot, no second lookup
+                    entry.remove();
+                    format!("reserved the last {}", item)
+                } else {
+                    format!("reserved one {}, {} left", item, entry.get())
+                }
+            }
+            // Vacant: inspect the key and walk away - nothing is inserted
+            Entry::Vacant(entry) => format!("no {} in stock", entry.key()),
+        };
+        writeln!(out, "  {}", outcome)?;
+    }
+    writeln!(out, "Stock after reservations: {:?}", stock)?;
+
+    Ok(())
+}
+
+/// Demonstrates the operations available on an OccupiedEntry.
+///
+/// `remove_entry` is the one or_insert users rarely meet: it returns the
+/// key *as stored in the map*, so an owned String key can be reused
+/// instead of being dropped.
+pub fn occupied_entry_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "OccupiedEntry Operations")?;
+
+    let mut sessions: HashMap<String, u32> = HashMap::new();
+    sessions.insert(String::from("alice"), 3);
+    sessions.insert(String::from("bob"), 7);
+    writeln!(out, "Sessions: {:?}", sessions)?;
+
+    if let Entry::Occupied(mut entry) = sessions.entry(String::from("alice")) {
+        // key() is the stored key, get()/get_mut() the value
+        writeln!(
+            out,
+            "\nOccupied: key {:?}, value {}",
+            entry.key(),
+            entry.get()
+        )?;
+
+        // insert() replaces the value and returns the old one
+        let old: u32 = entry.insert(10);
+        writeln!(out, "insert(10) returned the old value {}", old)?;
+
+        // into_mut() converts the entry into a &mut V tied to the map itself
+        let value: &mut u32 = entry.into_mut();
+        *value += 1;
+    }
+    writeln!(out, "After edits: {:?}", sessions)?;
+
+    // remove_entry() gives back both the stored key and the value
+    let mut archived: Vec<(String, u32)> = Vec::new();
+    if let Entry::Occupied(entry) = sessions.entry(String::from("bob")) {
+        let (key, value): (String, u32) = entry.remove_entry();
+        archived.push((key, value));
+    }
+    writeln!(out, "\nremove_entry() moved bob out: {:?}", archived)?;
+    writeln!(out, "Sessions now: {:?}", sessions)?;
+
+    Ok(())
+}
+
+/// Demonstrates the operations available on a VacantEntry.
+///
+/// `insert` returns `&'a mut V` - a reference tied to the map, not to the
+/// entry - so the freshly inserted value can be filled in or returned to a
+/// caller. `into_key` hands back the key when you decide not to insert.
+pub fn vacant_entry_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "VacantEntry Operations")?;
+
+    let mut groups: HashMap<String, Vec<u32>> = HashMap::new();
+
+    // Insert, then keep filling the value through the returned reference
+    if let Entry::Vacant(entry) = groups.entry(String::from("even")) {
+        writeln!(out, "Vacant: key {:?}", entry.key())?;
+        let members: &mut Vec<u32> = entry.insert(Vec::new());
+        members.extend((1..=10).filter(|n| n % 2 == 0));
+    }
+    writeln!(out, "After insert + extend: {:?}", groups)?;
+
+    // A helper can return the reference out of the match
+    fn group_for<'a>(groups: &'a mut HashMap<String, Vec<u32>>, name: &str) -> &'a mut Vec<u32> {
+        match groups.entry(name.to_string()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Vec::new()),
+        }
+    }
+    group_for(&mut groups, "odd").push(1);
+    group_for(&mut groups, "odd").push(3);
+    writeln!(out, "After group_for(\"odd\") twice: {:?}", groups)?;
+
+    // into_key(): take the owned key back without inserting anything
+    let rejected: Vec<String> = ["prime", "even"]
+        .into_iter()
+        .filter_map(|name| match groups.entry(name.to_string()) {
+            Entry::Vacant(entry) => Some(entry.into_key()),
+            Entry::Occupied(_) => None,
+        })
+        .collect();
+    writeln!(out, "\ninto_key() returned the unused keys: {:?}", rejected)?;
+    writeln!(out, "Map unchanged: {} groups", groups.len())?;
+
+    Ok(())
+}
+
+/// Demonstrates `insert_entry`, available on both Entry and VacantEntry.
+///
+/// It inserts (or overwrites) the value and returns an OccupiedEntry for
+/// the slot, so follow-up work - reading the key, removing it again - needs
+/// no second lookup.
+pub fn insert_entry_examples(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "insert_entry")?;
+
+    let mut config: HashMap<&str, String> = HashMap::new();
+    config.insert("mode", String::from("debug"));
+
+    // Entry::insert_entry: upsert whether or not the key existed
+    let entry: OccupiedEntry<&str, String> =
+        config.entry("mode").insert_entry(String::from("release"));
+    writeln!(out, "Upserted {:?} = {:?}", entry.key(), entry.get())?;
+
+    // VacantEntry::insert_entry: insert, then decide to undo it
+    if let Entry::Vacant(vacant) = config.entry("threads") {
+        let entry: OccupiedEntry<&str, String> = vacant.insert_entry(String::from("0"));
+        if entry.get() == "0" {
+            let (key, value) = entry.remove_entry();
+            writeln!(out, "Inserted then rolled back {:?} = {:?}", key, value)?;
+        }
+    }
+    writeln!(out, "Config: {:?}", config)?;
+
+    // Compare with the naive form: insert() returns the old value,
+    // then get() hashes the key a second time to see the new one
+    let old: Option<String> = config.insert("mode", String::from("profile"));
+    let new: Option<&String> = config.get("mode");
+    writeln!(out, "\nNaive insert + get: old {:?}, new {:?}", old, new)?;
+
+    Ok(())
+}
+
+/// Demonstrates using entries to move keys and values without re-hashing.
+///
+/// Each `get`, `insert`, `remove` or `contains_key` call hashes the key and
+/// probes the table. Patterns that chain two of them do that work twice;
+/// the entry forms below do it once.
+pub fn avoiding_double_hashing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Avoiding Double Hashing")?;
+
+    // 1. Take a value out when a condition holds
+    let mut jobs: HashMap<u32, &str> = HashMap::from([(1, "done"), (2, "running")]);
+
+    // Naive: get() to check, remove() to take - two lookups
+    if jobs.get(&1) == Some(&"done") {
+        jobs.remove(&1);
+    }
+    // Entry: check and take from the same slot
+    if let Entry::Occupied(entry) = jobs.entry(2)
+        && *entry.get() == "running"
+    {
+        let (id, state) = entry.remove_entry();
+        writeln!(out, "Took job {} ({}) out with one lookup", id, state)?;
+    }
+    writeln!(out, "Jobs left: {}", jobs.len())?;
+
+    // 2. Replace a value by moving it out and back in
+    let mut names: HashMap<&str, String> = HashMap::from([("greeting", String::from("hello"))]);
+    // Naive: remove() + insert() hashes twice and may shuffle the table
+    // Entry: into_mut() + mem::take moves the String without cloning
+    if let Entry::Occupied(entry) = names.entry("greeting") {
+        let slot: &mut String = entry.into_mut();
+        let owned: String = std::mem::take(slot);
+        *slot = owned.to_uppercase() + ", world";
+    }
+    writeln!(out, "\nRewritten in place: {:?}", names)?;
+
+    // 3. Owned keys: entry() needs the key by value, even on a hit
+    let words: [&str; 5] = ["a", "b", "a", "a", "c"];
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    let mut allocations: u32 = 0;
+    for word in words {
+        // Hit: get_mut() borrows a &str - no allocation
+        if let Some(count) = counts.get_mut(word) {
+            *count += 1;
+        } else {
+            // Miss: a second lookup, but only the first time a word is seen;
+            // allocate the String once and move it into the map
+            allocations += 1;
+            counts.entry(word.to_string()).or_insert(1);
+        }
+    }
+    writeln!(
+        out,
+        "\nCounted {} words with {} key allocations: {:?}",
+        words.len(),
+        allocations,
+        counts
+    )?;
+    writeln!(
+        out,
+        "entry(word.to_string()) alone would allocate {} times",
+        words.len()
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,592 @@
+// Entry API patterns for larger jobs.
+//
+// entry_examples covers the Entry type itself. This module puts it to work
+// on the shapes that come up again and again when building maps from data:
+//
+//   or_insert_with_key   compute a value from the key the entry already
+//                        owns, so the key isn't cloned just to build it
+//   adjacency lists      entry(node).or_default().push(neighbour), plus
+//                        entry(node).or_default() alone to register a node
+//   two-level chaining   outer.entry(a).or_default().entry(b) for a map of
+//                        maps, and the reverse walk that removes an inner
+//                        key and then the inner map once it is empty
+//   take and replace     Entry::remove moves the value out by value, so a
+//                        state transition can consume it and reuse its
+//                        fields before the next state goes back in
+//   inverted index       word -> postings, appending to the last posting
+//                        while the same document keeps matching
+//
+// Every pattern hashes the key once per step, where the get/insert spelling
+// would hash it two or three times.
+
+use demo_framework::section;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "or_insert_with_key_examples",
+        "or_insert_with_key: building a value from the key the entry owns",
+        or_insert_with_key_examples,
+    )?;
+
+    section(
+        out,
+        "adjacency_list_building",
+        "Building adjacency lists and degree counts from an edge list",
+        adjacency_list_building,
+    )?;
+
+    section(
+        out,
+        "two_level_entries",
+        "Chaining entries through a map of maps, and cleaning up on removal",
+        two_level_entries,
+    )?;
+
+    section(
+        out,
+        "take_and_replace",
+        "Moving a value out with Entry::remove, transforming it, putting it back",
+        take_and_replace,
+    )?;
+
+    section(
+        out,
+        "practical_inverted_index",
+        "Practical demo: an inverted index for full-text search",
+        practical_inverted_index,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// or_insert_with_key
+// ---------------------------------------------------------------------------
+
+/// Lowercase words joined by `-`: "Hello, World!" -> "hello-world".
+pub fn slugify(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+/// The slug for `title`, computed on first sight only. `computed` counts
+/// the calls to `slugify`. The key moves into `entry`, and the closure gets
+/// it back as `&String`, so nothing is cloned to compute the value.
+pub fn cached_slug<'a>(
+    cache: &'a mut HashMap<String, String>,
+    title: String,
+    computed: &mut u32,
+) -> &'a str {
+    cache.entry(title).or_insert_with_key(|title| {
+        *computed += 1;
+        slugify(title)
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Adjacency lists
+// ---------------------------------------------------------------------------
+
+/// Adjacency lists from an edge list. Every node is a key, including nodes
+/// with no outgoing edges; an undirected edge is listed from both ends.
+pub fn adjacency_lists<'a>(
+    edges: &[(&'a str, &'a str)],
+    directed: bool,
+) -> HashMap<&'a str, Vec<&'a str>> {
+    let mut graph: HashMap<&str, Vec<&str>> = HashMap::new();
+    for &(from, to) in edges {
+        graph.entry(from).or_default().push(to);
+        let back: &mut Vec<&str> = graph.entry(to).or_default();
+        if !directed {
+            back.push(from);
+        }
+    }
+    graph
+}
+
+/// Incoming edge counts, with `and_modify` for a repeat and `or_insert`
+/// for the first. Nodes without incoming edges are absent.
+pub fn in_degrees<'a>(edges: &[(&'a str, &'a str)]) -> HashMap<&'a str, usize> {
+    let mut degrees: HashMap<&str, usize> = HashMap::new();
+    for &(_, to) in edges {
+        degrees
+            .entry(to)
+            .and_modify(|count| *count += 1)
+            .or_insert(1);
+    }
+    degrees
+}
+
+/// A map's entries sorted by key, for printing.
+fn sorted<K: Ord + Copy, V>(map: &HashMap<K, V>) -> Vec<(K, &V)> {
+    let mut entries: Vec<(K, &V)> = map.iter().map(|(&key, value)| (key, value)).collect();
+    entries.sort_by_key(|&(key, _)| key);
+    entries
+}
+
+// ---------------------------------------------------------------------------
+// Map of maps
+// ---------------------------------------------------------------------------
+
+/// Visit counts per country, then per city.
+pub type Visits<'a> = HashMap<&'a str, HashMap<&'a str, u32>>;
+
+/// Counts one visit and returns the city's new count. The outer entry
+/// creates the country's map on first sight, the inner one the counter.
+pub fn record_visit<'a>(visits: &mut Visits<'a>, country: &'a str, city: &'a str) -> u32 {
+    let count: &mut u32 = visits.entry(country).or_default().entry(city).or_insert(0);
+    *count += 1;
+    *count
+}
+
+/// Takes one visit back. A city whose count reaches zero is removed, and
+/// so is a country with no cities left, so the map never holds empty
+/// entries. Returns false if there was no visit to take back.
+pub fn remove_visit<'a>(visits: &mut Visits<'a>, country: &'a str, city: &'a str) -> bool {
+    let Entry::Occupied(mut cities) = visits.entry(country) else {
+        return false;
+    };
+    let Entry::Occupied(mut count) = cities.get_mut().entry(city) else {
+        return false;
+    };
+    *count.get_mut() -= 1;
+    if *count.get() == 0 {
+        count.remove();
+        if cities.get().is_empty() {
+            cities.remove();
+        }
+    }
+    true
+}
+
+// ---------------------------------------------------------------------------
+// Take and replace
+// ---------------------------------------------------------------------------
+
+/// A support ticket. Each state owns its strings, and every transition
+/// moves them into the next state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ticket {
+    Open {
+        title: String,
+    },
+    Assigned {
+        title: String,
+        owner: String,
+    },
+    Resolved {
+        title: String,
+        owner: String,
+        resolution: String,
+    },
+}
+
+/// What can happen to a ticket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action<'a> {
+    Assign(&'a str),
+    Resolve(&'a str),
+    Reopen,
+    Close,
+}
+
+impl Ticket {
+    /// The state after `action`, or the ticket back unchanged when the
+    /// action doesn't apply to it. Closing ends the ticket rather than
+    /// changing its state, so `Close` is left to the caller.
+    pub fn apply(self, action: Action) -> Result<Ticket, Ticket> {
+        match (self, action) {
+            (Ticket::Open { title }, Action::Assign(owner))
+            | (Ticket::Assigned { title, .. }, Action::Assign(owner)) => Ok(Ticket::Assigned {
+                title,
+                owner: owner.to_string(),
+            }),
+            (Ticket::Assigned { title, owner }, Action::Resolve(resolution)) => {
+                Ok(Ticket::Resolved {
+                    title,
+                    owner,
+                    resolution: resolution.to_string(),
+                })
+            }
+            (Ticket::Resolved { title, .. }, Action::Reopen) => Ok(Ticket::Open { title }),
+            (ticket, _) => Err(ticket),
+        }
+    }
+
+    pub fn state(&self) -> &'static str {
+        match self {
+            Ticket::Open { .. } => "open",
+            Ticket::Assigned { .. } => "assigned",
+            Ticket::Resolved { .. } => "resolved",
+        }
+    }
+}
+
+/// Applies `action` to ticket `id`: `Entry::remove` takes the ticket out by
+/// value, `apply` consumes it, and the next state is inserted under the
+/// same id. Closing a resolved ticket returns it instead, for archiving.
+pub fn advance(
+    tickets: &mut HashMap<u32, Ticket>,
+    id: u32,
+    action: Action,
+) -> Result<Option<Ticket>, String> {
+    let Entry::Occupied(entry) = tickets.entry(id) else {
+        return Err(format!("no ticket #{id}"));
+    };
+    let next: Result<Ticket, Ticket> = match (entry.remove(), action) {
+        (resolved @ Ticket::Resolved { .. }, Action::Close) => return Ok(Some(resolved)),
+        (ticket, action) => ticket.apply(action),
+    };
+    match next {
+        Ok(next) => {
+            tickets.insert(id, next);
+            Ok(None)
+        }
+        Err(unchanged) => {
+            let message: String = format!("#{id} is {}, can't {action:?}", unchanged.state());
+            tickets.insert(id, unchanged);
+            Err(message)
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Inverted index
+// ---------------------------------------------------------------------------
+
+/// Where one word occurs in one document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Posting {
+    pub doc: u32,
+    /// Word positions within the document, ascending.
+    pub positions: Vec<u32>,
+}
+
+/// Word -> one posting per document containing it, in document order.
+pub type InvertedIndex = HashMap<String, Vec<Posting>>;
+
+pub const SAMPLE_DOCS: [(u32, &str); 6] = [
+    (1, "A HashMap finds a value by key in constant time."),
+    (
+        2,
+        "A BTreeMap keeps its keys sorted, so range queries are cheap.",
+    ),
+    (
+        3,
+        "The entry API finds the slot for a key once, then inserts or updates it.",
+    ),
+    (
+        4,
+        "A Vec is the default collection: contiguous, cache friendly, cheap to grow.",
+    ),
+    (
+        5,
+        "Use a BTreeMap when you need sorted keys, a HashMap when you need speed.",
+    ),
+    (
+        6,
+        "An inverted index maps each word to the documents that contain it.",
+    ),
+];
+
+/// The lowercase words of `text`.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Indexes `docs`, which must come in ascending id order. Lowercasing has
+/// already produced an owned `String`, so `entry(word)` costs no extra
+/// allocation; the posting list only grows when a new document matches.
+pub fn build_index(docs: &[(u32, &str)]) -> InvertedIndex {
+    let mut index: InvertedIndex = HashMap::new();
+    for &(doc, text) in docs {
+        for (position, word) in tokenize(text).enumerate() {
+            let postings: &mut Vec<Posting> = index.entry(word).or_default();
+            match postings.last_mut() {
+                Some(last) if last.doc == doc => last.positions.push(position as u32),
+                _ => postings.push(Posting {
+                    doc,
+                    positions: vec![position as u32],
+                }),
+            }
+        }
+    }
+    index
+}
+
+/// Documents containing every word of `query`, ascending. An empty query
+/// matches nothing.
+pub fn search(index: &InvertedIndex, query: &str) -> Vec<u32> {
+    let mut result: Option<Vec<u32>> = None;
+    for word in tokenize(query) {
+        let docs: Vec<u32> = index
+            .get(&word)
+            .map(|postings| postings.iter().map(|posting| posting.doc).collect())
+            .unwrap_or_default();
+        result = Some(match result {
+            None => docs,
+            Some(mut matched) => {
+                matched.retain(|doc| docs.binary_search(doc).is_ok());
+                matched
+            }
+        });
+    }
+    result.unwrap_or_default()
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// Demonstrates `or_insert_with_key`: the closure borrows the key the entry
+/// already owns, where `or_insert_with` would need a clone made beforehand.
+pub fn or_insert_with_key_examples(out: &mut dyn Write) -> io::Result<()> {
+    let titles: [&str; 5] = [
+        "Hello, World!",
+        "Rust Collections 101",
+        "Hello, World!",
+        "Entry API: the cookbook",
+        "Rust Collections 101",
+    ];
+    let mut cache: HashMap<String, String> = HashMap::new();
+    let mut computed: u32 = 0;
+
+    writeln!(
+        out,
+        "\n  cache.entry(title).or_insert_with_key(|t| slugify(t)):"
+    )?;
+    for title in titles {
+        let slug: &str = cached_slug(&mut cache, title.to_string(), &mut computed);
+        writeln!(out, "    {title:<26} -> {slug}")?;
+    }
+    writeln!(
+        out,
+        "  {} titles, slugify ran {computed} times",
+        titles.len()
+    )?;
+
+    writeln!(
+        out,
+        "\n  Without the key in the closure: let k = title.to_string();"
+    )?;
+    writeln!(
+        out,
+        "  cache.entry(k.clone()).or_insert_with(|| slugify(&k)) clones every key,"
+    )?;
+    writeln!(out, "  hit or miss, only to keep a copy for the closure.")?;
+    Ok(())
+}
+
+/// Demonstrates building adjacency lists with `entry().or_default()`, and
+/// registering a node with `or_default()` alone so sinks get an empty list.
+pub fn adjacency_list_building(out: &mut dyn Write) -> io::Result<()> {
+    let edges: [(&str, &str); 6] = [
+        ("parse", "lex"),
+        ("check", "parse"),
+        ("emit", "check"),
+        ("emit", "parse"),
+        ("link", "emit"),
+        ("check", "lex"),
+    ];
+    writeln!(out, "\n  Build steps, step -> what it needs: {edges:?}")?;
+
+    let directed: HashMap<&str, Vec<&str>> = adjacency_lists(&edges, true);
+    writeln!(out, "\n  Directed:")?;
+    for (node, needs) in sorted(&directed) {
+        writeln!(out, "    {node:<6} -> {needs:?}")?;
+    }
+    writeln!(
+        out,
+        "  lex has no edges of its own but is a key: entry(to).or_default() registered it."
+    )?;
+
+    let undirected: HashMap<&str, Vec<&str>> = adjacency_lists(&edges, false);
+    writeln!(out, "\n  Undirected, neighbours per node:")?;
+    for (node, neighbours) in sorted(&undirected) {
+        writeln!(out, "    {node:<6} {}", neighbours.len())?;
+    }
+
+    writeln!(out, "\n  In-degrees with and_modify(+1).or_insert(1):")?;
+    for (node, degree) in sorted(&in_degrees(&edges)) {
+        writeln!(out, "    {node:<6} {degree}")?;
+    }
+    Ok(())
+}
+
+/// Demonstrates a map of maps: `entry().or_default().entry()` creates both
+/// levels on demand, and removal walks both entries to drop what empties.
+pub fn two_level_entries(out: &mut dyn Write) -> io::Result<()> {
+    let log: [(&str, &str); 7] = [
+        ("fr", "paris"),
+        ("jp", "osaka"),
+        ("fr", "lyon"),
+        ("fr", "paris"),
+        ("jp", "tokyo"),
+        ("jp", "osaka"),
+        ("jp", "osaka"),
+    ];
+    let mut visits: Visits = HashMap::new();
+    for (country, city) in log {
+        record_visit(&mut visits, country, city);
+    }
+
+    writeln!(
+        out,
+        "\n  {} visits recorded with one chain per visit:",
+        log.len()
+    )?;
+    print_visits(out, &visits)?;
+
+    let undo: [(&str, &str); 4] = [
+        ("fr", "lyon"),
+        ("jp", "tokyo"),
+        ("jp", "kyoto"),
+        ("fr", "paris"),
+    ];
+    writeln!(out, "\n  Taking visits back:")?;
+    for (country, city) in undo {
+        let removed: bool = remove_visit(&mut visits, country, city);
+        writeln!(out, "    {country}/{city:<6} removed = {removed}")?;
+    }
+    print_visits(out, &visits)?;
+
+    remove_visit(&mut visits, "fr", "paris");
+    writeln!(
+        out,
+        "\n  After the last paris visit goes, fr goes too: countries = {:?}",
+        sorted(&visits)
+            .iter()
+            .map(|&(country, _)| country)
+            .collect::<Vec<&str>>()
+    )?;
+    Ok(())
+}
+
+fn print_visits(out: &mut dyn Write, visits: &Visits) -> io::Result<()> {
+    for (country, cities) in sorted(visits) {
+        let cities: Vec<(&str, &u32)> = sorted(cities);
+        writeln!(out, "    {country}: {cities:?}")?;
+    }
+    Ok(())
+}
+
+/// Demonstrates take-and-replace: `Entry::remove` hands over the value by
+/// value, so a transition that consumes it can move its strings into the
+/// next state instead of cloning them out of a `&mut`.
+pub fn take_and_replace(out: &mut dyn Write) -> io::Result<()> {
+    let mut tickets: HashMap<u32, Ticket> = HashMap::from([
+        (
+            1,
+            Ticket::Open {
+                title: "Login page times out".to_string(),
+            },
+        ),
+        (
+            2,
+            Ticket::Open {
+                title: "Typo in footer".to_string(),
+            },
+        ),
+    ]);
+
+    let steps: [(u32, Action); 7] = [
+        (1, Action::Assign("ana")),
+        (1, Action::Resolve("raised the pool size")),
+        (2, Action::Resolve("fixed")),
+        (2, Action::Assign("bo")),
+        (1, Action::Close),
+        (1, Action::Reopen),
+        (3, Action::Assign("ana")),
+    ];
+    writeln!(out)?;
+    for (id, action) in steps {
+        match advance(&mut tickets, id, action) {
+            Ok(None) => writeln!(
+                out,
+                "  #{id} {action:?} -> {}",
+                tickets.get(&id).map_or("?", Ticket::state)
+            )?,
+            Ok(Some(closed)) => writeln!(out, "  #{id} {action:?} -> closed, archived {closed:?}")?,
+            Err(message) => writeln!(out, "  #{id} {action:?} -> refused: {message}")?,
+        }
+    }
+
+    let mut ids: Vec<&u32> = tickets.keys().collect();
+    ids.sort();
+    writeln!(out, "\n  Tickets left: {ids:?}")?;
+    writeln!(
+        out,
+        "  A refused action puts the ticket back unchanged; each step costs a"
+    )?;
+    writeln!(
+        out,
+        "  remove and an insert. mem::replace on entry.get_mut() stays in one slot,"
+    )?;
+    writeln!(
+        out,
+        "  but only when there is a cheap placeholder state to leave behind."
+    )?;
+    Ok(())
+}
+
+/// Practical example: index a few documents, look at the postings for a
+/// word, and answer multi-word queries by intersecting posting lists.
+pub fn practical_inverted_index(out: &mut dyn Write) -> io::Result<()> {
+    let index: InvertedIndex = build_index(&SAMPLE_DOCS);
+    let words: usize = SAMPLE_DOCS
+        .iter()
+        .map(|(_, text)| tokenize(text).count())
+        .sum();
+    writeln!(
+        out,
+        "\n  {} documents, {words} words, {} distinct",
+        SAMPLE_DOCS.len(),
+        index.len()
+    )?;
+
+    for word in ["btreemap", "key", "a"] {
+        let postings: &[Posting] = index.get(word).map_or(&[], Vec::as_slice);
+        let listed: Vec<String> = postings
+            .iter()
+            .map(|posting| format!("{}@{:?}", posting.doc, posting.positions))
+            .collect();
+        writeln!(out, "  {word:<9} {}", listed.join(" "))?;
+    }
+
+    writeln!(out, "\n  Queries (every word must match):")?;
+    for query in ["sorted keys", "HashMap", "cheap", "key value", "tree"] {
+        writeln!(out, "    {query:<12} -> {:?}", search(&index, query))?;
+    }
+
+    writeln!(
+        out,
+        "\n  Two words in one document extend its last posting instead of adding one:"
+    )?;
+    let repeated: Vec<(&str, u32, usize)> = {
+        let mut repeated: Vec<(&str, u32, usize)> = index
+            .iter()
+            .flat_map(|(word, postings)| {
+                postings
+                    .iter()
+                    .filter(|posting| posting.positions.len() > 1)
+                    .map(move |posting| (word.as_str(), posting.doc, posting.positions.len()))
+            })
+            .collect();
+        repeated.sort();
+        repeated
+    };
+    for (word, doc, count) in repeated {
+        writeln!(out, "    {word:<5} x{count} in doc {doc}")?;
+    }
+    Ok(())
+}
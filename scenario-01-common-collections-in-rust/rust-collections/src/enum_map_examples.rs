@@ -0,0 +1,432 @@
+// Some maps have a small, closed key set fixed at compile time: log levels,
+// HTTP methods, days of the week, chess pieces. When the key is a fieldless
+// enum, four layouts hold the same data:
+//
+//   HashMap<Level, V>    hashes the discriminant, probes a heap-allocated
+//                        table, compares keys. Iterates in hash order.
+//   BTreeMap<Level, V>   a small sorted node on the heap, searched by
+//                        comparing. Iterates in declaration order.
+//   [V; Level::COUNT]    the value for `level` lives at `level as usize`:
+//                        no hashing, no search, no allocation. Every key has
+//                        a slot, so "absent" must be spelled Option<V> or a
+//                        default value.
+//   EnumMap<Level, V>    the same array behind a typed API, from the
+//                        `enum-map` crate: `map[Level::Warn]`, iteration
+//                        yields (key, value), and `enum_map!` checks at
+//                        compile time that every variant gets a value.
+//
+// The hand-rolled array needs a wrapper per enum (`LevelTable` below): a
+// generic `ArrayMap<K, V>` can't write `[V; K::COUNT]` on stable Rust. The
+// crate's `#[derive(Enum)]` gets around that by generating the array type
+// for each enum.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use enum_map::{Enum, EnumMap, enum_map};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::mem::size_of;
+use std::ops::{Index, IndexMut};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "four_layouts",
+        "Counting log lines per level in four layouts",
+        four_layouts,
+    )?;
+
+    section(
+        out,
+        "every_key_has_a_slot",
+        "Totality: defaults, Option<V> for absent keys, and enum_map! exhaustiveness",
+        every_key_has_a_slot,
+    )?;
+
+    section(
+        out,
+        "memory_and_order",
+        "Size and iteration order of each layout",
+        memory_and_order,
+    )?;
+
+    section(
+        out,
+        "practical_traffic_table",
+        "Practical demo: a method x status-class request table as nested EnumMaps",
+        practical_traffic_table,
+    )?;
+
+    Ok(())
+}
+
+/// Severity of a log line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Enum)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub const COUNT: usize = 5;
+    pub const ALL: [Level; Level::COUNT] = [
+        Level::Trace,
+        Level::Debug,
+        Level::Info,
+        Level::Warn,
+        Level::Error,
+    ];
+}
+
+/// A hand-rolled enum map: one slot per `Level`, indexed by discriminant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LevelTable<V>([V; Level::COUNT]);
+
+impl<V> LevelTable<V> {
+    pub fn from_fn(mut f: impl FnMut(Level) -> V) -> Self {
+        LevelTable(Level::ALL.map(&mut f))
+    }
+
+    /// Every level with its value, in declaration order.
+    pub fn iter(&self) -> impl Iterator<Item = (Level, &V)> {
+        Level::ALL.into_iter().zip(&self.0)
+    }
+}
+
+impl<V> Index<Level> for LevelTable<V> {
+    type Output = V;
+
+    fn index(&self, level: Level) -> &V {
+        &self.0[level as usize]
+    }
+}
+
+impl<V> IndexMut<Level> for LevelTable<V> {
+    fn index_mut(&mut self, level: Level) -> &mut V {
+        &mut self.0[level as usize]
+    }
+}
+
+/// `len` log levels, mostly Info and Debug with a few warnings and errors,
+/// from a fixed seed.
+pub fn log_levels(len: usize, seed: u64) -> Vec<Level> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..len)
+        .map(|_| match rng.below(100) {
+            0..=9 => Level::Trace,
+            10..=39 => Level::Debug,
+            40..=89 => Level::Info,
+            90..=97 => Level::Warn,
+            _ => Level::Error,
+        })
+        .collect()
+}
+
+pub fn count_hashmap(levels: &[Level]) -> HashMap<Level, u64> {
+    let mut counts: HashMap<Level, u64> = HashMap::new();
+    for &level in levels {
+        *counts.entry(level).or_insert(0) += 1;
+    }
+    counts
+}
+
+pub fn count_btreemap(levels: &[Level]) -> BTreeMap<Level, u64> {
+    let mut counts: BTreeMap<Level, u64> = BTreeMap::new();
+    for &level in levels {
+        *counts.entry(level).or_insert(0) += 1;
+    }
+    counts
+}
+
+pub fn count_array(levels: &[Level]) -> LevelTable<u64> {
+    let mut counts: LevelTable<u64> = LevelTable::default();
+    for &level in levels {
+        counts[level] += 1;
+    }
+    counts
+}
+
+pub fn count_enum_map(levels: &[Level]) -> EnumMap<Level, u64> {
+    let mut counts: EnumMap<Level, u64> = EnumMap::default();
+    for &level in levels {
+        counts[level] += 1;
+    }
+    counts
+}
+
+/// Demonstrates the same per-level count in each layout.
+///
+/// The map versions need `entry().or_insert(0)` because a missing key is
+/// possible; the array versions start with a zero in every slot and index
+/// directly.
+pub fn four_layouts(out: &mut dyn Write) -> io::Result<()> {
+    let levels: Vec<Level> = log_levels(10_000, 7);
+    let hashed: HashMap<Level, u64> = count_hashmap(&levels);
+    let sorted: BTreeMap<Level, u64> = count_btreemap(&levels);
+    let array: LevelTable<u64> = count_array(&levels);
+    let enum_map: EnumMap<Level, u64> = count_enum_map(&levels);
+
+    writeln!(out, "\n  Counting {} log lines per level:", levels.len())?;
+    writeln!(
+        out,
+        "    {:<6} {:>8} {:>9} {:>7} {:>8}",
+        "level", "HashMap", "BTreeMap", "array", "EnumMap"
+    )?;
+    for level in Level::ALL {
+        writeln!(
+            out,
+            "    {:<6} {:>8} {:>9} {:>7} {:>8}",
+            format!("{level:?}"),
+            hashed[&level],
+            sorted[&level],
+            array[level],
+            enum_map[level]
+        )?;
+    }
+
+    writeln!(out, "\n  The update in the loop:")?;
+    writeln!(
+        out,
+        "    HashMap / BTreeMap: *counts.entry(level).or_insert(0) += 1;  // hash or search"
+    )?;
+    writeln!(
+        out,
+        "    array / EnumMap:    counts[level] += 1;                      // index by discriminant"
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates that an array-backed map always has a value for every key.
+///
+/// That is what makes indexing infallible, and it changes how "absent" is
+/// spelled: as a default (a count of 0) or as `Option<V>`. `enum_map!` is a
+/// `match` on the key, so forgetting a variant is a compile error instead
+/// of a `None` at runtime.
+pub fn every_key_has_a_slot(out: &mut dyn Write) -> io::Result<()> {
+    let default_counts: EnumMap<Level, u64> = EnumMap::default();
+    writeln!(
+        out,
+        "\n  EnumMap::default() already holds every key: Error -> {}",
+        default_counts[Level::Error]
+    )?;
+
+    // Sparse data: only some levels have a configured sink
+    let mut sinks: EnumMap<Level, Option<&str>> = EnumMap::default();
+    sinks[Level::Warn] = Some("pager");
+    sinks[Level::Error] = Some("pager");
+    sinks[Level::Info] = Some("file");
+    writeln!(
+        out,
+        "\n  EnumMap<Level, Option<&str>> for keys that may be absent:"
+    )?;
+    for (level, sink) in &sinks {
+        writeln!(out, "    {level:?} -> {sink:?}")?;
+    }
+    let configured: usize = sinks.values().filter(|sink| sink.is_some()).count();
+    writeln!(
+        out,
+        "    {configured} of {} levels configured",
+        Level::COUNT
+    )?;
+
+    // Total data: every level must get a colour, checked at compile time
+    let colours: EnumMap<Level, &str> = enum_map! {
+        Level::Trace | Level::Debug => "grey",
+        Level::Info => "white",
+        Level::Warn => "yellow",
+        Level::Error => "red",
+    };
+    writeln!(
+        out,
+        "\n  enum_map! with one arm per variant (or group of variants):"
+    )?;
+    for (level, colour) in &colours {
+        writeln!(out, "    {level:?} -> {colour}")?;
+    }
+    writeln!(
+        out,
+        "  Adding a Level::Fatal variant turns this into a non-exhaustive match error."
+    )?;
+
+    let hand_built: HashMap<Level, &str> = HashMap::from([(Level::Warn, "yellow")]);
+    writeln!(
+        out,
+        "  A HashMap built by hand finds the gap only at runtime: get(&Error) = {:?}",
+        hand_built.get(&Level::Error)
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates what each layout costs in memory and how it iterates.
+pub fn memory_and_order(out: &mut dyn Write) -> io::Result<()> {
+    let levels: Vec<Level> = log_levels(1_000, 3);
+    let hashed: HashMap<Level, u64> = count_hashmap(&levels);
+    let sorted: BTreeMap<Level, u64> = count_btreemap(&levels);
+    let array: LevelTable<u64> = count_array(&levels);
+    let enum_map: EnumMap<Level, u64> = count_enum_map(&levels);
+
+    writeln!(out, "\n  Five u64 counters, one per level:")?;
+    writeln!(
+        out,
+        "    HashMap<Level, u64>    {:>3} bytes inline + a heap table of {} slots",
+        size_of::<HashMap<Level, u64>>(),
+        hashed.capacity()
+    )?;
+    writeln!(
+        out,
+        "    BTreeMap<Level, u64>   {:>3} bytes inline + a heap node",
+        size_of::<BTreeMap<Level, u64>>()
+    )?;
+    writeln!(
+        out,
+        "    LevelTable<u64>        {:>3} bytes inline, nothing on the heap",
+        size_of::<LevelTable<u64>>()
+    )?;
+    writeln!(
+        out,
+        "    EnumMap<Level, u64>    {:>3} bytes inline, nothing on the heap",
+        size_of::<EnumMap<Level, u64>>()
+    )?;
+
+    let btree_order: Vec<Level> = sorted.keys().copied().collect();
+    let array_order: Vec<Level> = array.iter().map(|(level, _)| level).collect();
+    let enum_map_order: Vec<Level> = enum_map.iter().map(|(level, _)| level).collect();
+    writeln!(out, "\n  Iteration order:")?;
+    writeln!(out, "    BTreeMap:   {btree_order:?}")?;
+    writeln!(out, "    LevelTable: {array_order:?}")?;
+    writeln!(out, "    EnumMap:    {enum_map_order:?}")?;
+    writeln!(
+        out,
+        "    HashMap:    whatever the hash puts first ({} keys, order varies per run)",
+        hashed.len()
+    )?;
+    writeln!(
+        out,
+        "  The array layouts and BTreeMap (via derived Ord) both follow declaration order."
+    )?;
+
+    Ok(())
+}
+
+/// The HTTP methods a service counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Enum)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+/// The first digit of an HTTP status code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Enum)]
+pub enum StatusClass {
+    Success,
+    Redirect,
+    ClientError,
+    ServerError,
+}
+
+impl StatusClass {
+    /// `None` for informational (1xx) and out-of-range codes.
+    pub fn of(status: u16) -> Option<StatusClass> {
+        match status {
+            200..=299 => Some(StatusClass::Success),
+            300..=399 => Some(StatusClass::Redirect),
+            400..=499 => Some(StatusClass::ClientError),
+            500..=599 => Some(StatusClass::ServerError),
+            _ => None,
+        }
+    }
+}
+
+/// Requests per method and status class: a 4x4 table of counters.
+pub type TrafficTable = EnumMap<Method, EnumMap<StatusClass, u64>>;
+
+/// Tallies `(method, status)` pairs, skipping codes outside 2xx-5xx.
+pub fn tally_traffic(requests: &[(Method, u16)]) -> TrafficTable {
+    let mut table: TrafficTable = EnumMap::default();
+    for &(method, status) in requests {
+        if let Some(class) = StatusClass::of(status) {
+            table[method][class] += 1;
+        }
+    }
+    table
+}
+
+/// Practical example: a request counter table for a metrics endpoint.
+///
+/// Nesting one EnumMap in another gives a fixed 2-D table: 16 counters in
+/// 128 contiguous bytes, cheap to copy as a snapshot and to subtract from
+/// the previous one. A `HashMap<(Method, StatusClass), u64>` would hash a
+/// tuple per request and still need the zero rows filled in for display.
+pub fn practical_traffic_table(out: &mut dyn Write) -> io::Result<()> {
+    let requests: Vec<(Method, u16)> = (0..2_000u32)
+        .map(|i| {
+            let method: Method = match i % 10 {
+                0..=5 => Method::Get,
+                6 | 7 => Method::Post,
+                8 => Method::Put,
+                _ => Method::Delete,
+            };
+            let status: u16 = match (i * 7919) % 100 {
+                0..=1 => 503,
+                2..=7 => 404,
+                8..=10 => 304,
+                11 => 101,
+                _ => 200,
+            };
+            (method, status)
+        })
+        .collect();
+
+    let before: TrafficTable = tally_traffic(&requests[..1_500]);
+    let after: TrafficTable = tally_traffic(&requests);
+
+    writeln!(
+        out,
+        "\n  {} requests tallied into a {}-byte table:",
+        requests.len(),
+        size_of::<TrafficTable>()
+    )?;
+    writeln!(
+        out,
+        "    {:<7} {:>8} {:>9} {:>12} {:>12}",
+        "method", "Success", "Redirect", "ClientError", "ServerError"
+    )?;
+    for (method, row) in &after {
+        write!(out, "    {:<7}", format!("{method:?}"))?;
+        // The header's column widths, in StatusClass order
+        for (width, count) in [8, 9, 12, 12].into_iter().zip(row.values()) {
+            write!(out, " {count:>width$}")?;
+        }
+        writeln!(out)?;
+    }
+    let counted: u64 = after.values().flat_map(|row| row.values()).sum();
+    writeln!(
+        out,
+        "    {counted} counted; {} informational responses skipped",
+        requests.len() as u64 - counted
+    )?;
+
+    // Rates over the last interval: the snapshot is a plain copy
+    let delta: TrafficTable = EnumMap::from_fn(|method| {
+        EnumMap::from_fn(|class| after[method][class] - before[method][class])
+    });
+    writeln!(out, "\n  Errors in the last 500 requests (after - before):")?;
+    for (method, row) in &delta {
+        writeln!(
+            out,
+            "    {:<7} 4xx {:>2}  5xx {:>2}",
+            format!("{method:?}"),
+            row[StatusClass::ClientError],
+            row[StatusClass::ServerError]
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,482 @@
+// Looking keys up by a view that isn't a borrow: the Equivalent trait.
+//
+// std's HashMap::get takes any &Q where K: Borrow<Q>, and borrow() has to
+// return a &Q that lives inside the key. That works for String -> str, but
+// not for a composite key: a (u32, String) holds no (u32, &str) to lend
+// out, so looking one up from a borrowed name means building the owned
+// tuple first, and allocating a String to throw away.
+//
+// hashbrown and indexmap relax the bound to Q: Hash + Equivalent<K>:
+//
+//   trait Equivalent<K: ?Sized> {
+//       fn equivalent(&self, key: &K) -> bool;
+//   }
+//
+// Q only has to compare against a key and hash the same way. A view like
+// TenantUserRef { tenant: u32, name: &str } can do that without any
+// (u32, &str) existing in memory. Both crates re-export the same trait from
+// the `equivalent` crate, so one impl serves hashbrown::HashMap, IndexMap
+// and IndexSet, and a blanket impl keeps every Borrow-based lookup working.
+//
+// The contract is Borrow's, unchecked as ever: if q.equivalent(k) is true,
+// q and k must hash the same. A view whose Hash writes the fields in a
+// different order compiles and misses on every lookup.
+
+use demo_framework::section;
+use indexmap::{Equivalent, IndexMap};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash, Hasher};
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "composite_key_views",
+        "Looking up a (u32, String) key with a (u32, &str) view",
+        composite_key_views,
+    )?;
+
+    section(
+        out,
+        "case_insensitive_views",
+        "A case-insensitive key looked up from any spelling, without lowercasing",
+        case_insensitive_views,
+    )?;
+
+    section(
+        out,
+        "broken_equivalence",
+        "An Equivalent impl that disagrees with Hash: silent misses",
+        broken_equivalence,
+    )?;
+
+    section(
+        out,
+        "practical_route_table",
+        "Practical demo: routing request lines through a (Method, String) table",
+        practical_route_table,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Composite keys
+// ---------------------------------------------------------------------------
+
+/// A directory key: tenant id and user name.
+pub type TenantUser = (u32, String);
+
+/// A borrowed view of a [`TenantUser`]. It hashes exactly like the tuple,
+/// field by field, so it finds the tuple's bucket.
+#[derive(Debug, Clone, Copy)]
+pub struct TenantUserRef<'a> {
+    pub tenant: u32,
+    pub name: &'a str,
+}
+
+impl Hash for TenantUserRef<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // The order a tuple hashes its fields in
+        self.tenant.hash(state);
+        self.name.hash(state);
+    }
+}
+
+impl Equivalent<TenantUser> for TenantUserRef<'_> {
+    fn equivalent(&self, key: &TenantUser) -> bool {
+        self.tenant == key.0 && self.name == key.1
+    }
+}
+
+/// `count` directory entries over 20 tenants.
+pub fn sample_directory(count: u32) -> Vec<(TenantUser, u32)> {
+    (0..count)
+        .map(|i| ((i % 20, format!("user{:04}", i)), i * 3))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Case-insensitive keys
+// ---------------------------------------------------------------------------
+
+/// Feeds `text` to the hasher ASCII-lowercased, byte by byte, ending with
+/// the `0xff` a `str` writes. No lowercase copy is made.
+fn hash_folded<H: Hasher>(text: &str, state: &mut H) {
+    for byte in text.bytes() {
+        state.write_u8(byte.to_ascii_lowercase());
+    }
+    state.write_u8(0xff);
+}
+
+/// A key that compares and hashes ignoring ASCII case, and keeps the
+/// spelling it was first inserted with.
+#[derive(Debug, Clone)]
+pub struct CaseInsensitive(pub String);
+
+impl PartialEq for CaseInsensitive {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for CaseInsensitive {}
+
+impl Hash for CaseInsensitive {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_folded(&self.0, state);
+    }
+}
+
+/// Any spelling of a [`CaseInsensitive`] key, as a `&str`. `Borrow<str>`
+/// can't do this: the map would hash the `str` as written.
+#[derive(Debug, Clone, Copy)]
+pub struct CaseInsensitiveStr<'a>(pub &'a str);
+
+impl Hash for CaseInsensitiveStr<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_folded(self.0, state);
+    }
+}
+
+impl Equivalent<CaseInsensitive> for CaseInsensitiveStr<'_> {
+    fn equivalent(&self, key: &CaseInsensitive) -> bool {
+        self.0.eq_ignore_ascii_case(&key.0)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Routes
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+impl Method {
+    pub fn parse(text: &str) -> Option<Method> {
+        match text {
+            "GET" => Some(Method::Get),
+            "POST" => Some(Method::Post),
+            "PUT" => Some(Method::Put),
+            "DELETE" => Some(Method::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// A route table key.
+pub type Route = (Method, String);
+
+/// A route as it appears in a request line, borrowing the path.
+#[derive(Debug, Clone, Copy)]
+pub struct RouteRef<'a> {
+    pub method: Method,
+    pub path: &'a str,
+}
+
+impl Hash for RouteRef<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.method.hash(state);
+        self.path.hash(state);
+    }
+}
+
+impl Equivalent<Route> for RouteRef<'_> {
+    fn equivalent(&self, key: &Route) -> bool {
+        self.method == key.0 && self.path == key.1
+    }
+}
+
+/// Splits `"GET /users HTTP/1.1"` into a [`RouteRef`] pointing into the
+/// line. The query string, if any, is not part of the route.
+pub fn parse_request_line(line: &str) -> Option<RouteRef<'_>> {
+    let mut parts = line.split_ascii_whitespace();
+    let method: Method = Method::parse(parts.next()?)?;
+    let target: &str = parts.next()?;
+    let path: &str = target.split_once('?').map_or(target, |(path, _)| path);
+    Some(RouteRef { method, path })
+}
+
+/// The routes `practical_route_table` registers, in order.
+pub const ROUTES: [(Method, &str, &str); 6] = [
+    (Method::Get, "/users", "list_users"),
+    (Method::Post, "/users", "create_user"),
+    (Method::Get, "/orders", "list_orders"),
+    (Method::Post, "/orders", "create_order"),
+    (Method::Delete, "/orders", "cancel_order"),
+    (Method::Get, "/health", "health"),
+];
+
+/// Builds the route table from [`ROUTES`].
+pub fn route_table() -> IndexMap<Route, &'static str> {
+    ROUTES
+        .iter()
+        .map(|&(method, path, handler)| ((method, path.to_string()), handler))
+        .collect()
+}
+
+/// `count` request lines cycling through the routes, with a query string
+/// on some and an unknown route or a malformed line now and then.
+pub fn sample_requests(count: usize) -> Vec<String> {
+    const TARGETS: [&str; 8] = [
+        "GET /users",
+        "GET /orders?page=2",
+        "POST /orders",
+        "GET /health",
+        "GET /orders",
+        "PUT /users",
+        "DELETE /orders?id=17",
+        "FETCH /users",
+    ];
+    (0..count)
+        .map(|i| format!("{} HTTP/1.1", TARGETS[(i * i + i / 3) % TARGETS.len()]))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// Demonstrates the same composite-key lookup three ways: std with an
+/// owned key, hashbrown and IndexMap with a borrowed view
+pub fn composite_key_views(out: &mut dyn Write) -> io::Result<()> {
+    let entries: Vec<(TenantUser, u32)> = sample_directory(1_000);
+    let std_map: HashMap<TenantUser, u32> = entries.iter().cloned().collect();
+    let brown: hashbrown::HashMap<TenantUser, u32> = entries.iter().cloned().collect();
+    let index: IndexMap<TenantUser, u32> = entries.iter().cloned().collect();
+
+    // The name arrives borrowed, e.g. sliced out of a request
+    let request: &str = "tenant=7 user=user0047";
+    let name: &str = &request["tenant=7 user=".len()..];
+    let view = TenantUserRef { tenant: 7, name };
+
+    writeln!(out, "\n  Looking up tenant 7, {:?} (borrowed):", name)?;
+    writeln!(
+        out,
+        "    std HashMap   get(&(7, name.to_string()))  {:?}   builds a String",
+        std_map.get(&(7, name.to_string()))
+    )?;
+    writeln!(
+        out,
+        "    hashbrown     get(&view)                   {:?}",
+        brown.get(&view)
+    )?;
+    writeln!(
+        out,
+        "    IndexMap      get_full(&view)              {:?}",
+        index.get_full(&view).map(|(i, _, v)| (i, *v))
+    )?;
+    let wrong_tenant = TenantUserRef { tenant: 8, name };
+    writeln!(
+        out,
+        "    hashbrown     get(&view with tenant 8)     {:?}",
+        brown.get(&wrong_tenant)
+    )?;
+
+    let state = brown.hasher();
+    writeln!(
+        out,
+        "\n  The view and the tuple hash alike: {}",
+        state.hash_one(view) == state.hash_one((7u32, name.to_string()))
+    )?;
+    writeln!(
+        out,
+        "  std can't take the view: get needs TenantUser: Borrow<Q>, and a tuple"
+    )?;
+    writeln!(
+        out,
+        "  owns no (u32, &str) to hand out a reference to. Equivalent asks only"
+    )?;
+    writeln!(
+        out,
+        "  that the view compares against a key and hashes like one."
+    )?;
+
+    // Borrow still works through the blanket impl: str is Equivalent<String>
+    let names: hashbrown::HashSet<String> = ["ann".to_string(), "bo".to_string()].into();
+    writeln!(
+        out,
+        "\n  Borrow-based lookups still work: HashSet<String>::contains(\"bo\") = {}",
+        names.contains("bo")
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates a case-insensitive key looked up by any spelling, which
+/// `Borrow<str>` gets wrong
+pub fn case_insensitive_views(out: &mut dyn Write) -> io::Result<()> {
+    let mut emails: IndexMap<CaseInsensitive, &str> = IndexMap::new();
+    emails.insert(CaseInsensitive("Alice".to_string()), "alice@example.com");
+    emails.insert(CaseInsensitive("bob".to_string()), "bob@example.com");
+
+    writeln!(out, "\n  Keys inserted as \"Alice\" and \"bob\":")?;
+    for spelling in ["alice", "ALICE", "Alice", "BoB", "carol"] {
+        writeln!(
+            out,
+            "    {:<35} {:?}",
+            format!("get(&CaseInsensitiveStr({spelling:?}))"),
+            emails.get(&CaseInsensitiveStr(spelling))
+        )?;
+    }
+
+    // Inserting another spelling updates the entry, and the first
+    // spelling stays as the key
+    emails.insert(CaseInsensitive("ALICE".to_string()), "a@example.org");
+    let (key, email) = emails.get_index(0).unwrap();
+    writeln!(
+        out,
+        "\n  insert(\"ALICE\", ..) replaced the value: {:?} -> {:?}",
+        key.0, email
+    )?;
+
+    writeln!(
+        out,
+        "\n  hashmap_examples shows the Borrow<str> version of this key missing:"
+    )?;
+    writeln!(
+        out,
+        "  the map hashes the str as written. The view hashes it lowercased, one"
+    )?;
+    writeln!(
+        out,
+        "  byte at a time, exactly as the key does, so no lowercase copy is made."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates what happens when `Equivalent` and `Hash` disagree
+pub fn broken_equivalence(out: &mut dyn Write) -> io::Result<()> {
+    // Compares like TenantUserRef, but hashes the name first
+    struct Swapped<'a>(u32, &'a str);
+
+    impl Hash for Swapped<'_> {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.1.hash(state);
+            self.0.hash(state);
+        }
+    }
+
+    impl Equivalent<TenantUser> for Swapped<'_> {
+        fn equivalent(&self, key: &TenantUser) -> bool {
+            self.0 == key.0 && self.1 == key.1
+        }
+    }
+
+    // A fixed hasher, so the output is the same on every run
+    type Fixed = BuildHasherDefault<DefaultHasher>;
+    let entries: Vec<(TenantUser, u32)> = sample_directory(1_000);
+    let mut brown: hashbrown::HashMap<TenantUser, u32, Fixed> = hashbrown::HashMap::default();
+    brown.extend(entries.iter().cloned());
+
+    let good: usize = entries
+        .iter()
+        .filter(|((tenant, name), _)| {
+            brown.contains_key(&TenantUserRef {
+                tenant: *tenant,
+                name,
+            })
+        })
+        .count();
+    let swapped: usize = entries
+        .iter()
+        .filter(|((tenant, name), _)| brown.contains_key(&Swapped(*tenant, name)))
+        .count();
+
+    writeln!(
+        out,
+        "\n  Looking up all {} keys of a hashbrown map:",
+        entries.len()
+    )?;
+    writeln!(
+        out,
+        "    TenantUserRef (hashes tenant, name)   found {}",
+        good
+    )?;
+    writeln!(
+        out,
+        "    Swapped       (hashes name, tenant)   found {}",
+        swapped
+    )?;
+    writeln!(
+        out,
+        "\n  Both compare equal to every key. The swapped one hashes to another"
+    )?;
+    writeln!(
+        out,
+        "  bucket, so equivalent() is never even asked. Nothing panics: the map"
+    )?;
+    writeln!(
+        out,
+        "  trusts that equivalent keys hash alike, just as it does for Borrow."
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: a router that matches request lines against a
+/// `(Method, String)` table without allocating a key per request
+pub fn practical_route_table(out: &mut dyn Write) -> io::Result<()> {
+    const REQUESTS: usize = 10_000;
+
+    let routes: IndexMap<Route, &str> = route_table();
+    let requests: Vec<String> = sample_requests(REQUESTS);
+    let mut hits: Vec<usize> = vec![0; routes.len()];
+    let mut not_found: usize = 0;
+    let mut bad_request: usize = 0;
+
+    for line in &requests {
+        let Some(route) = parse_request_line(line) else {
+            bad_request += 1;
+            continue;
+        };
+        match routes.get_index_of(&route) {
+            Some(index) => hits[index] += 1,
+            None => not_found += 1,
+        }
+    }
+
+    writeln!(
+        out,
+        "\n  {} request lines, e.g. {:?}, routed by (Method, &str) view:",
+        REQUESTS, requests[1]
+    )?;
+    writeln!(
+        out,
+        "    {:<8} {:<9} {:<14} {:>6}",
+        "method", "path", "handler", "hits"
+    )?;
+    for (((method, path), handler), count) in routes.iter().zip(&hits) {
+        writeln!(
+            out,
+            "    {:<8} {:<9} {:<14} {:>6}",
+            format!("{method:?}"),
+            path,
+            handler,
+            count
+        )?;
+    }
+    writeln!(out, "    404 not found: {}", not_found)?;
+    writeln!(out, "    400 bad request: {}", bad_request)?;
+
+    writeln!(
+        out,
+        "\n  The table owns its paths as Strings; each lookup borrows the path"
+    )?;
+    writeln!(
+        out,
+        "  from the request line. IndexMap keeps the routes in registration order,"
+    )?;
+    writeln!(
+        out,
+        "  so get_index_of doubles as an index into the per-route hit counters."
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+// Conditional bulk extraction: moving every entry that matches a predicate
+// out of a map, into another collection.
+//
+// HashMap::extract_if (stable since Rust 1.88, known as drain_filter while
+// it was unstable) does this in a single pass, handing out owned (K, V)
+// pairs. On older toolchains the usual fallbacks are:
+//
+//   retain + collect    - clone the matches out, then retain the rest.
+//                         Two passes, needs K: Clone + V: Clone.
+//   keys + remove       - collect matching keys, then remove() each one.
+//                         Two passes plus a re-hash per match.
+//   partition rebuild   - move the whole map into two new maps.
+//                         One pass, but reallocates even if nothing matches.
+//
+// Caveat: extract_if is lazy. Only the entries the iterator actually visits
+// are tested and removed - dropping it early, or panicking in the
+// predicate, leaves the rest of the map untouched.
+
+use demo_framework::section;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "extract_if_basics",
+        "Moving matching entries into another map with extract_if",
+        extract_if_basics,
+    )?;
+
+    section(
+        out,
+        "stable_fallbacks",
+        "The same extraction without extract_if: retain + collect and friends",
+        stable_fallbacks,
+    )?;
+
+    section(
+        out,
+        "partial_extraction",
+        "extract_if is lazy: stopping early leaves the rest in place",
+        partial_extraction,
+    )?;
+
+    Ok(())
+}
+
+/// Moves every entry matching `pred` out of `map` with `extract_if`.
+pub fn extract_matching<K, V, F>(map: &mut HashMap<K, V>, mut pred: F) -> HashMap<K, V>
+where
+    K: Eq + Hash,
+    F: FnMut(&K, &V) -> bool,
+{
+    // extract_if hands the predicate &mut V; this wrapper only needs to read
+    map.extract_if(|k, v| pred(k, v)).collect()
+}
+
+/// Moves every entry matching `pred` out of `map` without `extract_if`.
+///
+/// This is the retain + collect fallback: clone the matches into the
+/// result, then drop them from the map.
+pub fn extract_matching_fallback<K, V, F>(map: &mut HashMap<K, V>, mut pred: F) -> HashMap<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+    F: FnMut(&K, &V) -> bool,
+{
+    let extracted: HashMap<K, V> = map
+        .iter()
+        .filter(|(k, v)| pred(k, v))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    map.retain(|k, _| !extracted.contains_key(k));
+    extracted
+}
+
+/// Demonstrates extract_if: one pass, owned entries, no cloning.
+pub fn extract_if_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "extract_if Basics")?;
+
+    let mut inbox: HashMap<u32, String> =
+        (1..=8).map(|id| (id, format!("message-{}", id))).collect();
+    writeln!(out, "Inbox: {:?}", inbox)?;
+
+    // Move every even id into the archive - values are moved, not cloned
+    let archive: HashMap<u32, String> = inbox.extract_if(|id, _| id.is_multiple_of(2)).collect();
+    writeln!(out, "\nArchived (even ids): {:?}", archive)?;
+    writeln!(out, "Inbox after: {:?}", inbox)?;
+
+    // The predicate gets &mut V, so entries that stay can be edited in the same pass
+    let mut retries: HashMap<&str, u32> = HashMap::from([("a", 0), ("b", 2), ("c", 1)]);
+    let exhausted: Vec<&str> = retries
+        .extract_if(|_, attempts| {
+            *attempts += 1;
+            *attempts > 2
+        })
+        .map(|(name, _)| name)
+        .collect();
+    writeln!(
+        out,
+        "\nBumped attempts and dropped exhausted jobs {:?}",
+        exhausted
+    )?;
+    writeln!(out, "Remaining: {:?}", retries)?;
+
+    Ok(())
+}
+
+/// Demonstrates the pre-1.88 ways to do the same extraction.
+///
+/// All of them produce the same maps as extract_if; they differ in cost
+/// (see the Extract_If benchmarks) and in what they require of K and V.
+pub fn stable_fallbacks(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Stable Fallbacks")?;
+
+    let original: HashMap<u32, String> =
+        (1..=8).map(|id| (id, format!("message-{}", id))).collect();
+    let is_even = |id: &u32, _: &String| id.is_multiple_of(2);
+
+    // 1. retain + collect: clone matches out, then retain the rest
+    let mut inbox: HashMap<u32, String> = original.clone();
+    let archive: HashMap<u32, String> = extract_matching_fallback(&mut inbox, is_even);
+    writeln!(out, "retain + collect:   archived {:?}", archive)?;
+    writeln!(out, "                    kept     {:?}", inbox)?;
+
+    // 2. keys + remove: no Clone on V, but each match is hashed again
+    let mut inbox: HashMap<u32, String> = original.clone();
+    let keys: Vec<u32> = inbox
+        .iter()
+        .filter(|(id, msg)| is_even(id, msg))
+        .map(|(id, _)| *id)
+        .collect();
+    let archive: HashMap<u32, String> = keys
+        .into_iter()
+        .filter_map(|id| inbox.remove_entry(&id))
+        .collect();
+    writeln!(out, "keys + remove:      archived {:?}", archive)?;
+    writeln!(out, "                    kept     {:?}", inbox)?;
+
+    // 3. partition rebuild: one pass, no Clone, but both maps are rebuilt
+    let (archive, inbox): (HashMap<u32, String>, HashMap<u32, String>) = original
+        .clone()
+        .into_iter()
+        .partition(|(id, msg)| is_even(id, msg));
+    writeln!(out, "partition rebuild:  archived {:?}", archive)?;
+    writeln!(out, "                    kept     {:?}", inbox)?;
+
+    // And the one-liner they all stand in for
+    let mut inbox: HashMap<u32, String> = original;
+    let archive: HashMap<u32, String> = extract_matching(&mut inbox, is_even);
+    writeln!(out, "extract_if:         archived {:?}", archive)?;
+    writeln!(out, "                    kept     {:?}", inbox)?;
+
+    Ok(())
+}
+
+/// Demonstrates the laziness caveat.
+///
+/// extract_if only removes entries as the iterator reaches them. Taking a
+/// few and dropping the iterator - or a panic inside the predicate, which
+/// unwinds through the iterator's Drop - stops the extraction part-way:
+/// whatever was already yielded is gone, everything else stays.
+pub fn partial_extraction(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Partial Extraction")?;
+
+    let mut queue: HashMap<u32, &str> = (1..=6).map(|id| (id, "pending")).collect();
+    writeln!(out, "Queue: {} pending jobs", queue.len())?;
+
+    // Take at most 2 jobs; the iterator is dropped after the second match
+    let batch: Vec<(u32, &str)> = queue.extract_if(|_, _| true).take(2).collect();
+    writeln!(
+        out,
+        "\nextract_if(..).take(2) moved {} jobs out",
+        batch.len()
+    )?;
+    writeln!(out, "Queue still holds {} jobs", queue.len())?;
+
+    // Which two were taken depends on the map's iteration order - so code
+    // that stops early must not assume *which* entries it extracted.
+    let taken_are_gone: bool = batch.iter().all(|(id, _)| !queue.contains_key(id));
+    writeln!(
+        out,
+        "Taken jobs are gone from the queue: {}",
+        taken_are_gone
+    )?;
+
+    // The retain + collect fallback has no such middle state: extraction
+    // happens fully in the collect, and retain only runs once it succeeded.
+
+    Ok(())
+}
@@ -0,0 +1,314 @@
+// f64 addition rounds after every step, so (a + b) + c and a + (b + c) can
+// differ, and a plain loop over a map's values depends on the order the map
+// hands them out. iteration_order_examples shows the symptom: ten HashMaps
+// holding the same sales give several different totals. This module looks
+// at the fixes:
+//
+//   naive       s += x                     error grows with n: up to about
+//                                          n * eps * sum|x|
+//   pairwise    sum each half, add them    error grows with log2(n), but
+//                                          the halves still depend on order
+//   Kahan       s += x, and keep what      error about 2 * eps * |sum|, plus
+//   (Neumaier)  the rounding dropped in    a term in eps^2 that only shows
+//               a second variable          up with extreme cancellation
+//   exact       keep every rounding error  the correctly rounded sum of the
+//               as a list of partials      values: one answer for any order
+//
+// eps is f64::EPSILON / 2, about 1.1e-16: the largest relative error of a
+// single rounding.
+//
+// Classic Kahan folds the lost low bits back in on the next addition. It
+// assumes the running sum is larger than each new value; Neumaier's variant
+// checks which of the two is larger and so also survives a large value
+// arriving after small ones.
+//
+// The exact sum is Shewchuk's algorithm (Python's math.fsum). Its state is a
+// Vec<f64> of non-overlapping partials whose exact sum is the exact sum of
+// everything added so far; the vec stays short, since non-overlapping f64s
+// can't be more than about 40 in number. Only the final step rounds, once, so
+// every order of the same values gives the same bits.
+
+use crate::iteration_order_examples::sales;
+use demo_framework::section;
+use indexmap::IndexMap;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "order_changes_the_sum",
+        "Why the order of f64 additions matters",
+        order_changes_the_sum,
+    )?;
+
+    section(
+        out,
+        "summation_algorithms",
+        "Naive, pairwise, Kahan, Neumaier and exact sums side by side",
+        summation_algorithms,
+    )?;
+
+    section(
+        out,
+        "map_order_sums",
+        "Summing the same values in HashMap, BTreeMap and IndexMap order",
+        map_order_sums,
+    )?;
+
+    Ok(())
+}
+
+/// Adds the values left to right, rounding after each step.
+pub fn naive_sum(values: impl IntoIterator<Item = f64>) -> f64 {
+    values.into_iter().fold(0.0, |sum, x| sum + x)
+}
+
+/// Sums each half recursively and adds the two results. Below 8 values it
+/// falls back to a loop, the usual cut-off to keep the recursion cheap.
+pub fn pairwise_sum(values: &[f64]) -> f64 {
+    if values.len() <= 8 {
+        return naive_sum(values.iter().copied());
+    }
+    let (left, right) = values.split_at(values.len() / 2);
+    pairwise_sum(left) + pairwise_sum(right)
+}
+
+/// Kahan's compensated sum: `compensation` carries the low bits each
+/// addition rounded away into the next one.
+pub fn kahan_sum(values: impl IntoIterator<Item = f64>) -> f64 {
+    let mut sum: f64 = 0.0;
+    let mut compensation: f64 = 0.0;
+    for x in values {
+        let y: f64 = x - compensation;
+        let t: f64 = sum + y;
+        // (t - sum) is what actually got added; y minus that is what didn't
+        compensation = (t - sum) - y;
+        sum = t;
+    }
+    sum
+}
+
+/// Neumaier's improvement on Kahan: the lost bits are taken from whichever
+/// operand is smaller, and added back once at the end.
+pub fn neumaier_sum(values: impl IntoIterator<Item = f64>) -> f64 {
+    let mut sum: f64 = 0.0;
+    let mut compensation: f64 = 0.0;
+    for x in values {
+        let t: f64 = sum + x;
+        if sum.abs() >= x.abs() {
+            compensation += (sum - t) + x;
+        } else {
+            compensation += (x - t) + sum;
+        }
+        sum = t;
+    }
+    sum + compensation
+}
+
+/// The exact sum of the values, rounded once to the nearest f64 (ties to
+/// even), so any order of the same values gives the same result.
+///
+/// Expects finite values whose partial sums don't overflow.
+pub fn exact_sum(values: impl IntoIterator<Item = f64>) -> f64 {
+    // Non-overlapping, in increasing magnitude; their exact sum is the
+    // exact sum so far
+    let mut partials: Vec<f64> = Vec::new();
+    for mut x in values {
+        let mut kept: usize = 0;
+        for j in 0..partials.len() {
+            let mut y: f64 = partials[j];
+            if x.abs() < y.abs() {
+                std::mem::swap(&mut x, &mut y);
+            }
+            // hi + lo == x + y exactly (Fast2Sum, since |x| >= |y|)
+            let hi: f64 = x + y;
+            let lo: f64 = y - (hi - x);
+            if lo != 0.0 {
+                partials[kept] = lo;
+                kept += 1;
+            }
+            x = hi;
+        }
+        partials.truncate(kept);
+        partials.push(x);
+    }
+    round_partials(&partials)
+}
+
+/// Adds the partials from the largest down, stopping at the first inexact
+/// addition, then corrects a tie that the remaining partials break.
+fn round_partials(partials: &[f64]) -> f64 {
+    let Some((&top, rest)) = partials.split_last() else {
+        return 0.0;
+    };
+    let mut hi: f64 = top;
+    let mut lo: f64 = 0.0;
+    let mut remaining: usize = rest.len();
+    while remaining > 0 {
+        remaining -= 1;
+        let x: f64 = hi;
+        let y: f64 = rest[remaining];
+        hi = x + y;
+        lo = y - (hi - x);
+        if lo != 0.0 {
+            break;
+        }
+    }
+    // hi + lo was a tie rounded to even; the partials below lo decide
+    // which way it really goes
+    if remaining > 0 && lo.signum() == rest[remaining - 1].signum() {
+        let y: f64 = lo * 2.0;
+        let x: f64 = hi + y;
+        if y == x - hi {
+            hi = x;
+        }
+    }
+    hi
+}
+
+/// Error of `sum` against `exact`, in units in the last place of `exact`.
+pub fn ulps(sum: f64, exact: f64) -> f64 {
+    let ulp: f64 = f64::from_bits(exact.abs().to_bits() + 1) - exact.abs();
+    (sum - exact).abs() / ulp
+}
+
+/// Demonstrates non-associative addition on small inputs.
+pub fn order_changes_the_sum(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Order Changes the Sum")?;
+
+    writeln!(out, "(0.1 + 0.2) + 0.3 = {:?}", (0.1 + 0.2) + 0.3)?;
+    writeln!(out, "0.1 + (0.2 + 0.3) = {:?}", 0.1 + (0.2 + 0.3))?;
+
+    // Each 1.0 is below half an ulp of 1e16, so it rounds away
+    let big_first: [f64; 3] = [1e16, 1.0, 1.0];
+    let small_first: [f64; 3] = [1.0, 1.0, 1e16];
+    writeln!(out, "\n[1e16, 1.0, 1.0] summed: {:?}", naive_sum(big_first))?;
+    writeln!(out, "[1.0, 1.0, 1e16] summed: {:?}", naive_sum(small_first))?;
+    writeln!(out, "exact_sum gives {:?} for both", exact_sum(big_first))?;
+
+    // Cancellation: the big values leave only the rounding error behind
+    let cancelling: [f64; 4] = [1.0, 1e100, 1.0, -1e100];
+    writeln!(out, "\n[1.0, 1e100, 1.0, -1e100]:")?;
+    writeln!(out, "  naive_sum:    {:?}", naive_sum(cancelling))?;
+    writeln!(out, "  kahan_sum:    {:?}", kahan_sum(cancelling))?;
+    writeln!(out, "  neumaier_sum: {:?}", neumaier_sum(cancelling))?;
+    writeln!(out, "  exact_sum:    {:?}", exact_sum(cancelling))?;
+
+    Ok(())
+}
+
+/// Demonstrates the error of each algorithm against the exact sum.
+pub fn summation_algorithms(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Summation Algorithms")?;
+
+    // 0.1 isn't representable, but a million copies of the nearest f64
+    // still have one exact sum
+    let tenths: Vec<f64> = vec![0.1; 1_000_000];
+    // Alternating signs and magnitudes: lots of cancellation
+    let mixed: Vec<f64> = (0..100_000)
+        .map(|i| {
+            let magnitude: f64 = 10f64.powi(i % 12) / 7.0;
+            if i % 2 == 0 {
+                magnitude
+            } else {
+                -magnitude * 0.999
+            }
+        })
+        .collect();
+    let amounts: Vec<f64> = sales(100_000, 4).into_iter().map(|(_, x)| x).collect();
+
+    let inputs: [(&str, &[f64]); 3] = [
+        ("1M x 0.1", &tenths),
+        ("100k alternating", &mixed),
+        ("100k sales", &amounts),
+    ];
+    writeln!(
+        out,
+        "{:<18} {:>24} {:>9} {:>9} {:>9} {:>9}",
+        "input", "exact_sum", "naive", "pairwise", "kahan", "neumaier"
+    )?;
+    for (label, values) in inputs {
+        let exact: f64 = exact_sum(values.iter().copied());
+        writeln!(
+            out,
+            "{:<18} {:>24.6e} {:>9.0} {:>9.0} {:>9.0} {:>9.0}",
+            label,
+            exact,
+            ulps(naive_sum(values.iter().copied()), exact),
+            ulps(pairwise_sum(values), exact),
+            ulps(kahan_sum(values.iter().copied()), exact),
+            ulps(neumaier_sum(values.iter().copied()), exact)
+        )?;
+    }
+    writeln!(out, "(errors in ulps of the exact sum)")?;
+
+    Ok(())
+}
+
+/// A summation function over a slice.
+type SliceSum = fn(&[f64]) -> f64;
+
+/// Demonstrates which sums come out the same whatever order a map iterates
+/// in.
+pub fn map_order_sums(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Map Order Sums")?;
+
+    let arrivals: Vec<(u64, f64)> = sales(10_000, 9);
+    let sorted: BTreeMap<u64, f64> = arrivals.iter().copied().collect();
+    let inserted: IndexMap<u64, f64> = arrivals.iter().copied().collect();
+    // Ten HashMaps, each with its own RandomState and so its own order
+    let mut orders: Vec<Vec<f64>> = vec![
+        sorted.values().copied().collect(),
+        inserted.values().copied().collect(),
+    ];
+    for _ in 0..10 {
+        let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+        orders.push(hash.values().copied().collect());
+    }
+    writeln!(
+        out,
+        "{} sales, summed in BTreeMap, IndexMap and 10 HashMap orders\n",
+        arrivals.len()
+    )?;
+
+    let exact: f64 = exact_sum(orders[0].iter().copied());
+    let algorithms: [(&str, SliceSum); 5] = [
+        ("naive_sum", |values| naive_sum(values.iter().copied())),
+        ("pairwise_sum", pairwise_sum),
+        ("kahan_sum", |values| kahan_sum(values.iter().copied())),
+        ("neumaier_sum", |values| {
+            neumaier_sum(values.iter().copied())
+        }),
+        ("exact_sum", |values| exact_sum(values.iter().copied())),
+    ];
+    writeln!(
+        out,
+        "{:<14} {:>15} {:>16}",
+        "algorithm", "distinct sums", "worst error"
+    )?;
+    for (name, sum) in algorithms {
+        let sums: Vec<f64> = orders.iter().map(|values| sum(values)).collect();
+        let mut distinct: Vec<u64> = sums.iter().map(|s| s.to_bits()).collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        let worst: f64 = sums.iter().map(|&s| ulps(s, exact)).fold(0.0, f64::max);
+        writeln!(
+            out,
+            "{:<14} {:>15} {:>11.0} ulps",
+            name,
+            distinct.len(),
+            worst
+        )?;
+    }
+    writeln!(
+        out,
+        "\nThe compensated sums land on the exact total here, so every order agrees."
+    )?;
+    writeln!(
+        out,
+        "Only exact_sum promises that for any input; the others only bound the error."
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,595 @@
+// A graph is nodes plus edges, and Rust has no graph type in std: you build
+// one from the collections you already know. The same directed graph,
+//
+//   0 → 1, 0 → 2, 1 → 3, 2 → 3, 3 → 4
+//
+// four ways:
+//
+//   HashMap<N, Vec<N>>   {0: [1, 2], 1: [3], 2: [3], 3: [4]}
+//                        any node type (names, ids); a hash per lookup
+//
+//   Vec<Vec<usize>>      [[1, 2], [3], [3], [4], []]
+//                        nodes are 0..n; one heap allocation per node
+//
+//   matrix (Grid<bool>)  row i, column j set when i → j
+//                        O(1) edge test, but n² cells however few edges
+//
+//   CSR                  offsets: [0, 2, 3, 4, 5, 5]
+//   (compressed sparse   targets: [1, 2, 3, 3, 4]
+//    row)                node i's neighbors are targets[offsets[i]..offsets[i + 1]]
+//
+// CSR is the adjacency list with every Vec concatenated into one: two
+// allocations in total, and a scan over all edges reads memory front to
+// back. It is what graph libraries use for large graphs that don't change;
+// adding an edge means rebuilding the arrays.
+//
+// BFS and DFS only need "the neighbors of n", so the Adjacency trait gives all
+// four the same traversal code.
+
+use crate::grid_examples::Grid;
+use demo_framework::diagram::{self, Diagram};
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "four_representations",
+        "One graph as a HashMap, a Vec<Vec<usize>>, a matrix and CSR",
+        four_representations,
+    )?;
+
+    section(
+        out,
+        "bfs_and_dfs",
+        "The same BFS and DFS over every representation",
+        bfs_and_dfs,
+    )?;
+
+    section(
+        out,
+        "memory_and_edge_checks",
+        "What each representation costs in memory and per query",
+        memory_and_edge_checks,
+    )?;
+
+    section(
+        out,
+        "practical_dependency_order",
+        "Practical demo: interning package names into a CSR graph",
+        practical_dependency_order,
+    )?;
+
+    section(
+        out,
+        "traversal_at_scale",
+        "Scanning every edge of a 100,000-node graph",
+        traversal_at_scale,
+    )?;
+
+    Ok(())
+}
+
+/// A directed edge `from → to` between dense node ids.
+pub type Edge = (usize, usize);
+
+/// Adjacency lists keyed by any hashable node.
+pub type HashGraph<N> = HashMap<N, Vec<N>>;
+
+/// Adjacency lists indexed by node id.
+pub type AdjacencyList = Vec<Vec<usize>>;
+
+/// A set of visited nodes, sized for the representation.
+pub trait Visited<N> {
+    /// Marks `node`; `false` if it was already marked.
+    fn visit(&mut self, node: N) -> bool;
+}
+
+impl<N: Hash + Eq> Visited<N> for HashSet<N> {
+    fn visit(&mut self, node: N) -> bool {
+        self.insert(node)
+    }
+}
+
+impl Visited<usize> for Vec<bool> {
+    fn visit(&mut self, node: usize) -> bool {
+        !std::mem::replace(&mut self[node], true)
+    }
+}
+
+/// What BFS and DFS need from a graph.
+pub trait Adjacency {
+    type Node: Copy;
+    type Visited: Visited<Self::Node>;
+
+    /// `node`'s out-neighbors, in the representation's order.
+    fn neighbors(&self, node: Self::Node) -> impl Iterator<Item = Self::Node> + '_;
+
+    /// An empty visited set: a HashSet for arbitrary nodes, a Vec<bool>
+    /// for dense ids.
+    fn visited(&self) -> Self::Visited;
+}
+
+impl<N: Copy + Hash + Eq> Adjacency for HashGraph<N> {
+    type Node = N;
+    type Visited = HashSet<N>;
+
+    fn neighbors(&self, node: N) -> impl Iterator<Item = N> + '_ {
+        // A node with no entry has no out-edges
+        self.get(&node).into_iter().flatten().copied()
+    }
+
+    fn visited(&self) -> HashSet<N> {
+        HashSet::new()
+    }
+}
+
+impl Adjacency for AdjacencyList {
+    type Node = usize;
+    type Visited = Vec<bool>;
+
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self[node].iter().copied()
+    }
+
+    fn visited(&self) -> Vec<bool> {
+        vec![false; self.len()]
+    }
+}
+
+impl Adjacency for Grid<bool> {
+    type Node = usize;
+    type Visited = Vec<bool>;
+
+    /// Scans the whole row: O(n) per node, however few edges it has.
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.row(node)
+            .iter()
+            .enumerate()
+            .filter(|&(_, &edge)| edge)
+            .map(|(to, _)| to)
+    }
+
+    fn visited(&self) -> Vec<bool> {
+        vec![false; self.rows()]
+    }
+}
+
+/// Compressed sparse row: every adjacency list concatenated into `targets`,
+/// with `offsets[i]..offsets[i + 1]` marking node `i`'s slice.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Csr {
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+}
+
+impl Csr {
+    /// Builds the arrays with a counting sort on the source node, so each
+    /// node's neighbors keep their order in `edges`.
+    pub fn from_edges(nodes: usize, edges: &[Edge]) -> Self {
+        let mut offsets: Vec<usize> = vec![0; nodes + 1];
+        for &(from, _) in edges {
+            offsets[from + 1] += 1;
+        }
+        for i in 0..nodes {
+            offsets[i + 1] += offsets[i];
+        }
+        let mut next: Vec<usize> = offsets[..nodes].to_vec();
+        let mut targets: Vec<usize> = vec![0; edges.len()];
+        for &(from, to) in edges {
+            targets[next[from]] = to;
+            next[from] += 1;
+        }
+        Csr { offsets, targets }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    pub fn targets(&self) -> &[usize] {
+        &self.targets
+    }
+
+    /// `node`'s neighbors as one contiguous slice.
+    pub fn neighbor_slice(&self, node: usize) -> &[usize] {
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    /// The graph as a directed diagram, nodes labelled by `name`.
+    pub fn diagram(&self, diagram_name: &str, name: impl Fn(usize) -> String) -> Diagram {
+        let mut diagram: Diagram = Diagram::directed(diagram_name);
+        for node in 0..self.node_count() {
+            diagram.node(node, name(node));
+        }
+        for node in 0..self.node_count() {
+            for &target in self.neighbor_slice(node) {
+                diagram.edge(node, target, None);
+            }
+        }
+        diagram
+    }
+}
+
+impl Adjacency for Csr {
+    type Node = usize;
+    type Visited = Vec<bool>;
+
+    fn neighbors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.neighbor_slice(node).iter().copied()
+    }
+
+    fn visited(&self) -> Vec<bool> {
+        vec![false; self.node_count()]
+    }
+}
+
+pub fn hash_graph(edges: &[Edge]) -> HashGraph<usize> {
+    let mut graph: HashGraph<usize> = HashMap::new();
+    for &(from, to) in edges {
+        graph.entry(from).or_default().push(to);
+    }
+    graph
+}
+
+pub fn adjacency_list(nodes: usize, edges: &[Edge]) -> AdjacencyList {
+    let mut graph: AdjacencyList = vec![Vec::new(); nodes];
+    for &(from, to) in edges {
+        graph[from].push(to);
+    }
+    graph
+}
+
+pub fn adjacency_matrix(nodes: usize, edges: &[Edge]) -> Grid<bool> {
+    let mut matrix: Grid<bool> = Grid::filled(nodes, nodes, false);
+    for &(from, to) in edges {
+        matrix[(from, to)] = true;
+    }
+    matrix
+}
+
+/// Breadth-first order from `start`: every node at distance d before any
+/// at distance d + 1.
+pub fn bfs<G: Adjacency>(graph: &G, start: G::Node) -> Vec<G::Node> {
+    let mut visited: G::Visited = graph.visited();
+    let mut order: Vec<G::Node> = Vec::new();
+    let mut queue: VecDeque<G::Node> = VecDeque::new();
+    visited.visit(start);
+    queue.push_back(start);
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for next in graph.neighbors(node) {
+            if visited.visit(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    order
+}
+
+/// Depth-first preorder from `start`, visiting neighbors in order, as the
+/// recursive version would. Iterative, so a long path can't overflow the
+/// stack.
+pub fn dfs<G: Adjacency>(graph: &G, start: G::Node) -> Vec<G::Node> {
+    let mut visited: G::Visited = graph.visited();
+    let mut order: Vec<G::Node> = Vec::new();
+    let mut stack: Vec<G::Node> = vec![start];
+    let mut neighbors: Vec<G::Node> = Vec::new();
+    while let Some(node) = stack.pop() {
+        // A node can be pushed more than once before it's visited
+        if !visited.visit(node) {
+            continue;
+        }
+        order.push(node);
+        // Reversed, so the first neighbor is popped first
+        neighbors.clear();
+        neighbors.extend(graph.neighbors(node));
+        stack.extend(neighbors.iter().rev());
+    }
+    order
+}
+
+/// Sum of every edge's target, node by node: a full scan of the graph's
+/// edges that the optimizer can't skip.
+pub fn edge_checksum<G: Adjacency<Node = usize>>(graph: &G, nodes: usize) -> usize {
+    (0..nodes).flat_map(|node| graph.neighbors(node)).sum()
+}
+
+/// Deterministic random edges: `degree` out-edges per node.
+pub fn random_edges(nodes: usize, degree: usize, seed: u64) -> Vec<Edge> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..nodes)
+        .flat_map(|from| (0..degree).map(move |_| from))
+        .map(|from| (from, rng.below(nodes as u64) as usize))
+        .collect()
+}
+
+fn sample_edges() -> Vec<Edge> {
+    vec![(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (4, 1), (5, 4)]
+}
+
+/// Demonstrates the layouts of one small graph.
+pub fn four_representations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Four Representations")?;
+
+    let edges: Vec<Edge> = sample_edges();
+    writeln!(out, "Edges: {:?}", edges)?;
+
+    let hashed: HashGraph<usize> = hash_graph(&edges);
+    let mut entries: Vec<(&usize, &Vec<usize>)> = hashed.iter().collect();
+    entries.sort();
+    writeln!(out, "\nHashMap<usize, Vec<usize>> (sorted for display):")?;
+    writeln!(out, "  {:?}", entries)?;
+    writeln!(
+        out,
+        "  a node with no out-edges needs no entry: neighbors() of a missing key is empty"
+    )?;
+
+    let list: AdjacencyList = adjacency_list(6, &edges);
+    writeln!(out, "\nVec<Vec<usize>>:")?;
+    writeln!(out, "  {:?}", list)?;
+
+    let matrix: Grid<bool> = adjacency_matrix(6, &edges);
+    writeln!(out, "\nAdjacency matrix (row = from, column = to):")?;
+    writeln!(out, "     0 1 2 3 4 5")?;
+    for (from, row) in matrix.iter_rows().enumerate() {
+        let cells: Vec<&str> = row
+            .iter()
+            .map(|&edge| if edge { "1" } else { "." })
+            .collect();
+        writeln!(out, "  {}  {}", from, cells.join(" "))?;
+    }
+
+    let csr: Csr = Csr::from_edges(6, &edges);
+    writeln!(out, "\nCSR:")?;
+    writeln!(out, "  offsets: {:?}", csr.offsets())?;
+    writeln!(out, "  targets: {:?}", csr.targets())?;
+    for node in [0, 3, 5] {
+        writeln!(
+            out,
+            "  node {}: targets[{}..{}] = {:?}",
+            node,
+            csr.offsets()[node],
+            csr.offsets()[node + 1],
+            csr.neighbor_slice(node)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Demonstrates that one generic BFS and DFS run over all four.
+pub fn bfs_and_dfs(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "BFS and DFS")?;
+
+    let edges: Vec<Edge> = sample_edges();
+    let hashed: HashGraph<usize> = hash_graph(&edges);
+    let list: AdjacencyList = adjacency_list(6, &edges);
+    let matrix: Grid<bool> = adjacency_matrix(6, &edges);
+    let csr: Csr = Csr::from_edges(6, &edges);
+
+    // The matrix lists neighbors by column, so the others agree with it
+    // only because the sample's edges are already sorted
+    writeln!(out, "From node 0:")?;
+    writeln!(out, "  {:<16} {:<18} DFS", "", "BFS")?;
+    let rows: [(&str, Vec<usize>, Vec<usize>); 4] = [
+        ("HashMap", bfs(&hashed, 0), dfs(&hashed, 0)),
+        ("Vec<Vec<usize>>", bfs(&list, 0), dfs(&list, 0)),
+        ("matrix", bfs(&matrix, 0), dfs(&matrix, 0)),
+        ("CSR", bfs(&csr, 0), dfs(&csr, 0)),
+    ];
+    for (name, breadth, depth) in &rows {
+        writeln!(
+            out,
+            "  {:<16} {:<18} {:?}",
+            name,
+            format!("{:?}", breadth),
+            depth
+        )?;
+    }
+    writeln!(
+        out,
+        "BFS reaches 3 (distance 2) before 4 (distance 3); DFS follows 0 → 1 → 3 → 4 first"
+    )?;
+    writeln!(
+        out,
+        "Node 5 only has an edge out, so nothing reaches it from 0"
+    )?;
+
+    // The HashMap version takes any node type
+    let mut towns: HashGraph<&str> = HashMap::new();
+    towns.insert("Ash", vec!["Birch", "Cedar"]);
+    towns.insert("Birch", vec!["Elm"]);
+    towns.insert("Cedar", vec!["Elm", "Fir"]);
+    writeln!(
+        out,
+        "\nHashGraph<&str> BFS from Ash: {:?}",
+        bfs(&towns, "Ash")
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the memory footprint and edge test of each layout.
+pub fn memory_and_edge_checks(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Memory and Edge Checks")?;
+
+    let word: usize = size_of::<usize>();
+    writeln!(
+        out,
+        "{:>7} {:>5}  {:>12} {:>12} {:>12}",
+        "nodes", "edges", "Vec<Vec>", "matrix", "CSR"
+    )?;
+    let kib = |bytes: usize| -> String { format!("{:.1} KiB", bytes as f64 / 1024.0) };
+    for (nodes, degree) in [(100, 4), (1_000, 8), (10_000, 8)] {
+        let edges: usize = nodes * degree;
+        // A Vec header is 3 words; each edge is one usize either way
+        let list: usize = nodes * 3 * word + edges * word;
+        let matrix: usize = nodes * nodes * size_of::<bool>();
+        let csr: usize = (nodes + 1) * word + edges * word;
+        writeln!(
+            out,
+            "{:>7} {:>5}  {:>12} {:>12} {:>12}",
+            nodes,
+            edges,
+            kib(list),
+            kib(matrix),
+            kib(csr)
+        )?;
+    }
+    writeln!(
+        out,
+        "Not counting allocator overhead. The matrix grows with nodes², the others with nodes + edges"
+    )?;
+
+    let edges: Vec<Edge> = sample_edges();
+    let list: AdjacencyList = adjacency_list(6, &edges);
+    let matrix: Grid<bool> = adjacency_matrix(6, &edges);
+    let csr: Csr = Csr::from_edges(6, &edges);
+    writeln!(out, "\nIs there an edge 3 → 4?")?;
+    writeln!(
+        out,
+        "  matrix[(3, 4)]                   {}  O(1)",
+        matrix[(3, 4)]
+    )?;
+    writeln!(
+        out,
+        "  list[3].contains(&4)             {}  O(degree)",
+        list[3].contains(&4)
+    )?;
+    writeln!(
+        out,
+        "  csr.neighbor_slice(3).contains   {}  O(degree), or O(log degree) if sorted",
+        csr.neighbor_slice(3).contains(&4)
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates building a dense-id graph from names, then a BFS by level.
+pub fn practical_dependency_order(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Interning Names into a CSR Graph")?;
+
+    // "a depends on b" edges between package names
+    let depends: [(&str, &str); 8] = [
+        ("app", "web"),
+        ("app", "db"),
+        ("web", "http"),
+        ("web", "json"),
+        ("db", "pool"),
+        ("db", "json"),
+        ("http", "bytes"),
+        ("pool", "sync"),
+    ];
+
+    // Intern each name once: names[id] is the name, ids[name] the id
+    let mut names: Vec<&str> = Vec::new();
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    let mut intern = |name: &'static str| -> usize {
+        *ids.entry(name).or_insert_with(|| {
+            names.push(name);
+            names.len() - 1
+        })
+    };
+    let edges: Vec<Edge> = depends
+        .iter()
+        .map(|&(from, to)| (intern(from), intern(to)))
+        .collect();
+    writeln!(out, "Interned: {:?}", names)?;
+    writeln!(out, "Edges as ids: {:?}", edges)?;
+
+    let csr: Csr = Csr::from_edges(names.len(), &edges);
+
+    // BFS by level: everything app needs, nearest first
+    let mut level: Vec<usize> = vec![0];
+    let mut visited: Vec<bool> = csr.visited();
+    visited.visit(0);
+    let mut depth: usize = 0;
+    while !level.is_empty() {
+        let labels: Vec<&str> = level.iter().map(|&id| names[id]).collect();
+        writeln!(out, "  depth {}: {}", depth, labels.join(", "))?;
+        let mut next: Vec<usize> = Vec::new();
+        for &node in &level {
+            for &dependency in csr.neighbor_slice(node) {
+                if visited.visit(dependency) {
+                    next.push(dependency);
+                }
+            }
+        }
+        level = next;
+        depth += 1;
+    }
+    writeln!(
+        out,
+        "json is needed by both web and db but listed once: the visited Vec<bool> is indexed by id"
+    )?;
+    diagram::export(out, || {
+        csr.diagram("dependencies", |id| names[id].to_string())
+    })?;
+
+    Ok(())
+}
+
+/// Demonstrates the cost of scanning every edge in each layout.
+pub fn traversal_at_scale(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Traversal at Scale")?;
+
+    let nodes: usize = 100_000;
+    let edges: Vec<Edge> = random_edges(nodes, 8, 42);
+    let hashed: HashGraph<usize> = hash_graph(&edges);
+    let list: AdjacencyList = adjacency_list(nodes, &edges);
+    let csr: Csr = Csr::from_edges(nodes, &edges);
+    writeln!(
+        out,
+        "{} nodes, {} random edges (a matrix would need 10^10 cells)",
+        nodes,
+        edges.len()
+    )?;
+
+    let timed = |scan: &dyn Fn() -> usize| -> (usize, Duration) {
+        let start: Instant = Instant::now();
+        let checksum: usize = scan();
+        (checksum, start.elapsed())
+    };
+    let scans: [(&str, (usize, Duration)); 3] = [
+        ("HashMap", timed(&|| edge_checksum(&hashed, nodes))),
+        ("Vec<Vec<usize>>", timed(&|| edge_checksum(&list, nodes))),
+        ("CSR", timed(&|| edge_checksum(&csr, nodes))),
+    ];
+    writeln!(out, "Summing every edge's target, node by node:")?;
+    for (name, (_, elapsed)) in &scans {
+        writeln!(out, "  {:<16} {:?}", name, elapsed)?;
+    }
+    writeln!(
+        out,
+        "Same sum from all three: {}",
+        scans
+            .iter()
+            .all(|(_, (checksum, _))| *checksum == scans[0].1.0)
+    )?;
+    writeln!(
+        out,
+        "HashMap hashes each node id; Vec<Vec> follows one pointer per node; CSR reads one array front to back"
+    )?;
+
+    let reached: usize = bfs(&csr, 0).len();
+    writeln!(
+        out,
+        "BFS from node 0 over the CSR reaches {} nodes",
+        reached
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,418 @@
+// Two-dimensional data (images, game boards, spreadsheets, matrices) fits in
+// a single Vec: store the rows one after another and compute the index.
+//
+//   logical 3x4 grid          row-major Vec
+//   ┌────┬────┬────┬────┐
+//   │ a0 │ a1 │ a2 │ a3 │     [a0 a1 a2 a3 | b0 b1 b2 b3 | c0 c1 c2 c3]
+//   │ b0 │ b1 │ b2 │ b3 │      index(row, col) = row * cols + col
+//   │ c0 │ c1 │ c2 │ c3 │
+//   └────┴────┴────┴────┘
+//
+// Compared with Vec<Vec<T>>: one allocation instead of one per row, no
+// pointer chase per row, and rows can't end up with different lengths.
+//
+// The layout decides which traversal is fast. Walking a row reads
+// consecutive memory, so every cache line fetched is fully used. Walking a
+// column jumps `cols` elements at a time, so each step can touch a new cache
+// line. For large grids that can be several times slower for the same work.
+
+use demo_framework::{embed, section};
+use std::io::{self, Write};
+use std::iter::StepBy;
+use std::ops::{Index, IndexMut};
+use std::slice::{ChunksExact, Iter};
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "grid_basics",
+        "A Vec-backed grid: (row, col) indexing over one allocation",
+        grid_basics,
+    )?;
+
+    section(
+        out,
+        "rows_and_columns",
+        "Row slices and strided column views",
+        rows_and_columns,
+    )?;
+
+    section(
+        out,
+        "transpose_examples",
+        "Transposing: rows become columns",
+        transpose_examples,
+    )?;
+
+    section(
+        out,
+        "traversal_order",
+        "Row-major vs column-major traversal of the same data",
+        traversal_order,
+    )?;
+
+    section(
+        out,
+        "practical_column_totals",
+        "Practical demo: spreadsheet column totals in row order",
+        practical_column_totals,
+    )?;
+
+    Ok(())
+}
+
+/// A rectangular grid stored row-major in a single Vec.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    data: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid by calling `f(row, col)` for every cell, in row order.
+    pub fn from_fn(rows: usize, cols: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        let mut data: Vec<T> = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                data.push(f(row, col));
+            }
+        }
+        Grid { data, rows, cols }
+    }
+
+    /// Wraps a row-major Vec.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data.len() != rows * cols`.
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Self {
+        assert_eq!(
+            data.len(),
+            rows * cols,
+            "a {}x{} grid needs {} elements",
+            rows,
+            cols,
+            rows * cols
+        );
+        Grid { data, rows, cols }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        if row < self.rows && col < self.cols {
+            self.data.get(row * self.cols + col)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if row < self.rows && col < self.cols {
+            self.data.get_mut(row * self.cols + col)
+        } else {
+            None
+        }
+    }
+
+    /// One row as a contiguous slice.
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// Iterates over the rows, each as a slice.
+    pub fn iter_rows(&self) -> ChunksExact<'_, T> {
+        self.data.chunks_exact(self.cols.max(1))
+    }
+
+    /// One column, as a strided view: every `cols`-th element.
+    pub fn column(&self, col: usize) -> StepBy<Iter<'_, T>> {
+        assert!(col < self.cols, "column {} out of range", col);
+        self.data[col..].iter().step_by(self.cols)
+    }
+
+    /// Iterates over the columns, each as a strided view.
+    pub fn iter_columns(&self) -> impl Iterator<Item = StepBy<Iter<'_, T>>> {
+        (0..self.cols).map(|col| self.column(col))
+    }
+
+    /// The underlying row-major storage.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid with every cell set to `value`.
+    pub fn filled(rows: usize, cols: usize, value: T) -> Self {
+        Grid {
+            data: vec![value; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// Returns a `cols x rows` grid with `result[c][r] == self[r][c]`.
+    ///
+    /// Reading is row-major and writing is column-major, so one side of a
+    /// transpose is always strided. Going tile by tile keeps both the tile
+    /// being read and the tile being written in cache.
+    pub fn transpose(&self) -> Grid<T> {
+        const TILE: usize = 32;
+        // Start from a copy only to get a Vec of the right length
+        let mut data: Vec<T> = self.data.clone();
+        for row_tile in (0..self.rows).step_by(TILE) {
+            for col_tile in (0..self.cols).step_by(TILE) {
+                for row in row_tile..(row_tile + TILE).min(self.rows) {
+                    for col in col_tile..(col_tile + TILE).min(self.cols) {
+                        data[col * self.rows + row] = self[(row, col)].clone();
+                    }
+                }
+            }
+        }
+        Grid {
+            data,
+            rows: self.cols,
+            cols: self.rows,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({}, {}) out of range for a {}x{} grid",
+            row,
+            col,
+            self.rows,
+            self.cols
+        );
+        &self.data[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(
+            row < self.rows && col < self.cols,
+            "({}, {}) out of range for a {}x{} grid",
+            row,
+            col,
+            self.rows,
+            self.cols
+        );
+        &mut self.data[row * self.cols + col]
+    }
+}
+
+/// Sums every cell, visiting one row at a time (sequential memory).
+pub fn sum_row_major(grid: &Grid<u32>) -> u64 {
+    let mut sum: u64 = 0;
+    for row in 0..grid.rows() {
+        for col in 0..grid.cols() {
+            sum += grid[(row, col)] as u64;
+        }
+    }
+    sum
+}
+
+/// Sums every cell, visiting one column at a time (a stride of `cols`).
+pub fn sum_column_major(grid: &Grid<u32>) -> u64 {
+    let mut sum: u64 = 0;
+    for col in 0..grid.cols() {
+        for row in 0..grid.rows() {
+            sum += grid[(row, col)] as u64;
+        }
+    }
+    sum
+}
+
+/// Demonstrates creating and indexing a Grid.
+pub fn grid_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Grid Basics")?;
+
+    // A 3x4 multiplication table
+    let mut table: Grid<u32> = Grid::from_fn(3, 4, |row, col| (row as u32 + 1) * (col as u32 + 1));
+    writeln!(
+        out,
+        "{}x{} grid, storage {:?}",
+        table.rows(),
+        table.cols(),
+        table.as_slice()
+    )?;
+    writeln!(out, "table[(2, 3)] = {}", table[(2, 3)])?;
+    writeln!(out, "get(3, 0) = {:?} (out of range)", table.get(3, 0))?;
+
+    // (row, col) maps to row * cols + col: (1, 2) is storage index 6
+    table[(1, 2)] = 0;
+    writeln!(out, "After table[(1, 2)] = 0: {:?}", table.as_slice())?;
+
+    // Compare: Vec<Vec<T>> allocates each row separately
+    let nested: Vec<Vec<u32>> = (0..3).map(|r| table.row(r).to_vec()).collect();
+    writeln!(
+        out,
+        "\nVec<Vec<u32>>: {} allocations; Grid<u32>: 1 allocation",
+        1 + nested.len()
+    )?;
+
+    // from_vec checks the shape
+    let board: Grid<char> = Grid::from_vec(2, 3, vec!['x', '.', 'o', '.', 'x', 'o']);
+    for row in board.iter_rows() {
+        writeln!(out, "  {}", row.iter().collect::<String>())?;
+    }
+
+    Ok(())
+}
+
+/// Demonstrates row slices and column views.
+///
+/// A row is a plain `&[T]`, so every slice method works on it. A column is
+/// not contiguous, so it can only be an iterator that steps over the rows.
+pub fn rows_and_columns(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Rows and Columns")?;
+
+    let grid: Grid<u32> = Grid::from_fn(3, 4, |row, col| (row * 10 + col) as u32);
+    for (index, row) in grid.iter_rows().enumerate() {
+        writeln!(out, "  row {}: {:?}", index, row)?;
+    }
+
+    let column: Vec<u32> = grid.column(2).copied().collect();
+    writeln!(out, "\ncolumn(2): {:?}", column)?;
+
+    let column_sums: Vec<u32> = grid.iter_columns().map(|col| col.sum()).collect();
+    writeln!(out, "Column sums: {:?}", column_sums)?;
+
+    // Rows are slices: sort, search, copy in place
+    let mut grid: Grid<u32> = grid;
+    grid.row_mut(1).reverse();
+    writeln!(out, "\nAfter row_mut(1).reverse(): {:?}", grid.row(1))?;
+    writeln!(
+        out,
+        "row(2).binary_search(&22): {:?}",
+        grid.row(2).binary_search(&22)
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates transposing a grid.
+///
+/// After a transpose the old columns are rows, so column work becomes fast
+/// row work. Worth it when the same columns are read many times.
+pub fn transpose_examples(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Transpose")?;
+
+    let grid: Grid<u32> = Grid::from_fn(2, 3, |row, col| (row * 3 + col) as u32);
+    let transposed: Grid<u32> = grid.transpose();
+    writeln!(out, "2x3: {:?}", grid.iter_rows().collect::<Vec<_>>())?;
+    writeln!(out, "3x2: {:?}", transposed.iter_rows().collect::<Vec<_>>())?;
+    writeln!(
+        out,
+        "transposed.row(1) == grid.column(1): {}",
+        transposed.row(1).iter().eq(grid.column(1))
+    )?;
+    writeln!(
+        out,
+        "transpose().transpose() == original: {}",
+        transposed.transpose() == grid
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the cost of traversal order on a large grid.
+///
+/// Both loops add up the same 4M numbers. Only the order of memory
+/// accesses differs.
+pub fn traversal_order(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Traversal Order")?;
+
+    let size: usize = 2_048;
+    let grid: Grid<u32> = Grid::from_fn(size, size, |row, col| (row ^ col) as u32 & 0xff);
+    writeln!(
+        out,
+        "{}x{} grid of u32: {} MB",
+        size,
+        size,
+        size * size * 4 / 1_000_000
+    )?;
+
+    let start: Instant = Instant::now();
+    let by_rows: u64 = sum_row_major(&grid);
+    let row_time: Duration = start.elapsed();
+
+    let start: Instant = Instant::now();
+    let by_columns: u64 = sum_column_major(&grid);
+    let column_time: Duration = start.elapsed();
+
+    writeln!(out, "  Row-major sum:    {} in {:?}", by_rows, row_time)?;
+    writeln!(
+        out,
+        "  Column-major sum: {} in {:?}",
+        by_columns, column_time
+    )?;
+    let ratio: f64 = column_time.as_secs_f64() / row_time.as_secs_f64();
+    writeln!(out, "  Column-major / row-major: {:.1}x", ratio)?;
+    embed::record("row_major_ns", row_time.as_nanos());
+    embed::record("column_major_ns", column_time.as_nanos());
+    embed::record("column_over_row", format!("{:.2}", ratio));
+
+    // Each column step skips 2,048 * 4 = 8 KB, so every read lands on a
+    // different cache line - and often a different page.
+
+    Ok(())
+}
+
+/// Practical example: totals per column of a table.
+///
+/// The obvious loop walks each column top to bottom. Keeping one running
+/// total per column and walking the rows instead gives the same result with
+/// sequential reads.
+pub fn practical_column_totals(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Column Totals")?;
+
+    // Monthly sales: one row per store, one column per month
+    let months: [&str; 4] = ["Jan", "Feb", "Mar", "Apr"];
+    let sales: Grid<u32> = Grid::from_vec(
+        3,
+        4,
+        vec![
+            120, 135, 150, 160, //
+            80, 95, 90, 110, //
+            200, 180, 210, 230,
+        ],
+    );
+
+    // Column by column: strided reads
+    let strided: Vec<u32> = sales.iter_columns().map(|col| col.sum()).collect();
+
+    // Row by row into one accumulator per column: sequential reads
+    let mut totals: Vec<u32> = vec![0; sales.cols()];
+    for row in sales.iter_rows() {
+        for (total, value) in totals.iter_mut().zip(row) {
+            *total += value;
+        }
+    }
+
+    for (month, total) in months.iter().zip(&totals) {
+        writeln!(out, "  {}: {}", month, total)?;
+    }
+    writeln!(out, "Same totals both ways: {}", strided == totals)?;
+
+    Ok(())
+}
@@ -1,46 +1,142 @@
+use demo_framework::section;
+use std::borrow::Borrow;
 use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash, Hasher};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "creating_hashmaps",
+        "All the different ways to create a HashMap",
+        creating_hashmaps,
+    )?;
+
+    section(
+        out,
+        "basic_hashmap_operations",
+        "HashMap operations: insert, get, contains_key, update, and remove",
+        basic_hashmap_operations,
+    )?;
+
+    section(
+        out,
+        "entry_api_examples",
+        "The Entry API - Rust's solution for conditional insertion and updates",
+        entry_api_examples,
+    )?;
+
+    section(
+        out,
+        "accessing_values",
+        "The ways to read values from a HashMap",
+        accessing_values,
+    )?;
+
+    section(
+        out,
+        "removing_values",
+        "Methods for removing entries from a HashMap",
+        removing_values,
+    )?;
+
+    section(
+        out,
+        "iterating_hashmaps",
+        "All iteration patterns for HashMaps",
+        iterating_hashmaps,
+    )?;
+
+    section(
+        out,
+        "ownership_and_borrowing",
+        "How HashMap interacts with Rust's ownership system",
+        ownership_and_borrowing,
+    )?;
+
+    section(
+        out,
+        "custom_keys",
+        "Using custom types as HashMap keys",
+        custom_keys,
+    )?;
+
+    section(
+        out,
+        "borrowed_key_lookups",
+        "Looking up a newtype key with &str through Borrow<str>",
+        borrowed_key_lookups,
+    )?;
+
+    section(
+        out,
+        "capacity_growth",
+        "How capacity and load factor change as a HashMap grows",
+        capacity_growth,
+    )?;
+
+    section(
+        out,
+        "reserve_and_shrink",
+        "Sizing a HashMap up front with reserve, shrinking it, and try_reserve",
+        reserve_and_shrink,
+    )?;
+
+    section(
+        out,
+        "rehash_spikes",
+        "Timing every insert to find the ones that rehash the table",
+        rehash_spikes,
+    )?;
+
+    Ok(())
+}
 
 /// Demonstrates all the different ways to create a HashMap
-pub fn creating_hashmaps() {
+pub fn creating_hashmaps(out: &mut dyn Write) -> io::Result<()> {
     // Method 1: HashMap::new()
     // The most common way - start empty and add items
     let mut scores: HashMap<String, i8> = HashMap::new();
     scores.insert(String::from("Blue"), 10);
     scores.insert(String::from("Red"), 50);
-    println!("Created with new(): {:?}", scores);
+    writeln!(out, "Created with new(): {:?}", scores)?;
 
     // Method 2: collect() from an iterator of tuples
     // Useful when you already have data in tuple form
     let teams: Vec<(String, i8)> = vec![(String::from("Green"), 25), (String::from("Yellow"), 30)];
     let scores2: HashMap<String, i8> = teams.into_iter().collect();
-    println!("Created with collect(): {:?}", scores2);
+    writeln!(out, "Created with collect(): {:?}", scores2)?;
 
     // Method 3: From arrays (Rust 1.56+)
     // Concise syntax for small, known maps
     let scores3: HashMap<&str, i16> = HashMap::from([("Alpha", 100), ("Beta", 200)]);
-    println!("Created with from(): {:?}", scores3);
+    writeln!(out, "Created with from(): {:?}", scores3)?;
 
     // Method 4: with_capacity (performance optimization)
     // When you know approximately how many items you'll store,
     // pre-allocating avoids repeated reallocations
     let large_map: HashMap<i32, i32> = HashMap::with_capacity(1_000);
-    println!(
+    writeln!(
+        out,
         "Created with capacity 1_000, current len: {}",
         large_map.len()
-    );
+    )?;
 
     // Method 5: Using zip to combine two iterables
     // Perfect when keys and values come from separate sources
     let keys: Vec<&str> = vec!["a", "b", "c"];
     let values: Vec<i8> = vec![1, 2, 3];
     let zipped: HashMap<&str, i8> = keys.into_iter().zip(values).collect();
-    println!("Created with zip: {:?}", zipped);
+    writeln!(out, "Created with zip: {:?}", zipped)?;
+
+    Ok(())
 }
 
 /// Demonstrates HashMap operations: insert, get, contains_key, update, and remove
-pub fn basic_hashmap_operations() {
-    println!("Basic Hashmap Operations");
+pub fn basic_hashmap_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Basic Hashmap Operations")?;
 
     // Create a new HashMap to track player scores
     let mut scores: HashMap<String, i8> = HashMap::new();
@@ -50,42 +146,46 @@ pub fn basic_hashmap_operations() {
     scores.insert("Alice".to_string(), 100);
     scores.insert("Bob".to_string(), 85);
     scores.insert("Charlie".to_string(), 92);
-    println!("Initial scores: {:?}", scores);
+    writeln!(out, "Initial scores: {:?}", scores)?;
 
     // Access values with get() - returns Option<&V>
     // This is the safe way to access values (won't panic)
     match scores.get("Alice") {
-        Some(score) => println!("Alice's score: {}", score),
-        None => println!("Alice not found"),
+        Some(score) => writeln!(out, "Alice's score: {}", score)?,
+        None => writeln!(out, "Alice not found")?,
     }
 
     // You can also use unwrap_or for a default value
     let alice_score: &i8 = scores.get("Alice").unwrap_or(&0);
     let unknown_score: &i8 = scores.get("Unknown").unwrap_or(&0);
-    println!(
+    writeln!(
+        out,
         "With unwrap_or: Alice={}, Unknown={}",
         alice_score, unknown_score
-    );
+    )?;
 
     // Check if key exists without retrieving the value
     if scores.contains_key("Bob") {
-        println!("Bob is in the scoreboard");
+        writeln!(out, "Bob is in the scoreboard")?;
     }
 
     // Update a value by inserting with the same key
     // The old value is returned
     let old_score: Option<i8> = scores.insert("Alice".to_string(), 105);
-    println!(
+    writeln!(
+        out,
         "Alice's score updated from {:?} to {:?}",
         old_score,
         scores.get("Alice")
-    );
+    )?;
 
     // Remove a key-value pair
     // Returns Option<V> with the removed value
     let removed: Option<i8> = scores.remove("Charlie");
-    println!("Removed Charlie with score: {:?}", removed);
-    println!("Final scores: {:?}", scores);
+    writeln!(out, "Removed Charlie with score: {:?}", removed)?;
+    writeln!(out, "Final scores: {:?}", scores)?;
+
+    Ok(())
 }
 
 /// Demonstrates the Entry API - Rust's solution for conditional insertion and updates.
@@ -93,8 +193,8 @@ pub fn basic_hashmap_operations() {
 /// The Entry API handles the common pattern of "check if key exists, then insert
 /// or update" in a single, efficient operation. This avoids the inefficiency of
 /// doing two separate lookups (contains_key + insert) and is more idiomatic Rust.
-pub fn entry_api_examples() {
-    println!("The Entry API Examples");
+pub fn entry_api_examples(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "The Entry API Examples")?;
 
     // Use case: word frequency counter
     let text: &str = "hello world hello rust world rust rust";
@@ -113,35 +213,38 @@ pub fn entry_api_examples() {
         let count: &mut i8 = word_count.entry(word.to_string()).or_insert(0);
         *count += 1;
     }
-    println!("Word counts: {:?}", word_count);
+    let mut counted: Vec<(&String, &i8)> = word_count.iter().collect();
+    counted.sort();
+    writeln!(out, "Word counts (sorted): {:?}", counted)?;
 
     // or_insert_with() - lazy initialization with a closure
     // The closure only executes if the key doesn't exist
     let mut expensive_cache: HashMap<i8, String> = HashMap::new();
 
-    fn expensive_computation(key: i8) -> String {
-        println!("Computing expensive value for key {}...", key);
+    // or_insert_with() expects a plain String back, so this trace line can't use `?`
+    fn expensive_computation(out: &mut dyn Write, key: i8) -> String {
+        let _ = writeln!(out, "Computing expensive value for key {}...", key);
         format!("computed-{}", key)
     }
 
     let key: i8 = 42;
     let value: &mut String = expensive_cache
         .entry(key)
-        .or_insert_with(|| expensive_computation(key));
-    println!("First access: {}", value);
+        .or_insert_with(|| expensive_computation(out, key));
+    writeln!(out, "First access: {}", value)?;
 
     // Second access - the closure doesn't run because key exists
     let value2: &mut String = expensive_cache
         .entry(key)
-        .or_insert_with(|| expensive_computation(key));
-    println!("Second access (cached): {}", value2);
+        .or_insert_with(|| expensive_computation(out, key));
+    writeln!(out, "Second access (cached): {}", value2)?;
 
     // or_default() - uses the type's Default trait value
     // For i32, default is 0; for String, default is ""
     let mut counts: HashMap<&str, i8> = HashMap::new();
     *counts.entry("visits").or_default() += 1;
     *counts.entry("visits").or_default() += 1;
-    println!("Using or_default(): {:?}", counts);
+    writeln!(out, "Using or_default(): {:?}", counts)?;
 
     // and_modify() - modify existing value, can chain with or_insert
     // Pattern: "add to existing, or start at initial value"
@@ -157,12 +260,16 @@ pub fn entry_api_examples() {
         .entry("banana")
         .and_modify(|count| *count += 3)
         .or_insert(3);
-    println!("Inventory after and_modify: {:?}", inventory);
+    let mut stock: Vec<(&&str, &i8)> = inventory.iter().collect();
+    stock.sort();
+    writeln!(out, "Inventory after and_modify (sorted): {:?}", stock)?;
+
+    Ok(())
 }
 
 /// Demonstrates the ways to read values from a HashMap.
-pub fn accessing_values() {
-    println!("Accessing values");
+pub fn accessing_values(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Accessing values")?;
 
     let mut scores: HashMap<&str, i8> = HashMap::new();
     scores.insert("Alice", 100);
@@ -170,71 +277,80 @@ pub fn accessing_values() {
 
     // get() - returns Option<&V>, the safest approach
     match scores.get("Alice") {
-        Some(score) => println!("Alice's score: {}", score),
-        None => println!("Alice not found"),
+        Some(score) => writeln!(out, "Alice's score: {}", score)?,
+        None => writeln!(out, "Alice not found")?,
     }
     match scores.get("Charlie") {
-        Some(score) => println!("Charlie's score: {}", score),
-        None => println!("Charlie not found"),
+        Some(score) => writeln!(out, "Charlie's score: {}", score)?,
+        None => writeln!(out, "Charlie not found")?,
     }
 
     // contains_key() - check existence without getting value
-    println!("\ncontains_key(\"Bob\"): {}", scores.contains_key("Bob"));
-    println!("contains_key(\"Eve\"): {}", scores.contains_key("Eve"));
+    writeln!(
+        out,
+        "\ncontains_key(\"Bob\"): {}",
+        scores.contains_key("Bob")
+    )?;
+    writeln!(out, "contains_key(\"Eve\"): {}", scores.contains_key("Eve"))?;
 
     // get_mut() - get a mutable reference to modify in place
     if let Some(score) = scores.get_mut("Alice") {
         *score += 10;
-        println!("Alice's score: {}", score);
+        writeln!(out, "Alice's score: {}", score)?;
     }
 
     // get_key_value() - get both key and value as a tuple
     if let Some((key, value)) = scores.get_key_value("Bob") {
-        println!("get_key_value result: {} -> {}", key, value);
+        writeln!(out, "get_key_value result: {} -> {}", key, value)?;
     }
+
+    Ok(())
 }
 
 /// Demonstrates methods for removing entries from a HashMap.
-pub fn removing_values() {
-    println!("Removing values");
+pub fn removing_values(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Removing values")?;
 
     let mut map: HashMap<&str, i8> = HashMap::from([("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
-    println!("Starting map: {:?}", map);
+    writeln!(out, "Starting map: {:?}", map)?;
 
     // remove() - remove by key, returns Option<V> with the value
     let removed: Option<i8> = map.remove("b");
-    println!("remove(\"b\") returned: {:?}", removed);
-    println!("Map after remove: {:?}", map);
+    writeln!(out, "remove(\"b\") returned: {:?}", removed)?;
+    writeln!(out, "Map after remove: {:?}", map)?;
 
     // remove_entry() - get both the key and value back
     if let Some((key, value)) = map.remove_entry("c") {
-        println!("remove_entry returned: {} -> {}", key, value);
+        writeln!(out, "remove_entry returned: {} -> {}", key, value)?;
     }
 
     // retain() - keep only entries matching a predicate
     // Great for bulk filtering
     let square: fn(i8) -> i8 = |n: i8| n * n;
     let mut numbers: HashMap<i8, i8> = (0..10).map(|i| (i, square(i))).collect();
-    println!("Before retain: {:?}", numbers);
+    writeln!(out, "Before retain: {:?}", numbers)?;
 
     let keep_even: fn(&i8, &mut i8) -> bool = |key, _value| key % 2 == 0;
-    numbers.retain(|key, _value| keep_even(key, _value)); // Keep only even keys
-    println!("After retain (even keys only): {:?}", numbers);
+    numbers.retain(keep_even); // Keep only even keys
+    writeln!(out, "After retain (even keys only): {:?}", numbers)?;
 
     // clear() - remove all entries
     let mut to_clear: HashMap<&str, i8> = HashMap::from([("x", 1), ("y", 2)]);
-    println!("Before clear: {:?}", to_clear);
+    writeln!(out, "Before clear: {:?}", to_clear)?;
     to_clear.clear();
-    println!(
+    writeln!(
+        out,
         "After clear: {:?}, is_empty: {}",
         to_clear,
         to_clear.is_empty()
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates all iteration patterns for HashMaps.
-pub fn iterating_hashmaps() {
-    println!("Iterating over HashMaps");
+pub fn iterating_hashmaps(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Iterating over HashMaps")?;
 
     let mut capitals: HashMap<&str, &str> = HashMap::new();
     capitals.insert("France", "Paris");
@@ -242,40 +358,51 @@ pub fn iterating_hashmaps() {
     capitals.insert("Brazil", "Brasília");
     capitals.insert("Australia", "Canberra");
 
-    // Iterate over key-value pairs (order is NOT guaranteed!)
-    println!("All capitals (immutable iteration):");
-    for (country, capital) in &capitals {
-        println!("{} -> {}", country, capital);
+    // Iterate over key-value pairs (order is NOT guaranteed!), so collect
+    // and sort them to print the same order on every run
+    writeln!(out, "All capitals (immutable iteration, sorted):")?;
+    let mut pairs: Vec<(&&str, &&str)> = capitals.iter().collect();
+    pairs.sort();
+    for (country, capital) in pairs {
+        writeln!(out, "{} -> {}", country, capital)?;
     }
 
     // Iterate over keys only
-    println!("\nKeys only: {:?}", capitals.keys().collect::<Vec<_>>());
+    let mut keys: Vec<&&str> = capitals.keys().collect();
+    keys.sort();
+    writeln!(out, "\nKeys only (sorted): {:?}", keys)?;
 
     // Iterate over values only
-    println!("Values only: {:?}", capitals.values().collect::<Vec<_>>());
+    let mut values: Vec<&&str> = capitals.values().collect();
+    values.sort();
+    writeln!(out, "Values only (sorted): {:?}", values)?;
 
     // Mutable iteration - modify values in place
     let mut scores: HashMap<&str, i8> =
         HashMap::from([("Alice", 95), ("Bob", 87), ("Charlie", 91)]);
-    println!("Before curve: {:?}", scores);
-    for (_name, score) in &mut scores {
+    writeln!(out, "Before curve: {:?}", scores)?;
+    for score in scores.values_mut() {
         *score = (*score + 5).min(100); // Add 5 points, cap at 100
     }
-    println!("After curve: {:?}", scores);
+    writeln!(out, "After curve: {:?}", scores)?;
 
     // Consuming iteration - takes ownership of the HashMap
     let temp_map: HashMap<&str, i8> = HashMap::from([("x", 1), ("y", 2)]);
-    println!("Consuming iteration:");
-    for (key, value) in temp_map {
-        // temp_map is moved here
-        println!("Consumed: {} -> {}", key, value);
+    writeln!(out, "Consuming iteration (sorted):")?;
+    // temp_map is moved here
+    let mut consumed: Vec<(&str, i8)> = temp_map.into_iter().collect();
+    consumed.sort();
+    for (key, value) in consumed {
+        writeln!(out, "Consumed: {} -> {}", key, value)?;
     }
-    // println!("{:?}", temp_map);  // ERROR: temp_map was moved!
+    // writeln!(out, "{:?}", temp_map)?;  // ERROR: temp_map was moved!
+
+    Ok(())
 }
 
 /// Demonstrates how HashMap interacts with Rust's ownership system.
-pub fn ownership_and_borrowing() {
-    println!("Ownership and borrowing");
+pub fn ownership_and_borrowing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Ownership and borrowing")?;
 
     // For Copy types (like i32), values are copied into the HashMap
     let mut map: HashMap<i8, i8> = HashMap::new();
@@ -283,26 +410,27 @@ pub fn ownership_and_borrowing() {
     let value: i8 = 100;
     map.insert(key, value);
     // key and value are still valid because i32 implements Copy!
-    println!(
+    writeln!(
+        out,
         "Copy types: key and value still valid after insert: {}, {}",
         key, value
-    );
+    )?;
 
     // For owned types (like String), ownership transfers to the HashMap
     let mut map: HashMap<String, String> = HashMap::new();
     let key: String = String::from("name");
     let value: String = String::from("Rust");
     map.insert(key, value);
-    // println!("{}", key);     // ERROR: key was moved into the HashMap
-    // println!("{}", value);   // ERROR: value was moved into the HashMap
-    println!("Owned types moved into HashMap: {:?}", map);
+    // writeln!(out, "{}", key)?;     // ERROR: key was moved into the HashMap
+    // writeln!(out, "{}", value)?;   // ERROR: value was moved into the HashMap
+    writeln!(out, "Owned types moved into HashMap: {:?}", map)?;
 
     // Using references as keys - the referenced data must outlive the HashMap
     let names: Vec<String> = vec![String::from("Alice"), String::from("Bob")];
     let mut ages: HashMap<&str, i8> = HashMap::new();
     ages.insert(&names[0], 30); // Borrowing from names vector
     ages.insert(&names[1], 25);
-    println!("Borrowed keys from vec: {:?}", ages);
+    writeln!(out, "Borrowed keys from vec: {:?}", ages)?;
     // ages must not outlive names, or we'd have dangling references
 
     // Common pattern: clone to avoid ownership issues
@@ -310,8 +438,14 @@ pub fn ownership_and_borrowing() {
     let value: String = String::from("Rust");
     let mut map: HashMap<String, String> = HashMap::new();
     map.insert(key.clone(), value.clone()); // Clone to keep originals
-    println!("After cloning: originals still valid: {} = {}", key, value);
-    println!("HashMap contains: {:?}", map);
+    writeln!(
+        out,
+        "After cloning: originals still valid: {} = {}",
+        key, value
+    )?;
+    writeln!(out, "HashMap contains: {:?}", map)?;
+
+    Ok(())
 }
 
 /// Demonstrates using custom types as HashMap keys.
@@ -322,8 +456,8 @@ pub fn ownership_and_borrowing() {
 /// - `PartialEq`: required by Eq
 ///
 /// This function shows both manual implementation and the derive macro approach.
-pub fn custom_keys() {
-    println!("Custom Types as Keys");
+pub fn custom_keys(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Custom Types as Keys")?;
 
     // First, let's see the manual implementation approach
     // Normally you'd just derive these traits
@@ -357,7 +491,7 @@ pub fn custom_keys() {
     let mut point_values: HashMap<PointManual, String> = HashMap::new();
     point_values.insert(PointManual { x: 0, y: 0 }, "origin".to_string());
     point_values.insert(PointManual { x: 1, y: 1 }, "diagonal".to_string());
-    println!("Manual implementation - Point map: {:?}", point_values);
+    writeln!(out, "Manual implementation - Point map: {:?}", point_values)?;
 
     // The easy way: derive the traits automatically
     // This is what you should normally do for simple structs
@@ -375,13 +509,387 @@ pub fn custom_keys() {
 
     // Look up by creating an equivalent Point
     let query: Point = Point { x: 0, y: 0 };
-    println!(
+    writeln!(
+        out,
         "Derived implementation - Location at {:?}: {:?}",
         query,
         locations.get(&query)
-    );
+    )?;
 
     // Note on performance: Rust uses SipHash by default for security against
     // HashDoS attacks. For performance-critical code with trusted input,
     // consider using a faster hasher like FxHash or AHash.
+
+    Ok(())
+}
+
+/// A username used as a map key.
+///
+/// It derives `Hash`, `Eq` and `Ord` from the `String` inside, so it hashes
+/// and compares exactly like the `str` it borrows as. That is the whole
+/// contract `Borrow<str>` asks for, and what lets `get("alice")` find it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Username(String);
+
+impl Username {
+    pub fn new(name: &str) -> Self {
+        Username(name.to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Username {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Demonstrates `Borrow`-based lookups for a newtype key, and two keys
+/// whose `Borrow<str>` breaks the hashing contract.
+///
+/// `HashMap<K, V>::get` takes any `&Q` where `K: Borrow<Q>`. The map hashes
+/// the `Q`, so `K` and `Q` must hash and compare alike; nothing checks
+/// this, and a mismatch shows up as lookups that silently miss.
+pub fn borrowed_key_lookups(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Borrow<str> for a newtype key")?;
+
+    let mut logins: HashMap<Username, u32> = HashMap::new();
+    logins.insert(Username::new("alice"), 3);
+    logins.insert(Username::new("bob"), 7);
+
+    // Without Borrow<str> every lookup would have to build a Username,
+    // which allocates a String just to throw it away
+    writeln!(
+        out,
+        "get(&Username::new(\"alice\")): {:?}  (allocates a String per lookup)",
+        logins.get(&Username::new("alice"))
+    )?;
+    // With it, a plain &str works - from a literal, a slice of a request
+    // line, anywhere - and nothing is allocated
+    let request: &str = "GET /users/bob";
+    let name: &str = &request["GET /users/".len()..];
+    writeln!(
+        out,
+        "get(\"alice\"): {:?}, get(\"{}\"): {:?}, contains_key(\"carol\"): {}",
+        logins.get("alice"),
+        name,
+        logins.get(name),
+        logins.contains_key("carol")
+    )?;
+    if let Some(count) = logins.get_mut("bob") {
+        *count += 1;
+    }
+    writeln!(out, "get_mut(\"bob\") then +1: {:?}", logins.get("bob"))?;
+
+    let state: RandomState = RandomState::new();
+    writeln!(
+        out,
+        "Username(\"alice\") and \"alice\" hash alike: {}",
+        state.hash_one(Username::new("alice")) == state.hash_one("alice")
+    )?;
+
+    // The pitfall maps hash with fixed keys: a miss can still turn into a
+    // hit if the wrong hash happens to land on the key's bucket and control
+    // byte, and a random seed would make that a 1-in-128 chance per run
+    type Fixed = BuildHasherDefault<DefaultHasher>;
+
+    writeln!(out, "\n--- Pitfall: a case-insensitive key ---")?;
+
+    // Eq and Hash ignore case, but the borrowed str doesn't
+    #[derive(Debug)]
+    struct CaseInsensitive(String);
+
+    impl PartialEq for CaseInsensitive {
+        fn eq(&self, other: &Self) -> bool {
+            self.0.eq_ignore_ascii_case(&other.0)
+        }
+    }
+
+    impl Eq for CaseInsensitive {}
+
+    impl Hash for CaseInsensitive {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.0.to_ascii_lowercase().hash(state);
+        }
+    }
+
+    // Compiles fine - and breaks the contract: "Alice" and "alice" are equal
+    // as keys but not as strs, and they hash differently as strs
+    impl Borrow<str> for CaseInsensitive {
+        fn borrow(&self) -> &str {
+            &self.0
+        }
+    }
+
+    let mut emails: HashMap<CaseInsensitive, &str, Fixed> = HashMap::default();
+    emails.insert(CaseInsensitive("Alice".to_string()), "alice@example.com");
+    writeln!(
+        out,
+        "Inserted key \"Alice\". get(&CaseInsensitive(\"ALICE\")): {:?}",
+        emails.get(&CaseInsensitive("ALICE".to_string()))
+    )?;
+    writeln!(
+        out,
+        "get(\"ALICE\"): {:?}, get(\"Alice\"): {:?}",
+        emails.get("ALICE"),
+        emails.get("Alice")
+    )?;
+    writeln!(
+        out,
+        "The str lookups hash \"ALICE\" and \"Alice\" as written, not lowercased, so both"
+    )?;
+    writeln!(
+        out,
+        "land in the wrong bucket. Borrow a type that shares the key's Eq and Hash instead."
+    )?;
+
+    writeln!(
+        out,
+        "\n--- Pitfall: a key with more fields than it borrows ---"
+    )?;
+
+    // Hash covers both fields; the borrowed str is only one of them
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    struct Account {
+        name: String,
+        region: u8,
+    }
+
+    impl Borrow<str> for Account {
+        fn borrow(&self) -> &str {
+            &self.name
+        }
+    }
+
+    let mut balances: HashMap<Account, i64, Fixed> = HashMap::default();
+    balances.insert(
+        Account {
+            name: "alice".to_string(),
+            region: 1,
+        },
+        120,
+    );
+    writeln!(
+        out,
+        "Key Account {{ name: \"alice\", region: 1 }}. get(\"alice\"): {:?}",
+        balances.get("alice")
+    )?;
+    writeln!(
+        out,
+        "The key hashes name and region, the str only the name: the hashes never match."
+    )?;
+    writeln!(
+        out,
+        "Equal keys must hash alike; Borrow<Q> extends that to every Q a key borrows as."
+    )?;
+
+    Ok(())
+}
+
+/// Inserts `0..count` and records `(len, old capacity, new capacity)` each
+/// time the table grows.
+fn capacity_steps(count: u64) -> Vec<(usize, usize, usize)> {
+    let mut map: HashMap<u64, u64> = HashMap::new();
+    let mut steps: Vec<(usize, usize, usize)> = Vec::new();
+    for i in 0..count {
+        let before: usize = map.capacity();
+        map.insert(i, i);
+        if map.capacity() != before {
+            steps.push((map.len(), before, map.capacity()));
+        }
+    }
+    steps
+}
+
+/// Demonstrates how capacity follows the number of entries.
+///
+/// std's HashMap (hashbrown) has a power-of-two number of buckets and fills
+/// at most 7/8 of them, so `capacity()` is 7/8 of the bucket count (3 and 7
+/// for the two smallest tables). An insert into a full table allocates one
+/// twice the size and moves every entry across: a rehash.
+pub fn capacity_growth(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Capacity growth")?;
+
+    let map: HashMap<u64, u64> = HashMap::new();
+    writeln!(
+        out,
+        "HashMap::new(): capacity {} - no allocation until the first insert",
+        map.capacity()
+    )?;
+
+    writeln!(out, "\n  {:>6}  {:<16}  buckets", "len", "capacity")?;
+    for (len, before, after) in capacity_steps(2_000) {
+        // Tables under 8 buckets can be filled all but one; larger ones to 7/8
+        let buckets: usize = if after < 8 { after + 1 } else { after / 7 * 8 };
+        writeln!(
+            out,
+            "  {:>6}  {:>4} -> {:<8}  {}",
+            len, before, after, buckets
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\nCapacity roughly doubles at each step, so N inserts move fewer than N entries"
+    )?;
+    writeln!(
+        out,
+        "in total across all rehashes: amortized O(1) per insert, but not every insert."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates `reserve`, `shrink_to_fit`, `shrink_to` and `try_reserve`.
+pub fn reserve_and_shrink(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Reserving and shrinking")?;
+
+    // with_capacity guarantees room for at least that many entries
+    let mut map: HashMap<u64, u64> = HashMap::with_capacity(100);
+    writeln!(
+        out,
+        "with_capacity(100): capacity {} (rounded up to a whole table)",
+        map.capacity()
+    )?;
+
+    // reserve(n) makes room for n more than len(), not n in total
+    map.extend((0..10).map(|i| (i, i)));
+    map.reserve(500);
+    writeln!(
+        out,
+        "len 10, reserve(500): capacity {} (room for len + 500)",
+        map.capacity()
+    )?;
+    let capacity: usize = map.capacity();
+    map.extend((10..510).map(|i| (i, i)));
+    writeln!(
+        out,
+        "After 500 more inserts: capacity {}, reallocated: {}",
+        map.capacity(),
+        map.capacity() != capacity
+    )?;
+
+    // Removing never shrinks the table. capacity() is what fits before the
+    // next grow, and removed slots stay as tombstones until a rehash, so the
+    // exact number after removals depends on where the keys hashed
+    map.retain(|&k, _| k < 20);
+    writeln!(
+        out,
+        "\nretain 20 of 510: len {}, still room for 500+: {} (removal never shrinks)",
+        map.len(),
+        map.capacity() > 500
+    )?;
+    map.shrink_to(100);
+    writeln!(
+        out,
+        "shrink_to(100): capacity {} (keeps room for 100)",
+        map.capacity()
+    )?;
+    map.shrink_to_fit();
+    writeln!(
+        out,
+        "shrink_to_fit(): capacity {} (smallest table that holds 20)",
+        map.capacity()
+    )?;
+    map.clear();
+    writeln!(
+        out,
+        "clear(): len {}, capacity {} (keeps the table for reuse)",
+        map.len(),
+        map.capacity()
+    )?;
+
+    // try_reserve reports failure instead of aborting the process
+    writeln!(out)?;
+    match map.try_reserve(1_000) {
+        Ok(()) => writeln!(out, "try_reserve(1_000): Ok, capacity {}", map.capacity())?,
+        Err(e) => writeln!(out, "try_reserve(1_000): Err({})", e)?,
+    }
+    match map.try_reserve(usize::MAX) {
+        Ok(()) => writeln!(out, "try_reserve(usize::MAX): Ok")?,
+        Err(e) => writeln!(out, "try_reserve(usize::MAX): Err({})", e)?,
+    }
+    writeln!(
+        out,
+        "Use try_reserve when the size comes from untrusted input, e.g. a length prefix."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the cost spike of the inserts that trigger a rehash.
+///
+/// Each insert is timed on its own. Most take about the same time; the
+/// ones that find the table full also allocate a new one and move every
+/// entry, so their cost grows with the size of the map.
+pub fn rehash_spikes(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Rehash cost spikes")?;
+
+    let count: u64 = 200_000;
+    let mut map: HashMap<u64, u64> = HashMap::new();
+    let mut times: Vec<Duration> = Vec::with_capacity(count as usize);
+    let mut growths: Vec<(usize, usize, Duration)> = Vec::new();
+    for i in 0..count {
+        let before: usize = map.capacity();
+        let start: Instant = Instant::now();
+        map.insert(i, i);
+        let elapsed: Duration = start.elapsed();
+        times.push(elapsed);
+        if map.capacity() != before {
+            growths.push((map.len(), map.capacity(), elapsed));
+        }
+    }
+
+    let mut sorted: Vec<Duration> = times.clone();
+    sorted.sort();
+    let median: Duration = sorted[sorted.len() / 2];
+    writeln!(out, "{} inserts, median insert {:?}", count, median)?;
+
+    writeln!(out, "\nThe inserts that grew the table (the last few):")?;
+    let shown: usize = growths.len().saturating_sub(6);
+    for &(len, capacity, elapsed) in &growths[shown..] {
+        writeln!(
+            out,
+            "  insert #{:<7} -> capacity {:<7} took {:?}, {:.1}x the median",
+            len,
+            capacity,
+            elapsed,
+            elapsed.as_secs_f64() / median.as_secs_f64().max(1e-9)
+        )?;
+    }
+
+    let total: Duration = times.iter().sum();
+    let in_growths: Duration = growths.iter().map(|&(_, _, elapsed)| elapsed).sum();
+    writeln!(
+        out,
+        "\n{} of {} inserts rehashed and took {:.1}% of the total time",
+        growths.len(),
+        count,
+        in_growths.as_secs_f64() / total.as_secs_f64() * 100.0
+    )?;
+
+    // The same inserts into a map sized up front never rehash
+    let mut presized: HashMap<u64, u64> = HashMap::with_capacity(count as usize);
+    let capacity: usize = presized.capacity();
+    let start: Instant = Instant::now();
+    for i in 0..count {
+        presized.insert(i, i);
+    }
+    let presized_time: Duration = start.elapsed();
+    writeln!(
+        out,
+        "with_capacity({}): {:?} in total, capacity unchanged: {}",
+        count,
+        presized_time,
+        presized.capacity() == capacity
+    )?;
+    writeln!(
+        out,
+        "A latency-sensitive loop can reserve up front and move the spikes to a quiet moment."
+    )?;
+
+    Ok(())
 }
@@ -0,0 +1,752 @@
+// std's BinaryHeap is a binary heap in a Vec: compact, cache-friendly and
+// hard to beat for plain push/pop. Two workloads expose its limits:
+//
+//   decrease-key   Dijkstra and Prim lower the priority of an element that
+//                  is already queued. BinaryHeap can't find it, so the usual
+//                  workaround pushes a duplicate and skips stale pops later.
+//   merge          BinaryHeap::append re-sifts the smaller heap's elements
+//                  one by one (or rebuilds the whole heap).
+//
+// Two alternatives, both implemented here as min-heaps (the order
+// shortest-path algorithms want) so they compare with BinaryHeap<Reverse<T>>:
+//
+//   DaryHeap<T, D>   Same Vec layout, D children per node instead of 2.
+//                    The tree is log2(D) times shallower, so push (sift-up)
+//                    is cheaper; pop compares D children per level. A win
+//                    for push-heavy workloads like lazy decrease-key.
+//
+//   PairingHeap<T>   A multiway tree of nodes. push and merge just link two
+//                    roots (one comparison); pop does the deferred work by
+//                    pairing up the root's children. push returns a handle,
+//                    so decrease_key() can find and move an element.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::cell::Cell;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "dary_heap_basics",
+        "A D-ary heap: the BinaryHeap layout with wider, shallower nodes",
+        dary_heap_basics,
+    )?;
+
+    section(
+        out,
+        "pairing_heap_basics",
+        "A pairing heap: handles, cheap pushes and deferred work in pop",
+        pairing_heap_basics,
+    )?;
+
+    section(
+        out,
+        "decrease_key_workloads",
+        "Dijkstra three ways: lazy re-push vs a real decrease_key",
+        decrease_key_workloads,
+    )?;
+
+    section(
+        out,
+        "merge_workloads",
+        "Merging many heaps: BinaryHeap::append vs PairingHeap::merge",
+        merge_workloads,
+    )?;
+
+    Ok(())
+}
+
+/// A min-heap with `D` children per node, stored in a Vec.
+///
+/// The children of index `i` are at `D * i + 1 ..= D * i + D`; its parent
+/// is at `(i - 1) / D`. With `D = 2` this is the classic binary heap.
+#[derive(Clone, Debug)]
+pub struct DaryHeap<T, const D: usize> {
+    data: Vec<T>,
+}
+
+impl<T: Ord, const D: usize> DaryHeap<T, D> {
+    pub fn new() -> Self {
+        const { assert!(D >= 2, "a heap needs at least 2 children per node") };
+        DaryHeap { data: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The smallest element, if any.
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    pub fn push(&mut self, value: T) {
+        self.data.push(value);
+        self.sift_up(self.data.len() - 1);
+    }
+
+    /// Removes and returns the smallest element.
+    pub fn pop(&mut self) -> Option<T> {
+        let last: T = self.data.pop()?;
+        if self.data.is_empty() {
+            return Some(last);
+        }
+        let top: T = std::mem::replace(&mut self.data[0], last);
+
+        // Bottom-up: move the hole down the smallest-child path to a leaf,
+        // then sift the element back up. It came from the end of the Vec,
+        // so it almost always belongs near the bottom, and the walk down
+        // skips comparing it at every level.
+        let mut i: usize = 0;
+        loop {
+            let first: usize = D * i + 1;
+            if first >= self.data.len() {
+                break;
+            }
+            let smallest: usize = self.smallest_child(first);
+            self.data.swap(i, smallest);
+            i = smallest;
+        }
+        self.sift_up(i);
+        Some(top)
+    }
+
+    fn smallest_child(&self, first: usize) -> usize {
+        let last: usize = (first + D).min(self.data.len());
+        let mut smallest: usize = first;
+        for child in first + 1..last {
+            if self.data[child] < self.data[smallest] {
+                smallest = child;
+            }
+        }
+        smallest
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent: usize = (i - 1) / D;
+            if self.data[i] >= self.data[parent] {
+                break;
+            }
+            self.data.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let first: usize = D * i + 1;
+            if first >= self.data.len() {
+                break;
+            }
+            let smallest: usize = self.smallest_child(first);
+            if self.data[smallest] >= self.data[i] {
+                break;
+            }
+            self.data.swap(i, smallest);
+            i = smallest;
+        }
+    }
+}
+
+impl<T: Ord, const D: usize> Default for DaryHeap<T, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord, const D: usize> From<Vec<T>> for DaryHeap<T, D> {
+    /// Heapifies in O(n), sifting down from the last parent to the root.
+    fn from(data: Vec<T>) -> Self {
+        let mut heap: DaryHeap<T, D> = DaryHeap { data };
+        if heap.data.len() > 1 {
+            for i in (0..=(heap.data.len() - 2) / D).rev() {
+                heap.sift_down(i);
+            }
+        }
+        heap
+    }
+}
+
+/// Identifies an element pushed into a [`PairingHeap`], for `decrease_key`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HeapHandle(usize);
+
+#[derive(Clone, Debug)]
+struct PairingNode<T> {
+    /// None once the element has been popped.
+    value: Option<T>,
+    /// First child.
+    child: Option<usize>,
+    /// Next sibling.
+    next: Option<usize>,
+    /// Previous sibling, or the parent for a first child.
+    prev: Option<usize>,
+}
+
+/// A min-heap as a pairing heap, with nodes stored in a Vec arena.
+///
+/// Popped nodes are not reused, so a handle always refers to the element it
+/// was returned for.
+#[derive(Clone, Debug)]
+pub struct PairingHeap<T> {
+    nodes: Vec<PairingNode<T>>,
+    root: Option<usize>,
+    len: usize,
+    /// Reused by pop() for the first pass of pairing.
+    scratch: Vec<usize>,
+}
+
+impl<T: Ord> PairingHeap<T> {
+    pub fn new() -> Self {
+        PairingHeap {
+            nodes: Vec::new(),
+            root: None,
+            len: 0,
+            scratch: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The smallest element, if any.
+    pub fn peek(&self) -> Option<&T> {
+        self.root.map(|root| self.value(root))
+    }
+
+    /// The element behind `handle`, or None if it has been popped.
+    pub fn get(&self, handle: HeapHandle) -> Option<&T> {
+        self.nodes.get(handle.0)?.value.as_ref()
+    }
+
+    /// Adds an element in O(1): the new node is linked with the root.
+    pub fn push(&mut self, value: T) -> HeapHandle {
+        let index: usize = self.nodes.len();
+        self.nodes.push(PairingNode {
+            value: Some(value),
+            child: None,
+            next: None,
+            prev: None,
+        });
+        self.root = Some(match self.root {
+            Some(root) => self.link(root, index),
+            None => index,
+        });
+        self.len += 1;
+        HeapHandle(index)
+    }
+
+    /// Removes and returns the smallest element.
+    ///
+    /// The root's children are linked in pairs left to right, then the
+    /// results are linked right to left into one tree.
+    pub fn pop(&mut self) -> Option<T> {
+        let root: usize = self.root?;
+        let value: Option<T> = self.nodes[root].value.take();
+        self.root = self.nodes[root]
+            .child
+            .take()
+            .map(|first| self.combine_siblings(first));
+        self.len -= 1;
+        value
+    }
+
+    /// Replaces the element behind `handle` with a smaller one.
+    ///
+    /// The node's subtree is cut from its parent and linked with the root,
+    /// which is O(1); the restructuring is paid for by later pops.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the element has been popped or `value` is greater than it.
+    pub fn decrease_key(&mut self, handle: HeapHandle, value: T) {
+        let index: usize = handle.0;
+        let current: &T = self.nodes[index]
+            .value
+            .as_ref()
+            .expect("decrease_key on a popped element");
+        assert!(value <= *current, "decrease_key with a greater value");
+        self.nodes[index].value = Some(value);
+
+        if self.root != Some(index) {
+            self.cut(index);
+            let root: usize = self.root.expect("heap with a live node has a root");
+            self.root = Some(self.link(root, index));
+        }
+    }
+
+    /// Moves every element of `other` into this heap.
+    ///
+    /// Joining the two trees is a single comparison. Handles returned by
+    /// `other` are not valid for the merged heap.
+    pub fn merge(&mut self, mut other: PairingHeap<T>) {
+        let offset: usize = self.nodes.len();
+        let shift = |link: &mut Option<usize>| {
+            if let Some(index) = link {
+                *index += offset;
+            }
+        };
+        for node in &mut other.nodes {
+            shift(&mut node.child);
+            shift(&mut node.next);
+            shift(&mut node.prev);
+        }
+        self.nodes.append(&mut other.nodes);
+        self.len += other.len;
+
+        self.root = match (self.root, other.root) {
+            (Some(a), Some(b)) => Some(self.link(a, b + offset)),
+            (a, b) => a.or(b.map(|b| b + offset)),
+        };
+    }
+
+    fn value(&self, index: usize) -> &T {
+        self.nodes[index].value.as_ref().expect("live node")
+    }
+
+    /// Links two roots; the larger becomes the first child of the smaller.
+    fn link(&mut self, a: usize, b: usize) -> usize {
+        let (parent, child) = if self.value(b) < self.value(a) {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        let old_first: Option<usize> = self.nodes[parent].child;
+        self.nodes[child].next = old_first;
+        self.nodes[child].prev = Some(parent);
+        if let Some(first) = old_first {
+            self.nodes[first].prev = Some(child);
+        }
+        self.nodes[parent].child = Some(child);
+        parent
+    }
+
+    /// Detaches a node (with its subtree) from its parent and siblings.
+    fn cut(&mut self, index: usize) {
+        let prev: usize = self.nodes[index].prev.expect("non-root node has a prev");
+        let next: Option<usize> = self.nodes[index].next;
+        if self.nodes[prev].child == Some(index) {
+            self.nodes[prev].child = next;
+        } else {
+            self.nodes[prev].next = next;
+        }
+        if let Some(next) = next {
+            self.nodes[next].prev = Some(prev);
+        }
+        self.nodes[index].prev = None;
+        self.nodes[index].next = None;
+    }
+
+    /// Two-pass pairing of a sibling list into a single tree.
+    fn combine_siblings(&mut self, first: usize) -> usize {
+        let mut pairs: Vec<usize> = std::mem::take(&mut self.scratch);
+        let mut current: Option<usize> = Some(first);
+        while let Some(a) = current {
+            let b: Option<usize> = self.nodes[a].next;
+            self.nodes[a].next = None;
+            self.nodes[a].prev = None;
+            match b {
+                Some(b) => {
+                    current = self.nodes[b].next;
+                    self.nodes[b].next = None;
+                    self.nodes[b].prev = None;
+                    pairs.push(self.link(a, b));
+                }
+                None => {
+                    pairs.push(a);
+                    current = None;
+                }
+            }
+        }
+
+        let mut root: usize = pairs.pop().expect("at least one sibling");
+        while let Some(tree) = pairs.pop() {
+            root = self.link(tree, root);
+        }
+        self.scratch = pairs;
+        root
+    }
+}
+
+impl<T: Ord> Default for PairingHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A directed graph as adjacency lists of `(target, weight)`.
+pub type Graph = Vec<Vec<(usize, u32)>>;
+
+/// Builds a deterministic pseudo-random graph: a path 0 -> 1 -> ... -> n-1
+/// (so every node is reachable from 0) plus `extra_edges` random edges per
+/// node with weights 1..=100.
+pub fn random_graph(nodes: usize, extra_edges: usize, seed: u64) -> Graph {
+    let mut rng: Lcg = Lcg::new(seed);
+    let mut graph: Graph = vec![Vec::new(); nodes];
+    for (node, edges) in graph.iter_mut().enumerate() {
+        if node + 1 < nodes {
+            edges.push((node + 1, 100));
+        }
+        for _ in 0..extra_edges {
+            let target: usize = rng.below(nodes as u64) as usize;
+            let weight: u32 = rng.below(100) as u32 + 1;
+            edges.push((target, weight));
+        }
+    }
+    graph
+}
+
+/// Shortest distances from one source, plus how much work the queue did.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DijkstraRun {
+    /// `u32::MAX` for unreachable nodes.
+    pub dist: Vec<u32>,
+    pub pushes: usize,
+    /// Pops of an entry whose node had already been settled.
+    pub stale_pops: usize,
+    pub decrease_keys: usize,
+}
+
+impl DijkstraRun {
+    fn new(nodes: usize, source: usize) -> Self {
+        let mut dist: Vec<u32> = vec![u32::MAX; nodes];
+        dist[source] = 0;
+        DijkstraRun {
+            dist,
+            pushes: 0,
+            stale_pops: 0,
+            decrease_keys: 0,
+        }
+    }
+}
+
+/// Dijkstra with std's BinaryHeap: a shorter path pushes a duplicate entry
+/// and outdated entries are skipped when popped.
+pub fn dijkstra_binary_heap(graph: &Graph, source: usize) -> DijkstraRun {
+    let mut run: DijkstraRun = DijkstraRun::new(graph.len(), source);
+    let mut queue: BinaryHeap<Reverse<(u32, usize)>> = BinaryHeap::new();
+    queue.push(Reverse((0, source)));
+    run.pushes += 1;
+
+    while let Some(Reverse((d, node))) = queue.pop() {
+        if d > run.dist[node] {
+            run.stale_pops += 1;
+            continue;
+        }
+        for &(target, weight) in &graph[node] {
+            let candidate: u32 = d + weight;
+            if candidate < run.dist[target] {
+                run.dist[target] = candidate;
+                queue.push(Reverse((candidate, target)));
+                run.pushes += 1;
+            }
+        }
+    }
+    run
+}
+
+/// The same lazy strategy on a [`DaryHeap`] with `D` children per node.
+pub fn dijkstra_dary_heap<const D: usize>(graph: &Graph, source: usize) -> DijkstraRun {
+    let mut run: DijkstraRun = DijkstraRun::new(graph.len(), source);
+    let mut queue: DaryHeap<(u32, usize), D> = DaryHeap::new();
+    queue.push((0, source));
+    run.pushes += 1;
+
+    while let Some((d, node)) = queue.pop() {
+        if d > run.dist[node] {
+            run.stale_pops += 1;
+            continue;
+        }
+        for &(target, weight) in &graph[node] {
+            let candidate: u32 = d + weight;
+            if candidate < run.dist[target] {
+                run.dist[target] = candidate;
+                queue.push((candidate, target));
+                run.pushes += 1;
+            }
+        }
+    }
+    run
+}
+
+/// Dijkstra with a [`PairingHeap`]: each node is queued at most once and a
+/// shorter path calls decrease_key on its entry.
+pub fn dijkstra_pairing_heap(graph: &Graph, source: usize) -> DijkstraRun {
+    let mut run: DijkstraRun = DijkstraRun::new(graph.len(), source);
+    let mut queue: PairingHeap<(u32, usize)> = PairingHeap::new();
+    let mut handles: Vec<Option<HeapHandle>> = vec![None; graph.len()];
+    handles[source] = Some(queue.push((0, source)));
+    run.pushes += 1;
+
+    while let Some((d, node)) = queue.pop() {
+        for &(target, weight) in &graph[node] {
+            let candidate: u32 = d + weight;
+            if candidate < run.dist[target] {
+                run.dist[target] = candidate;
+                match handles[target] {
+                    Some(handle) => {
+                        queue.decrease_key(handle, (candidate, target));
+                        run.decrease_keys += 1;
+                    }
+                    None => {
+                        handles[target] = Some(queue.push((candidate, target)));
+                        run.pushes += 1;
+                    }
+                }
+            }
+        }
+    }
+    run
+}
+
+/// Demonstrates the D-ary heap and its depth compared with a binary heap.
+pub fn dary_heap_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "D-ary Heap Basics")?;
+
+    let mut heap: DaryHeap<u32, 4> = DaryHeap::new();
+    for value in [42, 7, 19, 3, 88, 21, 5, 64, 13] {
+        heap.push(value);
+    }
+    writeln!(out, "4-ary heap array: {:?}", heap.data)?;
+    writeln!(
+        out,
+        "  root {} has children {:?}",
+        heap.data[0],
+        &heap.data[1..5]
+    )?;
+    writeln!(
+        out,
+        "  node {} has children {:?}",
+        heap.data[1],
+        &heap.data[5..9]
+    )?;
+
+    let mut drained: Vec<u32> = Vec::new();
+    while let Some(value) = heap.pop() {
+        drained.push(value);
+    }
+    writeln!(out, "Popped in order: {:?}", drained)?;
+
+    // From<Vec> heapifies in O(n)
+    let heap: DaryHeap<u32, 8> = DaryHeap::from(vec![9, 4, 7, 1, 8]);
+    writeln!(
+        out,
+        "\n8-ary DaryHeap::from(vec![9, 4, 7, 1, 8]).peek(): {:?}",
+        heap.peek()
+    )?;
+
+    // Depth of a heap holding one million elements
+    writeln!(out, "\nLevels for 1,000,000 elements:")?;
+    for d in [2u32, 4, 8, 16] {
+        let mut levels: u32 = 0;
+        let mut capacity: u64 = 0;
+        let mut width: u64 = 1;
+        while capacity < 1_000_000 {
+            capacity += width;
+            width *= d as u64;
+            levels += 1;
+        }
+        writeln!(
+            out,
+            "  D = {:>2}: {:>2} levels, pop compares up to {:>3} children",
+            d,
+            levels,
+            levels * d
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Demonstrates the pairing heap's handle-based API.
+pub fn pairing_heap_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Pairing Heap Basics")?;
+
+    let mut tasks: PairingHeap<(u32, &str)> = PairingHeap::new();
+    let backup = tasks.push((50, "nightly backup"));
+    tasks.push((20, "send invoices"));
+    let report = tasks.push((80, "weekly report"));
+    tasks.push((35, "rotate logs"));
+    writeln!(out, "peek(): {:?}", tasks.peek())?;
+
+    // A handle finds the element again in O(1), wherever it sits in the tree
+    writeln!(out, "get(report): {:?}", tasks.get(report))?;
+    tasks.decrease_key(report, (10, "weekly report"));
+    writeln!(
+        out,
+        "After decrease_key(report, 10): peek() = {:?}",
+        tasks.peek()
+    )?;
+
+    let mut order: Vec<&str> = Vec::new();
+    while let Some((_, task)) = tasks.pop() {
+        order.push(task);
+    }
+    writeln!(out, "\nPopped in order: {:?}", order)?;
+    writeln!(out, "get(backup) after popping: {:?}", tasks.get(backup))?;
+
+    Ok(())
+}
+
+/// Demonstrates a decrease-key-heavy workload: single-source shortest paths.
+///
+/// The lazy strategy queues one entry per improvement, so the heap holds
+/// duplicates and every outdated entry costs a pop. A D-ary heap keeps the
+/// strategy but makes each of those extra pushes cheaper; the pairing heap
+/// avoids them altogether with decrease_key. The denser the graph, the more
+/// improvements per node and the more that matters.
+pub fn decrease_key_workloads(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Decrease-Key Workloads")?;
+
+    let graph: Graph = random_graph(2_000, 8, 42);
+    let edges: usize = graph.iter().map(Vec::len).sum();
+    writeln!(out, "Graph: {} nodes, {} edges", graph.len(), edges)?;
+
+    let binary: DijkstraRun = dijkstra_binary_heap(&graph, 0);
+    let dary: DijkstraRun = dijkstra_dary_heap::<4>(&graph, 0);
+    let pairing: DijkstraRun = dijkstra_pairing_heap(&graph, 0);
+
+    writeln!(
+        out,
+        "\n  {:<24} {:>7} {:>11} {:>14}",
+        "queue", "pushes", "stale pops", "decrease_keys"
+    )?;
+    for (name, run) in [
+        ("BinaryHeap (lazy)", &binary),
+        ("DaryHeap<_, 4> (lazy)", &dary),
+        ("PairingHeap", &pairing),
+    ] {
+        writeln!(
+            out,
+            "  {:<24} {:>7} {:>11} {:>14}",
+            name, run.pushes, run.stale_pops, run.decrease_keys
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\nSame distances from all three: {}",
+        binary.dist == dary.dist && dary.dist == pairing.dist
+    )?;
+    writeln!(out, "Distance to node 1999: {}", pairing.dist[1_999])?;
+
+    Ok(())
+}
+
+thread_local! {
+    static COMPARISONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A value that counts how often it is compared.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Counted(u32);
+
+impl Ord for Counted {
+    fn cmp(&self, other: &Self) -> Ordering {
+        COMPARISONS.with(|count| count.set(count.get() + 1));
+        self.0.cmp(&other.0)
+    }
+}
+
+impl PartialOrd for Counted {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn take_comparisons() -> usize {
+    COMPARISONS.with(|count| count.replace(0))
+}
+
+/// Demonstrates a merge-heavy workload by counting comparisons.
+///
+/// Merging pairing heaps links two roots, one comparison per merge, and
+/// leaves the restructuring to later pops. BinaryHeap::append has to put
+/// every moved element into heap order right away.
+pub fn merge_workloads(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Merge Workloads")?;
+
+    // 50 heaps of 200 values each, e.g. per-shard work queues
+    let shards: Vec<Vec<u32>> = (0..50u32)
+        .map(|shard| {
+            (0..200u32)
+                .map(|i| (shard * 7_919 + i * 104_729) % 100_000)
+                .collect()
+        })
+        .collect();
+
+    let mut binaries: Vec<BinaryHeap<Reverse<Counted>>> = shards
+        .iter()
+        .map(|values| values.iter().map(|&v| Reverse(Counted(v))).collect())
+        .collect();
+    let pairings: Vec<PairingHeap<Counted>> = shards
+        .iter()
+        .map(|values| {
+            let mut heap: PairingHeap<Counted> = PairingHeap::new();
+            for &v in values {
+                heap.push(Counted(v));
+            }
+            heap
+        })
+        .collect();
+    take_comparisons();
+
+    let mut binary: BinaryHeap<Reverse<Counted>> = BinaryHeap::new();
+    for heap in &mut binaries {
+        binary.append(heap);
+    }
+    let binary_merge: usize = take_comparisons();
+
+    let mut pairing: PairingHeap<Counted> = PairingHeap::new();
+    for heap in pairings {
+        pairing.merge(heap);
+    }
+    let pairing_merge: usize = take_comparisons();
+
+    writeln!(out, "Merging 50 heaps of 200 elements:")?;
+    writeln!(
+        out,
+        "  BinaryHeap::append:  {:>6} comparisons",
+        binary_merge
+    )?;
+    writeln!(
+        out,
+        "  PairingHeap::merge:  {:>6} comparisons",
+        pairing_merge
+    )?;
+
+    // The deferred work shows up when draining
+    let mut from_binary: Vec<u32> = Vec::new();
+    while let Some(Reverse(Counted(v))) = binary.pop() {
+        from_binary.push(v);
+    }
+    let binary_drain: usize = take_comparisons();
+    let mut from_pairing: Vec<u32> = Vec::new();
+    while let Some(Counted(v)) = pairing.pop() {
+        from_pairing.push(v);
+    }
+    let pairing_drain: usize = take_comparisons();
+
+    writeln!(out, "\nThen popping all 10,000:")?;
+    writeln!(out, "  BinaryHeap:   {:>6} comparisons", binary_drain)?;
+    writeln!(out, "  PairingHeap:  {:>6} comparisons", pairing_drain)?;
+    writeln!(out, "Same order: {}", from_binary == from_pairing)?;
+
+    // Merging wins when heaps are combined often and only partly drained -
+    // e.g. merging per-thread queues and then taking the top few.
+
+    Ok(())
+}
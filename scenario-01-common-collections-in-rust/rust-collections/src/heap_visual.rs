@@ -0,0 +1,132 @@
+//! ASCII rendering of a binary heap as the tree its Vec encodes.
+//!
+//! `BinaryHeap` stores its tree level by level in one Vec: the children of
+//! index `i` sit at `2i + 1` and `2i + 2`. The heap doesn't expose that Vec,
+//! so [`heap_vec`] takes it from a clone with `into_vec`, and [`render_tree`]
+//! draws it:
+//!
+//! ```text
+//!                 9
+//!         ┌───────┴───────┐
+//!         6               5
+//!     ┌───┴───┐       ┌───┴───┐
+//!     4       1       3       2
+//!   ┌─┘
+//!   1
+//! ```
+//!
+//! [`changed`] compares two layouts, so a demo can bracket the slots a push
+//! bubbled up through or a pop sifted down through, and [`heap_diagram`]
+//! turns a layout into a tree for `--export`.
+
+use demo_framework::diagram::Diagram;
+use std::collections::BinaryHeap;
+use std::fmt::Display;
+
+/// The heap's internal layout, root first.
+pub fn heap_vec<T: Ord + Clone>(heap: &BinaryHeap<T>) -> Vec<T> {
+    heap.clone().into_vec()
+}
+
+/// Indices whose element differs between two layouts of the same heap.
+/// Slots only one side has count as changed.
+pub fn changed<T: PartialEq>(before: &[T], after: &[T]) -> Vec<usize> {
+    (0..before.len().max(after.len()))
+        .filter(|&i| before.get(i) != after.get(i))
+        .collect()
+}
+
+/// Draws `items` (a heap layout) as a tree, one level per label line with
+/// connector lines between. Elements at `marked` indices are bracketed.
+pub fn render_tree<T: Display>(items: &[T], marked: &[usize]) -> String {
+    if items.is_empty() {
+        return "(empty)".to_string();
+    }
+
+    let labels: Vec<String> = items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            if marked.contains(&i) {
+                format!("[{}]", item)
+            } else {
+                item.to_string()
+            }
+        })
+        .collect();
+    // Sized for the widest element with brackets, so marking a node never
+    // shifts the layout
+    let widest: usize = items
+        .iter()
+        .map(|item| item.to_string().chars().count() + 2)
+        .max()
+        .unwrap_or(1);
+    let cell: usize = (widest + 2) & !1;
+    let levels: u32 = items.len().ilog2() + 1;
+    let width: usize = cell << (levels - 1);
+
+    // Each level splits the full width into equal spans; a node sits in the
+    // middle of its span, which is also midway between its children
+    let center = |index: usize| -> usize {
+        let level: u32 = (index + 1).ilog2();
+        let span: usize = width >> level;
+        (index + 1 - (1 << level)) * span + span / 2
+    };
+
+    let mut lines: Vec<String> = Vec::new();
+    for level in 0..levels {
+        let first: usize = (1 << level) - 1;
+        let last: usize = ((2 << level) - 1).min(items.len());
+
+        let mut row: Vec<char> = vec![' '; width];
+        for (i, label) in labels.iter().enumerate().take(last).skip(first) {
+            let start: usize = center(i) - (label.chars().count() - 1) / 2;
+            for (offset, c) in label.chars().enumerate() {
+                row[start + offset] = c;
+            }
+        }
+        lines.push(row.into_iter().collect::<String>().trim_end().to_string());
+
+        let mut connectors: Vec<char> = vec![' '; width];
+        for parent in first..last {
+            let (left, right) = (2 * parent + 1, 2 * parent + 2);
+            if left >= items.len() {
+                continue;
+            }
+            let (from, at) = (center(left), center(parent));
+            connectors[from..at].fill('─');
+            connectors[from] = '┌';
+            if right < items.len() {
+                let to: usize = center(right);
+                connectors[at..=to].fill('─');
+                connectors[at] = '┴';
+                connectors[to] = '┐';
+            } else {
+                connectors[at] = '┘';
+            }
+        }
+        let connectors: String = connectors.into_iter().collect::<String>();
+        if !connectors.trim().is_empty() {
+            lines.push(connectors.trim_end().to_string());
+        }
+    }
+    lines.join("\n")
+}
+
+/// [`render_tree`] for a heap, with nothing marked.
+pub fn render<T: Ord + Clone + Display>(heap: &BinaryHeap<T>) -> String {
+    render_tree(&heap_vec(heap), &[])
+}
+
+/// `items` (a heap layout) as a directed parent-to-child tree. Each node is
+/// labelled with its element and its index in the Vec.
+pub fn heap_diagram<T: Display>(name: &str, items: &[T]) -> Diagram {
+    let mut diagram: Diagram = Diagram::directed(name);
+    for (i, item) in items.iter().enumerate() {
+        diagram.node(i, format!("{} [{}]", item, i));
+    }
+    for child in 1..items.len() {
+        diagram.edge((child - 1) / 2, child, None);
+    }
+    diagram
+}
@@ -0,0 +1,529 @@
+// On a microcontroller there is often no heap at all: no allocator, no OS,
+// a few kilobytes of RAM laid out by the linker. The std collections in the
+// rest of this scenario all grow by allocating, so firmware reaches for the
+// `heapless` crate instead. Its collections are `no_std`, keep their
+// elements inline, and take their capacity as a const generic:
+//
+//   std                      heapless                   when it runs out of room
+//   Vec<T>                   heapless::Vec<T, N>        push returns Err(item)
+//   HashMap<K, V>            FnvIndexMap<K, V, N>       insert returns Err((key, value))
+//   VecDeque<T> as a queue   spsc::Queue<T, N>          enqueue returns Err(item)
+//
+// "Full" is an ordinary error the caller handles - drop the sample, flush
+// the buffer, count the overflow - rather than a reallocation that can fail
+// or stall at the worst moment. Every constructor is a `const fn`, so the
+// whole data structure can live in a `static` and its size is known when
+// the firmware is linked.
+//
+// This crate uses std, so the demos run on a desktop; the heapless types
+// themselves would compile unchanged in a `#![no_std]` binary.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use heapless::CapacityError;
+use heapless::index_map::FnvIndexMap;
+use heapless::spsc::{Consumer, Producer, Queue};
+use std::io::{self, Write};
+use std::mem::size_of;
+use std::sync::Mutex;
+use std::thread;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "heapless_vec",
+        "heapless::Vec: inline storage, a fixed capacity and Err on full",
+        heapless_vec,
+    )?;
+
+    section(
+        out,
+        "fnv_index_map",
+        "FnvIndexMap: a fixed-capacity hash map in insertion order",
+        fnv_index_map,
+    )?;
+
+    section(
+        out,
+        "spsc_queue",
+        "spsc::Queue: a lock-free ring split into producer and consumer halves",
+        spsc_queue,
+    )?;
+
+    section(
+        out,
+        "practical_sensor_telemetry",
+        "Practical demo: interrupt-fed telemetry with every buffer sized at compile time",
+        practical_sensor_telemetry,
+    )?;
+
+    Ok(())
+}
+
+/// An event log that lives in a `static`: `heapless::Vec::new` is a
+/// `const fn`, so no lazy initialization is needed.
+static EVENT_LOG: Mutex<heapless::Vec<u16, 4>> = Mutex::new(heapless::Vec::new());
+
+/// Demonstrates heapless::Vec next to std's Vec.
+///
+/// The elements sit inside the struct, so its size is the capacity times
+/// the element size plus a length field. `push` hands the element back when
+/// the vector is full; `extend_from_slice` adds all of the slice or nothing.
+pub fn heapless_vec(out: &mut dyn Write) -> io::Result<()> {
+    let mut samples: heapless::Vec<u16, 8> = heapless::Vec::new();
+    for sample in 100..106 {
+        // Capacity 8, six samples: these all fit
+        let _ = samples.push(sample);
+    }
+    writeln!(
+        out,
+        "\n  heapless::Vec<u16, 8> with {} of {} slots used: {:?}",
+        samples.len(),
+        samples.capacity(),
+        samples
+    )?;
+
+    let too_many: Result<(), CapacityError> = samples.extend_from_slice(&[1, 2, 3]);
+    writeln!(
+        out,
+        "  extend_from_slice(&[1, 2, 3]) -> {too_many:?}, len still {}",
+        samples.len()
+    )?;
+    let _ = samples.extend_from_slice(&[1, 2]);
+    writeln!(
+        out,
+        "  extend_from_slice(&[1, 2])    -> now full: {}",
+        samples.is_full()
+    )?;
+    writeln!(
+        out,
+        "  push(999)                     -> {:?}",
+        samples.push(999)
+    )?;
+
+    let mut growing: Vec<u16> = samples.to_vec();
+    let capacity_before: usize = growing.capacity();
+    growing.push(999);
+    writeln!(
+        out,
+        "  std Vec<u16> in the same spot grows instead: capacity {capacity_before} -> {}",
+        growing.capacity()
+    )?;
+
+    writeln!(out, "\n  Size of the value itself:")?;
+    writeln!(
+        out,
+        "    heapless::Vec<u8, 200>       {:>3} bytes (200 inline + a usize length)",
+        size_of::<heapless::Vec<u8, 200>>()
+    )?;
+    writeln!(
+        out,
+        "    heapless::Vec<u8, 200, u8>   {:>3} bytes (the length fits in one byte)",
+        size_of::<heapless::Vec<u8, 200, u8>>()
+    )?;
+    writeln!(
+        out,
+        "    std Vec<u8>                  {:>3} bytes + its heap buffer",
+        size_of::<Vec<u8>>()
+    )?;
+
+    let mut log = EVENT_LOG
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    log.clear();
+    let accepted: usize = [7, 8, 9, 10, 11]
+        .into_iter()
+        .filter(|&event| log.push(event).is_ok())
+        .count();
+    writeln!(
+        out,
+        "\n  static EVENT_LOG: Mutex<heapless::Vec<u16, 4>> accepted {accepted} of 5 events: {:?}",
+        *log
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates FnvIndexMap.
+///
+/// Entries are kept in a dense array in insertion order, with a separate
+/// index table of `N` slots, so `N` must be a power of two (checked at
+/// compile time). FNV has no random seed - there is no OS to ask for one -
+/// so the keys must come from the firmware itself, not from an attacker.
+pub fn fnv_index_map(out: &mut dyn Write) -> io::Result<()> {
+    let mut registers: FnvIndexMap<&str, u16, 4> = FnvIndexMap::new();
+    for (name, value) in [
+        ("ctrl", 0x01),
+        ("status", 0x80),
+        ("baud", 9600),
+        ("gain", 3),
+    ] {
+        let _ = registers.insert(name, value);
+    }
+    writeln!(
+        out,
+        "\n  FnvIndexMap<&str, u16, 4> ({} of {}): {:?}",
+        registers.len(),
+        registers.capacity(),
+        registers
+    )?;
+
+    let rejected: Result<Option<u16>, (&str, u16)> = registers.insert("offset", 12);
+    writeln!(out, "  insert(\"offset\", 12) when full -> {rejected:?}")?;
+    let replaced: Result<Option<u16>, (&str, u16)> = registers.insert("baud", 19_200);
+    writeln!(
+        out,
+        "  insert(\"baud\", ..) on an existing key still works -> {replaced:?}"
+    )?;
+
+    if let Ok(slot) = registers.entry("gain").or_insert(0) {
+        *slot += 1;
+    }
+    writeln!(
+        out,
+        "  entry(\"gain\").or_insert(0) += 1 -> {:?}",
+        registers.get("gain")
+    )?;
+
+    registers.swap_remove("ctrl");
+    writeln!(
+        out,
+        "  swap_remove(\"ctrl\") moves the last entry into its place: {:?}",
+        registers.keys().collect::<Vec<_>>()
+    )?;
+
+    writeln!(
+        out,
+        "\n  size_of::<FnvIndexMap<u8, u32, 16>>() = {} bytes, all inline",
+        size_of::<FnvIndexMap<u8, u32, 16>>()
+    )?;
+    writeln!(
+        out,
+        "  FnvIndexMap<_, _, 12> does not compile: the capacity must be a power of two."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates spsc::Queue.
+///
+/// A `Queue<T, N>` holds `N - 1` items: one slot stays empty so "full" and
+/// "empty" can be told apart without a shared counter. `split` hands out a
+/// `Producer` and a `Consumer` that may live on different threads (or in an
+/// interrupt handler and the main loop) without a lock.
+pub fn spsc_queue(out: &mut dyn Write) -> io::Result<()> {
+    let mut queue: Queue<u32, 4> = Queue::new();
+    let results: Vec<Result<(), u32>> = (1..=4).map(|item| queue.enqueue(item)).collect();
+    writeln!(
+        out,
+        "\n  Queue<u32, 4> has capacity {}: enqueue 1..=4 -> {results:?}",
+        queue.capacity()
+    )?;
+    writeln!(
+        out,
+        "  dequeue -> {:?}, then enqueue(4) -> {:?}",
+        queue.dequeue(),
+        queue.enqueue(4)
+    )?;
+
+    let mut ring: Queue<u32, 8> = Queue::new();
+    let (total, _retries) = transfer(&mut ring, 10_000);
+    writeln!(
+        out,
+        "\n  Producer thread -> Consumer thread through Queue<u32, 8>:"
+    )?;
+    writeln!(
+        out,
+        "    sent 1..=10000, received sum {total} (expected {})",
+        10_000u64 * 10_001 / 2
+    )?;
+    writeln!(
+        out,
+        "    7 items in flight at most: the producer retries while the queue is full"
+    )?;
+
+    Ok(())
+}
+
+/// Sends `1..=count` from a producer thread to a consumer thread through
+/// `queue`, retrying whenever it is full. Returns the sum received and how
+/// many enqueues found the queue full.
+pub fn transfer<const N: usize>(queue: &mut Queue<u32, N>, count: u32) -> (u64, u64) {
+    let (mut producer, mut consumer): (Producer<'_, u32>, Consumer<'_, u32>) = queue.split();
+    thread::scope(|scope| {
+        let sender = scope.spawn(move || {
+            let mut retries: u64 = 0;
+            for item in 1..=count {
+                let mut pending: u32 = item;
+                while let Err(back) = producer.enqueue(pending) {
+                    retries += 1;
+                    pending = back;
+                    thread::yield_now();
+                }
+            }
+            retries
+        });
+
+        let mut total: u64 = 0;
+        let mut received: u32 = 0;
+        while received < count {
+            match consumer.dequeue() {
+                Some(item) => {
+                    total += u64::from(item);
+                    received += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+        (total, sender.join().expect("producer thread panicked"))
+    })
+}
+
+/// Number of sensor IDs the telemetry table tracks.
+pub const MAX_SENSORS: usize = 8;
+
+/// Slots in the interrupt-to-main-loop queue; it holds one fewer reading.
+pub const QUEUE_SLOTS: usize = 8;
+
+/// Bytes in one radio frame.
+pub const FRAME_BYTES: usize = 24;
+
+/// Frames needed for a full table: 8-byte records, three per frame.
+pub const MAX_FRAMES: usize = 3;
+
+pub type SensorId = u8;
+pub type Frame = heapless::Vec<u8, FRAME_BYTES>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reading {
+    pub sensor: SensorId,
+    pub value: i16,
+}
+
+/// Running statistics for one sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SensorStats {
+    pub count: u16,
+    pub min: i16,
+    pub max: i16,
+    pub sum: i32,
+}
+
+impl SensorStats {
+    fn first(value: i16) -> Self {
+        SensorStats {
+            count: 1,
+            min: value,
+            max: value,
+            sum: i32::from(value),
+        }
+    }
+
+    fn add(&mut self, value: i16) {
+        self.count = self.count.saturating_add(1);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum = self.sum.saturating_add(i32::from(value));
+    }
+
+    pub fn mean(&self) -> i16 {
+        (self.sum / i32::from(self.count.max(1))) as i16
+    }
+}
+
+/// The firmware's telemetry state: an interrupt handler pushes readings,
+/// the main loop folds them into per-sensor statistics and packs those into
+/// radio frames. Every buffer has a fixed size, and every "full" is counted
+/// rather than allocated around.
+pub struct Telemetry {
+    queue: Queue<Reading, QUEUE_SLOTS>,
+    stats: FnvIndexMap<SensorId, SensorStats, MAX_SENSORS>,
+    /// Readings lost because the queue was full.
+    pub dropped: u32,
+    /// Readings from a sensor that didn't fit in the table.
+    pub untracked: u32,
+}
+
+impl Telemetry {
+    /// `const`, so a `Telemetry` can be a `static` placed by the linker.
+    pub const fn new() -> Self {
+        Telemetry {
+            queue: Queue::new(),
+            stats: FnvIndexMap::new(),
+            dropped: 0,
+            untracked: 0,
+        }
+    }
+
+    /// What the interrupt handler does: enqueue, or count the loss.
+    /// Returns whether the reading was queued.
+    pub fn on_interrupt(&mut self, reading: Reading) -> bool {
+        let queued: bool = self.queue.enqueue(reading).is_ok();
+        if !queued {
+            self.dropped += 1;
+        }
+        queued
+    }
+
+    /// One pass of the main loop: drain up to `budget` readings into the
+    /// table. Returns how many were processed.
+    pub fn main_loop_tick(&mut self, budget: usize) -> usize {
+        let mut processed: usize = 0;
+        while processed < budget {
+            let Some(reading) = self.queue.dequeue() else {
+                break;
+            };
+            processed += 1;
+            if let Some(stats) = self.stats.get_mut(&reading.sensor) {
+                stats.add(reading.value);
+            } else if self
+                .stats
+                .insert(reading.sensor, SensorStats::first(reading.value))
+                .is_err()
+            {
+                self.untracked += 1;
+            }
+        }
+        processed
+    }
+
+    pub fn stats(&self) -> &FnvIndexMap<SensorId, SensorStats, MAX_SENSORS> {
+        &self.stats
+    }
+
+    /// Packs the table into frames of 8-byte records (id, count, min, max,
+    /// mean; 16-bit fields big-endian), starting a new frame whenever a
+    /// record doesn't fit in the current one.
+    pub fn frames(&self) -> heapless::Vec<Frame, MAX_FRAMES> {
+        let mut frames: heapless::Vec<Frame, MAX_FRAMES> = heapless::Vec::new();
+        let mut frame: Frame = Frame::new();
+        for (&sensor, stats) in &self.stats {
+            let [min_hi, min_lo] = stats.min.to_be_bytes();
+            let [max_hi, max_lo] = stats.max.to_be_bytes();
+            let [mean_hi, mean_lo] = stats.mean().to_be_bytes();
+            let count: u8 = stats.count.min(u16::from(u8::MAX)) as u8;
+            let record: [u8; 8] = [
+                sensor, count, min_hi, min_lo, max_hi, max_lo, mean_hi, mean_lo,
+            ];
+            if frame.extend_from_slice(&record).is_err() {
+                // MAX_FRAMES frames hold MAX_SENSORS records
+                let _ = frames.push(frame);
+                frame = Frame::new();
+                let _ = frame.extend_from_slice(&record);
+            }
+        }
+        if !frame.is_empty() {
+            let _ = frames.push(frame);
+        }
+        frames
+    }
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Readings for `sensors` sensors arriving in bursts of `burst` per tick,
+/// from a fixed seed.
+pub fn sensor_bursts(ticks: usize, burst: usize, sensors: u8, seed: u64) -> Vec<Vec<Reading>> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..ticks)
+        .map(|_| {
+            (0..burst)
+                .map(|_| {
+                    let state: u64 = rng.next_u64();
+                    let sensor: SensorId = ((state >> 33) % u64::from(sensors)) as SensorId;
+                    let noise: i16 = ((state >> 40) % 21) as i16 - 10;
+                    Reading {
+                        sensor,
+                        value: 200 + i16::from(sensor) * 50 + noise,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Practical example: telemetry on a microcontroller.
+///
+/// An interrupt delivers sensor readings in bursts; the main loop can only
+/// process a few per tick. The queue absorbs short bursts and drops the
+/// rest; the stats table has room for eight sensors and ignores a ninth;
+/// the frame builder starts a new frame when a record doesn't fit. Each
+/// limit is a number in the source, and the program can't run out of
+/// memory at runtime.
+pub fn practical_sensor_telemetry(out: &mut dyn Write) -> io::Result<()> {
+    let mut telemetry: Telemetry = Telemetry::new();
+    let bursts: Vec<Vec<Reading>> = sensor_bursts(50, 6, 9, 21);
+
+    let mut queued: u32 = 0;
+    for burst in &bursts {
+        for &reading in burst {
+            queued += u32::from(telemetry.on_interrupt(reading));
+        }
+        telemetry.main_loop_tick(4);
+    }
+    while telemetry.main_loop_tick(4) > 0 {}
+
+    let sent: usize = bursts.iter().map(Vec::len).sum();
+    writeln!(
+        out,
+        "\n  {sent} readings from 9 sensors, 6 per interrupt burst, 4 processed per main-loop tick:"
+    )?;
+    writeln!(
+        out,
+        "    queued {queued}, dropped on a full queue {}, from an untracked sensor {}",
+        telemetry.dropped, telemetry.untracked
+    )?;
+
+    writeln!(
+        out,
+        "\n  Stats table ({} of {MAX_SENSORS} sensors):",
+        telemetry.stats().len()
+    )?;
+    writeln!(
+        out,
+        "    {:>6} {:>5} {:>5} {:>5} {:>5}",
+        "sensor", "count", "min", "max", "mean"
+    )?;
+    for (sensor, stats) in telemetry.stats() {
+        writeln!(
+            out,
+            "    {:>6} {:>5} {:>5} {:>5} {:>5}",
+            sensor,
+            stats.count,
+            stats.min,
+            stats.max,
+            stats.mean()
+        )?;
+    }
+
+    let frames: heapless::Vec<Frame, MAX_FRAMES> = telemetry.frames();
+    writeln!(
+        out,
+        "\n  Radio frames ({FRAME_BYTES} bytes max, 8 bytes per sensor):"
+    )?;
+    for (i, frame) in frames.iter().enumerate() {
+        writeln!(
+            out,
+            "    frame {i}: {:>2} bytes, sensors {:?}",
+            frame.len(),
+            frame_sensors(frame)
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\n  size_of::<Telemetry>() = {} bytes, fixed at compile time.",
+        size_of::<Telemetry>()
+    )?;
+
+    Ok(())
+}
+
+/// The sensor IDs in a frame: the first byte of each 8-byte record.
+fn frame_sensors(frame: &Frame) -> heapless::Vec<SensorId, 3> {
+    frame.chunks(8).map(|record| record[0]).collect()
+}
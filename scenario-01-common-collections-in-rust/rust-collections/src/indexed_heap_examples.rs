@@ -0,0 +1,488 @@
+// An indexed priority queue is a binary heap plus a map from each key to
+// its current position in the heap's Vec:
+//
+//   heap:      [ (b, 2) | (a, 5) | (c, 9) ]      positions: a -> 1
+//                  0        1        2                      b -> 0
+//                                                           c -> 2
+//
+// Every swap during sift-up/sift-down updates the two positions involved.
+// With the map, "change the priority of key a" finds a's slot in O(1) and
+// re-sifts from there: O(log n), with no duplicate entries.
+//
+// That is the decrease-key operation std's BinaryHeap lacks. The usual
+// workaround (push a duplicate, skip stale entries on pop) works, but the
+// heap grows with the number of updates rather than the number of keys.
+//
+// IndexedBinaryHeap here is a min-heap: the smallest priority pops first,
+// which is what shortest-path and scheduling algorithms want.
+
+use demo_framework::section;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "indexed_heap_basics",
+        "push, pop and the key -> position map",
+        indexed_heap_basics,
+    )?;
+
+    section(
+        out,
+        "changing_priorities",
+        "decrease_priority, change_priority and remove by key",
+        changing_priorities,
+    )?;
+
+    section(
+        out,
+        "practical_dijkstra",
+        "Practical demo: Dijkstra's shortest paths with decrease-key",
+        practical_dijkstra,
+    )?;
+
+    section(
+        out,
+        "heap_size_vs_lazy_deletion",
+        "Heap size with decrease-key vs pushing duplicates",
+        heap_size_vs_lazy_deletion,
+    )?;
+
+    Ok(())
+}
+
+/// A binary min-heap of `(key, priority)` pairs that can find any key.
+///
+/// Each key appears at most once. Pushing an existing key updates its
+/// priority instead of adding a second entry.
+#[derive(Clone, Debug)]
+pub struct IndexedBinaryHeap<K, P> {
+    heap: Vec<(K, P)>,
+    positions: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> IndexedBinaryHeap<K, P> {
+    pub fn new() -> Self {
+        IndexedBinaryHeap {
+            heap: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        IndexedBinaryHeap {
+            heap: Vec::with_capacity(capacity),
+            positions: HashMap::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    /// The current priority of `key`, if it is queued.
+    pub fn priority(&self, key: &K) -> Option<&P> {
+        self.positions.get(key).map(|&i| &self.heap[i].1)
+    }
+
+    /// The entry with the smallest priority.
+    pub fn peek(&self) -> Option<(&K, &P)> {
+        self.heap.first().map(|(k, p)| (k, p))
+    }
+
+    /// Queues `key`, or changes its priority if it is already queued.
+    /// Returns the previous priority in that case.
+    pub fn push(&mut self, key: K, priority: P) -> Option<P> {
+        if self.positions.contains_key(&key) {
+            return self.change_priority(&key, priority);
+        }
+        let index: usize = self.heap.len();
+        self.positions.insert(key.clone(), index);
+        self.heap.push((key, priority));
+        self.sift_up(index);
+        None
+    }
+
+    /// Removes and returns the entry with the smallest priority.
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    /// Lowers the priority of `key` to `priority` if that is smaller than
+    /// its current one. Returns whether anything changed.
+    pub fn decrease_priority(&mut self, key: &K, priority: P) -> bool {
+        let Some(&index) = self.positions.get(key) else {
+            return false;
+        };
+        if priority >= self.heap[index].1 {
+            return false;
+        }
+        self.heap[index].1 = priority;
+        self.sift_up(index);
+        true
+    }
+
+    /// Sets the priority of a queued `key`, up or down, and returns the old
+    /// one. Returns None (and does nothing) if the key is not queued.
+    pub fn change_priority(&mut self, key: &K, priority: P) -> Option<P> {
+        let index: usize = *self.positions.get(key)?;
+        let old: P = std::mem::replace(&mut self.heap[index].1, priority);
+        if self.heap[index].1 < old {
+            self.sift_up(index);
+        } else {
+            self.sift_down(index);
+        }
+        Some(old)
+    }
+
+    /// Removes `key` from the queue, wherever it is.
+    pub fn remove(&mut self, key: &K) -> Option<P> {
+        let index: usize = *self.positions.get(key)?;
+        Some(self.remove_at(index).1)
+    }
+
+    /// Iterates over the queued entries in heap order, not priority order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &P)> {
+        self.heap.iter().map(|(k, p)| (k, p))
+    }
+
+    /// Moves the last entry into `index`, then restores the heap property.
+    fn remove_at(&mut self, index: usize) -> (K, P) {
+        let last: usize = self.heap.len() - 1;
+        self.swap(index, last);
+        let removed: (K, P) = self.heap.pop().expect("heap is not empty");
+        self.positions.remove(&removed.0);
+        if index < self.heap.len() {
+            // The moved entry may belong above or below its new slot
+            self.sift_up(index);
+            self.sift_down(index);
+        }
+        removed
+    }
+
+    /// Swaps two entries and updates both positions.
+    fn swap(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        self.heap.swap(a, b);
+        self.positions.insert(self.heap[a].0.clone(), a);
+        self.positions.insert(self.heap[b].0.clone(), b);
+    }
+
+    fn sift_up(&mut self, mut index: usize) {
+        while index > 0 {
+            let parent: usize = (index - 1) / 2;
+            if self.heap[index].1 >= self.heap[parent].1 {
+                break;
+            }
+            self.swap(index, parent);
+            index = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut index: usize) {
+        loop {
+            let left: usize = 2 * index + 1;
+            let right: usize = left + 1;
+            let mut smallest: usize = index;
+            if left < self.heap.len() && self.heap[left].1 < self.heap[smallest].1 {
+                smallest = left;
+            }
+            if right < self.heap.len() && self.heap[right].1 < self.heap[smallest].1 {
+                smallest = right;
+            }
+            if smallest == index {
+                break;
+            }
+            self.swap(index, smallest);
+            index = smallest;
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, P: Ord> Default for IndexedBinaryHeap<K, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A weighted directed graph: each node's outgoing `(target, weight)` edges.
+pub type WeightedGraph<N> = HashMap<N, Vec<(N, u32)>>;
+
+/// The result of a single-source shortest-path search.
+#[derive(Clone, Debug)]
+pub struct ShortestPaths<N> {
+    /// Distance from the source to every reachable node.
+    pub dist: HashMap<N, u32>,
+    /// The node before each reachable node on its shortest path.
+    pub prev: HashMap<N, N>,
+    /// Largest number of entries the queue held at once.
+    pub max_queue_len: usize,
+}
+
+impl<N: Hash + Eq + Clone> ShortestPaths<N> {
+    /// The nodes on the shortest path from the source to `target`.
+    pub fn path_to(&self, target: &N) -> Option<Vec<N>> {
+        if !self.dist.contains_key(target) {
+            return None;
+        }
+        let mut path: Vec<N> = vec![target.clone()];
+        let mut current: &N = target;
+        while let Some(previous) = self.prev.get(current) {
+            path.push(previous.clone());
+            current = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Dijkstra's algorithm with an [`IndexedBinaryHeap`].
+///
+/// Each node is queued at most once. Finding a shorter path to a queued
+/// node lowers its priority in place.
+pub fn dijkstra<N: Hash + Eq + Clone>(graph: &WeightedGraph<N>, source: N) -> ShortestPaths<N> {
+    let mut dist: HashMap<N, u32> = HashMap::new();
+    let mut prev: HashMap<N, N> = HashMap::new();
+    let mut queue: IndexedBinaryHeap<N, u32> = IndexedBinaryHeap::new();
+    let mut max_queue_len: usize = 0;
+
+    dist.insert(source.clone(), 0);
+    queue.push(source, 0);
+
+    while let Some((node, d)) = queue.pop() {
+        for (target, weight) in graph.get(&node).into_iter().flatten() {
+            let candidate: u32 = d + weight;
+            if dist.get(target).is_none_or(|&known| candidate < known) {
+                dist.insert(target.clone(), candidate);
+                prev.insert(target.clone(), node.clone());
+                // Inserts an unseen node, lowers the priority of a queued one
+                queue.push(target.clone(), candidate);
+            }
+        }
+        max_queue_len = max_queue_len.max(queue.len());
+    }
+
+    ShortestPaths {
+        dist,
+        prev,
+        max_queue_len,
+    }
+}
+
+/// The same search with std's BinaryHeap and lazy deletion, for comparison.
+pub fn dijkstra_lazy<N: Hash + Eq + Clone + Ord>(
+    graph: &WeightedGraph<N>,
+    source: N,
+) -> ShortestPaths<N> {
+    let mut dist: HashMap<N, u32> = HashMap::new();
+    let mut prev: HashMap<N, N> = HashMap::new();
+    let mut queue: BinaryHeap<Reverse<(u32, N)>> = BinaryHeap::new();
+    let mut max_queue_len: usize = 0;
+
+    dist.insert(source.clone(), 0);
+    queue.push(Reverse((0, source)));
+
+    while let Some(Reverse((d, node))) = queue.pop() {
+        if d > dist[&node] {
+            continue; // stale duplicate
+        }
+        for (target, weight) in graph.get(&node).into_iter().flatten() {
+            let candidate: u32 = d + weight;
+            if dist.get(target).is_none_or(|&known| candidate < known) {
+                dist.insert(target.clone(), candidate);
+                prev.insert(target.clone(), node.clone());
+                queue.push(Reverse((candidate, target.clone())));
+            }
+        }
+        max_queue_len = max_queue_len.max(queue.len());
+    }
+
+    ShortestPaths {
+        dist,
+        prev,
+        max_queue_len,
+    }
+}
+
+/// Demonstrates the basic queue operations and the position map.
+pub fn indexed_heap_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Indexed Heap Basics")?;
+
+    let mut jobs: IndexedBinaryHeap<&str, u32> = IndexedBinaryHeap::new();
+    for (job, priority) in [("compile", 5), ("test", 7), ("lint", 2), ("deploy", 9)] {
+        jobs.push(job, priority);
+    }
+    writeln!(out, "Heap order: {:?}", jobs.iter().collect::<Vec<_>>())?;
+
+    // Every key knows where it is in the Vec
+    for job in ["lint", "compile", "test", "deploy"] {
+        writeln!(out, "  {:<8} at position {}", job, jobs.positions[job])?;
+    }
+
+    writeln!(out, "\npeek(): {:?}", jobs.peek())?;
+    writeln!(out, "priority(&\"test\"): {:?}", jobs.priority(&"test"))?;
+
+    // Pushing a queued key updates it rather than duplicating it
+    writeln!(
+        out,
+        "push(\"test\", 1) -> old priority {:?}",
+        jobs.push("test", 1)
+    )?;
+    writeln!(out, "len() still {}", jobs.len())?;
+
+    let mut order: Vec<(&str, u32)> = Vec::new();
+    while let Some(entry) = jobs.pop() {
+        order.push(entry);
+    }
+    writeln!(out, "Popped in order: {:?}", order)?;
+
+    Ok(())
+}
+
+/// Demonstrates updating and removing entries by key.
+pub fn changing_priorities(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Changing Priorities")?;
+
+    let mut tickets: IndexedBinaryHeap<u32, u32> = IndexedBinaryHeap::new();
+    for (ticket, sla_minutes) in [(101, 240), (102, 60), (103, 480), (104, 120)] {
+        tickets.push(ticket, sla_minutes);
+    }
+    writeln!(out, "Next ticket: {:?}", tickets.peek())?;
+
+    // A customer escalates ticket 103: its deadline moves up
+    writeln!(
+        out,
+        "decrease_priority(103, 30): {}",
+        tickets.decrease_priority(&103, 30)
+    )?;
+    writeln!(out, "Next ticket: {:?}", tickets.peek())?;
+
+    // decrease_priority never raises a priority...
+    writeln!(
+        out,
+        "decrease_priority(102, 90): {} (90 > 60)",
+        tickets.decrease_priority(&102, 90)
+    )?;
+    // ...change_priority moves it either way
+    writeln!(
+        out,
+        "change_priority(103, 600): old {:?}",
+        tickets.change_priority(&103, 600)
+    )?;
+    writeln!(out, "Next ticket: {:?}", tickets.peek())?;
+
+    // Remove from the middle of the heap by key
+    writeln!(out, "\nremove(&104): {:?}", tickets.remove(&104))?;
+    writeln!(out, "contains_key(&104): {}", tickets.contains_key(&104))?;
+
+    let mut order: Vec<u32> = Vec::new();
+    while let Some((ticket, _)) = tickets.pop() {
+        order.push(ticket);
+    }
+    writeln!(out, "Remaining, by deadline: {:?}", order)?;
+
+    Ok(())
+}
+
+/// A small road network (travel minutes between towns).
+fn road_network() -> WeightedGraph<&'static str> {
+    let roads: [(&str, &str, u32); 9] = [
+        ("Ashford", "Brook", 7),
+        ("Ashford", "Cliffton", 9),
+        ("Ashford", "Fairview", 14),
+        ("Brook", "Cliffton", 10),
+        ("Brook", "Dunmore", 15),
+        ("Cliffton", "Dunmore", 11),
+        ("Cliffton", "Fairview", 2),
+        ("Dunmore", "Eastwick", 6),
+        ("Eastwick", "Fairview", 9),
+    ];
+    let mut graph: WeightedGraph<&str> = HashMap::new();
+    for (a, b, minutes) in roads {
+        // Roads run both ways
+        graph.entry(a).or_default().push((b, minutes));
+        graph.entry(b).or_default().push((a, minutes));
+    }
+    graph
+}
+
+/// Practical example: shortest routes in a road network.
+///
+/// Fairview is first reached directly from Ashford (14 minutes), then
+/// decrease-keyed to 11 via Cliffton before it is ever popped.
+pub fn practical_dijkstra(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Dijkstra with Decrease-Key")?;
+
+    let graph: WeightedGraph<&str> = road_network();
+    let routes: ShortestPaths<&str> = dijkstra(&graph, "Ashford");
+
+    let mut towns: Vec<&&str> = routes.dist.keys().collect();
+    towns.sort();
+    for town in towns {
+        let path: Vec<&str> = routes.path_to(town).unwrap_or_default();
+        writeln!(
+            out,
+            "  {:<9} {:>2} min via {}",
+            town,
+            routes.dist[town],
+            path.join(" -> ")
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Demonstrates how large the queue gets with each strategy.
+///
+/// With decrease-key the queue never holds more than one entry per node.
+/// Lazy deletion holds one entry per improvement found, minus the ones
+/// already popped.
+pub fn heap_size_vs_lazy_deletion(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Heap Size: Decrease-Key vs Lazy Deletion")?;
+
+    // A dense graph with many shortcuts: lots of improvements per node
+    let nodes: u32 = 300;
+    let mut graph: WeightedGraph<u32> = HashMap::new();
+    for a in 0..nodes {
+        for b in 0..nodes {
+            if a != b {
+                let weight: u32 = (a * 7 + b * 13) % 97 + 1 + a.abs_diff(b);
+                graph.entry(a).or_default().push((b, weight));
+            }
+        }
+    }
+
+    let indexed: ShortestPaths<u32> = dijkstra(&graph, 0);
+    let lazy: ShortestPaths<u32> = dijkstra_lazy(&graph, 0);
+
+    writeln!(out, "Graph: {} nodes, {} edges", nodes, nodes * (nodes - 1))?;
+    writeln!(
+        out,
+        "  IndexedBinaryHeap: max queue length {}",
+        indexed.max_queue_len
+    )?;
+    writeln!(
+        out,
+        "  BinaryHeap (lazy): max queue length {}",
+        lazy.max_queue_len
+    )?;
+    writeln!(out, "Same distances: {}", indexed.dist == lazy.dist)?;
+
+    Ok(())
+}
@@ -0,0 +1,283 @@
+// IndexMap and IndexSet (from the `indexmap` crate) fill the gap between
+// HashMap and BTreeMap: a hash map that remembers insertion order.
+//
+// Internally it is two structures working together:
+//
+//   entries: Vec<(hash, K, V)>   ← dense, in insertion order
+//   indices: hash table of usize ← maps a key's hash to its position
+//
+// Key trade-offs:
+//   HashMap             BTreeMap             IndexMap
+//   - O(1) average      - O(log n)           - O(1) average
+//   - Arbitrary order   - Sorted by key      - Insertion order (or any order you sort into)
+//   - No positions      - No positions       - Every entry has an index: get_index(i)
+//
+// The catch is removal: keeping the Vec dense means either moving the last
+// entry into the hole (swap_remove, O(1), perturbs order) or shifting every
+// later entry down (shift_remove, O(n), preserves order).
+
+use demo_framework::section;
+use indexmap::{IndexMap, IndexSet};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "insertion_order_iteration",
+        "IndexMap iterates in insertion order, HashMap doesn't",
+        insertion_order_iteration,
+    )?;
+
+    section(
+        out,
+        "swap_remove_vs_shift_remove",
+        "The two ways to remove from an IndexMap and what they do to order",
+        swap_remove_vs_shift_remove,
+    )?;
+
+    section(
+        out,
+        "positional_access",
+        "Looking entries up by position: get_index, get_full, get_index_of",
+        positional_access,
+    )?;
+
+    section(
+        out,
+        "sorting_entries",
+        "Sorting an IndexMap by key or by value",
+        sorting_entries,
+    )?;
+
+    section(
+        out,
+        "indexset_examples",
+        "IndexSet: a HashSet that remembers insertion order",
+        indexset_examples,
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates IndexMap's defining feature: insertion-order iteration.
+///
+/// Re-inserting an existing key updates the value in place - it keeps its
+/// original position. That makes IndexMap a drop-in for config files,
+/// JSON objects and anything else whose order a human wrote down.
+pub fn insertion_order_iteration(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Insertion-Order Iteration")?;
+
+    let steps: [(&str, u32); 4] = [
+        ("checkout", 12),
+        ("build", 340),
+        ("test", 95),
+        ("deploy", 40),
+    ];
+
+    let index_map: IndexMap<&str, u32> = steps.into_iter().collect();
+    let hash_map: HashMap<&str, u32> = steps.into_iter().collect();
+
+    let index_order: Vec<&&str> = index_map.keys().collect();
+    writeln!(out, "IndexMap order: {:?}", index_order)?;
+    // HashMap order depends on the random hasher seed - it may differ per run
+    writeln!(
+        out,
+        "HashMap holds the same {} keys in arbitrary order",
+        hash_map.len()
+    )?;
+
+    // Updating an existing key keeps its position
+    let mut pipeline: IndexMap<&str, u32> = index_map.clone();
+    let old: Option<u32> = pipeline.insert("build", 300);
+    writeln!(out, "\nRe-inserted 'build' (old value {:?}):", old)?;
+    writeln!(out, "  {:?}", pipeline)?;
+
+    // New keys always go at the end
+    pipeline.insert("notify", 2);
+    writeln!(out, "Inserted 'notify': {:?}", pipeline)?;
+
+    // insert_full also reports the position the key landed in
+    let (position, previous) = pipeline.insert_full("lint", 20);
+    writeln!(
+        out,
+        "insert_full(\"lint\") -> index {}, previous {:?}",
+        position, previous
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the two removal strategies and their effect on order.
+///
+/// - `swap_remove`: moves the last entry into the hole. O(1), but the last
+///   entry jumps to a new position.
+/// - `shift_remove`: shifts every later entry down by one. O(n), but the
+///   remaining entries keep their relative order.
+///
+/// Plain `remove` is deprecated because it hid this choice.
+pub fn swap_remove_vs_shift_remove(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "swap_remove vs shift_remove")?;
+
+    let original: IndexMap<char, u32> = ('a'..='f').zip(1..).collect();
+    writeln!(out, "Original: {:?}", original)?;
+
+    // swap_remove: 'f' (last) fills the slot 'b' leaves behind
+    let mut swapped: IndexMap<char, u32> = original.clone();
+    let removed: Option<u32> = swapped.swap_remove(&'b');
+    writeln!(out, "\nswap_remove('b') -> {:?}", removed)?;
+    writeln!(out, "  {:?}", swapped)?;
+    writeln!(
+        out,
+        "  'f' is now at index {:?}",
+        swapped.get_index_of(&'f')
+    )?;
+
+    // shift_remove: c, d, e, f each move down one slot
+    let mut shifted: IndexMap<char, u32> = original.clone();
+    let removed: Option<u32> = shifted.shift_remove(&'b');
+    writeln!(out, "\nshift_remove('b') -> {:?}", removed)?;
+    writeln!(out, "  {:?}", shifted)?;
+    writeln!(
+        out,
+        "  'f' is now at index {:?}",
+        shifted.get_index_of(&'f')
+    )?;
+
+    // pop() removes the last entry - O(1) and order-preserving
+    let mut stack: IndexMap<char, u32> = original;
+    writeln!(out, "\npop() -> {:?}", stack.pop())?;
+    writeln!(out, "  {:?}", stack)?;
+
+    // Rule of thumb: use swap_remove unless order matters to your users;
+    // on a map of 100k entries shift_remove near the front moves ~100k entries.
+
+    Ok(())
+}
+
+/// Demonstrates positional access, which neither HashMap nor BTreeMap offer.
+///
+/// Every entry has a stable index (until a removal moves it), so an
+/// IndexMap doubles as a Vec you can also query by key.
+pub fn positional_access(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Positional Access")?;
+
+    let mut leaderboard: IndexMap<&str, u32> = IndexMap::new();
+    leaderboard.insert("alice", 3_200);
+    leaderboard.insert("bob", 2_900);
+    leaderboard.insert("carol", 3_050);
+    writeln!(out, "Leaderboard: {:?}", leaderboard)?;
+
+    // get_index: position -> (key, value)
+    writeln!(out, "\nget_index(0): {:?}", leaderboard.get_index(0))?;
+    writeln!(out, "get_index(9): {:?}", leaderboard.get_index(9))?;
+
+    // get_full: key -> (index, key, value)
+    writeln!(
+        out,
+        "get_full(\"carol\"): {:?}",
+        leaderboard.get_full("carol")
+    )?;
+
+    // get_index_of: key -> index
+    writeln!(
+        out,
+        "get_index_of(\"bob\"): {:?}",
+        leaderboard.get_index_of("bob")
+    )?;
+
+    // first()/last() are O(1), like on a Vec
+    writeln!(out, "first(): {:?}", leaderboard.first())?;
+    writeln!(out, "last(): {:?}", leaderboard.last())?;
+
+    // Mutate by position
+    if let Some((_, score)) = leaderboard.get_index_mut(1) {
+        *score += 500;
+    }
+    writeln!(out, "\nAfter get_index_mut(1) += 500: {:?}", leaderboard)?;
+
+    // Reorder without rehashing: move_index / swap_indices
+    leaderboard.move_index(2, 0);
+    writeln!(out, "After move_index(2, 0): {:?}", leaderboard)?;
+
+    Ok(())
+}
+
+/// Demonstrates sorting an IndexMap in place.
+///
+/// Because the entries live in a Vec, sorting is just a slice sort followed
+/// by a rebuild of the hash indices - the map stays a map afterwards.
+pub fn sorting_entries(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Sorting Entries")?;
+
+    let text: &str = "the quick brown fox jumps over the lazy dog the fox";
+    let mut counts: IndexMap<&str, u32> = IndexMap::new();
+    for word in text.split_whitespace() {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    writeln!(out, "First-seen order: {:?}", counts)?;
+
+    // By key
+    counts.sort_keys();
+    writeln!(out, "\nsort_keys(): {:?}", counts)?;
+
+    // By value, highest first; sort_by is stable, so ties keep key order
+    counts.sort_by(|_, a, _, b| b.cmp(a));
+    writeln!(out, "sort_by(value desc): {:?}", counts)?;
+
+    // Top-N is now a prefix slice
+    let top: Vec<(&&str, &u32)> = counts.iter().take(2).collect();
+    writeln!(out, "Top 2: {:?}", top)?;
+
+    // sorted_by consumes the map and yields (K, V) in order without re-indexing
+    let by_word_length: Vec<(&str, u32)> = counts
+        .sorted_by(|k1, _, k2, _| k1.len().cmp(&k2.len()).then(k1.cmp(k2)))
+        .collect();
+    writeln!(out, "sorted_by(key length): {:?}", by_word_length)?;
+
+    Ok(())
+}
+
+/// Demonstrates IndexSet: insertion-ordered, deduplicated values.
+///
+/// A common use is deduplicating while keeping the first occurrence's
+/// position, which `HashSet` can't do and `Vec::dedup` only does for
+/// adjacent duplicates.
+pub fn indexset_examples(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "IndexSet")?;
+
+    let visits: [&str; 7] = [
+        "/home", "/docs", "/home", "/blog", "/docs", "/about", "/home",
+    ];
+    let unique: IndexSet<&str> = visits.into_iter().collect();
+    writeln!(out, "Visits: {:?}", visits)?;
+    writeln!(out, "Unique, in first-visit order: {:?}", unique)?;
+
+    // insert_full reports whether the value was new and where it lives
+    let mut pages: IndexSet<&str> = unique;
+    writeln!(
+        out,
+        "\ninsert_full(\"/blog\"): {:?}",
+        pages.insert_full("/blog")
+    )?;
+    writeln!(
+        out,
+        "insert_full(\"/rss\"): {:?}",
+        pages.insert_full("/rss")
+    )?;
+
+    // Positional access works the same as on IndexMap
+    writeln!(out, "get_index(1): {:?}", pages.get_index(1))?;
+    writeln!(
+        out,
+        "get_index_of(\"/about\"): {:?}",
+        pages.get_index_of("/about")
+    )?;
+
+    // Set operations preserve the order of the left-hand set
+    let nav: IndexSet<&str> = IndexSet::from(["/about", "/home", "/contact"]);
+    let both: Vec<&&str> = pages.intersection(&nav).collect();
+    writeln!(out, "\nIntersection with nav: {:?}", both)?;
+
+    Ok(())
+}
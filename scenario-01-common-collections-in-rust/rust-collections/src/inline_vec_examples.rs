@@ -0,0 +1,294 @@
+// Inline-storage vectors keep their first N elements inside the value itself
+// (on the stack, or inline in whatever struct holds them) instead of in a
+// separate heap allocation.
+//
+//   Vec<T>:            [ptr | cap | len] ──► heap: [T, T, T, ...]
+//   SmallVec<[T; 4]>:  [ T | T | T | T | len ]   ← no allocation until len > 4
+//
+// Three crates, three trade-offs:
+//   smallvec::SmallVec  - inline up to N, then "spills" to the heap
+//   arrayvec::ArrayVec  - inline only; a fixed capacity that is never exceeded
+//   tinyvec::TinyVec    - like SmallVec, but 100% safe code (needs T: Default)
+//
+// Inline storage wins when most collections are small and there are many of
+// them: no allocator call, no pointer chase, better cache locality. It loses
+// when N is large (every value carries N slots, used or not) or when most
+// collections spill anyway (you pay for the inline buffer *and* the heap).
+
+use arrayvec::{ArrayString, ArrayVec};
+use demo_framework::section;
+use smallvec::{SmallVec, smallvec};
+use std::io::{self, Write};
+use std::mem::size_of;
+use tinyvec::{TinyVec, tiny_vec};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "smallvec_spilling",
+        "SmallVec: inline until full, then spilled to the heap",
+        smallvec_spilling,
+    )?;
+
+    section(
+        out,
+        "arrayvec_capacity_limits",
+        "ArrayVec and ArrayString: fixed capacity, fallible pushes",
+        arrayvec_capacity_limits,
+    )?;
+
+    section(
+        out,
+        "tinyvec_examples",
+        "tinyvec: the same ideas in safe code only",
+        tinyvec_examples,
+    )?;
+
+    section(
+        out,
+        "memory_footprint",
+        "How big each vector type is, before any heap allocation",
+        memory_footprint,
+    )?;
+
+    section(
+        out,
+        "when_inline_storage_wins",
+        "Choosing N from the length distribution of your data",
+        when_inline_storage_wins,
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates SmallVec's spill behavior.
+///
+/// Up to N elements live inline. Pushing element N+1 moves everything to a
+/// heap buffer - from then on it behaves like a Vec. Shrinking back to N or
+/// fewer elements and calling shrink_to_fit() moves the data inline again.
+pub fn smallvec_spilling(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "SmallVec Spilling")?;
+
+    let mut v: SmallVec<[u32; 4]> = SmallVec::new();
+    writeln!(out, "inline_size(): {}", v.inline_size())?;
+
+    for i in 1..=6 {
+        v.push(i * 10);
+        writeln!(
+            out,
+            "  push({:>2}) -> len {}, capacity {}, spilled: {}",
+            i * 10,
+            v.len(),
+            v.capacity(),
+            v.spilled()
+        )?;
+    }
+
+    // Back under the inline limit: shrink_to_fit() un-spills
+    v.truncate(3);
+    writeln!(out, "\nAfter truncate(3): spilled {}", v.spilled())?;
+    v.shrink_to_fit();
+    writeln!(out, "After shrink_to_fit(): spilled {}", v.spilled())?;
+
+    // The smallvec! macro works like vec!
+    let letters: SmallVec<[char; 8]> = smallvec!['a', 'b', 'c'];
+    writeln!(
+        out,
+        "\nsmallvec!['a', 'b', 'c'] with N = 8: {:?}, spilled {}",
+        letters,
+        letters.spilled()
+    )?;
+
+    // Derefs to a slice, so slice methods work unchanged
+    writeln!(out, "contains(&'b'): {}", letters.contains(&'b'))?;
+
+    Ok(())
+}
+
+/// Demonstrates ArrayVec's hard capacity limit.
+///
+/// An ArrayVec never allocates. `push` panics when it is full, so code that
+/// can't prove the bound uses `try_push`, which hands the element back
+/// inside a CapacityError.
+pub fn arrayvec_capacity_limits(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "ArrayVec Capacity Limits")?;
+
+    let mut recent: ArrayVec<u32, 3> = ArrayVec::new();
+    for reading in [17, 21, 19, 23] {
+        match recent.try_push(reading) {
+            Ok(()) => writeln!(
+                out,
+                "  try_push({}) ok, {} slot(s) left",
+                reading,
+                recent.remaining_capacity()
+            )?,
+            Err(err) => writeln!(
+                out,
+                "  try_push({}) failed: {} (element {} returned)",
+                reading,
+                err,
+                err.element()
+            )?,
+        }
+    }
+    writeln!(
+        out,
+        "is_full(): {}, contents {:?}",
+        recent.is_full(),
+        recent
+    )?;
+
+    // A sliding window: make room first, then push can't fail
+    if recent.is_full() {
+        recent.remove(0);
+    }
+    recent.push(23);
+    writeln!(out, "Window after dropping the oldest: {:?}", recent)?;
+
+    // Collecting more items than fit panics; take() keeps it in bounds
+    let firsts: ArrayVec<u32, 3> = (1..=10).take(3).collect();
+    writeln!(out, "\n(1..=10).take(3).collect(): {:?}", firsts)?;
+
+    // ArrayString: a fixed-capacity String, capacity counted in bytes
+    let mut code: ArrayString<8> = ArrayString::new();
+    code.push_str("RUST");
+    let fits: bool = code.try_push_str("-2024").is_ok();
+    writeln!(
+        out,
+        "\nArrayString<8> \"{}\" + \"-2024\" fits: {}",
+        code, fits
+    )?;
+    let fits: bool = code.try_push_str("-24").is_ok();
+    writeln!(
+        out,
+        "ArrayString<8> + \"-24\" fits: {} -> \"{}\"",
+        fits, code
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates tinyvec's ArrayVec and TinyVec.
+///
+/// tinyvec contains no unsafe code. The price is the `T: Default` bound:
+/// unused slots hold `T::default()` instead of uninitialized memory.
+pub fn tinyvec_examples(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "tinyvec")?;
+
+    // tinyvec::ArrayVec takes the backing array type, like SmallVec does
+    let mut fixed: tinyvec::ArrayVec<[u8; 4]> = tinyvec::ArrayVec::new();
+    fixed.extend([1, 2, 3]);
+    writeln!(
+        out,
+        "ArrayVec<[u8; 4]>: {:?}, capacity {}",
+        fixed,
+        fixed.capacity()
+    )?;
+    // try_push returns the element back instead of panicking
+    fixed.push(4);
+    writeln!(out, "try_push(5) when full: {:?}", fixed.try_push(5))?;
+
+    // TinyVec: Inline(ArrayVec) until it outgrows it, then Heap(Vec)
+    let mut v: TinyVec<[u8; 4]> = tiny_vec![1, 2, 3, 4];
+    writeln!(out, "\ntiny_vec![1, 2, 3, 4] is_inline: {}", v.is_inline())?;
+    v.push(5);
+    writeln!(out, "After push(5) is_inline: {}", v.is_inline())?;
+
+    // It is a plain enum, so you can match on where the data lives
+    match &v {
+        TinyVec::Inline(array) => writeln!(out, "Inline with {} elements", array.len())?,
+        TinyVec::Heap(vec) => writeln!(out, "Heap Vec with {} elements", vec.len())?,
+    }
+
+    // Like SmallVec, shrink_to_fit() moves short data back inline
+    v.truncate(2);
+    v.shrink_to_fit();
+    writeln!(
+        out,
+        "After truncate(2) + shrink_to_fit(): is_inline {}",
+        v.is_inline()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the size of each type itself, not counting heap buffers.
+///
+/// An inline vector is as big as its buffer plus bookkeeping. That size is
+/// paid for every value - a struct holding a SmallVec<[u64; 32]> is over
+/// 256 bytes even when empty.
+pub fn memory_footprint(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Memory Footprint (size_of, 64-bit)")?;
+
+    let sizes: [(&str, usize); 7] = [
+        ("Vec<u32>", size_of::<Vec<u32>>()),
+        ("SmallVec<[u32; 4]>", size_of::<SmallVec<[u32; 4]>>()),
+        ("SmallVec<[u32; 16]>", size_of::<SmallVec<[u32; 16]>>()),
+        ("arrayvec::ArrayVec<u32, 4>", size_of::<ArrayVec<u32, 4>>()),
+        (
+            "tinyvec::ArrayVec<[u32; 4]>",
+            size_of::<tinyvec::ArrayVec<[u32; 4]>>(),
+        ),
+        ("TinyVec<[u32; 4]>", size_of::<TinyVec<[u32; 4]>>()),
+        ("ArrayString<16>", size_of::<ArrayString<16>>()),
+    ];
+    for (name, size) in sizes {
+        writeln!(out, "  {:<28} {:>3} bytes", name, size)?;
+    }
+
+    // SmallVec<[u32; 4]> is only 8 bytes bigger than Vec<u32>: once spilled,
+    // the inline buffer's space holds the heap pointer and capacity instead.
+    // ArrayVec has no heap fallback, so it is just the array plus a length.
+
+    Ok(())
+}
+
+/// Demonstrates picking the inline capacity from real length data.
+///
+/// Inline storage only pays off if most values fit. Counting how many
+/// lists of a realistic workload would spill at each N shows the point of
+/// diminishing returns.
+pub fn when_inline_storage_wins(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "When Inline Storage Wins")?;
+
+    // Tokens per line of a small config file: mostly short, a few long
+    let text: &str = "\
+host = localhost
+port = 8080
+tags = web api internal
+log level = debug
+allowed origins = a.example b.example c.example d.example e.example
+retries = 3";
+    let lengths: Vec<usize> = text
+        .lines()
+        .map(|line| line.split_whitespace().count())
+        .collect();
+    writeln!(out, "Tokens per line: {:?}", lengths)?;
+
+    for n in [2, 4, 8] {
+        let inline: usize = lengths.iter().filter(|&&len| len <= n).count();
+        writeln!(
+            out,
+            "  N = {}: {} of {} lines stay inline ({}%)",
+            n,
+            inline,
+            lengths.len(),
+            inline * 100 / lengths.len()
+        )?;
+    }
+
+    // N = 4 keeps most lines allocation-free; the outlier just spills.
+    let tokens: Vec<SmallVec<[&str; 4]>> = text
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    let spilled: usize = tokens.iter().filter(|t| t.spilled()).count();
+    writeln!(
+        out,
+        "\nSmallVec<[&str; 4]> per line: {} heap allocation(s) instead of {}",
+        spilled,
+        tokens.len()
+    )?;
+
+    Ok(())
+}
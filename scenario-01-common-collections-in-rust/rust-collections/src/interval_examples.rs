@@ -0,0 +1,725 @@
+// An interval map stores values under half-open ranges [start, end) and
+// answers two questions:
+//
+//   stab(x):            which intervals contain the point x?
+//   overlapping(a..b):  which intervals share at least one point with a..b?
+//
+// Two implementations:
+//
+//   BTreeIntervalMap: BTreeMap<start, Vec<(end, V)>>
+//
+//     Sorting by start cuts the candidates to "starts before the query
+//     ends", but any of those could still reach into the query, so each
+//     one has to be checked: O(log n + intervals starting before b).
+//
+//   IntervalTree: an AVL tree ordered by start, where every node also
+//   records the largest end in its subtree (the augmentation):
+//
+//                       [20, 36) max 99
+//                    /                  \
+//          [5, 10) max 30            [50, 55) max 99
+//          /          \              /          \
+//   [3, 4) max 4  [12, 30) max 30  [40, 99) max 99  [60, 62) max 62
+//
+//     A subtree whose max end is at or before the query's start holds no
+//     match and is skipped whole; right subtrees are skipped once starts
+//     pass the query's end. Queries are O(log n + k) for k matches.
+//
+// Half-open intervals make adjacent ones ([9, 10) and [10, 11)) touch
+// without overlapping - the convention a calendar wants.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::ops::{Bound, Range};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "interval_basics",
+        "Half-open intervals, stabbing queries and overlap queries",
+        interval_basics,
+    )?;
+
+    section(
+        out,
+        "btreemap_interval_map",
+        "An interval map on BTreeMap: sorted starts, checked ends",
+        btreemap_interval_map,
+    )?;
+
+    section(
+        out,
+        "augmented_tree",
+        "An interval tree: each node knows the largest end below it",
+        augmented_tree,
+    )?;
+
+    section(
+        out,
+        "query_cost",
+        "Intervals examined per query: BTreeMap scan vs tree pruning",
+        query_cost,
+    )?;
+
+    section(
+        out,
+        "practical_calendar_conflicts",
+        "Practical demo: detecting calendar conflicts",
+        practical_calendar_conflicts,
+    )?;
+
+    Ok(())
+}
+
+fn check<K: Ord>(interval: &Range<K>) {
+    assert!(
+        interval.start < interval.end,
+        "an interval must start before it ends"
+    );
+}
+
+/// Whether an interval starting at `start` can reach a query whose
+/// intervals must start within `upper`.
+fn starts_in<K: Ord>(start: &K, upper: &Bound<K>) -> bool {
+    match upper {
+        Bound::Included(limit) => start <= limit,
+        Bound::Excluded(limit) => start < limit,
+        Bound::Unbounded => true,
+    }
+}
+
+/// An interval map on a BTreeMap keyed by start. Several intervals may
+/// share a start; they are kept in insertion order.
+#[derive(Clone, Debug)]
+pub struct BTreeIntervalMap<K, V> {
+    by_start: BTreeMap<K, Vec<(K, V)>>,
+    len: usize,
+}
+
+impl<K: Ord + Copy, V> BTreeIntervalMap<K, V> {
+    pub fn new() -> Self {
+        BTreeIntervalMap {
+            by_start: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Panics if `interval` is empty.
+    pub fn insert(&mut self, interval: Range<K>, value: V) {
+        check(&interval);
+        self.by_start
+            .entry(interval.start)
+            .or_default()
+            .push((interval.end, value));
+        self.len += 1;
+    }
+
+    /// Removes one interval with exactly these bounds.
+    pub fn remove(&mut self, interval: &Range<K>) -> Option<V> {
+        let ends: &mut Vec<(K, V)> = self.by_start.get_mut(&interval.start)?;
+        let position: usize = ends.iter().position(|(end, _)| *end == interval.end)?;
+        let (_, value) = ends.remove(position);
+        if ends.is_empty() {
+            self.by_start.remove(&interval.start);
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Every interval containing `point`, by start.
+    pub fn stab(&self, point: K) -> Vec<(Range<K>, &V)> {
+        self.query(Bound::Included(point), point)
+    }
+
+    /// Every interval sharing a point with `range`, by start.
+    pub fn overlapping(&self, range: &Range<K>) -> Vec<(Range<K>, &V)> {
+        self.query(Bound::Excluded(range.end), range.start)
+    }
+
+    /// How many intervals an overlap query looks at, matching or not.
+    pub fn overlap_query_cost(&self, range: &Range<K>) -> usize {
+        self.by_start
+            .range((Bound::Unbounded, Bound::Excluded(range.end)))
+            .map(|(_, ends)| ends.len())
+            .sum()
+    }
+
+    /// All intervals, by start.
+    pub fn iter(&self) -> impl Iterator<Item = (Range<K>, &V)> {
+        self.by_start
+            .iter()
+            .flat_map(|(&start, ends)| ends.iter().map(move |(end, value)| (start..*end, value)))
+    }
+
+    /// Intervals starting within `upper` and ending after `ends_after`.
+    fn query(&self, upper: Bound<K>, ends_after: K) -> Vec<(Range<K>, &V)> {
+        self.by_start
+            .range((Bound::Unbounded, upper))
+            .flat_map(|(&start, ends)| {
+                ends.iter()
+                    .filter(move |(end, _)| *end > ends_after)
+                    .map(move |(end, value)| (start..*end, value))
+            })
+            .collect()
+    }
+}
+
+impl<K: Ord + Copy, V> Default for BTreeIntervalMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Node<K, V> {
+    interval: Range<K>,
+    value: V,
+    /// The largest end anywhere in this subtree.
+    max_end: K,
+    height: u32,
+    left: Option<Box<Node<K, V>>>,
+    right: Option<Box<Node<K, V>>>,
+}
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+fn height<K, V>(link: &Link<K, V>) -> u32 {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+impl<K: Ord + Copy, V> Node<K, V> {
+    fn new(interval: Range<K>, value: V) -> Box<Self> {
+        Box::new(Node {
+            max_end: interval.end,
+            interval,
+            value,
+            height: 1,
+            left: None,
+            right: None,
+        })
+    }
+
+    fn key(&self) -> (K, K) {
+        (self.interval.start, self.interval.end)
+    }
+
+    /// Recomputes height and max_end from the children.
+    fn update(&mut self) {
+        self.height = 1 + height(&self.left).max(height(&self.right));
+        self.max_end = [&self.left, &self.right]
+            .into_iter()
+            .flatten()
+            .map(|child| child.max_end)
+            .fold(self.interval.end, K::max);
+    }
+
+    fn balance(&self) -> i64 {
+        height(&self.left) as i64 - height(&self.right) as i64
+    }
+}
+
+fn rotate_right<K: Ord + Copy, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut left: Box<Node<K, V>> = node.left.take().expect("rotating right needs a left child");
+    node.left = left.right.take();
+    node.update();
+    left.right = Some(node);
+    left.update();
+    left
+}
+
+fn rotate_left<K: Ord + Copy, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut right: Box<Node<K, V>> = node
+        .right
+        .take()
+        .expect("rotating left needs a right child");
+    node.right = right.left.take();
+    node.update();
+    right.left = Some(node);
+    right.update();
+    right
+}
+
+/// Restores the AVL property (child heights differ by at most one) at
+/// `node`, whose subtrees are already balanced.
+fn rebalance<K: Ord + Copy, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    node.update();
+    if node.balance() > 1 {
+        if node.left.as_ref().is_some_and(|left| left.balance() < 0) {
+            node.left = node.left.take().map(rotate_left);
+        }
+        return rotate_right(node);
+    }
+    if node.balance() < -1 {
+        if node.right.as_ref().is_some_and(|right| right.balance() > 0) {
+            node.right = node.right.take().map(rotate_right);
+        }
+        return rotate_left(node);
+    }
+    node
+}
+
+fn insert_at<K: Ord + Copy, V>(link: Link<K, V>, interval: Range<K>, value: V) -> Box<Node<K, V>> {
+    let Some(mut node) = link else {
+        return Node::new(interval, value);
+    };
+    // Equal intervals go right, so duplicates keep insertion order
+    if (interval.start, interval.end) < node.key() {
+        node.left = Some(insert_at(node.left.take(), interval, value));
+    } else {
+        node.right = Some(insert_at(node.right.take(), interval, value));
+    }
+    rebalance(node)
+}
+
+/// Detaches the leftmost node of a subtree; returns the rest and the node.
+fn remove_min<K: Ord + Copy, V>(mut node: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+    match node.left.take() {
+        None => (node.right.take(), node),
+        Some(left) => {
+            let (rest, min) = remove_min(left);
+            node.left = rest;
+            (Some(rebalance(node)), min)
+        }
+    }
+}
+
+fn remove_at<K: Ord + Copy, V>(link: Link<K, V>, key: (K, K)) -> (Link<K, V>, Option<V>) {
+    let Some(mut node) = link else {
+        return (None, None);
+    };
+    match key.cmp(&node.key()) {
+        Ordering::Less => {
+            let (left, removed) = remove_at(node.left.take(), key);
+            node.left = left;
+            (Some(rebalance(node)), removed)
+        }
+        Ordering::Greater => {
+            let (right, removed) = remove_at(node.right.take(), key);
+            node.right = right;
+            (Some(rebalance(node)), removed)
+        }
+        Ordering::Equal => {
+            let Node {
+                value, left, right, ..
+            } = *node;
+            let replacement: Link<K, V> = match (left, right) {
+                (None, None) => None,
+                (Some(child), None) | (None, Some(child)) => Some(child),
+                // The successor takes the removed node's place
+                (Some(left), Some(right)) => {
+                    let (rest, mut successor) = remove_min(right);
+                    successor.left = Some(left);
+                    successor.right = rest;
+                    Some(rebalance(successor))
+                }
+            };
+            (replacement, Some(value))
+        }
+    }
+}
+
+/// Collects matching intervals in order, counting the nodes it visits.
+fn query_at<'a, K: Ord + Copy, V>(
+    link: &'a Link<K, V>,
+    upper: &Bound<K>,
+    ends_after: K,
+    found: &mut Vec<(Range<K>, &'a V)>,
+    visited: &mut usize,
+) {
+    let Some(node) = link else {
+        return;
+    };
+    *visited += 1;
+    // Nothing in this subtree reaches past the query's start
+    if node.max_end <= ends_after {
+        return;
+    }
+    query_at(&node.left, upper, ends_after, found, visited);
+    if !starts_in(&node.interval.start, upper) {
+        // This node and everything to its right start too late
+        return;
+    }
+    if node.interval.end > ends_after {
+        found.push((node.interval.clone(), &node.value));
+    }
+    query_at(&node.right, upper, ends_after, found, visited);
+}
+
+/// An interval tree: an AVL tree of intervals ordered by (start, end), with
+/// each node's subtree max end for pruning.
+#[derive(Clone, Debug)]
+pub struct IntervalTree<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+impl<K: Ord + Copy, V> IntervalTree<K, V> {
+    pub fn new() -> Self {
+        IntervalTree { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Levels in the tree; at most about 1.44 log2(n) for an AVL tree.
+    pub fn height(&self) -> u32 {
+        height(&self.root)
+    }
+
+    /// Panics if `interval` is empty. O(log n).
+    pub fn insert(&mut self, interval: Range<K>, value: V) {
+        check(&interval);
+        self.root = Some(insert_at(self.root.take(), interval, value));
+        self.len += 1;
+    }
+
+    /// Removes one interval with exactly these bounds. O(log n).
+    pub fn remove(&mut self, interval: &Range<K>) -> Option<V> {
+        let (root, removed) = remove_at(self.root.take(), (interval.start, interval.end));
+        self.root = root;
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Every interval containing `point`, by start.
+    pub fn stab(&self, point: K) -> Vec<(Range<K>, &V)> {
+        self.query(Bound::Included(point), point).0
+    }
+
+    /// Every interval sharing a point with `range`, by start.
+    pub fn overlapping(&self, range: &Range<K>) -> Vec<(Range<K>, &V)> {
+        self.query(Bound::Excluded(range.end), range.start).0
+    }
+
+    /// How many nodes an overlap query visits, matching or not.
+    pub fn overlap_query_cost(&self, range: &Range<K>) -> usize {
+        self.query(Bound::Excluded(range.end), range.start).1
+    }
+
+    /// All intervals, by start.
+    pub fn iter(&self) -> impl Iterator<Item = (Range<K>, &V)> {
+        let mut stack: Vec<&Node<K, V>> = Vec::new();
+        let mut cursor: Option<&Node<K, V>> = self.root.as_deref();
+        std::iter::from_fn(move || {
+            while let Some(node) = cursor {
+                stack.push(node);
+                cursor = node.left.as_deref();
+            }
+            let node: &Node<K, V> = stack.pop()?;
+            cursor = node.right.as_deref();
+            Some((node.interval.clone(), &node.value))
+        })
+    }
+
+    fn query(&self, upper: Bound<K>, ends_after: K) -> (Vec<(Range<K>, &V)>, usize) {
+        let mut found: Vec<(Range<K>, &V)> = Vec::new();
+        let mut visited: usize = 0;
+        query_at(&self.root, &upper, ends_after, &mut found, &mut visited);
+        (found, visited)
+    }
+}
+
+impl<K: Ord + Copy, V> Default for IntervalTree<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Demonstrates what counts as containing and overlapping.
+pub fn interval_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Interval Basics")?;
+
+    let mut map: IntervalTree<u32, &str> = IntervalTree::new();
+    map.insert(9..12, "A");
+    map.insert(10..11, "B");
+    map.insert(12..14, "C");
+    map.insert(1..20, "D");
+    writeln!(out, "Intervals: {:?}", map.iter().collect::<Vec<_>>())?;
+
+    // Half-open: 12 is in C and D, but not in A, which ends at 12
+    writeln!(out, "stab(12): {:?}", map.stab(12))?;
+    writeln!(out, "stab(10): {:?}", map.stab(10))?;
+    writeln!(out, "stab(25): {:?}", map.stab(25))?;
+
+    writeln!(out, "overlapping(11..13): {:?}", map.overlapping(&(11..13)))?;
+    // Touching is not overlapping
+    writeln!(out, "overlapping(14..18): {:?}", map.overlapping(&(14..18)))?;
+
+    Ok(())
+}
+
+/// Demonstrates the BTreeMap version and where its cost comes from.
+pub fn btreemap_interval_map(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "BTreeMap Interval Map")?;
+
+    let mut map: BTreeIntervalMap<u32, &str> = BTreeIntervalMap::new();
+    map.insert(0..100, "long");
+    for (i, name) in ["a", "b", "c", "d", "e", "f"].into_iter().enumerate() {
+        let start: u32 = 10 * i as u32;
+        map.insert(start..start + 5, name);
+    }
+    writeln!(
+        out,
+        "Starts in order: {:?}",
+        map.by_start.keys().collect::<Vec<_>>()
+    )?;
+
+    // range(..=52) narrows to intervals starting by 52, then each end is checked
+    writeln!(out, "stab(52): {:?}", map.stab(52))?;
+    writeln!(
+        out,
+        "  examined {} of {} intervals to find 2",
+        map.overlap_query_cost(&(52..53)),
+        map.len()
+    )?;
+    writeln!(
+        out,
+        "\nA start says nothing about the end: \"long\" starts first"
+    )?;
+    writeln!(
+        out,
+        "but covers everything, so no early start can be skipped."
+    )?;
+
+    map.remove(&(0..100));
+    writeln!(out, "After remove(0..100): stab(52) = {:?}", map.stab(52))?;
+
+    Ok(())
+}
+
+/// Prints a tree sideways: right subtree above, left subtree below.
+fn print_tree(out: &mut dyn Write, link: &Link<u32, &str>, depth: usize) -> io::Result<()> {
+    if let Some(node) = link {
+        print_tree(out, &node.right, depth + 1)?;
+        writeln!(
+            out,
+            "{}[{}, {}) {} max_end={}",
+            "      ".repeat(depth),
+            node.interval.start,
+            node.interval.end,
+            node.value,
+            node.max_end
+        )?;
+        print_tree(out, &node.left, depth + 1)?;
+    }
+    Ok(())
+}
+
+/// Demonstrates the augmented tree and a pruned query.
+pub fn augmented_tree(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Augmented Interval Tree")?;
+
+    let mut tree: IntervalTree<u32, &str> = IntervalTree::new();
+    for (interval, name) in [
+        (20..36, "p"),
+        (5..10, "q"),
+        (40..99, "r"),
+        (3..4, "s"),
+        (12..30, "t"),
+        (50..55, "u"),
+        (60..62, "v"),
+    ] {
+        tree.insert(interval, name);
+    }
+    writeln!(out, "Sideways (root on the left, larger starts above):")?;
+    print_tree(out, &tree.root, 0)?;
+
+    // [3, 4) and [5, 10) end before 31; the subtree's max_end of 30 proves it
+    let query: Range<u32> = 31..45;
+    writeln!(out, "\noverlapping(31..45): {:?}", tree.overlapping(&query))?;
+    writeln!(
+        out,
+        "  visited {} of {} nodes: the left subtree's max_end=30 <= 31",
+        tree.overlap_query_cost(&query),
+        tree.len()
+    )?;
+
+    tree.remove(&(20..36));
+    writeln!(out, "\nAfter remove(20..36), height {}:", tree.height())?;
+    print_tree(out, &tree.root, 0)?;
+
+    Ok(())
+}
+
+/// Deterministic short intervals spread over a long timeline.
+pub fn random_intervals(count: usize, timeline: u32, max_len: u32, seed: u64) -> Vec<Range<u32>> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..count)
+        .map(|_| {
+            let start: u32 = rng.below(u64::from(timeline)) as u32;
+            start..start + 1 + rng.below(u64::from(max_len)) as u32
+        })
+        .collect()
+}
+
+/// Demonstrates how many intervals each structure examines per query.
+pub fn query_cost(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Query Cost")?;
+
+    let intervals: Vec<Range<u32>> = random_intervals(10_000, 1_000_000, 1_000, 42);
+    let mut map: BTreeIntervalMap<u32, usize> = BTreeIntervalMap::new();
+    let mut tree: IntervalTree<u32, usize> = IntervalTree::new();
+    for (id, interval) in intervals.iter().enumerate() {
+        map.insert(interval.clone(), id);
+        tree.insert(interval.clone(), id);
+    }
+    writeln!(
+        out,
+        "10,000 intervals up to 1,000 long over 0..1,000,000; tree height {}",
+        tree.height()
+    )?;
+
+    writeln!(
+        out,
+        "{:<18} {:>8} {:>14} {:>14}",
+        "Query", "matches", "BTreeMap looks", "tree visits"
+    )?;
+    for query in [1_000..2_000, 500_000..501_000, 990_000..991_000] {
+        writeln!(
+            out,
+            "{:<18} {:>8} {:>14} {:>14}",
+            format!("{:?}", query),
+            tree.overlapping(&query).len(),
+            map.overlap_query_cost(&query),
+            tree.overlap_query_cost(&query)
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\nThe BTreeMap looks at every interval that starts before"
+    )?;
+    writeln!(out, "the query ends; the tree's cost tracks the matches.")?;
+
+    Ok(())
+}
+
+/// Minutes since Monday 00:00.
+fn at(day: u32, hour: u32, minute: u32) -> u32 {
+    day * 24 * 60 + hour * 60 + minute
+}
+
+fn fmt_time(minutes: u32) -> String {
+    let days: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    format!(
+        "{} {:02}:{:02}",
+        days[(minutes / (24 * 60)) as usize],
+        minutes / 60 % 24,
+        minutes % 60
+    )
+}
+
+fn fmt_slot(slot: &Range<u32>) -> String {
+    format!(
+        "{}-{:02}:{:02}",
+        fmt_time(slot.start),
+        slot.end / 60 % 24,
+        slot.end % 60
+    )
+}
+
+/// Practical example: one interval tree per person.
+///
+/// A meeting conflicts with every existing meeting of any attendee that
+/// overlaps it - one overlap query per attendee.
+pub fn practical_calendar_conflicts(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Calendar Conflicts")?;
+
+    let mut calendars: HashMap<&str, IntervalTree<u32, &str>> = HashMap::new();
+    let meetings: [(&str, Range<u32>, &[&str]); 5] = [
+        (
+            "Standup",
+            at(1, 9, 0)..at(1, 9, 15),
+            &["alice", "bob", "carol"],
+        ),
+        ("Interview", at(1, 14, 30)..at(1, 15, 30), &["bob"]),
+        ("1:1", at(1, 11, 0)..at(1, 11, 30), &["alice", "carol"]),
+        ("Planning", at(2, 10, 0)..at(2, 12, 0), &["alice", "bob"]),
+        ("Lunch talk", at(2, 12, 0)..at(2, 13, 0), &["carol"]),
+    ];
+    for (title, slot, attendees) in &meetings {
+        for person in *attendees {
+            calendars
+                .entry(person)
+                .or_default()
+                .insert(slot.clone(), *title);
+        }
+    }
+    writeln!(out, "Booked {} meetings", meetings.len())?;
+
+    let requests: [(&str, Range<u32>, &[&str]); 3] = [
+        (
+            "Design review",
+            at(1, 14, 0)..at(1, 15, 0),
+            &["alice", "bob"],
+        ),
+        // Starts as Planning ends: touching, not overlapping
+        ("Retro", at(2, 12, 0)..at(2, 12, 45), &["alice", "bob"]),
+        (
+            "Offsite prep",
+            at(2, 11, 30)..at(2, 12, 30),
+            &["bob", "carol"],
+        ),
+    ];
+    for (title, slot, attendees) in requests {
+        writeln!(
+            out,
+            "\nRequest: {} {} for {:?}",
+            title,
+            fmt_slot(&slot),
+            attendees
+        )?;
+        let mut conflicts: Vec<String> = Vec::new();
+        for person in attendees {
+            if let Some(calendar) = calendars.get(person) {
+                for (busy, existing) in calendar.overlapping(&slot) {
+                    conflicts.push(format!("{} has {} {}", person, existing, fmt_slot(&busy)));
+                }
+            }
+        }
+        if conflicts.is_empty() {
+            writeln!(out, "  no conflicts, booked")?;
+            for person in attendees {
+                calendars
+                    .entry(person)
+                    .or_default()
+                    .insert(slot.clone(), title);
+            }
+        } else {
+            for conflict in conflicts {
+                writeln!(out, "  conflict: {}", conflict)?;
+            }
+        }
+    }
+
+    let now: u32 = at(2, 12, 15);
+    writeln!(out, "\nWho is busy at {}?", fmt_time(now))?;
+    for person in ["alice", "bob", "carol"] {
+        let busy: Vec<&str> = calendars[person]
+            .stab(now)
+            .into_iter()
+            .map(|(_, title)| *title)
+            .collect();
+        writeln!(out, "  {:<6} {:?}", person, busy)?;
+    }
+
+    Ok(())
+}
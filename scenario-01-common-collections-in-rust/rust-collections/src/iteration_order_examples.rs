@@ -0,0 +1,341 @@
+// The three maps hand back the same entries in three different orders:
+//
+//   HashMap    arbitrary: wherever the hashes put them, different for
+//              every map because each RandomState has its own keys
+//   BTreeMap   sorted by key
+//   IndexMap   insertion order, or any order sort_keys/sort_by leaves
+//
+// Code that only looks entries up never notices. Code that walks the map
+// and does something order-sensitive with what it sees does:
+//
+//   - Grouped processing ("a new region starts a new subtotal") does work
+//     at every group boundary, and the branch that detects a boundary is
+//     only predictable when equal groups arrive together
+//   - Serialized output compresses by finding repeats close together, so
+//     neighbouring lines that look alike compress better
+//   - f64 addition isn't associative, so a sum taken in hash order can
+//     come out differently for every map holding the same values
+//
+// Every example uses the same sales records. A key is a region in the high
+// 32 bits and a serial number in the low 32, so sorting by key also groups
+// by region. Records arrive with regions mixed, as they would from a
+// stream.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use indexmap::IndexMap;
+use miniz_oxide::deflate::compress_to_vec;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "three_orders",
+        "The same entries in hash, key and insertion order",
+        three_orders,
+    )?;
+
+    section(
+        out,
+        "grouped_processing",
+        "Subtotals per region: the number of groups follows iteration order",
+        grouped_processing,
+    )?;
+
+    section(
+        out,
+        "compression_ratio",
+        "Serialized output compresses better when similar lines sit together",
+        compression_ratio,
+    )?;
+
+    section(
+        out,
+        "float_sum_reproducibility",
+        "Summing f64 values in hash order: a different total per map",
+        float_sum_reproducibility,
+    )?;
+
+    Ok(())
+}
+
+/// Region names, indexed by the high 32 bits of a key.
+pub const REGIONS: [&str; 8] = [
+    "us-east", "us-west", "eu-west", "eu-north", "ap-south", "ap-east", "sa-east", "af-south",
+];
+
+/// The region index of a sale's key.
+pub fn region(key: u64) -> usize {
+    (key >> 32) as usize
+}
+
+/// `count` sales in arrival order: regions mixed at random, serial numbers
+/// ascending, and amounts from cents to billions.
+pub fn sales(count: usize, seed: u64) -> Vec<(u64, f64)> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..count as u64)
+        .map(|serial| {
+            let region: u64 = rng.below(REGIONS.len() as u64);
+            let cents: u64 = rng.below(100_000);
+            let scale: i32 = rng.below(8) as i32;
+            (
+                (region << 32) | serial,
+                cents as f64 / 100.0 * 10f64.powi(scale),
+            )
+        })
+        .collect()
+}
+
+/// Subtotals as `(region index, total)`, one per group.
+pub type Groups = Vec<(usize, f64)>;
+
+/// One subtotal per run of equal regions, in the order entries arrive.
+///
+/// Sorted input gives one subtotal per region. Mixed input starts a new
+/// one whenever the region changes.
+pub fn subtotals<'a>(entries: impl Iterator<Item = (&'a u64, &'a f64)>) -> Groups {
+    let mut groups: Groups = Vec::new();
+    for (&key, &amount) in entries {
+        match groups.last_mut() {
+            Some((region_index, total)) if *region_index == region(key) => *total += amount,
+            _ => groups.push((region(key), amount)),
+        }
+    }
+    groups
+}
+
+/// One `region/serial,amount` line per entry.
+pub fn serialize<'a>(entries: impl Iterator<Item = (&'a u64, &'a f64)>) -> String {
+    let mut text: String = String::new();
+    for (&key, &amount) in entries {
+        let _ = writeln!(
+            text,
+            "{}/{:06},{:.2}",
+            REGIONS[region(key)],
+            key & u32::MAX as u64,
+            amount
+        );
+    }
+    text
+}
+
+/// DEFLATE-compressed size of `text` at the default level.
+pub fn compressed_len(text: &str) -> usize {
+    compress_to_vec(text.as_bytes(), 6).len()
+}
+
+/// The quickest of five runs of `f`.
+fn quickest<T>(mut f: impl FnMut() -> T) -> (T, Duration) {
+    let mut best: Duration = Duration::MAX;
+    let mut result: Option<T> = None;
+    for _ in 0..5 {
+        let start: Instant = Instant::now();
+        let value: T = f();
+        best = best.min(start.elapsed());
+        result = Some(value);
+    }
+    (result.expect("ran at least once"), best)
+}
+
+/// Demonstrates that the same entries come back in a different order from
+/// each map, and from each HashMap.
+pub fn three_orders(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Three Orders")?;
+
+    let arrivals: Vec<(u64, f64)> = sales(8, 3);
+    let label = |key: &u64| format!("{}/{}", REGIONS[region(*key)], key & u32::MAX as u64);
+
+    let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+    let sorted: BTreeMap<u64, f64> = arrivals.iter().copied().collect();
+    let inserted: IndexMap<u64, f64> = arrivals.iter().copied().collect();
+    let hash_order: Vec<String> = hash.keys().map(label).collect();
+    let sorted_order: Vec<String> = sorted.keys().map(label).collect();
+    let inserted_order: Vec<String> = inserted.keys().map(label).collect();
+    writeln!(out, "Arrival order:  {:?}", inserted_order)?;
+    writeln!(out, "HashMap order:  {:?}", hash_order)?;
+    writeln!(out, "BTreeMap order: {:?}", sorted_order)?;
+    writeln!(out, "IndexMap order: {:?}", inserted_order)?;
+
+    // Each HashMap gets its own RandomState, so equal maps disagree on order
+    let arrivals: Vec<(u64, f64)> = sales(1_000, 3);
+    let first: HashMap<u64, f64> = arrivals.iter().copied().collect();
+    let second: HashMap<u64, f64> = arrivals.iter().copied().collect();
+    writeln!(
+        out,
+        "Two HashMaps with the same 1000 entries are equal: {}, iterate alike: {}",
+        first == second,
+        first.keys().eq(second.keys())
+    )?;
+    Ok(())
+}
+
+/// Demonstrates grouped processing: how many subtotals each order makes,
+/// and how long it takes.
+///
+/// Every order reaches the same per-region totals. Key order (BTreeMap, or
+/// an IndexMap after `sort_keys`) starts a group eight times. Hash order
+/// and arrival order start one for most entries, and the "same region as
+/// the last entry?" branch turns into a coin toss.
+pub fn grouped_processing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Grouped Processing")?;
+
+    let arrivals: Vec<(u64, f64)> = sales(200_000, 5);
+    let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+    let sorted: BTreeMap<u64, f64> = arrivals.iter().copied().collect();
+    let inserted: IndexMap<u64, f64> = arrivals.iter().copied().collect();
+    let mut key_sorted: IndexMap<u64, f64> = inserted.clone();
+    key_sorted.sort_keys();
+
+    writeln!(
+        out,
+        "{} sales over {} regions:",
+        arrivals.len(),
+        REGIONS.len()
+    )?;
+    let runs: [(&str, Groups, Duration); 4] = [
+        ("HashMap", quickest(|| subtotals(hash.iter()))),
+        ("BTreeMap", quickest(|| subtotals(sorted.iter()))),
+        (
+            "IndexMap (arrival)",
+            quickest(|| subtotals(inserted.iter())),
+        ),
+        (
+            "IndexMap (sort_keys)",
+            quickest(|| subtotals(key_sorted.iter())),
+        ),
+    ]
+    .map(|(name, (groups, elapsed))| (name, groups, elapsed));
+    for (name, groups, elapsed) in &runs {
+        writeln!(
+            out,
+            "  {:<21} {:>7} groups in {:?}",
+            name,
+            groups.len(),
+            elapsed
+        )?;
+    }
+
+    // Merge each order's groups back into one total per region
+    let per_region = |groups: &[(usize, f64)]| {
+        let mut totals: [f64; REGIONS.len()] = [0.0; REGIONS.len()];
+        for &(region_index, total) in groups {
+            totals[region_index] += total;
+        }
+        totals
+    };
+    let reference: [f64; REGIONS.len()] = per_region(&runs[1].1);
+    let agree: bool = runs.iter().all(|(_, groups, _)| {
+        per_region(groups)
+            .iter()
+            .zip(&reference)
+            .all(|(total, expected)| ((total - expected) / expected).abs() < 1e-9)
+    });
+    writeln!(out, "Per-region totals agree across orders: {}", agree)?;
+    Ok(())
+}
+
+/// Demonstrates that the same lines compress to different sizes depending
+/// on the order they are written in.
+///
+/// Sorted by key, consecutive lines share a region name and most digits of
+/// the serial number, and DEFLATE finds those repeats close by. In hash or
+/// arrival order the region changes on most lines and the serial numbers
+/// jump around.
+pub fn compression_ratio(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Compression Ratio")?;
+
+    let arrivals: Vec<(u64, f64)> = sales(20_000, 7);
+    let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+    let sorted: BTreeMap<u64, f64> = arrivals.iter().copied().collect();
+    let inserted: IndexMap<u64, f64> = arrivals.iter().copied().collect();
+
+    let texts: [(&str, String); 3] = [
+        ("HashMap", serialize(hash.iter())),
+        ("BTreeMap", serialize(sorted.iter())),
+        ("IndexMap (arrival)", serialize(inserted.iter())),
+    ];
+    writeln!(
+        out,
+        "{} sales, {} bytes serialized in any order",
+        arrivals.len(),
+        texts[1].1.len()
+    )?;
+    for (name, text) in &texts {
+        let compressed: usize = compressed_len(text);
+        writeln!(
+            out,
+            "  {:<19} {:>7} bytes compressed ({:.1}% of the original)",
+            name,
+            compressed,
+            compressed as f64 * 100.0 / text.len() as f64
+        )?;
+    }
+    Ok(())
+}
+
+/// Demonstrates that an f64 sum depends on the order of the additions, so
+/// summing a HashMap's values gives a total that changes from map to map.
+///
+/// Amounts run from cents to billions, so most additions round. Key order
+/// and arrival order are fixed, so each gives the same total on every run.
+/// Here the two happen to agree, which two different orders don't have
+/// to. Sorting the values first makes the result independent of the map.
+pub fn float_sum_reproducibility(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Float Sum Reproducibility")?;
+
+    let arrivals: Vec<(u64, f64)> = sales(10_000, 9);
+    let sorted: BTreeMap<u64, f64> = arrivals.iter().copied().collect();
+    let inserted: IndexMap<u64, f64> = arrivals.iter().copied().collect();
+    let key_order: f64 = sorted.values().sum();
+    let arrival_order: f64 = inserted.values().sum();
+    writeln!(
+        out,
+        "BTreeMap (key order):     {:.6} million",
+        key_order / 1e6
+    )?;
+    writeln!(
+        out,
+        "IndexMap (arrival order): {:.6} million",
+        arrival_order / 1e6
+    )?;
+    writeln!(
+        out,
+        "Same bits: {}, difference: {:.4}",
+        key_order.to_bits() == arrival_order.to_bits(),
+        (key_order - arrival_order).abs()
+    )?;
+
+    let hash_sums: Vec<f64> = (0..10)
+        .map(|_| {
+            let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+            hash.values().sum()
+        })
+        .collect();
+    let mut distinct: Vec<u64> = hash_sums.iter().map(|sum| sum.to_bits()).collect();
+    distinct.sort_unstable();
+    distinct.dedup();
+    writeln!(
+        out,
+        "10 HashMaps with the same entries: {} distinct totals",
+        distinct.len()
+    )?;
+
+    // Sorting the values fixes the order of the additions
+    let sorted_values = |values: &mut dyn Iterator<Item = &f64>| {
+        let mut values: Vec<f64> = values.copied().collect();
+        values.sort_unstable_by(f64::total_cmp);
+        values.iter().sum::<f64>()
+    };
+    let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+    let from_hash: f64 = sorted_values(&mut hash.values());
+    let from_btree: f64 = sorted_values(&mut sorted.values());
+    writeln!(
+        out,
+        "Values sorted before summing, HashMap and BTreeMap give the same bits: {}",
+        from_hash.to_bits() == from_btree.to_bits()
+    )?;
+    Ok(())
+}
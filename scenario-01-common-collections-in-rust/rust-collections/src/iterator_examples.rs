@@ -0,0 +1,620 @@
+// Every collection in this crate is consumed through the Iterator trait, and
+// most of the interesting work happens in the adapters between `iter()` and
+// the final `collect`/`sum`. This module walks through the ones that matter
+// for collections:
+//
+//   lazy           adapters only build a struct; nothing runs until a
+//                  consumer (for, collect, sum, next) pulls items, one item
+//                  through the whole chain at a time
+//   collect        the target type picks the FromIterator impl: Vec, String,
+//                  HashSet, BTreeMap, and Result<Vec<_>, E> that stops at
+//                  the first error
+//   fold / scan    fold keeps one accumulator and returns it; scan keeps
+//                  state but yields every step, and can stop early
+//   flat_map       one input item to zero or more outputs
+//   peekable       look at the next item without taking it; next_if takes
+//                  it only when it matches, which makes grouping runs easy
+//   by_ref         borrow an iterator so an adapter can consume part of it
+//                  and the rest stays usable
+//   size_hint      (lower, Some(upper)): collect reserves the lower bound up
+//                  front, so an exact hint means one allocation, and a
+//                  filter (lower bound 0) grows by doubling
+//
+// The Iterator_vs_Loop benchmarks check the usual claim that a chain costs
+// nothing over the equivalent index loop: both versions below compile to the
+// same kind of loop, and zip even drops the bounds checks the index loop
+// needs.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Write};
+use std::num::ParseIntError;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "lazy_evaluation",
+        "Adapters do nothing until a consumer pulls items",
+        lazy_evaluation,
+    )?;
+
+    section(
+        out,
+        "collect_targets",
+        "One iterator collected into Vec, String, HashSet, BTreeMap and Result",
+        collect_targets,
+    )?;
+
+    section(
+        out,
+        "fold_and_scan",
+        "fold for one result, scan for every intermediate state",
+        fold_and_scan,
+    )?;
+
+    section(
+        out,
+        "flat_map_and_flatten",
+        "Turning each item into zero or more items",
+        flat_map_and_flatten,
+    )?;
+
+    section(
+        out,
+        "peekable_grouping",
+        "Grouping runs with peekable and next_if",
+        peekable_grouping,
+    )?;
+
+    section(
+        out,
+        "by_ref_partial_consumption",
+        "Consuming part of an iterator with by_ref and keeping the rest",
+        by_ref_partial_consumption,
+    )?;
+
+    section(
+        out,
+        "size_hint_preallocation",
+        "How size_hint decides what collect allocates",
+        size_hint_preallocation,
+    )?;
+
+    section(
+        out,
+        "practical_order_report",
+        "Practical demo: parsing and totalling orders in iterator chains",
+        practical_order_report,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Building blocks
+// ---------------------------------------------------------------------------
+
+/// Collapses consecutive equal items into `(item, count)` pairs.
+pub fn run_lengths<T: PartialEq>(items: impl IntoIterator<Item = T>) -> Vec<(T, usize)> {
+    let mut items = items.into_iter().peekable();
+    let mut runs: Vec<(T, usize)> = Vec::new();
+    while let Some(item) = items.next() {
+        let mut count: usize = 1;
+        while items.next_if_eq(&item).is_some() {
+            count += 1;
+        }
+        runs.push((item, count));
+    }
+    runs
+}
+
+/// The balance after each transaction, stopping before the first one that
+/// would overdraw the account.
+pub fn running_balance(opening: i64, transactions: &[i64]) -> Vec<i64> {
+    transactions
+        .iter()
+        .scan(opening, |balance, &amount| {
+            *balance += amount;
+            (*balance >= 0).then_some(*balance)
+        })
+        .collect()
+}
+
+/// Splits a message into its `Name: value` headers, up to the first blank
+/// line, and the body lines after it.
+pub fn split_message(text: &str) -> (Vec<(&str, &str)>, Vec<&str>) {
+    let mut lines = text.lines();
+    // take_while consumes the blank line that stops it, which is exactly
+    // the separator we want to skip
+    let headers: Vec<(&str, &str)> = lines
+        .by_ref()
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(": "))
+        .collect();
+    let body: Vec<&str> = lines.collect();
+    (headers, body)
+}
+
+/// Hides the inner iterator's `size_hint`, as a hand-written iterator that
+/// doesn't override the default `(0, None)` would.
+pub struct NoHint<I>(pub I);
+
+impl<I: Iterator> Iterator for NoHint<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.0.next()
+    }
+}
+
+/// How many times collecting `items` into a Vec had to grow it, found by
+/// replaying `Vec::from_iter`'s strategy: reserve the lower bound of the
+/// hint, then grow whenever the vec is full.
+pub fn collect_growths<I: Iterator>(items: I) -> (usize, usize) {
+    let (lower, _) = items.size_hint();
+    let mut vec: Vec<I::Item> = Vec::with_capacity(lower);
+    let mut growths: usize = 0;
+    for item in items {
+        if vec.len() == vec.capacity() {
+            growths += 1;
+        }
+        vec.push(item);
+    }
+    (growths, vec.capacity())
+}
+
+// ---------------------------------------------------------------------------
+// Index loops vs iterator chains
+// ---------------------------------------------------------------------------
+
+/// Sum of the squares of the even values, as an index loop.
+#[allow(clippy::needless_range_loop)]
+pub fn sum_even_squares_indexed(values: &[u64]) -> u64 {
+    let mut sum: u64 = 0;
+    for i in 0..values.len() {
+        if values[i].is_multiple_of(2) {
+            sum = sum.wrapping_add(values[i].wrapping_mul(values[i]));
+        }
+    }
+    sum
+}
+
+/// Sum of the squares of the even values, as an iterator chain.
+pub fn sum_even_squares_iter(values: &[u64]) -> u64 {
+    values
+        .iter()
+        .filter(|&&x| x.is_multiple_of(2))
+        .fold(0u64, |sum, &x| sum.wrapping_add(x.wrapping_mul(x)))
+}
+
+/// Dot product with indices; every `b[i]` is bounds-checked unless the
+/// compiler proves `b` is long enough.
+#[allow(clippy::needless_range_loop)]
+pub fn dot_indexed(a: &[u32], b: &[u32]) -> u64 {
+    let mut sum: u64 = 0;
+    for i in 0..a.len() {
+        sum = sum.wrapping_add(u64::from(a[i]) * u64::from(b[i]));
+    }
+    sum
+}
+
+/// Dot product with zip, which stops at the shorter slice and needs no
+/// bounds checks.
+pub fn dot_zip(a: &[u32], b: &[u32]) -> u64 {
+    a.iter().zip(b).fold(0u64, |sum, (&x, &y)| {
+        sum.wrapping_add(u64::from(x) * u64::from(y))
+    })
+}
+
+/// `len` pseudo-random values below 1000. Fixed seed, so runs are
+/// repeatable.
+pub fn sample_values(len: usize, seed: u64) -> Vec<u64> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..len).map(|_| rng.below(1000)).collect()
+}
+
+// ---------------------------------------------------------------------------
+// Orders
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Order {
+    pub customer: String,
+    pub quantity: u32,
+    pub unit_cents: u64,
+}
+
+impl Order {
+    pub fn total_cents(&self) -> u64 {
+        u64::from(self.quantity) * self.unit_cents
+    }
+}
+
+pub const SAMPLE_ORDERS: &str = "\
+customer,quantity,unit_cents
+ada,3,250
+grace,1,1999
+ada,2,250
+linus,x,100
+grace,4,125
+ken,1
+ada,1,999";
+
+/// Parses `customer,quantity,unit_cents` lines after a header. Returns the
+/// orders and one message per line that didn't parse.
+pub fn parse_orders(csv: &str) -> (Vec<Order>, Vec<String>) {
+    let (orders, errors): (Vec<_>, Vec<_>) = csv
+        .lines()
+        .enumerate()
+        .skip(1)
+        .map(|(index, line)| parse_order(line).map_err(|e| format!("line {}: {e}", index + 1)))
+        .partition(Result::is_ok);
+    (
+        orders.into_iter().map(Result::unwrap).collect(),
+        errors.into_iter().map(Result::unwrap_err).collect(),
+    )
+}
+
+fn parse_order(line: &str) -> Result<Order, String> {
+    let fields: Vec<&str> = line.split(',').collect();
+    let [customer, quantity, unit_cents] = fields[..] else {
+        return Err(format!("expected 3 fields, found {}", fields.len()));
+    };
+    let numbers: Result<Vec<u64>, ParseIntError> =
+        [quantity, unit_cents].iter().map(|s| s.parse()).collect();
+    let numbers: Vec<u64> = numbers.map_err(|e| format!("{e}"))?;
+    Ok(Order {
+        customer: customer.to_string(),
+        quantity: u32::try_from(numbers[0]).map_err(|e| format!("{e}"))?,
+        unit_cents: numbers[1],
+    })
+}
+
+/// Per-customer `(orders, total cents)`, in customer order: sort, then
+/// group the runs with `peekable`.
+pub fn totals_by_customer(orders: &[Order]) -> Vec<(&str, usize, u64)> {
+    let mut sorted: Vec<&Order> = orders.iter().collect();
+    sorted.sort_by(|a, b| a.customer.cmp(&b.customer));
+
+    let mut sorted = sorted.into_iter().peekable();
+    let mut totals: Vec<(&str, usize, u64)> = Vec::new();
+    while let Some(first) = sorted.next() {
+        let (mut count, mut cents): (usize, u64) = (1, first.total_cents());
+        while let Some(next) = sorted.next_if(|order| order.customer == first.customer) {
+            count += 1;
+            cents += next.total_cents();
+        }
+        totals.push((&first.customer, count, cents));
+    }
+    totals
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// Demonstrates laziness: building a chain runs nothing, and consuming it
+/// pulls one item at a time through every adapter, only as far as needed.
+pub fn lazy_evaluation(out: &mut dyn Write) -> io::Result<()> {
+    let mut trace: Vec<String> = Vec::new();
+    let chain = (1..=10)
+        .inspect(|n| trace.push(format!("source {n}")))
+        .map(|n| n * n)
+        .filter(|square| square % 2 == 1)
+        .take(2);
+    // Nothing has run yet; `chain` is a struct of nested adapters
+    let size: usize = std::mem::size_of_val(&chain);
+    let squares: Vec<i32> = chain.collect();
+
+    writeln!(
+        out,
+        "\n  (1..=10).inspect(..).map(square).filter(odd).take(2) is a {size}-byte struct"
+    )?;
+    writeln!(out, "  Collecting it yields {squares:?} after pulling:")?;
+    writeln!(out, "    {}", trace.join(", "))?;
+    writeln!(
+        out,
+        "  take(2) stopped the source at 3; items 4 to 10 were never produced."
+    )?;
+
+    let first_big = (1u64..).map(|n| n.pow(3)).find(|cube| *cube > 1_000);
+    writeln!(
+        out,
+        "\n  Laziness makes infinite sources usable: (1..).map(cube).find(> 1000) = {first_big:?}"
+    )?;
+    Ok(())
+}
+
+/// Demonstrates how the target type picks what `collect` builds, including
+/// collecting `Result`s into a `Result` that stops at the first error.
+pub fn collect_targets(out: &mut dyn Write) -> io::Result<()> {
+    let words: [&str; 6] = ["pear", "fig", "apple", "fig", "kiwi", "apple"];
+
+    let lengths: Vec<usize> = words.iter().map(|w| w.len()).collect();
+    let initials: String = words.iter().filter_map(|w| w.chars().next()).collect();
+    let distinct: HashSet<&str> = words.iter().copied().collect();
+    let by_word: BTreeMap<&str, usize> = words.iter().map(|&w| (w, w.len())).collect();
+    let (short, long): (Vec<&str>, Vec<&str>) = words.iter().partition(|w| w.len() <= 4);
+
+    writeln!(out, "\n  words = {words:?}")?;
+    writeln!(out, "  Vec<usize>              {lengths:?}")?;
+    writeln!(out, "  String                  {initials:?}")?;
+    writeln!(
+        out,
+        "  HashSet<&str>           {} distinct words",
+        distinct.len()
+    )?;
+    writeln!(out, "  BTreeMap<&str, usize>   {by_word:?}")?;
+    writeln!(out, "  partition -> (Vec, Vec) {short:?} / {long:?}")?;
+
+    let good: Result<Vec<u32>, ParseIntError> =
+        ["4", "8", "15"].iter().map(|s| s.parse()).collect();
+    let mut parsed: usize = 0;
+    let bad: Result<Vec<u32>, ParseIntError> = ["16", "x", "42"]
+        .iter()
+        .inspect(|_| parsed += 1)
+        .map(|s| s.parse())
+        .collect();
+    writeln!(
+        out,
+        "\n  Result<Vec<u32>, _> from [\"4\", \"8\", \"15\"]:  {good:?}"
+    )?;
+    writeln!(
+        out,
+        "  Result<Vec<u32>, _> from [\"16\", \"x\", \"42\"]: {bad:?} after parsing {parsed} of 3"
+    )?;
+    let total: Option<u32> = [Some(1), None, Some(3)].into_iter().sum();
+    writeln!(
+        out,
+        "  Option works the same way: sum of [Some(1), None, Some(3)] = {total:?}"
+    )?;
+    Ok(())
+}
+
+/// Demonstrates `fold` for a single result and `scan` for a running state
+/// that yields every step and can end the iteration early.
+pub fn fold_and_scan(out: &mut dyn Write) -> io::Result<()> {
+    let readings: [i32; 7] = [12, -3, 40, 7, 7, -18, 25];
+    let (min, max, sum) = readings
+        .iter()
+        .fold((i32::MAX, i32::MIN, 0), |(min, max, sum), &r| {
+            (min.min(r), max.max(r), sum + r)
+        });
+    writeln!(out, "\n  readings = {readings:?}")?;
+    writeln!(
+        out,
+        "  fold into (min, max, sum) in one pass: ({min}, {max}, {sum})"
+    )?;
+
+    let prefix_sums: Vec<i32> = readings
+        .iter()
+        .scan(0, |total, &r| {
+            *total += r;
+            Some(*total)
+        })
+        .collect();
+    writeln!(
+        out,
+        "  scan into prefix sums:                {prefix_sums:?}"
+    )?;
+
+    let transactions: [i64; 6] = [-30, 20, -50, -60, 100, -10];
+    writeln!(
+        out,
+        "\n  Opening balance 100, transactions {transactions:?}"
+    )?;
+    writeln!(
+        out,
+        "  running_balance: {:?}",
+        running_balance(100, &transactions)
+    )?;
+    writeln!(
+        out,
+        "  scan returned None at -60 (it would overdraw), which ends the iterator;"
+    )?;
+    writeln!(out, "  the later +100 is never looked at.")?;
+    Ok(())
+}
+
+/// Demonstrates `flat_map` for one-to-many steps, and `flatten` over
+/// nested collections and `Option`s.
+pub fn flat_map_and_flatten(out: &mut dyn Write) -> io::Result<()> {
+    let lines: [&str; 3] = ["the quick fox", "", "jumps over"];
+    let words: Vec<&str> = lines
+        .iter()
+        .flat_map(|line| line.split_whitespace())
+        .collect();
+    writeln!(out, "\n  lines = {lines:?}")?;
+    writeln!(out, "  flat_map(split_whitespace) -> {words:?}")?;
+
+    let batches: Vec<Vec<u32>> = vec![vec![1, 2], vec![], vec![3], vec![4, 5, 6]];
+    let flat: Vec<u32> = batches.iter().flatten().copied().collect();
+    writeln!(out, "\n  {batches:?}.flatten() -> {flat:?}")?;
+
+    let ids: [&str; 5] = ["7", "", "12", "n/a", "3"];
+    let parsed: Vec<u32> = ids.iter().flat_map(|s| s.parse().ok()).collect();
+    writeln!(out, "  {ids:?} parsed, failures dropped: {parsed:?}")?;
+    writeln!(
+        out,
+        "  (Option is an iterator of zero or one item; filter_map(|s| s.parse().ok()) is the same)"
+    )?;
+
+    let pairs: Vec<(char, u32)> = ['a', 'b']
+        .iter()
+        .flat_map(|&c| (1..=3).map(move |n| (c, n)))
+        .collect();
+    writeln!(out, "\n  A nested loop as one chain: {pairs:?}")?;
+    Ok(())
+}
+
+/// Demonstrates `peekable`: looking ahead without consuming, and `next_if`
+/// for taking the next item only when it belongs to the current run.
+pub fn peekable_grouping(out: &mut dyn Write) -> io::Result<()> {
+    let text: &str = "aaabccddddde";
+    let runs: Vec<(char, usize)> = run_lengths(text.chars());
+    let encoded: String = runs.iter().map(|(c, n)| format!("{n}{c}")).collect();
+    writeln!(out, "\n  run_lengths({text:?}) = {encoded}")?;
+
+    let source: &str = "x1 = 42 + 7";
+    let mut chars = source.chars().peekable();
+    let mut tokens: Vec<String> = Vec::new();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphanumeric() {
+            let mut token: String = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric()) {
+                token.push(c);
+            }
+            tokens.push(token);
+        } else {
+            tokens.push(chars.next().map(String::from).unwrap_or_default());
+        }
+    }
+    writeln!(out, "  Tokens of {source:?}: {tokens:?}")?;
+    writeln!(
+        out,
+        "\n  peek() looks at the next char; next_if takes it only if it continues the token,"
+    )?;
+    writeln!(
+        out,
+        "  so the char that ends a token is still there for the next round."
+    )?;
+    Ok(())
+}
+
+/// Demonstrates `by_ref`: an adapter consumes part of an iterator through a
+/// borrow, and the rest stays available. Also the `take_while` catch.
+pub fn by_ref_partial_consumption(out: &mut dyn Write) -> io::Result<()> {
+    let message: &str = "From: ada\nSubject: report\n\nNumbers look good.\nShip it.";
+    let (headers, body) = split_message(message);
+    writeln!(out, "\n  split_message:")?;
+    writeln!(out, "    headers = {headers:?}")?;
+    writeln!(out, "    body    = {body:?}")?;
+
+    let mut numbers = 1..=8;
+    let first_three: Vec<i32> = numbers.by_ref().take(3).collect();
+    let rest: Vec<i32> = numbers.collect();
+    writeln!(
+        out,
+        "\n  (1..=8): by_ref().take(3) = {first_three:?}, then the rest = {rest:?}"
+    )?;
+
+    let mut numbers = 1..=8;
+    let small: Vec<i32> = numbers.by_ref().take_while(|&n| n < 4).collect();
+    let rest: Vec<i32> = numbers.collect();
+    writeln!(
+        out,
+        "  (1..=8): by_ref().take_while(< 4) = {small:?}, then the rest = {rest:?}"
+    )?;
+    writeln!(
+        out,
+        "  take_while had to pull 4 to see it fail, and 4 is gone. When that item"
+    )?;
+    writeln!(
+        out,
+        "  matters, use peekable and next_if, which leave it in place."
+    )?;
+    Ok(())
+}
+
+/// Demonstrates `size_hint` and what `collect` does with it: an exact hint
+/// means one allocation of the right size, no hint means growing by
+/// doubling.
+pub fn size_hint_preallocation(out: &mut dyn Write) -> io::Result<()> {
+    let values: Vec<u32> = (0..1_000).collect();
+    writeln!(out, "\n  size_hint of chains over a 1000-element Vec:")?;
+    let hints: [(&str, (usize, Option<usize>)); 6] = [
+        ("iter()", values.iter().size_hint()),
+        ("iter().map(..)", values.iter().map(|x| x * 2).size_hint()),
+        (
+            "iter().filter(..)",
+            values.iter().filter(|x| *x % 3 == 0).size_hint(),
+        ),
+        ("iter().take(10)", values.iter().take(10).size_hint()),
+        (
+            "iter().chain(iter())",
+            values.iter().chain(values.iter()).size_hint(),
+        ),
+        (
+            "iter().flat_map(..)",
+            values.iter().flat_map(|&x| 0..x % 3).size_hint(),
+        ),
+    ];
+    for (chain, hint) in hints {
+        writeln!(out, "    {chain:<22} {hint:?}")?;
+    }
+
+    writeln!(
+        out,
+        "\n  Collecting 1000 items (times the Vec grew, final capacity):"
+    )?;
+    writeln!(
+        out,
+        "    exact hint (map)       {:?}",
+        collect_growths(values.iter().map(|x| x * 2))
+    )?;
+    writeln!(
+        out,
+        "    no hint (NoHint)       {:?}",
+        collect_growths(NoHint(values.iter().map(|x| x * 2)))
+    )?;
+    writeln!(
+        out,
+        "    filter, keeps 334      {:?}",
+        collect_growths(values.iter().filter(|x| *x % 3 == 0))
+    )?;
+
+    let collected: Vec<u32> = values.iter().map(|x| x * 2).collect();
+    writeln!(
+        out,
+        "\n  Vec::from_iter agrees: the mapped collect has len {} and capacity {}.",
+        collected.len(),
+        collected.capacity()
+    )?;
+    writeln!(
+        out,
+        "  A hand-written iterator should override size_hint (and implement"
+    )?;
+    writeln!(
+        out,
+        "  ExactSizeIterator when it knows its length) so collect can do the same."
+    )?;
+    Ok(())
+}
+
+/// Practical example: parse order lines into orders and error messages with
+/// `partition`, then total them per customer with a sort and `peekable`.
+pub fn practical_order_report(out: &mut dyn Write) -> io::Result<()> {
+    let (orders, errors) = parse_orders(SAMPLE_ORDERS);
+    writeln!(
+        out,
+        "\n  {} data lines: {} orders, {} rejected",
+        SAMPLE_ORDERS.lines().count() - 1,
+        orders.len(),
+        errors.len()
+    )?;
+    for error in &errors {
+        writeln!(out, "    {error}")?;
+    }
+
+    writeln!(out, "\n  {:<8} {:>6} {:>10}", "customer", "orders", "total")?;
+    for (customer, count, cents) in totals_by_customer(&orders) {
+        writeln!(
+            out,
+            "  {customer:<8} {count:>6} {:>10}",
+            format!("${}.{:02}", cents / 100, cents % 100)
+        )?;
+    }
+
+    let biggest: Option<&Order> = orders.iter().max_by_key(|order| order.total_cents());
+    if let Some(order) = biggest {
+        writeln!(
+            out,
+            "\n  Largest order: {} x {} cents for {}",
+            order.quantity, order.unit_cents, order.customer
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,318 @@
+//! Examples for every collection type in Rust's standard library.
+//!
+//! Each module exposes its demos as `pub fn name(out: &mut dyn Write)` plus a
+//! `run_all` that prints all of them in order. [`MODULES`] registers them
+//! with the shared demo CLI; the snapshot tests run them against a buffer, and
+//! [`run_demo`] runs one of them for other programs.
+//! [`cheatsheet`] holds the complexity facts behind `cargo run -- cheatsheet`,
+//! [`heap_visual`] draws a `BinaryHeap`'s layout for the heap demos, and
+//! [`deque_visual`] models and draws a `VecDeque`'s ring buffer.
+//! [`custom_linked_list_examples`] builds a doubly linked list twice, with
+//! `Rc<RefCell>` and with raw pointers, to show what `LinkedList` costs.
+//! [`soak`] is the workload `cargo run -- soak` repeats to check that none
+//! of the structures leak; its `BTreeMap` runs operations from
+//! [`demo_framework::ops`], the language the property tests drive maps with.
+//!
+//! The `nightly` feature enables demos of unstable standard library APIs
+//! (the BTreeMap and LinkedList cursor APIs) and requires a nightly
+//! toolchain.
+
+#![cfg_attr(feature = "nightly", feature(btree_cursors, linked_list_cursors))]
+
+pub mod arena_examples;
+pub mod bimap_examples;
+pub mod binaryheap_examples;
+pub mod bitset_examples;
+pub mod bounded_queue_examples;
+pub mod bst_examples;
+pub mod btree_visual;
+pub mod btreemap_examples;
+pub mod cache_policies_examples;
+pub mod cheatsheet;
+pub mod collation_examples;
+pub mod columnar_examples;
+pub mod composite_keys_examples;
+pub mod cow_examples;
+pub mod custom_linked_list_examples;
+pub mod deque_visual;
+pub mod entry_examples;
+pub mod entry_patterns_examples;
+pub mod enum_map_examples;
+pub mod equivalent_keys_examples;
+pub mod extract_if_examples;
+pub mod float_sum_examples;
+pub mod graph_examples;
+pub mod grid_examples;
+pub mod hashmap_examples;
+pub mod heap_alternatives_examples;
+pub mod heap_visual;
+pub mod heapless_examples;
+pub mod indexed_heap_examples;
+pub mod indexmap_examples;
+pub mod inline_vec_examples;
+pub mod interval_examples;
+pub mod iteration_order_examples;
+pub mod iterator_examples;
+pub mod kdtree_examples;
+pub mod linked_list_examples;
+pub mod lru_examples;
+pub mod map_diff_examples;
+pub mod map_view_examples;
+pub mod matrix_examples;
+pub mod merge_patch_examples;
+pub mod multimap_examples;
+pub mod multiset_examples;
+pub mod nested_map_examples;
+pub mod object_pool_examples;
+pub mod persistent_examples;
+pub mod ring_buffer_examples;
+pub mod saturating_counter_examples;
+pub mod set_examples;
+pub mod shared_ownership_examples;
+pub mod shortest_path_examples;
+pub mod simulation_examples;
+pub mod slotmap_examples;
+pub mod soak;
+pub mod spatial_examples;
+pub mod static_table_examples;
+pub mod streaming_stats_examples;
+pub mod union_find_examples;
+pub mod vec_examples;
+pub mod vecdeque_examples;
+
+use demo_framework::Module;
+use demo_framework::embed::{self, DemoOutput, UnknownDemo};
+
+/// Demo registry, in the order the scenario's README introduces them.
+pub const MODULES: &[Module] = &[
+    Module {
+        name: "vec_examples",
+        run: vec_examples::run_all,
+    },
+    Module {
+        name: "inline_vec_examples",
+        run: inline_vec_examples::run_all,
+    },
+    Module {
+        name: "grid_examples",
+        run: grid_examples::run_all,
+    },
+    Module {
+        name: "matrix_examples",
+        run: matrix_examples::run_all,
+    },
+    Module {
+        name: "spatial_examples",
+        run: spatial_examples::run_all,
+    },
+    Module {
+        name: "kdtree_examples",
+        run: kdtree_examples::run_all,
+    },
+    Module {
+        name: "static_table_examples",
+        run: static_table_examples::run_all,
+    },
+    Module {
+        name: "iterator_examples",
+        run: iterator_examples::run_all,
+    },
+    Module {
+        name: "cow_examples",
+        run: cow_examples::run_all,
+    },
+    Module {
+        name: "vecdeque_examples",
+        run: vecdeque_examples::run_all,
+    },
+    Module {
+        name: "bounded_queue_examples",
+        run: bounded_queue_examples::run_all,
+    },
+    Module {
+        name: "ring_buffer_examples",
+        run: ring_buffer_examples::run_all,
+    },
+    Module {
+        name: "linked_list_examples",
+        run: linked_list_examples::run_all,
+    },
+    Module {
+        name: "custom_linked_list_examples",
+        run: custom_linked_list_examples::run_all,
+    },
+    Module {
+        name: "arena_examples",
+        run: arena_examples::run_all,
+    },
+    Module {
+        name: "hashmap_examples",
+        run: hashmap_examples::run_all,
+    },
+    Module {
+        name: "equivalent_keys_examples",
+        run: equivalent_keys_examples::run_all,
+    },
+    Module {
+        name: "entry_examples",
+        run: entry_examples::run_all,
+    },
+    Module {
+        name: "entry_patterns_examples",
+        run: entry_patterns_examples::run_all,
+    },
+    Module {
+        name: "extract_if_examples",
+        run: extract_if_examples::run_all,
+    },
+    Module {
+        name: "lru_examples",
+        run: lru_examples::run_all,
+    },
+    Module {
+        name: "cache_policies_examples",
+        run: cache_policies_examples::run_all,
+    },
+    Module {
+        name: "btreemap_examples",
+        run: btreemap_examples::run_all,
+    },
+    Module {
+        name: "btree_visual",
+        run: btree_visual::run_all,
+    },
+    Module {
+        name: "bst_examples",
+        run: bst_examples::run_all,
+    },
+    Module {
+        name: "map_view_examples",
+        run: map_view_examples::run_all,
+    },
+    Module {
+        name: "collation_examples",
+        run: collation_examples::run_all,
+    },
+    Module {
+        name: "nested_map_examples",
+        run: nested_map_examples::run_all,
+    },
+    Module {
+        name: "composite_keys_examples",
+        run: composite_keys_examples::run_all,
+    },
+    Module {
+        name: "map_diff_examples",
+        run: map_diff_examples::run_all,
+    },
+    Module {
+        name: "merge_patch_examples",
+        run: merge_patch_examples::run_all,
+    },
+    Module {
+        name: "interval_examples",
+        run: interval_examples::run_all,
+    },
+    Module {
+        name: "set_examples",
+        run: set_examples::run_all,
+    },
+    Module {
+        name: "binaryheap_examples",
+        run: binaryheap_examples::run_all,
+    },
+    Module {
+        name: "simulation_examples",
+        run: simulation_examples::run_all,
+    },
+    Module {
+        name: "streaming_stats_examples",
+        run: streaming_stats_examples::run_all,
+    },
+    Module {
+        name: "heap_alternatives_examples",
+        run: heap_alternatives_examples::run_all,
+    },
+    Module {
+        name: "indexed_heap_examples",
+        run: indexed_heap_examples::run_all,
+    },
+    Module {
+        name: "union_find_examples",
+        run: union_find_examples::run_all,
+    },
+    Module {
+        name: "graph_examples",
+        run: graph_examples::run_all,
+    },
+    Module {
+        name: "shortest_path_examples",
+        run: shortest_path_examples::run_all,
+    },
+    Module {
+        name: "indexmap_examples",
+        run: indexmap_examples::run_all,
+    },
+    Module {
+        name: "iteration_order_examples",
+        run: iteration_order_examples::run_all,
+    },
+    Module {
+        name: "columnar_examples",
+        run: columnar_examples::run_all,
+    },
+    Module {
+        name: "float_sum_examples",
+        run: float_sum_examples::run_all,
+    },
+    Module {
+        name: "slotmap_examples",
+        run: slotmap_examples::run_all,
+    },
+    Module {
+        name: "bitset_examples",
+        run: bitset_examples::run_all,
+    },
+    Module {
+        name: "enum_map_examples",
+        run: enum_map_examples::run_all,
+    },
+    Module {
+        name: "persistent_examples",
+        run: persistent_examples::run_all,
+    },
+    Module {
+        name: "multiset_examples",
+        run: multiset_examples::run_all,
+    },
+    Module {
+        name: "saturating_counter_examples",
+        run: saturating_counter_examples::run_all,
+    },
+    Module {
+        name: "bimap_examples",
+        run: bimap_examples::run_all,
+    },
+    Module {
+        name: "multimap_examples",
+        run: multimap_examples::run_all,
+    },
+    Module {
+        name: "object_pool_examples",
+        run: object_pool_examples::run_all,
+    },
+    Module {
+        name: "heapless_examples",
+        run: heapless_examples::run_all,
+    },
+    Module {
+        name: "shared_ownership_examples",
+        run: shared_ownership_examples::run_all,
+    },
+];
+
+/// Runs one module or demo by name and captures its text, timing and
+/// recorded values (see [`demo_framework::embed`]).
+pub fn run_demo(name: &str) -> Result<DemoOutput, UnknownDemo> {
+    embed::run_demo(MODULES, name)
+}
@@ -1,8 +1,64 @@
+use demo_framework::section;
 use std::collections::{LinkedList, VecDeque};
+use std::io::{self, Write};
 use std::time::{Duration, Instant};
 
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "basic_linked_list_operations",
+        "Basic LinkedList operations",
+        basic_linked_list_operations,
+    )?;
+
+    section(
+        out,
+        "append_and_split",
+        "LinkedList's strength: O(1) append and split",
+        append_and_split,
+    )?;
+
+    section(
+        out,
+        "linked_list_iteration",
+        "Iteration (works like other collections)",
+        linked_list_iteration,
+    )?;
+
+    section(
+        out,
+        "cursor_example",
+        "Cursor-based mutation",
+        cursor_example,
+    )?;
+
+    section(
+        out,
+        "splicing_on_stable",
+        "Stable alternative to cursors: split_off + append, and rebuilding to remove",
+        splicing_on_stable,
+    )?;
+
+    #[cfg(feature = "nightly")]
+    section(
+        out,
+        "cursor_splicing",
+        "Nightly CursorMut: removing and splicing in place during one walk",
+        cursor_splicing,
+    )?;
+
+    section(
+        out,
+        "compare_linked_list",
+        "A comparison of LinkedList with VecDeque",
+        compare_linked_list,
+    )?;
+
+    Ok(())
+}
+
 /// Demonstrates basic LinkedList operations
-pub fn basic_linked_list_operations() {
+pub fn basic_linked_list_operations(out: &mut dyn Write) -> io::Result<()> {
     let mut list: LinkedList<i8> = LinkedList::new();
 
     // Add elements to front and back - O(1)
@@ -10,32 +66,34 @@ pub fn basic_linked_list_operations() {
     list.push_back(3);
     list.push_front(1);
     list.push_front(0);
-    println!("List after pushes: {:?}", list);
+    writeln!(out, "List after pushes: {:?}", list)?;
 
     // Remove from front and back - O(1)
     let front: Option<i8> = list.pop_front();
     let back: Option<i8> = list.pop_back();
-    println!("Popped front: {:?}, back: {:?}", front, back);
-    println!("After pops: {:?}", list);
+    writeln!(out, "Popped front: {:?}, back: {:?}", front, back)?;
+    writeln!(out, "After pops: {:?}", list)?;
 
     // Peek without removing
     if let Some(first) = list.front() {
-        println!("First element: {}", first);
+        writeln!(out, "First element: {}", first)?;
     }
     if let Some(last) = list.back() {
-        println!("Last element: {}", last);
+        writeln!(out, "Last element: {}", last)?;
     }
 
     // Mutable peek
     if let Some(first) = list.front_mut() {
         *first *= 10;
     }
-    println!("After modifying front: {:?}", list);
+    writeln!(out, "After modifying front: {:?}", list)?;
+
+    Ok(())
 }
 
 /// Demonstrates LinkedList's strength: O(1) append and split
-pub fn append_and_split() {
-    println!("\n--- Append and Split Example ---");
+pub fn append_and_split(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Append and Split Example ---")?;
 
     let mut list1: LinkedList<i8> = LinkedList::new();
     let mut list2: LinkedList<i8> = LinkedList::new();
@@ -43,59 +101,63 @@ pub fn append_and_split() {
     list1.extend([1, 2, 3]);
     list2.extend([4, 5, 6]);
 
-    println!("List 1: {:?}", list1);
-    println!("List 2: {:?}", list2);
+    writeln!(out, "List 1: {:?}", list1)?;
+    writeln!(out, "List 2: {:?}", list2)?;
 
     // Append is O(1) - just relink pointers!
     // This is where LinkedList shines compared to Vec
     list1.append(&mut list2);
 
-    println!("After append:");
-    println!("List 1: {:?}", list1);
-    println!("List 2 (now empty): {:?}", list2);
+    writeln!(out, "After append:")?;
+    writeln!(out, "List 1: {:?}", list1)?;
+    writeln!(out, "List 2 (now empty): {:?}", list2)?;
 
     // Split also just relinks pointers
     let mut original: LinkedList<i8> = (0..10).collect();
-    println!("\nOriginal: {:?}", original);
+    writeln!(out, "\nOriginal: {:?}", original)?;
 
     // Split at index 5
     // Note: Finding position 5 is O(n), but the split itself is O(1)
     let second_half: LinkedList<i8> = original.split_off(5);
-    println!("After split_off(5):");
-    println!("First half: {:?}", original);
-    println!("Second half: {:?}", second_half);
+    writeln!(out, "After split_off(5):")?;
+    writeln!(out, "First half: {:?}", original)?;
+    writeln!(out, "Second half: {:?}", second_half)?;
+
+    Ok(())
 }
 
 /// Demonstrates iteration (works like other collections)
-pub fn linked_list_iteration() {
-    println!("\n--- Iteration Example ---");
+pub fn linked_list_iteration(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Iteration Example ---")?;
     let list: LinkedList<&str> = ["apple", "banana", "cherry"].into_iter().collect();
 
     // Immutable iteration
-    print!("Forward: ");
+    write!(out, "Forward: ")?;
     for item in &list {
-        print!("{} ", item);
+        write!(out, "{} ", item)?;
     }
-    println!();
+    writeln!(out)?;
 
     // Reverse iteration (LinkedList supports this efficiently)
-    print!("Backward: ");
+    write!(out, "Backward: ")?;
     for item in list.iter().rev() {
-        print!("{} ", item);
+        write!(out, "{} ", item)?;
     }
-    println!();
+    writeln!(out)?;
 
     // Mutable iteration
     let mut numbers: LinkedList<i8> = (1..=5).collect();
     for item in &mut numbers {
         *item *= 2;
     }
-    println!("Doubled: {:?}", numbers);
+    writeln!(out, "Doubled: {:?}", numbers)?;
+
+    Ok(())
 }
 
 /// Demonstrates cursor-based mutation
-pub fn cursor_example() {
-    println!("\n--- Understanding LinkedList Limitations ---");
+pub fn cursor_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Understanding LinkedList Limitations ---")?;
 
     // LinkedList doesn't support random access
     // You can't do list[3] like with Vec
@@ -104,17 +166,125 @@ pub fn cursor_example() {
 
     // To access the nth element, you must iterate
     if let Some(third) = list.iter().nth(2) {
-        println!("Third element (via iteration): {}", third);
+        writeln!(out, "Third element (via iteration): {}", third)?;
     }
 
     // This is O(n), not O(1)!
     // For most use cases, VecDeque is better
+
+    // Editing in the middle needs a position to edit at. Stable Rust has
+    // only split_off + append for that (splicing_on_stable); the cursor API
+    // that keeps a position while walking is nightly-only (cursor_splicing)
+
+    Ok(())
+}
+
+/// The playlist both splicing demos start from. `[skip]` tracks get
+/// removed; an ad break goes in right after the intro.
+fn playlist() -> LinkedList<&'static str> {
+    LinkedList::from([
+        "intro",
+        "song A",
+        "[skip] song B",
+        "song C",
+        "[skip] song D",
+        "outro",
+    ])
+}
+
+fn ad_break() -> LinkedList<&'static str> {
+    LinkedList::from(["ad 1", "ad 2"])
+}
+
+/// Demonstrates editing the middle of a list with stable APIs only.
+///
+/// `split_off(at)` walks to `at` (from whichever end is closer) and cuts
+/// the list there; `append` relinks in O(1). Removing while walking has no
+/// stable API at all (`retain` and `extract_if` are unstable for
+/// LinkedList), so the list is rebuilt: every element is popped and the
+/// kept ones are pushed onto a new list, freeing and allocating a node each.
+pub fn splicing_on_stable(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Splicing on Stable ---")?;
+
+    let mut list: LinkedList<&str> = playlist();
+    writeln!(out, "Playlist: {:?}", list)?;
+
+    // Splice: cut after the intro, append the ads, then the rest back on
+    let position: usize = list
+        .iter()
+        .position(|&track| track == "intro")
+        .map_or(0, |index| index + 1);
+    let mut tail: LinkedList<&str> = list.split_off(position);
+    list.append(&mut ad_break());
+    list.append(&mut tail);
+    writeln!(out, "split_off({}) + append + append: {:?}", position, list)?;
+
+    // Remove: rebuild the list from the elements to keep
+    let mut kept: LinkedList<&str> = LinkedList::new();
+    let mut removed: Vec<&str> = Vec::new();
+    while let Some(track) = list.pop_front() {
+        if track.starts_with("[skip]") {
+            removed.push(track);
+        } else {
+            kept.push_back(track);
+        }
+    }
+    writeln!(out, "Removed by rebuilding: {:?}", removed)?;
+    writeln!(out, "Result: {:?}", kept)?;
+
+    Ok(())
+}
+
+/// Demonstrates the nightly CursorMut API.
+///
+/// A cursor sits on one element (or on the "ghost" position between the
+/// back and the front) and can remove it or splice a whole list after it
+/// in O(1), then keep walking from where it is. One pass does both edits
+/// and no node is reallocated.
+#[cfg(feature = "nightly")]
+pub fn cursor_splicing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Cursor Splicing (nightly) ---")?;
+
+    let mut list: LinkedList<&str> = playlist();
+    writeln!(out, "Playlist: {:?}", list)?;
+
+    let mut removed: Vec<&str> = Vec::new();
+    let mut steps: usize = 0;
+    {
+        let mut cursor = list.cursor_front_mut();
+        while let Some(&mut track) = cursor.current() {
+            steps += 1;
+            if track.starts_with("[skip]") {
+                // Unlinks the node and moves on to the next one
+                removed.extend(cursor.remove_current());
+                continue;
+            }
+            if track == "intro" {
+                // Relinks the whole ad break after the intro; the cursor
+                // stays on the intro, so the ads are walked over next
+                cursor.splice_after(ad_break());
+            }
+            cursor.move_next();
+        }
+
+        // Off the end, the cursor is on the ghost position; insert_before
+        // from there adds at the back
+        cursor.insert_before("credits");
+    }
+    writeln!(out, "Removed during the walk: {:?}", removed)?;
+    writeln!(out, "Result: {:?}", list)?;
+    writeln!(out, "Elements visited: {}", steps)?;
+
+    Ok(())
 }
 
 /// Demonstrates a comparison of LinkedList with VecDeque
-pub fn compare_linked_list() {
-    println!("\n--- LinkedList vs VecDeque ---");
-    println!("For double-ended operations, VecDeque is usually better:\n");
+pub fn compare_linked_list(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- LinkedList vs VecDeque ---")?;
+    writeln!(
+        out,
+        "For double-ended operations, VecDeque is usually better:\n"
+    )?;
 
     // Both support O(1) push/pop at both ends
     let mut deque: VecDeque<i32> = VecDeque::new();
@@ -137,9 +307,9 @@ pub fn compare_linked_list() {
     }
     let linked_time: Duration = start.elapsed();
 
-    println!("Push {} elements to both ends:", iterations);
-    println!("VecDeque: {:?}", deque_time);
-    println!("LinkedList: {:?}", linked_time);
+    writeln!(out, "Push {} elements to both ends:", iterations)?;
+    writeln!(out, "VecDeque: {:?}", deque_time)?;
+    writeln!(out, "LinkedList: {:?}", linked_time)?;
 
     // Clear and test iteration
     deque.clear();
@@ -158,9 +328,14 @@ pub fn compare_linked_list() {
     let _sum: i32 = linked.iter().sum();
     let linked_iter_time: Duration = start.elapsed();
 
-    println!("\nIterate through {} elements:", iterations);
-    println!("VecDeque: {:?}", deque_iter_time);
-    println!("LinkedList: {:?}", linked_iter_time);
+    writeln!(out, "\nIterate through {} elements:", iterations)?;
+    writeln!(out, "VecDeque: {:?}", deque_iter_time)?;
+    writeln!(out, "LinkedList: {:?}", linked_iter_time)?;
+
+    writeln!(
+        out,
+        "\nVecDeque should win on iteration due to cache locality!"
+    )?;
 
-    println!("\nVecDeque should win on iteration due to cache locality!");
+    Ok(())
 }
@@ -0,0 +1,551 @@
+// An LRU (least recently used) cache holds at most `capacity` entries and,
+// when full, evicts the entry that was read or written longest ago. It
+// needs two things at once: a map to find an entry by key, and an order of
+// recency that can be updated on every access.
+//
+// Two ways to keep that order:
+//
+//   LinkedLru:  HashMap<K, usize> + doubly-linked list threaded through a Vec
+//
+//     map: a -> 2, b -> 0, c -> 1
+//     nodes: [ b | c | a ]        head (most recent) = 2 (a)
+//     links:   a <-> c <-> b      tail (least recent) = 0 (b)
+//
+//     A hit unlinks the node and relinks it at the head: O(1). Eviction
+//     takes the tail node and reuses its slot for the new entry.
+//
+//   DequeLru:   HashMap<K, V> + VecDeque<K> of keys, oldest at the front
+//
+//     A hit has to find the key in the deque before moving it to the back:
+//     O(n). Eviction is pop_front: O(1). Simple, and fast while small.
+//
+// The links are Vec indices rather than pointers, so the list needs no
+// unsafe code and its nodes sit in one allocation.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "lru_basics",
+        "Capacity, hits, misses and which entry gets evicted",
+        lru_basics,
+    )?;
+
+    section(
+        out,
+        "linked_lru_internals",
+        "The index-linked list behind LinkedLru, step by step",
+        linked_lru_internals,
+    )?;
+
+    section(
+        out,
+        "deque_lru_internals",
+        "DequeLru: recency as a VecDeque of keys",
+        deque_lru_internals,
+    )?;
+
+    section(out, "complexity", "get/put cost of each design", complexity)?;
+
+    section(
+        out,
+        "practical_hit_rates",
+        "Practical demo: hit rate vs capacity on a skewed workload",
+        practical_hit_rates,
+    )?;
+
+    Ok(())
+}
+
+/// Marks a missing link.
+const NIL: usize = usize::MAX;
+
+#[derive(Clone, Debug)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    /// Towards the head (more recently used).
+    prev: usize,
+    /// Towards the tail (less recently used).
+    next: usize,
+}
+
+/// An LRU cache: a HashMap from key to slot, plus a doubly-linked list of
+/// slots in recency order. get and put are O(1).
+#[derive(Clone, Debug)]
+pub struct LinkedLru<K, V> {
+    map: HashMap<K, usize>,
+    nodes: Vec<Node<K, V>>,
+    head: usize,
+    tail: usize,
+    capacity: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> LinkedLru<K, V> {
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "LRU capacity must be non-zero");
+        LinkedLru {
+            map: HashMap::with_capacity(capacity),
+            nodes: Vec::with_capacity(capacity),
+            head: NIL,
+            tail: NIL,
+            capacity,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Looks up `key` and marks it as most recently used.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let index: usize = *self.map.get(key)?;
+        self.move_to_head(index);
+        Some(&self.nodes[index].value)
+    }
+
+    /// Looks up `key` without changing its recency.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.map.get(key).map(|&index| &self.nodes[index].value)
+    }
+
+    /// Inserts or updates `key` as the most recently used entry.
+    ///
+    /// Returns the entry pushed out: the old value if `key` was already
+    /// cached, otherwise the evicted least recently used entry if the cache
+    /// was full.
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(&index) = self.map.get(&key) {
+            let old: V = std::mem::replace(&mut self.nodes[index].value, value);
+            self.move_to_head(index);
+            return Some((key, old));
+        }
+
+        if self.nodes.len() < self.capacity {
+            let index: usize = self.nodes.len();
+            self.nodes.push(Node {
+                key: key.clone(),
+                value,
+                prev: NIL,
+                next: NIL,
+            });
+            self.attach_at_head(index);
+            self.map.insert(key, index);
+            return None;
+        }
+
+        // Full: reuse the tail's slot for the new entry
+        let index: usize = self.tail;
+        let old_key: K = std::mem::replace(&mut self.nodes[index].key, key.clone());
+        let old_value: V = std::mem::replace(&mut self.nodes[index].value, value);
+        self.map.remove(&old_key);
+        self.map.insert(key, index);
+        self.move_to_head(index);
+        Some((old_key, old_value))
+    }
+
+    /// Removes `key` from the cache.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index: usize = self.map.remove(key)?;
+        Some(self.remove_slot(index).1)
+    }
+
+    /// Removes and returns the least recently used entry.
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        if self.tail == NIL {
+            return None;
+        }
+        let index: usize = self.tail;
+        self.map.remove(&self.nodes[index].key);
+        Some(self.remove_slot(index))
+    }
+
+    /// Iterates from the most to the least recently used entry.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut cursor: usize = self.head;
+        std::iter::from_fn(move || {
+            let node: &Node<K, V> = self.nodes.get(cursor)?;
+            cursor = node.next;
+            Some((&node.key, &node.value))
+        })
+    }
+
+    /// Unlinks the node at `index` from wherever it is in the list.
+    fn detach(&mut self, index: usize) {
+        let (prev, next) = (self.nodes[index].prev, self.nodes[index].next);
+        match prev {
+            NIL => self.head = next,
+            prev => self.nodes[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.nodes[next].prev = prev,
+        }
+    }
+
+    /// Links a detached node in front of the current head.
+    fn attach_at_head(&mut self, index: usize) {
+        self.nodes[index].prev = NIL;
+        self.nodes[index].next = self.head;
+        match self.head {
+            NIL => self.tail = index,
+            head => self.nodes[head].prev = index,
+        }
+        self.head = index;
+    }
+
+    fn move_to_head(&mut self, index: usize) {
+        if self.head != index {
+            self.detach(index);
+            self.attach_at_head(index);
+        }
+    }
+
+    /// Unlinks and frees the slot at `index`, whose map entry is already
+    /// gone. swap_remove moves the last node into the hole, so its
+    /// neighbours and its map entry are pointed at the new slot.
+    fn remove_slot(&mut self, index: usize) -> (K, V) {
+        self.detach(index);
+        let removed: Node<K, V> = self.nodes.swap_remove(index);
+        if index < self.nodes.len() {
+            let (prev, next) = (self.nodes[index].prev, self.nodes[index].next);
+            match prev {
+                NIL => self.head = index,
+                prev => self.nodes[prev].next = index,
+            }
+            match next {
+                NIL => self.tail = index,
+                next => self.nodes[next].prev = index,
+            }
+            self.map.insert(self.nodes[index].key.clone(), index);
+        }
+        (removed.key, removed.value)
+    }
+}
+
+/// An LRU cache: a HashMap of values plus a VecDeque of keys, least
+/// recently used at the front. put of a new key is O(1); a hit is O(n),
+/// because the key has to be found in the deque to move it.
+#[derive(Clone, Debug)]
+pub struct DequeLru<K, V> {
+    map: HashMap<K, V>,
+    order: VecDeque<K>,
+    capacity: usize,
+}
+
+impl<K: Hash + Eq + Clone, V> DequeLru<K, V> {
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "LRU capacity must be non-zero");
+        DequeLru {
+            map: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Looks up `key` and marks it as most recently used.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        if !self.map.contains_key(key) {
+            return None;
+        }
+        self.touch(key);
+        self.map.get(key)
+    }
+
+    /// Looks up `key` without changing its recency.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.map.get(key)
+    }
+
+    /// Inserts or updates `key` as the most recently used entry. Returns
+    /// the entry pushed out, like [`LinkedLru::put`].
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        if let Some(slot) = self.map.get_mut(&key) {
+            let old: V = std::mem::replace(slot, value);
+            self.touch(&key);
+            return Some((key, old));
+        }
+
+        let mut evicted: Option<(K, V)> = None;
+        if self.map.len() == self.capacity {
+            evicted = self.pop_lru();
+        }
+        self.order.push_back(key.clone());
+        self.map.insert(key, value);
+        evicted
+    }
+
+    /// Removes `key` from the cache.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let value: V = self.map.remove(key)?;
+        let position: usize = self.position(key);
+        self.order.remove(position);
+        Some(value)
+    }
+
+    /// Removes and returns the least recently used entry.
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let key: K = self.order.pop_front()?;
+        let value: V = self.map.remove(&key).expect("every queued key is cached");
+        Some((key, value))
+    }
+
+    /// Iterates from the most to the least recently used entry.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.order.iter().rev().map(|key| (key, &self.map[key]))
+    }
+
+    /// The O(n) part: find the key, then move it to the back.
+    fn touch(&mut self, key: &K) {
+        let position: usize = self.position(key);
+        let key: K = self.order.remove(position).expect("position is in range");
+        self.order.push_back(key);
+    }
+
+    fn position(&self, key: &K) -> usize {
+        self.order
+            .iter()
+            .position(|queued| queued == key)
+            .expect("every cached key is queued")
+    }
+}
+
+/// Demonstrates the behaviour both designs share.
+pub fn lru_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "LRU Basics")?;
+
+    let mut cache: LinkedLru<&str, u32> = LinkedLru::new(3);
+    cache.put("a", 1);
+    cache.put("b", 2);
+    cache.put("c", 3);
+    writeln!(
+        out,
+        "After put a, b, c: {:?}",
+        cache.iter().collect::<Vec<_>>()
+    )?;
+
+    // Reading "a" makes it the most recent, so "b" is now the oldest
+    writeln!(out, "get(\"a\"): {:?}", cache.get(&"a"))?;
+    writeln!(out, "Order (most recent first): {:?}", keys(&cache))?;
+
+    writeln!(out, "put(\"d\", 4) evicts: {:?}", cache.put("d", 4))?;
+    writeln!(out, "get(\"b\"): {:?} (evicted)", cache.get(&"b"))?;
+
+    // Updating an existing key refreshes it and returns the old value
+    writeln!(out, "put(\"c\", 30) returns: {:?}", cache.put("c", 30))?;
+    writeln!(out, "Order (most recent first): {:?}", keys(&cache))?;
+
+    // peek reads without promoting
+    writeln!(out, "\npeek(\"a\"): {:?}", cache.peek(&"a"))?;
+    writeln!(out, "Order unchanged: {:?}", keys(&cache))?;
+    writeln!(out, "pop_lru(): {:?}", cache.pop_lru())?;
+    writeln!(
+        out,
+        "len() = {} of capacity {}",
+        cache.len(),
+        cache.capacity()
+    )?;
+
+    Ok(())
+}
+
+fn keys<'a>(cache: &'a LinkedLru<&'a str, u32>) -> Vec<&'a str> {
+    cache.iter().map(|(k, _)| *k).collect()
+}
+
+/// Prints the slots of a LinkedLru with their links.
+fn print_nodes(out: &mut dyn Write, cache: &LinkedLru<char, u32>) -> io::Result<()> {
+    let link = |i: usize| -> String {
+        if i == NIL {
+            "-".to_string()
+        } else {
+            i.to_string()
+        }
+    };
+    for (i, node) in cache.nodes.iter().enumerate() {
+        writeln!(
+            out,
+            "  slot {}: {} prev={} next={}",
+            i,
+            node.key,
+            link(node.prev),
+            link(node.next)
+        )?;
+    }
+    writeln!(out, "  head={} tail={}", link(cache.head), link(cache.tail))
+}
+
+/// Demonstrates the slot array and its links as entries move around.
+pub fn linked_lru_internals(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "LinkedLru Internals")?;
+
+    let mut cache: LinkedLru<char, u32> = LinkedLru::new(3);
+    for (i, key) in ['x', 'y', 'z'].into_iter().enumerate() {
+        cache.put(key, i as u32);
+    }
+    writeln!(out, "put x, y, z (each new entry becomes the head):")?;
+    print_nodes(out, &cache)?;
+
+    cache.get(&'x');
+    writeln!(out, "\nget('x'): x moves to the head, its slot stays put:")?;
+    print_nodes(out, &cache)?;
+
+    cache.put('w', 3);
+    writeln!(out, "\nput('w'): y was the tail, w takes over slot 1:")?;
+    print_nodes(out, &cache)?;
+
+    cache.remove(&'z');
+    writeln!(
+        out,
+        "\nremove('z'): the last slot moves into the hole and is relinked:"
+    )?;
+    print_nodes(out, &cache)?;
+
+    Ok(())
+}
+
+/// Demonstrates the VecDeque design and where its O(n) comes from.
+pub fn deque_lru_internals(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "DequeLru Internals")?;
+
+    let mut cache: DequeLru<char, u32> = DequeLru::new(4);
+    for (i, key) in ['p', 'q', 'r', 's'].into_iter().enumerate() {
+        cache.put(key, i as u32);
+    }
+    writeln!(out, "Deque (oldest first): {:?}", cache.order)?;
+
+    // A hit scans for the key, removes it and pushes it to the back
+    cache.get(&'q');
+    writeln!(
+        out,
+        "get('q') scans to index 1, moves it: {:?}",
+        cache.order
+    )?;
+
+    // A miss-then-insert on a full cache is just pop_front + push_back
+    let evicted: Option<(char, u32)> = cache.put('t', 4);
+    writeln!(out, "put('t') evicts {:?}: {:?}", evicted, cache.order)?;
+
+    writeln!(
+        out,
+        "\nIteration, most recent first: {:?}",
+        cache.iter().collect::<Vec<_>>()
+    )?;
+
+    Ok(())
+}
+
+/// Summarizes the cost of each operation in the two designs.
+pub fn complexity(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Complexity")?;
+    writeln!(
+        out,
+        "{:<22} {:<12} {:<12}",
+        "Operation", "LinkedLru", "DequeLru"
+    )?;
+    for (op, linked, deque) in [
+        ("get (hit)", "O(1)", "O(n)"),
+        ("get (miss)", "O(1)", "O(1)"),
+        ("put (new key)", "O(1)", "O(1)"),
+        ("put (existing key)", "O(1)", "O(n)"),
+        ("evict LRU", "O(1)", "O(1)"),
+        ("remove(key)", "O(1)", "O(n)"),
+        ("memory per entry", "K + 2 links", "K twice"),
+    ] {
+        writeln!(out, "{:<22} {:<12} {:<12}", op, linked, deque)?;
+    }
+
+    writeln!(
+        out,
+        "\nLinkedLru stores each key twice too: once in the map,"
+    )?;
+    writeln!(out, "once in its node, so the evicted key can be unmapped.")?;
+
+    Ok(())
+}
+
+/// A deterministic skewed key stream: most requests go to a few hot keys.
+pub fn skewed_keys(count: usize, key_space: u64, seed: u64) -> Vec<u64> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..count)
+        .map(|_| {
+            let r: u64 = rng.bits();
+            // Raising a uniform value in [0, 1) to the 4th power piles the
+            // mass near 0: a quarter of requests hit the lowest 0.4% of keys
+            let unit: f64 = r as f64 / (1u64 << 31) as f64;
+            (unit.powi(4) * key_space as f64) as u64
+        })
+        .collect()
+}
+
+/// Practical example: caching an expensive lookup.
+///
+/// The hit rate climbs quickly at first: a small cache already holds the
+/// hot keys, and each extra slot after that covers colder ones.
+pub fn practical_hit_rates(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Hit Rate vs Capacity")?;
+
+    let requests: Vec<u64> = skewed_keys(100_000, 10_000, 7);
+    writeln!(
+        out,
+        "{} requests over {} keys, skewed towards low IDs",
+        requests.len(),
+        10_000
+    )?;
+
+    for capacity in [10, 100, 1_000, 5_000] {
+        let mut cache: LinkedLru<u64, u64> = LinkedLru::new(capacity);
+        let mut hits: usize = 0;
+        for &key in &requests {
+            if cache.get(&key).is_some() {
+                hits += 1;
+            } else {
+                // Stand-in for the expensive computation
+                cache.put(key, key * key);
+            }
+        }
+        writeln!(
+            out,
+            "  capacity {:>5}: {:>5.1}% hits",
+            capacity,
+            hits as f64 * 100.0 / requests.len() as f64
+        )?;
+    }
+
+    Ok(())
+}
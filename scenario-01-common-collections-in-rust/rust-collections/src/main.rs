@@ -1,6 +1,17 @@
-use rustc_version_runtime;
+use demo_framework::alloc::TrackingAllocator;
+use demo_framework::cli::Cli;
+use demo_framework::report::SectionSummary;
+use std::process::ExitCode;
 
-fn main() {
-    println!("Rust Collections Demo");
-    println!("Compiled with: {:?}", rustc_version_runtime::version());
+// Counts allocations for the per-section summary at the end of a run, and
+// watches the live heap in `soak` runs
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+fn main() -> ExitCode {
+    Cli::new("Rust Collections Demo", collections_demo::MODULES)
+        .cheatsheet(collections_demo::cheatsheet::tables)
+        .soak(collections_demo::soak::workload)
+        .report(SectionSummary::new())
+        .run()
 }
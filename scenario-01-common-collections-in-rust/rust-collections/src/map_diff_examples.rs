@@ -0,0 +1,427 @@
+// Diffing two versions of a map - yesterday's inventory against today's, the
+// running config against the file on disk - sorts every key into one of:
+//
+//   added:    only in the new map
+//   removed:  only in the old map
+//   changed:  in both, with different values
+//
+// diff_maps(old, new) works on HashMap and BTreeMap through the Diffable
+// trait, with two different algorithms underneath:
+//
+//   HashMap:   for each old entry, look the key up in the new map; then for
+//              each new entry, check the old map. O(n + m) lookups. The
+//              result comes out in hash order, which differs between runs
+//
+//   BTreeMap:  walk both maps in key order at once (a merge join), like
+//              merging two sorted lists. O(n + m), no lookups at all, and
+//              the result is already sorted by key: the same on every run
+//
+// The merge join only needs two sorted sequences, so diff_sorted also diffs
+// sorted Vecs, files or database cursors.
+
+use demo_framework::section;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, Write};
+use std::iter::Peekable;
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "diff_basics",
+        "Added, removed and changed entries between two maps",
+        diff_basics,
+    )?;
+
+    section(
+        out,
+        "ordered_diffs",
+        "HashMap diffs come out in hash order; BTreeMap diffs in key order",
+        ordered_diffs,
+    )?;
+
+    section(
+        out,
+        "merge_join",
+        "How the BTreeMap diff walks both maps without a single lookup",
+        merge_join,
+    )?;
+
+    section(
+        out,
+        "practical_snapshot_diff",
+        "Practical demo: what changed between two inventory snapshots",
+        practical_snapshot_diff,
+    )?;
+
+    Ok(())
+}
+
+/// The difference between an old and a new map, borrowing from both.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MapDiff<'a, K, V> {
+    /// Entries only in the new map.
+    pub added: Vec<(&'a K, &'a V)>,
+    /// Entries only in the old map.
+    pub removed: Vec<(&'a K, &'a V)>,
+    /// Keys in both maps with different values: (key, old, new).
+    pub changed: Vec<(&'a K, &'a V, &'a V)>,
+}
+
+impl<'a, K, V> MapDiff<'a, K, V> {
+    pub fn new() -> Self {
+        MapDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+
+    /// Total number of differences.
+    pub fn len(&self) -> usize {
+        self.added.len() + self.removed.len() + self.changed.len()
+    }
+
+    /// True when the maps are equal.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sorts each list by key, for a HashMap diff that must be compared or
+    /// printed deterministically.
+    pub fn into_sorted(mut self) -> Self
+    where
+        K: Ord,
+    {
+        self.added.sort_by_key(|&(k, _)| k);
+        self.removed.sort_by_key(|&(k, _)| k);
+        self.changed.sort_by_key(|&(k, _, _)| k);
+        self
+    }
+}
+
+impl<K, V> Default for MapDiff<'_, K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A map that can be diffed against another of the same type.
+pub trait Diffable {
+    type Key;
+    type Value;
+
+    fn diff<'a>(&'a self, new: &'a Self) -> MapDiff<'a, Self::Key, Self::Value>;
+}
+
+impl<K: Hash + Eq, V: PartialEq, S: BuildHasher> Diffable for HashMap<K, V, S> {
+    type Key = K;
+    type Value = V;
+
+    /// One lookup per entry of each map; the result is in hash order.
+    fn diff<'a>(&'a self, new: &'a Self) -> MapDiff<'a, K, V> {
+        let mut diff: MapDiff<'a, K, V> = MapDiff::new();
+        for (key, old_value) in self {
+            match new.get(key) {
+                None => diff.removed.push((key, old_value)),
+                Some(new_value) if new_value != old_value => {
+                    diff.changed.push((key, old_value, new_value))
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, new_value) in new {
+            if !self.contains_key(key) {
+                diff.added.push((key, new_value));
+            }
+        }
+        diff
+    }
+}
+
+impl<K: Ord, V: PartialEq> Diffable for BTreeMap<K, V> {
+    type Key = K;
+    type Value = V;
+
+    /// A merge join over both maps; the result is sorted by key.
+    fn diff<'a>(&'a self, new: &'a Self) -> MapDiff<'a, K, V> {
+        diff_sorted(self, new)
+    }
+}
+
+/// Diffs two maps of the same type: `HashMap` or `BTreeMap`.
+pub fn diff_maps<'a, M: Diffable>(old: &'a M, new: &'a M) -> MapDiff<'a, M::Key, M::Value> {
+    old.diff(new)
+}
+
+/// Diffs two sequences of entries sorted by key, with unique keys.
+pub fn diff_sorted<'a, K: Ord + 'a, V: PartialEq + 'a>(
+    old: impl IntoIterator<Item = (&'a K, &'a V)>,
+    new: impl IntoIterator<Item = (&'a K, &'a V)>,
+) -> MapDiff<'a, K, V> {
+    let mut old: Peekable<_> = old.into_iter().peekable();
+    let mut new: Peekable<_> = new.into_iter().peekable();
+    let mut diff: MapDiff<'a, K, V> = MapDiff::new();
+
+    loop {
+        let order: Ordering = match (old.peek(), new.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some((old_key, _)), Some((new_key, _))) => old_key.cmp(new_key),
+        };
+        match order {
+            // The smaller key can't appear later in the other sequence
+            Ordering::Less => diff.removed.extend(old.next()),
+            Ordering::Greater => diff.added.extend(new.next()),
+            Ordering::Equal => {
+                let (key, old_value) = old.next().expect("peeked");
+                let (_, new_value) = new.next().expect("peeked");
+                if old_value != new_value {
+                    diff.changed.push((key, old_value, new_value));
+                }
+            }
+        }
+    }
+    diff
+}
+
+/// Prints a diff as +, - and ~ lines.
+fn print_diff<K: std::fmt::Debug, V: std::fmt::Debug>(
+    out: &mut dyn Write,
+    diff: &MapDiff<'_, K, V>,
+) -> io::Result<()> {
+    for (key, value) in &diff.added {
+        writeln!(out, "  + {:?} = {:?}", key, value)?;
+    }
+    for (key, value) in &diff.removed {
+        writeln!(out, "  - {:?} = {:?}", key, value)?;
+    }
+    for (key, old, new) in &diff.changed {
+        writeln!(out, "  ~ {:?}: {:?} -> {:?}", key, old, new)?;
+    }
+    Ok(())
+}
+
+fn old_config() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("log_level", "info"),
+        ("max_connections", "100"),
+        ("timeout_ms", "5000"),
+        ("tls", "on"),
+        ("legacy_api", "enabled"),
+    ]
+}
+
+fn new_config() -> Vec<(&'static str, &'static str)> {
+    vec![
+        ("log_level", "debug"),
+        ("max_connections", "100"),
+        ("timeout_ms", "3000"),
+        ("tls", "on"),
+        ("cache_size_mb", "256"),
+    ]
+}
+
+/// Demonstrates the three kinds of difference.
+pub fn diff_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Diff Basics")?;
+
+    let old: HashMap<&str, &str> = old_config().into_iter().collect();
+    let new: HashMap<&str, &str> = new_config().into_iter().collect();
+
+    let diff: MapDiff<&str, &str> = diff_maps(&old, &new).into_sorted();
+    writeln!(out, "Old config -> new config:")?;
+    print_diff(out, &diff)?;
+    writeln!(
+        out,
+        "{} added, {} removed, {} changed; unchanged keys are not listed",
+        diff.added.len(),
+        diff.removed.len(),
+        diff.changed.len()
+    )?;
+
+    writeln!(
+        out,
+        "\nA map diffed against itself: {:?}",
+        diff_maps(&old, &old).is_empty()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates where the order of a diff comes from.
+pub fn ordered_diffs(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Ordered Diffs")?;
+
+    let old: BTreeMap<&str, &str> = old_config().into_iter().collect();
+    let new: BTreeMap<&str, &str> = new_config().into_iter().collect();
+    let sorted_diff: MapDiff<&str, &str> = diff_maps(&old, &new);
+    writeln!(out, "BTreeMap diff, in key order as produced:")?;
+    print_diff(out, &sorted_diff)?;
+
+    // Two HashMaps with the same contents iterate in different orders
+    let old_hashed: HashMap<&str, &str> = old_config().into_iter().collect();
+    let new_hashed: HashMap<&str, &str> = new_config().into_iter().collect();
+    let hashed_diff: MapDiff<&str, &str> = diff_maps(&old_hashed, &new_hashed);
+    writeln!(
+        out,
+        "\nHashMap diff, same entries in hash order; after into_sorted() equal to the BTreeMap diff: {}",
+        hashed_diff.into_sorted() == sorted_diff
+    )?;
+    writeln!(
+        out,
+        "Sort a HashMap diff before logging it, comparing it in"
+    )?;
+    writeln!(out, "a test or sending it anywhere a human reads diffs.")?;
+
+    Ok(())
+}
+
+/// Demonstrates the merge join step by step.
+pub fn merge_join(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Merge Join")?;
+
+    let old: BTreeMap<u32, char> = [(1, 'a'), (3, 'c'), (4, 'd'), (7, 'g')].into();
+    let new: BTreeMap<u32, char> = [(1, 'a'), (2, 'b'), (4, 'D'), (8, 'h')].into();
+    writeln!(out, "old: {:?}", old)?;
+    writeln!(out, "new: {:?}", new)?;
+
+    // The same loop as diff_sorted, narrated
+    let mut old_iter = old.iter().peekable();
+    let mut new_iter = new.iter().peekable();
+    loop {
+        match (old_iter.peek(), new_iter.peek()) {
+            (None, None) => break,
+            (Some((o, _)), Some((n, _))) if o == n => {
+                let (key, old_value) = old_iter.next().expect("peeked");
+                let (_, new_value) = new_iter.next().expect("peeked");
+                let verdict: &str = if old_value == new_value {
+                    "unchanged"
+                } else {
+                    "changed"
+                };
+                writeln!(out, "  {} vs {}: same key, {}", key, key, verdict)?;
+            }
+            (Some((o, _)), Some((n, _))) if o < n => {
+                writeln!(out, "  {} vs {}: {} is only in old -> removed", o, n, o)?;
+                old_iter.next();
+            }
+            (Some((o, _)), Some((n, _))) => {
+                writeln!(out, "  {} vs {}: {} is only in new -> added", o, n, n)?;
+                new_iter.next();
+            }
+            (Some((o, _)), None) => {
+                writeln!(out, "  {} vs end: removed", o)?;
+                old_iter.next();
+            }
+            (None, Some((n, _))) => {
+                writeln!(out, "  end vs {}: added", n)?;
+                new_iter.next();
+            }
+        }
+    }
+
+    writeln!(out, "\nResult:")?;
+    print_diff(out, &diff_maps(&old, &new))?;
+
+    // Any two sorted sequences work, not just BTreeMaps
+    let old_rows: Vec<(u32, char)> = old.into_iter().collect();
+    let new_rows: Vec<(u32, char)> = new.into_iter().collect();
+    let from_vecs: MapDiff<u32, char> = diff_sorted(
+        old_rows.iter().map(|(k, v)| (k, v)),
+        new_rows.iter().map(|(k, v)| (k, v)),
+    );
+    writeln!(
+        out,
+        "diff_sorted on two sorted Vecs finds {} differences",
+        from_vecs.len()
+    )?;
+
+    Ok(())
+}
+
+/// One inventory snapshot: (SKU, price in cents), sorted by SKU.
+pub type Snapshot = Vec<(u64, u64)>;
+
+/// Deterministic inventory snapshots. The second drops every 200th SKU,
+/// adds as many new ones and reprices every 100th.
+pub fn inventory_snapshots(skus: u64) -> (Snapshot, Snapshot) {
+    let old: Snapshot = (0..skus).map(|sku| (sku, 1_000 + sku % 9_000)).collect();
+    let mut new: Snapshot = old
+        .iter()
+        .filter(|(sku, _)| sku % 200 != 7)
+        .map(|&(sku, price)| {
+            if sku % 100 == 3 {
+                (sku, price + 50)
+            } else {
+                (sku, price)
+            }
+        })
+        .collect();
+    new.extend((skus..skus + skus / 200).map(|sku| (sku, 999)));
+    (old, new)
+}
+
+/// Practical example: diffing two 100,000-entry snapshots both ways.
+pub fn practical_snapshot_diff(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Inventory Snapshot Diff")?;
+
+    let (old_rows, new_rows) = inventory_snapshots(100_000);
+    let old_hashed: HashMap<u64, u64> = old_rows.iter().copied().collect();
+    let new_hashed: HashMap<u64, u64> = new_rows.iter().copied().collect();
+    let old_sorted: BTreeMap<u64, u64> = old_rows.into_iter().collect();
+    let new_sorted: BTreeMap<u64, u64> = new_rows.into_iter().collect();
+
+    let start: Instant = Instant::now();
+    let hashed: MapDiff<u64, u64> = diff_maps(&old_hashed, &new_hashed);
+    let hashed_time: Duration = start.elapsed();
+
+    let start: Instant = Instant::now();
+    let sorted: MapDiff<u64, u64> = diff_maps(&old_sorted, &new_sorted);
+    let sorted_time: Duration = start.elapsed();
+
+    writeln!(
+        out,
+        "{} SKUs yesterday, {} today",
+        old_sorted.len(),
+        new_sorted.len()
+    )?;
+    writeln!(
+        out,
+        "{:<9} {:>6} {:>8} {:>8}  time",
+        "Map", "added", "removed", "changed"
+    )?;
+    for (name, diff, time) in [
+        ("HashMap", &hashed, hashed_time),
+        ("BTreeMap", &sorted, sorted_time),
+    ] {
+        writeln!(
+            out,
+            // Unpadded: the width of a Duration varies from run to run
+            "{:<9} {:>6} {:>8} {:>8}  {:?}",
+            name,
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len(),
+            time
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\nFirst price changes, straight from the BTreeMap diff:"
+    )?;
+    for (sku, old, new) in sorted.changed.iter().take(3) {
+        writeln!(out, "  SKU {:>5}: {} -> {} cents", sku, old, new)?;
+    }
+    writeln!(
+        out,
+        "Same result both ways: {}",
+        hashed.into_sorted() == sorted
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,407 @@
+// A view is a window into a map: a key range and a predicate, kept
+// alongside a borrow of the map and applied only while iterating. It is
+// the alternative to a snapshot, which runs the query once and collects
+// the answer into a new map or Vec:
+//
+//                      view                      snapshot
+//   building it        O(1), no allocation       O(k log n) or more, clones
+//                                                every matching entry
+//   reading it         walks the range, filters  walks the copy
+//   map changes        sees them (once the       never sees them
+//                      borrow allows a change)
+//
+// Most queries are read once: printed, summed, or handed to one loop. For
+// those the snapshot's collect is pure overhead, and a view returns the
+// same entries without it.
+//
+// MapView is an experiment in making that a type. MapView::new(&map) sees
+// every entry; range() narrows the key range (two ranges intersect) and
+// filter() adds a predicate (two predicates must both hold). Nothing is
+// evaluated until iter(), keys(), values(), project() or first()/last()
+// walk it, and they can walk it again: a view holds no iterator state.
+// The filter is part of the view's type: KeepAll for a new view, Both<A, B>
+// once a second filter joins the first. A view with a filter is a
+// different type from one without, and a filter that captures nothing
+// costs nothing to store or to call.
+
+use demo_framework::section;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::mem;
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+use std::ops::RangeBounds;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "map_view_basics",
+        "A key range and a predicate, applied only when iterated",
+        map_view_basics,
+    )?;
+
+    section(
+        out,
+        "narrowing_views",
+        "Intersecting ranges and combining filters",
+        narrowing_views,
+    )?;
+
+    section(
+        out,
+        "views_vs_snapshots",
+        "What a collected snapshot costs that a view doesn't",
+        views_vs_snapshots,
+    )?;
+
+    Ok(())
+}
+
+/// What a view keeps. Implemented for every `Fn(&K, &V) -> bool`, and for
+/// the two filters below, which are types of their own so a view's filter
+/// is a plain generic parameter the compiler can inline.
+pub trait Filter<K, V> {
+    fn keep(&self, key: &K, value: &V) -> bool;
+}
+
+impl<K, V, F: Fn(&K, &V) -> bool> Filter<K, V> for F {
+    fn keep(&self, key: &K, value: &V) -> bool {
+        self(key, value)
+    }
+}
+
+/// The filter of a view that keeps every entry.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeepAll;
+
+impl<K, V> Filter<K, V> for KeepAll {
+    fn keep(&self, _: &K, _: &V) -> bool {
+        true
+    }
+}
+
+/// Two filters that must both keep an entry; the second isn't asked if
+/// the first says no.
+#[derive(Debug, Clone, Copy)]
+pub struct Both<A, B>(A, B);
+
+impl<K, V, A: Filter<K, V>, B: Filter<K, V>> Filter<K, V> for Both<A, B> {
+    fn keep(&self, key: &K, value: &V) -> bool {
+        self.0.keep(key, value) && self.1.keep(key, value)
+    }
+}
+
+/// A read-only window into a `BTreeMap`: the entries whose key is in a
+/// range and that pass a filter, found lazily on each walk.
+#[derive(Debug, Clone)]
+pub struct MapView<'a, K, V, F = KeepAll> {
+    map: &'a BTreeMap<K, V>,
+    start: Bound<K>,
+    end: Bound<K>,
+    filter: F,
+}
+
+impl<'a, K: Ord, V> MapView<'a, K, V> {
+    /// A view of every entry of `map`.
+    pub fn new(map: &'a BTreeMap<K, V>) -> Self {
+        MapView {
+            map,
+            start: Unbounded,
+            end: Unbounded,
+            filter: KeepAll,
+        }
+    }
+}
+
+impl<'a, K: Ord, V, F: Filter<K, V>> MapView<'a, K, V, F> {
+    /// Narrows the view to the keys in `range` as well as its current range.
+    pub fn range<R: RangeBounds<K>>(self, range: R) -> Self
+    where
+        K: Clone,
+    {
+        MapView {
+            start: tighter_start(self.start, range.start_bound().cloned()),
+            end: tighter_end(self.end, range.end_bound().cloned()),
+            ..self
+        }
+    }
+
+    /// Narrows the view to the entries that pass `keep` as well as its
+    /// current filter.
+    pub fn filter<G: Fn(&K, &V) -> bool>(self, keep: G) -> MapView<'a, K, V, Both<F, G>> {
+        MapView {
+            map: self.map,
+            start: self.start,
+            end: self.end,
+            filter: Both(self.filter, keep),
+        }
+    }
+
+    /// True if no key can be in the range, whatever the map holds.
+    /// `BTreeMap::range` panics on such a range instead of returning nothing.
+    fn range_is_empty(&self) -> bool {
+        match (&self.start, &self.end) {
+            (Included(start), Included(end)) => start > end,
+            (Included(start), Excluded(end))
+            | (Excluded(start), Included(end))
+            | (Excluded(start), Excluded(end)) => start >= end,
+            _ => false,
+        }
+    }
+
+    /// The entries in key order. Walks the range each time it is called.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&'a K, &'a V)> + '_ {
+        let range = (!self.range_is_empty())
+            .then(|| self.map.range((self.start.as_ref(), self.end.as_ref())));
+        range
+            .into_iter()
+            .flatten()
+            .filter(|(key, value)| self.filter.keep(key, value))
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &'a K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &'a V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Each entry's key with `project` applied to its value.
+    pub fn project<'s, W>(
+        &'s self,
+        project: impl Fn(&V) -> W + 's,
+    ) -> impl DoubleEndedIterator<Item = (&'a K, W)> + 's {
+        self.iter().map(move |(key, value)| (key, project(value)))
+    }
+
+    /// Entries in the view. O(k) for a range of k keys: nothing is cached.
+    pub fn count(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    pub fn first(&self) -> Option<(&'a K, &'a V)> {
+        self.iter().next()
+    }
+
+    pub fn last(&self) -> Option<(&'a K, &'a V)> {
+        self.iter().next_back()
+    }
+
+    /// Collects the view into an owned map: the snapshot a view replaces.
+    pub fn to_map(&self) -> BTreeMap<K, V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Range intersection
+// ----------------------------------------------------------------------------
+
+/// The later of two start bounds; on equal keys, Excluded is the later.
+fn tighter_start<K: Ord>(a: Bound<K>, b: Bound<K>) -> Bound<K> {
+    match (a, b) {
+        (Unbounded, bound) | (bound, Unbounded) => bound,
+        (Included(a), Included(b)) => Included(a.max(b)),
+        (Excluded(a), Excluded(b)) => Excluded(a.max(b)),
+        (Included(included), Excluded(excluded)) | (Excluded(excluded), Included(included)) => {
+            if included > excluded {
+                Included(included)
+            } else {
+                Excluded(excluded)
+            }
+        }
+    }
+}
+
+/// The earlier of two end bounds; on equal keys, Excluded is the earlier.
+fn tighter_end<K: Ord>(a: Bound<K>, b: Bound<K>) -> Bound<K> {
+    match (a, b) {
+        (Unbounded, bound) | (bound, Unbounded) => bound,
+        (Included(a), Included(b)) => Included(a.min(b)),
+        (Excluded(a), Excluded(b)) => Excluded(a.min(b)),
+        (Included(included), Excluded(excluded)) | (Excluded(excluded), Included(included)) => {
+            if included < excluded {
+                Included(included)
+            } else {
+                Excluded(excluded)
+            }
+        }
+    }
+}
+
+/// Order book prices in cents, with the quantity at each.
+fn price_levels() -> BTreeMap<u32, u32> {
+    BTreeMap::from([
+        (9_950, 40),
+        (9_975, 5),
+        (9_990, 120),
+        (10_000, 300),
+        (10_010, 15),
+        (10_025, 80),
+        (10_050, 2),
+        (10_100, 250),
+    ])
+}
+
+// ----------------------------------------------------------------------------
+// Demos
+// ----------------------------------------------------------------------------
+
+/// Demonstrates building a view and walking it.
+pub fn map_view_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Map View Basics")?;
+
+    let levels: BTreeMap<u32, u32> = price_levels();
+    writeln!(out, "Price levels (cents -> quantity): {:?}", levels)?;
+
+    // The filter counts its calls to show when it runs
+    let calls: Cell<usize> = Cell::new(0);
+    let deep: MapView<u32, u32, _> =
+        MapView::new(&levels)
+            .range(9_975..=10_050)
+            .filter(|_, &quantity| {
+                calls.set(calls.get() + 1);
+                quantity >= 50
+            });
+    writeln!(out, "\nView: prices 9975..=10050 with quantity >= 50")?;
+    writeln!(out, "Filter calls after building it: {}", calls.get())?;
+
+    writeln!(out, "iter(): {:?}", deep.iter().collect::<Vec<_>>())?;
+    writeln!(
+        out,
+        "Filter calls after one walk: {} - one per key in the range, none outside it",
+        calls.get()
+    )?;
+
+    // Every read walks again; nothing is cached between them
+    writeln!(out, "keys(): {:?}", deep.keys().collect::<Vec<_>>())?;
+    writeln!(
+        out,
+        "first(): {:?}, last(): {:?}, count(): {}",
+        deep.first(),
+        deep.last(),
+        deep.count()
+    )?;
+
+    // project() maps each value and keeps the key: notional in dollars
+    let notional: Vec<(&u32, f64)> = deep
+        .project(|&quantity| f64::from(quantity) * 0.01)
+        .collect();
+    writeln!(out, "project(quantity * $0.01): {:?}", notional)?;
+    writeln!(out, "Filter calls in total: {}", calls.get())?;
+
+    Ok(())
+}
+
+/// Demonstrates narrowing a view step by step.
+pub fn narrowing_views(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Narrowing Views")?;
+
+    let levels: BTreeMap<u32, u32> = price_levels();
+    let all: MapView<u32, u32> = MapView::new(&levels);
+    writeln!(
+        out,
+        "All prices:        {:?}",
+        all.keys().collect::<Vec<_>>()
+    )?;
+
+    // A second range intersects with the first
+    let near: MapView<u32, u32> = all.clone().range(9_960..10_050);
+    let upper: MapView<u32, u32> = near.clone().range(10_000..);
+    writeln!(
+        out,
+        "range(9960..10050): {:?}",
+        near.keys().collect::<Vec<_>>()
+    )?;
+    writeln!(
+        out,
+        "  .range(10000..):  {:?}",
+        upper.keys().collect::<Vec<_>>()
+    )?;
+
+    // A second filter must hold as well as the first
+    let round: MapView<u32, u32, _> = near.clone().filter(|&price, _| price % 25 == 0);
+    let round_and_deep = round.clone().filter(|_, &quantity| quantity >= 100);
+    writeln!(
+        out,
+        "filter(price % 25 == 0): {:?}",
+        round.iter().collect::<Vec<_>>()
+    )?;
+    writeln!(
+        out,
+        "  .filter(quantity >= 100): {:?}",
+        round_and_deep.iter().collect::<Vec<_>>()
+    )?;
+
+    // BTreeMap::range panics on a start past the end; a view is just empty
+    let disjoint: MapView<u32, u32> = upper.range(..9_990);
+    writeln!(
+        out,
+        "range(10000..) then range(..9990): empty: {}, count {}",
+        disjoint.is_empty(),
+        disjoint.count()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates what a snapshot copies and a view doesn't.
+pub fn views_vs_snapshots(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Views vs Snapshots")?;
+
+    let readings: BTreeMap<u64, f64> = (0..1_000u64)
+        .map(|second| (second, 20.0 + (second % 60) as f64 / 10.0))
+        .collect();
+
+    // The snapshot clones the answer into a new tree before anything reads it
+    let snapshot: BTreeMap<u64, f64> = readings
+        .range(600..900)
+        .filter(|(_, celsius)| **celsius > 24.0)
+        .map(|(&second, &celsius)| (second, celsius))
+        .collect();
+    let view = MapView::new(&readings)
+        .range(600..900)
+        .filter(|_, celsius| *celsius > 24.0);
+
+    writeln!(
+        out,
+        "Readings over 24°C in seconds 600..900: snapshot {}, view {}",
+        snapshot.len(),
+        view.count()
+    )?;
+    writeln!(out, "Same entries: {}", view.iter().eq(snapshot.iter()))?;
+    writeln!(
+        out,
+        "Snapshot: {} entries cloned into a new BTreeMap",
+        snapshot.len()
+    )?;
+    writeln!(
+        out,
+        "View:     {} bytes on the stack - a reference, two bounds and a zero-sized filter",
+        mem::size_of_val(&view)
+    )?;
+
+    // Read once, the view is the same loop as the snapshot minus the collect
+    let mean: f64 = view.values().sum::<f64>() / view.count() as f64;
+    writeln!(out, "Mean of the hot readings: {:.2}°C", mean)?;
+
+    // to_map() is the snapshot, for the caller that wants to keep one
+    writeln!(
+        out,
+        "view.to_map() == snapshot: {}",
+        view.to_map() == snapshot
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,470 @@
+// Numeric matrices in two layouts: the familiar Vec<Vec<f64>> and the flat
+// row-major Grid<f64> from grid_examples.
+//
+//   Vec<Vec<f64>>                        Grid<f64>
+//   ┌──────┐     ┌────┬────┬────┐
+//   │ ptr ─┼────▶│ a0 │ a1 │ a2 │        ┌────┬────┬────┬────┬────┬────┬────┬────┬────┐
+//   │ ptr ─┼──┐  └────┴────┴────┘        │ a0 │ a1 │ a2 │ b0 │ b1 │ b2 │ c0 │ c1 │ c2 │
+//   │ ptr ─┼┐ └─▶┌────┬────┬────┐        └────┴────┴────┴────┴────┴────┴────┴────┴────┘
+//   └──────┘│    │ b0 │ b1 │ b2 │         index(row, col) = row * cols + col
+//           │    └────┴────┴────┘
+//           └───▶┌────┬────┬────┐
+//                │ c0 │ c1 │ c2 │
+//                └────┴────┴────┘
+//
+// The nested version allocates once per row and reaches each element through
+// the row's pointer first. The flat version is one allocation; the index is
+// a multiply and an add.
+//
+// In both, a row is contiguous and a column is not. Matrix multiplication
+// shows why that matters: the textbook i-j-k loop walks a column of B for
+// every output cell, while the i-k-j order streams whole rows of B and C and
+// does the same arithmetic.
+
+use crate::grid_examples::Grid;
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "nested_vs_flat",
+        "Vec<Vec<f64>> vs a flat Grid<f64>: allocations and indexing",
+        nested_vs_flat,
+    )?;
+
+    section(
+        out,
+        "jagged_rows",
+        "Nested rows can disagree on their length; a Grid can't",
+        jagged_rows,
+    )?;
+
+    section(
+        out,
+        "row_vs_column_sums",
+        "Summing an f64 matrix by rows and by columns, in both layouts",
+        row_vs_column_sums,
+    )?;
+
+    section(
+        out,
+        "multiply_loop_orders",
+        "Matrix multiply: i-j-k, i-k-j and against a transposed B",
+        multiply_loop_orders,
+    )?;
+
+    section(
+        out,
+        "practical_markov_chain",
+        "Practical demo: stepping a Markov chain with matrix-vector products",
+        practical_markov_chain,
+    )?;
+
+    Ok(())
+}
+
+/// Copies a nested matrix into a Grid, or returns `None` if the rows don't
+/// all have the same length.
+pub fn grid_from_nested(nested: &[Vec<f64>]) -> Option<Grid<f64>> {
+    let cols: usize = nested.first().map_or(0, Vec::len);
+    if nested.iter().any(|row| row.len() != cols) {
+        return None;
+    }
+    Some(Grid::from_vec(nested.len(), cols, nested.concat()))
+}
+
+/// Copies a Grid into one Vec per row.
+pub fn nested_from_grid(grid: &Grid<f64>) -> Vec<Vec<f64>> {
+    grid.iter_rows().map(|row| row.to_vec()).collect()
+}
+
+/// Sums every element of a nested matrix, one row at a time.
+pub fn nested_sum_by_rows(nested: &[Vec<f64>]) -> f64 {
+    let mut sum: f64 = 0.0;
+    for row in nested {
+        for &value in row {
+            sum += value;
+        }
+    }
+    sum
+}
+
+/// Sums every element of a nested matrix, one column at a time: each step
+/// follows a different row's pointer.
+pub fn nested_sum_by_columns(nested: &[Vec<f64>]) -> f64 {
+    let cols: usize = nested.first().map_or(0, Vec::len);
+    let mut sum: f64 = 0.0;
+    for col in 0..cols {
+        for row in nested {
+            sum += row[col];
+        }
+    }
+    sum
+}
+
+/// Sums every element of a Grid, one row at a time (sequential memory).
+pub fn grid_sum_by_rows(grid: &Grid<f64>) -> f64 {
+    let mut sum: f64 = 0.0;
+    for row in 0..grid.rows() {
+        for col in 0..grid.cols() {
+            sum += grid[(row, col)];
+        }
+    }
+    sum
+}
+
+/// Sums every element of a Grid, one column at a time (a stride of `cols`).
+pub fn grid_sum_by_columns(grid: &Grid<f64>) -> f64 {
+    let mut sum: f64 = 0.0;
+    for col in 0..grid.cols() {
+        for row in 0..grid.rows() {
+            sum += grid[(row, col)];
+        }
+    }
+    sum
+}
+
+/// `a * b` with the textbook loop order. The inner loop walks down a column
+/// of `b`, a stride of `b.cols()` per step.
+///
+/// # Panics
+///
+/// Panics if `a.cols() != b.rows()`.
+pub fn multiply_ijk(a: &Grid<f64>, b: &Grid<f64>) -> Grid<f64> {
+    assert_multipliable(a, b);
+    let mut c: Grid<f64> = Grid::filled(a.rows(), b.cols(), 0.0);
+    for i in 0..a.rows() {
+        for j in 0..b.cols() {
+            let mut sum: f64 = 0.0;
+            for k in 0..a.cols() {
+                sum += a[(i, k)] * b[(k, j)];
+            }
+            c[(i, j)] = sum;
+        }
+    }
+    c
+}
+
+/// `a * b` with the k and j loops swapped. The inner loop adds a multiple of
+/// row `k` of `b` into row `i` of the result, so both are read in order.
+///
+/// # Panics
+///
+/// Panics if `a.cols() != b.rows()`.
+pub fn multiply_ikj(a: &Grid<f64>, b: &Grid<f64>) -> Grid<f64> {
+    assert_multipliable(a, b);
+    let mut c: Grid<f64> = Grid::filled(a.rows(), b.cols(), 0.0);
+    for i in 0..a.rows() {
+        for k in 0..a.cols() {
+            let factor: f64 = a[(i, k)];
+            for (cell, &value) in c.row_mut(i).iter_mut().zip(b.row(k)) {
+                *cell += factor * value;
+            }
+        }
+    }
+    c
+}
+
+/// `a * b` by transposing `b` first, so every output cell is the dot
+/// product of two contiguous rows.
+///
+/// # Panics
+///
+/// Panics if `a.cols() != b.rows()`.
+pub fn multiply_transposed(a: &Grid<f64>, b: &Grid<f64>) -> Grid<f64> {
+    assert_multipliable(a, b);
+    let b_t: Grid<f64> = b.transpose();
+    Grid::from_fn(a.rows(), b.cols(), |i, j| {
+        a.row(i).iter().zip(b_t.row(j)).map(|(x, y)| x * y).sum()
+    })
+}
+
+/// `a * b` for nested matrices, in the i-k-j order.
+///
+/// # Panics
+///
+/// Panics if the rows of `a` are longer than `b` has rows.
+pub fn multiply_nested_ikj(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let cols: usize = b.first().map_or(0, Vec::len);
+    let mut c: Vec<Vec<f64>> = vec![vec![0.0; cols]; a.len()];
+    for (a_row, c_row) in a.iter().zip(&mut c) {
+        for (k, &factor) in a_row.iter().enumerate() {
+            for (cell, &value) in c_row.iter_mut().zip(&b[k]) {
+                *cell += factor * value;
+            }
+        }
+    }
+    c
+}
+
+/// `matrix * vector`, one dot product per row.
+///
+/// # Panics
+///
+/// Panics if `vector.len() != matrix.cols()`.
+pub fn multiply_vector(matrix: &Grid<f64>, vector: &[f64]) -> Vec<f64> {
+    assert_eq!(
+        vector.len(),
+        matrix.cols(),
+        "a {}x{} matrix needs a vector of length {}",
+        matrix.rows(),
+        matrix.cols(),
+        matrix.cols()
+    );
+    matrix
+        .iter_rows()
+        .map(|row| row.iter().zip(vector).map(|(x, y)| x * y).sum())
+        .collect()
+}
+
+fn assert_multipliable(a: &Grid<f64>, b: &Grid<f64>) {
+    assert_eq!(
+        a.cols(),
+        b.rows(),
+        "can't multiply a {}x{} matrix by a {}x{} one",
+        a.rows(),
+        a.cols(),
+        b.rows(),
+        b.cols()
+    );
+}
+
+/// A deterministic `rows x cols` matrix with entries in [-1, 1).
+pub fn sample_matrix(rows: usize, cols: usize, seed: u64) -> Grid<f64> {
+    let mut rng: Lcg = Lcg::new(seed);
+    Grid::from_fn(rows, cols, |_, _| rng.below(2_000) as f64 / 1_000.0 - 1.0)
+}
+
+/// Demonstrates the same matrix in both layouts.
+pub fn nested_vs_flat(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Nested vs Flat")?;
+
+    let nested: Vec<Vec<f64>> = vec![
+        vec![1.0, 2.0, 3.0],
+        vec![4.0, 5.0, 6.0],
+        vec![7.0, 8.0, 9.0],
+    ];
+    let grid: Grid<f64> = grid_from_nested(&nested).expect("the rows have equal length");
+
+    // Both read (1, 2) - through a row pointer, or at 1 * 3 + 2
+    writeln!(out, "nested[1][2] = {}", nested[1][2])?;
+    writeln!(out, "grid[(1, 2)] = {}", grid[(1, 2)])?;
+    writeln!(out, "Flat storage: {:?}", grid.as_slice())?;
+
+    // One outer Vec of row headers plus one buffer per row, against one
+    // buffer for everything
+    let rows: usize = 1_000;
+    let cols: usize = 1_000;
+    writeln!(out, "A {}x{} matrix of f64:", rows, cols)?;
+    writeln!(
+        out,
+        "  Vec<Vec<f64>>: {} allocations, {} bytes",
+        rows + 1,
+        rows * size_of::<Vec<f64>>() + rows * cols * size_of::<f64>()
+    )?;
+    writeln!(
+        out,
+        "  Grid<f64>:     1 allocation,  {} bytes",
+        rows * cols * size_of::<f64>()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates that nothing stops a nested matrix from going ragged.
+pub fn jagged_rows(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Jagged Rows")?;
+
+    let mut nested: Vec<Vec<f64>> = vec![vec![0.0; 3]; 3];
+    writeln!(
+        out,
+        "Start rectangular: {:?}",
+        grid_from_nested(&nested).map(|g| (g.rows(), g.cols()))
+    )?;
+
+    // Any row can be pushed to independently
+    nested[1].push(1.0);
+    let lengths: Vec<usize> = nested.iter().map(Vec::len).collect();
+    writeln!(out, "After nested[1].push(1.0): row lengths {:?}", lengths)?;
+    writeln!(
+        out,
+        "As a Grid: {:?}",
+        grid_from_nested(&nested).map(|g| (g.rows(), g.cols()))
+    )?;
+
+    // Code that trusts nested[0].len() as the width now skips a value or,
+    // going the other way, indexes out of bounds
+    let width: usize = nested[0].len();
+    let visible: f64 = nested
+        .iter()
+        .map(|row| row[..width].iter().sum::<f64>())
+        .sum();
+    writeln!(
+        out,
+        "Sum over the first row's width: {} (the pushed 1.0 is missed)",
+        visible
+    )?;
+
+    // A Grid's shape is fixed at construction; rows can change values only
+    let mut grid: Grid<f64> = Grid::filled(3, 3, 0.0);
+    grid.row_mut(1).fill(1.0);
+    writeln!(out, "Grid row 1 after fill: {:?}", grid.row(1))?;
+
+    Ok(())
+}
+
+/// Demonstrates row vs column traversal for both layouts.
+///
+/// All four loops add the same 4M numbers. The Grid pays a stride of 16 KB
+/// per column step; the nested matrix pays a pointer chase to another row.
+pub fn row_vs_column_sums(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Row vs Column Sums")?;
+
+    let size: usize = 2_048;
+    let grid: Grid<f64> = Grid::from_fn(size, size, |row, col| ((row ^ col) & 0xff) as f64);
+    let nested: Vec<Vec<f64>> = nested_from_grid(&grid);
+    writeln!(
+        out,
+        "{}x{} matrix of f64: {} MB",
+        size,
+        size,
+        size * size * 8 / 1_000_000
+    )?;
+
+    let timings: [(&str, f64, Duration); 4] = [
+        time("Grid by rows:      ", || grid_sum_by_rows(&grid)),
+        time("Grid by columns:   ", || grid_sum_by_columns(&grid)),
+        time("Nested by rows:    ", || nested_sum_by_rows(&nested)),
+        time("Nested by columns: ", || nested_sum_by_columns(&nested)),
+    ];
+    for (label, _, elapsed) in &timings {
+        writeln!(out, "  {} {:?}", label, elapsed)?;
+    }
+    // Every value is a small integer, so the order of additions can't round
+    writeln!(
+        out,
+        "  All four sums equal: {}",
+        timings.iter().all(|(_, sum, _)| *sum == timings[0].1)
+    )?;
+    writeln!(
+        out,
+        "  Grid columns / rows:   {:.1}x",
+        timings[1].2.as_secs_f64() / timings[0].2.as_secs_f64()
+    )?;
+    writeln!(
+        out,
+        "  Nested columns / rows: {:.1}x",
+        timings[3].2.as_secs_f64() / timings[2].2.as_secs_f64()
+    )?;
+
+    Ok(())
+}
+
+fn time(label: &str, f: impl FnOnce() -> f64) -> (&str, f64, Duration) {
+    let start: Instant = Instant::now();
+    let result: f64 = f();
+    (label, result, start.elapsed())
+}
+
+/// Demonstrates that loop order changes the memory access pattern of a
+/// matrix multiply, not its result.
+pub fn multiply_loop_orders(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Multiply Loop Orders")?;
+
+    let a: Grid<f64> = Grid::from_vec(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let b: Grid<f64> = Grid::from_vec(3, 2, vec![7.0, 8.0, 9.0, 10.0, 11.0, 12.0]);
+    let c: Grid<f64> = multiply_ikj(&a, &b);
+    writeln!(out, "[[1 2 3] [4 5 6]] x [[7 8] [9 10] [11 12]]:")?;
+    for row in c.iter_rows() {
+        writeln!(out, "  {:?}", row)?;
+    }
+    writeln!(
+        out,
+        "i-j-k, i-k-j and transposed agree: {}",
+        multiply_ijk(&a, &b) == c && multiply_transposed(&a, &b) == c
+    )?;
+
+    // Large enough that a column of B no longer fits in L1
+    let size: usize = 256;
+    let a: Grid<f64> = sample_matrix(size, size, 1);
+    let b: Grid<f64> = sample_matrix(size, size, 2);
+    writeln!(out, "{}x{} by {}x{}:", size, size, size, size)?;
+
+    let start: Instant = Instant::now();
+    let ijk: Grid<f64> = multiply_ijk(&a, &b);
+    let ijk_time: Duration = start.elapsed();
+
+    let start: Instant = Instant::now();
+    let ikj: Grid<f64> = multiply_ikj(&a, &b);
+    let ikj_time: Duration = start.elapsed();
+
+    let start: Instant = Instant::now();
+    let transposed: Grid<f64> = multiply_transposed(&a, &b);
+    let transposed_time: Duration = start.elapsed();
+
+    writeln!(out, "  i-j-k (column of B per cell): {:?}", ijk_time)?;
+    writeln!(out, "  i-k-j (rows of B and C):      {:?}", ikj_time)?;
+    writeln!(out, "  Transpose B, then dot rows:   {:?}", transposed_time)?;
+
+    // Summation order differs, so compare with a tolerance, not ==
+    let close = |x: &Grid<f64>, y: &Grid<f64>| {
+        x.as_slice()
+            .iter()
+            .zip(y.as_slice())
+            .all(|(p, q)| (p - q).abs() < 1e-9)
+    };
+    writeln!(
+        out,
+        "  Results agree to 1e-9: {}",
+        close(&ijk, &ikj) && close(&ijk, &transposed)
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: a Markov chain as a transition matrix.
+///
+/// Row `i` holds the probabilities of moving from state `i` to each state.
+/// With the matrix transposed once, every step is one row-major
+/// matrix-vector product: the new probability of state `j` is row `j` of
+/// the transpose dotted with the current distribution.
+pub fn practical_markov_chain(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Markov Chain")?;
+
+    let states: [&str; 3] = ["sunny", "cloudy", "rainy"];
+    let transitions: Grid<f64> = Grid::from_vec(
+        3,
+        3,
+        vec![
+            0.7, 0.2, 0.1, //
+            0.3, 0.4, 0.3, //
+            0.2, 0.4, 0.4,
+        ],
+    );
+    let incoming: Grid<f64> = transitions.transpose();
+
+    let mut distribution: Vec<f64> = vec![1.0, 0.0, 0.0];
+    for day in 0..=20 {
+        if matches!(day, 0 | 1 | 2 | 5 | 20) {
+            let shown: Vec<String> = states
+                .iter()
+                .zip(&distribution)
+                .map(|(state, p)| format!("{} {:.3}", state, p))
+                .collect();
+            writeln!(out, "  Day {:>2}: {}", day, shown.join(", "))?;
+        }
+        distribution = multiply_vector(&incoming, &distribution);
+    }
+
+    // By day 20 the distribution no longer depends on the starting state
+    writeln!(
+        out,
+        "Probabilities still sum to 1: {}",
+        (distribution.iter().sum::<f64>() - 1.0).abs() < 1e-12
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,604 @@
+// Nested maps of string keys - config files, JSON documents, feature flag
+// trees - are usually combined with a recursive merge: where both sides hold
+// a map, merge the two maps key by key; anywhere else, one side has to win.
+//
+// JSON Merge Patch (RFC 7396) is the standard version of that rule:
+//
+//   patch is a map     merge it key by key into the target (a non-map
+//                      target is replaced by an empty map first)
+//   patch value null   remove the key from the target
+//   anything else      replace the target value, lists included
+//
+// "The patch wins" is only one conflict policy. Filling in defaults wants the
+// opposite (the existing value wins), and merging config fragments owned by
+// different teams wants neither: two different values for the same key is
+// an error, reported with its path.
+//
+// When the documents agree on shape - a path holds a map in all of them or in
+// none - and contain no nulls, the patch-wins merge is associative:
+// merge(merge(a, b), c) == merge(a, merge(b, c)), so a stack of layers can be
+// folded in any grouping. Both conditions matter. A patch can say "delete"
+// but not "replace this map wholesale", so a null followed by a map can't be
+// combined into one patch; and an empty map replaces a scalar but leaves a
+// map alone, so regrouping changes which of the two it meets.
+
+use crate::map_diff_examples::{MapDiff, diff_maps};
+use demo_framework::section;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "merge_patch_basics",
+        "RFC 7396: nested maps merge, null deletes, everything else replaces",
+        merge_patch_basics,
+    )?;
+
+    section(
+        out,
+        "conflict_policies",
+        "Patch wins, target wins, or reject conflicting values",
+        conflict_policies,
+    )?;
+
+    section(
+        out,
+        "composing_merges",
+        "Same-shape merges regroup freely; nulls and shape changes do not",
+        composing_merges,
+    )?;
+
+    section(
+        out,
+        "practical_layered_config",
+        "Defaults, config file, environment and command line in one map",
+        practical_layered_config,
+    )?;
+
+    Ok(())
+}
+
+/// A JSON-like value. Maps are `BTreeMap`s, so printing is deterministic.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Str(String),
+    List(Vec<Value>),
+    Map(Map),
+}
+
+pub type Map = BTreeMap<String, Value>;
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_string())
+    }
+}
+
+/// Builds a `Value::Map` from literal entries.
+pub fn map<const N: usize>(entries: [(&str, Value); N]) -> Value {
+    Value::Map(
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+    )
+}
+
+/// Compact JSON-style output.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Str(value) => write!(f, "{:?}", value),
+            Value::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Value::Map(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// What to do when the target and the patch both hold a value at the same
+/// path and at least one of them isn't a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// JSON Merge Patch: the patch value replaces the target value.
+    PatchWins,
+    /// Only fill in what the target is missing. Nulls in the patch are
+    /// ignored: there is nothing to fill in.
+    TargetWins,
+    /// Two different values at one path are an error. Equal values and
+    /// nulls (explicit deletions) are fine.
+    Reject,
+}
+
+/// Two different values at one path, under [`ConflictPolicy::Reject`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    /// Dotted path of the key, empty for the root.
+    pub path: String,
+    pub target: Value,
+    pub patch: Value,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path: &str = if self.path.is_empty() {
+            "<root>"
+        } else {
+            &self.path
+        };
+        write!(f, "conflict at {}: {} vs {}", path, self.target, self.patch)
+    }
+}
+
+impl std::error::Error for MergeConflict {}
+
+/// Applies `patch` to `target` with RFC 7396 JSON Merge Patch semantics.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Map(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !matches!(target, Value::Map(_)) {
+        *target = Value::Map(Map::new());
+    }
+    let Value::Map(target) = target else {
+        unreachable!("replaced by a map above");
+    };
+    for (key, value) in patch {
+        if *value == Value::Null {
+            target.remove(key);
+        } else {
+            // A new key starts as null, so a map value is merged into an
+            // empty map and loses its own nulls
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// Merges `patch` into `target` under a conflict policy. With
+/// [`ConflictPolicy::Reject`], a conflict leaves `target` unchanged.
+pub fn merge(
+    target: &mut Value,
+    patch: &Value,
+    policy: ConflictPolicy,
+) -> Result<(), MergeConflict> {
+    match policy {
+        ConflictPolicy::PatchWins => merge_patch(target, patch),
+        ConflictPolicy::TargetWins => fill_missing(target, patch),
+        ConflictPolicy::Reject => {
+            // Merge into a copy so a conflict halfway through doesn't leave
+            // a half-merged target behind
+            let mut merged: Value = target.clone();
+            merge_strict(&mut merged, patch, String::new())?;
+            *target = merged;
+        }
+    }
+    Ok(())
+}
+
+fn fill_missing(target: &mut Value, patch: &Value) {
+    if *target == Value::Null {
+        merge_patch(target, patch);
+        return;
+    }
+    let (Value::Map(target), Value::Map(patch)) = (target, patch) else {
+        return;
+    };
+    for (key, value) in patch {
+        match target.get_mut(key) {
+            Some(existing) => fill_missing(existing, value),
+            None if *value == Value::Null => {}
+            None => {
+                let mut fresh: Value = Value::Null;
+                merge_patch(&mut fresh, value);
+                target.insert(key.clone(), fresh);
+            }
+        }
+    }
+}
+
+fn merge_strict(target: &mut Value, patch: &Value, path: String) -> Result<(), MergeConflict> {
+    if *target == Value::Null {
+        merge_patch(target, patch);
+        return Ok(());
+    }
+    match (&mut *target, patch) {
+        (Value::Map(target), Value::Map(patch)) => {
+            for (key, value) in patch {
+                if *value == Value::Null {
+                    target.remove(key);
+                    continue;
+                }
+                let child: String = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                merge_strict(
+                    target.entry(key.clone()).or_insert(Value::Null),
+                    value,
+                    child,
+                )?;
+            }
+            Ok(())
+        }
+        (existing, patch) if existing == patch => Ok(()),
+        (existing, patch) => Err(MergeConflict {
+            path,
+            target: existing.clone(),
+            patch: patch.clone(),
+        }),
+    }
+}
+
+/// The smallest merge patch that turns `old` into `new`. Exact unless `new`
+/// contains nulls, which a merge patch can't express.
+pub fn create_patch(old: &Value, new: &Value) -> Value {
+    let (Value::Map(old), Value::Map(new)) = (old, new) else {
+        return new.clone();
+    };
+    // The BTreeMap diff walks both maps in key order once
+    let diff: MapDiff<String, Value> = diff_maps(old, new);
+    let mut patch: Map = Map::new();
+    for (key, _) in diff.removed {
+        patch.insert(key.clone(), Value::Null);
+    }
+    for (key, value) in diff.added {
+        patch.insert(key.clone(), value.clone());
+    }
+    for (key, old_value, new_value) in diff.changed {
+        patch.insert(key.clone(), create_patch(old_value, new_value));
+    }
+    Value::Map(patch)
+}
+
+/// Every non-map value (and every empty map) with its dotted path, in key
+/// order.
+pub fn leaves(value: &Value) -> Vec<(String, &Value)> {
+    fn walk<'a>(value: &'a Value, path: String, out: &mut Vec<(String, &'a Value)>) {
+        match value {
+            Value::Map(entries) if !entries.is_empty() => {
+                for (key, child) in entries {
+                    let child_path: String = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    walk(child, child_path, out);
+                }
+            }
+            _ => out.push((path, value)),
+        }
+    }
+    let mut out: Vec<(String, &Value)> = Vec::new();
+    walk(value, String::new(), &mut out);
+    out
+}
+
+/// Demonstrates the RFC 7396 rules on the RFC's own example.
+pub fn merge_patch_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Merge Patch Basics")?;
+
+    let mut document: Value = map([
+        ("title", "Goodbye!".into()),
+        (
+            "author",
+            map([("givenName", "John".into()), ("familyName", "Doe".into())]),
+        ),
+        ("tags", Value::List(vec!["example".into(), "sample".into()])),
+        ("content", "This will be unchanged".into()),
+    ]);
+    let patch: Value = map([
+        ("title", "Hello!".into()),
+        ("phoneNumber", "+01-123-456-7890".into()),
+        ("author", map([("familyName", Value::Null)])),
+        ("tags", Value::List(vec!["example".into()])),
+    ]);
+    writeln!(out, "target: {}", document)?;
+    writeln!(out, "patch:  {}", patch)?;
+    merge_patch(&mut document, &patch);
+    writeln!(out, "result: {}", document)?;
+    writeln!(out, "  title:          replaced")?;
+    writeln!(out, "  phoneNumber:    added")?;
+    writeln!(out, "  author:         merged; familyName deleted by null")?;
+    writeln!(
+        out,
+        "  tags:           the list replaced, not merged element-wise"
+    )?;
+    writeln!(out, "  content:        not in the patch, untouched")?;
+
+    // A patch that isn't a map replaces the whole target
+    let mut replaced: Value = document.clone();
+    merge_patch(&mut replaced, &Value::List(vec![Value::Int(1)]));
+    writeln!(out, "\nPatching with [1] gives {}", replaced)?;
+
+    // Applying the same patch twice changes nothing the second time
+    let mut twice: Value = document.clone();
+    merge_patch(&mut twice, &patch);
+    writeln!(
+        out,
+        "Applying the patch again is a no-op: {}",
+        twice == document
+    )?;
+
+    // Null can only mean "delete", so a merge patch can't set a key to null
+    let mut with_null: Value = map([("a", Value::Int(1))]);
+    merge_patch(&mut with_null, &map([("a", Value::Null)]));
+    writeln!(
+        out,
+        "Setting \"a\" to null removes it instead: {}",
+        with_null
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the three policies on one pair of maps.
+pub fn conflict_policies(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Conflict Policies")?;
+
+    let base: Value = map([
+        ("name", "checkout".into()),
+        (
+            "limits",
+            map([("rps", Value::Int(500)), ("burst", Value::Int(50))]),
+        ),
+        ("owner", "payments".into()),
+    ]);
+    let patch: Value = map([
+        (
+            "limits",
+            map([("rps", Value::Int(800)), ("timeout_ms", Value::Int(250))]),
+        ),
+        ("owner", Value::Null),
+        ("region", "eu-west".into()),
+    ]);
+    writeln!(out, "target: {}", base)?;
+    writeln!(out, "patch:  {}", patch)?;
+
+    for policy in [ConflictPolicy::PatchWins, ConflictPolicy::TargetWins] {
+        let mut merged: Value = base.clone();
+        merge(&mut merged, &patch, policy).expect("only Reject fails");
+        writeln!(out, "\n{:?}:\n  {}", policy, merged)?;
+    }
+
+    let mut strict: Value = base.clone();
+    match merge(&mut strict, &patch, ConflictPolicy::Reject) {
+        Ok(()) => writeln!(out, "\nReject:\n  {}", strict)?,
+        Err(conflict) => writeln!(out, "\nReject:\n  error: {}", conflict)?,
+    }
+    writeln!(
+        out,
+        "  target unchanged after the error: {}",
+        strict == base
+    )?;
+
+    // Drop the conflicting value and the same patch goes through
+    let mut agreed_patch: Value = patch.clone();
+    if let Value::Map(entries) = &mut agreed_patch {
+        entries.insert(
+            "limits".to_string(),
+            map([("rps", Value::Int(500)), ("timeout_ms", Value::Int(250))]),
+        );
+    }
+    merge(&mut strict, &agreed_patch, ConflictPolicy::Reject).expect("no conflicts left");
+    writeln!(out, "  with limits.rps agreeing at 500:\n  {}", strict)?;
+
+    writeln!(out, "\nPatchWins:  updates, overrides, RFC 7396")?;
+    writeln!(
+        out,
+        "TargetWins: filling in defaults under an existing value"
+    )?;
+    writeln!(
+        out,
+        "Reject:     combining fragments no one is allowed to override"
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates which merges can be regrouped and which can't.
+pub fn composing_merges(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Composing Merges")?;
+
+    let a: Value = map([
+        (
+            "db",
+            map([("host", "localhost".into()), ("port", Value::Int(5432))]),
+        ),
+        ("debug", true.into()),
+    ]);
+    let b: Value = map([("db", map([("host", "db.internal".into())]))]);
+    let c: Value = map([
+        ("db", map([("pool", Value::Int(20))])),
+        ("debug", false.into()),
+    ]);
+
+    let mut left: Value = a.clone();
+    merge_patch(&mut left, &b);
+    merge_patch(&mut left, &c);
+    let mut bc: Value = b.clone();
+    merge_patch(&mut bc, &c);
+    let mut right: Value = a.clone();
+    merge_patch(&mut right, &bc);
+    writeln!(out, "(a + b) + c = {}", left)?;
+    writeln!(out, "a + (b + c) = {}", right)?;
+    writeln!(
+        out,
+        "Equal: {} - same shapes, no nulls: layers can be pre-merged",
+        left == right
+    )?;
+
+    let x: Value = map([("port", map([("http", Value::Int(80))]))]);
+    let y: Value = map([("port", Value::Int(8080))]);
+    let z: Value = map([("port", map([]))]);
+    let mut xy_z: Value = x.clone();
+    merge_patch(&mut xy_z, &y);
+    merge_patch(&mut xy_z, &z);
+    let mut yz: Value = y.clone();
+    merge_patch(&mut yz, &z);
+    let mut x_yz: Value = x.clone();
+    merge_patch(&mut x_yz, &yz);
+    writeln!(out, "\nx = {}, y = {}, z = {}", x, y, z)?;
+    writeln!(out, "(x + y) + z = {}", xy_z)?;
+    writeln!(out, "x + (y + z) = {}", x_yz)?;
+    writeln!(
+        out,
+        "Equal: {} - \"port\" is a map in x and z but a number in y",
+        xy_z == x_yz
+    )?;
+
+    // p1 deletes "db", p2 then sets db.pool; merging p2 into p1 keeps only
+    // db.pool, but as a patch that merges into the target's db instead of
+    // replacing it
+    let p1: Value = map([("db", Value::Null)]);
+    let p2: Value = map([("db", map([("pool", Value::Int(5))]))]);
+    let mut sequential: Value = a.clone();
+    merge_patch(&mut sequential, &p1);
+    merge_patch(&mut sequential, &p2);
+    let mut combined_patch: Value = p1.clone();
+    merge_patch(&mut combined_patch, &p2);
+    let mut combined: Value = a.clone();
+    merge_patch(&mut combined, &combined_patch);
+    writeln!(out, "\np1 = {}, p2 = {}", p1, p2)?;
+    writeln!(out, "apply p1, then p2:       {}", sequential)?;
+    writeln!(out, "apply (p1 + p2) at once: {}", combined)?;
+    writeln!(
+        out,
+        "Equal: {} - \"delete, then set\" needs two patches",
+        sequential == combined
+    )?;
+
+    // A patch computed from two versions always reproduces the new one
+    let patch: Value = create_patch(&a, &left);
+    let mut rebuilt: Value = a.clone();
+    merge_patch(&mut rebuilt, &patch);
+    writeln!(out, "\ncreate_patch(a, (a + b) + c) = {}", patch)?;
+    writeln!(out, "Applied to a, gives (a + b) + c: {}", rebuilt == left)?;
+
+    Ok(())
+}
+
+/// Demonstrates layered configuration with per-key provenance.
+pub fn practical_layered_config(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Layered Config")?;
+
+    let layers: [(&str, Value); 4] = [
+        (
+            "defaults",
+            map([
+                (
+                    "server",
+                    map([
+                        ("host", "127.0.0.1".into()),
+                        ("port", Value::Int(8080)),
+                        ("workers", Value::Int(4)),
+                    ]),
+                ),
+                (
+                    "log",
+                    map([("level", "info".into()), ("json", false.into())]),
+                ),
+                ("features", map([("beta_search", false.into())])),
+            ]),
+        ),
+        (
+            "config.toml",
+            map([
+                ("server", map([("host", "0.0.0.0".into())])),
+                ("log", map([("json", true.into())])),
+                (
+                    "features",
+                    map([("beta_search", true.into()), ("new_checkout", true.into())]),
+                ),
+            ]),
+        ),
+        (
+            "environment",
+            map([("server", map([("workers", Value::Int(16))]))]),
+        ),
+        (
+            "command line",
+            map([
+                ("log", map([("level", "debug".into())])),
+                ("features", map([("new_checkout", Value::Null)])),
+            ]),
+        ),
+    ];
+
+    let mut config: Value = Value::Map(Map::new());
+    for (name, layer) in &layers {
+        merge_patch(&mut config, layer);
+        writeln!(out, "after {:<13} {}", format!("{}:", name), config)?;
+    }
+
+    // The source of a value is the last layer that set that path
+    writeln!(out, "\nFinal config:")?;
+    for (path, value) in leaves(&config) {
+        let source: &str = layers
+            .iter()
+            .rev()
+            .find(|(_, layer)| leaves(layer).iter().any(|(p, _)| *p == path))
+            .map_or("?", |(name, _)| name);
+        writeln!(
+            out,
+            "  {:<22} = {:<12} ({})",
+            path,
+            value.to_string(),
+            source
+        )?;
+    }
+    writeln!(
+        out,
+        "features.new_checkout: removed by the command line's null"
+    )?;
+
+    // A plugin may add keys but must not silently change anyone else's
+    let plugin: Value = map([("server", map([("port", Value::Int(9090))]))]);
+    if let Err(conflict) = merge(&mut config, &plugin, ConflictPolicy::Reject) {
+        writeln!(out, "\nPlugin fragment rejected: {}", conflict)?;
+    }
+    // ...and a user's settings file is completed with defaults underneath
+    let mut user: Value = map([("log", map([("level", "warn".into())]))]);
+    merge(&mut user, &layers[0].1, ConflictPolicy::TargetWins).expect("only Reject fails");
+    writeln!(out, "User settings completed with defaults: {}", user)?;
+
+    Ok(())
+}
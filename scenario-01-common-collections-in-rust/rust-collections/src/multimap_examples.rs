@@ -0,0 +1,526 @@
+// A multimap maps one key to many values: a tag to the documents carrying
+// it, a user to their sessions, a word to the lines it appears on. std has
+// no multimap type, but the usual building block is a HashMap whose values
+// are lists:
+//
+//   HashMap<K, Vec<V>>
+//   ┌────────┬──────────────────────────┐
+//   │ "rust" │ ──► heap: [1, 4, 7]      │   one allocation per key
+//   │ "db"   │ ──► heap: [2]            │
+//   └────────┴──────────────────────────┘
+//
+//   HashMap<K, SmallVec<[V; 4]>>
+//   ┌────────┬──────────────────────────┐
+//   │ "rust" │ [1, 4, 7, _] inline      │   no allocation up to 4 values
+//   │ "db"   │ [2, _, _, _] inline      │
+//   └────────┴──────────────────────────┘
+//
+// MultiMap<K, V, L> wraps either one, with L the per-key list. The
+// ValueList trait is all it needs from a list, so Vec and SmallVec plug in
+// the same way.
+//
+// The rule that keeps a multimap honest: a key with no values is removed,
+// so `key_count()` only counts keys that have something to return.
+
+use demo_framework::section;
+use smallvec::SmallVec;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::mem::size_of;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "multimap_basics",
+        "insert, get_all and remove_value on a MultiMap",
+        multimap_basics,
+    )?;
+
+    section(
+        out,
+        "by_hand_with_entry",
+        "The same operations on a plain HashMap<K, Vec<V>>",
+        by_hand_with_entry,
+    )?;
+
+    section(
+        out,
+        "vec_vs_smallvec_backing",
+        "Vec and SmallVec value lists: size and spills",
+        vec_vs_smallvec_backing,
+    )?;
+
+    section(
+        out,
+        "practical_tag_index",
+        "Practical demo: Finding documents by tag",
+        practical_tag_index,
+    )?;
+
+    Ok(())
+}
+
+/// The per-key list of values a [`MultiMap`] stores.
+pub trait ValueList<V>: Default {
+    fn push(&mut self, value: V);
+    fn as_slice(&self) -> &[V];
+    fn remove(&mut self, index: usize) -> V;
+    fn retain(&mut self, keep: impl FnMut(&V) -> bool);
+    /// Whether the values have moved to a separate heap allocation.
+    fn is_on_heap(&self) -> bool;
+}
+
+impl<V> ValueList<V> for Vec<V> {
+    fn push(&mut self, value: V) {
+        Vec::push(self, value);
+    }
+
+    fn as_slice(&self) -> &[V] {
+        self
+    }
+
+    fn remove(&mut self, index: usize) -> V {
+        Vec::remove(self, index)
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&V) -> bool) {
+        Vec::retain(self, |value| keep(value));
+    }
+
+    fn is_on_heap(&self) -> bool {
+        self.capacity() > 0
+    }
+}
+
+impl<V, const N: usize> ValueList<V> for SmallVec<[V; N]> {
+    fn push(&mut self, value: V) {
+        SmallVec::push(self, value);
+    }
+
+    fn as_slice(&self) -> &[V] {
+        self
+    }
+
+    fn remove(&mut self, index: usize) -> V {
+        SmallVec::remove(self, index)
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&V) -> bool) {
+        SmallVec::retain(self, |value| keep(value));
+    }
+
+    fn is_on_heap(&self) -> bool {
+        self.spilled()
+    }
+}
+
+/// A SmallVec-backed multimap: up to 4 values per key without allocating.
+pub type SmallMultiMap<K, V> = MultiMap<K, V, SmallVec<[V; 4]>>;
+
+/// One key, many values, kept in insertion order per key. Duplicate
+/// values are allowed; a key whose last value is removed goes away.
+#[derive(Clone, Debug)]
+pub struct MultiMap<K, V, L = Vec<V>> {
+    map: HashMap<K, L>,
+    len: usize,
+    values: PhantomData<V>,
+}
+
+impl<K: Hash + Eq, V, L: ValueList<V>> MultiMap<K, V, L> {
+    pub fn new() -> Self {
+        MultiMap {
+            map: HashMap::new(),
+            len: 0,
+            values: PhantomData,
+        }
+    }
+
+    /// Number of values, counting every key's.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of keys with at least one value.
+    pub fn key_count(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Appends `value` to `key`'s values.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.map.entry(key).or_default().push(value);
+        self.len += 1;
+    }
+
+    /// Every value of `key`, oldest first; empty if there are none.
+    pub fn get_all<Q>(&self, key: &Q) -> &[V]
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).map_or(&[], ValueList::as_slice)
+    }
+
+    /// The oldest value of `key`.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_all(key).first()
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Removes the first `value` under `key`; `false` if it wasn't there.
+    pub fn remove_value<Q>(&mut self, key: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: PartialEq,
+    {
+        let Some(values) = self.map.get_mut(key) else {
+            return false;
+        };
+        let Some(index) = values.as_slice().iter().position(|v| v == value) else {
+            return false;
+        };
+        values.remove(index);
+        if values.as_slice().is_empty() {
+            self.map.remove(key);
+        }
+        self.len -= 1;
+        true
+    }
+
+    /// Removes `key` and returns all its values.
+    pub fn remove_all<Q>(&mut self, key: &Q) -> Option<L>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let values: L = self.map.remove(key)?;
+        self.len -= values.as_slice().len();
+        Some(values)
+    }
+
+    /// Keeps only the values `keep` accepts, dropping keys left empty.
+    pub fn retain(&mut self, mut keep: impl FnMut(&K, &V) -> bool) {
+        let mut len: usize = 0;
+        self.map.retain(|key, values| {
+            values.retain(|value| keep(key, value));
+            len += values.as_slice().len();
+            !values.as_slice().is_empty()
+        });
+        self.len = len;
+    }
+
+    /// Each key with its values, keys in no particular order.
+    pub fn iter_all(&self) -> impl Iterator<Item = (&K, &[V])> {
+        self.map
+            .iter()
+            .map(|(key, values)| (key, values.as_slice()))
+    }
+
+    /// Every `(key, value)` pair.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.iter_all()
+            .flat_map(|(key, values)| values.iter().map(move |value| (key, value)))
+    }
+
+    /// How many keys keep their values in a separate heap allocation.
+    pub fn keys_on_heap(&self) -> usize {
+        self.map
+            .values()
+            .filter(|values| values.is_on_heap())
+            .count()
+    }
+}
+
+impl<K: Hash + Eq, V, L: ValueList<V>> Default for MultiMap<K, V, L> {
+    fn default() -> Self {
+        MultiMap::new()
+    }
+}
+
+impl<K: Hash + Eq, V, L: ValueList<V>> FromIterator<(K, V)> for MultiMap<K, V, L> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: MultiMap<K, V, L> = MultiMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Hash + Eq, V, L: ValueList<V>> Extend<(K, V)> for MultiMap<K, V, L> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+/// `key: [values]` lines, keys sorted.
+fn show<K, V, L>(map: &MultiMap<K, V, L>) -> Vec<String>
+where
+    K: Hash + Eq + Ord + std::fmt::Debug,
+    V: std::fmt::Debug,
+    L: ValueList<V>,
+{
+    let mut entries: Vec<(&K, &[V])> = map.iter_all().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+        .into_iter()
+        .map(|(key, values)| format!("{:?}: {:?}", key, values))
+        .collect()
+}
+
+/// Demonstrates the basic MultiMap operations.
+pub fn multimap_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "MultiMap Basics")?;
+
+    let mut sessions: MultiMap<&str, u32> = MultiMap::new();
+    for (user, session) in [
+        ("ana", 11),
+        ("bo", 12),
+        ("ana", 13),
+        ("cy", 14),
+        ("ana", 15),
+    ] {
+        sessions.insert(user, session);
+    }
+    writeln!(out, "Sessions per user:")?;
+    for line in show(&sessions) {
+        writeln!(out, "  {}", line)?;
+    }
+    writeln!(
+        out,
+        "len() = {} values under key_count() = {} keys",
+        sessions.len(),
+        sessions.key_count()
+    )?;
+
+    writeln!(out, "\nget_all(\"ana\") = {:?}", sessions.get_all("ana"))?;
+    writeln!(out, "get(\"ana\") = {:?} (the oldest)", sessions.get("ana"))?;
+    writeln!(
+        out,
+        "get_all(\"dee\") = {:?} (an empty slice, not None)",
+        sessions.get_all("dee")
+    )?;
+
+    writeln!(
+        out,
+        "\nremove_value(\"ana\", &13) -> {}",
+        sessions.remove_value("ana", &13)
+    )?;
+    writeln!(
+        out,
+        "remove_value(\"ana\", &99) -> {}",
+        sessions.remove_value("ana", &99)
+    )?;
+    writeln!(
+        out,
+        "remove_value(\"bo\", &12) -> {}, and bo's key is gone: contains_key(\"bo\") = {}",
+        sessions.remove_value("bo", &12),
+        sessions.contains_key("bo")
+    )?;
+    writeln!(
+        out,
+        "remove_all(\"ana\") -> {:?}",
+        sessions.remove_all("ana")
+    )?;
+    writeln!(
+        out,
+        "Left: {}, len() = {}",
+        show(&sessions).join(", "),
+        sessions.len()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the same operations without a wrapper type.
+pub fn by_hand_with_entry(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "By Hand with the Entry API")?;
+
+    let mut sessions: HashMap<&str, Vec<u32>> = HashMap::new();
+    for (user, session) in [("ana", 11), ("bo", 12), ("ana", 13)] {
+        // insert: one hash lookup, creating the Vec on first use
+        sessions.entry(user).or_default().push(session);
+    }
+    let mut users: Vec<(&&str, &Vec<u32>)> = sessions.iter().collect();
+    users.sort();
+    writeln!(out, "sessions: {:?}", users)?;
+
+    // get_all: a missing key needs mapping to an empty slice
+    let none: &[u32] = sessions.get("dee").map_or(&[], Vec::as_slice);
+    writeln!(out, "get_all(\"dee\") by hand: {:?}", none)?;
+
+    // remove_value: the step that is easy to forget is dropping the key
+    if let Entry::Occupied(mut entry) = sessions.entry("bo") {
+        entry.get_mut().retain(|&session| session != 12);
+        if entry.get().is_empty() {
+            entry.remove();
+        }
+    }
+    writeln!(
+        out,
+        "After removing bo's only session: contains_key(\"bo\") = {}",
+        sessions.contains_key("bo")
+    )?;
+
+    // Forgetting it leaves a key with nothing behind it
+    let mut careless: HashMap<&str, Vec<u32>> = HashMap::new();
+    careless.entry("bo").or_default().push(12);
+    if let Some(list) = careless.get_mut("bo") {
+        list.retain(|&session| session != 12);
+    }
+    writeln!(
+        out,
+        "Without that step: len() = {} key, holding {:?}",
+        careless.len(),
+        careless.get("bo")
+    )?;
+    writeln!(
+        out,
+        "MultiMap does both steps in remove_value, so key_count() never counts empty lists"
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the two backings side by side.
+pub fn vec_vs_smallvec_backing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Vec vs SmallVec Backing")?;
+
+    writeln!(
+        out,
+        "size_of::<Vec<u32>>() = {} bytes, size_of::<SmallVec<[u32; 4]>>() = {} bytes",
+        size_of::<Vec<u32>>(),
+        size_of::<SmallVec<[u32; 4]>>()
+    )?;
+
+    // Most keys have a few values, a handful have many
+    let pairs: Vec<(u32, u32)> = (0..10_000u32)
+        .map(|i| {
+            let key: u32 = if i % 100 == 0 { i % 7 } else { i % 3_000 };
+            (key, i)
+        })
+        .collect();
+    let vec_backed: MultiMap<u32, u32> = pairs.iter().copied().collect();
+    let small_backed: SmallMultiMap<u32, u32> = pairs.iter().copied().collect();
+
+    let mut fan_out: Vec<usize> = vec_backed
+        .iter_all()
+        .map(|(_, values)| values.len())
+        .collect();
+    fan_out.sort();
+    writeln!(
+        out,
+        "\n{} values under {} keys: median {} per key, largest {}",
+        vec_backed.len(),
+        vec_backed.key_count(),
+        fan_out[fan_out.len() / 2],
+        fan_out[fan_out.len() - 1]
+    )?;
+    writeln!(
+        out,
+        "Keys with a heap allocation: Vec {}, SmallVec<[_; 4]> {}",
+        vec_backed.keys_on_heap(),
+        small_backed.keys_on_heap()
+    )?;
+
+    let same: bool = vec_backed
+        .iter_all()
+        .all(|(key, values)| small_backed.get_all(key) == values);
+    writeln!(out, "Both backings return the same values: {}", same)?;
+    writeln!(
+        out,
+        "SmallVec saves an allocation per small key; keys past 4 values pay for the inline slots and the heap"
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: a tag index over documents.
+pub fn practical_tag_index(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Tag Index")?;
+
+    let documents: [(u32, &str, &[&str]); 6] = [
+        (1, "Ownership explained", &["rust", "memory"]),
+        (2, "Index tuning", &["db", "performance"]),
+        (3, "Async in practice", &["rust", "async"]),
+        (4, "Allocator tricks", &["rust", "memory", "performance"]),
+        (5, "Connection pools", &["db", "async"]),
+        (6, "Profiling 101", &["performance"]),
+    ];
+    let mut by_tag: SmallMultiMap<&str, u32> = SmallMultiMap::new();
+    for &(id, _, tags) in &documents {
+        for &tag in tags {
+            by_tag.insert(tag, id);
+        }
+    }
+    writeln!(out, "Index:")?;
+    for line in show(&by_tag) {
+        writeln!(out, "  {}", line)?;
+    }
+
+    let title = |id: &u32| -> &str {
+        documents
+            .iter()
+            .find(|(doc, _, _)| doc == id)
+            .map_or("?", |(_, title, _)| title)
+    };
+    let rust: Vec<&str> = by_tag.get_all("rust").iter().map(title).collect();
+    writeln!(out, "\nTagged rust: {:?}", rust)?;
+
+    // AND query: walk the shorter list, check the longer one
+    let (memory, performance) = (by_tag.get_all("memory"), by_tag.get_all("performance"));
+    let (short, long) = if memory.len() <= performance.len() {
+        (memory, performance)
+    } else {
+        (performance, memory)
+    };
+    let both: Vec<&str> = short
+        .iter()
+        .filter(|id| long.contains(id))
+        .map(title)
+        .collect();
+    writeln!(out, "Tagged memory AND performance: {:?}", both)?;
+
+    // Untagging and deleting
+    by_tag.remove_value("async", &5);
+    writeln!(
+        out,
+        "\nAfter untagging doc 5 from async: async -> {:?}",
+        by_tag.get_all("async")
+    )?;
+    by_tag.retain(|_, &id| id != 4);
+    writeln!(
+        out,
+        "After deleting doc 4 everywhere: memory -> {:?}, performance -> {:?}",
+        by_tag.get_all("memory"),
+        by_tag.get_all("performance")
+    )?;
+    writeln!(
+        out,
+        "{} tag entries under {} tags; none of them spilled: {}",
+        by_tag.len(),
+        by_tag.key_count(),
+        by_tag.keys_on_heap() == 0
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,473 @@
+// A multiset (bag) is a set that remembers how many times each element was
+// added. std has no multiset type, but `HashMap<T, usize>` is one: the key
+// is the element, the value its count. Python ships this as
+// collections.Counter; Counter<T> here wraps the map with the same
+// vocabulary:
+//
+//   add / remove         count up or down by one, dropping a key at zero
+//   most_common(n)       the n highest counts, largest first
+//   elements()           every element repeated by its count
+//   union (|)            the larger of the two counts, per element
+//   intersection (&)     the smaller of the two counts
+//   sum (+)              counts added together
+//   difference (-)       counts subtracted, stopping at zero
+//
+// Counts are never stored as zero, so the map's length is the number of
+// distinct elements and `total()` the number of elements with repeats.
+//
+//   Counter from "mississippi":  { i: 4, s: 4, p: 2, m: 1 }
+//   len() = 4 distinct, total() = 11
+
+use demo_framework::section;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "counter_basics",
+        "add, remove, count and elements on a Counter",
+        counter_basics,
+    )?;
+
+    section(
+        out,
+        "word_frequencies",
+        "Counting words and finding the most common",
+        word_frequencies,
+    )?;
+
+    section(
+        out,
+        "multiset_algebra",
+        "Union, intersection, sum and difference of counts",
+        multiset_algebra,
+    )?;
+
+    section(
+        out,
+        "practical_inventory",
+        "Practical demo: Warehouse stock as a multiset",
+        practical_inventory,
+    )?;
+
+    Ok(())
+}
+
+/// A multiset: each distinct element with how many times it occurs.
+///
+/// Zero counts are never stored, so two counters with the same counts are
+/// equal whatever happened to them before.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Counter<T: Hash + Eq> {
+    counts: HashMap<T, usize>,
+}
+
+impl<T: Hash + Eq> Counter<T> {
+    pub fn new() -> Self {
+        Counter {
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Adds one `item`; returns its new count.
+    pub fn add(&mut self, item: T) -> usize {
+        self.add_n(item, 1)
+    }
+
+    /// Adds `n` copies of `item`; returns its new count.
+    pub fn add_n(&mut self, item: T, n: usize) -> usize {
+        if n == 0 {
+            return self.count(&item);
+        }
+        let count: &mut usize = self.counts.entry(item).or_insert(0);
+        *count += n;
+        *count
+    }
+
+    /// Removes one `item`; `false` if there was none to remove.
+    pub fn remove(&mut self, item: &T) -> bool {
+        self.remove_n(item, 1) == 1
+    }
+
+    /// Removes up to `n` copies of `item` and returns how many were
+    /// removed. A count that reaches zero drops the element.
+    pub fn remove_n(&mut self, item: &T, n: usize) -> usize {
+        let Some(count) = self.counts.get_mut(item) else {
+            return 0;
+        };
+        let removed: usize = n.min(*count);
+        *count -= removed;
+        if *count == 0 {
+            self.counts.remove(item);
+        }
+        removed
+    }
+
+    /// How many times `item` occurs; 0 if it doesn't.
+    pub fn count(&self, item: &T) -> usize {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// Number of distinct elements.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Number of elements, counting repeats.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Each distinct element with its count, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, usize)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    /// Every element repeated by its count, in no particular order.
+    pub fn elements(&self) -> impl Iterator<Item = &T> {
+        self.counts
+            .iter()
+            .flat_map(|(item, &count)| std::iter::repeat_n(item, count))
+    }
+
+    /// Whether `self` has at least as many of every element as `other`.
+    pub fn contains_all(&self, other: &Counter<T>) -> bool {
+        other.iter().all(|(item, count)| self.count(item) >= count)
+    }
+
+    /// Counts every element of `self` and `other` with `combine(a, b)`,
+    /// where a missing element counts as 0.
+    fn combine(&self, other: &Counter<T>, combine: impl Fn(usize, usize) -> usize) -> Counter<T>
+    where
+        T: Clone,
+    {
+        let mut result: Counter<T> = Counter::new();
+        for item in self.counts.keys().chain(other.counts.keys()) {
+            if let Entry::Vacant(slot) = result.counts.entry(item.clone()) {
+                let count: usize = combine(self.count(item), other.count(item));
+                if count > 0 {
+                    slot.insert(count);
+                }
+            }
+        }
+        result
+    }
+
+    /// The larger count of each element (Python's `a | b`).
+    pub fn union(&self, other: &Counter<T>) -> Counter<T>
+    where
+        T: Clone,
+    {
+        self.combine(other, usize::max)
+    }
+
+    /// The smaller count of each element (Python's `a & b`).
+    pub fn intersection(&self, other: &Counter<T>) -> Counter<T>
+    where
+        T: Clone,
+    {
+        self.combine(other, usize::min)
+    }
+
+    /// Counts added together (Python's `a + b`).
+    pub fn sum(&self, other: &Counter<T>) -> Counter<T>
+    where
+        T: Clone,
+    {
+        self.combine(other, |a, b| a + b)
+    }
+
+    /// Counts of `self` minus those of `other`, dropping anything that
+    /// would go to zero or below (Python's `a - b`).
+    pub fn difference(&self, other: &Counter<T>) -> Counter<T>
+    where
+        T: Clone,
+    {
+        self.combine(other, usize::saturating_sub)
+    }
+}
+
+impl<T: Hash + Eq + Ord> Counter<T> {
+    /// The `n` highest counts, largest first. Equal counts are ordered by
+    /// element, so the result doesn't depend on the hasher.
+    pub fn most_common(&self, n: usize) -> Vec<(&T, usize)> {
+        let mut entries: Vec<(&T, usize)> = self.iter().collect();
+        entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        entries.truncate(n);
+        entries
+    }
+
+    /// Every element with its count, ordered by element.
+    pub fn sorted(&self) -> Vec<(&T, usize)> {
+        let mut entries: Vec<(&T, usize)> = self.iter().collect();
+        entries.sort();
+        entries
+    }
+}
+
+impl<T: Hash + Eq> Default for Counter<T> {
+    fn default() -> Self {
+        Counter::new()
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter: Counter<T> = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Hash + Eq> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+/// `{a: 2, b: 1}`, ordered by element.
+fn show<T: Hash + Eq + Ord + std::fmt::Display>(counter: &Counter<T>) -> String {
+    let entries: Vec<String> = counter
+        .sorted()
+        .into_iter()
+        .map(|(item, count)| format!("{}: {}", item, count))
+        .collect();
+    format!("{{{}}}", entries.join(", "))
+}
+
+/// Demonstrates the basic Counter operations.
+pub fn counter_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Counter Basics")?;
+
+    let letters: Counter<char> = "mississippi".chars().collect();
+    writeln!(out, "Counter from \"mississippi\": {}", show(&letters))?;
+    writeln!(
+        out,
+        "  len() = {} distinct, total() = {} letters",
+        letters.len(),
+        letters.total()
+    )?;
+    writeln!(
+        out,
+        "  count('s') = {}, count('z') = {} (missing counts as 0)",
+        letters.count(&'s'),
+        letters.count(&'z')
+    )?;
+
+    let mut bag: Counter<&str> = Counter::new();
+    writeln!(out, "\nadd(\"red\") -> {}", bag.add("red"))?;
+    writeln!(out, "add(\"red\") -> {}", bag.add("red"))?;
+    writeln!(out, "add_n(\"blue\", 3) -> {}", bag.add_n("blue", 3))?;
+    writeln!(out, "bag: {}", show(&bag))?;
+
+    writeln!(out, "\nremove(\"red\") -> {}", bag.remove(&"red"))?;
+    writeln!(out, "remove(\"red\") -> {}", bag.remove(&"red"))?;
+    writeln!(
+        out,
+        "remove(\"red\") -> {} (nothing left to remove)",
+        bag.remove(&"red")
+    )?;
+    writeln!(
+        out,
+        "remove_n(\"blue\", 5) -> {} removed (only 3 were there)",
+        bag.remove_n(&"blue", 5)
+    )?;
+    writeln!(
+        out,
+        "bag: {}, is_empty() = {}: zero counts are dropped",
+        show(&bag),
+        bag.is_empty()
+    )?;
+
+    // elements() repeats each item by its count
+    let mut elements: Vec<char> = letters.elements().copied().collect();
+    elements.sort();
+    writeln!(
+        out,
+        "\nletters.elements(), sorted: {}",
+        elements.iter().collect::<String>()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates word counting and most_common.
+pub fn word_frequencies(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Word Frequencies")?;
+
+    let text: &str = "\
+        It was the best of times, it was the worst of times, it was the age of \
+        wisdom, it was the age of foolishness, it was the epoch of belief, it \
+        was the epoch of incredulity, it was the season of Light, it was the \
+        season of Darkness";
+    let words: Counter<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    writeln!(out, "{} words, {} distinct", words.total(), words.len())?;
+
+    writeln!(out, "\nmost_common(5):")?;
+    for (word, count) in words.most_common(5) {
+        writeln!(out, "  {:<8} {:>2} {}", word, count, "█".repeat(count))?;
+    }
+
+    let once: Vec<&String> = words
+        .sorted()
+        .into_iter()
+        .filter(|&(_, count)| count == 1)
+        .map(|(word, _)| word)
+        .collect();
+    writeln!(out, "\nWords used once: {:?}", once)?;
+
+    // The same counting by hand is one entry() call per word
+    let mut by_hand: HashMap<String, usize> = HashMap::new();
+    for word in text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+    {
+        *by_hand.entry(word.to_lowercase()).or_insert(0) += 1;
+    }
+    writeln!(
+        out,
+        "A plain HashMap<String, usize> counted the same: {}",
+        words
+            .iter()
+            .all(|(word, count)| by_hand.get(word) == Some(&count))
+            && by_hand.len() == words.len()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates union, intersection, sum and difference.
+pub fn multiset_algebra(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Multiset Algebra")?;
+
+    let alice: Counter<&str> = ["apple", "apple", "bread", "milk", "milk", "milk"]
+        .into_iter()
+        .collect();
+    let bob: Counter<&str> = ["apple", "apple", "bread", "bread", "eggs", "milk"]
+        .into_iter()
+        .collect();
+    writeln!(out, "alice: {}", show(&alice))?;
+    writeln!(out, "bob:   {}", show(&bob))?;
+
+    writeln!(out, "\nunion (max):        {}", show(&alice.union(&bob)))?;
+    writeln!(
+        out,
+        "intersection (min): {}",
+        show(&alice.intersection(&bob))
+    )?;
+    writeln!(out, "sum (+):            {}", show(&alice.sum(&bob)))?;
+    writeln!(out, "alice - bob:        {}", show(&alice.difference(&bob)))?;
+    writeln!(out, "bob - alice:        {}", show(&bob.difference(&alice)))?;
+
+    // A set has every count at 1; a multiset keeps the repeats
+    let alice_set: HashSet<&str> = alice.iter().map(|(&item, _)| item).collect();
+    let bob_set: HashSet<&str> = bob.iter().map(|(&item, _)| item).collect();
+    writeln!(
+        out,
+        "\nItems in both: {} as sets, {} as multisets (both have two apples)",
+        alice_set.intersection(&bob_set).count(),
+        alice.intersection(&bob).total()
+    )?;
+    writeln!(
+        out,
+        "alice.sum(&bob).total() = {} = {} + {}",
+        alice.sum(&bob).total(),
+        alice.total(),
+        bob.total()
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: stock levels, orders and restocking.
+pub fn practical_inventory(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Warehouse Inventory")?;
+
+    let mut stock: Counter<&str> = Counter::new();
+    stock.add_n("bolt", 40);
+    stock.add_n("nut", 35);
+    stock.add_n("washer", 12);
+    stock.add_n("bracket", 3);
+    writeln!(out, "Stock: {}", show(&stock))?;
+
+    let orders: [(&str, Counter<&str>); 3] = [
+        (
+            "#1001",
+            [("bolt", 10), ("nut", 10), ("washer", 4)]
+                .into_iter()
+                .flat_map(|(part, n)| std::iter::repeat_n(part, n))
+                .collect(),
+        ),
+        (
+            "#1002",
+            [("bracket", 4), ("bolt", 4)]
+                .into_iter()
+                .flat_map(|(part, n)| std::iter::repeat_n(part, n))
+                .collect(),
+        ),
+        (
+            "#1003",
+            [("bolt", 20), ("nut", 20), ("washer", 8)]
+                .into_iter()
+                .flat_map(|(part, n)| std::iter::repeat_n(part, n))
+                .collect(),
+        ),
+    ];
+
+    let mut backorders: Counter<&str> = Counter::new();
+    for (id, order) in &orders {
+        // An order ships only if the stock covers every line of it
+        if stock.contains_all(order) {
+            stock = stock.difference(order);
+            writeln!(out, "\nOrder {} {}: shipped", id, show(order))?;
+        } else {
+            let short: Counter<&str> = order.difference(&stock);
+            backorders = backorders.sum(&short);
+            writeln!(
+                out,
+                "\nOrder {} {}: held, short by {}",
+                id,
+                show(order),
+                show(&short)
+            )?;
+        }
+        writeln!(out, "  stock now: {}", show(&stock))?;
+    }
+
+    writeln!(out, "\nTo reorder: {}", show(&backorders))?;
+    let low: Vec<(&&str, usize)> = stock
+        .sorted()
+        .into_iter()
+        .filter(|&(_, count)| count < 10)
+        .collect();
+    writeln!(out, "Below 10 on the shelf: {:?}", low)?;
+
+    // A delivery arrives: sum() merges it into the stock
+    let delivery: Counter<&str> = [("bracket", 10), ("washer", 20)]
+        .into_iter()
+        .flat_map(|(part, n)| std::iter::repeat_n(part, n))
+        .collect();
+    stock = stock.sum(&delivery);
+    writeln!(out, "\nAfter a delivery of {}:", show(&delivery))?;
+    writeln!(out, "  stock: {}", show(&stock))?;
+    writeln!(
+        out,
+        "  the held order #1002 can ship now: {}",
+        stock.contains_all(&orders[1].1)
+    )?;
+
+    Ok(())
+}
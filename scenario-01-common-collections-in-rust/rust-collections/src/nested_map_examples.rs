@@ -0,0 +1,311 @@
+// Two-level data - sales per region per product, permissions per user per
+// resource - fits three common map shapes:
+//
+//   HashMap<A, HashMap<B, V>>    nested: one inner table per outer key
+//   HashMap<(A, B), V>           flat: one table, composite key
+//   BTreeMap<A, BTreeMap<B, V>>  nested and sorted at both levels
+//
+// A point lookup of (a, b) is one hash in the flat shape and two in the
+// nested one. The nested shapes win when queries are "everything for a":
+// that is one get() returning a whole row, where the flat map has to scan
+// every entry. A flat BTreeMap<(A, B), V> gets the row back as a range.
+//
+// Rule of thumb: flatten when you almost always know both halves of the key;
+// nest when you group, count or iterate by the first half; sort when the
+// output order matters.
+
+use demo_framework::section;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "three_shapes",
+        "The same two-level data as nested, flat and sorted maps",
+        three_shapes,
+    )?;
+
+    section(
+        out,
+        "converting_between_shapes",
+        "flatten, nest, nest_sorted and transpose",
+        converting_between_shapes,
+    )?;
+
+    section(
+        out,
+        "row_queries",
+        "Everything for one outer key: get vs scan vs range",
+        row_queries,
+    )?;
+
+    section(
+        out,
+        "composite_key_pitfalls",
+        "Borrowed lookups, empty inner maps and per-table overhead",
+        composite_key_pitfalls,
+    )?;
+
+    Ok(())
+}
+
+/// Flattens a nested map into one map keyed by `(outer, inner)`.
+pub fn flatten<A, B, V>(nested: HashMap<A, HashMap<B, V>>) -> HashMap<(A, B), V>
+where
+    A: Eq + Hash + Clone,
+    B: Eq + Hash,
+{
+    let len: usize = nested.values().map(HashMap::len).sum();
+    let mut flat: HashMap<(A, B), V> = HashMap::with_capacity(len);
+    for (a, inner) in nested {
+        for (b, v) in inner {
+            flat.insert((a.clone(), b), v);
+        }
+    }
+    flat
+}
+
+/// Groups a map keyed by `(outer, inner)` into one inner map per outer key.
+pub fn nest<A, B, V>(flat: HashMap<(A, B), V>) -> HashMap<A, HashMap<B, V>>
+where
+    A: Eq + Hash,
+    B: Eq + Hash,
+{
+    let mut nested: HashMap<A, HashMap<B, V>> = HashMap::new();
+    for ((a, b), v) in flat {
+        nested.entry(a).or_default().insert(b, v);
+    }
+    nested
+}
+
+/// Groups `((outer, inner), value)` pairs into sorted nested maps.
+///
+/// Takes any iterator, so it accepts a flat HashMap, a flat BTreeMap or
+/// the output of [`flatten_sorted`].
+pub fn nest_sorted<A, B, V>(
+    pairs: impl IntoIterator<Item = ((A, B), V)>,
+) -> BTreeMap<A, BTreeMap<B, V>>
+where
+    A: Ord,
+    B: Ord,
+{
+    let mut nested: BTreeMap<A, BTreeMap<B, V>> = BTreeMap::new();
+    for ((a, b), v) in pairs {
+        nested.entry(a).or_default().insert(b, v);
+    }
+    nested
+}
+
+/// Flattens sorted nested maps into `((outer, inner), value)` pairs, in
+/// key order.
+pub fn flatten_sorted<A, B, V>(
+    nested: BTreeMap<A, BTreeMap<B, V>>,
+) -> impl Iterator<Item = ((A, B), V)>
+where
+    A: Clone,
+{
+    nested
+        .into_iter()
+        .flat_map(|(a, inner)| inner.into_iter().map(move |(b, v)| ((a.clone(), b), v)))
+}
+
+/// Swaps the two key levels: `a -> b -> v` becomes `b -> a -> v`.
+pub fn transpose<A, B, V>(nested: HashMap<A, HashMap<B, V>>) -> HashMap<B, HashMap<A, V>>
+where
+    A: Eq + Hash + Clone,
+    B: Eq + Hash,
+{
+    let mut transposed: HashMap<B, HashMap<A, V>> = HashMap::new();
+    for (a, inner) in nested {
+        for (b, v) in inner {
+            transposed.entry(b).or_default().insert(a.clone(), v);
+        }
+    }
+    transposed
+}
+
+/// Units sold per (region, product).
+fn sales() -> Vec<((&'static str, &'static str), u32)> {
+    vec![
+        (("north", "apples"), 120),
+        (("north", "pears"), 45),
+        (("south", "apples"), 80),
+        (("south", "plums"), 60),
+        (("south", "pears"), 15),
+        (("east", "plums"), 30),
+    ]
+}
+
+/// Demonstrates the three shapes holding the same data.
+pub fn three_shapes(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Three Shapes")?;
+
+    let flat: HashMap<(&str, &str), u32> = sales().into_iter().collect();
+    let nested: HashMap<&str, HashMap<&str, u32>> = nest(flat.clone());
+    let sorted: BTreeMap<&str, BTreeMap<&str, u32>> = nest_sorted(sales());
+
+    writeln!(out, "Flat HashMap<(A, B), V>: {} entries", flat.len())?;
+    writeln!(
+        out,
+        "Nested HashMap<A, HashMap<B, V>>: {} outer keys, {} entries",
+        nested.len(),
+        nested.values().map(HashMap::len).sum::<usize>()
+    )?;
+    writeln!(out, "Sorted BTreeMap<A, BTreeMap<B, V>>: {:?}", sorted)?;
+
+    // A point lookup in each shape. The nested shapes chain two gets.
+    writeln!(out, "\nUnits of pears sold in the south:")?;
+    writeln!(out, "  flat:   {:?}", flat.get(&("south", "pears")))?;
+    writeln!(
+        out,
+        "  nested: {:?}",
+        nested.get("south").and_then(|row| row.get("pears"))
+    )?;
+    writeln!(
+        out,
+        "  sorted: {:?}",
+        sorted.get("south").and_then(|row| row.get("pears"))
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates converting between the shapes.
+///
+/// flatten clones the outer key once per inner entry, so for large String
+/// keys nesting is also the more compact shape. transpose answers "by the
+/// second key" questions that neither nested shape can answer directly.
+pub fn converting_between_shapes(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Converting Between Shapes")?;
+
+    let flat: HashMap<(&str, &str), u32> = sales().into_iter().collect();
+
+    // Round trip: flat -> nested -> flat
+    let nested: HashMap<&str, HashMap<&str, u32>> = nest(flat.clone());
+    let round_trip: HashMap<(&str, &str), u32> = flatten(nested.clone());
+    writeln!(out, "flatten(nest(flat)) == flat: {}", round_trip == flat)?;
+
+    // Sorted nesting works from any ((A, B), V) iterator, and flattens
+    // back in key order
+    let sorted: BTreeMap<&str, BTreeMap<&str, u32>> = nest_sorted(flat);
+    let in_order: Vec<((&str, &str), u32)> = flatten_sorted(sorted).collect();
+    writeln!(out, "\nflatten_sorted, in key order:")?;
+    for ((region, product), units) in &in_order {
+        writeln!(out, "  ({}, {}) -> {}", region, product, units)?;
+    }
+
+    // Transpose: region -> product becomes product -> region
+    let by_product: HashMap<&str, HashMap<&str, u32>> = transpose(nested);
+    let plums: BTreeMap<&str, u32> = by_product["plums"].iter().map(|(&r, &u)| (r, u)).collect();
+    writeln!(out, "\ntranspose: regions selling plums: {:?}", plums)?;
+
+    Ok(())
+}
+
+/// Demonstrates the query the nested shapes are built for: one whole row.
+pub fn row_queries(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Row Queries")?;
+
+    let flat: HashMap<(&str, &str), u32> = sales().into_iter().collect();
+    let nested: HashMap<&str, HashMap<&str, u32>> = nest(flat.clone());
+    let flat_sorted: BTreeMap<(&str, &str), u32> = sales().into_iter().collect();
+
+    // Nested: one lookup hands back the row
+    let south: u32 = nested["south"].values().sum();
+    writeln!(out, "nested[\"south\"] total: {} (1 lookup)", south)?;
+
+    // Flat HashMap: no way to find a row except scanning every entry
+    let mut visited: usize = 0;
+    let south: u32 = flat
+        .iter()
+        .inspect(|_| visited += 1)
+        .filter(|((region, _), _)| *region == "south")
+        .map(|(_, units)| units)
+        .sum();
+    writeln!(
+        out,
+        "flat scan for \"south\" total: {} ({} entries visited)",
+        south, visited
+    )?;
+
+    // Flat BTreeMap: tuples sort by the first element, so a row is a range.
+    // ("south", "") is the smallest key in the row, ("south\0", "") sorts
+    // just past its end.
+    let row: Vec<(&str, u32)> = flat_sorted
+        .range(("south", "")..("south\0", ""))
+        .map(|(&(_, product), &units)| (product, units))
+        .collect();
+    writeln!(out, "flat BTreeMap range for \"south\": {:?}", row)?;
+
+    // Counting rows is free in the nested shape, a scan in the flat one
+    writeln!(out, "\nRegions: {} (nested.len())", nested.len())?;
+
+    Ok(())
+}
+
+/// Demonstrates the sharp edges of each shape.
+///
+/// - A flat map keyed by `(String, String)` can't be queried with
+///   `(&str, &str)`: Borrow doesn't reach inside tuples
+/// - Removing the last inner entry leaves an empty inner map behind
+/// - Every inner HashMap is its own allocation with its own spare capacity
+pub fn composite_key_pitfalls(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Composite Key Pitfalls")?;
+
+    // Borrowed lookups: the flat map needs an owned tuple to look up
+    let mut owned: HashMap<(String, String), u32> = HashMap::new();
+    owned.insert(("north".to_string(), "apples".to_string()), 120);
+    let key: (String, String) = ("north".to_string(), "apples".to_string());
+    writeln!(
+        out,
+        "flat get(&(String, String)): {:?} (allocates the key)",
+        owned.get(&key)
+    )?;
+
+    // The nested shape looks up each level by &str, no allocation
+    let nested: HashMap<String, HashMap<String, u32>> = nest(owned);
+    writeln!(
+        out,
+        "nested get(\"north\")?.get(\"apples\"): {:?}",
+        nested.get("north").and_then(|row| row.get("apples"))
+    )?;
+
+    // Empty inner maps: remove from the row, then drop the row if empty
+    let mut nested: HashMap<&str, HashMap<&str, u32>> = nest(sales().into_iter().collect());
+    if let Some(row) = nested.get_mut("east") {
+        row.remove("plums");
+    }
+    writeln!(
+        out,
+        "\nAfter removing (east, plums): \"east\" still present: {}",
+        nested.contains_key("east")
+    )?;
+    nested.retain(|_, row| !row.is_empty());
+    writeln!(
+        out,
+        "After retain(|_, row| !row.is_empty()): {}",
+        nested.contains_key("east")
+    )?;
+
+    // Overhead: 1,000 outer keys with 2 entries each
+    let nested: HashMap<u32, HashMap<u32, u32>> = (0..1_000)
+        .map(|a| (a, (0..2).map(|b| (b, a + b)).collect()))
+        .collect();
+    let inner_slots: usize = nested.values().map(HashMap::capacity).sum();
+    let flat: HashMap<(u32, u32), u32> = flatten(nested.clone());
+    writeln!(
+        out,
+        "\n1,000 rows x 2 entries: nested = {} tables, {} inner slots",
+        1 + nested.len(),
+        inner_slots
+    )?;
+    writeln!(
+        out,
+        "                        flat   = 1 table,  {} slots",
+        flat.capacity()
+    )?;
+
+    Ok(())
+}
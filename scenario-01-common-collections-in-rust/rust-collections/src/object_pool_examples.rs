@@ -0,0 +1,552 @@
+// An object pool keeps objects that are expensive to create - large
+// buffers, connections, parsers with big tables - and hands them out again
+// instead of building new ones. Returning an object resets it and puts it
+// on a free list; the next checkout pops it back off:
+//
+//   checkout ──► free list empty? create : pop      idle: [buf₁, buf₂]
+//   drop guard ─► reset, push back                   idle: [buf₁, buf₂, buf₃]
+//
+// The free list is a plain Vec used as a stack, so both ends are O(1) and
+// the most recently returned object - the one most likely still in cache -
+// goes out first.
+//
+// Two ways to hand objects out:
+//
+//   Pool<T>      checkout() moves an object out into a Pooled guard. Dropping
+//                the guard returns it, so an object goes back exactly once,
+//                on every path out of the scope, early returns included.
+//
+//   FreeList<T>  objects stay in a Vec of slots and callers hold a SlotId,
+//                a plain index. That is cheaper to pass around but nothing
+//                stops a caller releasing the same id twice, so release()
+//                checks and returns an error instead of corrupting the list.
+//
+// Unlike Slab (see slotmap_examples), neither drops an object when it is
+// released: keeping its allocation alive is the whole point.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::{Deref, DerefMut};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "pool_basics",
+        "Checking buffers out of a Pool and reusing their allocations",
+        pool_basics,
+    )?;
+
+    section(
+        out,
+        "raii_guards",
+        "Pooled guards: returned on drop, detached on request",
+        raii_guards,
+    )?;
+
+    section(
+        out,
+        "freelist_handles",
+        "FreeList: index handles, slot reuse and double-return checks",
+        freelist_handles,
+    )?;
+
+    section(
+        out,
+        "practical_request_buffers",
+        "Practical demo: scratch buffers for a request handler",
+        practical_request_buffers,
+    )?;
+
+    Ok(())
+}
+
+/// What a [`Pool`] has done so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Objects built by the pool's `create` function.
+    pub created: usize,
+    /// Checkouts served from the free list instead.
+    pub reused: usize,
+    /// Objects put back on the free list.
+    pub returned: usize,
+    /// Objects dropped on return because the free list was full.
+    pub discarded: usize,
+}
+
+impl fmt::Display for PoolStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "created {}, reused {}, returned {}, discarded {}",
+            self.created, self.reused, self.returned, self.discarded
+        )
+    }
+}
+
+/// A pool of reusable `T`s, handed out as [`Pooled`] guards.
+///
+/// Single-threaded: `checkout` takes `&self`, so several guards can be out
+/// at once, and the free list sits in a `RefCell`.
+pub struct Pool<T> {
+    idle: RefCell<Vec<T>>,
+    stats: Cell<PoolStats>,
+    create: Box<dyn Fn() -> T>,
+    reset: Box<dyn Fn(&mut T)>,
+    max_idle: usize,
+}
+
+impl<T> Pool<T> {
+    /// A pool that builds objects with `create` and cleans returned ones
+    /// with `reset`. It keeps every returned object.
+    pub fn new(create: impl Fn() -> T + 'static, reset: impl Fn(&mut T) + 'static) -> Self {
+        Pool {
+            idle: RefCell::new(Vec::new()),
+            stats: Cell::new(PoolStats::default()),
+            create: Box::new(create),
+            reset: Box::new(reset),
+            max_idle: usize::MAX,
+        }
+    }
+
+    /// Keeps at most `max_idle` objects on the free list and drops the
+    /// rest, so a burst of checkouts doesn't pin its peak memory forever.
+    pub fn with_max_idle(mut self, max_idle: usize) -> Self {
+        self.max_idle = max_idle;
+        self
+    }
+
+    /// Creates objects until `count` are idle, so the first checkouts
+    /// don't pay for creation.
+    pub fn prefill(&self, count: usize) {
+        let count: usize = count.min(self.max_idle);
+        let mut idle = self.idle.borrow_mut();
+        while idle.len() < count {
+            idle.push((self.create)());
+            self.update(|stats| stats.created += 1);
+        }
+    }
+
+    /// Takes an idle object, or creates one if none is idle.
+    pub fn checkout(&self) -> Pooled<'_, T> {
+        let reused: Option<T> = self.idle.borrow_mut().pop();
+        let value: T = match reused {
+            Some(value) => {
+                self.update(|stats| stats.reused += 1);
+                value
+            }
+            None => {
+                self.update(|stats| stats.created += 1);
+                (self.create)()
+            }
+        };
+        Pooled {
+            pool: self,
+            value: Some(value),
+        }
+    }
+
+    /// Objects waiting on the free list.
+    pub fn idle(&self) -> usize {
+        self.idle.borrow().len()
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        self.stats.get()
+    }
+
+    fn give_back(&self, mut value: T) {
+        (self.reset)(&mut value);
+        let mut idle = self.idle.borrow_mut();
+        if idle.len() < self.max_idle {
+            idle.push(value);
+            self.update(|stats| stats.returned += 1);
+        } else {
+            self.update(|stats| stats.discarded += 1);
+        }
+    }
+
+    fn update(&self, change: impl FnOnce(&mut PoolStats)) {
+        let mut stats: PoolStats = self.stats.get();
+        change(&mut stats);
+        self.stats.set(stats);
+    }
+}
+
+/// An object checked out of a [`Pool`]. Derefs to the object, and returns
+/// it to the pool when dropped.
+pub struct Pooled<'a, T> {
+    pool: &'a Pool<T>,
+    /// Always `Some` until `drop` or `detach` takes it.
+    value: Option<T>,
+}
+
+impl<T> Pooled<'_, T> {
+    /// Keeps the object for good: it never goes back to the pool.
+    pub fn detach(mut self) -> T {
+        self.value.take().expect("a live guard holds its object")
+    }
+}
+
+impl<T> Deref for Pooled<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().expect("a live guard holds its object")
+    }
+}
+
+impl<T> DerefMut for Pooled<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().expect("a live guard holds its object")
+    }
+}
+
+impl<T> Drop for Pooled<'_, T> {
+    fn drop(&mut self) {
+        if let Some(value) = self.value.take() {
+            self.pool.give_back(value);
+        }
+    }
+}
+
+/// A handle to a [`FreeList`] slot: just its index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SlotId(usize);
+
+impl SlotId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Why [`FreeList::release`] refused a handle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReleaseError {
+    /// The slot is already free: released twice, or never acquired.
+    DoubleReturn(SlotId),
+    /// The handle points past the last slot.
+    UnknownSlot(SlotId),
+}
+
+impl fmt::Display for ReleaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReleaseError::DoubleReturn(id) => write!(f, "slot {} is already free", id.0),
+            ReleaseError::UnknownSlot(id) => write!(f, "slot {} doesn't exist", id.0),
+        }
+    }
+}
+
+impl std::error::Error for ReleaseError {}
+
+/// Objects in a Vec of slots, handed out by index. Released slots keep
+/// their object and go on a free list for the next `acquire`.
+#[derive(Debug, Clone, Default)]
+pub struct FreeList<T> {
+    slots: Vec<T>,
+    in_use: Vec<bool>,
+    /// Free slot indices; the last one is reused first.
+    free: Vec<usize>,
+}
+
+impl<T> FreeList<T> {
+    pub fn new() -> Self {
+        FreeList {
+            slots: Vec::new(),
+            in_use: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Reuses the most recently freed slot, or adds a slot built by
+    /// `create`. A reused object keeps whatever state `reset` left it in.
+    pub fn acquire(&mut self, create: impl FnOnce() -> T) -> SlotId {
+        match self.free.pop() {
+            Some(index) => {
+                self.in_use[index] = true;
+                SlotId(index)
+            }
+            None => {
+                self.slots.push(create());
+                self.in_use.push(true);
+                SlotId(self.slots.len() - 1)
+            }
+        }
+    }
+
+    /// The object behind `id`, or `None` if the slot isn't checked out.
+    pub fn get(&self, id: SlotId) -> Option<&T> {
+        self.is_in_use(id).then(|| &self.slots[id.0])
+    }
+
+    pub fn get_mut(&mut self, id: SlotId) -> Option<&mut T> {
+        if self.is_in_use(id) {
+            Some(&mut self.slots[id.0])
+        } else {
+            None
+        }
+    }
+
+    /// Resets the slot's object with `reset` and frees the slot. A second
+    /// release of the same id is an error, and leaves the list unchanged:
+    /// pushing the index twice would hand one slot to two callers.
+    pub fn release(&mut self, id: SlotId, reset: impl FnOnce(&mut T)) -> Result<(), ReleaseError> {
+        match self.in_use.get(id.0) {
+            None => Err(ReleaseError::UnknownSlot(id)),
+            Some(false) => Err(ReleaseError::DoubleReturn(id)),
+            Some(true) => {
+                reset(&mut self.slots[id.0]);
+                self.in_use[id.0] = false;
+                self.free.push(id.0);
+                Ok(())
+            }
+        }
+    }
+
+    /// Slots checked out right now.
+    pub fn in_use(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// Slots waiting for reuse.
+    pub fn free(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Every slot ever created: the most ever checked out at once.
+    pub fn slots(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn is_in_use(&self, id: SlotId) -> bool {
+        self.in_use.get(id.0).copied().unwrap_or(false)
+    }
+}
+
+/// A pool of byte buffers with `capacity` bytes reserved up front.
+pub fn buffer_pool(capacity: usize) -> Pool<Vec<u8>> {
+    Pool::new(move || Vec::with_capacity(capacity), Vec::clear)
+}
+
+/// Demonstrates checking buffers out and the allocations they keep.
+pub fn pool_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Pool Basics")?;
+
+    let pool: Pool<Vec<u8>> = buffer_pool(64 * 1024);
+    let first_address: usize;
+    {
+        let mut buffer: Pooled<Vec<u8>> = pool.checkout();
+        buffer.extend_from_slice(b"GET /index.html");
+        first_address = buffer.as_ptr() as usize;
+        writeln!(
+            out,
+            "Checked out a buffer: len {}, capacity {}",
+            buffer.len(),
+            buffer.capacity()
+        )?;
+        writeln!(out, "While it is out, idle = {}", pool.idle())?;
+    }
+    // The guard went out of scope: the buffer was cleared and returned
+    writeln!(out, "After the guard drops, idle = {}", pool.idle())?;
+
+    let buffer: Pooled<Vec<u8>> = pool.checkout();
+    writeln!(
+        out,
+        "Next checkout: len {} (reset), capacity {}, same allocation: {}",
+        buffer.len(),
+        buffer.capacity(),
+        buffer.as_ptr() as usize == first_address
+    )?;
+    drop(buffer);
+
+    // Several checkouts at once each get their own buffer; the pool only
+    // creates what the free list can't cover
+    let a: Pooled<Vec<u8>> = pool.checkout();
+    let b: Pooled<Vec<u8>> = pool.checkout();
+    let c: Pooled<Vec<u8>> = pool.checkout();
+    writeln!(out, "\nThree checkouts at once: {}", pool.stats())?;
+    drop((a, b, c));
+    writeln!(out, "All three back: idle = {}", pool.idle())?;
+
+    Ok(())
+}
+
+/// Demonstrates how guards return objects, and how to opt out.
+pub fn raii_guards(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "RAII Guards")?;
+
+    let pool: Pool<Vec<u8>> = buffer_pool(1024);
+
+    // An early return still drops the guard, so the buffer comes back on
+    // the error path too - no "remember to return it" in every branch
+    let parse = |input: &[u8]| -> Result<usize, String> {
+        let mut scratch: Pooled<Vec<u8>> = pool.checkout();
+        for &byte in input {
+            if !byte.is_ascii_digit() {
+                return Err(format!("bad byte {:?}", byte as char));
+            }
+            scratch.push(byte - b'0');
+        }
+        Ok(scratch.iter().map(|&d| d as usize).sum())
+    };
+    writeln!(out, "parse(\"123\") = {:?}", parse(b"123"))?;
+    writeln!(out, "parse(\"1x3\") = {:?}", parse(b"1x3"))?;
+    writeln!(
+        out,
+        "Both calls returned their buffer: idle = {}, {}",
+        pool.idle(),
+        pool.stats()
+    )?;
+
+    // detach() moves the object out for good, e.g. to hand it to a caller
+    // who will keep it. Its guard then has nothing to return
+    let mut kept: Vec<u8> = pool.checkout().detach();
+    kept.extend_from_slice(b"kept");
+    writeln!(
+        out,
+        "\nDetached {:?}: idle = {} (it isn't coming back)",
+        String::from_utf8_lossy(&kept),
+        pool.idle()
+    )?;
+
+    // A bounded pool drops what it has no room for: after a burst of 5,
+    // only 2 buffers stay allocated
+    let bounded: Pool<Vec<u8>> = buffer_pool(1024).with_max_idle(2);
+    let burst: Vec<Pooled<Vec<u8>>> = (0..5).map(|_| bounded.checkout()).collect();
+    drop(burst);
+    writeln!(
+        out,
+        "\nBurst of 5 into with_max_idle(2): idle = {}, {}",
+        bounded.idle(),
+        bounded.stats()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates FreeList handles and what happens on a double return.
+pub fn freelist_handles(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "FreeList Handles")?;
+
+    let mut list: FreeList<String> = FreeList::new();
+    let a: SlotId = list.acquire(|| String::with_capacity(32));
+    let b: SlotId = list.acquire(|| String::with_capacity(32));
+    if let Some(text) = list.get_mut(a) {
+        text.push_str("request a");
+    }
+    writeln!(
+        out,
+        "Acquired slots {} and {}: in use {}, free {}",
+        a.index(),
+        b.index(),
+        list.in_use(),
+        list.free()
+    )?;
+
+    writeln!(out, "release(a) -> {:?}", list.release(a, String::clear))?;
+    // A SlotId is Copy, so nothing stops a second release at compile time
+    let again: Result<(), ReleaseError> = list.release(a, String::clear);
+    writeln!(out, "release(a) again -> {:?}", again)?;
+    if let Err(err) = again {
+        writeln!(out, "  error: {}", err)?;
+    }
+    writeln!(
+        out,
+        "release(slot 9) -> {:?}",
+        list.release(SlotId(9), String::clear)
+    )?;
+    writeln!(
+        out,
+        "A released handle reads None: get(a) = {:?}",
+        list.get(a)
+    )?;
+
+    // The freed slot is reused, object and all
+    let c: SlotId = list.acquire(|| unreachable!("slot a is free"));
+    writeln!(
+        out,
+        "\nacquire() reused slot {}: {:?} with capacity {}",
+        c.index(),
+        list.get(c),
+        list.get(c).map_or(0, String::capacity)
+    )?;
+    writeln!(
+        out,
+        "Slots {}, in use {}, free {}",
+        list.slots(),
+        list.in_use(),
+        list.free()
+    )?;
+
+    // Had the second release gone through, slot a would sit on the free
+    // list twice, and the next two acquires would both get it
+    writeln!(
+        out,
+        "\nWithout the check a double return puts slot {} on the free list twice,",
+        a.index()
+    )?;
+    writeln!(
+        out,
+        "and two later acquires would share one object. The in_use flags catch it."
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: scratch buffers for a request handler.
+///
+/// Each request needs a 64 KiB buffer while it is in flight, and up to 4
+/// are in flight at once. Without a pool that is one allocation per
+/// request; with one, the pool grows to the peak and then only reuses.
+pub fn practical_request_buffers(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Request Buffers")?;
+
+    let pool: Pool<Vec<u8>> = buffer_pool(64 * 1024).with_max_idle(8);
+    let mut in_flight: VecDeque<Pooled<Vec<u8>>> = VecDeque::new();
+    let mut rng: Lcg = Lcg::new(42);
+    let mut bytes_handled: usize = 0;
+    let mut peak: usize = 0;
+
+    for request in 0..1_000usize {
+        // Finish 0 to 2 requests, so the number in flight wanders up and down
+        for _ in 0..rng.below(3) {
+            if let Some(done) = in_flight.pop_front() {
+                bytes_handled += done.len();
+            }
+        }
+        if in_flight.len() == 4 {
+            in_flight.pop_front();
+        }
+
+        let mut buffer: Pooled<Vec<u8>> = pool.checkout();
+        buffer.resize(1024 + request % 4096, b'x');
+        in_flight.push_back(buffer);
+        peak = peak.max(in_flight.len());
+    }
+    in_flight.clear();
+
+    let stats: PoolStats = pool.stats();
+    writeln!(out, "Handled 1000 requests, at most {} in flight", peak)?;
+    writeln!(
+        out,
+        "Buffers created: {}, checkouts served by reuse: {}",
+        stats.created, stats.reused
+    )?;
+    writeln!(
+        out,
+        "Bytes written into pooled buffers (completed requests): {}",
+        bytes_handled
+    )?;
+    writeln!(
+        out,
+        "Without the pool: 1000 allocations of 64 KiB. With it: {}",
+        stats.created
+    )?;
+    writeln!(out, "Idle when done: {}", pool.idle())?;
+
+    Ok(())
+}
@@ -0,0 +1,375 @@
+// A ring buffer keeps the most recent `capacity` values and forgets the
+// rest. Audio and sensor pipelines use one between a producer that never
+// waits (an interrupt, a sound card callback) and readers that only care
+// about the last second or the last block of samples:
+//
+//   capacity 4, push 1..=6
+//
+//   push 1..=4   [1 2 3 4]        full
+//   push 5       [2 3 4 5]        1 is overwritten and handed back
+//   push 6       [3 4 5 6]        2 is overwritten
+//   latest_n(2)        [5 6]      the newest two, oldest first
+//
+// VecDeque is already a growable ring buffer. RingBuffer fixes its size:
+// the deque is allocated once, a push into a full buffer pops the front
+// first, so the deque never reallocates and its head just walks around the
+// allocation. Reads are in logical order, oldest to newest, whatever slot
+// the head is in.
+//
+// latest_n reads without copying. The newest n values are a suffix of the
+// deque, which is at most two runs of memory; latest_n_slices hands those
+// runs out directly for code that wants plain slices, like a filter that
+// takes &[f32].
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::collections::VecDeque;
+use std::collections::vec_deque::Iter;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "ring_buffer_basics",
+        "A fixed-capacity buffer that overwrites its oldest value",
+        ring_buffer_basics,
+    )?;
+
+    section(
+        out,
+        "latest_n_views",
+        "latest_n and latest_n_slices: the newest values without copying",
+        latest_n_views,
+    )?;
+
+    section(
+        out,
+        "practical_sensor_sampling",
+        "Practical demo: rolling statistics over a temperature sensor",
+        practical_sensor_sampling,
+    )?;
+
+    Ok(())
+}
+
+/// The last `capacity` values pushed, oldest first.
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    overwritten: u64,
+}
+
+impl<T> RingBuffer<T> {
+    /// An empty buffer that holds `capacity` values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "a ring buffer needs room for one value");
+        RingBuffer {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+            overwritten: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() == self.capacity
+    }
+
+    /// Adds `value` as the newest. When full, the oldest value is removed
+    /// to make room and returned.
+    pub fn push(&mut self, value: T) -> Option<T> {
+        let oldest: Option<T> = if self.is_full() {
+            self.overwritten += 1;
+            self.items.pop_front()
+        } else {
+            None
+        };
+        self.items.push_back(value);
+        oldest
+    }
+
+    /// Removes the oldest value.
+    pub fn pop_oldest(&mut self) -> Option<T> {
+        self.items.pop_front()
+    }
+
+    pub fn oldest(&self) -> Option<&T> {
+        self.items.front()
+    }
+
+    pub fn latest(&self) -> Option<&T> {
+        self.items.back()
+    }
+
+    /// How many values have been pushed out by newer ones.
+    pub fn overwritten(&self) -> u64 {
+        self.overwritten
+    }
+
+    /// Values from oldest to newest.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// The newest `n` values (all of them if fewer are held), oldest first.
+    pub fn latest_n(&self, n: usize) -> Iter<'_, T> {
+        self.items
+            .range(self.items.len() - n.min(self.items.len())..)
+    }
+
+    /// The newest `n` values as the two runs of memory they occupy. The
+    /// first run is empty unless the values wrap past the end of the
+    /// allocation.
+    pub fn latest_n_slices(&self, n: usize) -> (&[T], &[T]) {
+        let skip: usize = self.items.len() - n.min(self.items.len());
+        let (front, back) = self.items.as_slices();
+        if skip >= front.len() {
+            (&[], &back[skip - front.len()..])
+        } else {
+            (&front[skip..], back)
+        }
+    }
+
+    /// All values as the two runs of memory they occupy, oldest first.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        self.items.as_slices()
+    }
+
+    /// Rotates the values into one slice, oldest first. O(n) when wrapped.
+    pub fn make_contiguous(&mut self) -> &[T] {
+        self.items.make_contiguous()
+    }
+
+    /// Forgets every value; the allocation is kept.
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+impl<T> Extend<T> for RingBuffer<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Sample data
+// ----------------------------------------------------------------------------
+
+/// `count` temperature readings in degrees Celsius: about 21.5 with a slow
+/// drift and some noise, and a heater switching on at sample 40.
+pub fn sensor_samples(count: usize) -> Vec<f64> {
+    let mut rng: Lcg = Lcg::new(0x5EED);
+    (0..count)
+        .map(|i| {
+            let noise: f64 = rng.below(21) as f64 / 100.0 - 0.1;
+            let drift: f64 = (i % 20) as f64 / 40.0;
+            let heater: f64 = if i >= 40 { 0.15 * (i - 40) as f64 } else { 0.0 };
+            21.25 + drift + heater + noise
+        })
+        .collect()
+}
+
+fn mean<'a>(values: impl ExactSizeIterator<Item = &'a f64>) -> f64 {
+    let count: usize = values.len();
+    values.sum::<f64>() / count as f64
+}
+
+// ----------------------------------------------------------------------------
+// Demos
+// ----------------------------------------------------------------------------
+
+/// Demonstrates pushes into a full buffer and logical-order reads.
+pub fn ring_buffer_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Ring Buffer Basics")?;
+
+    let mut buffer: RingBuffer<u32> = RingBuffer::new(4);
+    for value in 1..=6 {
+        let overwritten: Option<u32> = buffer.push(value);
+        writeln!(
+            out,
+            "  push({}) -> {:<8} {:?}",
+            value,
+            format!("{:?}", overwritten),
+            buffer.iter().collect::<Vec<_>>()
+        )?;
+    }
+    writeln!(
+        out,
+        "len {}, full: {}, overwritten so far: {}",
+        buffer.len(),
+        buffer.is_full(),
+        buffer.overwritten()
+    )?;
+    writeln!(
+        out,
+        "oldest(): {:?}, latest(): {:?}",
+        buffer.oldest(),
+        buffer.latest()
+    )?;
+
+    // The deque's head has moved two slots, so the values wrap around the
+    // end of the allocation, but iteration is still oldest first
+    let (front, back) = buffer.as_slices();
+    writeln!(out, "as_slices(): {:?} then {:?}", front, back)?;
+    writeln!(
+        out,
+        "iter().rev(), newest first: {:?}",
+        buffer.iter().rev().collect::<Vec<_>>()
+    )?;
+
+    // Popping makes room again without anything being overwritten
+    writeln!(out, "pop_oldest(): {:?}", buffer.pop_oldest())?;
+    writeln!(out, "push(7) -> {:?}", buffer.push(7))?;
+    writeln!(out, "Capacity still {}", buffer.capacity())?;
+
+    Ok(())
+}
+
+/// Demonstrates reading the newest values of a wrapped buffer.
+pub fn latest_n_views(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Latest-n Views")?;
+
+    let mut buffer: RingBuffer<u32> = RingBuffer::new(8);
+    buffer.extend(1..=13);
+    writeln!(
+        out,
+        "Capacity 8 after pushing 1..=13: {:?}",
+        buffer.iter().collect::<Vec<_>>()
+    )?;
+    let (front, back) = buffer.as_slices();
+    writeln!(out, "Memory runs: {:?} then {:?}", front, back)?;
+
+    for n in [2, 5, 8, 20] {
+        let (first, second) = buffer.latest_n_slices(n);
+        writeln!(
+            out,
+            "  latest_n({:>2}): {:<28} slices {:?} + {:?}",
+            n,
+            format!("{:?}", buffer.latest_n(n).collect::<Vec<_>>()),
+            first,
+            second
+        )?;
+    }
+
+    // latest_n is a DoubleEndedIterator and ExactSizeIterator, so the usual
+    // adapters work on it
+    writeln!(
+        out,
+        "Sum of the latest 3: {}",
+        buffer.latest_n(3).sum::<u32>()
+    )?;
+    writeln!(
+        out,
+        "Newest first, latest 3: {:?}",
+        buffer.latest_n(3).rev().collect::<Vec<_>>()
+    )?;
+
+    // One slice when a caller needs it: make_contiguous rotates in place
+    writeln!(out, "make_contiguous(): {:?}", buffer.make_contiguous())?;
+    writeln!(
+        out,
+        "latest_n_slices(5) now: {:?}",
+        buffer.latest_n_slices(5)
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: a sensor sampled once a second into a one-minute
+/// buffer, with a short and a long moving average compared on every
+/// sample to catch a trend.
+///
+/// The buffer holds the last 60 readings whatever the uptime, and the
+/// averages, min and max are read from latest_n views of it without
+/// copying.
+pub fn practical_sensor_sampling(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Sensor Sampling")?;
+
+    let window: usize = 60;
+    let short: usize = 5;
+    let long: usize = 20;
+    let mut buffer: RingBuffer<f64> = RingBuffer::new(window);
+    let mut alert_at: Option<usize> = None;
+
+    for (second, reading) in sensor_samples(125).into_iter().enumerate() {
+        buffer.push(reading);
+        if buffer.len() < long {
+            continue;
+        }
+        let short_mean: f64 = mean(buffer.latest_n(short));
+        let long_mean: f64 = mean(buffer.latest_n(long));
+        if second % 20 == 19 {
+            writeln!(
+                out,
+                "  second {:>3}  latest {:.2}  mean of last {} {:.2}  of last {} {:.2}",
+                second, reading, short, short_mean, long, long_mean
+            )?;
+        }
+        if alert_at.is_none() && short_mean - long_mean > 0.75 {
+            alert_at = Some(second);
+        }
+    }
+
+    match alert_at {
+        Some(second) => writeln!(
+            out,
+            "Short mean ran 0.75 above the long mean at second {}: rising temperature",
+            second
+        )?,
+        None => writeln!(out, "No trend detected")?,
+    }
+
+    let (min, max) = buffer
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &value| {
+            (lo.min(value), hi.max(value))
+        });
+    writeln!(
+        out,
+        "Last minute: {} readings, min {:.2}, max {:.2}, {} older readings overwritten",
+        buffer.len(),
+        min,
+        max,
+        buffer.overwritten()
+    )?;
+
+    // A block-based consumer, like an audio filter, takes the newest block
+    // as plain slices: two at most, no copy
+    let (first, second) = buffer.latest_n_slices(16);
+    writeln!(
+        out,
+        "Newest 16 readings as slices of {} and {} values",
+        first.len(),
+        second.len()
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,599 @@
+// Counting with a small integer type saves memory, but `+= 1` on a full
+// count doesn't stop. A debug build panics with "attempt to add with
+// overflow"; a release build wraps around without a word:
+//
+//   i8 count at 127, += 1  ->  -128 in release, a panic in debug
+//   u8 count at 255, += 1  ->     0 in release, a panic in debug
+//
+// SaturatingCounter<K, M> wraps the map M, a HashMap<K, C> or a
+// BTreeMap<K, C> for counts in key order, and never lets a count in it
+// overflow. It has two modes:
+//
+//   Saturating   stop at the maximum: 250 + 10 in a u8 stores 255
+//   Checked      refuse the whole increment: 250 + 10 leaves 250
+//
+// Either way the increments that didn't fit are recorded per key, so a
+// report can say which counts are no longer exact and by how much.
+// `try_add` is the checked primitive for callers that want the error
+// right away instead.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap, btree_map, hash_map};
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "silent_overflow",
+        "What += 1 does to a full i8 or u8 count",
+        silent_overflow,
+    )?;
+
+    section(
+        out,
+        "saturating_vs_checked",
+        "The two overflow modes, and the increments each one loses",
+        saturating_vs_checked,
+    )?;
+
+    section(
+        out,
+        "overflow_report",
+        "Which counts stopped being exact, and when to widen the type",
+        overflow_report,
+    )?;
+
+    section(
+        out,
+        "practical_request_counters",
+        "Practical demo: per-client request counts in u8, with saturation flagged",
+        practical_request_counters,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Count types
+// ---------------------------------------------------------------------------
+
+/// An integer type a [`SaturatingCounter`] can count in.
+pub trait Count: Copy + Ord + Debug + Display {
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// The value as u64; counts are never negative.
+    fn to_u64(self) -> u64;
+}
+
+macro_rules! impl_count {
+    ($($int:ty),*) => {
+        $(
+            impl Count for $int {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MAX: Self = <$int>::MAX;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$int>::checked_add(self, other)
+                }
+
+                fn to_u64(self) -> u64 {
+                    self as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_count!(u8, u16, u32, u64, usize, i8, i16, i32, i64);
+
+/// Adds `by` to `count` as `mode` says and returns the part of `by` that
+/// was dropped: 0 when it fit.
+///
+/// # Panics
+///
+/// If `by` is negative: counts only go up.
+fn bump<C: Count>(count: &mut C, by: C, mode: OverflowMode) -> u64 {
+    assert!(by >= C::ZERO, "cannot add {} to a count", by);
+    if let Some(sum) = count.checked_add(by) {
+        *count = sum;
+        return 0;
+    }
+    match mode {
+        OverflowMode::Saturating => {
+            let dropped: u64 = by.to_u64() - (C::MAX.to_u64() - count.to_u64());
+            *count = C::MAX;
+            dropped
+        }
+        OverflowMode::Checked => by.to_u64(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SaturatingCounter
+// ---------------------------------------------------------------------------
+
+/// What an increment that doesn't fit does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum OverflowMode {
+    /// Store the maximum and drop the rest.
+    #[default]
+    Saturating,
+    /// Leave the count as it was and drop the whole increment.
+    Checked,
+}
+
+/// An increment that didn't fit: `count + by` is past `C::MAX`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow<C> {
+    pub count: C,
+    pub by: C,
+}
+
+impl<C: Count> Display for Overflow<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} + {} overflows the maximum {}",
+            self.count,
+            self.by,
+            C::MAX
+        )
+    }
+}
+
+impl<C: Count> std::error::Error for Overflow<C> {}
+
+/// A map of counts that never overflow. `M` is the map they live in:
+/// `HashMap<K, C>`, or `BTreeMap<K, C>` for counts in key order, with `C`
+/// any [`Count`] type. Increments that don't fit are handled by the
+/// [`OverflowMode`] and recorded per key.
+#[derive(Clone, Debug)]
+pub struct SaturatingCounter<K, M> {
+    counts: M,
+    mode: OverflowMode,
+    // Increments dropped per key, as u64 so they can't overflow either
+    lost: HashMap<K, u64>,
+}
+
+impl<K, M: Default> SaturatingCounter<K, M> {
+    pub fn new(mode: OverflowMode) -> Self {
+        SaturatingCounter {
+            counts: M::default(),
+            mode,
+            lost: HashMap::new(),
+        }
+    }
+
+    pub fn mode(&self) -> OverflowMode {
+        self.mode
+    }
+
+    /// Each key whose count overflowed, with the increments dropped.
+    pub fn overflows(&self) -> &HashMap<K, u64> {
+        &self.lost
+    }
+
+    /// Increments dropped over all keys.
+    pub fn lost(&self) -> u64 {
+        self.lost.values().sum()
+    }
+
+    /// Whether every count is exact: nothing has overflowed yet.
+    pub fn is_exact(&self) -> bool {
+        self.lost.is_empty()
+    }
+}
+
+impl<K, M: Default> Default for SaturatingCounter<K, M> {
+    fn default() -> Self {
+        SaturatingCounter::new(OverflowMode::default())
+    }
+}
+
+// The counting methods over either map; only the key and lookup bounds
+// differ. `$entry` is the map's module, for its Entry enum.
+macro_rules! counter_impl {
+    ($map:ident, $entry:ident, [$($key:tt)+], [$($query:tt)+]) => {
+        impl<K: $($key)+, C: Count> SaturatingCounter<K, $map<K, C>> {
+            /// Adds one `key`; returns its count afterwards.
+            pub fn add(&mut self, key: K) -> C
+            where
+                K: Clone,
+            {
+                self.add_n(key, C::ONE)
+            }
+
+            /// Adds `by` to `key`'s count and returns the count afterwards.
+            /// If it doesn't fit, the mode decides what is stored and the
+            /// dropped part is recorded in [`overflows`](Self::overflows).
+            /// Only an overflow clones the key.
+            ///
+            /// # Panics
+            ///
+            /// If `by` is negative: counts only go up.
+            pub fn add_n(&mut self, key: K, by: C) -> C
+            where
+                K: Clone,
+            {
+                assert!(by >= C::ZERO, "cannot add {} to a count", by);
+                match self.counts.entry(key) {
+                    // Nothing overflows from zero, so a new key just stores `by`
+                    $entry::Entry::Vacant(slot) => *slot.insert(by),
+                    $entry::Entry::Occupied(mut slot) => {
+                        let dropped: u64 = bump(slot.get_mut(), by, self.mode);
+                        if dropped > 0 {
+                            *self.lost.entry(slot.key().clone()).or_insert(0) += dropped;
+                        }
+                        *slot.get()
+                    }
+                }
+            }
+
+            /// Adds `by` to `key`'s count only if it fits, whatever the
+            /// mode, and returns the new count. An overflow changes nothing
+            /// and isn't recorded: the caller has the error.
+            ///
+            /// # Panics
+            ///
+            /// If `by` is negative: counts only go up.
+            pub fn try_add(&mut self, key: K, by: C) -> Result<C, Overflow<C>> {
+                assert!(by >= C::ZERO, "cannot add {} to a count", by);
+                let count: &mut C = self.counts.entry(key).or_insert(C::ZERO);
+                match count.checked_add(by) {
+                    Some(sum) => {
+                        *count = sum;
+                        Ok(sum)
+                    }
+                    None => Err(Overflow { count: *count, by }),
+                }
+            }
+
+            /// `key`'s count; 0 if it was never added. `key` can be any
+            /// borrowed form of `K`, as with the map's own `get`.
+            pub fn get<Q>(&self, key: &Q) -> C
+            where
+                K: Borrow<Q>,
+                Q: $($query)+ + ?Sized,
+            {
+                self.counts.get(key).copied().unwrap_or(C::ZERO)
+            }
+
+            /// The exact count of `key`: what is stored plus what was
+            /// dropped.
+            pub fn exact<Q>(&self, key: &Q) -> u64
+            where
+                K: Borrow<Q>,
+                Q: $($query)+ + ?Sized,
+            {
+                self.get(key).to_u64() + self.lost.get(key).copied().unwrap_or(0)
+            }
+
+            /// Number of distinct keys.
+            pub fn len(&self) -> usize {
+                self.counts.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.counts.is_empty()
+            }
+
+            /// Each key with its count, in the map's order.
+            pub fn iter(&self) -> impl Iterator<Item = (&K, C)> {
+                self.counts.iter().map(|(key, &count)| (key, count))
+            }
+
+            /// Every key with its count, ordered by key.
+            pub fn sorted(&self) -> Vec<(&K, C)>
+            where
+                K: Ord,
+            {
+                let mut entries: Vec<(&K, C)> = self.iter().collect();
+                entries.sort();
+                entries
+            }
+
+            /// The counts moved into a wider type, each with its dropped
+            /// increments added back. A total the new type can't hold
+            /// either is stored as its maximum, and the rest recorded again.
+            pub fn widen<D>(mut self) -> SaturatingCounter<K, $map<K, D>>
+            where
+                K: Clone,
+                D: Count + TryFrom<u64>,
+            {
+                let mut wider: SaturatingCounter<K, $map<K, D>> = SaturatingCounter::new(self.mode);
+                for (key, count) in self.counts {
+                    let dropped: u64 = self.lost.remove(&key).unwrap_or(0);
+                    let total: u64 = count.to_u64().saturating_add(dropped);
+                    match D::try_from(total) {
+                        Ok(count) => {
+                            wider.counts.insert(key, count);
+                        }
+                        Err(_) => {
+                            wider.lost.insert(key.clone(), total - D::MAX.to_u64());
+                            wider.counts.insert(key, D::MAX);
+                        }
+                    }
+                }
+                wider
+            }
+        }
+
+        impl<K: $($key)+ + Clone, C: Count> Extend<K> for SaturatingCounter<K, $map<K, C>> {
+            fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+                for key in iter {
+                    self.add(key);
+                }
+            }
+        }
+
+        impl<K: $($key)+ + Clone, C: Count> FromIterator<K> for SaturatingCounter<K, $map<K, C>> {
+            /// Counts in the default, saturating mode.
+            fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+                let mut counter: SaturatingCounter<K, $map<K, C>> = SaturatingCounter::default();
+                counter.extend(iter);
+                counter
+            }
+        }
+    };
+}
+
+// The lost-increment log is a HashMap whatever the backing map, so BTreeMap
+// keys need Hash as well as Ord
+counter_impl!(HashMap, hash_map, [Hash + Eq], [Hash + Eq]);
+counter_impl!(BTreeMap, btree_map, [Ord + Hash], [Ord + Hash]);
+
+/// A log of `requests` lines from a few clients, skewed so that the first
+/// ones send far more than the rest.
+pub fn sample_clients(requests: usize) -> Vec<String> {
+    let mut rng: Lcg = Lcg::new(29);
+    (0..requests)
+        .map(|_| {
+            // Squaring a uniform draw piles the mass onto low ids
+            let uniform: f64 = rng.unit();
+            format!("10.0.0.{}", (uniform * uniform * 12.0) as u32 + 1)
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// Demonstrates what plain arithmetic does at the top of a small type.
+pub fn silent_overflow(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "\n  {:<6}  {:>5}  {:<13}  {:<13}  {:<13}",
+        "type", "count", "wrapping +1", "checked +1", "saturating +1"
+    )?;
+    writeln!(
+        out,
+        "  {:<6}  {:>5}  {:<13}  {:<13}  {}",
+        "i8",
+        i8::MAX,
+        i8::MAX.wrapping_add(1),
+        format!("{:?}", i8::MAX.checked_add(1)),
+        i8::MAX.saturating_add(1)
+    )?;
+    writeln!(
+        out,
+        "  {:<6}  {:>5}  {:<13}  {:<13}  {}",
+        "u8",
+        u8::MAX,
+        u8::MAX.wrapping_add(1),
+        format!("{:?}", u8::MAX.checked_add(1)),
+        u8::MAX.saturating_add(1)
+    )?;
+    writeln!(
+        out,
+        "\n  `+= 1` is the wrapping column in a release build and a panic in a"
+    )?;
+    writeln!(
+        out,
+        "  debug build. A word seen 200 times in an i8 map, built for release:"
+    )?;
+
+    let mut plain: HashMap<&str, i8> = HashMap::new();
+    let mut counter: SaturatingCounter<&str, HashMap<&str, i8>> =
+        SaturatingCounter::new(OverflowMode::Saturating);
+    for _ in 0..200 {
+        let count: &mut i8 = plain.entry("the").or_insert(0);
+        *count = count.wrapping_add(1);
+        counter.add("the");
+    }
+    writeln!(
+        out,
+        "    HashMap<&str, i8> with wrapping adds:     {}",
+        plain["the"]
+    )?;
+    writeln!(
+        out,
+        "    SaturatingCounter over HashMap<&str, i8>: {} (exact: {}, {} dropped)",
+        counter.get("the"),
+        counter.exact(&"the"),
+        counter.lost()
+    )?;
+    writeln!(
+        out,
+        "\n  The wrapped count is negative and nothing says so. The saturated one"
+    )?;
+    writeln!(
+        out,
+        "  is wrong too, but never smaller than the truth, and the counter knows."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the two modes on the same increments.
+pub fn saturating_vs_checked(out: &mut dyn Write) -> io::Result<()> {
+    let increments: [(&str, u8); 5] = [
+        ("disk", 200),
+        ("disk", 50),
+        ("disk", 10),
+        ("disk", 1),
+        ("cpu", 90),
+    ];
+    writeln!(out, "\n  u8 counts, max 255:")?;
+    writeln!(
+        out,
+        "    {:<16}  {:>10}  {:>7}",
+        "add_n", "Saturating", "Checked"
+    )?;
+    let mut saturating: SaturatingCounter<&str, HashMap<&str, u8>> =
+        SaturatingCounter::new(OverflowMode::Saturating);
+    let mut checked: SaturatingCounter<&str, HashMap<&str, u8>> =
+        SaturatingCounter::new(OverflowMode::Checked);
+    for (key, by) in increments {
+        writeln!(
+            out,
+            "    {:<16}  {:>10}  {:>7}",
+            format!("({:?}, {})", key, by),
+            saturating.add_n(key, by),
+            checked.add_n(key, by)
+        )?;
+    }
+    for (name, counter) in [("Saturating", &saturating), ("Checked", &checked)] {
+        writeln!(
+            out,
+            "  {:<10}: disk {} stored, {} dropped, exact {}",
+            name,
+            counter.get("disk"),
+            counter.overflows()["disk"],
+            counter.exact(&"disk")
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\n  Saturating keeps as much as fits: the stored count is the closest u8"
+    )?;
+    writeln!(
+        out,
+        "  to the truth. Checked drops each increment that doesn't fit, so a"
+    )?;
+    writeln!(
+        out,
+        "  small one can still land after a large one was refused (250 + 1)."
+    )?;
+
+    let mut strict: SaturatingCounter<&str, HashMap<&str, u8>> = SaturatingCounter::default();
+    strict.add_n("disk", 250);
+    writeln!(out, "\n  try_add reports instead of recording:")?;
+    for by in [5, 1] {
+        match strict.try_add("disk", by) {
+            Ok(count) => writeln!(out, "    try_add(\"disk\", {}) -> Ok({})", by, count)?,
+            Err(overflow) => writeln!(out, "    try_add(\"disk\", {}) -> Err: {}", by, overflow)?,
+        }
+    }
+    writeln!(
+        out,
+        "    stored {}, is_exact(): {}",
+        strict.get("disk"),
+        strict.is_exact()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the overflow report and widening a counter once counts
+/// outgrow their type.
+pub fn overflow_report(out: &mut dyn Write) -> io::Result<()> {
+    let text: String = "the cat sat on the mat and the dog sat on the log ".repeat(40);
+    let words: SaturatingCounter<&str, HashMap<&str, i8>> = text.split_whitespace().collect();
+
+    writeln!(
+        out,
+        "\n  {} words, {} distinct, counted in i8 (max 127):",
+        text.split_whitespace().count(),
+        words.len()
+    )?;
+    for (word, count) in words.sorted() {
+        let note: String = match words.overflows().get(word) {
+            Some(dropped) => format!("saturated, {} dropped", dropped),
+            None => "exact".to_string(),
+        };
+        writeln!(out, "    {:<4} {:>4}  {}", word, count, note)?;
+    }
+    writeln!(
+        out,
+        "  is_exact(): {}, {} increments dropped in all",
+        words.is_exact(),
+        words.lost()
+    )?;
+
+    let wider: SaturatingCounter<&str, HashMap<&str, i32>> = words.widen();
+    writeln!(out, "\n  widen::<i32>() adds the dropped increments back:")?;
+    let entries: Vec<String> = wider
+        .sorted()
+        .into_iter()
+        .map(|(word, count)| format!("{}: {}", word, count))
+        .collect();
+    writeln!(out, "    {{{}}}", entries.join(", "))?;
+    writeln!(out, "    is_exact(): {}", wider.is_exact())?;
+    writeln!(
+        out,
+        "\n  Recording what was dropped is what makes the small type safe to try:"
+    )?;
+    writeln!(
+        out,
+        "  the report says when it stopped being enough, and nothing is lost."
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: requests per client in one-byte counters. A client
+/// whose count saturated sent at least 255 requests: enough to throttle,
+/// and the exact figure is still there for the report.
+pub fn practical_request_counters(out: &mut dyn Write) -> io::Result<()> {
+    let log: Vec<String> = sample_clients(3_000);
+    let mut per_client: SaturatingCounter<&str, HashMap<&str, u8>> = SaturatingCounter::default();
+    per_client.extend(log.iter().map(String::as_str));
+
+    let mut clients: Vec<(&&str, u8)> = per_client.sorted();
+    clients.sort_by_key(|&(client, count)| (std::cmp::Reverse(count), *client));
+    writeln!(
+        out,
+        "\n  {} requests from {} clients, counted in u8:",
+        log.len(),
+        per_client.len()
+    )?;
+    writeln!(
+        out,
+        "    {:<10}  {:>6}  {:>7}  action",
+        "client", "stored", "exact"
+    )?;
+    for (client, count) in clients {
+        let action: &str = if count == u8::MAX { "throttle" } else { "ok" };
+        writeln!(
+            out,
+            "    {:<10}  {:>6}  {:>7}  {}",
+            client,
+            count,
+            per_client.exact(client),
+            action
+        )?;
+    }
+    writeln!(
+        out,
+        "\n  {} of {} clients saturated; the map holds one byte per count",
+        per_client.overflows().len(),
+        per_client.len()
+    )?;
+    writeln!(
+        out,
+        "  and the exact totals live only for the few that needed them."
+    )?;
+
+    Ok(())
+}
@@ -8,54 +8,331 @@
 //   - Unordered             - Always sorted
 //   - Needs Hash + Eq       - Needs Ord
 //   - No range queries      - Supports range queries!
-
+//
+// A third option needs no hashing and no tree: a Vec kept sorted and
+// deduplicated (SortedVecSet below). contains is a binary search, the
+// elements sit in one contiguous allocation, and two sets intersect by
+// walking both in step, like the merge in merge sort. Inserting in the
+// middle shifts everything after it, so it suits sets that are built once
+// and then queried - tag lists, posting lists, allow-lists.
+
+use demo_framework::section;
+use std::cmp::Ordering;
 use std::collections::{BTreeSet, HashSet};
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "creating_hashsets",
+        "All the different ways to create a HashSet",
+        creating_hashsets,
+    )?;
+
+    section(
+        out,
+        "adding_removing_elements",
+        "Adding and removing elements from a HashSet",
+        adding_removing_elements,
+    )?;
+
+    section(
+        out,
+        "checking_membership",
+        "Checking membership in a HashSet",
+        checking_membership,
+    )?;
+
+    section(
+        out,
+        "set_operations",
+        "Set operations: union, intersection, difference, symmetric_difference",
+        set_operations,
+    )?;
+
+    section(
+        out,
+        "set_relationships",
+        "Subset, superset, and disjoint checks",
+        set_relationships,
+    )?;
+
+    section(
+        out,
+        "iterating_hashsets",
+        "Iteration patterns for HashSet",
+        iterating_hashsets,
+    )?;
+
+    section(
+        out,
+        "custom_types_in_hashset",
+        "Using custom types in HashSet",
+        custom_types_in_hashset,
+    )?;
+
+    section(
+        out,
+        "creating_btreesets",
+        "Creating BTreeSet - the sorted set",
+        creating_btreesets,
+    )?;
+
+    section(
+        out,
+        "btreeset_sorted_iteration",
+        "BTreeSet's feature: sorted iteration",
+        btreeset_sorted_iteration,
+    )?;
+
+    section(
+        out,
+        "btreeset_range_queries",
+        "Range queries - BTreeSet's other feature",
+        btreeset_range_queries,
+    )?;
+
+    section(
+        out,
+        "btreeset_min_max",
+        "First/last element access in BTreeSet",
+        btreeset_min_max,
+    )?;
+
+    section(
+        out,
+        "btreeset_set_operations",
+        "BTreeSet supports all the same set operations as HashSet",
+        btreeset_set_operations,
+    )?;
+
+    section(
+        out,
+        "sorted_vec_set",
+        "A sorted Vec as a set: binary search and merge intersection",
+        sorted_vec_set,
+    )?;
+
+    section(
+        out,
+        "practical_deduplication",
+        "Practical demo: Deduplication with order preservation options",
+        practical_deduplication,
+    )?;
+
+    section(
+        out,
+        "practical_finding_duplicates",
+        "Practical demo: Finding duplicates in a collection",
+        practical_finding_duplicates,
+    )?;
+
+    section(
+        out,
+        "practical_comparing_lists",
+        "Practical demo: Comparing two lists to find common/different elements",
+        practical_comparing_lists,
+    )?;
+
+    section(
+        out,
+        "practical_tag_system",
+        "Practical demo: Tag system using sets",
+        practical_tag_system,
+    )?;
+
+    section(
+        out,
+        "practical_posting_lists",
+        "Practical demo: Intersecting posting lists stored as sorted Vecs",
+        practical_posting_lists,
+    )?;
+
+    section(
+        out,
+        "practical_leaderboard",
+        "Practical demo: Using BTreeSet for a leaderboard with rankings",
+        practical_leaderboard,
+    )?;
+
+    Ok(())
+}
+
+/// A set stored as a sorted Vec with no duplicates.
+///
+/// `contains` is O(log n) like `BTreeSet`, but over one contiguous slice;
+/// `insert` and `remove` shift the tail, O(n). Build it in one go with
+/// `from_vec` or `collect` rather than inserting one at a time.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SortedVecSet<T> {
+    items: Vec<T>,
+}
+
+impl<T: Ord> SortedVecSet<T> {
+    pub fn new() -> Self {
+        SortedVecSet { items: Vec::new() }
+    }
+
+    /// Sorts and deduplicates `items`: O(n log n), in place.
+    pub fn from_vec(mut items: Vec<T>) -> Self {
+        items.sort_unstable();
+        items.dedup();
+        SortedVecSet { items }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.items.binary_search(value).is_ok()
+    }
+
+    /// Adds `value` at its sorted position. Returns false if it was
+    /// already there.
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.items.binary_search(&value) {
+            Ok(_) => false,
+            Err(position) => {
+                self.items.insert(position, value);
+                true
+            }
+        }
+    }
+
+    pub fn remove(&mut self, value: &T) -> bool {
+        match self.items.binary_search(value) {
+            Ok(position) => {
+                self.items.remove(position);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    /// The elements in both sets, by walking both in step: O(n + m).
+    pub fn intersection(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        let (mut i, mut j) = (0, 0);
+        let mut common: Vec<T> = Vec::new();
+        while i < self.items.len() && j < other.items.len() {
+            match self.items[i].cmp(&other.items[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    common.push(self.items[i].clone());
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        SortedVecSet { items: common }
+    }
+
+    /// The same intersection, by binary-searching each element of the
+    /// smaller set in the larger: O(small * log(large)), which beats the
+    /// merge when one set is far smaller. Each search starts where the last
+    /// one stopped.
+    pub fn intersection_by_search(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        let (small, large) = if self.len() <= other.len() {
+            (&self.items, &other.items)
+        } else {
+            (&other.items, &self.items)
+        };
+        let mut rest: &[T] = large;
+        let mut common: Vec<T> = Vec::new();
+        for value in small {
+            match rest.binary_search(value) {
+                Ok(position) => {
+                    common.push(value.clone());
+                    rest = &rest[position + 1..];
+                }
+                Err(position) => rest = &rest[position..],
+            }
+        }
+        SortedVecSet { items: common }
+    }
+
+    /// Whether every element of `self` is in `other`, in one merge pass.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        let mut rest = other.items.iter();
+        self.items
+            .iter()
+            .all(|value| rest.by_ref().find(|candidate| *candidate >= value) == Some(value))
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SortedVecSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        SortedVecSet::from_vec(iter.into_iter().collect())
+    }
+}
 
 /// Demonstrates all the different ways to create a HashSet.
 ///
 /// HashSet requires elements to implement Hash + Eq traits.
 /// Like HashMap, it uses SipHash by default for security.
-pub fn creating_hashsets() {
+pub fn creating_hashsets(out: &mut dyn Write) -> io::Result<()> {
     // Method 1: HashSet::new()
     // The most common way - start empty and add items
     let mut set: HashSet<i8> = HashSet::new();
     set.insert(1);
     set.insert(2);
     set.insert(3);
-    println!("Created with new(): {:?}", set);
+    writeln!(out, "Created with new(): {:?}", set)?;
 
     // Method 2: HashSet::from() - from array (Rust 1.56+)
     // Concise syntax for small, known sets
     let set2: HashSet<&str> = HashSet::from(["apple", "banana", "cherry"]);
-    println!("Created from array: {:?}", set2);
+    writeln!(out, "Created from array: {:?}", set2)?;
 
     // Method 3: collect() from iterator
     // Useful when transforming data
     let set3: HashSet<i8> = (1..=5).collect();
-    println!("Created with collect(): {:?}", set3);
+    writeln!(out, "Created with collect(): {:?}", set3)?;
 
     // Method 4: From a Vec (automatically deduplicates!)
     // This is a common pattern for removing duplicates
     let numbers: Vec<i8> = vec![1, 2, 2, 3, 3, 3, 4, 4, 4, 4];
     let unique: HashSet<i8> = numbers.into_iter().collect();
-    println!("From vec with duplicates: {:?}", unique);
-    println!("  (Original had 10 items, set has {})", unique.len());
+    writeln!(out, "From vec with duplicates: {:?}", unique)?;
+    writeln!(out, "  (Original had 10 items, set has {})", unique.len())?;
 
     // Method 5: with_capacity (performance optimization)
     // Pre-allocate when you know approximate size
     let large_set: HashSet<i8> = HashSet::with_capacity(1_000);
-    println!(
+    writeln!(
+        out,
         "Created with capacity 1000, current len: {}",
         large_set.len()
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates adding and removing elements from a HashSet.
 ///
 /// Key insight: insert() returns a bool indicating whether the value
 /// was newly added (true) or already existed (false).
-pub fn adding_removing_elements() {
-    println!("Adding and removing elements");
+pub fn adding_removing_elements(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Adding and removing elements")?;
 
     let mut fruits: HashSet<&str> = HashSet::new();
 
@@ -64,196 +341,214 @@ pub fn adding_removing_elements() {
     let added2: bool = fruits.insert("banana");
     let added3: bool = fruits.insert("apple"); // Duplicate!
 
-    println!("insert(\"apple\"): {} (was new)", added1);
-    println!("insert(\"banana\"): {} (was new)", added2);
-    println!("insert(\"apple\"): {} (already existed!)", added3);
-    println!("Set after inserts: {:?}", fruits);
+    writeln!(out, "insert(\"apple\"): {} (was new)", added1)?;
+    writeln!(out, "insert(\"banana\"): {} (was new)", added2)?;
+    writeln!(out, "insert(\"apple\"): {} (already existed!)", added3)?;
+    writeln!(out, "Set after inserts: {:?}", fruits)?;
 
     // remove() returns true if the value was present
     let removed1: bool = fruits.remove("apple");
     let removed2: bool = fruits.remove("grape"); // Not in set
 
-    println!("\nremove(\"apple\"): {} (was present)", removed1);
-    println!("remove(\"grape\"): {} (wasn't there)", removed2);
-    println!("Set after removes: {:?}", fruits);
+    writeln!(out, "\nremove(\"apple\"): {} (was present)", removed1)?;
+    writeln!(out, "remove(\"grape\"): {} (wasn't there)", removed2)?;
+    writeln!(out, "Set after removes: {:?}", fruits)?;
 
     // take() - removes and returns the value (if present)
     // Useful when you need ownership of the removed value
     fruits.insert("cherry");
     fruits.insert("date");
     let taken: Option<&str> = fruits.take("cherry");
-    println!("\ntake(\"cherry\"): {:?}", taken);
-    println!("Set after take: {:?}", fruits);
+    writeln!(out, "\ntake(\"cherry\"): {:?}", taken)?;
+    writeln!(out, "Set after take: {:?}", fruits)?;
 
     // retain() - keep only elements matching a predicate
     // Great for bulk filtering
     let mut numbers: HashSet<i8> = (1..=10).collect();
-    println!("\nBefore retain: {:?}", numbers);
+    writeln!(out, "\nBefore retain: {:?}", numbers)?;
     numbers.retain(|&x| x % 2 == 0); // Keep only even numbers
-    println!("After retain (even only): {:?}", numbers);
+    writeln!(out, "After retain (even only): {:?}", numbers)?;
 
     // clear() - remove all elements
     numbers.clear();
-    println!(
+    writeln!(
+        out,
         "After clear: {:?}, is_empty: {}",
         numbers,
         numbers.is_empty()
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates checking membership in a HashSet.
 ///
 /// Membership testing is O(1) average - this is the primary use case for HashSet.
-pub fn checking_membership() {
-    println!("Checking Membership");
+pub fn checking_membership(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Checking Membership")?;
 
     let languages: HashSet<&str> = HashSet::from(["Rust", "Python", "JavaScript", "Go", "C++"]);
 
-    println!("Set: {:?}", languages);
+    writeln!(out, "Set: {:?}", languages)?;
 
     // contains() - check if value exists (O(1) average)
     // This is the most common operation on sets
-    println!("\ncontains(\"Rust\"): {}", languages.contains("Rust"));
-    println!("contains(\"Java\"): {}", languages.contains("Java"));
+    writeln!(out, "\ncontains(\"Rust\"): {}", languages.contains("Rust"))?;
+    writeln!(out, "contains(\"Java\"): {}", languages.contains("Java"))?;
 
     // get() - get a reference to the value in the set
     // Useful when the set owns values and you need to borrow them
     match languages.get("Python") {
-        Some(lang) => println!("\nget(\"Python\") found: {}", lang),
-        None => println!("\nget(\"Python\") not found"),
+        Some(lang) => writeln!(out, "\nget(\"Python\") found: {}", lang)?,
+        None => writeln!(out, "\nget(\"Python\") not found")?,
     }
 
     match languages.get("Ruby") {
-        Some(lang) => println!("get(\"Ruby\") found: {}", lang),
-        None => println!("get(\"Ruby\") not found"),
+        Some(lang) => writeln!(out, "get(\"Ruby\") found: {}", lang)?,
+        None => writeln!(out, "get(\"Ruby\") not found")?,
     }
 
     // is_empty() and len()
-    println!(
+    writeln!(
+        out,
         "\nis_empty: {}, len: {}",
         languages.is_empty(),
         languages.len()
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates set operations: union, intersection, difference, symmetric_difference.
 ///
 /// These are the mathematical set operations that make sets so powerful.
 /// All operations return iterators, so you need to collect() them.
-pub fn set_operations() {
-    println!("Set Operations");
+pub fn set_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Set Operations")?;
 
     let set_a: HashSet<i8> = HashSet::from([1, 2, 3, 4, 5]);
     let set_b: HashSet<i8> = HashSet::from([4, 5, 6, 7, 8]);
 
-    println!("Set A: {:?}", set_a);
-    println!("Set B: {:?}", set_b);
+    writeln!(out, "Set A: {:?}", set_a)?;
+    writeln!(out, "Set B: {:?}", set_b)?;
 
     // UNION: All elements from both sets (A ∪ B)
     // Elements that appear in A OR B (or both)
     let union: HashSet<&i8> = set_a.union(&set_b).collect();
-    println!("\nUnion (A ∪ B): {:?}", union);
+    writeln!(out, "\nUnion (A ∪ B): {:?}", union)?;
     // {1, 2, 3, 4, 5} ∪ {4, 5, 6, 7, 8} = {1, 2, 3, 4, 5, 6, 7, 8}
 
     // INTERSECTION: Elements in both sets (A ∩ B)
     // Elements that appear in A AND B
     let intersection: HashSet<&i8> = set_a.intersection(&set_b).collect();
-    println!("Intersection (A ∩ B): {:?}", intersection);
+    writeln!(out, "Intersection (A ∩ B): {:?}", intersection)?;
     // {1, 2, 3, 4, 5} ∩ {4, 5, 6, 7, 8} = {4, 5}
 
     // DIFFERENCE: Elements in A but not in B (A - B)
     // Elements unique to the first set
     let difference_a_b: HashSet<&i8> = set_a.difference(&set_b).collect();
-    println!("Difference (A - B): {:?}", difference_a_b);
+    writeln!(out, "Difference (A - B): {:?}", difference_a_b)?;
     // {1, 2, 3, 4, 5} - {4, 5, 6, 7, 8} = {1, 2, 3}
 
     let difference_b_a: HashSet<&i8> = set_b.difference(&set_a).collect();
-    println!("Difference (B - A): {:?}", difference_b_a);
+    writeln!(out, "Difference (B - A): {:?}", difference_b_a)?;
     // {4, 5, 6, 7, 8} - {1, 2, 3, 4, 5} = {6, 7, 8}
 
     // SYMMETRIC DIFFERENCE: Elements in either but not both (A △ B)
     // Elements unique to each set (union minus intersection)
     let sym_diff: HashSet<&i8> = set_a.symmetric_difference(&set_b).collect();
-    println!("Symmetric Difference (A △ B): {:?}", sym_diff);
+    writeln!(out, "Symmetric Difference (A △ B): {:?}", sym_diff)?;
     // Elements in A or B but not both = {1, 2, 3, 6, 7, 8}
+
+    Ok(())
 }
 
 /// Demonstrates subset, superset, and disjoint checks.
 ///
 /// These predicates answer questions about set relationships.
-pub fn set_relationships() {
-    println!("Set Relationships");
+pub fn set_relationships(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Set Relationships")?;
 
     let small: HashSet<i16> = HashSet::from([1, 2]);
     let medium: HashSet<i16> = HashSet::from([1, 2, 3, 4, 5]);
     let large: HashSet<i16> = HashSet::from([1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
     let disjoint: HashSet<i16> = HashSet::from([100, 200, 300]);
 
-    println!("small: {:?}", small);
-    println!("medium: {:?}", medium);
-    println!("large: {:?}", large);
-    println!("disjoint: {:?}", disjoint);
+    writeln!(out, "small: {:?}", small)?;
+    writeln!(out, "medium: {:?}", medium)?;
+    writeln!(out, "large: {:?}", large)?;
+    writeln!(out, "disjoint: {:?}", disjoint)?;
 
     // is_subset() - are all elements of self in other?
-    println!("\nSubset checks:");
-    println!("  small ⊆ medium: {}", small.is_subset(&medium));
-    println!("  small ⊆ large: {}", small.is_subset(&large));
-    println!("  medium ⊆ small: {}", medium.is_subset(&small));
+    writeln!(out, "\nSubset checks:")?;
+    writeln!(out, "  small ⊆ medium: {}", small.is_subset(&medium))?;
+    writeln!(out, "  small ⊆ large: {}", small.is_subset(&large))?;
+    writeln!(out, "  medium ⊆ small: {}", medium.is_subset(&small))?;
 
     // is_superset() - does self contain all elements of other?
-    println!("\nSuperset checks:");
-    println!("  large ⊇ medium: {}", large.is_superset(&medium));
-    println!("  large ⊇ small: {}", large.is_superset(&small));
-    println!("  small ⊇ large: {}", small.is_superset(&large));
+    writeln!(out, "\nSuperset checks:")?;
+    writeln!(out, "  large ⊇ medium: {}", large.is_superset(&medium))?;
+    writeln!(out, "  large ⊇ small: {}", large.is_superset(&small))?;
+    writeln!(out, "  small ⊇ large: {}", small.is_superset(&large))?;
 
     // is_disjoint() - do the sets share NO elements?
-    println!("\nDisjoint checks:");
-    println!("  small ∩ disjoint = ∅: {}", small.is_disjoint(&disjoint));
-    println!("  small ∩ medium = ∅: {}", small.is_disjoint(&medium));
+    writeln!(out, "\nDisjoint checks:")?;
+    writeln!(
+        out,
+        "  small ∩ disjoint = ∅: {}",
+        small.is_disjoint(&disjoint)
+    )?;
+    writeln!(out, "  small ∩ medium = ∅: {}", small.is_disjoint(&medium))?;
+
+    Ok(())
 }
 
 /// Demonstrates iteration patterns for HashSet.
 ///
 /// Important: HashSet iteration order is NOT guaranteed!
 /// If you need ordered iteration, use BTreeSet.
-pub fn iterating_hashsets() {
-    println!("Iterating over HashSets");
+pub fn iterating_hashsets(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Iterating over HashSets")?;
 
     let colors: HashSet<&str> = HashSet::from(["red", "green", "blue", "yellow"]);
 
-    // Basic iteration (order is NOT guaranteed!)
-    println!("Basic iteration (order may vary):");
+    // Basic iteration (order is NOT guaranteed!), so the elements are
+    // collected and sorted before printing
+    let mut visited: Vec<&str> = Vec::new();
     for color in &colors {
-        println!("  {}", color);
+        visited.push(color);
+    }
+    visited.sort();
+    writeln!(out, "Basic iteration (order may vary, sorted here):")?;
+    for color in &visited {
+        writeln!(out, "  {}", color)?;
     }
 
     // iter() explicitly - same as &colors
-    print!("\nUsing iter(): ");
-    for color in colors.iter() {
-        print!("{} ", color);
-    }
-    println!();
+    let mut via_iter: Vec<&str> = colors.iter().copied().collect();
+    via_iter.sort();
+    writeln!(out, "\nUsing iter(), sorted: {}", via_iter.join(" "))?;
 
     // Consuming iteration - takes ownership
     let nums: HashSet<i8> = HashSet::from([1, 2, 3]);
-    print!("\nConsuming (into_iter): ");
-    for num in nums {
-        // nums is moved here
-        print!("{} ", num);
-    }
-    println!();
-    // nums is no longer valid after into_iter()
+    // nums is moved here and no longer valid after into_iter()
+    let mut consumed: Vec<i8> = nums.into_iter().collect();
+    consumed.sort();
+    writeln!(out, "\nConsuming (into_iter), sorted: {:?}", consumed)?;
 
     // Functional style with iterator adapters
     let numbers: HashSet<i8> = (1..=10).collect();
     let sum: i8 = numbers.iter().sum();
     let count_even: usize = numbers.iter().filter(|&x| x % 2 == 0).count();
-    let squares: Vec<i8> = numbers.iter().map(|&x| x * x).collect();
+    let mut squares: Vec<i8> = numbers.iter().map(|&x| x * x).collect();
+    squares.sort();
 
-    println!("\nFunctional operations on {:?}:", numbers);
-    println!("  Sum: {}", sum);
-    println!("  Even count: {}", count_even);
-    println!("  Squares: {:?}", squares);
+    writeln!(out, "\nFunctional operations on {:?}:", numbers)?;
+    writeln!(out, "  Sum: {}", sum)?;
+    writeln!(out, "  Even count: {}", count_even)?;
+    writeln!(out, "  Squares (sorted): {:?}", squares)?;
+
+    Ok(())
 }
 
 /// Demonstrates using custom types in HashSet.
@@ -261,8 +556,8 @@ pub fn iterating_hashsets() {
 /// To use a custom type, derive Hash, Eq, and PartialEq.
 /// The derived Hash must be consistent with Eq:
 /// if a == b, then hash(a) == hash(b).
-pub fn custom_types_in_hashset() {
-    println!("Custom Types in HashSet");
+pub fn custom_types_in_hashset(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Custom Types in HashSet")?;
 
     // Derive the required traits for HashSet usage
     #[derive(Hash, Eq, PartialEq, Debug)]
@@ -278,24 +573,26 @@ pub fn custom_types_in_hashset() {
     visited.insert(Point { x: 0, y: 1 });
     visited.insert(Point { x: 0, y: 0 }); // Duplicate - ignored!
 
-    println!("Visited points: {:?}", visited);
-    println!("Count: {} (duplicate was ignored)", visited.len());
+    writeln!(out, "Visited points: {:?}", visited)?;
+    writeln!(out, "Count: {} (duplicate was ignored)", visited.len())?;
 
     // Check membership with a new Point instance
     // This works because Hash and Eq are based on field values
     let query: Point = Point { x: 0, y: 0 };
-    println!(
+    writeln!(
+        out,
         "\nAlready visited {:?}? {}",
         query,
         visited.contains(&query)
-    );
+    )?;
 
     let query2: Point = Point { x: 5, y: 5 };
-    println!(
+    writeln!(
+        out,
         "Already visited {:?}? {}",
         query2,
         visited.contains(&query2)
-    );
+    )?;
 
     // Custom type with enum
     #[derive(Hash, Eq, PartialEq, Debug, Clone)]
@@ -316,12 +613,15 @@ pub fn custom_types_in_hashset() {
         Permission::Admin,
     ]);
 
-    println!("\nUser permissions: {:?}", user_permissions);
-    println!("Admin permissions: {:?}", admin_permissions);
-    println!(
+    writeln!(out, "\nUser permissions: {:?}", user_permissions)?;
+    writeln!(out, "Admin permissions: {:?}", admin_permissions)?;
+    writeln!(
+        out,
         "User is subset of admin: {}",
         user_permissions.is_subset(&admin_permissions)
-    );
+    )?;
+
+    Ok(())
 }
 
 // =============================================================================
@@ -332,8 +632,8 @@ pub fn custom_types_in_hashset() {
 ///
 /// BTreeSet keeps elements in sorted order and supports range queries.
 /// Elements must implement Ord (not Hash).
-pub fn creating_btreesets() {
-    println!("Creating BTreeSets");
+pub fn creating_btreesets(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Creating BTreeSets")?;
 
     // Method 1: BTreeSet::new()
     let mut set: BTreeSet<i8> = BTreeSet::new();
@@ -341,33 +641,35 @@ pub fn creating_btreesets() {
     set.insert(1);
     set.insert(9);
     set.insert(3);
-    println!("Created with new(): {:?}", set);
-    println!("Notice: Elements are automatically sorted!");
+    writeln!(out, "Created with new(): {:?}", set)?;
+    writeln!(out, "Notice: Elements are automatically sorted!")?;
 
     // Method 2: BTreeSet::from() - from array
     let set2: BTreeSet<&str> = BTreeSet::from(["cherry", "apple", "banana"]);
-    println!("\nFrom array: {:?}", set2);
-    println!("Notice: Strings sorted alphabetically!");
+    writeln!(out, "\nFrom array: {:?}", set2)?;
+    writeln!(out, "Notice: Strings sorted alphabetically!")?;
 
     // Method 3: collect() from iterator
     let set3: BTreeSet<i8> = (1..=5).collect();
-    println!("\nCollected from range: {:?}", set3);
+    writeln!(out, "\nCollected from range: {:?}", set3)?;
 
     // Method 4: From unsorted data - automatically sorts
     let unsorted: Vec<i8> = vec![7, 2, 9, 1, 5, 3, 8, 4, 6];
     let sorted_set: BTreeSet<i8> = unsorted.into_iter().collect();
-    println!("\nFrom unsorted vec: {:?}", sorted_set);
+    writeln!(out, "\nFrom unsorted vec: {:?}", sorted_set)?;
 
     // Note: Unlike HashSet, BTreeSet has no with_capacity() method.
     // B-trees allocate nodes as needed.
+
+    Ok(())
 }
 
 /// Demonstrates BTreeSet's feature: sorted iteration.
 ///
 /// Unlike HashSet, BTreeSet always iterates in sorted order.
 /// This is guaranteed and deterministic.
-pub fn btreeset_sorted_iteration() {
-    println!("BTreeSet Sorted Iteration");
+pub fn btreeset_sorted_iteration(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "BTreeSet Sorted Iteration")?;
 
     let mut numbers: BTreeSet<i8> = BTreeSet::new();
 
@@ -377,125 +679,134 @@ pub fn btreeset_sorted_iteration() {
     }
 
     // Iteration is ALWAYS in sorted order
-    println!("Inserted in random order, iterating in sorted order:");
+    writeln!(out, "Inserted in random order, iterating in sorted order:")?;
     for num in &numbers {
-        print!("{} ", num);
+        write!(out, "{} ", num)?;
     }
-    println!();
+    writeln!(out)?;
 
     // Reverse iteration is also efficient
-    println!("\nReverse iteration:");
+    writeln!(out, "\nReverse iteration:")?;
     for num in numbers.iter().rev() {
-        print!("{} ", num);
+        write!(out, "{} ", num)?;
     }
-    println!();
+    writeln!(out)?;
 
     // Compare with HashSet - order is arbitrary
     let hash_numbers: HashSet<i8> = [5, 2, 8, 1, 9, 3, 7, 4, 6].into_iter().collect();
-    println!("\nHashSet iteration (arbitrary order):");
-    for num in &hash_numbers {
-        print!("{} ", num);
-    }
-    println!();
+    writeln!(out, "\nHashSet (arbitrary order): {:?}", hash_numbers)?;
+
+    Ok(())
 }
 
 /// Demonstrates range queries - BTreeSet's other feature.
 ///
 /// Because elements are sorted, BTreeSet can efficiently answer
 /// "give me all elements between X and Y".
-pub fn btreeset_range_queries() {
-    println!("BTreeSet Range Queries");
+pub fn btreeset_range_queries(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "BTreeSet Range Queries")?;
 
     let numbers: BTreeSet<i8> = (1..=20).collect();
-    println!("Full set: {:?}", numbers);
+    writeln!(out, "Full set: {:?}", numbers)?;
 
     // range() with inclusive bounds: 5..=10 means 5 <= x <= 10
-    println!("\nrange(5..=10) - elements from 5 to 10 inclusive:");
+    writeln!(out, "\nrange(5..=10) - elements from 5 to 10 inclusive:")?;
     let range1: Vec<&i8> = numbers.range(5..=10).collect();
-    println!("  {:?}", range1);
+    writeln!(out, "  {:?}", range1)?;
 
     // range() with exclusive end: 5..10 means 5 <= x < 10
-    println!("\nrange(5..10) - elements from 5 to 10 exclusive:");
+    writeln!(out, "\nrange(5..10) - elements from 5 to 10 exclusive:")?;
     let range2: Vec<&i8> = numbers.range(5..10).collect();
-    println!("  {:?}", range2);
+    writeln!(out, "  {:?}", range2)?;
 
     // Unbounded start: ..8 means x < 8
-    println!("\nrange(..8) - elements less than 8:");
+    writeln!(out, "\nrange(..8) - elements less than 8:")?;
     let range3: Vec<&i8> = numbers.range(..8).collect();
-    println!("  {:?}", range3);
+    writeln!(out, "  {:?}", range3)?;
 
     // Unbounded end: 15.. means x >= 15
-    println!("\nrange(15..) - elements 15 and greater:");
+    writeln!(out, "\nrange(15..) - elements 15 and greater:")?;
     let range4: Vec<&i8> = numbers.range(15..).collect();
-    println!("  {:?}", range4);
+    writeln!(out, "  {:?}", range4)?;
 
     // Practical example: find all values in a score range
     let scores: BTreeSet<i8> = BTreeSet::from([65, 72, 78, 81, 85, 88, 92, 95, 98]);
-    println!("\nScores: {:?}", scores);
-    println!("B grades (80-89):");
+    writeln!(out, "\nScores: {:?}", scores)?;
+    writeln!(out, "B grades (80-89):")?;
     for score in scores.range(80..90) {
-        println!("  {}", score);
+        writeln!(out, "  {}", score)?;
     }
+
+    Ok(())
 }
 
 /// Demonstrates first/last element access in BTreeSet.
 ///
 /// Because elements are sorted, finding min (first) and max (last)
 /// is O(log n) - just traverse to the appropriate leaf.
-pub fn btreeset_min_max() {
-    println!("BTreeSet Min/Max operations");
+pub fn btreeset_min_max(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "BTreeSet Min/Max operations")?;
 
     let mut numbers: BTreeSet<i8> = BTreeSet::from([5, 2, 8, 1, 9, 3, 7]);
 
-    println!("Set: {:?}", numbers);
+    writeln!(out, "Set: {:?}", numbers)?;
 
     // first() and last() - peek without removing
-    println!("\nfirst() (minimum): {:?}", numbers.first());
-    println!("last() (maximum): {:?}", numbers.last());
+    writeln!(out, "\nfirst() (minimum): {:?}", numbers.first())?;
+    writeln!(out, "last() (maximum): {:?}", numbers.last())?;
 
     // pop_first() and pop_last() - remove and return
     let min: Option<i8> = numbers.pop_first();
-    println!("\npop_first() returned: {:?}", min);
-    println!("Set after pop_first: {:?}", numbers);
+    writeln!(out, "\npop_first() returned: {:?}", min)?;
+    writeln!(out, "Set after pop_first: {:?}", numbers)?;
 
     let max: Option<i8> = numbers.pop_last();
-    println!("\npop_last() returned: {:?}", max);
-    println!("Set after pop_last: {:?}", numbers);
+    writeln!(out, "\npop_last() returned: {:?}", max)?;
+    writeln!(out, "Set after pop_last: {:?}", numbers)?;
+
+    Ok(())
 }
 
 /// Demonstrates that BTreeSet supports all the same set operations as HashSet.
 ///
 /// The results are also sorted!
-pub fn btreeset_set_operations() {
-    println!("BTreeSet set operations");
+pub fn btreeset_set_operations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "BTreeSet set operations")?;
 
     let set_a: BTreeSet<i8> = BTreeSet::from([1, 2, 3, 4, 5]);
     let set_b: BTreeSet<i8> = BTreeSet::from([4, 5, 6, 7, 8]);
 
-    println!("Set A: {:?}", set_a);
-    println!("Set B: {:?}", set_b);
+    writeln!(out, "Set A: {:?}", set_a)?;
+    writeln!(out, "Set B: {:?}", set_b)?;
 
     // All set operations work the same, but results are sorted!
     let union: BTreeSet<&i8> = set_a.union(&set_b).collect();
-    println!("\nUnion (sorted): {:?}", union);
+    writeln!(out, "\nUnion (sorted): {:?}", union)?;
 
     let intersection: BTreeSet<&i8> = set_a.intersection(&set_b).collect();
-    println!("Intersection (sorted): {:?}", intersection);
+    writeln!(out, "Intersection (sorted): {:?}", intersection)?;
 
     let difference: BTreeSet<&i8> = set_a.difference(&set_b).collect();
-    println!("Difference A-B (sorted): {:?}", difference);
+    writeln!(out, "Difference A-B (sorted): {:?}", difference)?;
 
     let sym_diff: BTreeSet<&i8> = set_a.symmetric_difference(&set_b).collect();
-    println!("Symmetric Difference (sorted): {:?}", sym_diff);
+    writeln!(out, "Symmetric Difference (sorted): {:?}", sym_diff)?;
 
     // Relationship checks work the same too
     let subset: BTreeSet<i8> = BTreeSet::from([2, 3, 4]);
-    println!("\nsubset {:?} ⊆ A: {}", subset, subset.is_subset(&set_a));
+    writeln!(
+        out,
+        "\nsubset {:?} ⊆ A: {}",
+        subset,
+        subset.is_subset(&set_a)
+    )?;
+
+    Ok(())
 }
 
 /// Practical example: Deduplication with order preservation options.
-pub fn practical_deduplication() {
-    println!("Practical example: deduplication");
+pub fn practical_deduplication(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical example: deduplication")?;
 
     let emails: Vec<&str> = vec![
         "alice@example.com",
@@ -506,26 +817,29 @@ pub fn practical_deduplication() {
         "alice@example.com", // Another duplicate
     ];
 
-    println!("Original list ({} items):", emails.len());
+    writeln!(out, "Original list ({} items):", emails.len())?;
     for email in &emails {
-        println!("  {}", email);
+        writeln!(out, "  {}", email)?;
     }
 
     // Method 1: HashSet - fast dedup, arbitrary order
     let unique_hash: HashSet<&str> = emails.iter().cloned().collect();
-    println!(
+    writeln!(
+        out,
         "\nHashSet dedup ({} items, arbitrary order):",
         unique_hash.len()
-    );
-    for email in &unique_hash {
-        println!("  {}", email);
-    }
+    )?;
+    writeln!(out, "  {:?}", unique_hash)?;
 
     // Method 2: BTreeSet - dedup with sorted order
     let unique_btree: BTreeSet<&str> = emails.iter().cloned().collect();
-    println!("\nBTreeSet dedup ({} items, sorted):", unique_btree.len());
+    writeln!(
+        out,
+        "\nBTreeSet dedup ({} items, sorted):",
+        unique_btree.len()
+    )?;
     for email in &unique_btree {
-        println!("  {}", email);
+        writeln!(out, "  {}", email)?;
     }
 
     // Method 3: Preserve original insertion order (using Vec + HashSet)
@@ -535,21 +849,27 @@ pub fn practical_deduplication() {
         .cloned()
         .filter(|email| seen.insert(email))
         .collect();
-    println!("\nInsertion-order dedup ({} items):", unique_ordered.len());
+    writeln!(
+        out,
+        "\nInsertion-order dedup ({} items):",
+        unique_ordered.len()
+    )?;
     for email in &unique_ordered {
-        println!("  {}", email);
+        writeln!(out, "  {}", email)?;
     }
+
+    Ok(())
 }
 
 /// Practical example: Finding duplicates in a collection.
-pub fn practical_finding_duplicates() {
-    println!("Practical example: finding duplicates");
+pub fn practical_finding_duplicates(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical example: finding duplicates")?;
 
     let items: Vec<&str> = vec![
         "apple", "banana", "apple", "cherry", "banana", "date", "apple",
     ];
 
-    println!("Items: {:?}", items);
+    writeln!(out, "Items: {:?}", items)?;
 
     // Find which items appear more than once
     let mut seen: HashSet<&str> = HashSet::new();
@@ -562,57 +882,56 @@ pub fn practical_finding_duplicates() {
         }
     }
 
-    println!("Duplicate items: {:?}", duplicates);
+    writeln!(out, "Duplicate items: {:?}", duplicates)?;
 
     // Find the first duplicate
     fn find_first_duplicate<'a>(items: &[&'a str]) -> Option<&'a str> {
         let mut seen: HashSet<&str> = HashSet::new();
-        for &item in items {
-            if !seen.insert(item) {
-                return Some(item);
-            }
-        }
-        None
+        items.iter().copied().find(|&item| !seen.insert(item))
     }
 
     match find_first_duplicate(&items) {
-        Some(dup) => println!("First duplicate: {}", dup),
-        None => println!("No duplicates found"),
+        Some(dup) => writeln!(out, "First duplicate: {}", dup)?,
+        None => writeln!(out, "No duplicates found")?,
     }
+
+    Ok(())
 }
 
 /// Practical example: Comparing two lists to find common/different elements.
-pub fn practical_comparing_lists() {
-    println!("Practical example: comparing lists");
+pub fn practical_comparing_lists(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical example: comparing lists")?;
 
     let shopping_list_1: HashSet<&str> =
         HashSet::from(["milk", "bread", "eggs", "butter", "cheese"]);
     let shopping_list_2: HashSet<&str> =
         HashSet::from(["bread", "cheese", "apples", "oranges", "milk"]);
 
-    println!("Shopping List 1: {:?}", shopping_list_1);
-    println!("Shopping List 2: {:?}", shopping_list_2);
+    writeln!(out, "Shopping List 1: {:?}", shopping_list_1)?;
+    writeln!(out, "Shopping List 2: {:?}", shopping_list_2)?;
 
     // Items in both lists
     let common: HashSet<_> = shopping_list_1.intersection(&shopping_list_2).collect();
-    println!("\nItems in BOTH lists: {:?}", common);
+    writeln!(out, "\nItems in BOTH lists: {:?}", common)?;
 
     // Items only in list 1
     let only_in_1: HashSet<_> = shopping_list_1.difference(&shopping_list_2).collect();
-    println!("Only in list 1: {:?}", only_in_1);
+    writeln!(out, "Only in list 1: {:?}", only_in_1)?;
 
     // Items only in list 2
     let only_in_2: HashSet<_> = shopping_list_2.difference(&shopping_list_1).collect();
-    println!("Only in list 2: {:?}", only_in_2);
+    writeln!(out, "Only in list 2: {:?}", only_in_2)?;
 
     // Combined unique items (everything from both)
     let all_items: HashSet<_> = shopping_list_1.union(&shopping_list_2).collect();
-    println!("All unique items: {:?}", all_items);
+    writeln!(out, "All unique items: {:?}", all_items)?;
+
+    Ok(())
 }
 
 /// Practical example: Tag system using sets.
-pub fn practical_tag_system() {
-    println!("Practical example: tag system");
+pub fn practical_tag_system(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical example: tag system")?;
 
     // Articles with their tags
     struct Article {
@@ -658,32 +977,34 @@ pub fn practical_tag_system() {
     // Find articles with specific tags
     let search_tags: HashSet<String> = HashSet::from(["rust".to_string(), "beginner".to_string()]);
 
-    println!("Searching for articles with tags: {:?}\n", search_tags);
+    writeln!(out, "Searching for articles with tags: {:?}\n", search_tags)?;
 
     // Articles that have ALL the search tags
-    println!("Articles with ALL search tags:");
+    writeln!(out, "Articles with ALL search tags:")?;
     for article in &articles {
         if search_tags.is_subset(&article.tags) {
-            println!("  - {} (tags: {:?})", article.title, article.tags);
+            writeln!(out, "  - {} (tags: {:?})", article.title, article.tags)?;
         }
     }
 
     // Articles that have ANY of the search tags
-    println!("\nArticles with ANY search tags:");
+    writeln!(out, "\nArticles with ANY search tags:")?;
     for article in &articles {
         if !search_tags.is_disjoint(&article.tags) {
-            println!("  - {} (tags: {:?})", article.title, article.tags);
+            writeln!(out, "  - {} (tags: {:?})", article.title, article.tags)?;
         }
     }
 
     // Find all unique tags across all articles
     let all_tags: BTreeSet<&String> = articles.iter().flat_map(|a| a.tags.iter()).collect();
-    println!("\nAll tags (sorted): {:?}", all_tags);
+    writeln!(out, "\nAll tags (sorted): {:?}", all_tags)?;
+
+    Ok(())
 }
 
 /// Practical example: Using BTreeSet for a leaderboard with rankings.
-pub fn practical_leaderboard() {
-    println!("Practical example: leaderboard");
+pub fn practical_leaderboard(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical example: leaderboard")?;
 
     // For a leaderboard, we want scores sorted descending
     // BTreeSet sorts ascending, so we use Reverse
@@ -714,19 +1035,132 @@ pub fn practical_leaderboard() {
         name: "Diana".to_string(),
     });
 
-    println!("Leaderboard (sorted by score descending):");
+    writeln!(out, "Leaderboard (sorted by score descending):")?;
     for (rank, player) in leaderboard.iter().enumerate() {
-        println!(
+        writeln!(
+            out,
             "  {}. {} - {} points",
             rank + 1,
             player.name,
             player.score.0
-        );
+        )?;
     }
 
     // Top 3 players
-    println!("\nTop 3:");
+    writeln!(out, "\nTop 3:")?;
     for player in leaderboard.iter().take(3) {
-        println!("  {} - {} points", player.name, player.score.0);
+        writeln!(out, "  {} - {} points", player.name, player.score.0)?;
     }
+
+    Ok(())
+}
+
+/// Demonstrates a sorted Vec used as a set.
+pub fn sorted_vec_set(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Sorted Vec as a set")?;
+
+    let mut primes: SortedVecSet<u32> = SortedVecSet::from_vec(vec![7, 2, 13, 3, 5, 11, 2, 7]);
+    writeln!(
+        out,
+        "from_vec([7, 2, 13, 3, 5, 11, 2, 7]) = {:?}",
+        primes.as_slice()
+    )?;
+    writeln!(
+        out,
+        "contains(11): {}, contains(12): {}",
+        primes.contains(&11),
+        primes.contains(&12)
+    )?;
+    writeln!(
+        out,
+        "insert(17): {} -> {:?}",
+        primes.insert(17),
+        primes.as_slice()
+    )?;
+    writeln!(out, "insert(5):  {} (already there)", primes.insert(5))?;
+
+    let odd: SortedVecSet<u32> = (1..=15).filter(|n| n % 2 == 1).collect();
+    writeln!(out, "\nodd numbers to 15: {:?}", odd.as_slice())?;
+    writeln!(
+        out,
+        "primes ∩ odd (merge):  {:?}",
+        primes.intersection(&odd).as_slice()
+    )?;
+    writeln!(
+        out,
+        "primes ∩ odd (search): {:?}",
+        primes.intersection_by_search(&odd).as_slice()
+    )?;
+    let small: SortedVecSet<u32> = SortedVecSet::from_vec(vec![3, 5, 7]);
+    writeln!(
+        out,
+        "{:?} ⊆ primes: {}",
+        small.as_slice(),
+        small.is_subset(&primes)
+    )?;
+
+    // One allocation holding only the elements
+    let flat: SortedVecSet<u32> = (0..10_000).collect();
+    let hashed: HashSet<u32> = flat.iter().copied().collect();
+    writeln!(
+        out,
+        "\n{} u32s: SortedVecSet {} bytes, HashSet {} slots x 5 bytes (value + control byte) = {} bytes",
+        flat.len(),
+        flat.len() * std::mem::size_of::<u32>(),
+        hashed.capacity(),
+        hashed.capacity() * (std::mem::size_of::<u32>() + 1)
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: Intersecting posting lists stored as sorted Vecs.
+pub fn practical_posting_lists(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Search index: posting lists")?;
+
+    // Which documents contain each word; a search for several words wants
+    // the documents containing all of them
+    let documents: u32 = 100_000;
+    let postings = |every: u32, offset: u32| -> SortedVecSet<u32> {
+        (0..documents).filter(|doc| doc % every == offset).collect()
+    };
+    let rust: SortedVecSet<u32> = postings(3, 0);
+    let vec: SortedVecSet<u32> = postings(5, 0);
+    let capacity: SortedVecSet<u32> = postings(997, 0);
+    writeln!(
+        out,
+        "\"rust\": {} docs, \"vec\": {} docs, \"capacity\": {} docs",
+        rust.len(),
+        vec.len(),
+        capacity.len()
+    )?;
+
+    let both: SortedVecSet<u32> = rust.intersection(&vec);
+    writeln!(
+        out,
+        "rust AND vec: {} docs, first {:?}",
+        both.len(),
+        &both.as_slice()[..5]
+    )?;
+
+    // Start with the rarest word: every later intersection is smaller
+    let all_three: SortedVecSet<u32> = capacity
+        .intersection_by_search(&both)
+        .intersection_by_search(&rust);
+    writeln!(
+        out,
+        "capacity AND rust AND vec: {} docs {:?}",
+        all_three.len(),
+        all_three.as_slice()
+    )?;
+
+    let hashed: HashSet<u32> = rust.iter().copied().collect();
+    let check: usize = vec.iter().filter(|doc| hashed.contains(doc)).count();
+    writeln!(
+        out,
+        "\nSame count with a HashSet of \"rust\": {} (one hash lookup per \"vec\" doc)",
+        check
+    )?;
+
+    Ok(())
 }
@@ -0,0 +1,651 @@
+// Sharing one collection between several owners.
+//
+// A collection normally has one owner, and everyone else borrows it for a
+// scope the compiler can see. When the owners are peers with no common
+// scope - two UI views, a list of callbacks, worker threads - the
+// collection goes behind a reference count, plus a cell or a lock if it
+// must change:
+//
+//   Rc<RefCell<Vec<T>>>        one thread. Clone = a plain increment.
+//                              borrow()/borrow_mut() check a flag at run
+//                              time and panic on a conflict
+//   Arc<Mutex<HashMap<K, V>>>  many threads. Clone = an atomic increment.
+//                              lock() blocks while another thread holds it
+//   Arc<[T]>                   many threads, read-only. One allocation
+//                              (counts and elements together), no lock,
+//                              and a 16-byte fat pointer
+//
+// The compiler no longer proves these borrows, so the mistakes move to run
+// time: a borrow_mut() while a borrow() is alive panics, a second lock()
+// on the same thread deadlocks, and an Rc cycle is never freed.
+// `borrow_and_lock_pitfalls` shows each one with the non-panicking
+// try_borrow_mut/try_lock, and the fix.
+
+use demo_framework::section;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::mem::size_of;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, Mutex, TryLockError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "rc_refcell_vec",
+        "Rc<RefCell<Vec<T>>>: one list, several owners, one thread",
+        rc_refcell_vec,
+    )?;
+
+    section(
+        out,
+        "arc_mutex_map",
+        "Arc<Mutex<HashMap>>: a lock per word vs merging local maps",
+        arc_mutex_map,
+    )?;
+
+    section(
+        out,
+        "arc_slices",
+        "Arc<[T]>: one read-only allocation shared by every thread",
+        arc_slices,
+    )?;
+
+    section(
+        out,
+        "borrow_and_lock_pitfalls",
+        "Borrow panics, self-deadlocks and leaked cycles, and their fixes",
+        borrow_and_lock_pitfalls,
+    )?;
+
+    section(
+        out,
+        "practical_config_snapshots",
+        "Practical demo: readers on Arc<[T]> snapshots while a writer publishes",
+        practical_config_snapshots,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Rc<RefCell<Vec<T>>>
+// ---------------------------------------------------------------------------
+
+/// A callback that reacts to an event.
+pub type Listener = Box<dyn Fn(&str) -> String>;
+
+/// A list of callbacks that any holder of the `Rc` can add to.
+pub type Listeners = Rc<RefCell<Vec<Listener>>>;
+
+/// Calls every listener with `event`. Each call runs while the list is
+/// borrowed, so a listener that adds a listener would panic.
+pub fn notify_borrowed(listeners: &Listeners, event: &str) -> Vec<String> {
+    listeners
+        .borrow()
+        .iter()
+        .map(|listener| listener(event))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Arc<Mutex<HashMap>>
+// ---------------------------------------------------------------------------
+
+/// `count` documents of `words` words each, from a 40-word vocabulary.
+pub fn sample_documents(count: usize, words: usize) -> Vec<String> {
+    (0..count)
+        .map(|doc| {
+            (0..words)
+                .map(|i| format!("w{}", (doc * 7 + i * i) % 40))
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// Word counts from `threads` threads, each locking the shared map for
+/// every word. Returns the counts and how many times the lock was taken.
+pub fn count_locking_per_word(
+    docs: &Arc<[String]>,
+    threads: usize,
+) -> (HashMap<String, u64>, usize) {
+    let totals: Arc<Mutex<HashMap<String, u64>>> = Arc::new(Mutex::new(HashMap::new()));
+    let handles: Vec<thread::JoinHandle<usize>> = (0..threads)
+        .map(|t| {
+            let docs: Arc<[String]> = Arc::clone(docs);
+            let totals: Arc<Mutex<HashMap<String, u64>>> = Arc::clone(&totals);
+            thread::spawn(move || {
+                let mut locks: usize = 0;
+                for doc in docs.iter().skip(t).step_by(threads) {
+                    for word in doc.split(' ') {
+                        *totals.lock().unwrap().entry(word.to_string()).or_insert(0) += 1;
+                        locks += 1;
+                    }
+                }
+                locks
+            })
+        })
+        .collect();
+    let locks: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    let totals: HashMap<String, u64> = Arc::into_inner(totals)
+        .expect("every worker has finished")
+        .into_inner()
+        .unwrap();
+    (totals, locks)
+}
+
+/// Word counts from `threads` threads, each counting into its own map and
+/// locking the shared one once at the end to merge.
+pub fn count_merging_local(docs: &Arc<[String]>, threads: usize) -> (HashMap<String, u64>, usize) {
+    let totals: Arc<Mutex<HashMap<String, u64>>> = Arc::new(Mutex::new(HashMap::new()));
+    let handles: Vec<thread::JoinHandle<usize>> = (0..threads)
+        .map(|t| {
+            let docs: Arc<[String]> = Arc::clone(docs);
+            let totals: Arc<Mutex<HashMap<String, u64>>> = Arc::clone(&totals);
+            thread::spawn(move || {
+                let mut local: HashMap<&str, u64> = HashMap::new();
+                for doc in docs.iter().skip(t).step_by(threads) {
+                    for word in doc.split(' ') {
+                        *local.entry(word).or_insert(0) += 1;
+                    }
+                }
+                let mut totals = totals.lock().unwrap();
+                for (word, count) in local {
+                    *totals.entry(word.to_string()).or_insert(0) += count;
+                }
+                1
+            })
+        })
+        .collect();
+    let locks: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    let totals: HashMap<String, u64> = Arc::into_inner(totals)
+        .expect("every worker has finished")
+        .into_inner()
+        .unwrap();
+    (totals, locks)
+}
+
+// ---------------------------------------------------------------------------
+// Snapshots
+// ---------------------------------------------------------------------------
+
+/// Read-mostly data that readers take as `Arc<[T]>` snapshots. The lock
+/// is held only to clone or replace the `Arc`, never while reading.
+pub struct Snapshots<T> {
+    current: Mutex<(u64, Arc<[T]>)>,
+}
+
+impl<T> Snapshots<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        Snapshots {
+            current: Mutex::new((1, Arc::from(items))),
+        }
+    }
+
+    /// The current version number and its items.
+    pub fn snapshot(&self) -> (u64, Arc<[T]>) {
+        let current = self.current.lock().unwrap();
+        (current.0, Arc::clone(&current.1))
+    }
+
+    /// Replaces the items with a new version, returning its number.
+    /// Readers holding an older snapshot keep it until they drop it.
+    pub fn publish(&self, items: Vec<T>) -> u64 {
+        // Built before locking, so readers wait only for the swap
+        let items: Arc<[T]> = Arc::from(items);
+        let mut current = self.current.lock().unwrap();
+        current.0 += 1;
+        current.1 = items;
+        current.0
+    }
+}
+
+/// A rate limit rule: requests whose path starts with `prefix` may run
+/// `limit` times a second.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub prefix: String,
+    pub limit: u32,
+}
+
+/// The rules of config version `version`. Every rule in a version has
+/// `limit` equal to `version * 100 + i`, so a reader can tell whether
+/// a snapshot mixes versions.
+pub fn rules_version(version: u64) -> Vec<Rule> {
+    ["/api", "/api/search", "/static", "/admin"]
+        .iter()
+        .enumerate()
+        .map(|(i, prefix)| Rule {
+            prefix: prefix.to_string(),
+            limit: version as u32 * 100 + i as u32,
+        })
+        .collect()
+}
+
+/// The limit for `path`: the rule with the longest matching prefix.
+pub fn limit_for(rules: &[Rule], path: &str) -> Option<u32> {
+    rules
+        .iter()
+        .filter(|rule| path.starts_with(&rule.prefix))
+        .max_by_key(|rule| rule.prefix.len())
+        .map(|rule| rule.limit)
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// Demonstrates one `Vec` with several `Rc` owners, and the run-time
+/// borrow checks `RefCell` does
+pub fn rc_refcell_vec(out: &mut dyn Write) -> io::Result<()> {
+    let history: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+    let editor: Rc<RefCell<Vec<String>>> = Rc::clone(&history);
+    let sidebar: Rc<RefCell<Vec<String>>> = Rc::clone(&history);
+
+    editor.borrow_mut().push("open report.txt".to_string());
+    editor.borrow_mut().push("edit line 3".to_string());
+    sidebar.borrow_mut().push("rename to final.txt".to_string());
+
+    writeln!(
+        out,
+        "\n  Three owners of one list: strong_count = {}",
+        Rc::strong_count(&history)
+    )?;
+    writeln!(out, "  The editor sees: {:?}", editor.borrow())?;
+    writeln!(out, "  Same allocation: {}", Rc::ptr_eq(&editor, &sidebar))?;
+
+    // Any number of shared borrows, or one mutable one
+    let first = history.borrow();
+    let second = sidebar.borrow();
+    writeln!(
+        out,
+        "\n  Two borrow()s at once: fine ({} and {} entries)",
+        first.len(),
+        second.len()
+    )?;
+    writeln!(
+        out,
+        "  borrow_mut() while they live: {}",
+        match editor.try_borrow_mut() {
+            Ok(_) => "Ok".to_string(),
+            Err(error) => format!("Err({error}), borrow_mut() would panic"),
+        }
+    )?;
+    drop(first);
+    drop(second);
+    writeln!(
+        out,
+        "  After dropping them: {}",
+        editor.try_borrow_mut().map_or("Err", |_| "Ok")
+    )?;
+
+    drop(editor);
+    drop(sidebar);
+    writeln!(
+        out,
+        "\n  Owners dropped: strong_count = {}; the Vec is freed with the last one.",
+        Rc::strong_count(&history)
+    )?;
+    writeln!(
+        out,
+        "  Rc::clone is a plain increment and RefCell a flag check: cheap, but"
+    )?;
+    writeln!(
+        out,
+        "  neither is Send. Moving this list to another thread doesn't compile."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates two ways for threads to count into one shared map, and
+/// how many times each takes the lock
+pub fn arc_mutex_map(out: &mut dyn Write) -> io::Result<()> {
+    const THREADS: usize = 4;
+
+    let docs: Arc<[String]> = Arc::from(sample_documents(400, 50));
+    let words: usize = docs.iter().map(|doc| doc.split(' ').count()).sum();
+
+    let start: Instant = Instant::now();
+    let (per_word, per_word_locks) = count_locking_per_word(&docs, THREADS);
+    let per_word_time: Duration = start.elapsed();
+    let start: Instant = Instant::now();
+    let (merged, merged_locks) = count_merging_local(&docs, THREADS);
+    let merged_time: Duration = start.elapsed();
+
+    writeln!(
+        out,
+        "\n  {} threads counting {} words from {} documents:",
+        THREADS,
+        words,
+        docs.len()
+    )?;
+    writeln!(out, "    {:<31} {:>7}  time", "Strategy", "locks")?;
+    writeln!(
+        out,
+        "    {:<31} {:>7}  {:.2?}",
+        "lock() per word", per_word_locks, per_word_time
+    )?;
+    writeln!(
+        out,
+        "    {:<31} {:>7}  {:.2?}",
+        "local map, lock() once to merge", merged_locks, merged_time
+    )?;
+    writeln!(
+        out,
+        "    Same totals: {} ({} distinct words)",
+        per_word == merged,
+        merged.len()
+    )?;
+
+    writeln!(
+        out,
+        "\n  Each worker holds its own Arc clones of the documents and the map."
+    )?;
+    writeln!(
+        out,
+        "  Locking per word serializes the threads on one Mutex and builds a"
+    )?;
+    writeln!(
+        out,
+        "  String key per word. Counting locally borrows the words and locks once."
+    )?;
+    writeln!(
+        out,
+        "  Arc::into_inner takes the map back out once the last worker is done."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates `Arc<[T]>` next to `Arc<Vec<T>>` and a copy per thread
+pub fn arc_slices(out: &mut dyn Write) -> io::Result<()> {
+    let readings: Vec<u64> = (0..1_000).map(|i| (i * 37) % 101).collect();
+
+    writeln!(out, "\n  Sharing 1000 u64 readings with 4 threads:")?;
+    writeln!(
+        out,
+        "    {:<14} {:>8} {:>12} {:>13}",
+        "", "pointer", "allocations", "bytes copied"
+    )?;
+    writeln!(
+        out,
+        "    {:<14} {:>8} {:>12} {:>13}",
+        "Vec per thread",
+        size_of::<Vec<u64>>(),
+        4,
+        4 * readings.len() * size_of::<u64>()
+    )?;
+    writeln!(
+        out,
+        "    {:<14} {:>8} {:>12} {:>13}",
+        "Arc<Vec<u64>>",
+        size_of::<Arc<Vec<u64>>>(),
+        "2 (shared)",
+        0
+    )?;
+    writeln!(
+        out,
+        "    {:<14} {:>8} {:>12} {:>13}",
+        "Arc<[u64]>",
+        size_of::<Arc<[u64]>>(),
+        "1 (shared)",
+        readings.len() * size_of::<u64>()
+    )?;
+
+    // Arc::from(Vec) copies the elements once, next to the counts
+    let shared: Arc<[u64]> = Arc::from(readings);
+    let sums: Vec<u64> = (0..4)
+        .map(|t| {
+            let shared: Arc<[u64]> = Arc::clone(&shared);
+            thread::spawn(move || shared[t * 250..(t + 1) * 250].iter().sum())
+        })
+        .collect::<Vec<thread::JoinHandle<u64>>>()
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect();
+
+    writeln!(
+        out,
+        "\n  Four threads summed a quarter each: {:?} = {}",
+        sums,
+        sums.iter().sum::<u64>()
+    )?;
+    writeln!(
+        out,
+        "  strong_count after they finished: {}",
+        Arc::strong_count(&shared)
+    )?;
+
+    writeln!(
+        out,
+        "\n  Arc<[T]> keeps the counts and the elements in one allocation, so a"
+    )?;
+    writeln!(
+        out,
+        "  read is one pointer hop; Arc<Vec<T>> points at a Vec that points at"
+    )?;
+    writeln!(
+        out,
+        "  the elements. Building one from a Vec copies it once. It can't grow:"
+    )?;
+    writeln!(out, "  use it for data that is built, then only read.")?;
+
+    Ok(())
+}
+
+/// A node that counts its own drops, for the cycle demo.
+struct Node {
+    next: RefCell<Option<Rc<Node>>>,
+    back: RefCell<Weak<Node>>,
+    drops: Rc<Cell<usize>>,
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+/// Demonstrates the three classic run-time mistakes, caught with the
+/// non-panicking `try_` methods
+pub fn borrow_and_lock_pitfalls(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  1. A listener that subscribes another listener")?;
+    let listeners: Listeners = Rc::new(RefCell::new(Vec::new()));
+    let handle: Listeners = Rc::clone(&listeners);
+    listeners.borrow_mut().push(Box::new(move |event| {
+        // notify_borrowed holds borrow() while this runs
+        match handle.try_borrow_mut() {
+            Ok(mut list) => {
+                list.push(Box::new(|event| format!("late: {event}")));
+                format!("subscribed on {event}")
+            }
+            Err(_) => format!("{event}: borrow_mut() would panic: already borrowed"),
+        }
+    }));
+    writeln!(
+        out,
+        "     notify_borrowed:  {:?}",
+        notify_borrowed(&listeners, "save")
+    )?;
+    // The fix: finish the borrow before running the callbacks. Here the
+    // listeners move out for the call and go back in after.
+    let current: Vec<Listener> = listeners.take();
+    let results: Vec<String> = current.iter().map(|listener| listener("save")).collect();
+    let added: Vec<Listener> = listeners.replace(current);
+    listeners.borrow_mut().extend(added);
+    writeln!(out, "     take, call, put back:  {:?}", results)?;
+    writeln!(
+        out,
+        "     now {} listeners: {:?}",
+        listeners.borrow().len(),
+        notify_borrowed(&listeners, "quit")
+    )?;
+
+    writeln!(out, "\n  2. Locking a Mutex the same thread already holds")?;
+    let cache: Mutex<HashMap<&str, u32>> = Mutex::new(HashMap::from([("a", 1)]));
+    // match keeps the guard from the scrutinee alive for the whole match
+    match cache.lock().unwrap().get("b") {
+        Some(_) => {}
+        None => {
+            let second = match cache.try_lock() {
+                Ok(_) => "Ok".to_string(),
+                Err(TryLockError::WouldBlock) => "WouldBlock".to_string(),
+                Err(TryLockError::Poisoned(_)) => "Poisoned".to_string(),
+            };
+            writeln!(
+                out,
+                "     inside match cache.lock().get(..): try_lock() = {second}"
+            )?;
+            writeln!(
+                out,
+                "     lock() here would deadlock or panic: std's Mutex isn't re-entrant"
+            )?;
+        }
+    }
+    // The fix: end the guard's life before locking again
+    let missing: bool = cache.lock().unwrap().get("b").is_none();
+    if missing {
+        cache.lock().unwrap().insert("b", 2);
+    }
+    writeln!(
+        out,
+        "     copying the answer out first: inserted, {} entries",
+        cache.lock().unwrap().len()
+    )?;
+
+    writeln!(out, "\n  3. Two nodes that own each other")?;
+    let drops: Rc<Cell<usize>> = Rc::new(Cell::new(0));
+    let node = |drops: &Rc<Cell<usize>>| {
+        Rc::new(Node {
+            next: RefCell::new(None),
+            back: RefCell::new(Weak::new()),
+            drops: Rc::clone(drops),
+        })
+    };
+    {
+        let a: Rc<Node> = node(&drops);
+        let b: Rc<Node> = node(&drops);
+        *a.next.borrow_mut() = Some(Rc::clone(&b));
+        *b.next.borrow_mut() = Some(Rc::clone(&a));
+        writeln!(
+            out,
+            "     strong links both ways: strong_count = {} and {}",
+            Rc::strong_count(&a),
+            Rc::strong_count(&b)
+        )?;
+    }
+    writeln!(
+        out,
+        "     out of scope: {} of 2 dropped - the pair leaks",
+        drops.get()
+    )?;
+    let weak_drops: Rc<Cell<usize>> = Rc::new(Cell::new(0));
+    {
+        let a: Rc<Node> = node(&weak_drops);
+        let b: Rc<Node> = node(&weak_drops);
+        *a.next.borrow_mut() = Some(Rc::clone(&b));
+        *b.back.borrow_mut() = Rc::downgrade(&a);
+        let back_alive: bool = b.back.borrow().upgrade().is_some();
+        writeln!(
+            out,
+            "     Weak back link: strong_count = {} and {}, back link live: {}",
+            Rc::strong_count(&a),
+            Rc::strong_count(&b),
+            back_alive
+        )?;
+    }
+    writeln!(out, "     out of scope: {} of 2 dropped", weak_drops.get())?;
+
+    writeln!(
+        out,
+        "\n  All three compile. Keep RefCell borrows and Mutex guards short, and"
+    )?;
+    writeln!(
+        out,
+        "  make every back or parent pointer a Weak so ownership stays a tree."
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: request handlers reading rate-limit rules from
+/// `Arc<[Rule]>` snapshots while a writer publishes new versions
+pub fn practical_config_snapshots(out: &mut dyn Write) -> io::Result<()> {
+    const READERS: usize = 4;
+    const REQUESTS: usize = 5_000;
+    const VERSIONS: u64 = 5;
+
+    let config: Arc<Snapshots<Rule>> = Arc::new(Snapshots::new(rules_version(1)));
+    let paths: [&str; 4] = ["/api/users", "/api/search?q=x", "/static/app.js", "/health"];
+
+    let readers: Vec<thread::JoinHandle<(usize, bool)>> = (0..READERS)
+        .map(|r| {
+            let config: Arc<Snapshots<Rule>> = Arc::clone(&config);
+            thread::spawn(move || {
+                let mut consistent: bool = true;
+                let mut limited: usize = 0;
+                for i in 0..REQUESTS {
+                    // One short lock to clone the Arc, then lock-free reads
+                    let (version, rules) = config.snapshot();
+                    consistent &= rules
+                        .iter()
+                        .all(|rule| u64::from(rule.limit / 100) == version);
+                    if limit_for(&rules, paths[(i + r) % paths.len()]).is_some() {
+                        limited += 1;
+                    }
+                }
+                (limited, consistent)
+            })
+        })
+        .collect();
+
+    for version in 2..=VERSIONS {
+        thread::sleep(Duration::from_micros(200));
+        config.publish(rules_version(version));
+    }
+    let results: Vec<(usize, bool)> = readers
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect();
+    let (final_version, rules) = config.snapshot();
+
+    writeln!(
+        out,
+        "\n  {} readers x {} requests, while the writer published versions 2 to {}:",
+        READERS, REQUESTS, VERSIONS
+    )?;
+    writeln!(
+        out,
+        "    requests with a limit: {}",
+        results.iter().map(|(limited, _)| limited).sum::<usize>()
+    )?;
+    writeln!(
+        out,
+        "    every snapshot came from a single version: {}",
+        results.iter().all(|(_, consistent)| *consistent)
+    )?;
+    writeln!(out, "    final version: {}", final_version)?;
+    for path in paths {
+        writeln!(out, "      {:<16} -> {:?}", path, limit_for(&rules, path))?;
+    }
+
+    writeln!(
+        out,
+        "\n  The lock guards only the Arc, never the rules: a reader holds it for"
+    )?;
+    writeln!(
+        out,
+        "  one increment, and a writer for one pointer swap. A reader that took"
+    )?;
+    writeln!(
+        out,
+        "  version 3 keeps reading version 3 until it drops it, then the memory"
+    )?;
+    writeln!(out, "  goes with the last snapshot.")?;
+
+    Ok(())
+}
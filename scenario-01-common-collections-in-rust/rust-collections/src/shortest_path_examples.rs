@@ -0,0 +1,701 @@
+// Dijkstra's algorithm finds the cheapest path from a source by settling
+// nodes in order of distance: pop the closest unsettled node, relax its
+// edges, repeat. When the target is popped its distance is final, and the
+// predecessor links walk the path back to the source.
+//
+// A* is the same loop with a different priority. Each queued node n is
+// ordered by
+//
+//   f(n) = g(n) + h(n)
+//          │      └─ heuristic: an estimate of the cost left from n to the target
+//          └──────── cost of the best path found so far from the source to n
+//
+// With h = 0 it is exactly Dijkstra. With a good estimate, nodes that lead
+// away from the target get large f values and are never settled:
+//
+//   Dijkstra settles a disc        A* settles a lane towards the goal
+//        . . . . .                       . . . . .
+//      . . . . . . .                   . . . . . . .
+//      . . S . . . . G                 . . S * * * * G
+//      . . . . . . .                   . . . . . . .
+//        . . . . .                       . . . . .
+//
+// The heuristic must never overestimate (admissible), or A* can return a
+// longer path. Here it is also consistent (h(a) <= w(a, b) + h(b) for every
+// edge), which is what lets each node be settled once. Manhattan distance on
+// a 4-connected grid and straight-line distance on a road map are both.
+//
+// The queue is the IndexedBinaryHeap from indexed_heap_examples, so a
+// shorter path to a queued node lowers its priority in place.
+
+use crate::grid_examples::Grid;
+use crate::indexed_heap_examples::IndexedBinaryHeap;
+use demo_framework::diagram::{self, Diagram};
+use demo_framework::{embed, section, step};
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "dijkstra_paths",
+        "Dijkstra from one town to every other, with the routes",
+        dijkstra_paths,
+    )?;
+
+    section(
+        out,
+        "grid_pathfinding",
+        "Dijkstra vs A* on a grid with walls and mud",
+        grid_pathfinding,
+    )?;
+
+    section(
+        out,
+        "practical_route_planner",
+        "Practical demo: A* on a road map with straight-line distance",
+        practical_route_planner,
+    )?;
+
+    section(
+        out,
+        "heuristic_pitfalls",
+        "What an overestimating heuristic does to A*",
+        heuristic_pitfalls,
+    )?;
+
+    Ok(())
+}
+
+/// A weighted directed graph over nodes `0..n`.
+#[derive(Clone, Debug, Default)]
+pub struct WeightedGraph {
+    adjacency: Vec<Vec<(usize, u32)>>,
+}
+
+impl WeightedGraph {
+    pub fn new(nodes: usize) -> Self {
+        WeightedGraph {
+            adjacency: vec![Vec::new(); nodes],
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.iter().map(Vec::len).sum()
+    }
+
+    /// Adds the edge `from → to`.
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: u32) {
+        self.adjacency[from].push((to, weight));
+    }
+
+    /// Adds `a → b` and `b → a` with the same weight.
+    pub fn add_undirected_edge(&mut self, a: usize, b: usize, weight: u32) {
+        self.add_edge(a, b, weight);
+        self.add_edge(b, a, weight);
+    }
+
+    /// `node`'s outgoing `(target, weight)` edges.
+    pub fn neighbors(&self, node: usize) -> &[(usize, u32)] {
+        &self.adjacency[node]
+    }
+}
+
+/// A cheapest path found by [`dijkstra`] or [`a_star`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path {
+    /// The nodes from source to target, both included.
+    pub nodes: Vec<usize>,
+    /// Sum of the edge weights along `nodes`.
+    pub cost: u32,
+    /// Every node settled before the search stopped, in order.
+    pub expanded: Vec<usize>,
+}
+
+/// Dijkstra's algorithm from `source`, stopping once `target` is settled.
+///
+/// `None` if `target` can't be reached.
+pub fn dijkstra(graph: &WeightedGraph, source: usize, target: usize) -> Option<Path> {
+    a_star(graph, source, target, |_| 0)
+}
+
+/// A* from `source` to `target`, guided by `heuristic(node)`, an estimate
+/// of the cost from `node` to `target`.
+///
+/// The path is the cheapest one if the heuristic is consistent. Ties on
+/// f are broken towards the smaller estimate, i.e. the node that looks
+/// closer to the target.
+pub fn a_star(
+    graph: &WeightedGraph,
+    source: usize,
+    target: usize,
+    heuristic: impl Fn(usize) -> u32,
+) -> Option<Path> {
+    let mut search: Search<'_, _> = Search::new(graph, source, target, heuristic);
+    while search.advance().is_some() {}
+    search.into_path()
+}
+
+/// An A* search that runs one settled node at a time, so a demo can show
+/// the queue and the settled set in between. [`a_star`] runs one to the
+/// end.
+pub struct Search<'g, H> {
+    graph: &'g WeightedGraph,
+    target: usize,
+    heuristic: H,
+    dist: Vec<u32>,
+    prev: Vec<Option<usize>>,
+    settled: Vec<bool>,
+    expanded: Vec<usize>,
+    // Priority (f, h): lowest f first, then the lowest estimate
+    queue: IndexedBinaryHeap<usize, (u32, u32)>,
+}
+
+impl<'g, H: Fn(usize) -> u32> Search<'g, H> {
+    pub fn new(graph: &'g WeightedGraph, source: usize, target: usize, heuristic: H) -> Self {
+        let nodes: usize = graph.node_count();
+        let mut search: Search<'g, H> = Search {
+            graph,
+            target,
+            heuristic,
+            dist: vec![u32::MAX; nodes],
+            prev: vec![None; nodes],
+            settled: vec![false; nodes],
+            expanded: Vec::new(),
+            queue: IndexedBinaryHeap::new(),
+        };
+        let estimate: u32 = (search.heuristic)(source);
+        search.dist[source] = 0;
+        search.queue.push(source, (estimate, estimate));
+        search
+    }
+
+    /// Settles the next node and relaxes its edges, returning the node.
+    /// `None` once the target is settled or nothing is left to settle.
+    pub fn advance(&mut self) -> Option<usize> {
+        if self.is_finished() {
+            return None;
+        }
+        let (node, _) = self.queue.pop()?;
+        self.settled[node] = true;
+        self.expanded.push(node);
+        if node == self.target {
+            return Some(node);
+        }
+        for &(next, weight) in self.graph.neighbors(node) {
+            if self.settled[next] {
+                continue;
+            }
+            let candidate: u32 = self.dist[node] + weight;
+            if candidate < self.dist[next] {
+                self.dist[next] = candidate;
+                self.prev[next] = Some(node);
+                let estimate: u32 = (self.heuristic)(next);
+                // Queues an unseen node, lowers the priority of a queued one
+                self.queue.push(next, (candidate + estimate, estimate));
+            }
+        }
+        Some(node)
+    }
+
+    /// Whether the target is settled.
+    pub fn is_finished(&self) -> bool {
+        self.settled[self.target]
+    }
+
+    /// Cost of the best path found so far to `node`.
+    pub fn distance(&self, node: usize) -> Option<u32> {
+        Some(self.dist[node]).filter(|&d| d != u32::MAX)
+    }
+
+    /// `node`'s `(f, h)` priority if it is queued.
+    pub fn queued(&self, node: usize) -> Option<(u32, u32)> {
+        self.queue.priority(&node).copied()
+    }
+
+    /// Nodes settled so far, in order.
+    pub fn expanded(&self) -> &[usize] {
+        &self.expanded
+    }
+
+    /// The path, once the target is settled.
+    pub fn into_path(self) -> Option<Path> {
+        self.is_finished().then(|| Path {
+            nodes: walk_back(&self.prev, self.target),
+            cost: self.dist[self.target],
+            expanded: self.expanded,
+        })
+    }
+}
+
+fn walk_back(prev: &[Option<usize>], target: usize) -> Vec<usize> {
+    let mut path: Vec<usize> = vec![target];
+    let mut current: usize = target;
+    while let Some(previous) = prev[current] {
+        path.push(previous);
+        current = previous;
+    }
+    path.reverse();
+    path
+}
+
+/// A 4-connected grid map parsed from text.
+///
+/// `.` costs 1 to enter, `~` (mud) costs 4, `#` is a wall. `S` and `G` mark
+/// the start and goal and cost 1 like open ground.
+#[derive(Clone, Debug)]
+pub struct GridMap {
+    cells: Grid<char>,
+    pub start: usize,
+    pub goal: usize,
+}
+
+impl GridMap {
+    /// Parses one line per row; every row must have the same length.
+    pub fn parse(text: &str) -> Self {
+        let lines: Vec<&str> = text.lines().collect();
+        let rows: usize = lines.len();
+        let cols: usize = lines.first().map_or(0, |line| line.chars().count());
+        let data: Vec<char> = lines.iter().flat_map(|line| line.chars()).collect();
+        let cells: Grid<char> = Grid::from_vec(rows, cols, data);
+        let find = |mark: char| -> usize {
+            cells
+                .as_slice()
+                .iter()
+                .position(|&cell| cell == mark)
+                .unwrap_or_else(|| panic!("map has no '{}'", mark))
+        };
+        let (start, goal) = (find('S'), find('G'));
+        GridMap { cells, start, goal }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.cells.rows()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cells.cols()
+    }
+
+    /// The node id of a cell: its row-major index.
+    pub fn node(&self, row: usize, col: usize) -> usize {
+        row * self.cols() + col
+    }
+
+    /// The `(row, col)` of a node id.
+    pub fn position(&self, node: usize) -> (usize, usize) {
+        (node / self.cols(), node % self.cols())
+    }
+
+    /// The cost of stepping onto a cell, `None` for a wall.
+    fn entry_cost(cell: char) -> Option<u32> {
+        match cell {
+            '#' => None,
+            '~' => Some(4),
+            _ => Some(1),
+        }
+    }
+
+    /// One edge into every non-wall neighbor, weighted by its entry cost.
+    pub fn graph(&self) -> WeightedGraph {
+        let mut graph: WeightedGraph = WeightedGraph::new(self.rows() * self.cols());
+        for row in 0..self.rows() {
+            for col in 0..self.cols() {
+                if self.cells[(row, col)] == '#' {
+                    continue;
+                }
+                let steps: [(usize, usize); 4] = [
+                    (row.wrapping_sub(1), col),
+                    (row + 1, col),
+                    (row, col.wrapping_sub(1)),
+                    (row, col + 1),
+                ];
+                for (r, c) in steps {
+                    let Some(&cell) = self.cells.get(r, c) else {
+                        continue;
+                    };
+                    if let Some(cost) = Self::entry_cost(cell) {
+                        graph.add_edge(self.node(row, col), self.node(r, c), cost);
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    /// Manhattan distance to the goal: every step costs at least 1, so it
+    /// never overestimates.
+    pub fn manhattan_to_goal(&self, node: usize) -> u32 {
+        let (row, col) = self.position(node);
+        let (goal_row, goal_col) = self.position(self.goal);
+        (row.abs_diff(goal_row) + col.abs_diff(goal_col)) as u32
+    }
+
+    /// The map with `path` drawn as `*` and the other expanded cells as
+    /// `o`.
+    pub fn render(&self, path: &Path) -> Vec<String> {
+        let mut drawn: Grid<char> = self.cells.clone();
+        for &node in &path.expanded {
+            let (row, col) = self.position(node);
+            if drawn[(row, col)] == '.' {
+                drawn[(row, col)] = 'o';
+            }
+        }
+        for &node in &path.nodes {
+            let (row, col) = self.position(node);
+            if matches!(drawn[(row, col)], 'o' | '.' | '~') {
+                drawn[(row, col)] = '*';
+            }
+        }
+        drawn.iter_rows().map(|row| row.iter().collect()).collect()
+    }
+
+    /// The map part-way through `search`: `@` is the node settled last, `o`
+    /// the others settled before it and `+` the queued frontier.
+    pub fn render_search<H: Fn(usize) -> u32>(&self, search: &Search<'_, H>) -> Vec<String> {
+        let mut drawn: Grid<char> = self.cells.clone();
+        for node in 0..self.rows() * self.cols() {
+            let (row, col) = self.position(node);
+            if search.queued(node).is_some() && drawn[(row, col)] != 'G' {
+                drawn[(row, col)] = '+';
+            }
+        }
+        for &node in search.expanded() {
+            let (row, col) = self.position(node);
+            if !matches!(drawn[(row, col)], 'S' | 'G') {
+                drawn[(row, col)] = 'o';
+            }
+        }
+        if let Some(&last) = search.expanded().last() {
+            let (row, col) = self.position(last);
+            drawn[(row, col)] = '@';
+        }
+        drawn.iter_rows().map(|row| row.iter().collect()).collect()
+    }
+}
+
+/// A place on a road map, with coordinates in kilometres.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Town {
+    pub name: &'static str,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Towns and the roads between them, in kilometres.
+///
+/// No road is shorter than the straight line between its ends, which is
+/// what makes straight-line distance a safe A* heuristic.
+#[derive(Clone, Debug)]
+pub struct RoadMap {
+    pub towns: Vec<Town>,
+    pub graph: WeightedGraph,
+}
+
+impl RoadMap {
+    /// Builds the map; each road is `(a, b, detour)`: its length is the
+    /// straight-line distance rounded up, plus `detour` km.
+    pub fn new(towns: Vec<Town>, roads: &[(&str, &str, u32)]) -> Self {
+        let mut map: RoadMap = RoadMap {
+            graph: WeightedGraph::new(towns.len()),
+            towns,
+        };
+        for &(a, b, detour) in roads {
+            let (a, b) = (map.index(a), map.index(b));
+            let length: u32 = map.distance(a, b).ceil() as u32 + detour;
+            map.graph.add_undirected_edge(a, b, length);
+        }
+        map
+    }
+
+    /// The node id of the town called `name`.
+    pub fn index(&self, name: &str) -> usize {
+        self.towns
+            .iter()
+            .position(|town| town.name == name)
+            .unwrap_or_else(|| panic!("no town called {}", name))
+    }
+
+    fn distance(&self, a: usize, b: usize) -> f64 {
+        let (a, b) = (self.towns[a], self.towns[b]);
+        f64::from(a.x - b.x).hypot(f64::from(a.y - b.y))
+    }
+
+    /// Straight-line distance rounded down: never more than any road route.
+    pub fn straight_line(&self, a: usize, b: usize) -> u32 {
+        self.distance(a, b).floor() as u32
+    }
+
+    pub fn names(&self, nodes: &[usize]) -> Vec<&'static str> {
+        nodes.iter().map(|&node| self.towns[node].name).collect()
+    }
+
+    /// The towns and roads as an undirected diagram, each road labelled
+    /// with its length and `route`, if given, highlighted.
+    pub fn diagram(&self, name: &str, route: Option<&Path>) -> Diagram {
+        let mut diagram: Diagram = Diagram::undirected(name);
+        for (node, town) in self.towns.iter().enumerate() {
+            diagram.node(node, town.name);
+        }
+        for a in 0..self.towns.len() {
+            // Each road is stored both ways; draw it once
+            for &(b, length) in self.graph.neighbors(a).iter().filter(|&&(b, _)| a < b) {
+                diagram.edge(a, b, Some(format!("{} km", length)));
+            }
+        }
+        if let Some(route) = route {
+            diagram.highlight_path(&route.nodes);
+        }
+        diagram
+    }
+}
+
+/// The road map used by the demos: Dunmore in the middle, with towns on
+/// every side of it.
+pub fn sample_road_map() -> RoadMap {
+    let town = |name: &'static str, x: i32, y: i32| Town { name, x, y };
+    let towns: Vec<Town> = vec![
+        town("Ashford", -30, 0),
+        town("Brook", -18, 9),
+        town("Cliffton", -16, -8),
+        town("Dunmore", 0, 0),
+        town("Eastwick", 14, 7),
+        town("Fairview", 13, -9),
+        town("Glenrock", 30, 1),
+        town("Highmoor", -4, 22),
+        town("Ivybridge", 2, -21),
+        town("Juniper", 24, 18),
+    ];
+    let roads: [(&str, &str, u32); 14] = [
+        ("Ashford", "Brook", 2),
+        ("Ashford", "Cliffton", 3),
+        ("Brook", "Dunmore", 1),
+        ("Cliffton", "Dunmore", 4),
+        ("Brook", "Highmoor", 5),
+        ("Highmoor", "Dunmore", 2),
+        ("Cliffton", "Ivybridge", 1),
+        ("Ivybridge", "Dunmore", 3),
+        ("Dunmore", "Eastwick", 1),
+        ("Dunmore", "Fairview", 2),
+        ("Eastwick", "Glenrock", 4),
+        ("Fairview", "Glenrock", 1),
+        ("Eastwick", "Juniper", 2),
+        ("Juniper", "Glenrock", 6),
+    ];
+    RoadMap::new(towns, &roads)
+}
+
+/// The grid used by the demos: a thick wall with mud straight ahead and
+/// a cheaper gap two rows down.
+pub const SAMPLE_GRID: &str = "\
+........###.........
+........###.........
+........###.........
+...S....~~~......G..
+........###.........
+....................
+........###.........
+........###.........
+....................";
+
+/// Demonstrates Dijkstra's distances and paths on a road map.
+pub fn dijkstra_paths(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Dijkstra's Shortest Paths")?;
+
+    let map: RoadMap = sample_road_map();
+    writeln!(
+        out,
+        "{} towns, {} one-way road entries (each road is stored in both directions)",
+        map.towns.len(),
+        map.graph.edge_count()
+    )?;
+
+    let source: usize = map.index("Ashford");
+    writeln!(out, "\nFrom Ashford:")?;
+    for target in 0..map.towns.len() {
+        if let Some(path) = dijkstra(&map.graph, source, target) {
+            writeln!(
+                out,
+                "  {:<9} {:>3} km  settled {:>2}  {}",
+                map.towns[target].name,
+                path.cost,
+                path.expanded.len(),
+                map.names(&path.nodes).join(" -> ")
+            )?;
+        }
+    }
+    writeln!(
+        out,
+        "Each search stops when its target is popped: near towns settle fewer nodes"
+    )?;
+    diagram::export(out, || map.diagram("road_map", None))?;
+
+    // An island town with no roads
+    let mut isolated: WeightedGraph = map.graph.clone();
+    isolated.adjacency.push(Vec::new());
+    writeln!(
+        out,
+        "\nA town with no roads: dijkstra returns {:?}",
+        dijkstra(&isolated, source, map.towns.len())
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates how many cells each search settles on the same grid.
+pub fn grid_pathfinding(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Grid Pathfinding")?;
+
+    let map: GridMap = GridMap::parse(SAMPLE_GRID);
+    let graph: WeightedGraph = map.graph();
+    writeln!(
+        out,
+        "{}x{} grid, '.' costs 1, '~' costs 4, '#' is a wall",
+        map.rows(),
+        map.cols()
+    )?;
+
+    // Dijkstra is A* with the estimate scaled to 0
+    for (name, key, scale) in [("Dijkstra", "dijkstra", 0), ("A* (Manhattan)", "a_star", 1)] {
+        let mut search: Search<'_, _> = Search::new(&graph, map.start, map.goal, |node| {
+            scale * map.manhattan_to_goal(node)
+        });
+        while let Some(node) = search.advance() {
+            let (row, col) = map.position(node);
+            step::pause(
+                out,
+                format_args!(
+                    "{} settles ({}, {}) at cost {}",
+                    name,
+                    row,
+                    col,
+                    search.distance(node).unwrap_or(0)
+                ),
+                || {
+                    let mut lines: Vec<String> = map.render_search(&search);
+                    lines.push("'@' settled now, 'o' settled before, '+' queued".to_string());
+                    lines.join("\n")
+                },
+            )?;
+        }
+        let Some(path) = search.into_path() else {
+            writeln!(out, "\n{}: no path", name)?;
+            continue;
+        };
+        writeln!(
+            out,
+            "\n{}: cost {}, {} steps, {} cells settled ('*' path, 'o' settled)",
+            name,
+            path.cost,
+            path.nodes.len() - 1,
+            path.expanded.len()
+        )?;
+        embed::record(&format!("{}_cost", key), path.cost);
+        embed::record(&format!("{}_settled", key), path.expanded.len());
+        for line in map.render(&path) {
+            writeln!(out, "  {}", line)?;
+        }
+    }
+    writeln!(
+        out,
+        "\nBoth find a path of the same cost; A* skips most of the cells behind the start"
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: route planning with a straight-line heuristic.
+pub fn practical_route_planner(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Route Planner")?;
+
+    let map: RoadMap = sample_road_map();
+    let (from, to) = (map.index("Ashford"), map.index("Glenrock"));
+    writeln!(
+        out,
+        "Ashford -> Glenrock, {} km as the crow flies",
+        map.straight_line(from, to)
+    )?;
+
+    let plain: Option<Path> = dijkstra(&map.graph, from, to);
+    let guided: Option<Path> = a_star(&map.graph, from, to, |town| map.straight_line(town, to));
+    for (name, path) in [("Dijkstra", &plain), ("A*", &guided)] {
+        if let Some(path) = path {
+            writeln!(
+                out,
+                "\n{}: {} km via {}",
+                name,
+                path.cost,
+                map.names(&path.nodes).join(" -> ")
+            )?;
+            writeln!(out, "  settled: {}", map.names(&path.expanded).join(", "))?;
+        }
+    }
+    diagram::export(out, || {
+        map.diagram("route_ashford_glenrock", guided.as_ref())
+    })?;
+    // A* only settles a town whose f = g + h is at most the route's cost
+    if let Some(route) = &guided {
+        writeln!(
+            out,
+            "\nTowns A* skipped, with f = g + h against {} km:",
+            route.cost
+        )?;
+        for town in (0..map.towns.len()).filter(|town| !route.expanded.contains(town)) {
+            if let Some(path) = dijkstra(&map.graph, from, town) {
+                let h: u32 = map.straight_line(town, to);
+                writeln!(
+                    out,
+                    "  {:<9} {} + {} = {}",
+                    map.towns[town].name,
+                    path.cost,
+                    h,
+                    path.cost + h
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Demonstrates that an overestimating heuristic can miss the shortest
+/// path.
+pub fn heuristic_pitfalls(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Heuristic Pitfalls")?;
+
+    let map: GridMap = GridMap::parse(SAMPLE_GRID);
+    let graph: WeightedGraph = map.graph();
+    writeln!(out, "{:<22} {:>5} {:>9}", "heuristic", "cost", "settled")?;
+    for (name, scale) in [
+        ("0 (Dijkstra)", 0),
+        ("Manhattan", 1),
+        ("Manhattan x 3", 3),
+        ("Manhattan x 10", 10),
+    ] {
+        let path: Option<Path> = a_star(&graph, map.start, map.goal, |node| {
+            scale * map.manhattan_to_goal(node)
+        });
+        if let Some(path) = path {
+            writeln!(
+                out,
+                "{:<22} {:>5} {:>9}",
+                name,
+                path.cost,
+                path.expanded.len()
+            )?;
+        }
+    }
+    writeln!(
+        out,
+        "Scaled up, the estimate exceeds the real cost left: A* rushes through the mud,"
+    )?;
+    writeln!(
+        out,
+        "settles fewer cells and returns a longer path (weighted A* trades accuracy for speed)"
+    )?;
+
+    Ok(())
+}
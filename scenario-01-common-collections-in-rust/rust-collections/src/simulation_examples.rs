@@ -0,0 +1,917 @@
+// A discrete-event simulation jumps from one event to the next instead of
+// ticking a clock: nothing happens between events, so there is nothing to
+// compute there. The engine is a priority queue of future events ordered by
+// time, and the loop is
+//
+//   loop {
+//       pop the earliest event       ← the clock jumps to its time
+//       update the model's state
+//       schedule the events it causes (an arrival schedules the next
+//       arrival, a service start schedules its departure, ...)
+//   }
+//
+// BinaryHeap is a max-heap, so the queue holds Reverse<(time, id)>:
+//
+//   Reverse<(time, id)>
+//           │     └─ increasing sequence number: events at the same time
+//           │        come out in the order they were scheduled
+//           └─────── earliest time first
+//
+// The id also keys a HashMap holding each event's payload, which is what
+// makes cancelling cheap: remove the payload, leave the heap entry where it
+// is, and skip it when it surfaces (the same lazy deletion as the lazy
+// Dijkstra in indexed_heap_examples). Ordering on (time, id) alone also means
+// the payload type needs no Ord, and two runs with the same seed process the
+// same events in the same order.
+//
+// The model here is a bank: customers arrive at random, wait in one line
+// (a VecDeque) for the next free clerk, and may give up if the wait gets too
+// long. Queueing theory (Erlang C) predicts the mean wait when nobody gives
+// up, which gives the simulation something to be checked against.
+//
+// TimerWheel is the other classic scheduler: slots indexed by the digits of
+// the deadline instead of a heap ordered by it. Schedulers with many timers
+// due at the same moment (every connection's keep-alive, a batch of retries)
+// pay the heap's comparisons for every tie; the wheel files them all in one
+// slot. The Timer_Queues benchmarks compare the two with a BTreeMap of
+// Vec batches.
+
+use demo_framework::rng::Lcg;
+use demo_framework::{section, step};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::io::{self, Write};
+use std::mem;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "event_queue_basics",
+        "Scheduling, simultaneous events and cancellation on BinaryHeap<Reverse<(time, id)>>",
+        event_queue_basics,
+    )?;
+
+    section(
+        out,
+        "bank_trace",
+        "The first events of a bank simulation, one at a time",
+        bank_trace,
+    )?;
+
+    section(
+        out,
+        "practical_staffing",
+        "Practical demo: how many clerks, checked against queueing theory",
+        practical_staffing,
+    )?;
+
+    section(
+        out,
+        "impatient_customers",
+        "Customers who give up, and the cancelled events they leave behind",
+        impatient_customers,
+    )?;
+
+    section(
+        out,
+        "timer_wheel",
+        "A hierarchical timer wheel: slots by deadline digits, cascading down",
+        timer_wheel,
+    )?;
+
+    Ok(())
+}
+
+/// Simulated time in milliseconds.
+pub type Time = u64;
+
+/// Identifies a scheduled event, for [`EventQueue::cancel`].
+pub type EventId = u64;
+
+/// Future events, earliest first, with a clock that moves as they are
+/// popped.
+#[derive(Clone, Debug)]
+pub struct EventQueue<E> {
+    now: Time,
+    next_id: EventId,
+    // Min-heap on (time, id): ties go to the event scheduled first
+    heap: BinaryHeap<Reverse<(Time, EventId)>>,
+    pending: HashMap<EventId, E>,
+    processed: usize,
+    skipped: usize,
+}
+
+impl<E> EventQueue<E> {
+    pub fn new() -> Self {
+        EventQueue {
+            now: 0,
+            next_id: 0,
+            heap: BinaryHeap::new(),
+            pending: HashMap::new(),
+            processed: 0,
+            skipped: 0,
+        }
+    }
+
+    /// The time of the event popped last.
+    pub fn now(&self) -> Time {
+        self.now
+    }
+
+    /// Schedules `event` at time `at`.
+    ///
+    /// # Panics
+    ///
+    /// If `at` is before [`now`](Self::now): the past has already been
+    /// simulated.
+    pub fn schedule(&mut self, at: Time, event: E) -> EventId {
+        assert!(
+            at >= self.now,
+            "scheduled at {} but the clock is at {}",
+            at,
+            self.now
+        );
+        let id: EventId = self.next_id;
+        self.next_id += 1;
+        self.heap.push(Reverse((at, id)));
+        self.pending.insert(id, event);
+        id
+    }
+
+    /// Schedules `event` `delay` after [`now`](Self::now).
+    pub fn schedule_in(&mut self, delay: Time, event: E) -> EventId {
+        self.schedule(self.now + delay, event)
+    }
+
+    /// Cancels a pending event and returns it; `None` if it already ran or
+    /// was cancelled. The heap entry stays until [`pop`](Self::pop) skips
+    /// it.
+    pub fn cancel(&mut self, id: EventId) -> Option<E> {
+        self.pending.remove(&id)
+    }
+
+    /// Removes the earliest pending event and moves the clock to its time.
+    pub fn pop(&mut self) -> Option<(Time, E)> {
+        while let Some(Reverse((at, id))) = self.heap.pop() {
+            match self.pending.remove(&id) {
+                Some(event) => {
+                    self.now = at;
+                    self.processed += 1;
+                    return Some((at, event));
+                }
+                None => self.skipped += 1,
+            }
+        }
+        None
+    }
+
+    /// The time of the earliest pending event, without popping it.
+    pub fn peek_time(&mut self) -> Option<Time> {
+        // Drop cancelled entries first so the answer is a real event
+        while let Some(&Reverse((at, id))) = self.heap.peek() {
+            if self.pending.contains_key(&id) {
+                return Some(at);
+            }
+            self.heap.pop();
+            self.skipped += 1;
+        }
+        None
+    }
+
+    /// Pending events in the order they will be popped.
+    pub fn upcoming(&self) -> Vec<(Time, &E)> {
+        let mut entries: Vec<(Time, EventId)> = self.heap.iter().map(|entry| entry.0).collect();
+        entries.sort_unstable();
+        entries
+            .into_iter()
+            .filter_map(|(at, id)| self.pending.get(&id).map(|event| (at, event)))
+            .collect()
+    }
+
+    /// Pending events, not counting cancelled ones.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Entries in the heap, cancelled ones included.
+    pub fn heap_len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Events popped so far.
+    pub fn processed(&self) -> usize {
+        self.processed
+    }
+
+    /// Cancelled entries discarded so far.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+}
+
+impl<E> Default for EventQueue<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bits of a deadline each wheel level resolves: 64 slots per level.
+const WHEEL_BITS: u32 = 6;
+const WHEEL_SLOTS: usize = 1 << WHEEL_BITS;
+/// Six levels reach 2^36 ms, about two years, past the clock.
+const WHEEL_LEVELS: usize = 6;
+
+/// A hierarchical timer wheel: the same schedule-and-pop interface as
+/// [`EventQueue`], without comparisons.
+///
+/// Level 0 has one slot per millisecond of the current 64 ms block, level
+/// 1 one slot per 64 ms block of the current 4096 ms span, and so on. An
+/// event goes to the lowest level where its deadline and the clock share
+/// every higher digit, so scheduling is a shift and a push. When the clock
+/// reaches a higher-level slot, its events cascade down to finer levels.
+/// A level-0 slot holds events due at exactly one time, in the order they
+/// were scheduled: a thousand ties cost one slot, not a thousand
+/// comparisons. No cancelling, to keep it small.
+#[derive(Clone, Debug)]
+pub struct TimerWheel<E> {
+    now: Time,
+    // [level][slot], each slot in scheduling order
+    levels: Vec<Vec<Vec<(Time, E)>>>,
+    // The level-0 slot being popped
+    due: VecDeque<E>,
+    len: usize,
+    cascades: usize,
+}
+
+impl<E> TimerWheel<E> {
+    pub fn new() -> Self {
+        TimerWheel {
+            now: 0,
+            levels: (0..WHEEL_LEVELS)
+                .map(|_| (0..WHEEL_SLOTS).map(|_| Vec::new()).collect())
+                .collect(),
+            due: VecDeque::new(),
+            len: 0,
+            cascades: 0,
+        }
+    }
+
+    /// The time of the event popped last, or the start of the slot the
+    /// wheel last cascaded.
+    pub fn now(&self) -> Time {
+        self.now
+    }
+
+    /// The level and slot an event due at `at` goes to.
+    fn position(&self, at: Time) -> (usize, usize) {
+        let level: usize = match at ^ self.now {
+            0 => 0,
+            differing => ((Time::BITS - 1 - differing.leading_zeros()) / WHEEL_BITS) as usize,
+        };
+        assert!(
+            level < WHEEL_LEVELS,
+            "{} is beyond the wheel's reach from {}",
+            at,
+            self.now
+        );
+        let slot: usize = (at >> (WHEEL_BITS * level as u32)) as usize & (WHEEL_SLOTS - 1);
+        (level, slot)
+    }
+
+    /// Schedules `event` at time `at`.
+    ///
+    /// # Panics
+    ///
+    /// If `at` is before [`now`](Self::now), or 2^36 ms or more after it.
+    pub fn schedule(&mut self, at: Time, event: E) {
+        assert!(
+            at >= self.now,
+            "scheduled at {} but the clock is at {}",
+            at,
+            self.now
+        );
+        let (level, slot) = self.position(at);
+        self.levels[level][slot].push((at, event));
+        self.len += 1;
+    }
+
+    /// Schedules `event` `delay` after [`now`](Self::now).
+    pub fn schedule_in(&mut self, delay: Time, event: E) {
+        self.schedule(self.now + delay, event)
+    }
+
+    /// Removes the earliest event and moves the clock to its time. Events
+    /// due at the same time come out in the order they were scheduled.
+    pub fn pop(&mut self) -> Option<(Time, E)> {
+        loop {
+            if let Some(event) = self.due.pop_front() {
+                self.len -= 1;
+                return Some((self.now, event));
+            }
+            if self.len == 0 {
+                return None;
+            }
+            self.advance();
+        }
+    }
+
+    /// Moves the clock to the next non-empty slot. A level-0 slot becomes
+    /// `due`; a higher one is spread over the levels below it.
+    fn advance(&mut self) {
+        for level in 0..WHEEL_LEVELS {
+            let shift: u32 = WHEEL_BITS * level as u32;
+            let current: usize = (self.now >> shift) as usize & (WHEEL_SLOTS - 1);
+            // Higher levels only hold slots after the clock's: the current
+            // one was cascaded when the clock entered it
+            let first: usize = if level == 0 { current } else { current + 1 };
+            let Some(slot) =
+                (first..WHEEL_SLOTS).find(|&slot| !self.levels[level][slot].is_empty())
+            else {
+                continue;
+            };
+            let span: Time = (1 << (shift + WHEEL_BITS)) - 1;
+            self.now = (self.now & !span) | ((slot as Time) << shift);
+            if level == 0 {
+                self.due
+                    .extend(self.levels[0][slot].drain(..).map(|(_, event)| event));
+            } else {
+                // Take the slot out to re-place its events, then put the
+                // emptied Vec back so its capacity is reused
+                let mut events: Vec<(Time, E)> = mem::take(&mut self.levels[level][slot]);
+                for (at, event) in events.drain(..) {
+                    let (to_level, to_slot) = self.position(at);
+                    self.levels[to_level][to_slot].push((at, event));
+                }
+                self.levels[level][slot] = events;
+                self.cascades += 1;
+            }
+            return;
+        }
+        unreachable!("{} events scheduled but every slot is empty", self.len);
+    }
+
+    /// Events scheduled and not yet popped.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Events waiting at each level, finest first. Level 0 includes the
+    /// ones due now and not popped yet.
+    pub fn level_lens(&self) -> Vec<usize> {
+        let mut lens: Vec<usize> = self
+            .levels
+            .iter()
+            .map(|slots| slots.iter().map(Vec::len).sum())
+            .collect();
+        lens[0] += self.due.len();
+        lens
+    }
+
+    /// Higher-level slots spread over the levels below so far.
+    pub fn cascades(&self) -> usize {
+        self.cascades
+    }
+}
+
+impl<E> Default for TimerWheel<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `count` deadlines in `0..horizon` drawn from `distinct` evenly spaced
+/// times, in random order: few distinct times means many ties.
+pub fn timer_deadlines(count: usize, distinct: u64, horizon: Time, seed: u64) -> Vec<Time> {
+    let step: Time = (horizon / distinct).max(1);
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..count).map(|_| rng.below(distinct) * step).collect()
+}
+
+/// The bank being simulated. Times are in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BankConfig {
+    pub clerks: usize,
+    /// Mean time between arrivals (exponentially distributed).
+    pub mean_interarrival: f64,
+    /// Mean service time (exponentially distributed).
+    pub mean_service: f64,
+    /// How long a customer waits in line before leaving, if they ever do.
+    pub patience: Option<Time>,
+    /// Customers arriving before the doors close.
+    pub customers: usize,
+    pub seed: u64,
+}
+
+/// Something that happens in the bank. Customers are numbered in arrival
+/// order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    Arrival(usize),
+    Departure { clerk: usize, customer: usize },
+    GiveUp(usize),
+}
+
+/// What happened over a whole run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BankReport {
+    pub arrived: usize,
+    pub served: usize,
+    pub gave_up: usize,
+    /// Sum of the time served customers spent in line.
+    pub total_wait: Time,
+    pub max_wait: Time,
+    pub longest_line: usize,
+    /// Sum of every clerk's service time.
+    pub busy: Time,
+    /// When the last customer left.
+    pub end: Time,
+    /// Events processed, and cancelled events skipped.
+    pub events: usize,
+    pub skipped: usize,
+}
+
+impl BankReport {
+    /// Mean time in line of the customers who were served, in milliseconds.
+    pub fn mean_wait(&self) -> f64 {
+        self.total_wait as f64 / self.served.max(1) as f64
+    }
+
+    /// Mean time from arrival to leaving a desk, in milliseconds.
+    pub fn mean_time_in_bank(&self) -> f64 {
+        (self.total_wait + self.busy) as f64 / self.served.max(1) as f64
+    }
+
+    /// The share of clerk time spent serving.
+    pub fn utilization(&self, clerks: usize) -> f64 {
+        self.busy as f64 / (clerks as f64 * self.end.max(1) as f64)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Waiting {
+    customer: usize,
+    arrived: Time,
+    give_up: Option<EventId>,
+}
+
+/// A bank simulation that runs one event at a time, so a demo can show the
+/// line in between. [`Bank::run`] runs it to the end.
+#[derive(Clone, Debug)]
+pub struct Bank {
+    config: BankConfig,
+    rng: Lcg,
+    events: EventQueue<Event>,
+    line: VecDeque<Waiting>,
+    // The customer each clerk is serving
+    clerks: Vec<Option<usize>>,
+    report: BankReport,
+}
+
+impl Bank {
+    pub fn new(config: BankConfig) -> Self {
+        let mut bank: Bank = Bank {
+            config,
+            rng: Lcg::new(config.seed),
+            events: EventQueue::new(),
+            line: VecDeque::new(),
+            clerks: vec![None; config.clerks],
+            report: BankReport::default(),
+        };
+        if config.customers > 0 {
+            let first: Time = bank.draw(config.mean_interarrival);
+            bank.events.schedule(first, Event::Arrival(0));
+        }
+        bank
+    }
+
+    /// Processes the next event and returns it with its time; `None` once
+    /// every customer has left.
+    pub fn step(&mut self) -> Option<(Time, Event)> {
+        let (now, event) = self.events.pop()?;
+        match event {
+            Event::Arrival(customer) => self.arrive(customer),
+            Event::Departure { clerk, .. } => self.depart(clerk),
+            Event::GiveUp(customer) => {
+                if let Some(position) = self.line.iter().position(|w| w.customer == customer) {
+                    self.line.remove(position);
+                    self.report.gave_up += 1;
+                }
+            }
+        }
+        self.report.end = now;
+        Some((now, event))
+    }
+
+    /// Runs until every customer has left.
+    pub fn run(mut self) -> BankReport {
+        while self.step().is_some() {}
+        self.report()
+    }
+
+    pub fn now(&self) -> Time {
+        self.events.now()
+    }
+
+    /// Customers waiting, front of the line first.
+    pub fn line(&self) -> Vec<usize> {
+        self.line.iter().map(|waiting| waiting.customer).collect()
+    }
+
+    /// The customer at each clerk's desk.
+    pub fn desks(&self) -> &[Option<usize>] {
+        &self.clerks
+    }
+
+    pub fn events(&self) -> &EventQueue<Event> {
+        &self.events
+    }
+
+    /// The report so far.
+    pub fn report(&self) -> BankReport {
+        BankReport {
+            events: self.events.processed(),
+            skipped: self.events.skipped(),
+            ..self.report.clone()
+        }
+    }
+
+    fn arrive(&mut self, customer: usize) {
+        self.report.arrived += 1;
+        if customer + 1 < self.config.customers {
+            let gap: Time = self.draw(self.config.mean_interarrival);
+            self.events.schedule_in(gap, Event::Arrival(customer + 1));
+        }
+        match self.clerks.iter().position(Option::is_none) {
+            Some(clerk) => self.serve(clerk, customer),
+            None => {
+                let give_up: Option<EventId> = self
+                    .config
+                    .patience
+                    .map(|patience| self.events.schedule_in(patience, Event::GiveUp(customer)));
+                self.line.push_back(Waiting {
+                    customer,
+                    arrived: self.now(),
+                    give_up,
+                });
+                self.report.longest_line = self.report.longest_line.max(self.line.len());
+            }
+        }
+    }
+
+    fn depart(&mut self, clerk: usize) {
+        self.report.served += 1;
+        self.clerks[clerk] = None;
+        if let Some(next) = self.line.pop_front() {
+            // They made it to a desk, so they won't give up after all
+            if let Some(id) = next.give_up {
+                self.events.cancel(id);
+            }
+            let wait: Time = self.now() - next.arrived;
+            self.report.total_wait += wait;
+            self.report.max_wait = self.report.max_wait.max(wait);
+            self.serve(clerk, next.customer);
+        }
+    }
+
+    fn serve(&mut self, clerk: usize, customer: usize) {
+        let duration: Time = self.draw(self.config.mean_service);
+        self.report.busy += duration;
+        self.clerks[clerk] = Some(customer);
+        self.events
+            .schedule_in(duration, Event::Departure { clerk, customer });
+    }
+
+    /// An exponentially distributed duration with the given mean.
+    fn draw(&mut self, mean: f64) -> Time {
+        // Uniform in (0, 1], so the logarithm is finite
+        let uniform: f64 = (self.rng.bits() as f64 + 1.0) / (1u64 << 31) as f64;
+        (-mean * uniform.ln()).round() as Time
+    }
+}
+
+/// Mean time in line predicted by the Erlang C formula for `clerks` clerks,
+/// exponential arrivals and service, and customers who never give up.
+///
+/// `None` if the clerks can't keep up (arrivals outpace service), where the
+/// line grows without bound.
+pub fn erlang_c_wait(clerks: usize, mean_interarrival: f64, mean_service: f64) -> Option<f64> {
+    // Offered load in clerks' worth of work
+    let load: f64 = mean_service / mean_interarrival;
+    let c: f64 = clerks as f64;
+    if load >= c {
+        return None;
+    }
+    let mut term: f64 = 1.0;
+    let mut below: f64 = 0.0;
+    for k in 0..clerks {
+        below += term;
+        term *= load / (k + 1) as f64;
+    }
+    // term is now load^c / c!
+    let queued: f64 = term * c / (c - load);
+    let p_wait: f64 = queued / (below + queued);
+    Some(p_wait * mean_service / (c - load))
+}
+
+/// A simulated time or duration as minutes and seconds, `m:ss.s`.
+fn clock(millis: Time) -> String {
+    let tenths: Time = (millis + 50) / 100;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+fn describe(event: &Event) -> String {
+    match event {
+        Event::Arrival(customer) => format!("customer {} arrives", customer),
+        Event::Departure { clerk, customer } => {
+            format!("customer {} leaves clerk {}", customer, clerk)
+        }
+        Event::GiveUp(customer) => format!("customer {} gives up", customer),
+    }
+}
+
+/// Demonstrates the event queue: time order, ties in scheduling order,
+/// and cancelling.
+pub fn event_queue_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Event Queue Basics")?;
+
+    let mut kitchen: EventQueue<&str> = EventQueue::new();
+    let coffee: EventId = kitchen.schedule(420, "coffee ready");
+    kitchen.schedule(120, "toast pops");
+    kitchen.schedule(300, "kettle boils");
+    // Same time as the toast, scheduled later: it comes out second
+    kitchen.schedule(120, "alarm rings");
+
+    let mut raw: Vec<(Time, EventId)> = kitchen.heap.iter().map(|entry| entry.0).collect();
+    raw.sort_unstable();
+    writeln!(out, "Heap entries (time, id): {:?}", raw)?;
+    writeln!(out, "peek_time(): {:?}", kitchen.peek_time())?;
+
+    writeln!(out, "\ncancel(coffee): {:?}", kitchen.cancel(coffee))?;
+    writeln!(
+        out,
+        "len(): {}, heap_len(): {} (the cancelled entry is still queued)",
+        kitchen.len(),
+        kitchen.heap_len()
+    )?;
+
+    writeln!(out)?;
+    while let Some((at, event)) = kitchen.pop() {
+        writeln!(out, "  t={:<4} {}", at, event)?;
+        // Handling an event may schedule more, but never in the past
+        if event == "kettle boils" {
+            let id: EventId = kitchen.schedule_in(240, "tea steeped");
+            writeln!(out, "         schedule_in(240) -> id {}", id)?;
+        }
+    }
+    writeln!(
+        out,
+        "\nClock at {}; processed {}, skipped {} cancelled",
+        kitchen.now(),
+        kitchen.processed(),
+        kitchen.skipped()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the simulation loop on a small bank, event by event.
+pub fn bank_trace(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Bank Trace")?;
+
+    let config: BankConfig = BankConfig {
+        clerks: 2,
+        mean_interarrival: 30_000.0,
+        mean_service: 75_000.0,
+        patience: Some(60_000),
+        customers: 8,
+        seed: 7,
+    };
+    writeln!(
+        out,
+        "{} clerks, an arrival every {} and a service of {} on average (m:ss.s), patience {}",
+        config.clerks,
+        clock(config.mean_interarrival as Time),
+        clock(config.mean_service as Time),
+        clock(config.patience.unwrap_or_default())
+    )?;
+    writeln!(
+        out,
+        "\n  {:>7}  {:<28} {:<10} {:<14} {:>7}",
+        "time", "event", "line", "desks", "pending"
+    )?;
+
+    let mut bank: Bank = Bank::new(config);
+    while let Some((at, event)) = bank.step() {
+        let desks: Vec<String> = bank
+            .desks()
+            .iter()
+            .map(|desk| desk.map_or("-".to_string(), |customer| customer.to_string()))
+            .collect();
+        writeln!(
+            out,
+            "  {:>7}  {:<28} {:<10} {:<14} {:>7}",
+            clock(at),
+            describe(&event),
+            format!("{:?}", bank.line()),
+            format!("[{}]", desks.join(", ")),
+            bank.events().len()
+        )?;
+        step::pause(out, format_args!("{}", describe(&event)), || {
+            let upcoming: Vec<String> = bank
+                .events()
+                .upcoming()
+                .into_iter()
+                .map(|(at, event)| format!("    {:>7}  {}", clock(at), describe(event)))
+                .collect();
+            format!("  next events:\n{}", upcoming.join("\n"))
+        })?;
+    }
+
+    let report: BankReport = bank.report();
+    writeln!(
+        out,
+        "\nServed {}, gave up {}, longest line {}, {} events",
+        report.served, report.gave_up, report.longest_line, report.events
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: staffing a bank, with the simulated waits next to
+/// the Erlang C prediction.
+pub fn practical_staffing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Staffing a Bank")?;
+
+    let mean_interarrival: f64 = 60_000.0;
+    let customers: usize = 50_000;
+    writeln!(
+        out,
+        "A customer every {} on average, {} customers, nobody gives up",
+        clock(mean_interarrival as Time),
+        customers
+    )?;
+    writeln!(
+        out,
+        "\n  {:<22} {:>10} {:>10} {:>10} {:>10} {:>8} {:>6}",
+        "staffing", "mean wait", "Erlang C", "in bank", "max wait", "longest", "busy"
+    )?;
+
+    // The same total capacity split differently, then one more clerk
+    let staffings: [(&str, usize, f64); 4] = [
+        ("1 clerk, 0:48 each", 1, 48_000.0),
+        ("2 clerks, 1:36 each", 2, 96_000.0),
+        ("3 clerks, 1:36 each", 3, 96_000.0),
+        ("1 clerk, 0:58 each", 1, 58_000.0),
+    ];
+    for (label, clerks, mean_service) in staffings {
+        let config: BankConfig = BankConfig {
+            clerks,
+            mean_interarrival,
+            mean_service,
+            patience: None,
+            customers,
+            seed: 42,
+        };
+        let report: BankReport = Bank::new(config).run();
+        let predicted: String = erlang_c_wait(clerks, mean_interarrival, mean_service)
+            .map_or("-".to_string(), |wait| clock(wait.round() as Time));
+        writeln!(
+            out,
+            "  {:<22} {:>10} {:>10} {:>10} {:>10} {:>8} {:>5.0}%",
+            label,
+            clock(report.mean_wait().round() as Time),
+            predicted,
+            clock(report.mean_time_in_bank().round() as Time),
+            clock(report.max_wait),
+            report.longest_line,
+            report.utilization(clerks) * 100.0
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\nAt the same capacity, two slow clerks keep the line a little shorter, but each"
+    )?;
+    writeln!(
+        out,
+        "visit takes twice as long, so customers spend longer in the bank overall."
+    )?;
+    writeln!(
+        out,
+        "Near full load the wait explodes, and even 50000 customers are too few for the"
+    )?;
+    writeln!(out, "simulated mean to settle on the prediction.")?;
+
+    Ok(())
+}
+
+/// Demonstrates reneging customers: each one in line has a give-up event
+/// that is cancelled if a clerk frees up first.
+pub fn impatient_customers(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Impatient Customers")?;
+
+    writeln!(
+        out,
+        "1 clerk, 0:58 of service per 1:00 between arrivals, 20000 customers\n"
+    )?;
+    writeln!(
+        out,
+        "  {:<12} {:>8} {:>8} {:>10} {:>8} {:>8} {:>8}",
+        "patience", "served", "gave up", "mean wait", "longest", "events", "skipped"
+    )?;
+
+    for patience in [None, Some(600_000), Some(120_000), Some(30_000)] {
+        let config: BankConfig = BankConfig {
+            clerks: 1,
+            mean_interarrival: 60_000.0,
+            mean_service: 58_000.0,
+            patience,
+            customers: 20_000,
+            seed: 3,
+        };
+        let report: BankReport = Bank::new(config).run();
+        writeln!(
+            out,
+            "  {:<12} {:>8} {:>8} {:>10} {:>8} {:>8} {:>8}",
+            patience.map_or("none".to_string(), clock),
+            report.served,
+            report.gave_up,
+            clock(report.mean_wait().round() as Time),
+            report.longest_line,
+            report.events,
+            report.skipped
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\nEvery customer who reached a desk cancelled their give-up event; pop() skipped"
+    )?;
+    writeln!(out, "those entries instead of searching the heap for them.")?;
+
+    Ok(())
+}
+
+/// Demonstrates where a timer wheel files events, how they cascade as the
+/// clock reaches them, and that ties keep their scheduling order.
+pub fn timer_wheel(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Timer Wheel")?;
+
+    let mut wheel: TimerWheel<&str> = TimerWheel::new();
+    for (at, event) in [
+        (40, "retry a"),
+        (40, "retry b"),
+        (40, "retry c"),
+        (100, "flush"),
+        (5_000, "keep-alive"),
+        (300_000, "rotate logs"),
+        (40, "retry d"),
+    ] {
+        wheel.schedule(at, event);
+        writeln!(
+            out,
+            "schedule({:>6}, {:<13}) -> per level {:?}",
+            at,
+            format!("{:?}", event),
+            wheel.level_lens()
+        )?;
+    }
+    writeln!(
+        out,
+        "\n64 slots per level: level 0 covers 64 ms, level 1 4.096 s, level 2 262 s, ..."
+    )?;
+
+    writeln!(out)?;
+    while let Some((at, event)) = wheel.pop() {
+        writeln!(
+            out,
+            "  t={:<7} {:<12} cascades so far {}, per level {:?}",
+            at,
+            event,
+            wheel.cascades(),
+            wheel.level_lens()
+        )?;
+        if event == "flush" {
+            wheel.schedule_in(0, "flush done");
+            writeln!(out, "           schedule_in(0) -> due now, after flush")?;
+        }
+    }
+    writeln!(
+        out,
+        "\nThe four retries at t=40 came out in scheduling order from one slot: no"
+    )?;
+    writeln!(
+        out,
+        "comparisons between them. Each event cascades at most once per level."
+    )?;
+
+    Ok(())
+}
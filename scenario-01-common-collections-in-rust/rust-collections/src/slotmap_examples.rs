@@ -0,0 +1,312 @@
+// Slab and SlotMap store values in a Vec and hand out a *key* for each one.
+// Removing a value leaves a vacant slot that a later insert reuses, so keys
+// stay valid while other values come and go - unlike Vec indices, which
+// shift on remove().
+//
+//   Vec<T>        index 2 ─► whatever is at position 2 *now*
+//   Slab<T>       key 2   ─► slot 2, reused after a remove  (usize keys)
+//   SlotMap<K, T> key 2v1 ─► slot 2, but only in generation 1
+//
+// The generation is what separates the two crates. A Slab key is just an
+// index: remove a value, insert another, and the old key silently points at
+// the new value (the ABA problem). A SlotMap key carries a version that is
+// bumped every time its slot is reused, so a stale key returns None.
+//
+// Both give O(1) insert, remove and lookup with no hashing at all.
+
+use demo_framework::section;
+use slab::Slab;
+use slotmap::{DenseSlotMap, SecondaryMap, SlotMap, new_key_type};
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "slab_basics",
+        "Slab: insert returns a usize key, vacant slots are reused",
+        slab_basics,
+    )?;
+
+    section(
+        out,
+        "aba_problem",
+        "Stale keys: Slab returns the wrong value, SlotMap returns None",
+        aba_problem,
+    )?;
+
+    section(
+        out,
+        "generational_keys",
+        "How SlotMap versions its keys, and the SlotMap variants",
+        generational_keys,
+    )?;
+
+    section(
+        out,
+        "stable_handles_vs_vec_indices",
+        "Why Vec indices make poor handles and keys don't",
+        stable_handles_vs_vec_indices,
+    )?;
+
+    section(
+        out,
+        "entity_storage",
+        "Practical demo: entity storage with typed keys and component maps",
+        entity_storage,
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates Slab's insert/remove/reuse cycle.
+///
+/// Slab is the simplest of the two: keys are plain usize indices and the
+/// free slots form a linked list, so insert always reuses the most recently
+/// freed slot first.
+pub fn slab_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Slab Basics")?;
+
+    let mut connections: Slab<&str> = Slab::with_capacity(4);
+    let a: usize = connections.insert("10.0.0.1");
+    let b: usize = connections.insert("10.0.0.2");
+    let c: usize = connections.insert("10.0.0.3");
+    writeln!(out, "Inserted keys: a={}, b={}, c={}", a, b, c)?;
+
+    // remove() returns the value and frees the slot
+    writeln!(out, "remove(b) -> {:?}", connections.remove(b))?;
+    writeln!(out, "get(b) -> {:?}", connections.get(b))?;
+
+    // The next insert reuses the freed slot
+    let d: usize = connections.insert("10.0.0.4");
+    writeln!(out, "Next insert got key {} (b's old slot)", d)?;
+
+    // vacant_entry() tells you the key before inserting - handy when the
+    // value needs to know its own key
+    let entry = connections.vacant_entry();
+    let key: usize = entry.key();
+    entry.insert("10.0.0.5");
+    writeln!(out, "vacant_entry() key: {}", key)?;
+
+    // Iteration skips vacant slots and yields (key, value)
+    let all: Vec<(usize, &&str)> = connections.iter().collect();
+    writeln!(out, "\nAll connections: {:?}", all)?;
+    writeln!(
+        out,
+        "len {}, capacity {}",
+        connections.len(),
+        connections.capacity()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the ABA problem and how generations prevent it.
+///
+/// A handle that outlives its value is a stale handle. With plain indices
+/// there is no way to tell a stale handle from a live one once the slot has
+/// been reused; with generational keys there is.
+pub fn aba_problem(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "The ABA Problem")?;
+
+    // Slab: the stale key reads someone else's data
+    let mut slab: Slab<&str> = Slab::new();
+    let alice: usize = slab.insert("alice's session");
+    slab.remove(alice);
+    slab.insert("bob's session");
+    writeln!(out, "Slab:    stale key {} -> {:?}", alice, slab.get(alice))?;
+
+    // SlotMap: the stale key is detected
+    let mut map: SlotMap<slotmap::DefaultKey, &str> = SlotMap::new();
+    let alice = map.insert("alice's session");
+    map.remove(alice);
+    let bob = map.insert("bob's session");
+    writeln!(
+        out,
+        "SlotMap: stale key {:?} -> {:?}",
+        alice,
+        map.get(alice)
+    )?;
+    writeln!(out, "         live key  {:?} -> {:?}", bob, map.get(bob))?;
+    // Keys print as <slot>v<version>: same slot, the version tells them apart
+
+    Ok(())
+}
+
+/// Demonstrates SlotMap keys and the three SlotMap flavors.
+///
+/// - `SlotMap`: fast insert/remove/lookup; iteration visits empty slots too
+/// - `HopSlotMap`: skips empty slots while iterating, slightly slower lookups
+/// - `DenseSlotMap`: values packed in a dense Vec; fastest iteration, one
+///   extra indirection per lookup
+pub fn generational_keys(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Generational Keys")?;
+
+    let mut map: SlotMap<slotmap::DefaultKey, u32> = SlotMap::new();
+    let first = map.insert(1);
+    writeln!(out, "First key: {:?}", first)?;
+
+    // Reusing one slot over and over bumps its version each time. Versions
+    // are odd while the slot is occupied, so each reuse adds 2.
+    let mut key = first;
+    for value in 2..=4 {
+        map.remove(key);
+        key = map.insert(value);
+        writeln!(out, "  remove + insert -> {:?}", key)?;
+    }
+    writeln!(out, "contains_key(first): {}", map.contains_key(first))?;
+
+    // insert_with_key: the value can store its own key
+    let mut nodes: SlotMap<slotmap::DefaultKey, (slotmap::DefaultKey, &str)> = SlotMap::new();
+    let root = nodes.insert_with_key(|k| (k, "root"));
+    writeln!(out, "\ninsert_with_key: {:?} -> {:?}", root, nodes[root])?;
+
+    // DenseSlotMap: same API, values stored contiguously
+    let mut dense: DenseSlotMap<slotmap::DefaultKey, u32> = DenseSlotMap::new();
+    let keys: Vec<slotmap::DefaultKey> = (1..=5).map(|v| dense.insert(v * 100)).collect();
+    dense.remove(keys[1]);
+    writeln!(
+        out,
+        "DenseSlotMap values after a remove: {:?}",
+        dense.values().collect::<Vec<_>>()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates why Vec indices break as handles.
+///
+/// Vec::remove shifts every later element down; Vec::swap_remove moves the
+/// last element into the hole. Either way, indices held elsewhere now point
+/// at different values. Keys into a Slab or SlotMap never move.
+pub fn stable_handles_vs_vec_indices(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Stable Handles vs Vec Indices")?;
+
+    let mut tabs: Vec<&str> = vec!["inbox", "drafts", "sent", "spam"];
+    let sent_index: usize = 2;
+    writeln!(
+        out,
+        "Vec: {:?}, holding index {} for \"sent\"",
+        tabs, sent_index
+    )?;
+
+    tabs.remove(1);
+    writeln!(
+        out,
+        "  after remove(1):      index {} -> {:?}",
+        sent_index,
+        tabs.get(sent_index)
+    )?;
+
+    // swap_remove moves the last element instead - its index breaks
+    let mut tabs: Vec<&str> = vec!["inbox", "drafts", "sent", "spam"];
+    let spam_index: usize = 3;
+    tabs.swap_remove(0);
+    writeln!(
+        out,
+        "  after swap_remove(0): index {} (\"spam\") -> {:?}",
+        spam_index,
+        tabs.get(spam_index)
+    )?;
+
+    // The same operations with a SlotMap
+    let mut tabs: SlotMap<slotmap::DefaultKey, &str> = SlotMap::new();
+    let keys: Vec<slotmap::DefaultKey> = ["inbox", "drafts", "sent", "spam"]
+        .into_iter()
+        .map(|tab| tabs.insert(tab))
+        .collect();
+    let sent = keys[2];
+    tabs.remove(keys[1]);
+    tabs.remove(keys[0]);
+    writeln!(
+        out,
+        "\nSlotMap: after removing two tabs, {:?} -> {:?}",
+        sent,
+        tabs.get(sent)
+    )?;
+
+    // The cost: no dense 0..len indexing, and a key is 8 bytes, not a usize you can do math on
+    writeln!(
+        out,
+        "Key size: {} bytes",
+        std::mem::size_of::<slotmap::DefaultKey>()
+    )?;
+
+    Ok(())
+}
+
+new_key_type! {
+    /// Handle to an entity. A distinct key type, so an EntityId can't be
+    /// used to index some other SlotMap by mistake.
+    pub struct EntityId;
+}
+
+#[derive(Debug)]
+struct Entity {
+    name: &'static str,
+}
+
+/// Demonstrates a small entity store, the classic SlotMap use case.
+///
+/// Entities live in a SlotMap; optional components live in SecondaryMaps
+/// keyed by the same EntityId. Despawning an entity makes every copy of its
+/// handle stale at once - systems holding it get None instead of a
+/// recycled entity.
+pub fn entity_storage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Entity Storage")?;
+
+    let mut entities: SlotMap<EntityId, Entity> = SlotMap::with_key();
+    let mut health: SecondaryMap<EntityId, i32> = SecondaryMap::new();
+    let mut target: SecondaryMap<EntityId, EntityId> = SecondaryMap::new();
+
+    let player = entities.insert(Entity { name: "player" });
+    let goblin = entities.insert(Entity { name: "goblin" });
+    let chest = entities.insert(Entity { name: "chest" });
+    health.insert(player, 100);
+    health.insert(goblin, 20);
+    target.insert(goblin, player);
+    target.insert(player, goblin);
+    writeln!(
+        out,
+        "Spawned: player {:?}, goblin {:?}, chest {:?}",
+        player, goblin, chest
+    )?;
+
+    // Combat: each entity with a target hits it for 15
+    for _round in 1..=2 {
+        let hits: Vec<EntityId> = target.values().copied().collect();
+        for victim in hits {
+            if let Some(hp) = health.get_mut(victim) {
+                *hp -= 15;
+            }
+        }
+    }
+    let dead: Vec<EntityId> = health
+        .iter()
+        .filter(|(_, hp)| **hp <= 0)
+        .map(|(id, _)| id)
+        .collect();
+    for id in dead {
+        let entity: Option<Entity> = entities.remove(id);
+        health.remove(id);
+        target.remove(id);
+        writeln!(out, "Despawned {:?}: {:?}", id, entity.map(|e| e.name))?;
+    }
+
+    // A new entity reuses the goblin's slot...
+    let skeleton = entities.insert(Entity { name: "skeleton" });
+    health.insert(skeleton, 30);
+    writeln!(out, "Spawned skeleton: {:?}", skeleton)?;
+
+    // ...but the player's stale target handle doesn't resolve to it
+    let stale = target[player];
+    writeln!(
+        out,
+        "\nPlayer's target {:?} -> {:?}",
+        stale,
+        entities.get(stale).map(|e| e.name)
+    )?;
+    writeln!(out, "Player health: {:?}", health.get(player))?;
+    writeln!(out, "Live entities: {}", entities.len())?;
+
+    Ok(())
+}
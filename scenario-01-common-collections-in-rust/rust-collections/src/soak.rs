@@ -0,0 +1,246 @@
+//! The workload behind `cargo run -- soak`.
+//!
+//! [`Workload`] keeps one of each structure that owns memory in an unusual
+//! way and churns them all with random operations: std maps and queues
+//! (the `BTreeMap` runs [`demo_framework::ops`] operations), the
+//! hand-written caches from [`crate::lru_examples`] and
+//! [`crate::cache_policies_examples`], the `Slab` and `SlotMap` pools and
+//! the object pools from [`crate::object_pool_examples`], and the toy
+//! structures built on `Rc` and nested collections. Every structure is
+//! bounded, by a capacity or by a small key space, so once they are all
+//! full the live heap should stop growing. If it doesn't, one of them keeps
+//! something it dropped from view: an `Rc` with a stray clone, a freed slot
+//! that never gets reused, an empty list left under a key.
+
+use crate::bimap_examples::BiMap;
+use crate::cache_policies_examples::{ArcCache, CachePolicy, LfuCache};
+use crate::indexed_heap_examples::IndexedBinaryHeap;
+use crate::lru_examples::{DequeLru, LinkedLru};
+use crate::multimap_examples::MultiMap;
+use crate::multiset_examples::Counter;
+use crate::object_pool_examples::{FreeList, Pool, Pooled, SlotId, buffer_pool};
+use demo_framework::ops::{Op, OpTarget, random_op};
+use demo_framework::rng::Lcg;
+use demo_framework::soak::Round;
+use slab::Slab;
+use slotmap::{DefaultKey, SlotMap};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Keys are drawn from `0..KEY_SPACE`, which bounds every keyed structure.
+const KEY_SPACE: u64 = 4_096;
+
+/// Capacity of each cache and the most entries a queue or pool holds.
+const CAPACITY: usize = 512;
+
+/// Operations per round.
+const OPERATIONS: usize = 256;
+
+/// The round function `main` registers with [`demo_framework::cli::Cli::soak`].
+pub fn workload() -> Round {
+    let mut workload: Workload = Workload::new(1);
+    Box::new(move |_| workload.round())
+}
+
+/// Every structure the soak run churns.
+pub struct Workload {
+    rng: Lcg,
+    /// Sessions by id, expired oldest first through `expiry`.
+    sessions: HashMap<u64, String>,
+    expiry: VecDeque<u64>,
+    ordered: BTreeMap<u32, u32>,
+    caches: Vec<Box<dyn CachePolicy<u64, String>>>,
+    deque_lru: DequeLru<u64, String>,
+    slab: Slab<String>,
+    slab_handles: VecDeque<usize>,
+    slots: SlotMap<DefaultKey, String>,
+    slot_handles: VecDeque<DefaultKey>,
+    buffers: Pool<Vec<u8>>,
+    free_list: FreeList<Vec<u8>>,
+    free_list_handles: VecDeque<SlotId>,
+    names: BiMap<u64, String>,
+    tags: MultiMap<u64, u64>,
+    words: Counter<u64>,
+    queue: IndexedBinaryHeap<u64, u64>,
+}
+
+impl Workload {
+    pub fn new(seed: u64) -> Self {
+        Workload {
+            rng: Lcg::new(seed),
+            sessions: HashMap::new(),
+            expiry: VecDeque::new(),
+            ordered: BTreeMap::new(),
+            caches: vec![
+                Box::new(LinkedLru::new(CAPACITY)),
+                Box::new(LfuCache::new(CAPACITY)),
+                Box::new(ArcCache::new(CAPACITY)),
+            ],
+            // O(n) hits, so a smaller one
+            deque_lru: DequeLru::new(CAPACITY / 8),
+            slab: Slab::new(),
+            slab_handles: VecDeque::new(),
+            slots: SlotMap::new(),
+            slot_handles: VecDeque::new(),
+            buffers: buffer_pool(4 * 1024).with_max_idle(4),
+            free_list: FreeList::new(),
+            free_list_handles: VecDeque::new(),
+            names: BiMap::new(),
+            tags: MultiMap::new(),
+            words: Counter::new(),
+            queue: IndexedBinaryHeap::new(),
+        }
+    }
+
+    /// Entries held across every structure; bounded however long it runs.
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+            + self.expiry.len()
+            + self.ordered.len()
+            + self.caches.iter().map(|cache| cache.len()).sum::<usize>()
+            + self.deque_lru.len()
+            + self.slab.len()
+            + self.slots.len()
+            + self.buffers.idle()
+            + self.free_list.slots()
+            + self.names.len()
+            + self.tags.len()
+            + self.words.len()
+            + self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Runs one batch of random operations.
+    pub fn round(&mut self) {
+        for _ in 0..OPERATIONS {
+            let key: u64 = self.rng.below(KEY_SPACE);
+            let action: u64 = self.rng.below(100);
+            match self.rng.below(11) {
+                0 => self.sessions_op(key),
+                1 => self.ordered_op(),
+                2 => self.caches_op(key, action),
+                3 => self.deque_lru_op(key, action),
+                4 => self.slab_op(key),
+                5 => self.slots_op(key),
+                6 => self.object_pool_op(key, action),
+                7 => self.names_op(key, action),
+                8 => self.tags_op(key, action),
+                9 => self.words_op(key, action),
+                _ => self.queue_op(key, action),
+            }
+        }
+    }
+
+    fn sessions_op(&mut self, key: u64) {
+        self.sessions.insert(key, format!("session-{}", key));
+        self.expiry.push_back(key);
+        if self.expiry.len() > CAPACITY
+            && let Some(oldest) = self.expiry.pop_front()
+        {
+            self.sessions.remove(&oldest);
+        }
+    }
+
+    fn ordered_op(&mut self) {
+        let op: Op = random_op(KEY_SPACE as u32, &mut |bound| self.rng.below(bound));
+        self.ordered.apply(op);
+    }
+
+    fn caches_op(&mut self, key: u64, action: u64) {
+        let cache: &mut Box<dyn CachePolicy<u64, String>> = &mut self.caches[(action % 3) as usize];
+        if cache.get(&key).is_none() {
+            cache.put(key, key.to_string());
+        }
+    }
+
+    fn deque_lru_op(&mut self, key: u64, action: u64) {
+        let key: u64 = key % (CAPACITY as u64);
+        if action < 20 {
+            self.deque_lru.remove(&key);
+        } else if self.deque_lru.get(&key).is_none() {
+            self.deque_lru.put(key, key.to_string());
+        }
+    }
+
+    fn slab_op(&mut self, key: u64) {
+        self.slab_handles
+            .push_back(self.slab.insert(key.to_string()));
+        if self.slab_handles.len() > CAPACITY
+            && let Some(oldest) = self.slab_handles.pop_front()
+        {
+            self.slab.remove(oldest);
+        }
+    }
+
+    fn slots_op(&mut self, key: u64) {
+        self.slot_handles
+            .push_back(self.slots.insert(key.to_string()));
+        if self.slot_handles.len() > CAPACITY
+            && let Some(oldest) = self.slot_handles.pop_front()
+        {
+            self.slots.remove(oldest);
+        }
+    }
+
+    fn object_pool_op(&mut self, key: u64, action: u64) {
+        {
+            let mut buffer: Pooled<Vec<u8>> = self.buffers.checkout();
+            buffer.resize(64 + (action as usize) * 16, key as u8);
+        }
+        if action < 50 {
+            let id: SlotId = self.free_list.acquire(|| Vec::with_capacity(256));
+            if let Some(buffer) = self.free_list.get_mut(id) {
+                buffer.extend_from_slice(&key.to_le_bytes());
+            }
+            self.free_list_handles.push_back(id);
+        }
+        if (self.free_list_handles.len() > CAPACITY / 8 || action >= 50)
+            && let Some(oldest) = self.free_list_handles.pop_front()
+        {
+            self.free_list
+                .release(oldest, Vec::clear)
+                .expect("each handle is released once");
+        }
+    }
+
+    fn names_op(&mut self, key: u64, action: u64) {
+        let key: u64 = key % (CAPACITY as u64);
+        if action < 30 {
+            self.names.remove_by_left(&key);
+        } else {
+            // Names collide across keys, so inserts also overwrite pairs
+            self.names.insert(key, format!("user-{}", action % 64));
+        }
+    }
+
+    fn tags_op(&mut self, key: u64, action: u64) {
+        let key: u64 = key % 64;
+        match action {
+            0..=49 => self.tags.insert(key, action % 8),
+            50..=89 => {
+                self.tags.remove_value(&key, &(action % 8));
+            }
+            _ => {
+                self.tags.remove_all(&key);
+            }
+        }
+    }
+
+    fn words_op(&mut self, key: u64, action: u64) {
+        if action < 55 {
+            self.words.add(key);
+        } else {
+            self.words.remove(&key);
+        }
+    }
+
+    fn queue_op(&mut self, key: u64, action: u64) {
+        if action < 50 || self.queue.len() < CAPACITY / 2 {
+            self.queue.push(key % (CAPACITY as u64), action);
+        } else {
+            self.queue.pop();
+        }
+    }
+}
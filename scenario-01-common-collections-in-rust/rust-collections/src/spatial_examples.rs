@@ -0,0 +1,474 @@
+// A spatial hash answers "what is near this point?" without looking at
+// everything. The plane is cut into square cells of one fixed size, and a
+// HashMap from cell coordinates to the entities inside it stands in for a
+// grid that would otherwise need to cover the whole (maybe unbounded) world:
+//
+//        x: 0    10   20   30
+//   y: 0 ┌────┬────┬────┐
+//        │ a  │    │  c │        cells: (0, 0) -> [a]
+//     10 ├────┼────┼────┤               (2, 0) -> [c]
+//        │   b│ d  │    │               (0, 1) -> [b]
+//     20 └────┴────┴────┘               (1, 1) -> [d]
+//
+// An entity at (x, y) lives in cell (floor(x / size), floor(y / size)).
+// floor, not `as i32`, which rounds toward zero and would put -0.5 and 0.5
+// in the same cell. Only occupied cells have an entry, so empty space costs
+// nothing and coordinates can be negative or huge.
+//
+// A radius query visits the cells that overlap the query's bounding square
+// and checks the distance only for entities in them. Collision detection
+// checks each entity against its own cell and the neighbouring ones, which
+// is O(n) for evenly spread entities instead of the O(n²) of comparing
+// every pair. The cell size is the trade-off: a cell at least as wide as
+// the collision distance means only adjacent cells can hold a hit; much
+// bigger cells hold more entities that are too far away.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "spatial_hash_basics",
+        "Entities bucketed by cell in a HashMap<(i32, i32), Vec<EntityId>>",
+        spatial_hash_basics,
+    )?;
+
+    section(
+        out,
+        "radius_queries",
+        "Neighbour queries that visit a few cells instead of every entity",
+        radius_queries,
+    )?;
+
+    section(
+        out,
+        "practical_collision_detection",
+        "Practical demo: collision detection, spatial hash vs every pair",
+        practical_collision_detection,
+    )?;
+
+    Ok(())
+}
+
+/// Handle to an entity in a [`SpatialHash`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityId(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+
+    /// Squared distance; comparing it with a squared radius avoids sqrt.
+    pub fn distance_squared(self, other: Point) -> f64 {
+        let (dx, dy) = (self.x - other.x, self.y - other.y);
+        dx * dx + dy * dy
+    }
+}
+
+/// Cell coordinates: the position divided by the cell size, rounded down.
+pub type Cell = (i32, i32);
+
+/// Pairs of entities within some distance of each other, and how many
+/// distance checks it took to find them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Collisions {
+    /// Each pair once, smaller id first, sorted.
+    pub pairs: Vec<(EntityId, EntityId)>,
+    pub checks: usize,
+}
+
+/// The result of a radius query, and what it cost.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Neighbours {
+    /// Sorted by id.
+    pub ids: Vec<EntityId>,
+    pub cells_visited: usize,
+    pub checks: usize,
+}
+
+/// A uniform grid of square cells, stored sparsely.
+#[derive(Debug, Clone)]
+pub struct SpatialHash {
+    cell_size: f64,
+    cells: HashMap<Cell, Vec<EntityId>>,
+    positions: HashMap<EntityId, Point>,
+}
+
+impl SpatialHash {
+    /// An empty grid of `cell_size` by `cell_size` cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` isn't a positive finite number.
+    pub fn new(cell_size: f64) -> Self {
+        assert!(
+            cell_size > 0.0 && cell_size.is_finite(),
+            "cell size must be positive and finite, got {}",
+            cell_size
+        );
+        SpatialHash {
+            cell_size,
+            cells: HashMap::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Cells holding at least one entity; empty cells are removed.
+    pub fn occupied_cells(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn cell_of(&self, point: Point) -> Cell {
+        (
+            (point.x / self.cell_size).floor() as i32,
+            (point.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// The entities in `cell`, in the order they entered it.
+    pub fn entities_in(&self, cell: Cell) -> &[EntityId] {
+        self.cells.get(&cell).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn position(&self, id: EntityId) -> Option<Point> {
+        self.positions.get(&id).copied()
+    }
+
+    /// Places `id` at `point`, moving it if it is already in the grid.
+    /// Returns its previous position. A move within one cell leaves the
+    /// cell's list alone.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a coordinate is NaN or infinite.
+    pub fn insert(&mut self, id: EntityId, point: Point) -> Option<Point> {
+        assert!(
+            point.x.is_finite() && point.y.is_finite(),
+            "{:?} is not a finite position",
+            point
+        );
+        let cell: Cell = self.cell_of(point);
+        let previous: Option<Point> = self.positions.insert(id, point);
+        if let Some(old) = previous {
+            let old_cell: Cell = self.cell_of(old);
+            if old_cell == cell {
+                return previous;
+            }
+            self.unlink(id, old_cell);
+        }
+        self.cells.entry(cell).or_default().push(id);
+        previous
+    }
+
+    /// Takes `id` out of the grid; returns where it was.
+    pub fn remove(&mut self, id: EntityId) -> Option<Point> {
+        let point: Point = self.positions.remove(&id)?;
+        self.unlink(id, self.cell_of(point));
+        Some(point)
+    }
+
+    /// Removes `id` from `cell`'s list, and the cell once it is empty.
+    fn unlink(&mut self, id: EntityId, cell: Cell) {
+        if let Entry::Occupied(mut entry) = self.cells.entry(cell) {
+            let ids: &mut Vec<EntityId> = entry.get_mut();
+            if let Some(index) = ids.iter().position(|&other| other == id) {
+                ids.swap_remove(index);
+            }
+            if ids.is_empty() {
+                entry.remove();
+            }
+        }
+    }
+
+    /// The entities within `radius` of `center`.
+    pub fn query_radius(&self, center: Point, radius: f64) -> Neighbours {
+        let (min_x, min_y) = self.cell_of(Point::new(center.x - radius, center.y - radius));
+        let (max_x, max_y) = self.cell_of(Point::new(center.x + radius, center.y + radius));
+        let mut neighbours: Neighbours = Neighbours::default();
+        for cell_x in min_x..=max_x {
+            for cell_y in min_y..=max_y {
+                neighbours.cells_visited += 1;
+                for &id in self.entities_in((cell_x, cell_y)) {
+                    neighbours.checks += 1;
+                    if self.positions[&id].distance_squared(center) <= radius * radius {
+                        neighbours.ids.push(id);
+                    }
+                }
+            }
+        }
+        neighbours.ids.sort_unstable();
+        neighbours
+    }
+
+    /// Every pair of entities at most `distance` apart.
+    ///
+    /// Each cell is checked against itself and four of its eight
+    /// neighbours - right, and the three below - so every pair of adjacent
+    /// cells is visited once, from one side.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `distance` is larger than the cell size: a pair could then
+    /// be two cells apart.
+    pub fn colliding_pairs(&self, distance: f64) -> Collisions {
+        assert!(
+            distance <= self.cell_size,
+            "distance {} exceeds the cell size {}",
+            distance,
+            self.cell_size
+        );
+        const FORWARD: [(i32, i32); 4] = [(1, -1), (1, 0), (1, 1), (0, 1)];
+        let limit: f64 = distance * distance;
+        let mut collisions: Collisions = Collisions::default();
+        let check = |a: EntityId, b: EntityId, collisions: &mut Collisions| {
+            collisions.checks += 1;
+            if self.positions[&a].distance_squared(self.positions[&b]) <= limit {
+                collisions.pairs.push((a.min(b), a.max(b)));
+            }
+        };
+
+        for (&(cell_x, cell_y), ids) in &self.cells {
+            for (i, &a) in ids.iter().enumerate() {
+                for &b in &ids[i + 1..] {
+                    check(a, b, &mut collisions);
+                }
+            }
+            for (dx, dy) in FORWARD {
+                let neighbour: &[EntityId] = self.entities_in((cell_x + dx, cell_y + dy));
+                for &a in ids {
+                    for &b in neighbour {
+                        check(a, b, &mut collisions);
+                    }
+                }
+            }
+        }
+        collisions.pairs.sort_unstable();
+        collisions
+    }
+}
+
+/// Every pair of `entities` at most `distance` apart, by checking every
+/// pair.
+pub fn brute_force_pairs(entities: &[(EntityId, Point)], distance: f64) -> Collisions {
+    let limit: f64 = distance * distance;
+    let mut collisions: Collisions = Collisions::default();
+    for (i, &(a, a_at)) in entities.iter().enumerate() {
+        for &(b, b_at) in &entities[i + 1..] {
+            collisions.checks += 1;
+            if a_at.distance_squared(b_at) <= limit {
+                collisions.pairs.push((a.min(b), a.max(b)));
+            }
+        }
+    }
+    collisions.pairs.sort_unstable();
+    collisions
+}
+
+/// `count` entities at random positions in a `size` by `size` square.
+pub fn scatter(count: u32, size: f64, seed: u64) -> Vec<(EntityId, Point)> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..count)
+        .map(|id| {
+            (
+                EntityId(id),
+                Point::new(rng.unit() * size, rng.unit() * size),
+            )
+        })
+        .collect()
+}
+
+// ----------------------------------------------------------------------------
+// Demos
+// ----------------------------------------------------------------------------
+
+/// Demonstrates the cells entities land in, and moving and removing one.
+pub fn spatial_hash_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Spatial Hash Basics")?;
+
+    let mut grid: SpatialHash = SpatialHash::new(10.0);
+    let entities: [(u32, Point); 5] = [
+        (1, Point::new(3.0, 4.0)),
+        (2, Point::new(7.5, 12.0)),
+        (3, Point::new(25.0, 2.0)),
+        (4, Point::new(14.0, 18.0)),
+        (5, Point::new(-0.5, 4.0)),
+    ];
+    writeln!(out, "Cell size {}:", grid.cell_size())?;
+    for (id, point) in entities {
+        grid.insert(EntityId(id), point);
+        writeln!(
+            out,
+            "  entity {} at ({:>4}, {:>4}) -> cell {:?}",
+            id,
+            point.x,
+            point.y,
+            grid.cell_of(point)
+        )?;
+    }
+    // -0.5 rounds down to cell -1; truncating with `as` would give cell 0
+    writeln!(
+        out,
+        "{} entities in {} occupied cells",
+        grid.len(),
+        grid.occupied_cells()
+    )?;
+
+    // A move within the cell changes the position only
+    grid.insert(EntityId(1), Point::new(6.0, 8.0));
+    writeln!(
+        out,
+        "\nMove entity 1 to (6, 8): cell (0, 0) holds {:?}",
+        grid.entities_in((0, 0))
+    )?;
+    // A move across a border relinks it, and an emptied cell is dropped
+    grid.insert(EntityId(3), Point::new(14.5, 16.0));
+    writeln!(
+        out,
+        "Move entity 3 to (14.5, 16): cell (1, 1) holds {:?}, cell (2, 0) holds {:?}",
+        grid.entities_in((1, 1)),
+        grid.entities_in((2, 0))
+    )?;
+    writeln!(out, "remove(entity 5): {:?}", grid.remove(EntityId(5)))?;
+    writeln!(
+        out,
+        "{} entities in {} occupied cells",
+        grid.len(),
+        grid.occupied_cells()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates radius queries and the cells they visit.
+pub fn radius_queries(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Radius Queries")?;
+
+    let entities: Vec<(EntityId, Point)> = scatter(1_000, 100.0, 7);
+    let mut grid: SpatialHash = SpatialHash::new(5.0);
+    for &(id, point) in &entities {
+        grid.insert(id, point);
+    }
+    writeln!(
+        out,
+        "{} entities in a 100 x 100 square, cell size {}: {} occupied cells",
+        grid.len(),
+        grid.cell_size(),
+        grid.occupied_cells()
+    )?;
+
+    let center: Point = Point::new(50.0, 50.0);
+    for radius in [2.0, 5.0, 12.0] {
+        let found: Neighbours = grid.query_radius(center, radius);
+        // A linear scan computes every distance and agrees
+        let scanned: Vec<EntityId> = entities
+            .iter()
+            .filter(|(_, point)| point.distance_squared(center) <= radius * radius)
+            .map(|&(id, _)| id)
+            .collect();
+        writeln!(
+            out,
+            "  radius {:>4}: {:>3} found, {:>3} cells visited, {:>3} distance checks (linear scan: {}), same: {}",
+            radius,
+            found.ids.len(),
+            found.cells_visited,
+            found.checks,
+            entities.len(),
+            found.ids == scanned
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Practical example: circles of radius 1 drifting across a 200 x 200
+/// arena, with the touching pairs found every tick.
+///
+/// Two circles touch when their centres are within 2, so the cell size is
+/// 2: every touching pair is in the same or adjacent cells. The grid is
+/// updated in place as entities move, and finds the same pairs as the
+/// all-pairs check with a small fraction of its distance checks.
+pub fn practical_collision_detection(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Collision Detection")?;
+
+    let diameter: f64 = 2.0;
+    let arena: f64 = 200.0;
+    let mut entities: Vec<(EntityId, Point)> = scatter(2_000, arena, 3);
+    let velocities: Vec<(EntityId, Point)> = scatter(2_000, 2.0, 9);
+    let mut grid: SpatialHash = SpatialHash::new(diameter);
+    for &(id, point) in &entities {
+        grid.insert(id, point);
+    }
+
+    writeln!(
+        out,
+        "{} circles of diameter {} in a {} x {} arena",
+        entities.len(),
+        diameter,
+        arena,
+        arena
+    )?;
+    writeln!(
+        out,
+        "  {:<5} {:>8} {:>14} {:>14}  same",
+        "tick", "touching", "grid checks", "all-pairs"
+    )?;
+    let mut grid_time: Duration = Duration::ZERO;
+    let mut brute_time: Duration = Duration::ZERO;
+    for tick in 0..4 {
+        let start: Instant = Instant::now();
+        let found: Collisions = grid.colliding_pairs(diameter);
+        grid_time += start.elapsed();
+        let start: Instant = Instant::now();
+        let expected: Collisions = brute_force_pairs(&entities, diameter);
+        brute_time += start.elapsed();
+        writeln!(
+            out,
+            "  {:<5} {:>8} {:>14} {:>14}  {}",
+            tick,
+            found.pairs.len(),
+            found.checks,
+            expected.checks,
+            found.pairs == expected.pairs
+        )?;
+
+        // Everyone drifts, wrapping around the arena's edges
+        for ((id, point), (_, velocity)) in entities.iter_mut().zip(&velocities) {
+            point.x = (point.x + velocity.x - 1.0).rem_euclid(arena);
+            point.y = (point.y + velocity.y - 1.0).rem_euclid(arena);
+            grid.insert(*id, *point);
+        }
+    }
+    writeln!(
+        out,
+        "{} occupied cells after the last move",
+        grid.occupied_cells()
+    )?;
+    writeln!(out, "Spatial hash: {:.2?}", grid_time)?;
+    writeln!(out, "All pairs:    {:.2?}", brute_time)?;
+
+    Ok(())
+}
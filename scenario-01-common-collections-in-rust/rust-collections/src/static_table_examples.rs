@@ -0,0 +1,482 @@
+// Fixed data known when the program is written - HTTP status codes, weekday
+// names, unit suffixes - needs a lookup table but never an insert. The usual
+// choices:
+//
+//   LazyLock<HashMap>     built on first use, at run time: one allocation,
+//                         hashing on every lookup. std's version of
+//                         lazy_static!.
+//   phf::Map              a perfect hash computed by a macro at build time:
+//                         no collisions, no allocation, an extra crate.
+//   sorted static array   a `static [(K, V); N]` searched with
+//                         binary_search: no allocation, no hashing, no
+//                         dependency; O(log n) comparisons.
+//   const-built index     a `static [u8; M]` computed by a const fn, indexed
+//                         directly by the key: O(1), for small dense keys.
+//
+// The last two are std-only and live in the binary's read-only data. The
+// catch with a sorted array is keeping it sorted, and const fn handles that:
+// the source can list entries in any order, a const fn sorts them while
+// compiling, and a `const _: () = assert!(...)` turns a duplicate key into a
+// compile error instead of a lookup that quietly finds the wrong entry.
+//
+// The Static_Lookups benchmarks compare all four.
+
+use demo_framework::section;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::mem::size_of_val;
+use std::sync::LazyLock;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "sorted_static_table",
+        "A static sorted array searched with binary_search",
+        sorted_static_table,
+    )?;
+
+    section(
+        out,
+        "const_built_tables",
+        "Sorting and indexing a table with const fn at compile time",
+        const_built_tables,
+    )?;
+
+    section(
+        out,
+        "weekday_names",
+        "Parsing weekday names without allocating",
+        weekday_names,
+    )?;
+
+    section(
+        out,
+        "choosing_static_lookups",
+        "Sorted array, const index or LazyLock<HashMap>",
+        choosing_static_lookups,
+    )?;
+
+    Ok(())
+}
+
+/// Status codes as they'd be written down: by class, most common first.
+const STATUS_SOURCE: [(u16, &str); 47] = [
+    (200, "OK"),
+    (201, "Created"),
+    (202, "Accepted"),
+    (204, "No Content"),
+    (203, "Non-Authoritative Information"),
+    (205, "Reset Content"),
+    (206, "Partial Content"),
+    (301, "Moved Permanently"),
+    (302, "Found"),
+    (304, "Not Modified"),
+    (303, "See Other"),
+    (307, "Temporary Redirect"),
+    (308, "Permanent Redirect"),
+    (300, "Multiple Choices"),
+    (400, "Bad Request"),
+    (401, "Unauthorized"),
+    (403, "Forbidden"),
+    (404, "Not Found"),
+    (405, "Method Not Allowed"),
+    (409, "Conflict"),
+    (422, "Unprocessable Content"),
+    (429, "Too Many Requests"),
+    (402, "Payment Required"),
+    (406, "Not Acceptable"),
+    (408, "Request Timeout"),
+    (410, "Gone"),
+    (411, "Length Required"),
+    (412, "Precondition Failed"),
+    (413, "Content Too Large"),
+    (414, "URI Too Long"),
+    (415, "Unsupported Media Type"),
+    (418, "I'm a teapot"),
+    (425, "Too Early"),
+    (426, "Upgrade Required"),
+    (428, "Precondition Required"),
+    (431, "Request Header Fields Too Large"),
+    (451, "Unavailable For Legal Reasons"),
+    (500, "Internal Server Error"),
+    (502, "Bad Gateway"),
+    (503, "Service Unavailable"),
+    (504, "Gateway Timeout"),
+    (501, "Not Implemented"),
+    (505, "HTTP Version Not Supported"),
+    (100, "Continue"),
+    (101, "Switching Protocols"),
+    (102, "Processing"),
+    (103, "Early Hints"),
+];
+
+/// HTTP status codes and reason phrases, sorted by code.
+///
+/// A `static`, not a `const`: one copy at one address, rather than a copy
+/// wherever it is used.
+pub static HTTP_STATUSES: [(u16, &str); 47] = STATUS_TABLE;
+
+const STATUS_TABLE: [(u16, &str); 47] = sort_by_code(STATUS_SOURCE);
+const _: () = assert!(
+    strictly_increasing(&STATUS_TABLE),
+    "duplicate HTTP status code"
+);
+
+/// Insertion sort, as a const fn: no closures or trait methods (Ord::cmp
+/// isn't const), so the comparison is written out.
+const fn sort_by_code<const N: usize>(mut table: [(u16, &str); N]) -> [(u16, &str); N] {
+    let mut i: usize = 1;
+    while i < N {
+        let mut j: usize = i;
+        while j > 0 && table[j - 1].0 > table[j].0 {
+            let swapped: (u16, &str) = table[j - 1];
+            table[j - 1] = table[j];
+            table[j] = swapped;
+            j -= 1;
+        }
+        i += 1;
+    }
+    table
+}
+
+const fn strictly_increasing(table: &[(u16, &str)]) -> bool {
+    let mut i: usize = 1;
+    while i < table.len() {
+        if table[i - 1].0 >= table[i].0 {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The lowest code `STATUS_INDEX` covers.
+pub const FIRST_STATUS: u16 = 100;
+
+/// `STATUS_INDEX[code - 100]` is 1 + the position of `code` in
+/// `HTTP_STATUSES`, or 0 for a code with no entry. 500 bytes for 47 entries:
+/// worth it only because the codes are dense.
+pub static STATUS_INDEX: [u8; 500] = build_status_index(&STATUS_TABLE);
+
+const fn build_status_index(table: &[(u16, &str)]) -> [u8; 500] {
+    let mut index: [u8; 500] = [0; 500];
+    let mut i: usize = 0;
+    while i < table.len() {
+        index[(table[i].0 - FIRST_STATUS) as usize] = i as u8 + 1;
+        i += 1;
+    }
+    index
+}
+
+/// Looks `key` up in a table sorted by key.
+pub fn sorted_get<'a, K: Ord, V>(table: &'a [(K, V)], key: &K) -> Option<&'a V> {
+    table
+        .binary_search_by(|(k, _)| k.cmp(key))
+        .ok()
+        .map(|i| &table[i].1)
+}
+
+/// The reason phrase for a status code, by binary search.
+pub fn status_reason(code: u16) -> Option<&'static str> {
+    sorted_get(&HTTP_STATUSES, &code).copied()
+}
+
+/// The reason phrase for a status code, through the const-built index.
+pub fn status_reason_indexed(code: u16) -> Option<&'static str> {
+    let slot: usize = code.checked_sub(FIRST_STATUS)? as usize;
+    match STATUS_INDEX.get(slot) {
+        Some(&position) if position > 0 => Some(HTTP_STATUSES[position as usize - 1].1),
+        _ => None,
+    }
+}
+
+/// The same table as a HashMap, built on first use.
+pub static STATUS_MAP: LazyLock<HashMap<u16, &'static str>> =
+    LazyLock::new(|| HTTP_STATUSES.iter().copied().collect());
+
+/// The reason phrase for a status code, through `STATUS_MAP`.
+pub fn status_reason_hashed(code: u16) -> Option<&'static str> {
+    STATUS_MAP.get(&code).copied()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// The English name: an array indexed by the discriminant, so no
+    /// search at all.
+    pub const fn name(self) -> &'static str {
+        const NAMES: [&str; 7] = [
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ];
+        NAMES[self as usize]
+    }
+}
+
+/// Full names and three-letter abbreviations, lowercase, sorted by name.
+pub static WEEKDAY_NAMES: [(&str, Weekday); 14] = WEEKDAY_TABLE;
+
+const WEEKDAY_TABLE: [(&str, Weekday); 14] = sort_by_name([
+    ("monday", Weekday::Monday),
+    ("mon", Weekday::Monday),
+    ("tuesday", Weekday::Tuesday),
+    ("tue", Weekday::Tuesday),
+    ("wednesday", Weekday::Wednesday),
+    ("wed", Weekday::Wednesday),
+    ("thursday", Weekday::Thursday),
+    ("thu", Weekday::Thursday),
+    ("friday", Weekday::Friday),
+    ("fri", Weekday::Friday),
+    ("saturday", Weekday::Saturday),
+    ("sat", Weekday::Saturday),
+    ("sunday", Weekday::Sunday),
+    ("sun", Weekday::Sunday),
+]);
+const _: () = assert!(names_strictly_increasing(&WEEKDAY_TABLE));
+
+/// Byte-wise `a < b`, the same order as `str::cmp`.
+const fn str_less(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let mut i: usize = 0;
+    while i < a.len() && i < b.len() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+        i += 1;
+    }
+    a.len() < b.len()
+}
+
+const fn sort_by_name<const N: usize>(
+    mut table: [(&'static str, Weekday); N],
+) -> [(&'static str, Weekday); N] {
+    let mut i: usize = 1;
+    while i < N {
+        let mut j: usize = i;
+        while j > 0 && str_less(table[j].0, table[j - 1].0) {
+            let swapped: (&str, Weekday) = table[j - 1];
+            table[j - 1] = table[j];
+            table[j] = swapped;
+            j -= 1;
+        }
+        i += 1;
+    }
+    table
+}
+
+const fn names_strictly_increasing(table: &[(&str, Weekday)]) -> bool {
+    let mut i: usize = 1;
+    while i < table.len() {
+        if !str_less(table[i - 1].0, table[i].0) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// `str::cmp` on the ASCII-lowercased bytes of `b`, without building the
+/// lowercase string. `a` must already be lowercase.
+fn cmp_lowercase(a: &str, b: &str) -> Ordering {
+    a.bytes()
+        .cmp(b.bytes().map(|byte| byte.to_ascii_lowercase()))
+}
+
+/// Parses a weekday name or abbreviation, ignoring ASCII case.
+pub fn parse_weekday(text: &str) -> Option<Weekday> {
+    WEEKDAY_NAMES
+        .binary_search_by(|(name, _)| cmp_lowercase(name, text))
+        .ok()
+        .map(|i| WEEKDAY_NAMES[i].1)
+}
+
+/// How many comparisons a binary search for `code` makes.
+fn probes(code: u16) -> usize {
+    let mut count: usize = 0;
+    let _ = HTTP_STATUSES.binary_search_by(|(k, _)| {
+        count += 1;
+        k.cmp(&code)
+    });
+    count
+}
+
+/// Demonstrates lookups in a static sorted array.
+pub fn sorted_static_table(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Sorted Static Table")?;
+
+    writeln!(
+        out,
+        "HTTP_STATUSES: {} entries, {} bytes of read-only data, first {:?}, last {:?}\n",
+        HTTP_STATUSES.len(),
+        size_of_val(&HTTP_STATUSES),
+        HTTP_STATUSES[0],
+        HTTP_STATUSES[HTTP_STATUSES.len() - 1]
+    )?;
+    writeln!(out, "{:>5} {:<38} {:>11}", "code", "reason", "comparisons")?;
+    for code in [200, 404, 418, 451, 299, 999] {
+        writeln!(
+            out,
+            "{:>5} {:<38} {:>11}",
+            code,
+            format!("{:?}", status_reason(code)),
+            probes(code)
+        )?;
+    }
+    writeln!(
+        out,
+        "\nHit or miss, the search halves {} entries down to one ({} steps), then checks it.",
+        HTTP_STATUSES.len(),
+        HTTP_STATUSES.len().next_power_of_two().ilog2()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates a table sorted and indexed by const fn.
+pub fn const_built_tables(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Const-Built Tables")?;
+
+    let source: Vec<u16> = STATUS_SOURCE
+        .iter()
+        .take(8)
+        .map(|&(code, _)| code)
+        .collect();
+    let sorted: Vec<u16> = HTTP_STATUSES
+        .iter()
+        .take(8)
+        .map(|&(code, _)| code)
+        .collect();
+    writeln!(out, "Source order: {:?} ...", source)?;
+    writeln!(out, "Compiled:     {:?} ...", sorted)?;
+    writeln!(
+        out,
+        "sort_by_code ran while compiling; a duplicate code fails the build at the const assert."
+    )?;
+
+    let filled: usize = STATUS_INDEX.iter().filter(|&&slot| slot > 0).count();
+    writeln!(
+        out,
+        "\nSTATUS_INDEX: {} bytes, {} of {} slots filled",
+        size_of_val(&STATUS_INDEX),
+        filled,
+        STATUS_INDEX.len()
+    )?;
+    for code in [100u16, 302, 503, 599, 42] {
+        let slot: String = match code.checked_sub(FIRST_STATUS) {
+            Some(slot) if (slot as usize) < STATUS_INDEX.len() => {
+                format!("STATUS_INDEX[{}] = {}", slot, STATUS_INDEX[slot as usize])
+            }
+            _ => "out of range".to_string(),
+        };
+        writeln!(
+            out,
+            "  {:>3}: {:<22} -> {:?}",
+            code,
+            slot,
+            status_reason_indexed(code)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Demonstrates case-insensitive parsing against a sorted name table.
+pub fn weekday_names(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Weekday Names")?;
+
+    let names: Vec<&str> = WEEKDAY_NAMES.iter().map(|&(name, _)| name).collect();
+    writeln!(out, "WEEKDAY_NAMES (sorted): {:?}\n", names)?;
+    for text in ["Mon", "TUESDAY", "thu", "Sunday", "sund", "Funday", ""] {
+        writeln!(
+            out,
+            "  parse_weekday({:?}) = {:?}",
+            text,
+            parse_weekday(text)
+        )?;
+    }
+
+    let round_trip: bool = Weekday::ALL
+        .iter()
+        .all(|&day| parse_weekday(day.name()) == Some(day));
+    writeln!(
+        out,
+        "\nWeekday::name is an array index; every name parses back: {}",
+        round_trip
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates what each static lookup costs in memory and set-up.
+pub fn choosing_static_lookups(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Choosing Static Lookups")?;
+
+    // The first lookup builds the map; later ones only hash
+    writeln!(
+        out,
+        "status_reason_hashed(404) = {:?}",
+        status_reason_hashed(404)
+    )?;
+
+    let entry: usize = size_of_val(&HTTP_STATUSES[0]);
+    // hashbrown stores each slot plus one control byte
+    let map_bytes: usize = STATUS_MAP.capacity() * (entry + 1);
+    writeln!(out, "\n{:<22} {:>7} {:>12}", "table", "bytes", "built")?;
+    writeln!(
+        out,
+        "{:<22} {:>7} {:>12}",
+        "sorted static array",
+        size_of_val(&HTTP_STATUSES),
+        "compile time"
+    )?;
+    writeln!(
+        out,
+        "{:<22} {:>7} {:>12}",
+        "+ const index",
+        size_of_val(&HTTP_STATUSES) + size_of_val(&STATUS_INDEX),
+        "compile time"
+    )?;
+    writeln!(
+        out,
+        "{:<22} {:>7} {:>12}",
+        "LazyLock<HashMap>",
+        format!("~{}", map_bytes),
+        "first use"
+    )?;
+
+    writeln!(
+        out,
+        "\nAll three agree on every u16: {}",
+        (0..=u16::MAX).all(|code| {
+            let reason: Option<&str> = status_reason(code);
+            reason == status_reason_indexed(code) && reason == status_reason_hashed(code)
+        })
+    )?;
+
+    Ok(())
+}
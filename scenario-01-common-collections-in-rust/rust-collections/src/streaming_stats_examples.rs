@@ -0,0 +1,465 @@
+// Two statistics over a stream of numbers, each kept up to date per value
+// instead of recomputed from the whole history:
+//
+//   running median     two heaps split the values at the middle:
+//
+//                        lower (max-heap)   |   upper (min-heap)
+//                        3  1  2  [4]       |   [6]  9  7
+//                                 ^ top             ^ top
+//
+//                      every value in lower is <= every value in upper,
+//                      and lower holds the same count or one more. The
+//                      median is lower's top (odd count) or the two tops
+//                      (even count). A push is O(log n), a query O(1).
+//
+//   sliding maximum    a VecDeque of (index, value) with values decreasing
+//                      from front to back. A new value first drops every
+//                      smaller value from the back (none of them can be the
+//                      maximum again while the new one is in the window),
+//                      and the front leaves once it is older than the
+//                      window. The front is always the maximum: O(1)
+//                      amortized per value, whatever the window length.
+//
+// Recomputing instead costs O(n) per median (a select over everything so
+// far) and O(k) per maximum (a scan over the window). The
+// Streaming_Stats benchmarks put numbers on both.
+
+use demo_framework::rng::Lcg;
+use demo_framework::{section, step};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "running_median_basics",
+        "Two heaps that keep the median of a stream",
+        running_median_basics,
+    )?;
+
+    section(
+        out,
+        "practical_latency_median",
+        "Practical demo: Median and mean latency of a stream with spikes",
+        practical_latency_median,
+    )?;
+
+    section(
+        out,
+        "sliding_max_basics",
+        "A monotonic VecDeque that keeps the maximum of a window",
+        sliding_max_basics,
+    )?;
+
+    section(
+        out,
+        "practical_peak_load",
+        "Practical demo: Peak load over the last minute",
+        practical_peak_load,
+    )?;
+
+    Ok(())
+}
+
+/// The median of every value pushed so far, from two heaps.
+#[derive(Clone, Debug)]
+pub struct RunningMedian<T> {
+    /// The smaller half, largest on top. Holds the extra value when the
+    /// count is odd.
+    lower: BinaryHeap<T>,
+    /// The larger half, smallest on top.
+    upper: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> RunningMedian<T> {
+    pub fn new() -> Self {
+        RunningMedian {
+            lower: BinaryHeap::new(),
+            upper: BinaryHeap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lower.len() + self.upper.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lower.is_empty()
+    }
+
+    /// Adds a value: O(log n).
+    pub fn push(&mut self, value: T) {
+        match self.lower.peek() {
+            Some(top) if value > *top => self.upper.push(Reverse(value)),
+            _ => self.lower.push(value),
+        }
+        // Move one top across if a half got too big
+        if self.lower.len() > self.upper.len() + 1 {
+            let moved: T = self.lower.pop().expect("lower has at least two values");
+            self.upper.push(Reverse(moved));
+        } else if self.upper.len() > self.lower.len() {
+            let Reverse(moved) = self.upper.pop().expect("upper is not empty");
+            self.lower.push(moved);
+        }
+    }
+
+    /// The lower median: the middle value for an odd count, the smaller of
+    /// the two middle values for an even one.
+    pub fn low_median(&self) -> Option<&T> {
+        self.lower.peek()
+    }
+
+    /// The upper median: the middle value for an odd count, the larger of
+    /// the two middle values for an even one.
+    pub fn high_median(&self) -> Option<&T> {
+        if self.lower.len() > self.upper.len() {
+            self.lower.peek()
+        } else {
+            self.upper.peek().map(|Reverse(value)| value)
+        }
+    }
+
+    /// Both medians; they are the same value when the count is odd.
+    pub fn medians(&self) -> Option<(&T, &T)> {
+        Some((self.low_median()?, self.high_median()?))
+    }
+
+    /// The sizes of the lower and upper halves.
+    pub fn halves(&self) -> (usize, usize) {
+        (self.lower.len(), self.upper.len())
+    }
+
+    /// The largest value of the lower half and the smallest of the upper
+    /// half.
+    pub fn tops(&self) -> (Option<&T>, Option<&T>) {
+        (
+            self.lower.peek(),
+            self.upper.peek().map(|Reverse(value)| value),
+        )
+    }
+}
+
+impl<T: Ord> Default for RunningMedian<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunningMedian<u64> {
+    /// The median as a number: the mean of the two middle values for an
+    /// even count.
+    pub fn median(&self) -> Option<f64> {
+        let (low, high) = self.medians()?;
+        Some((*low as f64 + *high as f64) / 2.0)
+    }
+}
+
+/// The median after each value, recomputed from scratch: copies everything
+/// so far and selects the middle, O(n) per value.
+pub fn naive_running_medians<T: Ord + Clone>(values: &[T]) -> Vec<(T, T)> {
+    let mut medians: Vec<(T, T)> = Vec::with_capacity(values.len());
+    let mut seen: Vec<T> = Vec::with_capacity(values.len());
+    for (i, value) in values.iter().enumerate() {
+        seen.clear();
+        seen.extend_from_slice(&values[..i]);
+        seen.push(value.clone());
+        let middle: usize = i / 2;
+        let low: T = seen.select_nth_unstable(middle).1.clone();
+        let high: T = if seen.len() % 2 == 1 {
+            low.clone()
+        } else {
+            // The element after the lower median is the smallest of the
+            // right part that select_nth_unstable left unsorted
+            seen[middle + 1..]
+                .iter()
+                .min()
+                .expect("an even count has a right part")
+                .clone()
+        };
+        medians.push((low, high));
+    }
+    medians
+}
+
+/// The maximum of the last `window` values pushed.
+#[derive(Clone, Debug)]
+pub struct SlidingMax<T> {
+    window: usize,
+    /// Values that can still become the maximum, as (index, value), with
+    /// values strictly decreasing from front to back.
+    candidates: VecDeque<(usize, T)>,
+    pushed: usize,
+}
+
+impl<T: Ord> SlidingMax<T> {
+    /// # Panics
+    ///
+    /// Panics if `window` is 0.
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "a sliding window holds at least one value");
+        SlidingMax {
+            window,
+            candidates: VecDeque::new(),
+            pushed: 0,
+        }
+    }
+
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Adds a value, dropping the oldest one once the window is full.
+    /// O(1) amortized: each value enters and leaves the deque once.
+    pub fn push(&mut self, value: T) {
+        // Smaller (or equal) values before this one are out of the race for
+        // as long as this one is in the window
+        while self
+            .candidates
+            .back()
+            .is_some_and(|(_, last)| *last <= value)
+        {
+            self.candidates.pop_back();
+        }
+        self.candidates.push_back((self.pushed, value));
+        self.pushed += 1;
+        // The window now covers the last `window` indices; one value at most
+        // has just fallen out of it
+        if self
+            .candidates
+            .front()
+            .is_some_and(|&(index, _)| index + self.window < self.pushed)
+        {
+            self.candidates.pop_front();
+        }
+    }
+
+    /// The maximum of the window, or None before the first push.
+    pub fn max(&self) -> Option<&T> {
+        self.candidates.front().map(|(_, value)| value)
+    }
+
+    /// How many values are stored: at most the window length, usually far
+    /// fewer.
+    pub fn candidates(&self) -> usize {
+        self.candidates.len()
+    }
+
+    /// The stored values, front (the maximum) first.
+    pub fn candidate_values(&self) -> impl Iterator<Item = &T> {
+        self.candidates.iter().map(|(_, value)| value)
+    }
+}
+
+/// The maximum of every full window of `window` values, like
+/// `values.windows(window).map(max)`.
+pub fn sliding_window_max<T: Ord + Clone>(values: &[T], window: usize) -> Vec<T> {
+    let mut sliding: SlidingMax<T> = SlidingMax::new(window);
+    let mut maxima: Vec<T> = Vec::with_capacity(values.len().saturating_sub(window - 1));
+    for (i, value) in values.iter().enumerate() {
+        sliding.push(value.clone());
+        if i + 1 >= window {
+            maxima.push(sliding.max().expect("the window is full").clone());
+        }
+    }
+    maxima
+}
+
+/// The same maxima, recomputed by scanning each window: O(window) per value.
+pub fn naive_sliding_window_max<T: Ord + Clone>(values: &[T], window: usize) -> Vec<T> {
+    values
+        .windows(window)
+        .map(|w| w.iter().max().expect("windows are not empty").clone())
+        .collect()
+}
+
+/// Request latencies in ms: mostly 20-60, with a burst of slow requests in
+/// the middle and a few huge outliers.
+pub fn latencies(len: usize, seed: u64) -> Vec<u64> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..len)
+        .map(|i| {
+            let base: u64 = 20 + rng.below(41);
+            if rng.below(500) == 0 {
+                // A timeout
+                base + 30_000
+            } else if (len * 2 / 5..len / 2).contains(&i) {
+                // A slow dependency: every request pays for it
+                base + 80
+            } else {
+                base
+            }
+        })
+        .collect()
+}
+
+/// Demonstrates the two halves and their tops after each push.
+pub fn running_median_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Running Median Basics")?;
+
+    let mut median: RunningMedian<u64> = RunningMedian::new();
+    writeln!(
+        out,
+        "{:>5} {:>13} {:>13} {:>7}",
+        "push", "lower (max)", "upper (min)", "median"
+    )?;
+    for value in [5, 15, 1, 3, 8, 7, 9, 10, 20, 2] {
+        median.push(value);
+        let (lower, upper) = median.halves();
+        let (lower_top, upper_top) = median.tops();
+        let top = |top: Option<&u64>| top.map_or("-".to_string(), u64::to_string);
+        writeln!(
+            out,
+            "{:>5} {:>13} {:>13} {:>7}",
+            value,
+            format!("{} (top {})", lower, top(lower_top)),
+            format!("{} (top {})", upper, top(upper_top)),
+            median.median().expect("just pushed a value")
+        )?;
+    }
+    writeln!(
+        out,
+        "\nThe halves never differ by more than one, so the median is always on a top."
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: Median and mean latency of a stream with spikes.
+pub fn practical_latency_median(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical: Latency Median")?;
+
+    let stream: Vec<u64> = latencies(10_000, 42);
+    let mut median: RunningMedian<u64> = RunningMedian::new();
+    let mut total: u64 = 0;
+    writeln!(
+        out,
+        "{} requests; 1 in 500 takes over 30000 ms, and requests 4000-4999 are slow\n",
+        stream.len()
+    )?;
+    writeln!(
+        out,
+        "{:>9} {:>10} {:>10} {:>10}",
+        "requests", "median", "mean", "max"
+    )?;
+    let mut max: u64 = 0;
+    for (i, &latency) in stream.iter().enumerate() {
+        median.push(latency);
+        total += latency;
+        max = max.max(latency);
+        let count: usize = i + 1;
+        if count.is_multiple_of(1_000) {
+            step::pause(out, format_args!("{} requests", count), || {
+                let (lower, upper) = median.halves();
+                format!("  halves: {} below, {} above", lower, upper)
+            })?;
+            writeln!(
+                out,
+                "{:>9} {:>10.1} {:>10.1} {:>10}",
+                count,
+                median.median().expect("requests were pushed"),
+                total as f64 / count as f64,
+                max
+            )?;
+        }
+    }
+    writeln!(
+        out,
+        "\nThe timeouts more than double the mean; the median moves only with the slow"
+    )?;
+    writeln!(
+        out,
+        "stretch, and by a few ms, since that is a tenth of the requests."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the deque of candidates as a window slides over values.
+pub fn sliding_max_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Sliding Max Basics")?;
+
+    let values: [u32; 12] = [4, 2, 12, 3, 8, 7, 1, 5, 6, 11, 9, 2];
+    let mut sliding: SlidingMax<u32> = SlidingMax::new(4);
+    writeln!(out, "Window of {} over {:?}\n", sliding.window(), values)?;
+    writeln!(out, "{:>5} {:>16} {:>4}", "push", "deque", "max")?;
+    for value in values {
+        sliding.push(value);
+        let deque: Vec<u32> = sliding.candidate_values().copied().collect();
+        writeln!(
+            out,
+            "{:>5} {:>16} {:>4}",
+            value,
+            format!("{:?}", deque),
+            sliding.max().expect("just pushed a value")
+        )?;
+    }
+    writeln!(
+        out,
+        "\nA larger value clears the smaller ones behind it; the front leaves when it ages out."
+    )?;
+    writeln!(
+        out,
+        "sliding_window_max gives the same as windows(4).map(max): {:?}",
+        sliding_window_max(&values, 4)
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: Peak load over the last minute.
+pub fn practical_peak_load(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical: Peak Load")?;
+
+    // One sample a second for an hour: a daily-looking curve plus noise
+    let mut rng: Lcg = Lcg::new(7);
+    let load: Vec<u32> = (0..3_600u32)
+        .map(|second| {
+            let noise: u32 = rng.below(40) as u32;
+            let wave: f64 = (second as f64 / 3_600.0 * std::f64::consts::PI).sin();
+            200 + (wave * 600.0) as u32 + noise
+        })
+        .collect();
+
+    let mut sliding: SlidingMax<u32> = SlidingMax::new(60);
+    let mut largest_deque: usize = 0;
+    let mut stored: usize = 0;
+    writeln!(
+        out,
+        "{} samples, one per second; max over the last {} seconds\n",
+        load.len(),
+        sliding.window()
+    )?;
+    writeln!(
+        out,
+        "{:>7} {:>6} {:>13} {:>8}",
+        "minute", "load", "peak (60 s)", "deque"
+    )?;
+    for (second, &sample) in load.iter().enumerate() {
+        sliding.push(sample);
+        largest_deque = largest_deque.max(sliding.candidates());
+        stored += sliding.candidates();
+        if (second + 1).is_multiple_of(600) {
+            writeln!(
+                out,
+                "{:>7} {:>6} {:>13} {:>8}",
+                (second + 1) / 60,
+                sample,
+                sliding.max().expect("samples were pushed"),
+                sliding.candidates()
+            )?;
+        }
+    }
+    writeln!(
+        out,
+        "\nThe deque held {:.1} values on average and {} at most, for a window of 60.",
+        stored as f64 / load.len() as f64,
+        largest_deque
+    )?;
+    writeln!(
+        out,
+        "It grows only while the load falls: a rising load keeps clearing it."
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,687 @@
+// A union-find (disjoint set union, DSU) keeps n elements split into
+// disjoint groups and answers two questions: "are a and b in the same
+// group?" and "merge the groups of a and b". It is a forest stored in a
+// single Vec: parent[i] is i's parent, and a root is its own parent.
+//
+//   parent: [0, 0, 1, 3, 3]        0       3
+//   index:   0  1  2  3  4         |       |
+//                                  1       4
+//                                  |
+//                                  2
+//
+// find(x) walks up to the root, which names x's group. union(a, b) hangs one
+// root under the other. Two tricks keep the trees flat:
+//
+//   union by rank     hang the shorter tree under the taller one, so a tree
+//                     of height h has at least 2^h nodes: height <= log2(n)
+//   path compression  after find(x), point every node on the path straight
+//                     at the root, so the next find is one step
+//
+// Together they make any sequence of m operations O(m * alpha(n)), where
+// alpha is the inverse Ackermann function: at most 4 for any n that fits in
+// memory. Without them, a union that always hangs the first root under the
+// second turns sorted input into a linked list and every find into O(n).
+//
+// Kruskal's minimum spanning tree is the classic client: take edges from
+// lightest to heaviest, keep an edge when its ends are in different groups.
+
+use demo_framework::diagram::{self, Diagram};
+use demo_framework::rng::Lcg;
+use demo_framework::{section, step};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "union_find_basics",
+        "union, find, connected and the parent Vec",
+        union_find_basics,
+    )?;
+
+    section(
+        out,
+        "rank_and_path_compression",
+        "How union by rank and path compression keep trees flat",
+        rank_and_path_compression,
+    )?;
+
+    section(
+        out,
+        "connectivity_queries",
+        "Counting components as links arrive",
+        connectivity_queries,
+    )?;
+
+    section(
+        out,
+        "practical_kruskal_mst",
+        "Practical demo: Kruskal's minimum spanning tree",
+        practical_kruskal_mst,
+    )?;
+
+    section(
+        out,
+        "union_find_at_scale",
+        "One million elements with and without the two tricks",
+        union_find_at_scale,
+    )?;
+
+    Ok(())
+}
+
+/// Disjoint sets over `0..len` with union by rank and path compression.
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    /// An upper bound on the height of the tree under each root.
+    rank: Vec<u8>,
+    /// Number of elements in the set, valid at roots only.
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    /// `len` singleton sets.
+    pub fn new(len: usize) -> Self {
+        UnionFind {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            size: vec![1; len],
+            sets: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Adds a new singleton set and returns its element.
+    pub fn push(&mut self) -> usize {
+        let element: usize = self.parent.len();
+        self.parent.push(element);
+        self.rank.push(0);
+        self.size.push(1);
+        self.sets += 1;
+        element
+    }
+
+    /// Number of disjoint sets.
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+
+    /// The root of `x`'s set, compressing the path on the way.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` is out of bounds.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root: usize = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // Second pass: point everything on the path at the root. Iterative,
+        // so a long chain can't overflow the stack
+        let mut node: usize = x;
+        while self.parent[node] != root {
+            let next: usize = self.parent[node];
+            self.parent[node] = root;
+            node = next;
+        }
+        root
+    }
+
+    /// Merges the sets of `a` and `b`. Returns `false` if they were
+    /// already one set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.rank[a] < self.rank[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        // a is at least as tall: b goes under it
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        if self.rank[a] == self.rank[b] {
+            self.rank[a] += 1;
+        }
+        self.sets -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in `x`'s set.
+    pub fn set_size(&mut self, x: usize) -> usize {
+        let root: usize = self.find(x);
+        self.size[root]
+    }
+
+    /// Steps from `x` to its root, without compressing anything.
+    pub fn depth(&self, x: usize) -> usize {
+        let mut depth: usize = 0;
+        let mut node: usize = x;
+        while self.parent[node] != node {
+            node = self.parent[node];
+            depth += 1;
+        }
+        depth
+    }
+
+    /// The parent Vec, as it is stored.
+    pub fn parents(&self) -> &[usize] {
+        &self.parent
+    }
+
+    /// Every set, each sorted, ordered by smallest element.
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut by_root: Vec<Vec<usize>> = vec![Vec::new(); self.len()];
+        for x in 0..self.len() {
+            let root: usize = self.find(x);
+            by_root[root].push(x);
+        }
+        let mut groups: Vec<Vec<usize>> = by_root.into_iter().filter(|g| !g.is_empty()).collect();
+        groups.sort_by_key(|group| group[0]);
+        groups
+    }
+}
+
+/// Union-find with neither trick: `union(a, b)` hangs a's root under b's
+/// and `find` never rewrites a pointer. Here to be compared against.
+#[derive(Clone, Debug)]
+pub struct QuickUnion {
+    parent: Vec<usize>,
+}
+
+impl QuickUnion {
+    pub fn new(len: usize) -> Self {
+        QuickUnion {
+            parent: (0..len).collect(),
+        }
+    }
+
+    pub fn find(&self, x: usize) -> usize {
+        let mut node: usize = x;
+        while self.parent[node] != node {
+            node = self.parent[node];
+        }
+        node
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        self.parent[a] = b;
+        true
+    }
+
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Steps from `x` to its root.
+    pub fn depth(&self, x: usize) -> usize {
+        let mut depth: usize = 0;
+        let mut node: usize = x;
+        while self.parent[node] != node {
+            node = self.parent[node];
+            depth += 1;
+        }
+        depth
+    }
+}
+
+/// An undirected weighted edge: `(a, b, weight)`.
+pub type Edge = (usize, usize, u32);
+
+/// Kruskal's algorithm: the edges of a minimum spanning forest of the graph
+/// on `0..nodes`, lightest first. Disconnected graphs get one tree per
+/// component.
+pub fn kruskal(nodes: usize, edges: &[Edge]) -> Vec<Edge> {
+    let mut mst: Kruskal = Kruskal::new(nodes, edges);
+    // Once the tree spans every node, every edge left would be skipped
+    while !mst.is_spanning() && mst.advance().is_some() {}
+    mst.into_tree()
+}
+
+/// Kruskal's algorithm one edge at a time, so a demo can show the sets
+/// merging. [`kruskal`] runs one to the end.
+#[derive(Clone, Debug)]
+pub struct Kruskal {
+    sorted: Vec<Edge>,
+    next: usize,
+    sets: UnionFind,
+    tree: Vec<Edge>,
+}
+
+impl Kruskal {
+    pub fn new(nodes: usize, edges: &[Edge]) -> Self {
+        let mut sorted: Vec<Edge> = edges.to_vec();
+        // Stable, so equal weights keep their input order
+        sorted.sort_by_key(|&(_, _, weight)| weight);
+        Kruskal {
+            sorted,
+            next: 0,
+            sets: UnionFind::new(nodes),
+            tree: Vec::with_capacity(nodes.saturating_sub(1)),
+        }
+    }
+
+    /// Considers the next lightest edge and keeps it if its ends are in
+    /// different sets. Returns the edge and whether it was kept, or `None`
+    /// once every edge has been considered.
+    pub fn advance(&mut self) -> Option<(Edge, bool)> {
+        let edge: Edge = *self.sorted.get(self.next)?;
+        self.next += 1;
+        let (a, b, _) = edge;
+        let kept: bool = self.sets.union(a, b);
+        if kept {
+            self.tree.push(edge);
+        }
+        Some((edge, kept))
+    }
+
+    /// Whether the kept edges connect every node.
+    pub fn is_spanning(&self) -> bool {
+        self.tree.len() + 1 >= self.sets.len()
+    }
+
+    /// Edges kept so far.
+    pub fn tree(&self) -> &[Edge] {
+        &self.tree
+    }
+
+    /// Edges not considered yet, lightest first.
+    pub fn remaining(&self) -> &[Edge] {
+        &self.sorted[self.next..]
+    }
+
+    pub fn sets(&self) -> &UnionFind {
+        &self.sets
+    }
+
+    pub fn into_tree(self) -> Vec<Edge> {
+        self.tree
+    }
+}
+
+/// The weighted graph as an undirected diagram with the edges of `tree`
+/// highlighted, nodes labelled by `name`.
+pub fn mst_diagram(
+    diagram_name: &str,
+    nodes: usize,
+    edges: &[Edge],
+    tree: &[Edge],
+    name: impl Fn(usize) -> String,
+) -> Diagram {
+    let mut diagram: Diagram = Diagram::undirected(diagram_name);
+    for node in 0..nodes {
+        diagram.node(node, name(node));
+    }
+    for &(a, b, weight) in edges {
+        diagram.edge(a, b, Some(weight.to_string()));
+    }
+    for &(a, b, _) in tree {
+        diagram.highlight_edge(a, b);
+    }
+    diagram
+}
+
+/// The parent Vec and the sets it encodes, with elements shown by `name`.
+/// Roots are found by walking, so drawing changes nothing.
+pub fn render_sets(sets: &UnionFind, name: impl Fn(usize) -> String) -> String {
+    let parents: &[usize] = sets.parents();
+    let root = |mut x: usize| -> usize {
+        while parents[x] != x {
+            x = parents[x];
+        }
+        x
+    };
+    let mut by_root: Vec<Vec<String>> = vec![Vec::new(); parents.len()];
+    for x in 0..parents.len() {
+        by_root[root(x)].push(name(x));
+    }
+    let groups: Vec<String> = by_root
+        .iter()
+        .enumerate()
+        .filter(|(_, members)| !members.is_empty())
+        .map(|(r, members)| format!("{{{}}} root {}", members.join(", "), name(r)))
+        .collect();
+    format!("parent: {:?}\nsets:   {}", parents, groups.join("  "))
+}
+
+/// Deterministic random pairs over `0..len`.
+pub fn random_pairs(len: usize, count: usize, seed: u64) -> Vec<(usize, usize)> {
+    let mut rng: Lcg = Lcg::new(seed);
+    let mut next = move || rng.below(len as u64) as usize;
+    (0..count).map(|_| (next(), next())).collect()
+}
+
+/// Deterministic random edges with weights in `1..=max_weight`.
+pub fn random_edges(nodes: usize, count: usize, max_weight: u32, seed: u64) -> Vec<Edge> {
+    random_pairs(nodes, count, seed)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (a, b))| {
+            let weight: u32 = ((a ^ b ^ i).wrapping_mul(2654435761) % max_weight as usize) as u32;
+            (a, b, weight + 1)
+        })
+        .collect()
+}
+
+/// Demonstrates the basic operations and the parent Vec behind them.
+pub fn union_find_basics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Union-Find Basics")?;
+
+    let mut sets: UnionFind = UnionFind::new(8);
+    writeln!(out, "8 singletons, parents: {:?}", sets.parents())?;
+
+    for (a, b) in [(0, 1), (2, 3), (1, 3), (5, 6)] {
+        let merged: bool = sets.union(a, b);
+        writeln!(
+            out,
+            "union({}, {}) -> {:<5}  parents: {:?}",
+            a,
+            b,
+            merged,
+            sets.parents()
+        )?;
+    }
+    writeln!(
+        out,
+        "union(0, 2) -> {:<5}  (already one set through 1 and 3)",
+        sets.union(0, 2)
+    )?;
+
+    writeln!(out, "\nSets: {} {:?}", sets.set_count(), sets.groups())?;
+    writeln!(out, "connected(0, 3): {}", sets.connected(0, 3))?;
+    writeln!(out, "connected(0, 5): {}", sets.connected(0, 5))?;
+    writeln!(out, "set_size(2): {}", sets.set_size(2))?;
+    writeln!(out, "find(3) = {}: the root names the set", sets.find(3))?;
+
+    // Elements can be added later
+    let late: usize = sets.push();
+    sets.union(late, 7);
+    writeln!(
+        out,
+        "\npush() -> {}, union({}, 7): {} sets {:?}",
+        late,
+        late,
+        sets.set_count(),
+        sets.groups()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates what the two tricks do to tree height.
+pub fn rank_and_path_compression(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Rank and Path Compression")?;
+
+    // union(i, i + 1) in order: the naive version builds a chain
+    let n: usize = 16;
+    let mut naive: QuickUnion = QuickUnion::new(n);
+    let mut ranked: UnionFind = UnionFind::new(n);
+    for i in 0..n - 1 {
+        naive.union(i, i + 1);
+        ranked.union(i, i + 1);
+    }
+    writeln!(out, "union(i, i + 1) for i in 0..15:")?;
+    writeln!(
+        out,
+        "  QuickUnion: max depth {:>2}  (a 16-node linked list)",
+        (0..n).map(|x| naive.depth(x)).max().unwrap_or(0)
+    )?;
+    writeln!(
+        out,
+        "  UnionFind:  max depth {:>2}  (each singleton goes under the taller tree)",
+        (0..n).map(|x| ranked.depth(x)).max().unwrap_or(0)
+    )?;
+
+    // Union by rank caps the height at log2(n) for any order of unions
+    let n: usize = 1 << 10;
+    let mut ranked: UnionFind = UnionFind::new(n);
+    // Pairwise merges of equal trees: the worst case for rank
+    let mut width: usize = 1;
+    while width < n {
+        for start in (0..n).step_by(2 * width) {
+            ranked.union(start, start + width);
+        }
+        width *= 2;
+    }
+    let deepest: usize = (0..n).map(|x| ranked.depth(x)).max().unwrap_or(0);
+    writeln!(
+        out,
+        "\nMerging equal-sized trees pairwise, 1024 elements: max depth {} = log2(1024)",
+        deepest
+    )?;
+
+    // Path compression flattens whatever find touches
+    let mut path: UnionFind = UnionFind::new(4);
+    path.union(0, 1);
+    path.union(2, 3);
+    path.union(0, 2);
+    writeln!(
+        out,
+        "A rank-2 tree: parents {:?}, depth of 3 = {}",
+        path.parents(),
+        path.depth(3)
+    )?;
+    let root: usize = path.find(3);
+    writeln!(
+        out,
+        "After find(3) = {}: parents {:?}, depth of 3 = {}",
+        root,
+        path.parents(),
+        path.depth(3)
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates incremental connectivity.
+pub fn connectivity_queries(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Connectivity Queries")?;
+
+    // Offices joined by network links, one at a time
+    let offices: [&str; 6] = ["Berlin", "Lisbon", "Madrid", "Oslo", "Paris", "Rome"];
+    let links: [(usize, usize); 6] = [(0, 4), (1, 2), (4, 5), (2, 4), (0, 5), (3, 1)];
+    let mut network: UnionFind = UnionFind::new(offices.len());
+    writeln!(
+        out,
+        "{} offices, {} networks",
+        offices.len(),
+        network.set_count()
+    )?;
+    for (a, b) in links {
+        let merged: bool = network.union(a, b);
+        writeln!(
+            out,
+            "  link {:<6} - {:<6} {:<26} {} network{}",
+            offices[a],
+            offices[b],
+            if merged {
+                "joins two networks"
+            } else {
+                "redundant (already linked)"
+            },
+            network.set_count(),
+            if network.set_count() == 1 { "" } else { "s" }
+        )?;
+        if network.set_count() == 1 {
+            writeln!(out, "  every office can reach every other")?;
+        }
+    }
+
+    // Queries after the fact are near O(1)
+    writeln!(out, "\nOslo reaches Rome: {}", network.connected(3, 5))?;
+
+    // Islands in a grid: union each land cell with its land neighbors
+    let grid: [&str; 5] = ["##..#", "#...#", "..#..", "....#", "##.##"];
+    let width: usize = grid[0].len();
+    let land: Vec<bool> = grid
+        .iter()
+        .flat_map(|row| row.bytes().map(|c| c == b'#'))
+        .collect();
+    let mut cells: UnionFind = UnionFind::new(land.len());
+    for (i, &is_land) in land.iter().enumerate() {
+        if !is_land {
+            continue;
+        }
+        if i % width + 1 < width && land[i + 1] {
+            cells.union(i, i + 1);
+        }
+        if i + width < land.len() && land[i + width] {
+            cells.union(i, i + width);
+        }
+    }
+    let water: usize = land.iter().filter(|&&is_land| !is_land).count();
+    writeln!(out, "\nGrid:")?;
+    for row in grid {
+        writeln!(out, "  {}", row)?;
+    }
+    // Every water cell is still a singleton set
+    writeln!(out, "Islands: {}", cells.set_count() - water)?;
+
+    Ok(())
+}
+
+/// Demonstrates Kruskal's algorithm on a small road network.
+pub fn practical_kruskal_mst(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Practical Example: Kruskal's Minimum Spanning Tree")?;
+
+    let towns: [&str; 6] = ["Ash", "Birch", "Cedar", "Elm", "Fir", "Oak"];
+    // Cost of laying fiber along each road, in thousands
+    let roads: [Edge; 9] = [
+        (0, 1, 7),
+        (0, 3, 5),
+        (1, 2, 8),
+        (1, 3, 9),
+        (1, 4, 7),
+        (2, 4, 5),
+        (3, 4, 15),
+        (3, 5, 6),
+        (4, 5, 8),
+    ];
+
+    let mut mst: Kruskal = Kruskal::new(towns.len(), &roads);
+    writeln!(out, "Roads from cheapest to most expensive:")?;
+    while let Some(((a, b, weight), kept)) = mst.advance() {
+        let verdict: &str = if kept {
+            "take"
+        } else {
+            "skip: both ends already connected"
+        };
+        writeln!(
+            out,
+            "  {:>2}  {:<5} - {:<5}  {}",
+            weight, towns[a], towns[b], verdict
+        )?;
+        step::pause(
+            out,
+            format_args!("{} {} - {}", verdict, towns[a], towns[b]),
+            || render_sets(mst.sets(), |town| towns[town].to_string()),
+        )?;
+    }
+    let tree: Vec<Edge> = kruskal(towns.len(), &roads);
+    let total: u32 = tree.iter().map(|&(_, _, weight)| weight).sum();
+    let all: u32 = roads.iter().map(|&(_, _, weight)| weight).sum();
+    writeln!(
+        out,
+        "\n{} roads connect all {} towns for {}, against {} for every road",
+        tree.len(),
+        towns.len(),
+        total,
+        all
+    )?;
+    diagram::export(out, || {
+        mst_diagram("kruskal_mst", towns.len(), &roads, &tree, |town| {
+            towns[town].to_string()
+        })
+    })?;
+
+    // A disconnected graph has no spanning tree, only one tree per component
+    let islands: Vec<Edge> = kruskal(4, &[(0, 1, 3), (2, 3, 1)]);
+    writeln!(
+        out,
+        "A graph with two components gets a spanning forest: {:?}",
+        islands
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the two tricks at scale.
+pub fn union_find_at_scale(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Union-Find at Scale")?;
+
+    let n: usize = 1_000_000;
+    let pairs: Vec<(usize, usize)> = random_pairs(n, n, 42);
+    let queries: Vec<(usize, usize)> = random_pairs(n, n, 7);
+
+    let start: Instant = Instant::now();
+    let mut sets: UnionFind = UnionFind::new(n);
+    for &(a, b) in &pairs {
+        sets.union(a, b);
+    }
+    let connected: usize = queries
+        .iter()
+        .filter(|&&(a, b)| sets.connected(a, b))
+        .count();
+    let elapsed: Duration = start.elapsed();
+    let deepest: usize = (0..n).map(|x| sets.depth(x)).max().unwrap_or(0);
+    writeln!(
+        out,
+        "UnionFind:  {} unions + {} queries in {:?}",
+        pairs.len(),
+        queries.len(),
+        elapsed
+    )?;
+    writeln!(
+        out,
+        "  {} sets left, {} of the queried pairs connected, max depth {}",
+        sets.set_count(),
+        connected,
+        deepest
+    )?;
+
+    // Sorted unions are the naive version's worst case; 20,000 elements
+    // already make its finds slow
+    let n: usize = 20_000;
+    let mut naive: QuickUnion = QuickUnion::new(n);
+    let mut ranked: UnionFind = UnionFind::new(n);
+    for i in 0..n - 1 {
+        naive.union(i, i + 1);
+        ranked.union(i, i + 1);
+    }
+    let start: Instant = Instant::now();
+    let naive_steps: usize = (0..n).step_by(100).map(|x| naive.depth(x)).sum();
+    let naive_time: Duration = start.elapsed();
+    let start: Instant = Instant::now();
+    let ranked_steps: usize = (0..n).step_by(100).map(|x| ranked.depth(x)).sum();
+    let ranked_time: Duration = start.elapsed();
+    writeln!(out, "\nSorted unions of {} elements, then 200 finds:", n)?;
+    writeln!(
+        out,
+        "  QuickUnion: {} parent steps in {:?}",
+        naive_steps, naive_time
+    )?;
+    writeln!(
+        out,
+        "  UnionFind:  {} parent steps in {:?}",
+        ranked_steps, ranked_time
+    )?;
+
+    Ok(())
+}
@@ -1,129 +1,751 @@
+use demo_framework::alloc::{self, AllocCounts};
+use demo_framework::section;
+use std::cell::Cell;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "basic_vec_operations",
+        "Basic Vec creation patterns",
+        basic_vec_operations,
+    )?;
+
+    section(
+        out,
+        "accessing_elements",
+        "Accessing elements safely",
+        accessing_elements,
+    )?;
+
+    section(
+        out,
+        "modifying_vectors",
+        "Modifying vectors",
+        modifying_vectors,
+    )?;
+
+    section(
+        out,
+        "slicing_vectors",
+        "Slicing - borrowing parts of a vector",
+        slicing_vectors,
+    )?;
+
+    section(
+        out,
+        "capacity_demonstration",
+        "Vec's capacity behavior",
+        capacity_demonstration,
+    )?;
+
+    section(
+        out,
+        "swap_remove_unordered",
+        "swap_remove - O(1) removal when order doesn't matter",
+        swap_remove_unordered,
+    )?;
+
+    section(
+        out,
+        "retain_mut_in_place",
+        "retain_mut - update and filter in one pass",
+        retain_mut_in_place,
+    )?;
+
+    section(
+        out,
+        "dedup_by_runs",
+        "dedup_by and dedup_by_key - collapsing runs of neighbours",
+        dedup_by_runs,
+    )?;
+
+    section(
+        out,
+        "rotating_vectors",
+        "rotate_left and rotate_right - shifting without reallocating",
+        rotating_vectors,
+    )?;
+
+    section(
+        out,
+        "split_at_mut_borrows",
+        "split_at_mut - two mutable borrows of one vector",
+        split_at_mut_borrows,
+    )?;
+
+    section(
+        out,
+        "chunks_and_windows",
+        "chunks and windows - fixed-size and sliding views",
+        chunks_and_windows,
+    )?;
+
+    section(
+        out,
+        "drain_and_splice",
+        "drain and splice - removing and replacing a range",
+        drain_and_splice,
+    )?;
+
+    section(
+        out,
+        "stable_and_unstable_sorts",
+        "sort vs sort_unstable - what stability buys",
+        stable_and_unstable_sorts,
+    )?;
+
+    section(
+        out,
+        "sort_by_cached_key_example",
+        "sort_by_cached_key - computing an expensive key once per element",
+        sort_by_cached_key_example,
+    )?;
+
+    section(
+        out,
+        "binary_search_by_example",
+        "binary_search_by - O(log n) lookups and insertion points",
+        binary_search_by_example,
+    )?;
+
+    section(
+        out,
+        "partition_point_example",
+        "partition_point - where a sorted vector's predicate flips",
+        partition_point_example,
+    )?;
+
+    section(
+        out,
+        "select_nth_unstable_example",
+        "select_nth_unstable - medians and top-k without a full sort",
+        select_nth_unstable_example,
+    )?;
+
+    Ok(())
+}
+
 /// Demonstrates basic Vec creation patterns
-pub fn basic_vec_operations() {
+#[allow(clippy::vec_init_then_push)] // Method 2 pushes one by one on purpose
+pub fn basic_vec_operations(out: &mut dyn Write) -> io::Result<()> {
     // Method 1: Using the vec! macro (most common)
     let numbers: Vec<i8> = vec![1, 2, 3, 4, 5];
-    println!("Created with macro: {:?}", numbers);
+    writeln!(out, "Created with macro: {:?}", numbers)?;
 
     // Method 2: Using Vec::new() and push
     let mut fruits: Vec<&str> = Vec::new();
     fruits.push("apple");
     fruits.push("banana");
     fruits.push("cherry");
-    println!("Created with new(): {:?}", fruits);
+    writeln!(out, "Created with new(): {:?}", fruits)?;
 
     // Method 3: With pre-allocated capacity (important for performance!)
     // This avoids reallocations when you know the approximate size
     let mut with_capacity: Vec<i8> = Vec::with_capacity(100);
-    println!("Capacity before pushes: {}", with_capacity.capacity());
+    writeln!(out, "Capacity before pushes: {}", with_capacity.capacity())?;
 
     for i in 0..50 {
         with_capacity.push(i);
     }
     // Still has capacity 100, no reallocation occurred
-    println!("Capacity after 50 pushes: {}", with_capacity.capacity());
+    writeln!(
+        out,
+        "Capacity after 50 pushes: {}",
+        with_capacity.capacity()
+    )?;
 
     // Method 4: From an iterator
     let squares: Vec<i8> = (1..=5).map(|x| x * x).collect();
-    println!("Squares from iterator: {:?}", squares);
+    writeln!(out, "Squares from iterator: {:?}", squares)?;
+
+    Ok(())
 }
 
 /// Demonstrates accessing elements safely
-pub fn accessing_elements() {
+pub fn accessing_elements(out: &mut dyn Write) -> io::Result<()> {
     let colors: Vec<&str> = vec!["red", "green", "blue"];
 
     // Safe access with get() - returns Option<&T>
     // This is the recommended approach when the index might be out of bounds
     match colors.get(1) {
-        Some(color) => println!("Color at index 1: {}", color),
-        None => println!("No color at that index"),
+        Some(color) => writeln!(out, "Color at index 1: {}", color)?,
+        None => writeln!(out, "No color at that index")?,
     }
 
     // Direct indexing - panics if out of bounds!
     // Only use this when you're certain the index is valid
     let first: &str = colors[0];
-    println!("First color (direct access): {}", first);
+    writeln!(out, "First color (direct access): {}", first)?;
 
     // Safe access to first and last elements
     if let Some(first) = colors.first() {
-        println!("First: {}", first);
+        writeln!(out, "First: {}", first)?;
     }
     if let Some(last) = colors.last() {
-        println!("Last: {}", last);
+        writeln!(out, "Last: {}", last)?;
     }
+
+    Ok(())
 }
 
 /// Demonstrates modifying vectors
-pub fn modifying_vectors() {
+pub fn modifying_vectors(out: &mut dyn Write) -> io::Result<()> {
     let mut nums: Vec<i8> = vec![1, 2, 3];
 
     // Adding elements
     nums.push(4); // Add to end - O(1) amortized
     nums.insert(0, 0); // Insert at index - O(n) because elements shift
-    println!("After push and insert: {:?}", nums);
+    writeln!(out, "After push and insert: {:?}", nums)?;
 
     // Removing elements
     let last: Option<i8> = nums.pop(); // Remove from end - O(1)
-    println!("Popped: {:?}", last);
+    writeln!(out, "Popped: {:?}", last)?;
 
     let removed: i8 = nums.remove(1); // Remove at index - O(n) because elements shift
-    println!("Removed at index 1: {}", removed);
-    println!("After removals: {:?}", nums);
+    writeln!(out, "Removed at index 1: {}", removed)?;
+    writeln!(out, "After removals: {:?}", nums)?;
 
     // Extending with another collection
     let more_nums: Vec<i8> = vec![10, 20, 30];
     nums.extend(more_nums);
-    println!("After extend: {:?}", nums);
+    writeln!(out, "After extend: {:?}", nums)?;
 
     // Retain only elements matching a condition
     nums.retain(|&x| x < 15);
-    println!("After retain (x < 15): {:?}", nums);
+    writeln!(out, "After retain (x < 15): {:?}", nums)?;
+
+    Ok(())
 }
 
 /// Demonstrates slicing - borrowing parts of a vector
-pub fn slicing_vectors() {
+pub fn slicing_vectors(out: &mut dyn Write) -> io::Result<()> {
     let numbers: Vec<i8> = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
 
     // Slices borrow a portion of the vector
     let slice: &[i8] = &numbers[2..5]; // Elements at indices 2, 3, 4
-    println!("Slice [2..5]: {:?}", slice);
+    writeln!(out, "Slice [2..5]: {:?}", slice)?;
 
     let from_start: &[i8] = &numbers[..3]; // First 3 elements
-    println!("Slice [..3]: {:?}", from_start);
+    writeln!(out, "Slice [..3]: {:?}", from_start)?;
 
     let to_end: &[i8] = &numbers[7..]; // From index 7 to end
-    println!("Slice [7..]: {:?}", to_end);
+    writeln!(out, "Slice [7..]: {:?}", to_end)?;
 
     // You can iterate over slices
     for (i, num) in numbers[3..6].iter().enumerate() {
-        println!("  Index {}: {}", i, num);
+        writeln!(out, "  Index {}: {}", i, num)?;
     }
+
+    Ok(())
 }
 
 /// Demonstrates Vec's capacity behavior
-pub fn capacity_demonstration() {
+pub fn capacity_demonstration(out: &mut dyn Write) -> io::Result<()> {
     let mut v: Vec<i32> = Vec::new();
 
-    println!("Empty vec - len: {}, capacity: {}", v.len(), v.capacity());
+    writeln!(
+        out,
+        "Empty vec - len: {}, capacity: {}",
+        v.len(),
+        v.capacity()
+    )?;
 
-    // Watch how capacity grows as we add elements
+    // Watch how capacity grows as we add elements. With TrackingAllocator
+    // installed, as `cargo run` does, the allocator calls each push makes
+    // are counted rather than guessed from the capacity
+    let mut calls: Option<u64> = alloc::counts().map(|_| 0);
+    let mut called_at: Vec<i32> = Vec::new();
     for i in 0..20 {
+        let before: Option<AllocCounts> = alloc::counts();
         v.push(i);
-        println!(
+        let made: Option<u64> = before
+            .zip(alloc::counts())
+            .map(|(before, after)| after.since(before).allocations);
+        if made.is_some_and(|made| made > 0) {
+            called_at.push(i);
+        }
+        calls = calls.zip(made).map(|(calls, made)| calls + made);
+        writeln!(
+            out,
             "After push {} - len: {}, capacity: {}",
             i,
             v.len(),
             v.capacity()
-        );
+        )?;
     }
 
     // Capacity grows roughly by doubling (implementation detail, may vary)
     // This amortizes the cost of reallocation over many operations
+    match calls {
+        Some(calls) => writeln!(
+            out,
+            "Allocator calls during the 20 pushes: {} (at pushes {:?})",
+            calls, called_at
+        )?,
+        None => writeln!(out, "Allocator calls during the 20 pushes: untracked")?,
+    }
 
-    // Shrink to fit current contents
+    // Shrink to fit current contents; a smaller block means one more call
+    let before: Option<AllocCounts> = alloc::counts();
     v.shrink_to_fit();
-    println!(
-        "After shrink_to_fit - len: {}, capacity: {}",
+    let shrink_calls: Option<u64> = before
+        .zip(alloc::counts())
+        .map(|(before, after)| after.since(before).allocations);
+    writeln!(
+        out,
+        "After shrink_to_fit - len: {}, capacity: {}, allocator calls: {}",
         v.len(),
-        v.capacity()
-    );
+        v.capacity(),
+        shrink_calls.map_or_else(|| "untracked".to_string(), |calls| calls.to_string())
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates swap_remove - O(1) removal when order doesn't matter
+pub fn swap_remove_unordered(out: &mut dyn Write) -> io::Result<()> {
+    // remove(i) shifts every later element left to keep the order: O(n).
+    // swap_remove(i) moves the last element into the hole instead: O(1)
+    let mut ordered: Vec<&str> = vec!["a", "b", "c", "d", "e"];
+    let removed: &str = ordered.remove(1);
+    writeln!(out, "remove(1) -> {:?}, left {:?}", removed, ordered)?;
+
+    let mut unordered: Vec<&str> = vec!["a", "b", "c", "d", "e"];
+    let removed: &str = unordered.swap_remove(1);
+    writeln!(out, "swap_remove(1) -> {:?}, left {:?}", removed, unordered)?;
+
+    // Practical: a game removes dead entities every frame. Their order in
+    // the list means nothing, so each removal can be O(1)
+    let mut entities: Vec<(&str, i32)> = vec![("orc", 0), ("elf", 12), ("imp", 0), ("ent", 30)];
+    let mut i: usize = 0;
+    while i < entities.len() {
+        if entities[i].1 <= 0 {
+            // Don't advance: index i now holds what used to be the last entity
+            entities.swap_remove(i);
+        } else {
+            i += 1;
+        }
+    }
+    writeln!(out, "Entities still alive: {:?}", entities)?;
+
+    Ok(())
+}
+
+/// Demonstrates retain_mut - update and filter in one pass
+pub fn retain_mut_in_place(out: &mut dyn Write) -> io::Result<()> {
+    // retain's closure gets &T, so it can only decide. retain_mut's gets
+    // &mut T, so it can change the elements it keeps in the same pass
+    let mut timers: Vec<(&str, u32)> = vec![("toast", 1), ("tea", 3), ("eggs", 2), ("rice", 5)];
+    writeln!(out, "Timers: {:?}", timers)?;
+
+    // Practical: each tick counts every timer down and drops the ones that
+    // fire. Both happen in one O(n) pass, with no second vector
+    for tick in 1..=2 {
+        let mut fired: Vec<&str> = Vec::new();
+        timers.retain_mut(|(name, remaining)| {
+            *remaining -= 1;
+            if *remaining == 0 {
+                fired.push(name);
+                false
+            } else {
+                true
+            }
+        });
+        writeln!(out, "Tick {}: fired {:?}, left {:?}", tick, fired, timers)?;
+    }
+
+    Ok(())
+}
+
+/// Demonstrates dedup_by and dedup_by_key - collapsing runs of neighbours
+pub fn dedup_by_runs(out: &mut dyn Write) -> io::Result<()> {
+    // dedup only removes *consecutive* duplicates, in one O(n) pass
+    let mut readings: Vec<i32> = vec![1, 1, 2, 2, 2, 1, 3, 3];
+    readings.dedup();
+    writeln!(out, "dedup(): {:?} (the second 1 stays)", readings)?;
+
+    // Sort first to remove every duplicate
+    let mut all: Vec<i32> = vec![1, 1, 2, 2, 2, 1, 3, 3];
+    all.sort_unstable();
+    all.dedup();
+    writeln!(out, "sort_unstable() then dedup(): {:?}", all)?;
+
+    // dedup_by_key compares a derived key: here, case-insensitive
+    let mut words: Vec<&str> = vec!["Rust", "rust", "RUST", "go", "Go"];
+    words.dedup_by_key(|word| word.to_lowercase());
+    writeln!(out, "dedup_by_key(lowercase): {:?}", words)?;
+
+    // dedup_by gets (later, earlier) and may merge the later one into the
+    // earlier before it is dropped. Practical: collapse a log's repeated
+    // lines into one line with a count
+    let mut log: Vec<(&str, u32)> = ["connect", "retry", "retry", "retry", "ok", "retry"]
+        .iter()
+        .map(|&line| (line, 1))
+        .collect();
+    log.dedup_by(|later, earlier| {
+        if later.0 == earlier.0 {
+            earlier.1 += later.1;
+            true
+        } else {
+            false
+        }
+    });
+    writeln!(out, "Collapsed log: {:?}", log)?;
+
+    Ok(())
+}
+
+/// Demonstrates rotate_left and rotate_right - shifting without reallocating
+pub fn rotating_vectors(out: &mut dyn Write) -> io::Result<()> {
+    // Rotation moves every element k places, wrapping around the ends. It
+    // runs in O(n) in place: no new buffer, no element cloned
+    let mut v: Vec<i8> = vec![1, 2, 3, 4, 5, 6];
+    v.rotate_left(2);
+    writeln!(out, "rotate_left(2):  {:?}", v)?;
+    v.rotate_right(2);
+    writeln!(out, "rotate_right(2): {:?}", v)?;
+
+    // Practical: whose turn is it? Rotating a player list by one after each
+    // round gives every player the first move in turn
+    let mut players: Vec<&str> = vec!["ann", "bob", "cat"];
+    for round in 1..=3 {
+        writeln!(
+            out,
+            "Round {}: {} starts, order {:?}",
+            round, players[0], players
+        )?;
+        players.rotate_left(1);
+    }
+
+    // Rotating a sub-slice moves one element to a new position, shifting
+    // the ones in between: the hotfix jumps the queue but not the running task
+    let mut tasks: Vec<&str> = vec!["running", "lint", "build", "test", "hotfix"];
+    tasks[1..].rotate_right(1);
+    writeln!(out, "Hotfix next: {:?}", tasks)?;
+
+    Ok(())
+}
+
+/// Demonstrates split_at_mut - two mutable borrows of one vector
+pub fn split_at_mut_borrows(out: &mut dyn Write) -> io::Result<()> {
+    let mut balances: Vec<i32> = vec![100, 50, 75, 20];
+
+    // `let a = &mut balances[0]; let b = &mut balances[3];` doesn't compile:
+    // the borrow checker can't tell the two indices apart. split_at_mut
+    // returns two non-overlapping halves, each borrowable on its own
+    let (left, right) = balances.split_at_mut(2);
+    writeln!(out, "split_at_mut(2): {:?} and {:?}", left, right)?;
+
+    // Practical: move 30 from account 0 to account 3 in place
+    let (from, to): (&mut i32, &mut i32) = (&mut left[0], &mut right[1]);
+    *from -= 30;
+    *to += 30;
+    writeln!(out, "After transferring 30 from 0 to 3: {:?}", balances)?;
+
+    // For two elements only, get_disjoint_mut checks the indices at run time
+    if let Ok([a, b]) = balances.get_disjoint_mut([1, 2]) {
+        std::mem::swap(a, b);
+    }
+    writeln!(
+        out,
+        "After get_disjoint_mut([1, 2]) and swap: {:?}",
+        balances
+    )?;
+    // swap(i, j) does just that last step directly
+    balances.swap(1, 2);
+    writeln!(out, "After swap(1, 2): {:?}", balances)?;
+
+    Ok(())
+}
+
+/// Demonstrates chunks and windows - fixed-size and sliding views
+pub fn chunks_and_windows(out: &mut dyn Write) -> io::Result<()> {
+    let data: Vec<u8> = vec![1, 2, 3, 4, 5, 6, 7];
+
+    // chunks(n): non-overlapping pieces, the last one may be shorter
+    let chunks: Vec<&[u8]> = data.chunks(3).collect();
+    writeln!(out, "chunks(3): {:?}", chunks)?;
+    // chunks_exact(n) leaves the short tail in remainder()
+    let exact = data.chunks_exact(3);
+    writeln!(out, "chunks_exact(3) remainder: {:?}", exact.remainder())?;
+
+    // windows(n): every run of n neighbours, each overlapping the last
+    let windows: Vec<&[u8]> = data.windows(3).collect();
+    writeln!(out, "windows(3): {:?}", windows)?;
+
+    // Practical: send a payload in packets of at most 3 bytes
+    for (i, packet) in data.chunks(3).enumerate() {
+        writeln!(out, "  packet {}: {:?}", i, packet)?;
+    }
+
+    // Practical: a 3-point moving average and the day-to-day change. Both
+    // are views into the same Vec, nothing is copied
+    let prices: Vec<f64> = vec![10.0, 11.0, 13.0, 12.0, 15.0];
+    let averages: Vec<f64> = prices
+        .windows(3)
+        .map(|w| w.iter().sum::<f64>() / 3.0)
+        .collect();
+    let changes: Vec<f64> = prices.windows(2).map(|w| w[1] - w[0]).collect();
+    writeln!(out, "Moving average: {:.2?}", averages)?;
+    writeln!(out, "Daily change: {:?}", changes)?;
+
+    // chunks_mut hands out mutable pieces: normalise each row of a 2x3 matrix
+    let mut matrix: Vec<f64> = vec![1.0, 1.0, 2.0, 3.0, 0.0, 1.0];
+    for row in matrix.chunks_mut(3) {
+        let sum: f64 = row.iter().sum();
+        row.iter_mut().for_each(|x| *x /= sum);
+    }
+    writeln!(out, "Rows normalised with chunks_mut(3): {:.2?}", matrix)?;
+
+    Ok(())
+}
+
+/// Demonstrates drain and splice - removing and replacing a range
+pub fn drain_and_splice(out: &mut dyn Write) -> io::Result<()> {
+    // drain(range) removes a range and yields it by value. The tail shifts
+    // once when the iterator drops, not once per element
+    let mut queue: Vec<u32> = (1..=8).collect();
+    let batch: Vec<u32> = queue.drain(..3).collect();
+    writeln!(out, "drain(..3) -> {:?}, left {:?}", batch, queue)?;
+
+    // drain(..) empties the Vec but keeps its buffer for reuse
+    let capacity: usize = queue.capacity();
+    let total: u32 = queue.drain(..).sum();
+    writeln!(
+        out,
+        "drain(..).sum() = {}; len {}, capacity {} (was {})",
+        total,
+        queue.len(),
+        queue.capacity(),
+        capacity
+    )?;
+
+    // Practical: a job queue processed in batches of 3
+    let mut jobs: Vec<&str> = vec!["a", "b", "c", "d", "e", "f", "g"];
+    while !jobs.is_empty() {
+        let take: usize = jobs.len().min(3);
+        let batch: Vec<&str> = jobs.drain(..take).collect();
+        writeln!(out, "  processing batch {:?}", batch)?;
+    }
+
+    // splice(range, items) replaces a range with any number of new items,
+    // shifting the tail once, and yields what it removed
+    let mut sentence: Vec<&str> = vec!["the", "quick", "brown", "fox"];
+    let removed: Vec<&str> = sentence.splice(1..3, ["slow", "and", "steady"]).collect();
+    writeln!(
+        out,
+        "splice(1..3, [slow, and, steady]) -> removed {:?}",
+        removed
+    )?;
+    writeln!(out, "Now: {:?}", sentence)?;
+
+    // An empty range inserts a whole sequence at once; one insert per item
+    // would shift the tail each time
+    sentence.splice(0..0, ["look:"]);
+    writeln!(out, "splice(0..0, [look:]): {:?}", sentence)?;
+
+    Ok(())
+}
+
+/// Demonstrates sort vs sort_unstable - what stability buys
+pub fn stable_and_unstable_sorts(out: &mut dyn Write) -> io::Result<()> {
+    // A stable sort keeps equal elements in the order they arrived. The
+    // list is already in name order, so sorting by team keeps each team's
+    // names alphabetical: a two-key sort from two passes
+    let mut people: Vec<(&str, &str)> = vec![
+        ("ana", "ops"),
+        ("ben", "dev"),
+        ("cy", "ops"),
+        ("dee", "dev"),
+        ("eve", "ops"),
+    ];
+    people.sort_by_key(|&(_, team)| team);
+    writeln!(out, "sort_by_key(team), stable:  {:?}", people)?;
+
+    // sort_unstable may reorder equal elements. In return it sorts in place
+    // with no extra buffer, and is usually faster. For plain numbers, where
+    // equal elements are indistinguishable, it is the better default
+    let mut numbers: Vec<i32> = vec![5, 3, 9, 1, 5, 7, 3];
+    numbers.sort_unstable();
+    writeln!(out, "sort_unstable() on numbers: {:?}", numbers)?;
+
+    // Descending order: sort by Reverse, or compare b to a
+    numbers.sort_unstable_by(|a, b| b.cmp(a));
+    writeln!(out, "sort_unstable_by(b.cmp(a)): {:?}", numbers)?;
+
+    // f64 isn't Ord (NaN), so it needs sort_by with total_cmp
+    let mut readings: Vec<f64> = vec![2.5, -1.0, 0.0, 10.25];
+    readings.sort_by(f64::total_cmp);
+    writeln!(out, "sort_by(f64::total_cmp): {:?}", readings)?;
+
+    // Both sorts detect runs, so already-sorted input takes O(n)
+    writeln!(out, "is_sorted() after sorting: {}", readings.is_sorted())?;
+
+    Ok(())
+}
+
+/// Demonstrates sort_by_cached_key - computing an expensive key once per element
+pub fn sort_by_cached_key_example(out: &mut dyn Write) -> io::Result<()> {
+    let files: Vec<&str> = vec![
+        "README.md",
+        "main.rs",
+        "Cargo.toml",
+        "lib.rs",
+        "build.rs",
+        "LICENSE",
+    ];
+
+    // sort_by_key calls the key function on every comparison: O(n log n)
+    // calls. That is fine for a field access, wasteful for a key that
+    // allocates, like a lowercased copy
+    let calls: Cell<usize> = Cell::new(0);
+    let mut by_key: Vec<&str> = files.clone();
+    by_key.sort_by_key(|name| {
+        calls.set(calls.get() + 1);
+        name.to_lowercase()
+    });
+    writeln!(out, "sort_by_key(lowercase):        {:?}", by_key)?;
+    writeln!(
+        out,
+        "  key computed {} times for {} files",
+        calls.get(),
+        files.len()
+    )?;
+
+    // sort_by_cached_key computes each key once, sorts (key, index) pairs,
+    // then moves the elements into place
+    calls.set(0);
+    let mut cached: Vec<&str> = files.clone();
+    cached.sort_by_cached_key(|name| {
+        calls.set(calls.get() + 1);
+        name.to_lowercase()
+    });
+    writeln!(out, "sort_by_cached_key(lowercase): {:?}", cached)?;
+    writeln!(
+        out,
+        "  key computed {} times for {} files",
+        calls.get(),
+        files.len()
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates binary_search_by - O(log n) lookups and insertion points
+pub fn binary_search_by_example(out: &mut dyn Write) -> io::Result<()> {
+    // A price list sorted by product id
+    let mut prices: Vec<(u32, &str, u32)> = vec![
+        (101, "pen", 150),
+        (205, "ink", 420),
+        (310, "pad", 275),
+        (480, "clip", 30),
+    ];
+
+    // binary_search_by compares each probed element to the target. Ok(i)
+    // means found at i
+    let found: Result<usize, usize> = prices.binary_search_by(|&(id, _, _)| id.cmp(&310));
+    writeln!(out, "binary_search_by(id == 310) -> {:?}", found)?;
+
+    // Err(i) means not found, and i is where it would go to keep the order
+    let missing: Result<usize, usize> = prices.binary_search_by_key(&250, |&(id, _, _)| id);
+    writeln!(out, "binary_search_by_key(250)   -> {:?}", missing)?;
+
+    // Practical: insert keeping the list sorted, or update in place if the
+    // id is already there. O(log n) to find the spot, O(n) to shift
+    for (id, name, price) in [(250, "tape", 90), (101, "pen", 160)] {
+        match prices.binary_search_by_key(&id, |&(id, _, _)| id) {
+            Ok(i) => prices[i].2 = price,
+            Err(i) => prices.insert(i, (id, name, price)),
+        }
+    }
+    writeln!(out, "After upserting 250 and 101: {:?}", prices)?;
+
+    // With duplicates, Ok may point at any of the equal elements
+    let repeated: Vec<i32> = vec![1, 2, 2, 2, 3];
+    writeln!(
+        out,
+        "binary_search(2) in {:?} -> {:?} (any of 1..=3)",
+        repeated,
+        repeated.binary_search(&2)
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates partition_point - where a sorted vector's predicate flips
+pub fn partition_point_example(out: &mut dyn Write) -> io::Result<()> {
+    // partition_point(pred) assumes every element matching pred comes first
+    // and returns the index of the first one that doesn't, in O(log n)
+    let scores: Vec<u32> = vec![12, 35, 48, 50, 50, 67, 81, 90];
+    let below_50: usize = scores.partition_point(|&s| s < 50);
+    writeln!(out, "Scores: {:?}", scores)?;
+    writeln!(
+        out,
+        "partition_point(s < 50) = {} (first score >= 50)",
+        below_50
+    )?;
+
+    // Two calls give a range: everything in [50, 80)
+    let end: usize = scores.partition_point(|&s| s < 80);
+    writeln!(
+        out,
+        "Scores in [50, 80): {:?}, {} of them",
+        &scores[below_50..end],
+        end - below_50
+    )?;
+
+    // Unlike binary_search, it has one answer even with duplicates: here
+    // the first and one-past-last 50
+    let first: usize = scores.partition_point(|&s| s < 50);
+    let last: usize = scores.partition_point(|&s| s <= 50);
+    writeln!(out, "Score 50 spans indices {}..{}", first, last)?;
+
+    // Practical: timestamps in a sorted log, and the entries in a window
+    let log: Vec<(u32, &str)> = vec![
+        (100, "boot"),
+        (160, "login"),
+        (220, "error"),
+        (230, "retry"),
+        (400, "logout"),
+    ];
+    let from: usize = log.partition_point(|&(t, _)| t < 150);
+    let to: usize = log.partition_point(|&(t, _)| t < 250);
+    writeln!(out, "Log entries with 150 <= t < 250: {:?}", &log[from..to])?;
+
+    Ok(())
+}
+
+/// Demonstrates select_nth_unstable - medians and top-k without a full sort
+pub fn select_nth_unstable_example(out: &mut dyn Write) -> io::Result<()> {
+    // select_nth_unstable(k) puts the element that belongs at index k there,
+    // everything smaller before it and everything larger after it, in O(n)
+    // on average. The two sides are left unsorted
+    let mut latencies: Vec<u32> = vec![42, 7, 93, 15, 61, 8, 77, 30, 54];
+    let middle: usize = latencies.len() / 2;
+    let (lower, median, upper) = latencies.select_nth_unstable(middle);
+    writeln!(out, "Median latency: {}", median)?;
+    let mut lower: Vec<u32> = lower.to_vec();
+    let mut upper: Vec<u32> = upper.to_vec();
+    lower.sort_unstable();
+    upper.sort_unstable();
+    writeln!(out, "  smaller (sorted here for display): {:?}", lower)?;
+    writeln!(out, "  larger (sorted here for display):  {:?}", upper)?;
+
+    // Practical: the 3 slowest requests. Select the boundary, then sort only
+    // the top 3: O(n + k log k) instead of O(n log n)
+    let k: usize = 3;
+    let boundary: usize = latencies.len() - k;
+    latencies.select_nth_unstable(boundary);
+    let slowest: &mut [u32] = &mut latencies[boundary..];
+    slowest.sort_unstable_by(|a, b| b.cmp(a));
+    writeln!(out, "3 slowest: {:?}", slowest)?;
+
+    // The 90th percentile the same way
+    let mut samples: Vec<u32> = (1..=100).map(|i| (i * 37) % 101).collect();
+    let index: usize = samples.len() * 9 / 10;
+    let (_, p90, _) = samples.select_nth_unstable(index);
+    writeln!(out, "p90 of 100 samples: {}", p90)?;
+
+    Ok(())
 }
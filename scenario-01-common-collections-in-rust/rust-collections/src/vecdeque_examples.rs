@@ -1,7 +1,77 @@
+use crate::deque_visual::{RingModel, observed_head};
+use demo_framework::section;
 use std::collections::VecDeque;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "basic_vecdeque_operations",
+        "Basic VecDeque operations",
+        basic_vecdeque_operations,
+    )?;
+
+    section(
+        out,
+        "fifo_queue_example",
+        "Using VecDeque as a queue (FIFO)",
+        fifo_queue_example,
+    )?;
+
+    section(
+        out,
+        "sliding_window_example",
+        "Using VecDeque for sliding window operations",
+        sliding_window_example,
+    )?;
+
+    section(
+        out,
+        "ring_buffer_demonstration",
+        "VecDeque's ring buffer behavior",
+        ring_buffer_demonstration,
+    )?;
+
+    section(
+        out,
+        "contiguity_and_slices",
+        "as_slices and make_contiguous: one slice out of a wrapped buffer",
+        contiguity_and_slices,
+    )?;
+
+    section(
+        out,
+        "rotating_deques",
+        "rotate_left and rotate_right without reallocating",
+        rotating_deques,
+    )?;
+
+    section(
+        out,
+        "sorted_deque_search",
+        "Sorting through make_contiguous, then binary_search and partition_point",
+        sorted_deque_search,
+    )?;
+
+    section(
+        out,
+        "vec_conversions",
+        "Converting between Vec and VecDeque in place",
+        vec_conversions,
+    )?;
+
+    section(
+        out,
+        "practical_bounded_history",
+        "Practical demo: a bounded command history",
+        practical_bounded_history,
+    )?;
+
+    Ok(())
+}
 
 /// Demonstrates basic VecDeque operations
-pub fn basic_vecdeque_operations() {
+pub fn basic_vecdeque_operations(out: &mut dyn Write) -> io::Result<()> {
     // Create a new VecDeque
     let mut deque: VecDeque<i8> = VecDeque::new();
 
@@ -9,28 +79,30 @@ pub fn basic_vecdeque_operations() {
     deque.push_back(1);
     deque.push_back(2);
     deque.push_back(3);
-    println!("After push_back 1, 2, 3: {:?}", deque);
+    writeln!(out, "After push_back 1, 2, 3: {:?}", deque)?;
 
     // Add elements to the front - this is O(1)!
     // With Vec, this would be O(n) because all elements shift
     deque.push_front(0);
     deque.push_front(-1);
-    println!("After push_front 0, -1: {:?}", deque);
+    writeln!(out, "After push_front 0, -1: {:?}", deque)?;
 
     // Remove from front - O(1)
     let front: Option<i8> = deque.pop_front();
-    println!("Popped front: {:?}", front);
+    writeln!(out, "Popped front: {:?}", front)?;
 
     // Remove from back - O(1)
     let back: Option<i8> = deque.pop_back();
-    println!("Popped back: {:?}", back);
+    writeln!(out, "Popped back: {:?}", back)?;
+
+    writeln!(out, "Final state: {:?}", deque)?;
 
-    println!("Final state: {:?}", deque);
+    Ok(())
 }
 
 /// Demonstrates using VecDeque as a queue (FIFO)
-pub fn fifo_queue_example() {
-    println!("\n--- FIFO Queue Example ---");
+pub fn fifo_queue_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- FIFO Queue Example ---")?;
 
     let mut queue: VecDeque<String> = VecDeque::new();
 
@@ -39,17 +111,19 @@ pub fn fifo_queue_example() {
     queue.push_back("Document 2".to_string());
     queue.push_back("Document 3".to_string());
 
-    println!("Print queue: {:?}", queue);
+    writeln!(out, "Print queue: {:?}", queue)?;
 
     // Process items in order (first in, first out)
     while let Some(doc) = queue.pop_front() {
-        println!("Printing: {}", doc);
+        writeln!(out, "Printing: {}", doc)?;
     }
+
+    Ok(())
 }
 
 /// Demonstrates using VecDeque for sliding window operations
-pub fn sliding_window_example() {
-    println!("\n--- Sliding Window Example ---");
+pub fn sliding_window_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Sliding Window Example ---")?;
 
     // Calculate moving average of last 3 values
     let data: Vec<i8> = vec![1, 3, 5, 7, 9, 11, 13, 15];
@@ -69,33 +143,300 @@ pub fn sliding_window_example() {
         }
     }
 
-    println!(
+    writeln!(
+        out,
         "Moving averages (window size {}): {:?}",
         window_size, averages
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates VecDeque's ring buffer behavior
-pub fn ring_buffer_demonstration() {
-    println!("\n--- Ring Buffer Demonstration ---");
+pub fn ring_buffer_demonstration(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Ring Buffer Demonstration ---")?;
 
     // VecDeque uses a circular buffer internally
     // This means push_front doesn't actually move elements
 
     let mut deque: VecDeque<i8> = VecDeque::with_capacity(5);
+    // std doesn't expose the head index, so a model replays every operation
+    // with the same index math and is checked against the real deque
+    let mut model: RingModel<i8> = RingModel::with_capacity(5);
 
     // Fill the deque
     for i in 1..=5 {
         deque.push_back(i);
+        model.push_back(i);
     }
-    println!("Initial: {:?}", deque);
+    writeln!(out, "Initial: {:?}", deque)?;
+    writeln!(out, "{}\n", model.render())?;
 
     // Pop from front and push to back - elements "rotate"
     // but no actual memory movement occurs
     for _ in 0..3 {
         if let Some(front) = deque.pop_front() {
+            model.pop_front();
+            writeln!(out, "pop_front() -> {}", front)?;
+            writeln!(out, "{}\n", model.render())?;
+
             deque.push_back(front + 10);
+            model.push_back(front + 10);
+            writeln!(out, "push_back({})", front + 10)?;
+            writeln!(out, "{}\n", model.render())?;
         }
     }
-    println!("After rotation: {:?}", deque);
+    writeln!(out, "After rotation: {:?}", deque)?;
+    let (front, back) = deque.as_slices();
+    writeln!(
+        out,
+        "as_slices(): {:?} then {:?} - the wrap point is where one slice ends",
+        front, back
+    )?;
+
+    // A push into a full buffer doubles it, then moves the shorter wrapped
+    // part so the elements are in order again
+    deque.push_back(99);
+    model.push_back(99);
+    writeln!(
+        out,
+        "\npush_back(99) into a full buffer: capacity 5 -> {}",
+        deque.capacity()
+    )?;
+    writeln!(out, "{}\n", model.render())?;
+
+    // push_front steps head back one slot, wrapping below slot 0 if needed
+    deque.push_front(0);
+    model.push_front(0);
+    writeln!(out, "push_front(0)")?;
+    writeln!(out, "{}\n", model.render())?;
+
+    writeln!(
+        out,
+        "Model matches the VecDeque's contents, capacity and slices: {}",
+        model.matches(&deque)
+    )?;
+
+    Ok(())
+}
+
+/// Builds a wrapped deque: capacity 8, with the front three elements in the
+/// last slots and the rest at the start of the buffer.
+fn wrapped_deque() -> VecDeque<i32> {
+    let mut deque: VecDeque<i32> = VecDeque::with_capacity(8);
+    deque.extend([4, 5, 6, 7, 8]);
+    for value in [3, 2, 1] {
+        deque.push_front(value);
+    }
+    deque
+}
+
+/// Demonstrates the two slices of a wrapped deque and how to get one.
+pub fn contiguity_and_slices(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Contiguity and Slices ---")?;
+
+    let mut deque: VecDeque<i32> = wrapped_deque();
+    writeln!(out, "Deque: {:?}", deque)?;
+
+    // A wrapped deque is two runs of memory, so there is no single &[T]
+    let (front, back) = deque.as_slices();
+    writeln!(out, "as_slices(): {:?} then {:?}", front, back)?;
+    writeln!(
+        out,
+        "Head slot (read off the slice pointers): {:?}",
+        observed_head(&deque)
+    )?;
+
+    // Slice APIs on both halves in turn work without moving anything
+    let (front, back) = deque.as_slices();
+    let total: i32 = front.iter().chain(back).sum();
+    writeln!(out, "Sum over both slices: {}", total)?;
+
+    // make_contiguous moves the elements so they start at one slot and
+    // returns them as one mutable slice. O(n) once; free while unwrapped
+    let contiguous: &mut [i32] = deque.make_contiguous();
+    writeln!(out, "make_contiguous(): {:?}", contiguous)?;
+    contiguous.reverse();
+    writeln!(out, "After reversing the slice: {:?}", deque)?;
+    let (front, back) = deque.as_slices();
+    writeln!(
+        out,
+        "as_slices() now: {} and {} elements",
+        front.len(),
+        back.len()
+    )?;
+    writeln!(
+        out,
+        "Head slot now: {:?} (not wrapped)",
+        observed_head(&deque)
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates rotating a deque's elements in place.
+pub fn rotating_deques(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Rotating Deques ---")?;
+
+    let mut players: VecDeque<&str> = VecDeque::from(["ann", "bob", "cy", "dee", "eve"]);
+    writeln!(out, "Turn order: {:?}", players)?;
+
+    // rotate_left(k) moves the first k elements to the back. It copies
+    // min(k, len - k) elements, so rotating by 1 is O(1) like a
+    // pop_front + push_back
+    players.rotate_left(1);
+    writeln!(out, "rotate_left(1):  {:?}", players)?;
+    players.rotate_left(3);
+    writeln!(out, "rotate_left(3):  {:?}", players)?;
+
+    // rotate_right(k) is the inverse: the last k elements move to the front
+    players.rotate_right(4);
+    writeln!(out, "rotate_right(4): {:?} (back to the start)", players)?;
+
+    // Round-robin: whoever is at the front takes a turn and goes to the back
+    let mut turns: Vec<&str> = Vec::new();
+    for _ in 0..7 {
+        turns.push(players[0]);
+        players.rotate_left(1);
+    }
+    writeln!(out, "Seven round-robin turns: {:?}", turns)?;
+
+    Ok(())
+}
+
+/// Demonstrates searching a sorted deque.
+pub fn sorted_deque_search(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Sorted Deque Search ---")?;
+
+    // VecDeque has no sort(), but make_contiguous gives a slice that does
+    let mut deque: VecDeque<i32> = VecDeque::with_capacity(8);
+    deque.extend([42, 7, 19]);
+    for value in [3, 88, 25] {
+        deque.push_front(value);
+    }
+    writeln!(
+        out,
+        "Unsorted (wrapped: {}): {:?}",
+        observed_head(&deque).is_some(),
+        deque
+    )?;
+    deque.make_contiguous().sort();
+    writeln!(out, "Sorted via make_contiguous().sort(): {:?}", deque)?;
+
+    // binary_search and partition_point work on the deque directly, wrapped
+    // or not: they search one slice and then the other
+    writeln!(out, "binary_search(&25): {:?}", deque.binary_search(&25))?;
+    writeln!(out, "binary_search(&20): {:?}", deque.binary_search(&20))?;
+    writeln!(
+        out,
+        "partition_point(|&x| x < 20): {}",
+        deque.partition_point(|&x| x < 20)
+    )?;
+
+    // Err(index) is where the value belongs, so inserting there keeps the
+    // deque sorted. insert shifts whichever side is shorter
+    for value in [20, 1, 100] {
+        let index: usize = deque.binary_search(&value).unwrap_or_else(|index| index);
+        deque.insert(index, value);
+    }
+    writeln!(out, "After sorted inserts of 20, 1, 100: {:?}", deque)?;
+
+    Ok(())
+}
+
+/// Demonstrates Vec <-> VecDeque conversions that keep the allocation.
+pub fn vec_conversions(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Vec Conversions ---")?;
+
+    // Vec -> VecDeque is O(1): the Vec's buffer becomes the ring buffer
+    let vec: Vec<i32> = vec![1, 2, 3, 4];
+    let buffer: *const i32 = vec.as_ptr();
+    let mut deque: VecDeque<i32> = VecDeque::from(vec);
+    writeln!(out, "VecDeque::from(vec): {:?}", deque)?;
+    writeln!(
+        out,
+        "  Same buffer: {}",
+        deque.as_slices().0.as_ptr() == buffer
+    )?;
+
+    // Wrap it, then go back. VecDeque -> Vec never reallocates, but a
+    // wrapped deque's elements are moved so they start at slot 0
+    deque.rotate_right(1);
+    deque.push_front(0);
+    let wrapped: bool = observed_head(&deque).is_some();
+    let buffer_start: usize = deque.as_slices().1.as_ptr() as usize;
+    let vec: Vec<i32> = Vec::from(deque);
+    writeln!(
+        out,
+        "Vec::from(deque) of a wrapped deque (wrapped: {}): {:?}",
+        wrapped, vec
+    )?;
+    writeln!(
+        out,
+        "  Same buffer: {}",
+        vec.as_ptr() as usize == buffer_start
+    )?;
+
+    // collect() builds a new buffer instead
+    let copy: VecDeque<i32> = vec.iter().copied().collect();
+    writeln!(out, "collect() copies: {:?}", copy)?;
+
+    Ok(())
+}
+
+/// Practical example: a shell-style command history that keeps the last N
+/// entries.
+///
+/// push_back adds the newest, pop_front drops the oldest once full, and the
+/// buffer never grows past its first allocation.
+pub fn practical_bounded_history(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n--- Practical Example: Bounded Command History ---")?;
+
+    let limit: usize = 5;
+    let mut history: VecDeque<&str> = VecDeque::with_capacity(limit);
+    let capacity: usize = history.capacity();
+    let commands: [&str; 8] = [
+        "cargo build",
+        "cargo test",
+        "git status",
+        "cargo fmt",
+        "git diff",
+        "cargo test",
+        "git add -A",
+        "git commit",
+    ];
+    for command in commands {
+        if history.len() == limit {
+            history.pop_front();
+        }
+        history.push_back(command);
+    }
+    writeln!(out, "Last {} commands, oldest first:", limit)?;
+    for (number, command) in history.iter().enumerate() {
+        writeln!(out, "  {}  {}", number + 1, command)?;
+    }
+    writeln!(
+        out,
+        "Capacity unchanged: {}",
+        history.capacity() == capacity
+    )?;
+
+    // Up-arrow walks back from the newest
+    let recent: Vec<&str> = history.iter().rev().take(2).copied().collect();
+    writeln!(out, "Up-arrow twice: {:?}", recent)?;
+
+    // Reverse search: the newest entry containing "cargo"
+    let found: Option<&str> = history
+        .iter()
+        .rev()
+        .find(|command| command.contains("cargo"))
+        .copied();
+    writeln!(out, "Reverse search for \"cargo\": {:?}", found)?;
+
+    // Saving to disk wants one slice; make_contiguous gives it without
+    // copying into a Vec
+    let saved: String = history.make_contiguous().join("\n");
+    writeln!(out, "Saved file is {} lines", saved.lines().count())?;
+
+    Ok(())
 }
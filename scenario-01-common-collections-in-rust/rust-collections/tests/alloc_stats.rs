@@ -0,0 +1,82 @@
+//! Allocation statistics with the tracking allocator installed for this test
+//! binary: the peak follows the live heap through nested spans, the section
+//! summary reports it, and `capacity_demonstration` prints the allocator
+//! calls its pushes really made.
+//!
+//! The counters are process-wide, so everything runs in a single test.
+
+use collections_demo::vec_examples;
+use demo_framework::alloc::{self, TrackingAllocator};
+use demo_framework::report::{self, SectionSummary};
+use demo_framework::{capture, section};
+use std::hint::black_box;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+const MIB: usize = 1 << 20;
+
+#[test]
+fn peaks_sections_and_reallocations_are_counted() {
+    // A block freed before the peak is read still counts
+    let start: u64 = alloc::live_bytes().expect("the tracking allocator is installed");
+    alloc::reset_peak();
+    drop(black_box(vec![0u8; 2 * MIB]));
+    let inner: u64 = alloc::reset_peak().unwrap();
+    assert!(inner >= start + 2 * MIB as u64);
+
+    // A smaller nested span sees only its own high point, and putting the
+    // outer peak back restores the larger one
+    drop(black_box(vec![0u8; MIB]));
+    let nested: u64 = alloc::peak_live_bytes().unwrap();
+    assert!(nested >= start + MIB as u64 && nested < inner);
+    alloc::raise_peak(inner);
+    assert_eq!(alloc::peak_live_bytes(), Some(inner));
+
+    // Growing in place must not count the old and new blocks together
+    let mut grown: Vec<u8> = Vec::with_capacity(MIB);
+    alloc::reset_peak();
+    let before: u64 = alloc::live_bytes().unwrap();
+    grown.reserve_exact(2 * MIB);
+    assert!(alloc::peak_live_bytes().unwrap() < before + 3 * MIB as u64);
+    drop(grown);
+
+    // The summary gets a Peak column with real figures for each section
+    report::install(Box::new(SectionSummary::new()));
+    let text: String = capture(|out| {
+        section(
+            out,
+            "holds_a_mebibyte",
+            "allocates and frees 1 MiB",
+            |out| {
+                drop(black_box(vec![0u8; MIB]));
+                writeln!(out, "done")
+            },
+        )?;
+        report::finish(out)
+    });
+    let header: &str = text
+        .lines()
+        .find(|line| line.starts_with("Section"))
+        .unwrap();
+    assert!(header.ends_with("Peak"), "{}", header);
+    let row: &str = text
+        .lines()
+        .find(|line| line.starts_with("holds_a_mebibyte"))
+        .unwrap();
+    assert!(row.ends_with("MiB"), "{}", row);
+    assert!(!text.contains("Allocations are counted only"));
+
+    // Vec<i32> starts at 4 slots and doubles: 4, 8, 16, 32
+    let text: String = capture(vec_examples::capacity_demonstration);
+    assert!(
+        text.contains("Allocator calls during the 20 pushes: 4 (at pushes [0, 4, 8, 16])"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("capacity: 20, allocator calls: 1"),
+        "{}",
+        text
+    );
+}
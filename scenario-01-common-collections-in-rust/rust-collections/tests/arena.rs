@@ -0,0 +1,79 @@
+//! The Box-per-node tree and both arena trees against a `BTreeSet` model,
+//! and the bump-allocated list's links in both directions.
+
+use bumpalo::Bump;
+use collections_demo::arena_examples::{ArenaTree, BoxTree, ListNode, Node, bump_list, tree_keys};
+use std::collections::BTreeSet;
+use typed_arena::Arena;
+
+#[test]
+fn all_three_trees_match_a_set() {
+    let keys: Vec<u64> = tree_keys(2_000, 9);
+    let arena: Arena<Node> = Arena::new();
+    let bump: Bump = Bump::new();
+    let mut boxed: BoxTree = BoxTree::new();
+    let mut typed: ArenaTree = ArenaTree::new();
+    let mut bumped: ArenaTree = ArenaTree::new();
+    let mut model: BTreeSet<u64> = BTreeSet::new();
+
+    for &key in &keys {
+        let new: bool = model.insert(key);
+        assert_eq!(boxed.insert(key), new);
+        assert_eq!(typed.insert(&arena, key), new);
+        assert_eq!(bumped.insert(&bump, key), new);
+    }
+
+    let expected: Vec<u64> = model.iter().copied().collect();
+    assert_eq!(boxed.keys(), expected);
+    assert_eq!(typed.keys(), expected);
+    assert_eq!(bumped.keys(), expected);
+    assert_eq!(boxed.len(), model.len());
+    for key in 0..8_000 {
+        let present: bool = model.contains(&key);
+        assert_eq!(boxed.contains(key), present);
+        assert_eq!(typed.contains(key), present);
+        assert_eq!(bumped.contains(key), present);
+    }
+}
+
+#[test]
+fn duplicates_never_allocate() {
+    let arena: Arena<Node> = Arena::new();
+    let mut tree: ArenaTree = ArenaTree::new();
+    for key in [5, 3, 8, 3, 5, 8, 5] {
+        tree.insert(&arena, key);
+    }
+    assert_eq!(tree.len(), 3);
+    assert_eq!(arena.len(), 3);
+}
+
+#[test]
+fn empty_trees_are_empty() {
+    let boxed: BoxTree = BoxTree::new();
+    let tree: ArenaTree = ArenaTree::new();
+    assert!(boxed.is_empty() && tree.is_empty());
+    assert!(boxed.keys().is_empty() && tree.keys().is_empty());
+    assert!(!boxed.contains(0) && !tree.contains(0));
+}
+
+#[test]
+fn bump_list_links_both_ways() {
+    let bump: Bump = Bump::new();
+    assert!(bump_list(&bump, &[]).is_none());
+
+    let values: [&str; 4] = ["a", "b", "c", "d"];
+    let (head, tail) = bump_list(&bump, &values).unwrap();
+    assert!(head.prev().is_none() && tail.next().is_none());
+
+    let mut forward: Vec<&str> = Vec::new();
+    let mut current: Option<&ListNode> = Some(head);
+    while let Some(node) = current {
+        forward.push(node.value);
+        // Each node's successor points back at it
+        if let Some(next) = node.next() {
+            assert!(std::ptr::eq(next.prev().unwrap(), node));
+        }
+        current = node.next();
+    }
+    assert_eq!(forward, values);
+}
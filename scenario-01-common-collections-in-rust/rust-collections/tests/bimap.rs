@@ -0,0 +1,147 @@
+//! `BiMap` overwrite semantics, case by case, and a model check: after any
+//! mix of inserts and removes both directions agree with a one-to-one
+//! reference built from a plain `Vec` of pairs.
+
+use collections_demo::bimap_examples::{BiMap, Overwritten};
+use demo_framework::rng::Lcg;
+
+fn sample() -> BiMap<u32, String> {
+    [(1, "ada"), (2, "grace"), (3, "linus")]
+        .into_iter()
+        .map(|(id, name)| (id, name.to_string()))
+        .collect()
+}
+
+#[test]
+fn lookups_work_both_ways() {
+    let map: BiMap<u32, String> = sample();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get_by_left(&2), Some(&"grace".to_string()));
+    assert_eq!(map.get_by_right(&"linus".to_string()), Some(&3));
+    assert_eq!(map.get_by_left(&9), None);
+    assert!(!map.contains_right(&"bjarne".to_string()));
+    assert!(map.is_consistent());
+}
+
+#[test]
+fn insert_reports_each_kind_of_overwrite() {
+    let mut map: BiMap<u32, String> = sample();
+    assert_eq!(map.insert(4, "bjarne".into()), Overwritten::Neither);
+    assert_eq!(
+        map.insert(4, "bjarne".into()),
+        Overwritten::Pair(4, "bjarne".into())
+    );
+    assert_eq!(
+        map.insert(1, "alan".into()),
+        Overwritten::Left(1, "ada".into())
+    );
+    assert!(!map.contains_right(&"ada".to_string()));
+    assert_eq!(
+        map.insert(5, "grace".into()),
+        Overwritten::Right(2, "grace".into())
+    );
+    assert!(!map.contains_left(&2));
+    assert_eq!(
+        map.insert(3, "bjarne".into()),
+        Overwritten::Both((3, "linus".into()), (4, "bjarne".into()))
+    );
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get_by_right(&"bjarne".to_string()), Some(&3));
+    assert!(map.is_consistent());
+}
+
+#[test]
+fn insert_no_overwrite_leaves_the_map_alone() {
+    let mut map: BiMap<u32, String> = sample();
+    assert_eq!(
+        map.insert_no_overwrite(1, "new".into()),
+        Err((1, "new".into()))
+    );
+    assert_eq!(
+        map.insert_no_overwrite(9, "ada".into()),
+        Err((9, "ada".into()))
+    );
+    assert_eq!(map.len(), 3);
+    assert_eq!(map.get_by_left(&1), Some(&"ada".to_string()));
+    assert_eq!(map.insert_no_overwrite(9, "new".into()), Ok(()));
+    assert_eq!(map.get_by_right(&"new".to_string()), Some(&9));
+}
+
+#[test]
+fn remove_from_either_side_removes_the_pair() {
+    let mut map: BiMap<u32, String> = sample();
+    assert_eq!(map.remove_by_left(&1), Some((1, "ada".into())));
+    assert_eq!(map.get_by_right(&"ada".to_string()), None);
+    assert_eq!(
+        map.remove_by_right(&"grace".to_string()),
+        Some((2, "grace".into()))
+    );
+    assert_eq!(map.get_by_left(&2), None);
+    assert_eq!(map.remove_by_left(&1), None);
+    assert_eq!(map.len(), 1);
+    assert!(map.is_consistent());
+}
+
+#[test]
+fn clones_are_independent() {
+    let original: BiMap<u32, String> = sample();
+    let mut copy: BiMap<u32, String> = original.clone();
+    // Removing returns owned values, which needs the clone's own Rcs
+    assert_eq!(copy.remove_by_left(&1), Some((1, "ada".into())));
+    assert!(copy.is_consistent());
+    assert!(original.is_consistent());
+    assert_eq!(original.get_by_left(&1), Some(&"ada".to_string()));
+}
+
+#[test]
+fn random_operations_match_a_list_of_pairs() {
+    let mut rng: Lcg = Lcg::new(5);
+    for _ in 0..20 {
+        let mut map: BiMap<u8, u16> = BiMap::new();
+        let mut pairs: Vec<(u8, u16)> = Vec::new();
+        for _ in 0..300 {
+            let left: u8 = rng.below(12) as u8;
+            let right: u16 = rng.below(12) as u16 + 100;
+            match rng.below(4) {
+                0..=1 => {
+                    let removed: Vec<(u8, u16)> = pairs
+                        .iter()
+                        .copied()
+                        .filter(|&(l, r)| l == left || r == right)
+                        .collect();
+                    pairs.retain(|&(l, r)| l != left && r != right);
+                    pairs.push((left, right));
+                    let expected: Overwritten<u8, u16> = match removed[..] {
+                        [] => Overwritten::Neither,
+                        [pair] if pair == (left, right) => Overwritten::Pair(left, right),
+                        [(l, r)] if l == left => Overwritten::Left(l, r),
+                        [(l, r)] => Overwritten::Right(l, r),
+                        [a, b] if a.0 == left => Overwritten::Both(a, b),
+                        [a, b] => Overwritten::Both(b, a),
+                        _ => unreachable!("one-to-one: at most two pairs clash"),
+                    };
+                    assert_eq!(map.insert(left, right), expected);
+                }
+                2 => {
+                    let position: Option<usize> = pairs.iter().position(|&(l, _)| l == left);
+                    let expected: Option<(u8, u16)> = position.map(|i| pairs.remove(i));
+                    assert_eq!(map.remove_by_left(&left), expected);
+                }
+                _ => {
+                    let fits: bool = pairs.iter().all(|&(l, r)| l != left && r != right);
+                    if fits {
+                        pairs.push((left, right));
+                    }
+                    assert_eq!(map.insert_no_overwrite(left, right).is_ok(), fits);
+                }
+            }
+
+            assert_eq!(map.len(), pairs.len());
+            assert!(map.is_consistent());
+            for &(l, r) in &pairs {
+                assert_eq!(map.get_by_left(&l), Some(&r));
+                assert_eq!(map.get_by_right(&r), Some(&l));
+            }
+        }
+    }
+}
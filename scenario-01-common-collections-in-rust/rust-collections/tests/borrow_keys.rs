@@ -0,0 +1,70 @@
+//! `Username` lookups through `Borrow<str>`, with the tracking allocator
+//! installed for this test binary to prove a `&str` lookup allocates
+//! nothing.
+//!
+//! The allocation counters are process-wide, so everything runs in a single
+//! test: another test allocating on a parallel thread would show up in the
+//! count.
+
+use collections_demo::hashmap_examples::Username;
+use demo_framework::alloc::{self, AllocCounts, TrackingAllocator};
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::hint::black_box;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+#[test]
+fn str_lookups_hash_like_the_key_and_never_allocate() {
+    let names: Vec<String> = (0..1_000).map(|i| format!("user{i}")).collect();
+    let mut map: HashMap<Username, usize> = HashMap::new();
+    for (i, name) in names.iter().enumerate() {
+        map.insert(Username::new(name), i);
+    }
+
+    let state: RandomState = RandomState::new();
+    for name in &names {
+        assert_eq!(
+            state.hash_one(Username::new(name)),
+            state.hash_one(name.as_str())
+        );
+    }
+    let probes: Vec<&str> = names
+        .iter()
+        .map(String::as_str)
+        .chain(["nobody", "user1000", ""])
+        .collect();
+
+    // Borrowed lookups: hits, misses and in-place updates
+    let before: AllocCounts = alloc::counts().expect("the tracking allocator is installed");
+    let mut hits: usize = 0;
+    for probe in &probes {
+        let found: Option<usize> = map.get(*probe).copied();
+        if let Some(i) = found {
+            assert_eq!(names[i], *probe);
+            hits += 1;
+        }
+        assert_eq!(map.contains_key(*probe), found.is_some());
+        if let Some(value) = map.get_mut(*probe) {
+            *value += 1;
+        }
+    }
+    let borrowed: AllocCounts = alloc::counts().unwrap().since(before);
+    assert_eq!(hits, names.len());
+    assert_eq!(borrowed.allocations, 0, "{borrowed:?}");
+
+    // The owned form builds a String per lookup, which the counter sees
+    let before: AllocCounts = alloc::counts().unwrap();
+    for probe in &probes {
+        black_box(map.get(&Username::new(probe)));
+    }
+    let owned: AllocCounts = alloc::counts().unwrap().since(before);
+    // The empty probe's String doesn't allocate
+    assert_eq!(owned.allocations, probes.len() as u64 - 1);
+
+    for (i, name) in names.iter().enumerate() {
+        assert_eq!(map[name.as_str()], i + 1);
+    }
+}
@@ -0,0 +1,118 @@
+//! Each overflow policy of `BoundedQueue` on a full queue, and random pushes
+//! and pops checked against an unbounded `VecDeque` model with the policy
+//! applied by hand.
+
+use collections_demo::bounded_queue_examples::{
+    BoundedQueue, OverflowPolicy, PushError, Pushed, QueueStats,
+};
+use demo_framework::rng::Lcg;
+use std::collections::VecDeque;
+
+fn full_queue(policy: OverflowPolicy) -> BoundedQueue<u32> {
+    let mut queue: BoundedQueue<u32> = BoundedQueue::new(3, policy);
+    for item in 1..=3 {
+        assert_eq!(queue.push(item), Ok(Pushed::Accepted));
+    }
+    assert!(queue.is_full());
+    queue
+}
+
+#[test]
+fn reject_drops_the_new_item() {
+    let mut queue: BoundedQueue<u32> = full_queue(OverflowPolicy::Reject);
+    assert_eq!(queue.push(4), Err(PushError::Rejected(4)));
+    assert_eq!(queue.iter().copied().collect::<Vec<u32>>(), vec![1, 2, 3]);
+    assert_eq!(queue.stats().rejected, 1);
+
+    // Room again after a pop
+    assert_eq!(queue.pop(), Some(1));
+    assert_eq!(queue.push(4), Ok(Pushed::Accepted));
+    assert_eq!(queue.iter().copied().collect::<Vec<u32>>(), vec![2, 3, 4]);
+}
+
+#[test]
+fn overwrite_oldest_evicts_the_front() {
+    let mut queue: BoundedQueue<u32> = full_queue(OverflowPolicy::OverwriteOldest);
+    assert_eq!(queue.push(4), Ok(Pushed::Overwrote(1)));
+    assert_eq!(queue.push(5), Ok(Pushed::Overwrote(2)));
+    assert_eq!(queue.peek(), Some(&3));
+    assert_eq!(queue.len(), 3);
+    assert_eq!(
+        queue.stats(),
+        QueueStats {
+            accepted: 5,
+            overwritten: 2,
+            rejected: 0,
+            blocked: 0
+        }
+    );
+}
+
+#[test]
+fn block_hands_the_item_back_as_would_block() {
+    let mut queue: BoundedQueue<u32> = full_queue(OverflowPolicy::Block);
+    let error: PushError<u32> = queue.push(4).unwrap_err();
+    assert_eq!(error, PushError::WouldBlock(4));
+    assert_eq!(error.to_string(), "queue is full, retry after a pop");
+    assert_eq!(error.into_inner(), 4);
+    assert_eq!(queue.stats().blocked, 1);
+    assert_eq!(queue.stats().rejected, 0);
+
+    // The retry succeeds once the consumer has made room
+    queue.pop();
+    assert_eq!(queue.push(4), Ok(Pushed::Accepted));
+}
+
+#[test]
+#[should_panic(expected = "a bounded queue needs room for one item")]
+fn zero_capacity_panics() {
+    BoundedQueue::<u32>::new(0, OverflowPolicy::Reject);
+}
+
+#[test]
+fn random_pushes_and_pops_match_the_model() {
+    for policy in [
+        OverflowPolicy::Reject,
+        OverflowPolicy::OverwriteOldest,
+        OverflowPolicy::Block,
+    ] {
+        for seed in 0..10 {
+            let mut rng: Lcg = Lcg::new(seed);
+            let capacity: usize = 1 + rng.below(8) as usize;
+            let mut queue: BoundedQueue<u64> = BoundedQueue::new(capacity, policy);
+            let mut model: VecDeque<u64> = VecDeque::new();
+            for step in 0..1_000u64 {
+                // Pushes outnumber pops, so the queue spends time full
+                if rng.below(5) < 3 {
+                    let expected: Result<Pushed<u64>, PushError<u64>> = if model.len() < capacity {
+                        model.push_back(step);
+                        Ok(Pushed::Accepted)
+                    } else {
+                        match policy {
+                            OverflowPolicy::Reject => Err(PushError::Rejected(step)),
+                            OverflowPolicy::Block => Err(PushError::WouldBlock(step)),
+                            OverflowPolicy::OverwriteOldest => {
+                                let oldest: u64 = model.pop_front().unwrap();
+                                model.push_back(step);
+                                Ok(Pushed::Overwrote(oldest))
+                            }
+                        }
+                    };
+                    assert_eq!(
+                        queue.push(step),
+                        expected,
+                        "{:?} seed {} step {}",
+                        policy,
+                        seed,
+                        step
+                    );
+                } else {
+                    assert_eq!(queue.pop(), model.pop_front());
+                }
+                assert!(queue.len() <= capacity);
+                assert_eq!(queue.remaining(), capacity - model.len());
+                assert!(queue.iter().eq(model.iter()));
+            }
+        }
+    }
+}
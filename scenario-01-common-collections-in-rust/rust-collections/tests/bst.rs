@@ -0,0 +1,116 @@
+//! Both trees against the `BTreeMap` reference under generated operation
+//! programs (proptest), with the ordering invariant checked after every
+//! step, the AVL balance and height bounds, and sorted inputs deep enough
+//! to overflow a recursive walk or drop.
+
+use collections_demo::bst_examples::{AvlTree, Bst, btreemap_node_bytes, tree_node_bytes};
+use demo_framework::ops::{Divergence, OpTarget, Program, check, program};
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+
+/// Keys from a small range, so inserts overwrite and removes hit.
+const KEY_SPACE: u32 = 64;
+
+/// Runs `program` on `tree` one operation at a time, checking the tree's
+/// invariants after each, and returns the first failure.
+fn replay_checked<T: OpTarget>(
+    program: &Program,
+    tree: &mut T,
+    invariants: impl Fn(&T) -> Result<(), String>,
+) -> Result<(), String> {
+    for (step, &op) in program.0.iter().enumerate() {
+        tree.apply(op);
+        invariants(tree).map_err(|error| format!("step {} ({}): {}", step, op, error))?;
+    }
+    Ok(())
+}
+
+/// The tallest an AVL tree with `len` entries can be: the sparsest one,
+/// a Fibonacci tree, has F(h + 2) - 1 entries at height h.
+fn max_avl_height(len: usize) -> usize {
+    let (mut height, mut smallest, mut next) = (0, 0usize, 1usize);
+    while next <= len {
+        (smallest, next) = (next, smallest + next + 1);
+        height += 1;
+    }
+    height
+}
+
+proptest! {
+    #[test]
+    fn matches_the_btreemap_model(program in program(KEY_SPACE, 300)) {
+        let result: Result<(), Divergence> = check(&program, &mut Bst::new());
+        prop_assert!(result.is_ok(), "Bst {}", result.unwrap_err());
+        let result: Result<(), Divergence> = check(&program, &mut AvlTree::new());
+        prop_assert!(result.is_ok(), "AvlTree {}", result.unwrap_err());
+
+        let mut bst: Bst<u32, u32> = Bst::new();
+        let mut avl: AvlTree<u32, u32> = AvlTree::new();
+        prop_assert_eq!(replay_checked(&program, &mut bst, Bst::check), Ok(()));
+        prop_assert_eq!(replay_checked(&program, &mut avl, AvlTree::check), Ok(()));
+
+        let mut model: BTreeMap<u32, u32> = BTreeMap::new();
+        program.run(&mut model);
+        prop_assert!(bst.iter().eq(model.iter()));
+        prop_assert!(avl.iter().eq(model.iter()));
+        for key in 0..KEY_SPACE + 6 {
+            prop_assert_eq!(bst.get(&key), model.get(&key));
+            prop_assert_eq!(avl.get(&key), model.get(&key));
+            prop_assert!(avl.comparisons(&key) <= avl.height());
+        }
+        prop_assert!(avl.height() <= max_avl_height(avl.len()));
+    }
+
+    #[test]
+    fn iterator_size_hint_counts_down(keys in prop::collection::vec(any::<u32>(), 0..100)) {
+        let mut avl: AvlTree<u32, ()> = AvlTree::new();
+        for key in keys {
+            avl.insert(key, ());
+        }
+        let mut iter = avl.iter();
+        for remaining in (0..avl.len()).rev() {
+            prop_assert!(iter.stack_depth() <= avl.height());
+            prop_assert!(iter.next().is_some());
+            prop_assert_eq!(iter.size_hint(), (remaining, Some(remaining)));
+        }
+        prop_assert!(iter.next().is_none());
+    }
+}
+
+#[test]
+fn sorted_inserts_chain_the_bst_but_not_the_avl_tree() {
+    let n: u32 = 20_000;
+    let mut bst: Bst<u32, ()> = Bst::new();
+    let mut avl: AvlTree<u32, ()> = AvlTree::new();
+    for key in 0..n {
+        bst.insert(key, ());
+        avl.insert(key, ());
+    }
+    // Walking, checking and dropping a 20000-deep chain must not recurse
+    assert_eq!(bst.height(), n as usize);
+    assert_eq!(bst.check(), Ok(()));
+    assert_eq!(bst.comparisons(&(n - 1)), n as usize);
+    assert_eq!(avl.check(), Ok(()));
+    assert!(avl.height() <= max_avl_height(avl.len()));
+    assert!(avl.height() >= 15, "log2(20000) is 14.3");
+
+    // Removing from both ends keeps it balanced. The chain's smallest
+    // key is always its root, so the BST only loses those
+    for key in (0..n / 4).chain(n / 2..n) {
+        assert_eq!(avl.remove(&key), Some(()));
+    }
+    for key in 0..n / 4 {
+        assert_eq!(bst.remove(&key), Some(()));
+    }
+    assert_eq!(avl.check(), Ok(()));
+    assert!(avl.iter().map(|(key, _)| *key).eq(n / 4..n / 2));
+    assert!(bst.iter().map(|(key, _)| *key).eq(n / 4..n));
+    drop(bst);
+}
+
+#[test]
+fn btreemap_nodes_match_the_std_layout() {
+    assert_eq!(tree_node_bytes::<u64, u64>(), 40);
+    assert_eq!(btreemap_node_bytes::<u64, u64>(), (192, 288));
+    assert_eq!(btreemap_node_bytes::<u32, u32>(), (104, 200));
+}
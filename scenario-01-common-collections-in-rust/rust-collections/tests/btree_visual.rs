@@ -0,0 +1,78 @@
+//! The instructional B-tree against std's BTreeSet, with the B-tree rules
+//! checked after every insert.
+
+use collections_demo::btree_visual::{BTree, shuffled};
+use std::collections::BTreeSet;
+
+/// The tallest a valid B-tree of `order` with `len` keys can be: every
+/// node but the root at minimum fill.
+fn max_height(order: usize, len: usize) -> usize {
+    let min_children: f64 = order.div_ceil(2) as f64;
+    1 + (((len + 1) as f64 / 2.0).ln() / min_children.ln()).floor() as usize
+}
+
+#[test]
+fn matches_btreeset_and_keeps_the_rules() {
+    for order in [3, 4, 5, 12] {
+        for seed in 0..10 {
+            let mut tree: BTree<u32> = BTree::new(order);
+            let mut set: BTreeSet<u32> = BTreeSet::new();
+            // Duplicates included: keys drawn from a range half the count
+            for key in shuffled(400, seed).into_iter().map(|key| key % 200) {
+                assert_eq!(tree.insert(key), set.insert(key));
+                assert_eq!(tree.check(), Ok(()), "order {} seed {}", order, seed);
+            }
+            assert_eq!(tree.len(), set.len());
+            assert!(tree.keys().into_iter().eq(set.iter()));
+            assert!((0..250).all(|key| tree.contains(&key) == set.contains(&key)));
+            assert!(tree.height() <= max_height(order, tree.len()));
+        }
+    }
+}
+
+#[test]
+fn sorted_inserts_stay_balanced() {
+    for order in [3, 4] {
+        let mut tree: BTree<u32> = BTree::new(order);
+        for key in 0..10_000 {
+            tree.insert(key);
+        }
+        assert_eq!(tree.check(), Ok(()));
+        assert!(tree.height() <= max_height(order, tree.len()));
+    }
+}
+
+#[test]
+fn search_reads_one_node_per_level() {
+    let mut tree: BTree<u32> = BTree::new(4);
+    for key in shuffled(1_000, 1) {
+        tree.insert(key * 2);
+    }
+    for key in [0, 1, 998, 1_001, 5_000] {
+        let (path, found) = tree.search_path(&key);
+        assert_eq!(found, key % 2 == 0 && key < 2_000);
+        assert!(path.len() <= tree.height());
+        // A miss always ends in a leaf
+        if !found {
+            assert_eq!(path.len(), tree.height());
+        }
+    }
+}
+
+#[test]
+fn render_shows_every_key_once() {
+    let mut tree: BTree<u32> = BTree::new(3);
+    assert_eq!(tree.render(), "[]");
+    for key in [10, 20, 30, 40, 50, 60] {
+        tree.insert(key);
+    }
+    assert_eq!(tree.render(), "[20 40]\n├── [10]\n├── [30]\n└── [50 60]");
+    assert_eq!(tree.node_count(), 4);
+    assert_eq!(tree.splits(), 2);
+}
+
+#[test]
+#[should_panic(expected = "order 3 or more")]
+fn order_two_is_rejected() {
+    let _tree: BTree<u32> = BTree::new(2);
+}
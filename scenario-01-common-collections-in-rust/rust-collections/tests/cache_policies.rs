@@ -0,0 +1,186 @@
+//! LFU and ARC against their definitions.
+//!
+//! LFU is checked against a brute-force model that scans every entry for
+//! the lowest (frequency, arrival) pair. ARC is checked against the size
+//! invariants from the paper after every request of long Zipfian traces.
+
+use collections_demo::cache_policies_examples::{
+    ArcCache, ArcLists, CachePolicy, HitStats, LfuCache, all_policies, simulate, zipf_trace,
+};
+use std::collections::HashMap;
+
+#[test]
+fn lfu_evicts_the_least_frequent_then_the_oldest() {
+    let mut cache: LfuCache<&str, u32> = LfuCache::new(3);
+    cache.put("a", 1);
+    cache.put("b", 2);
+    cache.put("c", 3);
+    cache.get(&"a");
+    cache.get(&"c");
+
+    // "b" is the only key still at frequency 1
+    assert_eq!(cache.put("d", 4), Some(("b", 2)));
+    // "a" reached frequency 2 before "c"; "d" is new at 1
+    assert_eq!(cache.put("e", 5), Some(("d", 4)));
+    cache.get(&"e");
+    assert_eq!(cache.pop_lfu(), Some(("a", 1)));
+    assert_eq!(cache.pop_lfu(), Some(("c", 3)));
+    assert_eq!(cache.pop_lfu(), Some(("e", 5)));
+    assert_eq!(cache.pop_lfu(), None);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn lfu_put_on_an_existing_key_counts_as_an_access() {
+    let mut cache: LfuCache<u32, u32> = LfuCache::new(2);
+    cache.put(1, 10);
+    cache.put(2, 20);
+    assert_eq!(cache.put(1, 11), Some((1, 10)));
+    assert_eq!(cache.frequency(&1), Some(2));
+    assert_eq!(cache.put(3, 30), Some((2, 20)));
+    assert_eq!(cache.get(&1), Some(&11));
+}
+
+/// Value, frequency and the tick at which the key reached that frequency.
+type ModelEntry = (u64, u64, u64);
+
+#[test]
+fn lfu_matches_a_brute_force_model() {
+    for capacity in [1, 3, 16, 100] {
+        let mut cache: LfuCache<u64, u64> = LfuCache::new(capacity);
+        let mut model: HashMap<u64, ModelEntry> = HashMap::new();
+
+        let trace: Vec<u64> = zipf_trace(5_000, 4 * capacity, 0.9, capacity as u64);
+        for (tick, &key) in trace.iter().enumerate() {
+            let tick: u64 = tick as u64;
+            if let Some((value, frequency, reached)) = model.get_mut(&key) {
+                *frequency += 1;
+                *reached = tick;
+                assert_eq!(cache.get(&key), Some(&*value));
+                continue;
+            }
+
+            assert_eq!(cache.get(&key), None);
+            let mut expected: Option<(u64, u64)> = None;
+            if model.len() == capacity {
+                let victim: u64 = *model
+                    .iter()
+                    .min_by_key(|(_, (_, frequency, reached))| (*frequency, *reached))
+                    .map(|(k, _)| k)
+                    .unwrap();
+                let (value, _, _) = model.remove(&victim).unwrap();
+                expected = Some((victim, value));
+            }
+            model.insert(key, (tick, 1, tick));
+            assert_eq!(cache.put(key, tick), expected, "capacity {}", capacity);
+        }
+
+        for (key, (_, frequency, _)) in &model {
+            assert_eq!(cache.frequency(key), Some(*frequency));
+        }
+    }
+}
+
+#[test]
+fn arc_keeps_its_list_invariants() {
+    for capacity in [1, 2, 10, 100] {
+        for exponent in [0.6, 1.0, 1.4] {
+            let mut cache: ArcCache<u64, u64> = ArcCache::new(capacity);
+            for &key in &zipf_trace(10_000, 5 * capacity, exponent, 11) {
+                if cache.get(&key).is_none() {
+                    cache.put(key, key);
+                }
+                let lists: ArcLists = cache.lists();
+                let cached: usize = lists.recent + lists.frequent;
+                assert!(cached <= capacity);
+                assert_eq!(cached, cache.len());
+                assert!(lists.recent + lists.recent_ghosts <= capacity);
+                assert!(cached + lists.recent_ghosts + lists.frequent_ghosts <= 2 * capacity);
+                assert!(lists.target <= capacity);
+            }
+        }
+    }
+}
+
+#[test]
+fn arc_returns_what_was_put() {
+    let mut cache: ArcCache<u32, &str> = ArcCache::new(2);
+    assert_eq!(cache.put(1, "one"), None);
+    assert_eq!(cache.put(1, "uno"), Some((1, "one")));
+    assert_eq!(cache.get(&1), Some(&"uno"));
+
+    // 1 sits in T2 now; new keys cycle through T1 and into the ghosts
+    for key in 2..10 {
+        cache.put(key, "other");
+    }
+    assert_eq!(cache.get(&1), Some(&"uno"));
+    assert_eq!(cache.get(&2), None);
+}
+
+#[test]
+fn arc_ghost_hits_move_the_target() {
+    let mut cache: ArcCache<u32, u32> = ArcCache::new(4);
+    for key in [1, 2, 3, 4] {
+        cache.put(key, key);
+    }
+    cache.get(&1);
+    cache.get(&2);
+    cache.put(5, 5);
+    cache.put(6, 6);
+    assert_eq!(cache.lists().target, 0);
+
+    // 3 was evicted from T1 into B1
+    cache.put(3, 3);
+    assert_eq!(cache.lists().target, 1);
+    assert_eq!(cache.get(&3), Some(&3));
+}
+
+#[test]
+fn scan_resistant_policies_keep_the_hot_set() {
+    let hot: Vec<u64> = (0..50).cycle().take(500).collect();
+    let scan: Vec<u64> = (1_000..2_000).collect();
+
+    for mut cache in all_policies(100) {
+        simulate(cache.as_mut(), &hot);
+        simulate(cache.as_mut(), &scan);
+        let survivors: usize = (0..50).filter(|k| cache.get(k).is_some()).count();
+        match cache.name() {
+            "LRU" => assert_eq!(survivors, 0),
+            _ => assert_eq!(survivors, 50, "{}", cache.name()),
+        }
+    }
+}
+
+#[test]
+fn simulation_counts_every_request_and_respects_capacity() {
+    let trace: Vec<u64> = zipf_trace(20_000, 1_000, 1.0, 5);
+    for mut cache in all_policies(64) {
+        let stats: HitStats = simulate(cache.as_mut(), &trace);
+        assert_eq!(stats.hits + stats.misses, trace.len());
+        assert_eq!(cache.len(), cache.capacity());
+    }
+}
+
+#[test]
+fn zipf_traces_are_deterministic_and_skewed() {
+    let trace: Vec<u64> = zipf_trace(50_000, 100, 1.0, 9);
+    assert_eq!(trace, zipf_trace(50_000, 100, 1.0, 9));
+    assert!(trace.iter().all(|&k| k < 100));
+
+    let mut counts: [usize; 100] = [0; 100];
+    for &key in &trace {
+        counts[key as usize] += 1;
+    }
+    // With exponent 1, rank 1 is requested about twice as often as rank 2
+    // and ten times as often as rank 10
+    let ratio_2: f64 = counts[0] as f64 / counts[1] as f64;
+    let ratio_10: f64 = counts[0] as f64 / counts[9] as f64;
+    assert!((1.8..2.2).contains(&ratio_2), "{}", ratio_2);
+    assert!((8.5..11.5).contains(&ratio_10), "{}", ratio_10);
+}
+
+#[test]
+#[should_panic(expected = "cache capacity must be non-zero")]
+fn zero_capacity_panics() {
+    LfuCache::<u32, u32>::new(0);
+}
@@ -0,0 +1,58 @@
+//! The README's reference tables must be exactly what `cheatsheet --markdown`
+//! prints. Regenerate them with
+//! `cargo run -- cheatsheet --markdown` and paste the output between the
+//! markers.
+
+use collections_demo::cheatsheet::{COLLECTIONS, tables};
+use demo_framework::cheatsheet::{embedded, markdown};
+use std::collections::HashSet;
+
+#[test]
+fn readme_tables_match_the_code() {
+    let readme: &str = include_str!("../../README.md");
+    let generated: String = markdown(&tables());
+    assert_eq!(
+        embedded(readme),
+        Some(generated.trim_end()),
+        "README cheat sheet is out of date; regenerate it with `cargo run -- cheatsheet --markdown`"
+    );
+}
+
+#[test]
+fn every_collection_appears_once_in_every_table() {
+    let names: HashSet<&str> = COLLECTIONS.iter().map(|c| c.name).collect();
+    assert_eq!(names.len(), COLLECTIONS.len());
+    for table in tables() {
+        assert_eq!(table.rows.len(), COLLECTIONS.len(), "{}", table.title);
+        for row in &table.rows {
+            assert_eq!(
+                row.len(),
+                table.columns.len(),
+                "{}: {}",
+                table.title,
+                row[0]
+            );
+        }
+    }
+}
+
+#[test]
+fn footnote_markers_have_notes() {
+    for table in tables() {
+        for row in &table.rows {
+            for cell in row {
+                for marker in cell.split(|c: char| c != '*').filter(|run| !run.is_empty()) {
+                    assert!(
+                        table
+                            .notes
+                            .iter()
+                            .any(|note| note.starts_with(&format!("{} ", marker))),
+                        "{}: no note for {:?}",
+                        table.title,
+                        cell
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,324 @@
+//! Collated keys on non-ASCII names: fixed name lists against the orders
+//! ICU's collator (icu_collator 1.5) gives them in each locale, the level
+//! rules on random words (proptest), and the keys each wrapper type merges.
+
+use collections_demo::collation_examples::{CaseInsensitive, Collated, Locale, collate};
+use proptest::prelude::*;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+use unicode_normalization::UnicodeNormalization;
+
+const NAMES: [&str; 36] = [
+    "Zoë",
+    "adam",
+    "Adam",
+    "Émile",
+    "émile",
+    "Emile",
+    "emile",
+    "Élodie",
+    "Ève",
+    "eve",
+    "Ødegaard",
+    "Oden",
+    "Olof",
+    "Örn",
+    "Öberg",
+    "Åsa",
+    "Ärla",
+    "Aaron",
+    "Ana María",
+    "Anna",
+    "Anna-Lena",
+    "Anna Berg",
+    "Annabel",
+    "Müller",
+    "Mueller",
+    "Muller",
+    "Mufti",
+    "Nuñez",
+    "Nunez",
+    "Nuzzo",
+    "Ümit",
+    "Yngve",
+    "Łukasz",
+    "Lukas",
+    "Strauß",
+    "Strauss",
+];
+
+/// What ICU's collator makes of `NAMES`, per locale ("und", "sv", "es" and
+/// "de-u-co-phonebk").
+const ICU_ROOT: [&str; 36] = [
+    "Aaron",
+    "adam",
+    "Adam",
+    "Ana María",
+    "Anna",
+    "Anna Berg",
+    "Anna-Lena",
+    "Annabel",
+    "Ärla",
+    "Åsa",
+    "Élodie",
+    "emile",
+    "Emile",
+    "émile",
+    "Émile",
+    "eve",
+    "Ève",
+    "Lukas",
+    "Łukasz",
+    "Mueller",
+    "Mufti",
+    "Muller",
+    "Müller",
+    "Nunez",
+    "Nuñez",
+    "Nuzzo",
+    "Öberg",
+    "Ødegaard",
+    "Oden",
+    "Olof",
+    "Örn",
+    "Strauss",
+    "Strauß",
+    "Ümit",
+    "Yngve",
+    "Zoë",
+];
+const ICU_SWEDISH: [&str; 36] = [
+    "Aaron",
+    "adam",
+    "Adam",
+    "Ana María",
+    "Anna",
+    "Anna Berg",
+    "Anna-Lena",
+    "Annabel",
+    "Élodie",
+    "emile",
+    "Emile",
+    "émile",
+    "Émile",
+    "eve",
+    "Ève",
+    "Lukas",
+    "Łukasz",
+    "Mueller",
+    "Mufti",
+    "Muller",
+    "Müller",
+    "Nunez",
+    "Nuñez",
+    "Nuzzo",
+    "Oden",
+    "Olof",
+    "Strauss",
+    "Strauß",
+    "Ümit",
+    "Yngve",
+    "Zoë",
+    "Åsa",
+    "Ärla",
+    "Öberg",
+    "Ødegaard",
+    "Örn",
+];
+const ICU_GERMAN_PHONEBOOK: [&str; 36] = [
+    "Aaron",
+    "adam",
+    "Adam",
+    "Ärla",
+    "Ana María",
+    "Anna",
+    "Anna Berg",
+    "Anna-Lena",
+    "Annabel",
+    "Åsa",
+    "Élodie",
+    "emile",
+    "Emile",
+    "émile",
+    "Émile",
+    "eve",
+    "Ève",
+    "Lukas",
+    "Łukasz",
+    "Mueller",
+    "Müller",
+    "Mufti",
+    "Muller",
+    "Nunez",
+    "Nuñez",
+    "Nuzzo",
+    "Ødegaard",
+    "Oden",
+    "Öberg",
+    "Örn",
+    "Olof",
+    "Strauss",
+    "Strauß",
+    "Ümit",
+    "Yngve",
+    "Zoë",
+];
+
+#[test]
+fn name_lists_sort_like_icu_in_every_locale() {
+    assert_eq!(collate(&NAMES, Locale::Root), ICU_ROOT);
+    assert_eq!(collate(&NAMES, Locale::Swedish), ICU_SWEDISH);
+    // Spanish only moves ñ, and Nuñez already sorts between Nunez and Nuzzo
+    assert_eq!(collate(&NAMES, Locale::Spanish), ICU_ROOT);
+    assert_eq!(
+        collate(&NAMES, Locale::GermanPhonebook),
+        ICU_GERMAN_PHONEBOOK
+    );
+}
+
+#[test]
+fn a_collated_btreemap_iterates_in_collation_order() {
+    let map: BTreeMap<Collated, usize> = NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, &name)| (Collated::new(name, Locale::Root), i))
+        .collect();
+    let keys: Vec<&str> = map.keys().map(Collated::as_str).collect();
+    assert_eq!(keys, ICU_ROOT);
+}
+
+#[test]
+fn canonically_equivalent_spellings_are_one_key() {
+    let mut map: BTreeMap<Collated, u32> = BTreeMap::new();
+    map.insert(Collated::new("Ren\u{e9}e", Locale::Root), 1);
+    map.insert(Collated::new("Rene\u{301}e", Locale::Root), 2);
+    map.insert(Collated::new("Renee", Locale::Root), 3);
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get(&Collated::new("Renée", Locale::Root)), Some(&2));
+
+    // Tailorings see through the spelling too
+    let composed: Collated = Collated::new("\u{c5}sa", Locale::Swedish);
+    let decomposed: Collated = Collated::new("A\u{30a}sa", Locale::Swedish);
+    assert_eq!(composed, decomposed);
+    assert!(composed > Collated::new("Zorn", Locale::Swedish));
+}
+
+#[test]
+fn case_insensitive_keys_merge_case_but_not_accents() {
+    let keys: HashSet<CaseInsensitive> = ["Bob", "bob", "BOB", "Émile", "émile", "emile"]
+        .into_iter()
+        .map(|name| CaseInsensitive(name.to_string()))
+        .collect();
+    assert_eq!(keys.len(), 3);
+    let ordered: BTreeMap<CaseInsensitive, ()> = keys.into_iter().map(|key| (key, ())).collect();
+    let folded: Vec<String> = ordered.keys().map(|key| key.0.to_lowercase()).collect();
+    assert_eq!(folded, ["bob", "emile", "émile"]);
+    assert_eq!(
+        CaseInsensitive("émile".to_string()).cmp(&CaseInsensitive("Zoë".to_string())),
+        Ordering::Greater
+    );
+}
+
+#[test]
+fn primary_comparison_ignores_accents_and_case() {
+    let key = |name: &str| Collated::new(name, Locale::Root);
+    for (a, b) in [
+        ("emile", "ÉMILE"),
+        ("Łukasz", "lukasz"),
+        ("Strauß", "strauss"),
+    ] {
+        assert_eq!(
+            key(a).sort_key().cmp_primary(key(b).sort_key()),
+            Ordering::Equal
+        );
+        assert_ne!(key(a), key(b));
+    }
+}
+
+const LOCALES: [Locale; 4] = [
+    Locale::Root,
+    Locale::Swedish,
+    Locale::Spanish,
+    Locale::GermanPhonebook,
+];
+
+/// Words over ASCII letters in both cases, digits, a space and a hyphen.
+fn ascii_word() -> impl Strategy<Value = String> {
+    "[a-cA-CyzYZ0-9 -]{0,6}"
+}
+
+/// A word of lowercase letters with an accent or none on each letter, as
+/// (letters, accented spelling in NFD).
+fn accented_word() -> impl Strategy<Value = (String, String)> {
+    let marks: Vec<&str> = vec![
+        "", "", "\u{301}", "\u{300}", "\u{302}", "\u{308}", "\u{30A}", "\u{303}",
+    ];
+    prop::collection::vec(("[aenouy]", prop::sample::select(marks)), 0..6).prop_map(|letters| {
+        let plain: String = letters.iter().map(|(letter, _)| letter.as_str()).collect();
+        let accented: String = letters
+            .iter()
+            .map(|(letter, mark)| format!("{}{}", letter, mark))
+            .collect();
+        (plain, accented)
+    })
+}
+
+/// Letters with and without accents in both cases, digits, a space and
+/// some punctuation.
+const ALPHABET: [&str; 35] = [
+    "a", "A", "ä", "Ä", "å", "á", "æ", "e", "É", "è", "ê", "ë", "ě", "n", "ñ", "Ñ", "o", "ö", "Ø",
+    "s", "ß", "u", "ü", "y", "z", "ç", "ł", "Ł", "1", "9", " ", "-", "'", ".", "_",
+];
+
+fn word() -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(ALPHABET.to_vec()), 1..7)
+        .prop_map(|parts| parts.concat())
+}
+
+proptest! {
+    #[test]
+    fn ascii_words_sort_by_letters_then_case(a in ascii_word(), b in ascii_word()) {
+        // Spaces, hyphen and digits already sort before letters by code point
+        let cases = |word: &str| -> Vec<bool> { word.chars().map(|c| c.is_uppercase()).collect() };
+        let expected: Ordering = a
+            .to_lowercase()
+            .cmp(&b.to_lowercase())
+            .then_with(|| cases(&a).cmp(&cases(&b)));
+        for locale in LOCALES {
+            prop_assert_eq!(Collated::new(&a, locale).cmp(&Collated::new(&b, locale)), expected);
+        }
+    }
+
+    #[test]
+    fn accents_only_decide_between_equal_letters(
+        (a, accented_a) in accented_word(),
+        (b, accented_b) in accented_word(),
+    ) {
+        let x: Collated = Collated::new(&accented_a, Locale::Root);
+        let y: Collated = Collated::new(&accented_b, Locale::Root);
+        if a != b {
+            prop_assert_eq!(x.cmp(&y), a.cmp(&b));
+        }
+        // No accent sorts first
+        prop_assert!(Collated::new(&a, Locale::Root) <= x);
+    }
+
+    #[test]
+    fn composed_and_decomposed_spellings_are_one_key(word in word()) {
+        let composed: String = word.nfc().collect();
+        let decomposed: String = word.nfd().collect();
+        for locale in LOCALES {
+            prop_assert_eq!(Collated::new(&composed, locale), Collated::new(&decomposed, locale));
+        }
+    }
+
+    #[test]
+    fn swedish_letters_come_after_z(first in "[åäöÅÄÖ]", rest in word(), other in word()) {
+        let swedish: Collated = Collated::new(&format!("{}{}", first, rest), Locale::Swedish);
+        let z: Collated = Collated::new(&format!("z{}", other), Locale::Swedish);
+        prop_assert!(swedish > z);
+        // ...but in the default order they file under a and o
+        let root: Collated = Collated::new(&format!("{}{}", first, rest), Locale::Root);
+        prop_assert!(root < Collated::new("p", Locale::Root));
+    }
+}
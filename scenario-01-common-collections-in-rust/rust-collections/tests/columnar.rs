@@ -0,0 +1,96 @@
+//! The three group-bys agree on sales of every shape, the runs cover every
+//! row once in key order, and the columnar group-by's allocations don't
+//! grow with the number of rows.
+//!
+//! The tracking allocator is installed for this test binary, and the
+//! counters are process-wide, so everything runs in a single test.
+
+use collections_demo::columnar_examples::{
+    Aggregate, GroupedColumns, SaleRow, SalesColumns, group_codes, group_columns, group_rows,
+    sales_rows, sorted_runs,
+};
+use demo_framework::alloc::{self, AllocCounts, TrackingAllocator};
+use std::collections::BTreeMap;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+/// Allocations made while running `work`, not counting dropping its result.
+fn allocations<T>(work: impl FnOnce() -> T) -> u64 {
+    let before: AllocCounts = alloc::counts().expect("the tracking allocator is installed");
+    let result: T = work();
+    let made: u64 = alloc::counts().unwrap().since(before).allocations;
+    drop(result);
+    made
+}
+
+fn check_runs(keys: &[u32]) {
+    let (order, runs) = sorted_runs(keys);
+    let mut rows: Vec<u32> = order.clone();
+    rows.sort_unstable();
+    assert_eq!(rows, (0..keys.len() as u32).collect::<Vec<u32>>());
+
+    assert!(runs.windows(2).all(|pair| pair[0].key < pair[1].key));
+    let mut next: usize = 0;
+    for run in &runs {
+        assert_eq!(run.start, next);
+        let span: &[u32] = &order[run.start..run.start + run.len];
+        assert!(span.iter().all(|&row| keys[row as usize] == run.key));
+        // Equal keys keep their row order
+        assert!(span.windows(2).all(|pair| pair[0] < pair[1]));
+        next += run.len;
+    }
+    assert_eq!(next, keys.len());
+}
+
+#[test]
+fn group_bys_agree_and_columnar_allocations_stay_fixed() {
+    for (count, stores, seed) in [
+        (0, 1, 1),
+        (1, 1, 2),
+        (50, 1, 3),
+        (300, 7, 4),
+        (5_000, 300, 5),
+    ] {
+        let rows: Vec<SaleRow> = sales_rows(count, stores, seed);
+        let expected: BTreeMap<String, Aggregate> = group_rows(&rows).into_iter().collect();
+        assert_eq!(
+            expected
+                .values()
+                .map(|aggregate| aggregate.rows)
+                .sum::<u64>(),
+            count as u64
+        );
+
+        let columns: SalesColumns = SalesColumns::from_rows(&rows);
+        assert_eq!(columns.len(), count);
+        let from_codes: BTreeMap<String, Aggregate> = group_codes(&columns)
+            .into_iter()
+            .map(|(code, aggregate)| (columns.dictionary[code as usize].clone(), aggregate))
+            .collect();
+        assert_eq!(from_codes, expected, "{} rows", count);
+
+        let grouped: GroupedColumns = group_columns(&columns);
+        assert!(grouped.store.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(
+            grouped.to_map(&columns.dictionary),
+            expected,
+            "{} rows",
+            count
+        );
+
+        check_runs(&columns.store);
+    }
+
+    // Twenty times the rows over the same stores: the same buffers
+    let small: SalesColumns = SalesColumns::from_rows(&sales_rows(2_500, 100, 6));
+    let large: SalesColumns = SalesColumns::from_rows(&sales_rows(50_000, 100, 7));
+    assert_eq!(
+        allocations(|| group_columns(&small)),
+        allocations(|| group_columns(&large))
+    );
+
+    // The row version clones one String per store
+    let rows: Vec<SaleRow> = sales_rows(50_000, 500, 8);
+    assert!(allocations(|| group_rows(&rows)) >= 500);
+}
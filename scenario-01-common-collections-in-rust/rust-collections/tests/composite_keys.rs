@@ -0,0 +1,110 @@
+//! The three `TenantDirectory` layouts against each other: random inserts,
+//! lookups, listings, pages and tenant removals must agree entry for entry,
+//! and paging through a tenant must visit each of its users exactly once.
+
+use collections_demo::composite_keys_examples::{
+    LastLogin, Tenant, TenantDirectory, User, directory, layouts,
+};
+use demo_framework::rng::Lcg;
+use std::collections::BTreeMap;
+
+#[test]
+fn layouts_agree_under_random_operations() {
+    let mut directories: Vec<Box<dyn TenantDirectory>> = layouts(&[]);
+    let mut rng: Lcg = Lcg::new(17);
+
+    for step in 0..20_000u64 {
+        let tenant: Tenant = rng.below(12) as Tenant;
+        let user: User = rng.below(64) as User;
+        match rng.below(100) {
+            0..60 => {
+                let previous: Vec<Option<LastLogin>> = directories
+                    .iter_mut()
+                    .map(|directory| directory.insert(tenant, user, step))
+                    .collect();
+                assert!(previous.windows(2).all(|pair| pair[0] == pair[1]));
+            }
+            60..80 => {
+                let found: Vec<Option<LastLogin>> = directories
+                    .iter()
+                    .map(|directory| directory.get(tenant, user))
+                    .collect();
+                assert!(found.windows(2).all(|pair| pair[0] == pair[1]));
+            }
+            80..90 => {
+                let after: Option<User> = (rng.below(2) == 0).then_some(user);
+                let pages: Vec<Vec<(User, LastLogin)>> = directories
+                    .iter()
+                    .map(|directory| directory.page(tenant, after, 5))
+                    .collect();
+                assert!(pages.windows(2).all(|pair| pair[0] == pair[1]));
+            }
+            90..99 => {
+                let listings: Vec<Vec<(User, LastLogin)>> = directories
+                    .iter()
+                    .map(|directory| directory.users_of(tenant).0)
+                    .collect();
+                assert!(listings.windows(2).all(|pair| pair[0] == pair[1]));
+                assert!(listings[0].is_sorted());
+            }
+            _ => {
+                let removed: Vec<usize> = directories
+                    .iter_mut()
+                    .map(|directory| directory.remove_tenant(tenant))
+                    .collect();
+                assert!(removed.windows(2).all(|pair| pair[0] == pair[1]));
+            }
+        }
+        let lens: Vec<usize> = directories
+            .iter()
+            .map(|directory| directory.len())
+            .collect();
+        assert!(
+            lens.windows(2).all(|pair| pair[0] == pair[1]),
+            "step {}",
+            step
+        );
+    }
+}
+
+#[test]
+fn paging_visits_every_user_once() {
+    let entries: Vec<((Tenant, User), LastLogin)> = directory(20, 5);
+    let model: BTreeMap<(Tenant, User), LastLogin> = entries.iter().copied().collect();
+    for directory in layouts(&entries) {
+        for tenant in [0, 3, 19, 20] {
+            let mut paged: Vec<(User, LastLogin)> = Vec::new();
+            let mut after: Option<User> = None;
+            loop {
+                let page: Vec<(User, LastLogin)> = directory.page(tenant, after, 7);
+                assert!(page.len() <= 7);
+                let Some(&(last, _)) = page.last() else {
+                    break;
+                };
+                paged.extend(page);
+                after = Some(last);
+            }
+            let expected: Vec<(User, LastLogin)> = model
+                .range((tenant, 0)..=(tenant, User::MAX))
+                .map(|(&(_, user), &login)| (user, login))
+                .collect();
+            assert_eq!(paged, expected, "{} tenant {}", directory.layout(), tenant);
+            assert_eq!(directory.users_of(tenant).0, expected);
+        }
+        // Nothing comes after the largest user id
+        assert!(directory.page(0, Some(User::MAX), 10).is_empty());
+    }
+}
+
+#[test]
+fn only_the_flat_hashmap_scans_everything_for_one_tenant() {
+    let entries: Vec<((Tenant, User), LastLogin)> = directory(50, 9);
+    for directory in layouts(&entries) {
+        let (users, visited) = directory.users_of(49);
+        if directory.layout() == "HashMap<(T, U), V>" {
+            assert_eq!(visited, entries.len());
+        } else {
+            assert_eq!(visited, users.len());
+        }
+    }
+}
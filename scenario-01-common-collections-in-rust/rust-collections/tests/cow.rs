@@ -0,0 +1,101 @@
+//! The copy-on-write helpers: the Cow normalizer agrees with the String one
+//! and allocates only for lines it changes, `clamp_readings` copies at most
+//! once, and `SharedList` copies only while a snapshot is alive.
+//!
+//! The tracking allocator is installed for this test binary, and the
+//! counters are process-wide, so everything runs in a single test.
+
+use collections_demo::cow_examples::{
+    SharedList, clamp_readings, collapse_whitespace, lowercase_ascii, normalize,
+    normalize_to_string, sample_log_lines, strip_control,
+};
+use demo_framework::alloc::{self, AllocCounts, TrackingAllocator};
+use std::borrow::Cow;
+use std::hint::black_box;
+use std::sync::Arc;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+/// Allocations made while running `work`.
+fn allocations(work: impl FnOnce()) -> u64 {
+    let before: AllocCounts = alloc::counts().expect("the tracking allocator is installed");
+    work();
+    alloc::counts().unwrap().since(before).allocations
+}
+
+#[test]
+fn copy_on_write_copies_only_what_changes() {
+    // Each step borrows clean input and agrees with the eager version
+    assert!(matches!(collapse_whitespace("a b"), Cow::Borrowed("a b")));
+    assert!(matches!(
+        collapse_whitespace(" a b\n"),
+        Cow::Borrowed("a b")
+    ));
+    assert_eq!(collapse_whitespace("a \t b"), "a b");
+    assert_eq!(collapse_whitespace(" \t "), "");
+    assert!(matches!(lowercase_ascii("ok é"), Cow::Borrowed(_)));
+    assert_eq!(lowercase_ascii("OK É"), "ok É");
+    assert_eq!(strip_control("a\u{0}b\tc"), "a?b\tc");
+
+    for dirty_every in [0, 1, 3, 10] {
+        let lines: Vec<String> = sample_log_lines(300, dirty_every);
+        for line in &lines {
+            assert_eq!(normalize(line), normalize_to_string(line), "{line:?}");
+        }
+    }
+    for line in ["", "   ", "A\u{1b}  B ", "x\ty", "already fine"] {
+        assert_eq!(normalize(line), normalize_to_string(line), "{line:?}");
+    }
+
+    // Clean lines allocate nothing; every dirty line costs at most 3
+    let clean: Vec<String> = sample_log_lines(1_000, 0);
+    let cow: u64 = allocations(|| {
+        for line in &clean {
+            black_box(normalize(line));
+        }
+    });
+    assert_eq!(cow, 0);
+    let eager: u64 = allocations(|| {
+        for line in &clean {
+            black_box(normalize_to_string(line));
+        }
+    });
+    assert!(eager >= 3_000, "at least three Strings per line: {eager}");
+
+    let mixed: Vec<String> = sample_log_lines(1_000, 10);
+    let owned: usize = mixed
+        .iter()
+        .filter(|line| matches!(normalize(line), Cow::Owned(_)))
+        .count();
+    let cow: u64 = allocations(|| {
+        for line in &mixed {
+            black_box(normalize(line));
+        }
+    });
+    assert!(owned > 0 && owned < 100, "{owned}");
+    assert!(cow >= owned as u64 && cow <= 3 * owned as u64, "{cow}");
+
+    // to_mut copies the slice once, however many values change
+    let readings: Vec<i32> = vec![5, -1, 200, 7, 300, -9];
+    let mut clamped: Option<Cow<[i32]>> = None;
+    let copies: u64 = allocations(|| clamped = Some(clamp_readings(&readings, 0, 100)));
+    assert_eq!(copies, 1);
+    assert_eq!(*clamped.unwrap(), [5, 0, 100, 7, 100, 0]);
+    let in_range: u64 = allocations(|| {
+        black_box(clamp_readings(&readings[3..4], 0, 100));
+    });
+    assert_eq!(in_range, 0);
+
+    // make_mut copies only while a snapshot shares the current version
+    let mut list: SharedList<u32> = SharedList::new(vec![1, 2, 3]);
+    assert!(!list.update(|items| items[0] = 10));
+    let snapshot: Arc<Vec<u32>> = list.snapshot();
+    assert!(list.update(|items| items.push(4)));
+    assert!(!list.update(|items| items.push(5)));
+    assert_eq!(*snapshot, [10, 2, 3], "the reader's version is untouched");
+    drop(snapshot);
+    assert!(!list.update(|items| items.retain(|&x| x != 2)));
+    assert_eq!(*list.snapshot(), [10, 3, 4, 5]);
+    assert_eq!(list.copies, 1);
+}
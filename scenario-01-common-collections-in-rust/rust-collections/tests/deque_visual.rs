@@ -0,0 +1,174 @@
+//! The ring-buffer model against real `VecDeque`s: after every push and pop
+//! the model must agree on contents, capacity, the `as_slices` split and,
+//! once wrapped, the head slot read off the slice pointers.
+
+use collections_demo::deque_visual::{RingModel, observed_head};
+use demo_framework::rng::Lcg;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+/// Replays `count` pseudo-random pushes and pops on `deque` and `model`,
+/// asserting they agree after each one.
+fn replay<T: Clone + PartialEq + Debug>(
+    mut deque: VecDeque<T>,
+    mut model: RingModel<T>,
+    count: usize,
+    seed: u64,
+    value: impl Fn(u64) -> T,
+) {
+    let mut rng: Lcg = Lcg::new(seed);
+    for step in 0..count {
+        // Pushes slightly outnumber pops, so the buffer fills, wraps and grows
+        match rng.below(9) {
+            0..=1 => {
+                let item: T = value(rng.below(1_000));
+                deque.push_back(item.clone());
+                model.push_back(item);
+            }
+            2..=4 => {
+                let item: T = value(rng.below(1_000));
+                deque.push_front(item.clone());
+                model.push_front(item);
+            }
+            5..=6 => assert_eq!(deque.pop_front(), model.pop_front()),
+            _ => assert_eq!(deque.pop_back(), model.pop_back()),
+        }
+        assert!(
+            model.matches(&deque),
+            "step {}: model {:?} (head {}) vs deque {:?} (capacity {})",
+            step,
+            model.contents(),
+            model.head(),
+            deque,
+            deque.capacity()
+        );
+    }
+}
+
+#[test]
+fn model_matches_vecdeque_for_small_elements() {
+    for seed in 0..20 {
+        replay(VecDeque::new(), RingModel::new(), 400, seed, |n| n as u8);
+        replay(
+            VecDeque::with_capacity(5),
+            RingModel::with_capacity(5),
+            400,
+            seed,
+            |n| n as u8,
+        );
+    }
+}
+
+#[test]
+fn model_matches_vecdeque_for_word_sized_elements() {
+    for seed in 0..20 {
+        replay(VecDeque::new(), RingModel::new(), 400, seed, |n| n);
+        replay(
+            VecDeque::with_capacity(3),
+            RingModel::with_capacity(3),
+            400,
+            seed,
+            |n| n as u32,
+        );
+    }
+}
+
+#[test]
+fn model_matches_vecdeque_for_large_elements() {
+    // Over 1 KiB per element, so the first allocation holds just one
+    for seed in 0..5 {
+        replay(VecDeque::new(), RingModel::new(), 200, seed, |n| {
+            [n as u8; 1100]
+        });
+    }
+}
+
+#[test]
+fn growth_moves_the_shorter_part() {
+    // Head near the end: the two front elements move to the end of the
+    // new buffer
+    let mut deque: VecDeque<u32> = VecDeque::with_capacity(5);
+    let mut model: RingModel<u32> = RingModel::with_capacity(5);
+    for i in 0..5 {
+        deque.push_back(i);
+        model.push_back(i);
+    }
+    for i in 5..8 {
+        deque.pop_front();
+        model.pop_front();
+        deque.push_back(i);
+        model.push_back(i);
+    }
+    assert_eq!(model.head(), 3);
+    assert_eq!(observed_head(&deque), Some(3));
+    deque.push_back(8);
+    model.push_back(8);
+    assert_eq!(deque.capacity(), 10);
+    assert_eq!(model.head(), 8);
+    assert!(model.matches(&deque));
+
+    // Head near the start: the short wrapped part moves past the old end
+    // and the buffer stops wrapping
+    let mut deque: VecDeque<u32> = VecDeque::with_capacity(8);
+    let mut model: RingModel<u32> = RingModel::with_capacity(8);
+    for i in 0..8 {
+        deque.push_back(i);
+        model.push_back(i);
+    }
+    deque.pop_front();
+    model.pop_front();
+    deque.push_back(8);
+    model.push_back(8);
+    assert!(model.is_wrapped());
+    deque.push_back(9);
+    model.push_back(9);
+    assert_eq!(deque.capacity(), 16);
+    assert_eq!(model.head(), 1);
+    assert!(!model.is_wrapped());
+    assert_eq!(deque.as_slices().1.len(), 0);
+    assert!(model.matches(&deque));
+}
+
+#[test]
+fn observed_head_needs_a_wrapped_deque() {
+    let mut deque: VecDeque<u64> = VecDeque::with_capacity(4);
+    deque.extend([1, 2, 3]);
+    assert_eq!(observed_head(&deque), None);
+    deque.push_front(0);
+    assert_eq!(observed_head(&deque), Some(3));
+    assert_eq!(deque.as_slices(), (&[0][..], &[1, 2, 3][..]));
+}
+
+#[test]
+fn render_marks_head_and_tail() {
+    let mut model: RingModel<i8> = RingModel::with_capacity(5);
+    for i in 1..=5 {
+        model.push_back(i);
+    }
+    for _ in 0..3 {
+        let front: i8 = model.pop_front().expect("non-empty");
+        model.push_back(front + 10);
+    }
+    let expected: &str = "\
+capacity 5, len 5, head 3, tail 3, wrapped: 2 in front, 3 at the start
+slot    0    1    2    3    4
+     │ 11 │ 12 │ 13 │  4 │  5 │
+head                   ^
+tail                   ^";
+    assert_eq!(model.render(), expected);
+
+    model.pop_back();
+    model.pop_back();
+    assert!(
+        model
+            .render()
+            .starts_with("capacity 5, len 3, head 3, tail 1, wrapped")
+    );
+    assert!(model.render().contains("│ 11 │  · │  · │  4 │  5 │"));
+
+    let empty: RingModel<i8> = RingModel::new();
+    assert_eq!(
+        empty.render(),
+        "capacity 0, len 0, head 0, tail 0\n(no buffer)"
+    );
+}
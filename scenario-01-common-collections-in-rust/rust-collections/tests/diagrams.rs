@@ -0,0 +1,137 @@
+//! The DOT and Mermaid text the exporters produce, and `--export` mode
+//! itself writing files for a demo run.
+
+use collections_demo::graph_examples::{Csr, practical_dependency_order};
+use collections_demo::heap_visual::heap_diagram;
+use collections_demo::shortest_path_examples::{Path, RoadMap, dijkstra, sample_road_map};
+use collections_demo::union_find_examples::{Edge, kruskal, mst_diagram};
+use demo_framework::capture;
+use demo_framework::diagram::{self, Diagram};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn dot_and_mermaid_for_a_small_tree() {
+    let tree: Diagram = heap_diagram("heap", &[9, 6, 5, 4]);
+    assert!(tree.is_directed());
+    assert_eq!(
+        tree.to_dot(),
+        "\
+digraph \"heap\" {
+    node [shape=box, style=rounded, fontname=\"Helvetica\"];
+    edge [fontname=\"Helvetica\"];
+    n0 [label=\"9 [0]\"];
+    n1 [label=\"6 [1]\"];
+    n2 [label=\"5 [2]\"];
+    n3 [label=\"4 [3]\"];
+    n0 -> n1;
+    n0 -> n2;
+    n1 -> n3;
+}
+"
+    );
+    assert_eq!(
+        tree.to_mermaid(),
+        "\
+%% heap
+flowchart LR
+    n0[\"9 [0]\"]
+    n1[\"6 [1]\"]
+    n2[\"5 [2]\"]
+    n3[\"4 [3]\"]
+    n0 --> n1
+    n0 --> n2
+    n1 --> n3
+"
+    );
+}
+
+#[test]
+fn labels_are_escaped() {
+    let mut diagram: Diagram = Diagram::undirected("quotes");
+    diagram.node(0, "say \"hi\"");
+    diagram.node(1, r"C:\path");
+    diagram.edge(0, 1, Some("\"edge\"".to_string()));
+    assert!(diagram.to_dot().contains(r#"n0 [label="say \"hi\""];"#));
+    assert!(diagram.to_dot().contains(r#"n1 [label="C:\\path"];"#));
+    assert!(diagram.to_dot().contains(r#"n0 -- n1 [label="\"edge\""];"#));
+    assert!(diagram.to_mermaid().contains("n0[\"say #quot;hi#quot;\"]"));
+    assert!(
+        diagram
+            .to_mermaid()
+            .contains("n0 ---|\"#quot;edge#quot;\"| n1")
+    );
+}
+
+#[test]
+fn road_map_draws_each_road_once_with_the_route_highlighted() {
+    let map: RoadMap = sample_road_map();
+    let (from, to) = (map.index("Ashford"), map.index("Glenrock"));
+    let route: Path = dijkstra(&map.graph, from, to).expect("connected");
+    let diagram: Diagram = map.diagram("route", Some(&route));
+    assert_eq!(diagram.node_count(), map.towns.len());
+    assert_eq!(diagram.edge_count(), map.graph.edge_count() / 2);
+
+    let dot: String = diagram.to_dot();
+    let highlighted_edges: usize = dot
+        .lines()
+        .filter(|line| line.contains(" -- ") && line.contains("crimson"))
+        .count();
+    assert_eq!(highlighted_edges, route.nodes.len() - 1);
+    for &node in &route.nodes {
+        assert!(dot.contains(&format!(
+            "n{} [label=\"{}\", color=crimson",
+            node, map.towns[node].name
+        )));
+    }
+}
+
+#[test]
+fn mst_diagram_styles_the_tree_edges_by_position() {
+    let edges: [Edge; 3] = [(0, 1, 4), (1, 2, 1), (0, 2, 2)];
+    let tree: Vec<Edge> = kruskal(3, &edges);
+    let diagram: Diagram = mst_diagram("mst", 3, &edges, &tree, |node| node.to_string());
+    // The tree keeps 1-2 and 0-2, the second and third edges drawn
+    assert!(
+        diagram
+            .to_mermaid()
+            .ends_with("    linkStyle 1,2 stroke:crimson,stroke-width:3px\n")
+    );
+    assert!(!diagram.to_mermaid().contains("classDef"));
+}
+
+#[test]
+fn csr_diagram_keeps_every_edge() {
+    let csr: Csr = Csr::from_edges(3, &[(0, 1), (0, 2), (1, 2)]);
+    let diagram: Diagram = csr.diagram("deps", |node| format!("p{}", node));
+    assert_eq!(diagram.edge_count(), 3);
+    assert!(diagram.to_dot().contains("    n1 -> n2;\n"));
+}
+
+#[test]
+fn export_mode_writes_both_files() {
+    let dir: PathBuf = env::temp_dir().join(format!("diagrams-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).expect("temp dir");
+
+    let plain: String = capture(practical_dependency_order);
+    diagram::enable(dir.clone());
+    let exported: String = capture(practical_dependency_order);
+    diagram::disable();
+
+    // The only difference in the output is the line naming the files
+    assert!(!plain.contains("Exported"));
+    let extra: Vec<&str> = exported
+        .lines()
+        .filter(|line| !plain.lines().any(|plain_line| plain_line == *line))
+        .collect();
+    assert_eq!(extra.len(), 1);
+    assert!(extra[0].starts_with("Exported dependencies: "));
+
+    let dot: String = fs::read_to_string(dir.join("dependencies.dot")).expect("dot file");
+    let mermaid: String = fs::read_to_string(dir.join("dependencies.mmd")).expect("mmd file");
+    assert!(dot.starts_with("digraph \"dependencies\" {"));
+    assert_eq!(dot.matches(" -> ").count(), 8);
+    assert_eq!(mermaid.matches(" --> ").count(), 8);
+    fs::remove_dir_all(&dir).expect("clean up");
+}
@@ -0,0 +1,80 @@
+//! `run_demo` as another program would call it: whole modules, single demos
+//! by bare and qualified name, recorded values, and unknown names.
+
+use collections_demo::run_demo;
+use demo_framework::capture;
+use demo_framework::embed::{DemoOutput, UnknownDemo};
+
+/// The `DEMO: <name>` header of every section in `text`.
+fn demos(text: &str) -> Vec<&str> {
+    text.lines()
+        .filter_map(|line| line.strip_prefix("DEMO: "))
+        .collect()
+}
+
+#[test]
+fn a_module_runs_every_demo() {
+    let output: DemoOutput = run_demo("vecdeque_examples").unwrap();
+    assert_eq!(
+        output.text,
+        capture(collections_demo::vecdeque_examples::run_all)
+    );
+    // `_examples` may be left off
+    assert_eq!(run_demo("vecdeque").unwrap().text, output.text);
+}
+
+#[test]
+fn a_demo_runs_alone() {
+    let output: DemoOutput = run_demo("rotating_deques").unwrap();
+    assert_eq!(demos(&output.text), vec!["rotating_deques"]);
+    assert!(output.text.contains("rotate_left(1):"));
+
+    let qualified: DemoOutput = run_demo("vecdeque_examples::rotating_deques").unwrap();
+    assert_eq!(qualified.text, output.text);
+}
+
+#[test]
+fn a_shared_demo_name_picks_the_first_module_unless_qualified() {
+    // Both hashmap_examples and btreemap_examples have entry_api_examples;
+    // hashmap comes first in the registry
+    let bare: DemoOutput = run_demo("entry_api_examples").unwrap();
+    let hashmap: DemoOutput = run_demo("hashmap::entry_api_examples").unwrap();
+    let btreemap: DemoOutput = run_demo("btreemap::entry_api_examples").unwrap();
+    assert_eq!(demos(&btreemap.text), vec!["entry_api_examples"]);
+    assert_ne!(hashmap.text, btreemap.text);
+    // HashMap iteration order varies between runs, so compare the first line
+    assert_eq!(bare.text.lines().nth(4), hashmap.text.lines().nth(4));
+}
+
+#[test]
+fn recorded_values_come_back_as_data() {
+    let output: DemoOutput = run_demo("grid_pathfinding").unwrap();
+    assert_eq!(output.get("dijkstra_cost"), output.get("a_star_cost"));
+    let dijkstra: usize = output.get("dijkstra_settled").unwrap().parse().unwrap();
+    let a_star: usize = output.get("a_star_settled").unwrap().parse().unwrap();
+    assert!(a_star < dijkstra);
+    assert_eq!(output.data.len(), 4);
+
+    // Nothing leaks into the next run, and demos without records have none
+    assert!(run_demo("rotating_deques").unwrap().data.is_empty());
+}
+
+#[test]
+fn unknown_names_are_errors() {
+    for name in [
+        "no_such_demo",
+        "no_such_module::rotating_deques",
+        "vec_examples::rotating_deques",
+    ] {
+        assert_eq!(
+            run_demo(name),
+            Err(UnknownDemo {
+                name: name.to_string()
+            })
+        );
+    }
+    assert_eq!(
+        run_demo("nope").unwrap_err().to_string(),
+        "no module or demo named `nope`"
+    );
+}
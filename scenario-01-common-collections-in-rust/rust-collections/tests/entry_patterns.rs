@@ -0,0 +1,148 @@
+//! The entry-API patterns: slug caching, adjacency lists and degrees, the
+//! two-level visit map staying free of empty entries, ticket transitions
+//! through Entry::remove, and the inverted index against a linear scan.
+
+use collections_demo::entry_patterns_examples::{
+    Action, InvertedIndex, SAMPLE_DOCS, Ticket, Visits, adjacency_lists, advance, build_index,
+    cached_slug, in_degrees, record_visit, remove_visit, search, slugify, tokenize,
+};
+use std::collections::HashMap;
+
+#[test]
+fn slugs_are_computed_once_per_title() {
+    assert_eq!(slugify("  Hello,   World! "), "hello-world");
+    assert_eq!(slugify("!!!"), "");
+
+    let mut cache: HashMap<String, String> = HashMap::new();
+    let mut computed: u32 = 0;
+    for title in ["A b", "C", "A b", "A b"] {
+        cached_slug(&mut cache, title.to_string(), &mut computed);
+    }
+    assert_eq!(computed, 2);
+    assert_eq!(cache["A b"], "a-b");
+}
+
+#[test]
+fn adjacency_lists_register_every_node() {
+    let edges: [(&str, &str); 4] = [("a", "b"), ("a", "c"), ("b", "c"), ("c", "d")];
+
+    let directed: HashMap<&str, Vec<&str>> = adjacency_lists(&edges, true);
+    assert_eq!(directed.len(), 4);
+    assert_eq!(directed["a"], ["b", "c"]);
+    assert!(directed["d"].is_empty(), "a sink is still a key");
+
+    let undirected: HashMap<&str, Vec<&str>> = adjacency_lists(&edges, false);
+    assert_eq!(undirected["c"], ["a", "b", "d"]);
+    let endpoints: usize = undirected.values().map(Vec::len).sum();
+    assert_eq!(endpoints, 2 * edges.len());
+
+    let degrees: HashMap<&str, usize> = in_degrees(&edges);
+    assert_eq!(degrees["c"], 2);
+    assert!(!degrees.contains_key("a"));
+    assert_eq!(degrees.values().sum::<usize>(), edges.len());
+}
+
+#[test]
+fn visit_map_never_keeps_empty_entries() {
+    let mut visits: Visits = HashMap::new();
+    assert_eq!(record_visit(&mut visits, "fr", "paris"), 1);
+    assert_eq!(record_visit(&mut visits, "fr", "paris"), 2);
+    assert_eq!(record_visit(&mut visits, "fr", "lyon"), 1);
+
+    assert!(!remove_visit(&mut visits, "jp", "osaka"));
+    assert!(!remove_visit(&mut visits, "fr", "nice"));
+    assert!(remove_visit(&mut visits, "fr", "lyon"));
+    assert!(!visits["fr"].contains_key("lyon"));
+
+    assert!(remove_visit(&mut visits, "fr", "paris"));
+    assert!(remove_visit(&mut visits, "fr", "paris"));
+    assert!(visits.is_empty(), "the country goes with its last city");
+    assert!(!remove_visit(&mut visits, "fr", "paris"));
+}
+
+#[test]
+fn tickets_move_through_their_states() {
+    let mut tickets: HashMap<u32, Ticket> = HashMap::from([(
+        7,
+        Ticket::Open {
+            title: "t".to_string(),
+        },
+    )]);
+
+    assert!(advance(&mut tickets, 7, Action::Close).is_err());
+    assert_eq!(tickets[&7].state(), "open", "a refused action leaves it be");
+
+    assert_eq!(advance(&mut tickets, 7, Action::Assign("a")), Ok(None));
+    assert_eq!(advance(&mut tickets, 7, Action::Assign("b")), Ok(None));
+    assert_eq!(advance(&mut tickets, 7, Action::Resolve("done")), Ok(None));
+    assert_eq!(
+        tickets[&7],
+        Ticket::Resolved {
+            title: "t".to_string(),
+            owner: "b".to_string(),
+            resolution: "done".to_string(),
+        }
+    );
+
+    assert_eq!(advance(&mut tickets, 7, Action::Reopen), Ok(None));
+    assert_eq!(tickets[&7].state(), "open");
+    advance(&mut tickets, 7, Action::Assign("c")).unwrap();
+    advance(&mut tickets, 7, Action::Resolve("again")).unwrap();
+
+    let closed: Option<Ticket> = advance(&mut tickets, 7, Action::Close).unwrap();
+    assert_eq!(closed.map(|ticket| ticket.state()), Some("resolved"));
+    assert!(tickets.is_empty());
+    assert_eq!(
+        advance(&mut tickets, 7, Action::Reopen),
+        Err("no ticket #7".to_string())
+    );
+}
+
+/// Documents containing every query word, by scanning each document.
+fn scan(query: &str) -> Vec<u32> {
+    let words: Vec<String> = tokenize(query).collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+    SAMPLE_DOCS
+        .iter()
+        .filter(|(_, text)| {
+            let doc: Vec<String> = tokenize(text).collect();
+            words.iter().all(|word| doc.contains(word))
+        })
+        .map(|&(id, _)| id)
+        .collect()
+}
+
+#[test]
+fn inverted_index_matches_a_linear_scan() {
+    let index: InvertedIndex = build_index(&SAMPLE_DOCS);
+
+    // Every position points back at its word
+    for (word, postings) in &index {
+        assert!(postings.windows(2).all(|pair| pair[0].doc < pair[1].doc));
+        for posting in postings {
+            let text: &str = SAMPLE_DOCS
+                .iter()
+                .find(|(id, _)| *id == posting.doc)
+                .unwrap()
+                .1;
+            let tokens: Vec<String> = tokenize(text).collect();
+            for &position in &posting.positions {
+                assert_eq!(&tokens[position as usize], word);
+            }
+        }
+    }
+
+    for query in [
+        "a",
+        "the key",
+        "SORTED keys",
+        "hashmap btreemap",
+        "missing",
+        "",
+        "it, it",
+    ] {
+        assert_eq!(search(&index, query), scan(query), "{query:?}");
+    }
+}
@@ -0,0 +1,85 @@
+//! The four enum-keyed layouts agree, the hand-rolled table indexes by
+//! discriminant in declaration order, and the traffic table tallies
+//! status classes.
+
+use collections_demo::enum_map_examples::{
+    Level, LevelTable, Method, StatusClass, TrafficTable, count_array, count_btreemap,
+    count_enum_map, count_hashmap, log_levels, tally_traffic,
+};
+use enum_map::{Enum, EnumMap};
+use std::collections::{BTreeMap, HashMap};
+
+#[test]
+fn all_layouts_count_the_same() {
+    for seed in 0..20 {
+        let levels: Vec<Level> = log_levels(500 + seed as usize * 37, seed);
+        let hashed: HashMap<Level, u64> = count_hashmap(&levels);
+        let sorted: BTreeMap<Level, u64> = count_btreemap(&levels);
+        let array: LevelTable<u64> = count_array(&levels);
+        let enum_map: EnumMap<Level, u64> = count_enum_map(&levels);
+
+        for level in Level::ALL {
+            let expected: u64 = levels.iter().filter(|&&l| l == level).count() as u64;
+            assert_eq!(hashed.get(&level).copied().unwrap_or(0), expected);
+            assert_eq!(sorted.get(&level).copied().unwrap_or(0), expected);
+            assert_eq!(array[level], expected);
+            assert_eq!(enum_map[level], expected);
+        }
+        assert_eq!(
+            array.iter().map(|(_, &n)| n).sum::<u64>(),
+            levels.len() as u64
+        );
+    }
+}
+
+#[test]
+fn level_table_follows_declaration_order() {
+    assert_eq!(Level::ALL.len(), Level::COUNT);
+    for (i, level) in Level::ALL.into_iter().enumerate() {
+        assert_eq!(level as usize, i);
+        assert_eq!(level.into_usize(), i, "EnumMap uses the same slot");
+    }
+
+    let table: LevelTable<usize> = LevelTable::from_fn(|level| level as usize * 10);
+    let pairs: Vec<(Level, usize)> = table.iter().map(|(level, &v)| (level, v)).collect();
+    assert_eq!(
+        pairs,
+        [
+            (Level::Trace, 0),
+            (Level::Debug, 10),
+            (Level::Info, 20),
+            (Level::Warn, 30),
+            (Level::Error, 40),
+        ]
+    );
+}
+
+#[test]
+fn traffic_table_tallies_by_status_class() {
+    assert_eq!(StatusClass::of(204), Some(StatusClass::Success));
+    assert_eq!(StatusClass::of(399), Some(StatusClass::Redirect));
+    assert_eq!(StatusClass::of(404), Some(StatusClass::ClientError));
+    assert_eq!(StatusClass::of(599), Some(StatusClass::ServerError));
+    assert_eq!(StatusClass::of(101), None);
+    assert_eq!(StatusClass::of(600), None);
+
+    let requests: [(Method, u16); 6] = [
+        (Method::Get, 200),
+        (Method::Get, 200),
+        (Method::Get, 503),
+        (Method::Post, 404),
+        (Method::Delete, 301),
+        (Method::Put, 100),
+    ];
+    let table: TrafficTable = tally_traffic(&requests);
+
+    assert_eq!(table[Method::Get][StatusClass::Success], 2);
+    assert_eq!(table[Method::Get][StatusClass::ServerError], 1);
+    assert_eq!(table[Method::Post][StatusClass::ClientError], 1);
+    assert_eq!(table[Method::Delete][StatusClass::Redirect], 1);
+    assert_eq!(
+        table.values().flat_map(|row| row.values()).sum::<u64>(),
+        5,
+        "the informational response is skipped"
+    );
+}
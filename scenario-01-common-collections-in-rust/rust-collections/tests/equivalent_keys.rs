@@ -0,0 +1,113 @@
+//! Lookups through `Equivalent` views: they hash like the keys they stand
+//! for, find the same entries as owned-key lookups in hashbrown and
+//! IndexMap, and allocate nothing. The router agrees with a linear scan.
+//!
+//! The tracking allocator is installed for this test binary, and the
+//! counters are process-wide, so everything runs in a single test.
+
+use collections_demo::equivalent_keys_examples::{
+    CaseInsensitive, CaseInsensitiveStr, ROUTES, Route, RouteRef, TenantUser, TenantUserRef,
+    parse_request_line, route_table, sample_directory, sample_requests,
+};
+use demo_framework::alloc::{self, AllocCounts, TrackingAllocator};
+use indexmap::{IndexMap, IndexSet};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+#[test]
+fn views_find_the_same_entries_without_allocating() {
+    let entries: Vec<(TenantUser, u32)> = sample_directory(500);
+    let brown: hashbrown::HashMap<TenantUser, u32> = entries.iter().cloned().collect();
+    let index: IndexMap<TenantUser, u32> = entries.iter().cloned().collect();
+    let set: IndexSet<TenantUser> = entries.iter().map(|(key, _)| key.clone()).collect();
+
+    // Views hash like their keys under any hasher
+    let state: RandomState = RandomState::new();
+    for ((tenant, name), _) in &entries {
+        let view = TenantUserRef {
+            tenant: *tenant,
+            name,
+        };
+        assert_eq!(
+            state.hash_one(view),
+            state.hash_one((*tenant, name.clone()))
+        );
+    }
+    for (spelling, key) in [("mixed Case", "Mixed case"), ("ABC", "abc"), ("", "")] {
+        assert_eq!(
+            state.hash_one(CaseInsensitiveStr(spelling)),
+            state.hash_one(CaseInsensitive(key.to_string()))
+        );
+    }
+
+    // Hits, misses with the right name under the wrong tenant, and
+    // unknown names: all without building a key
+    let probes: Vec<(u32, String)> = (0..40)
+        .flat_map(|tenant| {
+            [
+                (tenant, format!("user{:04}", tenant * 3)),
+                (tenant, format!("user{:04}", 900 + tenant)),
+            ]
+        })
+        .collect();
+    let mut found: Vec<Option<u32>> = Vec::with_capacity(probes.len());
+    let before: AllocCounts = alloc::counts().expect("the tracking allocator is installed");
+    for (tenant, name) in &probes {
+        let view = TenantUserRef {
+            tenant: *tenant,
+            name,
+        };
+        let value: Option<u32> = brown.get(&view).copied();
+        assert_eq!(index.get(&view).copied(), value);
+        assert_eq!(set.contains(&view), value.is_some());
+        found.push(value);
+    }
+    let viewed: AllocCounts = alloc::counts().unwrap().since(before);
+    assert_eq!(viewed.allocations, 0, "{viewed:?}");
+
+    for ((tenant, name), value) in probes.iter().zip(&found) {
+        assert_eq!(*value, brown.get(&(*tenant, name.clone())).copied());
+    }
+    assert_eq!(found.iter().filter(|value| value.is_some()).count(), 2);
+
+    // The case-insensitive view finds every spelling and nothing else
+    let mut emails: IndexMap<CaseInsensitive, u32> = IndexMap::new();
+    emails.insert(CaseInsensitive("Alice".to_string()), 1);
+    emails.insert(CaseInsensitive("bob".to_string()), 2);
+    let before: AllocCounts = alloc::counts().unwrap();
+    assert_eq!(emails.get(&CaseInsensitiveStr("aLiCe")), Some(&1));
+    assert_eq!(emails.get(&CaseInsensitiveStr("BOB")), Some(&2));
+    assert_eq!(emails.get(&CaseInsensitiveStr("bobby")), None);
+    assert_eq!(emails.get(&CaseInsensitiveStr("")), None);
+    assert_eq!(alloc::counts().unwrap().since(before).allocations, 0);
+
+    // The router agrees with matching every route by hand
+    let routes: IndexMap<Route, &str> = route_table();
+    assert_eq!(routes.len(), ROUTES.len());
+    for line in sample_requests(300).iter().map(String::as_str).chain([
+        "GET",
+        "",
+        "GET /users?",
+        "POST /users extra words",
+    ]) {
+        let parsed: Option<RouteRef> = parse_request_line(line);
+        let expected: Option<usize> = parsed.and_then(|route| {
+            ROUTES
+                .iter()
+                .position(|&(method, path, _)| method == route.method && path == route.path)
+        });
+        assert_eq!(
+            parsed.and_then(|route| routes.get_index_of(&route)),
+            expected,
+            "{line:?}"
+        );
+    }
+    assert_eq!(
+        parse_request_line("GET /users?").map(|route| route.path),
+        Some("/users")
+    );
+    assert!(parse_request_line("FETCH /users HTTP/1.1").is_none());
+}
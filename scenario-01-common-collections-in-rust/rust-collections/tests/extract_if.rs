@@ -0,0 +1,82 @@
+//! Behaviour of HashMap::extract_if that the demos describe but can't show
+//! without panicking: a predicate that panics part-way stops the extraction,
+//! and the fallback leaves the map untouched.
+
+use collections_demo::extract_if_examples::{extract_matching, extract_matching_fallback};
+use std::collections::HashMap;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+fn numbers() -> HashMap<u32, u32> {
+    (0..100).map(|n| (n, n)).collect()
+}
+
+#[test]
+fn extract_matching_agrees_with_fallback() {
+    let mut map: HashMap<u32, u32> = numbers();
+    let mut fallback_map: HashMap<u32, u32> = numbers();
+
+    let extracted = extract_matching(&mut map, |k, _| k.is_multiple_of(3));
+    let fallback = extract_matching_fallback(&mut fallback_map, |k, _| k.is_multiple_of(3));
+
+    assert_eq!(extracted, fallback);
+    assert_eq!(map, fallback_map);
+    assert_eq!(extracted.len(), 34);
+    assert!(map.keys().all(|k| !k.is_multiple_of(3)));
+}
+
+#[test]
+fn panicking_predicate_leaves_a_partial_drain() {
+    let mut map: HashMap<u32, u32> = numbers();
+    let mut extracted: Vec<(u32, u32)> = Vec::new();
+    let mut calls: u32 = 0;
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        for entry in map.extract_if(|_, _| {
+            calls += 1;
+            if calls == 10 {
+                panic!("predicate failed on its 10th call");
+            }
+            true
+        }) {
+            extracted.push(entry);
+        }
+    }));
+
+    assert!(result.is_err());
+    // The 9 entries yielded before the panic are gone; everything else,
+    // including the entry the predicate panicked on, is still there
+    assert_eq!(extracted.len(), 9);
+    assert_eq!(map.len(), 100 - 9);
+    for (k, _) in &extracted {
+        assert!(!map.contains_key(k));
+    }
+}
+
+#[test]
+fn panicking_predicate_leaves_fallback_untouched() {
+    let mut map: HashMap<u32, u32> = numbers();
+    let mut calls: u32 = 0;
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        extract_matching_fallback(&mut map, |_, _| {
+            calls += 1;
+            if calls == 10 {
+                panic!("predicate failed on its 10th call");
+            }
+            true
+        })
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(map, numbers());
+}
+
+#[test]
+fn dropping_the_iterator_early_keeps_the_rest() {
+    let mut map: HashMap<u32, u32> = numbers();
+
+    let taken: Vec<(u32, u32)> = map.extract_if(|_, _| true).take(5).collect();
+
+    assert_eq!(taken.len(), 5);
+    assert_eq!(map.len(), 95);
+}
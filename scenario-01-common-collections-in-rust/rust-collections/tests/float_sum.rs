@@ -0,0 +1,109 @@
+//! The summation algorithms against an exact integer reference (proptest):
+//! `exact_sum` is correctly rounded in any order, and the others stay within
+//! their error bounds. Also the map-order claim from the demo, on fixed data.
+
+use collections_demo::float_sum_examples::{
+    exact_sum, kahan_sum, naive_sum, neumaier_sum, pairwise_sum,
+};
+use collections_demo::iteration_order_examples::sales;
+use proptest::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// Largest relative error of one rounding.
+const U: f64 = f64::EPSILON / 2.0;
+
+/// Values `m * 2^e` with scale `2^-30` as the finest bit, so their sum is
+/// an exact `i128` count of `2^-30` units.
+fn dyadic() -> impl Strategy<Value = (i128, f64)> {
+    (-(1i64 << 40)..(1i64 << 40), -30i32..=30).prop_map(|(mantissa, exponent)| {
+        let units: i128 = (mantissa as i128) << (exponent + 30);
+        (units, mantissa as f64 * 2f64.powi(exponent))
+    })
+}
+
+/// The exact sum of the values, rounded once: `i128 as f64` rounds to
+/// nearest, and scaling by a power of two is exact.
+fn reference(units: &[i128]) -> f64 {
+    units.iter().sum::<i128>() as f64 * 2f64.powi(-30)
+}
+
+proptest! {
+    #[test]
+    fn exact_sum_is_correctly_rounded_in_any_order(
+        (values, shuffled) in prop::collection::vec(dyadic(), 0..200)
+            .prop_flat_map(|values| (Just(values.clone()), Just(values).prop_shuffle()))
+    ) {
+        let units: Vec<i128> = values.iter().map(|&(units, _)| units).collect();
+        let expected: f64 = reference(&units);
+        let sum: f64 = exact_sum(values.iter().map(|&(_, x)| x));
+        prop_assert_eq!(sum.to_bits(), expected.to_bits(), "{} vs {}", sum, expected);
+        let reordered: f64 = exact_sum(shuffled.iter().map(|&(_, x)| x));
+        prop_assert_eq!(reordered.to_bits(), expected.to_bits());
+    }
+
+    #[test]
+    fn the_other_sums_stay_within_their_bounds(
+        values in prop::collection::vec(dyadic(), 1..500)
+    ) {
+        let units: Vec<i128> = values.iter().map(|&(units, _)| units).collect();
+        let exact: f64 = reference(&units);
+        let floats: Vec<f64> = values.iter().map(|&(_, x)| x).collect();
+        let magnitude: f64 = floats.iter().map(|x| x.abs()).sum();
+        let n: f64 = floats.len() as f64;
+
+        // Plus half an ulp of the exact sum for the reference's own rounding
+        let rounding: f64 = U * exact.abs();
+        let naive_error: f64 = (naive_sum(floats.iter().copied()) - exact).abs();
+        prop_assert!(naive_error <= 1.01 * n * U * magnitude + rounding);
+        let depth: f64 = (n / 8.0).log2().ceil().max(0.0) + 8.0;
+        let pairwise_error: f64 = (pairwise_sum(&floats) - exact).abs();
+        prop_assert!(pairwise_error <= 1.01 * depth * U * magnitude + rounding);
+        let kahan_error: f64 = (kahan_sum(floats.iter().copied()) - exact).abs();
+        prop_assert!(kahan_error <= (2.0 * U + 4.0 * n * U * U) * magnitude + rounding);
+        let neumaier_error: f64 = (neumaier_sum(floats.iter().copied()) - exact).abs();
+        prop_assert!(neumaier_error <= 2.0 * U * exact.abs() + 4.0 * n * U * U * magnitude + rounding);
+    }
+}
+
+#[test]
+fn edge_cases() {
+    assert_eq!(exact_sum([]), 0.0);
+    assert_eq!(pairwise_sum(&[]), 0.0);
+    // 1 + 2^-53 is a tie that rounds down to even; 2^-106 breaks it upward
+    let tie: [f64; 3] = [1.0, 2f64.powi(-53), 2f64.powi(-106)];
+    assert_eq!(exact_sum(tie), 1.0 + f64::EPSILON);
+    assert_eq!(exact_sum([1.0, 2f64.powi(-53)]), 1.0);
+    // A large value after small ones: classic Kahan loses them, Neumaier doesn't
+    let cancelling: [f64; 4] = [1.0, 1e100, 1.0, -1e100];
+    assert_eq!(naive_sum(cancelling), 0.0);
+    assert_eq!(kahan_sum(cancelling), 0.0);
+    assert_eq!(neumaier_sum(cancelling), 2.0);
+    assert_eq!(exact_sum(cancelling), 2.0);
+}
+
+#[test]
+fn compensated_sums_ignore_map_order() {
+    let arrivals: Vec<(u64, f64)> = sales(10_000, 9);
+    let sorted: BTreeMap<u64, f64> = arrivals.iter().copied().collect();
+    let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+    let by_key: Vec<f64> = sorted.values().copied().collect();
+    let reversed: Vec<f64> = by_key.iter().rev().copied().collect();
+    // The same values in a different order give a different naive total
+    assert_ne!(
+        naive_sum(by_key.iter().copied()),
+        naive_sum(reversed.iter().copied())
+    );
+
+    let orders: [Vec<f64>; 4] = [
+        hash.values().copied().collect(),
+        arrivals.iter().map(|&(_, x)| x).collect(),
+        reversed,
+        by_key.clone(),
+    ];
+    let exact: f64 = exact_sum(by_key.iter().copied());
+    for values in &orders {
+        assert_eq!(kahan_sum(values.iter().copied()), exact);
+        assert_eq!(neumaier_sum(values.iter().copied()), exact);
+        assert_eq!(exact_sum(values.iter().copied()), exact);
+    }
+}
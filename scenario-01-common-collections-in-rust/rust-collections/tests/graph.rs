@@ -0,0 +1,145 @@
+//! The four graph representations against each other, and BFS/DFS against
+//! simple references, on random graphs.
+
+use collections_demo::graph_examples::{
+    AdjacencyList, Csr, Edge, HashGraph, adjacency_list, adjacency_matrix, bfs, dfs, edge_checksum,
+    hash_graph, random_edges,
+};
+use collections_demo::grid_examples::Grid;
+use std::collections::HashSet;
+
+/// Shortest hop count from `start` to every node, by relaxing every edge
+/// until nothing changes (Bellman-Ford with unit weights).
+fn distances(nodes: usize, edges: &[Edge], start: usize) -> Vec<Option<usize>> {
+    let mut distance: Vec<Option<usize>> = vec![None; nodes];
+    distance[start] = Some(0);
+    let mut changed: bool = true;
+    while changed {
+        changed = false;
+        for &(from, to) in edges {
+            if let Some(d) = distance[from]
+                && distance[to].is_none_or(|old| d + 1 < old)
+            {
+                distance[to] = Some(d + 1);
+                changed = true;
+            }
+        }
+    }
+    distance
+}
+
+/// Recursive preorder DFS, the textbook version.
+fn dfs_recursive(graph: &AdjacencyList, node: usize, seen: &mut Vec<bool>, order: &mut Vec<usize>) {
+    seen[node] = true;
+    order.push(node);
+    for &next in &graph[node] {
+        if !seen[next] {
+            dfs_recursive(graph, next, seen, order);
+        }
+    }
+}
+
+fn sorted(mut edges: Vec<Edge>) -> Vec<Edge> {
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+}
+
+#[test]
+fn representations_hold_the_same_neighbors() {
+    for seed in 0..20 {
+        let nodes: usize = 1 + seed as usize * 3;
+        let edges: Vec<Edge> = random_edges(nodes, 3, seed);
+        let hashed: HashGraph<usize> = hash_graph(&edges);
+        let list: AdjacencyList = adjacency_list(nodes, &edges);
+        let csr: Csr = Csr::from_edges(nodes, &edges);
+        assert_eq!(csr.node_count(), nodes);
+        assert_eq!(csr.edge_count(), edges.len());
+        for (node, neighbors) in list.iter().enumerate() {
+            // List and CSR keep input order; the HashMap's Vecs do too
+            assert_eq!(csr.neighbor_slice(node), &neighbors[..]);
+            assert_eq!(
+                hashed.get(&node).map_or(&[][..], |v| &v[..]),
+                &neighbors[..]
+            );
+        }
+        let checksum: usize = edges.iter().map(|&(_, to)| to).sum();
+        assert_eq!(edge_checksum(&list, nodes), checksum);
+        assert_eq!(edge_checksum(&csr, nodes), checksum);
+        assert_eq!(edge_checksum(&hashed, nodes), checksum);
+    }
+}
+
+#[test]
+fn bfs_visits_reachable_nodes_by_distance() {
+    for seed in 0..20 {
+        let nodes: usize = 2 + seed as usize * 5;
+        let edges: Vec<Edge> = sorted(random_edges(nodes, 2, seed));
+        let expected: Vec<Option<usize>> = distances(nodes, &edges, 0);
+        let reachable: usize = expected.iter().filter(|d| d.is_some()).count();
+
+        let list: AdjacencyList = adjacency_list(nodes, &edges);
+        let matrix: Grid<bool> = adjacency_matrix(nodes, &edges);
+        let csr: Csr = Csr::from_edges(nodes, &edges);
+        let hashed: HashGraph<usize> = hash_graph(&edges);
+        let order: Vec<usize> = bfs(&list, 0);
+
+        assert_eq!(order.len(), reachable);
+        assert!(order.iter().all(|&node| expected[node].is_some()));
+        // Distances never decrease along the BFS order
+        assert!(order.is_sorted_by_key(|&node| expected[node]));
+        // Sorted, deduplicated edges: every representation agrees exactly
+        assert_eq!(bfs(&matrix, 0), order);
+        assert_eq!(bfs(&csr, 0), order);
+        assert_eq!(bfs(&hashed, 0), order);
+    }
+}
+
+#[test]
+fn dfs_matches_the_recursive_version() {
+    for seed in 0..20 {
+        let nodes: usize = 2 + seed as usize * 5;
+        let edges: Vec<Edge> = sorted(random_edges(nodes, 2, seed));
+        let list: AdjacencyList = adjacency_list(nodes, &edges);
+        let mut expected: Vec<usize> = Vec::new();
+        dfs_recursive(&list, 0, &mut vec![false; nodes], &mut expected);
+
+        assert_eq!(dfs(&list, 0), expected);
+        assert_eq!(dfs(&adjacency_matrix(nodes, &edges), 0), expected);
+        assert_eq!(dfs(&Csr::from_edges(nodes, &edges), 0), expected);
+        assert_eq!(dfs(&hash_graph(&edges), 0), expected);
+    }
+}
+
+#[test]
+fn traversals_on_named_nodes() {
+    let mut graph: HashGraph<&str> = HashGraph::new();
+    graph.insert("a", vec!["b", "c"]);
+    graph.insert("b", vec!["d"]);
+    graph.insert("c", vec!["d"]);
+    graph.insert("d", vec!["a"]);
+    assert_eq!(bfs(&graph, "a"), vec!["a", "b", "c", "d"]);
+    assert_eq!(dfs(&graph, "a"), vec!["a", "b", "d", "c"]);
+    // A node with no entry is a dead end
+    assert_eq!(bfs(&graph, "z"), vec!["z"]);
+}
+
+#[test]
+fn deep_paths_do_not_overflow_the_stack() {
+    let nodes: usize = 200_000;
+    let edges: Vec<Edge> = (0..nodes - 1).map(|i| (i, i + 1)).collect();
+    let csr: Csr = Csr::from_edges(nodes, &edges);
+    assert_eq!(dfs(&csr, 0).len(), nodes);
+    assert_eq!(bfs(&csr, 0).len(), nodes);
+}
+
+#[test]
+fn empty_and_isolated_nodes() {
+    let csr: Csr = Csr::from_edges(3, &[]);
+    assert_eq!(csr.offsets(), &[0, 0, 0, 0]);
+    assert_eq!(bfs(&csr, 1), vec![1]);
+    let unique: HashSet<usize> = dfs(&adjacency_list(3, &[(0, 0), (0, 0)]), 0)
+        .into_iter()
+        .collect();
+    assert_eq!(unique, HashSet::from([0]));
+}
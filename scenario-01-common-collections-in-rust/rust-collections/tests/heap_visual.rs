@@ -0,0 +1,99 @@
+//! The heap renderer's layout, and which slots `changed` marks after a push
+//! or a pop on a real `BinaryHeap`.
+
+use collections_demo::heap_visual::{changed, heap_vec, render, render_tree};
+use demo_framework::rng::Lcg;
+use std::collections::BinaryHeap;
+
+/// Indices from `index` up to the root.
+fn ancestors(mut index: usize) -> Vec<usize> {
+    let mut path: Vec<usize> = vec![index];
+    while index > 0 {
+        index = (index - 1) / 2;
+        path.push(index);
+    }
+    path
+}
+
+#[test]
+fn renders_the_module_doc_example() {
+    // Pushed one at a time, as in max_heap_behavior; BinaryHeap::from
+    // heapifies bottom-up and lays the same values out differently
+    let mut heap: BinaryHeap<i8> = BinaryHeap::new();
+    for item in [3, 1, 4, 1, 5, 9, 2, 6] {
+        heap.push(item);
+    }
+    let expected: &str = "                9
+        ┌───────┴───────┐
+        6               5
+    ┌───┴───┐       ┌───┴───┐
+    4       1       3       2
+  ┌─┘
+  1";
+    assert_eq!(heap_vec(&heap), vec![9, 6, 5, 4, 1, 3, 2, 1]);
+    assert_eq!(render(&heap), expected);
+}
+
+#[test]
+fn small_and_empty_heaps() {
+    assert_eq!(render_tree::<i8>(&[], &[]), "(empty)");
+    assert_eq!(render_tree(&[7], &[]), "  7");
+    assert_eq!(render_tree(&[7], &[0]), " [7]");
+    assert_eq!(render_tree(&[7, 3], &[]), "    7\n  ┌─┘\n  3");
+}
+
+#[test]
+fn marking_never_moves_the_other_nodes() {
+    let items: Vec<u32> = vec![120, 64, 100, 7, 33];
+    let plain: String = render_tree(&items, &[]);
+    let marked: String = render_tree(&items, &[1, 3]);
+    assert_eq!(plain.lines().count(), marked.lines().count());
+    for (a, b) in plain.lines().zip(marked.lines()) {
+        assert_eq!(
+            a.replace(['[', ']'], " ").trim_end(),
+            b.replace(['[', ']'], " ").trim_end()
+        );
+    }
+    // Multi-digit labels in reading order, none overlapping
+    let labels: Vec<&str> = marked
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .collect();
+    assert_eq!(labels, vec!["120", "64", "100", "7", "33"]);
+}
+
+#[test]
+fn a_push_changes_one_leaf_to_root_path() {
+    let mut heap: BinaryHeap<u32> = BinaryHeap::new();
+    let mut rng: Lcg = Lcg::new(11);
+    for _ in 0..200 {
+        let state: u64 = rng.next_u64();
+        let before: Vec<u32> = heap_vec(&heap);
+        heap.push((state >> 40) as u32 % 1000);
+        let after: Vec<u32> = heap_vec(&heap);
+        let marks: Vec<usize> = changed(&before, &after);
+        let path: Vec<usize> = ancestors(after.len() - 1);
+        // The new leaf always changes; everything else changed is above it
+        assert!(marks.contains(&(after.len() - 1)));
+        assert!(marks.iter().all(|i| path.contains(i)), "{:?}", marks);
+    }
+}
+
+#[test]
+fn a_pop_changes_slots_below_the_root_only_along_one_path() {
+    let mut heap: BinaryHeap<u32> = (0..100).map(|i| (i * 37) % 101).collect();
+    while heap.len() > 1 {
+        let before: Vec<u32> = heap_vec(&heap);
+        heap.pop();
+        let after: Vec<u32> = heap_vec(&heap);
+        let marks: Vec<usize> = changed(&before, &after);
+        // The vacated last slot, plus a chain down from the root where each
+        // mark is the parent of the next (the values are distinct)
+        assert_eq!(marks.last(), Some(&(before.len() - 1)));
+        let inside: Vec<usize> = marks.into_iter().filter(|&i| i < after.len()).collect();
+        assert_eq!(inside.first(), Some(&0));
+        for pair in inside.windows(2) {
+            assert_eq!((pair[1] - 1) / 2, pair[0], "{:?}", inside);
+        }
+    }
+}
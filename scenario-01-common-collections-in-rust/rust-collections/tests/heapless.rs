@@ -0,0 +1,98 @@
+//! The heapless collections' error-on-full behaviour, the telemetry
+//! pipeline's accounting, and - with the tracking allocator installed for
+//! this test binary - proof that the pipeline never touches the heap.
+//!
+//! The allocation counters are process-wide, so everything runs in a single
+//! test: another test allocating on a parallel thread would show up in the
+//! count.
+
+use collections_demo::heapless_examples::{
+    FRAME_BYTES, Frame, MAX_SENSORS, Reading, Telemetry, sensor_bursts, transfer,
+};
+use demo_framework::alloc::{self, AllocCounts, TrackingAllocator};
+use heapless::index_map::FnvIndexMap;
+use heapless::spsc::Queue;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+#[test]
+fn fixed_capacity_collections_report_full_and_never_allocate() {
+    // Every "full" hands the rejected value back
+    let mut vec: heapless::Vec<u8, 3> = heapless::Vec::new();
+    assert!(vec.extend_from_slice(&[1, 2, 3, 4]).is_err());
+    assert!(vec.is_empty(), "extend_from_slice is all or nothing");
+    assert!(vec.extend_from_slice(&[1, 2, 3]).is_ok());
+    assert_eq!(vec.push(4), Err(4));
+
+    let mut map: FnvIndexMap<u8, char, 2> = FnvIndexMap::new();
+    assert_eq!(map.insert(1, 'a'), Ok(None));
+    assert_eq!(map.insert(2, 'b'), Ok(None));
+    assert_eq!(map.insert(3, 'c'), Err((3, 'c')));
+    assert_eq!(
+        map.insert(1, 'z'),
+        Ok(Some('a')),
+        "replacing needs no new slot"
+    );
+
+    let mut queue: Queue<u8, 4> = Queue::new();
+    assert_eq!(queue.capacity(), 3);
+    assert_eq!(
+        (0..4)
+            .map(|i| queue.enqueue(i))
+            .filter(Result::is_err)
+            .count(),
+        1
+    );
+
+    // Handing items across threads loses nothing
+    let mut ring: Queue<u32, 8> = Queue::new();
+    assert_eq!(transfer(&mut ring, 50_000).0, 50_000 * 50_001 / 2);
+
+    // The telemetry pipeline accounts for every reading
+    let bursts: Vec<Vec<Reading>> = sensor_bursts(200, 6, 10, 5);
+    let sent: usize = bursts.iter().map(Vec::len).sum();
+    let mut telemetry: Telemetry = Telemetry::new();
+
+    let before: AllocCounts = alloc::counts().expect("the tracking allocator is installed");
+    let mut queued: u32 = 0;
+    let mut processed: usize = 0;
+    for burst in &bursts {
+        for &reading in burst {
+            queued += u32::from(telemetry.on_interrupt(reading));
+        }
+        processed += telemetry.main_loop_tick(4);
+    }
+    while let n @ 1.. = telemetry.main_loop_tick(4) {
+        processed += n;
+    }
+    let frames: heapless::Vec<Frame, 3> = telemetry.frames();
+    let pipeline: AllocCounts = alloc::counts().unwrap().since(before);
+    assert_eq!(pipeline.allocations, 0, "{pipeline:?}");
+
+    assert_eq!(queued as usize + telemetry.dropped as usize, sent);
+    assert_eq!(processed, queued as usize);
+    assert_eq!(telemetry.stats().len(), MAX_SENSORS);
+    let tracked: u32 = telemetry
+        .stats()
+        .values()
+        .map(|stats| u32::from(stats.count))
+        .sum();
+    assert_eq!(tracked + telemetry.untracked, queued);
+    for (&sensor, stats) in telemetry.stats() {
+        let expected: i16 = 200 + i16::from(sensor) * 50;
+        assert!(stats.min >= expected - 10 && stats.max <= expected + 10);
+        assert!((stats.min..=stats.max).contains(&stats.mean()));
+    }
+
+    // Every tracked sensor lands in exactly one 8-byte record
+    assert!(frames.iter().all(|frame| frame.len() <= FRAME_BYTES));
+    let mut framed: Vec<u8> = frames
+        .iter()
+        .flat_map(|frame| frame.chunks(8).map(|record| record[0]))
+        .collect();
+    framed.sort_unstable();
+    let mut keys: Vec<u8> = telemetry.stats().keys().copied().collect();
+    keys.sort_unstable();
+    assert_eq!(framed, keys);
+}
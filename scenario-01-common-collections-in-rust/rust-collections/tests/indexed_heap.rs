@@ -0,0 +1,214 @@
+//! IndexedBinaryHeap invariants and Dijkstra on graphs with known answers.
+//!
+//! The demos show one pass through each operation; these tests check the
+//! position map stays in sync under long mixed sequences, and that the
+//! decrease-key Dijkstra agrees with hand-computed distances and with the
+//! lazy-deletion version on random graphs.
+
+use collections_demo::heap_alternatives_examples::{Graph, dijkstra_binary_heap, random_graph};
+use collections_demo::indexed_heap_examples::{
+    IndexedBinaryHeap, ShortestPaths, WeightedGraph, dijkstra, dijkstra_lazy,
+};
+use demo_framework::rng::Lcg;
+use std::collections::HashMap;
+
+fn graph_from(edges: &[(&'static str, &'static str, u32)]) -> WeightedGraph<&'static str> {
+    let mut graph: WeightedGraph<&str> = HashMap::new();
+    for &(a, b, weight) in edges {
+        graph.entry(a).or_default().push((b, weight));
+    }
+    graph
+}
+
+fn drain<K: std::hash::Hash + Eq + Clone, P: Ord>(heap: &mut IndexedBinaryHeap<K, P>) -> Vec<P> {
+    let mut priorities: Vec<P> = Vec::new();
+    while let Some((_, priority)) = heap.pop() {
+        priorities.push(priority);
+    }
+    priorities
+}
+
+#[test]
+fn pops_in_ascending_priority_order() {
+    let mut heap: IndexedBinaryHeap<u32, u32> = IndexedBinaryHeap::new();
+    for key in 0..200 {
+        heap.push(key, (key * 37) % 101);
+    }
+    assert_eq!(heap.len(), 200);
+
+    let priorities: Vec<u32> = drain(&mut heap);
+    assert!(priorities.is_sorted());
+    assert_eq!(priorities.len(), 200);
+    assert!(heap.is_empty());
+}
+
+#[test]
+fn pushing_an_existing_key_updates_it() {
+    let mut heap: IndexedBinaryHeap<&str, u32> = IndexedBinaryHeap::new();
+    assert_eq!(heap.push("a", 5), None);
+    assert_eq!(heap.push("b", 3), None);
+    assert_eq!(heap.push("a", 1), Some(5));
+
+    assert_eq!(heap.len(), 2);
+    assert_eq!(heap.peek(), Some((&"a", &1)));
+}
+
+#[test]
+fn decrease_priority_only_lowers() {
+    let mut heap: IndexedBinaryHeap<char, u32> = IndexedBinaryHeap::new();
+    for (key, priority) in [('a', 10), ('b', 20), ('c', 30)] {
+        heap.push(key, priority);
+    }
+
+    assert!(heap.decrease_priority(&'c', 5));
+    assert_eq!(heap.peek(), Some((&'c', &5)));
+
+    assert!(!heap.decrease_priority(&'b', 25));
+    assert!(!heap.decrease_priority(&'b', 20));
+    assert_eq!(heap.priority(&'b'), Some(&20));
+
+    assert!(!heap.decrease_priority(&'z', 0));
+}
+
+#[test]
+fn change_priority_moves_both_ways() {
+    let mut heap: IndexedBinaryHeap<u32, u32> = IndexedBinaryHeap::new();
+    for key in 0..10 {
+        heap.push(key, key * 10);
+    }
+
+    assert_eq!(heap.change_priority(&0, 1_000), Some(0));
+    assert_eq!(heap.peek(), Some((&1, &10)));
+    assert_eq!(heap.change_priority(&9, 0), Some(90));
+    assert_eq!(heap.peek(), Some((&9, &0)));
+    assert_eq!(heap.change_priority(&42, 0), None);
+
+    let priorities: Vec<u32> = drain(&mut heap);
+    assert_eq!(priorities, vec![0, 10, 20, 30, 40, 50, 60, 70, 80, 1_000]);
+}
+
+#[test]
+fn remove_from_the_middle_keeps_the_heap_valid() {
+    let mut heap: IndexedBinaryHeap<u32, u32> = IndexedBinaryHeap::new();
+    for key in 0..50 {
+        heap.push(key, (key * 17) % 50);
+    }
+
+    for key in (0..50).step_by(3) {
+        assert_eq!(heap.remove(&key), Some((key * 17) % 50));
+        assert!(!heap.contains_key(&key));
+    }
+    assert_eq!(heap.remove(&0), None);
+
+    let priorities: Vec<u32> = drain(&mut heap);
+    assert!(priorities.is_sorted());
+    assert_eq!(priorities.len(), 50 - 17);
+}
+
+#[test]
+fn priority_lookup_survives_mixed_operations() {
+    let mut heap: IndexedBinaryHeap<u64, u64> = IndexedBinaryHeap::new();
+    let mut expected: HashMap<u64, u64> = HashMap::new();
+    let mut rng: Lcg = Lcg::new(7);
+
+    for _ in 0..5_000 {
+        let state: u64 = rng.next_u64();
+        let key: u64 = (state >> 40) % 64;
+        let priority: u64 = (state >> 20) % 1_000;
+        match (state >> 60) % 4 {
+            0 => {
+                heap.push(key, priority);
+                expected.insert(key, priority);
+            }
+            1 => {
+                if heap.decrease_priority(&key, priority) {
+                    expected.insert(key, priority);
+                }
+            }
+            2 => {
+                assert_eq!(heap.remove(&key), expected.remove(&key));
+            }
+            _ => {
+                if let Some((popped, priority)) = heap.pop() {
+                    assert_eq!(expected.remove(&popped), Some(priority));
+                    assert!(expected.values().all(|&p| p >= priority));
+                }
+            }
+        }
+        assert_eq!(heap.len(), expected.len());
+        for (key, priority) in &expected {
+            assert_eq!(heap.priority(key), Some(priority));
+        }
+    }
+}
+
+#[test]
+fn dijkstra_on_the_textbook_graph() {
+    // The five-node example from Cormen et al., Introduction to Algorithms
+    let graph: WeightedGraph<&str> = graph_from(&[
+        ("s", "t", 10),
+        ("s", "y", 5),
+        ("t", "x", 1),
+        ("t", "y", 2),
+        ("x", "z", 4),
+        ("y", "t", 3),
+        ("y", "x", 9),
+        ("y", "z", 2),
+        ("z", "s", 7),
+        ("z", "x", 6),
+    ]);
+    let paths: ShortestPaths<&str> = dijkstra(&graph, "s");
+
+    let expected: HashMap<&str, u32> = [("s", 0), ("t", 8), ("x", 9), ("y", 5), ("z", 7)]
+        .into_iter()
+        .collect();
+    assert_eq!(paths.dist, expected);
+    assert_eq!(paths.path_to(&"x"), Some(vec!["s", "y", "t", "x"]));
+    assert_eq!(paths.path_to(&"z"), Some(vec!["s", "y", "z"]));
+    assert_eq!(paths.path_to(&"s"), Some(vec!["s"]));
+}
+
+#[test]
+fn dijkstra_skips_unreachable_nodes() {
+    let graph: WeightedGraph<&str> = graph_from(&[("a", "b", 1), ("b", "c", 2), ("d", "a", 1)]);
+    let paths: ShortestPaths<&str> = dijkstra(&graph, "a");
+
+    assert_eq!(paths.dist.len(), 3);
+    assert_eq!(paths.dist.get("c"), Some(&3));
+    assert_eq!(paths.dist.get("d"), None);
+    assert_eq!(paths.path_to(&"d"), None);
+}
+
+#[test]
+fn dijkstra_prefers_many_cheap_hops() {
+    // The direct edge is found first and then decrease-keyed twice
+    let graph: WeightedGraph<&str> = graph_from(&[
+        ("a", "d", 10),
+        ("a", "b", 1),
+        ("b", "d", 8),
+        ("b", "c", 1),
+        ("c", "d", 1),
+    ]);
+    let paths: ShortestPaths<&str> = dijkstra(&graph, "a");
+
+    assert_eq!(paths.dist["d"], 3);
+    assert_eq!(paths.path_to(&"d"), Some(vec!["a", "b", "c", "d"]));
+}
+
+#[test]
+fn dijkstra_agrees_with_lazy_deletion_on_random_graphs() {
+    for seed in [1, 2, 3] {
+        let graph: Graph = random_graph(500, 6, seed);
+        let weighted: WeightedGraph<usize> = graph.iter().cloned().enumerate().collect();
+
+        let indexed: ShortestPaths<usize> = dijkstra(&weighted, 0);
+        let lazy: ShortestPaths<usize> = dijkstra_lazy(&weighted, 0);
+        let reference: Vec<u32> = dijkstra_binary_heap(&graph, 0).dist;
+
+        assert_eq!(indexed.dist, lazy.dist);
+        for (node, &dist) in reference.iter().enumerate() {
+            assert_eq!(indexed.dist.get(&node), Some(&dist));
+        }
+        assert!(indexed.max_queue_len <= graph.len());
+    }
+}
@@ -0,0 +1,176 @@
+//! Both interval maps against a brute-force list of intervals.
+//!
+//! Random intervals are inserted and removed, and every stabbing and
+//! overlap query is compared with a linear scan. The tree is also checked
+//! to stay balanced.
+
+use collections_demo::interval_examples::{BTreeIntervalMap, IntervalTree, random_intervals};
+use std::ops::Range;
+
+type Found = Vec<(Range<u32>, usize)>;
+
+fn sorted(mut found: Found) -> Found {
+    found.sort_by_key(|(interval, id)| (interval.start, interval.end, *id));
+    found
+}
+
+fn owned(found: Vec<(Range<u32>, &usize)>) -> Found {
+    found
+        .into_iter()
+        .map(|(interval, &id)| (interval, id))
+        .collect()
+}
+
+fn brute_stab(all: &[(Range<u32>, usize)], point: u32) -> Found {
+    sorted(
+        all.iter()
+            .filter(|(interval, _)| interval.contains(&point))
+            .cloned()
+            .collect(),
+    )
+}
+
+fn brute_overlapping(all: &[(Range<u32>, usize)], query: &Range<u32>) -> Found {
+    sorted(
+        all.iter()
+            .filter(|(interval, _)| interval.start < query.end && query.start < interval.end)
+            .cloned()
+            .collect(),
+    )
+}
+
+#[test]
+fn half_open_intervals_touch_without_overlapping() {
+    let mut tree: IntervalTree<u32, &str> = IntervalTree::new();
+    let mut map: BTreeIntervalMap<u32, &str> = BTreeIntervalMap::new();
+    for (interval, name) in [(9..10, "a"), (10..11, "b")] {
+        tree.insert(interval.clone(), name);
+        map.insert(interval, name);
+    }
+    assert_eq!(tree.stab(10), vec![(10..11, &"b")]);
+    assert_eq!(map.stab(10), vec![(10..11, &"b")]);
+    assert!(tree.overlapping(&(11..12)).is_empty());
+    assert!(map.overlapping(&(8..9)).is_empty());
+    assert_eq!(tree.overlapping(&(9..11)).len(), 2);
+}
+
+#[test]
+fn queries_match_a_linear_scan() {
+    let intervals: Vec<Range<u32>> = random_intervals(2_000, 10_000, 200, 7);
+    let mut all: Vec<(Range<u32>, usize)> = Vec::new();
+    let mut tree: IntervalTree<u32, usize> = IntervalTree::new();
+    let mut map: BTreeIntervalMap<u32, usize> = BTreeIntervalMap::new();
+    for (id, interval) in intervals.into_iter().enumerate() {
+        tree.insert(interval.clone(), id);
+        map.insert(interval.clone(), id);
+        all.push((interval, id));
+    }
+
+    for point in (0..10_300).step_by(37) {
+        let expected: Found = brute_stab(&all, point);
+        assert_eq!(sorted(owned(tree.stab(point))), expected, "stab({})", point);
+        assert_eq!(sorted(owned(map.stab(point))), expected, "stab({})", point);
+    }
+    for start in (0..10_300).step_by(101) {
+        for len in [1, 10, 500] {
+            let query: Range<u32> = start..start + len;
+            let expected: Found = brute_overlapping(&all, &query);
+            assert_eq!(sorted(owned(tree.overlapping(&query))), expected);
+            assert_eq!(sorted(owned(map.overlapping(&query))), expected);
+        }
+    }
+}
+
+#[test]
+fn removals_keep_queries_correct_and_the_tree_balanced() {
+    let intervals: Vec<Range<u32>> = random_intervals(1_000, 5_000, 100, 3);
+    let mut all: Vec<(Range<u32>, usize)> = Vec::new();
+    let mut tree: IntervalTree<u32, usize> = IntervalTree::new();
+    let mut map: BTreeIntervalMap<u32, usize> = BTreeIntervalMap::new();
+    for (id, interval) in intervals.into_iter().enumerate() {
+        tree.insert(interval.clone(), id);
+        map.insert(interval.clone(), id);
+        all.push((interval, id));
+    }
+
+    // Remove every third interval; duplicates of the same bounds may
+    // remove a different id, so compare intervals only
+    let mut removed: usize = 0;
+    for i in (0..all.len()).rev().step_by(3) {
+        let (interval, _) = all.remove(i);
+        assert!(tree.remove(&interval).is_some());
+        assert!(map.remove(&interval).is_some());
+        removed += 1;
+    }
+    assert_eq!(tree.len(), 1_000 - removed);
+    assert_eq!(map.len(), 1_000 - removed);
+
+    let bounds = |found: Found| -> Vec<Range<u32>> {
+        let mut bounds: Vec<Range<u32>> = found.into_iter().map(|(i, _)| i).collect();
+        bounds.sort_by_key(|i| (i.start, i.end));
+        bounds
+    };
+    for start in (0..5_200).step_by(53) {
+        let query: Range<u32> = start..start + 40;
+        let expected: Vec<Range<u32>> = bounds(brute_overlapping(&all, &query));
+        assert_eq!(bounds(owned(tree.overlapping(&query))), expected);
+        assert_eq!(bounds(owned(map.overlapping(&query))), expected);
+    }
+
+    // An AVL tree of n nodes is at most about 1.44 log2(n) high
+    let limit: f64 = 1.45 * ((tree.len() + 2) as f64).log2();
+    assert!(
+        (tree.height() as f64) <= limit,
+        "height {} for {} nodes",
+        tree.height(),
+        tree.len()
+    );
+}
+
+#[test]
+fn sorted_inserts_stay_balanced() {
+    let mut tree: IntervalTree<u32, ()> = IntervalTree::new();
+    for start in 0..1_023 {
+        tree.insert(start..start + 1, ());
+    }
+    // A plain BST would be a 1,023-level list
+    assert_eq!(tree.height(), 10);
+    assert!(tree.iter().map(|(i, _)| i.start).eq(0..1_023));
+}
+
+#[test]
+fn removing_a_missing_interval_changes_nothing() {
+    let mut tree: IntervalTree<u32, &str> = IntervalTree::new();
+    let mut map: BTreeIntervalMap<u32, &str> = BTreeIntervalMap::new();
+    tree.insert(1..5, "x");
+    map.insert(1..5, "x");
+    assert_eq!(tree.remove(&(1..4)), None);
+    assert_eq!(map.remove(&(1..4)), None);
+    assert_eq!(tree.len(), 1);
+    assert_eq!(map.len(), 1);
+    assert_eq!(tree.remove(&(1..5)), Some("x"));
+    assert_eq!(map.remove(&(1..5)), Some("x"));
+    assert!(tree.is_empty() && map.is_empty());
+}
+
+#[test]
+fn pruning_skips_most_of_the_tree() {
+    let mut tree: IntervalTree<u32, usize> = IntervalTree::new();
+    let mut map: BTreeIntervalMap<u32, usize> = BTreeIntervalMap::new();
+    for (id, interval) in random_intervals(10_000, 1_000_000, 1_000, 42)
+        .into_iter()
+        .enumerate()
+    {
+        tree.insert(interval.clone(), id);
+        map.insert(interval, id);
+    }
+    let query: Range<u32> = 990_000..991_000;
+    assert!(tree.overlap_query_cost(&query) < 100);
+    assert!(map.overlap_query_cost(&query) > 9_000);
+}
+
+#[test]
+#[should_panic(expected = "an interval must start before it ends")]
+fn empty_intervals_panic() {
+    IntervalTree::<u32, ()>::new().insert(5..5, ());
+}
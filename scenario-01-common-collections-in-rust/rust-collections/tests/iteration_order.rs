@@ -0,0 +1,81 @@
+//! What iteration order does and doesn't change downstream: the same
+//! per-region totals and the same serialized lines from every map, one group
+//! per region only from key order, and a smaller compressed output when
+//! similar lines sit together.
+
+use collections_demo::iteration_order_examples::{
+    REGIONS, compressed_len, region, sales, serialize, subtotals,
+};
+use indexmap::IndexMap;
+use std::collections::{BTreeMap, HashMap};
+
+fn per_region(groups: &[(usize, f64)]) -> Vec<f64> {
+    let mut totals: Vec<f64> = vec![0.0; REGIONS.len()];
+    for &(region_index, total) in groups {
+        totals[region_index] += total;
+    }
+    totals
+}
+
+#[test]
+fn every_order_reaches_the_same_totals() {
+    let arrivals: Vec<(u64, f64)> = sales(5_000, 1);
+    assert!(arrivals.iter().all(|&(key, _)| region(key) < REGIONS.len()));
+
+    let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+    let sorted: BTreeMap<u64, f64> = arrivals.iter().copied().collect();
+    let inserted: IndexMap<u64, f64> = arrivals.iter().copied().collect();
+
+    let by_key: Vec<(usize, f64)> = subtotals(sorted.iter());
+    let regions: Vec<usize> = by_key
+        .iter()
+        .map(|&(region_index, _)| region_index)
+        .collect();
+    assert_eq!(regions, (0..REGIONS.len()).collect::<Vec<usize>>());
+
+    let reference: Vec<f64> = per_region(&by_key);
+    for groups in [subtotals(hash.iter()), subtotals(inserted.iter())] {
+        // Neighbouring groups never share a region
+        assert!(groups.windows(2).all(|pair| pair[0].0 != pair[1].0));
+        assert!(groups.len() > arrivals.len() / 2);
+        for (total, expected) in per_region(&groups).iter().zip(&reference) {
+            assert!(((total - expected) / expected).abs() < 1e-9);
+        }
+    }
+}
+
+#[test]
+fn serializing_writes_the_same_lines_in_each_order() {
+    let arrivals: Vec<(u64, f64)> = sales(2_000, 2);
+    let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+    let sorted: BTreeMap<u64, f64> = arrivals.iter().copied().collect();
+
+    let sorted_text: String = serialize(sorted.iter());
+    let hash_text: String = serialize(hash.iter());
+    let mut sorted_lines: Vec<&str> = sorted_text.lines().collect();
+    let mut hash_lines: Vec<&str> = hash_text.lines().collect();
+    assert_eq!(sorted_lines.len(), arrivals.len());
+    // Key order writes each region's lines together, in region order
+    let mut prefixes: Vec<&str> = sorted_lines
+        .iter()
+        .map(|line| line.split('/').next().unwrap_or_default())
+        .collect();
+    prefixes.dedup();
+    assert_eq!(prefixes, REGIONS);
+    sorted_lines.sort_unstable();
+    hash_lines.sort_unstable();
+    assert_eq!(sorted_lines, hash_lines);
+}
+
+#[test]
+fn key_order_compresses_best() {
+    let arrivals: Vec<(u64, f64)> = sales(10_000, 3);
+    let hash: HashMap<u64, f64> = arrivals.iter().copied().collect();
+    let sorted: BTreeMap<u64, f64> = arrivals.iter().copied().collect();
+    let inserted: IndexMap<u64, f64> = arrivals.iter().copied().collect();
+
+    let by_key: usize = compressed_len(&serialize(sorted.iter()));
+    assert!(by_key < compressed_len(&serialize(inserted.iter())));
+    assert!(by_key < compressed_len(&serialize(hash.iter())));
+    assert!(by_key < serialize(sorted.iter()).len() / 2);
+}
@@ -0,0 +1,122 @@
+//! The iterator building blocks: run-length grouping, the overdraft-stopping
+//! scan, header/body splitting with by_ref, size_hint-driven preallocation,
+//! order parsing and totals, and index loops agreeing with their iterator
+//! chains.
+
+use collections_demo::iterator_examples::{
+    NoHint, Order, SAMPLE_ORDERS, collect_growths, dot_indexed, dot_zip, parse_orders, run_lengths,
+    running_balance, sample_values, split_message, sum_even_squares_indexed, sum_even_squares_iter,
+    totals_by_customer,
+};
+
+#[test]
+fn run_lengths_groups_consecutive_items_only() {
+    assert_eq!(run_lengths([1, 1, 2, 1, 1, 1]), [(1, 2), (2, 1), (1, 3)]);
+    assert_eq!(run_lengths(Vec::<u8>::new()), []);
+    assert_eq!(run_lengths("z".chars()), [('z', 1)]);
+
+    // Expanding the runs gives the input back
+    let input: Vec<u64> = sample_values(500, 3).iter().map(|v| v % 3).collect();
+    let expanded: Vec<u64> = run_lengths(input.iter().copied())
+        .into_iter()
+        .flat_map(|(v, n)| std::iter::repeat_n(v, n))
+        .collect();
+    assert_eq!(expanded, input);
+}
+
+#[test]
+fn running_balance_stops_before_an_overdraft() {
+    assert_eq!(running_balance(10, &[5, -15, 3]), [15, 0, 3]);
+    assert_eq!(running_balance(10, &[-11, 100]), Vec::<i64>::new());
+    assert_eq!(running_balance(0, &[]), Vec::<i64>::new());
+}
+
+#[test]
+fn split_message_skips_the_blank_separator() {
+    let (headers, body) = split_message("A: 1\nB: two\n\nbody\n\nmore");
+    assert_eq!(headers, [("A", "1"), ("B", "two")]);
+    assert_eq!(
+        body,
+        ["body", "", "more"],
+        "only the first blank line splits"
+    );
+
+    let (headers, body) = split_message("A: 1");
+    assert_eq!(headers, [("A", "1")]);
+    assert!(body.is_empty());
+}
+
+#[test]
+fn collect_preallocates_from_the_lower_bound() {
+    let values: Vec<u32> = (0..1_000).collect();
+    assert_eq!(collect_growths(values.iter()), (0, 1_000));
+    assert_eq!(collect_growths(values.iter().take(10)), (0, 10));
+
+    let (growths, capacity) = collect_growths(NoHint(values.iter()));
+    assert_eq!(growths, 9, "4, 8, ..., 1024");
+    assert_eq!(capacity, 1_024);
+
+    // The replay matches what Vec::from_iter actually does
+    let exact: Vec<u32> = values.iter().map(|x| x + 1).collect();
+    assert_eq!(exact.capacity(), 1_000);
+    let hidden: Vec<&u32> = NoHint(values.iter()).collect();
+    assert_eq!(hidden.capacity(), 1_024);
+    assert_eq!(NoHint(values.iter()).size_hint(), (0, None));
+}
+
+#[test]
+fn orders_parse_and_total_per_customer() {
+    let (orders, errors) = parse_orders(SAMPLE_ORDERS);
+    assert_eq!(orders.len(), 5);
+    assert_eq!(
+        errors,
+        [
+            "line 5: invalid digit found in string",
+            "line 7: expected 3 fields, found 2"
+        ]
+    );
+    assert_eq!(
+        orders[1],
+        Order {
+            customer: "grace".to_string(),
+            quantity: 1,
+            unit_cents: 1999
+        }
+    );
+
+    let totals: Vec<(&str, usize, u64)> = totals_by_customer(&orders);
+    assert_eq!(totals, [("ada", 3, 2_249), ("grace", 2, 2_499)]);
+    let grand: u64 = orders.iter().map(Order::total_cents).sum();
+    assert_eq!(
+        totals.iter().map(|&(_, _, cents)| cents).sum::<u64>(),
+        grand
+    );
+
+    let (_, errors) = parse_orders("header\nbob,99999999999,1");
+    assert_eq!(errors.len(), 1, "quantity overflows u32: {errors:?}");
+}
+
+#[test]
+fn index_loops_and_chains_agree() {
+    for (len, seed) in [(0, 1), (1, 2), (999, 3), (10_000, 4)] {
+        let values: Vec<u64> = sample_values(len, seed);
+        assert!(values.iter().all(|&v| v < 1_000));
+        assert_eq!(
+            sum_even_squares_indexed(&values),
+            sum_even_squares_iter(&values)
+        );
+
+        let a: Vec<u32> = values.iter().map(|&v| v as u32).collect();
+        let b: Vec<u32> = sample_values(len, seed + 100)
+            .iter()
+            .map(|&v| v as u32)
+            .collect();
+        assert_eq!(dot_indexed(&a, &b), dot_zip(&a, &b));
+    }
+    assert_eq!(sum_even_squares_iter(&[1, 2, 3, 4]), 4 + 16);
+    assert_eq!(
+        dot_zip(&[1, 2, 3], &[4, 5]),
+        4 + 10,
+        "zip stops at the shorter"
+    );
+}
@@ -0,0 +1,86 @@
+//! KdTree against linear scans (proptest): nearest, k-nearest and
+//! rectangle queries on random points. Coordinates come from a small grid
+//! so duplicate points and ties across split lines are common.
+
+use collections_demo::kdtree_examples::{
+    Found, KdTree, Nearest, linear_k_nearest, linear_nearest, linear_range,
+};
+use collections_demo::spatial_examples::{EntityId, Point};
+use proptest::prelude::*;
+
+fn grid_point() -> impl Strategy<Value = Point> {
+    (-20i32..20, -20i32..20).prop_map(|(x, y)| Point::new(x as f64 / 2.0, y as f64 / 2.0))
+}
+
+fn entities(points: Vec<Point>) -> Vec<(EntityId, Point)> {
+    points
+        .into_iter()
+        .enumerate()
+        .map(|(id, point)| (EntityId(id as u32), point))
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn queries_match_linear_scans(
+        points in prop::collection::vec(grid_point(), 0..200),
+        query in grid_point(),
+        k in 0usize..20,
+        corner in grid_point(),
+        (width, height) in (0.0f64..15.0, 0.0f64..15.0),
+    ) {
+        let entities: Vec<(EntityId, Point)> = entities(points);
+        let tree: KdTree = KdTree::build(entities.clone());
+        prop_assert_eq!(tree.len(), entities.len());
+
+        let found: Option<Nearest> = tree.nearest(query);
+        let scanned: Option<Nearest> = linear_nearest(&entities, query);
+        prop_assert_eq!(found.map(|f| (f.id, f.point)), scanned.map(|f| (f.id, f.point)));
+        prop_assert!(found.map_or(0, |f| f.visited) <= entities.len());
+
+        let found: Found = tree.k_nearest(query, k);
+        prop_assert_eq!(&found.ids, &linear_k_nearest(&entities, query, k).ids);
+        prop_assert_eq!(found.ids.len(), k.min(entities.len()));
+
+        let max: Point = Point::new(corner.x + width, corner.y + height);
+        let found: Found = tree.range(corner, max);
+        prop_assert_eq!(&found.ids, &linear_range(&entities, corner, max).ids);
+        prop_assert!(found.visited <= entities.len());
+    }
+
+    #[test]
+    fn every_subtree_is_split_at_its_median(points in prop::collection::vec(grid_point(), 0..100)) {
+        let tree: KdTree = KdTree::build(entities(points));
+        let mut stack: Vec<(&[(EntityId, Point)], usize)> = vec![(tree.entries(), 0)];
+        while let Some((slice, depth)) = stack.pop() {
+            if slice.is_empty() {
+                continue;
+            }
+            let mid: usize = slice.len() / 2;
+            let axis = |p: Point| if depth.is_multiple_of(2) { p.x } else { p.y };
+            let split: f64 = axis(slice[mid].1);
+            prop_assert!(slice[..mid].iter().all(|&(_, p)| axis(p) <= split));
+            prop_assert!(slice[mid + 1..].iter().all(|&(_, p)| axis(p) >= split));
+            stack.push((&slice[..mid], depth + 1));
+            stack.push((&slice[mid + 1..], depth + 1));
+        }
+    }
+}
+
+#[test]
+fn spread_out_points_skip_most_of_the_tree() {
+    let entities: Vec<(EntityId, Point)> = (0..4_096u32)
+        .map(|i| (EntityId(i), Point::new((i % 64) as f64, (i / 64) as f64)))
+        .collect();
+    let tree: KdTree = KdTree::build(entities);
+    assert_eq!(tree.depth(), 13);
+    let found: Nearest = tree.nearest(Point::new(31.2, 40.9)).unwrap();
+    assert_eq!(found.point, Point::new(31.0, 41.0));
+    assert!(found.visited < 100, "visited {}", found.visited);
+}
+
+#[test]
+#[should_panic(expected = "is not a finite position")]
+fn nan_points_are_refused() {
+    KdTree::build(vec![(EntityId(0), Point::new(0.0, f64::NAN))]);
+}
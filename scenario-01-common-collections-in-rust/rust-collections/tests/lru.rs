@@ -0,0 +1,143 @@
+//! Both LRU designs against the `lru` crate as a reference model.
+//!
+//! The demos walk through a handful of operations; these tests run long
+//! random sequences of get/put/remove/pop_lru on all three caches and check
+//! they agree on every return value and on the full recency order.
+
+use collections_demo::lru_examples::{DequeLru, LinkedLru, skewed_keys};
+use lru::LruCache;
+use std::num::NonZeroUsize;
+
+#[test]
+fn evicts_the_least_recently_used_entry() {
+    let mut linked: LinkedLru<u32, &str> = LinkedLru::new(2);
+    let mut deque: DequeLru<u32, &str> = DequeLru::new(2);
+
+    for (key, value) in [(1, "one"), (2, "two")] {
+        assert_eq!(linked.put(key, value), None);
+        assert_eq!(deque.put(key, value), None);
+    }
+    assert_eq!(linked.get(&1), Some(&"one"));
+    assert_eq!(deque.get(&1), Some(&"one"));
+
+    assert_eq!(linked.put(3, "three"), Some((2, "two")));
+    assert_eq!(deque.put(3, "three"), Some((2, "two")));
+    assert!(!linked.contains(&2));
+    assert!(!deque.contains(&2));
+}
+
+#[test]
+fn put_on_an_existing_key_returns_the_old_value_and_refreshes_it() {
+    let mut linked: LinkedLru<&str, u32> = LinkedLru::new(2);
+    linked.put("a", 1);
+    linked.put("b", 2);
+    assert_eq!(linked.put("a", 10), Some(("a", 1)));
+    // "a" was refreshed, so "b" is the one to go
+    assert_eq!(linked.put("c", 3), Some(("b", 2)));
+    assert_eq!(linked.len(), 2);
+
+    let mut deque: DequeLru<&str, u32> = DequeLru::new(2);
+    deque.put("a", 1);
+    deque.put("b", 2);
+    assert_eq!(deque.put("a", 10), Some(("a", 1)));
+    assert_eq!(deque.put("c", 3), Some(("b", 2)));
+    assert_eq!(deque.len(), 2);
+}
+
+#[test]
+fn peek_does_not_change_recency() {
+    let mut linked: LinkedLru<u32, u32> = LinkedLru::new(2);
+    linked.put(1, 1);
+    linked.put(2, 2);
+    assert_eq!(linked.peek(&1), Some(&1));
+    assert_eq!(linked.put(3, 3), Some((1, 1)));
+
+    let mut deque: DequeLru<u32, u32> = DequeLru::new(2);
+    deque.put(1, 1);
+    deque.put(2, 2);
+    assert_eq!(deque.peek(&1), Some(&1));
+    assert_eq!(deque.put(3, 3), Some((1, 1)));
+}
+
+#[test]
+fn capacity_one_always_holds_the_last_put() {
+    let mut linked: LinkedLru<u32, u32> = LinkedLru::new(1);
+    for i in 0..10 {
+        linked.put(i, i);
+        assert_eq!(linked.iter().collect::<Vec<_>>(), vec![(&i, &i)]);
+    }
+    assert_eq!(linked.pop_lru(), Some((9, 9)));
+    assert!(linked.is_empty());
+    assert_eq!(linked.pop_lru(), None);
+}
+
+#[test]
+#[should_panic(expected = "LRU capacity must be non-zero")]
+fn zero_capacity_panics() {
+    LinkedLru::<u32, u32>::new(0);
+}
+
+#[test]
+fn removing_every_entry_leaves_an_empty_list() {
+    let mut linked: LinkedLru<u32, u32> = LinkedLru::new(8);
+    for i in 0..8 {
+        linked.put(i, i * 10);
+    }
+    // Remove from the middle, the head and the tail, in that order
+    for key in [3, 7, 0, 5, 1, 6, 2, 4] {
+        assert_eq!(linked.remove(&key), Some(key * 10));
+        assert_eq!(linked.iter().count(), linked.len());
+    }
+    assert!(linked.is_empty());
+    assert_eq!(linked.remove(&3), None);
+}
+
+fn lru_order(cache: &LruCache<u64, u64>) -> Vec<(u64, u64)> {
+    cache.iter().map(|(&k, &v)| (k, v)).collect()
+}
+
+fn order<'a>(iter: impl Iterator<Item = (&'a u64, &'a u64)>) -> Vec<(u64, u64)> {
+    iter.map(|(&k, &v)| (k, v)).collect()
+}
+
+#[test]
+fn random_operations_match_the_lru_crate() {
+    for capacity in [1, 2, 7, 64] {
+        let mut reference: LruCache<u64, u64> = LruCache::new(NonZeroUsize::new(capacity).unwrap());
+        let mut linked: LinkedLru<u64, u64> = LinkedLru::new(capacity);
+        let mut deque: DequeLru<u64, u64> = DequeLru::new(capacity);
+
+        let keys: Vec<u64> = skewed_keys(5_000, 3 * capacity as u64, capacity as u64);
+        for (step, &key) in keys.iter().enumerate() {
+            match step % 7 {
+                0..=2 => {
+                    let expected: Option<(u64, u64)> = reference.push(key, step as u64);
+                    assert_eq!(linked.put(key, step as u64), expected);
+                    assert_eq!(deque.put(key, step as u64), expected);
+                }
+                3 | 4 => {
+                    let expected: Option<u64> = reference.get(&key).copied();
+                    assert_eq!(linked.get(&key).copied(), expected);
+                    assert_eq!(deque.get(&key).copied(), expected);
+                }
+                5 => {
+                    let expected: Option<u64> = reference.pop(&key);
+                    assert_eq!(linked.remove(&key), expected);
+                    assert_eq!(deque.remove(&key), expected);
+                }
+                _ => {
+                    if step % 5 == 0 {
+                        let expected: Option<(u64, u64)> = reference.pop_lru();
+                        assert_eq!(linked.pop_lru(), expected);
+                        assert_eq!(deque.pop_lru(), expected);
+                    }
+                }
+            }
+
+            let expected: Vec<(u64, u64)> = lru_order(&reference);
+            assert_eq!(order(linked.iter()), expected, "capacity {}", capacity);
+            assert_eq!(order(deque.iter()), expected, "capacity {}", capacity);
+            assert_eq!(linked.len(), reference.len());
+        }
+    }
+}
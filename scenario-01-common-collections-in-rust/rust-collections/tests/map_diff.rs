@@ -0,0 +1,122 @@
+//! Both diff algorithms against each other and against reconstruction.
+//!
+//! The HashMap lookup diff and the BTreeMap merge join must report the same
+//! differences, and applying a diff to the old map must give the new one.
+
+use collections_demo::map_diff_examples::{MapDiff, diff_maps, diff_sorted, inventory_snapshots};
+use demo_framework::rng::Lcg;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasherDefault, DefaultHasher};
+
+/// Deterministic pseudo-random maps sharing part of their key space.
+fn random_map(len: usize, key_space: u64, seed: u64) -> BTreeMap<u64, u8> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..len)
+        .map(|_| {
+            let state: u64 = rng.next_u64();
+            ((state >> 33) % key_space, (state >> 60) as u8)
+        })
+        .collect()
+}
+
+fn apply(old: &BTreeMap<u64, u8>, diff: &MapDiff<u64, u8>) -> BTreeMap<u64, u8> {
+    let mut result: BTreeMap<u64, u8> = old.clone();
+    for (key, _) in &diff.removed {
+        assert!(result.remove(key).is_some());
+    }
+    for (key, old_value, new_value) in &diff.changed {
+        assert_eq!(result.insert(**key, **new_value), Some(**old_value));
+    }
+    for (key, value) in &diff.added {
+        assert_eq!(result.insert(**key, **value), None);
+    }
+    result
+}
+
+#[test]
+fn both_algorithms_agree_and_reconstruct_the_new_map() {
+    for (len, key_space) in [
+        (0, 10),
+        (5, 10),
+        (100, 150),
+        (2_000, 2_500),
+        (1_000, 100_000),
+    ] {
+        for seed in 0..4 {
+            let old: BTreeMap<u64, u8> = random_map(len, key_space, seed);
+            let new: BTreeMap<u64, u8> = random_map(len, key_space, seed + 100);
+            let old_hashed: HashMap<u64, u8> = old.iter().map(|(&k, &v)| (k, v)).collect();
+            let new_hashed: HashMap<u64, u8> = new.iter().map(|(&k, &v)| (k, v)).collect();
+
+            let sorted: MapDiff<u64, u8> = diff_maps(&old, &new);
+            let hashed: MapDiff<u64, u8> = diff_maps(&old_hashed, &new_hashed);
+            assert_eq!(hashed.into_sorted(), sorted);
+            assert_eq!(apply(&old, &sorted), new);
+        }
+    }
+}
+
+#[test]
+fn btreemap_diffs_come_out_sorted() {
+    let old: BTreeMap<u64, u8> = random_map(5_000, 8_000, 1);
+    let new: BTreeMap<u64, u8> = random_map(5_000, 8_000, 2);
+    let diff: MapDiff<u64, u8> = diff_maps(&old, &new);
+    assert!(diff.added.is_sorted_by_key(|(k, _)| *k));
+    assert!(diff.removed.is_sorted_by_key(|(k, _)| *k));
+    assert!(diff.changed.is_sorted_by_key(|(k, _, _)| *k));
+}
+
+#[test]
+fn edge_cases() {
+    let empty: BTreeMap<u32, u32> = BTreeMap::new();
+    let full: BTreeMap<u32, u32> = (0..10).map(|i| (i, i)).collect();
+
+    assert!(diff_maps(&empty, &empty).is_empty());
+    assert!(diff_maps(&full, &full).is_empty());
+
+    let grown: MapDiff<u32, u32> = diff_maps(&empty, &full);
+    assert_eq!((grown.added.len(), grown.removed.len()), (10, 0));
+    let emptied: MapDiff<u32, u32> = diff_maps(&full, &empty);
+    assert_eq!((emptied.added.len(), emptied.removed.len()), (0, 10));
+
+    // Every value changed, no key moved
+    let bumped: BTreeMap<u32, u32> = full.iter().map(|(&k, &v)| (k, v + 1)).collect();
+    let diff: MapDiff<u32, u32> = diff_maps(&full, &bumped);
+    assert_eq!(diff.changed.len(), 10);
+    assert_eq!(diff.len(), 10);
+}
+
+#[test]
+fn diff_sorted_works_on_sorted_vecs() {
+    let old: Vec<(&str, i32)> = vec![("a", 1), ("b", 2), ("d", 4)];
+    let new: Vec<(&str, i32)> = vec![("a", 1), ("c", 3), ("d", 5)];
+    let diff: MapDiff<&str, i32> = diff_sorted(
+        old.iter().map(|(k, v)| (k, v)),
+        new.iter().map(|(k, v)| (k, v)),
+    );
+    assert_eq!(diff.added, vec![(&"c", &3)]);
+    assert_eq!(diff.removed, vec![(&"b", &2)]);
+    assert_eq!(diff.changed, vec![(&"d", &4, &5)]);
+}
+
+#[test]
+fn any_build_hasher_works() {
+    type Fixed = BuildHasherDefault<DefaultHasher>;
+    let old: HashMap<&str, u32, Fixed> = [("x", 1), ("y", 2)].into_iter().collect();
+    let new: HashMap<&str, u32, Fixed> = [("y", 3), ("z", 4)].into_iter().collect();
+    let diff: MapDiff<&str, u32> = diff_maps(&old, &new).into_sorted();
+    assert_eq!(diff.added, vec![(&"z", &4)]);
+    assert_eq!(diff.removed, vec![(&"x", &1)]);
+    assert_eq!(diff.changed, vec![(&"y", &2, &3)]);
+}
+
+#[test]
+fn inventory_snapshots_differ_as_documented() {
+    let (old, new) = inventory_snapshots(10_000);
+    let old: BTreeMap<u64, u64> = old.into_iter().collect();
+    let new: BTreeMap<u64, u64> = new.into_iter().collect();
+    let diff: MapDiff<u64, u64> = diff_maps(&old, &new);
+    assert_eq!(diff.added.len(), 50);
+    assert_eq!(diff.removed.len(), 50);
+    assert_eq!(diff.changed.len(), 100);
+}
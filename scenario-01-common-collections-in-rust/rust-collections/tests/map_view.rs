@@ -0,0 +1,84 @@
+//! MapView with random ranges and filters against the same query run on a
+//! plain BTreeMap (proptest), including ranges that intersect to nothing.
+
+use collections_demo::map_view_examples::MapView;
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+use std::ops::Bound::{self, Excluded, Included, Unbounded};
+use std::ops::RangeBounds;
+
+fn bound() -> impl Strategy<Value = Bound<u8>> {
+    prop_oneof![
+        Just(Unbounded),
+        (0u8..40).prop_map(Included),
+        (0u8..40).prop_map(Excluded),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn matches_filtering_the_whole_map(
+        entries in prop::collection::btree_map(0u8..40, any::<u16>(), 0..40),
+        first in (bound(), bound()),
+        second in (bound(), bound()),
+        divisor in 1u16..5,
+    ) {
+        let keep = |key: &u8, value: &u16| value.is_multiple_of(divisor) || *key < 5;
+        let expected: Vec<(&u8, &u16)> = entries
+            .iter()
+            .filter(|(key, value)| {
+                first.contains(*key) && second.contains(*key) && keep(key, value)
+            })
+            .collect();
+
+        let view = MapView::new(&entries).range(first).range(second).filter(keep);
+        prop_assert_eq!(view.iter().collect::<Vec<_>>(), expected.clone());
+        prop_assert_eq!(
+            view.iter().rev().collect::<Vec<_>>(),
+            expected.iter().rev().copied().collect::<Vec<_>>()
+        );
+        prop_assert_eq!(view.count(), expected.len());
+        prop_assert_eq!(view.is_empty(), expected.is_empty());
+        prop_assert_eq!(view.first(), expected.first().copied());
+        prop_assert_eq!(view.last(), expected.last().copied());
+        let snapshot: BTreeMap<u8, u16> =
+            expected.iter().map(|&(&key, &value)| (key, value)).collect();
+        prop_assert_eq!(view.to_map(), snapshot);
+    }
+}
+
+#[test]
+fn ranges_that_cannot_hold_a_key_are_empty_not_a_panic() {
+    let map: BTreeMap<u32, &str> = BTreeMap::from([(1, "a"), (2, "b"), (3, "c")]);
+    let ranges: [(Bound<u32>, Bound<u32>); 4] = [
+        (Included(3), Included(2)),
+        (Included(2), Excluded(2)),
+        (Excluded(2), Included(2)),
+        (Excluded(2), Excluded(2)),
+    ];
+    for range in ranges {
+        assert!(MapView::new(&map).range(range).is_empty(), "{:?}", range);
+    }
+    // Only the key between two exclusive bounds
+    let view = MapView::new(&map).range((Excluded(1), Excluded(3)));
+    assert_eq!(view.keys().collect::<Vec<_>>(), vec![&2]);
+}
+
+#[test]
+fn a_view_walks_again_and_project_keeps_keys() {
+    let map: BTreeMap<&str, u32> = BTreeMap::from([("apples", 3), ("pears", 0), ("plums", 7)]);
+    let stocked = MapView::new(&map).filter(|_, &count| count > 0);
+    assert_eq!(
+        stocked.keys().collect::<Vec<_>>(),
+        vec![&"apples", &"plums"]
+    );
+    assert_eq!(
+        stocked.keys().collect::<Vec<_>>(),
+        vec![&"apples", &"plums"]
+    );
+    assert_eq!(
+        stocked.project(|&count| count * 2).collect::<Vec<_>>(),
+        vec![(&"apples", 6), (&"plums", 14)]
+    );
+    assert_eq!(stocked.values().sum::<u32>(), 10);
+}
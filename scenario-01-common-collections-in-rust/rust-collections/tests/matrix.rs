@@ -0,0 +1,102 @@
+//! The matrix multiply loop orders and both layouts agreeing with each
+//! other on random shapes, and nested rows converting to a Grid only when
+//! they are rectangular.
+
+use collections_demo::grid_examples::Grid;
+use collections_demo::matrix_examples::{
+    grid_from_nested, grid_sum_by_columns, grid_sum_by_rows, multiply_ijk, multiply_ikj,
+    multiply_nested_ikj, multiply_transposed, multiply_vector, nested_from_grid,
+    nested_sum_by_columns, nested_sum_by_rows, sample_matrix,
+};
+use demo_framework::rng::Lcg;
+
+fn assert_close(x: &[f64], y: &[f64], context: &str) {
+    assert_eq!(x.len(), y.len(), "{}", context);
+    for (index, (p, q)) in x.iter().zip(y).enumerate() {
+        assert!(
+            (p - q).abs() < 1e-9,
+            "{}: element {}: {} vs {}",
+            context,
+            index,
+            p,
+            q
+        );
+    }
+}
+
+#[test]
+fn loop_orders_agree_on_random_shapes() {
+    for seed in 0..20 {
+        let mut rng: Lcg = Lcg::new(seed);
+        let n: usize = 1 + rng.below(12) as usize;
+        let m: usize = 1 + rng.below(12) as usize;
+        let p: usize = 1 + rng.below(12) as usize;
+        let a: Grid<f64> = sample_matrix(n, m, seed * 2);
+        let b: Grid<f64> = sample_matrix(m, p, seed * 2 + 1);
+        let context: String = format!("seed {} ({}x{} by {}x{})", seed, n, m, m, p);
+
+        let expected: Grid<f64> = multiply_ijk(&a, &b);
+        assert_eq!((expected.rows(), expected.cols()), (n, p), "{}", context);
+        assert_close(
+            expected.as_slice(),
+            multiply_ikj(&a, &b).as_slice(),
+            &context,
+        );
+        assert_close(
+            expected.as_slice(),
+            multiply_transposed(&a, &b).as_slice(),
+            &context,
+        );
+        let nested: Vec<Vec<f64>> =
+            multiply_nested_ikj(&nested_from_grid(&a), &nested_from_grid(&b));
+        assert_close(expected.as_slice(), &nested.concat(), &context);
+
+        // A matrix-vector product is a multiply by a one-column matrix
+        let vector: Grid<f64> = sample_matrix(m, 1, seed);
+        assert_close(
+            multiply_ikj(&a, &vector).as_slice(),
+            &multiply_vector(&a, vector.as_slice()),
+            &context,
+        );
+    }
+}
+
+#[test]
+fn identity_leaves_a_matrix_unchanged() {
+    let a: Grid<f64> = sample_matrix(5, 7, 3);
+    let identity: Grid<f64> = Grid::from_fn(7, 7, |row, col| if row == col { 1.0 } else { 0.0 });
+    assert_eq!(multiply_ikj(&a, &identity), a);
+    assert_eq!(multiply_ijk(&a, &identity), a);
+}
+
+#[test]
+#[should_panic(expected = "can't multiply a 2x3 matrix by a 2x3 one")]
+fn mismatched_shapes_panic() {
+    let a: Grid<f64> = sample_matrix(2, 3, 1);
+    multiply_ikj(&a, &a);
+}
+
+#[test]
+fn only_rectangular_rows_become_a_grid() {
+    let mut nested: Vec<Vec<f64>> = vec![vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]];
+    let grid: Grid<f64> = grid_from_nested(&nested).unwrap();
+    assert_eq!((grid.rows(), grid.cols()), (3, 2));
+    assert_eq!(grid.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    assert_eq!(nested_from_grid(&grid), nested);
+
+    nested[2].pop();
+    assert_eq!(grid_from_nested(&nested), None);
+    assert_eq!(grid_from_nested(&[]).map(|g| g.rows()), Some(0));
+}
+
+#[test]
+fn every_traversal_sums_the_same() {
+    // Integer values, so the summation order can't change the result
+    let grid: Grid<f64> = Grid::from_fn(37, 53, |row, col| (row * 53 + col) as f64);
+    let nested: Vec<Vec<f64>> = nested_from_grid(&grid);
+    let expected: f64 = (0..37 * 53).sum::<usize>() as f64;
+    assert_eq!(grid_sum_by_rows(&grid), expected);
+    assert_eq!(grid_sum_by_columns(&grid), expected);
+    assert_eq!(nested_sum_by_rows(&nested), expected);
+    assert_eq!(nested_sum_by_columns(&nested), expected);
+}
@@ -0,0 +1,280 @@
+//! Merge patch against the RFC 7396 examples, and the merge properties the
+//! module's header claims, checked on random nested maps.
+//!
+//! Random documents draw keys from a small alphabet so that targets and
+//! patches overlap at every depth, and mix maps with scalars and lists so
+//! that maps get replaced by scalars and the other way round.
+
+use collections_demo::merge_patch_examples::{
+    ConflictPolicy, Map, MergeConflict, Value, create_patch, leaves, map, merge, merge_patch,
+};
+use demo_framework::rng::Lcg;
+
+struct Random(Lcg);
+
+impl Random {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0.below(bound)
+    }
+
+    /// A random map up to `depth` levels deep; with `nulls`, some values
+    /// are null, as in a patch.
+    fn document(&mut self, depth: u32, nulls: bool) -> Value {
+        let mut entries: Map = Map::new();
+        for _ in 0..self.next(4) {
+            let key: String = ["a", "b", "c", "d"][self.next(4) as usize].to_string();
+            entries.insert(key, self.value(depth, nulls));
+        }
+        Value::Map(entries)
+    }
+
+    /// A random null-free map in which "a" and "b" always hold maps (above
+    /// the last level) and "c" and "d" never do, so any two such documents
+    /// agree on the shape of every path.
+    fn shaped(&mut self, depth: u32) -> Value {
+        let mut entries: Map = Map::new();
+        for _ in 0..self.next(4) {
+            let key: &str = ["a", "b", "c", "d"][self.next(4) as usize];
+            let value: Value = if depth > 0 && (key == "a" || key == "b") {
+                self.shaped(depth - 1)
+            } else {
+                self.value(0, false)
+            };
+            entries.insert(key.to_string(), value);
+        }
+        Value::Map(entries)
+    }
+
+    fn value(&mut self, depth: u32, nulls: bool) -> Value {
+        match self.next(7) {
+            0 | 1 if depth > 0 => self.document(depth - 1, nulls),
+            2 if nulls => Value::Null,
+            3 => Value::Bool(self.next(2) == 0),
+            4 => Value::List(vec![Value::Int(self.next(3) as i64)]),
+            _ => Value::Int(self.next(3) as i64),
+        }
+    }
+}
+
+fn patched(target: &Value, patch: &Value) -> Value {
+    let mut result: Value = target.clone();
+    merge_patch(&mut result, patch);
+    result
+}
+
+fn merged(target: &Value, patch: &Value, policy: ConflictPolicy) -> Result<Value, MergeConflict> {
+    let mut result: Value = target.clone();
+    merge(&mut result, patch, policy)?;
+    Ok(result)
+}
+
+fn contains_null(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Map(entries) => entries.values().any(contains_null),
+        _ => false,
+    }
+}
+
+#[test]
+fn rfc_7396_appendix_a() {
+    let a = || Value::from("b");
+    let cases: Vec<(Value, Value, Value)> = vec![
+        (
+            map([("a", a())]),
+            map([("a", "c".into())]),
+            map([("a", "c".into())]),
+        ),
+        (
+            map([("a", a())]),
+            map([("b", "c".into())]),
+            map([("a", a()), ("b", "c".into())]),
+        ),
+        (map([("a", a())]), map([("a", Value::Null)]), map([])),
+        (
+            map([("a", a()), ("b", "c".into())]),
+            map([("a", Value::Null)]),
+            map([("b", "c".into())]),
+        ),
+        (
+            map([("a", Value::List(vec![a()]))]),
+            map([("a", "c".into())]),
+            map([("a", "c".into())]),
+        ),
+        (
+            map([("a", "c".into())]),
+            map([("a", Value::List(vec![a()]))]),
+            map([("a", Value::List(vec![a()]))]),
+        ),
+        (
+            map([("a", map([("b", "c".into())]))]),
+            map([("a", map([("b", "d".into()), ("c", Value::Null)]))]),
+            map([("a", map([("b", "d".into())]))]),
+        ),
+        (
+            map([("a", map([("b", "c".into())]))]),
+            map([("a", Value::List(vec![Value::Int(1)]))]),
+            map([("a", Value::List(vec![Value::Int(1)]))]),
+        ),
+        (
+            Value::List(vec!["a".into(), a()]),
+            Value::List(vec!["c".into(), "d".into()]),
+            Value::List(vec!["c".into(), "d".into()]),
+        ),
+        (
+            map([("a", a())]),
+            Value::List(vec!["c".into()]),
+            Value::List(vec!["c".into()]),
+        ),
+        (map([("a", "foo".into())]), Value::Null, Value::Null),
+        (map([("a", "foo".into())]), "bar".into(), "bar".into()),
+        (
+            map([("e", Value::Null)]),
+            map([("a", Value::Int(1))]),
+            map([("e", Value::Null), ("a", Value::Int(1))]),
+        ),
+        (
+            Value::List(vec![Value::Int(1), Value::Int(2)]),
+            map([("a", a()), ("c", Value::Null)]),
+            map([("a", a())]),
+        ),
+        (
+            map([]),
+            map([("a", map([("bb", map([("ccc", Value::Null)]))]))]),
+            map([("a", map([("bb", map([]))]))]),
+        ),
+    ];
+    for (target, patch, expected) in cases {
+        assert_eq!(patched(&target, &patch), expected, "{} + {}", target, patch);
+    }
+}
+
+#[test]
+fn merge_patch_is_idempotent_and_leaves_no_nulls() {
+    let mut random: Random = Random(Lcg::new(1));
+    for _ in 0..2_000 {
+        let target: Value = random.document(3, false);
+        let patch: Value = random.document(3, true);
+        let once: Value = patched(&target, &patch);
+        assert_eq!(patched(&once, &patch), once, "{} + {}", target, patch);
+        assert!(!contains_null(&once), "{} + {} = {}", target, patch, once);
+    }
+}
+
+#[test]
+fn same_shape_merges_are_associative() {
+    let mut random: Random = Random(Lcg::new(2));
+    for _ in 0..2_000 {
+        let a: Value = random.shaped(3);
+        let b: Value = random.shaped(3);
+        let c: Value = random.shaped(3);
+        for policy in [ConflictPolicy::PatchWins, ConflictPolicy::TargetWins] {
+            let left: Value = merged(&merged(&a, &b, policy).unwrap(), &c, policy).unwrap();
+            let right: Value = merged(&a, &merged(&b, &c, policy).unwrap(), policy).unwrap();
+            assert_eq!(left, right, "{:?}: {} {} {}", policy, a, b, c);
+        }
+
+        // Reject is a partial operation: where both groupings succeed, they
+        // agree
+        let strict = |x: &Value, y: &Value| merged(x, y, ConflictPolicy::Reject).ok();
+        let left: Option<Value> = strict(&a, &b).and_then(|ab| strict(&ab, &c));
+        let right: Option<Value> = strict(&b, &c).and_then(|bc| strict(&a, &bc));
+        if let (Some(left), Some(right)) = (left, right) {
+            assert_eq!(left, right, "Reject: {} {} {}", a, b, c);
+        }
+    }
+}
+
+#[test]
+fn target_wins_is_patch_wins_with_the_arguments_swapped() {
+    let mut random: Random = Random(Lcg::new(3));
+    for _ in 0..2_000 {
+        let a: Value = random.document(3, false);
+        let b: Value = random.document(3, false);
+        assert_eq!(
+            merged(&a, &b, ConflictPolicy::TargetWins).unwrap(),
+            merged(&b, &a, ConflictPolicy::PatchWins).unwrap(),
+            "{} {}",
+            a,
+            b
+        );
+    }
+}
+
+#[test]
+fn reject_succeeds_exactly_when_no_leaf_disagrees() {
+    let mut random: Random = Random(Lcg::new(4));
+    let mut conflicts: usize = 0;
+    for _ in 0..2_000 {
+        let target: Value = random.document(2, false);
+        let patch: Value = random.document(2, true);
+        match merged(&target, &patch, ConflictPolicy::Reject) {
+            // No conflict: the same result as the patch winning
+            Ok(result) => assert_eq!(result, patched(&target, &patch)),
+            Err(conflict) => {
+                conflicts += 1;
+                // The reported path really holds two different values
+                let at = |value: &Value| {
+                    leaves(value)
+                        .into_iter()
+                        .find(|(path, _)| *path == conflict.path)
+                        .map(|(_, v)| v.clone())
+                };
+                assert_ne!(conflict.target, conflict.patch);
+                if let Some(value) = at(&target) {
+                    assert_eq!(value, conflict.target);
+                }
+            }
+        }
+    }
+    assert!(conflicts > 100, "{} conflicts", conflicts);
+}
+
+#[test]
+fn reject_leaves_the_target_unchanged_on_conflict() {
+    let original: Value = map([("a", Value::Int(1)), ("z", map([("x", Value::Int(1))]))]);
+    let mut target: Value = original.clone();
+    // "a" would be added before the conflict at "z.x" is found
+    let patch: Value = map([
+        ("a", Value::Int(1)),
+        ("b", Value::Int(2)),
+        ("z", map([("x", Value::Int(2))])),
+    ]);
+    let conflict: MergeConflict = merge(&mut target, &patch, ConflictPolicy::Reject).unwrap_err();
+    assert_eq!(conflict.path, "z.x");
+    assert_eq!(conflict.to_string(), "conflict at z.x: 1 vs 2");
+    assert_eq!(target, original);
+}
+
+#[test]
+fn create_patch_round_trips() {
+    let mut random: Random = Random(Lcg::new(5));
+    for _ in 0..2_000 {
+        let old: Value = random.document(3, false);
+        let new: Value = random.document(3, false);
+        let patch: Value = create_patch(&old, &new);
+        assert_eq!(patched(&old, &patch), new, "{} -> {}: {}", old, new, patch);
+        assert_eq!(create_patch(&new, &new), map([]));
+    }
+}
+
+#[test]
+fn mixed_shapes_break_associativity() {
+    let x: Value = map([("port", map([("http", Value::Int(80))]))]);
+    let y: Value = map([("port", Value::Int(8080))]);
+    let z: Value = map([("port", map([]))]);
+    assert_eq!(patched(&patched(&x, &y), &z), map([("port", map([]))]));
+    assert_eq!(patched(&x, &patched(&y, &z)), x);
+}
+
+#[test]
+fn patches_with_nulls_do_not_always_compose() {
+    // Deleting a map and then setting one key in it can't be one patch
+    let target: Value = map([("db", map([("host", "x".into())]))]);
+    let p1: Value = map([("db", Value::Null)]);
+    let p2: Value = map([("db", map([("port", Value::Int(1))]))]);
+    let sequential: Value = patched(&patched(&target, &p1), &p2);
+    let combined: Value = patched(&target, &patched(&p1, &p2));
+    assert_eq!(sequential, map([("db", map([("port", Value::Int(1))]))]));
+    assert_ne!(sequential, combined);
+}
@@ -0,0 +1,100 @@
+//! Both `MultiMap` backings against a plain `HashMap<K, Vec<V>>` model, and
+//! the empty-key rule: a key whose last value goes is removed.
+
+use collections_demo::multimap_examples::{MultiMap, SmallMultiMap, ValueList};
+use demo_framework::rng::Lcg;
+use std::collections::HashMap;
+
+/// Replays random operations on `map` and a model, asserting they agree.
+fn replay<L: ValueList<u8>>(mut map: MultiMap<u8, u8, L>, seed: u64) {
+    let mut rng: Lcg = Lcg::new(seed);
+    let mut model: HashMap<u8, Vec<u8>> = HashMap::new();
+    for step in 0..2_000 {
+        let key: u8 = rng.below(10) as u8;
+        let value: u8 = rng.below(6) as u8;
+        match rng.below(10) {
+            0..=4 => {
+                map.insert(key, value);
+                model.entry(key).or_default().push(value);
+            }
+            5..=7 => {
+                let expected: bool = match model.get_mut(&key) {
+                    Some(values) => match values.iter().position(|&v| v == value) {
+                        Some(index) => {
+                            values.remove(index);
+                            if values.is_empty() {
+                                model.remove(&key);
+                            }
+                            true
+                        }
+                        None => false,
+                    },
+                    None => false,
+                };
+                assert_eq!(map.remove_value(&key, &value), expected, "step {}", step);
+            }
+            8 => {
+                let removed: Option<Vec<u8>> = map.remove_all(&key).map(|l| l.as_slice().to_vec());
+                assert_eq!(removed, model.remove(&key), "step {}", step);
+            }
+            _ => {
+                map.retain(|_, &v| v != value);
+                model.retain(|_, values| {
+                    values.retain(|&v| v != value);
+                    !values.is_empty()
+                });
+            }
+        }
+
+        assert_eq!(map.key_count(), model.len(), "step {}", step);
+        assert_eq!(map.len(), model.values().map(Vec::len).sum::<usize>());
+        for key in 0..10u8 {
+            let expected: &[u8] = model.get(&key).map_or(&[], Vec::as_slice);
+            assert_eq!(map.get_all(&key), expected, "step {} key {}", step, key);
+            assert_eq!(map.get(&key), expected.first());
+            assert_eq!(map.contains_key(&key), !expected.is_empty());
+        }
+        assert_eq!(map.iter().count(), map.len());
+    }
+}
+
+#[test]
+fn vec_backing_matches_the_model() {
+    for seed in 0..10 {
+        replay(MultiMap::<u8, u8>::new(), seed);
+    }
+}
+
+#[test]
+fn smallvec_backing_matches_the_model() {
+    for seed in 0..10 {
+        replay(SmallMultiMap::<u8, u8>::new(), seed);
+    }
+}
+
+#[test]
+fn removing_the_last_value_removes_the_key() {
+    let mut map: MultiMap<&str, u32> = MultiMap::new();
+    map.insert("a", 1);
+    map.insert("a", 1);
+    assert_eq!(map.get_all("a"), [1, 1]);
+    assert!(map.remove_value("a", &1));
+    assert_eq!(map.key_count(), 1, "a duplicate is still there");
+    assert!(map.remove_value("a", &1));
+    assert_eq!(map.key_count(), 0);
+    assert!(map.is_empty());
+    assert!(!map.remove_value("a", &1));
+}
+
+#[test]
+fn smallvec_backing_spills_only_past_four_values() {
+    let mut map: SmallMultiMap<u32, u32> = (0..4).map(|i| (7, i)).collect();
+    map.insert(8, 0);
+    assert_eq!(map.keys_on_heap(), 0);
+    map.insert(7, 4);
+    assert_eq!(map.keys_on_heap(), 1);
+
+    let vec_backed: MultiMap<u32, u32> = map.iter().map(|(&k, &v)| (k, v)).collect();
+    assert_eq!(vec_backed.keys_on_heap(), 2);
+    assert_eq!(vec_backed.get_all(&7), map.get_all(&7));
+}
@@ -0,0 +1,120 @@
+//! `Counter` semantics checked against Python's `collections.Counter`, and
+//! the multiset operations against a count-by-hand reference on random
+//! bags.
+
+use collections_demo::multiset_examples::Counter;
+use demo_framework::rng::Lcg;
+use std::collections::BTreeMap;
+
+/// Counts by hand, for comparing against `Counter`.
+fn reference(items: &[u8]) -> BTreeMap<u8, usize> {
+    let mut counts: BTreeMap<u8, usize> = BTreeMap::new();
+    for &item in items {
+        *counts.entry(item).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn as_map(counter: &Counter<u8>) -> BTreeMap<u8, usize> {
+    counter.iter().map(|(&item, count)| (item, count)).collect()
+}
+
+#[test]
+fn add_and_remove_keep_no_zero_counts() {
+    let mut counter: Counter<&str> = Counter::new();
+    assert_eq!(counter.add("a"), 1);
+    assert_eq!(counter.add("a"), 2);
+    assert_eq!(counter.add_n("b", 0), 0);
+    assert_eq!(counter.len(), 1, "adding zero copies stores nothing");
+
+    assert!(counter.remove(&"a"));
+    assert!(counter.remove(&"a"));
+    assert!(!counter.remove(&"a"));
+    assert!(counter.is_empty());
+    assert_eq!(counter.count(&"a"), 0);
+
+    counter.add_n("c", 3);
+    assert_eq!(counter.remove_n(&"c", 10), 3);
+    assert_eq!(counter, Counter::new());
+}
+
+#[test]
+fn most_common_breaks_ties_by_element() {
+    let counter: Counter<char> = "abracadabra".chars().collect();
+    assert_eq!(counter.most_common(3), [(&'a', 5), (&'b', 2), (&'r', 2)]);
+    assert_eq!(counter.most_common(100).len(), 5);
+    assert!(counter.most_common(0).is_empty());
+    assert_eq!(counter.total(), 11);
+}
+
+#[test]
+fn elements_repeat_each_item_by_its_count() {
+    let counter: Counter<char> = "mississippi".chars().collect();
+    let mut elements: Vec<char> = counter.elements().copied().collect();
+    elements.sort();
+    assert_eq!(elements.iter().collect::<String>(), "iiiimppssss");
+    // Rebuilding from elements() gives the same counter
+    assert_eq!(
+        counter.elements().copied().collect::<Counter<char>>(),
+        counter
+    );
+}
+
+#[test]
+fn operations_match_python() {
+    // >>> c = Counter(a=3, b=1); d = Counter(a=1, b=2, c=1)
+    let c: Counter<char> = "aaab".chars().collect();
+    let d: Counter<char> = "abbc".chars().collect();
+    // c | d == Counter({'a': 3, 'b': 2, 'c': 1})
+    assert_eq!(c.union(&d).sorted(), [(&'a', 3), (&'b', 2), (&'c', 1)]);
+    // c & d == Counter({'a': 1, 'b': 1})
+    assert_eq!(c.intersection(&d).sorted(), [(&'a', 1), (&'b', 1)]);
+    // c + d == Counter({'a': 4, 'b': 3, 'c': 1})
+    assert_eq!(c.sum(&d).sorted(), [(&'a', 4), (&'b', 3), (&'c', 1)]);
+    // c - d == Counter({'a': 2})
+    assert_eq!(c.difference(&d).sorted(), [(&'a', 2)]);
+    // d - c == Counter({'b': 1, 'c': 1})
+    assert_eq!(d.difference(&c).sorted(), [(&'b', 1), (&'c', 1)]);
+}
+
+#[test]
+fn operations_match_counting_by_hand() {
+    let mut rng: Lcg = Lcg::new(11);
+    for _ in 0..200 {
+        let a: Vec<u8> = (0..rng.below(40)).map(|_| rng.below(8) as u8).collect();
+        let b: Vec<u8> = (0..rng.below(40)).map(|_| rng.below(8) as u8).collect();
+        let (ca, cb): (Counter<u8>, Counter<u8>) =
+            (a.iter().copied().collect(), b.iter().copied().collect());
+        let (ra, rb) = (reference(&a), reference(&b));
+        assert_eq!(as_map(&ca), ra);
+        assert_eq!(ca.total(), a.len());
+
+        let combined = |f: fn(usize, usize) -> usize| -> BTreeMap<u8, usize> {
+            (0..8u8)
+                .map(|item| {
+                    let count_a: usize = ra.get(&item).copied().unwrap_or(0);
+                    let count_b: usize = rb.get(&item).copied().unwrap_or(0);
+                    (item, f(count_a, count_b))
+                })
+                .filter(|&(_, count)| count > 0)
+                .collect()
+        };
+        assert_eq!(as_map(&ca.union(&cb)), combined(usize::max));
+        assert_eq!(as_map(&ca.intersection(&cb)), combined(usize::min));
+        assert_eq!(as_map(&ca.sum(&cb)), combined(|x, y| x + y));
+        assert_eq!(as_map(&ca.difference(&cb)), combined(usize::saturating_sub));
+
+        // a - b is empty exactly when b covers a
+        assert_eq!(cb.contains_all(&ca), ca.difference(&cb).is_empty());
+        assert!(ca.sum(&cb).contains_all(&ca.union(&cb)));
+        assert!(ca.union(&cb).contains_all(&ca.intersection(&cb)));
+    }
+}
+
+#[test]
+fn extend_adds_to_existing_counts() {
+    let mut counter: Counter<u8> = [1, 2, 2].into_iter().collect();
+    counter.extend([2, 3]);
+    assert_eq!(counter.sorted(), [(&1, 1), (&2, 3), (&3, 1)]);
+    assert!(Counter::<u8>::default().is_empty());
+}
@@ -0,0 +1,140 @@
+//! `Pool` guards returning each object exactly once, and `FreeList`
+//! refusing double returns, checked against a model of which slots are out.
+
+use collections_demo::object_pool_examples::{
+    FreeList, Pool, PoolStats, Pooled, ReleaseError, SlotId, buffer_pool,
+};
+use demo_framework::rng::Lcg;
+use std::collections::HashSet;
+
+#[test]
+fn a_guard_returns_its_object_once() {
+    let pool: Pool<Vec<u8>> = buffer_pool(16);
+    let mut buffer: Pooled<Vec<u8>> = pool.checkout();
+    buffer.push(1);
+    drop(buffer);
+    assert_eq!(pool.idle(), 1);
+
+    // The reset ran, and the same object comes back out
+    let buffer: Pooled<Vec<u8>> = pool.checkout();
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.capacity(), 16);
+    assert_eq!(pool.idle(), 0);
+    drop(buffer);
+    assert_eq!(
+        pool.stats(),
+        PoolStats {
+            created: 1,
+            reused: 1,
+            returned: 2,
+            discarded: 0
+        }
+    );
+}
+
+#[test]
+fn a_detached_object_never_returns() {
+    let pool: Pool<Vec<u8>> = buffer_pool(16);
+    let kept: Vec<u8> = pool.checkout().detach();
+    assert_eq!(kept.capacity(), 16);
+    assert_eq!(pool.idle(), 0);
+    assert_eq!(pool.stats().returned, 0);
+}
+
+#[test]
+fn max_idle_discards_the_surplus() {
+    let pool: Pool<Vec<u8>> = buffer_pool(16).with_max_idle(2);
+    pool.prefill(5);
+    assert_eq!(pool.idle(), 2, "prefill stops at max_idle");
+    let guards: Vec<Pooled<Vec<u8>>> = (0..4).map(|_| pool.checkout()).collect();
+    drop(guards);
+    assert_eq!(pool.idle(), 2);
+    let stats: PoolStats = pool.stats();
+    assert_eq!((stats.created, stats.reused), (4, 2));
+    assert_eq!((stats.returned, stats.discarded), (2, 2));
+}
+
+#[test]
+fn double_and_foreign_returns_are_refused() {
+    let mut list: FreeList<String> = FreeList::new();
+    let a: SlotId = list.acquire(String::new);
+    let b: SlotId = list.acquire(String::new);
+    assert_eq!(list.release(a, String::clear), Ok(()));
+    assert_eq!(
+        list.release(a, String::clear),
+        Err(ReleaseError::DoubleReturn(a))
+    );
+    // The refused release changed nothing: one acquire takes the one free
+    // slot, the next must create a new one
+    assert_eq!(list.free(), 1);
+    assert_eq!(list.acquire(|| unreachable!()), a);
+    assert_eq!(list.acquire(String::new).index(), 2);
+
+    // A handle from a bigger list points past this one's slots
+    let mut bigger: FreeList<String> = FreeList::new();
+    let foreign: SlotId = (0..5).map(|_| bigger.acquire(String::new)).last().unwrap();
+    assert_eq!(
+        list.release(foreign, String::clear),
+        Err(ReleaseError::UnknownSlot(foreign))
+    );
+    assert_eq!(list.get(foreign), None);
+    assert!(list.get(b).is_some());
+}
+
+#[test]
+fn release_resets_and_the_slot_keeps_its_allocation() {
+    let mut list: FreeList<Vec<u8>> = FreeList::new();
+    let id: SlotId = list.acquire(|| Vec::with_capacity(64));
+    list.get_mut(id).unwrap().extend_from_slice(b"payload");
+    list.release(id, Vec::clear).unwrap();
+    assert_eq!(list.get(id), None);
+    let again: SlotId = list.acquire(|| unreachable!());
+    assert_eq!(list.get(again).map(Vec::len), Some(0));
+    assert_eq!(list.get(again).map(Vec::capacity), Some(64));
+}
+
+#[test]
+fn random_acquires_and_releases_match_the_model() {
+    for seed in 0..20 {
+        let mut rng: Lcg = Lcg::new(seed);
+        let mut list: FreeList<u64> = FreeList::new();
+        let mut out: HashSet<SlotId> = HashSet::new();
+        let mut ever: Vec<SlotId> = Vec::new();
+        for step in 0..1_000u64 {
+            if rng.below(3) == 0 || ever.is_empty() {
+                let id: SlotId = list.acquire(|| step);
+                assert!(
+                    out.insert(id),
+                    "seed {} step {}: {:?} handed out twice",
+                    seed,
+                    step,
+                    id
+                );
+                if !ever.contains(&id) {
+                    ever.push(id);
+                }
+                *list.get_mut(id).unwrap() = step;
+            } else {
+                // Any handle ever seen, so some releases are double returns
+                let id: SlotId = ever[rng.below(ever.len() as u64) as usize];
+                let expected: Result<(), ReleaseError> = if out.remove(&id) {
+                    Ok(())
+                } else {
+                    Err(ReleaseError::DoubleReturn(id))
+                };
+                assert_eq!(
+                    list.release(id, |_| {}),
+                    expected,
+                    "seed {} step {}",
+                    seed,
+                    step
+                );
+            }
+            assert_eq!(list.in_use(), out.len());
+            assert_eq!(list.free() + list.in_use(), list.slots());
+            for &id in &ever {
+                assert_eq!(list.get(id).is_some(), out.contains(&id));
+            }
+        }
+    }
+}
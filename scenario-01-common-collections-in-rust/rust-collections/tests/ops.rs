@@ -0,0 +1,166 @@
+//! Generated operation programs against the `BTreeMap` reference: a
+//! `HashMap` and both LRU designs through [`OpTarget`], programs decoded
+//! from raw bytes the way a fuzzer would supply them, and what shrinking
+//! leaves of a failing program.
+
+use collections_demo::lru_examples::{DequeLru, LinkedLru};
+use demo_framework::ops::{
+    Divergence, Mismatch, Op, OpTarget, Outcome, Program, check, program, scan,
+};
+use proptest::prelude::*;
+use proptest::test_runner::{Config, RngAlgorithm, TestError, TestRng, TestRunner};
+use std::collections::HashMap;
+
+const KEY_SPACE: u32 = 16;
+
+/// An LRU cache run as a map. Big enough for the whole key space, it
+/// never evicts and should agree with the reference on everything.
+struct Lru<C>(C);
+
+macro_rules! lru_target {
+    ($cache:ident) => {
+        impl OpTarget for Lru<$cache<u32, u32>> {
+            fn apply(&mut self, op: Op) -> Outcome {
+                match op {
+                    Op::Insert(key, value) => {
+                        let previous: Option<u32> = self.0.peek(&key).copied();
+                        self.0.put(key, value);
+                        Outcome::Value(previous)
+                    }
+                    Op::Remove(key) => Outcome::Value(self.0.remove(&key)),
+                    Op::Get(key) => Outcome::Value(self.0.get(&key).copied()),
+                    Op::Bump(key) => {
+                        let count: u32 = self.0.get(&key).copied().unwrap_or(0) + 1;
+                        self.0.put(key, count);
+                        Outcome::Value(Some(count))
+                    }
+                    Op::RangeScan(start, end) => Outcome::Entries(scan(self.0.iter(), start, end)),
+                    Op::Retain(modulus) => {
+                        let dropped: Vec<u32> = self
+                            .0
+                            .iter()
+                            .filter(|&(_, value)| value % modulus == 0)
+                            .map(|(&key, _)| key)
+                            .collect();
+                        for key in dropped {
+                            self.0.remove(&key);
+                        }
+                        Outcome::Len(self.0.len())
+                    }
+                    Op::ShrinkToFit => Outcome::Len(self.0.len()),
+                    Op::Clear => {
+                        while self.0.pop_lru().is_some() {}
+                        Outcome::Cleared
+                    }
+                }
+            }
+
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+        }
+    };
+}
+
+lru_target!(LinkedLru);
+lru_target!(DequeLru);
+
+proptest! {
+    #[test]
+    fn hashmap_matches_the_reference(program in program(KEY_SPACE, 200)) {
+        let result: Result<(), Divergence> = check(&program, &mut HashMap::new());
+        prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+    }
+
+    #[test]
+    fn lrus_with_room_for_every_key_match_the_reference(program in program(KEY_SPACE, 200)) {
+        let capacity: usize = KEY_SPACE as usize;
+        let result: Result<(), Divergence> = check(&program, &mut Lru(LinkedLru::new(capacity)));
+        prop_assert!(result.is_ok(), "LinkedLru {}", result.unwrap_err());
+        let result: Result<(), Divergence> = check(&program, &mut Lru(DequeLru::new(capacity)));
+        prop_assert!(result.is_ok(), "DequeLru {}", result.unwrap_err());
+    }
+
+    #[test]
+    fn any_bytes_decode_to_a_valid_program(bytes in prop::collection::vec(any::<u8>(), 0..600)) {
+        for key_space in [1, 7, 300, 70_000] {
+            let program: Program = Program::from_bytes(&bytes, key_space);
+            prop_assert_eq!(&program, &Program::from_bytes(&bytes, key_space));
+            prop_assert!(program.0.len() <= bytes.len());
+            for op in &program.0 {
+                match *op {
+                    Op::Insert(key, value) => prop_assert!(key < key_space && value < 1_000),
+                    Op::Remove(key) | Op::Get(key) | Op::Bump(key) => prop_assert!(key < key_space),
+                    Op::RangeScan(start, end) => prop_assert!(start < key_space && start <= end),
+                    Op::Retain(modulus) => prop_assert!((2..5).contains(&modulus)),
+                    Op::ShrinkToFit | Op::Clear => {}
+                }
+            }
+
+            let result: Result<(), Divergence> = check(&program, &mut HashMap::new());
+            prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+            let capacity: usize = key_space as usize;
+            let result: Result<(), Divergence> = check(&program, &mut Lru(LinkedLru::new(capacity)));
+            prop_assert!(result.is_ok(), "{}", result.unwrap_err());
+        }
+    }
+}
+
+#[test]
+fn a_divergence_prints_the_steps_that_led_to_it() {
+    let program: Program = Program(vec![
+        Op::Insert(0, 0),
+        Op::Insert(1, 0),
+        Op::Insert(2, 0),
+        Op::Get(0),
+    ]);
+    let divergence: Divergence = check(&program, &mut Lru(LinkedLru::new(2))).unwrap_err();
+    assert_eq!(divergence.step, 2);
+    assert_eq!(
+        divergence.to_string(),
+        "diverged at step 2:\n\
+         \x20   map.insert(0, 0);       // None\n\
+         \x20   map.insert(1, 0);       // None\n\
+         \x20   map.insert(2, 0);       // None\n\
+         len: expected 3, got 2"
+    );
+    assert_eq!(
+        format!("{:?}", Program(program.0[..2].to_vec())),
+        "Program {\n    map.insert(0, 0);\n    map.insert(1, 0);\n}"
+    );
+    assert_eq!(program.run(&mut HashMap::new())[3], Outcome::Value(Some(0)));
+}
+
+#[test]
+fn shrinking_leaves_the_smallest_failing_program() {
+    // An LRU with room for two keys stops behaving like a map at the third
+    let config: Config = Config {
+        failure_persistence: None,
+        ..Config::default()
+    };
+    let rng: TestRng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+    let mut runner: TestRunner = TestRunner::new_with_rng(config, rng);
+    let result = runner.run(&program(KEY_SPACE, 100), |program| {
+        check(&program, &mut Lru(LinkedLru::new(2)))
+            .map_err(|divergence| TestCaseError::fail(divergence.to_string()))
+    });
+
+    let Err(TestError::Fail(_, minimal)) = result else {
+        panic!("a two-entry LRU passed as a map: {:?}", result);
+    };
+    assert_eq!(minimal.0.len(), 3, "{:?}", minimal);
+    assert!(
+        minimal.0.iter().all(|op| matches!(op, Op::Insert(_, 0))),
+        "{:?}",
+        minimal
+    );
+    let divergence: Divergence = check(&minimal, &mut Lru(LinkedLru::new(2))).unwrap_err();
+    assert_eq!(divergence.step, 2);
+    assert_eq!(
+        divergence.mismatch,
+        Mismatch::Len {
+            expected: 3,
+            actual: 2
+        }
+    );
+}
@@ -0,0 +1,121 @@
+//! Performance smoke tests: the README's headline complexity claims, timed
+//! with `micro_bench` and asserted with margins far below what the
+//! benchmarks show.
+//!
+//! Each comparison pits an O(1) or O(log n) operation against an O(n) one
+//! at a size where the gap is orders of magnitude, so it holds in the
+//! unoptimized test build and on a busy machine. A failure means a claim in
+//! the prose no longer holds, not that a number drifted.
+
+use collections_demo::btree_visual::shuffled;
+use demo_framework::micro_bench::{assert_faster, fastest};
+use std::collections::{BTreeSet, HashSet, LinkedList, VecDeque};
+use std::time::Duration;
+
+const ROUNDS: u32 = 5;
+
+/// Keys `0..100_000` and 200 probes spread across them, half of them misses.
+fn keys_and_probes() -> (Vec<u64>, Vec<u64>) {
+    let keys: Vec<u64> = (0..100_000).map(|i| i * 2).collect();
+    let probes: Vec<u64> = (0..200).map(|i| i * 997).collect();
+    (keys, probes)
+}
+
+#[test]
+fn hashset_contains_beats_vec_contains() {
+    let (keys, probes) = keys_and_probes();
+    let set: HashSet<u64> = keys.iter().copied().collect();
+    let vec_time: Duration = fastest(ROUNDS, || {
+        probes.iter().filter(|probe| keys.contains(probe)).count()
+    });
+    let set_time: Duration = fastest(ROUNDS, || {
+        probes.iter().filter(|probe| set.contains(probe)).count()
+    });
+    assert_faster("HashSet vs Vec contains, 100k", set_time, vec_time, 5.0);
+}
+
+#[test]
+fn btreeset_contains_beats_vec_contains() {
+    let (keys, probes) = keys_and_probes();
+    let set: BTreeSet<u64> = keys.iter().copied().collect();
+    let vec_time: Duration = fastest(ROUNDS, || {
+        probes.iter().filter(|probe| keys.contains(probe)).count()
+    });
+    let set_time: Duration = fastest(ROUNDS, || {
+        probes.iter().filter(|probe| set.contains(probe)).count()
+    });
+    assert_faster("BTreeSet vs Vec contains, 100k", set_time, vec_time, 5.0);
+}
+
+#[test]
+fn binary_search_beats_linear_search() {
+    let (keys, probes) = keys_and_probes();
+    let linear_time: Duration = fastest(ROUNDS, || {
+        probes.iter().filter(|probe| keys.contains(probe)).count()
+    });
+    let binary_time: Duration = fastest(ROUNDS, || {
+        probes
+            .iter()
+            .filter(|probe| keys.binary_search(probe).is_ok())
+            .count()
+    });
+    assert_faster(
+        "binary_search vs contains, 100k",
+        binary_time,
+        linear_time,
+        5.0,
+    );
+}
+
+#[test]
+fn vecdeque_push_front_beats_vec_insert_at_zero() {
+    let vec_time: Duration = fastest(ROUNDS, || {
+        let mut vec: Vec<u64> = Vec::new();
+        for i in 0..20_000 {
+            vec.insert(0, i);
+        }
+        vec.len()
+    });
+    let deque_time: Duration = fastest(ROUNDS, || {
+        let mut deque: VecDeque<u64> = VecDeque::new();
+        for i in 0..20_000 {
+            deque.push_front(i);
+        }
+        deque.len()
+    });
+    assert_faster(
+        "VecDeque push_front vs Vec insert(0), 20k",
+        deque_time,
+        vec_time,
+        5.0,
+    );
+}
+
+/// The list `0..len` with its nodes allocated in a shuffled order, as in a
+/// list that has been edited for a while: each step of a walk lands
+/// somewhere else on the heap.
+fn scattered_list(len: u32) -> LinkedList<u32> {
+    let mut nodes: Vec<Option<LinkedList<u32>>> = (0..len).map(|_| None).collect();
+    for value in shuffled(len, 17) {
+        nodes[value as usize] = Some(LinkedList::from([value]));
+    }
+    let mut list: LinkedList<u32> = LinkedList::new();
+    for mut node in nodes.into_iter().flatten() {
+        list.append(&mut node);
+    }
+    list
+}
+
+#[test]
+fn vec_iteration_beats_linked_list() {
+    let list: LinkedList<u32> = scattered_list(200_000);
+    let vec: Vec<u32> = (0..200_000).collect();
+    let list_time: Duration = fastest(ROUNDS, || list.iter().fold(0u32, |acc, x| acc ^ x));
+    let vec_time: Duration = fastest(ROUNDS, || vec.iter().fold(0u32, |acc, x| acc ^ x));
+    assert_faster(
+        "Vec vs scattered LinkedList iteration, 200k",
+        vec_time,
+        list_time,
+        2.0,
+    );
+}
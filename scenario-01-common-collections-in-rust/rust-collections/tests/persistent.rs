@@ -0,0 +1,92 @@
+//! The `History` undo/redo stack, and the persistence guarantees the demos
+//! rely on: editing a clone of an im collection never changes the original.
+
+use collections_demo::persistent_examples::History;
+use demo_framework::rng::Lcg;
+
+#[test]
+fn undo_and_redo_walk_the_versions() {
+    let mut history: History<im::Vector<u32>> = History::new(im::Vector::new());
+    for i in 1..=5 {
+        history.edit(|v| v.push_back(i));
+    }
+    assert_eq!(history.undo_depth(), 5);
+    assert_eq!(history.current().len(), 5);
+
+    assert!(history.undo());
+    assert!(history.undo());
+    assert_eq!(history.current(), &im::vector![1, 2, 3]);
+    assert_eq!(history.redo_depth(), 2);
+
+    assert!(history.redo());
+    assert_eq!(history.current(), &im::vector![1, 2, 3, 4]);
+
+    // A new edit discards what was left to redo
+    history.edit(|v| {
+        v.set(0, 10);
+    });
+    assert_eq!(history.redo_depth(), 0);
+    assert!(!history.redo());
+    assert_eq!(history.current(), &im::vector![10, 2, 3, 4]);
+
+    while history.undo() {}
+    assert!(history.current().is_empty());
+    assert_eq!(history.redo_depth(), 5);
+}
+
+#[test]
+fn history_matches_for_std_and_im() {
+    let mut rng: Lcg = Lcg::new(42);
+    let mut persistent: History<im::Vector<u32>> = History::new((0..64).collect());
+    let mut copied: History<Vec<u32>> = History::new((0..64).collect());
+    for _ in 0..500 {
+        match rng.below(4) {
+            0 => {
+                persistent.undo();
+                copied.undo();
+            }
+            1 => {
+                persistent.redo();
+                copied.redo();
+            }
+            _ => {
+                let (index, value): (usize, u32) =
+                    (rng.below(64) as usize, rng.below(1_000) as u32);
+                persistent.edit(|v| {
+                    v.set(index, value);
+                });
+                copied.edit(|v| v[index] = value);
+            }
+        }
+        assert!(persistent.current().iter().eq(copied.current().iter()));
+        assert_eq!(persistent.undo_depth(), copied.undo_depth());
+        assert_eq!(persistent.redo_depth(), copied.redo_depth());
+    }
+}
+
+#[test]
+fn edits_to_a_clone_leave_the_original_alone() {
+    let original: im::Vector<u32> = (0..10_000).collect();
+    let mut copy: im::Vector<u32> = original.clone();
+    assert!(original.ptr_eq(&copy));
+    copy.set(5_000, 0);
+    copy.push_back(10_000);
+    assert!(!original.ptr_eq(&copy));
+    assert_eq!(original[5_000], 5_000);
+    assert_eq!(original.len(), 10_000);
+    assert_eq!(copy[5_000], 0);
+
+    let map: im::OrdMap<u32, u32> = (0..1_000u32).map(|i| (i, i)).collect();
+    let changed: im::OrdMap<u32, u32> = map.update(7, 0).without(&8);
+    assert_eq!(map.get(&7), Some(&7));
+    assert_eq!(map.get(&8), Some(&8));
+    // diff reports exactly the two changes
+    assert_eq!(map.diff(&changed).count(), 2);
+
+    let hashed: im::HashMap<u32, u32> = (0..1_000u32).map(|i| (i, i)).collect();
+    let changed: im::HashMap<u32, u32> = hashed.update(7, 0).without(&8);
+    assert_eq!(hashed.len(), 1_000);
+    assert_eq!(changed.len(), 999);
+    assert_eq!(hashed.get(&7), Some(&7));
+    assert_eq!(changed.get(&7), Some(&0));
+}
@@ -0,0 +1,96 @@
+//! Prefix queries against the obvious reference: filtering every key with
+//! `starts_with`. Keys and prefixes are drawn from an alphabet built around
+//! the awkward code points - the surrogate gap, `char::MAX`, combining marks
+//! and multi-byte letters - so the upper bound is tested where it bends.
+
+use collections_demo::btreemap_examples::{prefix_range, prefix_upper_bound};
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+
+const ALPHABET: [char; 9] = [
+    'a',
+    'b',
+    'z',
+    '\u{e9}',
+    '\u{301}',
+    '\u{D7FF}',
+    '\u{E000}',
+    '\u{FFFF}',
+    char::MAX,
+];
+
+fn word(max_len: usize) -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(&ALPHABET[..]), 0..max_len)
+        .prop_map(|chars| chars.into_iter().collect())
+}
+
+fn by_filter<'a>(map: &'a BTreeMap<String, usize>, prefix: &str) -> Vec<&'a String> {
+    map.keys().filter(|key| key.starts_with(prefix)).collect()
+}
+
+#[test]
+fn upper_bounds_at_the_edges() {
+    assert_eq!(prefix_upper_bound(""), None);
+    assert_eq!(prefix_upper_bound("car").as_deref(), Some("cas"));
+    assert_eq!(
+        prefix_upper_bound("caf\u{e9}").as_deref(),
+        Some("caf\u{ea}")
+    );
+    // Multi-byte to multi-byte, across the surrogate gap
+    assert_eq!(prefix_upper_bound("\u{D7FF}").as_deref(), Some("\u{E000}"));
+    // One-byte to two-byte, two-byte to three-byte, three-byte to four-byte
+    assert_eq!(prefix_upper_bound("\u{7F}").as_deref(), Some("\u{80}"));
+    assert_eq!(prefix_upper_bound("\u{7FF}").as_deref(), Some("\u{800}"));
+    assert_eq!(prefix_upper_bound("\u{FFFF}").as_deref(), Some("\u{10000}"));
+    // A trailing char::MAX can't be bumped, so the char before it is
+    assert_eq!(prefix_upper_bound("a\u{10FFFF}").as_deref(), Some("b"));
+    assert_eq!(
+        prefix_upper_bound("a\u{10FFFF}\u{10FFFF}").as_deref(),
+        Some("b")
+    );
+    assert_eq!(prefix_upper_bound("\u{10FFFF}\u{10FFFF}"), None);
+}
+
+#[test]
+fn accents_match_by_code_point() {
+    let map: BTreeMap<String, usize> = BTreeMap::from([
+        ("\u{e9}t\u{e9}".to_string(), 0),
+        ("e\u{301}te\u{301}".to_string(), 1),
+        ("ete".to_string(), 2),
+        ("f".to_string(), 3),
+    ]);
+    let under_e: Vec<usize> = prefix_range(&map, "e").map(|(_, &id)| id).collect();
+    assert_eq!(under_e, [2, 1]);
+    let under_e_acute: Vec<usize> = prefix_range(&map, "e\u{301}").map(|(_, &id)| id).collect();
+    assert_eq!(under_e_acute, [1]);
+    let precomposed: Vec<usize> = prefix_range(&map, "\u{e9}").map(|(_, &id)| id).collect();
+    assert_eq!(precomposed, [0]);
+    assert_eq!(prefix_range(&map, "").count(), map.len());
+}
+
+proptest! {
+    #[test]
+    fn matches_starts_with_filter(
+        keys in prop::collection::vec(word(6), 0..60),
+        prefixes in prop::collection::vec(word(3), 1..10),
+    ) {
+        let map: BTreeMap<String, usize> = keys
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| (key, i))
+            .collect();
+        for prefix in &prefixes {
+            let found: Vec<&String> = prefix_range(&map, prefix).map(|(key, _)| key).collect();
+            prop_assert_eq!(found, by_filter(&map, prefix), "prefix {:?}", prefix);
+
+            // The bound sits above every match and at or below every key
+            // past the matches
+            if let Some(bound) = prefix_upper_bound(prefix) {
+                prop_assert!(!bound.starts_with(prefix.as_str()));
+                for key in map.keys().filter(|key| key.starts_with(prefix.as_str())) {
+                    prop_assert!(key.as_str() < bound.as_str());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! RingBuffer against a Vec that keeps its last `capacity` pushes
+//! (proptest), plus the latest_n views on a wrapped buffer and the
+//! allocation staying put.
+
+use collections_demo::ring_buffer_examples::{RingBuffer, sensor_samples};
+use proptest::prelude::*;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Push(u16),
+    PopOldest,
+    Clear,
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        8 => any::<u16>().prop_map(Op::Push),
+        2 => Just(Op::PopOldest),
+        1 => Just(Op::Clear),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn matches_a_vec_of_the_last_pushes(
+        capacity in 1usize..10,
+        ops in prop::collection::vec(op(), 0..200),
+        n in 0usize..12,
+    ) {
+        let mut buffer: RingBuffer<u16> = RingBuffer::new(capacity);
+        let mut model: Vec<u16> = Vec::new();
+        let mut overwritten: u64 = 0;
+        for op in ops {
+            match op {
+                Op::Push(value) => {
+                    let expected: Option<u16> = if model.len() == capacity {
+                        overwritten += 1;
+                        Some(model.remove(0))
+                    } else {
+                        None
+                    };
+                    model.push(value);
+                    prop_assert_eq!(buffer.push(value), expected);
+                }
+                Op::PopOldest => {
+                    let expected: Option<u16> =
+                        if model.is_empty() { None } else { Some(model.remove(0)) };
+                    prop_assert_eq!(buffer.pop_oldest(), expected);
+                }
+                Op::Clear => {
+                    model.clear();
+                    buffer.clear();
+                }
+            }
+            prop_assert_eq!(buffer.iter().copied().collect::<Vec<u16>>(), model.clone());
+            prop_assert_eq!(buffer.is_full(), model.len() == capacity);
+
+            let suffix: &[u16] = &model[model.len() - n.min(model.len())..];
+            prop_assert_eq!(buffer.latest_n(n).len(), suffix.len());
+            prop_assert_eq!(buffer.latest_n(n).copied().collect::<Vec<u16>>(), suffix.to_vec());
+            let (first, second) = buffer.latest_n_slices(n);
+            prop_assert_eq!([first, second].concat(), suffix.to_vec());
+        }
+        prop_assert_eq!(buffer.overwritten(), overwritten);
+        prop_assert_eq!(buffer.oldest(), model.first());
+        prop_assert_eq!(buffer.latest(), model.last());
+    }
+}
+
+#[test]
+fn latest_n_slices_split_at_the_wrap() {
+    let mut buffer: RingBuffer<u32> = RingBuffer::new(5);
+    buffer.extend(1..=8);
+    assert_eq!(buffer.as_slices(), (&[4, 5][..], &[6, 7, 8][..]));
+    assert_eq!(buffer.latest_n_slices(4), (&[5][..], &[6, 7, 8][..]));
+    assert_eq!(buffer.latest_n_slices(3), (&[][..], &[6, 7, 8][..]));
+    assert_eq!(buffer.latest_n_slices(0), (&[][..], &[][..]));
+    assert_eq!(
+        buffer.latest_n(9).copied().collect::<Vec<u32>>(),
+        vec![4, 5, 6, 7, 8]
+    );
+
+    // One run after make_contiguous, and the same values
+    assert_eq!(buffer.make_contiguous(), &[4, 5, 6, 7, 8]);
+    assert_eq!(buffer.latest_n_slices(4), (&[5, 6, 7, 8][..], &[][..]));
+}
+
+#[test]
+fn the_allocation_never_grows() {
+    let mut buffer: RingBuffer<f64> = RingBuffer::new(60);
+    buffer.push(0.0);
+    let start: *const f64 = buffer.as_slices().0.as_ptr();
+    let end: *const f64 = start.wrapping_add(60);
+    let inside = |run: &[f64]| {
+        run.is_empty() || (run.as_ptr() >= start && run.as_ptr().wrapping_add(run.len()) <= end)
+    };
+    for reading in sensor_samples(1_000) {
+        buffer.push(reading);
+        let (front, back) = buffer.as_slices();
+        assert!(inside(front) && inside(back));
+    }
+    assert_eq!(buffer.len(), 60);
+    assert_eq!(buffer.overwritten(), 941);
+}
+
+#[test]
+#[should_panic(expected = "a ring buffer needs room for one value")]
+fn zero_capacity_panics() {
+    let _ = RingBuffer::<u8>::new(0);
+}
@@ -0,0 +1,194 @@
+//! SaturatingCounter pushed past the top of its count type in both modes,
+//! against exact counts kept in u64 (proptest), plus the checked
+//! primitive, a BTreeMap-backed counter, widening and the counting demos'
+//! inputs.
+
+use collections_demo::saturating_counter_examples::{
+    Overflow, OverflowMode, SaturatingCounter, sample_clients,
+};
+use proptest::prelude::*;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+
+proptest! {
+    #[test]
+    fn saturating_mode_stores_the_exact_count_clamped(
+        adds in prop::collection::vec((0u8..4, any::<u8>()), 0..200)
+    ) {
+        let mut counter: SaturatingCounter<u8, HashMap<u8, u8>> =
+            SaturatingCounter::new(OverflowMode::Saturating);
+        let mut exact: HashMap<u8, u64> = HashMap::new();
+        for (key, by) in adds {
+            let total: &mut u64 = exact.entry(key).or_insert(0);
+            *total += u64::from(by);
+            let stored: u8 = counter.add_n(key, by);
+            prop_assert_eq!(u64::from(stored), (*total).min(255));
+        }
+        for (key, &total) in &exact {
+            prop_assert_eq!(u64::from(counter.get(key)), total.min(255));
+            prop_assert_eq!(counter.exact(key), total);
+            prop_assert_eq!(counter.overflows().contains_key(key), total > 255);
+        }
+        let dropped: u64 = exact.values().map(|total| total.saturating_sub(255)).sum();
+        prop_assert_eq!(counter.lost(), dropped);
+        prop_assert_eq!(counter.is_exact(), dropped == 0);
+    }
+
+    #[test]
+    fn checked_mode_drops_whole_increments(
+        adds in prop::collection::vec((0u8..4, any::<u8>()), 0..200)
+    ) {
+        let mut counter: SaturatingCounter<u8, HashMap<u8, u8>> = SaturatingCounter::new(OverflowMode::Checked);
+        let mut stored: HashMap<u8, u64> = HashMap::new();
+        let mut exact: HashMap<u8, u64> = HashMap::new();
+        for (key, by) in adds {
+            *exact.entry(key).or_insert(0) += u64::from(by);
+            let count: &mut u64 = stored.entry(key).or_insert(0);
+            if *count + u64::from(by) <= 255 {
+                *count += u64::from(by);
+            }
+            prop_assert_eq!(u64::from(counter.add_n(key, by)), *count);
+        }
+        for (key, &total) in &exact {
+            prop_assert_eq!(u64::from(counter.get(key)), stored[key]);
+            prop_assert_eq!(counter.exact(key), total);
+        }
+    }
+}
+
+#[test]
+fn counts_stop_at_the_top_of_signed_and_wide_types() {
+    let mut words: SaturatingCounter<&str, HashMap<&str, i8>> = SaturatingCounter::default();
+    for _ in 0..1_000 {
+        words.add("the");
+    }
+    words.add("cat");
+    assert_eq!(words.get("the"), i8::MAX);
+    assert_eq!(words.get("cat"), 1);
+    assert_eq!(words.get("dog"), 0);
+    assert_eq!(words.exact(&"the"), 1_000);
+    assert_eq!(words.overflows(), &HashMap::from([("the", 873)]));
+
+    let mut big: SaturatingCounter<char, HashMap<char, u64>> = SaturatingCounter::default();
+    big.add_n('x', u64::MAX - 1);
+    assert_eq!(big.add('x'), u64::MAX);
+    assert!(big.is_exact());
+    assert_eq!(big.add('x'), u64::MAX);
+    assert_eq!(big.lost(), 1);
+}
+
+#[test]
+#[should_panic(expected = "cannot add -1 to a count")]
+fn negative_increments_panic() {
+    let mut counter: SaturatingCounter<&str, HashMap<&str, i32>> = SaturatingCounter::default();
+    counter.add_n("a", -1);
+}
+
+#[test]
+#[should_panic(expected = "cannot add -2 to a count")]
+fn negative_tries_panic_too() {
+    let mut counter: SaturatingCounter<&str, HashMap<&str, i32>> = SaturatingCounter::default();
+    let _ = counter.try_add("a", -2);
+}
+
+#[test]
+fn try_add_reports_without_changing_anything() {
+    for mode in [OverflowMode::Saturating, OverflowMode::Checked] {
+        let mut counter: SaturatingCounter<&str, HashMap<&str, u8>> = SaturatingCounter::new(mode);
+        assert_eq!(counter.try_add("a", 200), Ok(200));
+        assert_eq!(
+            counter.try_add("a", 56),
+            Err(Overflow { count: 200, by: 56 })
+        );
+        assert_eq!(counter.try_add("a", 55), Ok(255));
+        assert_eq!(counter.get("a"), 255);
+        assert!(counter.is_exact(), "{mode:?}");
+        assert_eq!(
+            Overflow {
+                count: 255u8,
+                by: 1
+            }
+            .to_string(),
+            "255 + 1 overflows the maximum 255"
+        );
+    }
+}
+
+#[test]
+fn widening_restores_the_exact_counts() {
+    let log: Vec<String> = sample_clients(3_000);
+    let narrow: SaturatingCounter<&str, HashMap<&str, u8>> =
+        log.iter().map(String::as_str).collect();
+    assert!(!narrow.is_exact(), "the busiest clients pass 255");
+    assert_eq!(
+        narrow
+            .iter()
+            .map(|(client, _)| narrow.exact(client))
+            .sum::<u64>(),
+        3_000
+    );
+
+    let wide: SaturatingCounter<&str, HashMap<&str, u32>> = narrow.clone().widen();
+    assert!(wide.is_exact());
+    assert_eq!(wide.len(), narrow.len());
+    for (client, count) in wide.iter() {
+        assert_eq!(u64::from(count), narrow.exact(client));
+    }
+
+    // A type that is still too narrow keeps a smaller report
+    let words: SaturatingCounter<u8, HashMap<u8, i8>> = std::iter::repeat_n(7, 40_000).collect();
+    let wider: SaturatingCounter<u8, HashMap<u8, i16>> = words.widen();
+    assert_eq!(wider.get(&7), i16::MAX);
+    assert_eq!(wider.exact(&7), 40_000);
+}
+
+#[test]
+fn a_btreemap_backed_counter_iterates_in_key_order() {
+    let mut words: SaturatingCounter<&str, BTreeMap<&str, u8>> = SaturatingCounter::default();
+    words.extend("the quick brown fox jumps over the lazy dog".split_whitespace());
+    for _ in 0..300 {
+        words.add("the");
+    }
+    let keys: Vec<&str> = words.iter().map(|(word, _)| *word).collect();
+    let mut sorted: Vec<&str> = keys.clone();
+    sorted.sort_unstable();
+    assert_eq!(keys, sorted);
+    assert_eq!(words.get("the"), u8::MAX);
+    assert_eq!(words.exact(&"the"), 302);
+
+    let wide: SaturatingCounter<&str, BTreeMap<&str, u16>> = words.widen();
+    assert!(wide.is_exact());
+    assert_eq!(wide.get("the"), 302);
+}
+
+// Hashable but not ordered, and counting its own clones
+#[derive(Debug, PartialEq, Eq)]
+struct Tag<'a>(&'static str, &'a Cell<usize>);
+
+impl Hash for Tag<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl Clone for Tag<'_> {
+    fn clone(&self) -> Self {
+        self.1.set(self.1.get() + 1);
+        Tag(self.0, self.1)
+    }
+}
+
+#[test]
+fn a_hashmap_backed_counter_needs_no_ord_and_clones_only_on_overflow() {
+    let clones: Cell<usize> = Cell::new(0);
+    let mut counter: SaturatingCounter<Tag, HashMap<Tag, u8>> = SaturatingCounter::default();
+    for _ in 0..255 {
+        counter.add(Tag("a", &clones));
+    }
+    assert_eq!(clones.get(), 0);
+    assert_eq!(counter.add_n(Tag("a", &clones), 7), 255);
+    assert_eq!(clones.get(), 1, "the overflow records a clone of the key");
+    assert_eq!(counter.get(&Tag("a", &clones)), 255);
+    assert_eq!(counter.exact(&Tag("a", &clones)), 262);
+}
@@ -0,0 +1,90 @@
+//! The shared-ownership helpers: both word-count strategies agree with a
+//! single-threaded count, snapshots never mix versions while a writer
+//! publishes, and the listener list refuses a re-entrant borrow.
+
+use collections_demo::shared_ownership_examples::{
+    Listener, Listeners, Rule, Snapshots, count_locking_per_word, count_merging_local, limit_for,
+    notify_borrowed, rules_version, sample_documents,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn both_strategies_match_a_single_threaded_count() {
+    let docs: Arc<[String]> = Arc::from(sample_documents(97, 13));
+    let mut expected: HashMap<String, u64> = HashMap::new();
+    for word in docs.iter().flat_map(|doc| doc.split(' ')) {
+        *expected.entry(word.to_string()).or_insert(0) += 1;
+    }
+
+    for threads in [1, 3, 8] {
+        let (per_word, locks) = count_locking_per_word(&docs, threads);
+        assert_eq!(per_word, expected, "{threads} threads");
+        assert_eq!(locks, 97 * 13);
+
+        let (merged, locks) = count_merging_local(&docs, threads);
+        assert_eq!(merged, expected, "{threads} threads");
+        assert_eq!(locks, threads);
+    }
+    assert_eq!(
+        Arc::strong_count(&docs),
+        1,
+        "the workers dropped their clones"
+    );
+}
+
+#[test]
+fn snapshots_never_mix_versions() {
+    let config: Arc<Snapshots<Rule>> = Arc::new(Snapshots::new(rules_version(1)));
+    let readers: Vec<thread::JoinHandle<u64>> = (0..4)
+        .map(|_| {
+            let config: Arc<Snapshots<Rule>> = Arc::clone(&config);
+            thread::spawn(move || {
+                let mut last: u64 = 0;
+                for _ in 0..2_000 {
+                    let (version, rules) = config.snapshot();
+                    assert!(version >= last, "versions only move forward");
+                    assert_eq!(*rules, rules_version(version));
+                    last = version;
+                }
+                last
+            })
+        })
+        .collect();
+    for version in 2..=20 {
+        assert_eq!(config.publish(rules_version(version)), version);
+    }
+    for reader in readers {
+        assert!(reader.join().unwrap() <= 20);
+    }
+
+    // A snapshot outlives the versions published after it
+    let (version, old) = config.snapshot();
+    config.publish(rules_version(21));
+    assert_eq!(version, 20);
+    assert_eq!(limit_for(&old, "/api/search/x"), Some(2001));
+    assert_eq!(limit_for(&config.snapshot().1, "/api/x"), Some(2100));
+    assert_eq!(limit_for(&old, "/other"), None);
+}
+
+#[test]
+fn listeners_refuse_a_reentrant_borrow() {
+    let listeners: Listeners = Rc::new(RefCell::new(Vec::new()));
+    let handle: Listeners = Rc::clone(&listeners);
+    let subscriber: Listener = Box::new(move |event| {
+        handle
+            .try_borrow_mut()
+            .map_or_else(|_| format!("busy {event}"), |_| format!("free {event}"))
+    });
+    listeners.borrow_mut().push(subscriber);
+    listeners
+        .borrow_mut()
+        .push(Box::new(|event| event.to_uppercase()));
+
+    assert_eq!(notify_borrowed(&listeners, "x"), ["busy x", "X"]);
+    assert!(listeners.try_borrow_mut().is_ok(), "the borrow ended");
+    assert_eq!(Rc::strong_count(&listeners), 2);
+}
@@ -0,0 +1,215 @@
+//! Dijkstra and A* on grid and road-map fixtures with known answers, and
+//! against a Bellman-Ford reference on random graphs.
+//!
+//! Every returned path is checked edge by edge: it must start and end at
+//! the right nodes, use only edges that exist, and cost what it claims.
+
+use collections_demo::shortest_path_examples::{
+    GridMap, Path, RoadMap, SAMPLE_GRID, Town, WeightedGraph, a_star, dijkstra, sample_road_map,
+};
+use demo_framework::rng::Lcg;
+
+/// Cheapest cost from `source` to every node, by relaxing every edge until
+/// nothing changes.
+fn bellman_ford(graph: &WeightedGraph, source: usize) -> Vec<Option<u32>> {
+    let mut dist: Vec<Option<u32>> = vec![None; graph.node_count()];
+    dist[source] = Some(0);
+    let mut changed: bool = true;
+    while changed {
+        changed = false;
+        for from in 0..graph.node_count() {
+            let Some(d) = dist[from] else { continue };
+            for &(to, weight) in graph.neighbors(from) {
+                if dist[to].is_none_or(|old| d + weight < old) {
+                    dist[to] = Some(d + weight);
+                    changed = true;
+                }
+            }
+        }
+    }
+    dist
+}
+
+/// Asserts `path` runs from `source` to `target` over real edges and that
+/// its cost is their sum.
+fn assert_valid(graph: &WeightedGraph, path: &Path, source: usize, target: usize) {
+    assert_eq!(path.nodes.first(), Some(&source));
+    assert_eq!(path.nodes.last(), Some(&target));
+    let mut cost: u32 = 0;
+    for step in path.nodes.windows(2) {
+        let weight: u32 = graph
+            .neighbors(step[0])
+            .iter()
+            .filter(|&&(to, _)| to == step[1])
+            .map(|&(_, weight)| weight)
+            .min()
+            .unwrap_or_else(|| panic!("no edge {} -> {}", step[0], step[1]));
+        cost += weight;
+    }
+    assert_eq!(path.cost, cost);
+    assert_eq!(path.expanded.last(), Some(&target));
+}
+
+/// Deterministic random graph: `degree` out-edges per node, weights 1..=20.
+fn random_graph(nodes: usize, degree: usize, seed: u64) -> WeightedGraph {
+    let mut rng: Lcg = Lcg::new(seed);
+    let mut graph: WeightedGraph = WeightedGraph::new(nodes);
+    for from in 0..nodes {
+        for _ in 0..degree {
+            let to: usize = rng.below(nodes as u64) as usize;
+            graph.add_edge(from, to, 1 + rng.below(20) as u32);
+        }
+    }
+    graph
+}
+
+#[test]
+fn dijkstra_matches_bellman_ford_on_random_graphs() {
+    for seed in 0..30 {
+        let nodes: usize = 5 + seed as usize * 4;
+        let graph: WeightedGraph = random_graph(nodes, 2, seed);
+        let expected: Vec<Option<u32>> = bellman_ford(&graph, 0);
+        for (target, &distance) in expected.iter().enumerate() {
+            let path: Option<Path> = dijkstra(&graph, 0, target);
+            assert_eq!(path.as_ref().map(|path| path.cost), distance);
+            if let Some(path) = path {
+                assert_valid(&graph, &path, 0, target);
+            }
+        }
+    }
+}
+
+#[test]
+fn open_grid_path_is_manhattan_distance() {
+    let map: GridMap = GridMap::parse("S.....\n......\n......\n.....G");
+    let graph: WeightedGraph = map.graph();
+    let path: Path = a_star(&graph, map.start, map.goal, |node| {
+        map.manhattan_to_goal(node)
+    })
+    .expect("open grid is connected");
+    assert_valid(&graph, &path, map.start, map.goal);
+    assert_eq!(path.cost, 8);
+    assert_eq!(path.nodes.len(), 9);
+    // With ties on f broken by the estimate, A* walks straight to the goal
+    assert_eq!(path.expanded.len(), 9);
+}
+
+#[test]
+fn maze_path_goes_around_walls() {
+    let map: GridMap = GridMap::parse(
+        "\
+S.#.....
+.##.##.#
+....#..G
+.##...#.",
+    );
+    let graph: WeightedGraph = map.graph();
+    let plain: Path = dijkstra(&graph, map.start, map.goal).expect("maze is solvable");
+    let guided: Path = a_star(&graph, map.start, map.goal, |node| {
+        map.manhattan_to_goal(node)
+    })
+    .expect("maze is solvable");
+    assert_valid(&graph, &plain, map.start, map.goal);
+    assert_valid(&graph, &guided, map.start, map.goal);
+    let expected: Option<u32> = bellman_ford(&graph, map.start)[map.goal];
+    assert_eq!(Some(plain.cost), expected);
+    assert_eq!(Some(guided.cost), expected);
+    assert_eq!(plain.cost, 11);
+    assert!(guided.expanded.len() <= plain.expanded.len());
+}
+
+#[test]
+fn mud_costs_more_than_the_detour() {
+    let map: GridMap = GridMap::parse(SAMPLE_GRID);
+    let graph: WeightedGraph = map.graph();
+    let plain: Path = dijkstra(&graph, map.start, map.goal).expect("reachable");
+    let guided: Path = a_star(&graph, map.start, map.goal, |node| {
+        map.manhattan_to_goal(node)
+    })
+    .expect("reachable");
+    assert_eq!(plain.cost, 18);
+    assert_eq!(guided.cost, 18);
+    assert!(guided.expanded.len() < plain.expanded.len());
+
+    // Overestimating by 3x takes the mud: a valid path, but not the cheapest
+    let greedy: Path = a_star(&graph, map.start, map.goal, |node| {
+        3 * map.manhattan_to_goal(node)
+    })
+    .expect("reachable");
+    assert_valid(&graph, &greedy, map.start, map.goal);
+    assert_eq!(greedy.cost, 23);
+}
+
+#[test]
+fn walled_off_goal_is_unreachable() {
+    let map: GridMap = GridMap::parse("S.#..\n..#.G\n..#..");
+    let graph: WeightedGraph = map.graph();
+    assert_eq!(dijkstra(&graph, map.start, map.goal), None);
+    assert_eq!(
+        a_star(&graph, map.start, map.goal, |node| map
+            .manhattan_to_goal(node)),
+        None
+    );
+}
+
+#[test]
+fn road_map_routes() {
+    let map: RoadMap = sample_road_map();
+    let (from, to) = (map.index("Ashford"), map.index("Glenrock"));
+    let plain: Path = dijkstra(&map.graph, from, to).expect("connected");
+    let guided: Path =
+        a_star(&map.graph, from, to, |town| map.straight_line(town, to)).expect("connected");
+    assert_valid(&map.graph, &plain, from, to);
+    assert_valid(&map.graph, &guided, from, to);
+    assert_eq!(plain.cost, 78);
+    assert_eq!(guided.cost, 78);
+    assert_eq!(
+        map.names(&guided.nodes),
+        ["Ashford", "Brook", "Dunmore", "Eastwick", "Glenrock"]
+    );
+    assert_eq!(plain.expanded.len(), 10);
+    assert_eq!(guided.expanded.len(), 7);
+}
+
+#[test]
+fn straight_line_never_exceeds_the_road_distance() {
+    let map: RoadMap = sample_road_map();
+    for source in 0..map.towns.len() {
+        let expected: Vec<Option<u32>> = bellman_ford(&map.graph, source);
+        for (target, distance) in expected.iter().enumerate() {
+            let distance: u32 = distance.expect("road map is connected");
+            assert!(map.straight_line(source, target) <= distance);
+            let path: Path = a_star(&map.graph, source, target, |town| {
+                map.straight_line(town, target)
+            })
+            .expect("road map is connected");
+            assert_eq!(path.cost, distance);
+        }
+    }
+}
+
+#[test]
+fn one_way_roads_are_respected() {
+    let town = |name: &'static str, x: i32, y: i32| Town { name, x, y };
+    let mut map: RoadMap = RoadMap::new(
+        vec![town("A", 0, 0), town("B", 10, 0), town("C", 5, 5)],
+        &[("A", "C", 0), ("C", "B", 0)],
+    );
+    // A direct road that only runs B -> A
+    map.graph.add_edge(1, 0, 10);
+    let there: Path = dijkstra(&map.graph, 0, 1).expect("via C");
+    let back: Path = dijkstra(&map.graph, 1, 0).expect("direct");
+    assert_eq!(map.names(&there.nodes), ["A", "C", "B"]);
+    assert_eq!(there.cost, 16);
+    assert_eq!(map.names(&back.nodes), ["B", "A"]);
+    assert_eq!(back.cost, 10);
+}
+
+#[test]
+fn source_is_its_own_target() {
+    let graph: WeightedGraph = random_graph(10, 2, 7);
+    let path: Path = dijkstra(&graph, 3, 3).expect("trivially reachable");
+    assert_eq!(path.nodes, vec![3]);
+    assert_eq!(path.cost, 0);
+    assert_eq!(path.expanded, vec![3]);
+}
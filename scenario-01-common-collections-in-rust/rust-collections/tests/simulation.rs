@@ -0,0 +1,231 @@
+//! The event queue against a sorted reference, the timer wheel against the
+//! event queue, and the bank simulation checked for determinism,
+//! bookkeeping and agreement with queueing theory.
+
+use collections_demo::simulation_examples::{
+    Bank, BankConfig, BankReport, EventId, EventQueue, Time, TimerWheel, erlang_c_wait,
+    timer_deadlines,
+};
+use demo_framework::rng::Lcg;
+use std::collections::BTreeMap;
+
+#[test]
+fn events_pop_like_a_sorted_map() {
+    let mut queue: EventQueue<u32> = EventQueue::new();
+    // (time, id) -> payload: the order the queue must follow
+    let mut reference: BTreeMap<(Time, EventId), u32> = BTreeMap::new();
+    let mut rng: Lcg = Lcg::new(11);
+
+    for step in 0..20_000u32 {
+        match rng.below(10) {
+            0..5 => {
+                // Few distinct times, so many events share one
+                let at: Time = queue.now() + rng.below(8);
+                let id: EventId = queue.schedule(at, step);
+                reference.insert((at, id), step);
+            }
+            5..7 => {
+                let Some(&(at, id)) = reference.keys().nth(rng.below(4) as usize) else {
+                    continue;
+                };
+                assert_eq!(queue.cancel(id), reference.remove(&(at, id)));
+                assert_eq!(queue.cancel(id), None);
+            }
+            _ => {
+                let expected: Option<(Time, u32)> = reference
+                    .pop_first()
+                    .map(|((at, _), payload)| (at, payload));
+                assert_eq!(queue.pop(), expected);
+            }
+        }
+        assert_eq!(queue.len(), reference.len());
+        assert!(queue.heap_len() >= queue.len());
+        assert_eq!(
+            queue.upcoming(),
+            reference
+                .iter()
+                .map(|(&(at, _), payload)| (at, payload))
+                .collect::<Vec<(Time, &u32)>>()
+        );
+        assert_eq!(
+            queue.peek_time(),
+            reference.keys().next().map(|&(at, _)| at)
+        );
+    }
+}
+
+#[test]
+fn simultaneous_events_keep_their_scheduling_order() {
+    let mut queue: EventQueue<&str> = EventQueue::new();
+    for name in ["first", "second", "third"] {
+        queue.schedule(5, name);
+    }
+    queue.schedule(1, "earliest");
+    let order: Vec<&str> = std::iter::from_fn(|| queue.pop())
+        .map(|(_, name)| name)
+        .collect();
+    assert_eq!(order, ["earliest", "first", "second", "third"]);
+    assert_eq!(queue.now(), 5);
+}
+
+#[test]
+#[should_panic(expected = "clock is at 10")]
+fn scheduling_in_the_past_panics() {
+    let mut queue: EventQueue<()> = EventQueue::new();
+    queue.schedule(10, ());
+    queue.pop();
+    queue.schedule(9, ());
+}
+
+#[test]
+fn timer_wheel_pops_like_the_event_queue() {
+    let mut wheel: TimerWheel<u32> = TimerWheel::new();
+    let mut queue: EventQueue<u32> = EventQueue::new();
+    let mut rng: Lcg = Lcg::new(5);
+
+    for step in 0..50_000u32 {
+        if rng.below(5) < 3 {
+            // Mostly near deadlines with many ties, some far enough to
+            // cascade through several levels
+            let delay: Time = match rng.below(10) {
+                0..6 => rng.below(4),
+                6..9 => rng.below(5_000),
+                _ => rng.below(50_000_000),
+            };
+            wheel.schedule_in(delay, step);
+            queue.schedule_in(delay, step);
+        } else {
+            assert_eq!(wheel.pop(), queue.pop());
+            assert_eq!(wheel.now(), queue.now());
+        }
+        assert_eq!(wheel.len(), queue.len());
+        assert_eq!(wheel.level_lens().iter().sum::<usize>(), wheel.len());
+    }
+    while let Some(event) = queue.pop() {
+        assert_eq!(wheel.pop(), Some(event));
+    }
+    assert_eq!(wheel.pop(), None);
+    assert!(wheel.is_empty());
+    assert!(wheel.cascades() > 0);
+}
+
+#[test]
+fn timer_wheel_files_ties_in_one_slot() {
+    let deadlines: Vec<Time> = timer_deadlines(1_000, 4, 1_000, 9);
+    let mut wheel: TimerWheel<usize> = TimerWheel::new();
+    for (i, &at) in deadlines.iter().enumerate() {
+        wheel.schedule(at, i);
+    }
+    let mut distinct: Vec<Time> = deadlines.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    assert_eq!(distinct, [0, 250, 500, 750]);
+
+    let popped: Vec<(Time, usize)> = std::iter::from_fn(|| wheel.pop()).collect();
+    let mut expected: Vec<(Time, usize)> = deadlines.into_iter().zip(0..).collect();
+    expected.sort_unstable();
+    assert_eq!(popped, expected);
+    // One cascade per distinct deadline past the first 64 ms block
+    assert_eq!(wheel.cascades(), 3);
+}
+
+#[test]
+#[should_panic(expected = "beyond the wheel's reach")]
+fn timer_wheel_rejects_deadlines_past_its_levels() {
+    let mut wheel: TimerWheel<()> = TimerWheel::new();
+    wheel.schedule(1 << 36, ());
+}
+
+fn config(clerks: usize, mean_service: f64, patience: Option<Time>, seed: u64) -> BankConfig {
+    BankConfig {
+        clerks,
+        mean_interarrival: 60_000.0,
+        mean_service,
+        patience,
+        customers: 5_000,
+        seed,
+    }
+}
+
+#[test]
+fn the_same_seed_replays_the_same_run() {
+    let first: BankReport = Bank::new(config(2, 100_000.0, Some(90_000), 5)).run();
+    assert_eq!(
+        first,
+        Bank::new(config(2, 100_000.0, Some(90_000), 5)).run()
+    );
+    assert_ne!(
+        first,
+        Bank::new(config(2, 100_000.0, Some(90_000), 6)).run()
+    );
+}
+
+#[test]
+fn every_customer_is_served_or_gives_up() {
+    for patience in [None, Some(0), Some(45_000), Some(600_000)] {
+        for clerks in 1..=3 {
+            let mut bank: Bank = Bank::new(config(clerks, 150_000.0, patience, clerks as u64));
+            let mut last: Time = 0;
+            while let Some((at, _)) = bank.step() {
+                assert!(at >= last, "the clock went back");
+                last = at;
+                // Nobody waits while a desk is free
+                if !bank.line().is_empty() {
+                    assert!(bank.desks().iter().all(Option::is_some));
+                }
+            }
+            assert!(bank.desks().iter().all(Option::is_none));
+            assert!(bank.events().is_empty());
+
+            let report: BankReport = bank.report();
+            assert_eq!(report.arrived, 5_000);
+            assert_eq!(report.served + report.gave_up, report.arrived);
+            assert_eq!(
+                report.events,
+                report.arrived + report.served + report.gave_up
+            );
+            assert!(report.utilization(clerks) <= 1.0);
+            if patience.is_none() {
+                assert_eq!(report.gave_up, 0);
+                assert_eq!(report.skipped, 0);
+            }
+            if let Some(patience) = patience {
+                assert!(report.max_wait <= patience);
+            }
+        }
+    }
+}
+
+#[test]
+fn erlang_c_matches_the_closed_forms() {
+    // One clerk: wait = load / (mu - lambda)
+    let wait: f64 = erlang_c_wait(1, 60.0, 48.0).unwrap_or_default();
+    assert!((wait - 0.8 / (1.0 / 48.0 - 1.0 / 60.0)).abs() < 1e-9);
+    // Two clerks: wait = 2 rho^3 / (1 - rho^2) / lambda, rho = lambda / 2 mu
+    let rho: f64 = 90.0 / (2.0 * 60.0);
+    let wait: f64 = erlang_c_wait(2, 60.0, 90.0).unwrap_or_default();
+    assert!((wait - 2.0 * rho.powi(3) / (1.0 - rho * rho) * 60.0).abs() < 1e-9);
+    // Arrivals as fast as service: the line never settles
+    assert_eq!(erlang_c_wait(1, 60.0, 60.0), None);
+    assert_eq!(erlang_c_wait(3, 60.0, 200.0), None);
+}
+
+#[test]
+fn simulated_waits_approach_erlang_c() {
+    for (clerks, mean_service) in [(1, 36_000.0), (2, 84_000.0), (4, 180_000.0)] {
+        let report: BankReport = Bank::new(BankConfig {
+            customers: 200_000,
+            ..config(clerks, mean_service, None, 21)
+        })
+        .run();
+        let predicted: f64 = erlang_c_wait(clerks, 60_000.0, mean_service).unwrap_or_default();
+        let error: f64 = (report.mean_wait() - predicted).abs() / predicted;
+        assert!(
+            error < 0.1,
+            "{} clerks: simulated {:.0} ms, predicted {:.0} ms",
+            clerks,
+            report.mean_wait(),
+            predicted
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! `SlidingWindow` against a model that applies the same rules one reading
+//! at a time: random overlapping batches, then outlier removal.
+
+use collections_demo::btreemap_examples::{Measurement, SlidingWindow};
+use demo_framework::rng::Lcg;
+use std::collections::BTreeMap;
+
+const SPAN: u64 = 500;
+const MAX_LEN: usize = 40;
+
+/// Inserts the batch one reading at a time, then removes the oldest reading
+/// while it is too old or the window is too long.
+fn model_ingest(
+    model: &mut BTreeMap<u64, Measurement>,
+    batch: &BTreeMap<u64, Measurement>,
+) -> Vec<u64> {
+    for (&time, &reading) in batch {
+        model.insert(time, reading);
+    }
+    let latest: u64 = *model.keys().next_back().unwrap();
+    let mut evicted: Vec<u64> = Vec::new();
+    while let Some(&oldest) = model.keys().next() {
+        if oldest + SPAN >= latest && model.len() <= MAX_LEN {
+            break;
+        }
+        model.remove(&oldest);
+        evicted.push(oldest);
+    }
+    evicted
+}
+
+#[test]
+fn window_matches_the_one_at_a_time_model() {
+    let mut rng: Lcg = Lcg::new(5);
+    let mut window: SlidingWindow = SlidingWindow::new(SPAN, MAX_LEN);
+    let mut model: BTreeMap<u64, Measurement> = BTreeMap::new();
+    let mut clock: u64 = 10_000;
+
+    for _ in 0..300 {
+        clock += rng.below(120);
+        // Batches reach back in time, so they overlap what is stored
+        let batch: BTreeMap<u64, Measurement> = (0..rng.below(12))
+            .map(|_| {
+                let time: u64 = clock - rng.below(300);
+                // -30..90°C, so some readings are outliers
+                let temperature: f64 = rng.below(1_200) as f64 / 10.0 - 30.0;
+                (time, Measurement::new(temperature, rng.below(100) as f64))
+            })
+            .collect();
+        if batch.is_empty() {
+            assert!(window.ingest(batch).is_empty());
+            continue;
+        }
+
+        let expected: Vec<u64> = model_ingest(&mut model, &batch);
+        let evicted: Vec<u64> = window.ingest(batch).into_keys().collect();
+        assert_eq!(evicted, expected);
+        assert_eq!(window.readings(), &model);
+        assert!(window.readings().len() <= MAX_LEN);
+    }
+
+    let since: u64 = *model.keys().nth(model.len() / 2).unwrap();
+    let outliers: BTreeMap<u64, Measurement> = window.drop_outliers(since, -20.0..=60.0);
+    for (time, reading) in &outliers {
+        assert!(*time >= since);
+        assert!(!(-20.0..=60.0).contains(&reading.temperature));
+    }
+    model.retain(|time, reading| *time < since || (-20.0..=60.0).contains(&reading.temperature));
+    assert_eq!(window.readings(), &model);
+
+    let latest: u64 = window
+        .correct_latest(|reading| reading.humidity = 0.0)
+        .unwrap();
+    assert_eq!(Some(&latest), model.keys().next_back());
+    assert_eq!(window.readings()[&latest].humidity, 0.0);
+    assert_eq!(
+        SlidingWindow::new(SPAN, MAX_LEN).correct_latest(|_| {}),
+        None
+    );
+}
@@ -0,0 +1,350 @@
+//! Snapshot tests for the educational output of every example module.
+//!
+//! Each test captures a module's `run_all` into a buffer and compares it
+//! with the reviewed snapshot in `tests/snapshots/`. After an intentional
+//! change to a demo, review and accept the new output with:
+//!
+//!   cargo insta review
+//!
+//! or regenerate everything with `INSTA_UPDATE=always cargo test`.
+
+use collections_demo::{
+    arena_examples, bimap_examples, binaryheap_examples, bitset_examples, bounded_queue_examples,
+    bst_examples, btree_visual, btreemap_examples, cache_policies_examples, collation_examples,
+    columnar_examples, composite_keys_examples, cow_examples, custom_linked_list_examples,
+    entry_examples, entry_patterns_examples, enum_map_examples, equivalent_keys_examples,
+    extract_if_examples, float_sum_examples, graph_examples, grid_examples, hashmap_examples,
+    heap_alternatives_examples, heapless_examples, indexed_heap_examples, indexmap_examples,
+    inline_vec_examples, interval_examples, iteration_order_examples, iterator_examples,
+    kdtree_examples, linked_list_examples, lru_examples, map_diff_examples, map_view_examples,
+    matrix_examples, merge_patch_examples, multimap_examples, multiset_examples,
+    nested_map_examples, object_pool_examples, persistent_examples, ring_buffer_examples,
+    saturating_counter_examples, set_examples, shared_ownership_examples, shortest_path_examples,
+    simulation_examples, slotmap_examples, spatial_examples, static_table_examples,
+    streaming_stats_examples, union_find_examples, vec_examples, vecdeque_examples,
+};
+use demo_framework::capture;
+use demo_framework::snapshot::{redact, stabilize};
+use insta::assert_snapshot;
+
+#[test]
+fn vec_examples() {
+    assert_snapshot!(stabilize(&capture(vec_examples::run_all)));
+}
+
+#[test]
+fn inline_vec_examples() {
+    assert_snapshot!(stabilize(&capture(inline_vec_examples::run_all)));
+}
+
+#[test]
+fn vecdeque_examples() {
+    assert_snapshot!(stabilize(&capture(vecdeque_examples::run_all)));
+}
+
+#[test]
+fn bounded_queue_examples() {
+    assert_snapshot!(stabilize(&capture(bounded_queue_examples::run_all)));
+}
+
+#[test]
+fn ring_buffer_examples() {
+    assert_snapshot!(stabilize(&capture(ring_buffer_examples::run_all)));
+}
+
+#[test]
+fn linked_list_examples() {
+    let output: String = stabilize(&capture(linked_list_examples::run_all));
+    // The nightly feature adds the cursor demo, so it gets its own snapshot
+    if cfg!(feature = "nightly") {
+        assert_snapshot!("linked_list_examples_nightly", output);
+    } else {
+        assert_snapshot!(output);
+    }
+}
+
+#[test]
+fn custom_linked_list_examples() {
+    assert_snapshot!(stabilize(&capture(custom_linked_list_examples::run_all)));
+}
+
+#[test]
+fn arena_examples() {
+    assert_snapshot!(stabilize(&capture(arena_examples::run_all)));
+}
+
+#[test]
+fn hashmap_examples() {
+    // The share of time spent in rehashing inserts is measured
+    let output: String = redact(
+        &capture(hashmap_examples::run_all),
+        &[(r"took [\d.]+% of the total", "took [share] of the total")],
+    );
+    assert_snapshot!(stabilize(&output));
+}
+
+#[test]
+fn equivalent_keys_examples() {
+    assert_snapshot!(stabilize(&capture(equivalent_keys_examples::run_all)));
+}
+
+#[test]
+fn btreemap_examples() {
+    let output: String = stabilize(&capture(btreemap_examples::run_all));
+    // The nightly feature adds the cursor demos, so it gets its own snapshot
+    if cfg!(feature = "nightly") {
+        assert_snapshot!("btreemap_examples_nightly", output);
+    } else {
+        assert_snapshot!(output);
+    }
+}
+
+#[test]
+fn btree_visual() {
+    assert_snapshot!(stabilize(&capture(btree_visual::run_all)));
+}
+
+#[test]
+fn bst_examples() {
+    assert_snapshot!(stabilize(&capture(bst_examples::run_all)));
+}
+
+#[test]
+fn map_view_examples() {
+    assert_snapshot!(stabilize(&capture(map_view_examples::run_all)));
+}
+
+#[test]
+fn entry_examples() {
+    assert_snapshot!(stabilize(&capture(entry_examples::run_all)));
+}
+
+#[test]
+fn entry_patterns_examples() {
+    assert_snapshot!(stabilize(&capture(entry_patterns_examples::run_all)));
+}
+
+#[test]
+fn extract_if_examples() {
+    assert_snapshot!(stabilize(&capture(extract_if_examples::run_all)));
+}
+
+#[test]
+fn map_diff_examples() {
+    assert_snapshot!(stabilize(&capture(map_diff_examples::run_all)));
+}
+
+#[test]
+fn merge_patch_examples() {
+    assert_snapshot!(stabilize(&capture(merge_patch_examples::run_all)));
+}
+
+#[test]
+fn interval_examples() {
+    assert_snapshot!(stabilize(&capture(interval_examples::run_all)));
+}
+
+#[test]
+fn set_examples() {
+    assert_snapshot!(stabilize(&capture(set_examples::run_all)));
+}
+
+#[test]
+fn slotmap_examples() {
+    assert_snapshot!(stabilize(&capture(slotmap_examples::run_all)));
+}
+
+#[test]
+fn static_table_examples() {
+    assert_snapshot!(stabilize(&capture(static_table_examples::run_all)));
+}
+
+#[test]
+fn iterator_examples() {
+    assert_snapshot!(stabilize(&capture(iterator_examples::run_all)));
+}
+
+#[test]
+fn cow_examples() {
+    assert_snapshot!(stabilize(&capture(cow_examples::run_all)));
+}
+
+#[test]
+fn binaryheap_examples() {
+    // Iterating a BinaryHeap directly yields its internal (heap) order,
+    // which is deterministic, so no extra filtering is needed.
+    assert_snapshot!(stabilize(&capture(binaryheap_examples::run_all)));
+}
+
+#[test]
+fn indexmap_examples() {
+    // Deterministic as-is; stabilize() would sort the {} maps whose order is
+    // the whole point of this module
+    assert_snapshot!(capture(indexmap_examples::run_all));
+}
+
+#[test]
+fn iteration_order_examples() {
+    // Counts and sizes that depend on a HashMap's order change every run
+    let output: String = redact(
+        &capture(iteration_order_examples::run_all),
+        &[
+            (r"HashMap order: .*", "HashMap order:  [hash order]"),
+            (r"HashMap( +)\d+ groups", "HashMap$1[count] groups"),
+            (
+                r"HashMap( +)\d+ bytes compressed \([\d.]+%",
+                "HashMap$1[size] bytes compressed ([share]",
+            ),
+            (r"\d+ distinct totals", "[count] distinct totals"),
+        ],
+    );
+    assert_snapshot!(stabilize(&output));
+}
+
+#[test]
+fn columnar_examples() {
+    assert_snapshot!(stabilize(&capture(columnar_examples::run_all)));
+}
+
+#[test]
+fn float_sum_examples() {
+    // Uncompensated sums in hash order vary from run to run
+    let output: String = redact(
+        &capture(float_sum_examples::run_all),
+        &[(
+            r"(naive_sum|pairwise_sum) +\d+ +\d+ ulps",
+            "$1 [count] [error] ulps",
+        )],
+    );
+    assert_snapshot!(stabilize(&output));
+}
+
+#[test]
+fn bitset_examples() {
+    assert_snapshot!(stabilize(&capture(bitset_examples::run_all)));
+}
+
+#[test]
+fn enum_map_examples() {
+    assert_snapshot!(stabilize(&capture(enum_map_examples::run_all)));
+}
+
+#[test]
+fn nested_map_examples() {
+    assert_snapshot!(stabilize(&capture(nested_map_examples::run_all)));
+}
+
+#[test]
+fn collation_examples() {
+    assert_snapshot!(stabilize(&capture(collation_examples::run_all)));
+}
+
+#[test]
+fn composite_keys_examples() {
+    assert_snapshot!(stabilize(&capture(composite_keys_examples::run_all)));
+}
+
+#[test]
+fn heap_alternatives_examples() {
+    assert_snapshot!(stabilize(&capture(heap_alternatives_examples::run_all)));
+}
+
+#[test]
+fn indexed_heap_examples() {
+    assert_snapshot!(stabilize(&capture(indexed_heap_examples::run_all)));
+}
+
+#[test]
+fn simulation_examples() {
+    assert_snapshot!(stabilize(&capture(simulation_examples::run_all)));
+}
+
+#[test]
+fn streaming_stats_examples() {
+    assert_snapshot!(stabilize(&capture(streaming_stats_examples::run_all)));
+}
+
+#[test]
+fn union_find_examples() {
+    assert_snapshot!(stabilize(&capture(union_find_examples::run_all)));
+}
+
+#[test]
+fn lru_examples() {
+    assert_snapshot!(stabilize(&capture(lru_examples::run_all)));
+}
+
+#[test]
+fn cache_policies_examples() {
+    assert_snapshot!(stabilize(&capture(cache_policies_examples::run_all)));
+}
+
+#[test]
+fn grid_examples() {
+    assert_snapshot!(stabilize(&capture(grid_examples::run_all)));
+}
+
+#[test]
+fn matrix_examples() {
+    assert_snapshot!(stabilize(&capture(matrix_examples::run_all)));
+}
+
+#[test]
+fn spatial_examples() {
+    assert_snapshot!(stabilize(&capture(spatial_examples::run_all)));
+}
+
+#[test]
+fn kdtree_examples() {
+    assert_snapshot!(stabilize(&capture(kdtree_examples::run_all)));
+}
+
+#[test]
+fn graph_examples() {
+    assert_snapshot!(stabilize(&capture(graph_examples::run_all)));
+}
+
+#[test]
+fn shortest_path_examples() {
+    assert_snapshot!(stabilize(&capture(shortest_path_examples::run_all)));
+}
+
+#[test]
+fn persistent_examples() {
+    assert_snapshot!(stabilize(&capture(persistent_examples::run_all)));
+}
+
+#[test]
+fn multiset_examples() {
+    assert_snapshot!(stabilize(&capture(multiset_examples::run_all)));
+}
+
+#[test]
+fn bimap_examples() {
+    assert_snapshot!(stabilize(&capture(bimap_examples::run_all)));
+}
+
+#[test]
+fn multimap_examples() {
+    assert_snapshot!(stabilize(&capture(multimap_examples::run_all)));
+}
+
+#[test]
+fn object_pool_examples() {
+    assert_snapshot!(stabilize(&capture(object_pool_examples::run_all)));
+}
+
+#[test]
+fn heapless_examples() {
+    assert_snapshot!(stabilize(&capture(heapless_examples::run_all)));
+}
+
+#[test]
+fn shared_ownership_examples() {
+    assert_snapshot!(stabilize(&capture(shared_ownership_examples::run_all)));
+}
+
+#[test]
+fn saturating_counter_examples() {
+    assert_snapshot!(stabilize(&capture(saturating_counter_examples::run_all)));
+}
@@ -0,0 +1,64 @@
+//! Soak runs of the `soak` workload, with the tracking allocator installed
+//! for this test binary.
+//!
+//! The live heap counter is process-wide, so everything that reads it runs
+//! in a single test: another test allocating on a parallel thread would
+//! show up as growth.
+
+use collections_demo::soak::Workload;
+use demo_framework::alloc::{self, TrackingAllocator};
+use demo_framework::soak::{self, SoakReport, WARM_UP, WINDOWS};
+use std::io;
+use std::time::Duration;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+/// Rounds that fill every structure before a run starts, so even a slow
+/// debug build begins its windows at steady state.
+const PREFILL: usize = 2_000;
+
+fn soak_for(duration: Duration, round: &mut dyn FnMut(u64)) -> SoakReport {
+    soak::run(&mut io::sink(), duration, round).expect("the tracking allocator is installed")
+}
+
+#[test]
+fn the_workload_plateaus_and_a_leak_does_not() {
+    assert!(alloc::live_bytes().is_some());
+
+    let mut workload: Workload = Workload::new(7);
+    for _ in 0..PREFILL {
+        workload.round();
+    }
+    let full: usize = workload.len();
+    let report: SoakReport = soak_for(Duration::from_secs(1), &mut |_| workload.round());
+    assert_eq!(report.windows.len(), WINDOWS);
+    assert!(report.windows.iter().all(|window| window.rounds > 0));
+    assert!(
+        report.plateaued(),
+        "settled at {} bytes, ended at {}",
+        report.settled_peak(),
+        report.final_peak()
+    );
+    // Entries stay within the structures' bounds too
+    assert!(workload.len() <= full + full / 10);
+    drop(workload);
+
+    // The same workload, except every round keeps a string it should drop
+    let mut workload: Workload = Workload::new(7);
+    for _ in 0..PREFILL {
+        workload.round();
+    }
+    let mut forgotten: Vec<String> = Vec::new();
+    let report: SoakReport = soak_for(Duration::from_secs(1), &mut |round| {
+        workload.round();
+        forgotten.push(format!("{:01024}", round));
+    });
+    assert!(
+        !report.plateaued(),
+        "settled at {} bytes, ended at {}",
+        report.settled_peak(),
+        report.final_peak()
+    );
+    assert!(report.windows[WINDOWS - 1].peak_bytes > report.windows[WARM_UP].peak_bytes);
+}
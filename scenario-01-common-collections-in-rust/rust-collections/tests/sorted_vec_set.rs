@@ -0,0 +1,73 @@
+//! `SortedVecSet` against `BTreeSet` under random inserts, removes and
+//! lookups, and both intersections against `BTreeSet::intersection`.
+
+use collections_demo::set_examples::SortedVecSet;
+use demo_framework::rng::Lcg;
+use std::collections::BTreeSet;
+
+#[test]
+fn behaves_like_a_btreeset() {
+    let mut set: SortedVecSet<u64> = SortedVecSet::new();
+    let mut reference: BTreeSet<u64> = BTreeSet::new();
+    let mut rng: Lcg = Lcg::new(4);
+    for _ in 0..20_000 {
+        let value: u64 = rng.below(500);
+        match rng.below(3) {
+            0 => assert_eq!(set.insert(value), reference.insert(value)),
+            1 => assert_eq!(set.remove(&value), reference.remove(&value)),
+            _ => assert_eq!(set.contains(&value), reference.contains(&value)),
+        }
+        assert_eq!(set.len(), reference.len());
+    }
+    assert!(set.iter().eq(reference.iter()));
+}
+
+#[test]
+fn from_vec_sorts_and_deduplicates() {
+    let mut rng: Lcg = Lcg::new(9);
+    let values: Vec<u64> = (0..5_000).map(|_| rng.below(1_000)).collect();
+    let set: SortedVecSet<u64> = SortedVecSet::from_vec(values.clone());
+    let reference: BTreeSet<u64> = values.iter().copied().collect();
+    assert!(set.iter().eq(reference.iter()));
+    assert_eq!(set, values.into_iter().collect());
+    assert!(SortedVecSet::<u8>::from_vec(Vec::new()).is_empty());
+}
+
+#[test]
+fn intersections_match_btreeset() {
+    let mut rng: Lcg = Lcg::new(21);
+    for (len_a, len_b, bound) in [
+        (0, 100, 1_000),
+        (10, 10_000, 20_000),
+        (1_000, 1_000, 1_500),
+        (5_000, 300, 100_000),
+    ] {
+        let a: Vec<u64> = (0..len_a).map(|_| rng.below(bound)).collect();
+        let b: Vec<u64> = (0..len_b).map(|_| rng.below(bound)).collect();
+        let expected: Vec<u64> = a
+            .iter()
+            .copied()
+            .collect::<BTreeSet<u64>>()
+            .intersection(&b.iter().copied().collect())
+            .copied()
+            .collect();
+        let a: SortedVecSet<u64> = SortedVecSet::from_vec(a);
+        let b: SortedVecSet<u64> = SortedVecSet::from_vec(b);
+        assert_eq!(a.intersection(&b).as_slice(), expected);
+        assert_eq!(b.intersection(&a).as_slice(), expected);
+        assert_eq!(a.intersection_by_search(&b).as_slice(), expected);
+        assert_eq!(b.intersection_by_search(&a).as_slice(), expected);
+    }
+}
+
+#[test]
+fn subsets() {
+    let set: SortedVecSet<u32> = (0..100).map(|n| n * 2).collect();
+    let evens: SortedVecSet<u32> = (0..10).map(|n| n * 4).collect();
+    let with_odd: SortedVecSet<u32> = [4, 8, 9].into_iter().collect();
+    assert!(evens.is_subset(&set));
+    assert!(!with_odd.is_subset(&set));
+    assert!(SortedVecSet::new().is_subset(&set));
+    assert!(!set.is_subset(&SortedVecSet::new()));
+    assert!(set.is_subset(&set));
+}
@@ -0,0 +1,100 @@
+//! SpatialHash against checking every entity (proptest): radius queries,
+//! colliding pairs, and the cell lists after random inserts, moves and
+//! removes.
+
+use collections_demo::spatial_examples::{
+    Collisions, EntityId, Neighbours, Point, SpatialHash, brute_force_pairs,
+};
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+
+fn point() -> impl Strategy<Value = Point> {
+    (-50.0f64..50.0, -50.0f64..50.0).prop_map(|(x, y)| Point::new(x, y))
+}
+
+proptest! {
+    #[test]
+    fn pairs_and_queries_match_checking_everything(
+        points in prop::collection::vec(point(), 0..150),
+        cell_size in 1.0f64..20.0,
+        distance_share in 0.0f64..=1.0,
+        center in point(),
+        radius in 0.0f64..30.0,
+    ) {
+        let entities: Vec<(EntityId, Point)> = points
+            .into_iter()
+            .enumerate()
+            .map(|(id, point)| (EntityId(id as u32), point))
+            .collect();
+        let mut grid: SpatialHash = SpatialHash::new(cell_size);
+        for &(id, point) in &entities {
+            prop_assert_eq!(grid.insert(id, point), None);
+        }
+
+        let distance: f64 = cell_size * distance_share;
+        let found: Collisions = grid.colliding_pairs(distance);
+        let expected: Collisions = brute_force_pairs(&entities, distance);
+        prop_assert_eq!(&found.pairs, &expected.pairs);
+        prop_assert!(found.checks <= expected.checks.max(1) * 9);
+
+        let neighbours: Neighbours = grid.query_radius(center, radius);
+        let scanned: Vec<EntityId> = entities
+            .iter()
+            .filter(|(_, point)| point.distance_squared(center) <= radius * radius)
+            .map(|&(id, _)| id)
+            .collect();
+        prop_assert_eq!(neighbours.ids, scanned);
+        prop_assert!(neighbours.checks <= entities.len());
+    }
+
+    #[test]
+    fn cells_track_moves_and_removes(
+        ops in prop::collection::vec((0u32..20, prop::option::weighted(0.8, point())), 0..200),
+    ) {
+        let mut grid: SpatialHash = SpatialHash::new(7.5);
+        let mut model: BTreeMap<EntityId, Point> = BTreeMap::new();
+        for (id, target) in ops {
+            let id: EntityId = EntityId(id);
+            match target {
+                Some(point) => prop_assert_eq!(grid.insert(id, point), model.insert(id, point)),
+                None => prop_assert_eq!(grid.remove(id), model.remove(&id)),
+            }
+        }
+
+        prop_assert_eq!(grid.len(), model.len());
+        // Each entity is listed once, in the cell of its position, and no
+        // cell is left empty
+        let mut cells: BTreeMap<(i32, i32), Vec<EntityId>> = BTreeMap::new();
+        for (&id, &point) in &model {
+            prop_assert_eq!(grid.position(id), Some(point));
+            cells.entry(grid.cell_of(point)).or_default().push(id);
+        }
+        prop_assert_eq!(grid.occupied_cells(), cells.len());
+        for (cell, mut ids) in cells {
+            let mut listed: Vec<EntityId> = grid.entities_in(cell).to_vec();
+            listed.sort_unstable();
+            ids.sort_unstable();
+            prop_assert_eq!(listed, ids);
+        }
+    }
+}
+
+#[test]
+fn negative_coordinates_round_down() {
+    let grid: SpatialHash = SpatialHash::new(10.0);
+    assert_eq!(grid.cell_of(Point::new(-0.5, 0.5)), (-1, 0));
+    assert_eq!(grid.cell_of(Point::new(-10.0, -10.5)), (-1, -2));
+    assert_eq!(grid.cell_of(Point::new(9.99, 10.0)), (0, 1));
+}
+
+#[test]
+#[should_panic(expected = "exceeds the cell size")]
+fn pairs_further_apart_than_a_cell_are_refused() {
+    SpatialHash::new(1.0).colliding_pairs(1.5);
+}
+
+#[test]
+#[should_panic(expected = "is not a finite position")]
+fn nan_positions_are_refused() {
+    SpatialHash::new(1.0).insert(EntityId(0), Point::new(f64::NAN, 0.0));
+}
@@ -0,0 +1,67 @@
+//! The compile-time tables: sorted and complete, and every lookup path
+//! agreeing with a HashMap built from the same entries.
+
+use collections_demo::static_table_examples::{
+    FIRST_STATUS, HTTP_STATUSES, STATUS_INDEX, WEEKDAY_NAMES, Weekday, parse_weekday, sorted_get,
+    status_reason, status_reason_hashed, status_reason_indexed,
+};
+use std::collections::HashMap;
+
+#[test]
+fn tables_are_sorted_without_duplicates() {
+    assert!(HTTP_STATUSES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert!(WEEKDAY_NAMES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert!(
+        WEEKDAY_NAMES
+            .iter()
+            .all(|(name, _)| *name == name.to_lowercase())
+    );
+}
+
+#[test]
+fn every_code_agrees_with_a_hashmap() {
+    let reference: HashMap<u16, &str> = HTTP_STATUSES.iter().copied().collect();
+    for code in 0..=u16::MAX {
+        let expected: Option<&str> = reference.get(&code).copied();
+        assert_eq!(status_reason(code), expected, "code {}", code);
+        assert_eq!(status_reason_indexed(code), expected, "code {}", code);
+        assert_eq!(status_reason_hashed(code), expected, "code {}", code);
+    }
+    assert_eq!(status_reason(418), Some("I'm a teapot"));
+}
+
+#[test]
+fn index_points_back_at_its_code() {
+    for (slot, &position) in STATUS_INDEX.iter().enumerate() {
+        if position > 0 {
+            let (code, _) = HTTP_STATUSES[position as usize - 1];
+            assert_eq!(code, FIRST_STATUS + slot as u16);
+        }
+    }
+    let filled: usize = STATUS_INDEX.iter().filter(|&&slot| slot > 0).count();
+    assert_eq!(filled, HTTP_STATUSES.len());
+}
+
+#[test]
+fn weekdays_parse_in_any_ascii_case() {
+    for day in Weekday::ALL {
+        let name: &str = day.name();
+        assert_eq!(parse_weekday(name), Some(day));
+        assert_eq!(parse_weekday(&name.to_uppercase()), Some(day));
+        assert_eq!(parse_weekday(&name[..3]), Some(day));
+        assert_eq!(parse_weekday(&name[..2]), None);
+    }
+    for text in ["", "mo", "mondays", "funday", "tues", "mon ", "Ｍon"] {
+        assert_eq!(parse_weekday(text), None, "{:?}", text);
+    }
+}
+
+#[test]
+fn sorted_get_finds_every_key_and_nothing_else() {
+    let table: Vec<(u32, u32)> = (0..100).map(|i| (i * 3, i)).collect();
+    for key in 0..300 {
+        let expected: Option<u32> = (key % 3 == 0).then_some(key / 3);
+        assert_eq!(sorted_get(&table, &key).copied(), expected);
+    }
+    assert_eq!(sorted_get::<u32, u32>(&[], &0), None);
+}
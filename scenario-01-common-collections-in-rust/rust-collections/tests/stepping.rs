@@ -0,0 +1,194 @@
+//! The resumable algorithms behind `--step`, against their run-to-the-end
+//! versions, and step mode itself driven by scripted input.
+//!
+//! Step mode is per thread, so each test that enables it only affects its
+//! own demo run.
+
+use collections_demo::binaryheap_examples::{
+    Heapsort, HeapsortStep, KWayMerge, practical_merge_sorted_lists,
+};
+use collections_demo::shortest_path_examples::{
+    GridMap, Path, SAMPLE_GRID, Search, WeightedGraph, a_star,
+};
+use collections_demo::union_find_examples::{Edge, Kruskal, kruskal, random_edges, render_sets};
+use demo_framework::rng::Lcg;
+use demo_framework::{capture, section, step};
+use std::io::Cursor;
+
+#[test]
+fn search_steps_settle_the_same_nodes_as_a_star() {
+    let map: GridMap = GridMap::parse(SAMPLE_GRID);
+    let graph: WeightedGraph = map.graph();
+    for scale in [0, 1, 3] {
+        let heuristic = |node: usize| scale * map.manhattan_to_goal(node);
+        let mut search: Search<'_, _> = Search::new(&graph, map.start, map.goal, heuristic);
+        let mut settled: Vec<usize> = Vec::new();
+        while let Some(node) = search.advance() {
+            assert_eq!(search.expanded().last(), Some(&node));
+            assert_eq!(search.queued(node), None);
+            settled.push(node);
+        }
+        assert!(search.is_finished());
+        // Finished searches stay finished
+        assert_eq!(search.advance(), None);
+        let stepped: Path = search.into_path().expect("reachable");
+        let whole: Path = a_star(&graph, map.start, map.goal, heuristic).expect("reachable");
+        assert_eq!(settled, whole.expanded);
+        assert_eq!(stepped, whole);
+    }
+}
+
+#[test]
+fn search_render_marks_the_frontier() {
+    let map: GridMap = GridMap::parse("S..\n...\n..G");
+    let graph: WeightedGraph = map.graph();
+    let mut search: Search<'_, _> = Search::new(&graph, map.start, map.goal, |_| 0);
+    search.advance();
+    assert_eq!(map.render_search(&search), ["@+.", "+..", "..G"]);
+    search.advance();
+    assert_eq!(search.distance(1), Some(1));
+    assert_eq!(map.render_search(&search).len(), 3);
+    assert_eq!(
+        map.render_search(&search)
+            .concat()
+            .chars()
+            .filter(|&c| c == '@')
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn heapsort_pushes_everything_then_pops_in_order() {
+    let mut rng: Lcg = Lcg::new(7);
+    let data: Vec<u32> = (0..200).map(|_| rng.below(50) as u32).collect();
+    let mut sort: Heapsort<u32> = Heapsort::new(data.clone());
+    let mut pushed: Vec<u32> = Vec::new();
+    let mut popped: Vec<u32> = Vec::new();
+    while let Some(done) = sort.advance() {
+        match done {
+            HeapsortStep::Pushed(val) => {
+                assert!(popped.is_empty(), "every push comes before the first pop");
+                pushed.push(val);
+            }
+            HeapsortStep::Popped(val) => {
+                assert_eq!(sort.heap().len(), data.len() - popped.len() - 1);
+                popped.push(val);
+            }
+        }
+    }
+    assert_eq!(pushed, data);
+    let mut expected: Vec<u32> = data;
+    expected.sort_by(|a, b| b.cmp(a));
+    assert_eq!(popped, expected);
+    assert_eq!(sort.sorted(), &expected[..]);
+    assert_eq!(sort.into_sorted(), expected);
+}
+
+#[test]
+fn k_way_merge_matches_sorting_the_concatenation() {
+    let mut rng: Lcg = Lcg::new(3);
+    for k in 0..8 {
+        let lists: Vec<Vec<u32>> = (0..k)
+            .map(|_| {
+                let mut list: Vec<u32> =
+                    (0..rng.below(12)).map(|_| rng.below(100) as u32).collect();
+                list.sort();
+                list
+            })
+            .collect();
+        let mut expected: Vec<u32> = lists.concat();
+        expected.sort();
+
+        let mut merge: KWayMerge<u32> = KWayMerge::new(lists.clone());
+        let mut from: Vec<usize> = Vec::new();
+        while let Some((val, list_idx)) = merge.advance() {
+            assert!(lists[list_idx].contains(&val));
+            from.push(list_idx);
+        }
+        assert_eq!(merge.merged(), &expected[..]);
+        for (list_idx, list) in lists.iter().enumerate() {
+            assert_eq!(from.iter().filter(|&&i| i == list_idx).count(), list.len());
+        }
+    }
+}
+
+#[test]
+fn kruskal_steps_build_the_same_tree() {
+    for seed in 0..10 {
+        let nodes: usize = 30;
+        let edges: Vec<Edge> = random_edges(nodes, 80, 20, seed);
+        let mut mst: Kruskal = Kruskal::new(nodes, &edges);
+        let mut considered: usize = 0;
+        let mut kept_so_far: usize = 0;
+        while let Some(((a, b, _), kept)) = mst.advance() {
+            considered += 1;
+            kept_so_far += usize::from(kept);
+            // Either way, both ends are in one set afterwards
+            assert!(mst.sets().clone().connected(a, b));
+            assert_eq!(mst.tree().len(), kept_so_far);
+        }
+        assert_eq!(considered, edges.len());
+        assert!(mst.remaining().is_empty());
+        // Stepping past the point where kruskal() stops only skips edges
+        assert_eq!(mst.into_tree(), kruskal(nodes, &edges));
+    }
+}
+
+#[test]
+fn render_sets_names_each_group_by_its_root() {
+    let mut mst: Kruskal = Kruskal::new(4, &[(0, 1, 1), (2, 3, 2), (1, 3, 3)]);
+    mst.advance();
+    let names: [&str; 4] = ["a", "b", "c", "d"];
+    assert_eq!(
+        render_sets(mst.sets(), |x| names[x].to_string()),
+        "parent: [0, 0, 2, 3]\nsets:   {a, b} root a  {c} root c  {d} root d"
+    );
+}
+
+#[test]
+fn step_mode_prints_each_step_and_waits() {
+    // Two steps, then finish the demo without pausing
+    step::enable(Box::new(Cursor::new("\n\nc\n")));
+    let output: String =
+        capture(|out| section(out, "merge", "k-way merge", practical_merge_sorted_lists));
+    step::disable();
+
+    assert!(output.contains("Step 1: pop 1 from list 0\n"));
+    assert!(output.contains("Step 3: pop 3 from list 2\n"));
+    assert!(!output.contains("Step 4:"));
+    assert!(output.contains("merged: [1, 2]\n"));
+    assert_eq!(output.matches("[Enter] next step").count(), 3);
+    assert!(output.contains("Merged result: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]"));
+}
+
+#[test]
+fn step_mode_ends_with_the_input() {
+    // One answer, then end of input: step mode switches itself off
+    step::enable(Box::new(Cursor::new("\n")));
+    let output: String = capture(practical_merge_sorted_lists);
+    assert!(!step::is_enabled());
+    assert_eq!(output.matches("[Enter] next step").count(), 2);
+}
+
+#[test]
+fn finishing_a_section_resets_the_step_count() {
+    step::enable(Box::new(Cursor::new("c\n\n")));
+    let output: String = capture(|out| {
+        section(out, "first", "first run", practical_merge_sorted_lists)?;
+        section(out, "second", "second run", practical_merge_sorted_lists)
+    });
+    step::disable();
+
+    // "c" skips the rest of the first section only; the second starts
+    // again at step 1
+    assert_eq!(output.matches("Step 1: pop 1 from list 0").count(), 2);
+    assert!(output.contains("Step 2: pop 2 from list 1"));
+}
+
+#[test]
+fn without_step_mode_nothing_is_printed() {
+    let output: String = capture(practical_merge_sorted_lists);
+    assert!(!output.contains("Step"));
+    assert!(!output.contains("[Enter]"));
+}
@@ -0,0 +1,104 @@
+//! `RunningMedian` and `SlidingMax` against recomputing from scratch, on
+//! random streams with many duplicates and on the edge cases.
+
+use collections_demo::streaming_stats_examples::{
+    RunningMedian, SlidingMax, latencies, naive_running_medians, naive_sliding_window_max,
+    sliding_window_max,
+};
+use demo_framework::rng::Lcg;
+
+#[test]
+fn running_median_matches_recomputing() {
+    let mut rng: Lcg = Lcg::new(3);
+    for bound in [2, 10, 1_000_000] {
+        let values: Vec<u64> = (0..2_000).map(|_| rng.below(bound)).collect();
+        let expected: Vec<(u64, u64)> = naive_running_medians(&values);
+        let mut median: RunningMedian<u64> = RunningMedian::new();
+        for (value, &(low, high)) in values.iter().zip(&expected) {
+            median.push(*value);
+            assert_eq!(median.medians(), Some((&low, &high)));
+            let (lower, upper) = median.halves();
+            assert!(lower == upper || lower == upper + 1);
+            assert_eq!(median.median(), Some((low + high) as f64 / 2.0));
+        }
+        assert_eq!(median.len(), values.len());
+    }
+}
+
+#[test]
+fn running_median_on_sorted_and_reversed_input() {
+    // Every push lands on the same side and forces a move across
+    let ascending: Vec<u64> = (0..1_000).collect();
+    let descending: Vec<u64> = ascending.iter().rev().copied().collect();
+    for values in [ascending, descending] {
+        let mut median: RunningMedian<u64> = RunningMedian::new();
+        for &value in &values {
+            median.push(value);
+        }
+        assert_eq!(median.medians(), Some((&499, &500)));
+        assert_eq!(median.median(), Some(499.5));
+    }
+}
+
+#[test]
+fn empty_and_single_value() {
+    let mut median: RunningMedian<&str> = RunningMedian::default();
+    assert!(median.is_empty());
+    assert_eq!(median.medians(), None);
+    median.push("m");
+    assert_eq!(median.medians(), Some((&"m", &"m")));
+    median.push("a");
+    assert_eq!(median.medians(), Some((&"a", &"m")));
+    assert_eq!(median.tops(), (Some(&"a"), Some(&"m")));
+}
+
+#[test]
+fn sliding_max_matches_scanning_each_window() {
+    let mut rng: Lcg = Lcg::new(8);
+    for bound in [3, 100, 1_000_000] {
+        let values: Vec<u64> = (0..3_000).map(|_| rng.below(bound)).collect();
+        for window in [1, 2, 7, 64, 2_999, 3_000] {
+            assert_eq!(
+                sliding_window_max(&values, window),
+                naive_sliding_window_max(&values, window),
+                "bound {} window {}",
+                bound,
+                window
+            );
+        }
+    }
+}
+
+#[test]
+fn sliding_max_before_the_window_fills() {
+    let mut sliding: SlidingMax<i32> = SlidingMax::new(3);
+    assert_eq!(sliding.max(), None);
+    let mut seen: Vec<i32> = Vec::new();
+    for value in [-5, 2, 2, -1, -7, -3, -2, 9] {
+        sliding.push(value);
+        seen.push(value);
+        let start: usize = seen.len().saturating_sub(3);
+        assert_eq!(sliding.max(), seen[start..].iter().max());
+        assert!(sliding.candidates() <= 3);
+    }
+    // Shorter input than the window: no full window at all
+    assert!(sliding_window_max(&[1, 2], 3).is_empty());
+}
+
+#[test]
+fn candidates_stay_decreasing_and_within_the_window() {
+    let values: Vec<u64> = latencies(5_000, 1);
+    let mut sliding: SlidingMax<u64> = SlidingMax::new(50);
+    for &value in &values {
+        sliding.push(value);
+        let stored: Vec<u64> = sliding.candidate_values().copied().collect();
+        assert!(stored.windows(2).all(|pair| pair[0] > pair[1]));
+        assert!(stored.len() <= sliding.window());
+    }
+}
+
+#[test]
+#[should_panic(expected = "at least one value")]
+fn zero_length_window_panics() {
+    SlidingMax::<u8>::new(0);
+}
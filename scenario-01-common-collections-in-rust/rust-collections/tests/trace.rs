@@ -0,0 +1,92 @@
+//! The `tracing` spans and events a demo run emits, captured by the same
+//! subscribers the command line installs, writing to a buffer.
+
+use collections_demo::run_demo;
+use demo_framework::embed::DemoOutput;
+use demo_framework::trace::{self, Format};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// A writer the subscriber can clone, all clones appending to one buffer.
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs the demo `name` under a `format` subscriber and returns the demo
+/// output and the trace lines.
+fn traced(format: Format, filter: &str, name: &str) -> (DemoOutput, Vec<String>) {
+    let buffer: Buffer = Buffer::default();
+    let writer: Buffer = buffer.clone();
+    let output: DemoOutput = tracing::subscriber::with_default(
+        trace::subscriber(format, filter, move || writer.clone()),
+        || run_demo(name).unwrap(),
+    );
+    let text: String = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    (output, text.lines().map(str::to_string).collect())
+}
+
+#[test]
+fn each_section_runs_in_its_own_span() {
+    let (_, lines) = traced(Format::Json, "demo=info", "grid_examples::rows_and_columns");
+
+    // Sections skipped by run_demo never open a span
+    assert_eq!(lines.len(), 1);
+    let line: &str = &lines[0];
+    assert!(line.contains(r#""message":"section finished""#), "{line}");
+    assert!(line.contains(r#""elapsed_us":"#), "{line}");
+    assert!(line.contains(r#""ok":true"#), "{line}");
+    assert!(
+        line.contains(
+            r#""spans":[{"module":"grid_examples","name":"module"},{"demo":"rows_and_columns","name":"section"}]"#
+        ),
+        "{line}"
+    );
+}
+
+#[test]
+fn recorded_values_become_events() {
+    let (output, lines) = traced(Format::Json, "demo=info", "grid_pathfinding");
+
+    let recorded: Vec<&String> = lines
+        .iter()
+        .filter(|line| line.contains(r#""message":"recorded""#))
+        .collect();
+    assert_eq!(recorded.len(), output.data.len());
+    for ((key, value), line) in output.data.iter().zip(&recorded) {
+        assert!(line.contains(&format!(r#""key":"{key}""#)), "{line}");
+        assert!(line.contains(&format!(r#""value":"{value}""#)), "{line}");
+        assert!(line.contains(r#"{"demo":"grid_pathfinding","name":"section"}"#));
+    }
+    // The section's own event comes after everything it recorded
+    assert!(lines.last().unwrap().contains("section finished"));
+}
+
+#[test]
+fn the_readable_format_is_quiet_unless_asked() {
+    let (_, lines) = traced(
+        Format::Text,
+        Format::Text.default_filter(),
+        "rotating_deques",
+    );
+    assert!(lines.is_empty(), "{lines:?}");
+
+    let (_, lines) = traced(Format::Text, "demo=info", "rotating_deques");
+    assert_eq!(lines.len(), 1);
+    assert!(
+        lines[0].contains(
+            r#"module{module="vecdeque_examples"}:section{demo="rotating_deques"}: demo: section finished"#
+        ),
+        "{}",
+        lines[0]
+    );
+}
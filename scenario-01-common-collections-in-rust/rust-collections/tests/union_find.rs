@@ -0,0 +1,195 @@
+//! Union-find invariants under arbitrary operation sequences (proptest),
+//! and Kruskal against a brute-force Prim on small random graphs.
+//!
+//! The reference for connectivity is a plain component label per element,
+//! relabelled on every merge: O(n) per union, obviously correct.
+
+use collections_demo::union_find_examples::{
+    Edge, QuickUnion, UnionFind, kruskal, random_edges, random_pairs,
+};
+use proptest::prelude::*;
+
+/// Component labels, merged the slow way.
+struct Labels(Vec<usize>);
+
+impl Labels {
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (from, to) = (self.0[a], self.0[b]);
+        if from == to {
+            return false;
+        }
+        for label in &mut self.0 {
+            if *label == from {
+                *label = to;
+            }
+        }
+        true
+    }
+
+    fn count(&self) -> usize {
+        let mut labels: Vec<usize> = self.0.clone();
+        labels.sort_unstable();
+        labels.dedup();
+        labels.len()
+    }
+}
+
+fn operations() -> impl Strategy<Value = (usize, Vec<(usize, usize)>)> {
+    (1usize..64).prop_flat_map(|n| (Just(n), prop::collection::vec((0..n, 0..n), 0..200)))
+}
+
+proptest! {
+    #[test]
+    fn matches_the_label_reference((n, unions) in operations()) {
+        let mut sets: UnionFind = UnionFind::new(n);
+        let mut labels: Labels = Labels((0..n).collect());
+        for &(a, b) in &unions {
+            prop_assert_eq!(sets.union(a, b), labels.union(a, b));
+        }
+        prop_assert_eq!(sets.set_count(), labels.count());
+        for a in 0..n {
+            for b in 0..n {
+                prop_assert_eq!(sets.connected(a, b), labels.0[a] == labels.0[b]);
+            }
+            let size: usize = labels.0.iter().filter(|&&l| l == labels.0[a]).count();
+            prop_assert_eq!(sets.set_size(a), size);
+        }
+    }
+
+    #[test]
+    fn rank_keeps_every_tree_logarithmic((n, unions) in operations()) {
+        let mut sets: UnionFind = UnionFind::new(n);
+        for &(a, b) in &unions {
+            sets.union(a, b);
+            // Union by rank alone guarantees height <= log2(n)
+            let limit: usize = n.ilog2() as usize;
+            for x in 0..n {
+                prop_assert!(sets.depth(x) <= limit, "depth {} > {}", sets.depth(x), limit);
+            }
+        }
+    }
+
+    #[test]
+    fn find_compresses_the_path((n, unions) in operations(), x in 0usize..64) {
+        let x: usize = x % n;
+        let mut sets: UnionFind = UnionFind::new(n);
+        for &(a, b) in &unions {
+            sets.union(a, b);
+        }
+        let root: usize = sets.find(x);
+        prop_assert!(sets.depth(x) <= 1);
+        prop_assert_eq!(sets.parents()[root], root);
+    }
+
+    #[test]
+    fn groups_partition_the_elements((n, unions) in operations()) {
+        let mut sets: UnionFind = UnionFind::new(n);
+        for &(a, b) in &unions {
+            sets.union(a, b);
+        }
+        let groups: Vec<Vec<usize>> = sets.groups();
+        prop_assert_eq!(groups.len(), sets.set_count());
+        let mut all: Vec<usize> = groups.concat();
+        all.sort_unstable();
+        prop_assert_eq!(all, (0..n).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn quick_union_agrees_on_connectivity((n, unions) in operations()) {
+        let mut sets: UnionFind = UnionFind::new(n);
+        let mut naive: QuickUnion = QuickUnion::new(n);
+        for &(a, b) in &unions {
+            prop_assert_eq!(sets.union(a, b), naive.union(a, b));
+        }
+        for a in 0..n {
+            prop_assert_eq!(sets.connected(a, 0), naive.connected(a, 0));
+        }
+    }
+}
+
+/// O(n^2) Prim per component: the total weight of a minimum spanning forest.
+fn prim_weight(nodes: usize, edges: &[Edge]) -> u64 {
+    let mut weight: Vec<Vec<Option<u32>>> = vec![vec![None; nodes]; nodes];
+    for &(a, b, w) in edges {
+        if a != b {
+            let best: u32 = weight[a][b].map_or(w, |old| old.min(w));
+            weight[a][b] = Some(best);
+            weight[b][a] = Some(best);
+        }
+    }
+    let mut in_tree: Vec<bool> = vec![false; nodes];
+    let mut total: u64 = 0;
+    for start in 0..nodes {
+        if in_tree[start] {
+            continue;
+        }
+        let mut cost: Vec<Option<u32>> = vec![None; nodes];
+        cost[start] = Some(0);
+        while let Some(next) = (0..nodes)
+            .filter(|&v| !in_tree[v] && cost[v].is_some())
+            .min_by_key(|&v| cost[v])
+        {
+            in_tree[next] = true;
+            total += u64::from(cost[next].unwrap_or(0));
+            for v in 0..nodes {
+                if let Some(w) = weight[next][v]
+                    && !in_tree[v]
+                    && cost[v].is_none_or(|c| w < c)
+                {
+                    cost[v] = Some(w);
+                }
+            }
+        }
+    }
+    total
+}
+
+#[test]
+fn kruskal_matches_prim() {
+    for seed in 0..50 {
+        let nodes: usize = 2 + seed as usize % 30;
+        let edges: Vec<Edge> = random_edges(nodes, nodes * 2, 20, seed);
+        let tree: Vec<Edge> = kruskal(nodes, &edges);
+        let total: u64 = tree.iter().map(|&(_, _, w)| u64::from(w)).sum();
+        assert_eq!(total, prim_weight(nodes, &edges), "seed {}", seed);
+
+        // A forest: one edge fewer than nodes per component
+        let mut sets: UnionFind = UnionFind::new(nodes);
+        for &(a, b, _) in &edges {
+            sets.union(a, b);
+        }
+        assert_eq!(tree.len(), nodes - sets.set_count(), "seed {}", seed);
+        assert!(tree.is_sorted_by_key(|&(_, _, w)| w));
+    }
+}
+
+#[test]
+fn kruskal_edge_cases() {
+    assert!(kruskal(0, &[]).is_empty());
+    assert!(kruskal(1, &[(0, 0, 5)]).is_empty());
+    // Parallel edges: the cheaper one wins
+    assert_eq!(kruskal(2, &[(0, 1, 9), (1, 0, 2)]), vec![(1, 0, 2)]);
+}
+
+#[test]
+fn random_unions_at_one_million_stay_flat() {
+    let n: usize = 1_000_000;
+    let mut sets: UnionFind = UnionFind::new(n);
+    for (a, b) in random_pairs(n, n, 42) {
+        sets.union(a, b);
+    }
+    assert!((0..n).all(|x| sets.depth(x) <= 19));
+    let sizes: usize = sets.groups().iter().map(Vec::len).sum();
+    assert_eq!(sizes, n);
+}
+
+#[test]
+fn push_adds_singletons() {
+    let mut sets: UnionFind = UnionFind::new(0);
+    assert!(sets.is_empty());
+    let a: usize = sets.push();
+    let b: usize = sets.push();
+    assert_eq!((a, b, sets.set_count()), (0, 1, 2));
+    assert!(sets.union(a, b));
+    assert_eq!((sets.len(), sets.set_count()), (2, 1));
+}
@@ -8,6 +8,19 @@
 //!   3. HashMap lookup performance
 //!   4. Performance across different key sizes
 //!   5. Performance with different key types
+//!   6. Deserializing a JSON map with and without a capacity hint
+//!   7. Creating many tiny, short-lived maps per hasher
+//!   8. A fresh map per request vs one cleared and reused, and vs a SmallVec
+//!   9. One mixed workload over every `KvStore` backend, and enum dispatch
+//!      vs a direct call
+//!  10. Lookup-or-insert on a String-keyed cache: std's APIs vs hashbrown's
+//!      entry_ref and raw_entry_mut
+//!  11. Scope stacks: a Vec of maps vs scope-tagged keys vs persistent-map
+//!      snapshots, at shallow and deep nesting
+//!  12. Dynamic programming: a Vec table vs a HashMap memo under several
+//!      hashers, on dense edit-distance states and sparse coin-change states
+//!  13. Inserting string keys: cloning each key vs moving owned keys vs a
+//!      Cow-keyed map, and an insert split into hash, clone and probe
 //!
 //! To run these benchmarks:
 //!   cargo bench
@@ -16,13 +29,25 @@
 //!   cargo bench -- Hashing
 //!   cargo bench -- HashMap_Insert
 //!   cargo bench -- HashMap_Lookup
+//!   cargo bench -- Map_Construction
+//!   cargo bench -- Map_Reuse
+//!   cargo bench -- KvStore_Backends
+//!   cargo bench -- Cache_Lookup
+//!   cargo bench -- Scoped_Map
+//!   cargo bench -- Memo_Table
+//!   cargo bench -- Key_Ownership
 //!
 //! Results are saved to target/criterion/ with HTML reports.
 
+// Hashers are driven by hand (build_hasher -> hash -> finish) to mirror the
+// examples; `hash_one` would measure the same work.
+#![allow(clippy::manual_hash_one)]
+
 use criterion::measurement::WallTime;
 use criterion::{
-    BenchmarkGroup, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main,
+    BatchSize, BenchmarkGroup, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main,
 };
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash, Hasher};
 use std::hint::black_box;
@@ -31,11 +56,27 @@ use std::hint::black_box;
 use ahash::{AHashMap, AHasher, RandomState as AHashRandomState};
 use foldhash::fast::{FoldHasher, RandomState as FoldRandomState};
 use foldhash::{HashMap as FoldHashMap, HashMapExt};
+use hashing_demo::deserialize_examples::PresizedMap;
+use hashing_demo::hashbrown_examples::{
+    count_entry_owned, count_entry_ref, count_get_then_insert, count_raw_entry, session_requests,
+};
+use hashing_demo::hasher_cost_examples::fill_tiny_map;
+use hashing_demo::kv_store_examples::{AnyStore, Backend, churn};
+use hashing_demo::memoization_examples::{
+    Memo, edit_distance_memo, edit_distance_table, min_coins_memo, min_coins_table, random_sequence,
+};
+use hashing_demo::scoped_map::{
+    PersistentScopedMap, ScopeOp, ScopeStack, ScopedMap, TaggedScopedMap, run_workload,
+    scope_workload,
+};
 use nohash_hasher::{BuildNoHashHasher, IntMap, NoHashHasher};
-use rustc_hash::{FxHashMap, FxHasher};
+use rustc_hash::{FxBuildHasher, FxHashMap, FxHasher};
+use serde::de::DeserializeSeed;
+use smallvec::SmallVec;
 use std::collections::hash_map::RandomState as StdRandomState;
 use twox_hash::XxHash64;
-use xxhash_rust::xxh3::xxh3_64;
+use twox_hash::xxhash64::RandomState as XxHash64RandomState;
+use xxhash_rust::xxh3::{Xxh3Builder, xxh3_64};
 
 // ============================================================================
 // RAW HASHING BENCHMARKS
@@ -612,6 +653,559 @@ fn bench_large_keys(c: &mut Criterion) {
     group.finish();
 }
 
+// ============================================================================
+// DESERIALIZATION BENCHMARKS
+// ============================================================================
+// Loads a JSON object into a map. serde_json gives no size hint, so the
+// default impl grows the table from empty; PresizedMap reserves the known
+// length up front and never rehashes.
+
+fn bench_deserialize_map(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Deserialize_Map");
+    // A 1M-entry parse takes long enough that 10 samples are plenty
+    group.sample_size(10);
+
+    for size in [10_000, 100_000, 1_000_000] {
+        group.throughput(Throughput::Elements(size as u64));
+
+        let entries: Vec<String> = (0..size)
+            .map(|i| format!("\"user-{:07}\":{}", i, i * 7))
+            .collect();
+        let json: String = format!("{{{}}}", entries.join(","));
+
+        // serde's built-in HashMap impl: no hint, grows from empty
+        group.bench_with_input(
+            BenchmarkId::new("SipHash_default", size),
+            &json,
+            |b, json| b.iter(|| serde_json::from_str::<HashMap<String, u64>>(json).unwrap()),
+        );
+
+        // Pre-sized with the default hasher
+        group.bench_with_input(
+            BenchmarkId::new("SipHash_presized", size),
+            &json,
+            |b, json| {
+                b.iter(|| {
+                    let mut de = serde_json::Deserializer::from_str(json);
+                    PresizedMap::<String, u64, StdRandomState>::new(size)
+                        .deserialize(&mut de)
+                        .unwrap()
+                        .map
+                })
+            },
+        );
+
+        // Pre-sized with FxHash: with_capacity_and_hasher works for any S
+        group.bench_with_input(
+            BenchmarkId::new("FxHash_presized", size),
+            &json,
+            |b, json| {
+                b.iter(|| {
+                    let mut de = serde_json::Deserializer::from_str(json);
+                    PresizedMap::<String, u64, FxBuildHasher>::new(size)
+                        .deserialize(&mut de)
+                        .unwrap()
+                        .map
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// MAP CONSTRUCTION BENCHMARKS
+// ============================================================================
+// Creates 1M tiny maps per iteration: a new builder, three inserts, drop.
+// This is the per-map overhead the throughput groups never see - building
+// the BuildHasher, the hasher state copied per key, and the table
+// allocation every map pays on its first insert.
+
+/// Builds `count` tiny maps, each with a builder from `make`.
+fn tiny_maps<S: BuildHasher>(count: u64, make: impl Fn() -> S) -> usize {
+    (0..count).map(|seed| fill_tiny_map(make(), seed)).sum()
+}
+
+fn bench_map_construction(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Map_Construction");
+    // One iteration builds 1M maps, so 10 samples are plenty
+    group.sample_size(10);
+
+    let count: u64 = 1_000_000;
+    group.throughput(Throughput::Elements(count));
+
+    group.bench_function("SipHash", |b| {
+        b.iter(|| tiny_maps(count, StdRandomState::new))
+    });
+
+    group.bench_function("FxHash", |b| b.iter(|| tiny_maps(count, || FxBuildHasher)));
+
+    group.bench_function("aHash", |b| {
+        b.iter(|| tiny_maps(count, AHashRandomState::new))
+    });
+
+    group.bench_function("Foldhash", |b| {
+        b.iter(|| tiny_maps(count, FoldRandomState::default))
+    });
+
+    group.bench_function("xxHash64", |b| {
+        b.iter(|| tiny_maps(count, XxHash64RandomState::default))
+    });
+
+    group.bench_function("xxh3", |b| b.iter(|| tiny_maps(count, Xxh3Builder::new)));
+
+    group.bench_function("NoHash", |b| {
+        b.iter(|| tiny_maps(count, BuildNoHashHasher::<u64>::default))
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// MAP REUSE BENCHMARKS
+// ============================================================================
+// A web handler often collects a request's parameters into a map, reads a
+// few of them back and throws the map away. Each iteration here handles
+// one request of N string-keyed parameters three ways per hasher:
+//   - fresh:  a new map (and BuildHasher) per request, dropped at the end
+//   - reused: one map per handler, `clear()`ed per request, which keeps its
+//             table, so only the first request allocates
+// plus a SmallVec of pairs searched linearly, the usual answer for tiny
+// cardinalities, which needs no hashing at all.
+
+/// Fills `map` with `params` and looks every parameter up again.
+fn serve<'a, S: BuildHasher>(map: &mut HashMap<&'a str, u64, S>, params: &[(&'a str, u64)]) -> u64 {
+    map.extend(params.iter().copied());
+    params.iter().map(|(key, _)| map[key]).sum()
+}
+
+fn serve_fresh<S: BuildHasher>(params: &[(&str, u64)], make: impl Fn() -> S) -> u64 {
+    let mut map: HashMap<&str, u64, S> = HashMap::with_hasher(make());
+    serve(&mut map, params)
+}
+
+fn serve_reused<'a, S: BuildHasher>(
+    map: &mut HashMap<&'a str, u64, S>,
+    params: &[(&'a str, u64)],
+) -> u64 {
+    map.clear();
+    serve(map, params)
+}
+
+/// The same request in a SmallVec: push every pair, find each by scanning.
+fn serve_smallvec<'a>(
+    pairs: &mut SmallVec<[(&'a str, u64); 16]>,
+    params: &[(&'a str, u64)],
+) -> u64 {
+    pairs.clear();
+    pairs.extend(params.iter().copied());
+    params
+        .iter()
+        .map(|(key, _)| pairs.iter().find(|(k, _)| k == key).map_or(0, |&(_, v)| v))
+        .sum()
+}
+
+/// Adds the fresh and reused cases for one hasher.
+fn bench_reuse_for<S: BuildHasher>(
+    group: &mut BenchmarkGroup<WallTime>,
+    name: &str,
+    params: &[(&str, u64)],
+    make: impl Fn() -> S + Copy,
+) {
+    let size: usize = params.len();
+    group.bench_with_input(
+        BenchmarkId::new(format!("{name}_fresh"), size),
+        &params,
+        |b, params| b.iter(|| serve_fresh(params, make)),
+    );
+    group.bench_with_input(
+        BenchmarkId::new(format!("{name}_reused"), size),
+        &params,
+        |b, params| {
+            let mut map: HashMap<&str, u64, S> = HashMap::with_hasher(make());
+            b.iter(|| serve_reused(&mut map, params))
+        },
+    );
+}
+
+fn bench_map_reuse(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Map_Reuse");
+
+    let names: Vec<String> = (0..64).map(|i| format!("param_{i}")).collect();
+    for size in [4, 16, 64] {
+        let params: Vec<(&str, u64)> = names[..size]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), i as u64))
+            .collect();
+        group.throughput(Throughput::Elements(size as u64));
+
+        bench_reuse_for(&mut group, "SipHash", &params, StdRandomState::new);
+        bench_reuse_for(&mut group, "FxHash", &params, || FxBuildHasher);
+        bench_reuse_for(&mut group, "aHash", &params, AHashRandomState::new);
+        bench_reuse_for(&mut group, "Foldhash", &params, FoldRandomState::default);
+
+        // Inline up to 16 pairs; 64 spills to the heap like a Vec
+        group.bench_with_input(
+            BenchmarkId::new("SmallVec_fresh", size),
+            &params,
+            |b, params| {
+                b.iter(|| {
+                    let mut pairs: SmallVec<[(&str, u64); 16]> = SmallVec::new();
+                    serve_smallvec(&mut pairs, params)
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("SmallVec_reused", size),
+            &params,
+            |b, params| {
+                let mut pairs: SmallVec<[(&str, u64); 16]> = SmallVec::new();
+                b.iter(|| serve_smallvec(&mut pairs, params))
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// KV STORE BACKEND BENCHMARKS
+// ============================================================================
+// The `churn` workload from kv_store_examples (inserts, upserts, lookups and
+// removals over a fixed ID range) on every `Backend`, each reached through
+// `AnyStore`. `HashMap_direct` runs it on a plain HashMap so the cost of the
+// enum's match shows up next to `HashMap`.
+
+fn bench_kv_store_backends(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("KvStore_Backends");
+
+    let ops: usize = 10_000;
+    group.throughput(Throughput::Elements(ops as u64));
+    for keys in [100, 2_000] {
+        for backend in Backend::ALL {
+            group.bench_with_input(BenchmarkId::new(backend.name(), keys), &keys, |b, &keys| {
+                b.iter(|| {
+                    let mut store: AnyStore<u64> = AnyStore::new(backend);
+                    churn(&mut store, ops, keys)
+                })
+            });
+        }
+
+        group.bench_with_input(
+            BenchmarkId::new("HashMap_direct", keys),
+            &keys,
+            |b, &keys| {
+                b.iter(|| {
+                    let mut map: HashMap<u64, u64> = HashMap::new();
+                    churn(&mut map, ops, keys)
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// STRING CACHE BENCHMARKS
+// ============================================================================
+// 100k requests with `&str` keys against a cache keyed by `String`, starting
+// empty, so each distinct key is a miss once and a hit after that. 1k
+// distinct keys is a 99% hit rate, 20k is 80%. Every map uses foldhash, so
+// only the lookup API differs.
+
+/// Runs every request through `count` on a fresh map.
+fn serve_cache<M: Default>(requests: &[String], count: impl Fn(&mut M, &str) -> u64) -> u64 {
+    let mut map: M = M::default();
+    requests.iter().map(|key| count(&mut map, key)).sum()
+}
+
+fn bench_cache_lookup(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Cache_Lookup");
+
+    let count: usize = 100_000;
+    group.throughput(Throughput::Elements(count as u64));
+    for distinct in [1_000, 20_000] {
+        let requests: Vec<String> = session_requests(count, distinct);
+
+        group.bench_with_input(
+            BenchmarkId::new("std_get_then_insert", distinct),
+            &requests,
+            |b, requests| {
+                b.iter(|| {
+                    serve_cache::<HashMap<String, u64, FoldRandomState>>(
+                        requests,
+                        count_get_then_insert,
+                    )
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("std_entry_owned", distinct),
+            &requests,
+            |b, requests| {
+                b.iter(|| {
+                    serve_cache::<HashMap<String, u64, FoldRandomState>>(
+                        requests,
+                        count_entry_owned,
+                    )
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("hashbrown_entry_ref", distinct),
+            &requests,
+            |b, requests| {
+                b.iter(|| {
+                    serve_cache::<hashbrown::HashMap<String, u64, FoldRandomState>>(
+                        requests,
+                        count_entry_ref,
+                    )
+                })
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("hashbrown_raw_entry", distinct),
+            &requests,
+            |b, requests| {
+                b.iter(|| {
+                    serve_cache::<hashbrown::HashMap<String, u64, FoldRandomState>>(
+                        requests,
+                        count_raw_entry,
+                    )
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// SCOPE STACK BENCHMARKS
+// ============================================================================
+// 200 function bodies, each a chain of blocks nested 2 or 16 deep. Every
+// block declares 4 names and looks up 16: a quarter undefined, the rest
+// spread over the open scopes, so deep nesting means long outward walks for
+// the layouts that probe once per scope. Each iteration starts from an empty
+// stack; all three hash with FxHash.
+
+fn bench_scope_stack<S: ScopeStack<u32, u32> + Default>(
+    group: &mut BenchmarkGroup<WallTime>,
+    name: &str,
+    nesting: usize,
+    ops: &[ScopeOp],
+) {
+    group.bench_with_input(BenchmarkId::new(name, nesting), ops, |b, ops| {
+        b.iter(|| run_workload(&mut S::default(), black_box(ops)))
+    });
+}
+
+fn bench_scoped_map(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Scoped_Map");
+
+    for nesting in [2, 16] {
+        let ops: Vec<ScopeOp> = scope_workload(200, nesting, 42);
+        group.throughput(Throughput::Elements(ops.len() as u64));
+
+        bench_scope_stack::<ScopedMap<u32, u32>>(&mut group, "vec_of_maps", nesting, &ops);
+        bench_scope_stack::<TaggedScopedMap<u32, u32>>(&mut group, "tagged_keys", nesting, &ops);
+        bench_scope_stack::<PersistentScopedMap<u32, u32>>(&mut group, "persistent", nesting, &ops);
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// MEMO TABLE BENCHMARKS
+// ============================================================================
+// Edit distance between two unrelated 300-base strings reaches about three
+// quarters of its 90,601 states: dense, the table's home ground. Coin change
+// with coins [400, 1100, 2900] to 1,000,000 reaches under 1% of the totals a
+// table fills. Each memo starts empty, so every iteration pays for growing it.
+
+fn bench_edit_distance_memo<S: BuildHasher + Default>(
+    group: &mut BenchmarkGroup<WallTime>,
+    name: &str,
+    a: &[u8],
+    b: &[u8],
+) {
+    group.bench_function(BenchmarkId::new("edit_distance", name), |bench| {
+        bench.iter(|| {
+            let mut memo: Memo<(usize, usize), usize, S> = Memo::new();
+            edit_distance_memo(black_box(a), black_box(b), &mut memo)
+        })
+    });
+}
+
+fn bench_memo_table(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Memo_Table");
+
+    let a: Vec<u8> = random_sequence(300, 1);
+    let b: Vec<u8> = random_sequence(300, 2);
+    group.bench_function(BenchmarkId::new("edit_distance", "table"), |bench| {
+        bench.iter(|| edit_distance_table(black_box(&a), black_box(&b)))
+    });
+    bench_edit_distance_memo::<StdRandomState>(&mut group, "memo_siphash", &a, &b);
+    bench_edit_distance_memo::<FxBuildHasher>(&mut group, "memo_fxhash", &a, &b);
+    bench_edit_distance_memo::<FoldRandomState>(&mut group, "memo_foldhash", &a, &b);
+    bench_edit_distance_memo::<AHashRandomState>(&mut group, "memo_ahash", &a, &b);
+
+    let coins: [u64; 3] = [400, 1_100, 2_900];
+    group.bench_function(BenchmarkId::new("coin_change", "table"), |bench| {
+        bench.iter(|| min_coins_table(black_box(&coins), black_box(1_000_000)))
+    });
+    group.bench_function(BenchmarkId::new("coin_change", "memo_fxhash"), |bench| {
+        bench.iter(|| {
+            let mut memo: Memo<u64, Option<u32>, FxBuildHasher> = Memo::new();
+            min_coins_memo(black_box(&coins), black_box(1_000_000), &mut memo)
+        })
+    });
+
+    group.finish();
+}
+
+// ============================================================================
+// KEY OWNERSHIP BENCHMARKS
+// ============================================================================
+// Builds a 10k-entry index from string keys the caller keeps using, such as
+// paths read from a config file. One key in ten arrives in mixed case and
+// is stored lowercased. Every map uses foldhash and is presized, so no
+// variant pays for growth. The insert paths:
+//   - clone_per_insert: `insert(key.clone(), v)`, one String per key
+//   - owned_once:       the caller hands over its Vec<String> and the keys
+//                       move into the map (the Vec's clone is setup, untimed)
+//   - cow_keys:         a HashMap<Cow<str>, _> that borrows the clean keys
+//                       and allocates only for the lowercased tenth
+//   - borrowed_str:     a HashMap<&str, _> over keys normalized beforehand,
+//                       i.e. an insert with nothing to allocate
+// and, over the same keys, the parts of an insert timed on their own:
+//   - part_hash:  hashing every key
+//   - part_clone: cloning every key and dropping the clone
+//   - part_probe: finding every key in a built table, given its hash
+
+/// `count` keys like `tenant-0042/orders/00042`, every tenth in mixed case.
+fn ownership_keys(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| {
+            let key: String = format!("tenant-{:04}/orders/{:05}", i % 100, i);
+            if i % 10 == 0 {
+                key.replace("tenant", "Tenant").replace("orders", "Orders")
+            } else {
+                key
+            }
+        })
+        .collect()
+}
+
+/// Borrows a key that is already lowercase, lowercases a copy otherwise.
+fn normalize(key: &str) -> Cow<'_, str> {
+    if key.bytes().any(|b| b.is_ascii_uppercase()) {
+        Cow::Owned(key.to_ascii_lowercase())
+    } else {
+        Cow::Borrowed(key)
+    }
+}
+
+fn bench_key_ownership(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<WallTime> = c.benchmark_group("Key_Ownership");
+
+    let count: usize = 10_000;
+    group.throughput(Throughput::Elements(count as u64));
+    let keys: Vec<String> = ownership_keys(count);
+    let normalized: Vec<String> = keys.iter().map(|key| key.to_ascii_lowercase()).collect();
+    let state: FoldRandomState = FoldRandomState::default();
+
+    group.bench_function("clone_per_insert", |b| {
+        b.iter(|| {
+            let mut map: HashMap<String, u32, FoldRandomState> =
+                HashMap::with_capacity_and_hasher(count, state.clone());
+            for (i, key) in keys.iter().enumerate() {
+                map.insert(normalize(key).into_owned(), i as u32);
+            }
+            map.len()
+        })
+    });
+
+    group.bench_function("owned_once", |b| {
+        b.iter_batched(
+            || keys.clone(),
+            |owned| {
+                let mut map: HashMap<String, u32, FoldRandomState> =
+                    HashMap::with_capacity_and_hasher(count, state.clone());
+                for (i, mut key) in owned.into_iter().enumerate() {
+                    key.make_ascii_lowercase();
+                    map.insert(key, i as u32);
+                }
+                map.len()
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("cow_keys", |b| {
+        b.iter(|| {
+            let mut map: HashMap<Cow<str>, u32, FoldRandomState> =
+                HashMap::with_capacity_and_hasher(count, state.clone());
+            for (i, key) in keys.iter().enumerate() {
+                map.insert(normalize(key), i as u32);
+            }
+            map.len()
+        })
+    });
+
+    group.bench_function("borrowed_str", |b| {
+        b.iter(|| {
+            let mut map: HashMap<&str, u32, FoldRandomState> =
+                HashMap::with_capacity_and_hasher(count, state.clone());
+            for (i, key) in normalized.iter().enumerate() {
+                map.insert(key.as_str(), i as u32);
+            }
+            map.len()
+        })
+    });
+
+    group.bench_function("part_hash", |b| {
+        b.iter(|| {
+            normalized
+                .iter()
+                .fold(0u64, |acc, key| acc ^ state.hash_one(key.as_str()))
+        })
+    });
+
+    group.bench_function("part_clone", |b| {
+        b.iter(|| {
+            for key in &normalized {
+                black_box(key.clone());
+            }
+        })
+    });
+
+    let mut table: hashbrown::HashMap<String, u32, FoldRandomState> =
+        hashbrown::HashMap::with_capacity_and_hasher(count, state.clone());
+    table.extend(normalized.iter().cloned().zip(0..));
+    let hashed: Vec<(u64, &str)> = normalized
+        .iter()
+        .map(|key| (state.hash_one(key.as_str()), key.as_str()))
+        .collect();
+    group.bench_function("part_probe", |b| {
+        b.iter(|| {
+            hashed
+                .iter()
+                .filter_map(|&(hash, key)| table.raw_entry().from_key_hashed_nocheck(hash, key))
+                .map(|(_, &value)| u64::from(value))
+                .sum::<u64>()
+        })
+    });
+
+    group.finish();
+}
+
 // ============================================================================
 // CRITERION CONFIGURATION
 // ============================================================================
@@ -624,6 +1218,14 @@ criterion_group!(
     bench_hashmap_lookup,
     bench_entry_api,
     bench_large_keys,
+    bench_deserialize_map,
+    bench_map_construction,
+    bench_map_reuse,
+    bench_kv_store_backends,
+    bench_cache_lookup,
+    bench_scoped_map,
+    bench_memo_table,
+    bench_key_ownership,
 );
 
-criterion_main!(benches);
\ No newline at end of file
+criterion_main!(benches);
@@ -0,0 +1,434 @@
+//! A/B Bucketing - Assigning Users to Experiment Arms With a Seeded Hash
+//!
+//! An experiment splits users into arms (control, treatment, ...) and the
+//! split has to be:
+//!
+//! - Stable: a user sees the same arm on every request, on every server,
+//!   after every deploy. No table of assignments to store or sync
+//! - Weighted: 50/50, or 90/10 while a risky change ramps up
+//! - Independent across experiments: being in treatment for the checkout
+//!   test must say nothing about your arm in the search test
+//!
+//! `arm = weights.locate(hash(seed, user_id) * total_weight >> 64)` gives all
+//! three - if the hash is a good one. Seeded xxh3 is: its output is
+//! specified, so assignments survive restarts and upgrades, and a seed
+//! derived from the experiment's name makes every experiment an independent
+//! shuffle. FxHash of sequential IDs is the counter-example: its buckets
+//! are perfectly balanced and still not random.
+
+use crate::sampling_examples::chi_squared;
+use demo_framework::section;
+use rustc_hash::FxHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use xxhash_rust::xxh3::{xxh3_64, xxh3_64_with_seed};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "stable_assignment",
+        "Same user, same arm: assignment as a pure function of (seed, user_id)",
+        stable_assignment,
+    )?;
+
+    section(
+        out,
+        "weighted_arms",
+        "Weighted arms and a chi-squared check of the split",
+        weighted_arms,
+    )?;
+
+    section(
+        out,
+        "independent_experiments",
+        "Per-experiment seeds make experiments independent",
+        independent_experiments,
+    )?;
+
+    section(
+        out,
+        "ramping_up",
+        "Growing the treatment arm without reshuffling anyone already in it",
+        ramping_up,
+    )?;
+
+    section(
+        out,
+        "fxhash_bias",
+        "Why FxHash of sequential IDs makes a biased bucketer",
+        fxhash_bias,
+    )?;
+
+    Ok(())
+}
+
+/// An experiment: a seed plus named arms with integer weights.
+#[derive(Clone, Debug)]
+pub struct Experiment {
+    name: String,
+    seed: u64,
+    arms: Vec<(String, u32)>,
+    total_weight: u64,
+}
+
+impl Experiment {
+    /// An experiment seeded by hashing its name, so two experiments with
+    /// different names get independent assignments.
+    pub fn new(name: &str, arms: &[(&str, u32)]) -> Self {
+        Self::with_seed(name, xxh3_64(name.as_bytes()), arms)
+    }
+
+    /// Panics if there are no arms or all weights are zero.
+    pub fn with_seed(name: &str, seed: u64, arms: &[(&str, u32)]) -> Self {
+        let total_weight: u64 = arms.iter().map(|&(_, w)| w as u64).sum();
+        assert!(total_weight > 0, "an experiment needs a non-zero weight");
+        Experiment {
+            name: name.to_string(),
+            seed,
+            arms: arms.iter().map(|&(arm, w)| (arm.to_string(), w)).collect(),
+            total_weight,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn arm_names(&self) -> impl Iterator<Item = &str> {
+        self.arms.iter().map(|(arm, _)| arm.as_str())
+    }
+
+    pub fn weights(&self) -> impl Iterator<Item = u32> {
+        self.arms.iter().map(|&(_, w)| w)
+    }
+
+    /// The index of the arm `user_id` belongs to.
+    pub fn assign(&self, user_id: u64) -> usize {
+        let hash: u64 = xxh3_64_with_seed(&user_id.to_le_bytes(), self.seed);
+        // Scale the hash to 0..total_weight using its high bits
+        let point: u64 = ((hash as u128 * self.total_weight as u128) >> 64) as u64;
+        let mut upper: u64 = 0;
+        for (index, &(_, weight)) in self.arms.iter().enumerate() {
+            upper += weight as u64;
+            if point < upper {
+                return index;
+            }
+        }
+        unreachable!("point is below the total weight")
+    }
+
+    /// The name of the arm `user_id` belongs to.
+    pub fn arm(&self, user_id: u64) -> &str {
+        &self.arms[self.assign(user_id)].0
+    }
+}
+
+/// How many of `user_ids` land in each arm.
+pub fn arm_counts(experiment: &Experiment, user_ids: impl IntoIterator<Item = u64>) -> Vec<u64> {
+    let mut counts: Vec<u64> = vec![0; experiment.arms.len()];
+    for user_id in user_ids {
+        counts[experiment.assign(user_id)] += 1;
+    }
+    counts
+}
+
+/// Pearson's chi-squared statistic of `counts` against `weights`.
+pub fn chi_squared_weighted(counts: &[u64], weights: &[u32]) -> f64 {
+    let total: f64 = counts.iter().sum::<u64>() as f64;
+    let total_weight: f64 = weights.iter().map(|&w| w as f64).sum();
+    counts
+        .iter()
+        .zip(weights)
+        .filter(|&(_, &weight)| weight > 0)
+        .map(|(&observed, &weight)| {
+            let expected: f64 = total * weight as f64 / total_weight;
+            let diff: f64 = observed as f64 - expected;
+            diff * diff / expected
+        })
+        .sum()
+}
+
+/// Counts users by (arm in `a`, arm in `b`): rows are `a`'s arms.
+pub fn cross_tabulate(
+    a: &Experiment,
+    b: &Experiment,
+    user_ids: impl IntoIterator<Item = u64>,
+) -> Vec<Vec<u64>> {
+    let mut table: Vec<Vec<u64>> = vec![vec![0; b.arms.len()]; a.arms.len()];
+    for user_id in user_ids {
+        table[a.assign(user_id)][b.assign(user_id)] += 1;
+    }
+    table
+}
+
+/// Chi-squared test of independence for a contingency table.
+pub fn chi_squared_independence(table: &[Vec<u64>]) -> f64 {
+    let total: f64 = table.iter().flatten().sum::<u64>() as f64;
+    let row_totals: Vec<f64> = table.iter().map(|r| r.iter().sum::<u64>() as f64).collect();
+    let columns: usize = table.first().map_or(0, Vec::len);
+    let column_totals: Vec<f64> = (0..columns)
+        .map(|c| table.iter().map(|r| r[c]).sum::<u64>() as f64)
+        .collect();
+
+    let mut chi: f64 = 0.0;
+    for (r, row) in table.iter().enumerate() {
+        for (c, &observed) in row.iter().enumerate() {
+            let expected: f64 = row_totals[r] * column_totals[c] / total;
+            if expected > 0.0 {
+                let diff: f64 = observed as f64 - expected;
+                chi += diff * diff / expected;
+            }
+        }
+    }
+    chi
+}
+
+/// FxHash of a user ID, optionally seeded.
+fn fx_hash(seed: usize, user_id: u64) -> u64 {
+    let mut hasher: FxHasher = FxHasher::with_seed(seed);
+    user_id.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Demonstrates that assignment needs no stored state.
+pub fn stable_assignment(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Stable Assignment:")?;
+
+    let experiment: Experiment =
+        Experiment::new("checkout-redesign", &[("control", 50), ("treatment", 50)]);
+    writeln!(out, "    Experiment: {}", experiment.name())?;
+    writeln!(out, "    Seed = xxh3(name) = {:#018x}", experiment.seed())?;
+
+    for user_id in 1..=6 {
+        writeln!(out, "    user {} -> {}", user_id, experiment.arm(user_id))?;
+    }
+
+    // Any server, any process, any day: the same answer
+    let replica: Experiment =
+        Experiment::new("checkout-redesign", &[("control", 50), ("treatment", 50)]);
+    let agree: bool = (0..10_000).all(|id| replica.assign(id) == experiment.assign(id));
+    writeln!(
+        out,
+        "\n    A second instance (another server) agrees on 10,000 users: {}",
+        agree
+    )?;
+    writeln!(out, "    xxh3's output is specified, so this holds across")?;
+    writeln!(out, "    restarts, platforms and crate upgrades.")?;
+
+    Ok(())
+}
+
+/// Demonstrates weighted arms and checks the split is what was asked for.
+pub fn weighted_arms(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Weighted Arms:")?;
+
+    let experiment: Experiment = Experiment::new(
+        "search-ranking",
+        &[("control", 50), ("variant-a", 25), ("variant-b", 25)],
+    );
+    let counts: Vec<u64> = arm_counts(&experiment, 0..100_000);
+    let weights: Vec<u32> = experiment.weights().collect();
+
+    writeln!(out, "    100,000 sequential user IDs:")?;
+    for ((arm, weight), count) in experiment.arm_names().zip(&weights).zip(&counts) {
+        writeln!(
+            out,
+            "    {:<10} weight {:>2}%: {:>6} users",
+            arm, weight, count
+        )?;
+    }
+
+    let chi: f64 = chi_squared_weighted(&counts, &weights);
+    writeln!(
+        out,
+        "    chi-squared {:.2} (2 degrees of freedom, 13.82 at p = 0.001): {}",
+        chi,
+        if chi < 13.82 { "as weighted" } else { "SKEWED" }
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates that differently-seeded experiments don't interfere.
+pub fn independent_experiments(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Independent Experiments:")?;
+
+    let checkout: Experiment =
+        Experiment::new("checkout-redesign", &[("control", 50), ("treatment", 50)]);
+    let search: Experiment =
+        Experiment::new("search-ranking", &[("control", 50), ("treatment", 50)]);
+    let table: Vec<Vec<u64>> = cross_tabulate(&checkout, &search, 0..100_000);
+
+    writeln!(out, "    Users by arm, checkout (rows) x search (columns):")?;
+    writeln!(out, "    {:<10} {:>9} {:>9}", "", "control", "treatment")?;
+    for (arm, row) in checkout.arm_names().zip(&table) {
+        writeln!(out, "    {:<10} {:>9} {:>9}", arm, row[0], row[1])?;
+    }
+    let chi: f64 = chi_squared_independence(&table);
+    writeln!(
+        out,
+        "    Independence chi-squared {:.2} (1 degree of freedom, 10.83 at p = 0.001)",
+        chi
+    )?;
+
+    // Reusing one seed for both would put the same users in treatment twice
+    let same_seed: Experiment = Experiment::with_seed(
+        "search-ranking",
+        checkout.seed(),
+        &[("control", 50), ("treatment", 50)],
+    );
+    let table: Vec<Vec<u64>> = cross_tabulate(&checkout, &same_seed, 0..100_000);
+    writeln!(
+        out,
+        "\n    Same seed for both experiments: {} users in treatment twice,",
+        table[1][1]
+    )?;
+    writeln!(
+        out,
+        "    {} in exactly one treatment arm",
+        table[0][1] + table[1][0]
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates a 10% -> 50% ramp that keeps the original 10% treated.
+///
+/// Treatment is the last arm, so its range of points grows downwards:
+/// users already in it stay in it, and only control users move.
+pub fn ramping_up(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Ramping Up:")?;
+
+    let ids = 0..100_000u64;
+    let stages: [u32; 4] = [10, 25, 50, 100];
+    let mut previous: Option<Vec<bool>> = None;
+    for treated in stages {
+        let experiment: Experiment = Experiment::new(
+            "new-onboarding",
+            &[("control", 100 - treated), ("treatment", treated)],
+        );
+        let in_treatment: Vec<bool> = ids.clone().map(|id| experiment.assign(id) == 1).collect();
+        let count: usize = in_treatment.iter().filter(|&&t| t).count();
+        let kept: String = match &previous {
+            Some(before) => {
+                let stayed: bool = before.iter().zip(&in_treatment).all(|(&b, &a)| !b || a);
+                format!(", everyone treated before still treated: {}", stayed)
+            }
+            None => String::new(),
+        };
+        writeln!(
+            out,
+            "    {:>3}% treatment: {:>6} users{}",
+            treated, count, kept
+        )?;
+        previous = Some(in_treatment);
+    }
+
+    writeln!(
+        out,
+        "\n    Changing the seed or the order of the arms mid-ramp"
+    )?;
+    writeln!(out, "    would reshuffle everyone.")?;
+
+    Ok(())
+}
+
+/// Demonstrates what goes wrong with FxHash as the bucketing hash.
+///
+/// FxHash of an integer is roughly `(seed + id) * K`: consecutive IDs step
+/// through the hash space by a fixed stride, and a different seed only
+/// shifts where the walk starts.
+pub fn fxhash_bias(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  FxHash of Sequential IDs:")?;
+
+    let ids = 0..100_000u64;
+    let fx_bucket =
+        |seed: usize, id: u64| -> u64 { ((fx_hash(seed, id) as u128 * 2) >> 64) as u64 };
+    let xx_bucket = |seed: u64, id: u64| -> u64 {
+        ((xxh3_64_with_seed(&id.to_le_bytes(), seed) as u128 * 2) >> 64) as u64
+    };
+
+    // 1. The split looks perfect
+    let mut fx_counts: [u64; 2] = [0; 2];
+    let mut xx_counts: [u64; 2] = [0; 2];
+    for id in ids.clone() {
+        fx_counts[fx_bucket(0, id) as usize] += 1;
+        xx_counts[xx_bucket(1, id) as usize] += 1;
+    }
+    writeln!(out, "    50/50 split of 100,000 users:")?;
+    writeln!(
+        out,
+        "      FxHash: {:?}, chi-squared {:.3}",
+        fx_counts,
+        chi_squared(&fx_counts)
+    )?;
+    writeln!(
+        out,
+        "      xxh3:   {:?}, chi-squared {:.3}",
+        xx_counts,
+        chi_squared(&xx_counts)
+    )?;
+    writeln!(out, "      A random split is off by ~160 users; FxHash's")?;
+    writeln!(out, "      is too even to be random.")?;
+
+    // 2. Neighbouring IDs follow a pattern
+    let pattern = |bucket: &dyn Fn(u64) -> u64| -> String {
+        (0..40)
+            .map(|id| if bucket(id) == 0 { 'A' } else { 'B' })
+            .collect()
+    };
+    writeln!(out, "\n    Arms of users 0..40:")?;
+    writeln!(out, "      FxHash: {}", pattern(&|id| fx_bucket(0, id)))?;
+    writeln!(out, "      xxh3:   {}", pattern(&|id| xx_bucket(1, id)))?;
+    let fx_adjacent: usize = ids
+        .clone()
+        .filter(|&id| fx_bucket(0, id) == fx_bucket(0, id + 1))
+        .count();
+    let xx_adjacent: usize = ids
+        .clone()
+        .filter(|&id| xx_bucket(1, id) == xx_bucket(1, id + 1))
+        .count();
+    writeln!(
+        out,
+        "      Users n and n+1 in the same arm: FxHash {:.1}%, xxh3 {:.1}% (random: 50%)",
+        fx_adjacent as f64 / 1_000.0,
+        xx_adjacent as f64 / 1_000.0
+    )?;
+
+    // 3. Two experiments are correlated, seeded or not
+    let agreement = |same: &dyn Fn(u64) -> bool| -> f64 {
+        ids.clone().filter(|&id| same(id)).count() as f64 / 1_000.0
+    };
+    writeln!(out, "\n    Users in the same arm of two 50/50 experiments:")?;
+    writeln!(
+        out,
+        "      FxHash, no seed:        {:.1}%",
+        agreement(&|id| fx_bucket(0, id) == fx_bucket(0, id))
+    )?;
+    writeln!(
+        out,
+        "      FxHash, seeds 1 and 2:  {:.1}%",
+        agreement(&|id| fx_bucket(1, id) == fx_bucket(2, id))
+    )?;
+    writeln!(
+        out,
+        "      xxh3, seeds 1 and 2:    {:.1}% (independent: 50%)",
+        agreement(&|id| xx_bucket(1, id) == xx_bucket(2, id))
+    )?;
+
+    writeln!(out, "\n    Teams and households sign up together and get")?;
+    writeln!(
+        out,
+        "    consecutive IDs; with FxHash their arms are patterned,"
+    )?;
+    writeln!(
+        out,
+        "    and one experiment's treatment group predicts the other's."
+    )?;
+
+    Ok(())
+}
@@ -13,76 +13,79 @@
 //! aHash is a popular choice for applications that need both speed and safety.
 
 use ahash::{AHashMap, AHashSet, AHasher, RandomState};
+use demo_framework::section;
 use rustc_hash::FxHasher;
 use std::collections::hash_map::RandomState as StdRandomState;
 use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash, Hasher};
+use std::io::{self, Write};
 use std::time::{Duration, Instant};
 
-fn section(name: &str, what: &str, f: impl FnOnce()) {
-    println!("\n{:=<80}", "");
-    println!("DEMO: {name}");
-    println!("  {what}");
-    println!("{:=<80}", "");
-
-    f();
-}
-
-pub fn run_all() {
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
     section(
+        out,
         "basic_ahashmap_usage",
         "Basic AHashMap API usage (drop-in replacement for std::HashMap, keyed by default)",
         basic_ahashmap_usage,
-    );
+    )?;
 
     section(
+        out,
         "ahashset_usage",
         "AHashSet usage for membership testing and deduplication",
         ahashset_usage,
-    );
+    )?;
 
     section(
+        out,
         "random_seeding",
         "Random seeds: different RandomState instances typically hash the same input differently",
         random_seeding,
-    );
+    )?;
 
     section(
+        out,
         "deterministic_ahash",
         "Fixed seeds: reproducible hashing using RandomState::with_seeds(...)",
         deterministic_ahash,
-    );
+    )?;
 
     section(
+        out,
         "performance_comparison",
         "Rough timing: aHash vs SipHash vs FxHash (not a real benchmark)",
         performance_comparison,
-    );
+    )?;
 
     section(
+        out,
         "hardware_detection",
         "Backend notes: compile-time AES selection vs runtime CPU feature detection",
         hardware_detection,
-    );
+    )?;
 
     section(
+        out,
         "cache_example",
         "Practical demo: high-performance cache with expiration using AHashMap",
         cache_example,
-    );
+    )?;
 
     section(
+        out,
         "counting_example",
         "Practical demo: word frequency counting using AHashMap",
         counting_example,
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates basic AHashMap usage.
 ///
 /// AHashMap is just HashMap with aHash as the hasher.
 /// The API is identical to standard HashMap, making it easy to adopt.
-pub fn basic_ahashmap_usage() {
-    println!("\n  Basic AHashMap Usage:");
+pub fn basic_ahashmap_usage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Basic AHashMap Usage:")?;
 
     // Using the provided type alias - the simplest way to use aHash.
     // AHashMap::new() creates a new map with a random seed for security.
@@ -91,30 +94,33 @@ pub fn basic_ahashmap_usage() {
     cache.insert("key1".to_string(), vec![1, 2, 3]);
     cache.insert("key2".to_string(), vec![4, 5, 6]);
 
-    println!("    AHashMap: {:?}", cache);
+    writeln!(out, "    AHashMap: {:?}", cache)?;
 
     // All standard HashMap operations work exactly the same
     if let Some(data) = cache.get("key1") {
-        println!("    Retrieved key1: {:?}", data);
+        writeln!(out, "    Retrieved key1: {:?}", data)?;
     }
 
     // Can also create with capacity for better performance.
     // This pre-allocates space for approximately 1_000 entries,
     // reducing the need for reallocations as the map grows.
     let with_capacity: AHashMap<i16, i16> = AHashMap::with_capacity(1_000);
-    println!(
+    writeln!(
+        out,
         "    With capacity: len={}, capacity={}",
         with_capacity.len(),
         with_capacity.capacity()
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates AHashSet usage.
 ///
 /// AHashSet provides the same performance benefits as AHashMap
 /// for set operations like membership testing and deduplication.
-pub fn ahashset_usage() {
-    println!("\n  AHashSet Usage:");
+pub fn ahashset_usage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  AHashSet Usage:")?;
 
     let mut seen: AHashSet<String> = AHashSet::new();
 
@@ -124,12 +130,14 @@ pub fn ahashset_usage() {
 
     for item in items {
         if !seen.insert(item.to_string()) {
-            println!("    Duplicate detected: {}", item);
+            writeln!(out, "    Duplicate detected: {}", item)?;
         }
     }
 
-    println!("    Unique items: {:?}", seen);
-    println!("    Count: {}", seen.len());
+    writeln!(out, "    Unique items: {:?}", seen)?;
+    writeln!(out, "    Count: {}", seen.len())?;
+
+    Ok(())
 }
 
 /// Demonstrates aHash's random seeding behavior.
@@ -137,8 +145,8 @@ pub fn ahashset_usage() {
 /// Like SipHash, aHash uses random seeds to prevent HashDoS attacks.
 /// Unlike FxHash, the same input produces different hashes across
 /// different HashMap instances.
-pub fn random_seeding() {
-    println!("\n  aHash Uses Random Seeds:");
+pub fn random_seeding(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  aHash Uses Random Seeds:")?;
 
     // Each RandomState gets its own random seed.
     // This is the default behavior when you create an AHashMap.
@@ -157,23 +165,31 @@ pub fn random_seeding() {
     value.hash(&mut h2);
     let hash2: u64 = h2.finish();
 
-    println!("    Same value, different RandomState:");
-    println!("      State 1: {}", hash1);
-    println!("      State 2: {}", hash2);
-    println!("      Equal? {} (expected: false)", hash1 == hash2);
-
-    println!();
-    println!("    This randomness prevents attackers from pre-computing");
-    println!("    colliding keys, similar to how SipHash protects you.");
-    println!("    But aHash does it much faster!");
+    writeln!(out, "    Same value, different RandomState:")?;
+    writeln!(out, "      State 1: {}", hash1)?;
+    writeln!(out, "      State 2: {}", hash2)?;
+    writeln!(out, "      Equal? {} (expected: false)", hash1 == hash2)?;
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    This randomness prevents attackers from pre-computing"
+    )?;
+    writeln!(
+        out,
+        "    colliding keys, similar to how SipHash protects you."
+    )?;
+    writeln!(out, "    But aHash does it much faster!")?;
+
+    Ok(())
 }
 
 /// Demonstrates aHash with fixed seeds for reproducible results.
 ///
 /// For testing or when you need deterministic behavior (like
 /// reproducible builds), aHash allows creating hashers with specific seeds.
-pub fn deterministic_ahash() {
-    println!("\n  Deterministic aHash (Fixed Seeds):");
+pub fn deterministic_ahash(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Deterministic aHash (Fixed Seeds):")?;
 
     // Create two RandomStates with the same seeds.
     // The four u64 values are the seed material for the hasher.
@@ -192,10 +208,10 @@ pub fn deterministic_ahash() {
     value.hash(&mut h2);
     let hash2: u64 = h2.finish();
 
-    println!("    With identical seeds:");
-    println!("      Hash 1: {}", hash1);
-    println!("      Hash 2: {}", hash2);
-    println!("      Equal? {}", hash1 == hash2);
+    writeln!(out, "    With identical seeds:")?;
+    writeln!(out, "      Hash 1: {}", hash1)?;
+    writeln!(out, "      Hash 2: {}", hash2)?;
+    writeln!(out, "      Equal? {}", hash1 == hash2)?;
 
     // Different seeds produce different hashes
     let state3: RandomState = RandomState::with_seeds(5, 6, 7, 8);
@@ -203,23 +219,25 @@ pub fn deterministic_ahash() {
     value.hash(&mut h3);
     let hash3: u64 = h3.finish();
 
-    println!("\n    With different seeds:");
-    println!("      Hash 3: {}", hash3);
-    println!("      Equal to hash1? {}", hash1 == hash3);
+    writeln!(out, "\n    With different seeds:")?;
+    writeln!(out, "      Hash 3: {}", hash3)?;
+    writeln!(out, "      Equal to hash1? {}", hash1 == hash3)?;
+
+    writeln!(out)?;
+    writeln!(out, "    Use fixed seeds for:")?;
+    writeln!(out, "    - Unit tests that need deterministic behavior")?;
+    writeln!(out, "    - Reproducible builds")?;
+    writeln!(out, "    - Debugging hash-related issues")?;
 
-    println!();
-    println!("    Use fixed seeds for:");
-    println!("    - Unit tests that need deterministic behavior");
-    println!("    - Reproducible builds");
-    println!("    - Debugging hash-related issues");
+    Ok(())
 }
 
 /// Compares aHash performance to SipHash and FxHash.
 ///
 /// This demonstrates why aHash is a good middle ground: it's much
 /// faster than SipHash while still providing security.
-pub fn performance_comparison() {
-    println!("\n  aHash Performance Comparison:");
+pub fn performance_comparison(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  aHash Performance Comparison:")?;
 
     let iterations: i32 = 500_000;
 
@@ -229,7 +247,7 @@ pub fn performance_comparison() {
     let fxhash_build: BuildHasherDefault<FxHasher> = BuildHasherDefault::<FxHasher>::default();
 
     // === Test integer hashing ===
-    println!("    Integer keys ({} iterations):", iterations);
+    writeln!(out, "    Integer keys ({} iterations):", iterations)?;
 
     // aHash timing
     let start: Instant = Instant::now();
@@ -258,18 +276,19 @@ pub fn performance_comparison() {
     }
     let fxhash_time: Duration = start.elapsed();
 
-    println!("      aHash:   {:?}", ahash_time);
-    println!("      SipHash: {:?}", siphash_time);
-    println!("      FxHash:  {:?}", fxhash_time);
-    println!(
+    writeln!(out, "      aHash:   {:?}", ahash_time)?;
+    writeln!(out, "      SipHash: {:?}", siphash_time)?;
+    writeln!(out, "      FxHash:  {:?}", fxhash_time)?;
+    writeln!(
+        out,
         "      aHash vs SipHash: {:.2}x faster",
         siphash_time.as_nanos() as f64 / ahash_time.as_nanos() as f64
-    );
+    )?;
 
     // === Test string hashing ===
     let test_strings: Vec<String> = (0..1_000).map(|i| format!("string_key_{}", i)).collect();
 
-    println!("\n    String keys ({} iterations):", iterations);
+    writeln!(out, "\n    String keys ({} iterations):", iterations)?;
 
     let start: Instant = Instant::now();
     for _ in 0..iterations / 1_000 {
@@ -291,20 +310,23 @@ pub fn performance_comparison() {
     }
     let siphash_str_time: Duration = start.elapsed();
 
-    println!("      aHash:   {:?}", ahash_str_time);
-    println!("      SipHash: {:?}", siphash_str_time);
-    println!(
+    writeln!(out, "      aHash:   {:?}", ahash_str_time)?;
+    writeln!(out, "      SipHash: {:?}", siphash_str_time)?;
+    writeln!(
+        out,
         "      aHash vs SipHash: {:.2}x faster",
         siphash_str_time.as_nanos() as f64 / ahash_str_time.as_nanos() as f64
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates CPU capability (runtime) vs what aHash can actually use (compile-time).
 ///
 /// aHash uses AES-NI instructions when available for maximum speed.
 /// Understanding this helps you optimize your builds.
-pub fn hardware_detection() {
-    println!("\n  aHash Hardware / Backend Notes:");
+pub fn hardware_detection(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  aHash Hardware / Backend Notes:")?;
 
     // aHash selects its backend at compile time based on target features.
     // This means the decision is made when you compile, not when you run.
@@ -314,44 +336,59 @@ pub fn hardware_detection() {
         not(miri)
     ));
 
-    println!(
+    writeln!(
+        out,
         "    aHash AES backend compiled in: {}",
         ahash_aes_backend_compiled_in
-    );
+    )?;
 
     // On x86/x86_64, we can check if the CPU actually supports AES-NI
     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
     {
         let cpu_has_aes: bool = std::arch::is_x86_feature_detected!("aes");
-        println!("    CPU advertises AES-NI (runtime): {}", cpu_has_aes);
+        writeln!(out, "    CPU advertises AES-NI (runtime): {}", cpu_has_aes)?;
 
         if cpu_has_aes && !ahash_aes_backend_compiled_in {
-            println!();
-            println!("    Note: CPU supports AES-NI, but this binary wasn't compiled");
-            println!("    with +aes, so aHash will use its fallback backend.");
-            println!();
-            println!("    To enable AES acceleration, compile with:");
-            println!("      RUSTFLAGS='-C target-feature=+aes' cargo build --release");
-            println!("    Or add to .cargo/config.toml:");
-            println!("      [build]");
-            println!("      rustflags = [\"-C\", \"target-feature=+aes\"]");
+            writeln!(out)?;
+            writeln!(
+                out,
+                "    Note: CPU supports AES-NI, but this binary wasn't compiled"
+            )?;
+            writeln!(
+                out,
+                "    with +aes, so aHash will use its fallback backend."
+            )?;
+            writeln!(out)?;
+            writeln!(out, "    To enable AES acceleration, compile with:")?;
+            writeln!(
+                out,
+                "      RUSTFLAGS='-C target-feature=+aes' cargo build --release"
+            )?;
+            writeln!(out, "    Or add to .cargo/config.toml:")?;
+            writeln!(out, "      [build]")?;
+            writeln!(out, "      rustflags = [\"-C\", \"target-feature=+aes\"]")?;
         }
     }
 
     #[cfg(target_arch = "aarch64")]
     {
         let cpu_has_aes = std::arch::is_aarch64_feature_detected!("aes");
-        println!("    CPU advertises ARM AES (runtime): {}", cpu_has_aes);
-        println!("    Note: aHash 0.8.x documents acceleration as x86/x86_64-only.");
+        writeln!(out, "    CPU advertises ARM AES (runtime): {}", cpu_has_aes)?;
+        writeln!(
+            out,
+            "    Note: aHash 0.8.x documents acceleration as x86/x86_64-only."
+        )?;
     }
+
+    Ok(())
 }
 
 /// Practical example: High-performance cache with expiration.
 ///
 /// aHash is ideal for caches that need both speed and safety,
 /// like web server response caches.
-pub fn cache_example() {
-    println!("\n  Practical Example: High-Performance Cache");
+pub fn cache_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Practical Example: High-Performance Cache")?;
 
     // A simple time-based cache entry
     struct CacheEntry<V> {
@@ -405,21 +442,37 @@ pub fn cache_example() {
     cache.insert("user:123".to_string(), "Alice".to_string());
     cache.insert("user:456".to_string(), "Bob".to_string());
 
-    println!("    Cache size: {}", cache.len());
-    println!("    Get user:123: {:?}", cache.get(&"user:123".to_string()));
-    println!("    Get user:789: {:?}", cache.get(&"user:789".to_string()));
-
-    println!();
-    println!("    aHash makes this cache fast while protecting against");
-    println!("    attackers who might try to cause cache collisions.");
+    writeln!(out, "    Cache size: {}", cache.len())?;
+    writeln!(
+        out,
+        "    Get user:123: {:?}",
+        cache.get(&"user:123".to_string())
+    )?;
+    writeln!(
+        out,
+        "    Get user:789: {:?}",
+        cache.get(&"user:789".to_string())
+    )?;
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    aHash makes this cache fast while protecting against"
+    )?;
+    writeln!(
+        out,
+        "    attackers who might try to cause cache collisions."
+    )?;
+
+    Ok(())
 }
 
 /// Practical example: Word frequency counting.
 ///
 /// Counting word frequencies is a common task that benefits from
 /// fast hash table operations.
-pub fn counting_example() {
-    println!("\n  Practical Example: Word Frequency Counter");
+pub fn counting_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Practical Example: Word Frequency Counter")?;
 
     let text: &str = "the quick brown fox jumps over the lazy dog the fox is quick";
 
@@ -431,14 +484,17 @@ pub fn counting_example() {
         *counts.entry(word).or_insert(0) += 1;
     }
 
-    // Sort by count for display (most frequent first)
+    // Sort by count for display (most frequent first), breaking ties by
+    // word so the same five are shown on every run
     let mut sorted: Vec<_> = counts.iter().collect();
-    sorted.sort_by(|a, b| b.1.cmp(a.1));
+    sorted.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
 
-    println!("    Word frequencies (top 5):");
+    writeln!(out, "    Word frequencies (top 5):")?;
     for (word, count) in sorted.iter().take(5) {
-        println!("      {}: {}", word, count);
+        writeln!(out, "      {}: {}", word, count)?;
     }
 
-    println!("\n    Total unique words: {}", counts.len());
+    writeln!(out, "\n    Total unique words: {}", counts.len())?;
+
+    Ok(())
 }
@@ -0,0 +1,468 @@
+//! Automatic Hasher Selection - The Scenario's Advice as a Type
+//!
+//! The takeaways at the end of the README boil down to a rule of thumb:
+//!
+//! - integer keys handed out by the program (entity ids, row ids): NoHash
+//! - short fixed-width keys, and any key the program controls: FxHash
+//! - everything else, which is usually text that came from outside: foldhash
+//!
+//! [`AutoHashMap<K, V>`] applies the rule from the key type. [`AutoKey`]
+//! carries the choice as an associated `BuildHasher`, so the map is a plain
+//! `std::collections::HashMap` with that hasher and no runtime dispatch:
+//!
+//! ```text
+//! AutoHashMap<u32, V>              -> HashMap<u32, V, BuildNoHashHasher<u32>>
+//! AutoHashMap<(i32, i32), V>       -> HashMap<(i32, i32), V, FxBuildHasher>
+//! AutoHashMap<Trusted<String>, V>  -> HashMap<Trusted<String>, V, FxBuildHasher>
+//! AutoHashMap<String, V>           -> HashMap<String, V, foldhash::fast::RandomState>
+//! ```
+//!
+//! What this would like to say is "NoHash for any `IsEnabled` key, foldhash
+//! for the rest". That takes specialization, which stable Rust doesn't
+//! have: a blanket `impl<T: IsEnabled>` would overlap with every other
+//! impl. So [`AutoKey`] is implemented type by type, and it is sealed: the
+//! list below is the whole rule, and a key type outside it is a compile
+//! error rather than a silent default. A custom id type picks its hasher
+//! explicitly, as `nohash_examples` does for its `EntityId`.
+//!
+//! A type can't say whether its values are trusted. Fixed-width keys get
+//! FxHash because there is little to gain from attacking them: the program
+//! usually makes them up. Variable-length keys get foldhash, keyed per map
+//! from a per-process seed, unless wrapped in [`Trusted`], which says the
+//! program chose them (identifiers from its own source, asset names,
+//! interned symbols).
+
+use crate::cheatsheet::{HASHERS, HasherFacts};
+use demo_framework::section;
+use foldhash::fast::RandomState as FoldRandomState;
+use nohash_hasher::BuildNoHashHasher;
+use rustc_hash::FxBuildHasher;
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "selection_rules",
+        "Which hasher AutoHashMap picks for each key type, and why",
+        selection_rules,
+    )?;
+
+    section(
+        out,
+        "trusted_keys",
+        "Trusted<K>: opting program-chosen strings into FxHash",
+        trusted_keys,
+    )?;
+
+    section(
+        out,
+        "practical_game_world",
+        "Practical demo: one game world, four key types, no hasher named",
+        practical_game_world,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// The selection
+// ---------------------------------------------------------------------------
+
+/// The hashers [`AutoKey`] chooses between.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HasherChoice {
+    NoHash,
+    FxHash,
+    Foldhash,
+}
+
+impl HasherChoice {
+    /// The name the cheat sheet uses.
+    pub fn name(self) -> &'static str {
+        match self {
+            HasherChoice::NoHash => "NoHash",
+            HasherChoice::FxHash => "FxHash",
+            HasherChoice::Foldhash => "Foldhash",
+        }
+    }
+
+    /// Why keys of this kind get this hasher.
+    pub fn reason(self) -> &'static str {
+        match self {
+            HasherChoice::NoHash => "integer ids are their own hash",
+            HasherChoice::FxHash => "short or program-chosen keys",
+            HasherChoice::Foldhash => "variable-length, maybe from outside",
+        }
+    }
+
+    /// The hasher's row in the cheat sheet.
+    pub fn facts(self) -> &'static HasherFacts {
+        HASHERS
+            .iter()
+            .find(|facts| facts.name == self.name())
+            .expect("every choice has a cheat sheet row")
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A key type with a hasher chosen for it. Sealed: the impls in this
+/// module are the selection rule.
+pub trait AutoKey: Hash + Eq + sealed::Sealed {
+    /// The hasher `AutoHashMap` and `AutoHashSet` use for this key.
+    type BuildHasher: BuildHasher + Default + Clone;
+
+    const CHOICE: HasherChoice;
+}
+
+/// A `HashMap` whose hasher is picked from its key type.
+pub type AutoHashMap<K, V> = HashMap<K, V, <K as AutoKey>::BuildHasher>;
+
+/// A `HashSet` whose hasher is picked from its element type.
+pub type AutoHashSet<K> = HashSet<K, <K as AutoKey>::BuildHasher>;
+
+/// An empty [`AutoHashMap`] with room for `capacity` entries.
+/// `AutoHashMap::default()` is the same without the capacity.
+pub fn auto_map_with_capacity<K: AutoKey, V>(capacity: usize) -> AutoHashMap<K, V> {
+    HashMap::with_capacity_and_hasher(capacity, K::BuildHasher::default())
+}
+
+/// The hasher `K` gets.
+pub fn choice_for<K: AutoKey>() -> HasherChoice {
+    K::CHOICE
+}
+
+/// Fixed-width scalars: the parts a tuple key can be built from.
+pub trait ShortKey: AutoKey + Copy {}
+
+macro_rules! nohash_keys {
+    ($($key:ty),*) => {$(
+        impl sealed::Sealed for $key {}
+        impl AutoKey for $key {
+            type BuildHasher = BuildNoHashHasher<$key>;
+            const CHOICE: HasherChoice = HasherChoice::NoHash;
+        }
+        impl ShortKey for $key {}
+    )*};
+}
+
+// Exactly the integers nohash-hasher enables; u128 and i128 don't fit the
+// u64 it returns
+nohash_keys!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+macro_rules! fx_keys {
+    ($($key:ty),*) => {$(
+        impl sealed::Sealed for $key {}
+        impl AutoKey for $key {
+            type BuildHasher = FxBuildHasher;
+            const CHOICE: HasherChoice = HasherChoice::FxHash;
+        }
+        impl ShortKey for $key {}
+    )*};
+}
+
+fx_keys!(u128, i128, char, bool);
+
+// Coordinates and composite ids: a few words, multiply-rotated per field
+impl<A: ShortKey, B: ShortKey> sealed::Sealed for (A, B) {}
+impl<A: ShortKey, B: ShortKey> AutoKey for (A, B) {
+    type BuildHasher = FxBuildHasher;
+    const CHOICE: HasherChoice = HasherChoice::FxHash;
+}
+
+impl<A: ShortKey, B: ShortKey, C: ShortKey> sealed::Sealed for (A, B, C) {}
+impl<A: ShortKey, B: ShortKey, C: ShortKey> AutoKey for (A, B, C) {
+    type BuildHasher = FxBuildHasher;
+    const CHOICE: HasherChoice = HasherChoice::FxHash;
+}
+
+macro_rules! foldhash_keys {
+    ($($key:ty),*) => {$(
+        impl sealed::Sealed for $key {}
+        impl AutoKey for $key {
+            type BuildHasher = FoldRandomState;
+            const CHOICE: HasherChoice = HasherChoice::Foldhash;
+        }
+    )*};
+}
+
+foldhash_keys!(String, Box<str>, Rc<str>, Arc<str>);
+
+impl sealed::Sealed for &str {}
+impl AutoKey for &str {
+    type BuildHasher = FoldRandomState;
+    const CHOICE: HasherChoice = HasherChoice::Foldhash;
+}
+
+impl<T: Hash + Eq> sealed::Sealed for Vec<T> {}
+impl<T: Hash + Eq> AutoKey for Vec<T> {
+    type BuildHasher = FoldRandomState;
+    const CHOICE: HasherChoice = HasherChoice::Foldhash;
+}
+
+impl<T: Hash + Eq> sealed::Sealed for Box<[T]> {}
+impl<T: Hash + Eq> AutoKey for Box<[T]> {
+    type BuildHasher = FoldRandomState;
+    const CHOICE: HasherChoice = HasherChoice::Foldhash;
+}
+
+impl<T: Hash + Eq> sealed::Sealed for &[T] {}
+impl<T: Hash + Eq> AutoKey for &[T] {
+    type BuildHasher = FoldRandomState;
+    const CHOICE: HasherChoice = HasherChoice::Foldhash;
+}
+
+/// A key the program chose itself, so FxHash is safe whatever its type.
+///
+/// Hashes exactly like the `K` inside, and borrows as it, so a map of
+/// `Trusted<String>` can be searched with a `&String`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Trusted<K>(pub K);
+
+impl<K: Hash + Eq> sealed::Sealed for Trusted<K> {}
+impl<K: Hash + Eq> AutoKey for Trusted<K> {
+    type BuildHasher = FxBuildHasher;
+    const CHOICE: HasherChoice = HasherChoice::FxHash;
+}
+
+impl<K> Borrow<K> for Trusted<K> {
+    fn borrow(&self) -> &K {
+        &self.0
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// One row of the selection table: a key type's name and its choice.
+fn rule<K: AutoKey>(name: &'static str) -> (&'static str, HasherChoice) {
+    (name, choice_for::<K>())
+}
+
+/// Demonstrates the hasher chosen for each supported key type
+pub fn selection_rules(out: &mut dyn Write) -> io::Result<()> {
+    let rules: [(&str, HasherChoice); 12] = [
+        rule::<u32>("u32"),
+        rule::<u64>("u64"),
+        rule::<isize>("isize"),
+        rule::<u128>("u128"),
+        rule::<char>("char"),
+        rule::<(i32, i32)>("(i32, i32)"),
+        rule::<(u64, u16, bool)>("(u64, u16, bool)"),
+        rule::<Trusted<&str>>("Trusted<&str>"),
+        rule::<String>("String"),
+        rule::<&str>("&str"),
+        rule::<Arc<str>>("Arc<str>"),
+        rule::<Vec<u8>>("Vec<u8>"),
+    ];
+
+    writeln!(out, "\n  AutoHashMap<K, V> picks the hasher from K:")?;
+    writeln!(
+        out,
+        "    {:<18} {:<9} {:<37} HashDoS resistant",
+        "K", "hasher", "because"
+    )?;
+    for (key, choice) in rules {
+        writeln!(
+            out,
+            "    {:<18} {:<9} {:<37} {}",
+            key,
+            choice.name(),
+            choice.reason(),
+            choice.facts().dos_resistance
+        )?;
+    }
+
+    // The choice is a type, not a value: the map is a plain HashMap
+    let mut ids: AutoHashMap<u32, &str> = AutoHashMap::default();
+    ids.insert(7, "seven");
+    writeln!(
+        out,
+        "\n    A u32 hashes to itself: hash_one(7) = {}",
+        ids.hasher().hash_one(7u32)
+    )?;
+    writeln!(
+        out,
+        "    Map size is the same as HashMap<u32, &str, BuildNoHashHasher<u32>>: {}",
+        size_of::<AutoHashMap<u32, &str>>()
+            == size_of::<HashMap<u32, &str, BuildNoHashHasher<u32>>>()
+    )?;
+
+    writeln!(
+        out,
+        "\n    u128 and i128 get FxHash: NoHash returns a u64 and doesn't take them."
+    )?;
+    writeln!(
+        out,
+        "    Tuples of fixed-width parts get FxHash; a tuple holding a String is"
+    )?;
+    writeln!(
+        out,
+        "    not an AutoKey, and neither is a custom id type. Those don't compile:"
+    )?;
+    writeln!(
+        out,
+        "    AutoKey is sealed, so a key the rule doesn't cover never falls back to"
+    )?;
+    writeln!(out, "    a default nobody chose.")?;
+
+    Ok(())
+}
+
+/// Demonstrates wrapping program-chosen strings in `Trusted`
+pub fn trusted_keys(out: &mut dyn Write) -> io::Result<()> {
+    // Names the program defines: nobody outside picks them
+    let builtins: [&str; 6] = ["print", "len", "range", "open", "sorted", "zip"];
+    let mut arity: AutoHashMap<Trusted<&str>, u8> = auto_map_with_capacity(builtins.len());
+    for (name, args) in builtins.into_iter().zip([1, 1, 3, 2, 1, 2]) {
+        arity.insert(Trusted(name), args);
+    }
+
+    writeln!(out, "\n  Built-in functions keyed by Trusted<&str>:")?;
+    writeln!(out, "    hasher: {}", choice_for::<Trusted<&str>>().name())?;
+    // Borrow<K> lets the bare &str look the wrapped key up
+    writeln!(out, "    arity.get(&\"range\"): {:?}", arity.get(&"range"))?;
+    writeln!(out, "    arity.get(&\"eval\"): {:?}", arity.get(&"eval"))?;
+
+    // Trusted adds nothing to the hash: same value as FxHash on the str
+    let wrapped: u64 = arity.hasher().hash_one(Trusted("range"));
+    let plain: u64 = FxBuildHasher.hash_one("range");
+    writeln!(
+        out,
+        "    hash of Trusted(\"range\") == FxHash of \"range\": {}",
+        wrapped == plain
+    )?;
+
+    // The same names typed in by a user are just strings
+    let typed: AutoHashSet<String> = ["range", "eval"].into_iter().map(String::from).collect();
+    writeln!(
+        out,
+        "\n  Names typed by a user, as String: hasher {}, {} names",
+        choice_for::<String>().name(),
+        typed.len()
+    )?;
+    writeln!(
+        out,
+        "    foldhash keys each map from a random per-process seed, so a user"
+    )?;
+    writeln!(
+        out,
+        "    who learns one hash can't replay it against another map or the next"
+    )?;
+    writeln!(
+        out,
+        "    run. Wrapping input in Trusted would throw that away: the wrapper is"
+    )?;
+    writeln!(
+        out,
+        "    a claim about where the values come from, and the type checker can't"
+    )?;
+    writeln!(out, "    verify it.")?;
+
+    Ok(())
+}
+
+/// Looks up every key in `keys` `rounds` times, for rough timing.
+fn time_lookups<K: Hash + Eq, S: BuildHasher>(
+    map: &HashMap<K, u32, S>,
+    keys: &[K],
+    rounds: usize,
+) -> (u64, Duration) {
+    let start: Instant = Instant::now();
+    let mut sum: u64 = 0;
+    for _ in 0..rounds {
+        for key in keys {
+            sum += u64::from(*map.get(key).unwrap_or(&0));
+        }
+    }
+    (sum, start.elapsed())
+}
+
+/// Builds the same map with the chosen hasher and with SipHash, times the
+/// lookups in both, and prints one row.
+fn compare_with_siphash<K: AutoKey + Clone>(
+    out: &mut dyn Write,
+    name: &str,
+    keys: &[K],
+) -> io::Result<()> {
+    const ROUNDS: usize = 20;
+
+    let mut auto: AutoHashMap<K, u32> = auto_map_with_capacity(keys.len());
+    let mut sip: HashMap<K, u32, RandomState> = HashMap::with_capacity(keys.len());
+    for (value, key) in keys.iter().enumerate() {
+        auto.insert(key.clone(), value as u32);
+        sip.insert(key.clone(), value as u32);
+    }
+    let (auto_sum, auto_time) = time_lookups(&auto, keys, ROUNDS);
+    let (sip_sum, sip_time) = time_lookups(&sip, keys, ROUNDS);
+
+    writeln!(
+        out,
+        "    {:<30} {:>6} {:<9} {:<5} {:.2?} vs SipHash {:.2?}",
+        name,
+        keys.len(),
+        K::CHOICE.name(),
+        auto_sum == sip_sum,
+        auto_time,
+        sip_time
+    )
+}
+
+/// Practical example: the lookup tables of a small game server.
+///
+/// Four maps, four key types, and none of them names a hasher: entity ids
+/// get NoHash, grid cells and asset names FxHash, and player chat handles
+/// foldhash. Each is timed against the same map on SipHash.
+pub fn practical_game_world(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Practical Example: Game Server Lookup Tables")?;
+
+    let entities: Vec<u32> = (0..50_000).collect();
+    let cells: Vec<(i32, i32)> = (0..50_000)
+        .map(|i: i32| (i % 250 - 125, i / 250 - 100))
+        .collect();
+    let assets: Vec<Trusted<String>> = (0..2_000)
+        .map(|i| Trusted(format!("textures/props/crate_{:04}.png", i)))
+        .collect();
+    let handles: Vec<String> = (0..10_000)
+        .map(|i| format!("player_{}_{}", i * 7_919 % 10_007, i % 13))
+        .collect();
+
+    writeln!(
+        out,
+        "    {:<30} {:>6} {:<9} {:<5} 20 lookups per key: auto vs SipHash",
+        "table", "keys", "hasher", "same"
+    )?;
+    compare_with_siphash(out, "entity id -> component", &entities)?;
+    compare_with_siphash(out, "(x, y) cell -> tile", &cells)?;
+    compare_with_siphash(out, "Trusted asset path -> handle", &assets)?;
+    compare_with_siphash(out, "chat handle -> player", &handles)?;
+
+    writeln!(
+        out,
+        "\n    The chat handles are the only keys a player types, and the only"
+    )?;
+    writeln!(
+        out,
+        "    map left on a seeded hasher. Changing a table's key type changes its"
+    )?;
+    writeln!(
+        out,
+        "    hasher with it: key the entities by a String name and that map moves"
+    )?;
+    writeln!(
+        out,
+        "    to foldhash without anyone touching the declaration."
+    )?;
+
+    Ok(())
+}
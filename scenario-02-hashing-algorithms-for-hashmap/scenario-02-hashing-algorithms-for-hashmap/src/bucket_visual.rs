@@ -0,0 +1,622 @@
+//! Bucket Visualizer - Two Teaching Hash Tables, Drawn After Every Step
+//!
+//! std's HashMap hides its buckets, so this module builds the two classic
+//! designs small enough to print:
+//!
+//! - [`ChainedTable`]: every bucket is a Vec ("chain") of the entries whose
+//!   hash lands there. Collisions make chains longer
+//! - [`OpenAddressingTable`]: one flat array of slots. A colliding entry
+//!   takes the next free slot (linear probing), and a lookup walks from the
+//!   home slot until it finds the key or an empty slot. Removing leaves a
+//!   tombstone so later lookups keep walking past it
+//!
+//! Both pick a bucket from the hash's low bits (`hash & (buckets - 1)`),
+//! like hashbrown does. The renderers show what the hasher did:
+//!
+//! ```text
+//!     chained                           open addressing
+//!        0 │ ██   16, 8                 0 │ 0 1 . x 0 . 2 .
+//!        1 │ █    1                     slot char = probe distance,
+//!      2-3 │ (empty)                    '.' empty, 'x' tombstone
+//! ```
+//!
+//! The HashDoS demo in `security_examples` uses them to show the attack's
+//! single giant bucket.
+
+use demo_framework::section;
+use nohash_hasher::BuildNoHashHasher;
+use std::fmt::Display;
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, Write};
+use xxhash_rust::xxh3::Xxh3Builder;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "chained_table_step_by_step",
+        "A chained table filling up, drawn after every insert",
+        chained_table_step_by_step,
+    )?;
+
+    section(
+        out,
+        "linear_probing_and_tombstones",
+        "Open addressing: probe runs, removals and tombstones",
+        linear_probing_and_tombstones,
+    )?;
+
+    section(
+        out,
+        "growth_and_rehashing",
+        "Growing the table: chains split and tombstones disappear",
+        growth_and_rehashing,
+    )?;
+
+    Ok(())
+}
+
+/// Longest bar drawn for one chain before it is cut off.
+const MAX_BAR: usize = 32;
+
+/// Keys listed per bucket before the rest are summarized.
+const MAX_KEYS: usize = 6;
+
+/// Slots per row of the open-addressing map.
+const SLOTS_PER_ROW: usize = 32;
+
+/// A hash table with separate chaining.
+///
+/// Doubles its bucket count when there are more entries than buckets.
+#[derive(Clone, Debug)]
+pub struct ChainedTable<K, V, S> {
+    buckets: Vec<Vec<(K, V)>>,
+    len: usize,
+    hasher: S,
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> ChainedTable<K, V, S> {
+    /// An empty table with `buckets` buckets, rounded up to a power of two.
+    pub fn with_buckets(buckets: usize, hasher: S) -> Self {
+        let count: usize = buckets.max(1).next_power_of_two();
+        ChainedTable {
+            buckets: (0..count).map(|_| Vec::new()).collect(),
+            len: 0,
+            hasher,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// The bucket `key` belongs in: the hash's low bits.
+    pub fn bucket_of(&self, key: &K) -> usize {
+        (self.hasher.hash_one(key) as usize) & (self.buckets.len() - 1)
+    }
+
+    /// The entries in bucket `index`, in insertion order.
+    pub fn bucket(&self, index: usize) -> &[(K, V)] {
+        &self.buckets[index]
+    }
+
+    /// Number of entries in each bucket.
+    pub fn chain_lengths(&self) -> Vec<usize> {
+        self.buckets.iter().map(Vec::len).collect()
+    }
+
+    pub fn longest_chain(&self) -> usize {
+        self.buckets.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    pub fn load_factor(&self) -> f64 {
+        self.len as f64 / self.buckets.len() as f64
+    }
+
+    /// Inserts or replaces; returns the previous value for `key`.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let index: usize = self.bucket_of(&key);
+        if let Some(entry) = self.buckets[index].iter_mut().find(|(k, _)| *k == key) {
+            return Some(std::mem::replace(&mut entry.1, value));
+        }
+        self.buckets[index].push((key, value));
+        self.len += 1;
+        if self.len > self.buckets.len() {
+            self.resize(self.buckets.len() * 2);
+        }
+        None
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.buckets[self.bucket_of(key)]
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let index: usize = self.bucket_of(key);
+        self.buckets[index]
+            .iter_mut()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    /// How many keys a lookup of `key` compares: its position in the chain
+    /// plus one, or the whole chain if it is missing.
+    pub fn comparisons(&self, key: &K) -> usize {
+        let chain: &[(K, V)] = &self.buckets[self.bucket_of(key)];
+        chain
+            .iter()
+            .position(|(k, _)| k == key)
+            .map_or(chain.len(), |i| i + 1)
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index: usize = self.bucket_of(key);
+        let position: usize = self.buckets[index].iter().position(|(k, _)| k == key)?;
+        self.len -= 1;
+        // Order within a chain doesn't matter
+        Some(self.buckets[index].swap_remove(position).1)
+    }
+
+    /// Rehashes every entry into `buckets` buckets.
+    pub fn resize(&mut self, buckets: usize) {
+        let count: usize = buckets.max(1).next_power_of_two();
+        let old: Vec<Vec<(K, V)>> =
+            std::mem::replace(&mut self.buckets, (0..count).map(|_| Vec::new()).collect());
+        for (key, value) in old.into_iter().flatten() {
+            let index: usize = self.bucket_of(&key);
+            self.buckets[index].push((key, value));
+        }
+    }
+}
+
+impl<K: Hash + Eq + Display, V, S: BuildHasher> ChainedTable<K, V, S> {
+    /// One line per bucket: a bar with one block per entry, then the keys.
+    /// Runs of empty buckets share a line.
+    pub fn render(&self) -> String {
+        let mut lines: Vec<String> = vec![format!(
+            "{} buckets, {} entries, load factor {:.2}, longest chain {}",
+            self.bucket_count(),
+            self.len,
+            self.load_factor(),
+            self.longest_chain()
+        )];
+        let label_width: usize = {
+            let last: usize = self.bucket_count() - 1;
+            // Wide enough for an "a-b" range of empty buckets
+            2 * last.to_string().len() + 1
+        };
+
+        let mut i: usize = 0;
+        while i < self.buckets.len() {
+            if self.buckets[i].is_empty() {
+                let start: usize = i;
+                while i < self.buckets.len() && self.buckets[i].is_empty() {
+                    i += 1;
+                }
+                let label: String = if i - start == 1 {
+                    start.to_string()
+                } else {
+                    format!("{}-{}", start, i - 1)
+                };
+                lines.push(format!("{:>w$} │ (empty)", label, w = label_width));
+                continue;
+            }
+
+            let chain: &[(K, V)] = &self.buckets[i];
+            // Padded to the longest bar so the key lists line up
+            let mut bar: String = format!(
+                "{:<w$}",
+                "█".repeat(chain.len().min(MAX_BAR)),
+                w = self.longest_chain().min(MAX_BAR)
+            );
+            if chain.len() > MAX_BAR {
+                bar.push_str(&format!(" +{}", chain.len() - MAX_BAR));
+            }
+            let mut keys: Vec<String> = chain
+                .iter()
+                .take(MAX_KEYS)
+                .map(|(k, _)| k.to_string())
+                .collect();
+            if chain.len() > MAX_KEYS {
+                keys.push(format!("… ({} keys)", chain.len()));
+            }
+            lines.push(format!(
+                "{:>w$} │ {}  {}",
+                i,
+                bar,
+                keys.join(", "),
+                w = label_width
+            ));
+            i += 1;
+        }
+        lines.join("\n")
+    }
+}
+
+/// One slot of an [`OpenAddressingTable`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Slot<K, V> {
+    Empty,
+    /// A removed entry: lookups walk past it, inserts may reuse it.
+    Tombstone,
+    Full(K, V),
+}
+
+/// A hash table with open addressing and linear probing.
+///
+/// Grows when entries plus tombstones pass 7/8 of the slots. If most of
+/// that is tombstones, it rehashes at the same size instead, which clears
+/// them.
+#[derive(Clone, Debug)]
+pub struct OpenAddressingTable<K, V, S> {
+    slots: Vec<Slot<K, V>>,
+    len: usize,
+    tombstones: usize,
+    hasher: S,
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> OpenAddressingTable<K, V, S> {
+    /// An empty table with `slots` slots, rounded up to a power of two.
+    pub fn with_slots(slots: usize, hasher: S) -> Self {
+        let count: usize = slots.max(1).next_power_of_two();
+        OpenAddressingTable {
+            slots: (0..count).map(|_| Slot::Empty).collect(),
+            len: 0,
+            tombstones: 0,
+            hasher,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn tombstones(&self) -> usize {
+        self.tombstones
+    }
+
+    pub fn slots(&self) -> &[Slot<K, V>] {
+        &self.slots
+    }
+
+    /// The slot `key`'s probe starts from.
+    pub fn home_of(&self, key: &K) -> usize {
+        (self.hasher.hash_one(key) as usize) & self.mask()
+    }
+
+    fn mask(&self) -> usize {
+        self.slots.len() - 1
+    }
+
+    /// How far the entry in slot `index` sits from its home slot, `None` for
+    /// an empty slot or a tombstone.
+    pub fn probe_distance(&self, index: usize) -> Option<usize> {
+        match &self.slots[index] {
+            Slot::Full(key, _) => Some(index.wrapping_sub(self.home_of(key)) & self.mask()),
+            _ => None,
+        }
+    }
+
+    pub fn longest_probe(&self) -> usize {
+        (0..self.slots.len())
+            .filter_map(|i| self.probe_distance(i))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Average number of slots a successful lookup inspects.
+    pub fn average_probe(&self) -> f64 {
+        if self.len == 0 {
+            return 0.0;
+        }
+        let total: usize = (0..self.slots.len())
+            .filter_map(|i| self.probe_distance(i))
+            .map(|distance| distance + 1)
+            .sum();
+        total as f64 / self.len as f64
+    }
+
+    /// The slot holding `key`, walking from its home slot.
+    fn find(&self, key: &K) -> Option<usize> {
+        let mut index: usize = self.home_of(key);
+        for _ in 0..self.slots.len() {
+            match &self.slots[index] {
+                Slot::Empty => return None,
+                Slot::Full(k, _) if k == key => return Some(index),
+                _ => index = (index + 1) & self.mask(),
+            }
+        }
+        None
+    }
+
+    /// How many slots a lookup of `key` inspects before it stops.
+    pub fn probes(&self, key: &K) -> usize {
+        let mut index: usize = self.home_of(key);
+        for probes in 1..=self.slots.len() {
+            match &self.slots[index] {
+                Slot::Empty => return probes,
+                Slot::Full(k, _) if k == key => return probes,
+                _ => index = (index + 1) & self.mask(),
+            }
+        }
+        self.slots.len()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        match &self.slots[self.find(key)?] {
+            Slot::Full(_, value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Inserts or replaces; returns the previous value for `key`.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(index) = self.find(&key)
+            && let Slot::Full(_, old) = &mut self.slots[index]
+        {
+            return Some(std::mem::replace(old, value));
+        }
+        if (self.len + self.tombstones + 1) * 8 > self.slots.len() * 7 {
+            // Mostly tombstones: a same-size rehash makes enough room
+            let grow: bool = (self.len + 1) * 2 > self.slots.len();
+            let slots: usize = if grow {
+                self.slots.len() * 2
+            } else {
+                self.slots.len()
+            };
+            self.resize(slots);
+        }
+
+        // The key is absent, so the first tombstone or empty slot is free
+        let mut index: usize = self.home_of(&key);
+        while let Slot::Full(..) = self.slots[index] {
+            index = (index + 1) & self.mask();
+        }
+        if matches!(self.slots[index], Slot::Tombstone) {
+            self.tombstones -= 1;
+        }
+        self.slots[index] = Slot::Full(key, value);
+        self.len += 1;
+        None
+    }
+
+    /// Removes `key`, leaving a tombstone in its slot.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let index: usize = self.find(key)?;
+        match std::mem::replace(&mut self.slots[index], Slot::Tombstone) {
+            Slot::Full(_, value) => {
+                self.len -= 1;
+                self.tombstones += 1;
+                Some(value)
+            }
+            _ => unreachable!("find only returns full slots"),
+        }
+    }
+
+    /// Reinserts every entry into `slots` slots, dropping the tombstones.
+    pub fn resize(&mut self, slots: usize) {
+        let count: usize = slots.max(1).next_power_of_two();
+        let old: Vec<Slot<K, V>> =
+            std::mem::replace(&mut self.slots, (0..count).map(|_| Slot::Empty).collect());
+        self.tombstones = 0;
+        for slot in old {
+            if let Slot::Full(key, value) = slot {
+                let mut index: usize = self.home_of(&key);
+                while let Slot::Full(..) = self.slots[index] {
+                    index = (index + 1) & self.mask();
+                }
+                self.slots[index] = Slot::Full(key, value);
+            }
+        }
+    }
+}
+
+impl<K: Hash + Eq + Display, V, S: BuildHasher> OpenAddressingTable<K, V, S> {
+    /// A compact map, one character per slot: the entry's probe distance
+    /// (`+` from 10 up), `.` for empty, `x` for a tombstone.
+    pub fn render(&self) -> String {
+        let mut lines: Vec<String> = vec![format!(
+            "{} slots, {} entries, {} tombstones, longest probe {}, average lookup {:.2} slots",
+            self.slot_count(),
+            self.len,
+            self.tombstones,
+            self.longest_probe(),
+            self.average_probe()
+        )];
+        let width: usize = (self.slot_count() - 1).to_string().len();
+        for start in (0..self.slots.len()).step_by(SLOTS_PER_ROW) {
+            let end: usize = (start + SLOTS_PER_ROW).min(self.slots.len());
+            let cells: Vec<String> = (start..end)
+                .map(|i| self.slot_char(i).to_string())
+                .collect();
+            lines.push(format!("{:>w$} │ {}", start, cells.join(" "), w = width));
+        }
+        lines.join("\n")
+    }
+
+    /// One line per slot with its key, home slot and probe distance. For
+    /// small tables.
+    pub fn render_slots(&self) -> String {
+        let width: usize = (self.slot_count() - 1).to_string().len();
+        (0..self.slots.len())
+            .map(|i| {
+                let contents: String = match &self.slots[i] {
+                    Slot::Empty => ".".to_string(),
+                    Slot::Tombstone => "x (tombstone)".to_string(),
+                    Slot::Full(key, _) => {
+                        let home: usize = self.home_of(key);
+                        match self.probe_distance(i) {
+                            Some(0) | None => format!("{:<6} home", key),
+                            Some(distance) => {
+                                format!("{:<6} home {}, probe {}", key, home, distance)
+                            }
+                        }
+                    }
+                };
+                format!("{:>w$} │ {}", i, contents, w = width)
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn slot_char(&self, index: usize) -> char {
+        match (&self.slots[index], self.probe_distance(index)) {
+            (Slot::Empty, _) => '.',
+            (Slot::Tombstone, _) => 'x',
+            (_, Some(distance)) if distance < 10 => {
+                char::from_digit(distance as u32, 10).unwrap_or('+')
+            }
+            _ => '+',
+        }
+    }
+}
+
+/// Indents every line of a rendering to sit under a demo heading.
+pub fn indent(text: &str, spaces: usize) -> String {
+    let pad: String = " ".repeat(spaces);
+    text.lines()
+        .map(|line| format!("{}{}", pad, line))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Demonstrates chains forming as keys collide in their low bits.
+pub fn chained_table_step_by_step(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Chained Table, Step by Step:")?;
+    writeln!(
+        out,
+        "    NoHash uses the key as its hash, so with 8 buckets key k lands in k % 8"
+    )?;
+
+    let mut table: ChainedTable<u64, (), BuildNoHashHasher<u64>> =
+        ChainedTable::with_buckets(8, BuildNoHashHasher::default());
+    for key in [3, 11, 6, 19, 8] {
+        table.insert(key, ());
+        writeln!(
+            out,
+            "\n    insert {} (bucket {}):",
+            key,
+            table.bucket_of(&key)
+        )?;
+        writeln!(out, "{}", indent(&table.render(), 6))?;
+    }
+
+    writeln!(out)?;
+    for key in [19, 4] {
+        writeln!(
+            out,
+            "    get({}) compares {} key(s) in bucket {}",
+            key,
+            table.comparisons(&key),
+            table.bucket_of(&key)
+        )?;
+    }
+    writeln!(
+        out,
+        "    A lookup only ever scans one chain: its cost is that chain's length"
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates probe runs, removal and tombstones in an open-addressing
+/// table.
+pub fn linear_probing_and_tombstones(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Linear Probing and Tombstones:")?;
+
+    let mut table: OpenAddressingTable<u64, (), BuildNoHashHasher<u64>> =
+        OpenAddressingTable::with_slots(8, BuildNoHashHasher::default());
+    for key in [2, 10, 18, 5] {
+        table.insert(key, ());
+    }
+    writeln!(
+        out,
+        "    2, 10 and 18 all have home slot 2: each collision walks one further"
+    )?;
+    writeln!(out, "{}", indent(&table.render_slots(), 6))?;
+
+    table.remove(&10);
+    writeln!(
+        out,
+        "\n    remove 10: its slot becomes a tombstone, not empty"
+    )?;
+    writeln!(out, "{}", indent(&table.render_slots(), 6))?;
+    writeln!(
+        out,
+        "    get(18) inspects {} slots: an empty slot at 3 would have stopped it at a miss",
+        table.probes(&18)
+    )?;
+
+    table.insert(26, ());
+    writeln!(
+        out,
+        "\n    insert 26 (home 2): reuses the tombstone at slot 3"
+    )?;
+    writeln!(out, "{}", indent(&table.render_slots(), 6))?;
+
+    writeln!(out, "\n    The same table as a compact map:")?;
+    writeln!(out, "{}", indent(&table.render(), 6))?;
+
+    Ok(())
+}
+
+/// Demonstrates that growing a table rehashes every entry.
+pub fn growth_and_rehashing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Growth and Rehashing:")?;
+
+    // Seeded xxh3: well spread, and the same on every run
+    let hasher: Xxh3Builder = Xxh3Builder::new().with_seed(7);
+    let mut chained: ChainedTable<u64, (), Xxh3Builder> = ChainedTable::with_buckets(8, hasher);
+    for key in 0..8 {
+        chained.insert(key, ());
+    }
+    writeln!(out, "    8 keys in 8 buckets (seeded xxh3):")?;
+    writeln!(out, "{}", indent(&chained.render(), 6))?;
+    chained.insert(8, ());
+    writeln!(
+        out,
+        "\n    A 9th key passes load factor 1: 16 buckets, every key rehashed"
+    )?;
+    writeln!(out, "{}", indent(&chained.render(), 6))?;
+
+    let mut open: OpenAddressingTable<u64, (), Xxh3Builder> =
+        OpenAddressingTable::with_slots(16, hasher);
+    for key in 0..12 {
+        open.insert(key, ());
+    }
+    for key in 0..6 {
+        open.remove(&key);
+    }
+    writeln!(out, "\n    Open addressing: 12 inserts, then 6 removes:")?;
+    writeln!(out, "{}", indent(&open.render(), 6))?;
+    writeln!(
+        out,
+        "    Inserts reuse tombstones, but lookups still walk past every one they meet."
+    )?;
+    open.resize(16);
+    writeln!(
+        out,
+        "\n    resize(16) reinserts the 6 live entries into fresh slots, tombstones dropped:"
+    )?;
+    writeln!(out, "{}", indent(&open.render(), 6))?;
+
+    Ok(())
+}
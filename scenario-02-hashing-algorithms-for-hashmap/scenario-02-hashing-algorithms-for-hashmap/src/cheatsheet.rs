@@ -0,0 +1,183 @@
+//! Capability facts for every hasher the scenario covers.
+//!
+//! [`HASHERS`] is the one place these facts are written down. The
+//! `cheatsheet` subcommand prints [`tables`] built from it, and the README's
+//! cheat sheet is that same output pasted between markers, checked by
+//! `tests/cheatsheet.rs`.
+
+use demo_framework::cheatsheet::Table;
+use std::fmt;
+
+/// Where a `HashMap` using the hasher gets its key from by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seeding {
+    /// A fresh random key for every map.
+    RandomPerMap,
+    /// One random key per process, varied per map.
+    RandomPerProcess,
+    /// A constant; seeded constructors exist.
+    Fixed,
+    /// Nothing to seed: the key is the hash.
+    None,
+}
+
+impl fmt::Display for Seeding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name: &str = match self {
+            Seeding::RandomPerMap => "random per map",
+            Seeding::RandomPerProcess => "random per process",
+            Seeding::Fixed => "fixed",
+            Seeding::None => "none",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How hard it is for an attacker to choose keys that collide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DosResistance {
+    Resistant,
+    /// Resistant with a random seed, not with a fixed one.
+    WhenKeyed,
+    Minimal,
+    None,
+}
+
+impl fmt::Display for DosResistance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name: &str = match self {
+            DosResistance::Resistant => "yes",
+            DosResistance::WhenKeyed => "when keyed",
+            DosResistance::Minimal => "minimal",
+            DosResistance::None => "no",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// What the scenario teaches about one hasher.
+pub struct HasherFacts {
+    pub name: &'static str,
+    /// `std` or the crate(s) it comes from.
+    pub source: &'static str,
+    pub algorithm: &'static str,
+    pub output_bits: &'static str,
+    pub seeding: Seeding,
+    pub dos_resistance: DosResistance,
+    /// Whether a default-built hasher gives the same hash in every run, so
+    /// hashes can be stored or sent elsewhere.
+    pub stable_across_runs: bool,
+    /// Small-key hashing speed from the Step 9 benchmarks.
+    pub small_keys_vs_siphash: &'static str,
+    pub best_for: &'static str,
+}
+
+pub const HASHERS: &[HasherFacts] = &[
+    HasherFacts {
+        name: "SipHash",
+        source: "std",
+        algorithm: "SipHash 1-3",
+        output_bits: "64",
+        seeding: Seeding::RandomPerMap,
+        dos_resistance: DosResistance::Resistant,
+        stable_across_runs: false,
+        small_keys_vs_siphash: "1× (baseline)",
+        best_for: "Untrusted input, default choice",
+    },
+    HasherFacts {
+        name: "FxHash",
+        source: "rustc-hash",
+        algorithm: "multiply-rotate",
+        output_bits: "64*",
+        seeding: Seeding::Fixed,
+        dos_resistance: DosResistance::None,
+        stable_across_runs: true,
+        small_keys_vs_siphash: "~13× faster",
+        best_for: "Compilers, trusted internal data",
+    },
+    HasherFacts {
+        name: "aHash",
+        source: "ahash",
+        algorithm: "AES rounds or folded multiply**",
+        output_bits: "64",
+        seeding: Seeding::RandomPerMap,
+        dos_resistance: DosResistance::WhenKeyed,
+        stable_across_runs: false,
+        small_keys_vs_siphash: "~11× faster",
+        best_for: "General purpose with speed needs",
+    },
+    HasherFacts {
+        name: "Foldhash",
+        source: "foldhash",
+        algorithm: "folded multiply",
+        output_bits: "64",
+        seeding: Seeding::RandomPerProcess,
+        dos_resistance: DosResistance::Minimal,
+        stable_across_runs: false,
+        small_keys_vs_siphash: "~13× faster",
+        best_for: "Modern general purpose",
+    },
+    HasherFacts {
+        name: "xxHash3",
+        source: "twox-hash, xxhash-rust",
+        algorithm: "XXH3",
+        output_bits: "64 or 128",
+        seeding: Seeding::Fixed,
+        dos_resistance: DosResistance::None,
+        stable_across_runs: true,
+        small_keys_vs_siphash: "~10× faster",
+        best_for: "Large data checksums, files",
+    },
+    HasherFacts {
+        name: "NoHash",
+        source: "nohash-hasher",
+        algorithm: "identity",
+        output_bits: "64",
+        seeding: Seeding::None,
+        dos_resistance: DosResistance::None,
+        stable_across_runs: true,
+        small_keys_vs_siphash: "~27× faster***",
+        best_for: "Integer keys only, ECS, caches",
+    },
+];
+
+/// The hasher table, as the README shows it.
+pub fn tables() -> Vec<Table> {
+    let hashers: Table = Table {
+        title: "Hasher Reference",
+        columns: vec![
+            "Hasher",
+            "From",
+            "Algorithm",
+            "Output bits",
+            "Default seed",
+            "HashDoS resistant",
+            "Same hash every run",
+            "Small keys vs SipHash",
+            "Best for",
+        ],
+        rows: HASHERS
+            .iter()
+            .map(|h| {
+                vec![
+                    h.name.to_string(),
+                    h.source.to_string(),
+                    h.algorithm.to_string(),
+                    h.output_bits.to_string(),
+                    h.seeding.to_string(),
+                    h.dos_resistance.to_string(),
+                    if h.stable_across_runs { "yes" } else { "no" }.to_string(),
+                    h.small_keys_vs_siphash.to_string(),
+                    h.best_for.to_string(),
+                ]
+            })
+            .collect(),
+        notes: vec![
+            "* usize: 32 bits on 32-bit targets",
+            "** AES rounds where AES-NI is available, folded multiply elsewhere",
+            "*** Integer keys only; NoHash can't hash strings",
+        ],
+    };
+
+    vec![hashers]
+}
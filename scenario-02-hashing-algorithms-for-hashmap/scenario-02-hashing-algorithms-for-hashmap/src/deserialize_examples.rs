@@ -0,0 +1,269 @@
+//! Capacity-Aware Deserialization - Loading Huge Maps Without Rehashing
+//!
+//! When serde deserializes a map it asks the format for a size hint and
+//! pre-allocates that many slots. JSON can't give one: the length of an
+//! object is only known once the closing brace has been parsed. So a
+//! 1M-entry JSON object starts in an empty HashMap that doubles its way up,
+//! rehashing every key it already holds at each step (~20 times for 1M).
+//!
+//! Key points:
+//! - `MapAccess::size_hint()` is `None` for self-delimiting formats (JSON,
+//!   YAML, TOML) and `Some(len)` for length-prefixed ones (bincode, CBOR)
+//! - serde's built-in HashMap impl caps even a real hint, so a hostile
+//!   length prefix can't make it allocate gigabytes up front
+//! - When *you* know the size (a count field, a previous run, file size),
+//!   a `DeserializeSeed` can carry it into a custom Visitor that calls
+//!   `with_capacity_and_hasher` - for any hasher, not just SipHash
+
+use demo_framework::section;
+use rustc_hash::FxBuildHasher;
+use serde::Deserialize;
+use serde::de::{DeserializeSeed, Deserializer, MapAccess, Visitor};
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, Write};
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+/// Number of entries in the demo's JSON object.
+const ENTRIES: usize = 1_000_000;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "json_has_no_size_hint",
+        "Why deserializing a JSON map can't pre-allocate on its own",
+        json_has_no_size_hint,
+    )?;
+
+    section(
+        out,
+        "rehashing_without_hint",
+        "Counting the table growths while loading a 1M-entry map",
+        rehashing_without_hint,
+    )?;
+
+    section(
+        out,
+        "presized_deserialization",
+        "A DeserializeSeed that pre-sizes the map from a known length",
+        presized_deserialization,
+    )?;
+
+    Ok(())
+}
+
+/// A map deserialized by [`PresizedMap`], plus what happened while loading it.
+pub struct LoadedMap<K, V, S> {
+    pub map: HashMap<K, V, S>,
+    /// The size hint the format reported, if any.
+    pub format_hint: Option<usize>,
+    /// How many times the table had to grow (and rehash) during loading.
+    pub resizes: usize,
+}
+
+/// Deserializes a map pre-sized for `capacity` entries.
+///
+/// A `DeserializeSeed` is a Deserialize impl that carries state in - here,
+/// a length known from outside the data. A hint from the format itself
+/// takes precedence; a capacity of 0 behaves like the default impl.
+pub struct PresizedMap<K, V, S = RandomState> {
+    capacity: usize,
+    marker: PhantomData<(K, V, S)>,
+}
+
+impl<K, V, S> PresizedMap<K, V, S> {
+    pub fn new(capacity: usize) -> Self {
+        PresizedMap {
+            capacity,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, K, V, S> DeserializeSeed<'de> for PresizedMap<K, V, S>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    type Value = LoadedMap<K, V, S>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, K, V, S> Visitor<'de> for PresizedMap<K, V, S>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: BuildHasher + Default,
+{
+    type Value = LoadedMap<K, V, S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let format_hint: Option<usize> = access.size_hint();
+        let capacity: usize = format_hint.unwrap_or(self.capacity);
+        let mut map: HashMap<K, V, S> = HashMap::with_capacity_and_hasher(capacity, S::default());
+
+        let mut resizes: usize = 0;
+        while let Some((key, value)) = access.next_entry()? {
+            let before: usize = map.capacity();
+            map.insert(key, value);
+            if map.capacity() != before {
+                resizes += 1;
+            }
+        }
+
+        Ok(LoadedMap {
+            map,
+            format_hint,
+            resizes,
+        })
+    }
+}
+
+/// Builds a JSON object with `entries` string keys and integer values.
+fn sample_json(entries: usize) -> String {
+    let mut json: String = String::with_capacity(entries * 24);
+    json.push('{');
+    for i in 0..entries {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push_str(&format!("\"user-{:07}\":{}", i, i * 7));
+    }
+    json.push('}');
+    json
+}
+
+/// Loads `json` through a [`PresizedMap`] seed and times it.
+fn load<S: BuildHasher + Default>(
+    json: &str,
+    capacity: usize,
+) -> serde_json::Result<(LoadedMap<String, u64, S>, Duration)> {
+    let start: Instant = Instant::now();
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let loaded = PresizedMap::<String, u64, S>::new(capacity).deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok((loaded, start.elapsed()))
+}
+
+/// Demonstrates what size hint a JSON map gives serde.
+pub fn json_has_no_size_hint(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  JSON Size Hints:")?;
+
+    let json: String = sample_json(5);
+    writeln!(out, "    Input: {}", json)?;
+
+    let (loaded, _) = load::<RandomState>(&json, 0).map_err(io::Error::other)?;
+    writeln!(out, "    MapAccess::size_hint(): {:?}", loaded.format_hint)?;
+    writeln!(out, "    Entries loaded: {}", loaded.map.len())?;
+    writeln!(out, "    Table growths while loading: {}", loaded.resizes)?;
+
+    writeln!(
+        out,
+        "\n    JSON objects don't carry their length, so the map"
+    )?;
+    writeln!(
+        out,
+        "    starts empty and grows as entries arrive - exactly"
+    )?;
+    writeln!(
+        out,
+        "    like calling insert() on HashMap::new() in a loop."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the cost of loading a large map with no capacity hint.
+///
+/// Each growth allocates a table twice as large and re-inserts every
+/// existing entry, so the last few growths alone move over a million keys.
+pub fn rehashing_without_hint(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Loading {} Entries Without a Hint:", ENTRIES)?;
+
+    let json: String = sample_json(ENTRIES);
+    writeln!(out, "    JSON size: {} MB", json.len() / 1_000_000)?;
+
+    // serde's own HashMap impl - the baseline everyone gets by default
+    let start: Instant = Instant::now();
+    let plain: HashMap<String, u64> = serde_json::from_str(&json).map_err(io::Error::other)?;
+    writeln!(
+        out,
+        "    serde_json::from_str::<HashMap<..>>: {} entries in {:?}",
+        plain.len(),
+        start.elapsed()
+    )?;
+
+    // The same path through our visitor, which counts the growths
+    let (loaded, elapsed) = load::<RandomState>(&json, 0).map_err(io::Error::other)?;
+    writeln!(
+        out,
+        "    PresizedMap::new(0):                 {} entries in {:?}",
+        loaded.map.len(),
+        elapsed
+    )?;
+    writeln!(
+        out,
+        "    Table growths (each one rehashes every entry): {}",
+        loaded.resizes
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates pre-sizing the map from a length known ahead of time.
+///
+/// With the capacity reserved up front, the table never grows: one
+/// allocation, no rehashing. Because the visitor is generic over the
+/// BuildHasher, the same seed pre-sizes maps with any hasher.
+pub fn presized_deserialization(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Pre-sized Deserialization ({} entries):", ENTRIES)?;
+
+    let json: String = sample_json(ENTRIES);
+
+    let (growing, growing_time) = load::<RandomState>(&json, 0).map_err(io::Error::other)?;
+    let (presized, presized_time) =
+        load::<RandomState>(&json, ENTRIES).map_err(io::Error::other)?;
+    let (fx, fx_time) = load::<FxBuildHasher>(&json, ENTRIES).map_err(io::Error::other)?;
+
+    writeln!(
+        out,
+        "    SipHash, no hint:       {} growths, {:?}",
+        growing.resizes, growing_time
+    )?;
+    writeln!(
+        out,
+        "    SipHash, hint {}: {} growths, {:?}",
+        ENTRIES, presized.resizes, presized_time
+    )?;
+    writeln!(
+        out,
+        "    FxHash,  hint {}: {} growths, {:?}",
+        ENTRIES, fx.resizes, fx_time
+    )?;
+
+    let speedup: f64 = growing_time.as_secs_f64() / presized_time.as_secs_f64();
+    writeln!(out, "\n    Pre-sizing speedup (SipHash): {:.2}x", speedup)?;
+    writeln!(
+        out,
+        "    Same contents either way: {}",
+        growing.map == presized.map && presized.map.len() == fx.map.len()
+    )?;
+
+    // Where the hint can come from: a count field written next to the
+    // map, the size of the previous snapshot, or the file size divided by
+    // a typical entry size. Overestimating wastes memory; underestimating
+    // just means a few growths at the end.
+
+    Ok(())
+}
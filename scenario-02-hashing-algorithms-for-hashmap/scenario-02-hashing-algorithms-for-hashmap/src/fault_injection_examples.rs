@@ -0,0 +1,565 @@
+//! Fault Injection - Testing the Paths Where Storage Misbehaves
+//!
+//! Code that only ever talks to a perfect map only has its happy path
+//! tested. [`FaultyStore`] wraps any [`KvStore`] and, on the schedule a
+//! [`FaultPlan`] describes, makes it misbehave the way real storage does:
+//!
+//! - spurious capacity errors: the write is refused before anything
+//!   changes, so retrying it is safe
+//! - slow operations: the operation still happens but takes longer, and
+//!   past the caller's deadline it reports a timeout. A timed-out write
+//!   *was* applied, and the caller can't tell
+//! - corrupted checksums in the write-ahead log the wrapper keeps, which
+//!   recovery has to detect and stop at
+//!
+//! Time is simulated: a slow operation adds to [`FaultyStore::elapsed`]
+//! instead of sleeping, so a test with thousands of faults stays fast.
+//! Faults are scheduled by operation count, not drawn at random, so the
+//! same plan fails the same calls on every run and a failing test replays
+//! exactly.
+
+use crate::kv_store_examples::KvStore;
+use demo_framework::section;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash};
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Simulated time for an operation that isn't slowed down.
+pub const OP_COST: Duration = Duration::from_micros(50);
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "retrying_refused_writes",
+        "Retrying spurious capacity errors, and giving up",
+        retrying_refused_writes,
+    )?;
+
+    section(
+        out,
+        "timeouts_and_idempotence",
+        "A timed-out write may have landed: retrying increments vs sets",
+        timeouts_and_idempotence,
+    )?;
+
+    section(
+        out,
+        "log_corruption_recovery",
+        "Replaying a write-ahead log up to the first bad checksum",
+        log_corruption_recovery,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// The plan and the errors
+// ---------------------------------------------------------------------------
+
+/// Which operations a [`FaultyStore`] breaks. Every schedule counts from
+/// 1, so `every(3)` hits the 3rd, 6th, 9th... matching operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaultPlan {
+    refuse_writes_every: Option<u64>,
+    slow_ops_every: Option<u64>,
+    slow_by: Duration,
+    deadline: Duration,
+    corrupt_log_every: Option<u64>,
+}
+
+impl FaultPlan {
+    /// No faults and a 10 ms deadline: behaves like the wrapped store.
+    pub fn none() -> Self {
+        FaultPlan {
+            refuse_writes_every: None,
+            slow_ops_every: None,
+            slow_by: Duration::ZERO,
+            deadline: Duration::from_millis(10),
+            corrupt_log_every: None,
+        }
+    }
+
+    /// Refuses every `n`th insert or remove with [`StoreError::CapacityExceeded`].
+    pub fn refuse_writes_every(mut self, n: u64) -> Self {
+        self.refuse_writes_every = Some(n);
+        self
+    }
+
+    /// Adds `delay` to every `n`th operation, reads included.
+    pub fn slow_ops_every(mut self, n: u64, delay: Duration) -> Self {
+        self.slow_ops_every = Some(n);
+        self.slow_by = delay;
+        self
+    }
+
+    /// Operations slower than `deadline` report [`StoreError::TimedOut`].
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Writes a wrong checksum into every `n`th log record.
+    pub fn corrupt_log_every(mut self, n: u64) -> Self {
+        self.corrupt_log_every = Some(n);
+        self
+    }
+}
+
+impl Default for FaultPlan {
+    fn default() -> Self {
+        FaultPlan::none()
+    }
+}
+
+fn hits(schedule: Option<u64>, count: u64) -> bool {
+    schedule.is_some_and(|every| count.is_multiple_of(every))
+}
+
+/// A failed [`FaultyStore`] operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreError {
+    /// Refused before anything changed; retrying is safe.
+    CapacityExceeded,
+    /// Slower than the deadline. A write has been applied anyway.
+    TimedOut,
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::CapacityExceeded => write!(f, "store is full, nothing was written"),
+            StoreError::TimedOut => write!(f, "no reply before the deadline"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+/// The faults a [`FaultyStore`] has injected so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultStats {
+    /// Writes refused with `CapacityExceeded`.
+    pub refused: u64,
+    /// Operations slowed down, whether or not they missed the deadline.
+    pub slowed: u64,
+    /// Operations that reported `TimedOut`.
+    pub timed_out: u64,
+    /// Log records written with a wrong checksum.
+    pub corrupted: u64,
+}
+
+// ---------------------------------------------------------------------------
+// The write-ahead log
+// ---------------------------------------------------------------------------
+
+/// One logged write: `Some(value)` for an insert, `None` for a remove.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord<K, V> {
+    pub key: K,
+    pub value: Option<V>,
+    pub checksum: u64,
+}
+
+impl<K: Hash, V: Hash> LogRecord<K, V> {
+    fn new(key: K, value: Option<V>) -> Self {
+        let checksum: u64 = checksum(&key, &value);
+        LogRecord {
+            key,
+            value,
+            checksum,
+        }
+    }
+
+    pub fn is_intact(&self) -> bool {
+        self.checksum == checksum(&self.key, &self.value)
+    }
+}
+
+/// A fixed-key hash of the record's contents, the same on every run.
+fn checksum<K: Hash, V: Hash>(key: &K, value: &Option<V>) -> u64 {
+    BuildHasherDefault::<DefaultHasher>::default().hash_one((key, value))
+}
+
+/// A store rebuilt from a log.
+#[derive(Debug)]
+pub struct Recovery<S> {
+    pub store: S,
+    /// Records replayed into `store`.
+    pub applied: usize,
+    /// Index of the first record with a bad checksum, if any. Nothing from
+    /// there on is trusted: a real log can't tell where the damage ends.
+    pub corrupt_at: Option<usize>,
+}
+
+/// Replays `log` into `store`, stopping at the first corrupt record.
+pub fn recover<K: Hash + Clone, V: Hash + Clone, S: KvStore<K, V>>(
+    log: &[LogRecord<K, V>],
+    mut store: S,
+) -> Recovery<S> {
+    for (index, record) in log.iter().enumerate() {
+        if !record.is_intact() {
+            return Recovery {
+                store,
+                applied: index,
+                corrupt_at: Some(index),
+            };
+        }
+        match &record.value {
+            Some(value) => store.insert(record.key.clone(), value.clone()),
+            None => store.remove(&record.key),
+        };
+    }
+    Recovery {
+        store,
+        applied: log.len(),
+        corrupt_at: None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// The wrapper
+// ---------------------------------------------------------------------------
+
+/// A [`KvStore`] that fails on purpose, following a [`FaultPlan`].
+///
+/// The fallible `try_*` methods are the API under test. `inner()` is the
+/// ground truth: what actually got written, timeouts included.
+#[derive(Debug, Clone)]
+pub struct FaultyStore<K, V, S> {
+    inner: S,
+    plan: FaultPlan,
+    ops: u64,
+    writes: u64,
+    log: Vec<LogRecord<K, V>>,
+    elapsed: Duration,
+    stats: FaultStats,
+}
+
+impl<K: Hash + Clone, V: Hash + Clone, S: KvStore<K, V>> FaultyStore<K, V, S> {
+    pub fn new(inner: S, plan: FaultPlan) -> Self {
+        FaultyStore {
+            inner,
+            plan,
+            ops: 0,
+            writes: 0,
+            log: Vec::new(),
+            elapsed: Duration::ZERO,
+            stats: FaultStats::default(),
+        }
+    }
+
+    /// Counts one operation and its simulated time; true if it ran past
+    /// the deadline.
+    fn tick(&mut self) -> bool {
+        self.ops += 1;
+        let mut latency: Duration = OP_COST;
+        if hits(self.plan.slow_ops_every, self.ops) {
+            self.stats.slowed += 1;
+            latency += self.plan.slow_by;
+        }
+        self.elapsed += latency;
+        let late: bool = latency > self.plan.deadline;
+        if late {
+            self.stats.timed_out += 1;
+        }
+        late
+    }
+
+    /// Counts one write; true if the plan refuses it.
+    fn refuse_write(&mut self) -> bool {
+        self.writes += 1;
+        let refused: bool = hits(self.plan.refuse_writes_every, self.writes);
+        if refused {
+            self.stats.refused += 1;
+        }
+        refused
+    }
+
+    fn append_log(&mut self, key: K, value: Option<V>) {
+        let mut record: LogRecord<K, V> = LogRecord::new(key, value);
+        if hits(self.plan.corrupt_log_every, self.log.len() as u64 + 1) {
+            record.checksum ^= 1;
+            self.stats.corrupted += 1;
+        }
+        self.log.push(record);
+    }
+
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, StoreError> {
+        if self.refuse_write() {
+            self.elapsed += OP_COST;
+            return Err(StoreError::CapacityExceeded);
+        }
+        let late: bool = self.tick();
+        self.append_log(key.clone(), Some(value.clone()));
+        let previous: Option<V> = self.inner.insert(key, value);
+        if late {
+            return Err(StoreError::TimedOut);
+        }
+        Ok(previous)
+    }
+
+    pub fn try_remove(&mut self, key: &K) -> Result<Option<V>, StoreError> {
+        if self.refuse_write() {
+            self.elapsed += OP_COST;
+            return Err(StoreError::CapacityExceeded);
+        }
+        let late: bool = self.tick();
+        self.append_log(key.clone(), None);
+        let removed: Option<V> = self.inner.remove(key);
+        if late {
+            return Err(StoreError::TimedOut);
+        }
+        Ok(removed)
+    }
+
+    /// Reads are never refused, but can be slow.
+    pub fn try_get(&mut self, key: &K) -> Result<Option<&V>, StoreError> {
+        if self.tick() {
+            return Err(StoreError::TimedOut);
+        }
+        Ok(self.inner.get(key))
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Every applied write, in order - the write-ahead log.
+    pub fn log(&self) -> &[LogRecord<K, V>] {
+        &self.log
+    }
+
+    /// Simulated time spent in operations so far.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn stats(&self) -> FaultStats {
+        self.stats
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Degradation paths
+// ---------------------------------------------------------------------------
+
+/// Inserts with up to `max_attempts` tries, retrying only refusals; a
+/// timeout is returned as-is, since the write may already be in. Returns
+/// the number of attempts made.
+pub fn insert_with_retry<K, V, S>(
+    store: &mut FaultyStore<K, V, S>,
+    key: K,
+    value: V,
+    max_attempts: u32,
+) -> Result<u32, StoreError>
+where
+    K: Hash + Clone,
+    V: Hash + Clone,
+    S: KvStore<K, V>,
+{
+    let mut attempts: u32 = 0;
+    loop {
+        attempts += 1;
+        match store.try_insert(key.clone(), value.clone()) {
+            Ok(_) => return Ok(attempts),
+            Err(StoreError::CapacityExceeded) if attempts < max_attempts => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Adds one to the counter at `key`, retrying every error. Not idempotent:
+/// a retry after a timed-out write adds one again.
+pub fn increment_with_retry<S: KvStore<u64, u64>>(
+    store: &mut FaultyStore<u64, u64, S>,
+    key: u64,
+) -> u32 {
+    let mut attempts: u32 = 0;
+    loop {
+        attempts += 1;
+        let Ok(current) = store.try_get(&key).map(|count| count.copied().unwrap_or(0)) else {
+            continue;
+        };
+        if store.try_insert(key, current + 1).is_ok() {
+            return attempts;
+        }
+    }
+}
+
+/// Sets the counter at `key` to `value`, retrying every error. Idempotent:
+/// writing the same value twice leaves the same state.
+pub fn set_with_retry<S: KvStore<u64, u64>>(
+    store: &mut FaultyStore<u64, u64, S>,
+    key: u64,
+    value: u64,
+) -> u32 {
+    let mut attempts: u32 = 0;
+    loop {
+        attempts += 1;
+        if store.try_insert(key, value).is_ok() {
+            return attempts;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// Demonstrates a retry loop against spurious capacity errors
+pub fn retrying_refused_writes(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "\n  Every 3rd write refused, 20 inserts, up to 3 attempts each:"
+    )?;
+    let plan: FaultPlan = FaultPlan::none().refuse_writes_every(3);
+    let mut store: FaultyStore<u64, u64, BTreeMap<u64, u64>> =
+        FaultyStore::new(BTreeMap::new(), plan);
+    let mut retried: usize = 0;
+    for key in 0..20 {
+        let attempts: u32 =
+            insert_with_retry(&mut store, key, key * 10, 3).expect("3 attempts outlast every 3rd");
+        if attempts > 1 {
+            retried += 1;
+        }
+    }
+    writeln!(
+        out,
+        "    stored {} of 20, {} inserts needed a retry, {} refusals",
+        store.inner().len(),
+        retried,
+        store.stats().refused
+    )?;
+    writeln!(out, "    simulated time: {:?}", store.elapsed())?;
+
+    writeln!(out, "\n  Every 2nd write refused, no retries:")?;
+    let plan: FaultPlan = FaultPlan::none().refuse_writes_every(2);
+    let mut store: FaultyStore<u64, u64, BTreeMap<u64, u64>> =
+        FaultyStore::new(BTreeMap::new(), plan);
+    let mut lost: Vec<u64> = Vec::new();
+    for key in 0..10 {
+        if let Err(error) = insert_with_retry(&mut store, key, key * 10, 1) {
+            assert_eq!(error, StoreError::CapacityExceeded);
+            lost.push(key);
+        }
+    }
+    writeln!(
+        out,
+        "    stored {} of 10; failed keys {:?} (\"{}\")",
+        store.inner().len(),
+        lost,
+        StoreError::CapacityExceeded
+    )?;
+    writeln!(
+        out,
+        "    A refusal changes nothing, so the caller knows exactly what is missing."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates why retrying a timed-out write needs an idempotent write
+pub fn timeouts_and_idempotence(out: &mut dyn Write) -> io::Result<()> {
+    // Every 6th operation takes 25 ms against a 10 ms deadline. An increment
+    // is a read and a write, so the late ones are always writes
+    let plan: FaultPlan = FaultPlan::none()
+        .slow_ops_every(6, Duration::from_millis(25))
+        .deadline(Duration::from_millis(10));
+    writeln!(
+        out,
+        "\n  Every 6th operation 25 ms late (deadline 10 ms), 100 events for key 7:"
+    )?;
+
+    let mut counter: FaultyStore<u64, u64, BTreeMap<u64, u64>> =
+        FaultyStore::new(BTreeMap::new(), plan);
+    for _ in 0..100 {
+        increment_with_retry(&mut counter, 7);
+    }
+    let counted: u64 = counter.inner().get(&7).copied().unwrap_or(0);
+
+    let mut absolute: FaultyStore<u64, u64, BTreeMap<u64, u64>> =
+        FaultyStore::new(BTreeMap::new(), plan);
+    for event in 1..=100 {
+        set_with_retry(&mut absolute, 7, event);
+    }
+    let set: u64 = absolute.inner().get(&7).copied().unwrap_or(0);
+
+    writeln!(
+        out,
+        "    get + insert(count + 1), retried:  count = {} ({} timeouts)",
+        counted,
+        counter.stats().timed_out
+    )?;
+    writeln!(
+        out,
+        "    insert(event number), retried:     count = {} ({} timeouts)",
+        set,
+        absolute.stats().timed_out
+    )?;
+    writeln!(
+        out,
+        "\n    A timeout doesn't mean the write failed. Retrying \"add one\" after a"
+    )?;
+    writeln!(
+        out,
+        "    timed-out write that landed counts the event twice; retrying \"set to N\""
+    )?;
+    writeln!(out, "    writes the same value again and is harmless.")?;
+
+    Ok(())
+}
+
+/// Demonstrates detecting corrupt log records during recovery
+pub fn log_corruption_recovery(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "\n  30 writes (inserts and removes), replayed into a fresh map:"
+    )?;
+    for (label, plan) in [
+        ("intact log", FaultPlan::none()),
+        (
+            "every 12th record corrupt",
+            FaultPlan::none().corrupt_log_every(12),
+        ),
+    ] {
+        let mut store: FaultyStore<u64, u64, BTreeMap<u64, u64>> =
+            FaultyStore::new(BTreeMap::new(), plan);
+        for i in 0..30u64 {
+            let result: Result<(), StoreError> = if i % 4 == 3 {
+                store.try_remove(&(i - 2)).map(|_| ())
+            } else {
+                store.try_insert(i, i * i).map(|_| ())
+            };
+            result.expect("this plan only damages the log");
+        }
+
+        let recovery: Recovery<BTreeMap<u64, u64>> = recover(store.log(), BTreeMap::new());
+        writeln!(out, "    {}:", label)?;
+        writeln!(
+            out,
+            "      replayed {} of {} records, first bad record: {:?}",
+            recovery.applied,
+            store.log().len(),
+            recovery.corrupt_at
+        )?;
+        writeln!(
+            out,
+            "      recovered {} keys, live store has {}; identical: {}",
+            recovery.store.len(),
+            store.inner().len(),
+            &recovery.store == store.inner()
+        )?;
+    }
+    writeln!(
+        out,
+        "\n    Replay stops at the first bad checksum rather than skipping it: a later"
+    )?;
+    writeln!(
+        out,
+        "    record may depend on the lost one (a remove of a key it inserted)."
+    )?;
+
+    Ok(())
+}
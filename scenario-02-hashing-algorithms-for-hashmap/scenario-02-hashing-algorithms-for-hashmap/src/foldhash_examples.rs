@@ -14,6 +14,7 @@
 //! Foldhash aims to be a "no compromises" hasher for general use.
 
 use ahash::{AHasher, RandomState as AHashRandomState};
+use demo_framework::section;
 use foldhash::fast::{FoldHasher, RandomState as FoldRandomState};
 use foldhash::{
     HashMap as FoldHashMap, HashMapExt, HashSet as FoldHashSet, HashSetExt, SharedSeed, fast,
@@ -22,67 +23,68 @@ use foldhash::{
 use rustc_hash::FxHasher;
 use std::collections::hash_map::RandomState as StdRandomState;
 use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash, Hasher};
+use std::io::{self, Write};
 use std::time::{Duration, Instant};
 
-fn section(name: &str, what: &str, f: impl FnOnce()) {
-    println!("\n{:=<80}", "");
-    println!("DEMO: {name}");
-    println!("  {what}");
-    println!("{:=<80}", "");
-
-    f();
-}
-
-pub fn run_all() {
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
     section(
+        out,
         "basic_foldhashmap_usage",
         "Basic FoldHashMap API usage (modern, high-quality hash map)",
         basic_foldhashmap_usage,
-    );
+    )?;
 
     section(
+        out,
         "foldhashset_usage",
         "FoldHashSet usage and set operations (membership, dedup, intersection)",
         foldhashset_usage,
-    );
+    )?;
 
     section(
+        out,
         "hash_quality_demonstration",
         "Inspect distribution on sequential inputs (looking for patterns)",
         hash_quality_demonstration,
-    );
+    )?;
 
     section(
+        out,
         "performance_comparison",
         "Rough timing: Foldhash vs aHash vs SipHash vs FxHash (not a benchmark)",
         performance_comparison,
-    );
+    )?;
 
     section(
+        out,
         "variants_demonstration",
         "Fast vs Quality variants and when to use each",
         variants_demonstration,
-    );
+    )?;
 
     section(
+        out,
         "deduplication_example",
         "Practical demo: fast deduplication with FoldHashSet",
         deduplication_example,
-    );
+    )?;
 
     section(
+        out,
         "group_by_example",
         "Practical demo: group-by aggregation with FoldHashMap + entry()",
         group_by_example,
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates basic FoldHashMap usage.
 ///
 /// FoldHashMap provides a modern, high-quality hash map implementation
 /// that's both fast and has excellent distribution properties.
-pub fn basic_foldhashmap_usage() {
-    println!("\n  Basic FoldHashMap Usage:");
+pub fn basic_foldhashmap_usage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Basic FoldHashMap Usage:")?;
 
     // Create a new FoldHashMap using the HashMapExt trait
     let mut map: FoldHashMap<String, i8> = FoldHashMap::new();
@@ -91,24 +93,26 @@ pub fn basic_foldhashmap_usage() {
     map.insert("two".to_string(), 2);
     map.insert("three".to_string(), 3);
 
-    println!("    FoldHashMap: {:?}", map);
+    writeln!(out, "    FoldHashMap: {:?}", map)?;
 
     // Standard HashMap operations work identically
     if let Some(value) = map.get("two") {
-        println!("    Get 'two': {}", value);
+        writeln!(out, "    Get 'two': {}", value)?;
     }
 
     // With capacity - useful when you know the approximate size
     let with_cap: FoldHashMap<i8, i8> = FoldHashMap::with_capacity(1_000);
-    println!("    With capacity: {}", with_cap.capacity());
+    writeln!(out, "    With capacity: {}", with_cap.capacity())?;
+
+    Ok(())
 }
 
 /// Demonstrates FoldHashSet usage.
 ///
 /// FoldHashSet provides the same benefits as FoldHashMap for
 /// set operations like membership testing and deduplication.
-pub fn foldhashset_usage() {
-    println!("\n  FoldHashSet Usage:");
+pub fn foldhashset_usage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  FoldHashSet Usage:")?;
 
     let mut set: FoldHashSet<String> = FoldHashSet::new();
 
@@ -117,9 +121,9 @@ pub fn foldhashset_usage() {
     set.insert("cherry".to_string());
     set.insert("apple".to_string()); // Duplicate, will be ignored
 
-    println!("    FoldHashSet: {:?}", set);
-    println!("    Contains 'apple': {}", set.contains("apple"));
-    println!("    Contains 'grape': {}", set.contains("grape"));
+    writeln!(out, "    FoldHashSet: {:?}", set)?;
+    writeln!(out, "    Contains 'apple': {}", set.contains("apple"))?;
+    writeln!(out, "    Contains 'grape': {}", set.contains("grape"))?;
 
     // Set operations work as expected
     let mut other: FoldHashSet<String> = FoldHashSet::new();
@@ -127,7 +131,9 @@ pub fn foldhashset_usage() {
     other.insert("date".to_string());
 
     let intersection: FoldHashSet<_> = set.intersection(&other).cloned().collect();
-    println!("    Intersection: {:?}", intersection);
+    writeln!(out, "    Intersection: {:?}", intersection)?;
+
+    Ok(())
 }
 
 /// Demonstrates hash quality by examining distribution.
@@ -135,15 +141,15 @@ pub fn foldhashset_usage() {
 /// Good hash functions should produce random-looking outputs even for
 /// sequential or patterned inputs. This is crucial for hash table
 /// performance because it minimizes collisions.
-pub fn hash_quality_demonstration() {
-    println!("\n  Foldhash Quality Demonstration:");
+pub fn hash_quality_demonstration(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Foldhash Quality Demonstration:")?;
 
     let state: FoldRandomState = FoldRandomState::default();
 
     // Hash sequential integers and examine the outputs.
     // A poor hash function might show patterns here (like all outputs
     // differing by a constant). A good one looks random.
-    println!("    Sequential integer hashes (looking for patterns):");
+    writeln!(out, "    Sequential integer hashes (looking for patterns):")?;
     let mut hashes: Vec<u64> = Vec::new();
     for i in 0..10 {
         let mut hasher: FoldHasher = state.build_hasher();
@@ -151,7 +157,7 @@ pub fn hash_quality_demonstration() {
         let hash: u64 = hasher.finish();
         hashes.push(hash);
         // Display in hex to see bit patterns more clearly
-        println!("      hash({}) = {:016x}", i, hash);
+        writeln!(out, "      hash({}) = {:016x}", i, hash)?;
     }
 
     // Check for obvious patterns (good hashers should show none).
@@ -166,24 +172,33 @@ pub fn hash_quality_demonstration() {
         }
     }
 
-    println!("\n    Pattern analysis:");
-    println!(
+    writeln!(out, "\n    Pattern analysis:")?;
+    writeln!(
+        out,
         "      Sequential differences constant: {}",
         sequential_diffs
-    );
-    println!("      (Good hashers should show 'false' - random-looking output)");
+    )?;
+    writeln!(
+        out,
+        "      (Good hashers should show 'false' - random-looking output)"
+    )?;
 
     if !sequential_diffs {
-        println!("      Foldhash produces well-distributed, random-looking hashes");
+        writeln!(
+            out,
+            "      Foldhash produces well-distributed, random-looking hashes"
+        )?;
     }
+
+    Ok(())
 }
 
 /// Compares Foldhash performance to other hashers.
 ///
 /// This benchmark helps you understand where Foldhash fits in the
 /// performance spectrum relative to other popular hashers.
-pub fn performance_comparison() {
-    println!("\n  Foldhash Performance Comparison:");
+pub fn performance_comparison(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Foldhash Performance Comparison:")?;
 
     let iterations: i32 = 500_000;
 
@@ -194,7 +209,7 @@ pub fn performance_comparison() {
     let fxhash_build: BuildHasherDefault<FxHasher> = BuildHasherDefault::<FxHasher>::default();
 
     // === Test integer hashing ===
-    println!("    Integer keys ({} iterations):", iterations);
+    writeln!(out, "    Integer keys ({} iterations):", iterations)?;
 
     let start: Instant = Instant::now();
     for i in 0..iterations {
@@ -228,17 +243,17 @@ pub fn performance_comparison() {
     }
     let fxhash_time: Duration = start.elapsed();
 
-    println!("      Foldhash: {:?}", fold_time);
-    println!("      aHash:    {:?}", ahash_time);
-    println!("      SipHash:  {:?}", siphash_time);
-    println!("      FxHash:   {:?}", fxhash_time);
+    writeln!(out, "      Foldhash: {:?}", fold_time)?;
+    writeln!(out, "      aHash:    {:?}", ahash_time)?;
+    writeln!(out, "      SipHash:  {:?}", siphash_time)?;
+    writeln!(out, "      FxHash:   {:?}", fxhash_time)?;
 
     // === String hashing ===
     let test_strings: Vec<String> = (0..1_000)
         .map(|i| format!("test_string_key_{}", i))
         .collect();
 
-    println!("\n    String keys ({} iterations):", iterations);
+    writeln!(out, "\n    String keys ({} iterations):", iterations)?;
 
     let start: Instant = Instant::now();
     for _ in 0..iterations / 1_000 {
@@ -260,12 +275,15 @@ pub fn performance_comparison() {
     }
     let siphash_str_time: Duration = start.elapsed();
 
-    println!("      Foldhash: {:?}", fold_str_time);
-    println!("      SipHash:  {:?}", siphash_str_time);
-    println!(
+    writeln!(out, "      Foldhash: {:?}", fold_str_time)?;
+    writeln!(out, "      SipHash:  {:?}", siphash_str_time)?;
+    writeln!(
+        out,
         "      Speedup:  {:.2}x faster than SipHash",
         siphash_str_time.as_nanos() as f64 / fold_str_time.as_nanos() as f64
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates the "fast" vs "quality" variants.
@@ -273,8 +291,8 @@ pub fn performance_comparison() {
 /// Foldhash provides two variants optimized for different use cases:
 /// - fast: optimized for hash table use (default)
 /// - quality: better statistical properties for sketches, bloom filters
-pub fn variants_demonstration() {
-    println!("\n  Fast vs Quality Variants:");
+pub fn variants_demonstration(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Fast vs Quality Variants:")?;
 
     // Both variants are available through different modules.
     // We use a shared seed to make the comparison fair.
@@ -291,25 +309,36 @@ pub fn variants_demonstration() {
     let fast_hash = fast_state.hash_one(value);
     let quality_hash = quality_state.hash_one(value);
 
-    println!("    Same value, different variants:");
-    println!("      Fast:    {:016x}", fast_hash);
-    println!("      Quality: {:016x}", quality_hash);
-
-    println!();
-    println!("    When to use each variant:");
-    println!("      fast:    HashMap, HashSet, general hash tables (default)");
-    println!("      quality: Bloom filters, count-min sketches, HyperLogLog");
-    println!();
-    println!("    The 'quality' variant has better avalanche properties,");
-    println!("    which matters for probabilistic data structures.");
+    writeln!(out, "    Same value, different variants:")?;
+    writeln!(out, "      Fast:    {:016x}", fast_hash)?;
+    writeln!(out, "      Quality: {:016x}", quality_hash)?;
+
+    writeln!(out)?;
+    writeln!(out, "    When to use each variant:")?;
+    writeln!(
+        out,
+        "      fast:    HashMap, HashSet, general hash tables (default)"
+    )?;
+    writeln!(
+        out,
+        "      quality: Bloom filters, count-min sketches, HyperLogLog"
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    The 'quality' variant has better avalanche properties,"
+    )?;
+    writeln!(out, "    which matters for probabilistic data structures.")?;
+
+    Ok(())
 }
 
 /// Practical example: Fast deduplication.
 ///
 /// Deduplication is a common operation that benefits greatly from
 /// fast hash table performance. Foldhash makes this very efficient.
-pub fn deduplication_example() {
-    println!("\n  Practical Example: Fast Deduplication");
+pub fn deduplication_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Practical Example: Fast Deduplication")?;
 
     // Simulate a dataset with many duplicates.
     // This is common when processing logs, events, or user actions.
@@ -325,23 +354,29 @@ pub fn deduplication_example() {
 
     let elapsed: Duration = start.elapsed();
 
-    println!(
+    writeln!(
+        out,
         "    Deduplicated 10,000 items (with 10x duplicates) in {:?}",
         elapsed
-    );
-    println!("    Unique count: {}", unique.len());
+    )?;
+    writeln!(out, "    Unique count: {}", unique.len())?;
+
+    // Show a sample of the results: the five smallest, since the set's
+    // own order changes from run to run
+    let mut sample: Vec<_> = unique.iter().collect();
+    sample.sort();
+    sample.truncate(5);
+    writeln!(out, "    Sample: {:?}", sample)?;
 
-    // Show a sample of the results
-    let sample: Vec<_> = unique.iter().take(5).collect();
-    println!("    Sample: {:?}", sample);
+    Ok(())
 }
 
 /// Practical example: Group-by operation.
 ///
 /// Group-by is fundamental in data processing. FoldHashMap provides
 /// fast grouping with excellent distribution properties.
-pub fn group_by_example() {
-    println!("\n  Practical Example: Group-By Operation");
+pub fn group_by_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Practical Example: Group-By Operation")?;
 
     // Sample data representing sales records
     #[derive(Debug)]
@@ -384,18 +419,25 @@ pub fn group_by_example() {
     for record in records {
         groups
             .entry(record.category)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(record.value);
     }
 
-    println!("    Grouped records:");
-    for (category, values) in &groups {
+    // Print the groups in category order rather than hash order
+    let mut categories: Vec<_> = groups.iter().collect();
+    categories.sort();
+
+    writeln!(out, "    Grouped records:")?;
+    for (category, values) in categories {
         let sum: i8 = values.iter().sum();
         let count = values.len();
         let avg = sum as f64 / count as f64;
-        println!(
+        writeln!(
+            out,
             "      {}: {:?} (count: {}, sum: {}, avg: {:.1})",
             category, values, count, sum, avg
-        );
+        )?;
     }
+
+    Ok(())
 }
@@ -0,0 +1,826 @@
+//! Compiler Front-End - Interning, Scopes and Name Resolution on FxHash
+//!
+//! `fxhash_examples` shows a flat symbol table and a string interner on
+//! their own. This module puts them to work the way rustc does, on a toy
+//! language with functions, `let`, blocks and calls:
+//!
+//! - [`Interner`] stores every distinct name once in one `String` arena and
+//!   hands out a [`Symbol`] - a `u32` index. Keywords are interned first,
+//!   so `let` is always `Symbol::LET` and the parser compares integers
+//! - [`lex`] turns source text into [`Token`]s, interning identifiers as it
+//!   goes
+//! - a [`ScopedMap<Symbol, Binding>`](ScopedMap) is the scope stack: one
+//!   `FxHashMap` per open block. Lookups walk from the innermost map
+//!   outwards, so an inner `let x` shadows an outer one until its block
+//!   closes
+//! - [`check`] resolves every name use against the stack and reports
+//!   undefined and duplicate names as [`Diagnostic`]s
+//!
+//! After interning, every map key is a `u32`, which FxHash turns into a
+//! bucket with one multiply. The source comes from the developer, so
+//! there is no HashDoS risk to pay SipHash for.
+
+use crate::scoped_map::{ScopeStack, ScopedMap};
+use demo_framework::section;
+use hashbrown::HashTable;
+use rustc_hash::FxBuildHasher;
+use std::hash::BuildHasher;
+use std::io::{self, Write};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "arena_interner",
+        "Interning identifiers into one arena and handing out u32 symbols",
+        arena_interner,
+    )?;
+
+    section(
+        out,
+        "tokenizing",
+        "Tokenizing source text with the interner",
+        tokenizing,
+    )?;
+
+    section(
+        out,
+        "scoped_resolution",
+        "Resolving names through a stack of FxHashMap scopes with shadowing",
+        scoped_resolution,
+    )?;
+
+    section(
+        out,
+        "practical_diagnostics",
+        "Practical demo: reporting undefined and duplicate names",
+        practical_diagnostics,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Interning
+// ---------------------------------------------------------------------------
+
+/// An interned name: an index into an [`Interner`].
+///
+/// Two symbols from the same interner are equal exactly when their names
+/// are, so comparing and hashing names costs as much as a `u32`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub const FN: Symbol = Symbol(0);
+    pub const LET: Symbol = Symbol(1);
+    pub const RETURN: Symbol = Symbol(2);
+
+    /// Interned by [`Interner::new`] in this order, matching the constants.
+    pub const KEYWORDS: [&'static str; 3] = ["fn", "let", "return"];
+
+    pub fn index(self) -> u32 {
+        self.0
+    }
+
+    pub fn is_keyword(self) -> bool {
+        (self.0 as usize) < Self::KEYWORDS.len()
+    }
+}
+
+/// Stores each distinct name once and maps it to a [`Symbol`].
+///
+/// Names are appended to one `String` and remembered as `(start, end)`
+/// offsets, so the arena can grow without invalidating anything. The
+/// lookup table is a hashbrown `HashTable<Symbol>`: it holds only the
+/// symbols and hashes a candidate's name by slicing it out of the arena,
+/// so no name is ever stored twice.
+pub struct Interner {
+    arena: String,
+    spans: Vec<(u32, u32)>,
+    table: HashTable<Symbol>,
+    hasher: FxBuildHasher,
+}
+
+impl Interner {
+    /// An interner holding only the keywords.
+    pub fn new() -> Self {
+        let mut interner: Interner = Interner {
+            arena: String::new(),
+            spans: Vec::new(),
+            table: HashTable::new(),
+            hasher: FxBuildHasher,
+        };
+        for keyword in Symbol::KEYWORDS {
+            interner.intern(keyword);
+        }
+        interner
+    }
+
+    /// Returns the symbol for `name`, adding it on first sight.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        let hash: u64 = self.hasher.hash_one(name);
+        let Interner {
+            arena,
+            spans,
+            table,
+            hasher,
+        } = self;
+        if let Some(&symbol) = table.find(hash, |&symbol| name_of(arena, spans, symbol) == name) {
+            return symbol;
+        }
+
+        let symbol: Symbol = Symbol(spans.len() as u32);
+        let start: u32 = arena.len() as u32;
+        arena.push_str(name);
+        spans.push((start, arena.len() as u32));
+        table.insert_unique(hash, symbol, |&symbol| {
+            hasher.hash_one(name_of(arena, spans, symbol))
+        });
+        symbol
+    }
+
+    /// The symbol for `name` if it has been interned.
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        let hash: u64 = self.hasher.hash_one(name);
+        self.table
+            .find(hash, |&symbol| self.resolve(symbol) == name)
+            .copied()
+    }
+
+    /// The name behind `symbol`. Panics on a symbol from another interner
+    /// that is out of range for this one.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        name_of(&self.arena, &self.spans, symbol)
+    }
+
+    /// Distinct names interned, keywords included.
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Bytes of name text in the arena.
+    pub fn arena_bytes(&self) -> usize {
+        self.arena.len()
+    }
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn name_of<'a>(arena: &'a str, spans: &[(u32, u32)], symbol: Symbol) -> &'a str {
+    let (start, end) = spans[symbol.0 as usize];
+    &arena[start as usize..end as usize]
+}
+
+// ---------------------------------------------------------------------------
+// Lexing
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// An identifier or keyword; see [`Symbol::is_keyword`].
+    Ident(Symbol),
+    Number(u64),
+    /// One of `( ) { } , ; = + - *`.
+    Punct(char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// 1-based source line.
+    pub line: u32,
+}
+
+/// Splits `source` into tokens, interning every identifier.
+///
+/// Whitespace and `//` comments are skipped. Characters the language
+/// doesn't use and numbers that overflow `u64` become syntax diagnostics.
+pub fn lex(source: &str, interner: &mut Interner) -> (Vec<Token>, Vec<Diagnostic>) {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let bytes: &[u8] = source.as_bytes();
+    let mut line: u32 = 1;
+    let mut pos: usize = 0;
+
+    while pos < bytes.len() {
+        let start: usize = pos;
+        let c: char = source[pos..].chars().next().unwrap_or_default();
+        pos += c.len_utf8();
+        let kind: TokenKind = match c {
+            '\n' => {
+                line += 1;
+                continue;
+            }
+            c if c.is_whitespace() => continue,
+            '/' if bytes.get(pos) == Some(&b'/') => {
+                while pos < bytes.len() && bytes[pos] != b'\n' {
+                    pos += 1;
+                }
+                continue;
+            }
+            'a'..='z' | 'A'..='Z' | '_' => {
+                while pos < bytes.len()
+                    && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_')
+                {
+                    pos += 1;
+                }
+                TokenKind::Ident(interner.intern(&source[start..pos]))
+            }
+            '0'..='9' => {
+                while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                    pos += 1;
+                }
+                match source[start..pos].parse() {
+                    Ok(value) => TokenKind::Number(value),
+                    Err(_) => {
+                        diagnostics.push(Diagnostic::Syntax {
+                            line,
+                            message: format!("number {} is too large", &source[start..pos]),
+                        });
+                        continue;
+                    }
+                }
+            }
+            '(' | ')' | '{' | '}' | ',' | ';' | '=' | '+' | '-' | '*' => TokenKind::Punct(c),
+            other => {
+                diagnostics.push(Diagnostic::Syntax {
+                    line,
+                    message: format!("unexpected character {other:?}"),
+                });
+                continue;
+            }
+        };
+        tokens.push(Token { kind, line });
+    }
+
+    (tokens, diagnostics)
+}
+
+// ---------------------------------------------------------------------------
+// Scopes
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Parameter,
+    Variable,
+}
+
+/// What a name was declared as, and where.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    pub kind: SymbolKind,
+    pub line: u32,
+}
+
+// ---------------------------------------------------------------------------
+// Resolution
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    Undefined {
+        name: Symbol,
+        line: u32,
+    },
+    Duplicate {
+        name: Symbol,
+        line: u32,
+        previous: u32,
+    },
+    Syntax {
+        line: u32,
+        message: String,
+    },
+}
+
+impl Diagnostic {
+    pub fn line(&self) -> u32 {
+        match self {
+            Diagnostic::Undefined { line, .. }
+            | Diagnostic::Duplicate { line, .. }
+            | Diagnostic::Syntax { line, .. } => *line,
+        }
+    }
+
+    /// The message a compiler would print, with names spelled out.
+    pub fn render(&self, interner: &Interner) -> String {
+        match self {
+            Diagnostic::Undefined { name, line } => {
+                format!(
+                    "line {line}: cannot find `{}` in this scope",
+                    interner.resolve(*name)
+                )
+            }
+            Diagnostic::Duplicate {
+                name,
+                line,
+                previous,
+            } => format!(
+                "line {line}: `{}` is already declared in this scope (line {previous})",
+                interner.resolve(*name)
+            ),
+            Diagnostic::Syntax { line, message } => format!("line {line}: syntax error: {message}"),
+        }
+    }
+}
+
+/// One use of a name and what it resolved to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reference {
+    pub name: Symbol,
+    pub line: u32,
+    /// Scope depth and binding of the declaration, `None` if undefined.
+    pub target: Option<(usize, Binding)>,
+}
+
+#[derive(Debug, Default)]
+pub struct Analysis {
+    pub tokens: usize,
+    pub references: Vec<Reference>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Analysis {
+    pub fn is_ok(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+/// Lexes `source` and resolves every name in it.
+///
+/// The grammar:
+///
+/// ```text
+/// program := ("fn" name "(" [name ("," name)*] ")" block)*
+/// block   := "{" stmt* "}"
+/// stmt    := "let" name "=" expr ";" | "return" expr ";" | block | expr ";"
+/// expr    := operand (("+" | "-" | "*") operand)*
+/// operand := number | name ["(" [expr ("," expr)*] ")"] | "(" expr ")"
+/// ```
+///
+/// Functions are declared in the global scope before any body is read,
+/// so a call may come before the callee. A `let` binding is declared after
+/// its initializer, so `let x = x + 1;` reads the outer `x`. Parameters
+/// get a scope of their own and each block opens another.
+///
+/// Lexing errors stop the analysis before resolution; resolution stops at
+/// the first syntax error, keeping the diagnostics found so far. The
+/// diagnostics come back sorted by line.
+pub fn check(source: &str, interner: &mut Interner) -> Analysis {
+    let (tokens, diagnostics) = lex(source, interner);
+    let mut resolver: Resolver = Resolver {
+        tokens: &tokens,
+        pos: 0,
+        scopes: ScopedMap::new(),
+        analysis: Analysis {
+            tokens: tokens.len(),
+            references: Vec::new(),
+            diagnostics,
+        },
+    };
+    if resolver.analysis.is_ok() {
+        // A syntax error is already recorded when `program` returns it
+        let _ = resolver.program();
+    }
+    // Hoisting reports duplicate functions first; list everything in source order
+    resolver.analysis.diagnostics.sort_by_key(Diagnostic::line);
+    resolver.analysis
+}
+
+/// Marks a syntax error that has been recorded and ends resolution.
+struct Stop;
+
+struct Resolver<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    scopes: ScopedMap<Symbol, Binding>,
+    analysis: Analysis,
+}
+
+impl Resolver<'_> {
+    fn program(&mut self) -> Result<(), Stop> {
+        self.hoist_functions();
+        while self.pos < self.tokens.len() {
+            self.keyword(Symbol::FN)?;
+            self.name()?;
+            self.scopes.push_scope();
+            self.punct('(')?;
+            if !self.eat(')') {
+                loop {
+                    let (parameter, line) = self.name()?;
+                    self.declare(parameter, SymbolKind::Parameter, line);
+                    if self.eat(')') {
+                        break;
+                    }
+                    self.punct(',')?;
+                }
+            }
+            self.block()?;
+            self.scopes.pop_scope();
+        }
+        Ok(())
+    }
+
+    /// Declares every top-level `fn` name before any body is resolved.
+    fn hoist_functions(&mut self) {
+        let mut depth: usize = 0;
+        for pair in self.tokens.windows(2) {
+            match (pair[0].kind, pair[1].kind) {
+                (TokenKind::Punct('{'), _) => depth += 1,
+                (TokenKind::Punct('}'), _) => depth = depth.saturating_sub(1),
+                (TokenKind::Ident(Symbol::FN), TokenKind::Ident(name))
+                    if depth == 0 && !name.is_keyword() =>
+                {
+                    self.declare(name, SymbolKind::Function, pair[1].line);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn block(&mut self) -> Result<(), Stop> {
+        self.punct('{')?;
+        self.scopes.push_scope();
+        while !self.eat('}') {
+            if self.pos == self.tokens.len() {
+                return self.error("expected `}`".to_string());
+            }
+            self.statement()?;
+        }
+        self.scopes.pop_scope();
+        Ok(())
+    }
+
+    fn statement(&mut self) -> Result<(), Stop> {
+        match self.peek() {
+            Some(TokenKind::Ident(Symbol::LET)) => {
+                self.pos += 1;
+                let (name, line) = self.name()?;
+                self.punct('=')?;
+                self.expression()?;
+                self.punct(';')?;
+                self.declare(name, SymbolKind::Variable, line);
+                Ok(())
+            }
+            Some(TokenKind::Ident(Symbol::RETURN)) => {
+                self.pos += 1;
+                self.expression()?;
+                self.punct(';')
+            }
+            Some(TokenKind::Punct('{')) => self.block(),
+            _ => {
+                self.expression()?;
+                self.punct(';')
+            }
+        }
+    }
+
+    fn expression(&mut self) -> Result<(), Stop> {
+        self.operand()?;
+        while self.eat('+') || self.eat('-') || self.eat('*') {
+            self.operand()?;
+        }
+        Ok(())
+    }
+
+    fn operand(&mut self) -> Result<(), Stop> {
+        match self.peek() {
+            Some(TokenKind::Number(_)) => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(TokenKind::Punct('(')) => {
+                self.pos += 1;
+                self.expression()?;
+                self.punct(')')
+            }
+            _ => {
+                let (name, line) = self.name()?;
+                self.use_name(name, line);
+                if self.eat('(') && !self.eat(')') {
+                    loop {
+                        self.expression()?;
+                        if self.eat(')') {
+                            break;
+                        }
+                        self.punct(',')?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn declare(&mut self, name: Symbol, kind: SymbolKind, line: u32) {
+        // A name already declared in this scope keeps its first binding;
+        // one declared further out is shadowed
+        match self.scopes.get_in_current_scope(&name) {
+            Some(previous) => self.analysis.diagnostics.push(Diagnostic::Duplicate {
+                name,
+                line,
+                previous: previous.line,
+            }),
+            None => {
+                self.scopes.insert(name, Binding { kind, line });
+            }
+        }
+    }
+
+    fn use_name(&mut self, name: Symbol, line: u32) {
+        let target: Option<(usize, Binding)> = self
+            .scopes
+            .lookup(&name)
+            .map(|(depth, &binding)| (depth, binding));
+        if target.is_none() {
+            self.analysis
+                .diagnostics
+                .push(Diagnostic::Undefined { name, line });
+        }
+        self.analysis
+            .references
+            .push(Reference { name, line, target });
+    }
+
+    fn peek(&self) -> Option<TokenKind> {
+        self.tokens.get(self.pos).map(|token| token.kind)
+    }
+
+    /// Consumes the punctuation `c` if it is next.
+    fn eat(&mut self, c: char) -> bool {
+        let found: bool = self.peek() == Some(TokenKind::Punct(c));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn punct(&mut self, c: char) -> Result<(), Stop> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.error(format!("expected `{c}`"))
+        }
+    }
+
+    fn keyword(&mut self, keyword: Symbol) -> Result<(), Stop> {
+        if self.peek() == Some(TokenKind::Ident(keyword)) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.error(format!(
+                "expected `{}`",
+                Symbol::KEYWORDS[keyword.0 as usize]
+            ))
+        }
+    }
+
+    /// Consumes an identifier that is not a keyword.
+    fn name(&mut self) -> Result<(Symbol, u32), Stop> {
+        match self.tokens.get(self.pos) {
+            Some(&Token {
+                kind: TokenKind::Ident(name),
+                line,
+            }) if !name.is_keyword() => {
+                self.pos += 1;
+                Ok((name, line))
+            }
+            Some(&Token {
+                kind: TokenKind::Ident(keyword),
+                ..
+            }) => self.error(format!(
+                "expected a name, found keyword `{}`",
+                Symbol::KEYWORDS[keyword.0 as usize]
+            )),
+            _ => self.error("expected a name".to_string()),
+        }
+    }
+
+    fn error<T>(&mut self, message: String) -> Result<T, Stop> {
+        let line: u32 = match self.tokens.get(self.pos).or(self.tokens.last()) {
+            Some(token) => token.line,
+            None => 1,
+        };
+        let message: String = if self.pos < self.tokens.len() {
+            message
+        } else {
+            format!("{message} at end of input")
+        };
+        self.analysis
+            .diagnostics
+            .push(Diagnostic::Syntax { line, message });
+        Err(Stop)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// The program `tokenizing` and `scoped_resolution` run on.
+pub const SAMPLE_PROGRAM: &str = "\
+fn main() {
+    let total = add(1, 2);
+    let x = total * 2;
+    {
+        let x = x + 1; // shadows the outer x
+        print(x);
+    }
+    return x;
+}
+fn add(a, b) {
+    return a + b;
+}
+fn print(value) {
+    return value;
+}
+";
+
+/// The program `practical_diagnostics` reports on.
+pub const BROKEN_PROGRAM: &str = "\
+fn main() {
+    let count = 1;
+    let count = 2;
+    {
+        let scratch = count;
+    }
+    return scratch + missing(count);
+}
+fn helper(n, n) {
+    return n;
+}
+fn main() {
+    return 0;
+}
+";
+
+/// Demonstrates the arena interner: repeated names share one symbol and
+/// one copy of their text.
+pub fn arena_interner(out: &mut dyn Write) -> io::Result<()> {
+    let mut interner: Interner = Interner::new();
+    writeln!(
+        out,
+        "\n  Keywords are interned first: fn = {:?}, let = {:?}, return = {:?}",
+        Symbol::FN,
+        Symbol::LET,
+        Symbol::RETURN
+    )?;
+
+    let words: [&str; 8] = ["total", "x", "add", "x", "total", "print", "x", "add"];
+    writeln!(out, "\n  Interning {} identifier uses:", words.len())?;
+    for word in words {
+        let symbol: Symbol = interner.intern(word);
+        writeln!(out, "    {word:<6} -> {symbol:?}")?;
+    }
+
+    let text_bytes: usize = words.iter().map(|word| word.len()).sum();
+    let keyword_bytes: usize = Symbol::KEYWORDS.iter().map(|keyword| keyword.len()).sum();
+    writeln!(
+        out,
+        "\n  {} distinct identifiers add {} bytes to the arena (after {} keyword bytes),",
+        interner.len() - Symbol::KEYWORDS.len(),
+        interner.arena_bytes() - keyword_bytes,
+        keyword_bytes
+    )?;
+    writeln!(
+        out,
+        "  where one String per use would hold {text_bytes} bytes in {} allocations.",
+        words.len()
+    )?;
+    writeln!(
+        out,
+        "  resolve({:?}) = {:?}; get(\"missing\") = {:?}",
+        Symbol(4),
+        interner.resolve(Symbol(4)),
+        interner.get("missing")
+    )?;
+    writeln!(
+        out,
+        "\n  Later maps key on Symbol, a u32: FxHash hashes it with one multiply,"
+    )?;
+    writeln!(
+        out,
+        "  and comparing two names is an integer compare instead of a memcmp."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates lexing: identifiers come out as symbols, keywords as the
+/// pre-interned ones.
+pub fn tokenizing(out: &mut dyn Write) -> io::Result<()> {
+    let mut interner: Interner = Interner::new();
+    let line: &str = "let x = add(total, 2) * 3; // comment";
+    let (tokens, diagnostics) = lex(line, &mut interner);
+
+    writeln!(out, "\n  Source: {line}")?;
+    writeln!(out, "  Tokens:")?;
+    for token in &tokens {
+        let shown: String = match token.kind {
+            TokenKind::Ident(symbol) if symbol.is_keyword() => {
+                format!("keyword {:?} ({symbol:?})", interner.resolve(symbol))
+            }
+            TokenKind::Ident(symbol) => {
+                format!("ident   {:?} ({symbol:?})", interner.resolve(symbol))
+            }
+            TokenKind::Number(value) => format!("number  {value}"),
+            TokenKind::Punct(c) => format!("punct   {c:?}"),
+        };
+        writeln!(out, "    {shown}")?;
+    }
+    writeln!(out, "  Lexer diagnostics: {}", diagnostics.len())?;
+
+    let (_, bad) = lex("let price = 5 € 2;", &mut interner);
+    writeln!(out, "\n  Source: let price = 5 € 2;")?;
+    for diagnostic in &bad {
+        writeln!(out, "    {}", diagnostic.render(&interner))?;
+    }
+
+    Ok(())
+}
+
+/// Demonstrates name resolution through the scope stack: each use finds
+/// the innermost declaration, and a block's names vanish when it closes.
+pub fn scoped_resolution(out: &mut dyn Write) -> io::Result<()> {
+    let mut interner: Interner = Interner::new();
+    let analysis: Analysis = check(SAMPLE_PROGRAM, &mut interner);
+
+    writeln!(out, "\n  Program:")?;
+    for (number, text) in SAMPLE_PROGRAM.lines().enumerate() {
+        writeln!(out, "    {:>2} | {text}", number + 1)?;
+    }
+
+    writeln!(
+        out,
+        "\n  {} tokens, {} name uses resolved:",
+        analysis.tokens,
+        analysis.references.len()
+    )?;
+    for reference in &analysis.references {
+        let name: &str = interner.resolve(reference.name);
+        match reference.target {
+            Some((depth, binding)) => writeln!(
+                out,
+                "    line {:>2}: {name:<6} -> {:?} declared on line {} (scope depth {depth})",
+                reference.line, binding.kind, binding.line
+            )?,
+            None => writeln!(
+                out,
+                "    line {:>2}: {name:<6} -> undefined",
+                reference.line
+            )?,
+        }
+    }
+    writeln!(out, "  Diagnostics: {}", analysis.diagnostics.len())?;
+    writeln!(
+        out,
+        "\n  `add` and `print` are called before they are defined: function names"
+    )?;
+    writeln!(
+        out,
+        "  are hoisted into the global scope. Line 5 reads the outer x (depth 2)"
+    )?;
+    writeln!(
+        out,
+        "  while declaring the inner one (depth 3), which line 6 then finds."
+    )?;
+
+    Ok(())
+}
+
+/// Practical example: the diagnostics a compiler's resolver pass reports.
+///
+/// Redeclaring a name in the same scope is an error; declaring it in an
+/// inner scope is shadowing. A name is only visible until its block closes.
+pub fn practical_diagnostics(out: &mut dyn Write) -> io::Result<()> {
+    let mut interner: Interner = Interner::new();
+    let analysis: Analysis = check(BROKEN_PROGRAM, &mut interner);
+
+    writeln!(out, "\n  Program:")?;
+    for (number, text) in BROKEN_PROGRAM.lines().enumerate() {
+        writeln!(out, "    {:>2} | {text}", number + 1)?;
+    }
+
+    writeln!(out, "\n  {} diagnostics:", analysis.diagnostics.len())?;
+    for diagnostic in &analysis.diagnostics {
+        writeln!(out, "    {}", diagnostic.render(&interner))?;
+    }
+
+    let mut syntax: Interner = Interner::new();
+    let source: &str = "fn main() { let let = 1; }";
+    writeln!(out, "\n  Source: {source}")?;
+    for diagnostic in &check(source, &mut syntax).diagnostics {
+        writeln!(out, "    {}", diagnostic.render(&syntax))?;
+    }
+
+    Ok(())
+}
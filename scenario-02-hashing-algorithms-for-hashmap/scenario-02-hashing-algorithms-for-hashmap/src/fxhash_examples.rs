@@ -15,76 +15,79 @@
 //!
 //! IMPORTANT: Only use FxHash when you control/trust the input!
 
+use demo_framework::section;
 use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
 use std::collections::HashMap;
 use std::collections::hash_map::RandomState;
 use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash, Hasher};
+use std::io::{self, Write};
 use std::rc::Rc;
 use std::time::{Duration, Instant};
 
 /// Type aliases for clarity.
 /// FxHashMap is just HashMap with FxHasher as the hasher.
+#[allow(dead_code)]
 pub type FxMap<K, V> = HashMap<K, V, BuildHasherDefault<FxHasher>>;
 
-fn section(name: &str, what: &str, f: impl FnOnce()) {
-    println!("\n{:=<80}", "");
-    println!("DEMO: {name}");
-    println!("  {what}");
-    println!("{:=<80}", "");
-
-    f();
-}
-
-pub fn run_all() {
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
     section(
+        out,
         "basic_fxhashmap_usage",
         "Basic FxHashMap API usage (drop-in replacement for std::HashMap)",
         basic_fxhashmap_usage,
-    );
+    )?;
 
     section(
+        out,
         "fxhashset_usage",
         "FxHashSet usage and set operations",
         fxhashset_usage,
-    );
+    )?;
 
     section(
+        out,
         "deterministic_hashing",
         "FxHash is deterministic (same input -> same hash across runs)",
         deterministic_hashing,
-    );
+    )?;
 
     section(
+        out,
         "examining_fxhash_output",
         "Inspect FxHasher output (hex) for strings and integers",
         examining_fxhash_output,
-    );
+    )?;
 
     section(
+        out,
         "performance_comparison",
         "Rough timing: FxHash vs SipHash (not a real benchmark)",
         performance_comparison,
-    );
+    )?;
 
     section(
+        out,
         "compiler_symbol_table",
         "Practical demo: symbol table lookups (compiler-like workload)",
         compiler_symbol_table,
-    );
+    )?;
 
     section(
+        out,
         "string_interning",
         "Practical demo: string interning with FxHashSet<Rc<str>>",
         string_interning,
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates basic FxHashMap usage.
 ///
 /// The API is identical to standard HashMap - only the hasher differs.
 /// This makes it easy to swap hashers without changing your code logic.
-pub fn basic_fxhashmap_usage() {
-    println!("\n  Basic FxHashMap Usage:");
+pub fn basic_fxhashmap_usage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Basic FxHashMap Usage:")?;
 
     // Method 1: Using the provided type alias from rustc-hash crate.
     // This is the most common and convenient way.
@@ -94,33 +97,35 @@ pub fn basic_fxhashmap_usage() {
     scores.insert("Bob".to_string(), 85);
     scores.insert("Charlie".to_string(), 92);
 
-    println!("    FxHashMap: {:?}", scores);
+    writeln!(out, "    FxHashMap: {:?}", scores)?;
 
     // Method 2: Using explicit type parameters.
     // This shows what's actually happening under the hood.
     // BuildHasherDefault<FxHasher> creates FxHasher instances.
     let mut explicit: HashMap<String, i8, BuildHasherDefault<FxHasher>> = HashMap::default();
     explicit.insert("test".to_string(), 42);
-    println!("    Explicit type: {:?}", explicit);
+    writeln!(out, "    Explicit type: {:?}", explicit)?;
 
     // Method 3: Converting from standard HashMap.
     // You can collect any iterator into an FxHashMap.
     let std_map: HashMap<&str, i8> = HashMap::from([("a", 1), ("b", 2)]);
     let fx_map: FxHashMap<&str, i8> = std_map.into_iter().collect();
-    println!("    Converted from std HashMap: {:?}", fx_map);
+    writeln!(out, "    Converted from std HashMap: {:?}", fx_map)?;
 
     // All standard HashMap methods work identically
     if let Some(score) = scores.get("Alice") {
-        println!("    Get 'Alice': {}", score);
+        writeln!(out, "    Get 'Alice': {}", score)?;
     }
+
+    Ok(())
 }
 
 /// Demonstrates FxHashSet usage.
 ///
 /// FxHashSet provides the same performance benefits as FxHashMap
 /// for set operations (membership testing, deduplication).
-pub fn fxhashset_usage() {
-    println!("\n  FxHashSet Usage:");
+pub fn fxhashset_usage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  FxHashSet Usage:")?;
 
     let mut visited: FxHashSet<i8> = FxHashSet::default();
 
@@ -129,9 +134,9 @@ pub fn fxhashset_usage() {
     visited.insert(3);
     visited.insert(2); // Duplicate, will be ignored
 
-    println!("    FxHashSet: {:?}", visited);
-    println!("    Contains 2? {}", visited.contains(&2));
-    println!("    Contains 5? {}", visited.contains(&5));
+    writeln!(out, "    FxHashSet: {:?}", visited)?;
+    writeln!(out, "    Contains 2? {}", visited.contains(&2))?;
+    writeln!(out, "    Contains 5? {}", visited.contains(&5))?;
 
     // Set operations work as expected
     let mut other: FxHashSet<i8> = FxHashSet::default();
@@ -141,7 +146,9 @@ pub fn fxhashset_usage() {
 
     // Intersection, union, difference all work
     let intersection: FxHashSet<_> = visited.intersection(&other).cloned().collect();
-    println!("    Intersection with {{2,3,4}}: {:?}", intersection);
+    writeln!(out, "    Intersection with {{2,3,4}}: {:?}", intersection)?;
+
+    Ok(())
 }
 
 /// Demonstrates FxHash's deterministic behavior.
@@ -149,8 +156,8 @@ pub fn fxhashset_usage() {
 /// Unlike SipHash, FxHash produces the same hash for the same input
 /// across different HashMap instances and even different program runs.
 /// This is both a feature (reproducibility) and a vulnerability (predictable).
-pub fn deterministic_hashing() {
-    println!("\n  FxHash is Deterministic:");
+pub fn deterministic_hashing(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  FxHash is Deterministic:")?;
 
     // Create two separate BuildHasherDefault instances
     let hasher1: BuildHasherDefault<FxHasher> = BuildHasherDefault::<FxHasher>::default();
@@ -168,44 +175,48 @@ pub fn deterministic_hashing() {
     value.hash(&mut h2);
     let hash2: u64 = h2.finish();
 
-    println!("    Hash from instance 1: {}", hash1);
-    println!("    Hash from instance 2: {}", hash2);
-    println!("    Are they equal? {}", hash1 == hash2);
+    writeln!(out, "    Hash from instance 1: {}", hash1)?;
+    writeln!(out, "    Hash from instance 2: {}", hash2)?;
+    writeln!(out, "    Are they equal? {}", hash1 == hash2)?;
+
+    Ok(())
 }
 
 /// Examines the actual hash values FxHash produces.
 ///
 /// Looking at the hash output helps understand the algorithm's behavior
 /// and verify it has good distribution properties.
-pub fn examining_fxhash_output() {
-    println!("\n  Examining FxHash Output:");
+pub fn examining_fxhash_output(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Examining FxHash Output:")?;
 
     let build_hasher: BuildHasherDefault<FxHasher> = BuildHasherDefault::<FxHasher>::default();
 
     // Hash various strings
-    println!("    String hashes:");
+    writeln!(out, "    String hashes:")?;
     for value in ["a", "b", "ab", "ba", "hello", "world"] {
         let mut hasher: FxHasher = build_hasher.build_hasher();
         value.hash(&mut hasher);
         // Display in hex to see bit patterns more clearly
-        println!("      hash({:?}) = {:016x}", value, hasher.finish());
+        writeln!(out, "      hash({:?}) = {:016x}", value, hasher.finish())?;
     }
 
     // Hash various integers
-    println!("\n    Integer hashes:");
+    writeln!(out, "\n    Integer hashes:")?;
     for value in [0i32, 1, 42, 100, 1_000, -1] {
         let mut hasher: FxHasher = build_hasher.build_hasher();
         value.hash(&mut hasher);
-        println!("      hash({:5}) = {:016x}", value, hasher.finish());
+        writeln!(out, "      hash({:5}) = {:016x}", value, hasher.finish())?;
     }
+
+    Ok(())
 }
 
 /// Demonstrates FxHash performance compared to SipHash.
 ///
 /// This comparison shows why FxHash is preferred for performance-critical
 /// applications where security isn't a concern.
-pub fn performance_comparison() {
-    println!("\n  FxHash vs SipHash Performance:");
+pub fn performance_comparison(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  FxHash vs SipHash Performance:")?;
 
     let iterations: i32 = 500_000;
 
@@ -233,13 +244,14 @@ pub fn performance_comparison() {
     }
     let sip_int_time: Duration = start.elapsed();
 
-    println!("    Integer keys ({} iterations):", iterations);
-    println!("      FxHash:  {:?}", fx_int_time);
-    println!("      SipHash: {:?}", sip_int_time);
-    println!(
+    writeln!(out, "    Integer keys ({} iterations):", iterations)?;
+    writeln!(out, "      FxHash:  {:?}", fx_int_time)?;
+    writeln!(out, "      SipHash: {:?}", sip_int_time)?;
+    writeln!(
+        out,
         "      Speedup: {:.2}x faster",
         sip_int_time.as_nanos() as f64 / fx_int_time.as_nanos() as f64
-    );
+    )?;
 
     // === Test with string keys ===
     let test_strings: Vec<String> = (0..1_000).map(|i| format!("key_{}", i)).collect();
@@ -264,27 +276,33 @@ pub fn performance_comparison() {
     }
     let sip_str_time: Duration = start.elapsed();
 
-    println!("\n    String keys ({} iterations):", iterations);
-    println!("      FxHash:  {:?}", fx_str_time);
-    println!("      SipHash: {:?}", sip_str_time);
-    println!(
+    writeln!(out, "\n    String keys ({} iterations):", iterations)?;
+    writeln!(out, "      FxHash:  {:?}", fx_str_time)?;
+    writeln!(out, "      SipHash: {:?}", sip_str_time)?;
+    writeln!(
+        out,
         "      Speedup: {:.2}x faster",
         sip_str_time.as_nanos() as f64 / fx_str_time.as_nanos() as f64
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Practical example: Symbol table for a compiler/interpreter.
 ///
 /// This is FxHash's ideal use case - a compiler controls its input
 /// (source code), so HashDoS resistance isn't needed. Speed matters
-/// because compilers do millions of symbol lookups.
-pub fn compiler_symbol_table() {
-    println!("\n  Practical Example: Compiler Symbol Table");
+/// because compilers do millions of symbol lookups. `frontend_examples`
+/// grows this into interned symbols and nested scopes.
+pub fn compiler_symbol_table(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Practical Example: Compiler Symbol Table")?;
 
     // A symbol table maps identifiers to their semantic information.
     // Compilers look up symbols constantly during parsing, type checking,
     // and code generation.
 
+    // Fields are only read through the Debug output below.
+    #[allow(dead_code)]
     #[derive(Debug, Clone)]
     struct Symbol {
         name: String,
@@ -324,25 +342,27 @@ pub fn compiler_symbol_table() {
         );
     }
 
-    println!("    Symbol table contents:");
+    writeln!(out, "    Symbol table contents:")?;
     for (name, symbol) in &symbols {
-        println!("      {} -> {:?}", name, symbol.kind);
+        writeln!(out, "      {} -> {:?}", name, symbol.kind)?;
     }
 
     // Fast lookups during semantic analysis
     if let Some(symbol) = symbols.get("main") {
-        println!("\n    Found entry point: {:?}", symbol);
+        writeln!(out, "\n    Found entry point: {:?}", symbol)?;
     }
 
     // Simulate multiple lookups (what happens during type checking)
     let lookups: [&str; 5] = ["x", "y", "main", "Point", "unknown"];
-    println!("\n    Performing lookups:");
+    writeln!(out, "\n    Performing lookups:")?;
     for name in lookups {
         match symbols.get(name) {
-            Some(sym) => println!("      {} -> found ({:?})", name, sym.kind),
-            None => println!("      {} -> not found", name),
+            Some(sym) => writeln!(out, "      {} -> found ({:?})", name, sym.kind)?,
+            None => writeln!(out, "      {} -> not found", name)?,
         }
     }
+
+    Ok(())
 }
 
 /// Practical example: String interning.
@@ -351,8 +371,8 @@ pub fn compiler_symbol_table() {
 /// references to the stored copy. This saves memory when the same
 /// strings appear many times (common in compilers and parsers).
 /// FxHash makes lookups fast.
-pub fn string_interning() {
-    println!("\n  Practical Example: String Interning");
+pub fn string_interning(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Practical Example: String Interning")?;
 
     // An interner stores unique strings and returns references to them.
     // This is useful when you have many duplicate strings (like identifiers
@@ -391,18 +411,28 @@ pub fn string_interning() {
     // Intern some strings (with duplicates, simulating repeated identifiers)
     let words: [&str; 6] = ["hello", "world", "hello", "rust", "world", "hello"];
 
-    println!("    Interning strings:");
+    writeln!(out, "    Interning strings:")?;
     for word in words {
         let interned: Rc<str> = interner.intern(word);
         // Show the pointer address - same strings get same pointer
-        println!(
+        writeln!(
+            out,
             "      Interned {:?} -> ptr {:?}",
             word,
             Rc::as_ptr(&interned)
-        );
+        )?;
     }
 
-    println!("\n    Total unique strings stored: {}", interner.stats());
-    println!("    Notice: Same strings get the same pointer!");
-    println!("    This saves memory and enables O(1) string comparison by pointer.");
+    writeln!(
+        out,
+        "\n    Total unique strings stored: {}",
+        interner.stats()
+    )?;
+    writeln!(out, "    Notice: Same strings get the same pointer!")?;
+    writeln!(
+        out,
+        "    This saves memory and enables O(1) string comparison by pointer."
+    )?;
+
+    Ok(())
 }
@@ -0,0 +1,656 @@
+//! Hash-Consing - One Shared Node Per Distinct Subtree
+//!
+//! An immutable expression tree often repeats itself: `x * y` appears in
+//! ten formulas, a recursive builder produces the same subtree thousands
+//! of times. Hash-consing builds every node through a table so that
+//! structurally equal subtrees are the *same* allocation:
+//!
+//! - [`ExprPool`] keeps a `FxHashMap<ExprKey, Rc<Expr>>`. An [`ExprKey`]
+//!   is a node's own data plus the *addresses* of its children, with a
+//!   derived `Hash`. Children already come from the pool, so equal
+//!   children have equal addresses, and hashing or comparing a key costs
+//!   the same at any tree depth
+//! - building a node that already exists returns the existing `Rc`, so
+//!   memory grows with the number of *distinct* subtrees, not their uses
+//! - two pooled expressions are equal exactly when `Rc::ptr_eq` says so:
+//!   equality is one pointer compare instead of a walk over both trees
+//!
+//! [`TreeBuilder`] is the baseline that allocates every node it is asked
+//! for. Both implement [`ExprBuilder`], so [`parse`] and [`fib_expr`] build
+//! either kind of tree.
+
+use demo_framework::section;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::fmt;
+use std::io::{self, Write};
+use std::mem::size_of;
+use std::rc::Rc;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "interning_nodes",
+        "Building nodes through a pool keyed by child pointers",
+        interning_nodes,
+    )?;
+
+    section(
+        out,
+        "shared_subtrees",
+        "Memory for a tree with repeated subtrees, plain vs hash-consed",
+        shared_subtrees,
+    )?;
+
+    section(
+        out,
+        "constant_time_equality",
+        "Deep structural equality vs one pointer compare",
+        constant_time_equality,
+    )?;
+
+    section(
+        out,
+        "practical_formula_dedup",
+        "Practical demo: deduplicating parsed spreadsheet formulas",
+        practical_formula_dedup,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Expressions
+// ---------------------------------------------------------------------------
+
+/// A node of the toy expression language. The derived `PartialEq` and
+/// `Hash` walk the whole tree.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Num(i64),
+    Var(char),
+    Neg(Rc<Expr>),
+    Add(Rc<Expr>, Rc<Expr>),
+    Mul(Rc<Expr>, Rc<Expr>),
+}
+
+/// Heap bytes behind one `Rc<Expr>`: the node plus the strong and weak
+/// counts.
+pub const NODE_BYTES: usize = size_of::<Expr>() + 2 * size_of::<usize>();
+
+impl Expr {
+    /// Evaluates with `vars` giving each variable's value (0 if missing).
+    pub fn eval(&self, vars: &impl Fn(char) -> i64) -> i64 {
+        match self {
+            Expr::Num(n) => *n,
+            Expr::Var(name) => vars(*name),
+            Expr::Neg(inner) => -inner.eval(vars),
+            Expr::Add(left, right) => left.eval(vars).wrapping_add(right.eval(vars)),
+            Expr::Mul(left, right) => left.eval(vars).wrapping_mul(right.eval(vars)),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{n}"),
+            Expr::Var(name) => write!(f, "{name}"),
+            Expr::Neg(inner) => match **inner {
+                Expr::Add(..) | Expr::Mul(..) => write!(f, "-({inner})"),
+                _ => write!(f, "-{inner}"),
+            },
+            // Parenthesize what the left-associative parser would group
+            // differently, so printing and re-parsing gives the same tree
+            Expr::Add(left, right) => match **right {
+                Expr::Add(..) => write!(f, "{left} + ({right})"),
+                _ => write!(f, "{left} + {right}"),
+            },
+            Expr::Mul(left, right) => {
+                match **left {
+                    Expr::Add(..) => write!(f, "({left}) * ")?,
+                    _ => write!(f, "{left} * ")?,
+                }
+                match **right {
+                    Expr::Add(..) | Expr::Mul(..) => write!(f, "({right})"),
+                    _ => write!(f, "{right}"),
+                }
+            }
+        }
+    }
+}
+
+/// Something that turns node descriptions into shared nodes.
+pub trait ExprBuilder {
+    fn node(&mut self, expr: Expr) -> Rc<Expr>;
+
+    fn num(&mut self, n: i64) -> Rc<Expr> {
+        self.node(Expr::Num(n))
+    }
+
+    fn var(&mut self, name: char) -> Rc<Expr> {
+        self.node(Expr::Var(name))
+    }
+
+    fn neg(&mut self, inner: Rc<Expr>) -> Rc<Expr> {
+        self.node(Expr::Neg(inner))
+    }
+
+    fn add(&mut self, left: Rc<Expr>, right: Rc<Expr>) -> Rc<Expr> {
+        self.node(Expr::Add(left, right))
+    }
+
+    fn mul(&mut self, left: Rc<Expr>, right: Rc<Expr>) -> Rc<Expr> {
+        self.node(Expr::Mul(left, right))
+    }
+}
+
+/// Allocates every node it is asked for, like building the tree by hand.
+#[derive(Debug, Default)]
+pub struct TreeBuilder {
+    /// Nodes allocated so far.
+    pub allocated: usize,
+}
+
+impl ExprBuilder for TreeBuilder {
+    fn node(&mut self, expr: Expr) -> Rc<Expr> {
+        self.allocated += 1;
+        Rc::new(expr)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// The pool
+// ---------------------------------------------------------------------------
+
+/// A node's identity for the pool: its own data, and its children by
+/// address. `*const Expr` derives `Hash` and `Eq` from the address, so a
+/// key is hashed and compared in constant time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExprKey {
+    Num(i64),
+    Var(char),
+    Neg(*const Expr),
+    Add(*const Expr, *const Expr),
+    Mul(*const Expr, *const Expr),
+}
+
+impl ExprKey {
+    pub fn of(expr: &Expr) -> ExprKey {
+        match expr {
+            Expr::Num(n) => ExprKey::Num(*n),
+            Expr::Var(name) => ExprKey::Var(*name),
+            Expr::Neg(inner) => ExprKey::Neg(Rc::as_ptr(inner)),
+            Expr::Add(left, right) => ExprKey::Add(Rc::as_ptr(left), Rc::as_ptr(right)),
+            Expr::Mul(left, right) => ExprKey::Mul(Rc::as_ptr(left), Rc::as_ptr(right)),
+        }
+    }
+}
+
+/// A hash-consing table: every distinct node is allocated once.
+///
+/// Keys hold child addresses, which stay valid because the pool holds an
+/// `Rc` to every node it returned, and each node holds its children.
+/// Expressions built by the same pool are structurally equal exactly when
+/// [`Rc::ptr_eq`] is true.
+#[derive(Debug, Default)]
+pub struct ExprPool {
+    nodes: FxHashMap<ExprKey, Rc<Expr>>,
+    /// Requests answered with an existing node.
+    pub hits: usize,
+}
+
+impl ExprPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Distinct nodes allocated.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Approximate heap bytes: the nodes, plus an entry and a control byte
+    /// for each slot the table has room for.
+    pub fn heap_bytes(&self) -> usize {
+        let entry: usize = size_of::<(ExprKey, Rc<Expr>)>() + 1;
+        self.len() * NODE_BYTES + self.nodes.capacity() * entry
+    }
+}
+
+impl ExprBuilder for ExprPool {
+    fn node(&mut self, expr: Expr) -> Rc<Expr> {
+        let key: ExprKey = ExprKey::of(&expr);
+        if let Some(existing) = self.nodes.get(&key) {
+            self.hits += 1;
+            return Rc::clone(existing);
+        }
+        let node: Rc<Expr> = Rc::new(expr);
+        self.nodes.insert(key, Rc::clone(&node));
+        node
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Building and measuring
+// ---------------------------------------------------------------------------
+
+/// `fib(0) = x`, `fib(1) = y`, `fib(n) = fib(n-1) + fib(n-2)`, built
+/// naively: every call builds its subtrees again. As a tree that is
+/// exponentially many nodes; hash-consed it is `n + 1`.
+pub fn fib_expr(builder: &mut impl ExprBuilder, n: u32) -> Rc<Expr> {
+    match n {
+        0 => builder.var('x'),
+        1 => builder.var('y'),
+        _ => {
+            let left: Rc<Expr> = fib_expr(builder, n - 1);
+            let right: Rc<Expr> = fib_expr(builder, n - 2);
+            builder.add(left, right)
+        }
+    }
+}
+
+/// Nodes in `expr` counted as a tree, i.e. every use of a shared subtree
+/// counts again. Memoized by node address, so this is linear in the
+/// distinct nodes even when the tree is exponentially larger.
+pub fn tree_size(expr: &Rc<Expr>) -> u64 {
+    fn size(expr: &Rc<Expr>, memo: &mut FxHashMap<*const Expr, u64>) -> u64 {
+        if let Some(&known) = memo.get(&Rc::as_ptr(expr)) {
+            return known;
+        }
+        let total: u64 = 1 + match &**expr {
+            Expr::Num(_) | Expr::Var(_) => 0,
+            Expr::Neg(inner) => size(inner, memo),
+            Expr::Add(left, right) | Expr::Mul(left, right) => size(left, memo) + size(right, memo),
+        };
+        memo.insert(Rc::as_ptr(expr), total);
+        total
+    }
+    size(expr, &mut FxHashMap::default())
+}
+
+/// Distinct allocations reachable from `expr`.
+pub fn distinct_nodes(expr: &Rc<Expr>) -> usize {
+    let mut seen: FxHashSet<*const Expr> = FxHashSet::default();
+    let mut stack: Vec<&Rc<Expr>> = vec![expr];
+    while let Some(node) = stack.pop() {
+        if !seen.insert(Rc::as_ptr(node)) {
+            continue;
+        }
+        match &**node {
+            Expr::Num(_) | Expr::Var(_) => {}
+            Expr::Neg(inner) => stack.push(inner),
+            Expr::Add(left, right) | Expr::Mul(left, right) => stack.extend([left, right]),
+        }
+    }
+    seen.len()
+}
+
+/// Structural equality like the derived `==`, counting the node pairs it
+/// visits.
+pub fn structural_eq(a: &Expr, b: &Expr, visits: &mut u64) -> bool {
+    *visits += 1;
+    match (a, b) {
+        (Expr::Num(x), Expr::Num(y)) => x == y,
+        (Expr::Var(x), Expr::Var(y)) => x == y,
+        (Expr::Neg(x), Expr::Neg(y)) => structural_eq(x, y, visits),
+        (Expr::Add(a1, a2), Expr::Add(b1, b2)) | (Expr::Mul(a1, a2), Expr::Mul(b1, b2)) => {
+            structural_eq(a1, b1, visits) && structural_eq(a2, b2, visits)
+        }
+        _ => false,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset into the source.
+    pub offset: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `source` (integers, one-letter variables, `+`, `-`, `*`,
+/// parentheses; usual precedence, left-associative) into nodes from
+/// `builder`. `a - b` becomes `a + -b`.
+pub fn parse(builder: &mut impl ExprBuilder, source: &str) -> Result<Rc<Expr>, ParseError> {
+    let mut parser: Parser<'_, _> = Parser {
+        builder,
+        bytes: source.as_bytes(),
+        pos: 0,
+    };
+    let expr: Rc<Expr> = parser.sum()?;
+    parser.skip_spaces();
+    match parser.bytes.get(parser.pos) {
+        None => Ok(expr),
+        Some(_) => Err(parser.error("unexpected character")),
+    }
+}
+
+struct Parser<'a, B> {
+    builder: &'a mut B,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<B: ExprBuilder> Parser<'_, B> {
+    fn sum(&mut self) -> Result<Rc<Expr>, ParseError> {
+        let mut left: Rc<Expr> = self.product()?;
+        loop {
+            if self.eat(b'+') {
+                let right: Rc<Expr> = self.product()?;
+                left = self.builder.add(left, right);
+            } else if self.eat(b'-') {
+                let right: Rc<Expr> = self.product()?;
+                let negated: Rc<Expr> = self.builder.neg(right);
+                left = self.builder.add(left, negated);
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<Rc<Expr>, ParseError> {
+        let mut left: Rc<Expr> = self.atom()?;
+        while self.eat(b'*') {
+            let right: Rc<Expr> = self.atom()?;
+            left = self.builder.mul(left, right);
+        }
+        Ok(left)
+    }
+
+    fn atom(&mut self) -> Result<Rc<Expr>, ParseError> {
+        self.skip_spaces();
+        match self.bytes.get(self.pos).copied() {
+            Some(b'(') => {
+                self.pos += 1;
+                let inner: Rc<Expr> = self.sum()?;
+                if self.eat(b')') {
+                    Ok(inner)
+                } else {
+                    Err(self.error("expected `)`"))
+                }
+            }
+            Some(b'-') => {
+                self.pos += 1;
+                let inner: Rc<Expr> = self.atom()?;
+                Ok(self.builder.neg(inner))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let start: usize = self.pos;
+                while self.bytes.get(self.pos).is_some_and(u8::is_ascii_digit) {
+                    self.pos += 1;
+                }
+                let digits: &str = std::str::from_utf8(&self.bytes[start..self.pos])
+                    .expect("ASCII digits are UTF-8");
+                match digits.parse::<i64>() {
+                    Ok(n) => Ok(self.builder.num(n)),
+                    Err(_) => Err(ParseError {
+                        offset: start,
+                        message: "number too large",
+                    }),
+                }
+            }
+            Some(c) if c.is_ascii_lowercase() => {
+                self.pos += 1;
+                Ok(self.builder.var(char::from(c)))
+            }
+            Some(_) => Err(self.error("expected a number, variable or `(`")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        self.skip_spaces();
+        let found: bool = self.bytes.get(self.pos) == Some(&c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.bytes.get(self.pos) == Some(&b' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: &'static str) -> ParseError {
+        ParseError {
+            offset: self.pos,
+            message,
+        }
+    }
+}
+
+/// `count` spreadsheet-style formulas assembled from a handful of common
+/// terms, so many formulas share subterms and some repeat outright.
+pub fn sample_formulas(count: usize) -> Vec<String> {
+    const TERMS: [&str; 6] = [
+        "r * h",
+        "(b + k) * 2",
+        "t * (1 - d)",
+        "-c",
+        "q * q * p",
+        "(r * h + o) * 3",
+    ];
+    (0..count)
+        .map(|i| {
+            let first: &str = TERMS[i % TERMS.len()];
+            let second: &str = TERMS[(i * 7 / 3) % TERMS.len()];
+            match i % 4 {
+                0 => format!("{first} + {second}"),
+                1 => format!("({first}) * ({second})"),
+                2 => format!("{first} - {second} + {}", i % 5),
+                _ => format!("{first} + {second} * {}", i % 3),
+            }
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// Demonstrates a node request that hits the pool: both `x * y` subtrees of
+/// one expression come back as the same allocation.
+pub fn interning_nodes(out: &mut dyn Write) -> io::Result<()> {
+    let mut pool: ExprPool = ExprPool::new();
+    let expr: Rc<Expr> = parse(&mut pool, "x * y + x * y").expect("valid expression");
+
+    writeln!(out, "\n  Parsed: {expr}")?;
+    if let Expr::Add(left, right) = &*expr {
+        writeln!(
+            out,
+            "  Both `x * y` operands are one allocation: Rc::ptr_eq = {}",
+            Rc::ptr_eq(left, right)
+        )?;
+    }
+    writeln!(
+        out,
+        "  7 node requests ({} hits) -> {} distinct nodes: x, y, x * y, the sum",
+        pool.hits,
+        pool.len()
+    )?;
+
+    writeln!(
+        out,
+        "\n  The sum's key is Add(&x * y, &x * y): two addresses, {} bytes, hashed",
+        size_of::<ExprKey>()
+    )?;
+    writeln!(
+        out,
+        "  and compared in one step however deep the operands are."
+    )?;
+
+    let again: Rc<Expr> = parse(&mut pool, "(x * y) + (x * y)").expect("valid expression");
+    writeln!(
+        out,
+        "\n  Parsing `(x * y) + (x * y)` again allocates nothing: pool still {} nodes, same root = {}",
+        pool.len(),
+        Rc::ptr_eq(&expr, &again)
+    )?;
+    Ok(())
+}
+
+/// Demonstrates the memory saving on a tree whose subtrees repeat:
+/// `fib_expr` rebuilds every subtree from scratch on each use.
+pub fn shared_subtrees(out: &mut dyn Write) -> io::Result<()> {
+    let n: u32 = 20;
+    let mut plain: TreeBuilder = TreeBuilder::default();
+    let tree: Rc<Expr> = fib_expr(&mut plain, n);
+    let mut pool: ExprPool = ExprPool::new();
+    let consed: Rc<Expr> = fib_expr(&mut pool, n);
+
+    let vars = |name: char| if name == 'x' { 0 } else { 1 };
+    writeln!(
+        out,
+        "\n  fib_expr({n}): {} nodes as a tree, value with x = 0, y = 1: {}",
+        tree_size(&tree),
+        tree.eval(&vars)
+    )?;
+    writeln!(
+        out,
+        "\n  {:<14} {:>10} {:>12} {:>12}",
+        "builder", "requests", "allocations", "heap bytes"
+    )?;
+    writeln!(
+        out,
+        "  {:<14} {:>10} {:>12} {:>12}",
+        "TreeBuilder",
+        plain.allocated,
+        distinct_nodes(&tree),
+        distinct_nodes(&tree) * NODE_BYTES
+    )?;
+    writeln!(
+        out,
+        "  {:<14} {:>10} {:>12} {:>12}",
+        "ExprPool",
+        pool.len() + pool.hits,
+        pool.len(),
+        pool.heap_bytes()
+    )?;
+    writeln!(
+        out,
+        "\n  Same tree either way: tree_size = {}, eval = {}, == {}",
+        tree_size(&consed),
+        consed.eval(&vars),
+        *tree == *consed
+    )?;
+    writeln!(
+        out,
+        "  The pool's bytes include its table; {NODE_BYTES} bytes per Rc<Expr> node."
+    )?;
+    Ok(())
+}
+
+/// Demonstrates equality on hash-consed nodes: the derived `==` compares
+/// two trees node by node, a pooled pair is equal iff it is one pointer.
+pub fn constant_time_equality(out: &mut dyn Write) -> io::Result<()> {
+    let n: u32 = 16;
+    let mut plain: TreeBuilder = TreeBuilder::default();
+    let first: Rc<Expr> = fib_expr(&mut plain, n);
+    let second: Rc<Expr> = fib_expr(&mut plain, n);
+
+    let mut visits: u64 = 0;
+    let equal: bool = structural_eq(&first, &second, &mut visits);
+    writeln!(
+        out,
+        "\n  Two separately built fib_expr({n}) trees: equal = {equal} after visiting {visits} node pairs"
+    )?;
+
+    let mut pool: ExprPool = ExprPool::new();
+    let a: Rc<Expr> = fib_expr(&mut pool, n);
+    let b: Rc<Expr> = fib_expr(&mut pool, n);
+    let c: Rc<Expr> = fib_expr(&mut pool, n - 1);
+    writeln!(
+        out,
+        "  The same from one pool: Rc::ptr_eq = {}, one compare",
+        Rc::ptr_eq(&a, &b)
+    )?;
+    writeln!(
+        out,
+        "  fib_expr({n}) vs fib_expr({}): Rc::ptr_eq = {}, also one compare",
+        n - 1,
+        Rc::ptr_eq(&a, &c)
+    )?;
+    writeln!(
+        out,
+        "\n  Hashing follows the same rule: a pooled node's key hashes {} bytes,",
+        size_of::<ExprKey>()
+    )?;
+    writeln!(
+        out,
+        "  where the derived Hash on Expr walks all {} nodes of the tree.",
+        tree_size(&a)
+    )?;
+    Ok(())
+}
+
+/// Practical example: parse a sheet of formulas through one pool. Common
+/// subterms are stored once, and finding duplicate formulas is a set of
+/// pointers instead of comparing trees.
+pub fn practical_formula_dedup(out: &mut dyn Write) -> io::Result<()> {
+    let formulas: Vec<String> = sample_formulas(2_000);
+    writeln!(out, "\n  {} formulas, for example:", formulas.len())?;
+    for formula in &formulas[..4] {
+        writeln!(out, "    {formula}")?;
+    }
+
+    let mut plain: TreeBuilder = TreeBuilder::default();
+    let mut pool: ExprPool = ExprPool::new();
+    let mut roots: Vec<Rc<Expr>> = Vec::with_capacity(formulas.len());
+    for formula in &formulas {
+        parse(&mut plain, formula).expect("sample formulas parse");
+        roots.push(parse(&mut pool, formula).expect("sample formulas parse"));
+    }
+
+    writeln!(
+        out,
+        "\n  TreeBuilder: {} nodes, {} heap bytes",
+        plain.allocated,
+        plain.allocated * NODE_BYTES
+    )?;
+    writeln!(
+        out,
+        "  ExprPool:    {} nodes, {} heap bytes including the table ({} requests hit)",
+        pool.len(),
+        pool.heap_bytes(),
+        pool.hits
+    )?;
+
+    // Pointer identity is formula identity, so dedup is a pointer set
+    let mut uses: FxHashMap<*const Expr, usize> = FxHashMap::default();
+    for root in &roots {
+        *uses.entry(Rc::as_ptr(root)).or_default() += 1;
+    }
+    let most_repeated: usize = uses.values().copied().max().unwrap_or(0);
+    writeln!(
+        out,
+        "\n  {} distinct formulas; the most repeated one appears {most_repeated} times",
+        uses.len()
+    )?;
+
+    let shared: &Rc<Expr> = &roots[0];
+    let vars = |name: char| name as i64 - 'a' as i64;
+    writeln!(
+        out,
+        "  `{}` evaluates to {} with a = 0, b = 1, ...",
+        shared,
+        shared.eval(&vars)
+    )?;
+    Ok(())
+}
@@ -0,0 +1,404 @@
+//! hashbrown - The Map Behind std's HashMap, With Its Extra APIs
+//!
+//! std's `HashMap` is a wrapper around the `hashbrown` crate. Using
+//! hashbrown directly gives the same table and the same hashers, plus
+//! lookup APIs std keeps unstable or doesn't expose:
+//!
+//! - `entry_ref(&str)`: an entry for a `String`-keyed map without owning
+//!   the key first. The `String` is built only if the entry is vacant,
+//!   where std's `entry(key.to_owned())` allocates on every call
+//! - `raw_entry_mut()`: find or insert by a key *and* a hash the caller
+//!   computed, so one hash can serve several lookups
+//! - `raw_entry().from_key_hashed_nocheck(hash, key)`: the same for reads
+//!
+//! The usual alternative to `entry`, `get_mut` and then `insert` on a
+//! miss, avoids the allocation on hits but hashes the key twice on every
+//! miss. [`CountingState`] counts the hashes so the difference is visible.
+//!
+//! The pre-hashed APIs trust the caller: a hash from a different builder
+//! (or a different key) doesn't panic, it just looks in the wrong place.
+
+use demo_framework::section;
+use foldhash::fast::{FixedState, RandomState as FoldRandomState};
+use hashbrown::hash_map::{EntryRef, RawEntryMut};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::hint::black_box;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Requests in `double_hashing` and `prehashed_lookups`.
+const REQUESTS: usize = 20_000;
+
+/// Distinct session keys among them.
+const SESSIONS: usize = 1_000;
+
+/// Requests timed per pattern in `cache_timing`.
+const TIMED_REQUESTS: usize = 200_000;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "double_hashing",
+        "Hashes and key allocations per lookup-or-insert pattern",
+        double_hashing,
+    )?;
+
+    section(
+        out,
+        "prehashed_lookups",
+        "Hashing a key once for a two-tier cache with raw_entry",
+        prehashed_lookups,
+    )?;
+
+    section(
+        out,
+        "cache_timing",
+        "Timing each pattern on a String-keyed cache",
+        cache_timing,
+    )?;
+
+    Ok(())
+}
+
+/// A `BuildHasher` that counts how many keys it has hashed: every
+/// `build_hasher()` call is one hash. Clones share the seed and the count.
+#[derive(Clone)]
+pub struct CountingState {
+    inner: FixedState,
+    hashes: Rc<Cell<u64>>,
+}
+
+impl CountingState {
+    /// A foldhash builder with a fixed `seed`, so runs are repeatable and
+    /// two seeds give two unrelated hash functions.
+    pub fn with_seed(seed: u64) -> Self {
+        CountingState {
+            inner: FixedState::with_seed(seed),
+            hashes: Rc::new(Cell::new(0)),
+        }
+    }
+
+    /// Keys hashed since creation or the last `reset`.
+    pub fn hashes(&self) -> u64 {
+        self.hashes.get()
+    }
+
+    pub fn reset(&self) {
+        self.hashes.set(0);
+    }
+}
+
+impl BuildHasher for CountingState {
+    type Hasher = <FixedState as BuildHasher>::Hasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        self.hashes.set(self.hashes.get() + 1);
+        self.inner.build_hasher()
+    }
+}
+
+/// `count` requests spread over `distinct` session keys, every key seen
+/// once before any repeats.
+pub fn session_requests(count: usize, distinct: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| format!("session:{:05}", i % distinct))
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// One request, four ways. Each bumps the counter for `key` and returns it.
+// ---------------------------------------------------------------------------
+
+/// std without the entry API: `get_mut`, then `insert` an owned key on a
+/// miss. Allocates only on a miss, but a miss hashes the key twice.
+pub fn count_get_then_insert<S: BuildHasher>(map: &mut HashMap<String, u64, S>, key: &str) -> u64 {
+    if let Some(count) = map.get_mut(key) {
+        *count += 1;
+        return *count;
+    }
+    map.insert(key.to_owned(), 1);
+    1
+}
+
+/// std's entry API: one hash, but `entry` takes the key by value, so
+/// every call allocates a `String`, hit or miss.
+pub fn count_entry_owned<S: BuildHasher>(map: &mut HashMap<String, u64, S>, key: &str) -> u64 {
+    *map.entry(key.to_owned())
+        .and_modify(|count| *count += 1)
+        .or_insert(1)
+}
+
+/// hashbrown's `entry_ref`: one hash, and the `String` is built from the
+/// `&str` (via `From<&str>`) only when the entry is vacant.
+pub fn count_entry_ref<S: BuildHasher>(
+    map: &mut hashbrown::HashMap<String, u64, S>,
+    key: &str,
+) -> u64 {
+    *map.entry_ref(key)
+        .and_modify(|count| *count += 1)
+        .or_insert(1)
+}
+
+/// hashbrown's raw entry with a hash computed up front: the same one hash
+/// and one allocation per miss as `entry_ref`, spelled out. The hash could
+/// have come from anywhere, which is what `prehashed_lookups` uses.
+pub fn count_raw_entry<S: BuildHasher>(
+    map: &mut hashbrown::HashMap<String, u64, S>,
+    key: &str,
+) -> u64 {
+    let hash: u64 = map.hasher().hash_one(key);
+    match map.raw_entry_mut().from_key_hashed_nocheck(hash, key) {
+        RawEntryMut::Occupied(mut entry) => {
+            *entry.get_mut() += 1;
+            *entry.get()
+        }
+        RawEntryMut::Vacant(entry) => {
+            entry.insert_hashed_nocheck(hash, key.to_owned(), 1);
+            1
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// Demonstrates how many hashes and key allocations each pattern costs
+pub fn double_hashing(out: &mut dyn Write) -> io::Result<()> {
+    let requests: Vec<String> = session_requests(REQUESTS, SESSIONS);
+    writeln!(
+        out,
+        "\n  Counting {} requests over {} session keys (String keys, &str requests):",
+        REQUESTS, SESSIONS
+    )?;
+    writeln!(
+        out,
+        "    {:<28} {:>8} {:>14}",
+        "Pattern", "hashes", "Strings built"
+    )?;
+
+    // Presized, so growing the table never rehashes and every count below
+    // is a lookup
+    let state: CountingState = CountingState::with_seed(7);
+    let mut std_map: HashMap<String, u64, CountingState> =
+        HashMap::with_capacity_and_hasher(SESSIONS, state.clone());
+    let mut owned_built: usize = 0;
+    let mut rows: Vec<(&str, u64, usize)> = Vec::new();
+
+    for key in &requests {
+        count_get_then_insert(&mut std_map, key);
+    }
+    rows.push(("get_mut, else insert", state.hashes(), std_map.len()));
+
+    std_map.clear();
+    state.reset();
+    for key in &requests {
+        count_entry_owned(&mut std_map, key);
+        owned_built += 1;
+    }
+    rows.push(("entry(key.to_owned())", state.hashes(), owned_built));
+    let expected: HashMap<String, u64, CountingState> = std_map;
+
+    let mut brown: hashbrown::HashMap<String, u64, CountingState> =
+        hashbrown::HashMap::with_capacity_and_hasher(SESSIONS, state.clone());
+    state.reset();
+    let mut vacant: usize = 0;
+    for key in &requests {
+        // The same as count_entry_ref, with the vacant case counted
+        match brown.entry_ref(key.as_str()) {
+            EntryRef::Occupied(mut entry) => *entry.get_mut() += 1,
+            EntryRef::Vacant(entry) => {
+                vacant += 1;
+                entry.insert(1);
+            }
+        }
+    }
+    rows.push(("hashbrown entry_ref(&str)", state.hashes(), vacant));
+    let entry_ref_agrees: bool = brown.iter().all(|(key, count)| expected[key] == *count);
+
+    brown.clear();
+    state.reset();
+    for key in &requests {
+        count_raw_entry(&mut brown, key);
+    }
+    rows.push(("hashbrown raw_entry_mut", state.hashes(), brown.len()));
+    let raw_entry_agrees: bool = brown.iter().all(|(key, count)| expected[key] == *count);
+
+    for (pattern, hashes, built) in &rows {
+        writeln!(out, "    {:<28} {:>8} {:>14}", pattern, hashes, built)?;
+    }
+    writeln!(
+        out,
+        "\n    Same counts from every pattern: {}",
+        entry_ref_agrees && raw_entry_agrees && expected.len() == SESSIONS
+    )?;
+
+    writeln!(
+        out,
+        "\n    get_mut-then-insert hashes each new key twice (the very first get_mut"
+    )?;
+    writeln!(
+        out,
+        "    finds an empty map and skips hashing). entry(to_owned()) builds a String"
+    )?;
+    writeln!(
+        out,
+        "    for every request and drops it on a hit. entry_ref does neither."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates reusing one hash across two maps with `raw_entry`
+pub fn prehashed_lookups(out: &mut dyn Write) -> io::Result<()> {
+    // A small hot tier in front of the full cache. Both use clones of one
+    // builder, so a key hashes to the same value in each.
+    let state: CountingState = CountingState::with_seed(7);
+    let mut hot: hashbrown::HashMap<String, u64, CountingState> =
+        hashbrown::HashMap::with_capacity_and_hasher(64, state.clone());
+    let mut cold: hashbrown::HashMap<String, u64, CountingState> =
+        hashbrown::HashMap::with_capacity_and_hasher(SESSIONS, state.clone());
+    for (i, key) in session_requests(SESSIONS, SESSIONS).into_iter().enumerate() {
+        if i < 50 {
+            hot.insert(key.clone(), i as u64);
+        }
+        cold.insert(key, i as u64);
+    }
+    let requests: Vec<String> = session_requests(REQUESTS, SESSIONS);
+
+    state.reset();
+    let mut plain_sum: u64 = 0;
+    for key in &requests {
+        let found: Option<&u64> = hot.get(key.as_str()).or_else(|| cold.get(key.as_str()));
+        plain_sum += found.copied().unwrap_or(0);
+    }
+    let plain_hashes: u64 = state.hashes();
+
+    state.reset();
+    let mut prehashed_sum: u64 = 0;
+    for key in &requests {
+        let hash: u64 = state.hash_one(key.as_str());
+        let found: Option<&u64> = hot
+            .raw_entry()
+            .from_key_hashed_nocheck(hash, key.as_str())
+            .or_else(|| cold.raw_entry().from_key_hashed_nocheck(hash, key.as_str()))
+            .map(|(_, value)| value);
+        prehashed_sum += found.copied().unwrap_or(0);
+    }
+    let prehashed_hashes: u64 = state.hashes();
+
+    writeln!(
+        out,
+        "\n  {} lookups, hot tier of 50 keys in front of {} (5% hot hits):",
+        REQUESTS, SESSIONS
+    )?;
+    writeln!(
+        out,
+        "    hot.get(k).or_else(|| cold.get(k))   {:>6} hashes",
+        plain_hashes
+    )?;
+    writeln!(
+        out,
+        "    hash once, raw_entry() on each tier  {:>6} hashes",
+        prehashed_hashes
+    )?;
+    writeln!(out, "    Same values found: {}", plain_sum == prehashed_sum)?;
+
+    // The hash must come from the same builder. A hash from another seed
+    // isn't an error: the lookup probes the wrong buckets and misses.
+    let other: CountingState = CountingState::with_seed(8);
+    let found_with_wrong_hash: usize = cold
+        .keys()
+        .filter(|key| {
+            let wrong: u64 = other.hash_one(key.as_str());
+            cold.raw_entry()
+                .from_key_hashed_nocheck(wrong, key.as_str())
+                .is_some()
+        })
+        .count();
+    writeln!(
+        out,
+        "\n  The same {} keys looked up with hashes from a different seed:",
+        cold.len()
+    )?;
+    writeln!(
+        out,
+        "    found {} of {} - no panic, no error, just misses",
+        found_with_wrong_hash,
+        cold.len()
+    )?;
+
+    writeln!(
+        out,
+        "\n    \"nocheck\" means hashbrown trusts the hash. Only compute it with the map's"
+    )?;
+    writeln!(
+        out,
+        "    own builder (map.hasher().hash_one(k)) or a clone of it."
+    )?;
+
+    Ok(())
+}
+
+/// Times `TIMED_REQUESTS` calls of `count` on a fresh map from `make`.
+fn time_pattern<M>(
+    requests: &[String],
+    make: impl Fn() -> M,
+    count: impl Fn(&mut M, &str) -> u64,
+) -> Duration {
+    let mut map: M = make();
+    let start: Instant = Instant::now();
+    for key in requests {
+        black_box(count(&mut map, key));
+    }
+    start.elapsed()
+}
+
+/// Demonstrates what the saved hashes and allocations are worth in time
+pub fn cache_timing(out: &mut dyn Write) -> io::Result<()> {
+    // 10k sessions, 20 requests each: a 95% hit rate
+    let requests: Vec<String> = session_requests(TIMED_REQUESTS, 10_000);
+    writeln!(
+        out,
+        "\n  {} requests over 10000 sessions, foldhash for every map:",
+        TIMED_REQUESTS
+    )?;
+
+    let std_map = HashMap::<String, u64, FoldRandomState>::default;
+    let brown_map = hashbrown::HashMap::<String, u64, FoldRandomState>::default;
+    let timings: [(&str, Duration); 4] = [
+        (
+            "get_mut, else insert",
+            time_pattern(&requests, std_map, count_get_then_insert),
+        ),
+        (
+            "entry(key.to_owned())",
+            time_pattern(&requests, std_map, count_entry_owned),
+        ),
+        (
+            "hashbrown entry_ref(&str)",
+            time_pattern(&requests, brown_map, count_entry_ref),
+        ),
+        (
+            "hashbrown raw_entry_mut",
+            time_pattern(&requests, brown_map, count_raw_entry),
+        ),
+    ];
+    for (pattern, elapsed) in &timings {
+        writeln!(out, "    {:<28} {:?}", pattern, elapsed)?;
+    }
+
+    writeln!(
+        out,
+        "\n    With mostly hits, entry(to_owned()) pays an allocation and a free per"
+    )?;
+    writeln!(
+        out,
+        "    request for nothing. The Cache_Lookup benchmark measures this properly."
+    )?;
+
+    Ok(())
+}
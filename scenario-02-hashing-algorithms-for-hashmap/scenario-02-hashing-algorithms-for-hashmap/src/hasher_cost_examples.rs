@@ -0,0 +1,307 @@
+//! Hasher Construction Costs - What a Map Pays Before Its First Insert
+//!
+//! The throughput benchmarks measure hashing many keys with one hasher.
+//! Code that creates many short-lived maps pays two other costs per map,
+//! and they can outweigh the hashing itself:
+//!
+//! - Building the `BuildHasher` (`RandomState::new()` and friends). Seeded
+//!   hashers need random keys; std fetches them from the OS once per
+//!   thread and then just bumps a counter, aHash and foldhash seed from a
+//!   process-wide value, and the fixed hashers (FxHash, NoHash) are
+//!   zero-sized and free
+//! - Calling `build_hasher()` once per hashed key. The Hasher's state is
+//!   copied out of the builder every time, so its size matters: 8 bytes
+//!   for FxHash, several hundred for xxh3's streaming buffer
+//!
+//! The builder also lives inside every `HashMap<K, V, S>`, so its size is
+//! added to each map - noticeable when maps are themselves values in a
+//! bigger collection.
+
+use ahash::RandomState as AHashRandomState;
+use demo_framework::section;
+use foldhash::fast::RandomState as FoldRandomState;
+use nohash_hasher::BuildNoHashHasher;
+use rustc_hash::FxBuildHasher;
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::hint::black_box;
+use std::io::{self, Write};
+use std::mem::size_of;
+use std::thread;
+use std::time::{Duration, Instant};
+use twox_hash::xxhash64::RandomState as XxHash64RandomState;
+use xxhash_rust::xxh3::Xxh3Builder;
+
+/// Maps built per hasher in `short_lived_maps`. The `Map_Construction`
+/// benchmark builds 1M.
+const TINY_MAPS: u64 = 100_000;
+
+/// Calls timed per hasher in `build_hasher_cost` and `random_state_cost`.
+const CALLS: u32 = 100_000;
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "state_sizes",
+        "size_of each BuildHasher, its Hasher, and a HashMap using it",
+        state_sizes,
+    )?;
+
+    section(
+        out,
+        "build_hasher_cost",
+        "The cost of build_hasher() + finish() with no data hashed",
+        build_hasher_cost,
+    )?;
+
+    section(
+        out,
+        "random_state_cost",
+        "Creating a seeded BuildHasher, and std's OS entropy fetch per thread",
+        random_state_cost,
+    )?;
+
+    section(
+        out,
+        "short_lived_maps",
+        "Creating, filling and dropping many tiny maps per hasher",
+        short_lived_maps,
+    )?;
+
+    Ok(())
+}
+
+/// Builds a map with `state`, inserts three `u64` keys derived from `seed`
+/// and drops it; returns the length so the work can't be optimized away.
+///
+/// This is the per-map unit of work in `short_lived_maps` and in the
+/// `Map_Construction` benchmark.
+pub fn fill_tiny_map<S: BuildHasher>(state: S, seed: u64) -> usize {
+    let mut map: HashMap<u64, u64, S> = HashMap::with_hasher(state);
+    for i in 0..3 {
+        map.insert(seed.wrapping_mul(31).wrapping_add(i), i);
+    }
+    map.len()
+}
+
+/// Sizes in bytes of a builder, the hasher it builds, and a map using it.
+struct Sizes {
+    builder: usize,
+    hasher: usize,
+    map: usize,
+}
+
+fn sizes_of<S: BuildHasher>() -> Sizes {
+    Sizes {
+        builder: size_of::<S>(),
+        hasher: size_of::<S::Hasher>(),
+        map: size_of::<HashMap<u64, u64, S>>(),
+    }
+}
+
+/// Demonstrates how much state each hasher carries
+pub fn state_sizes(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Sizes in bytes (64-bit target):")?;
+    writeln!(
+        out,
+        "    {:<10} {:>8} {:>8} {:>20}",
+        "Hasher", "builder", "hasher", "HashMap<u64, u64, S>"
+    )?;
+
+    let rows: [(&str, Sizes); 7] = [
+        ("SipHash", sizes_of::<RandomState>()),
+        ("FxHash", sizes_of::<FxBuildHasher>()),
+        ("aHash", sizes_of::<AHashRandomState>()),
+        ("Foldhash", sizes_of::<FoldRandomState>()),
+        ("xxHash64", sizes_of::<XxHash64RandomState>()),
+        ("xxh3", sizes_of::<Xxh3Builder>()),
+        ("NoHash", sizes_of::<BuildNoHashHasher<u64>>()),
+    ];
+    for (name, sizes) in &rows {
+        writeln!(
+            out,
+            "    {:<10} {:>8} {:>8} {:>20}",
+            name, sizes.builder, sizes.hasher, sizes.map
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\n    A HashMap is its table pointer, mask and counts plus the builder:"
+    )?;
+    writeln!(
+        out,
+        "    zero-sized builders (FxHash, NoHash) give the smallest map."
+    )?;
+    writeln!(
+        out,
+        "    The hasher is created per hashed key; xxh3's carries a streaming buffer."
+    )?;
+
+    Ok(())
+}
+
+/// Times `CALLS` calls of `build_hasher()` followed by `finish()`.
+fn time_build_hasher<S: BuildHasher>(state: &S) -> Duration {
+    let start: Instant = Instant::now();
+    for _ in 0..CALLS {
+        let hasher: S::Hasher = state.build_hasher();
+        black_box(black_box(hasher).finish());
+    }
+    start.elapsed()
+}
+
+/// Demonstrates the per-key cost of creating a hasher
+pub fn build_hasher_cost(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "\n  build_hasher() + finish() with nothing hashed ({} calls):",
+        CALLS
+    )?;
+
+    let timings: [(&str, Duration); 7] = [
+        ("SipHash", time_build_hasher(&RandomState::new())),
+        ("FxHash", time_build_hasher(&FxBuildHasher)),
+        ("aHash", time_build_hasher(&AHashRandomState::new())),
+        ("Foldhash", time_build_hasher(&FoldRandomState::default())),
+        (
+            "xxHash64",
+            time_build_hasher(&XxHash64RandomState::default()),
+        ),
+        ("xxh3", time_build_hasher(&Xxh3Builder::new())),
+        (
+            "NoHash",
+            time_build_hasher(&BuildNoHashHasher::<u64>::default()),
+        ),
+    ];
+    for (name, elapsed) in &timings {
+        writeln!(out, "    {:<10} {:?}", name, elapsed)?;
+    }
+
+    writeln!(
+        out,
+        "\n    Every get/insert pays this once. For an 8-byte key it can cost as much as"
+    )?;
+    writeln!(
+        out,
+        "    the hashing, so small hasher state helps short keys most."
+    )?;
+
+    Ok(())
+}
+
+/// Times `CALLS` builder constructions with `make`.
+fn time_construction<S>(make: impl Fn() -> S) -> Duration {
+    let start: Instant = Instant::now();
+    for _ in 0..CALLS {
+        black_box(make());
+    }
+    start.elapsed()
+}
+
+/// Demonstrates what creating a seeded builder costs
+pub fn random_state_cost(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Creating a BuildHasher ({} times):", CALLS)?;
+
+    let timings: [(&str, Duration); 5] = [
+        ("RandomState::new()", time_construction(RandomState::new)),
+        (
+            "ahash::RandomState::new()",
+            time_construction(AHashRandomState::new),
+        ),
+        (
+            "foldhash RandomState",
+            time_construction(FoldRandomState::default),
+        ),
+        (
+            "xxhash64 RandomState",
+            time_construction(XxHash64RandomState::default),
+        ),
+        ("FxBuildHasher", time_construction(|| FxBuildHasher)),
+    ];
+    for (name, elapsed) in &timings {
+        writeln!(out, "    {:<26} {:?}", name, elapsed)?;
+    }
+
+    // std keeps its keys in a thread-local: the first RandomState::new() on
+    // a thread asks the OS for random bytes, later ones add 1 to k0
+    let (first, later): (Duration, Duration) = thread::spawn(|| {
+        let start: Instant = Instant::now();
+        black_box(RandomState::new());
+        let first: Duration = start.elapsed();
+        let start: Instant = Instant::now();
+        black_box(RandomState::new());
+        (first, start.elapsed())
+    })
+    .join()
+    .expect("timing thread doesn't panic");
+
+    writeln!(out, "\n  On a fresh thread:")?;
+    writeln!(
+        out,
+        "    First RandomState::new():  {:?} (OS entropy fetch)",
+        first
+    )?;
+    writeln!(
+        out,
+        "    Second RandomState::new(): {:?} (counter bump)",
+        later
+    )?;
+    writeln!(
+        out,
+        "    Two maps from the same thread get keys one apart, still a different hash"
+    )?;
+    writeln!(
+        out,
+        "    order each. A thread pool pays the fetch once per worker, not per map."
+    )?;
+
+    Ok(())
+}
+
+/// Times `TINY_MAPS` maps built with `make`, each filled and dropped.
+fn time_tiny_maps<S: BuildHasher>(make: impl Fn() -> S) -> Duration {
+    let start: Instant = Instant::now();
+    for seed in 0..TINY_MAPS {
+        black_box(fill_tiny_map(make(), seed));
+    }
+    start.elapsed()
+}
+
+/// Demonstrates the total cost of many short-lived maps per hasher
+pub fn short_lived_maps(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "\n  {} maps, each: new builder, 3 inserts, drop:",
+        TINY_MAPS
+    )?;
+
+    let timings: [(&str, Duration); 7] = [
+        ("SipHash", time_tiny_maps(RandomState::new)),
+        ("FxHash", time_tiny_maps(|| FxBuildHasher)),
+        ("aHash", time_tiny_maps(AHashRandomState::new)),
+        ("Foldhash", time_tiny_maps(FoldRandomState::default)),
+        ("xxHash64", time_tiny_maps(XxHash64RandomState::default)),
+        ("xxh3", time_tiny_maps(Xxh3Builder::new)),
+        ("NoHash", time_tiny_maps(BuildNoHashHasher::<u64>::default)),
+    ];
+    for (name, elapsed) in &timings {
+        writeln!(out, "    {:<10} {:?}", name, elapsed)?;
+    }
+
+    writeln!(
+        out,
+        "\n    Every map allocates its table on the first insert, and in a release build"
+    )?;
+    writeln!(
+        out,
+        "    that allocation is most of the cost. What the hasher adds is mostly its"
+    )?;
+    writeln!(
+        out,
+        "    builder: a slower constructor (aHash) or a large state to move (xxh3)."
+    )?;
+
+    Ok(())
+}
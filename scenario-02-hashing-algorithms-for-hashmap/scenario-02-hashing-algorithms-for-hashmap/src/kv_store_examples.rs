@@ -0,0 +1,522 @@
+//! Storage Backends - One Key-Value API Over Five Implementations
+//!
+//! Most demos ask the same question of a map: insert, look up, update,
+//! remove. [`KvStore`] is that question as a trait, implemented by
+//!
+//! - `HashMap<K, V, S>` for any hasher - SipHash, FxHash, and NoHash's
+//!   `IntMap` are all this one impl
+//! - `BTreeMap<K, V>`
+//! - [`SortedVecMap`]: pairs in a sorted `Vec`, binary search to find a
+//!   key, O(n) inserts and removes
+//! - [`ChainedTable`]: the teaching table from `bucket_visual`
+//!
+//! Code written against `S: KvStore<K, V>` is monomorphized per backend,
+//! so every call is direct. To sweep backends picked at runtime - a table
+//! of timings, a benchmark loop - [`AnyStore`] wraps one of them in an enum
+//! and forwards each call with a `match`: no boxing, and one cheap
+//! branch per call. It uses `u64` keys, which every backend (IntMap
+//! included) accepts, and [`Backend::ALL`] lists the choices.
+
+use crate::bucket_visual::ChainedTable;
+use demo_framework::section;
+use nohash_hasher::IntMap;
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+use std::hint::black_box;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "generic_over_backends",
+        "One inverted index function, four storage backends",
+        generic_over_backends,
+    )?;
+
+    section(
+        out,
+        "sweep_backends",
+        "Running one workload over every backend chosen at runtime",
+        sweep_backends,
+    )?;
+
+    section(
+        out,
+        "enum_vs_generic",
+        "What enum dispatch costs next to a monomorphized call",
+        enum_vs_generic,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// The trait
+// ---------------------------------------------------------------------------
+
+/// The map operations the demos need, over any backend.
+pub trait KvStore<K, V> {
+    /// Inserts or replaces; returns the previous value for `key`.
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+    fn get(&self, key: &K) -> Option<&V>;
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V>;
+
+    fn remove(&mut self, key: &K) -> Option<V>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Applies `update` to the value for `key`, inserting `default` first
+    /// if there is none - the `entry().or_insert()` pattern for backends
+    /// without an entry API.
+    fn upsert(&mut self, key: K, default: V, update: impl FnOnce(&mut V))
+    where
+        Self: Sized,
+    {
+        match self.get_mut(&key) {
+            Some(value) => update(value),
+            None => {
+                let mut value: V = default;
+                update(&mut value);
+                self.insert(key, value);
+            }
+        }
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> KvStore<K, V> for HashMap<K, V, S> {
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        HashMap::insert(self, key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        HashMap::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        HashMap::remove(self, key)
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+impl<K: Ord, V> KvStore<K, V> for BTreeMap<K, V> {
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        BTreeMap::insert(self, key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        BTreeMap::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        BTreeMap::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        BTreeMap::remove(self, key)
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> KvStore<K, V> for ChainedTable<K, V, S> {
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        ChainedTable::insert(self, key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        ChainedTable::get(self, key)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        ChainedTable::get_mut(self, key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        ChainedTable::remove(self, key)
+    }
+
+    fn len(&self) -> usize {
+        ChainedTable::len(self)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Sorted Vec backend
+// ---------------------------------------------------------------------------
+
+/// A map kept as `(key, value)` pairs sorted by key.
+///
+/// Lookups are a binary search over one contiguous array; inserts and
+/// removes shift everything after the position. Small, read-mostly maps
+/// often beat both HashMap and BTreeMap this way.
+#[derive(Clone, Debug)]
+pub struct SortedVecMap<K, V> {
+    entries: Vec<(K, V)>,
+}
+
+impl<K: Ord, V> SortedVecMap<K, V> {
+    pub fn new() -> Self {
+        SortedVecMap {
+            entries: Vec::new(),
+        }
+    }
+
+    fn position(&self, key: &K) -> Result<usize, usize> {
+        self.entries.binary_search_by(|(k, _)| k.cmp(key))
+    }
+
+    /// Every entry in key order.
+    pub fn entries(&self) -> &[(K, V)] {
+        &self.entries
+    }
+}
+
+impl<K: Ord, V> Default for SortedVecMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> KvStore<K, V> for SortedVecMap<K, V> {
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.position(&key) {
+            Ok(i) => Some(std::mem::replace(&mut self.entries[i].1, value)),
+            Err(i) => {
+                self.entries.insert(i, (key, value));
+                None
+            }
+        }
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        let i: usize = self.position(key).ok()?;
+        Some(&self.entries[i].1)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let i: usize = self.position(key).ok()?;
+        Some(&mut self.entries[i].1)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let i: usize = self.position(key).ok()?;
+        Some(self.entries.remove(i).1)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Enum dispatch
+// ---------------------------------------------------------------------------
+
+/// The backends [`AnyStore`] can hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    HashMap,
+    BTreeMap,
+    IntMap,
+    SortedVec,
+    ChainedTable,
+}
+
+impl Backend {
+    /// Every backend, in the order tables and benchmarks list them.
+    pub const ALL: [Backend; 5] = [
+        Backend::HashMap,
+        Backend::BTreeMap,
+        Backend::IntMap,
+        Backend::SortedVec,
+        Backend::ChainedTable,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::HashMap => "HashMap",
+            Backend::BTreeMap => "BTreeMap",
+            Backend::IntMap => "IntMap",
+            Backend::SortedVec => "SortedVec",
+            Backend::ChainedTable => "ChainedTable",
+        }
+    }
+}
+
+/// One of the [`Backend`]s with `u64` keys, chosen at runtime.
+#[derive(Clone, Debug)]
+pub enum AnyStore<V> {
+    HashMap(HashMap<u64, V>),
+    BTreeMap(BTreeMap<u64, V>),
+    IntMap(IntMap<u64, V>),
+    SortedVec(SortedVecMap<u64, V>),
+    ChainedTable(ChainedTable<u64, V, RandomState>),
+}
+
+impl<V> AnyStore<V> {
+    /// An empty store on `backend`.
+    pub fn new(backend: Backend) -> Self {
+        match backend {
+            Backend::HashMap => AnyStore::HashMap(HashMap::new()),
+            Backend::BTreeMap => AnyStore::BTreeMap(BTreeMap::new()),
+            Backend::IntMap => AnyStore::IntMap(IntMap::default()),
+            Backend::SortedVec => AnyStore::SortedVec(SortedVecMap::new()),
+            Backend::ChainedTable => {
+                AnyStore::ChainedTable(ChainedTable::with_buckets(8, RandomState::new()))
+            }
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        match self {
+            AnyStore::HashMap(_) => Backend::HashMap,
+            AnyStore::BTreeMap(_) => Backend::BTreeMap,
+            AnyStore::IntMap(_) => Backend::IntMap,
+            AnyStore::SortedVec(_) => Backend::SortedVec,
+            AnyStore::ChainedTable(_) => Backend::ChainedTable,
+        }
+    }
+}
+
+impl<V> KvStore<u64, V> for AnyStore<V> {
+    fn insert(&mut self, key: u64, value: V) -> Option<V> {
+        match self {
+            AnyStore::HashMap(store) => KvStore::insert(store, key, value),
+            AnyStore::BTreeMap(store) => KvStore::insert(store, key, value),
+            AnyStore::IntMap(store) => KvStore::insert(store, key, value),
+            AnyStore::SortedVec(store) => KvStore::insert(store, key, value),
+            AnyStore::ChainedTable(store) => KvStore::insert(store, key, value),
+        }
+    }
+
+    fn get(&self, key: &u64) -> Option<&V> {
+        match self {
+            AnyStore::HashMap(store) => KvStore::get(store, key),
+            AnyStore::BTreeMap(store) => KvStore::get(store, key),
+            AnyStore::IntMap(store) => KvStore::get(store, key),
+            AnyStore::SortedVec(store) => KvStore::get(store, key),
+            AnyStore::ChainedTable(store) => KvStore::get(store, key),
+        }
+    }
+
+    fn get_mut(&mut self, key: &u64) -> Option<&mut V> {
+        match self {
+            AnyStore::HashMap(store) => KvStore::get_mut(store, key),
+            AnyStore::BTreeMap(store) => KvStore::get_mut(store, key),
+            AnyStore::IntMap(store) => KvStore::get_mut(store, key),
+            AnyStore::SortedVec(store) => KvStore::get_mut(store, key),
+            AnyStore::ChainedTable(store) => KvStore::get_mut(store, key),
+        }
+    }
+
+    fn remove(&mut self, key: &u64) -> Option<V> {
+        match self {
+            AnyStore::HashMap(store) => KvStore::remove(store, key),
+            AnyStore::BTreeMap(store) => KvStore::remove(store, key),
+            AnyStore::IntMap(store) => KvStore::remove(store, key),
+            AnyStore::SortedVec(store) => KvStore::remove(store, key),
+            AnyStore::ChainedTable(store) => KvStore::remove(store, key),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            AnyStore::HashMap(store) => KvStore::len(store),
+            AnyStore::BTreeMap(store) => KvStore::len(store),
+            AnyStore::IntMap(store) => KvStore::len(store),
+            AnyStore::SortedVec(store) => KvStore::len(store),
+            AnyStore::ChainedTable(store) => KvStore::len(store),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Workloads
+// ---------------------------------------------------------------------------
+
+/// Maps each word of `text` to the positions it appears at.
+pub fn build_index<S: KvStore<String, Vec<usize>>>(store: &mut S, text: &str) {
+    for (position, word) in text.split_whitespace().enumerate() {
+        let word: String = word
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        store.upsert(word, Vec::new(), |positions| positions.push(position));
+    }
+}
+
+/// A mixed workload over a pool of `keys` IDs: inserts, counter updates,
+/// lookups and removes in a fixed pseudo-random order, like components
+/// churning in an ECS resource table. Returns a checksum of everything
+/// read, so every backend must return the same one.
+pub fn churn<S: KvStore<u64, u64>>(store: &mut S, ops: usize, keys: u64) -> u64 {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut checksum: u64 = 0;
+    for op in 0..ops as u64 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let key: u64 = state % keys;
+        match state >> 61 {
+            0..=2 => {
+                store.insert(key, op);
+            }
+            3 => store.upsert(key, 0, |count| *count += 1),
+            4..=6 => checksum = checksum.wrapping_add(*store.get(&key).unwrap_or(&0)),
+            _ => checksum = checksum.wrapping_add(store.remove(&key).unwrap_or(0)),
+        }
+    }
+    checksum.wrapping_add(store.len() as u64)
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+const TEXT: &str = "The map stores the key. The key picks the bucket, and the bucket \
+                    holds the value; a map without keys is just a list.";
+
+/// Demonstrates one generic function running on four backends
+pub fn generic_over_backends(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "\n  build_index<S: KvStore<String, Vec<usize>>> on each backend:"
+    )?;
+
+    let mut hash: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut btree: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut sorted: SortedVecMap<String, Vec<usize>> = SortedVecMap::new();
+    let mut chained: ChainedTable<String, Vec<usize>, RandomState> =
+        ChainedTable::with_buckets(4, RandomState::new());
+    build_index(&mut hash, TEXT);
+    build_index(&mut btree, TEXT);
+    build_index(&mut sorted, TEXT);
+    build_index(&mut chained, TEXT);
+
+    for word in ["the", "key", "bucket", "tree"] {
+        let word: String = word.to_string();
+        let found: [Option<&Vec<usize>>; 4] = [
+            hash.get(&word),
+            btree.get(&word),
+            sorted.get(&word),
+            chained.get(&word),
+        ];
+        writeln!(
+            out,
+            "    {:<7} {:<28} same on all four: {}",
+            word,
+            format!("{:?}", found[0]),
+            found.iter().all(|positions| *positions == found[0])
+        )?;
+    }
+    writeln!(
+        out,
+        "    Distinct words: {} / {} / {} / {}",
+        hash.len(),
+        btree.len(),
+        sorted.len(),
+        chained.len()
+    )?;
+
+    writeln!(
+        out,
+        "\n    The function is compiled once per backend, so each call is direct."
+    )?;
+    writeln!(
+        out,
+        "    IntMap is missing: NoHash only hashes integer keys, not Strings."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates a workload swept over every backend picked at runtime
+pub fn sweep_backends(out: &mut dyn Write) -> io::Result<()> {
+    let ops: usize = 50_000;
+    let keys: u64 = 2_000;
+    writeln!(
+        out,
+        "\n  churn(): {} mixed operations over {} IDs, for each of Backend::ALL:",
+        ops, keys
+    )?;
+
+    let mut checksums: Vec<u64> = Vec::new();
+    for backend in Backend::ALL {
+        let mut store: AnyStore<u64> = AnyStore::new(backend);
+        let start: Instant = Instant::now();
+        let checksum: u64 = churn(&mut store, ops, keys);
+        let elapsed: Duration = start.elapsed();
+        checksums.push(checksum);
+        writeln!(
+            out,
+            "    {:<13} {:>5} live entries, {:?}",
+            store.backend().name(),
+            store.len(),
+            elapsed
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\n    Same checksum from every backend: {}",
+        checksums.windows(2).all(|pair| pair[0] == pair[1])
+    )?;
+    writeln!(
+        out,
+        "    The loop body is written once; adding a backend means one more enum variant."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates the cost of the `match` in `AnyStore`
+pub fn enum_vs_generic(out: &mut dyn Write) -> io::Result<()> {
+    let ops: usize = 200_000;
+    let keys: u64 = 2_000;
+    writeln!(out, "\n  churn() on a HashMap, {} operations:", ops)?;
+
+    let mut direct: HashMap<u64, u64> = HashMap::new();
+    let start: Instant = Instant::now();
+    let direct_sum: u64 = black_box(churn(&mut direct, ops, keys));
+    let direct_time: Duration = start.elapsed();
+
+    let mut wrapped: AnyStore<u64> = AnyStore::new(Backend::HashMap);
+    let start: Instant = Instant::now();
+    let wrapped_sum: u64 = black_box(churn(&mut wrapped, ops, keys));
+    let wrapped_time: Duration = start.elapsed();
+
+    writeln!(out, "    HashMap<u64, u64> directly:    {:?}", direct_time)?;
+    writeln!(out, "    AnyStore::HashMap via match:   {:?}", wrapped_time)?;
+    writeln!(out, "    Same result: {}", direct_sum == wrapped_sum)?;
+
+    writeln!(
+        out,
+        "\n    The match is one predictable branch per call; the hashing and probing"
+    )?;
+    writeln!(
+        out,
+        "    behind it cost far more. A Box<dyn KvStore> would add an indirect call"
+    )?;
+    writeln!(
+        out,
+        "    and a heap allocation, and couldn't offer the generic upsert() at all."
+    )?;
+
+    Ok(())
+}
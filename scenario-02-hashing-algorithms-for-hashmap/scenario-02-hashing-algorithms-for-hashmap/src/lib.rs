@@ -0,0 +1,139 @@
+//! Examples for the default SipHash hasher and its popular alternatives.
+//!
+//! Each module exposes its demos as `pub fn name(out: &mut dyn Write)` plus a
+//! `run_all` that prints all of them in order. [`MODULES`] registers them
+//! with the shared demo CLI; the snapshot tests run them against a buffer, and
+//! [`run_demo`] runs one of them for other programs.
+//! [`cheatsheet`] holds the hasher facts behind `cargo run -- cheatsheet`, and
+//! [`scoped_map`] the scope-stack maps behind the compiler front-end demo.
+//! [`auto_hash`] packages the README's hasher advice as `AutoHashMap`.
+
+// The examples spell out build_hasher() -> hash() -> finish() on purpose so
+// every hashing step is visible; `hash_one` would hide them.
+#![allow(clippy::manual_hash_one)]
+
+pub mod ab_testing_examples;
+pub mod ahash_examples;
+pub mod auto_hash;
+pub mod bucket_visual;
+pub mod cheatsheet;
+pub mod counting_hasher;
+pub mod deserialize_examples;
+pub mod fault_injection_examples;
+pub mod foldhash_examples;
+pub mod frontend_examples;
+pub mod fxhash_examples;
+pub mod hash_consing;
+pub mod hashbrown_examples;
+pub mod hasher_cost_examples;
+pub mod id_allocation_examples;
+pub mod kv_store_examples;
+pub mod memoization_examples;
+pub mod nohash_examples;
+pub mod pseudonymization_examples;
+pub mod sampling_examples;
+pub mod scoped_map;
+pub mod security_examples;
+pub mod siphash_examples;
+pub mod xxhash_examples;
+
+use demo_framework::Module;
+use demo_framework::embed::{self, DemoOutput, UnknownDemo};
+
+/// Demo registry, in the order the scenario's README introduces them.
+pub const MODULES: &[Module] = &[
+    Module {
+        name: "siphash_examples",
+        run: siphash_examples::run_all,
+    },
+    Module {
+        name: "fxhash_examples",
+        run: fxhash_examples::run_all,
+    },
+    Module {
+        name: "frontend_examples",
+        run: frontend_examples::run_all,
+    },
+    Module {
+        name: "hash_consing",
+        run: hash_consing::run_all,
+    },
+    Module {
+        name: "ahash_examples",
+        run: ahash_examples::run_all,
+    },
+    Module {
+        name: "foldhash_examples",
+        run: foldhash_examples::run_all,
+    },
+    Module {
+        name: "xxhash_examples",
+        run: xxhash_examples::run_all,
+    },
+    Module {
+        name: "nohash_examples",
+        run: nohash_examples::run_all,
+    },
+    Module {
+        name: "id_allocation_examples",
+        run: id_allocation_examples::run_all,
+    },
+    Module {
+        name: "sampling_examples",
+        run: sampling_examples::run_all,
+    },
+    Module {
+        name: "ab_testing_examples",
+        run: ab_testing_examples::run_all,
+    },
+    Module {
+        name: "pseudonymization_examples",
+        run: pseudonymization_examples::run_all,
+    },
+    Module {
+        name: "bucket_visual",
+        run: bucket_visual::run_all,
+    },
+    Module {
+        name: "security_examples",
+        run: security_examples::run_all,
+    },
+    Module {
+        name: "deserialize_examples",
+        run: deserialize_examples::run_all,
+    },
+    Module {
+        name: "hasher_cost_examples",
+        run: hasher_cost_examples::run_all,
+    },
+    Module {
+        name: "kv_store_examples",
+        run: kv_store_examples::run_all,
+    },
+    Module {
+        name: "hashbrown_examples",
+        run: hashbrown_examples::run_all,
+    },
+    Module {
+        name: "counting_hasher",
+        run: counting_hasher::run_all,
+    },
+    Module {
+        name: "memoization_examples",
+        run: memoization_examples::run_all,
+    },
+    Module {
+        name: "fault_injection_examples",
+        run: fault_injection_examples::run_all,
+    },
+    Module {
+        name: "auto_hash",
+        run: auto_hash::run_all,
+    },
+];
+
+/// Runs one module or demo by name and captures its text, timing and
+/// recorded values (see [`demo_framework::embed`]).
+pub fn run_demo(name: &str) -> Result<DemoOutput, UnknownDemo> {
+    embed::run_demo(MODULES, name)
+}
@@ -1,6 +1,18 @@
-use rustc_version_runtime;
+use demo_framework::alloc::TrackingAllocator;
+use demo_framework::cli::Cli;
+use demo_framework::report::SectionSummary;
+use std::process::ExitCode;
 
-fn main() {
-    println!("Hashing Algorithms for HashMap - Demo");
-    println!("Compiled with: {:?}", rustc_version_runtime::version());
+// Counts allocations for the per-section summary at the end of a run
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+fn main() -> ExitCode {
+    Cli::new(
+        "Hashing Algorithms for HashMap - Demo",
+        hashing_demo::MODULES,
+    )
+    .cheatsheet(hashing_demo::cheatsheet::tables)
+    .report(SectionSummary::new())
+    .run()
 }
@@ -0,0 +1,540 @@
+//! Memo Tables - Dynamic Programming With a HashMap
+//!
+//! A dynamic program can be written two ways:
+//!
+//! - **tabulation**: allocate a `Vec` with a cell for every subproblem and
+//!   fill it in dependency order. Every cell is computed, whether the final
+//!   answer needs it or not, and an index is just arithmetic
+//! - **memoization**: write the recursion directly and cache each answer in
+//!   a [`Memo`], a `HashMap` from subproblem to answer. Only subproblems the
+//!   recursion reaches are computed, and each costs a hash and a probe
+//!
+//! When nearly every state is reachable (edit distance between unrelated
+//! strings) the table wins, and the memo's hasher decides by how much.
+//! When the reachable states are a thin slice of a huge space (coin change
+//! with large coins, a knapsack measured in bytes) the table is mostly
+//! wasted or cannot be allocated at all, and the map is the only option.
+
+use demo_framework::rng::Lcg;
+use demo_framework::section;
+use rustc_hash::FxBuildHasher;
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, Write};
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "edit_distance_two_ways",
+        "Levenshtein distance as a full table and as a memoized recursion",
+        edit_distance_two_ways,
+    )?;
+
+    section(
+        out,
+        "memo_hasher_cost",
+        "What the memo's hasher costs on a problem with dense states",
+        memo_hasher_cost,
+    )?;
+
+    section(
+        out,
+        "sparse_coin_change",
+        "Coin change where most cells of the table are unreachable",
+        sparse_coin_change,
+    )?;
+
+    section(
+        out,
+        "practical_backup_planner",
+        "Practical demo: choosing files for a backup volume with a memoized knapsack",
+        practical_backup_planner,
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// The memo table
+// ---------------------------------------------------------------------------
+
+/// A memoized recursion's cache: subproblem -> answer, with the hasher as a
+/// type parameter so the same solver runs on any of them.
+#[derive(Debug)]
+pub struct Memo<K, V, S = FxBuildHasher> {
+    answers: HashMap<K, V, S>,
+    /// Lookups answered from the cache.
+    pub hits: u64,
+}
+
+impl<K: Hash + Eq, V: Copy, S: BuildHasher + Default> Memo<K, V, S> {
+    pub fn new() -> Self {
+        Memo {
+            answers: HashMap::default(),
+            hits: 0,
+        }
+    }
+
+    /// Subproblems solved so far.
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+
+    /// Approximate heap bytes: an entry and a control byte for each slot the
+    /// table has room for.
+    pub fn heap_bytes(&self) -> usize {
+        self.answers.capacity() * (size_of::<(K, V)>() + 1)
+    }
+
+    /// The cached answer for `key`, counting a hit if there is one.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let answer: Option<V> = self.answers.get(key).copied();
+        if answer.is_some() {
+            self.hits += 1;
+        }
+        answer
+    }
+
+    /// Caches `answer` for `key` and returns it.
+    pub fn insert(&mut self, key: K, answer: V) -> V {
+        self.answers.insert(key, answer);
+        answer
+    }
+}
+
+impl<K: Hash + Eq, V: Copy, S: BuildHasher + Default> Default for Memo<K, V, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Edit distance
+// ---------------------------------------------------------------------------
+
+/// Levenshtein distance by tabulation: fills all `(len(a) + 1) * (len(b) + 1)`
+/// cells of the table, row by row.
+pub fn edit_distance_table(a: &[u8], b: &[u8]) -> usize {
+    let width: usize = b.len() + 1;
+    let mut table: Vec<usize> = vec![0; (a.len() + 1) * width];
+    for (j, cell) in table[..width].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        table[i * width] = i;
+        for j in 1..=b.len() {
+            let substitute: usize = usize::from(a[i - 1] != b[j - 1]);
+            table[i * width + j] = (table[(i - 1) * width + j - 1] + substitute)
+                .min(table[(i - 1) * width + j] + 1)
+                .min(table[i * width + j - 1] + 1);
+        }
+    }
+    table[a.len() * width + b.len()]
+}
+
+/// Levenshtein distance top-down. `(i, j)` is the distance between the first
+/// `i` bytes of `a` and the first `j` of `b`. When those end in the same
+/// byte, matching them is always optimal, so the recursion only follows
+/// the diagonal and skips the cells beside it.
+pub fn edit_distance_memo<S: BuildHasher + Default>(
+    a: &[u8],
+    b: &[u8],
+    memo: &mut Memo<(usize, usize), usize, S>,
+) -> usize {
+    fn solve<S: BuildHasher + Default>(
+        a: &[u8],
+        b: &[u8],
+        i: usize,
+        j: usize,
+        memo: &mut Memo<(usize, usize), usize, S>,
+    ) -> usize {
+        if i == 0 || j == 0 {
+            return i + j;
+        }
+        if let Some(distance) = memo.get(&(i, j)) {
+            return distance;
+        }
+        let distance: usize = if a[i - 1] == b[j - 1] {
+            solve(a, b, i - 1, j - 1, memo)
+        } else {
+            1 + solve(a, b, i - 1, j - 1, memo)
+                .min(solve(a, b, i - 1, j, memo))
+                .min(solve(a, b, i, j - 1, memo))
+        };
+        memo.insert((i, j), distance)
+    }
+
+    solve(a, b, a.len(), b.len(), memo)
+}
+
+/// A pseudo-random DNA string of `len` bases. Fixed seed, so runs are
+/// repeatable.
+pub fn random_sequence(len: usize, seed: u64) -> Vec<u8> {
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..len).map(|_| b"ACGT"[rng.below(4) as usize]).collect()
+}
+
+/// `sequence` with `edits` random substitutions, insertions and deletions.
+pub fn mutate(sequence: &[u8], edits: usize, seed: u64) -> Vec<u8> {
+    let mut rng: Lcg = Lcg::new(seed);
+    let mut result: Vec<u8> = sequence.to_vec();
+    for _ in 0..edits {
+        let at: usize = rng.below(result.len() as u64 + 1) as usize;
+        let base: u8 = b"ACGT"[rng.below(4) as usize];
+        match rng.below(3) {
+            0 if at < result.len() => result[at] = base,
+            1 if at < result.len() => {
+                result.remove(at);
+            }
+            _ => result.insert(at, base),
+        }
+    }
+    result
+}
+
+// ---------------------------------------------------------------------------
+// Coin change
+// ---------------------------------------------------------------------------
+
+/// Fewest coins summing to `amount`, by tabulation over every total from 0
+/// to `amount`. `None` if no combination works.
+pub fn min_coins_table(coins: &[u64], amount: u64) -> Option<u32> {
+    let mut fewest: Vec<u32> = vec![u32::MAX; amount as usize + 1];
+    fewest[0] = 0;
+    for total in 1..fewest.len() {
+        for &coin in coins {
+            let coin: usize = coin as usize;
+            if coin <= total && fewest[total - coin] != u32::MAX {
+                fewest[total] = fewest[total].min(fewest[total - coin] + 1);
+            }
+        }
+    }
+    Some(fewest[amount as usize]).filter(|&count| count != u32::MAX)
+}
+
+/// Fewest coins summing to `amount`, top-down: only the remainders reachable
+/// by subtracting coins from `amount` are ever solved.
+pub fn min_coins_memo<S: BuildHasher + Default>(
+    coins: &[u64],
+    amount: u64,
+    memo: &mut Memo<u64, Option<u32>, S>,
+) -> Option<u32> {
+    if amount == 0 {
+        return Some(0);
+    }
+    if let Some(fewest) = memo.get(&amount) {
+        return fewest;
+    }
+    // A plain loop: the recursion goes amount / smallest coin calls deep,
+    // and iterator adapters would add frames at every level
+    let mut fewest: Option<u32> = None;
+    for &coin in coins {
+        if coin <= amount
+            && let Some(count) = min_coins_memo(coins, amount - coin, memo)
+        {
+            fewest = Some(fewest.map_or(count + 1, |best| best.min(count + 1)));
+        }
+    }
+    memo.insert(amount, fewest)
+}
+
+// ---------------------------------------------------------------------------
+// Knapsack
+// ---------------------------------------------------------------------------
+
+/// A file that could go into a backup, with how much the owner wants it kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupFile {
+    pub name: String,
+    pub bytes: u64,
+    pub priority: u32,
+}
+
+/// `count` files between 100 MB and 1.5 GB with priorities 1 to 20. Fixed
+/// seed, so runs are repeatable.
+pub fn sample_backup(count: usize, seed: u64) -> Vec<BackupFile> {
+    const KINDS: [&str; 5] = ["photos", "video", "mail", "projects", "music"];
+    let mut rng: Lcg = Lcg::new(seed);
+    (0..count)
+        .map(|i| BackupFile {
+            name: format!("{}-{:02}.tar", KINDS[i % KINDS.len()], i),
+            bytes: 100_000_000 + rng.below(1_400_000_000),
+            priority: 1 + rng.below(20) as u32,
+        })
+        .collect()
+}
+
+/// The highest total priority of files that fit in `capacity` bytes (0/1
+/// knapsack), and the indices of one best choice. The state is
+/// `(next file, bytes left)`, so a table would need a cell per byte of
+/// capacity for every file; the memo holds only the states reached.
+pub fn plan_backup<S: BuildHasher + Default>(
+    files: &[BackupFile],
+    capacity: u64,
+    memo: &mut Memo<(usize, u64), u32, S>,
+) -> (u32, Vec<usize>) {
+    fn best<S: BuildHasher + Default>(
+        files: &[BackupFile],
+        i: usize,
+        left: u64,
+        memo: &mut Memo<(usize, u64), u32, S>,
+    ) -> u32 {
+        let Some(file) = files.get(i) else {
+            return 0;
+        };
+        if let Some(priority) = memo.get(&(i, left)) {
+            return priority;
+        }
+        let skip: u32 = best(files, i + 1, left, memo);
+        let take: u32 = match left.checked_sub(file.bytes) {
+            Some(rest) => file.priority + best(files, i + 1, rest, memo),
+            None => 0,
+        };
+        memo.insert((i, left), skip.max(take))
+    }
+
+    let total: u32 = best(files, 0, capacity, memo);
+
+    // Walk the same states again: a file is taken where skipping it loses
+    let mut chosen: Vec<usize> = Vec::new();
+    let mut left: u64 = capacity;
+    for i in 0..files.len() {
+        if best(files, i, left, memo) != best(files, i + 1, left, memo) {
+            chosen.push(i);
+            left -= files[i].bytes;
+        }
+    }
+    (total, chosen)
+}
+
+// ---------------------------------------------------------------------------
+// Demos
+// ---------------------------------------------------------------------------
+
+/// Demonstrates the two shapes of the same recurrence, and how many of the
+/// table's cells the memoized version actually visits.
+pub fn edit_distance_two_ways(out: &mut dyn Write) -> io::Result<()> {
+    let mut memo: Memo<(usize, usize), usize> = Memo::new();
+    writeln!(
+        out,
+        "\n  kitten -> sitting: table {}, memo {}",
+        edit_distance_table(b"kitten", b"sitting"),
+        edit_distance_memo(b"kitten", b"sitting", &mut memo)
+    )?;
+
+    let reference: Vec<u8> = random_sequence(300, 1);
+    let cases: [(&str, Vec<u8>); 2] = [
+        ("unrelated", random_sequence(300, 2)),
+        ("5 edits apart", mutate(&reference, 5, 3)),
+    ];
+    writeln!(
+        out,
+        "\n  {:<16} {:>9} {:>12} {:>12} {:>8} {:>10}",
+        "300 bases vs", "distance", "table cells", "memo states", "reached", "memo hits"
+    )?;
+    for (label, other) in &cases {
+        let mut memo: Memo<(usize, usize), usize> = Memo::new();
+        let distance: usize = edit_distance_memo(&reference, other, &mut memo);
+        assert_eq!(distance, edit_distance_table(&reference, other));
+        let cells: usize = (reference.len() + 1) * (other.len() + 1);
+        writeln!(
+            out,
+            "  {:<16} {:>9} {:>12} {:>12} {:>7}% {:>10}",
+            label,
+            distance,
+            cells,
+            memo.len(),
+            memo.len() * 100 / cells,
+            memo.hits
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\n  Matching bytes send the recursion straight down the diagonal, but every"
+    )?;
+    writeln!(
+        out,
+        "  mismatch branches three ways and the branches wander off it, so even close"
+    )?;
+    writeln!(
+        out,
+        "  strings reach a large part of the table. These states are dense, and each"
+    )?;
+    writeln!(
+        out,
+        "  one costs the memo a hash and {} bytes of map entry instead of {}.",
+        size_of::<((usize, usize), usize)>(),
+        size_of::<usize>()
+    )?;
+    Ok(())
+}
+
+/// Times `solve` on a fresh memo from `S`, returning the distance and time.
+fn time_memo<S: BuildHasher + Default>(a: &[u8], b: &[u8]) -> (usize, Duration) {
+    let start: Instant = Instant::now();
+    let mut memo: Memo<(usize, usize), usize, S> = Memo::new();
+    let distance: usize = edit_distance_memo(a, b, &mut memo);
+    (distance, start.elapsed())
+}
+
+/// Demonstrates the hasher's share of a memoized solver's time when almost
+/// every state is reached: each state is a hash, a probe and an insert.
+pub fn memo_hasher_cost(out: &mut dyn Write) -> io::Result<()> {
+    let a: Vec<u8> = random_sequence(400, 11);
+    let b: Vec<u8> = random_sequence(400, 12);
+
+    let start: Instant = Instant::now();
+    let expected: usize = edit_distance_table(&a, &b);
+    let table: Duration = start.elapsed();
+
+    let timings: [(&str, (usize, Duration)); 4] = [
+        ("memo, SipHash", time_memo::<RandomState>(&a, &b)),
+        ("memo, FxHash", time_memo::<FxBuildHasher>(&a, &b)),
+        (
+            "memo, foldhash",
+            time_memo::<foldhash::fast::RandomState>(&a, &b),
+        ),
+        ("memo, aHash", time_memo::<ahash::RandomState>(&a, &b)),
+    ];
+
+    writeln!(
+        out,
+        "\n  Two unrelated 400-base strings, distance {expected}:"
+    )?;
+    writeln!(out, "    {:<16} {:?}", "table", table)?;
+    for (label, (distance, elapsed)) in &timings {
+        assert_eq!(*distance, expected);
+        writeln!(out, "    {:<16} {:?}", label, elapsed)?;
+    }
+
+    writeln!(
+        out,
+        "\n  The keys are pairs of small integers the program makes itself, so"
+    )?;
+    writeln!(
+        out,
+        "  there is nothing to defend against: FxHash or foldhash is the right memo"
+    )?;
+    writeln!(
+        out,
+        "  hasher, and SipHash's extra rounds are pure overhead. None of them comes"
+    )?;
+    writeln!(
+        out,
+        "  close to the table, which does no hashing at all. The Memo_Table benchmark"
+    )?;
+    writeln!(out, "  measures this properly.")?;
+    Ok(())
+}
+
+/// Demonstrates coin change both ways: with small coins every total is
+/// reachable and the table is the better fit, with large coins only a few
+/// totals are and the memo does a fraction of the work.
+pub fn sparse_coin_change(out: &mut dyn Write) -> io::Result<()> {
+    let cases: [(&str, &[u64], u64); 2] = [
+        ("cents", &[1, 5, 10, 25], 1_000),
+        ("pallets", &[400, 1_100, 2_900], 1_000_000),
+    ];
+    writeln!(
+        out,
+        "\n  {:<9} {:>10} {:>7} {:>12} {:>12} {:>12} {:>12}",
+        "coins", "amount", "fewest", "table cells", "table bytes", "memo states", "memo bytes"
+    )?;
+    for (label, coins, amount) in cases {
+        let mut memo: Memo<u64, Option<u32>> = Memo::new();
+        let fewest: Option<u32> = min_coins_memo(coins, amount, &mut memo);
+        assert_eq!(fewest, min_coins_table(coins, amount));
+        writeln!(
+            out,
+            "  {:<9} {:>10} {:>7} {:>12} {:>12} {:>12} {:>12}",
+            label,
+            amount,
+            fewest.map_or("none".to_string(), |count| count.to_string()),
+            amount + 1,
+            (amount + 1) as usize * size_of::<u32>(),
+            memo.len(),
+            memo.heap_bytes()
+        )?;
+    }
+
+    writeln!(
+        out,
+        "\n  cents: coins [1, 5, 10, 25] reach every total, so the memo solves as many"
+    )?;
+    writeln!(
+        out,
+        "  states as the table has cells and stores each one several times larger."
+    )?;
+    writeln!(
+        out,
+        "  pallets: coins [400, 1100, 2900] only reach multiples of 100, so the table"
+    )?;
+    writeln!(
+        out,
+        "  computes 99 of every 100 cells for nothing. The memo never sees them."
+    )?;
+    Ok(())
+}
+
+/// Practical example: fill a 4.7 GB disc with the most valuable files. The
+/// capacity is in bytes, so a dense table is out of the question; a memo
+/// keyed by `(file, bytes left)` holds only the states the search reaches.
+pub fn practical_backup_planner(out: &mut dyn Write) -> io::Result<()> {
+    const DISC_BYTES: u64 = 4_700_000_000;
+    let files: Vec<BackupFile> = sample_backup(20, 7);
+    let offered: u64 = files.iter().map(|file| file.bytes).sum();
+    writeln!(
+        out,
+        "\n  {} files, {:.1} GB offered for a {:.1} GB disc",
+        files.len(),
+        offered as f64 / 1e9,
+        DISC_BYTES as f64 / 1e9
+    )?;
+
+    let mut memo: Memo<(usize, u64), u32> = Memo::new();
+    let (priority, chosen) = plan_backup(&files, DISC_BYTES, &mut memo);
+    let used: u64 = chosen.iter().map(|&i| files[i].bytes).sum();
+
+    writeln!(out, "\n  Best choice, total priority {priority}:")?;
+    for &i in &chosen {
+        let file: &BackupFile = &files[i];
+        writeln!(
+            out,
+            "    {:<16} {:>6.2} GB  priority {:>2}",
+            file.name,
+            file.bytes as f64 / 1e9,
+            file.priority
+        )?;
+    }
+    writeln!(
+        out,
+        "  {:.3} GB used, {:.1} MB to spare",
+        used as f64 / 1e9,
+        (DISC_BYTES - used) as f64 / 1e6
+    )?;
+
+    writeln!(
+        out,
+        "\n  A table indexed by (file, bytes left) would need {} x 4.7 billion cells.",
+        files.len() + 1
+    )?;
+    writeln!(
+        out,
+        "  The memo solved {} states in {} KiB: file i only ever sees the space left",
+        memo.len(),
+        memo.heap_bytes() / 1024
+    )?;
+    writeln!(
+        out,
+        "  by the subsets of the files before it that fit on the disc."
+    )?;
+    Ok(())
+}
@@ -13,72 +13,74 @@
 //! Supported types: i8, i16, i32, i64, isize, u8, u16, u32, u64, usize
 //! NOT supported by default: i128, u128
 
+use demo_framework::section;
 use nohash_hasher::{BuildNoHashHasher, IntMap, IntSet, IsEnabled, NoHashHasher};
 use rustc_hash::FxHasher;
 use std::collections::HashMap;
 use std::collections::hash_map::RandomState;
 use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use std::io::{self, Write};
 use std::time::{Duration, Instant};
 
-fn section(name: &str, what: &str, f: impl FnOnce()) {
-    println!("\n{:=<80}", "");
-    println!("DEMO: {name}");
-    println!("  {what}");
-    println!("{:=<80}", "");
-
-    f();
-}
-
-pub fn run_all() {
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
     section(
+        out,
         "basic_intmap_usage",
         "Basic IntMap usage (integer keys, zero hashing overhead)",
         basic_intmap_usage,
-    );
+    )?;
 
     section(
+        out,
         "intset_usage",
         "IntSet usage for tracking seen integer IDs and deduplication",
         intset_usage,
-    );
+    )?;
 
     section(
+        out,
         "performance_comparison",
         "Rough timing: NoHash vs FxHash vs SipHash for integer hashing",
         performance_comparison,
-    );
+    )?;
 
     section(
+        out,
         "good_key_distribution",
         "When NoHash works well: already well-distributed integer keys",
         good_key_distribution,
-    );
+    )?;
 
     section(
+        out,
         "poor_key_distribution",
         "When NoHash performs poorly: clustered keys (power-of-two patterns)",
         poor_key_distribution,
-    );
+    )?;
 
     section(
+        out,
         "custom_type_with_nohash",
         "Using NoHash with custom ID wrapper types via IsEnabled",
         custom_type_with_nohash,
-    );
+    )?;
 
     section(
+        out,
         "ecs_example",
         "Practical demo: ECS-style component storage with IntMap lookups",
         ecs_example,
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates basic IntMap usage.
 ///
 /// IntMap is a HashMap that uses NoHash - it only accepts integer keys
 /// and uses them directly as hash values, eliminating hashing overhead.
-pub fn basic_intmap_usage() {
-    println!("\n  Basic IntMap Usage (NoHash):");
+pub fn basic_intmap_usage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Basic IntMap Usage (NoHash):")?;
 
     // IntMap only accepts integer keys - this is enforced at compile time.
     // The integer value IS the hash value, so there's no hashing overhead.
@@ -88,26 +90,28 @@ pub fn basic_intmap_usage() {
     entity_names.insert(2, "Enemy".to_string());
     entity_names.insert(3, "NPC".to_string());
 
-    println!("    IntMap<u8, String>: {:?}", entity_names);
+    writeln!(out, "    IntMap<u8, String>: {:?}", entity_names)?;
 
     // Standard HashMap operations work identically
     if let Some(name) = entity_names.get(&2) {
-        println!("    Entity 2: {}", name);
+        writeln!(out, "    Entity 2: {}", name)?;
     }
 
     // Different integer types work too
     let mut large_ids: IntMap<u64, &str> = IntMap::default();
     large_ids.insert(1_000_000_001, "item_a");
     large_ids.insert(1_000_000_002, "item_b");
-    println!("    IntMap<u64, &str>: {} entries", large_ids.len());
+    writeln!(out, "    IntMap<u64, &str>: {} entries", large_ids.len())?;
+
+    Ok(())
 }
 
 /// Demonstrates IntSet usage.
 ///
 /// IntSet is a HashSet that uses NoHash - perfect for tracking
 /// which integer IDs you've seen.
-pub fn intset_usage() {
-    println!("\n  IntSet Usage:");
+pub fn intset_usage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  IntSet Usage:")?;
 
     // IntSet for tracking seen IDs
     let mut seen_ids: IntSet<u16> = IntSet::default();
@@ -117,30 +121,32 @@ pub fn intset_usage() {
     seen_ids.insert(1_003);
     seen_ids.insert(1_001); // Duplicate, will be ignored
 
-    println!("    IntSet<u16>: {:?}", seen_ids);
-    println!("    Contains 1002: {}", seen_ids.contains(&1_002));
-    println!("    Contains 9999: {}", seen_ids.contains(&9_999));
+    writeln!(out, "    IntSet<u16>: {:?}", seen_ids)?;
+    writeln!(out, "    Contains 1002: {}", seen_ids.contains(&1_002))?;
+    writeln!(out, "    Contains 9999: {}", seen_ids.contains(&9_999))?;
 
     // Common use case: tracking processed items
     let items_to_process = [1001, 1002, 1003, 1001, 1004, 1002];
     let mut processed: IntSet<u16> = IntSet::default();
 
-    println!("\n    Processing items:");
+    writeln!(out, "\n    Processing items:")?;
     for &item in &items_to_process {
         if processed.insert(item) {
-            println!("      Processing item {}", item);
+            writeln!(out, "      Processing item {}", item)?;
         } else {
-            println!("      Skipping {} (already processed)", item);
+            writeln!(out, "      Skipping {} (already processed)", item)?;
         }
     }
+
+    Ok(())
 }
 
 /// Demonstrates the performance advantage of NoHash.
 ///
 /// When you eliminate hashing entirely, you get the fastest possible
 /// HashMap performance for integer keys.
-pub fn performance_comparison() {
-    println!("\n  NoHash Performance Comparison:");
+pub fn performance_comparison(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  NoHash Performance Comparison:")?;
 
     let iterations: u64 = 1_000_000;
 
@@ -149,7 +155,7 @@ pub fn performance_comparison() {
     let siphash_build: RandomState = RandomState::new();
     let fxhash_build: BuildHasherDefault<FxHasher> = BuildHasherDefault::<FxHasher>::default();
 
-    println!("    Integer key hashing ({} iterations):", iterations);
+    writeln!(out, "    Integer key hashing ({} iterations):", iterations)?;
 
     // NoHash timing - should be fastest
     let start: Instant = Instant::now();
@@ -178,25 +184,29 @@ pub fn performance_comparison() {
     }
     let fxhash_time: Duration = start.elapsed();
 
-    println!("      NoHash:  {:?}", nohash_time);
-    println!("      FxHash:  {:?}", fxhash_time);
-    println!("      SipHash: {:?}", siphash_time);
-    println!(
+    writeln!(out, "      NoHash:  {:?}", nohash_time)?;
+    writeln!(out, "      FxHash:  {:?}", fxhash_time)?;
+    writeln!(out, "      SipHash: {:?}", siphash_time)?;
+    writeln!(
+        out,
         "\n      NoHash speedup vs SipHash: {:.1}x",
         siphash_time.as_nanos() as f64 / nohash_time.as_nanos() as f64
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "      NoHash speedup vs FxHash: {:.1}x",
         fxhash_time.as_nanos() as f64 / nohash_time.as_nanos() as f64
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates when NoHash works well.
 ///
 /// NoHash works great when your integer keys are already reasonably
 /// distributed. This includes sequential IDs, random IDs, and pre-hashed values.
-pub fn good_key_distribution() {
-    println!("\n  NoHash Works Well With:");
+pub fn good_key_distribution(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  NoHash Works Well With:")?;
 
     // === 1. Sequential IDs (like database primary keys) ===
     // Sequential IDs are already well-distributed for hash table purposes
@@ -205,10 +215,11 @@ pub fn good_key_distribution() {
     for id in 1..=100u64 {
         db_records.insert(id, "record");
     }
-    println!(
+    writeln!(
+        out,
         "    1. Sequential IDs (1, 2, 3, ...): {} entries",
         db_records.len()
-    );
+    )?;
 
     // === 2. Random IDs ===
     // Random values are inherently well-distributed
@@ -221,7 +232,7 @@ pub fn good_key_distribution() {
         let random_id: u64 = h.finish();
         random_ids.insert(random_id, "random");
     }
-    println!("    2. Random IDs: {} entries", random_ids.len());
+    writeln!(out, "    2. Random IDs: {} entries", random_ids.len())?;
 
     // === 3. UUIDs as u64 (high bits) ===
     // If you have UUIDs, taking the high or low 64 bits works well
@@ -232,24 +243,29 @@ pub fn good_key_distribution() {
         let fake_uuid: u64 = i.wrapping_mul(0x9E37_79B9_7F4A_7C15u64);
         uuid_map.insert(fake_uuid, "uuid_data");
     }
-    println!("    3. UUID-like values: {} entries", uuid_map.len());
+    writeln!(out, "    3. UUID-like values: {} entries", uuid_map.len())?;
 
     // === 4. Pre-hashed values ===
     // If you've already computed a hash elsewhere (e.g., file content hash),
     // just use that hash directly as the key!
-    println!("    4. Pre-hashed values (already distributed)");
+    writeln!(out, "    4. Pre-hashed values (already distributed)")?;
+
+    Ok(())
 }
 
 /// Demonstrates when NoHash performs poorly.
 ///
 /// NoHash can cause severe performance degradation when keys cluster.
 /// This happens with certain patterns like multiples of powers of 2.
-pub fn poor_key_distribution() {
-    println!("\n  NoHash Performs Poorly With Clustered Keys:");
-    println!("    (This demonstrates the danger of using NoHash carelessly)");
+pub fn poor_key_distribution(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  NoHash Performs Poorly With Clustered Keys:")?;
+    writeln!(
+        out,
+        "    (This demonstrates the danger of using NoHash carelessly)"
+    )?;
 
     // Measure lookup time with clustered keys vs sequential keys
-    println!("\n    Measuring lookup time with 1000 keys:");
+    writeln!(out, "\n    Measuring lookup time with 1000 keys:")?;
 
     // === Clustered keys (multiples of 64) ===
     // Keys that are multiples of a power of 2 can cluster badly
@@ -285,35 +301,46 @@ pub fn poor_key_distribution() {
     }
     let sequential_time: Duration = start.elapsed();
 
-    println!(
+    writeln!(
+        out,
         "      Clustered keys (multiples of 64): {:?}",
         clustered_time
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "      Sequential keys (0, 1, 2, ...):   {:?}",
         sequential_time
-    );
+    )?;
 
     if clustered_time > sequential_time {
-        println!(
+        writeln!(
+            out,
             "      Clustering caused {:.1}x slowdown!",
             clustered_time.as_nanos() as f64 / sequential_time.as_nanos() as f64
-        );
+        )?;
     }
 
-    println!();
-    println!("     ️  Avoid using NoHash with:");
-    println!("       - Multiples of powers of 2 (8, 16, 32, 64, ...)");
-    println!("       - Pointer addresses (often 8 or 16-byte aligned)");
-    println!("       - Any systematically clustered values");
+    writeln!(out)?;
+    writeln!(out, "     ️  Avoid using NoHash with:")?;
+    writeln!(
+        out,
+        "       - Multiples of powers of 2 (8, 16, 32, 64, ...)"
+    )?;
+    writeln!(
+        out,
+        "       - Pointer addresses (often 8 or 16-byte aligned)"
+    )?;
+    writeln!(out, "       - Any systematically clustered values")?;
+
+    Ok(())
 }
 
 /// Demonstrates using NoHash with custom wrapper types.
 ///
 /// If you have a custom type that wraps an integer, you can enable
 /// NoHash for it by implementing the IsEnabled marker trait.
-pub fn custom_type_with_nohash() {
-    println!("\n  Using NoHash with Custom Types:");
+pub fn custom_type_with_nohash(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Using NoHash with Custom Types:")?;
 
     // Your type must implement IsEnabled to use with NoHash.
     // This is a safety guard to prevent accidental misuse.
@@ -344,15 +371,20 @@ pub fn custom_type_with_nohash() {
     entities.insert(EntityId(2), "Enemy".to_string());
     entities.insert(EntityId(100), "Boss".to_string());
 
-    println!("    EntityMap with custom EntityId:");
+    writeln!(out, "    EntityMap with custom EntityId:")?;
     for (id, name) in &entities {
-        println!("      {:?} -> {}", id, name);
+        writeln!(out, "      {:?} -> {}", id, name)?;
     }
 
-    println!();
-    println!("    Custom types with NoHash are useful for:");
-    println!("      - Type-safe ID wrappers (prevent mixing different ID types)");
-    println!("      - Newtype patterns with zero-cost abstraction");
+    writeln!(out)?;
+    writeln!(out, "    Custom types with NoHash are useful for:")?;
+    writeln!(
+        out,
+        "      - Type-safe ID wrappers (prevent mixing different ID types)"
+    )?;
+    writeln!(out, "      - Newtype patterns with zero-cost abstraction")?;
+
+    Ok(())
 }
 
 /// Practical example: Entity Component System (ECS).
@@ -360,8 +392,8 @@ pub fn custom_type_with_nohash() {
 /// In an ECS, entities are identified by integer IDs, and components
 /// are looked up by entity ID. This is a perfect use case for NoHash
 /// because entity IDs are typically sequential integers.
-pub fn ecs_example() {
-    println!("\n  Practical Example: Entity Component System (ECS)");
+pub fn ecs_example(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Practical Example: Entity Component System (ECS)")?;
 
     // In game engines, an ECS stores components in separate maps
     // indexed by entity ID. NoHash makes these lookups extremely fast.
@@ -379,6 +411,8 @@ pub fn ecs_example() {
         dy: f32,
     }
 
+    // Fields are only read through the Debug output below.
+    #[allow(dead_code)]
     #[derive(Debug)]
     struct Health {
         current: i32,
@@ -450,38 +484,53 @@ pub fn ecs_example() {
         },
     );
 
-    println!("    Initial state:");
-    println!(
+    writeln!(out, "    Initial state:")?;
+    writeln!(
+        out,
         "      Player (entity {}): pos={:?}, health={:?}",
         player,
         world.positions.get(&player),
         world.healths.get(&player)
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "      Enemy (entity {}): pos={:?}, health={:?}",
         enemy,
         world.positions.get(&enemy),
         world.healths.get(&enemy)
-    );
+    )?;
 
     // Run the movement system
     world.movement_system();
 
-    println!("\n    After running movement system:");
-    println!(
+    writeln!(out, "\n    After running movement system:")?;
+    writeln!(
+        out,
         "      Player (entity {}): pos={:?}",
         player,
         world.positions.get(&player)
-    );
-    println!(
+    )?;
+    writeln!(
+        out,
         "      Enemy (entity {}): pos={:?}",
         enemy,
         world.positions.get(&enemy)
-    );
-
-    println!();
-    println!("    NoHash is ideal for ECS because:");
-    println!("      - Entity IDs are sequential integers (well-distributed)");
-    println!("      - Component lookups happen millions of times per frame");
-    println!("      - Zero hashing overhead means maximum performance");
+    )?;
+
+    writeln!(out)?;
+    writeln!(out, "    NoHash is ideal for ECS because:")?;
+    writeln!(
+        out,
+        "      - Entity IDs are sequential integers (well-distributed)"
+    )?;
+    writeln!(
+        out,
+        "      - Component lookups happen millions of times per frame"
+    )?;
+    writeln!(
+        out,
+        "      - Zero hashing overhead means maximum performance"
+    )?;
+
+    Ok(())
 }
@@ -0,0 +1,545 @@
+//! Hash-Based Sampling - Deterministic Decisions From a Hash
+//!
+//! "Log 1% of requests", "show the new checkout to 10% of users", "keep a
+//! fixed-size sample of a stream" - sampling is one of the most common uses
+//! of hashing outside hash tables. Two families:
+//!
+//! - Random sampling (reservoir sampling): each run picks a fresh, uniform
+//!   sample of k items from a stream of unknown length, in O(k) memory
+//! - Hash-threshold sampling: an item is in the sample when
+//!   `hash(key) < rate * 2^64`. No state, no coordination: every server
+//!   makes the same decision for the same user, today and tomorrow
+//!
+//! Hash-threshold sampling is only as good as the hasher. It needs the
+//! hash's *high* bits to be uniform (a hash table mostly uses the low ones),
+//! and if decisions must survive a restart, it needs a fixed seed and an
+//! algorithm whose output is specified - which rules out RandomState and
+//! makes xxHash the natural choice.
+
+use demo_framework::section;
+use nohash_hasher::BuildNoHashHasher;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rustc_hash::FxBuildHasher;
+use std::collections::hash_map::RandomState;
+use std::collections::{BinaryHeap, HashSet};
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash};
+use std::io::{self, Write};
+use xxhash_rust::xxh3::{Xxh3Builder, xxh3_64_with_seed};
+
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
+    section(
+        out,
+        "reservoir_sampling",
+        "Uniform k-item samples from a stream of unknown length",
+        reservoir_sampling,
+    )?;
+
+    section(
+        out,
+        "hash_threshold_sampling",
+        "Sample 1% of users by hash(user_id) < threshold",
+        hash_threshold_sampling,
+    )?;
+
+    section(
+        out,
+        "uniformity_per_hasher",
+        "Sample rates and chi-squared uniformity of each hasher's high bits",
+        uniformity_per_hasher,
+    )?;
+
+    section(
+        out,
+        "stable_decisions",
+        "Which hashers give the same decision after a restart",
+        stable_decisions,
+    )?;
+
+    section(
+        out,
+        "hash_shuffling",
+        "Deterministic shuffles and bottom-k samples keyed by hash",
+        hash_shuffling,
+    )?;
+
+    Ok(())
+}
+
+/// Chi-squared critical value for 63 degrees of freedom at p = 0.001.
+///
+/// A uniform hash spread over 64 buckets exceeds this one time in 1,000.
+pub const CHI_SQUARED_64_BUCKETS: f64 = 103.44;
+
+/// Includes a key when its hash falls below `rate * 2^64`.
+///
+/// Lowering the rate only shrinks the sample: with the same hasher, the 1%
+/// sample is a subset of the 10% sample.
+#[derive(Clone, Debug)]
+pub struct HashSampler<S> {
+    build_hasher: S,
+    /// Keys whose hash is below this are sampled. u128 so a rate of 1.0
+    /// can include `u64::MAX`.
+    threshold: u128,
+}
+
+impl<S: BuildHasher> HashSampler<S> {
+    /// Panics if `rate` is outside `0.0..=1.0`.
+    pub fn new(build_hasher: S, rate: f64) -> Self {
+        assert!((0.0..=1.0).contains(&rate), "sample rate must be in 0..=1");
+        HashSampler {
+            build_hasher,
+            threshold: (rate * 2f64.powi(64)) as u128,
+        }
+    }
+
+    pub fn rate(&self) -> f64 {
+        self.threshold as f64 / 2f64.powi(64)
+    }
+
+    pub fn includes<K: Hash + ?Sized>(&self, key: &K) -> bool {
+        (self.build_hasher.hash_one(key) as u128) < self.threshold
+    }
+}
+
+/// Hash-threshold sampling on raw bytes with seeded xxh3.
+///
+/// Unlike [`HashSampler`], this doesn't go through the `Hash` trait, whose
+/// output for a given type is not guaranteed across Rust versions. Use it
+/// when sampling decisions are stored or compared across services.
+pub fn stable_sample(key: &[u8], salt: u64, rate: f64) -> bool {
+    assert!((0.0..=1.0).contains(&rate), "sample rate must be in 0..=1");
+    (xxh3_64_with_seed(key, salt) as u128) < (rate * 2f64.powi(64)) as u128
+}
+
+/// Algorithm R: a uniform sample of `k` items from `items`, in one pass.
+///
+/// Item `i` (0-based) replaces a random slot with probability `k / (i + 1)`,
+/// which leaves every item in the final sample with probability
+/// `k / n`. Returns fewer than `k` items if the stream is shorter.
+pub fn reservoir_sample<T, R: Rng>(
+    items: impl IntoIterator<Item = T>,
+    k: usize,
+    rng: &mut R,
+) -> Vec<T> {
+    let mut reservoir: Vec<T> = Vec::with_capacity(k);
+    for (i, item) in items.into_iter().enumerate() {
+        if i < k {
+            reservoir.push(item);
+        } else {
+            let slot: usize = rng.random_range(0..=i);
+            if slot < k {
+                reservoir[slot] = item;
+            }
+        }
+    }
+    reservoir
+}
+
+/// An item ordered by its hash alone.
+struct Hashed<T> {
+    hash: u64,
+    item: T,
+}
+
+impl<T> PartialEq for Hashed<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+}
+
+impl<T> Eq for Hashed<T> {}
+
+impl<T> PartialOrd for Hashed<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Hashed<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.hash.cmp(&other.hash)
+    }
+}
+
+/// The `k` distinct items with the smallest hashes, smallest first.
+///
+/// A deterministic reservoir: the same input gives the same sample, and an
+/// item seen 1,000 times is no more likely to be picked than one seen once.
+pub fn bottom_k_sample<T: Hash, S: BuildHasher>(
+    items: impl IntoIterator<Item = T>,
+    k: usize,
+    build_hasher: &S,
+) -> Vec<T> {
+    // Max-heap of the k smallest hashes so far: the root is the one to evict
+    let mut heap: BinaryHeap<Hashed<T>> = BinaryHeap::with_capacity(k + 1);
+    let mut kept: HashSet<u64> = HashSet::with_capacity(k + 1);
+    for item in items {
+        let hash: u64 = build_hasher.hash_one(&item);
+        if kept.contains(&hash) {
+            continue; // a duplicate of an item already in the sample
+        }
+        if heap.len() < k {
+            kept.insert(hash);
+            heap.push(Hashed { hash, item });
+        } else if heap.peek().is_some_and(|largest| hash < largest.hash) {
+            let evicted: Hashed<T> = heap.pop().expect("heap holds k items");
+            kept.remove(&evicted.hash);
+            kept.insert(hash);
+            heap.push(Hashed { hash, item });
+        }
+    }
+    heap.into_sorted_vec().into_iter().map(|h| h.item).collect()
+}
+
+/// Orders `items` by their hash: a shuffle that is the same on every run.
+pub fn hash_shuffle<T: Hash, S: BuildHasher>(items: &mut [T], build_hasher: &S) {
+    items.sort_by_cached_key(|item| build_hasher.hash_one(item));
+}
+
+/// Pearson's chi-squared statistic of `counts` against a uniform spread.
+pub fn chi_squared(counts: &[u64]) -> f64 {
+    let total: u64 = counts.iter().sum();
+    let expected: f64 = total as f64 / counts.len() as f64;
+    counts
+        .iter()
+        .map(|&observed| {
+            let diff: f64 = observed as f64 - expected;
+            diff * diff / expected
+        })
+        .sum()
+}
+
+/// Counts how many keys' hashes land in each of `buckets` equal slices of
+/// the u64 range, i.e. by the hash's high bits.
+pub fn high_bit_buckets<K: Hash, S: BuildHasher>(
+    keys: &[K],
+    build_hasher: &S,
+    buckets: usize,
+) -> Vec<u64> {
+    let mut counts: Vec<u64> = vec![0; buckets];
+    for key in keys {
+        let hash: u64 = build_hasher.hash_one(key);
+        let bucket: usize = ((hash as u128 * buckets as u128) >> 64) as usize;
+        counts[bucket] += 1;
+    }
+    counts
+}
+
+/// Demonstrates reservoir sampling and checks it is uniform.
+pub fn reservoir_sampling(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Reservoir Sampling (Algorithm R):")?;
+
+    // A stream we can't hold in memory: keep 5 items, whatever its length
+    let mut rng: StdRng = StdRng::seed_from_u64(42);
+    let sample: Vec<u32> = reservoir_sample(1..=1_000_000, 5, &mut rng);
+    writeln!(out, "    5 of 1,000,000 stream items: {:?}", sample)?;
+    writeln!(out, "    Memory: 5 slots, whatever the stream length")?;
+
+    // Every item should be picked k/n of the time
+    let trials: u32 = 20_000;
+    let mut counts: Vec<u64> = vec![0; 20];
+    for _ in 0..trials {
+        for item in reservoir_sample(0..20usize, 5, &mut rng) {
+            counts[item] += 1;
+        }
+    }
+    writeln!(
+        out,
+        "\n    {} samples of 5 from 0..20, expected {} picks per item:",
+        trials,
+        trials * 5 / 20
+    )?;
+    writeln!(
+        out,
+        "    min {}, max {}",
+        counts.iter().min().unwrap_or(&0),
+        counts.iter().max().unwrap_or(&0)
+    )?;
+    writeln!(
+        out,
+        "    chi-squared {:.1} (19 degrees of freedom, 43.8 at p = 0.001)",
+        chi_squared(&counts)
+    )?;
+
+    writeln!(
+        out,
+        "\n    A new RNG seed gives a new sample: fine for one-off"
+    )?;
+    writeln!(out, "    analysis, wrong when two services must agree.")?;
+
+    Ok(())
+}
+
+/// Demonstrates sampling users by comparing their hash to a threshold.
+pub fn hash_threshold_sampling(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Hash-Threshold Sampling:")?;
+
+    let users: Vec<String> = (0..100_000).map(|n| format!("user-{}", n)).collect();
+    let seed: u64 = 2024;
+    let one_percent = HashSampler::new(Xxh3Builder::new().with_seed(seed), 0.01);
+    let ten_percent = HashSampler::new(Xxh3Builder::new().with_seed(seed), 0.10);
+
+    let small: Vec<&String> = users.iter().filter(|u| one_percent.includes(u)).collect();
+    let large: HashSet<&String> = users.iter().filter(|u| ten_percent.includes(u)).collect();
+    writeln!(
+        out,
+        "    1% of {} users: {} sampled (expected 1000)",
+        users.len(),
+        small.len()
+    )?;
+    writeln!(out, "    10%: {} sampled (expected 10000)", large.len())?;
+
+    // The decision is a pure function of the key
+    let again: bool = small.iter().all(|u| one_percent.includes(u));
+    writeln!(out, "    Same user, same decision on every call: {}", again)?;
+
+    // Raising the rate only adds users
+    let nested: bool = small.iter().all(|u| large.contains(u));
+    writeln!(
+        out,
+        "    Every 1% user is also in the 10% sample: {}",
+        nested
+    )?;
+
+    // A different seed (one per experiment) gives an independent sample
+    let other = HashSampler::new(Xxh3Builder::new().with_seed(seed + 1), 0.01);
+    let overlap: usize = small.iter().filter(|u| other.includes(u)).count();
+    writeln!(
+        out,
+        "    Overlap with a differently-seeded 1% sample: {} (expected ~10)",
+        overlap
+    )?;
+
+    writeln!(
+        out,
+        "\n    Use one seed per experiment, or every 1% rollout"
+    )?;
+    writeln!(out, "    lands on the same unlucky 1% of users.")?;
+
+    Ok(())
+}
+
+/// One row of the uniformity table.
+fn uniformity_row<K: Hash, S: BuildHasher>(
+    out: &mut dyn Write,
+    name: &str,
+    keys: &[K],
+    build_hasher: S,
+) -> io::Result<()> {
+    let sampler: HashSampler<S> = HashSampler::new(build_hasher, 0.01);
+    let sampled: usize = keys.iter().filter(|k| sampler.includes(*k)).count();
+    let chi: f64 = chi_squared(&high_bit_buckets(keys, &sampler.build_hasher, 64));
+    let verdict: &str = if chi < CHI_SQUARED_64_BUCKETS {
+        "uniform"
+    } else {
+        "NOT uniform"
+    };
+    writeln!(
+        out,
+        "    {:<14} {:>8} {:>14.1}  {}",
+        name, sampled, chi, verdict
+    )
+}
+
+/// Demonstrates how evenly each hasher spreads keys over its high bits.
+///
+/// Sequential integers are the hard case: they differ only in a few low
+/// bits, so the hasher has to carry those differences all the way up.
+pub fn uniformity_per_hasher(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Uniformity of the High Bits, per Hasher:")?;
+    writeln!(
+        out,
+        "    1% sample of 100,000 keys (expect ~1000), chi-squared over 64"
+    )?;
+    writeln!(
+        out,
+        "    high-bit buckets (uniform below {:.1})",
+        CHI_SQUARED_64_BUCKETS
+    )?;
+
+    let ids: Vec<u64> = (0..100_000).collect();
+    writeln!(out, "\n    Sequential u64 IDs:")?;
+    writeln!(
+        out,
+        "    {:<14} {:>8} {:>14}",
+        "Hasher", "Sampled", "Chi-squared"
+    )?;
+    uniformity_row(
+        out,
+        "SipHash-1-3",
+        &ids,
+        BuildHasherDefault::<DefaultHasher>::default(),
+    )?;
+    uniformity_row(out, "FxHash", &ids, FxBuildHasher)?;
+    uniformity_row(
+        out,
+        "aHash",
+        &ids,
+        ahash::RandomState::with_seeds(1, 2, 3, 4),
+    )?;
+    uniformity_row(
+        out,
+        "foldhash",
+        &ids,
+        foldhash::fast::FixedState::with_seed(7),
+    )?;
+    uniformity_row(
+        out,
+        "xxHash64",
+        &ids,
+        twox_hash::xxhash64::State::with_seed(7),
+    )?;
+    uniformity_row(out, "xxh3", &ids, Xxh3Builder::new().with_seed(7))?;
+    uniformity_row(out, "NoHash", &ids, BuildNoHashHasher::<u64>::default())?;
+
+    let names: Vec<String> = (0..100_000).map(|n| format!("user-{}", n)).collect();
+    writeln!(out, "\n    \"user-N\" strings:")?;
+    writeln!(
+        out,
+        "    {:<14} {:>8} {:>14}",
+        "Hasher", "Sampled", "Chi-squared"
+    )?;
+    uniformity_row(
+        out,
+        "SipHash-1-3",
+        &names,
+        BuildHasherDefault::<DefaultHasher>::default(),
+    )?;
+    uniformity_row(out, "FxHash", &names, FxBuildHasher)?;
+    uniformity_row(
+        out,
+        "aHash",
+        &names,
+        ahash::RandomState::with_seeds(1, 2, 3, 4),
+    )?;
+    uniformity_row(
+        out,
+        "foldhash",
+        &names,
+        foldhash::fast::FixedState::with_seed(7),
+    )?;
+    uniformity_row(
+        out,
+        "xxHash64",
+        &names,
+        twox_hash::xxhash64::State::with_seed(7),
+    )?;
+    uniformity_row(out, "xxh3", &names, Xxh3Builder::new().with_seed(7))?;
+
+    writeln!(
+        out,
+        "\n    NoHash returns the ID itself: every small ID is below"
+    )?;
+    writeln!(out, "    the threshold, so it \"samples\" all of them.")?;
+    writeln!(
+        out,
+        "    FxHash and foldhash score near 0 on sequential IDs,"
+    )?;
+    writeln!(
+        out,
+        "    far below the ~63 a random spread gives: multiplying by"
+    )?;
+    writeln!(
+        out,
+        "    a constant spaces consecutive IDs evenly. The rate is"
+    )?;
+    writeln!(
+        out,
+        "    accurate, but which IDs are picked follows a pattern."
+    )?;
+
+    Ok(())
+}
+
+/// Demonstrates which hashers keep sampling decisions stable over time.
+pub fn stable_decisions(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Stable Decisions Across Restarts:")?;
+
+    // Two RandomStates stand in for two processes (or two restarts)
+    let users: Vec<String> = (0..100_000).map(|n| format!("user-{}", n)).collect();
+    let first = HashSampler::new(RandomState::new(), 0.01);
+    let second = HashSampler::new(RandomState::new(), 0.01);
+    let agreed: usize = users
+        .iter()
+        .filter(|u| first.includes(*u) && second.includes(*u))
+        .count();
+    writeln!(
+        out,
+        "    RandomState, two processes: {} users in both 1% samples",
+        if agreed < 100 { "few" } else { "many" }
+    )?;
+    writeln!(out, "    (a random seed per process: ~1% of 1% agree)")?;
+
+    // Raw bytes + seeded xxh3: the same answer anywhere
+    let kept: usize = users
+        .iter()
+        .filter(|u| stable_sample(u.as_bytes(), 2024, 0.01))
+        .count();
+    writeln!(
+        out,
+        "\n    stable_sample(bytes, salt, 0.01): {} users",
+        kept
+    )?;
+    writeln!(
+        out,
+        "    stable_sample(b\"user-42\", 2024, 0.5): {}",
+        stable_sample(b"user-42", 2024, 0.5)
+    )?;
+
+    writeln!(out, "\n    Hasher        Same decision after a restart?")?;
+    writeln!(out, "    RandomState   no - random keys per process")?;
+    writeln!(out, "    SipHash-1-3   same build only - std may change it")?;
+    writeln!(out, "    FxHash        same crate version only")?;
+    writeln!(out, "    aHash         same version and CPU features only")?;
+    writeln!(out, "    foldhash      same crate version only")?;
+    writeln!(out, "    xxHash        yes - the algorithm is specified")?;
+
+    writeln!(
+        out,
+        "\n    Persisted decisions should hash bytes, not `Hash`:"
+    )?;
+    writeln!(out, "    how a type feeds a Hasher is not a stable format.")?;
+
+    Ok(())
+}
+
+/// Demonstrates shuffling and sampling with a hash as the sort key.
+pub fn hash_shuffling(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Hash Shuffling and Bottom-k Samples:")?;
+
+    let seeded = Xxh3Builder::new().with_seed(99);
+    let mut playlist: Vec<&str> = vec!["intro", "verse", "chorus", "bridge", "solo", "outro"];
+    hash_shuffle(&mut playlist, &seeded);
+    writeln!(out, "    hash_shuffle, seed 99:  {:?}", playlist)?;
+    let mut again: Vec<&str> = vec!["outro", "solo", "bridge", "chorus", "verse", "intro"];
+    hash_shuffle(&mut again, &seeded);
+    writeln!(out, "    from reversed input:    {:?}", again)?;
+    writeln!(
+        out,
+        "    Same order regardless of input order: {}",
+        playlist == again
+    )?;
+
+    // Bottom-k: the k smallest hashes, counting each distinct item once
+    let mut stream: Vec<String> = Vec::new();
+    for n in 0..1_000 {
+        stream.push(format!("page-{}", n));
+    }
+    for _ in 0..10_000 {
+        stream.push("page-home".to_string()); // a very popular page
+    }
+    let sample: Vec<String> = bottom_k_sample(stream.iter().cloned(), 5, &seeded);
+    writeln!(
+        out,
+        "\n    bottom_k_sample(5) of 11,000 page views: {:?}",
+        sample
+    )?;
+    writeln!(
+        out,
+        "    \"page-home\" was 10,000 of the views but is one item: {}",
+        sample.contains(&"page-home".to_string())
+    )?;
+
+    Ok(())
+}
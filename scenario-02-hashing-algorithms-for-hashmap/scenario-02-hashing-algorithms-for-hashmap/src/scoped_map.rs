@@ -0,0 +1,319 @@
+//! Scope Stacks - Maps With Nested, Shadowing Scopes
+//!
+//! A compiler's symbol table, an interpreter's environment and a template
+//! engine's variables all need the same thing: a map where entering a block
+//! opens a scope, a binding in it shadows the same name further out, and
+//! leaving the block forgets the scope's bindings at once.
+//! [`ScopeStack`] is that API; three layouts implement it:
+//!
+//! - [`ScopedMap`]: a `Vec` of `FxHashMap`s, one per open scope. A lookup
+//!   hashes the key once per scope, innermost first, until it hits.
+//!   Popping drops one map (kept for reuse, so its table isn't reallocated
+//!   by the next block)
+//! - [`TaggedScopedMap`]: one `FxHashMap<(depth, K), V>` plus the keys each
+//!   scope declared. A lookup probes `(depth, key)` for each depth, like
+//!   the Vec of maps but in a single, larger table; popping removes the
+//!   scope's keys one by one
+//! - [`PersistentScopedMap`]: an `im::HashMap` holding only the visible
+//!   binding of each key, and a stack of snapshots. Pushing a scope saves
+//!   a snapshot in O(1) thanks to structural sharing, a lookup is one hash
+//!   at any depth, and popping restores the snapshot
+//!
+//! All three use FxHash: the keys are interned symbols or small integers
+//! the program controls. `scope_workload` generates the block-structured
+//! operations the `Scoped_Map` benchmark and the tests run.
+
+use demo_framework::rng::Lcg;
+use rustc_hash::{FxBuildHasher, FxHashMap};
+use std::hash::Hash;
+
+/// A map with a stack of scopes. Depth 0 is the global scope, which is
+/// always open.
+pub trait ScopeStack<K, V> {
+    /// Opens a new innermost scope.
+    fn push_scope(&mut self);
+
+    /// Closes the innermost scope, dropping its bindings. Panics on the
+    /// global scope.
+    fn pop_scope(&mut self);
+
+    /// Depth of the innermost scope.
+    fn depth(&self) -> usize;
+
+    /// Binds `key` in the innermost scope. Returns the value it replaced
+    /// in *that* scope; a binding further out is shadowed, not replaced.
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+    /// The innermost binding of `key` and the depth it was found at.
+    fn lookup(&self, key: &K) -> Option<(usize, &V)>;
+
+    /// The binding of `key` in the innermost scope only.
+    fn get_in_current_scope(&self, key: &K) -> Option<&V> {
+        match self.lookup(key) {
+            Some((depth, value)) if depth == self.depth() => Some(value),
+            _ => None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Vec of maps
+// ---------------------------------------------------------------------------
+
+/// A scope stack as a `Vec` of `FxHashMap`s, innermost last.
+#[derive(Debug, Clone)]
+pub struct ScopedMap<K, V> {
+    scopes: Vec<FxHashMap<K, V>>,
+    /// Emptied maps from popped scopes, reused by `push_scope`.
+    spare: Vec<FxHashMap<K, V>>,
+}
+
+impl<K: Hash + Eq, V> ScopedMap<K, V> {
+    /// A map with only the (empty) global scope open.
+    pub fn new() -> Self {
+        ScopedMap {
+            scopes: vec![FxHashMap::default()],
+            spare: Vec::new(),
+        }
+    }
+
+    /// Bindings in all open scopes, shadowed ones included.
+    pub fn len(&self) -> usize {
+        self.scopes.iter().map(|scope| scope.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scopes.iter().all(|scope| scope.is_empty())
+    }
+
+    /// The bindings of the innermost scope, in hash order.
+    pub fn current_scope(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.scopes.last().into_iter().flatten()
+    }
+}
+
+impl<K: Hash + Eq, V> Default for ScopedMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq, V> ScopeStack<K, V> for ScopedMap<K, V> {
+    fn push_scope(&mut self) {
+        let scope: FxHashMap<K, V> = self.spare.pop().unwrap_or_default();
+        self.scopes.push(scope);
+    }
+
+    fn pop_scope(&mut self) {
+        assert!(self.scopes.len() > 1, "the global scope cannot be popped");
+        if let Some(mut scope) = self.scopes.pop() {
+            scope.clear();
+            self.spare.push(scope);
+        }
+    }
+
+    fn depth(&self) -> usize {
+        self.scopes.len() - 1
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.scopes
+            .last_mut()
+            .expect("the global scope is never popped")
+            .insert(key, value)
+    }
+
+    fn lookup(&self, key: &K) -> Option<(usize, &V)> {
+        self.scopes
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(depth, scope)| scope.get(key).map(|value| (depth, value)))
+    }
+
+    fn get_in_current_scope(&self, key: &K) -> Option<&V> {
+        self.scopes.last().and_then(|scope| scope.get(key))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// One map, scope-tagged keys
+// ---------------------------------------------------------------------------
+
+/// A scope stack as one map keyed by `(depth, key)`, plus the keys each
+/// open scope declared so `pop_scope` knows what to remove.
+#[derive(Debug, Clone)]
+pub struct TaggedScopedMap<K, V> {
+    bindings: FxHashMap<(usize, K), V>,
+    declared: Vec<Vec<K>>,
+}
+
+impl<K: Hash + Eq + Clone, V> TaggedScopedMap<K, V> {
+    pub fn new() -> Self {
+        TaggedScopedMap {
+            bindings: FxHashMap::default(),
+            declared: vec![Vec::new()],
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Default for TaggedScopedMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> ScopeStack<K, V> for TaggedScopedMap<K, V> {
+    fn push_scope(&mut self) {
+        self.declared.push(Vec::new());
+    }
+
+    fn pop_scope(&mut self) {
+        assert!(self.declared.len() > 1, "the global scope cannot be popped");
+        let depth: usize = self.depth();
+        for key in self.declared.pop().into_iter().flatten() {
+            self.bindings.remove(&(depth, key));
+        }
+    }
+
+    fn depth(&self) -> usize {
+        self.declared.len() - 1
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let depth: usize = self.depth();
+        let previous: Option<V> = self.bindings.insert((depth, key.clone()), value);
+        if previous.is_none() {
+            self.declared[depth].push(key);
+        }
+        previous
+    }
+
+    fn lookup(&self, key: &K) -> Option<(usize, &V)> {
+        (0..=self.depth()).rev().find_map(|depth| {
+            self.bindings
+                .get(&(depth, key.clone()))
+                .map(|value| (depth, value))
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Persistent map with snapshots
+// ---------------------------------------------------------------------------
+
+/// A scope stack as a persistent map of the visible bindings, with one
+/// snapshot per open scope to return to.
+#[derive(Clone)]
+pub struct PersistentScopedMap<K, V> {
+    /// Each key's innermost binding, tagged with its depth.
+    visible: im::HashMap<K, (usize, V), FxBuildHasher>,
+    /// `visible` as it was when each open scope was pushed.
+    saved: Vec<im::HashMap<K, (usize, V), FxBuildHasher>>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> PersistentScopedMap<K, V> {
+    pub fn new() -> Self {
+        PersistentScopedMap {
+            visible: im::HashMap::default(),
+            saved: Vec::new(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Default for PersistentScopedMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> ScopeStack<K, V> for PersistentScopedMap<K, V> {
+    fn push_scope(&mut self) {
+        self.saved.push(self.visible.clone());
+    }
+
+    fn pop_scope(&mut self) {
+        self.visible = self.saved.pop().expect("the global scope cannot be popped");
+    }
+
+    fn depth(&self) -> usize {
+        self.saved.len()
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let depth: usize = self.depth();
+        match self.visible.insert(key, (depth, value)) {
+            Some((previous_depth, previous)) if previous_depth == depth => Some(previous),
+            _ => None,
+        }
+    }
+
+    fn lookup(&self, key: &K) -> Option<(usize, &V)> {
+        self.visible.get(key).map(|(depth, value)| (*depth, value))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Workload
+// ---------------------------------------------------------------------------
+
+/// One step of a block-structured program, as a scope stack sees it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeOp {
+    Push,
+    Pop,
+    Insert(u32),
+    Lookup(u32),
+}
+
+/// Operations for `functions` function bodies, each a chain of blocks
+/// nested `nesting` deep. Every block declares 4 names and then looks up 16,
+/// a quarter of them undefined, the rest spread over all open scopes. The
+/// global scope holds 64 names. Fixed seed, so runs are repeatable.
+pub fn scope_workload(functions: usize, nesting: usize, seed: u64) -> Vec<ScopeOp> {
+    let mut rng: Lcg = Lcg::new(seed);
+    let mut next = move |bound: u32| -> u32 { rng.below(u64::from(bound)) as u32 };
+
+    // Names are u32s; block b at depth d declares 1000 * d + 4 * b + i
+    let mut ops: Vec<ScopeOp> = (0..64).map(ScopeOp::Insert).collect();
+    for function in 0..functions as u32 {
+        for depth in 1..=nesting as u32 {
+            ops.push(ScopeOp::Push);
+            let base: u32 = 1000 * depth + 4 * (function % 8);
+            ops.extend((0..4).map(|i| ScopeOp::Insert(base + i)));
+            for _ in 0..16 {
+                let name: u32 = match next(4) {
+                    0 => 1_000_000 + next(1000),
+                    _ => match next(depth + 1) {
+                        0 => next(64),
+                        outer => 1000 * outer + 4 * (function % 8) + next(4),
+                    },
+                };
+                ops.push(ScopeOp::Lookup(name));
+            }
+        }
+        ops.extend((0..nesting).map(|_| ScopeOp::Pop));
+    }
+    ops
+}
+
+/// Runs `ops` against `stack` and returns a checksum of what the lookups
+/// found: the sum of depth + value over every hit.
+pub fn run_workload<S: ScopeStack<u32, u32>>(stack: &mut S, ops: &[ScopeOp]) -> u64 {
+    let mut checksum: u64 = 0;
+    for &op in ops {
+        match op {
+            ScopeOp::Push => stack.push_scope(),
+            ScopeOp::Pop => stack.pop_scope(),
+            ScopeOp::Insert(name) => {
+                stack.insert(name, name ^ 0x5555);
+            }
+            ScopeOp::Lookup(name) => {
+                if let Some((depth, &value)) = stack.lookup(&name) {
+                    checksum += depth as u64 + u64::from(value);
+                }
+            }
+        }
+    }
+    checksum
+}
@@ -9,63 +9,73 @@
 //!
 //! IMPORTANT: The examples here are educational.
 
+use crate::bucket_visual::{ChainedTable, OpenAddressingTable, indent};
 use ahash::AHasher;
+use demo_framework::{embed, section};
 use nohash_hasher::BuildNoHashHasher;
 use rustc_hash::FxHasher;
 use std::collections::HashMap;
 use std::collections::hash_map::RandomState as StdRandomState;
 use std::hash::{BuildHasher, DefaultHasher, Hash, Hasher};
+use std::io::{self, Write};
 use std::time::{Duration, Instant};
+use xxhash_rust::xxh3::Xxh3Builder;
 
-fn section(name: &str, what: &str, f: impl FnOnce()) {
-    println!("\n{:=<80}", "");
-    println!("DEMO: {name}");
-    println!("  {what}");
-    println!("{:=<80}", "");
-
-    f();
-}
-
-pub fn run_all() {
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
     section(
+        out,
         "understanding_hashdos",
         "What happens when hash collisions are exploited",
         understanding_hashdos,
-    );
+    )?;
 
     section(
+        out,
         "collision_impact_demonstration",
         "Measuring the performance impact of hash collisions",
         collision_impact_demonstration,
-    );
+    )?;
+
+    section(
+        out,
+        "hashdos_bucket_view",
+        "The attack drawn: one giant bucket and one long probe run",
+        hashdos_bucket_view,
+    )?;
 
     section(
+        out,
         "keyed_vs_unkeyed_hashers",
         "Why keyed hashers (SipHash, aHash) prevent prediction attacks",
         keyed_vs_unkeyed_hashers,
-    );
+    )?;
 
     section(
+        out,
         "vulnerable_hasher_demonstration",
         "Demonstrating why FxHash is vulnerable to HashDoS",
         vulnerable_hasher_demonstration,
-    );
+    )?;
 
     section(
+        out,
         "secure_hasher_demonstration",
         "How SipHash and aHash protect against HashDoS",
         secure_hasher_demonstration,
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Explains the mechanics of HashDoS attacks.
 ///
 /// When an attacker can predict hash values, they can craft inputs
 /// that all hash to the same bucket, turning O(1) operations into O(n).
-pub fn understanding_hashdos() {
-    println!("\n  Understanding HashDoS Attacks:");
+pub fn understanding_hashdos(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Understanding HashDoS Attacks:")?;
 
-    println!(
+    writeln!(
+        out,
         "
     Hash tables achieve O(1) performance by distributing items across buckets:
 
@@ -90,20 +100,31 @@ pub fn understanding_hashdos() {
     └─────────────────────────────────────────────────────────────┘
     Lookup time: O(n) - must scan entire chain!
     "
-    );
-
-    println!("    Impact:");
-    println!("      - A single malicious HTTP request can exhaust server CPU");
-    println!("      - Attack requires minimal bandwidth (small payload, huge impact)");
-    println!("      - Led to CVEs and emergency patches across the industry");
+    )?;
+
+    writeln!(out, "    Impact:")?;
+    writeln!(
+        out,
+        "      - A single malicious HTTP request can exhaust server CPU"
+    )?;
+    writeln!(
+        out,
+        "      - Attack requires minimal bandwidth (small payload, huge impact)"
+    )?;
+    writeln!(
+        out,
+        "      - Led to CVEs and emergency patches across the industry"
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates the performance impact of hash collisions.
 ///
 /// This simulation shows how performance degrades when items cluster
 /// in the same bucket versus being well-distributed.
-pub fn collision_impact_demonstration() {
-    println!("\n  Collision Impact Demonstration:");
+pub fn collision_impact_demonstration(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Collision Impact Demonstration:")?;
 
     // We'll simulate the effect of collisions by comparing lookup times
     // in a well-distributed map versus a poorly-distributed one.
@@ -149,21 +170,96 @@ pub fn collision_impact_demonstration() {
     }
     let bad_time: Duration = start.elapsed();
 
-    println!(
+    writeln!(
+        out,
         "    {} items, {} lookup iterations each:",
         num_items, num_lookups
-    );
-    println!("      Well-distributed keys: {:?}", good_time);
-    println!("      Clustered keys:        {:?}", bad_time);
+    )?;
+    writeln!(out, "      Well-distributed keys: {:?}", good_time)?;
+    writeln!(out, "      Clustered keys:        {:?}", bad_time)?;
+    embed::record("well_distributed_ns", good_time.as_nanos());
+    embed::record("clustered_ns", bad_time.as_nanos());
 
     if bad_time > good_time {
         let slowdown: f64 = bad_time.as_nanos() as f64 / good_time.as_nanos() as f64;
-        println!("      Clustering caused {:.1}x slowdown!", slowdown);
+        writeln!(out, "      Clustering caused {:.1}x slowdown!", slowdown)?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "    This demonstrates why key distribution matters.")?;
+    writeln!(
+        out,
+        "    An attacker who can control keys can exploit this."
+    )?;
+
+    Ok(())
+}
+
+/// Draws the collision attack in the teaching tables.
+///
+/// Multiples of 1024, as above: with NoHash their low bits are all zero,
+/// so every key shares bucket 0 however far the table grows (until it has
+/// more than 1024 buckets). A keyed hasher spreads them.
+pub fn hashdos_bucket_view(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  HashDoS, Bucket by Bucket:")?;
+
+    let honest: Vec<u64> = (0..16).collect();
+    let attack: Vec<u64> = (0..16).map(|i| i * 1024).collect();
+    let last: u64 = attack[attack.len() - 1];
+
+    let mut normal: ChainedTable<u64, (), BuildNoHashHasher<u64>> =
+        ChainedTable::with_buckets(8, BuildNoHashHasher::default());
+    let mut attacked: ChainedTable<u64, (), BuildNoHashHasher<u64>> =
+        ChainedTable::with_buckets(8, BuildNoHashHasher::default());
+    for (&good, &bad) in honest.iter().zip(&attack) {
+        normal.insert(good, ());
+        attacked.insert(bad, ());
     }
 
-    println!();
-    println!("    This demonstrates why key distribution matters.");
-    println!("    An attacker who can control keys can exploit this.");
+    writeln!(out, "    Chained table, NoHash, keys 0..16:")?;
+    writeln!(out, "{}", indent(&normal.render(), 6))?;
+    writeln!(out, "\n    Chained table, NoHash, keys 0, 1024, 2048, ...:")?;
+    writeln!(out, "{}", indent(&attacked.render(), 6))?;
+    writeln!(
+        out,
+        "    The table grew to {} buckets and it didn't help: looking up {} compares {} keys",
+        attacked.bucket_count(),
+        last,
+        attacked.comparisons(&last)
+    )?;
+
+    let mut probed: OpenAddressingTable<u64, (), BuildNoHashHasher<u64>> =
+        OpenAddressingTable::with_slots(8, BuildNoHashHasher::default());
+    for &key in &attack {
+        probed.insert(key, ());
+    }
+    writeln!(out, "\n    Open addressing, same attack keys:")?;
+    writeln!(out, "{}", indent(&probed.render(), 6))?;
+    writeln!(
+        out,
+        "    Every key's home is slot 0, so they form one run: looking up {} inspects {} slots",
+        last,
+        probed.probes(&last)
+    )?;
+
+    // A fixed seed keeps the output reproducible; in production it would
+    // be random, which is what stops the attacker precomputing collisions
+    let mut keyed: ChainedTable<u64, (), Xxh3Builder> =
+        ChainedTable::with_buckets(8, Xxh3Builder::new().with_seed(0x5eed));
+    for &key in &attack {
+        keyed.insert(key, ());
+    }
+    writeln!(
+        out,
+        "\n    Chained table, seeded xxh3, the same attack keys:"
+    )?;
+    writeln!(out, "{}", indent(&keyed.render(), 6))?;
+    writeln!(
+        out,
+        "    Once the hash mixes every bit of the key, multiples of 1024 are just keys"
+    )?;
+
+    Ok(())
 }
 
 /// Explains the difference between keyed and unkeyed hashers.
@@ -171,10 +267,11 @@ pub fn collision_impact_demonstration() {
 /// Keyed hashers use a random seed, making hash values unpredictable
 /// to attackers. Unkeyed hashers always produce the same output for
 /// the same input, making them vulnerable to prediction attacks.
-pub fn keyed_vs_unkeyed_hashers() {
-    println!("\n  Keyed vs Unkeyed Hashers:");
+pub fn keyed_vs_unkeyed_hashers(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Keyed vs Unkeyed Hashers:")?;
 
-    println!(
+    writeln!(
+        out,
         "
     UNKEYED HASHERS:
     ┌─────────────────────────────────────────────────────────────┐
@@ -195,10 +292,10 @@ pub fn keyed_vs_unkeyed_hashers() {
     │ Attacker doesn't know the key, can't predict hash values!   │
     └─────────────────────────────────────────────────────────────┘
     "
-    );
+    )?;
 
     // Demonstrate with actual hashers
-    println!("    Demonstration with real hashers:");
+    writeln!(out, "    Demonstration with real hashers:")?;
 
     let value: &str = "test_input";
 
@@ -214,10 +311,10 @@ pub fn keyed_vs_unkeyed_hashers() {
         h.finish()
     };
 
-    println!("      FxHash (unkeyed):");
-    println!("        First call:  {:016x}", fx_hash1);
-    println!("        Second call: {:016x}", fx_hash2);
-    println!("        Same? {} - PREDICTABLE!", fx_hash1 == fx_hash2);
+    writeln!(out, "      FxHash (unkeyed):")?;
+    writeln!(out, "        First call:  {:016x}", fx_hash1)?;
+    writeln!(out, "        Second call: {:016x}", fx_hash2)?;
+    writeln!(out, "        Same? {} - PREDICTABLE!", fx_hash1 == fx_hash2)?;
 
     // SipHash - keyed, random per instance
     let sip_state1: StdRandomState = StdRandomState::new();
@@ -234,45 +331,62 @@ pub fn keyed_vs_unkeyed_hashers() {
         h.finish()
     };
 
-    println!();
-    println!("      SipHash (keyed with random seed):");
-    println!("        State 1: {:016x}", sip_hash1);
-    println!("        State 2: {:016x}", sip_hash2);
-    println!("        Same? {} - UNPREDICTABLE!", sip_hash1 == sip_hash2);
+    writeln!(out)?;
+    writeln!(out, "      SipHash (keyed with random seed):")?;
+    writeln!(out, "        State 1: {:016x}", sip_hash1)?;
+    writeln!(out, "        State 2: {:016x}", sip_hash2)?;
+    writeln!(
+        out,
+        "        Same? {} - UNPREDICTABLE!",
+        sip_hash1 == sip_hash2
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates why FxHash is vulnerable to HashDoS.
 ///
 /// Because FxHash is deterministic, an attacker can pre-compute
 /// colliding keys offline and use them against any target.
-pub fn vulnerable_hasher_demonstration() {
-    println!("\n  FxHash Vulnerability Demonstration:");
+pub fn vulnerable_hasher_demonstration(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  FxHash Vulnerability Demonstration:")?;
 
-    println!("    FxHash produces deterministic, predictable hashes.");
-    println!("    An attacker can find colliding keys offline:");
+    writeln!(
+        out,
+        "    FxHash produces deterministic, predictable hashes."
+    )?;
+    writeln!(out, "    An attacker can find colliding keys offline:")?;
 
     // Show that FxHash is completely predictable
     let test_keys: [&str; 5] = ["key1", "key2", "key3", "key4", "key5"];
 
-    println!();
-    println!("    FxHash values (same on every run, every machine):");
+    writeln!(out)?;
+    writeln!(out, "    FxHash values (same on every run, every machine):")?;
     for key in test_keys {
         let mut h: FxHasher = FxHasher::default();
         key.hash(&mut h);
-        println!("      hash({:?}) = {:016x}", key, h.finish());
+        writeln!(out, "      hash({:?}) = {:016x}", key, h.finish())?;
     }
+
+    Ok(())
 }
 
 /// Demonstrates how SipHash and aHash protect against HashDoS.
 ///
 /// These hashers use random seeds, making it computationally infeasible
 /// for attackers to predict hash values or find collisions.
-pub fn secure_hasher_demonstration() {
-    println!("\n  Secure Hasher Protection:");
-
-    println!("    SipHash and aHash use random seeds from the OS.");
-    println!("    Even if an attacker knows the algorithm, they can't");
-    println!("    predict hash values without knowing the secret seed.");
+pub fn secure_hasher_demonstration(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Secure Hasher Protection:")?;
+
+    writeln!(out, "    SipHash and aHash use random seeds from the OS.")?;
+    writeln!(
+        out,
+        "    Even if an attacker knows the algorithm, they can't"
+    )?;
+    writeln!(
+        out,
+        "    predict hash values without knowing the secret seed."
+    )?;
 
     // Show that each HashMap gets different hash values
     let key: &str = "potentially_malicious_input";
@@ -292,14 +406,15 @@ pub fn secure_hasher_demonstration() {
         h.finish()
     };
 
-    println!();
-    println!("    SipHash (default HashMap):");
-    println!("      Map 1 hash: {:016x}", hash1);
-    println!("      Map 2 hash: {:016x}", hash2);
-    println!(
+    writeln!(out)?;
+    writeln!(out, "    SipHash (default HashMap):")?;
+    writeln!(out, "      Map 1 hash: {:016x}", hash1)?;
+    writeln!(out, "      Map 2 hash: {:016x}", hash2)?;
+    writeln!(
+        out,
         "      Different? {} - each map has its own seed!",
         hash1 != hash2
-    );
+    )?;
 
     // aHash - same protection
     let amap1: ahash::AHashMap<&str, i32> = ahash::AHashMap::new();
@@ -316,18 +431,30 @@ pub fn secure_hasher_demonstration() {
         h.finish()
     };
 
-    println!();
-    println!("    aHash:");
-    println!("      Map 1 hash: {:016x}", ahash1);
-    println!("      Map 2 hash: {:016x}", ahash2);
-    println!(
+    writeln!(out)?;
+    writeln!(out, "    aHash:")?;
+    writeln!(out, "      Map 1 hash: {:016x}", ahash1)?;
+    writeln!(out, "      Map 2 hash: {:016x}", ahash2)?;
+    writeln!(
+        out,
         "      Different? {} - also uses random seeds!",
         ahash1 != ahash2
-    );
-
-    println!();
-    println!("    Why this protects you:");
-    println!("      - Attacker can't pre-compute collisions (unknown seed)");
-    println!("      - Even if they crash one HashMap, they need new keys for others");
-    println!("      - Brute-forcing collisions is computationally infeasible");
+    )?;
+
+    writeln!(out)?;
+    writeln!(out, "    Why this protects you:")?;
+    writeln!(
+        out,
+        "      - Attacker can't pre-compute collisions (unknown seed)"
+    )?;
+    writeln!(
+        out,
+        "      - Even if they crash one HashMap, they need new keys for others"
+    )?;
+    writeln!(
+        out,
+        "      - Brute-forcing collisions is computationally infeasible"
+    )?;
+
+    Ok(())
 }
@@ -12,45 +12,45 @@
 //! The "1-3" in SipHash 1-3 refers to the number of compression rounds:
 //! - 1 round per block during message processing
 //! - 3 rounds during finalization
+//!
 //! This is a speed-optimized variant; SipHash 2-4 is more conservative.
 
+use demo_framework::section;
 use std::collections::HashMap;
 use std::hash::{BuildHasher, DefaultHasher, Hash, Hasher, RandomState};
+use std::io::{self, Write};
 use std::time::{Duration, Instant};
 
-fn section(name: &str, what: &str, f: impl FnOnce()) {
-    println!("\n{:=<80}", "");
-    println!("DEMO: {name}");
-    println!("  {what}");
-    println!("{:=<80}", "");
-
-    f();
-}
-
-pub fn run_all() {
+pub fn run_all(out: &mut dyn Write) -> io::Result<()> {
     section(
+        out,
         "default_hashmap_usage",
         "Default HashMap uses RandomState (keyed SipHash) under the hood",
         default_hashmap_usage,
-    );
+    )?;
 
     section(
+        out,
         "examining_siphash_output",
         "Hash outputs differ strongly for small input changes (avalanche effect)",
         examining_siphash_output,
-    );
+    )?;
 
     section(
+        out,
         "keyed_hash_demonstration",
         "Different RandomState instances typically hash the same value differently",
         keyed_hash_demonstration,
-    );
+    )?;
 
     section(
+        out,
         "performance_characteristics",
         "Rough timing across key sizes (not a benchmark)",
         performance_characteristics,
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates the default HashMap using SipHash.
@@ -58,8 +58,8 @@ pub fn run_all() {
 /// When you write `HashMap::new()`, you get a HashMap using RandomState,
 /// which creates SipHash instances with a randomly generated key.
 /// This is the most common way to use HashMaps in Rust.
-pub fn default_hashmap_usage() {
-    println!("\n  Default HashMap with SipHash:");
+pub fn default_hashmap_usage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Default HashMap with SipHash:")?;
 
     // This HashMap uses SipHash via RandomState under the hood.
     // You don't need to specify anything - it's the default choice
@@ -70,7 +70,7 @@ pub fn default_hashmap_usage() {
     scores.insert("Bob".to_string(), 85);
     scores.insert("Charlie".to_string(), 92);
 
-    println!("    Scores: {:?}", scores);
+    writeln!(out, "    Scores: {:?}", scores)?;
 
     // The same operations work as always - SipHash is transparent to the user.
     // Under the hood, each operation:
@@ -78,18 +78,21 @@ pub fn default_hashmap_usage() {
     // 2. Feeds the key data through the hasher
     // 3. Uses the resulting hash to find the bucket
     if let Some(score) = scores.get("Alice") {
-        println!("    Alice's score: {}", score);
+        writeln!(out, "    Alice's score: {}", score)?;
     }
 
     // You can also create with capacity for better performance
     // when you know approximately how many items you'll store.
     let mut with_capacity: HashMap<String, i8> = HashMap::with_capacity(100);
     with_capacity.insert("test".to_string(), 42);
-    println!(
+    writeln!(
+        out,
         "    Map with capacity: len={}, capacity={}",
         with_capacity.len(),
         with_capacity.capacity()
-    );
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates how to examine the hash value SipHash produces.
@@ -97,8 +100,8 @@ pub fn default_hashmap_usage() {
 /// This shows the avalanche effect - small changes in input produce
 /// dramatically different hash outputs. This is a hallmark of good
 /// hash functions and helps ensure uniform distribution.
-pub fn examining_siphash_output() {
-    println!("\n  Examining SipHash Output:");
+pub fn examining_siphash_output(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  Examining SipHash Output:")?;
 
     // RandomState is the BuildHasher that creates SipHash instances.
     // Each RandomState gets its own random 128-bit key.
@@ -107,33 +110,47 @@ pub fn examining_siphash_output() {
     // Hash some sample values and observe the outputs
     let samples: [&str; 4] = ["hello", "hallo", "Hello", "world"];
 
-    println!("    String hashes:");
+    writeln!(out, "    String hashes:")?;
     for &value in &samples {
         let mut hasher: DefaultHasher = build_hasher.build_hasher();
         value.hash(&mut hasher);
         let hash: u64 = hasher.finish();
         // Show the full hash value - notice how different they are
-        println!("      hash({:?}) = {}", value, hash);
+        writeln!(out, "      hash({:?}) = {}", value, hash)?;
     }
 
-    println!();
-    println!("    Notice how similar inputs produce very different hashes.");
-    println!("    'hello' vs 'hallo' - just one character changed,");
-    println!("    but the hash values are completely different.");
-    println!("    This is the 'avalanche effect' - a hallmark of good hash functions.");
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    Notice how similar inputs produce very different hashes."
+    )?;
+    writeln!(out, "    'hello' vs 'hallo' - just one character changed,")?;
+    writeln!(out, "    but the hash values are completely different.")?;
+    writeln!(
+        out,
+        "    This is the 'avalanche effect' - a hallmark of good hash functions."
+    )?;
 
     // Also demonstrate with integers
-    println!("\n    Integer hashes:");
+    writeln!(out, "\n    Integer hashes:")?;
     for value in [0i32, 1, 42, 100, 1_000, -1] {
         let mut hasher: DefaultHasher = build_hasher.build_hasher();
         value.hash(&mut hasher);
-        println!("      hash({:5}) = {}", value, hasher.finish());
+        writeln!(out, "      hash({:5}) = {}", value, hasher.finish())?;
     }
 
     // Important note about reproducibility
-    println!();
-    println!("    Note: Hash values will differ between program runs.");
-    println!("    This unpredictability is what protects against HashDoS attacks.");
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    Note: Hash values will differ between program runs."
+    )?;
+    writeln!(
+        out,
+        "    This unpredictability is what protects against HashDoS attacks."
+    )?;
+
+    Ok(())
 }
 
 /// Demonstrates that SipHash is keyed (seeded with random data).
@@ -141,8 +158,8 @@ pub fn examining_siphash_output() {
 /// Two different RandomState instances will produce different hashes
 /// for the same input, because they have different random keys.
 /// This is the key security feature that prevents HashDoS attacks.
-pub fn keyed_hash_demonstration() {
-    println!("\n  SipHash is a Keyed Hash:");
+pub fn keyed_hash_demonstration(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  SipHash is a Keyed Hash:")?;
 
     // Create two different RandomState instances.
     // Each gets its own random 128-bit key from the OS.
@@ -161,28 +178,45 @@ pub fn keyed_hash_demonstration() {
     value.hash(&mut hasher2);
     let hash2: u64 = hasher2.finish();
 
-    println!("    Same value, different RandomState instances:");
-    println!("      State 1 hash: {}", hash1);
-    println!("      State 2 hash: {}", hash2);
-    println!("      Are they equal? {}", hash1 == hash2);
+    writeln!(out, "    Same value, different RandomState instances:")?;
+    writeln!(out, "      State 1 hash: {}", hash1)?;
+    writeln!(out, "      State 2 hash: {}", hash2)?;
+    writeln!(out, "      Are they equal? {}", hash1 == hash2)?;
 
     // Explain the security implications
-    println!();
-    println!("    This is why an attacker can't pre-compute colliding keys:");
-    println!("    they don't know which random seed your HashMap will use.");
-    println!();
-    println!("    Each HashMap instance gets its own RandomState, so even");
-    println!("    if an attacker crashes one HashMap with collisions,");
-    println!("    they'd need different keys for each HashMap instance.");
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    This is why an attacker can't pre-compute colliding keys:"
+    )?;
+    writeln!(
+        out,
+        "    they don't know which random seed your HashMap will use."
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    Each HashMap instance gets its own RandomState, so even"
+    )?;
+    writeln!(
+        out,
+        "    if an attacker crashes one HashMap with collisions,"
+    )?;
+    writeln!(
+        out,
+        "    they'd need different keys for each HashMap instance."
+    )?;
 
     // Demonstrate that the same RandomState produces consistent hashes
-    println!();
-    println!("    However, the SAME RandomState is consistent:");
+    writeln!(out)?;
+    writeln!(out, "    However, the SAME RandomState is consistent:")?;
     let mut hasher3: DefaultHasher = state1.build_hasher();
     value.hash(&mut hasher3);
     let hash3: u64 = hasher3.finish();
-    println!("      State 1 hash (again): {}", hash3);
-    println!("      Same as before? {}", hash1 == hash3);
+    writeln!(out, "      State 1 hash (again): {}", hash3)?;
+    writeln!(out, "      Same as before? {}", hash1 == hash3)?;
+
+    Ok(())
 }
 
 /// Demonstrates SipHash performance characteristics.
@@ -190,8 +224,8 @@ pub fn keyed_hash_demonstration() {
 /// SipHash has consistent performance regardless of input patterns.
 /// This is important because some faster hashers can be exploited
 /// with adversarial input to cause worst-case performance.
-pub fn performance_characteristics() {
-    println!("\n  SipHash Performance Characteristics:");
+pub fn performance_characteristics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "\n  SipHash Performance Characteristics:")?;
 
     let build_hasher: RandomState = RandomState::new();
     let iterations: i32 = 100_000;
@@ -201,7 +235,11 @@ pub fn performance_characteristics() {
     let medium_key: &str = "hello world, this is a medium length string";
     let large_key: String = "x".repeat(1_000);
 
-    println!("    Testing {} iterations for each key size:", iterations);
+    writeln!(
+        out,
+        "    Testing {} iterations for each key size:",
+        iterations
+    )?;
 
     for (name, key) in [
         ("small (2 bytes)", small_key.to_string()),
@@ -222,9 +260,12 @@ pub fn performance_characteristics() {
         let throughput_mbps: f64 =
             (key.len() as f64 * iterations as f64) / elapsed.as_secs_f64() / 1_000_000.0;
 
-        println!(
+        writeln!(
+            out,
             "      {} key: {:.1} ns/hash, {:.1} MB/s",
             name, ns_per_hash, throughput_mbps
-        );
+        )?;
     }
+
+    Ok(())
 }
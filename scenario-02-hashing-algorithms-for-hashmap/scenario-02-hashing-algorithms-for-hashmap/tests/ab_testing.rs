@@ -0,0 +1,128 @@
+//! Statistical checks of experiment bucketing.
+//!
+//! Splits must match their weights (chi-squared goodness of fit), and two
+//! experiments must be independent (chi-squared test of independence), for
+//! many arm layouts and experiment names. Critical values are at p = 0.001;
+//! every input is deterministic, so the tests are not flaky.
+
+use hashing_demo::ab_testing_examples::{
+    Experiment, arm_counts, chi_squared_independence, chi_squared_weighted, cross_tabulate,
+};
+
+const USERS: u64 = 100_000;
+
+/// Chi-squared critical values at p = 0.001, indexed by degrees of freedom.
+const CRITICAL: [f64; 10] = [
+    0.0, 10.83, 13.82, 16.27, 18.47, 20.52, 22.46, 24.32, 26.12, 27.88,
+];
+
+fn experiment(name: &str, weights: &[u32]) -> Experiment {
+    let names: Vec<String> = (0..weights.len()).map(|i| format!("arm-{}", i)).collect();
+    let arms: Vec<(&str, u32)> = names
+        .iter()
+        .map(String::as_str)
+        .zip(weights.iter().copied())
+        .collect();
+    Experiment::new(name, &arms)
+}
+
+#[test]
+fn assignments_are_pinned_across_releases() {
+    // xxh3 is a specified algorithm: these values must never change, or
+    // every running experiment would reshuffle its users on upgrade
+    let checkout: Experiment =
+        Experiment::new("checkout-redesign", &[("control", 50), ("treatment", 50)]);
+    assert_eq!(checkout.seed(), 0xfef2e02fefd70952);
+    let arms: Vec<&str> = (1..=5).map(|id| checkout.arm(id)).collect();
+    assert_eq!(
+        arms,
+        vec!["control", "control", "treatment", "treatment", "control"]
+    );
+}
+
+#[test]
+fn splits_match_their_weights() {
+    let layouts: [&[u32]; 5] = [
+        &[50, 50],
+        &[90, 10],
+        &[50, 25, 25],
+        &[1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
+        &[70, 20, 9, 1],
+    ];
+    for (i, weights) in layouts.into_iter().enumerate() {
+        for name in ["checkout", "search", "onboarding"] {
+            let experiment: Experiment = experiment(&format!("{}-{}", name, i), weights);
+            let counts: Vec<u64> = arm_counts(&experiment, 0..USERS);
+            let chi: f64 = chi_squared_weighted(&counts, weights);
+            assert!(
+                chi < CRITICAL[weights.len() - 1],
+                "{} {:?}: chi-squared {:.2}, counts {:?}",
+                experiment.name(),
+                weights,
+                chi,
+                counts
+            );
+        }
+    }
+}
+
+#[test]
+fn zero_weight_arms_get_no_users() {
+    let experiment: Experiment = experiment("paused", &[50, 0, 50]);
+    let counts: Vec<u64> = arm_counts(&experiment, 0..USERS);
+    assert_eq!(counts[1], 0);
+    assert_eq!(counts.iter().sum::<u64>(), USERS);
+}
+
+#[test]
+#[should_panic(expected = "an experiment needs a non-zero weight")]
+fn all_zero_weights_panic() {
+    experiment("empty", &[0, 0]);
+}
+
+#[test]
+fn differently_named_experiments_are_independent() {
+    let names: [&str; 4] = ["checkout", "search", "pricing", "onboarding"];
+    for (i, a) in names.iter().enumerate() {
+        for b in &names[i + 1..] {
+            let first: Experiment = experiment(a, &[50, 50]);
+            let second: Experiment = experiment(b, &[34, 33, 33]);
+            let table: Vec<Vec<u64>> = cross_tabulate(&first, &second, 0..USERS);
+            // (2 - 1) * (3 - 1) degrees of freedom
+            let chi: f64 = chi_squared_independence(&table);
+            assert!(chi < CRITICAL[2], "{} x {}: chi-squared {:.2}", a, b, chi);
+        }
+    }
+}
+
+#[test]
+fn sharing_a_seed_makes_experiments_identical() {
+    let first: Experiment = Experiment::with_seed("a", 7, &[("off", 50), ("on", 50)]);
+    let second: Experiment = Experiment::with_seed("b", 7, &[("off", 50), ("on", 50)]);
+    let table: Vec<Vec<u64>> = cross_tabulate(&first, &second, 0..USERS);
+    assert_eq!(table[0][1] + table[1][0], 0);
+    assert!(chi_squared_independence(&table) > CRITICAL[1]);
+}
+
+#[test]
+fn ramping_up_only_moves_control_users() {
+    let stages: [u32; 5] = [1, 5, 20, 50, 100];
+    for pair in stages.windows(2) {
+        let before: Experiment = experiment("ramp", &[100 - pair[0], pair[0]]);
+        let after: Experiment = experiment("ramp", &[100 - pair[1], pair[1]]);
+        for id in 0..USERS {
+            if before.assign(id) == 1 {
+                assert_eq!(after.assign(id), 1, "user {} left treatment", id);
+            }
+        }
+    }
+}
+
+#[test]
+fn proportional_weights_give_the_same_assignment() {
+    let percent: Experiment = experiment("scaled", &[90, 10]);
+    let per_mille: Experiment = experiment("scaled", &[900, 100]);
+    for id in 0..USERS {
+        assert_eq!(percent.assign(id), per_mille.assign(id));
+    }
+}
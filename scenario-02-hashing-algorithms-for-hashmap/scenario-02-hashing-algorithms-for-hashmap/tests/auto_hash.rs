@@ -0,0 +1,115 @@
+//! AutoHashMap's selection: each key type gets the hasher the README
+//! recommends, the maps hash exactly as that hasher does, and they behave
+//! like a BTreeMap under random operations whichever hasher was picked.
+
+use hashing_demo::auto_hash::{
+    AutoHashMap, AutoHashSet, AutoKey, HasherChoice, Trusted, auto_map_with_capacity, choice_for,
+};
+use hashing_demo::cheatsheet::DosResistance;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rustc_hash::FxBuildHasher;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::hash::BuildHasher;
+use std::sync::Arc;
+
+#[test]
+fn key_types_get_the_recommended_hasher() {
+    assert_eq!(choice_for::<u8>(), HasherChoice::NoHash);
+    assert_eq!(choice_for::<u64>(), HasherChoice::NoHash);
+    assert_eq!(choice_for::<usize>(), HasherChoice::NoHash);
+    assert_eq!(choice_for::<i32>(), HasherChoice::NoHash);
+
+    assert_eq!(choice_for::<u128>(), HasherChoice::FxHash);
+    assert_eq!(choice_for::<char>(), HasherChoice::FxHash);
+    assert_eq!(choice_for::<(u32, u32)>(), HasherChoice::FxHash);
+    assert_eq!(choice_for::<(i64, char, bool)>(), HasherChoice::FxHash);
+    assert_eq!(choice_for::<Trusted<String>>(), HasherChoice::FxHash);
+    assert_eq!(choice_for::<Trusted<Vec<u8>>>(), HasherChoice::FxHash);
+
+    assert_eq!(choice_for::<String>(), HasherChoice::Foldhash);
+    assert_eq!(choice_for::<&str>(), HasherChoice::Foldhash);
+    assert_eq!(choice_for::<Box<str>>(), HasherChoice::Foldhash);
+    assert_eq!(choice_for::<Arc<str>>(), HasherChoice::Foldhash);
+    assert_eq!(choice_for::<Vec<u16>>(), HasherChoice::Foldhash);
+    assert_eq!(choice_for::<&[u8]>(), HasherChoice::Foldhash);
+}
+
+#[test]
+fn choices_match_the_cheat_sheet() {
+    for choice in [
+        HasherChoice::NoHash,
+        HasherChoice::FxHash,
+        HasherChoice::Foldhash,
+    ] {
+        assert_eq!(choice.facts().name, choice.name());
+    }
+    // Untrusted text is the one case with any HashDoS resistance
+    assert_eq!(
+        HasherChoice::Foldhash.facts().dos_resistance,
+        DosResistance::Minimal
+    );
+}
+
+#[test]
+fn maps_hash_with_the_chosen_hasher() {
+    let ids: AutoHashMap<u64, ()> = AutoHashMap::default();
+    for id in [0u64, 1, 42, u64::MAX] {
+        assert_eq!(ids.hasher().hash_one(id), id, "NoHash is the identity");
+    }
+
+    let cells: AutoHashSet<(i32, i32)> = AutoHashSet::default();
+    assert_eq!(
+        cells.hasher().hash_one((3, -4)),
+        FxBuildHasher.hash_one((3, -4))
+    );
+
+    // Trusted hashes as its contents, so Borrow lookups find it
+    let mut names: AutoHashMap<Trusted<String>, u32> = auto_map_with_capacity(4);
+    assert!(names.capacity() >= 4);
+    names.insert(Trusted("len".to_string()), 1);
+    assert_eq!(
+        names.hasher().hash_one(Trusted("len".to_string())),
+        FxBuildHasher.hash_one("len".to_string())
+    );
+    let len: String = "len".to_string();
+    assert_eq!(names.get(&len), Some(&1));
+
+    // foldhash's key is random per process and varied per map
+    let first: AutoHashMap<String, ()> = AutoHashMap::default();
+    let second: AutoHashMap<String, ()> = AutoHashMap::default();
+    assert_ne!(
+        first.hasher().hash_one("session"),
+        second.hasher().hash_one("session")
+    );
+}
+
+/// Random inserts and removes on an AutoHashMap and a BTreeMap, compared
+/// after every step.
+fn matches_btreemap<K: AutoKey + Ord + Clone + Debug>(generate: impl Fn(u64) -> K) {
+    let mut rng: StdRng = StdRng::seed_from_u64(0xA070);
+    let mut map: AutoHashMap<K, u64> = AutoHashMap::default();
+    let mut model: BTreeMap<K, u64> = BTreeMap::new();
+    for step in 0..5_000u64 {
+        let key: K = generate(rng.random_range(0..300));
+        if rng.random_bool(0.7) {
+            assert_eq!(map.insert(key.clone(), step), model.insert(key, step));
+        } else {
+            assert_eq!(map.remove(&key), model.remove(&key));
+        }
+        assert_eq!(map.len(), model.len());
+    }
+    let mut contents: Vec<(K, u64)> = map.into_iter().collect();
+    contents.sort();
+    assert_eq!(contents, model.into_iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn every_choice_behaves_like_a_btreemap() {
+    // Spread over the high bits too, which NoHash leaves as they are
+    matches_btreemap(|n| n << 40 | n);
+    matches_btreemap(|n| (n as i32 % 17, n as i32 / 17));
+    matches_btreemap(|n| Trusted(format!("ident_{}", n)));
+    matches_btreemap(|n| format!("user-input-{}", n));
+}
@@ -0,0 +1,313 @@
+//! The teaching tables against the `BTreeMap` reference, and what their
+//! renderers draw for collision-free and fully colliding keys.
+//!
+//! `demo_framework::ops` programs are generated from a fixed seed and run
+//! on a teaching table and the reference; after every step the two must
+//! agree, and the table's own invariants must hold.
+
+use demo_framework::ops::{Op, OpTarget, Outcome, Program, check, random_op, scan};
+use hashing_demo::bucket_visual::{ChainedTable, OpenAddressingTable, Slot};
+use nohash_hasher::BuildNoHashHasher;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+use xxhash_rust::xxh3::Xxh3Builder;
+
+type NoHash = BuildNoHashHasher<u64>;
+type Chained = ChainedTable<u64, u32, NoHash>;
+type OpenAddressed = OpenAddressingTable<u64, u32, NoHash>;
+
+/// Operation keys are drawn from `0..KEY_SPACE`.
+const KEY_SPACE: u32 = 40;
+
+/// Operation key `n` as a table key. Even keys stay small and odd ones
+/// become multiples of 64, so chains and probe runs form.
+fn table_key(n: u32) -> u64 {
+    if n.is_multiple_of(2) {
+        n as u64
+    } else {
+        n as u64 * 64
+    }
+}
+
+/// The operation key behind a table key.
+fn op_key(key: u64) -> u32 {
+    if key < 64 {
+        key as u32
+    } else {
+        (key / 64) as u32
+    }
+}
+
+/// Mixed operations over a small key space, so keys come back.
+fn operations(count: usize, seed: u64) -> Program {
+    let mut rng: StdRng = StdRng::seed_from_u64(seed);
+    Program(
+        (0..count)
+            .map(|_| random_op(KEY_SPACE, &mut |bound| rng.random_range(0..bound)))
+            .collect(),
+    )
+}
+
+/// A teaching table run as a map, checking its own invariants after every
+/// operation.
+struct Table<T>(T);
+
+impl Table<Chained> {
+    fn entries(&self) -> Vec<(u64, u32)> {
+        (0..self.0.bucket_count())
+            .flat_map(|index| self.0.bucket(index).iter().copied())
+            .collect()
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.0.resize(self.0.len());
+    }
+
+    fn check_invariants(&self) {
+        // Growth keeps the load factor at most 1
+        assert!(self.0.len() <= self.0.bucket_count());
+        assert_eq!(self.0.chain_lengths().iter().sum::<usize>(), self.0.len());
+    }
+}
+
+impl Table<OpenAddressed> {
+    fn entries(&self) -> Vec<(u64, u32)> {
+        self.0
+            .slots()
+            .iter()
+            .filter_map(|slot| match slot {
+                Slot::Full(key, value) => Some((*key, *value)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.0.resize(self.0.len() * 2);
+    }
+
+    fn check_invariants(&self) {
+        // At least one empty slot always remains to end a miss
+        assert!(self.0.len() + self.0.tombstones() < self.0.slot_count());
+        let tombstones: usize = self
+            .0
+            .slots()
+            .iter()
+            .filter(|slot| matches!(slot, Slot::Tombstone))
+            .count();
+        assert_eq!(tombstones, self.0.tombstones());
+    }
+}
+
+macro_rules! table_target {
+    ($table:ty) => {
+        impl OpTarget for Table<$table> {
+            fn apply(&mut self, op: Op) -> Outcome {
+                let outcome: Outcome = match op {
+                    Op::Insert(n, value) => Outcome::Value(self.0.insert(table_key(n), value)),
+                    Op::Remove(n) => Outcome::Value(self.0.remove(&table_key(n))),
+                    Op::Get(n) => Outcome::Value(self.0.get(&table_key(n)).copied()),
+                    Op::Bump(n) => {
+                        let count: u32 = self.0.get(&table_key(n)).copied().unwrap_or(0) + 1;
+                        self.0.insert(table_key(n), count);
+                        Outcome::Value(Some(count))
+                    }
+                    Op::RangeScan(start, end) => {
+                        let entries: Vec<(u32, u32)> = self
+                            .entries()
+                            .into_iter()
+                            .map(|(key, value)| (op_key(key), value))
+                            .collect();
+                        Outcome::Entries(scan(entries.iter().map(|(k, v)| (k, v)), start, end))
+                    }
+                    Op::Retain(modulus) => {
+                        for (key, value) in self.entries() {
+                            if value % modulus == 0 {
+                                self.0.remove(&key);
+                            }
+                        }
+                        Outcome::Len(self.0.len())
+                    }
+                    Op::ShrinkToFit => {
+                        self.shrink_to_fit();
+                        Outcome::Len(self.0.len())
+                    }
+                    Op::Clear => {
+                        for (key, _) in self.entries() {
+                            self.0.remove(&key);
+                        }
+                        Outcome::Cleared
+                    }
+                };
+                self.check_invariants();
+                outcome
+            }
+
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+        }
+    };
+}
+
+table_target!(Chained);
+table_target!(OpenAddressed);
+
+/// Runs `program` on `table`, panicking at the first divergence, and
+/// returns the reference's final contents.
+fn replay<T>(program: &Program, table: &mut Table<T>) -> BTreeMap<u64, u32>
+where
+    Table<T>: OpTarget,
+{
+    if let Err(divergence) = check(program, table) {
+        panic!("{}", divergence);
+    }
+    let mut reference: BTreeMap<u32, u32> = BTreeMap::new();
+    program.run(&mut reference);
+    reference
+        .into_iter()
+        .map(|(n, value)| (table_key(n), value))
+        .collect()
+}
+
+#[test]
+fn chained_table_matches_the_reference() {
+    for seed in 0..10 {
+        let mut table: Table<Chained> = Table(ChainedTable::with_buckets(4, NoHash::default()));
+        let reference: BTreeMap<u64, u32> = replay(&operations(500, seed), &mut table);
+        let table: Chained = table.0;
+        for (key, value) in &reference {
+            assert_eq!(table.get(key), Some(value));
+            assert_eq!(
+                table
+                    .bucket(table.bucket_of(key))
+                    .iter()
+                    .filter(|(k, _)| k == key)
+                    .count(),
+                1
+            );
+        }
+        assert_eq!(table.chain_lengths().iter().sum::<usize>(), reference.len());
+    }
+}
+
+#[test]
+fn open_addressing_table_matches_the_reference() {
+    for seed in 0..10 {
+        let mut table: Table<OpenAddressed> =
+            Table(OpenAddressingTable::with_slots(4, NoHash::default()));
+        let reference: BTreeMap<u64, u32> = replay(&operations(500, seed), &mut table);
+        let table: OpenAddressed = table.0;
+        for (key, value) in &reference {
+            assert_eq!(table.get(key), Some(value));
+        }
+        for key in 0..KEY_SPACE as u64 * 64 {
+            if !reference.contains_key(&key) {
+                assert_eq!(table.get(&key), None);
+            }
+        }
+    }
+}
+
+#[test]
+fn tombstones_keep_probe_runs_intact() {
+    let mut table: OpenAddressingTable<u64, (), NoHash> =
+        OpenAddressingTable::with_slots(8, NoHash::default());
+    for key in [2, 10, 18] {
+        table.insert(key, ());
+    }
+    assert_eq!(table.probe_distance(4), Some(2));
+    table.remove(&10);
+    assert_eq!(table.slots()[3], Slot::Tombstone);
+    // 18 is still found by walking past the tombstone
+    assert_eq!(table.get(&18), Some(&()));
+    assert_eq!(table.probes(&18), 3);
+
+    // The next key with home 2 reuses the tombstone
+    table.insert(26, ());
+    assert_eq!(table.slots()[3], Slot::Full(26, ()));
+    assert_eq!(table.tombstones(), 0);
+
+    table.remove(&2);
+    table.remove(&26);
+    table.resize(8);
+    assert_eq!(table.tombstones(), 0);
+    assert_eq!(table.home_of(&18), 2);
+    assert_eq!(table.probe_distance(2), Some(0));
+}
+
+#[test]
+fn colliding_keys_share_one_bucket() {
+    let attack: Vec<u64> = (0..16).map(|i| i * 1024).collect();
+    let mut chained: ChainedTable<u64, (), NoHash> =
+        ChainedTable::with_buckets(8, NoHash::default());
+    let mut probed: OpenAddressingTable<u64, (), NoHash> =
+        OpenAddressingTable::with_slots(8, NoHash::default());
+    for &key in &attack {
+        chained.insert(key, ());
+        probed.insert(key, ());
+    }
+    assert_eq!(chained.bucket_count(), 16);
+    assert_eq!(chained.longest_chain(), 16);
+    assert_eq!(chained.bucket(0).len(), 16);
+    assert_eq!(chained.comparisons(&15360), 16);
+    assert_eq!(probed.longest_probe(), 15);
+    assert_eq!(probed.probes(&15360), 16);
+    assert_eq!(probed.average_probe(), 8.5);
+
+    // A seeded hash spreads the same keys
+    let mut keyed: ChainedTable<u64, (), Xxh3Builder> =
+        ChainedTable::with_buckets(8, Xxh3Builder::new().with_seed(0x5eed));
+    for &key in &attack {
+        keyed.insert(key, ());
+    }
+    assert!(keyed.longest_chain() <= 4);
+}
+
+#[test]
+fn chained_render_groups_empty_buckets() {
+    let mut table: ChainedTable<u64, (), NoHash> = ChainedTable::with_buckets(8, NoHash::default());
+    for key in [3, 11, 6] {
+        table.insert(key, ());
+    }
+    let expected: &str = "\
+8 buckets, 3 entries, load factor 0.38, longest chain 2
+0-2 │ (empty)
+  3 │ ██  3, 11
+4-5 │ (empty)
+  6 │ █   6
+  7 │ (empty)";
+    assert_eq!(table.render(), expected);
+}
+
+#[test]
+fn long_chains_are_cut_off() {
+    let mut table: ChainedTable<u64, (), NoHash> =
+        ChainedTable::with_buckets(64, NoHash::default());
+    for i in 0..40 {
+        table.insert(i * 64, ());
+    }
+    let render: String = table.render();
+    let bucket_zero: &str = render.lines().nth(1).expect("bucket 0 line");
+    assert!(bucket_zero.contains(&format!("{} +8", "█".repeat(32))));
+    assert!(bucket_zero.ends_with("0, 64, 128, 192, 256, 320, … (40 keys)"));
+}
+
+#[test]
+fn open_addressing_render_shows_probe_distances() {
+    let mut table: OpenAddressingTable<u64, (), NoHash> =
+        OpenAddressingTable::with_slots(8, NoHash::default());
+    for key in [2, 10, 18, 5] {
+        table.insert(key, ());
+    }
+    table.remove(&10);
+    let expected: &str = "\
+8 slots, 3 entries, 1 tombstones, longest probe 2, average lookup 1.67 slots
+0 │ . . 0 x 2 0 . .";
+    assert_eq!(table.render(), expected);
+    assert_eq!(
+        table.render_slots().lines().nth(4),
+        Some("4 │ 18     home 2, probe 2")
+    );
+}
@@ -0,0 +1,125 @@
+//! The counting wrapper: it leaves hash values alone, counts one hash per
+//! lookup and the bytes each `Hash` impl writes, and its counts for the
+//! word-count and dedup patterns match what each pattern should cost.
+
+use hashing_demo::counting_hasher::{
+    CountingBuildHasher, HashCounts, bytes_hashed, dedup_contains_then_insert,
+    dedup_insert_returns, sample_event_ids, sample_words, tally_contains_then_update, tally_entry,
+    tally_get_mut_or_insert, tally_insert_missing_then_update,
+};
+use rustc_hash::FxBuildHasher;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::hash::BuildHasher;
+
+#[test]
+fn wrapping_keeps_the_hash_values() {
+    let sip: RandomState = RandomState::new();
+    let counted: CountingBuildHasher<RandomState> = CountingBuildHasher::new(sip.clone());
+    let fold = foldhash::fast::FixedState::with_seed(3);
+    let counted_fold = CountingBuildHasher::new(fold.clone());
+    for key in ["", "a", "session:00042"] {
+        assert_eq!(counted.hash_one(key), sip.hash_one(key));
+        assert_eq!(counted_fold.hash_one(key), fold.hash_one(key));
+    }
+    for key in [0u64, 7, u64::MAX] {
+        assert_eq!(counted.hash_one(key), sip.hash_one(key));
+        assert_eq!(counted_fold.hash_one(key), fold.hash_one(key));
+    }
+    assert_eq!(
+        counted.counts(),
+        HashCounts {
+            hashes: 6,
+            bytes: 1 + 2 + 14 + 3 * 8,
+        }
+    );
+
+    let clone: CountingBuildHasher<RandomState> = counted.clone();
+    clone.hash_one(1u8);
+    assert_eq!(counted.counts().hashes, 7, "clones share the counters");
+    counted.reset();
+    assert_eq!(clone.counts(), HashCounts::default());
+}
+
+#[test]
+fn bytes_follow_the_hash_impl() {
+    assert_eq!(bytes_hashed(&1u64), 8);
+    assert_eq!(bytes_hashed(&1i32), 4, "signed ints go through write_u32");
+    assert_eq!(bytes_hashed("abc"), 4);
+    assert_eq!(bytes_hashed(&"abc".to_string()), bytes_hashed("abc"));
+    assert_eq!(bytes_hashed(&[1u8, 2, 3][..]), 8 + 3);
+    assert_eq!(bytes_hashed(&(1u8, "ab")), 1 + 3);
+    assert_eq!(bytes_hashed(&Some(5u16)), 8 + 2);
+}
+
+type Tally = for<'a> fn(&mut HashMap<&'a str, u32, CountingBuildHasher<FxBuildHasher>>, &'a str);
+
+#[test]
+fn word_count_patterns_cost_what_they_should() {
+    let words: Vec<String> = sample_words(3_000, 200);
+    let patterns: [(Tally, u64); 4] = [
+        // Every pattern but entry skips hashing on the first, empty lookup
+        (tally_contains_then_update, 2 * 3_000 - 1),
+        (tally_insert_missing_then_update, 2 * 3_000 + 200 - 1),
+        (tally_get_mut_or_insert, 3_000 + 200 - 1),
+        (tally_entry, 3_000),
+    ];
+    let mut expected: HashMap<&str, u32> = HashMap::new();
+    for word in &words {
+        *expected.entry(word).or_default() += 1;
+    }
+
+    for (tally, hashes) in patterns {
+        let state: CountingBuildHasher<FxBuildHasher> = CountingBuildHasher::new(FxBuildHasher);
+        let mut counts: HashMap<&str, u32, _> =
+            HashMap::with_capacity_and_hasher(200, state.clone());
+        let ((), used) = state.measure(|| {
+            for word in &words {
+                tally(&mut counts, word);
+            }
+        });
+        assert_eq!(used.hashes, hashes);
+        assert_eq!(used.bytes, 9 * hashes, "8-byte words plus 0xff");
+        assert!(counts.iter().all(|(word, count)| expected[word] == *count));
+        assert_eq!(counts.len(), expected.len());
+    }
+}
+
+#[test]
+fn growing_rehashes_and_presizing_does_not() {
+    let state: CountingBuildHasher<FxBuildHasher> = CountingBuildHasher::new(FxBuildHasher);
+    let mut grown: HashSet<u32, _> = HashSet::with_hasher(state.clone());
+    let ((), used) = state.measure(|| {
+        for i in 0..10_000 {
+            grown.insert(i);
+        }
+    });
+    assert!(used.hashes > 10_000 && used.hashes < 30_000, "{used:?}");
+
+    // extend reserves for the iterator's size_hint up front
+    let mut extended: HashSet<u32, _> = HashSet::with_hasher(state.clone());
+    let ((), used) = state.measure(|| extended.extend(0..10_000));
+    assert_eq!(used.hashes, 10_000);
+
+    let mut presized: HashSet<u32, _> = HashSet::with_capacity_and_hasher(10_000, state.clone());
+    let ((), used) = state.measure(|| presized.extend(0..10_000));
+    assert_eq!(used.hashes, 10_000);
+}
+
+#[test]
+fn dedup_passes_agree_and_the_naive_one_hashes_more() {
+    let ids: Vec<String> = sample_event_ids(5_000, 700);
+    let state: CountingBuildHasher<FxBuildHasher> = CountingBuildHasher::new(FxBuildHasher);
+
+    let mut naive: HashSet<String, _> = HashSet::with_capacity_and_hasher(700, state.clone());
+    let (naive_unique, naive_counts) =
+        state.measure(|| dedup_contains_then_insert(&ids, &mut naive));
+    let mut borrowed: HashSet<&str, _> = HashSet::with_capacity_and_hasher(700, state.clone());
+    let (unique, counts) = state.measure(|| dedup_insert_returns(&ids, &mut borrowed));
+
+    assert_eq!(naive_unique, unique);
+    assert_eq!(unique.len(), 700);
+    assert_eq!(unique[..3], ["evt-000000", "evt-000013", "evt-000026"]);
+    assert_eq!(counts.hashes, 5_000);
+    assert_eq!(naive_counts.hashes, 5_000 + 700 - 1);
+}
@@ -0,0 +1,309 @@
+//! Differential tests: the same random `demo_framework::ops` programs applied
+//! to a HashMap per hasher, and to the BTreeMap reference that doesn't hash
+//! at all.
+//!
+//! A hasher can't change what a HashMap contains, only where it keeps it.
+//! So after every step each map must agree with the BTreeMap on the result
+//! of the operation and the length. Range scans and the final check read
+//! the HashMap's contents by iteration, compared as a set. Lookups go through the borrowed form of
+//! the key (`&str` for `String`, `&[u8]` for `Vec<u8>`), which only works if
+//! the hasher hashes both forms alike.
+//!
+//! The fixed-seed builders the demos and the other tests rely on are also
+//! checked for being fixed: two builders from the same seed must hash alike
+//! and lay out the same operations in the same iteration order.
+
+use demo_framework::ops::{Op, OpTarget, Outcome, Program, check, random_op};
+use nohash_hasher::BuildNoHashHasher;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rustc_hash::FxBuildHasher;
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash};
+use xxhash_rust::xxh3::Xxh3Builder;
+
+/// A key type with the borrowed form lookups use.
+trait Key: Hash + Eq + Ord + Clone + Debug + Borrow<Self::Borrowed> {
+    type Borrowed: Hash + Eq + Ord + Debug + ?Sized;
+
+    /// Key number `n`. Different numbers give different keys.
+    fn generate(n: u64) -> Self;
+}
+
+impl Key for u64 {
+    type Borrowed = u64;
+
+    fn generate(n: u64) -> Self {
+        // Multiples of 2^32 too, which only differ in the high bits
+        if n.is_multiple_of(2) { n } else { n << 32 }
+    }
+}
+
+impl Key for String {
+    type Borrowed = str;
+
+    fn generate(n: u64) -> Self {
+        // Empty, prefix-related and non-ASCII strings alongside plain ones
+        match n % 5 {
+            0 => "a".repeat(n as usize / 5),
+            1 => format!("user-{}", n),
+            2 => format!("ü{}ß", n),
+            3 => format!("{}\u{0}", n),
+            _ => format!("{:08x}", n.wrapping_mul(0x9e37_79b9_7f4a_7c15)),
+        }
+    }
+}
+
+impl Key for Vec<u8> {
+    type Borrowed = [u8];
+
+    fn generate(n: u64) -> Self {
+        // A run of up to six zeros, then the number's bytes without their
+        // leading zeros
+        let mut bytes: Vec<u8> = vec![0; (n % 7) as usize];
+        bytes.extend(n.to_be_bytes().iter().skip_while(|&&byte| byte == 0));
+        bytes
+    }
+}
+
+/// A HashMap run as an [`OpTarget`]: operation key `n` is `K::generate(n)`.
+struct Keyed<K, S> {
+    map: HashMap<K, u32, S>,
+    /// The operation key behind each generated key, so a range scan read
+    /// by iteration can be reported in operation keys.
+    ids: BTreeMap<K, u32>,
+}
+
+impl<K: Key, S: BuildHasher> Keyed<K, S> {
+    fn new(build_hasher: S) -> Self {
+        Keyed {
+            map: HashMap::with_hasher(build_hasher),
+            ids: BTreeMap::new(),
+        }
+    }
+
+    fn key(&mut self, n: u32) -> K {
+        let key: K = K::generate(n as u64);
+        self.ids.entry(key.clone()).or_insert(n);
+        key
+    }
+}
+
+impl<K: Key, S: BuildHasher> OpTarget for Keyed<K, S> {
+    fn apply(&mut self, op: Op) -> Outcome {
+        match op {
+            Op::Insert(n, value) => {
+                let key: K = self.key(n);
+                Outcome::Value(self.map.insert(key, value))
+            }
+            Op::Remove(n) => {
+                let key: K = self.key(n);
+                Outcome::Value(self.map.remove(key.borrow()))
+            }
+            Op::Get(n) => {
+                let key: K = self.key(n);
+                let found: Option<u32> = self.map.get(key.borrow()).copied();
+                assert_eq!(self.map.contains_key(key.borrow()), found.is_some());
+                assert_eq!(
+                    self.map.get_key_value(key.borrow()).map(|(k, _)| k),
+                    found.map(|_| &key)
+                );
+                Outcome::Value(found)
+            }
+            Op::Bump(n) => {
+                let key: K = self.key(n);
+                let count: &mut u32 = self.map.entry(key).or_insert(0);
+                *count += 1;
+                Outcome::Value(Some(*count))
+            }
+            Op::RangeScan(start, end) => {
+                // Read by iteration, so every scan also checks what it yields
+                let ids: &BTreeMap<K, u32> = &self.ids;
+                let mut found: Vec<(u32, u32)> = contents(&self.map)
+                    .into_iter()
+                    .map(|(key, value)| (ids[key.borrow()], value))
+                    .filter(|&(n, _)| start <= n && n < end)
+                    .collect();
+                found.sort_unstable();
+                Outcome::Entries(found)
+            }
+            Op::Retain(modulus) => {
+                self.map.retain(|_, value| *value % modulus != 0);
+                Outcome::Len(self.map.len())
+            }
+            Op::ShrinkToFit => {
+                self.map.shrink_to_fit();
+                Outcome::Len(self.map.len())
+            }
+            Op::Clear => {
+                self.map.clear();
+                Outcome::Cleared
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+}
+
+/// `count` random operations over a pool of 60 keys, so keys come back
+/// after removes.
+fn operations(count: usize, seed: u64) -> Program {
+    let mut rng: StdRng = StdRng::seed_from_u64(seed);
+    Program(
+        (0..count)
+            .map(|_| random_op(60, &mut |bound| rng.random_range(0..bound)))
+            .collect(),
+    )
+}
+
+/// The map's contents read by iteration, as a set.
+fn contents<K: Key, S>(map: &HashMap<K, u32, S>) -> BTreeMap<K, u32> {
+    let pairs: BTreeMap<K, u32> = map.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    assert_eq!(pairs.len(), map.len(), "iteration yielded a key twice");
+    assert_eq!(map.keys().count(), map.len());
+    assert_eq!(map.values().count(), map.len());
+    pairs
+}
+
+/// Replays `program` on a HashMap built with `build_hasher` and on the
+/// `BTreeMap` reference, asserting they agree throughout. Returns the
+/// HashMap.
+fn replay<K: Key, S: BuildHasher>(
+    name: &str,
+    program: &Program,
+    build_hasher: S,
+) -> HashMap<K, u32, S> {
+    let mut target: Keyed<K, S> = Keyed::new(build_hasher);
+    if let Err(divergence) = check(program, &mut target) {
+        panic!("{}: {}", name, divergence);
+    }
+    let mut reference: BTreeMap<u32, u32> = BTreeMap::new();
+    program.run(&mut reference);
+    let expected: BTreeMap<K, u32> = reference
+        .into_iter()
+        .map(|(n, value)| (K::generate(n as u64), value))
+        .collect();
+    assert_eq!(contents(&target.map), expected, "{}: at the end", name);
+    target.map
+}
+
+/// `build_hasher` hashes every key exactly like its borrowed form.
+fn assert_borrow_contract<K: Key, S: BuildHasher>(name: &str, build_hasher: &S) {
+    for n in 0..200 {
+        let key: K = K::generate(n);
+        let borrowed: &K::Borrowed = key.borrow();
+        assert_eq!(
+            build_hasher.hash_one(&key),
+            build_hasher.hash_one(borrowed),
+            "{}: {:?} hashes unlike its borrowed form",
+            name,
+            key
+        );
+    }
+}
+
+/// Checks a hasher whose builders are random: only agreement with the
+/// reference can be asserted.
+fn check_random<K: Key, S: BuildHasher>(name: &str, build: impl Fn() -> S) {
+    assert_borrow_contract::<K, S>(name, &build());
+    for seed in 0..8 {
+        replay::<K, S>(name, &operations(600, seed), build());
+    }
+}
+
+/// Checks a fixed-seed hasher: agreement with the reference, plus two
+/// builders from `build` hashing alike and iterating in the same order.
+fn check_fixed<K: Key, S: BuildHasher>(name: &str, build: impl Fn() -> S) {
+    check_random::<K, S>(name, &build);
+    let (first, second) = (build(), build());
+    for n in 0..200 {
+        let key: K = K::generate(n);
+        assert_eq!(
+            first.hash_one(&key),
+            second.hash_one(&key),
+            "{}: a fixed seed hashed {:?} two ways",
+            name,
+            key
+        );
+    }
+    let program: Program = operations(600, 99);
+    let order = |map: HashMap<K, u32, S>| -> Vec<K> { map.into_keys().collect() };
+    assert_eq!(
+        order(replay(name, &program, build())),
+        order(replay(name, &program, build())),
+        "{}: same seed, same operations, different iteration order",
+        name
+    );
+}
+
+fn check_every_hasher<K: Key>() {
+    check_random::<K, _>("SipHash-1-3 (RandomState)", RandomState::new);
+    check_fixed::<K, _>(
+        "SipHash-1-3 (zero keys)",
+        BuildHasherDefault::<DefaultHasher>::default,
+    );
+    check_fixed::<K, _>("FxHash", || FxBuildHasher);
+    check_random::<K, _>("aHash (random)", ahash::RandomState::new);
+    check_fixed::<K, _>("aHash (with_seeds)", || {
+        ahash::RandomState::with_seeds(1, 2, 3, 4)
+    });
+    check_random::<K, _>(
+        "foldhash fast (random)",
+        foldhash::fast::RandomState::default,
+    );
+    check_fixed::<K, _>("foldhash fast (fixed)", || {
+        foldhash::fast::FixedState::with_seed(7)
+    });
+    check_fixed::<K, _>("foldhash quality (fixed)", || {
+        foldhash::quality::FixedState::with_seed(7)
+    });
+    check_fixed::<K, _>("xxHash64", || twox_hash::xxhash64::State::with_seed(7));
+    check_fixed::<K, _>("xxh3", || Xxh3Builder::new().with_seed(7));
+}
+
+#[test]
+fn every_hasher_agrees_on_integer_keys() {
+    check_every_hasher::<u64>();
+    // NoHash passes integers through, so it only handles integer keys
+    check_fixed::<u64, _>("NoHash", BuildNoHashHasher::<u64>::default);
+}
+
+#[test]
+fn every_hasher_agrees_on_string_keys_looked_up_by_str() {
+    check_every_hasher::<String>();
+}
+
+#[test]
+fn every_hasher_agrees_on_byte_keys_looked_up_by_slice() {
+    check_every_hasher::<Vec<u8>>();
+}
+
+#[test]
+fn different_seeds_hash_differently() {
+    // Not a correctness property, but a seed that changed nothing would
+    // mean the helper ignores it
+    let keys: Vec<String> = (0..100).map(String::generate).collect();
+    let differ = |a: &dyn Fn(&String) -> u64, b: &dyn Fn(&String) -> u64| -> usize {
+        keys.iter().filter(|key| a(key) != b(key)).count()
+    };
+    let ahash_a = ahash::RandomState::with_seeds(1, 2, 3, 4);
+    let ahash_b = ahash::RandomState::with_seeds(5, 6, 7, 8);
+    assert!(differ(&|k| ahash_a.hash_one(k), &|k| ahash_b.hash_one(k)) > 95);
+
+    let fold_a = foldhash::fast::FixedState::with_seed(7);
+    let fold_b = foldhash::fast::FixedState::with_seed(8);
+    assert!(differ(&|k| fold_a.hash_one(k), &|k| fold_b.hash_one(k)) > 95);
+
+    let xx_a = Xxh3Builder::new().with_seed(7);
+    let xx_b = Xxh3Builder::new().with_seed(8);
+    assert!(differ(&|k| xx_a.hash_one(k), &|k| xx_b.hash_one(k)) > 95);
+
+    let twox_a = twox_hash::xxhash64::State::with_seed(7);
+    let twox_b = twox_hash::xxhash64::State::with_seed(8);
+    assert!(differ(&|k| twox_a.hash_one(k), &|k| twox_b.hash_one(k)) > 95);
+}
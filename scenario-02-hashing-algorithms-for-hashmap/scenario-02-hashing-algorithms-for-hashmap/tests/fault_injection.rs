@@ -0,0 +1,128 @@
+//! `FaultyStore` keeps its promises: with no faults it is the wrapped
+//! store, a refusal changes nothing, a timed-out write is applied anyway,
+//! and recovery replays exactly the log up to the first corrupt record.
+
+use demo_framework::rng::Lcg;
+use hashing_demo::fault_injection_examples::{
+    FaultPlan, FaultyStore, OP_COST, Recovery, StoreError, insert_with_retry, recover,
+};
+use hashing_demo::kv_store_examples::{AnyStore, Backend, KvStore};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+#[test]
+fn without_faults_every_backend_behaves_like_the_model() {
+    for backend in Backend::ALL {
+        let mut rng: Lcg = Lcg::new(3);
+        let mut store: FaultyStore<u64, u64, AnyStore<u64>> =
+            FaultyStore::new(AnyStore::new(backend), FaultPlan::none());
+        let mut model: BTreeMap<u64, u64> = BTreeMap::new();
+
+        for step in 0..2_000 {
+            let key: u64 = rng.below(100);
+            match rng.below(3) {
+                0 => assert_eq!(store.try_insert(key, step), Ok(model.insert(key, step))),
+                1 => assert_eq!(store.try_remove(&key), Ok(model.remove(&key))),
+                _ => assert_eq!(store.try_get(&key), Ok(model.get(&key))),
+            }
+        }
+        assert_eq!(store.inner().len(), model.len(), "{}", backend.name());
+        assert_eq!(store.stats(), Default::default());
+        assert_eq!(store.elapsed(), OP_COST * 2_000);
+
+        let recovered: BTreeMap<u64, u64> = recover(store.log(), BTreeMap::new()).store;
+        assert_eq!(recovered, model, "{}", backend.name());
+    }
+}
+
+#[test]
+fn refused_writes_change_nothing_and_retries_get_through() {
+    let mut store: FaultyStore<u64, u64, BTreeMap<u64, u64>> =
+        FaultyStore::new(BTreeMap::new(), FaultPlan::none().refuse_writes_every(2));
+    assert_eq!(store.try_insert(1, 10), Ok(None));
+    assert_eq!(store.try_insert(2, 20), Err(StoreError::CapacityExceeded));
+    assert_eq!(store.try_remove(&1), Ok(Some(10)));
+    assert_eq!(store.try_remove(&1), Err(StoreError::CapacityExceeded));
+    assert!(store.inner().is_empty());
+    assert_eq!(store.log().len(), 2);
+
+    // Every 2nd write refused: two attempts are always enough. A retry
+    // shifts the next key's first attempt onto a refused write, so every
+    // key after the first needs one
+    for key in 0..50 {
+        let attempts: u32 = insert_with_retry(&mut store, key, key, 2).unwrap();
+        assert!(attempts <= 2);
+    }
+    assert_eq!(store.inner().len(), 50);
+    assert_eq!(store.stats().refused, 2 + 49);
+}
+
+#[test]
+fn timed_out_writes_are_applied_and_reads_are_dropped() {
+    let delay: Duration = Duration::from_millis(20);
+    let plan: FaultPlan = FaultPlan::none()
+        .slow_ops_every(2, delay)
+        .deadline(Duration::from_millis(10));
+    let mut store: FaultyStore<u64, u64, BTreeMap<u64, u64>> =
+        FaultyStore::new(BTreeMap::new(), plan);
+
+    assert_eq!(store.try_insert(1, 10), Ok(None));
+    assert_eq!(store.try_insert(2, 20), Err(StoreError::TimedOut));
+    assert_eq!(store.inner().get(&2), Some(&20));
+    assert_eq!(store.try_get(&2), Ok(Some(&20)));
+    assert_eq!(store.try_get(&2), Err(StoreError::TimedOut));
+    assert_eq!(store.try_remove(&1), Ok(Some(10)));
+    assert_eq!(store.try_remove(&2), Err(StoreError::TimedOut));
+    assert!(store.inner().is_empty());
+
+    assert_eq!(store.stats().slowed, 3);
+    assert_eq!(store.stats().timed_out, 3);
+    assert_eq!(store.elapsed(), OP_COST * 6 + delay * 3);
+
+    // A generous deadline turns the same delays into slow successes
+    let plan: FaultPlan = FaultPlan::none()
+        .slow_ops_every(2, delay)
+        .deadline(Duration::from_millis(30));
+    let mut store: FaultyStore<u64, u64, BTreeMap<u64, u64>> =
+        FaultyStore::new(BTreeMap::new(), plan);
+    for key in 0..10 {
+        assert_eq!(store.try_insert(key, key), Ok(None));
+    }
+    assert_eq!(store.stats().slowed, 5);
+    assert_eq!(store.stats().timed_out, 0);
+}
+
+#[test]
+fn recovery_replays_exactly_the_prefix_before_the_first_bad_record() {
+    for every in [1, 5, 17, 100] {
+        let mut store: FaultyStore<u64, u64, BTreeMap<u64, u64>> =
+            FaultyStore::new(BTreeMap::new(), FaultPlan::none().corrupt_log_every(every));
+        let mut rng: Lcg = Lcg::new(every);
+        for step in 0..60 {
+            let key: u64 = rng.below(20);
+            if rng.below(4) == 0 {
+                store.try_remove(&key).unwrap();
+            } else {
+                store.try_insert(key, step).unwrap();
+            }
+        }
+
+        let first_bad: Option<usize> = (every <= 60).then_some(every as usize - 1);
+        let recovery: Recovery<BTreeMap<u64, u64>> = recover(store.log(), BTreeMap::new());
+        assert_eq!(recovery.corrupt_at, first_bad);
+        let intact: usize = first_bad.unwrap_or(60);
+        assert_eq!(recovery.applied, intact);
+        assert_eq!(store.stats().corrupted, 60 / every);
+
+        // Replaying the intact prefix by hand gives the same map
+        let mut expected: BTreeMap<u64, u64> = BTreeMap::new();
+        for record in &store.log()[..intact] {
+            match record.value {
+                Some(value) => expected.insert(record.key, value),
+                None => expected.remove(&record.key),
+            };
+        }
+        assert_eq!(recovery.store, expected);
+        assert_eq!(first_bad.is_none(), &recovery.store == store.inner());
+    }
+}
@@ -0,0 +1,193 @@
+//! The toy compiler front-end over sample programs: interning, scope
+//! shadowing, hoisting, and the undefined, duplicate and syntax
+//! diagnostics the resolver reports.
+
+use hashing_demo::frontend_examples::{
+    Analysis, BROKEN_PROGRAM, Binding, Diagnostic, Interner, SAMPLE_PROGRAM, Symbol, SymbolKind,
+    TokenKind, check, lex,
+};
+use hashing_demo::scoped_map::{ScopeStack, ScopedMap};
+
+fn rendered(source: &str) -> Vec<String> {
+    let mut interner: Interner = Interner::new();
+    let analysis: Analysis = check(source, &mut interner);
+    analysis
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.render(&interner))
+        .collect()
+}
+
+/// Declaring line and scope depth of each use of `name`, in source order.
+fn targets(source: &str, name: &str) -> Vec<Option<(u32, usize)>> {
+    let mut interner: Interner = Interner::new();
+    let analysis: Analysis = check(source, &mut interner);
+    let symbol: Symbol = interner.get(name).expect("name appears in the source");
+    analysis
+        .references
+        .iter()
+        .filter(|reference| reference.name == symbol)
+        .map(|reference| {
+            reference
+                .target
+                .map(|(depth, binding)| (binding.line, depth))
+        })
+        .collect()
+}
+
+#[test]
+fn interning_deduplicates_and_round_trips() {
+    let mut interner: Interner = Interner::new();
+    assert_eq!(interner.len(), Symbol::KEYWORDS.len());
+    assert_eq!(interner.intern("let"), Symbol::LET);
+
+    let names: Vec<String> = (0..1_000).map(|i| format!("name_{}", i % 250)).collect();
+    let symbols: Vec<Symbol> = names.iter().map(|name| interner.intern(name)).collect();
+
+    assert_eq!(interner.len(), Symbol::KEYWORDS.len() + 250);
+    for (name, &symbol) in names.iter().zip(&symbols) {
+        assert_eq!(interner.resolve(symbol), name);
+        assert_eq!(interner.get(name), Some(symbol));
+        assert!(!symbol.is_keyword());
+    }
+    assert_eq!(symbols[0], symbols[250]);
+    assert_eq!(interner.get("name_250"), None);
+}
+
+#[test]
+fn lexer_interns_identifiers_and_tracks_lines() {
+    let mut interner: Interner = Interner::new();
+    let (tokens, diagnostics) = lex("let a = 1;\n// note\nreturn a;", &mut interner);
+    let a: Symbol = interner.get("a").unwrap();
+
+    assert!(diagnostics.is_empty());
+    let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            TokenKind::Ident(Symbol::LET),
+            TokenKind::Ident(a),
+            TokenKind::Punct('='),
+            TokenKind::Number(1),
+            TokenKind::Punct(';'),
+            TokenKind::Ident(Symbol::RETURN),
+            TokenKind::Ident(a),
+            TokenKind::Punct(';'),
+        ]
+    );
+    assert_eq!(tokens[5].line, 3);
+}
+
+#[test]
+fn scopes_shadow_and_restore() {
+    let mut interner: Interner = Interner::new();
+    let x: Symbol = interner.intern("x");
+    let outer: Binding = Binding {
+        kind: SymbolKind::Variable,
+        line: 1,
+    };
+    let inner: Binding = Binding {
+        kind: SymbolKind::Variable,
+        line: 2,
+    };
+
+    let mut scopes: ScopedMap<Symbol, Binding> = ScopedMap::new();
+    assert_eq!(scopes.insert(x, outer), None);
+    assert_eq!(scopes.get_in_current_scope(&x), Some(&outer));
+    scopes.push_scope();
+    assert_eq!(
+        scopes.get_in_current_scope(&x),
+        None,
+        "outer x is shadowable"
+    );
+    assert_eq!(scopes.insert(x, inner), None);
+    assert_eq!(scopes.lookup(&x), Some((1, &inner)));
+    scopes.pop_scope();
+    assert_eq!(scopes.lookup(&x), Some((0, &outer)));
+    assert_eq!(scopes.depth(), 0);
+}
+
+#[test]
+fn sample_program_resolves_cleanly() {
+    let mut interner: Interner = Interner::new();
+    let analysis: Analysis = check(SAMPLE_PROGRAM, &mut interner);
+
+    assert!(analysis.is_ok(), "{:?}", analysis.diagnostics);
+    assert!(
+        analysis
+            .references
+            .iter()
+            .all(|reference| reference.target.is_some())
+    );
+    // Outer x on line 3, the inner x it initializes on line 5, the outer again after the block
+    assert_eq!(
+        targets(SAMPLE_PROGRAM, "x"),
+        [Some((3, 2)), Some((5, 3)), Some((3, 2))]
+    );
+    // `add` is called on line 2 but defined on line 10
+    assert_eq!(targets(SAMPLE_PROGRAM, "add"), [Some((10, 0))]);
+}
+
+#[test]
+fn broken_program_reports_every_error_in_line_order() {
+    assert_eq!(
+        rendered(BROKEN_PROGRAM),
+        [
+            "line 3: `count` is already declared in this scope (line 2)",
+            "line 7: cannot find `scratch` in this scope",
+            "line 7: cannot find `missing` in this scope",
+            "line 9: `n` is already declared in this scope (line 9)",
+            "line 12: `main` is already declared in this scope (line 1)",
+        ]
+    );
+}
+
+#[test]
+fn a_let_is_not_visible_in_its_own_initializer() {
+    let source: &str = "fn f(x) {\n    let x = x + 1;\n    let y = y;\n    return x;\n}";
+    assert_eq!(targets(source, "x"), [Some((1, 1)), Some((2, 2))]);
+    assert_eq!(rendered(source), ["line 3: cannot find `y` in this scope"]);
+}
+
+#[test]
+fn names_in_one_function_are_invisible_in_another() {
+    let source: &str =
+        "fn f(a) {\n    let b = a;\n    return b;\n}\nfn g() {\n    return a + b;\n}";
+    assert_eq!(
+        rendered(source),
+        [
+            "line 6: cannot find `a` in this scope",
+            "line 6: cannot find `b` in this scope",
+        ]
+    );
+}
+
+#[test]
+fn syntax_errors_stop_resolution() {
+    assert_eq!(
+        rendered("fn main() {\n    return missing\n}"),
+        [
+            "line 2: cannot find `missing` in this scope",
+            "line 3: syntax error: expected `;`",
+        ]
+    );
+    assert_eq!(
+        rendered("fn main() { let fn = 1; }"),
+        ["line 1: syntax error: expected a name, found keyword `fn`"]
+    );
+    assert_eq!(
+        rendered("fn main() {"),
+        ["line 1: syntax error: expected `}` at end of input"]
+    );
+    assert_eq!(
+        rendered("fn main() { return 1 # 2; }"),
+        ["line 1: syntax error: unexpected character '#'"]
+    );
+
+    let mut interner: Interner = Interner::new();
+    let analysis: Analysis = check("let x = 1;", &mut interner);
+    assert!(matches!(
+        analysis.diagnostics[..],
+        [Diagnostic::Syntax { line: 1, .. }]
+    ));
+}
@@ -0,0 +1,101 @@
+//! Hash-consed expressions against plainly built trees: the same structure
+//! and values, pointer equality exactly when the trees are equal, printing
+//! that parses back, and - with the tracking allocator installed for this
+//! test binary - the allocations the pool saves.
+//!
+//! The allocation counters are process-wide, so everything runs in a single
+//! test: another test allocating on a parallel thread would show up in the
+//! count.
+
+use demo_framework::alloc::{self, AllocCounts, TrackingAllocator};
+use hashing_demo::hash_consing::{
+    Expr, ExprPool, ParseError, TreeBuilder, distinct_nodes, fib_expr, parse, sample_formulas,
+    tree_size,
+};
+use std::rc::Rc;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+fn vars(name: char) -> i64 {
+    name as i64 - 'a' as i64 + 1
+}
+
+#[test]
+fn pooled_trees_match_plain_ones_and_allocate_once_per_distinct_node() {
+    // Parsing: precedence, subtraction as + -, errors with offsets
+    let mut pool: ExprPool = ExprPool::new();
+    let expr: Rc<Expr> = parse(&mut pool, "2 * (a + b) - c * 3").unwrap();
+    assert_eq!(expr.eval(&vars), 2 * (1 + 2) - 3 * 3);
+    assert_eq!(
+        parse(&mut pool, "a + * b"),
+        Err(ParseError {
+            offset: 4,
+            message: "expected a number, variable or `(`"
+        })
+    );
+    assert_eq!(parse(&mut pool, "(a + b").unwrap_err().offset, 6);
+    assert_eq!(parse(&mut pool, "a b").unwrap_err().offset, 2);
+
+    // Same structure as a plain tree; pointer equality iff structural
+    let formulas: Vec<String> = sample_formulas(300);
+    let mut plain: TreeBuilder = TreeBuilder::default();
+    let mut roots: Vec<Rc<Expr>> = Vec::new();
+    for formula in &formulas {
+        let tree: Rc<Expr> = parse(&mut plain, formula).unwrap();
+        let consed: Rc<Expr> = parse(&mut pool, formula).unwrap();
+        assert_eq!(tree, consed, "{formula}");
+        assert_eq!(tree.eval(&vars), consed.eval(&vars));
+        assert_eq!(tree_size(&tree), tree_size(&consed));
+
+        // Printing parses back to the very same pooled node
+        let printed: String = consed.to_string();
+        assert!(
+            Rc::ptr_eq(&parse(&mut pool, &printed).unwrap(), &consed),
+            "{printed}"
+        );
+        roots.push(consed);
+    }
+    for (a, formula_a) in roots.iter().zip(&formulas).step_by(7) {
+        for (b, formula_b) in roots.iter().zip(&formulas) {
+            assert_eq!(Rc::ptr_eq(a, b), a == b, "{formula_a} vs {formula_b}");
+        }
+    }
+
+    // fib_expr: exponential as a tree, n + 1 nodes hash-consed
+    let n: u32 = 18;
+    let before: AllocCounts = alloc::counts().expect("the tracking allocator is installed");
+    let tree: Rc<Expr> = fib_expr(&mut TreeBuilder::default(), n);
+    let tree_allocs: AllocCounts = alloc::counts().unwrap().since(before);
+
+    let mut fib_pool: ExprPool = ExprPool::new();
+    let before: AllocCounts = alloc::counts().unwrap();
+    let consed: Rc<Expr> = fib_expr(&mut fib_pool, n);
+    let pool_allocs: AllocCounts = alloc::counts().unwrap().since(before);
+
+    let size: u64 = tree_size(&tree);
+    assert_eq!(size, 8361);
+    assert_eq!(
+        tree_allocs.allocations, size,
+        "one allocation per tree node"
+    );
+    assert_eq!(distinct_nodes(&tree) as u64, size);
+    assert_eq!(fib_pool.len(), n as usize + 1);
+    assert_eq!(distinct_nodes(&consed), n as usize + 1);
+    assert!(
+        pool_allocs.allocations < 30,
+        "n + 1 nodes plus a few table resizes: {pool_allocs:?}"
+    );
+    assert!(
+        pool_allocs.bytes * 50 < tree_allocs.bytes,
+        "{pool_allocs:?} vs {tree_allocs:?}"
+    );
+    assert_eq!(tree, consed);
+    assert!(Rc::ptr_eq(&consed, &fib_expr(&mut fib_pool, n)));
+
+    // A rebuild through a warm pool allocates nothing at all
+    let before: AllocCounts = alloc::counts().unwrap();
+    let again: Rc<Expr> = fib_expr(&mut fib_pool, n);
+    assert_eq!(alloc::counts().unwrap().since(before).allocations, 0);
+    assert!(Rc::ptr_eq(&again, &consed));
+}
@@ -0,0 +1,76 @@
+//! The four lookup-or-insert patterns from `hashbrown_examples`: they agree
+//! on every count, and the tracking allocator (installed for this test
+//! binary) confirms which of them allocate a key on a hit.
+//!
+//! The allocation counters are process-wide, so everything runs in a single
+//! test: another test allocating on a parallel thread would show up in the
+//! count.
+
+use demo_framework::alloc::{self, AllocCounts, TrackingAllocator};
+use hashing_demo::hashbrown_examples::{
+    CountingState, count_entry_owned, count_entry_ref, count_get_then_insert, count_raw_entry,
+    session_requests,
+};
+use std::collections::HashMap;
+
+#[global_allocator]
+static ALLOCATOR: TrackingAllocator = TrackingAllocator;
+
+#[test]
+fn patterns_agree_and_only_the_owned_entry_allocates_on_hits() {
+    let requests: Vec<String> = session_requests(5_000, 300);
+    let state: CountingState = CountingState::with_seed(1);
+    let mut get_then_insert: HashMap<String, u64, CountingState> =
+        HashMap::with_hasher(state.clone());
+    let mut owned: HashMap<String, u64, CountingState> = HashMap::with_hasher(state.clone());
+    let mut by_ref: hashbrown::HashMap<String, u64, CountingState> =
+        hashbrown::HashMap::with_hasher(state.clone());
+    let mut raw: hashbrown::HashMap<String, u64, CountingState> =
+        hashbrown::HashMap::with_hasher(state.clone());
+
+    for key in &requests {
+        let expected: u64 = count_get_then_insert(&mut get_then_insert, key);
+        assert_eq!(count_entry_owned(&mut owned, key), expected, "{key}");
+        assert_eq!(count_entry_ref(&mut by_ref, key), expected, "{key}");
+        assert_eq!(count_raw_entry(&mut raw, key), expected, "{key}");
+    }
+    assert_eq!(get_then_insert.len(), 300);
+    for (key, count) in &get_then_insert {
+        assert_eq!(
+            *count,
+            5_000 / 300 + u64::from(key.as_str() < "session:00200")
+        );
+        assert_eq!(owned[key], *count);
+        assert_eq!(by_ref[key.as_str()], *count);
+        assert_eq!(raw[key.as_str()], *count);
+    }
+
+    // Every key is present now, so each request below is a hit: one hash
+    // each, and only entry(key.to_owned()) allocates
+    let hits: &[String] = &requests[..1_000];
+    let mut allocations: Vec<u64> = Vec::new();
+    for pattern in 0..4 {
+        state.reset();
+        let before: AllocCounts = alloc::counts().expect("the tracking allocator is installed");
+        for key in hits {
+            match pattern {
+                0 => count_get_then_insert(&mut get_then_insert, key),
+                1 => count_entry_owned(&mut owned, key),
+                2 => count_entry_ref(&mut by_ref, key),
+                _ => count_raw_entry(&mut raw, key),
+            };
+        }
+        allocations.push(alloc::counts().unwrap().since(before).allocations);
+        assert_eq!(state.hashes(), hits.len() as u64, "pattern {pattern}");
+    }
+    assert_eq!(allocations, [0, hits.len() as u64, 0, 0]);
+
+    // A miss: get_mut then insert hashes twice, the entry APIs once
+    state.reset();
+    count_get_then_insert(&mut get_then_insert, "new session");
+    assert_eq!(state.hashes(), 2);
+    state.reset();
+    count_entry_ref(&mut by_ref, "new session");
+    count_raw_entry(&mut raw, "new session");
+    assert_eq!(state.hashes(), 2);
+}
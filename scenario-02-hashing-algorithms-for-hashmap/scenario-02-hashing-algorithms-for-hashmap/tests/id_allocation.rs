@@ -0,0 +1,116 @@
+//! Behaviour of the ID allocators and the distribution audit that the demo
+//! output summarizes: reuse order, stale-ID detection, and the audit's
+//! verdict on dense, clustered and generational keys.
+
+use hashing_demo::id_allocation_examples::{
+    EntityId, IdAllocator, IdScheme, audit_distribution, simulate_churn,
+};
+use nohash_hasher::BuildNoHashHasher;
+use rustc_hash::FxBuildHasher;
+use std::collections::HashSet;
+
+#[test]
+fn incrementing_never_reuses_an_index() {
+    let mut allocator: IdAllocator = IdAllocator::new(IdScheme::Incrementing);
+    let first: EntityId = allocator.allocate();
+    assert!(allocator.free(first));
+    let second: EntityId = allocator.allocate();
+
+    assert_ne!(first.index, second.index);
+    assert!(!allocator.is_alive(first));
+    assert_eq!(allocator.high_water(), 2);
+}
+
+#[test]
+fn free_list_reuses_the_most_recently_freed_index() {
+    let mut allocator: IdAllocator = IdAllocator::new(IdScheme::FreeList);
+    let ids: Vec<EntityId> = (0..4).map(|_| allocator.allocate()).collect();
+    allocator.free(ids[1]);
+    allocator.free(ids[3]);
+
+    assert_eq!(allocator.allocate().index, 3);
+    assert_eq!(allocator.allocate().index, 1);
+    assert_eq!(allocator.high_water(), 4);
+}
+
+#[test]
+fn free_list_cannot_detect_a_stale_id() {
+    let mut allocator: IdAllocator = IdAllocator::new(IdScheme::FreeList);
+    let old: EntityId = allocator.allocate();
+    allocator.free(old);
+    let new: EntityId = allocator.allocate();
+
+    assert_eq!(old, new);
+    assert!(allocator.is_alive(old));
+}
+
+#[test]
+fn generational_ids_detect_stale_handles() {
+    let mut allocator: IdAllocator = IdAllocator::new(IdScheme::Generational);
+    let old: EntityId = allocator.allocate();
+    allocator.free(old);
+    let new: EntityId = allocator.allocate();
+
+    assert_eq!(new.index, old.index);
+    assert_eq!(new.generation, old.generation + 1);
+    assert!(!allocator.is_alive(old));
+    assert!(allocator.is_alive(new));
+    assert!(!allocator.free(old), "freeing a stale ID must be a no-op");
+    assert_eq!(allocator.live(), 1);
+}
+
+#[test]
+fn churn_keeps_reused_indices_below_the_peak() {
+    for scheme in [IdScheme::FreeList, IdScheme::Generational] {
+        let mut allocator: IdAllocator = IdAllocator::new(scheme);
+        let live: Vec<EntityId> = simulate_churn(&mut allocator, 500, 20, 100);
+
+        assert_eq!(live.len(), 500);
+        assert_eq!(allocator.high_water(), 500);
+        let distinct: HashSet<u32> = live.iter().map(|id| id.index).collect();
+        assert_eq!(distinct.len(), 500);
+    }
+
+    let mut allocator: IdAllocator = IdAllocator::new(IdScheme::Incrementing);
+    simulate_churn(&mut allocator, 500, 20, 100);
+    assert_eq!(allocator.high_water(), 500 + 20 * 100);
+}
+
+#[test]
+fn audit_finds_no_collisions_for_dense_keys() {
+    let keys: Vec<u64> = (0..1_000).collect();
+    let audit = audit_distribution(&keys, &BuildNoHashHasher::<u64>::default());
+
+    assert_eq!(audit.buckets, 2_048);
+    assert_eq!(audit.home_collisions, 0);
+    // Small integers all share the top 7 bits
+    assert_eq!(audit.distinct_tags, 1);
+}
+
+#[test]
+fn audit_flags_power_of_two_strides() {
+    let keys: Vec<u64> = (0..1_000).map(|i| i * 64).collect();
+    let nohash = audit_distribution(&keys, &BuildNoHashHasher::<u64>::default());
+    let fx = audit_distribution(&keys, &FxBuildHasher);
+
+    assert!(nohash.home_collisions as f64 > 2.0 * nohash.expected_random_collisions());
+    assert!(nohash.max_group_load > 16);
+    assert!(fx.home_collisions < nohash.home_collisions);
+}
+
+#[test]
+fn generation_in_the_high_bits_keeps_nohash_safe() {
+    let mut allocator: IdAllocator = IdAllocator::new(IdScheme::Generational);
+    let live: Vec<EntityId> = simulate_churn(&mut allocator, 1_000, 50, 300);
+    let nohash: BuildNoHashHasher<u64> = BuildNoHashHasher::default();
+
+    let packed: Vec<u64> = live.iter().map(|id| id.to_bits()).collect();
+    let swapped: Vec<u64> = live
+        .iter()
+        .map(|id| ((id.index as u64) << 32) | id.generation as u64)
+        .collect();
+
+    assert_eq!(audit_distribution(&packed, &nohash).home_collisions, 0);
+    let swapped_audit = audit_distribution(&swapped, &nohash);
+    assert!(swapped_audit.home_collisions as f64 > swapped_audit.expected_random_collisions());
+}
@@ -0,0 +1,86 @@
+//! Every `KvStore` backend against a `BTreeMap` model: the same random
+//! operations through `AnyStore` for each `Backend`, and the generic
+//! string-keyed path for the backends that take `String` keys.
+
+use hashing_demo::bucket_visual::ChainedTable;
+use hashing_demo::kv_store_examples::{
+    AnyStore, Backend, KvStore, SortedVecMap, build_index, churn,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap};
+
+#[test]
+fn every_backend_matches_the_model() {
+    for backend in Backend::ALL {
+        let mut rng: StdRng = StdRng::seed_from_u64(11);
+        let mut store: AnyStore<u64> = AnyStore::new(backend);
+        let mut model: BTreeMap<u64, u64> = BTreeMap::new();
+        assert_eq!(store.backend(), backend);
+        assert!(store.is_empty());
+
+        for step in 0..5_000u64 {
+            let key: u64 = rng.random_range(0..300);
+            match rng.random_range(0..5) {
+                0 | 1 => assert_eq!(
+                    store.insert(key, step),
+                    model.insert(key, step),
+                    "{} insert at step {step}",
+                    backend.name()
+                ),
+                2 => {
+                    store.upsert(key, 100, |value| *value += 1);
+                    *model.entry(key).or_insert(100) += 1;
+                }
+                3 => assert_eq!(store.get(&key), model.get(&key)),
+                _ => assert_eq!(store.remove(&key), model.remove(&key)),
+            }
+            assert_eq!(store.len(), model.len(), "{} len", backend.name());
+        }
+        for key in 0..300 {
+            assert_eq!(store.get(&key), model.get(&key), "{}", backend.name());
+        }
+    }
+}
+
+#[test]
+fn churn_checksum_is_the_same_on_every_backend() {
+    let mut model: BTreeMap<u64, u64> = BTreeMap::new();
+    let expected: u64 = churn(&mut model, 20_000, 500);
+    for backend in Backend::ALL {
+        let mut store: AnyStore<u64> = AnyStore::new(backend);
+        assert_eq!(
+            churn(&mut store, 20_000, 500),
+            expected,
+            "{}",
+            backend.name()
+        );
+    }
+}
+
+#[test]
+fn string_keyed_backends_build_the_same_index() {
+    let text: &str = "one two three two three three Four four FOUR";
+    let mut model: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    build_index(&mut model, text);
+    assert_eq!(model["three"], vec![2, 4, 5]);
+    assert_eq!(model["four"], vec![6, 7, 8]);
+
+    let mut hash: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut sorted: SortedVecMap<String, Vec<usize>> = SortedVecMap::new();
+    let mut chained: ChainedTable<String, Vec<usize>, RandomState> =
+        ChainedTable::with_buckets(1, RandomState::new());
+    build_index(&mut hash, text);
+    build_index(&mut sorted, text);
+    build_index(&mut chained, text);
+
+    let expected: Vec<(String, Vec<usize>)> = model.into_iter().collect();
+    assert_eq!(sorted.entries(), expected.as_slice());
+    for (word, positions) in &expected {
+        assert_eq!(hash.get(word), Some(positions));
+        assert_eq!(chained.get(word), Some(positions));
+    }
+    assert_eq!(hash.len(), expected.len());
+    assert_eq!(chained.len(), expected.len());
+}
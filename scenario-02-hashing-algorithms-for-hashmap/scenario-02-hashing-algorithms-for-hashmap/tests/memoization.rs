@@ -0,0 +1,111 @@
+//! Memoized and tabulated solvers agree: edit distance on known pairs and
+//! random ones under every hasher, coin change including impossible
+//! amounts, and the backup knapsack against brute force.
+
+use hashing_demo::memoization_examples::{
+    BackupFile, Memo, edit_distance_memo, edit_distance_table, min_coins_memo, min_coins_table,
+    mutate, plan_backup, random_sequence, sample_backup,
+};
+use rustc_hash::FxBuildHasher;
+use std::collections::hash_map::RandomState;
+
+#[test]
+fn edit_distance_matches_known_values() {
+    let pairs: [(&str, &str, usize); 6] = [
+        ("kitten", "sitting", 3),
+        ("flaw", "lawn", 2),
+        ("", "abc", 3),
+        ("abc", "", 3),
+        ("same", "same", 0),
+        ("intention", "execution", 5),
+    ];
+    for (a, b, expected) in pairs {
+        let mut memo: Memo<(usize, usize), usize> = Memo::new();
+        assert_eq!(edit_distance_table(a.as_bytes(), b.as_bytes()), expected);
+        assert_eq!(
+            edit_distance_memo(a.as_bytes(), b.as_bytes(), &mut memo),
+            expected,
+            "{a} -> {b}"
+        );
+    }
+}
+
+#[test]
+fn edit_distance_agrees_under_every_hasher() {
+    for seed in 0..10 {
+        let a: Vec<u8> = random_sequence(40 + seed as usize * 7, seed);
+        let b: Vec<u8> = mutate(&a, seed as usize * 3, seed + 50);
+        let expected: usize = edit_distance_table(&a, &b);
+        assert!(expected <= seed as usize * 3, "each edit costs at most 1");
+
+        let mut fx: Memo<(usize, usize), usize, FxBuildHasher> = Memo::new();
+        let mut sip: Memo<(usize, usize), usize, RandomState> = Memo::new();
+        let mut fold: Memo<(usize, usize), usize, foldhash::fast::RandomState> = Memo::new();
+        assert_eq!(edit_distance_memo(&a, &b, &mut fx), expected);
+        assert_eq!(edit_distance_memo(&a, &b, &mut sip), expected);
+        assert_eq!(edit_distance_memo(&a, &b, &mut fold), expected);
+        assert_eq!(fx.len(), sip.len(), "the hasher doesn't change the states");
+        assert!(fx.len() <= (a.len() + 1) * (b.len() + 1));
+    }
+}
+
+#[test]
+fn coin_change_agrees_and_visits_only_reachable_totals() {
+    let cases: [(&[u64], u64, Option<u32>); 5] = [
+        (&[1, 5, 10, 25], 63, Some(6)),
+        (&[1, 3, 4], 6, Some(2)),
+        (&[5, 10], 3, None),
+        (&[7], 0, Some(0)),
+        (&[400, 1_100, 2_900], 1_000_050, None),
+    ];
+    for (coins, amount, expected) in cases {
+        let mut memo: Memo<u64, Option<u32>> = Memo::new();
+        assert_eq!(
+            min_coins_table(coins, amount),
+            expected,
+            "{coins:?} {amount}"
+        );
+        assert_eq!(min_coins_memo(coins, amount, &mut memo), expected);
+    }
+
+    let mut memo: Memo<u64, Option<u32>> = Memo::new();
+    min_coins_memo(&[400, 1_100, 2_900], 1_000_000, &mut memo);
+    assert!(
+        memo.len() <= 10_000,
+        "only multiples of 100 are reachable: {}",
+        memo.len()
+    );
+}
+
+/// Tries every subset of `files`.
+fn brute_force(files: &[BackupFile], capacity: u64) -> u32 {
+    (0u32..1 << files.len())
+        .filter_map(|mask| {
+            let picked = files
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0);
+            let bytes: u64 = picked.clone().map(|(_, file)| file.bytes).sum();
+            (bytes <= capacity).then(|| picked.map(|(_, file)| file.priority).sum())
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+#[test]
+fn backup_plan_is_optimal_and_fits() {
+    for seed in 0..6 {
+        let files: Vec<BackupFile> = sample_backup(12, seed);
+        for capacity in [0, 500_000_000, 2_000_000_000, 20_000_000_000] {
+            let mut memo: Memo<(usize, u64), u32> = Memo::new();
+            let (priority, chosen) = plan_backup(&files, capacity, &mut memo);
+            assert_eq!(priority, brute_force(&files, capacity), "seed {seed}");
+
+            let bytes: u64 = chosen.iter().map(|&i| files[i].bytes).sum();
+            let picked: u32 = chosen.iter().map(|&i| files[i].priority).sum();
+            assert!(bytes <= capacity);
+            assert_eq!(picked, priority, "the choice achieves the total");
+            assert!(chosen.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Performance smoke tests: the README's headline hasher comparisons, timed
+//! with `micro_bench` and asserted with margins far below the Criterion
+//! numbers it quotes.
+//!
+//! They run in the unoptimized test build, where every hasher is slower
+//! and the gaps shrink, so each margin only says "still clearly ahead".
+//! A failure means a claim in the prose no longer holds, not that a number
+//! drifted.
+
+use demo_framework::micro_bench::{assert_faster, fastest};
+use nohash_hasher::BuildNoHashHasher;
+use rustc_hash::FxBuildHasher;
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+const ROUNDS: u32 = 5;
+
+/// Hashes `0..count` one `u64` at a time and folds the results together.
+fn hash_integers(build: &impl BuildHasher, count: u64) -> u64 {
+    (0..count).fold(0, |acc, i| acc ^ build.hash_one(i))
+}
+
+/// Builds a map of `0..count` and times looking every key up.
+fn time_lookups<S: BuildHasher + Default>(count: u64) -> Duration {
+    let mut map: HashMap<u64, u64, S> =
+        HashMap::with_capacity_and_hasher(count as usize, S::default());
+    for i in 0..count {
+        map.insert(i, i);
+    }
+    fastest(ROUNDS, || {
+        (0..count).filter(|i| map.contains_key(i)).count()
+    })
+}
+
+#[test]
+fn nohash_integer_hashing_beats_siphash() {
+    let sip: RandomState = RandomState::new();
+    let nohash: BuildNoHashHasher<u64> = BuildNoHashHasher::default();
+    let sip_time: Duration = fastest(ROUNDS, || hash_integers(&sip, 100_000));
+    let nohash_time: Duration = fastest(ROUNDS, || hash_integers(&nohash, 100_000));
+    // README: 26.7x in the raw integer hashing benchmark
+    assert_faster("NoHash vs SipHash, u64 hashing", nohash_time, sip_time, 2.0);
+}
+
+#[test]
+fn fxhash_integer_hashing_beats_siphash() {
+    let sip: RandomState = RandomState::new();
+    let sip_time: Duration = fastest(ROUNDS, || hash_integers(&sip, 100_000));
+    let fx_time: Duration = fastest(ROUNDS, || hash_integers(&FxBuildHasher, 100_000));
+    // README: 18.1x in the raw integer hashing benchmark
+    assert_faster("FxHash vs SipHash, u64 hashing", fx_time, sip_time, 2.0);
+}
+
+#[test]
+fn nohash_map_lookups_beat_siphash() {
+    let sip_time: Duration = time_lookups::<RandomState>(100_000);
+    let nohash_time: Duration = time_lookups::<BuildNoHashHasher<u64>>(100_000);
+    // README: 9.9x for 100,000 integer lookups; the probing is shared, so
+    // the gap in a whole lookup is smaller than in hashing alone
+    assert_faster("NoHash vs SipHash, u64 lookups", nohash_time, sip_time, 1.5);
+}
@@ -0,0 +1,111 @@
+//! Pseudonyms against published test vectors, and the brute-force attack
+//! against both kinds of pseudonym.
+//!
+//! The vectors pin the output: a pseudonym that changed between releases
+//! would split every user's history in two.
+
+use hashing_demo::pseudonymization_examples::{
+    Pseudonym, Pseudonymizer, brute_force, normalize_phone, sha256_pseudonym, short_hex,
+};
+use std::collections::HashSet;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[test]
+fn sha256_matches_the_fips_vector() {
+    assert_eq!(
+        hex(&sha256_pseudonym("abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+}
+
+#[test]
+fn hmac_matches_rfc_4231() {
+    // Test case 2
+    let pseudonymizer: Pseudonymizer = Pseudonymizer::new(b"Jefe");
+    assert_eq!(
+        hex(&pseudonymizer.pseudonym("what do ya want for nothing?")),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+}
+
+#[test]
+fn every_spelling_normalizes_to_one_number() {
+    for raw in [
+        "+1 (555) 010-4477",
+        "555-010-4477",
+        "5550104477",
+        "1.555.010.4477",
+        "+15550104477",
+    ] {
+        assert_eq!(
+            normalize_phone(raw).as_deref(),
+            Some("+15550104477"),
+            "{}",
+            raw
+        );
+    }
+    assert_eq!(
+        normalize_phone("+44 20 7946 0123").as_deref(),
+        Some("+442079460123")
+    );
+}
+
+#[test]
+fn non_numbers_do_not_normalize() {
+    for raw in [
+        "",
+        "555-0104",
+        "call me",
+        "555-010-447x",
+        "+1234567890123456",
+    ] {
+        assert_eq!(normalize_phone(raw), None, "{}", raw);
+    }
+}
+
+#[test]
+fn brute_force_recovers_unkeyed_pseudonyms() {
+    let phones: [&str; 3] = ["+15550100000", "+15550104477", "+15550109999"];
+    let targets: HashSet<Pseudonym> = phones.iter().map(|p| sha256_pseudonym(p)).collect();
+    assert_eq!(
+        brute_force("+1555010", 4, &targets, sha256_pseudonym),
+        phones
+    );
+}
+
+#[test]
+fn brute_force_needs_the_key() {
+    let pseudonymizer: Pseudonymizer = Pseudonymizer::new(b"secret key");
+    let targets: HashSet<Pseudonym> = [pseudonymizer.pseudonym("+15550104477")].into();
+
+    assert!(brute_force("+1555010", 4, &targets, sha256_pseudonym).is_empty());
+    let wrong: Pseudonymizer = Pseudonymizer::new(b"secret kez");
+    assert!(brute_force("+1555010", 4, &targets, |id| wrong.pseudonym(id)).is_empty());
+    assert_eq!(
+        brute_force("+1555010", 4, &targets, |id| pseudonymizer.pseudonym(id)),
+        ["+15550104477"]
+    );
+}
+
+#[test]
+fn keyed_pseudonyms_are_stable_per_key_and_unlinkable_across_keys() {
+    let analytics: Pseudonymizer = Pseudonymizer::new(b"analytics");
+    let replica: Pseudonymizer = Pseudonymizer::new(b"analytics");
+    let support: Pseudonymizer = Pseudonymizer::new(b"support");
+
+    for n in 0..1_000 {
+        let phone: String = format!("+1555010{:04}", n);
+        let pseudonym: Pseudonym = analytics.pseudonym(&phone);
+        assert_eq!(pseudonym, replica.pseudonym(&phone));
+        assert_ne!(pseudonym, support.pseudonym(&phone));
+        assert_ne!(pseudonym, sha256_pseudonym(&phone));
+    }
+}
+
+#[test]
+fn short_hex_shows_the_first_six_bytes() {
+    assert_eq!(short_hex(&sha256_pseudonym("abc")), "ba7816bf8f01…");
+}
@@ -0,0 +1,202 @@
+//! Statistical checks of the sampling utilities, per hasher.
+//!
+//! Hash-threshold sampling needs each hasher's high bits to be uniform, so
+//! every fixed-seed hasher is checked for its sample rate and for a
+//! chi-squared statistic below the p = 0.001 critical value, on both
+//! sequential integers and strings. All inputs are deterministic, so these
+//! tests either always pass or always fail.
+
+use hashing_demo::sampling_examples::{
+    CHI_SQUARED_64_BUCKETS, HashSampler, bottom_k_sample, chi_squared, hash_shuffle,
+    high_bit_buckets, reservoir_sample, stable_sample,
+};
+use nohash_hasher::BuildNoHashHasher;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rustc_hash::FxBuildHasher;
+use std::collections::HashSet;
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash};
+use xxhash_rust::xxh3::Xxh3Builder;
+
+const KEYS: u64 = 100_000;
+
+fn sequential_ids() -> Vec<u64> {
+    (0..KEYS).collect()
+}
+
+fn user_names() -> Vec<String> {
+    (0..KEYS).map(|n| format!("user-{}", n)).collect()
+}
+
+/// Asserts a 1% sample is within 5 standard deviations of 1,000 keys and
+/// the high bits pass the chi-squared test.
+fn assert_uniform<K: Hash, S: BuildHasher>(name: &str, keys: &[K], build_hasher: S) {
+    let counts: Vec<u64> = high_bit_buckets(keys, &build_hasher, 64);
+    let chi: f64 = chi_squared(&counts);
+    assert!(
+        chi < CHI_SQUARED_64_BUCKETS,
+        "{}: chi-squared {:.1} over the critical value",
+        name,
+        chi
+    );
+
+    let sampler: HashSampler<S> = HashSampler::new(build_hasher, 0.01);
+    let sampled: usize = keys.iter().filter(|k| sampler.includes(*k)).count();
+    assert!(
+        (842..=1158).contains(&sampled),
+        "{}: sampled {} of {}",
+        name,
+        sampled,
+        keys.len()
+    );
+}
+
+fn assert_uniform_for_all_hashers<K: Hash>(keys: &[K]) {
+    assert_uniform(
+        "SipHash-1-3",
+        keys,
+        BuildHasherDefault::<DefaultHasher>::default(),
+    );
+    assert_uniform("FxHash", keys, FxBuildHasher);
+    assert_uniform("aHash", keys, ahash::RandomState::with_seeds(1, 2, 3, 4));
+    assert_uniform("foldhash", keys, foldhash::fast::FixedState::with_seed(7));
+    assert_uniform("xxHash64", keys, twox_hash::xxhash64::State::with_seed(7));
+    assert_uniform("xxh3", keys, Xxh3Builder::new().with_seed(7));
+}
+
+#[test]
+fn every_hasher_samples_sequential_ids_uniformly() {
+    assert_uniform_for_all_hashers(&sequential_ids());
+}
+
+#[test]
+fn every_hasher_samples_strings_uniformly() {
+    assert_uniform_for_all_hashers(&user_names());
+}
+
+#[test]
+fn nohash_fails_the_uniformity_test() {
+    let ids: Vec<u64> = sequential_ids();
+    let build_hasher = BuildNoHashHasher::<u64>::default();
+    assert!(chi_squared(&high_bit_buckets(&ids, &build_hasher, 64)) > CHI_SQUARED_64_BUCKETS);
+
+    let sampler = HashSampler::new(build_hasher, 0.01);
+    assert!(ids.iter().all(|id| sampler.includes(id)));
+}
+
+#[test]
+fn lower_rates_sample_a_subset() {
+    let names: Vec<String> = user_names();
+    let small = HashSampler::new(Xxh3Builder::new().with_seed(1), 0.01);
+    let large = HashSampler::new(Xxh3Builder::new().with_seed(1), 0.2);
+
+    let large_sample: HashSet<&String> = names.iter().filter(|n| large.includes(*n)).collect();
+    assert!(
+        names
+            .iter()
+            .filter(|n| small.includes(*n))
+            .all(|n| large_sample.contains(n))
+    );
+}
+
+#[test]
+fn rate_bounds_sample_nothing_or_everything() {
+    let none = HashSampler::new(Xxh3Builder::new(), 0.0);
+    let all = HashSampler::new(Xxh3Builder::new(), 1.0);
+    assert_eq!(all.rate(), 1.0);
+
+    for name in user_names().iter().take(1_000) {
+        assert!(!none.includes(name));
+        assert!(all.includes(name));
+        assert!(stable_sample(name.as_bytes(), 3, 1.0));
+        assert!(!stable_sample(name.as_bytes(), 3, 0.0));
+    }
+}
+
+#[test]
+#[should_panic(expected = "sample rate must be in 0..=1")]
+fn rate_above_one_panics() {
+    HashSampler::new(Xxh3Builder::new(), 1.5);
+}
+
+#[test]
+fn stable_sample_rate_and_salt_independence() {
+    let names: Vec<String> = user_names();
+    let first: HashSet<&String> = names
+        .iter()
+        .filter(|n| stable_sample(n.as_bytes(), 1, 0.05))
+        .collect();
+    let second: HashSet<&String> = names
+        .iter()
+        .filter(|n| stable_sample(n.as_bytes(), 2, 0.05))
+        .collect();
+
+    // 5,000 expected, sd ~69
+    assert!((4_650..=5_350).contains(&first.len()));
+    // Independent samples overlap on ~5% of 5%: 250 expected, sd ~15.6
+    let overlap: usize = first.intersection(&second).count();
+    assert!((170..=330).contains(&overlap), "overlap {}", overlap);
+}
+
+#[test]
+fn reservoir_picks_every_item_equally_often() {
+    let mut rng: StdRng = StdRng::seed_from_u64(7);
+    let mut counts: Vec<u64> = vec![0; 100];
+    for _ in 0..20_000 {
+        let sample: Vec<usize> = reservoir_sample(0..100usize, 10, &mut rng);
+        assert_eq!(sample.len(), 10);
+        for item in sample {
+            counts[item] += 1;
+        }
+    }
+    // 99 degrees of freedom, p = 0.001
+    assert!(chi_squared(&counts) < 148.2);
+}
+
+#[test]
+fn reservoir_returns_short_streams_whole() {
+    let mut rng: StdRng = StdRng::seed_from_u64(7);
+    assert_eq!(reservoir_sample(0..3, 10, &mut rng), vec![0, 1, 2]);
+    assert!(reservoir_sample(0..100, 0, &mut rng).is_empty());
+}
+
+#[test]
+fn bottom_k_is_deterministic_and_counts_duplicates_once() {
+    let build_hasher = Xxh3Builder::new().with_seed(5);
+    let items: Vec<u32> = (0..10_000).collect();
+    let mut repeated: Vec<u32> = items.clone();
+    repeated.extend(std::iter::repeat_n(9_999, 50_000));
+    repeated.reverse();
+
+    let sample: Vec<u32> = bottom_k_sample(items.iter().copied(), 20, &build_hasher);
+    let again: Vec<u32> = bottom_k_sample(repeated.iter().copied(), 20, &build_hasher);
+    assert_eq!(sample, again);
+
+    let unique: HashSet<u32> = sample.iter().copied().collect();
+    assert_eq!(unique.len(), 20);
+
+    // Smallest hashes first, and nothing outside the sample hashes lower
+    let hashes: Vec<u64> = sample.iter().map(|i| build_hasher.hash_one(i)).collect();
+    assert!(hashes.is_sorted());
+    let largest: u64 = *hashes.last().unwrap();
+    let below: usize = items
+        .iter()
+        .filter(|i| build_hasher.hash_one(*i) <= largest)
+        .count();
+    assert_eq!(below, 20);
+}
+
+#[test]
+fn hash_shuffle_is_a_deterministic_permutation() {
+    let build_hasher = Xxh3Builder::new().with_seed(11);
+    let mut forward: Vec<u32> = (0..1_000).collect();
+    let mut backward: Vec<u32> = (0..1_000).rev().collect();
+    hash_shuffle(&mut forward, &build_hasher);
+    hash_shuffle(&mut backward, &build_hasher);
+
+    assert_eq!(forward, backward);
+    assert_ne!(forward, (0..1_000).collect::<Vec<u32>>());
+    let mut sorted: Vec<u32> = forward.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..1_000).collect::<Vec<u32>>());
+}
@@ -0,0 +1,150 @@
+//! The three scope-stack layouts against a naive model: shadowing,
+//! replacement within one scope, popping back to outer bindings, and the
+//! benchmark workload's checksum.
+
+use hashing_demo::scoped_map::{
+    PersistentScopedMap, ScopeOp, ScopeStack, ScopedMap, TaggedScopedMap, run_workload,
+    scope_workload,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// A scope stack as a list of association lists, searched linearly.
+struct Model {
+    scopes: Vec<Vec<(u32, u32)>>,
+}
+
+impl Model {
+    fn new() -> Self {
+        Model {
+            scopes: vec![Vec::new()],
+        }
+    }
+
+    fn insert(&mut self, key: u32, value: u32) -> Option<u32> {
+        let scope: &mut Vec<(u32, u32)> = self.scopes.last_mut().unwrap();
+        match scope.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => Some(std::mem::replace(v, value)),
+            None => {
+                scope.push((key, value));
+                None
+            }
+        }
+    }
+
+    fn lookup(&self, key: u32) -> Option<(usize, u32)> {
+        self.scopes
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(depth, scope)| {
+                scope
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|&(_, v)| (depth, v))
+            })
+    }
+}
+
+fn check_against_model<S: ScopeStack<u32, u32> + Default>(seed: u64) {
+    let mut rng: StdRng = StdRng::seed_from_u64(seed);
+    let mut stack: S = S::default();
+    let mut model: Model = Model::new();
+
+    for step in 0..5_000 {
+        let key: u32 = rng.random_range(0..24);
+        match rng.random_range(0..10) {
+            0 | 1 => {
+                stack.push_scope();
+                model.scopes.push(Vec::new());
+            }
+            2 if model.scopes.len() > 1 => {
+                stack.pop_scope();
+                model.scopes.pop();
+            }
+            3..=5 => {
+                let value: u32 = rng.random();
+                assert_eq!(
+                    stack.insert(key, value),
+                    model.insert(key, value),
+                    "step {step}"
+                );
+            }
+            _ => {
+                let found: Option<(usize, u32)> =
+                    stack.lookup(&key).map(|(depth, &value)| (depth, value));
+                assert_eq!(found, model.lookup(key), "step {step}");
+                let current: Option<u32> = model
+                    .scopes
+                    .last()
+                    .unwrap()
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|&(_, v)| v);
+                assert_eq!(stack.get_in_current_scope(&key).copied(), current);
+            }
+        }
+        assert_eq!(stack.depth(), model.scopes.len() - 1);
+    }
+}
+
+#[test]
+fn every_layout_matches_the_model() {
+    for seed in 0..8 {
+        check_against_model::<ScopedMap<u32, u32>>(seed);
+        check_against_model::<TaggedScopedMap<u32, u32>>(seed);
+        check_against_model::<PersistentScopedMap<u32, u32>>(seed);
+    }
+}
+
+#[test]
+fn inner_bindings_shadow_until_their_scope_closes() {
+    let mut scopes: ScopedMap<&str, i32> = ScopedMap::new();
+    scopes.insert("x", 1);
+    scopes.insert("y", 2);
+    scopes.push_scope();
+    assert_eq!(scopes.insert("x", 10), None, "shadowing is not replacing");
+    assert_eq!(scopes.insert("x", 11), Some(10));
+    assert_eq!(scopes.lookup(&"x"), Some((1, &11)));
+    assert_eq!(scopes.lookup(&"y"), Some((0, &2)));
+    assert_eq!(scopes.len(), 3);
+    assert_eq!(scopes.current_scope().count(), 1);
+
+    scopes.pop_scope();
+    assert_eq!(scopes.lookup(&"x"), Some((0, &1)));
+    assert_eq!(scopes.len(), 2);
+
+    // A reused scope map starts empty
+    scopes.push_scope();
+    assert_eq!(scopes.get_in_current_scope(&"x"), None);
+    assert_eq!(scopes.current_scope().count(), 0);
+}
+
+#[test]
+#[should_panic(expected = "global scope")]
+fn popping_the_global_scope_panics() {
+    let mut scopes: PersistentScopedMap<u32, u32> = PersistentScopedMap::new();
+    scopes.push_scope();
+    scopes.pop_scope();
+    scopes.pop_scope();
+}
+
+#[test]
+fn layouts_agree_on_the_benchmark_workload() {
+    for nesting in [1, 2, 16] {
+        let ops: Vec<ScopeOp> = scope_workload(50, nesting, 7);
+        assert_eq!(
+            ops.iter().filter(|&&op| op == ScopeOp::Push).count(),
+            ops.iter().filter(|&&op| op == ScopeOp::Pop).count(),
+            "every block is closed"
+        );
+
+        let expected: u64 = run_workload(&mut ScopedMap::new(), &ops);
+        assert!(expected > 0);
+        assert_eq!(run_workload(&mut TaggedScopedMap::new(), &ops), expected);
+        assert_eq!(
+            run_workload(&mut PersistentScopedMap::new(), &ops),
+            expected
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Snapshot tests for the educational output of every example module.
+//!
+//! Each test captures a module's `run_all` into a buffer and compares it
+//! with the reviewed snapshot in `tests/snapshots/`. After an intentional
+//! change to a demo, review and accept the new output with:
+//!
+//!   cargo insta review
+//!
+//! or regenerate everything with `INSTA_UPDATE=always cargo test`.
+//!
+//! Demos that iterate hash maps sort what they print, so every module goes
+//! through plain `stabilize`, which only redacts volatile values and sorts
+//! the contents of Debug-printed maps and sets.
+
+use demo_framework::capture;
+use demo_framework::snapshot::{redact, stabilize};
+use hashing_demo::{
+    ab_testing_examples, ahash_examples, auto_hash, bucket_visual, counting_hasher,
+    deserialize_examples, fault_injection_examples, foldhash_examples, frontend_examples,
+    fxhash_examples, hash_consing, hashbrown_examples, hasher_cost_examples,
+    id_allocation_examples, kv_store_examples, memoization_examples, nohash_examples,
+    pseudonymization_examples, sampling_examples, security_examples, siphash_examples,
+    xxhash_examples,
+};
+use insta::assert_snapshot;
+
+#[test]
+fn siphash_examples() {
+    assert_snapshot!(stabilize(&capture(siphash_examples::run_all)));
+}
+
+#[test]
+fn fxhash_examples() {
+    assert_snapshot!(stabilize(&capture(fxhash_examples::run_all)));
+}
+
+#[test]
+fn frontend_examples() {
+    assert_snapshot!(stabilize(&capture(frontend_examples::run_all)));
+}
+
+#[test]
+fn hash_consing() {
+    assert_snapshot!(stabilize(&capture(hash_consing::run_all)));
+}
+
+#[test]
+fn ahash_examples() {
+    assert_snapshot!(stabilize(&capture(ahash_examples::run_all)));
+}
+
+#[test]
+fn foldhash_examples() {
+    assert_snapshot!(stabilize(&capture(foldhash_examples::run_all)));
+}
+
+#[test]
+fn xxhash_examples() {
+    assert_snapshot!(stabilize(&capture(xxhash_examples::run_all)));
+}
+
+#[test]
+fn nohash_examples() {
+    assert_snapshot!(stabilize(&capture(nohash_examples::run_all)));
+}
+
+#[test]
+fn id_allocation_examples() {
+    assert_snapshot!(stabilize(&capture(id_allocation_examples::run_all)));
+}
+
+#[test]
+fn sampling_examples() {
+    // aHash's fixed-seed output depends on the CPU features it was built for
+    let output: String = redact(
+        &capture(sampling_examples::run_all),
+        &[(
+            r"(?m)^    aHash +\d+ +[\d.]+",
+            "    aHash [platform-dependent]",
+        )],
+    );
+    assert_snapshot!(stabilize(&output));
+}
+
+#[test]
+fn ab_testing_examples() {
+    assert_snapshot!(stabilize(&capture(ab_testing_examples::run_all)));
+}
+
+#[test]
+fn pseudonymization_examples() {
+    // Guesses per second and the times extrapolated from them are measured
+    let output: String = redact(
+        &capture(pseudonymization_examples::run_all),
+        &[(r"(?m)^    ([\w-]+) +[\d.]+ M .*$", "    $1 [measured]")],
+    );
+    assert_snapshot!(stabilize(&output));
+}
+
+#[test]
+fn security_examples() {
+    assert_snapshot!(stabilize(&capture(security_examples::run_all)));
+}
+
+#[test]
+fn deserialize_examples() {
+    assert_snapshot!(stabilize(&capture(deserialize_examples::run_all)));
+}
+
+#[test]
+fn bucket_visual() {
+    assert_snapshot!(stabilize(&capture(bucket_visual::run_all)));
+}
+
+#[test]
+fn hasher_cost_examples() {
+    assert_snapshot!(stabilize(&capture(hasher_cost_examples::run_all)));
+}
+
+#[test]
+fn kv_store_examples() {
+    assert_snapshot!(stabilize(&capture(kv_store_examples::run_all)));
+}
+
+#[test]
+fn hashbrown_examples() {
+    assert_snapshot!(stabilize(&capture(hashbrown_examples::run_all)));
+}
+
+#[test]
+fn counting_hasher() {
+    assert_snapshot!(stabilize(&capture(counting_hasher::run_all)));
+}
+
+#[test]
+fn memoization_examples() {
+    assert_snapshot!(stabilize(&capture(memoization_examples::run_all)));
+}
+
+#[test]
+fn fault_injection_examples() {
+    assert_snapshot!(stabilize(&capture(fault_injection_examples::run_all)));
+}
+
+#[test]
+fn auto_hash() {
+    assert_snapshot!(stabilize(&capture(auto_hash::run_all)));
+}
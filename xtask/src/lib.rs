@@ -0,0 +1,4 @@
+//! Repository automation, run through the `cargo xtask` alias.
+
+pub mod scaffold;
+pub mod scenarios;
@@ -0,0 +1,48 @@
+//! Repository automation.
+//!
+//! Usage:
+//!   cargo xtask new-scenario <name>
+//!
+//! `new-scenario` generates `scenario-NN-<name>/` with a lib+bin demo crate
+//! that is already wired into the workspace, the shared demo registry and
+//! CLI, snapshot tests and a Criterion bench skeleton.
+
+use std::env;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: cargo xtask <command>
+
+Commands:
+  new-scenario <name>   Generate a new scenario crate (e.g. `smart-pointers`)
+  help                  Print this message";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<&str>>()
+        .as_slice()
+    {
+        ["new-scenario", name] => match xtask::scaffold::new_scenario(name) {
+            Ok(scenario) => {
+                scenario.print_next_steps();
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::FAILURE
+            }
+        },
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Scenario scaffolding.
+//!
+//! A generated scenario mirrors the layout of the existing ones:
+//!
+//! ```text
+//! scenario-NN-<name>/
+//! ├── README.md
+//! └── scenario-NN-<name>/
+//!     ├── Cargo.toml
+//!     ├── benches/<name>_benchmarks.rs
+//!     ├── src/
+//!     │   ├── lib.rs
+//!     │   ├── main.rs
+//!     │   └── <name>_examples.rs
+//!     └── tests/snapshots.rs
+//! ```
+//!
+//! The crate directory is also added to the workspace `members` list.
+
+use crate::scenarios::workspace_root;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+const CARGO_TOML: &str = include_str!("../templates/Cargo.toml.tmpl");
+const LIB_RS: &str = include_str!("../templates/lib.rs.tmpl");
+const MAIN_RS: &str = include_str!("../templates/main.rs.tmpl");
+const EXAMPLES_RS: &str = include_str!("../templates/examples.rs.tmpl");
+const BENCH_RS: &str = include_str!("../templates/bench.rs.tmpl");
+const README_MD: &str = include_str!("../templates/README.md.tmpl");
+const SNAPSHOTS_RS: &str = include_str!("../templates/snapshots.rs.tmpl");
+
+/// A freshly generated scenario.
+pub struct Scenario {
+    /// Workspace-relative path of the scenario crate.
+    pub crate_path: String,
+    /// Cargo package name, e.g. `smart_pointers_demo`.
+    pub package: String,
+}
+
+impl Scenario {
+    pub fn print_next_steps(&self) {
+        println!("Created {}", self.crate_path);
+        println!();
+        println!("Next steps:");
+        // There is no .snap file yet, so a plain `cargo test` would fail
+        println!(
+            "  INSTA_UPDATE=always cargo test -p {}   # records the first snapshot",
+            self.package
+        );
+        println!("  cargo run -p {}", self.package);
+        println!("  cargo bench -p {}", self.package);
+        println!("  Add the scenario to the \"Available Scenarios\" list in README.md");
+    }
+}
+
+/// Generates a new scenario named `name` (kebab-case) in the workspace
+/// containing the current directory.
+pub fn new_scenario(name: &str) -> Result<Scenario> {
+    validate_name(name)?;
+
+    let root: PathBuf = workspace_root()?;
+    let number: u32 = next_scenario_number(&root)?;
+    let dir_name: String = format!("scenario-{number:02}-{name}");
+    let crate_path: String = format!("{dir_name}/{dir_name}");
+    let crate_dir: PathBuf = root.join(&crate_path);
+
+    if root.join(&dir_name).exists() {
+        return Err(format!("{dir_name} already exists").into());
+    }
+
+    let snake: String = name.replace('-', "_");
+    let vars: [(&str, String); 4] = [
+        ("title", title_case(name)),
+        ("package", format!("{snake}_demo")),
+        ("module", format!("{snake}_examples")),
+        ("bench", format!("{snake}_benchmarks")),
+    ];
+
+    let files: [(PathBuf, &str); 7] = [
+        (root.join(&dir_name).join("README.md"), README_MD),
+        (crate_dir.join("Cargo.toml"), CARGO_TOML),
+        (crate_dir.join("src/lib.rs"), LIB_RS),
+        (crate_dir.join("src/main.rs"), MAIN_RS),
+        (
+            crate_dir.join(format!("src/{snake}_examples.rs")),
+            EXAMPLES_RS,
+        ),
+        (
+            crate_dir.join(format!("benches/{snake}_benchmarks.rs")),
+            BENCH_RS,
+        ),
+        (crate_dir.join("tests/snapshots.rs"), SNAPSHOTS_RS),
+    ];
+
+    for (path, template) in &files {
+        write_file(path, &render(template, &vars))?;
+    }
+
+    add_workspace_member(&root, &crate_path)?;
+
+    Ok(Scenario {
+        crate_path,
+        package: format!("{snake}_demo"),
+    })
+}
+
+/// Names become directory, package and module names, so only lowercase
+/// ASCII words separated by single dashes are accepted.
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid: bool = !name.is_empty()
+        && name.split('-').all(|word| {
+            !word.is_empty()
+                && word
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+        })
+        && name.starts_with(|c: char| c.is_ascii_lowercase());
+
+    if !valid {
+        return Err(format!(
+            "invalid scenario name `{name}` (expected kebab-case, e.g. `smart-pointers`)"
+        )
+        .into());
+    }
+    if name.starts_with("scenario-") {
+        return Err("the `scenario-NN-` prefix is added automatically".into());
+    }
+    Ok(())
+}
+
+/// Returns one more than the highest existing `scenario-NN-*` directory.
+pub fn next_scenario_number(root: &Path) -> Result<u32> {
+    let mut highest: u32 = 0;
+
+    for entry in fs::read_dir(root)? {
+        let file_name = entry?.file_name();
+        let number: Option<u32> = file_name
+            .to_str()
+            .and_then(|name| name.strip_prefix("scenario-"))
+            .and_then(|rest| rest.split('-').next())
+            .and_then(|digits| digits.parse().ok());
+
+        if let Some(number) = number {
+            highest = highest.max(number);
+        }
+    }
+    Ok(highest + 1)
+}
+
+/// Adds `crate_path` to the workspace `members` array, keeping it sorted.
+/// A path that is already a member is not added again.
+pub fn add_workspace_member(root: &Path, crate_path: &str) -> Result<()> {
+    let manifest: PathBuf = root.join("Cargo.toml");
+    let contents: String = fs::read_to_string(&manifest)?;
+
+    let list_start: usize = contents
+        .find("members = [")
+        .map(|start| start + "members = [".len())
+        .ok_or("workspace Cargo.toml has no `members = [` list")?;
+    let list_end: usize = list_start
+        + contents[list_start..]
+            .find("\n]")
+            .ok_or("unterminated workspace `members` list")?;
+
+    let mut members: Vec<String> = contents[list_start..list_end]
+        .lines()
+        .map(|line| line.trim().trim_end_matches(',').to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    let member: String = format!("\"{crate_path}\"");
+    if !members.contains(&member) {
+        members.push(member);
+    }
+    members.sort();
+
+    let mut updated: String = String::with_capacity(contents.len() + crate_path.len() + 8);
+    updated.push_str(&contents[..list_start]);
+    for member in &members {
+        updated.push_str(&format!("\n    {member},"));
+    }
+    updated.push_str(&contents[list_end..]);
+
+    fs::write(&manifest, updated)?;
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Replaces every `{{key}}` placeholder in `template`.
+pub fn render(template: &str, vars: &[(&str, String)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |text, (key, value)| {
+            text.replace(&format!("{{{{{key}}}}}"), value)
+        })
+}
+
+/// `smart-pointers` -> `Smart Pointers`
+pub fn title_case(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -0,0 +1,102 @@
+//! Finding the scenario crates in the workspace.
+//!
+//! Every `scenario-NN-<name>/` directory at the workspace root holds one
+//! demo crate, in a subdirectory with its own `Cargo.toml`. A scenario is
+//! known by its number, its directory name, its package name or the short
+//! name: the package name without `_demo`, e.g. `collections` for
+//! `collections_demo`.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// One scenario's demo crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScenarioCrate {
+    /// The `NN` of `scenario-NN-<name>`.
+    pub number: u32,
+    /// The package name without `_demo`, e.g. `hashing`.
+    pub short_name: String,
+    /// Cargo package name, e.g. `hashing_demo`.
+    pub package: String,
+    /// The top-level scenario directory, e.g. `scenario-02-hashing-algorithms-for-hashmap`.
+    pub dir: String,
+}
+
+impl ScenarioCrate {
+    /// Whether `query` names this scenario: its short name, package,
+    /// directory or number (`2` or `02`).
+    pub fn matches(&self, query: &str) -> bool {
+        query == self.short_name
+            || query == self.package
+            || query.trim_end_matches('/') == self.dir
+            || query.parse::<u32>() == Ok(self.number)
+    }
+}
+
+/// Walks up from the current directory to the `Cargo.toml` declaring `[workspace]`.
+pub fn workspace_root() -> Result<PathBuf> {
+    let cwd: PathBuf = std::env::current_dir()?;
+
+    for dir in cwd.ancestors() {
+        let manifest: PathBuf = dir.join("Cargo.toml");
+        if manifest.is_file() && fs::read_to_string(&manifest)?.contains("[workspace]") {
+            return Ok(dir.to_path_buf());
+        }
+    }
+    Err("could not find the workspace root (no Cargo.toml with [workspace])".into())
+}
+
+/// Every scenario crate under `root`, ordered by number.
+pub fn discover(root: &Path) -> Result<Vec<ScenarioCrate>> {
+    let mut scenarios: Vec<ScenarioCrate> = Vec::new();
+
+    for entry in fs::read_dir(root)? {
+        let entry = entry?;
+        let Some(dir) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let number: Option<u32> = dir
+            .strip_prefix("scenario-")
+            .and_then(|rest| rest.split('-').next())
+            .and_then(|digits| digits.parse().ok());
+        let Some(number) = number else {
+            continue;
+        };
+        if let Some(package) = find_package(&entry.path())? {
+            scenarios.push(ScenarioCrate {
+                number,
+                short_name: package.trim_end_matches("_demo").to_string(),
+                package,
+                dir,
+            });
+        }
+    }
+
+    scenarios.sort_by_key(|scenario| scenario.number);
+    Ok(scenarios)
+}
+
+/// The package name of the first crate directly inside `scenario_dir`.
+fn find_package(scenario_dir: &Path) -> Result<Option<String>> {
+    let mut crate_dirs: Vec<PathBuf> = fs::read_dir(scenario_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.join("Cargo.toml").is_file())
+        .collect();
+    crate_dirs.sort();
+
+    for crate_dir in crate_dirs {
+        let manifest: String = fs::read_to_string(crate_dir.join("Cargo.toml"))?;
+        let name: Option<String> = manifest
+            .lines()
+            .map(str::trim)
+            .find_map(|line| line.strip_prefix("name = "))
+            .map(|value| value.trim_matches('"').to_string());
+        if name.is_some() {
+            return Ok(name);
+        }
+    }
+    Ok(None)
+}
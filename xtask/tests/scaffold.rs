@@ -0,0 +1,113 @@
+//! The scaffolding helpers: which names are accepted, the next scenario
+//! number, the workspace `members` edit and template rendering, each run
+//! in a temp dir rather than this workspace.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use xtask::scaffold::{
+    add_workspace_member, next_scenario_number, render, title_case, validate_name,
+};
+
+/// An empty directory of its own for one test.
+fn temp_dir(test: &str) -> PathBuf {
+    let dir: PathBuf =
+        env::temp_dir().join(format!("xtask-scaffold-{}-{}", std::process::id(), test));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("temp dir");
+    dir
+}
+
+const MANIFEST: &str = r#"[workspace]
+resolver = "3"
+members = [
+    "demo-framework",
+    "scenario-01-collections/scenario-01-collections",
+    "xtask",
+]
+
+[workspace.dependencies]
+serde = "1"
+"#;
+
+#[test]
+fn names_must_be_lowercase_kebab_case() {
+    for name in ["smart-pointers", "iterators", "async-io-2", "b-trees"] {
+        assert!(validate_name(name).is_ok(), "{name}");
+    }
+    for name in [
+        "",
+        "Smart-Pointers",
+        "smartPointers",
+        "-smart",
+        "smart-",
+        "smart--pointers",
+        "smart_pointers",
+        "2-phase",
+        "smart pointers",
+    ] {
+        assert!(validate_name(name).is_err(), "{name:?}");
+    }
+    let prefixed: String = validate_name("scenario-03-lifetimes")
+        .unwrap_err()
+        .to_string();
+    assert!(prefixed.contains("added automatically"), "{prefixed}");
+}
+
+#[test]
+fn the_next_number_follows_the_highest_scenario() {
+    let root: PathBuf = temp_dir("numbers");
+    assert_eq!(next_scenario_number(&root).unwrap(), 1);
+
+    for dir in [
+        "scenario-01-collections",
+        "scenario-04-lifetimes",
+        "scenario-notes",
+        "playground",
+    ] {
+        fs::create_dir(root.join(dir)).expect("scenario dir");
+    }
+    fs::write(root.join("scenario-09.txt"), "").expect("stray file");
+    assert_eq!(next_scenario_number(&root).unwrap(), 5);
+    fs::remove_dir_all(&root).expect("clean up");
+}
+
+#[test]
+fn a_new_member_is_inserted_in_order_once() {
+    let root: PathBuf = temp_dir("members");
+    let manifest: PathBuf = root.join("Cargo.toml");
+    fs::write(&manifest, MANIFEST).expect("manifest");
+
+    add_workspace_member(&root, "scenario-02-hashing/scenario-02-hashing").unwrap();
+    add_workspace_member(&root, "scenario-02-hashing/scenario-02-hashing").unwrap();
+    let updated: String = fs::read_to_string(&manifest).expect("manifest");
+    assert_eq!(
+        updated,
+        MANIFEST.replace(
+            "    \"xtask\",\n",
+            "    \"scenario-02-hashing/scenario-02-hashing\",\n    \"xtask\",\n"
+        )
+    );
+
+    fs::write(&manifest, "[workspace]\n").expect("manifest");
+    assert!(add_workspace_member(&root, "scenario-02-hashing/scenario-02-hashing").is_err());
+    fs::remove_dir_all(&root).expect("clean up");
+}
+
+#[test]
+fn templates_render_every_placeholder() {
+    assert_eq!(title_case("smart-pointers"), "Smart Pointers");
+    assert_eq!(title_case("async-io-2"), "Async Io 2");
+
+    let vars: [(&str, String); 2] = [
+        ("title", title_case("smart-pointers")),
+        ("package", "smart_pointers_demo".to_string()),
+    ];
+    assert_eq!(
+        render(
+            "# {{title}} in Rust\ncargo run -p {{package}} # {{package}}\n{{other}}",
+            &vars
+        ),
+        "# Smart Pointers in Rust\ncargo run -p smart_pointers_demo # smart_pointers_demo\n{{other}}"
+    );
+}
@@ -0,0 +1,54 @@
+//! Scenario discovery against this workspace, and the names `playground`
+//! accepts for a scenario.
+
+use std::path::PathBuf;
+use xtask::scenarios::{self, ScenarioCrate};
+
+fn workspace() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask sits in the workspace root")
+        .to_path_buf()
+}
+
+#[test]
+fn finds_every_scenario_in_order() {
+    let found: Vec<ScenarioCrate> = scenarios::discover(&workspace()).unwrap();
+    let names: Vec<(u32, &str, &str)> = found
+        .iter()
+        .map(|scenario| {
+            (
+                scenario.number,
+                scenario.short_name.as_str(),
+                scenario.package.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        &names[..2],
+        &[
+            (1, "collections", "collections_demo"),
+            (2, "hashing", "hashing_demo")
+        ]
+    );
+    assert!(found.windows(2).all(|pair| pair[0].number < pair[1].number));
+}
+
+#[test]
+fn a_scenario_answers_to_every_name() {
+    let found: Vec<ScenarioCrate> = scenarios::discover(&workspace()).unwrap();
+    let hashing: &ScenarioCrate = &found[1];
+    for query in [
+        "hashing",
+        "hashing_demo",
+        "2",
+        "02",
+        "scenario-02-hashing-algorithms-for-hashmap",
+        "scenario-02-hashing-algorithms-for-hashmap/",
+    ] {
+        assert!(hashing.matches(query), "{query}");
+    }
+    for query in ["hash", "1", "collections", ""] {
+        assert!(!hashing.matches(query), "{query}");
+    }
+}